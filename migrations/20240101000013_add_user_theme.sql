@@ -0,0 +1,2 @@
+-- Add UI theme preference to users (system, light, dark, high-contrast)
+ALTER TABLE users ADD COLUMN theme TEXT NOT NULL DEFAULT 'system';
@@ -0,0 +1,11 @@
+-- Unsent comment text, one draft per user per card, expiring after a while
+CREATE TABLE comment_drafts (
+    card_id TEXT NOT NULL REFERENCES cards(id) ON DELETE CASCADE,
+    user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    body TEXT NOT NULL,
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+    expires_at TEXT NOT NULL,
+    PRIMARY KEY (card_id, user_id)
+);
+
+CREATE INDEX idx_comment_drafts_expires ON comment_drafts(expires_at);
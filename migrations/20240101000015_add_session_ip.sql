@@ -0,0 +1,2 @@
+-- Record the client IP a session was created from
+ALTER TABLE sessions ADD COLUMN ip_address TEXT;
@@ -0,0 +1,3 @@
+-- What the chat assistant does when create_card names a column that doesn't exist
+-- (reject, suggest, auto_create)
+ALTER TABLE boards ADD COLUMN chat_column_policy TEXT NOT NULL DEFAULT 'reject';
@@ -0,0 +1,11 @@
+-- Bookkeeping for data backfills (idempotent data migrations run at startup,
+-- separate from the sqlx schema migrations)
+CREATE TABLE IF NOT EXISTS data_backfills (
+    version INTEGER PRIMARY KEY NOT NULL,
+    name TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'running',
+    rows_affected INTEGER NOT NULL DEFAULT 0,
+    error TEXT,
+    started_at TEXT NOT NULL DEFAULT (datetime('now')),
+    completed_at TEXT
+);
@@ -0,0 +1,3 @@
+-- Read-only lock for cards (e.g. finalized decision records)
+ALTER TABLE cards ADD COLUMN locked_at TEXT;
+ALTER TABLE cards ADD COLUMN locked_by TEXT REFERENCES users(id);
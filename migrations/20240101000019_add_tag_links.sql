@@ -0,0 +1,4 @@
+-- Board tags can be linked to a global tag, inheriting its name and color
+ALTER TABLE tags ADD COLUMN linked_tag_id TEXT REFERENCES tags(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_tags_linked_tag_id ON tags(linked_tag_id);
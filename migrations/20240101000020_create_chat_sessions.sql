@@ -0,0 +1,13 @@
+-- Stateful multi-turn chat sessions (e.g. weekly planning)
+CREATE TABLE IF NOT EXISTS chat_sessions (
+    id TEXT PRIMARY KEY NOT NULL,
+    user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    session_type TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'active',
+    step TEXT NOT NULL,
+    state TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX idx_chat_sessions_user ON chat_sessions(user_id, created_at DESC);
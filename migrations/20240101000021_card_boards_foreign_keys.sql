@@ -0,0 +1,28 @@
+-- Add the foreign keys card_boards was created without, so deleting a card,
+-- board or column no longer leaves dangling assignments.
+-- Orphaned rows are dropped while copying; assignments to a column that is
+-- missing or belongs to another board fall back to "no column".
+CREATE TABLE card_boards_new (
+    id TEXT PRIMARY KEY NOT NULL,
+    card_id TEXT NOT NULL REFERENCES cards(id) ON DELETE CASCADE,
+    board_id TEXT NOT NULL REFERENCES boards(id) ON DELETE CASCADE,
+    column_id TEXT REFERENCES columns(id) ON DELETE SET NULL,
+    position INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    UNIQUE(card_id, board_id)
+);
+
+INSERT INTO card_boards_new (id, card_id, board_id, column_id, position, created_at)
+SELECT cb.id, cb.card_id, cb.board_id,
+       (SELECT col.id FROM columns col WHERE col.id = cb.column_id AND col.board_id = cb.board_id),
+       cb.position, cb.created_at
+FROM card_boards cb
+WHERE EXISTS (SELECT 1 FROM cards c WHERE c.id = cb.card_id)
+  AND EXISTS (SELECT 1 FROM boards b WHERE b.id = cb.board_id);
+
+DROP TABLE card_boards;
+ALTER TABLE card_boards_new RENAME TO card_boards;
+
+CREATE INDEX idx_card_boards_card ON card_boards(card_id);
+CREATE INDEX idx_card_boards_board ON card_boards(board_id);
+CREATE INDEX idx_card_boards_column ON card_boards(column_id);
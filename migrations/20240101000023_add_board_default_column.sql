@@ -0,0 +1,2 @@
+-- Column that cards assigned to the board without a column land in
+ALTER TABLE boards ADD COLUMN default_column_id TEXT REFERENCES columns(id) ON DELETE SET NULL;
@@ -0,0 +1,15 @@
+-- Audit trail of changes to boards and the columns, cards, tags and comments on them.
+-- Card and entity ids are kept without foreign keys so history outlives deletions.
+CREATE TABLE IF NOT EXISTS activity_log (
+    id TEXT PRIMARY KEY NOT NULL,
+    board_id TEXT NOT NULL REFERENCES boards(id) ON DELETE CASCADE,
+    card_id TEXT,
+    actor_id TEXT REFERENCES users(id) ON DELETE SET NULL,
+    event TEXT NOT NULL,
+    entity_id TEXT NOT NULL,
+    details TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX idx_activity_log_board ON activity_log(board_id, created_at DESC);
+CREATE INDEX idx_activity_log_card ON activity_log(card_id, created_at DESC);
@@ -0,0 +1,15 @@
+-- Files attached to cards. Content lives either in the data column or, when an
+-- attachments directory is configured, in a file named by storage_path.
+CREATE TABLE IF NOT EXISTS attachments (
+    id TEXT PRIMARY KEY NOT NULL,
+    card_id TEXT NOT NULL REFERENCES cards(id) ON DELETE CASCADE,
+    uploaded_by TEXT NOT NULL REFERENCES users(id),
+    filename TEXT NOT NULL,
+    content_type TEXT NOT NULL,
+    size INTEGER NOT NULL,
+    storage_path TEXT,
+    data BLOB,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX idx_attachments_card ON attachments(card_id, created_at);
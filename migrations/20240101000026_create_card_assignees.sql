@@ -0,0 +1,10 @@
+-- Users responsible for a card
+CREATE TABLE IF NOT EXISTS card_assignees (
+    card_id TEXT NOT NULL REFERENCES cards(id) ON DELETE CASCADE,
+    user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    assigned_by TEXT REFERENCES users(id) ON DELETE SET NULL,
+    assigned_at TEXT NOT NULL DEFAULT (datetime('now')),
+    PRIMARY KEY (card_id, user_id)
+);
+
+CREATE INDEX idx_card_assignees_user ON card_assignees(user_id);
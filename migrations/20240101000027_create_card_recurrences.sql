@@ -0,0 +1,14 @@
+-- Recurrence rules. The card carrying a rule is the template that is copied
+-- every time the rule comes due.
+CREATE TABLE IF NOT EXISTS card_recurrences (
+    card_id TEXT PRIMARY KEY NOT NULL REFERENCES cards(id) ON DELETE CASCADE,
+    frequency TEXT NOT NULL,
+    interval INTEGER NOT NULL DEFAULT 1,
+    starts_on TEXT NOT NULL,
+    next_run_on TEXT NOT NULL,
+    created_by TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    last_run_at TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX idx_card_recurrences_next_run ON card_recurrences(next_run_on);
@@ -0,0 +1,14 @@
+-- Reminders about cards whose due date is near or past. A notification is
+-- tied to the due date it was raised for, so moving the date raises a new one.
+CREATE TABLE IF NOT EXISTS notifications (
+    id TEXT PRIMARY KEY NOT NULL,
+    user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    card_id TEXT NOT NULL REFERENCES cards(id) ON DELETE CASCADE,
+    kind TEXT NOT NULL,
+    due_date TEXT NOT NULL,
+    read_at TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    UNIQUE (user_id, card_id, kind, due_date)
+);
+
+CREATE INDEX idx_notifications_user ON notifications(user_id, read_at);
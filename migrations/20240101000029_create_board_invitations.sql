@@ -0,0 +1,15 @@
+-- Pending invitations to a board, addressed to an email address. An invitation
+-- is removed once the role it carries has been granted.
+CREATE TABLE IF NOT EXISTS board_invitations (
+    id TEXT PRIMARY KEY NOT NULL,
+    board_id TEXT NOT NULL REFERENCES boards(id) ON DELETE CASCADE,
+    email TEXT NOT NULL COLLATE NOCASE,
+    role TEXT NOT NULL,
+    token_hash TEXT NOT NULL UNIQUE,
+    invited_by TEXT REFERENCES users(id) ON DELETE SET NULL,
+    expires_at TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    UNIQUE (board_id, email)
+);
+
+CREATE INDEX idx_board_invitations_email ON board_invitations(email);
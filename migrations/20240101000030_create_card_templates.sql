@@ -0,0 +1,23 @@
+-- Reusable card skeletons of a board
+CREATE TABLE IF NOT EXISTS card_templates (
+    id TEXT PRIMARY KEY NOT NULL,
+    board_id TEXT NOT NULL REFERENCES boards(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    -- Title of created cards; {title} and {date} are filled in on creation
+    title_pattern TEXT NOT NULL,
+    body TEXT,
+    -- JSON array of checklist item texts
+    checklist TEXT NOT NULL DEFAULT '[]',
+    created_by TEXT REFERENCES users(id) ON DELETE SET NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX idx_card_templates_board ON card_templates(board_id);
+
+-- Board tags put on cards created from a template
+CREATE TABLE IF NOT EXISTS card_template_tags (
+    template_id TEXT NOT NULL REFERENCES card_templates(id) ON DELETE CASCADE,
+    tag_id TEXT NOT NULL REFERENCES tags(id) ON DELETE CASCADE,
+    PRIMARY KEY (template_id, tag_id)
+);
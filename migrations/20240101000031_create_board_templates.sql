@@ -0,0 +1,14 @@
+-- User-defined board layouts, saved from existing boards
+CREATE TABLE IF NOT EXISTS board_templates (
+    id TEXT PRIMARY KEY NOT NULL,
+    owner_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    description TEXT,
+    -- JSON array of column names, in board order
+    columns TEXT NOT NULL DEFAULT '[]',
+    -- JSON array of {"name", "color"} objects
+    tags TEXT NOT NULL DEFAULT '[]',
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX idx_board_templates_owner ON board_templates(owner_id);
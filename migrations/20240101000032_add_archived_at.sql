@@ -0,0 +1,6 @@
+-- Archived cards and columns are hidden from board views but kept with their history
+ALTER TABLE cards ADD COLUMN archived_at TEXT;
+ALTER TABLE columns ADD COLUMN archived_at TEXT;
+
+CREATE INDEX idx_cards_archived_at ON cards(archived_at);
+CREATE INDEX idx_columns_archived_at ON columns(archived_at);
@@ -0,0 +1,11 @@
+-- Deleted boards, columns and cards stay in the trash until they are purged
+ALTER TABLE boards ADD COLUMN deleted_at TEXT;
+ALTER TABLE boards ADD COLUMN deleted_by TEXT REFERENCES users(id) ON DELETE SET NULL;
+ALTER TABLE columns ADD COLUMN deleted_at TEXT;
+ALTER TABLE columns ADD COLUMN deleted_by TEXT REFERENCES users(id) ON DELETE SET NULL;
+ALTER TABLE cards ADD COLUMN deleted_at TEXT;
+ALTER TABLE cards ADD COLUMN deleted_by TEXT REFERENCES users(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_boards_deleted_at ON boards(deleted_at);
+CREATE INDEX idx_columns_deleted_at ON columns(deleted_at);
+CREATE INDEX idx_cards_deleted_at ON cards(deleted_at);
@@ -0,0 +1,15 @@
+-- Chat actions that can be undone, with the step that reverses them
+CREATE TABLE IF NOT EXISTS chat_actions (
+    id TEXT PRIMARY KEY NOT NULL,
+    user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- Board chat the action was taken in, NULL for global chat
+    board_id TEXT REFERENCES boards(id) ON DELETE CASCADE,
+    action TEXT NOT NULL,
+    description TEXT NOT NULL,
+    -- JSON undo step
+    undo TEXT NOT NULL,
+    undone_at TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX idx_chat_actions_user ON chat_actions(user_id, created_at);
@@ -0,0 +1,15 @@
+-- Ask before executing the actions the board chat assistant proposes
+ALTER TABLE users ADD COLUMN chat_confirm_actions INTEGER NOT NULL DEFAULT 0;
+
+-- Actions proposed in a board chat, waiting for the user to confirm them
+CREATE TABLE IF NOT EXISTS pending_chat_actions (
+    id TEXT PRIMARY KEY NOT NULL,
+    user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    board_id TEXT NOT NULL REFERENCES boards(id) ON DELETE CASCADE,
+    chat_message_id TEXT NOT NULL REFERENCES chat_messages(id) ON DELETE CASCADE,
+    -- JSON array of the proposed actions
+    actions TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX idx_pending_chat_actions_user ON pending_chat_actions(user_id);
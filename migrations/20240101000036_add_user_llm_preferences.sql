@@ -0,0 +1,4 @@
+-- LLM used for the user's chat requests, the server defaults when NULL
+ALTER TABLE users ADD COLUMN llm_provider TEXT;
+ALTER TABLE users ADD COLUMN llm_model TEXT;
+ALTER TABLE users ADD COLUMN llm_temperature REAL;
@@ -0,0 +1,6 @@
+-- Sessions from "remember me" logins last longer
+ALTER TABLE sessions ADD COLUMN remember INTEGER NOT NULL DEFAULT 0;
+
+-- Store expiry in the format of datetime('now'), which it is compared with
+UPDATE sessions SET expires_at = datetime(expires_at);
+CREATE INDEX idx_sessions_expires_at ON sessions(expires_at);
@@ -0,0 +1,46 @@
+-- Users signing in with OAuth / OpenID Connect have no password, and their accounts
+-- are linked to provider identities.
+-- SQLite requires table recreation to make password_hash optional. Migrations run with
+-- foreign keys off, so dropping the old table leaves the rows referencing users alone.
+CREATE TABLE users_new (
+    id TEXT PRIMARY KEY NOT NULL,
+    email TEXT NOT NULL UNIQUE,
+    password_hash TEXT,
+    name TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+    llm_context TEXT,
+    theme TEXT NOT NULL DEFAULT 'system',
+    is_admin INTEGER NOT NULL DEFAULT 0,
+    chat_confirm_actions INTEGER NOT NULL DEFAULT 0,
+    llm_provider TEXT,
+    llm_model TEXT,
+    llm_temperature REAL
+);
+
+INSERT INTO users_new (
+    id, email, password_hash, name, created_at, updated_at, llm_context, theme, is_admin,
+    chat_confirm_actions, llm_provider, llm_model, llm_temperature
+)
+SELECT
+    id, email, password_hash, name, created_at, updated_at, llm_context, theme, is_admin,
+    chat_confirm_actions, llm_provider, llm_model, llm_temperature
+FROM users;
+
+DROP TABLE users;
+ALTER TABLE users_new RENAME TO users;
+
+CREATE INDEX idx_users_email ON users(email);
+
+-- Provider accounts a user signs in with, by the provider's stable user id
+CREATE TABLE IF NOT EXISTS user_identities (
+    id TEXT PRIMARY KEY NOT NULL,
+    user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    provider TEXT NOT NULL,
+    subject TEXT NOT NULL,
+    email TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    UNIQUE(provider, subject)
+);
+
+CREATE INDEX idx_user_identities_user ON user_identities(user_id);
@@ -0,0 +1,10 @@
+-- Email address changes waiting for the new address to be confirmed. A user has at
+-- most one; requesting another replaces it.
+CREATE TABLE IF NOT EXISTS email_changes (
+    id TEXT PRIMARY KEY NOT NULL,
+    user_id TEXT NOT NULL UNIQUE REFERENCES users(id) ON DELETE CASCADE,
+    new_email TEXT NOT NULL,
+    token_hash TEXT NOT NULL UNIQUE,
+    expires_at TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
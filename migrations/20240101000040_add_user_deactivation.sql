@@ -0,0 +1,3 @@
+-- Administrators can deactivate accounts; deactivated users cannot log in or use
+-- their API tokens, and keep their data.
+ALTER TABLE users ADD COLUMN deactivated_at TEXT;
@@ -0,0 +1,4 @@
+-- Optional limit on the cards in progress in a column. Each board chooses whether
+-- exceeding a limit only warns ('warn') or is refused ('block').
+ALTER TABLE columns ADD COLUMN wip_limit INTEGER;
+ALTER TABLE boards ADD COLUMN wip_limit_policy TEXT NOT NULL DEFAULT 'warn';
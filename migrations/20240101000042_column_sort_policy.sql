@@ -0,0 +1,3 @@
+-- How a column orders its cards: by hand ('manual'), by due date ('due_date') or by
+-- last update ('updated_at'). Auto-sorted columns ignore card positions.
+ALTER TABLE columns ADD COLUMN sort_policy TEXT NOT NULL DEFAULT 'manual';
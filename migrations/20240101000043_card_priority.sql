@@ -0,0 +1,3 @@
+-- How urgent a card is: 'low', 'medium', 'high' or 'urgent'. NULL means no priority.
+ALTER TABLE cards ADD COLUMN priority TEXT;
+CREATE INDEX IF NOT EXISTS idx_cards_priority ON cards(priority);
@@ -0,0 +1,13 @@
+-- Typed relations between two cards. Only one direction is stored: 'blocked_by' is
+-- saved as the reverse 'blocks' and 'duplicated_by' as the reverse 'duplicate_of'.
+CREATE TABLE IF NOT EXISTS card_links (
+    id TEXT PRIMARY KEY NOT NULL,
+    source_card_id TEXT NOT NULL REFERENCES cards(id) ON DELETE CASCADE,
+    target_card_id TEXT NOT NULL REFERENCES cards(id) ON DELETE CASCADE,
+    link_type TEXT NOT NULL,
+    created_by TEXT REFERENCES users(id) ON DELETE SET NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    UNIQUE (source_card_id, target_card_id, link_type)
+);
+
+CREATE INDEX idx_card_links_target ON card_links(target_card_id);
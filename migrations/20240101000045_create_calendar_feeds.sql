@@ -0,0 +1,7 @@
+-- Secret address of each user's iCalendar feed of card dates. Only a hash of the
+-- token is kept; generating a new one replaces the old.
+CREATE TABLE IF NOT EXISTS calendar_feeds (
+    user_id TEXT PRIMARY KEY NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    token_hash TEXT NOT NULL UNIQUE,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
@@ -0,0 +1,7 @@
+-- Secret token in the address each user forwards emails to, turning them into inbox
+-- cards. Only a hash of the token is kept; generating a new one replaces the old.
+CREATE TABLE IF NOT EXISTS inbound_email_addresses (
+    user_id TEXT PRIMARY KEY NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    token_hash TEXT NOT NULL UNIQUE,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
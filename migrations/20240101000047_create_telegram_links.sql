@@ -0,0 +1,10 @@
+-- Telegram chats linked to users, so the bot can answer messages as the chat
+-- assistant. A user links a chat by sending the bot a one-time code from the settings
+-- page; only a hash of the code is kept.
+CREATE TABLE IF NOT EXISTS telegram_links (
+    user_id TEXT PRIMARY KEY NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    chat_id INTEGER UNIQUE,
+    link_code_hash TEXT UNIQUE,
+    link_code_expires_at TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
@@ -0,0 +1,29 @@
+-- Version counters for optimistic concurrency control. Clients send the version they
+-- last read back as an If-Match header and updates of a stale version are refused.
+-- The repository update statements bump the version themselves; the triggers cover
+-- every other statement that changes the content of a row (moves, status changes,
+-- chat edits) so the version always moves when the visible data does.
+ALTER TABLE cards ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE boards ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE columns ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+
+CREATE TRIGGER IF NOT EXISTS cards_bump_version
+AFTER UPDATE OF column_id, title, body, visibility, status, start_date, end_date, due_date, priority, owner_id, archived_at ON cards
+FOR EACH ROW WHEN NEW.version = OLD.version
+BEGIN
+    UPDATE cards SET version = OLD.version + 1 WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS boards_bump_version
+AFTER UPDATE OF name, description, chat_column_policy, default_column_id, wip_limit_policy ON boards
+FOR EACH ROW WHEN NEW.version = OLD.version
+BEGIN
+    UPDATE boards SET version = OLD.version + 1 WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS columns_bump_version
+AFTER UPDATE OF name, wip_limit, sort_policy, archived_at ON columns
+FOR EACH ROW WHEN NEW.version = OLD.version
+BEGIN
+    UPDATE columns SET version = OLD.version + 1 WHERE id = NEW.id;
+END;
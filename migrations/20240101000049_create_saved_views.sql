@@ -0,0 +1,13 @@
+-- Named card filters a user keeps for a board
+CREATE TABLE IF NOT EXISTS saved_views (
+    id TEXT PRIMARY KEY NOT NULL,
+    board_id TEXT NOT NULL REFERENCES boards(id) ON DELETE CASCADE,
+    user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    -- JSON object with the fields of a card filter
+    filter TEXT NOT NULL DEFAULT '{}',
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE UNIQUE INDEX idx_saved_views_board_user_name ON saved_views(board_id, user_id, name);
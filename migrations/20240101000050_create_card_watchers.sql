@@ -0,0 +1,33 @@
+-- Users subscribed to the changes of a card
+CREATE TABLE IF NOT EXISTS card_watchers (
+    card_id TEXT NOT NULL REFERENCES cards(id) ON DELETE CASCADE,
+    user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    PRIMARY KEY (card_id, user_id)
+);
+
+CREATE INDEX idx_card_watchers_user ON card_watchers(user_id);
+
+-- Watchers are notified of changes made by other users, which have no due date but
+-- an actor and a short description of the change.
+-- SQLite requires table recreation to make due_date optional.
+CREATE TABLE notifications_new (
+    id TEXT PRIMARY KEY NOT NULL,
+    user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    card_id TEXT NOT NULL REFERENCES cards(id) ON DELETE CASCADE,
+    kind TEXT NOT NULL,
+    due_date TEXT,
+    read_at TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    actor_id TEXT REFERENCES users(id) ON DELETE SET NULL,
+    message TEXT,
+    UNIQUE (user_id, card_id, kind, due_date)
+);
+
+INSERT INTO notifications_new (id, user_id, card_id, kind, due_date, read_at, created_at)
+SELECT id, user_id, card_id, kind, due_date, read_at, created_at FROM notifications;
+
+DROP TABLE notifications;
+ALTER TABLE notifications_new RENAME TO notifications;
+
+CREATE INDEX idx_notifications_user ON notifications(user_id, read_at);
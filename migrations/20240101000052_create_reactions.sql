@@ -0,0 +1,17 @@
+-- Emoji reactions on cards and their comments
+CREATE TABLE IF NOT EXISTS reactions (
+    id TEXT PRIMARY KEY NOT NULL,
+    card_id TEXT NOT NULL REFERENCES cards(id) ON DELETE CASCADE,
+    -- NULL for reactions on the card itself
+    comment_id TEXT REFERENCES comments(id) ON DELETE CASCADE,
+    user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    emoji TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+-- Each user reacts with a given emoji once per card or comment
+CREATE UNIQUE INDEX idx_reactions_card ON reactions(card_id, user_id, emoji)
+    WHERE comment_id IS NULL;
+CREATE UNIQUE INDEX idx_reactions_comment ON reactions(comment_id, user_id, emoji)
+    WHERE comment_id IS NOT NULL;
+CREATE INDEX idx_reactions_user ON reactions(user_id);
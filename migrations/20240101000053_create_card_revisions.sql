@@ -0,0 +1,12 @@
+-- Previous titles and bodies of cards, saved whenever an edit replaces them
+CREATE TABLE IF NOT EXISTS card_revisions (
+    id TEXT PRIMARY KEY NOT NULL,
+    card_id TEXT NOT NULL REFERENCES cards(id) ON DELETE CASCADE,
+    title TEXT NOT NULL,
+    body TEXT,
+    -- Who made the edit that replaced this version
+    edited_by TEXT REFERENCES users(id) ON DELETE SET NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX idx_card_revisions_card ON card_revisions(card_id, created_at);
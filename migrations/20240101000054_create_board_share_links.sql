@@ -0,0 +1,8 @@
+-- Secret read-only address of a board for people without an account. Only a hash of
+-- the token is kept; generating a new one replaces the old.
+CREATE TABLE IF NOT EXISTS board_share_links (
+    board_id TEXT PRIMARY KEY NOT NULL REFERENCES boards(id) ON DELETE CASCADE,
+    token_hash TEXT NOT NULL UNIQUE,
+    created_by TEXT REFERENCES users(id) ON DELETE SET NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
@@ -0,0 +1,11 @@
+-- Whether the public cards of a board can be viewed without an account at
+-- /cards/{card_id}/public
+ALTER TABLE boards ADD COLUMN allow_public_links INTEGER NOT NULL DEFAULT 0;
+
+DROP TRIGGER IF EXISTS boards_bump_version;
+CREATE TRIGGER IF NOT EXISTS boards_bump_version
+AFTER UPDATE OF name, description, chat_column_policy, default_column_id, wip_limit_policy, allow_public_links ON boards
+FOR EACH ROW WHEN NEW.version = OLD.version
+BEGIN
+    UPDATE boards SET version = OLD.version + 1 WHERE id = NEW.id;
+END;
@@ -0,0 +1,36 @@
+-- Workspaces group boards and the people working on them. A member of a workspace
+-- gets their workspace role on each of its boards unless the board gives them one.
+CREATE TABLE IF NOT EXISTS workspaces (
+    id TEXT PRIMARY KEY NOT NULL,
+    name TEXT NOT NULL,
+    owner_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE TABLE IF NOT EXISTS workspace_members (
+    id TEXT PRIMARY KEY NOT NULL,
+    workspace_id TEXT NOT NULL REFERENCES workspaces(id) ON DELETE CASCADE,
+    user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    role TEXT NOT NULL DEFAULT 'reader',
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    UNIQUE(workspace_id, user_id)
+);
+
+CREATE INDEX idx_workspace_members_user_id ON workspace_members(user_id);
+
+ALTER TABLE boards ADD COLUMN workspace_id TEXT REFERENCES workspaces(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_boards_workspace_id ON boards(workspace_id);
+
+-- Effective role of each user on each board: their board permission, or else their
+-- role in the board's workspace
+CREATE VIEW IF NOT EXISTS board_access AS
+SELECT bp.board_id, bp.user_id, bp.role FROM board_permissions bp
+UNION ALL
+SELECT b.id AS board_id, wm.user_id, wm.role FROM boards b
+INNER JOIN workspace_members wm ON wm.workspace_id = b.workspace_id
+WHERE NOT EXISTS (
+    SELECT 1 FROM board_permissions bp
+    WHERE bp.board_id = b.id AND bp.user_id = wm.user_id
+);
@@ -0,0 +1,21 @@
+-- Folders a user sorts their boards into on the boards page
+CREATE TABLE IF NOT EXISTS board_groups (
+    id TEXT PRIMARY KEY NOT NULL,
+    user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    position INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX idx_board_groups_user_id ON board_groups(user_id);
+
+-- How a user arranges a board they can see: starred, in one of their groups, and
+-- where in the list. Boards without a row keep the default order.
+CREATE TABLE IF NOT EXISTS board_preferences (
+    user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    board_id TEXT NOT NULL REFERENCES boards(id) ON DELETE CASCADE,
+    starred INTEGER NOT NULL DEFAULT 0,
+    group_id TEXT REFERENCES board_groups(id) ON DELETE SET NULL,
+    position INTEGER,
+    PRIMARY KEY (user_id, board_id)
+);
@@ -0,0 +1,20 @@
+-- A column can carry a color, shown along its header, and a description of what
+-- belongs in it
+ALTER TABLE columns ADD COLUMN color TEXT;
+ALTER TABLE columns ADD COLUMN description TEXT;
+
+DROP TRIGGER IF EXISTS columns_bump_version;
+CREATE TRIGGER IF NOT EXISTS columns_bump_version
+AFTER UPDATE OF name, wip_limit, sort_policy, archived_at, color, description ON columns
+FOR EACH ROW WHEN NEW.version = OLD.version
+BEGIN
+    UPDATE columns SET version = OLD.version + 1 WHERE id = NEW.id;
+END;
+
+-- Columns a user has collapsed on their own view of the board
+CREATE TABLE IF NOT EXISTS column_collapses (
+    user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    column_id TEXT NOT NULL REFERENCES columns(id) ON DELETE CASCADE,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    PRIMARY KEY (user_id, column_id)
+);
@@ -0,0 +1,4 @@
+-- A card can have a cover shown on top of it on the board: a color, or one of its
+-- image attachments
+ALTER TABLE cards ADD COLUMN cover_color TEXT;
+ALTER TABLE cards ADD COLUMN cover_attachment_id TEXT REFERENCES attachments(id) ON DELETE SET NULL;
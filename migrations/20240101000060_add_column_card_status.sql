@@ -0,0 +1,15 @@
+-- Status a card takes when it is moved into the column, keeping the status of cards
+-- in line with where they sit on the board. Columns named like the statuses start
+-- with the matching one; existing cards are reconciled by a data backfill.
+ALTER TABLE columns ADD COLUMN card_status TEXT;
+
+UPDATE columns SET card_status = 'done' WHERE lower(trim(name)) = 'done';
+UPDATE columns SET card_status = 'in_progress' WHERE lower(trim(name)) IN ('in progress', 'doing');
+
+DROP TRIGGER IF EXISTS columns_bump_version;
+CREATE TRIGGER IF NOT EXISTS columns_bump_version
+AFTER UPDATE OF name, wip_limit, sort_policy, archived_at, color, description, card_status ON columns
+FOR EACH ROW WHEN NEW.version = OLD.version
+BEGIN
+    UPDATE columns SET version = OLD.version + 1 WHERE id = NEW.id;
+END;
@@ -0,0 +1,72 @@
+-- card_boards becomes the single record of where a card sits on a board.
+-- cards.column_id is kept as the card's home column for older code paths, and the
+-- triggers below mirror it into card_boards so both always agree.
+-- Ids are stored as the 16 bytes of the uuid, so new ones are 16 random bytes.
+
+-- Cards placed through their column since the standalone cards migration have no
+-- assignment yet, or one pointing at another column of the same board
+INSERT INTO card_boards (id, card_id, board_id, column_id, position, created_at)
+SELECT
+    randomblob(16),
+    c.id,
+    col.board_id,
+    c.column_id,
+    c.position,
+    c.created_at
+FROM cards c
+JOIN columns col ON c.column_id = col.id
+WHERE true
+ON CONFLICT(card_id, board_id) DO UPDATE SET
+    column_id = excluded.column_id,
+    position = excluded.position;
+
+CREATE TRIGGER cards_placement_insert
+AFTER INSERT ON cards
+WHEN NEW.column_id IS NOT NULL
+BEGIN
+    INSERT INTO card_boards (id, card_id, board_id, column_id, position, created_at)
+    SELECT
+        randomblob(16),
+        NEW.id,
+        col.board_id,
+        NEW.column_id,
+        NEW.position,
+        datetime('now')
+    FROM columns col
+    WHERE col.id = NEW.column_id
+    ON CONFLICT(card_id, board_id) DO UPDATE SET
+        column_id = excluded.column_id,
+        position = excluded.position;
+END;
+
+CREATE TRIGGER cards_placement_update
+AFTER UPDATE OF column_id, position ON cards
+WHEN NEW.column_id IS NOT OLD.column_id OR NEW.position IS NOT OLD.position
+BEGIN
+    -- A card whose home column moves to another board leaves the old board
+    DELETE FROM card_boards
+    WHERE card_id = NEW.id
+      AND NEW.column_id IS NOT NULL
+      AND board_id = (SELECT board_id FROM columns WHERE id = OLD.column_id)
+      AND board_id IS NOT (SELECT board_id FROM columns WHERE id = NEW.column_id);
+
+    -- Without a home column it stays on the board, outside any column
+    UPDATE card_boards SET column_id = NULL
+    WHERE card_id = NEW.id
+      AND NEW.column_id IS NULL
+      AND column_id IS OLD.column_id;
+
+    INSERT INTO card_boards (id, card_id, board_id, column_id, position, created_at)
+    SELECT
+        randomblob(16),
+        NEW.id,
+        col.board_id,
+        NEW.column_id,
+        NEW.position,
+        datetime('now')
+    FROM columns col
+    WHERE col.id = NEW.column_id
+    ON CONFLICT(card_id, board_id) DO UPDATE SET
+        column_id = excluded.column_id,
+        position = excluded.position;
+END;
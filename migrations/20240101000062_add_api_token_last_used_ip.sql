@@ -0,0 +1,2 @@
+-- Record the client IP an API token was last used from
+ALTER TABLE api_tokens ADD COLUMN last_used_ip TEXT;
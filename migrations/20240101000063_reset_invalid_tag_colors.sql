@@ -0,0 +1,11 @@
+-- Tag colors are written into style attributes, so only hex colors and color names are
+-- accepted from now on. Anything else stored before falls back to the default gray.
+UPDATE tags SET color = '#6c757d'
+WHERE NOT (
+    (color GLOB '#*'
+        AND length(color) IN (4, 5, 7, 9)
+        AND substr(color, 2) NOT GLOB '*[^0-9a-fA-F]*')
+    OR (color <> ''
+        AND length(color) <= 32
+        AND color NOT GLOB '*[^a-zA-Z]*')
+);
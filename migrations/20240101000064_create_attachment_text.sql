@@ -0,0 +1,12 @@
+-- Text extracted from attachments (plain text files and PDFs), indexed for search.
+-- Attachments without text, such as images, have no row.
+CREATE VIRTUAL TABLE IF NOT EXISTS attachment_text USING fts5(
+    text,
+    attachment_id UNINDEXED,
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
+CREATE TRIGGER IF NOT EXISTS attachment_text_delete AFTER DELETE ON attachments
+BEGIN
+    DELETE FROM attachment_text WHERE attachment_id = old.id;
+END;
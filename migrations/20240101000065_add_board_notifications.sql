@@ -0,0 +1,24 @@
+-- Users added to or removed from a board, or given another role on it, are notified
+-- about the board rather than a card.
+-- SQLite requires table recreation to make card_id optional.
+CREATE TABLE notifications_new (
+    id TEXT PRIMARY KEY NOT NULL,
+    user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    card_id TEXT REFERENCES cards(id) ON DELETE CASCADE,
+    board_id TEXT REFERENCES boards(id) ON DELETE CASCADE,
+    kind TEXT NOT NULL,
+    due_date TEXT,
+    read_at TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    actor_id TEXT REFERENCES users(id) ON DELETE SET NULL,
+    message TEXT,
+    UNIQUE (user_id, card_id, kind, due_date)
+);
+
+INSERT INTO notifications_new (id, user_id, card_id, kind, due_date, read_at, created_at, actor_id, message)
+SELECT id, user_id, card_id, kind, due_date, read_at, created_at, actor_id, message FROM notifications;
+
+DROP TABLE notifications;
+ALTER TABLE notifications_new RENAME TO notifications;
+
+CREATE INDEX idx_notifications_user ON notifications(user_id, read_at);
@@ -0,0 +1,15 @@
+-- Comments left without an account by visitors of a board's share link. They are
+-- only shown once an owner of the board approves them.
+CREATE TABLE IF NOT EXISTS guest_comments (
+    id TEXT PRIMARY KEY NOT NULL,
+    card_id TEXT NOT NULL REFERENCES cards(id) ON DELETE CASCADE,
+    board_id TEXT NOT NULL REFERENCES boards(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    body TEXT NOT NULL,
+    approved_at TEXT,
+    approved_by TEXT REFERENCES users(id) ON DELETE SET NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX idx_guest_comments_board ON guest_comments(board_id, approved_at);
+CREATE INDEX idx_guest_comments_card ON guest_comments(card_id, approved_at);
@@ -0,0 +1,136 @@
+use axum::{
+    extract::{ConnectInfo, FromRef, FromRequestParts},
+    http::{request::Parts, Extensions, HeaderMap},
+};
+use ipnet::IpNet;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use tracing::warn;
+
+use crate::error::AppError;
+use crate::state::AppState;
+
+/// Reverse proxies allowed to report the client address via `X-Forwarded-For`.
+/// Configured with `TRUSTED_PROXIES`, a comma-separated list of IPs or CIDR ranges.
+#[derive(Debug, Clone, Default)]
+pub struct TrustedProxies {
+    nets: Vec<IpNet>,
+}
+
+impl TrustedProxies {
+    pub fn from_env() -> Self {
+        std::env::var("TRUSTED_PROXIES")
+            .map(|value| Self::parse(&value))
+            .unwrap_or_default()
+    }
+
+    /// Parse a comma-separated list of IPs or CIDR ranges, skipping invalid entries
+    pub fn parse(value: &str) -> Self {
+        let nets = value
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .filter_map(|entry| {
+                let net = entry
+                    .parse::<IpNet>()
+                    .or_else(|_| entry.parse::<IpAddr>().map(IpNet::from));
+                if net.is_err() {
+                    warn!(entry, "Ignoring invalid trusted proxy entry");
+                }
+                net.ok()
+            })
+            .collect();
+
+        Self { nets }
+    }
+
+    pub fn is_trusted(&self, ip: IpAddr) -> bool {
+        self.nets.iter().any(|net| net.contains(&ip))
+    }
+
+    /// Resolve the client address of a request received from `peer`.
+    ///
+    /// `X-Forwarded-For` is only honored when the peer is a trusted proxy. It is read right
+    /// to left, since each proxy appends the address it received the request from: the first
+    /// untrusted hop is the client, anything further left may be spoofed.
+    pub fn client_ip(&self, peer: IpAddr, headers: &HeaderMap) -> IpAddr {
+        if !self.is_trusted(peer) {
+            return peer;
+        }
+
+        let forwarded: Vec<IpAddr> = headers
+            .get_all("x-forwarded-for")
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .filter_map(|entry| entry.trim().parse().ok())
+            .collect();
+
+        forwarded
+            .iter()
+            .rev()
+            .find(|ip| !self.is_trusted(**ip))
+            .or_else(|| forwarded.first())
+            .copied()
+            .unwrap_or(peer)
+    }
+}
+
+/// Address of the directly connected peer, or localhost when the server was not
+/// started with connect info (e.g. in tests)
+pub fn peer_ip(extensions: &Extensions) -> IpAddr {
+    extensions
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip())
+        .unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST))
+}
+
+/// Client IP of the request, taking trusted proxies into account
+pub struct ClientIp(pub IpAddr);
+
+impl<S> FromRequestParts<S> for ClientIp
+where
+    AppState: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let state = AppState::from_ref(state);
+        let peer = peer_ip(&parts.extensions);
+        Ok(ClientIp(
+            state.trusted_proxies.client_ip(peer, &parts.headers),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(xff: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", xff.parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_untrusted_peer_ignores_forwarded_header() {
+        let proxies = TrustedProxies::parse("10.0.0.0/8");
+        let peer: IpAddr = "203.0.113.9".parse().unwrap();
+
+        assert_eq!(proxies.client_ip(peer, &headers("198.51.100.1")), peer);
+    }
+
+    #[test]
+    fn test_trusted_chain_resolves_rightmost_untrusted_hop() {
+        let proxies = TrustedProxies::parse("127.0.0.1, 10.0.0.0/8, not-an-ip");
+        let peer: IpAddr = "127.0.0.1".parse().unwrap();
+
+        // The left-most entry is client-controlled and must not win
+        let resolved = proxies.client_ip(peer, &headers("1.2.3.4, 198.51.100.7, 10.1.2.3"));
+        assert_eq!(resolved, "198.51.100.7".parse::<IpAddr>().unwrap());
+
+        // Without a header the peer itself is the client
+        assert_eq!(proxies.client_ip(peer, &HeaderMap::new()), peer);
+    }
+}
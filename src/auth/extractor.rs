@@ -1,17 +1,20 @@
 use axum::{
-    extract::{FromRef, FromRequestParts},
+    extract::{FromRef, FromRequestParts, MatchedPath},
     http::request::Parts,
 };
 use axum_extra::extract::CookieJar;
 
-use crate::auth::hash_token;
+use crate::auth::{hash_token, peer_ip};
 use crate::error::AppError;
-use crate::models::User;
+use crate::handlers::mcp;
+use crate::models::{TokenScope, User};
 use crate::state::AppState;
 
 pub struct AuthUser {
     pub user: User,
     pub session_token: Option<String>,
+    /// Scope of the API token the request was made with; None for session logins
+    pub token_scope: Option<TokenScope>,
 }
 
 impl<S> FromRequestParts<S> for AuthUser
@@ -27,14 +30,16 @@ where
             .await
             .map_err(|_| AppError::Unauthorized)?;
 
-        // Check session cookie first
+        // Check session cookie first. Deactivated users are treated as logged out.
         if let Some(session_cookie) = cookies.get("session") {
             let token = session_cookie.value();
             if let Some(session) = state.sessions.find_by_token(token).await? {
-                if let Some(user) = state.users.find_by_id(session.user_id).await? {
+                let user = state.users.find_by_id(session.user_id).await?;
+                if let Some(user) = user.filter(User::is_active) {
                     return Ok(AuthUser {
                         user,
                         session_token: Some(token.to_string()),
+                        token_scope: None,
                     });
                 }
             }
@@ -47,11 +52,26 @@ where
             if let Some(token) = auth_str.strip_prefix("Bearer ") {
                 let token_hash = hash_token(token);
                 if let Some(api_token) = state.tokens.find_by_hash(&token_hash).await? {
-                    state.tokens.update_last_used(api_token.id).await?;
-                    if let Some(user) = state.users.find_by_id(api_token.user_id).await? {
+                    // Read-only tokens cannot make requests that change anything. MCP
+                    // requests are all POSTs, its tools check the scope one by one.
+                    let scope = api_token.scope();
+                    let mcp = parts
+                        .extensions
+                        .get::<MatchedPath>()
+                        .is_some_and(|path| path.as_str() == mcp::PATH);
+                    if !parts.method.is_safe() && !mcp && !scope.allows(TokenScope::Write) {
+                        return Err(AppError::Forbidden);
+                    }
+                    let ip = state
+                        .trusted_proxies
+                        .client_ip(peer_ip(&parts.extensions), &parts.headers);
+                    state.tokens.update_last_used(api_token.id, ip).await?;
+                    let user = state.users.find_by_id(api_token.user_id).await?;
+                    if let Some(user) = user.filter(User::is_active) {
                         return Ok(AuthUser {
                             user,
                             session_token: None,
+                            token_scope: Some(scope),
                         });
                     }
                 }
@@ -78,3 +98,25 @@ where
         }
     }
 }
+
+/// An authenticated instance administrator, using their session or an admin-scoped token
+pub struct AdminUser(pub AuthUser);
+
+impl<S> FromRequestParts<S> for AdminUser
+where
+    AppState: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let auth = AuthUser::from_request_parts(parts, state).await?;
+        let token_allows = auth
+            .token_scope
+            .is_none_or(|scope| scope.allows(TokenScope::Admin));
+        if !auth.user.is_admin || !token_allows {
+            return Err(AppError::Forbidden);
+        }
+        Ok(AdminUser(auth))
+    }
+}
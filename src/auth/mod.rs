@@ -1,5 +1,17 @@
+mod client_ip;
 mod extractor;
 mod password;
+mod rate_limit;
+mod security_headers;
+mod session;
 
-pub use extractor::{AuthUser, OptionalAuthUser};
+pub use client_ip::{peer_ip, ClientIp, TrustedProxies};
+pub use extractor::{AdminUser, AuthUser, OptionalAuthUser};
 pub use password::{generate_token, hash_password, hash_token, verify_password};
+pub use rate_limit::{
+    limit_api, limit_chat, limit_guest_comments, limit_login, RateLimiter, RateLimits,
+};
+pub use security_headers::{security_headers, SecurityHeaders};
+pub use session::{
+    renew_session, run_purge_job as run_session_purge_job, session_cookie, session_lifetime,
+};
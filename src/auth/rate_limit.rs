@@ -0,0 +1,451 @@
+use axum::{
+    extract::{Request, State},
+    http::HeaderMap,
+    middleware::Next,
+    response::Response,
+};
+use axum_extra::extract::CookieJar;
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv6Addr};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::auth::{hash_token, peer_ip};
+use crate::error::{AppError, Result};
+use crate::state::AppState;
+
+/// Most buckets kept at once. Once reached, idle buckets are dropped, then the least
+/// recently used ones, until only `BUCKETS_KEPT_ON_EVICTION` are left.
+const MAX_TRACKED_BUCKETS: usize = 10_000;
+
+/// Buckets left after an eviction, so the next one is at least a thousand new clients
+/// away
+const BUCKETS_KEPT_ON_EVICTION: usize = MAX_TRACKED_BUCKETS * 9 / 10;
+
+/// Requests allowed per minute, per user and per client IP. Configured with
+/// `RATE_LIMIT_*` variables; 0 disables a limit.
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimits {
+    /// Any API request
+    pub api_per_user: u32,
+    pub api_per_ip: u32,
+    /// Chat messages, each of which calls the LLM
+    pub chat_per_user: u32,
+    pub chat_per_ip: u32,
+    /// Login attempts, against password guessing
+    pub login_per_ip: u32,
+    /// Comments left by guests on shared boards, against spam
+    pub guest_comments_per_ip: u32,
+}
+
+impl Default for RateLimits {
+    fn default() -> Self {
+        Self {
+            api_per_user: 300,
+            api_per_ip: 600,
+            chat_per_user: 20,
+            chat_per_ip: 40,
+            login_per_ip: 10,
+            guest_comments_per_ip: 5,
+        }
+    }
+}
+
+impl RateLimits {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let var = |name: &str, default: u32| {
+            std::env::var(name)
+                .ok()
+                .filter(|v| !v.trim().is_empty())
+                .and_then(|v| {
+                    let limit = v.trim().parse().ok();
+                    if limit.is_none() {
+                        warn!(name, value = %v, "Ignoring invalid rate limit");
+                    }
+                    limit
+                })
+                .unwrap_or(default)
+        };
+        Self {
+            api_per_user: var("RATE_LIMIT_API_PER_USER", defaults.api_per_user),
+            api_per_ip: var("RATE_LIMIT_API_PER_IP", defaults.api_per_ip),
+            chat_per_user: var("RATE_LIMIT_CHAT_PER_USER", defaults.chat_per_user),
+            chat_per_ip: var("RATE_LIMIT_CHAT_PER_IP", defaults.chat_per_ip),
+            login_per_ip: var("RATE_LIMIT_LOGIN_PER_IP", defaults.login_per_ip),
+            guest_comments_per_ip: var(
+                "RATE_LIMIT_GUEST_COMMENTS_PER_IP",
+                defaults.guest_comments_per_ip,
+            ),
+        }
+    }
+
+    /// No limits at all
+    pub fn disabled() -> Self {
+        Self {
+            api_per_user: 0,
+            api_per_ip: 0,
+            chat_per_user: 0,
+            chat_per_ip: 0,
+            login_per_ip: 0,
+            guest_comments_per_ip: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Kind {
+    Api,
+    Chat,
+    Login,
+    GuestComment,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Client {
+    User(Uuid),
+    Ip(IpAddr),
+}
+
+/// Requests left for one client, refilled continuously up to the per-minute limit
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// Token buckets for every client, shared by all requests
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    limits: RateLimits,
+    buckets: Arc<Mutex<HashMap<(Kind, Client), Bucket>>>,
+}
+
+impl RateLimiter {
+    pub fn new(limits: RateLimits) -> Self {
+        Self {
+            limits,
+            buckets: Arc::default(),
+        }
+    }
+
+    pub fn limits(&self) -> RateLimits {
+        self.limits
+    }
+
+    /// Take one request from the client's bucket, or fail with the seconds until the
+    /// next request is allowed
+    fn check(
+        &self,
+        kind: Kind,
+        client: Client,
+        per_minute: u32,
+        now: Instant,
+    ) -> std::result::Result<(), u64> {
+        self.check_clients(kind, &[(client, per_minute)], now)
+    }
+
+    /// Take one request from each of the clients' buckets, or from none of them when
+    /// one is empty, failing with the seconds until all of them allow a request
+    fn check_clients(
+        &self,
+        kind: Kind,
+        clients: &[(Client, u32)],
+        now: Instant,
+    ) -> std::result::Result<(), u64> {
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        if buckets.len() + clients.len() > MAX_TRACKED_BUCKETS {
+            make_room(&mut buckets, now);
+        }
+
+        let mut retry_after = 0;
+        for &(client, per_minute) in clients {
+            if per_minute == 0 {
+                continue;
+            }
+            let capacity = f64::from(per_minute);
+            let per_second = capacity / 60.0;
+
+            let bucket = buckets.entry((kind, client)).or_insert(Bucket {
+                tokens: capacity,
+                updated: now,
+            });
+            let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+            bucket.tokens = (bucket.tokens + elapsed * per_second).min(capacity);
+            bucket.updated = now;
+
+            if bucket.tokens < 1.0 {
+                let wait = ((1.0 - bucket.tokens) / per_second).ceil().max(1.0) as u64;
+                retry_after = retry_after.max(wait);
+            }
+        }
+        if retry_after > 0 {
+            return Err(retry_after);
+        }
+
+        for &(client, per_minute) in clients {
+            if let Some(bucket) = buckets.get_mut(&(kind, client)).filter(|_| per_minute > 0) {
+                bucket.tokens -= 1.0;
+            }
+        }
+        Ok(())
+    }
+
+    /// Check the limits of `kind` for the user, when known, and the client IP. A request
+    /// refused by one limit is not counted against the other.
+    fn check_all(
+        &self,
+        kind: Kind,
+        user_id: Option<Uuid>,
+        ip: IpAddr,
+        (per_user, per_ip): (u32, u32),
+    ) -> Result<()> {
+        let mut clients = vec![(Client::Ip(ip_network(ip)), per_ip)];
+        if let Some(id) = user_id {
+            clients.push((Client::User(id), per_user));
+        }
+        self.check_clients(kind, &clients, Instant::now())
+            .map_err(|retry_after| {
+                warn!(?kind, %ip, ?user_id, retry_after, "Rate limit exceeded");
+                AppError::TooManyRequests { retry_after }
+            })
+    }
+
+    /// Chat limit of a user sending messages from outside the API, like the Telegram bot
+    pub fn check_chat_user(&self, user_id: Uuid) -> Result<()> {
+        self.check(
+            Kind::Chat,
+            Client::User(user_id),
+            self.limits.chat_per_user,
+            Instant::now(),
+        )
+        .map_err(|retry_after| {
+            warn!(%user_id, retry_after, "Chat rate limit exceeded");
+            AppError::TooManyRequests { retry_after }
+        })
+    }
+}
+
+/// Drop buckets until no more than `BUCKETS_KEPT_ON_EVICTION` are left: first those
+/// idle for a minute, which are full again so forgetting them changes nothing, then the
+/// least recently used ones
+fn make_room(buckets: &mut HashMap<(Kind, Client), Bucket>, now: Instant) {
+    buckets.retain(|_, b| now.duration_since(b.updated).as_secs() < 60);
+    if buckets.len() <= BUCKETS_KEPT_ON_EVICTION {
+        return;
+    }
+
+    let mut updated: Vec<Instant> = buckets.values().map(|b| b.updated).collect();
+    let evicted = buckets.len() - BUCKETS_KEPT_ON_EVICTION;
+    let (_, &mut newest_evicted, _) = updated.select_nth_unstable(evicted - 1);
+    buckets.retain(|_, b| b.updated > newest_evicted);
+}
+
+/// Address clients are limited by: IPv6 clients by their /64 network, which usually
+/// belongs to a single host that can pick any address in it
+fn ip_network(ip: IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V6(v6) => match v6.to_ipv4_mapped() {
+            Some(v4) => IpAddr::V4(v4),
+            None => IpAddr::V6(Ipv6Addr::from(u128::from(v6) & (u128::MAX << 64))),
+        },
+        IpAddr::V4(_) => ip,
+    }
+}
+
+/// User the request's session cookie or API token belongs to. Invalid credentials are
+/// left to the handler to reject.
+async fn request_user(state: &AppState, headers: &HeaderMap) -> Result<Option<Uuid>> {
+    let session = CookieJar::from_headers(headers)
+        .get("session")
+        .map(|cookie| cookie.value().to_string());
+    if let Some(token) = session {
+        if let Some(session) = state.sessions.find_by_token(&token).await? {
+            return Ok(Some(session.user_id));
+        }
+    }
+
+    let bearer = headers
+        .get("Authorization")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    if let Some(token) = bearer {
+        if let Some(api_token) = state.tokens.find_by_hash(&hash_token(token)).await? {
+            return Ok(Some(api_token.user_id));
+        }
+    }
+
+    Ok(None)
+}
+
+fn client_ip(state: &AppState, request: &Request) -> IpAddr {
+    state
+        .trusted_proxies
+        .client_ip(peer_ip(request.extensions()), request.headers())
+}
+
+/// Limit API requests per user and per IP
+pub async fn limit_api(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Result<Response> {
+    let limits = state.rate_limiter.limits();
+    if limits.api_per_user > 0 || limits.api_per_ip > 0 {
+        let user_id = request_user(&state, request.headers()).await?;
+        state.rate_limiter.check_all(
+            Kind::Api,
+            user_id,
+            client_ip(&state, &request),
+            (limits.api_per_user, limits.api_per_ip),
+        )?;
+    }
+    Ok(next.run(request).await)
+}
+
+/// Stricter limit for chat messages, which call the LLM
+pub async fn limit_chat(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Result<Response> {
+    let limits = state.rate_limiter.limits();
+    if limits.chat_per_user > 0 || limits.chat_per_ip > 0 {
+        let user_id = request_user(&state, request.headers()).await?;
+        state.rate_limiter.check_all(
+            Kind::Chat,
+            user_id,
+            client_ip(&state, &request),
+            (limits.chat_per_user, limits.chat_per_ip),
+        )?;
+    }
+    Ok(next.run(request).await)
+}
+
+/// Limit login attempts per IP
+pub async fn limit_login(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Result<Response> {
+    let limits = state.rate_limiter.limits();
+    state.rate_limiter.check_all(
+        Kind::Login,
+        None,
+        client_ip(&state, &request),
+        (0, limits.login_per_ip),
+    )?;
+    Ok(next.run(request).await)
+}
+
+/// Limit comments left by guests per IP
+pub async fn limit_guest_comments(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Result<Response> {
+    let limits = state.rate_limiter.limits();
+    state.rate_limiter.check_all(
+        Kind::GuestComment,
+        None,
+        client_ip(&state, &request),
+        (0, limits.guest_comments_per_ip),
+    )?;
+    Ok(next.run(request).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_bucket_refills_over_time() {
+        let limiter = RateLimiter::new(RateLimits::default());
+        let client = Client::Ip("203.0.113.9".parse().unwrap());
+        let start = Instant::now();
+
+        for _ in 0..3 {
+            assert_eq!(limiter.check(Kind::Login, client, 3, start), Ok(()));
+        }
+        // One request per 20 seconds comes back
+        assert_eq!(limiter.check(Kind::Login, client, 3, start), Err(20));
+        let later = start + Duration::from_secs(15);
+        assert_eq!(limiter.check(Kind::Login, client, 3, later), Err(5));
+        let later = start + Duration::from_secs(20);
+        assert_eq!(limiter.check(Kind::Login, client, 3, later), Ok(()));
+    }
+
+    #[test]
+    fn test_clients_and_kinds_have_separate_buckets() {
+        let limiter = RateLimiter::new(RateLimits::default());
+        let now = Instant::now();
+        let user = Client::User(Uuid::new_v4());
+
+        assert_eq!(limiter.check(Kind::Chat, user, 1, now), Ok(()));
+        assert!(limiter.check(Kind::Chat, user, 1, now).is_err());
+        assert_eq!(limiter.check(Kind::Api, user, 1, now), Ok(()));
+        assert_eq!(
+            limiter.check(Kind::Chat, Client::User(Uuid::new_v4()), 1, now),
+            Ok(())
+        );
+        // A limit of 0 is no limit
+        assert_eq!(limiter.check(Kind::Chat, user, 0, now), Ok(()));
+    }
+
+    #[test]
+    fn test_refused_request_takes_from_neither_bucket() {
+        let limiter = RateLimiter::new(RateLimits::default());
+        let user_id = Uuid::new_v4();
+        let ip: IpAddr = "203.0.113.9".parse().unwrap();
+        let other_ip: IpAddr = "198.51.100.7".parse().unwrap();
+
+        assert!(limiter
+            .check_all(Kind::Api, Some(user_id), ip, (2, 1))
+            .is_ok());
+        // Refused by the IP limit, so the user keeps their last request
+        assert!(limiter
+            .check_all(Kind::Api, Some(user_id), ip, (2, 1))
+            .is_err());
+        assert!(limiter
+            .check_all(Kind::Api, Some(user_id), other_ip, (2, 1))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_ipv6_clients_are_limited_by_network() {
+        let limiter = RateLimiter::new(RateLimits::default());
+        let limit = (0, 1);
+
+        let ip: IpAddr = "2001:db8:1:2::1".parse().unwrap();
+        assert!(limiter.check_all(Kind::Login, None, ip, limit).is_ok());
+        let same_network: IpAddr = "2001:db8:1:2:ffff::9".parse().unwrap();
+        assert!(limiter
+            .check_all(Kind::Login, None, same_network, limit)
+            .is_err());
+        let other_network: IpAddr = "2001:db8:1:3::1".parse().unwrap();
+        assert!(limiter
+            .check_all(Kind::Login, None, other_network, limit)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_least_recently_used_buckets_are_evicted() {
+        let limiter = RateLimiter::new(RateLimits::default());
+        let start = Instant::now();
+        let clients = MAX_TRACKED_BUCKETS as u32 + 1;
+
+        for i in 0..clients {
+            let client = Client::Ip(IpAddr::V4(i.into()));
+            let now = start + Duration::from_millis(u64::from(i));
+            assert_eq!(limiter.check(Kind::Api, client, 1, now), Ok(()));
+        }
+
+        let buckets = limiter.buckets.lock().unwrap();
+        assert_eq!(buckets.len(), BUCKETS_KEPT_ON_EVICTION + 1);
+        assert!(buckets.contains_key(&(Kind::Api, Client::Ip(IpAddr::V4((clients - 1).into())))));
+        assert!(!buckets.contains_key(&(Kind::Api, Client::Ip(IpAddr::V4(0.into())))));
+    }
+}
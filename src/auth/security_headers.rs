@@ -0,0 +1,134 @@
+use axum::{
+    extract::{Request, State},
+    http::{
+        header::{
+            CONTENT_SECURITY_POLICY, REFERRER_POLICY, STRICT_TRANSPORT_SECURITY,
+            X_CONTENT_TYPE_OPTIONS, X_FRAME_OPTIONS,
+        },
+        HeaderValue,
+    },
+    middleware::Next,
+    response::Response,
+};
+use tracing::warn;
+
+use crate::state::AppState;
+
+/// Pages only run the app's own scripts and those of the CDNs serving Bootstrap and the
+/// API docs; inline scripts and event handlers are refused. Inline styles stay allowed
+/// for the colors and sizes the templates set per element.
+const POLICY: &str = "default-src 'self'; \
+    script-src 'self' https://cdn.jsdelivr.net https://unpkg.com; \
+    style-src 'self' 'unsafe-inline' https://cdn.jsdelivr.net https://unpkg.com; \
+    font-src 'self' https://cdn.jsdelivr.net; \
+    img-src 'self' data:; \
+    connect-src 'self'; \
+    object-src 'none'; \
+    base-uri 'self'; \
+    form-action 'self'; \
+    frame-ancestors 'none'";
+
+/// Headers hardening every response. `Strict-Transport-Security` is only sent when
+/// `HSTS_MAX_AGE` (seconds) is set, as browsers then refuse plain HTTP to the host for
+/// that long; set it once the app is only served over HTTPS.
+#[derive(Debug, Clone, Default)]
+pub struct SecurityHeaders {
+    hsts: Option<HeaderValue>,
+}
+
+impl SecurityHeaders {
+    pub fn from_env() -> Self {
+        let Some(value) = std::env::var("HSTS_MAX_AGE")
+            .ok()
+            .filter(|value| !value.trim().is_empty())
+        else {
+            return Self::default();
+        };
+        match value.trim().parse::<u64>() {
+            Ok(max_age) => Self::with_hsts(max_age),
+            Err(_) => {
+                warn!(value, "Ignoring invalid HSTS_MAX_AGE");
+                Self::default()
+            }
+        }
+    }
+
+    /// Send `Strict-Transport-Security` for `max_age` seconds, subdomains included
+    pub fn with_hsts(max_age: u64) -> Self {
+        let value = format!("max-age={}; includeSubDomains", max_age);
+        Self {
+            hsts: HeaderValue::from_str(&value).ok(),
+        }
+    }
+}
+
+/// Set the security headers on the response, unless the handler chose its own
+pub async fn security_headers(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let mut response = next.run(request).await;
+    let headers = response.headers_mut();
+    headers
+        .entry(CONTENT_SECURITY_POLICY)
+        .or_insert(HeaderValue::from_static(POLICY));
+    headers
+        .entry(X_FRAME_OPTIONS)
+        .or_insert(HeaderValue::from_static("DENY"));
+    headers
+        .entry(X_CONTENT_TYPE_OPTIONS)
+        .or_insert(HeaderValue::from_static("nosniff"));
+    // Share links carry their token in the path, which must not leak to other sites
+    headers
+        .entry(REFERRER_POLICY)
+        .or_insert(HeaderValue::from_static("same-origin"));
+    if let Some(hsts) = &state.security_headers.hsts {
+        headers
+            .entry(STRICT_TRANSPORT_SECURITY)
+            .or_insert(hsts.clone());
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hsts_value() {
+        let headers = SecurityHeaders::with_hsts(31_536_000);
+        assert_eq!(headers.hsts.unwrap(), "max-age=31536000; includeSubDomains");
+        assert!(SecurityHeaders::default().hsts.is_none());
+    }
+
+    #[test]
+    fn test_policy_is_a_valid_header() {
+        assert!(HeaderValue::from_str(POLICY).is_ok());
+        let scripts = POLICY
+            .split("; ")
+            .find(|directive| directive.starts_with("script-src"))
+            .unwrap();
+        assert!(!scripts.contains("'unsafe-inline'"));
+    }
+
+    /// The policy refuses inline event handlers, so behaviours go in forms.js instead
+    #[test]
+    fn test_templates_have_no_inline_handlers() {
+        let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("templates");
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            let html = std::fs::read_to_string(&path).unwrap();
+            for word in html.split_whitespace() {
+                let Some((name, _)) = word.split_once('=') else {
+                    continue;
+                };
+                let name = name.to_ascii_lowercase();
+                let handler = name.len() > 2
+                    && name.starts_with("on")
+                    && name.chars().all(|c| c.is_ascii_alphabetic());
+                assert!(!handler, "{}: inline handler {}", path.display(), word);
+            }
+        }
+    }
+}
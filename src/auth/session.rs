@@ -0,0 +1,119 @@
+use axum::{
+    extract::{Request, State},
+    http::header::SET_COOKIE,
+    middleware::Next,
+    response::Response,
+};
+use axum_extra::extract::cookie::{Cookie, CookieJar, SameSite};
+use chrono::{Duration, Utc};
+use tracing::warn;
+
+use crate::error::Result;
+use crate::state::AppState;
+
+/// Days a session lasts when SESSION_LIFETIME_DAYS is not set
+const DEFAULT_LIFETIME_DAYS: i64 = 7;
+
+/// Days a "remember me" session lasts when SESSION_REMEMBER_DAYS is not set
+const DEFAULT_REMEMBER_DAYS: i64 = 30;
+
+/// A session is extended at most this often, not on every request
+const RENEW_INTERVAL: Duration = Duration::hours(1);
+
+/// How often the purge job runs
+const PURGE_PERIOD: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+
+/// How long a session lasts after the last activity, from SESSION_LIFETIME_DAYS, or
+/// SESSION_REMEMBER_DAYS for "remember me" logins
+pub fn session_lifetime(remember: bool) -> Duration {
+    let (name, default) = if remember {
+        ("SESSION_REMEMBER_DAYS", DEFAULT_REMEMBER_DAYS)
+    } else {
+        ("SESSION_LIFETIME_DAYS", DEFAULT_LIFETIME_DAYS)
+    };
+    let days = std::env::var(name)
+        .ok()
+        .and_then(|v| v.trim().parse().ok())
+        .filter(|days| *days > 0)
+        .unwrap_or(default);
+    Duration::days(days)
+}
+
+/// Cookie holding a session token. "Remember me" sessions survive closing the browser,
+/// others end with it.
+pub fn session_cookie(token: String, remember: bool) -> Cookie<'static> {
+    let mut cookie = Cookie::build(("session", token))
+        .path("/")
+        .http_only(true)
+        .same_site(SameSite::Lax)
+        .build();
+    if remember {
+        let lifetime = session_lifetime(true).num_seconds();
+        cookie.set_max_age(time::Duration::seconds(lifetime));
+    }
+    cookie
+}
+
+/// Extend the session of the request on activity. The cookie of a "remember me"
+/// session is sent again so the browser keeps it as long as the server does.
+pub async fn renew_session(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let token = CookieJar::from_headers(request.headers())
+        .get("session")
+        .map(|cookie| cookie.value().to_string());
+    let renewed = match token {
+        Some(token) => renew(&state, token).await.unwrap_or_else(|e| {
+            warn!(error = %e, "Could not renew session");
+            None
+        }),
+        None => None,
+    };
+
+    let mut response = next.run(request).await;
+    // Logging in or out replaces the cookie, which must not be overwritten
+    let sets_session = response
+        .headers()
+        .get_all(SET_COOKIE)
+        .iter()
+        .any(|value| value.as_bytes().starts_with(b"session="));
+    if let Some(cookie) = renewed.filter(|_| !sets_session) {
+        if let Ok(value) = cookie.to_string().parse() {
+            response.headers_mut().append(SET_COOKIE, value);
+        }
+    }
+    response
+}
+
+/// Extend a live session when it was last extended more than RENEW_INTERVAL ago,
+/// returning the cookie to send again for "remember me" sessions
+async fn renew(state: &AppState, token: String) -> Result<Option<Cookie<'static>>> {
+    let Some(session) = state.sessions.find_by_token(&token).await? else {
+        return Ok(None);
+    };
+    let lifetime = session_lifetime(session.remember);
+    let expires_at = Utc::now() + lifetime;
+    if session.expires_at > expires_at - RENEW_INTERVAL {
+        return Ok(None);
+    }
+
+    state
+        .sessions
+        .set_expires_at(session.id, expires_at)
+        .await?;
+    Ok(session.remember.then(|| session_cookie(token, true)))
+}
+
+/// Delete expired sessions now and then, until the server shuts down
+pub async fn run_purge_job(state: AppState) {
+    let mut ticks = tokio::time::interval(PURGE_PERIOD);
+    while state.jobs.tick(&mut ticks).await {
+        match state.sessions.delete_expired().await {
+            Ok(0) => {}
+            Ok(purged) => tracing::info!("Purged {} expired session(s)", purged),
+            Err(e) => tracing::warn!(error = %e, "Session purge failed"),
+        }
+    }
+}
@@ -0,0 +1,303 @@
+use std::io::{BufRead, IsTerminal, Write};
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand};
+use personal_os::client::{self, short_id, ApiClient, Card, ChatReply};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt};
+
+/// Command line client of Personal OS
+#[derive(Parser)]
+#[command(name = "pos-cli", version)]
+struct Cli {
+    /// Address of the server
+    #[arg(
+        long,
+        env = "POS_URL",
+        default_value = "http://localhost:3000",
+        global = true
+    )]
+    url: String,
+
+    /// API token, created with POST /api/auth/tokens
+    #[arg(long, env = "POS_TOKEN", hide_env_values = true, global = true)]
+    token: Option<String>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// List and create boards
+    #[command(subcommand)]
+    Boards(BoardsCommand),
+
+    /// List, add and move the cards of a board
+    #[command(subcommand)]
+    Cards(CardsCommand),
+
+    /// Capture cards that belong to no board yet
+    #[command(subcommand)]
+    Inbox(InboxCommand),
+
+    /// Ask the assistant, about a board or about everything
+    Chat {
+        /// Board the message is about, by name or id
+        #[arg(long)]
+        board: Option<String>,
+        /// Apply proposed changes without asking
+        #[arg(long, short)]
+        yes: bool,
+        #[arg(required = true, num_args = 1..)]
+        message: Vec<String>,
+    },
+
+    /// Serve the server's MCP tools over stdin and stdout, for agents such as Claude
+    /// Desktop
+    Mcp,
+}
+
+#[derive(Subcommand)]
+enum BoardsCommand {
+    /// List the boards you are a member of
+    List,
+    /// Create a board
+    Create {
+        name: String,
+        #[arg(long)]
+        description: Option<String>,
+        /// Columns to start with: kanban, sprint, gtd or the id of a saved template
+        #[arg(long)]
+        template: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum CardsCommand {
+    /// List the cards of a board, column by column
+    List {
+        /// Board, by name or id
+        board: String,
+    },
+    /// Add a card to a board
+    Add {
+        /// Board, by name or id
+        board: String,
+        title: String,
+        /// Column, by name or id; the board's default column otherwise
+        #[arg(long)]
+        column: Option<String>,
+        #[arg(long)]
+        body: Option<String>,
+    },
+    /// Move a card to the end of another column
+    Move {
+        /// Board, by name or id
+        board: String,
+        /// Card, by id (the first characters are enough) or title
+        card: String,
+        /// Column, by name or id
+        column: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum InboxCommand {
+    /// Add a card to the inbox
+    Add {
+        title: String,
+        #[arg(long)]
+        body: Option<String>,
+    },
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let Some(token) = cli.token.as_deref().filter(|t| !t.trim().is_empty()) else {
+        eprintln!("error: set POS_TOKEN or pass --token with an API token");
+        return ExitCode::FAILURE;
+    };
+    let api = ApiClient::new(&cli.url, token.trim());
+
+    if let Command::Mcp = cli.command {
+        return match serve_mcp(&api).await {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("error: {}", e);
+                ExitCode::FAILURE
+            }
+        };
+    }
+
+    match run(&api, cli.command).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(api: &ApiClient, command: Command) -> client::Result<()> {
+    match command {
+        Command::Boards(BoardsCommand::List) => {
+            for board in api.boards().await? {
+                println!("{}  {}  ({})", short_id(board.id), board.name, board.role);
+            }
+        }
+        Command::Boards(BoardsCommand::Create {
+            name,
+            description,
+            template,
+        }) => {
+            let board = api
+                .create_board(&name, description.as_deref(), template.as_deref())
+                .await?;
+            println!("Created board {}  {}", short_id(board.id), board.name);
+        }
+        Command::Cards(CardsCommand::List { board }) => {
+            let board = api.board(api.find_board(&board).await?.id).await?;
+            for (i, column) in board.columns.iter().enumerate() {
+                if i > 0 {
+                    println!();
+                }
+                println!("{} ({})", column.name, column.cards.len());
+                for card in &column.cards {
+                    println!("  {}", card_line(card));
+                }
+            }
+        }
+        Command::Cards(CardsCommand::Add {
+            board,
+            title,
+            column,
+            body,
+        }) => {
+            let board = api.board(api.find_board(&board).await?.id).await?;
+            let column = match column {
+                Some(column) => board.find_column(&column)?,
+                None => board.default_column().ok_or_else(|| {
+                    client::ClientError::Lookup(format!("Board {} has no column", board.name))
+                })?,
+            };
+            let card = api.create_card(column.id, &title, body.as_deref()).await?;
+            println!("Added to {}: {}", column.name, card_line(&card));
+        }
+        Command::Cards(CardsCommand::Move {
+            board,
+            card,
+            column,
+        }) => {
+            let board = api.board(api.find_board(&board).await?.id).await?;
+            let card = board.find_card(&card)?;
+            let column = board.find_column(&column)?;
+            if card.column_id == Some(column.id) {
+                println!("Already in {}: {}", column.name, card_line(card));
+                return Ok(());
+            }
+            let position = column
+                .cards
+                .iter()
+                .map(|c| c.position + 1)
+                .max()
+                .unwrap_or(0);
+            let card = api.move_card(card.id, column.id, position).await?;
+            println!("Moved to {}: {}", column.name, card_line(&card));
+        }
+        Command::Inbox(InboxCommand::Add { title, body }) => {
+            let card = api.create_inbox_card(&title, body.as_deref()).await?;
+            println!("Added to the inbox: {}", card_line(&card));
+        }
+        Command::Chat {
+            board,
+            yes,
+            message,
+        } => {
+            let board_id = match board {
+                Some(board) => Some(api.find_board(&board).await?.id),
+                None => None,
+            };
+            let reply = api
+                .chat(board_id, &message.join(" "), yes.then_some(false))
+                .await?;
+            print_reply(&reply);
+
+            if let (Some(board_id), Some(pending)) = (board_id, reply.pending) {
+                println!("\nProposed changes:");
+                for action in &pending.actions {
+                    println!("  - {}", action.message);
+                }
+                if confirm("Apply them?") {
+                    let reply = api
+                        .confirm_chat(board_id, pending.confirmation_token)
+                        .await?;
+                    print_reply(&reply);
+                } else {
+                    println!("Nothing was changed.");
+                }
+            }
+        }
+        Command::Mcp => unreachable!("served by serve_mcp"),
+    }
+    Ok(())
+}
+
+/// Relay the newline-delimited JSON-RPC messages of stdin to the server, writing its
+/// answers to stdout
+async fn serve_mcp(api: &ApiClient) -> std::io::Result<()> {
+    let mut lines = tokio::io::BufReader::new(tokio::io::stdin()).lines();
+    let mut stdout = tokio::io::stdout();
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(answer) = api.mcp(&line).await {
+            stdout.write_all(answer.as_bytes()).await?;
+            stdout.write_all(b"\n").await?;
+            stdout.flush().await?;
+        }
+    }
+    Ok(())
+}
+
+/// A card on one line: short id, title, and what stands out about it
+fn card_line(card: &Card) -> String {
+    let mut line = format!("{}  {}", short_id(card.id), card.title);
+    let mut notes = Vec::new();
+    if card.status != "open" {
+        notes.push(card.status.replace('_', " "));
+    }
+    if let Some(priority) = &card.priority {
+        notes.push(format!("{} priority", priority));
+    }
+    if let Some(due) = card.due_date {
+        notes.push(format!("due {}", due));
+    }
+    if !notes.is_empty() {
+        line.push_str(&format!("  [{}]", notes.join(", ")));
+    }
+    line
+}
+
+fn print_reply(reply: &ChatReply) {
+    println!("{}", reply.response);
+    for action in &reply.actions_taken {
+        let mark = if action.success { "done" } else { "failed" };
+        println!("  [{}] {}", mark, action.description);
+    }
+}
+
+/// Ask a yes/no question on the terminal; anything but yes is no
+fn confirm(question: &str) -> bool {
+    if !std::io::stdin().is_terminal() {
+        return false;
+    }
+    print!("{} [y/N] ", question);
+    let _ = std::io::stdout().flush();
+    let mut answer = String::new();
+    if std::io::stdin().lock().read_line(&mut answer).is_err() {
+        return false;
+    }
+    matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+}
@@ -0,0 +1,413 @@
+//! Client of the REST API authenticated with an API token, used by `pos-cli`
+
+use chrono::NaiveDate;
+use reqwest::{header, Method, StatusCode};
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::models::chat::{ActionTaken, LlmAction};
+
+/// Boards fetched per request when listing them all
+const BOARD_PAGE_SIZE: usize = 500;
+
+/// JSON-RPC error code of MCP messages the server could not answer
+const MCP_TRANSPORT_ERROR: i64 = -32000;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ClientError {
+    #[error("Cannot reach the server: {0}")]
+    Http(#[from] reqwest::Error),
+
+    /// An error response of the API
+    #[error("{message} ({status})")]
+    Api {
+        status: StatusCode,
+        code: String,
+        message: String,
+    },
+
+    /// A board, column or card given on the command line matches nothing, or more
+    /// than one item
+    #[error("{0}")]
+    Lookup(String),
+}
+
+pub type Result<T> = std::result::Result<T, ClientError>;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Board {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub role: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BoardDetails {
+    pub id: Uuid,
+    pub name: String,
+    pub default_column_id: Option<Uuid>,
+    pub columns: Vec<Column>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Column {
+    pub id: Uuid,
+    pub name: String,
+    pub cards: Vec<Card>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Card {
+    pub id: Uuid,
+    pub column_id: Option<Uuid>,
+    pub title: String,
+    pub position: i32,
+    pub status: String,
+    pub due_date: Option<NaiveDate>,
+    pub priority: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChatReply {
+    pub response: String,
+    pub actions_taken: Vec<ActionTaken>,
+    pub pending: Option<PendingActions>,
+}
+
+/// Actions the assistant proposed, applied once confirmed
+#[derive(Debug, Clone, Deserialize)]
+pub struct PendingActions {
+    pub confirmation_token: Uuid,
+    pub actions: Vec<LlmAction>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ErrorBody {
+    code: String,
+    message: String,
+}
+
+/// First characters of an id, enough to tell cards apart on a board
+pub fn short_id(id: Uuid) -> String {
+    id.simple().to_string()[..8].to_string()
+}
+
+/// The only item whose id starts with `query` or whose name is `query`, ignoring case
+fn find_one<'a, T>(
+    items: &'a [T],
+    query: &str,
+    kind: &str,
+    id: impl Fn(&T) -> Uuid,
+    name: impl Fn(&T) -> &str,
+) -> Result<&'a T> {
+    let query = query.trim();
+    let prefix = query.to_lowercase().replace('-', "");
+    let by_name: Vec<&T> = items
+        .iter()
+        .filter(|item| name(item).eq_ignore_ascii_case(query))
+        .collect();
+    let matches = if by_name.is_empty() && !prefix.is_empty() {
+        items
+            .iter()
+            .filter(|item| id(item).simple().to_string().starts_with(&prefix))
+            .collect()
+    } else {
+        by_name
+    };
+
+    match matches.as_slice() {
+        [item] => Ok(item),
+        [] => Err(ClientError::Lookup(format!(
+            "No {} matches \"{}\"",
+            kind, query
+        ))),
+        _ => Err(ClientError::Lookup(format!(
+            "\"{}\" matches {} {}s, be more specific",
+            query,
+            matches.len(),
+            kind
+        ))),
+    }
+}
+
+/// The board given by name or id among `boards`
+pub fn find_board<'a>(boards: &'a [Board], query: &str) -> Result<&'a Board> {
+    find_one(boards, query, "board", |b| b.id, |b| &b.name)
+}
+
+impl BoardDetails {
+    /// The column given by name or id
+    pub fn find_column(&self, query: &str) -> Result<&Column> {
+        find_one(&self.columns, query, "column", |c| c.id, |c| &c.name)
+    }
+
+    /// The card given by id, or by title when no id starts with `query`
+    pub fn find_card(&self, query: &str) -> Result<&Card> {
+        let cards: Vec<&Card> = self.columns.iter().flat_map(|c| &c.cards).collect();
+        find_one(&cards, query, "card", |c| c.id, |c| &c.title).copied()
+    }
+
+    /// Where new cards go: the board's default column, or else its first one
+    pub fn default_column(&self) -> Option<&Column> {
+        self.default_column_id
+            .and_then(|id| self.columns.iter().find(|c| c.id == id))
+            .or_else(|| self.columns.first())
+    }
+}
+
+pub struct ApiClient {
+    http: reqwest::Client,
+    base_url: String,
+    token: String,
+}
+
+impl ApiClient {
+    /// A client of the server at `base_url`, such as `http://localhost:3000`
+    pub fn new(base_url: &str, token: &str) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            token: token.to_string(),
+        }
+    }
+
+    async fn request<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<Value>,
+    ) -> Result<T> {
+        let mut request = self
+            .http
+            .request(method, format!("{}/api{}", self.base_url, path))
+            .header(header::AUTHORIZATION, format!("Bearer {}", self.token));
+        if let Some(body) = body {
+            request = request.json(&body);
+        }
+
+        let response = request.send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(match serde_json::from_str::<ErrorBody>(&body) {
+                Ok(error) => ClientError::Api {
+                    status,
+                    code: error.code,
+                    message: error.message,
+                },
+                Err(_) => ClientError::Api {
+                    status,
+                    code: "unexpected_response".to_string(),
+                    message: body,
+                },
+            });
+        }
+        Ok(response.json().await?)
+    }
+
+    /// Every board the user is a member of
+    pub async fn boards(&self) -> Result<Vec<Board>> {
+        let mut boards = Vec::new();
+        loop {
+            let page: Vec<Board> = self
+                .request(
+                    Method::GET,
+                    &format!("/boards?limit={}&offset={}", BOARD_PAGE_SIZE, boards.len()),
+                    None,
+                )
+                .await?;
+            let last = page.len() < BOARD_PAGE_SIZE;
+            boards.extend(page);
+            if last {
+                return Ok(boards);
+            }
+        }
+    }
+
+    /// The board given by name or id
+    pub async fn find_board(&self, query: &str) -> Result<Board> {
+        let boards = self.boards().await?;
+        find_board(&boards, query).cloned()
+    }
+
+    /// Create a board, with the columns of `template` when given
+    pub async fn create_board(
+        &self,
+        name: &str,
+        description: Option<&str>,
+        template: Option<&str>,
+    ) -> Result<Board> {
+        self.request(
+            Method::POST,
+            "/boards",
+            Some(json!({"name": name, "description": description, "template": template})),
+        )
+        .await
+    }
+
+    /// A board with its columns and their cards
+    pub async fn board(&self, board_id: Uuid) -> Result<BoardDetails> {
+        self.request(Method::GET, &format!("/boards/{}", board_id), None)
+            .await
+    }
+
+    pub async fn create_card(
+        &self,
+        column_id: Uuid,
+        title: &str,
+        body: Option<&str>,
+    ) -> Result<Card> {
+        self.request(
+            Method::POST,
+            &format!("/columns/{}/cards", column_id),
+            Some(json!({"title": title, "body": body})),
+        )
+        .await
+    }
+
+    pub async fn move_card(&self, card_id: Uuid, column_id: Uuid, position: i32) -> Result<Card> {
+        self.request(
+            Method::PATCH,
+            &format!("/cards/{}/move", card_id),
+            Some(json!({"column_id": column_id, "position": position})),
+        )
+        .await
+    }
+
+    /// Create a card in the inbox, on no board
+    pub async fn create_inbox_card(&self, title: &str, body: Option<&str>) -> Result<Card> {
+        self.request(
+            Method::POST,
+            "/cards",
+            Some(json!({"title": title, "body": body})),
+        )
+        .await
+    }
+
+    /// Send a message to the assistant of a board, or to the global one. With
+    /// `require_confirmation` unset, the user's setting decides whether board actions
+    /// wait for confirmation.
+    pub async fn chat(
+        &self,
+        board_id: Option<Uuid>,
+        message: &str,
+        require_confirmation: Option<bool>,
+    ) -> Result<ChatReply> {
+        let path = match board_id {
+            Some(board_id) => format!("/boards/{}/chat", board_id),
+            None => "/chat".to_string(),
+        };
+        self.request(
+            Method::POST,
+            &path,
+            Some(json!({"message": message, "require_confirmation": require_confirmation})),
+        )
+        .await
+    }
+
+    /// Apply the actions the assistant of a board proposed
+    pub async fn confirm_chat(
+        &self,
+        board_id: Uuid,
+        confirmation_token: Uuid,
+    ) -> Result<ChatReply> {
+        self.request(
+            Method::POST,
+            &format!("/boards/{}/chat/confirm", board_id),
+            Some(json!({"confirmation_token": confirmation_token})),
+        )
+        .await
+    }
+
+    /// Relay a JSON-RPC message to the MCP server and return its answer, if the message
+    /// calls for one. Failures to reach the server are answered as JSON-RPC errors, so
+    /// the agent on the other end is never left waiting.
+    pub async fn mcp(&self, message: &str) -> Option<String> {
+        let id = serde_json::from_str::<Value>(message)
+            .ok()
+            .and_then(|m| m.get("id").cloned());
+        let error = |message: String| {
+            id.clone().map(|id| {
+                json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "error": {"code": MCP_TRANSPORT_ERROR, "message": message},
+                })
+                .to_string()
+            })
+        };
+
+        let sent = self
+            .http
+            .post(format!("{}/api/mcp", self.base_url))
+            .header(header::AUTHORIZATION, format!("Bearer {}", self.token))
+            .header(header::CONTENT_TYPE, "application/json")
+            .header(header::ACCEPT, "application/json, text/event-stream")
+            .body(message.to_string())
+            .send()
+            .await;
+        let response = match sent {
+            Ok(response) => response,
+            Err(e) => return error(ClientError::from(e).to_string()),
+        };
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+
+        if status == StatusCode::ACCEPTED || body.is_empty() {
+            return None;
+        }
+        match serde_json::from_str::<Value>(&body) {
+            Ok(answer) if answer.get("jsonrpc").is_some() => Some(body),
+            // An error of the API, such as a revoked token
+            _ => error(
+                serde_json::from_str::<ErrorBody>(&body)
+                    .map(|e| e.message)
+                    .unwrap_or(body),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn board(name: &str) -> Board {
+        Board {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            description: None,
+            role: "owner".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_find_board_by_name_or_id_prefix() {
+        let boards = vec![board("Work"), board("Home")];
+
+        assert_eq!(find_board(&boards, "work").unwrap().id, boards[0].id);
+        let prefix = short_id(boards[1].id);
+        assert_eq!(find_board(&boards, &prefix).unwrap().id, boards[1].id);
+        assert_eq!(
+            find_board(&boards, &boards[1].id.to_string()).unwrap().id,
+            boards[1].id
+        );
+        assert!(matches!(
+            find_board(&boards, "Garden"),
+            Err(ClientError::Lookup(_))
+        ));
+    }
+
+    #[test]
+    fn test_find_board_rejects_ambiguous_names() {
+        let boards = vec![board("Work"), board("work")];
+
+        let error = find_board(&boards, "Work").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "\"Work\" matches 2 boards, be more specific"
+        );
+    }
+}
@@ -0,0 +1,225 @@
+use rustls::pki_types::{pem::PemObject, CertificateDer, PrivateKeyDer};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+const DEFAULT_PORT: u16 = 3000;
+const DEFAULT_SHUTDOWN_TIMEOUT_SECS: u64 = 30;
+
+/// Invalid server settings, reported at startup instead of falling back silently
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    #[error("{name} is invalid: {value}")]
+    Invalid { name: &'static str, value: String },
+
+    #[error("HTTPS needs both TLS_CERT_FILE and TLS_KEY_FILE")]
+    IncompleteTls,
+
+    #[error("Cannot load TLS certificate from {path}: {message}")]
+    Certificate { path: PathBuf, message: String },
+}
+
+/// Certificate chain and private key files, in PEM format
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TlsFiles {
+    pub cert: PathBuf,
+    pub key: PathBuf,
+}
+
+impl TlsFiles {
+    /// Read the files into a rustls configuration offering HTTP/2 and HTTP/1.1
+    pub fn load(&self) -> Result<Arc<rustls::ServerConfig>, ConfigError> {
+        let error = |path: &PathBuf, message: String| ConfigError::Certificate {
+            path: path.clone(),
+            message,
+        };
+
+        let certs = CertificateDer::pem_file_iter(&self.cert)
+            .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+            .map_err(|e| error(&self.cert, e.to_string()))?;
+        if certs.is_empty() {
+            return Err(error(&self.cert, "no certificate found".to_string()));
+        }
+        let key =
+            PrivateKeyDer::from_pem_file(&self.key).map_err(|e| error(&self.key, e.to_string()))?;
+
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+        let mut config = rustls::ServerConfig::builder_with_provider(provider)
+            .with_safe_default_protocol_versions()
+            .and_then(|builder| builder.with_no_client_auth().with_single_cert(certs, key))
+            .map_err(|e| error(&self.cert, e.to_string()))?;
+        config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+
+        Ok(Arc::new(config))
+    }
+}
+
+/// Where the server listens and how it is reached.
+///
+/// - `HOST`: address to bind, IPv4 or IPv6 (default `127.0.0.1`; `0.0.0.0` or `::` for
+///   every interface)
+/// - `PORT`: port to bind (default `3000`)
+/// - `TLS_CERT_FILE` and `TLS_KEY_FILE`: serve HTTPS with this certificate chain and key
+/// - `APP_URL`: public address of the web UI, which generated links point to. Defaults to
+///   `localhost` on the bound port.
+/// - `SHUTDOWN_TIMEOUT_SECS`: on SIGTERM or Ctrl+C, time given to requests and background
+///   tasks in progress before they are abandoned (default 30)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerConfig {
+    pub addr: SocketAddr,
+    pub tls: Option<TlsFiles>,
+    pub app_url: String,
+    pub shutdown_timeout: Duration,
+}
+
+impl ServerConfig {
+    pub fn from_env() -> Result<Self, ConfigError> {
+        Self::from_vars(|name| std::env::var(name).ok())
+    }
+
+    /// Build the configuration from `var`, which returns the value of a setting. Blank
+    /// values count as unset.
+    pub fn from_vars(var: impl Fn(&str) -> Option<String>) -> Result<Self, ConfigError> {
+        let var = |name: &str| {
+            var(name)
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+
+        let host = match var("HOST") {
+            // Brackets are accepted around IPv6 addresses, as in URLs
+            Some(host) => host
+                .trim_start_matches('[')
+                .trim_end_matches(']')
+                .parse::<IpAddr>()
+                .map_err(|_| ConfigError::Invalid {
+                    name: "HOST",
+                    value: host,
+                })?,
+            None => IpAddr::V4(Ipv4Addr::LOCALHOST),
+        };
+        let port = match var("PORT") {
+            Some(port) => port.parse::<u16>().map_err(|_| ConfigError::Invalid {
+                name: "PORT",
+                value: port,
+            })?,
+            None => DEFAULT_PORT,
+        };
+
+        let tls = match (var("TLS_CERT_FILE"), var("TLS_KEY_FILE")) {
+            (Some(cert), Some(key)) => Some(TlsFiles {
+                cert: cert.into(),
+                key: key.into(),
+            }),
+            (None, None) => None,
+            _ => return Err(ConfigError::IncompleteTls),
+        };
+
+        let app_url = match var("APP_URL") {
+            Some(url) if url.starts_with("http://") || url.starts_with("https://") => {
+                url.trim_end_matches('/').to_string()
+            }
+            Some(url) => {
+                return Err(ConfigError::Invalid {
+                    name: "APP_URL",
+                    value: url,
+                })
+            }
+            None => {
+                let scheme = if tls.is_some() { "https" } else { "http" };
+                format!("{}://localhost:{}", scheme, port)
+            }
+        };
+
+        let shutdown_timeout = match var("SHUTDOWN_TIMEOUT_SECS") {
+            Some(secs) => secs.parse::<u64>().map_err(|_| ConfigError::Invalid {
+                name: "SHUTDOWN_TIMEOUT_SECS",
+                value: secs,
+            })?,
+            None => DEFAULT_SHUTDOWN_TIMEOUT_SECS,
+        };
+
+        Ok(Self {
+            addr: SocketAddr::new(host, port),
+            tls,
+            app_url,
+            shutdown_timeout: Duration::from_secs(shutdown_timeout),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn parse(vars: &[(&str, &str)]) -> Result<ServerConfig, ConfigError> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        ServerConfig::from_vars(|name| vars.get(name).cloned())
+    }
+
+    #[test]
+    fn test_defaults() {
+        let config = parse(&[("HOST", ""), ("APP_URL", " ")]).unwrap();
+        assert_eq!(config.addr, "127.0.0.1:3000".parse().unwrap());
+        assert_eq!(config.tls, None);
+        assert_eq!(config.app_url, "http://localhost:3000");
+        assert_eq!(config.shutdown_timeout, Duration::from_secs(30));
+
+        let config = parse(&[("SHUTDOWN_TIMEOUT_SECS", "5")]).unwrap();
+        assert_eq!(config.shutdown_timeout, Duration::from_secs(5));
+        assert!(parse(&[("SHUTDOWN_TIMEOUT_SECS", "soon")]).is_err());
+    }
+
+    #[test]
+    fn test_bind_address() {
+        let config = parse(&[("HOST", "::"), ("PORT", "8443")]).unwrap();
+        assert_eq!(config.addr, "[::]:8443".parse().unwrap());
+
+        let config = parse(&[("HOST", "[::1]")]).unwrap();
+        assert_eq!(config.addr, "[::1]:3000".parse().unwrap());
+
+        assert!(matches!(
+            parse(&[("HOST", "localhost")]),
+            Err(ConfigError::Invalid { name: "HOST", .. })
+        ));
+        assert!(matches!(
+            parse(&[("PORT", "70000")]),
+            Err(ConfigError::Invalid { name: "PORT", .. })
+        ));
+    }
+
+    #[test]
+    fn test_tls_and_app_url() {
+        let config = parse(&[
+            ("PORT", "8443"),
+            ("TLS_CERT_FILE", "cert.pem"),
+            ("TLS_KEY_FILE", "key.pem"),
+        ])
+        .unwrap();
+        assert_eq!(config.tls.unwrap().cert, PathBuf::from("cert.pem"));
+        assert_eq!(config.app_url, "https://localhost:8443");
+
+        assert!(matches!(
+            parse(&[("TLS_CERT_FILE", "cert.pem")]),
+            Err(ConfigError::IncompleteTls)
+        ));
+
+        let config = parse(&[("APP_URL", "https://os.example.com/")]).unwrap();
+        assert_eq!(config.app_url, "https://os.example.com");
+        assert!(parse(&[("APP_URL", "os.example.com")]).is_err());
+    }
+
+    #[test]
+    fn test_missing_certificate() {
+        let files = TlsFiles {
+            cert: "/nonexistent/cert.pem".into(),
+            key: "/nonexistent/key.pem".into(),
+        };
+        assert!(matches!(files.load(), Err(ConfigError::Certificate { .. })));
+    }
+}
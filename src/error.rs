@@ -1,17 +1,24 @@
 use axum::{
-    http::StatusCode,
+    body::Body,
+    extract::Request,
+    http::{header, HeaderValue, StatusCode},
+    middleware::Next,
     response::{IntoResponse, Response},
     Json,
 };
-use serde_json::json;
+use schemars::JsonSchema;
+use serde::Serialize;
+use serde_json::{json, Value};
 
 #[derive(Debug, thiserror::Error)]
 pub enum AppError {
     #[error("Database error: {0}")]
     Database(#[from] sqlx::Error),
 
-    #[error("Not found")]
-    NotFound,
+    /// The named resource, such as "board" or "card_template", does not exist or is
+    /// hidden from the user
+    #[error("{0} not found")]
+    NotFound(&'static str),
 
     #[error("Unauthorized")]
     Unauthorized,
@@ -27,6 +34,61 @@ pub enum AppError {
 
     #[error("Validation error: {0}")]
     Validation(String),
+
+    /// A validation error caused by one field of the request
+    #[error("Validation error on {field}: {message}")]
+    InvalidField {
+        field: &'static str,
+        message: String,
+    },
+
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
+    #[error("Precondition failed")]
+    PreconditionFailed,
+
+    #[error("Card is locked")]
+    Locked,
+
+    #[error("Too many requests")]
+    TooManyRequests { retry_after: u64 },
+}
+
+impl AppError {
+    pub fn invalid_field(field: &'static str, message: impl Into<String>) -> Self {
+        AppError::InvalidField {
+            field,
+            message: message.into(),
+        }
+    }
+
+    /// Stable identifier of the error, for clients to match on instead of the message
+    pub fn code(&self) -> String {
+        match self {
+            AppError::Database(_) | AppError::Internal(_) => "internal_error".to_string(),
+            AppError::NotFound(resource) => format!("{}_not_found", resource),
+            AppError::Unauthorized => "unauthorized".to_string(),
+            AppError::Forbidden => "forbidden".to_string(),
+            AppError::BadRequest(_) => "bad_request".to_string(),
+            AppError::Validation(_) | AppError::InvalidField { .. } => {
+                "validation_failed".to_string()
+            }
+            AppError::Conflict(_) => "conflict".to_string(),
+            AppError::PreconditionFailed => "precondition_failed".to_string(),
+            AppError::Locked => "card_locked".to_string(),
+            AppError::TooManyRequests { .. } => "rate_limited".to_string(),
+        }
+    }
+}
+
+/// Body of every API error response
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ErrorBody {
+    pub code: String,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<Value>,
 }
 
 impl IntoResponse for AppError {
@@ -39,7 +101,15 @@ impl IntoResponse for AppError {
                     "Internal server error".to_string(),
                 )
             }
-            AppError::NotFound => (StatusCode::NOT_FOUND, self.to_string()),
+            AppError::NotFound(resource) => {
+                let name = resource.replace('_', " ");
+                let mut chars = name.chars();
+                let name = match chars.next() {
+                    Some(first) => first.to_uppercase().chain(chars).collect(),
+                    None => name,
+                };
+                (StatusCode::NOT_FOUND, format!("{} not found", name))
+            }
             AppError::Unauthorized => (StatusCode::UNAUTHORIZED, self.to_string()),
             AppError::Forbidden => (StatusCode::FORBIDDEN, self.to_string()),
             AppError::BadRequest(msg) => (StatusCode::BAD_REQUEST, msg.clone()),
@@ -50,11 +120,90 @@ impl IntoResponse for AppError {
                     "Internal server error".to_string(),
                 )
             }
-            AppError::Validation(msg) => (StatusCode::UNPROCESSABLE_ENTITY, msg.clone()),
+            AppError::Validation(msg) | AppError::InvalidField { message: msg, .. } => {
+                (StatusCode::UNPROCESSABLE_ENTITY, msg.clone())
+            }
+            AppError::Conflict(msg) => (StatusCode::CONFLICT, msg.clone()),
+            AppError::PreconditionFailed => (
+                StatusCode::PRECONDITION_FAILED,
+                "The resource has changed since it was read; fetch it again and retry".to_string(),
+            ),
+            AppError::Locked => (
+                StatusCode::LOCKED,
+                "Card is locked; unlock it before making changes".to_string(),
+            ),
+            AppError::TooManyRequests { retry_after } => (
+                StatusCode::TOO_MANY_REQUESTS,
+                format!("Too many requests; retry in {} seconds", retry_after),
+            ),
+        };
+        let details = match &self {
+            AppError::InvalidField { field, message } => {
+                Some(json!({ "fields": { *field: message } }))
+            }
+            AppError::TooManyRequests { retry_after } => {
+                Some(json!({ "retry_after": retry_after }))
+            }
+            _ => None,
         };
+        let body = ErrorBody {
+            code: self.code(),
+            message,
+            details,
+        };
+
+        let mut response = (status, Json(body)).into_response();
+        if let AppError::TooManyRequests { retry_after } = self {
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(retry_after));
+        }
+        response
+    }
+}
 
-        (status, Json(json!({ "error": message }))).into_response()
+/// Rewrite the plain text errors produced outside of the handlers, such as a body
+/// that doesn't deserialize or a method the route doesn't accept, into an `ErrorBody`
+pub async fn json_errors(request: Request, next: Next) -> Response {
+    let response = next.run(request).await;
+    let status = response.status();
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .is_some_and(|v| v.as_bytes().starts_with(b"application/json"));
+    if !(status.is_client_error() || status.is_server_error()) || is_json {
+        return response;
     }
+
+    let (mut parts, body) = response.into_parts();
+    let text = axum::body::to_bytes(body, 64 * 1024)
+        .await
+        .map(|bytes| String::from_utf8_lossy(&bytes).trim().to_string())
+        .unwrap_or_default();
+    let reason = status.canonical_reason().unwrap_or("Error");
+    let code = match status {
+        StatusCode::BAD_REQUEST | StatusCode::UNPROCESSABLE_ENTITY => "invalid_request".to_string(),
+        _ => reason.to_lowercase().replace(' ', "_"),
+    };
+    let body = ErrorBody {
+        code,
+        message: if text.is_empty() {
+            reason.to_string()
+        } else {
+            text
+        },
+        details: None,
+    };
+
+    parts.headers.remove(header::CONTENT_LENGTH);
+    parts.headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("application/json"),
+    );
+    Response::from_parts(
+        parts,
+        Body::from(serde_json::to_vec(&body).unwrap_or_default()),
+    )
 }
 
 pub type Result<T> = std::result::Result<T, AppError>;
@@ -0,0 +1,116 @@
+use axum::{
+    extract::State,
+    http::header,
+    response::{IntoResponse, Response},
+    Json,
+};
+use axum_extra::extract::cookie::{Cookie, CookieJar};
+use chrono::Utc;
+
+use crate::auth::AuthUser;
+use crate::error::{AppError, Result};
+use crate::handlers::attachments::delete_card_with_attachments;
+use crate::handlers::auth::{check_current_password, require_account_access};
+use crate::models::{AccountExport, BoardExport, DeleteAccount, UserSettings};
+use crate::state::AppState;
+
+/// Download everything stored about the user as one JSON document
+pub async fn export_account(State(state): State<AppState>, auth: AuthUser) -> Result<Response> {
+    require_account_access(&auth)?;
+    let user = auth.user;
+
+    let mut boards = Vec::new();
+    for (board, role) in state.boards.list_for_user(user.id).await? {
+        let columns = if board.owner_id == user.id {
+            state.columns.list_by_board(board.id).await?
+        } else {
+            Vec::new()
+        };
+        boards.push(BoardExport {
+            board,
+            role,
+            columns,
+        });
+    }
+
+    let export = AccountExport {
+        exported_at: Utc::now(),
+        settings: UserSettings::from(&user),
+        identities: state.identities.list_for_user(user.id).await?,
+        boards,
+        cards: state.accounts.list_cards(user.id).await?,
+        comments: state.accounts.list_comments(user.id).await?,
+        tags: state.tags.list_by_owner(user.id).await?,
+        chat_messages: state
+            .accounts
+            .list_chat_messages(user.id)
+            .await?
+            .into_iter()
+            .map(|m| m.into_response())
+            .collect(),
+        api_tokens: state
+            .tokens
+            .list_by_user(user.id)
+            .await?
+            .into_iter()
+            .map(Into::into)
+            .collect(),
+        notifications: state.notifications.list_for_user(user.id, false).await?,
+        saved_views: state
+            .saved_views
+            .list_by_user(user.id)
+            .await?
+            .into_iter()
+            .map(Into::into)
+            .collect(),
+        user: user.into(),
+    };
+
+    let filename = format!("account-{}.json", export.exported_at.format("%Y%m%d"));
+    Ok((
+        [(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}\"", filename),
+        )],
+        Json(export),
+    )
+        .into_response())
+}
+
+/// Delete the user's account for good. Their boards, inbox cards, chat history,
+/// tokens and sessions are deleted; what they left on other people's boards is
+/// credited to "Deleted user". The user confirms by typing their email address and,
+/// if they have one, their password.
+pub async fn delete_account(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    jar: CookieJar,
+    Json(input): Json<DeleteAccount>,
+) -> Result<CookieJar> {
+    require_account_access(&auth)?;
+    let user = &auth.user;
+    if !input.confirm_email.trim().eq_ignore_ascii_case(&user.email) {
+        return Err(AppError::BadRequest(
+            "Type your email address to confirm the deletion".to_string(),
+        ));
+    }
+    check_current_password(user, input.current_password.as_deref())?;
+    if user.is_admin && state.admin.stats().await?.users.admins <= 1 {
+        return Err(AppError::BadRequest(
+            "You are the only administrator, make someone else an administrator first".to_string(),
+        ));
+    }
+
+    for card_id in state.accounts.list_card_ids_to_delete(user.id).await? {
+        delete_card_with_attachments(&state, card_id).await?;
+    }
+    state.accounts.delete_and_anonymize(user.id).await?;
+
+    tracing::info!("Account {} deleted by its user", user.id);
+    let cookie = Cookie::build(("session", ""))
+        .path("/")
+        .http_only(true)
+        .max_age(time::Duration::seconds(0))
+        .build();
+    Ok(jar.add(cookie))
+}
@@ -0,0 +1,370 @@
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use uuid::Uuid;
+
+use crate::auth::AuthUser;
+use crate::error::{AppError, Result};
+use crate::handlers::comments::can_view_card;
+use crate::models::{
+    ActivityQuery, ActivityResponse, BoardEvent, BoardEventKind, BoardRole, CardVisibility,
+    NotificationKind,
+};
+use crate::services::activity::{completes_card, quote, watcher_notice};
+use crate::services::mentions;
+use crate::state::AppState;
+
+/// Entries returned when no limit is given
+pub(crate) const DEFAULT_ACTIVITY_LIMIT: i64 = 50;
+/// Largest accepted limit
+const MAX_ACTIVITY_LIMIT: i64 = 200;
+
+/// Record a change in the activity log and push it to the board's subscribers. Every
+/// change to a board goes through here, which also drops its cached chat summary.
+pub(crate) async fn record(state: &AppState, event: BoardEvent) {
+    let board_id = event.board_id;
+    record_on_boards(state, &[board_id], event).await;
+}
+
+/// Record a change to a card once and push it to every board the card is on
+pub(crate) async fn record_for_card(
+    state: &AppState,
+    event: BoardEventKind,
+    card_id: Uuid,
+    id: Uuid,
+    actor_id: Uuid,
+    details: Option<serde_json::Value>,
+) {
+    let board_ids = match state.card_boards.list_board_ids_for_card(card_id).await {
+        Ok(board_ids) => board_ids,
+        Err(e) => {
+            tracing::warn!(card_id = %card_id, error = %e, "Could not record card activity");
+            return;
+        }
+    };
+    let Some(first_board_id) = board_ids.first().copied() else {
+        return;
+    };
+
+    let mut event = BoardEvent::new(event, first_board_id, id, actor_id).with_card(card_id);
+    event.details = details;
+    record_on_boards(state, &board_ids, event).await;
+}
+
+/// Activity of changes made in a transaction, held back until it commits so nothing
+/// is logged, notified or pushed for changes that end up rolled back
+#[derive(Default)]
+pub(crate) struct PendingActivity(Vec<PendingEvent>);
+
+enum PendingEvent {
+    Board(BoardEvent),
+    Card {
+        event: BoardEventKind,
+        card_id: Uuid,
+        id: Uuid,
+        actor_id: Uuid,
+        details: Option<serde_json::Value>,
+    },
+}
+
+impl PendingActivity {
+    /// Hold back a change to a board, see [`record`]
+    pub(crate) fn record(&mut self, event: BoardEvent) {
+        self.0.push(PendingEvent::Board(event));
+    }
+
+    /// Hold back a change to a card, see [`record_for_card`]
+    pub(crate) fn record_for_card(
+        &mut self,
+        event: BoardEventKind,
+        card_id: Uuid,
+        id: Uuid,
+        actor_id: Uuid,
+        details: Option<serde_json::Value>,
+    ) {
+        self.0.push(PendingEvent::Card {
+            event,
+            card_id,
+            id,
+            actor_id,
+            details,
+        });
+    }
+
+    /// Record the changes held back, in order, once their transaction committed
+    pub(crate) async fn flush(self, state: &AppState) {
+        for pending in self.0 {
+            match pending {
+                PendingEvent::Board(event) => record(state, event).await,
+                PendingEvent::Card {
+                    event,
+                    card_id,
+                    id,
+                    actor_id,
+                    details,
+                } => record_for_card(state, event, card_id, id, actor_id, details).await,
+            }
+        }
+    }
+}
+
+/// Record a change once, under the first board, and push it to every listed board
+/// (the board of `event` is replaced by each of them).
+///
+/// The change itself is already saved, so a failure to write the log is reported
+/// in the server log rather than to the client.
+pub(crate) async fn record_on_boards(state: &AppState, board_ids: &[Uuid], event: BoardEvent) {
+    let Some(first_board_id) = board_ids.first().copied() else {
+        return;
+    };
+
+    // A deleted board takes its log with it
+    if event.event != BoardEventKind::BoardDeleted {
+        let card_id = event
+            .card_id
+            .or_else(|| event.event.is_card_event().then_some(event.id));
+        let details = event.details.as_ref().map(|d| d.to_string());
+        if let Err(e) = state
+            .activity
+            .record(
+                first_board_id,
+                card_id,
+                event.actor_id,
+                &event.event.to_string(),
+                event.id,
+                details.as_deref(),
+            )
+            .await
+        {
+            tracing::warn!(event = %event.event, error = %e, "Could not record activity");
+        }
+    }
+
+    if let Some(card_id) = event
+        .card_id
+        .or_else(|| event.event.is_card_event().then_some(event.id))
+    {
+        let mentioned = notify_mentioned(state, card_id, &event).await;
+        notify_watchers(state, card_id, &event, &mentioned).await;
+        notify_unblocked(state, card_id, &event).await;
+    }
+
+    crate::handlers::webhooks::dispatch(state, board_ids, &event).await;
+
+    for board_id in board_ids {
+        state.board_summaries.invalidate(*board_id);
+        state.events.publish(BoardEvent {
+            board_id: *board_id,
+            ..event.clone()
+        });
+    }
+}
+
+/// Save who a new or edited comment mentions and notify the users it did not mention
+/// before, returning them. Only users who can see the card can be mentioned, and
+/// authors do not notify themselves.
+async fn notify_mentioned(state: &AppState, card_id: Uuid, event: &BoardEvent) -> Vec<Uuid> {
+    let details = event.details.as_ref();
+    let body = match event.event {
+        BoardEventKind::CommentCreated => details.and_then(|d| d.get("body")),
+        BoardEventKind::CommentUpdated => details
+            .and_then(|d| d.get("body"))
+            .and_then(|body| body.get("to")),
+        _ => None,
+    };
+    let Some(body) = body.and_then(|b| b.as_str()) else {
+        return Vec::new();
+    };
+
+    let result = async {
+        let handles = mentions::handles(body);
+        let mut user_ids = Vec::new();
+        if !handles.is_empty() {
+            for user in state.mentions.list_card_users(card_id).await? {
+                let mentionable = mentions::Mentionable {
+                    name: &user.name,
+                    email: &user.email,
+                };
+                if user.id != event.actor_id && handles.iter().any(|h| mentionable.matches(h)) {
+                    user_ids.push(user.id);
+                }
+            }
+        }
+        let added = state.mentions.set_for_comment(event.id, &user_ids).await?;
+        let message = format!("mentioned you: {}", quote(body));
+        state
+            .notifications
+            .create_for_users(
+                &added,
+                card_id,
+                event.actor_id,
+                NotificationKind::Mentioned,
+                &message,
+            )
+            .await?;
+        Ok::<_, AppError>(added)
+    }
+    .await;
+    match result {
+        Ok(added) => added,
+        Err(e) => {
+            tracing::warn!(card_id = %card_id, error = %e, "Could not notify mentioned users");
+            Vec::new()
+        }
+    }
+}
+
+/// Notify the users watching the card of a change someone else made, if it is a
+/// kind of change they are told about. Watchers who lost access to the card are
+/// skipped, as are the users in `mentioned`, who were already told. Like the log,
+/// failures are only reported in the server log.
+async fn notify_watchers(state: &AppState, card_id: Uuid, event: &BoardEvent, mentioned: &[Uuid]) {
+    let details = event.details.as_ref();
+    let column_name = match event.event {
+        BoardEventKind::CardMoved => {
+            let column_id = details
+                .and_then(|d| d.get("to_column_id"))
+                .and_then(|id| id.as_str())
+                .and_then(|id| id.parse::<Uuid>().ok());
+            match column_id {
+                Some(column_id) => state
+                    .columns
+                    .find_by_id(column_id)
+                    .await
+                    .ok()
+                    .flatten()
+                    .map(|c| c.name),
+                None => None,
+            }
+        }
+        _ => None,
+    };
+    let Some((kind, message)) = watcher_notice(event.event, details, column_name.as_deref()) else {
+        return;
+    };
+
+    let result = async {
+        let mut recipients = Vec::new();
+        for user_id in state.watchers.list_user_ids(card_id).await? {
+            if user_id != event.actor_id
+                && !mentioned.contains(&user_id)
+                && can_view_card(state, card_id, user_id).await?
+            {
+                recipients.push(user_id);
+            }
+        }
+        state
+            .notifications
+            .create_for_users(&recipients, card_id, event.actor_id, kind, &message)
+            .await
+    }
+    .await;
+    if let Err(e) = result {
+        tracing::warn!(card_id = %card_id, error = %e, "Could not notify card watchers");
+    }
+}
+
+/// When a card is done or closed, tell the watchers of each card it blocked that has
+/// no open blocker left. As for other changes, the user who made it and watchers who
+/// lost access to the blocked card are skipped.
+async fn notify_unblocked(state: &AppState, card_id: Uuid, event: &BoardEvent) {
+    if !completes_card(event.event, event.details.as_ref()) {
+        return;
+    }
+
+    let result = async {
+        for blocked in state.links.list_blocked(card_id).await? {
+            if matches!(blocked.status.as_str(), "done" | "closed")
+                || !state.links.list_open_blockers(blocked.id).await?.is_empty()
+            {
+                continue;
+            }
+            let mut recipients = Vec::new();
+            for user_id in state.watchers.list_user_ids(blocked.id).await? {
+                if user_id != event.actor_id && can_view_card(state, blocked.id, user_id).await? {
+                    recipients.push(user_id);
+                }
+            }
+            state
+                .notifications
+                .create_for_users(
+                    &recipients,
+                    blocked.id,
+                    event.actor_id,
+                    NotificationKind::CardUnblocked,
+                    "completed the last card blocking it",
+                )
+                .await?;
+        }
+        Ok::<_, AppError>(())
+    }
+    .await;
+    if let Err(e) = result {
+        tracing::warn!(card_id = %card_id, error = %e, "Could not notify watchers of unblocked cards");
+    }
+}
+
+fn activity_limit(query: &ActivityQuery) -> i64 {
+    query
+        .limit
+        .unwrap_or(DEFAULT_ACTIVITY_LIMIT)
+        .clamp(1, MAX_ACTIVITY_LIMIT)
+}
+
+/// Recent changes to the board and the cards on it, newest first
+pub async fn get_board_activity(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(board_id): Path<Uuid>,
+    Query(query): Query<ActivityQuery>,
+) -> Result<Json<Vec<ActivityResponse>>> {
+    let role = state
+        .boards
+        .get_user_role(board_id, auth.user.id)
+        .await?
+        .ok_or(AppError::Forbidden)?;
+
+    let entries = state
+        .activity
+        .list_for_board(board_id, &role, activity_limit(&query))
+        .await?;
+    Ok(Json(entries.into_iter().map(Into::into).collect()))
+}
+
+/// Timeline of a card: its edits, moves, tags and comments on every board, newest first
+pub async fn get_card_activity(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(card_id): Path<Uuid>,
+    Query(query): Query<ActivityQuery>,
+) -> Result<Json<Vec<ActivityResponse>>> {
+    let card = state.cards.get_by_id(card_id).await?;
+
+    // Same visibility rules as the card itself; the card's owner always has access
+    let boards = state
+        .card_boards
+        .list_board_access_for_card(card_id, auth.user.id)
+        .await?;
+    let roles: Vec<BoardRole> = boards
+        .iter()
+        .filter_map(|b| b.role.as_deref().and_then(|r| r.parse().ok()))
+        .collect();
+    let is_owner = card.owner_id == Some(auth.user.id) || card.created_by == auth.user.id;
+    let visibility: CardVisibility = card.visibility.parse().unwrap_or(CardVisibility::Private);
+    let has_access = is_owner
+        || match visibility {
+            CardVisibility::Private => roles.iter().any(|r| r.can_edit()),
+            CardVisibility::Restricted | CardVisibility::Public => !roles.is_empty(),
+        };
+
+    if !has_access {
+        return Err(AppError::Forbidden);
+    }
+
+    let entries = state
+        .activity
+        .list_for_card(card_id, activity_limit(&query))
+        .await?;
+    Ok(Json(entries.into_iter().map(Into::into).collect()))
+}
@@ -0,0 +1,123 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use uuid::Uuid;
+
+use crate::auth::AdminUser;
+use crate::error::{AppError, Result};
+use crate::models::{
+    Board, InstanceStats, IntegrityReport, TransferBoard, UpdateUserAccount, UserAccount,
+};
+use crate::state::AppState;
+
+/// Scan the database for orphaned and dangling rows without changing anything
+pub async fn get_integrity(
+    State(state): State<AppState>,
+    _admin: AdminUser,
+) -> Result<Json<IntegrityReport>> {
+    let checks = state.integrity.scan().await?;
+    Ok(Json(IntegrityReport::new(false, checks)))
+}
+
+/// Delete orphaned rows and detach dangling references in one transaction
+pub async fn repair_integrity(
+    State(state): State<AppState>,
+    admin: AdminUser,
+) -> Result<Json<IntegrityReport>> {
+    let checks = state.integrity.repair().await?;
+    let report = IntegrityReport::new(true, checks);
+
+    tracing::info!(
+        "Integrity repair by {} fixed {} row(s)",
+        admin.0.user.email,
+        report.total_fixed
+    );
+
+    Ok(Json(report))
+}
+
+/// Every user of the instance, with their boards and chat usage
+pub async fn list_users(
+    State(state): State<AppState>,
+    _admin: AdminUser,
+) -> Result<Json<Vec<UserAccount>>> {
+    Ok(Json(state.admin.list_users().await?))
+}
+
+/// Deactivate or reactivate an account, or change whether it is an administrator.
+/// Deactivating logs the user out everywhere.
+pub async fn update_user(
+    State(state): State<AppState>,
+    admin: AdminUser,
+    Path(user_id): Path<Uuid>,
+    Json(input): Json<UpdateUserAccount>,
+) -> Result<Json<UserAccount>> {
+    // Administrators cannot lock themselves out
+    if user_id == admin.0.user.id && (input.active == Some(false) || input.is_admin == Some(false))
+    {
+        return Err(AppError::BadRequest(
+            "You cannot deactivate or demote your own account".to_string(),
+        ));
+    }
+    state.users.get_by_id(user_id).await?;
+
+    if let Some(active) = input.active {
+        state.users.set_active(user_id, active).await?;
+        if !active {
+            state.sessions.delete_for_user(user_id, None).await?;
+        }
+    }
+    if let Some(is_admin) = input.is_admin {
+        state.users.set_admin(user_id, is_admin).await?;
+    }
+
+    tracing::info!(
+        "Account {} updated by {}: active {:?}, admin {:?}",
+        user_id,
+        admin.0.user.email,
+        input.active,
+        input.is_admin
+    );
+
+    let account = state
+        .admin
+        .find_user(user_id)
+        .await?
+        .ok_or(AppError::NotFound("user"))?;
+    Ok(Json(account))
+}
+
+/// Number of users, boards, cards and chat messages on the instance
+pub async fn get_stats(
+    State(state): State<AppState>,
+    _admin: AdminUser,
+) -> Result<Json<InstanceStats>> {
+    Ok(Json(state.admin.stats().await?))
+}
+
+/// Hand a board over to another user, for example when its owner leaves
+pub async fn transfer_board(
+    State(state): State<AppState>,
+    admin: AdminUser,
+    Path(board_id): Path<Uuid>,
+    Json(input): Json<TransferBoard>,
+) -> Result<Json<Board>> {
+    let owner = state.users.get_by_id(input.owner_id).await?;
+    if !owner.is_active() {
+        return Err(AppError::BadRequest(
+            "Cannot transfer a board to a deactivated account".to_string(),
+        ));
+    }
+
+    let board = state.boards.transfer_ownership(board_id, owner.id).await?;
+
+    tracing::info!(
+        "Board {} transferred to {} by {}",
+        board_id,
+        owner.email,
+        admin.0.user.email
+    );
+
+    Ok(Json(board))
+}
@@ -0,0 +1,125 @@
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::auth::AuthUser;
+use crate::error::{AppError, Result};
+use crate::handlers::activity::record_for_card;
+use crate::handlers::comments::{can_edit_card, can_view_card};
+use crate::handlers::inbox::ListCardsQuery;
+use crate::handlers::links::mark_blocked;
+use crate::models::{AssignCardUser, BoardEventKind, CardAssignee, CardResponse, CardStatus};
+use crate::state::AppState;
+
+/// List the users assigned to a card
+pub async fn list_assignees(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(card_id): Path<Uuid>,
+) -> Result<Json<Vec<CardAssignee>>> {
+    if !can_view_card(&state, card_id, auth.user.id).await? {
+        return Err(AppError::Forbidden);
+    }
+
+    let assignees = state.assignees.list_for_card(card_id).await?;
+    Ok(Json(assignees))
+}
+
+/// Assign a user to a card. The user must be able to see the card.
+pub async fn assign_user(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(card_id): Path<Uuid>,
+    Json(input): Json<AssignCardUser>,
+) -> Result<Json<Vec<CardAssignee>>> {
+    if !can_edit_card(&state, card_id, auth.user.id).await? {
+        return Err(AppError::Forbidden);
+    }
+
+    state.cards.ensure_unlocked(card_id).await?;
+
+    let user = state
+        .users
+        .find_by_id(input.user_id)
+        .await?
+        .ok_or_else(|| AppError::invalid_field("user_id", "User not found"))?;
+    if !can_view_card(&state, card_id, user.id).await? {
+        return Err(AppError::Validation(format!(
+            "{} has no access to this card",
+            user.name
+        )));
+    }
+
+    if state.assignees.add(card_id, user.id, auth.user.id).await? {
+        record_for_card(
+            &state,
+            BoardEventKind::AssigneeAdded,
+            card_id,
+            user.id,
+            auth.user.id,
+            Some(json!({"name": user.name})),
+        )
+        .await;
+    }
+
+    let assignees = state.assignees.list_for_card(card_id).await?;
+    Ok(Json(assignees))
+}
+
+/// Unassign a user from a card
+pub async fn unassign_user(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path((card_id, user_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<Vec<CardAssignee>>> {
+    if !can_edit_card(&state, card_id, auth.user.id).await? {
+        return Err(AppError::Forbidden);
+    }
+
+    state.cards.ensure_unlocked(card_id).await?;
+
+    if state.assignees.remove(card_id, user_id).await? {
+        let name = state.users.find_by_id(user_id).await?.map(|u| u.name);
+        record_for_card(
+            &state,
+            BoardEventKind::AssigneeRemoved,
+            card_id,
+            user_id,
+            auth.user.id,
+            Some(json!({"name": name})),
+        )
+        .await;
+    }
+
+    let assignees = state.assignees.list_for_card(card_id).await?;
+    Ok(Json(assignees))
+}
+
+/// Cards assigned to the current user that they can still see
+pub async fn list_assigned_cards(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Query(query): Query<ListCardsQuery>,
+) -> Result<Json<Vec<CardResponse>>> {
+    let status = query.status.and_then(|s| s.parse::<CardStatus>().ok());
+
+    let cards = state
+        .assignees
+        .list_cards_for_user(auth.user.id, status)
+        .await?;
+
+    let mut responses = Vec::new();
+    for card in cards {
+        if !can_view_card(&state, card.id, auth.user.id).await? {
+            continue;
+        }
+        let tags = state.tags.list_for_card(card.id).await?;
+        responses.push(card.into_response(tags.into_iter().map(|t| t.into()).collect()));
+    }
+    mark_blocked(&state, &mut responses).await?;
+
+    Ok(Json(responses))
+}
@@ -0,0 +1,272 @@
+use axum::{
+    extract::{Multipart, Path, State},
+    http::{header, HeaderValue},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::auth::AuthUser;
+use crate::error::{AppError, Result};
+use crate::handlers::activity::record_for_card;
+use crate::handlers::comments::{can_edit_card, can_view_card};
+use crate::models::{Attachment, AttachmentResponse, BoardEventKind};
+use crate::services::attachments::{content_disposition, extract_text, sanitize_filename};
+use crate::state::AppState;
+
+/// Largest accepted attachment, in bytes
+pub const MAX_ATTACHMENT_SIZE: usize = 10 * 1024 * 1024;
+
+/// Room for the multipart boundaries and headers around the file
+pub const MULTIPART_OVERHEAD: usize = 64 * 1024;
+
+/// Delete a card and the files of its attachments stored on disk
+pub(crate) async fn delete_card_with_attachments(state: &AppState, card_id: Uuid) -> Result<()> {
+    let paths = state
+        .attachments
+        .list_storage_paths_for_card(card_id)
+        .await?;
+    state.cards.delete(card_id).await?;
+
+    for path in paths {
+        state.attachment_storage.remove(&path).await;
+    }
+    Ok(())
+}
+
+/// Store a file as an attachment of a card and record it in the card's activity. The
+/// caller checks access and the size limit.
+pub(crate) async fn store_attachment(
+    state: &AppState,
+    card_id: Uuid,
+    user_id: Uuid,
+    filename: &str,
+    content_type: &str,
+    data: &[u8],
+) -> Result<Attachment> {
+    let id = Uuid::new_v4();
+    let storage_path = state.attachment_storage.write(id, data).await?;
+    let stored_data = storage_path.is_none().then_some(data);
+    let attachment = match state
+        .attachments
+        .create(
+            id,
+            card_id,
+            user_id,
+            filename,
+            content_type,
+            data.len() as i64,
+            storage_path.as_deref(),
+            stored_data,
+        )
+        .await
+    {
+        Ok(attachment) => attachment,
+        Err(e) => {
+            if let Some(path) = &storage_path {
+                state.attachment_storage.remove(path).await;
+            }
+            return Err(e);
+        }
+    };
+
+    index_text(state, attachment.id, filename, content_type, data).await;
+
+    record_for_card(
+        state,
+        BoardEventKind::AttachmentCreated,
+        card_id,
+        attachment.id,
+        user_id,
+        Some(json!({"filename": attachment.filename, "size": attachment.size})),
+    )
+    .await;
+
+    Ok(attachment)
+}
+
+/// Index the text of a new attachment for search. Extraction failures only leave
+/// the attachment out of search results, so they are logged rather than returned.
+async fn index_text(
+    state: &AppState,
+    attachment_id: Uuid,
+    filename: &str,
+    content_type: &str,
+    data: &[u8],
+) {
+    let (filename, content_type, data) = (
+        filename.to_string(),
+        content_type.to_string(),
+        data.to_vec(),
+    );
+    let text =
+        tokio::task::spawn_blocking(move || extract_text(&filename, &content_type, &data)).await;
+
+    let result = match text {
+        Ok(Some(text)) => state.attachments.set_text(attachment_id, &text).await,
+        Ok(None) => Ok(()),
+        Err(e) => Err(AppError::Internal(e.to_string())),
+    };
+    if let Err(e) = result {
+        tracing::warn!(attachment_id = %attachment_id, error = %e, "Could not index attachment text");
+    }
+}
+
+/// List all attachments of a card
+pub async fn list_attachments(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(card_id): Path<Uuid>,
+) -> Result<Json<Vec<AttachmentResponse>>> {
+    if !can_view_card(&state, card_id, auth.user.id).await? {
+        return Err(AppError::Forbidden);
+    }
+
+    let attachments = state.attachments.list_by_card(card_id).await?;
+    Ok(Json(attachments.into_iter().map(|a| a.into()).collect()))
+}
+
+/// Upload a file to a card, sent as the `file` field of a multipart form
+pub async fn upload_attachment(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(card_id): Path<Uuid>,
+    mut multipart: Multipart,
+) -> Result<Json<AttachmentResponse>> {
+    if !can_edit_card(&state, card_id, auth.user.id).await? {
+        return Err(AppError::Forbidden);
+    }
+
+    state.cards.ensure_unlocked(card_id).await?;
+
+    let mut upload = None;
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| AppError::BadRequest(e.to_string()))?
+    {
+        if field.name() != Some("file") {
+            continue;
+        }
+
+        let filename = field
+            .file_name()
+            .and_then(sanitize_filename)
+            .ok_or_else(|| AppError::Validation("File name is required".to_string()))?;
+        // Served back as a header, so anything that is not a valid header value is dropped
+        let content_type = field
+            .content_type()
+            .filter(|ct| HeaderValue::from_str(ct).is_ok())
+            .unwrap_or("application/octet-stream")
+            .to_string();
+        let data = field
+            .bytes()
+            .await
+            .map_err(|e| AppError::BadRequest(e.to_string()))?;
+        upload = Some((filename, content_type, data));
+        break;
+    }
+
+    let (filename, content_type, data) =
+        upload.ok_or_else(|| AppError::invalid_field("file", "A file field is required"))?;
+    if data.is_empty() {
+        return Err(AppError::invalid_field("file", "File is empty"));
+    }
+    if data.len() > MAX_ATTACHMENT_SIZE {
+        return Err(AppError::Validation(format!(
+            "Attachments are limited to {} MB",
+            MAX_ATTACHMENT_SIZE / (1024 * 1024)
+        )));
+    }
+
+    let attachment = store_attachment(
+        &state,
+        card_id,
+        auth.user.id,
+        &filename,
+        &content_type,
+        &data,
+    )
+    .await?;
+
+    let user = state.users.get_by_id(auth.user.id).await?;
+
+    Ok(Json(AttachmentResponse {
+        id: attachment.id,
+        card_id: attachment.card_id,
+        uploaded_by: attachment.uploaded_by,
+        uploader_name: user.name,
+        filename: attachment.filename,
+        content_type: attachment.content_type,
+        size: attachment.size,
+        created_at: attachment.created_at,
+    }))
+}
+
+/// Download an attachment's content
+pub async fn download_attachment(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(attachment_id): Path<Uuid>,
+) -> Result<Response> {
+    let attachment = state.attachments.get_by_id(attachment_id).await?;
+
+    if !can_view_card(&state, attachment.card_id, auth.user.id).await? {
+        return Err(AppError::Forbidden);
+    }
+
+    let data = match &attachment.storage_path {
+        Some(path) => state.attachment_storage.read(path).await?,
+        None => state
+            .attachments
+            .get_data(attachment_id)
+            .await?
+            .unwrap_or_default(),
+    };
+
+    // Always served as a download, so uploaded HTML or SVG never renders in the app's origin
+    Ok((
+        [
+            (header::CONTENT_TYPE, attachment.content_type),
+            (
+                header::CONTENT_DISPOSITION,
+                content_disposition(&attachment.filename),
+            ),
+            (header::X_CONTENT_TYPE_OPTIONS, "nosniff".to_string()),
+        ],
+        data,
+    )
+        .into_response())
+}
+
+/// Delete an attachment (only the uploader can delete)
+pub async fn delete_attachment(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(attachment_id): Path<Uuid>,
+) -> Result<()> {
+    let attachment = state.attachments.get_by_id(attachment_id).await?;
+
+    // Only the uploader can delete their attachment
+    if attachment.uploaded_by != auth.user.id {
+        return Err(AppError::Forbidden);
+    }
+
+    state.cards.ensure_unlocked(attachment.card_id).await?;
+    state.attachments.delete(attachment_id).await?;
+    if let Some(path) = &attachment.storage_path {
+        state.attachment_storage.remove(path).await;
+    }
+
+    record_for_card(
+        &state,
+        BoardEventKind::AttachmentDeleted,
+        attachment.card_id,
+        attachment_id,
+        auth.user.id,
+        Some(json!({"filename": attachment.filename})),
+    )
+    .await;
+    Ok(())
+}
@@ -3,23 +3,36 @@ use axum::{
     Json,
 };
 use axum_extra::extract::cookie::{Cookie, CookieJar};
+use chrono::{Duration, Utc};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::auth::{generate_token, hash_password, hash_token, verify_password, AuthUser};
+use crate::auth::{
+    generate_token, hash_password, hash_token, session_cookie, session_lifetime, AuthUser, ClientIp,
+};
 use crate::error::{AppError, Result};
+use crate::handlers::invitations::accept_for_new_user;
 use crate::models::{
-    ApiTokenCreatedResponse, ApiTokenResponse, CreateApiToken, CreateUser, UserResponse,
+    ApiTokenCreatedResponse, ApiTokenResponse, ChangeEmail, ChangePassword, ConfirmEmailChange,
+    CreateApiToken, CreateUser, EmailChange, SessionResponse, SessionsRevoked, TokenScope, User,
+    UserResponse,
 };
 use crate::state::AppState;
 
-#[derive(Debug, Deserialize)]
+/// How long the link confirming a new email address works
+const EMAIL_CHANGE_TTL_HOURS: i64 = 24;
+
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct LoginRequest {
     pub email: String,
     pub password: String,
+    /// Keep the session for SESSION_REMEMBER_DAYS, across browser restarts
+    #[serde(default)]
+    pub remember: bool,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct AuthResponse {
     pub user: UserResponse,
     pub token: Option<String>,
@@ -34,11 +47,7 @@ pub async fn register(
         return Err(AppError::Validation("All fields are required".to_string()));
     }
 
-    if input.password.len() < 8 {
-        return Err(AppError::Validation(
-            "Password must be at least 8 characters".to_string(),
-        ));
-    }
+    validate_password(&input.password)?;
 
     // Check if email already exists
     if state.users.email_exists(&input.email).await? {
@@ -50,8 +59,9 @@ pub async fn register(
     let id = Uuid::new_v4();
     let user = state
         .users
-        .create(id, &input.email, &password_hash, &input.name)
+        .create(id, &input.email, Some(&password_hash), &input.name)
         .await?;
+    accept_for_new_user(&state, &user).await?;
 
     Ok(Json(AuthResponse {
         user: user.into(),
@@ -61,6 +71,7 @@ pub async fn register(
 
 pub async fn login(
     State(state): State<AppState>,
+    ClientIp(ip): ClientIp,
     jar: CookieJar,
     Json(input): Json<LoginRequest>,
 ) -> Result<(CookieJar, Json<AuthResponse>)> {
@@ -70,22 +81,28 @@ pub async fn login(
         .await?
         .ok_or(AppError::Unauthorized)?;
 
-    if !verify_password(&input.password, &user.password_hash)? {
+    if !user.check_password(&input.password)? {
         return Err(AppError::Unauthorized);
     }
+    if !user.is_active() {
+        return Err(AppError::Forbidden);
+    }
 
     // Create session
     let token = generate_token();
-    state.sessions.create(user.id, &token).await?;
-
-    let cookie = Cookie::build(("session", token))
-        .path("/")
-        .http_only(true)
-        .same_site(axum_extra::extract::cookie::SameSite::Lax)
-        .build();
+    state
+        .sessions
+        .create(
+            user.id,
+            &token,
+            Some(ip),
+            input.remember,
+            session_lifetime(input.remember),
+        )
+        .await?;
 
     Ok((
-        jar.add(cookie),
+        jar.add(session_cookie(token, input.remember)),
         Json(AuthResponse {
             user: user.into(),
             token: None,
@@ -117,7 +134,14 @@ pub async fn create_api_token(
     Json(input): Json<CreateApiToken>,
 ) -> Result<Json<ApiTokenCreatedResponse>> {
     if input.name.is_empty() {
-        return Err(AppError::Validation("Token name is required".to_string()));
+        return Err(AppError::invalid_field("name", "Token name is required"));
+    }
+    // A token cannot create a token allowed to do more than itself
+    if auth
+        .token_scope
+        .is_some_and(|scope| !scope.allows(input.scope))
+    {
+        return Err(AppError::Forbidden);
     }
 
     let token = generate_token();
@@ -159,3 +183,199 @@ pub async fn revoke_api_token(
     state.tokens.delete(token_id, auth.user.id).await?;
     Ok(())
 }
+
+/// Sessions the user is logged in with, the current one flagged
+pub async fn list_sessions(
+    State(state): State<AppState>,
+    auth: AuthUser,
+) -> Result<Json<Vec<SessionResponse>>> {
+    let sessions = state.sessions.list_active_for_user(auth.user.id).await?;
+    Ok(Json(
+        sessions
+            .into_iter()
+            .map(|s| SessionResponse::new(s, auth.session_token.as_deref()))
+            .collect(),
+    ))
+}
+
+/// Log out one of the user's sessions
+pub async fn revoke_session(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(session_id): Path<Uuid>,
+) -> Result<()> {
+    require_account_access(&auth)?;
+    state
+        .sessions
+        .delete_for_user_by_id(session_id, auth.user.id)
+        .await
+}
+
+/// Log out every session of the user except the one making the request
+pub async fn revoke_other_sessions(
+    State(state): State<AppState>,
+    auth: AuthUser,
+) -> Result<Json<SessionsRevoked>> {
+    require_account_access(&auth)?;
+    let revoked = state
+        .sessions
+        .delete_for_user(auth.user.id, auth.session_token.as_deref())
+        .await?;
+    Ok(Json(SessionsRevoked { revoked }))
+}
+
+fn validate_password(password: &str) -> Result<()> {
+    if password.len() < 8 {
+        return Err(AppError::Validation(
+            "Password must be at least 8 characters".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Credentials can be changed from a session or with an admin token, never with a token
+/// that only manages data
+pub(crate) fn require_account_access(auth: &AuthUser) -> Result<()> {
+    if auth
+        .token_scope
+        .is_some_and(|scope| !scope.allows(TokenScope::Admin))
+    {
+        return Err(AppError::Forbidden);
+    }
+    Ok(())
+}
+
+/// Check the password a credential change is confirmed with. Users who only sign in
+/// through an OAuth provider have none to confirm.
+pub(crate) fn check_current_password(user: &User, password: Option<&str>) -> Result<()> {
+    if user.password_hash.is_some() && !user.check_password(password.unwrap_or_default())? {
+        return Err(AppError::BadRequest(
+            "Current password is incorrect".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Replace the user's password and log out every other session
+pub(crate) async fn set_password(
+    state: &AppState,
+    auth: &AuthUser,
+    current_password: Option<&str>,
+    new_password: &str,
+) -> Result<()> {
+    require_account_access(auth)?;
+    check_current_password(&auth.user, current_password)?;
+    validate_password(new_password)?;
+
+    let password_hash = hash_password(new_password)?;
+    state
+        .users
+        .update_password(auth.user.id, &password_hash)
+        .await?;
+    state
+        .sessions
+        .delete_for_user(auth.user.id, auth.session_token.as_deref())
+        .await?;
+    Ok(())
+}
+
+/// Email a link confirming the new address to it. The address changes once the link
+/// is followed.
+pub(crate) async fn request_email_change(
+    state: &AppState,
+    auth: &AuthUser,
+    new_email: &str,
+    current_password: Option<&str>,
+) -> Result<EmailChange> {
+    require_account_access(auth)?;
+    check_current_password(&auth.user, current_password)?;
+
+    let new_email = new_email.trim();
+    if !new_email.contains('@') {
+        return Err(AppError::invalid_field(
+            "new_email",
+            "A valid email address is required",
+        ));
+    }
+    if new_email == auth.user.email {
+        return Err(AppError::BadRequest(
+            "This is already your email address".to_string(),
+        ));
+    }
+    if state.users.email_exists(new_email).await? {
+        return Err(AppError::BadRequest("Email already registered".to_string()));
+    }
+
+    let token = generate_token();
+    let change = state
+        .email_changes
+        .create(
+            auth.user.id,
+            new_email,
+            &hash_token(&token),
+            Utc::now() + Duration::hours(EMAIL_CHANGE_TTL_HOURS),
+        )
+        .await?;
+    let message = state.email.email_change(new_email, &auth.user.name, &token);
+    state.email.send_in_background(message);
+
+    Ok(change)
+}
+
+/// Apply the email change a confirmation token was sent for
+pub(crate) async fn confirm_email(state: &AppState, token: &str) -> Result<User> {
+    let change = state
+        .email_changes
+        .find_by_token_hash(&hash_token(token))
+        .await?
+        .ok_or(AppError::NotFound("email_change"))?;
+    state.email_changes.delete(change.id).await?;
+
+    // The address may have been registered since the change was requested
+    if state.users.email_exists(&change.new_email).await? {
+        return Err(AppError::BadRequest("Email already registered".to_string()));
+    }
+    state
+        .users
+        .update_email(change.user_id, &change.new_email)
+        .await
+}
+
+pub async fn change_password(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Json(input): Json<ChangePassword>,
+) -> Result<()> {
+    set_password(
+        &state,
+        &auth,
+        input.current_password.as_deref(),
+        &input.new_password,
+    )
+    .await
+}
+
+pub async fn change_email(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Json(input): Json<ChangeEmail>,
+) -> Result<Json<EmailChange>> {
+    let change = request_email_change(
+        &state,
+        &auth,
+        &input.new_email,
+        input.current_password.as_deref(),
+    )
+    .await?;
+    Ok(Json(change))
+}
+
+/// Confirm a new email address with the token emailed to it. Needs no login, the token
+/// proves the address is the user's.
+pub async fn confirm_email_change(
+    State(state): State<AppState>,
+    Json(input): Json<ConfirmEmailChange>,
+) -> Result<Json<UserResponse>> {
+    let user = confirm_email(&state, &input.token).await?;
+    Ok(Json(user.into()))
+}
@@ -0,0 +1,15 @@
+use axum::{extract::State, Json};
+
+use crate::auth::AuthUser;
+use crate::error::Result;
+use crate::models::BackfillStatus;
+use crate::services::backfill;
+use crate::state::AppState;
+
+/// List registered data backfills and the outcome of their last run
+pub async fn list_backfills(
+    State(state): State<AppState>,
+    _auth: AuthUser,
+) -> Result<Json<Vec<BackfillStatus>>> {
+    Ok(Json(backfill::status(&state.backfills).await?))
+}
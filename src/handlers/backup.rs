@@ -0,0 +1,126 @@
+use axum::{
+    body::{Body, Bytes},
+    extract::State,
+    http::header,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::Utc;
+use serde_json::json;
+use std::path::PathBuf;
+use tokio_util::io::ReaderStream;
+use uuid::Uuid;
+
+use crate::auth::AdminUser;
+use crate::error::{AppError, Result};
+use crate::services::backup::{self, BackupSchedule};
+use crate::state::AppState;
+
+/// Largest snapshot accepted by the restore endpoint
+pub const MAX_RESTORE_SIZE: usize = 512 * 1024 * 1024;
+
+/// A file in the temporary directory, removed when dropped
+struct TempFile(PathBuf);
+
+impl TempFile {
+    fn new() -> Self {
+        Self(std::env::temp_dir().join(format!("pos-{}.db", Uuid::new_v4().simple())))
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+/// Download a consistent snapshot of the whole database, as a SQLite file.
+/// Attachments stored under ATTACHMENTS_DIR are not part of it.
+pub async fn download_backup(State(state): State<AppState>, admin: AdminUser) -> Result<Response> {
+    let temp = TempFile::new();
+    state.backups.snapshot(&temp.0).await?;
+    // The open file stays readable once its name is gone
+    let file = tokio::fs::File::open(&temp.0)
+        .await
+        .map_err(|e| AppError::Internal(format!("Cannot read snapshot: {}", e)))?;
+    drop(temp);
+
+    tracing::info!("Database backup downloaded by {}", admin.0.user.email);
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/vnd.sqlite3".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", backup::file_name(Utc::now())),
+            ),
+        ],
+        Body::from_stream(ReaderStream::new(file)),
+    )
+        .into_response())
+}
+
+/// Replace the content of the database with a snapshot downloaded from
+/// `download_backup`. Sessions come from the snapshot too, so users logged in since
+/// it was taken have to log in again.
+pub async fn restore_backup(
+    State(state): State<AppState>,
+    admin: AdminUser,
+    body: Bytes,
+) -> Result<Json<serde_json::Value>> {
+    if body.is_empty() {
+        return Err(AppError::BadRequest(
+            "Send the snapshot as the request body".to_string(),
+        ));
+    }
+
+    let temp = TempFile::new();
+    tokio::fs::write(&temp.0, &body)
+        .await
+        .map_err(|e| AppError::Internal(format!("Cannot write snapshot: {}", e)))?;
+    let rows = state.backups.restore(&temp.0).await?;
+    state.board_summaries.clear();
+
+    tracing::warn!(
+        "Database restored from a snapshot by {} ({} rows)",
+        admin.0.user.email,
+        rows
+    );
+
+    Ok(Json(json!({ "restored_rows": rows })))
+}
+
+/// Write a snapshot to BACKUP_DIR on every period and delete the ones beyond
+/// BACKUP_RETENTION, until the server shuts down
+pub async fn run_backup_job(state: AppState, schedule: BackupSchedule) {
+    let mut ticks = tokio::time::interval(schedule.interval);
+    while state.jobs.tick(&mut ticks).await {
+        match write_snapshot(&state, &schedule).await {
+            Ok(path) => tracing::info!("Wrote database snapshot {}", path.display()),
+            Err(e) => tracing::warn!(error = %e, "Database snapshot failed"),
+        }
+    }
+}
+
+async fn write_snapshot(state: &AppState, schedule: &BackupSchedule) -> Result<PathBuf> {
+    let io_error = |e: std::io::Error| AppError::Internal(format!("Backup directory: {}", e));
+
+    tokio::fs::create_dir_all(&schedule.dir)
+        .await
+        .map_err(io_error)?;
+    let path = schedule.dir.join(backup::file_name(Utc::now()));
+    state.backups.snapshot(&path).await?;
+
+    let mut names = Vec::new();
+    let mut entries = tokio::fs::read_dir(&schedule.dir).await.map_err(io_error)?;
+    while let Some(entry) = entries.next_entry().await.map_err(io_error)? {
+        names.push(entry.file_name().to_string_lossy().into_owned());
+    }
+    for name in backup::expired(&names, schedule.retention) {
+        if let Err(e) = tokio::fs::remove_file(schedule.dir.join(&name)).await {
+            tracing::warn!(error = %e, "Cannot delete old snapshot {}", name);
+        }
+    }
+
+    Ok(path)
+}
@@ -0,0 +1,140 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use uuid::Uuid;
+
+use crate::auth::AuthUser;
+use crate::error::{AppError, Result};
+use crate::models::{
+    BoardGroup, BoardPreference, CreateBoardGroup, ReorderBoards, UpdateBoardGroup,
+    UpdateBoardPreference,
+};
+use crate::state::AppState;
+
+pub async fn list_groups(
+    State(state): State<AppState>,
+    auth: AuthUser,
+) -> Result<Json<Vec<BoardGroup>>> {
+    Ok(Json(
+        state.board_preferences.list_groups(auth.user.id).await?,
+    ))
+}
+
+pub async fn create_group(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Json(input): Json<CreateBoardGroup>,
+) -> Result<Json<BoardGroup>> {
+    let name = input.name.trim();
+    if name.is_empty() {
+        return Err(AppError::invalid_field("name", "Group name is required"));
+    }
+
+    Ok(Json(
+        state
+            .board_preferences
+            .create_group(auth.user.id, name)
+            .await?,
+    ))
+}
+
+pub async fn update_group(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(group_id): Path<Uuid>,
+    Json(input): Json<UpdateBoardGroup>,
+) -> Result<Json<BoardGroup>> {
+    let name = input.name.as_deref().map(str::trim);
+    if name == Some("") {
+        return Err(AppError::invalid_field("name", "Group name is required"));
+    }
+
+    Ok(Json(
+        state
+            .board_preferences
+            .update_group(auth.user.id, group_id, name, input.position)
+            .await?,
+    ))
+}
+
+/// Delete a group; its boards move out of it
+pub async fn delete_group(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(group_id): Path<Uuid>,
+) -> Result<()> {
+    state
+        .board_preferences
+        .delete_group(auth.user.id, group_id)
+        .await
+}
+
+/// The user's stars, groups and positions of their boards
+pub async fn list_preferences(
+    State(state): State<AppState>,
+    auth: AuthUser,
+) -> Result<Json<Vec<BoardPreference>>> {
+    Ok(Json(
+        state.board_preferences.list_for_user(auth.user.id).await?,
+    ))
+}
+
+/// Star a board or put it in one of the user's groups
+pub async fn update_preference(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(board_id): Path<Uuid>,
+    Json(input): Json<UpdateBoardPreference>,
+) -> Result<Json<BoardPreference>> {
+    state
+        .boards
+        .get_user_role(board_id, auth.user.id)
+        .await?
+        .ok_or(AppError::Forbidden)?;
+    if let Some(Some(group_id)) = input.group_id {
+        if !state
+            .board_preferences
+            .owns_group(auth.user.id, group_id)
+            .await?
+        {
+            return Err(AppError::invalid_field(
+                "group_id",
+                "Group must be one of your groups",
+            ));
+        }
+    }
+
+    Ok(Json(
+        state
+            .board_preferences
+            .update(auth.user.id, board_id, input.starred, input.group_id)
+            .await?,
+    ))
+}
+
+/// Put the listed boards first on the user's boards page, in that order
+pub async fn reorder_boards(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Json(input): Json<ReorderBoards>,
+) -> Result<()> {
+    for board_id in &input.board_ids {
+        if state
+            .boards
+            .get_user_role(*board_id, auth.user.id)
+            .await?
+            .is_none()
+        {
+            return Err(AppError::invalid_field(
+                "board_ids",
+                format!("{} is not one of your boards", board_id),
+            ));
+        }
+    }
+
+    state
+        .board_preferences
+        .set_order(auth.user.id, &input.board_ids)
+        .await
+}
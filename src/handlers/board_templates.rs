@@ -0,0 +1,137 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use uuid::Uuid;
+
+use crate::auth::AuthUser;
+use crate::error::{AppError, Result};
+use crate::models::{BoardLayout, BoardTemplateResponse, SaveBoardTemplate, TemplateTag};
+use crate::services::board_templates::{find_builtin, BUILTIN_TEMPLATES};
+use crate::state::AppState;
+
+/// List the built-in templates followed by the user's own
+pub async fn list_templates(
+    State(state): State<AppState>,
+    auth: AuthUser,
+) -> Result<Json<Vec<BoardTemplateResponse>>> {
+    let mut templates: Vec<BoardTemplateResponse> =
+        BUILTIN_TEMPLATES.iter().map(|t| t.to_response()).collect();
+    templates.extend(
+        state
+            .board_templates
+            .list_for_owner(auth.user.id)
+            .await?
+            .into_iter()
+            .map(Into::into),
+    );
+    Ok(Json(templates))
+}
+
+/// Save the columns and tags of a board as a template of the current user
+pub async fn save_board_as_template(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(board_id): Path<Uuid>,
+    input: Option<Json<SaveBoardTemplate>>,
+) -> Result<Json<BoardTemplateResponse>> {
+    state
+        .boards
+        .get_user_role(board_id, auth.user.id)
+        .await?
+        .ok_or(AppError::Forbidden)?;
+
+    let board = state.boards.get_by_id(board_id).await?;
+    let input = input.map(|Json(input)| input).unwrap_or_default();
+    let name = input
+        .name
+        .as_deref()
+        .map(str::trim)
+        .unwrap_or(board.name.as_str());
+    if name.is_empty() {
+        return Err(AppError::invalid_field("name", "Template name is required"));
+    }
+    let description = input
+        .description
+        .as_deref()
+        .or(board.description.as_deref());
+
+    let layout = BoardLayout {
+        columns: state
+            .columns
+            .list_by_board(board_id)
+            .await?
+            .into_iter()
+            .map(|c| c.name)
+            .collect(),
+        tags: state
+            .tags
+            .list_by_board(board_id)
+            .await?
+            .into_iter()
+            .map(|t| TemplateTag {
+                name: t.name,
+                color: t.color,
+            })
+            .collect(),
+    };
+
+    let template = state
+        .board_templates
+        .create(auth.user.id, name, description, &layout)
+        .await?;
+    Ok(Json(template.into()))
+}
+
+/// Delete one of the user's templates. Boards created from it are kept.
+pub async fn delete_template(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(template_id): Path<Uuid>,
+) -> Result<()> {
+    if !state
+        .board_templates
+        .delete(template_id, auth.user.id)
+        .await?
+    {
+        return Err(AppError::NotFound("board_template"));
+    }
+    Ok(())
+}
+
+/// Layout of a built-in template key or of a template saved by this user
+pub(crate) async fn resolve_layout(
+    state: &AppState,
+    template: &str,
+    user_id: Uuid,
+) -> Result<BoardLayout> {
+    if let Some(builtin) = find_builtin(template) {
+        return Ok(builtin.layout());
+    }
+
+    let saved = match Uuid::parse_str(template.trim()) {
+        Ok(id) => state.board_templates.find_for_owner(id, user_id).await?,
+        Err(_) => None,
+    };
+    saved
+        .map(|t| t.layout())
+        .ok_or_else(|| AppError::Validation(format!("Unknown board template: {}", template)))
+}
+
+/// Create the columns and tags of a layout on a new board
+pub(crate) async fn apply_layout(
+    state: &AppState,
+    board_id: Uuid,
+    layout: &BoardLayout,
+) -> Result<()> {
+    for name in &layout.columns {
+        state
+            .columns
+            .create(board_id, name, None, None, None)
+            .await?;
+    }
+    for tag in &layout.tags {
+        state.tags.create(board_id, &tag.name, &tag.color).await?;
+    }
+    Ok(())
+}
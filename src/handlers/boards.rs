@@ -1,14 +1,25 @@
 use axum::{
-    extract::{Path, State},
+    extract::{OriginalUri, Path, Query, State},
     Json,
 };
+use serde_json::json;
+use std::collections::HashMap;
 use uuid::Uuid;
 
 use crate::auth::AuthUser;
 use crate::error::{AppError, Result};
+use crate::handlers::activity::record;
+use crate::handlers::board_templates::{apply_layout, resolve_layout};
+use crate::handlers::workspaces::require_workspace_editor;
 use crate::models::{
-    AddBoardPermission, BoardResponse, BoardRole, BoardWithDetails, CreateBoard, UpdateBoard,
+    AddBoardPermission, ArchivedItems, BoardEvent, BoardEventKind, BoardResponse, BoardRole,
+    BoardWithDetails, CardResponse, CreateBoard, DuplicateBoard, NotificationKind, PageQuery,
+    PositionRepair, UpdateBoard, User, DEFAULT_PAGE_SIZE,
 };
+use crate::services::activity::changes;
+use crate::services::email::Email;
+use crate::services::etag::{ETag, IfMatch};
+use crate::services::pagination::Paginated;
 use crate::state::AppState;
 
 pub async fn create_board(
@@ -17,13 +28,80 @@ pub async fn create_board(
     Json(input): Json<CreateBoard>,
 ) -> Result<Json<BoardResponse>> {
     if input.name.is_empty() {
-        return Err(AppError::Validation("Board name is required".to_string()));
+        return Err(AppError::invalid_field("name", "Board name is required"));
     }
 
-    let board = state
+    // Resolve the template first so an unknown one creates no board
+    let layout = match input.template.as_deref() {
+        Some(template) => Some(resolve_layout(&state, template, auth.user.id).await?),
+        None => None,
+    };
+    if let Some(workspace_id) = input.workspace_id {
+        require_workspace_editor(&state, workspace_id, auth.user.id).await?;
+    }
+
+    let mut board = state
         .boards
         .create(&input.name, input.description.as_deref(), auth.user.id)
         .await?;
+    if let Some(layout) = layout {
+        apply_layout(&state, board.id, &layout).await?;
+    }
+    if input.workspace_id.is_some() {
+        board = state
+            .boards
+            .set_workspace(board.id, input.workspace_id)
+            .await?;
+    }
+
+    Ok(Json(BoardResponse {
+        id: board.id,
+        name: board.name,
+        description: board.description,
+        owner_id: board.owner_id,
+        role: "owner".to_string(),
+        chat_column_policy: board.chat_column_policy,
+        default_column_id: board.default_column_id,
+        wip_limit_policy: board.wip_limit_policy,
+        allow_public_links: board.allow_public_links,
+        workspace_id: board.workspace_id,
+        version: board.version,
+        created_at: board.created_at,
+        updated_at: board.updated_at,
+    }))
+}
+
+/// Copy a board with its columns, tags and cards, as a new board of the current user
+pub async fn duplicate_board(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(board_id): Path<Uuid>,
+    input: Option<Json<DuplicateBoard>>,
+) -> Result<Json<BoardResponse>> {
+    // Private cards are copied too, so only editors may copy a board
+    let role = state
+        .boards
+        .get_user_role(board_id, auth.user.id)
+        .await?
+        .ok_or(AppError::Forbidden)?;
+    if !role.can_edit() {
+        return Err(AppError::Forbidden);
+    }
+
+    let source = state.boards.get_by_id(board_id).await?;
+    let input = input.map(|Json(input)| input).unwrap_or_default();
+    let name = match input.name.as_deref().map(str::trim) {
+        Some(name) => name.to_string(),
+        None => format!("{} (copy)", source.name),
+    };
+    if name.is_empty() {
+        return Err(AppError::invalid_field("name", "Board name is required"));
+    }
+
+    let board = state
+        .boards
+        .duplicate(board_id, &name, auth.user.id, input.include_comments)
+        .await?;
 
     Ok(Json(BoardResponse {
         id: board.id,
@@ -31,19 +109,30 @@ pub async fn create_board(
         description: board.description,
         owner_id: board.owner_id,
         role: "owner".to_string(),
+        chat_column_policy: board.chat_column_policy,
+        default_column_id: board.default_column_id,
+        wip_limit_policy: board.wip_limit_policy,
+        allow_public_links: board.allow_public_links,
+        workspace_id: board.workspace_id,
+        version: board.version,
         created_at: board.created_at,
         updated_at: board.updated_at,
     }))
 }
 
+/// Boards the user is a member of, most recently updated first, a page at a time
 pub async fn list_boards(
     State(state): State<AppState>,
     auth: AuthUser,
-) -> Result<Json<Vec<BoardResponse>>> {
-    let boards = state.boards.list_for_user(auth.user.id).await?;
+    OriginalUri(uri): OriginalUri,
+    Query(paging): Query<PageQuery>,
+) -> Result<Paginated<BoardResponse>> {
+    let page = paging.page(DEFAULT_PAGE_SIZE);
+    let boards = state.boards.list_page_for_user(auth.user.id, page).await?;
+    let total = state.boards.count_for_user(auth.user.id).await?;
 
-    Ok(Json(
-        boards
+    Ok(Paginated {
+        items: boards
             .into_iter()
             .map(|(board, role)| BoardResponse {
                 id: board.id,
@@ -51,18 +140,27 @@ pub async fn list_boards(
                 description: board.description,
                 owner_id: board.owner_id,
                 role,
+                chat_column_policy: board.chat_column_policy,
+                default_column_id: board.default_column_id,
+                wip_limit_policy: board.wip_limit_policy,
+                allow_public_links: board.allow_public_links,
+                workspace_id: board.workspace_id,
+                version: board.version,
                 created_at: board.created_at,
                 updated_at: board.updated_at,
             })
             .collect(),
-    ))
+        total,
+        page,
+        uri,
+    })
 }
 
 pub async fn get_board(
     State(state): State<AppState>,
     auth: AuthUser,
     Path(board_id): Path<Uuid>,
-) -> Result<Json<BoardWithDetails>> {
+) -> Result<(ETag, Json<BoardWithDetails>)> {
     let board = state.boards.get_by_id(board_id).await?;
     let role = state
         .boards
@@ -70,42 +168,147 @@ pub async fn get_board(
         .await?
         .ok_or(AppError::Forbidden)?;
 
-    let columns = state.columns.list_by_board(board_id).await?;
-    let tags = state.tags.list_by_board(board_id).await?;
+    let (columns, tags, cards, mut card_tags, collapsed) = tokio::try_join!(
+        state.columns.list_by_board(board_id),
+        state.tags.list_by_board(board_id),
+        state.cards.list_by_board_columns(board_id),
+        state.tags.list_for_board_cards(board_id),
+        state.columns.collapsed_for_user(board_id, auth.user.id),
+    )?;
+
+    let mut cards_by_column: HashMap<Uuid, Vec<CardResponse>> = HashMap::new();
+    for card in cards {
+        let Some(column_id) = card.column_id else {
+            continue;
+        };
+        let tags_of_card = card_tags.remove(&card.id).unwrap_or_default();
+        cards_by_column
+            .entry(column_id)
+            .or_default()
+            .push(card.into_response(tags_of_card.into_iter().map(|t| t.into()).collect()));
+    }
+
+    let ids: Vec<Uuid> = cards_by_column.values().flatten().map(|c| c.id).collect();
+    let blocked = state.links.blocked_card_ids(&ids).await?;
+    for card in cards_by_column.values_mut().flatten() {
+        card.is_blocked = blocked.contains(&card.id);
+    }
 
     let mut column_responses = Vec::new();
     for col in columns {
-        let cards = state.cards.list_by_column(col.id).await?;
-        let mut card_responses = Vec::new();
-        for card in cards {
+        let cards = cards_by_column.remove(&col.id).unwrap_or_default();
+        let collapsed = collapsed.contains(&col.id);
+        let mut col_response: crate::models::ColumnResponse = col.into();
+        col_response.cards = cards;
+        col_response.collapsed = collapsed;
+        column_responses.push(col_response);
+    }
+
+    Ok((
+        ETag(board.version),
+        Json(BoardWithDetails {
+            id: board.id,
+            name: board.name,
+            description: board.description,
+            owner_id: board.owner_id,
+            role: role.to_string(),
+            chat_column_policy: board.chat_column_policy,
+            default_column_id: board.default_column_id,
+            wip_limit_policy: board.wip_limit_policy,
+            allow_public_links: board.allow_public_links,
+            workspace_id: board.workspace_id,
+            version: board.version,
+            columns: column_responses,
+            tags: tags.into_iter().map(|t| t.into()).collect(),
+            created_at: board.created_at,
+            updated_at: board.updated_at,
+        }),
+    ))
+}
+
+/// The archived columns of a board, with their cards, and its archived cards
+pub async fn get_archived(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(board_id): Path<Uuid>,
+) -> Result<Json<ArchivedItems>> {
+    state
+        .boards
+        .get_user_role(board_id, auth.user.id)
+        .await?
+        .ok_or(AppError::Forbidden)?;
+
+    let mut columns = Vec::new();
+    for col in state.columns.list_archived_by_board(board_id).await? {
+        let mut cards = Vec::new();
+        for card in state.cards.list_by_column(col.id).await? {
             let card_tags = state.tags.list_for_card(card.id).await?;
-            card_responses
-                .push(card.into_response(card_tags.into_iter().map(|t| t.into()).collect()));
+            cards.push(card.into_response(card_tags.into_iter().map(|t| t.into()).collect()));
         }
         let mut col_response: crate::models::ColumnResponse = col.into();
-        col_response.cards = card_responses;
-        column_responses.push(col_response);
+        col_response.cards = cards;
+        columns.push(col_response);
     }
 
-    Ok(Json(BoardWithDetails {
-        id: board.id,
-        name: board.name,
-        description: board.description,
-        owner_id: board.owner_id,
-        role: role.to_string(),
-        columns: column_responses,
-        tags: tags.into_iter().map(|t| t.into()).collect(),
-        created_at: board.created_at,
-        updated_at: board.updated_at,
-    }))
+    let mut cards = Vec::new();
+    for card in state.cards.list_archived_by_board(board_id).await? {
+        let card_tags = state.tags.list_for_card(card.id).await?;
+        cards.push(card.into_response(card_tags.into_iter().map(|t| t.into()).collect()));
+    }
+
+    Ok(Json(ArchivedItems { columns, cards }))
+}
+
+/// Renumber the columns of a board and the cards of each column from 0, keeping their
+/// order, where duplicate positions or gaps crept in
+pub async fn repair_positions(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(board_id): Path<Uuid>,
+) -> Result<Json<PositionRepair>> {
+    let role = state
+        .boards
+        .get_user_role(board_id, auth.user.id)
+        .await?
+        .ok_or(AppError::Forbidden)?;
+    if !role.can_edit() {
+        return Err(AppError::Forbidden);
+    }
+
+    let mut repair = PositionRepair {
+        columns: state.columns.rebalance_board(board_id).await?,
+        cards: 0,
+    };
+    for column in state.columns.list_by_board(board_id).await? {
+        repair.cards += state.cards.rebalance_column(column.id).await?;
+    }
+
+    if repair.columns > 0 || repair.cards > 0 {
+        record(
+            &state,
+            BoardEvent::new(
+                BoardEventKind::BoardUpdated,
+                board_id,
+                board_id,
+                auth.user.id,
+            )
+            .with_details(json!({
+                "repaired_positions": {"columns": repair.columns, "cards": repair.cards}
+            })),
+        )
+        .await;
+    }
+
+    Ok(Json(repair))
 }
 
 pub async fn update_board(
     State(state): State<AppState>,
     auth: AuthUser,
     Path(board_id): Path<Uuid>,
+    if_match: IfMatch,
     Json(input): Json<UpdateBoard>,
-) -> Result<Json<BoardResponse>> {
+) -> Result<(ETag, Json<BoardResponse>)> {
     let role = state
         .boards
         .get_user_role(board_id, auth.user.id)
@@ -115,25 +318,69 @@ pub async fn update_board(
     if !role.can_edit() {
         return Err(AppError::Forbidden);
     }
+    // Exposing cards to people without an account is the owner's call
+    if input.allow_public_links.is_some() && !role.can_manage_permissions() {
+        return Err(AppError::Forbidden);
+    }
 
+    if let Some(Some(column_id)) = input.default_column_id {
+        let column = state.columns.get_by_id(column_id).await?;
+        if column.board_id != board_id {
+            return Err(AppError::invalid_field(
+                "default_column_id",
+                "Default column must belong to this board",
+            ));
+        }
+    }
+
+    let before = state.boards.get_by_id(board_id).await?;
+    let expected_version = if_match.expected_version(before.version)?;
     let board = state
         .boards
         .update(
             board_id,
             input.name.as_deref(),
             input.description.as_deref(),
+            input.chat_column_policy,
+            input.default_column_id,
+            input.wip_limit_policy,
+            input.allow_public_links,
+            expected_version,
         )
         .await?;
 
-    Ok(Json(BoardResponse {
-        id: board.id,
-        name: board.name,
-        description: board.description,
-        owner_id: board.owner_id,
-        role: role.to_string(),
-        created_at: board.created_at,
-        updated_at: board.updated_at,
-    }))
+    if let Some(details) = changes(&before, &board) {
+        record(
+            &state,
+            BoardEvent::new(
+                BoardEventKind::BoardUpdated,
+                board_id,
+                board_id,
+                auth.user.id,
+            )
+            .with_details(details),
+        )
+        .await;
+    }
+
+    Ok((
+        ETag(board.version),
+        Json(BoardResponse {
+            id: board.id,
+            name: board.name,
+            description: board.description,
+            owner_id: board.owner_id,
+            role: role.to_string(),
+            chat_column_policy: board.chat_column_policy,
+            default_column_id: board.default_column_id,
+            wip_limit_policy: board.wip_limit_policy,
+            allow_public_links: board.allow_public_links,
+            workspace_id: board.workspace_id,
+            version: board.version,
+            created_at: board.created_at,
+            updated_at: board.updated_at,
+        }),
+    ))
 }
 
 pub async fn delete_board(
@@ -151,7 +398,18 @@ pub async fn delete_board(
         return Err(AppError::Forbidden);
     }
 
-    state.boards.delete(board_id).await?;
+    state.boards.move_to_trash(board_id, auth.user.id).await?;
+
+    record(
+        &state,
+        BoardEvent::new(
+            BoardEventKind::BoardDeleted,
+            board_id,
+            board_id,
+            auth.user.id,
+        ),
+    )
+    .await;
     Ok(())
 }
 
@@ -176,10 +434,57 @@ pub async fn add_permission(
         return Err(AppError::BadRequest("Cannot add another owner".to_string()));
     }
 
+    let previous = state
+        .boards
+        .find_permission(board_id, input.user_id)
+        .await?;
     state
         .boards
         .add_permission(board_id, input.user_id, input.role)
         .await?;
+    if previous == Some(input.role) {
+        return Ok(());
+    }
+
+    if let Some(user) = state.users.find_by_id(input.user_id).await? {
+        let board = state.boards.get_by_id(board_id).await?;
+        let (event, details, kind, email) = match previous {
+            None => (
+                BoardEventKind::MemberAdded,
+                json!({"name": user.name, "role": input.role.to_string()}),
+                NotificationKind::AddedToBoard,
+                state.email.board_shared(
+                    &user.email,
+                    &auth.user.name,
+                    &board.name,
+                    board_id,
+                    input.role,
+                ),
+            ),
+            Some(from) => (
+                BoardEventKind::MemberRoleChanged,
+                json!({
+                    "name": user.name,
+                    "role": {"from": from.to_string(), "to": input.role.to_string()},
+                }),
+                NotificationKind::BoardRoleChanged,
+                state.email.board_role_changed(
+                    &user.email,
+                    &auth.user.name,
+                    &board.name,
+                    board_id,
+                    input.role,
+                ),
+            ),
+        };
+        record(
+            &state,
+            BoardEvent::new(event, board_id, user.id, auth.user.id).with_details(details),
+        )
+        .await;
+        let message = format!("gave you {} access", input.role);
+        notify_member(&state, board_id, &user, auth.user.id, kind, &message, email).await;
+    }
 
     Ok(())
 }
@@ -199,6 +504,63 @@ pub async fn remove_permission(
         return Err(AppError::Forbidden);
     }
 
+    let previous = state.boards.find_permission(board_id, user_id).await?;
     state.boards.remove_permission(board_id, user_id).await?;
+
+    if let Some(user) = state.users.find_by_id(user_id).await? {
+        let board = state.boards.get_by_id(board_id).await?;
+        let role = previous.map(|r| r.to_string());
+        record(
+            &state,
+            BoardEvent::new(
+                BoardEventKind::MemberRemoved,
+                board_id,
+                user.id,
+                auth.user.id,
+            )
+            .with_details(json!({"name": user.name, "role": role})),
+        )
+        .await;
+        let email = state
+            .email
+            .board_unshared(&user.email, &auth.user.name, &board.name);
+        notify_member(
+            &state,
+            board_id,
+            &user,
+            auth.user.id,
+            NotificationKind::RemovedFromBoard,
+            "removed your access",
+            email,
+        )
+        .await;
+    }
+
     Ok(())
 }
+
+/// Tell a user, in the app and by email, about a change someone else made to their
+/// role on the board. The change is already saved, so a failed notification is only
+/// reported in the server log.
+async fn notify_member(
+    state: &AppState,
+    board_id: Uuid,
+    user: &User,
+    actor_id: Uuid,
+    kind: NotificationKind,
+    message: &str,
+    email: Email,
+) {
+    if user.id == actor_id {
+        return;
+    }
+
+    if let Err(e) = state
+        .notifications
+        .create_for_board(user.id, board_id, actor_id, kind, message)
+        .await
+    {
+        tracing::warn!(board_id = %board_id, error = %e, "Could not notify board member");
+    }
+    state.email.send_in_background(email);
+}
@@ -0,0 +1,87 @@
+use axum::{
+    extract::{Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::auth::{generate_token, hash_token, AuthUser};
+use crate::error::{AppError, Result};
+use crate::models::{CalendarFeedResponse, User};
+use crate::services::calendar::ics_feed;
+use crate::state::AppState;
+
+#[derive(Debug, Deserialize)]
+pub struct CalendarFeedQuery {
+    pub token: Option<String>,
+}
+
+/// Create a new feed token for the user, replacing the previous one, and return its
+/// address. Calendar apps cannot sign in, so the token in the address is all they need.
+pub(crate) async fn create_feed_token(
+    state: &AppState,
+    user_id: Uuid,
+) -> Result<CalendarFeedResponse> {
+    let token = generate_token();
+    state
+        .calendar
+        .set_feed_token(user_id, &hash_token(&token))
+        .await?;
+
+    Ok(CalendarFeedResponse {
+        url: state.link(&format!("/calendar.ics?token={}", token)),
+        token,
+    })
+}
+
+/// Generate the user's calendar feed address. The token is only shown once.
+pub async fn create_calendar_feed(
+    State(state): State<AppState>,
+    auth: AuthUser,
+) -> Result<Json<CalendarFeedResponse>> {
+    Ok(Json(create_feed_token(&state, auth.user.id).await?))
+}
+
+/// Revoke the user's calendar feed address
+pub async fn delete_calendar_feed(
+    State(state): State<AppState>,
+    auth: AuthUser,
+) -> Result<StatusCode> {
+    if !state.calendar.delete_feed(auth.user.id).await? {
+        return Err(AppError::NotFound("calendar_feed"));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// iCalendar feed of the due, start and end dates of the cards the token's user can see
+pub async fn calendar_feed(
+    State(state): State<AppState>,
+    Query(query): Query<CalendarFeedQuery>,
+) -> Result<Response> {
+    let token = query.token.ok_or(AppError::Unauthorized)?;
+    let user_id = state
+        .calendar
+        .find_user_by_feed_token(&hash_token(&token))
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+    let user = state
+        .users
+        .find_by_id(user_id)
+        .await?
+        .filter(User::is_active)
+        .ok_or(AppError::Unauthorized)?;
+
+    let cards = state.calendar.list_dated_cards(user.id, None, None).await?;
+    let body = ics_feed(&cards, &state.link(""));
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/calendar; charset=utf-8"),
+            (header::CACHE_CONTROL, "no-store"),
+        ],
+        body,
+    )
+        .into_response())
+}
@@ -0,0 +1,261 @@
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    Json,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::auth::AuthUser;
+use crate::error::{AppError, Result};
+use crate::handlers::activity::record;
+use crate::handlers::columns::{check_wip_limit, wip_warning_headers};
+use crate::models::{
+    BoardEvent, BoardEventKind, BoardRole, CardResponse, CardStatus, CardTemplate,
+    CardTemplateResponse, CardVisibility, CreateCardFromTemplate, CreateCardTemplate,
+    UpdateCardTemplate,
+};
+use crate::services::card_templates::{render_body, render_title};
+use crate::state::AppState;
+
+async fn board_role(state: &AppState, board_id: Uuid, user_id: Uuid) -> Result<BoardRole> {
+    state
+        .boards
+        .get_user_role(board_id, user_id)
+        .await?
+        .ok_or(AppError::Forbidden)
+}
+
+/// A template with the board it belongs to checked against the path
+async fn board_template(
+    state: &AppState,
+    board_id: Uuid,
+    template_id: Uuid,
+) -> Result<CardTemplate> {
+    let template = state.card_templates.get_by_id(template_id).await?;
+    if template.board_id != board_id {
+        return Err(AppError::NotFound("card_template"));
+    }
+    Ok(template)
+}
+
+async fn template_response(
+    state: &AppState,
+    template: CardTemplate,
+) -> Result<CardTemplateResponse> {
+    let tags = state.card_templates.list_tags(template.id).await?;
+    Ok(template.into_response(tags.into_iter().map(Into::into).collect()))
+}
+
+/// Check that the tags exist and belong to the board
+async fn validate_tags(state: &AppState, board_id: Uuid, tag_ids: &[Uuid]) -> Result<()> {
+    for &tag_id in tag_ids {
+        let tag = state.tags.find_by_id(tag_id).await?;
+        if tag.and_then(|t| t.board_id) != Some(board_id) {
+            return Err(AppError::Validation(format!(
+                "Tag {} is not a tag of this board",
+                tag_id
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn checklist_json(checklist: &[String]) -> Result<String> {
+    serde_json::to_string(checklist).map_err(|e| AppError::Internal(e.to_string()))
+}
+
+fn validate_names(name: Option<&str>, title_pattern: Option<&str>) -> Result<()> {
+    if name.is_some_and(|n| n.trim().is_empty()) {
+        return Err(AppError::invalid_field("name", "Template name is required"));
+    }
+    if title_pattern.is_some_and(|p| p.trim().is_empty()) {
+        return Err(AppError::invalid_field(
+            "title_pattern",
+            "Title pattern is required",
+        ));
+    }
+    Ok(())
+}
+
+/// List the card templates of a board
+pub async fn list_templates(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(board_id): Path<Uuid>,
+) -> Result<Json<Vec<CardTemplateResponse>>> {
+    board_role(&state, board_id, auth.user.id).await?;
+
+    let mut templates = Vec::new();
+    for template in state.card_templates.list_by_board(board_id).await? {
+        templates.push(template_response(&state, template).await?);
+    }
+    Ok(Json(templates))
+}
+
+pub async fn create_template(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(board_id): Path<Uuid>,
+    Json(input): Json<CreateCardTemplate>,
+) -> Result<Json<CardTemplateResponse>> {
+    if !board_role(&state, board_id, auth.user.id).await?.can_edit() {
+        return Err(AppError::Forbidden);
+    }
+
+    validate_names(Some(&input.name), Some(&input.title_pattern))?;
+    validate_tags(&state, board_id, &input.tag_ids).await?;
+
+    let template = state
+        .card_templates
+        .create(
+            board_id,
+            input.name.trim(),
+            &input.title_pattern,
+            input.body.as_deref(),
+            &checklist_json(&input.checklist)?,
+            auth.user.id,
+        )
+        .await?;
+    state
+        .card_templates
+        .set_tags(template.id, &input.tag_ids)
+        .await?;
+
+    Ok(Json(template_response(&state, template).await?))
+}
+
+pub async fn update_template(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path((board_id, template_id)): Path<(Uuid, Uuid)>,
+    Json(input): Json<UpdateCardTemplate>,
+) -> Result<Json<CardTemplateResponse>> {
+    if !board_role(&state, board_id, auth.user.id).await?.can_edit() {
+        return Err(AppError::Forbidden);
+    }
+    board_template(&state, board_id, template_id).await?;
+
+    validate_names(input.name.as_deref(), input.title_pattern.as_deref())?;
+    if let Some(ref tag_ids) = input.tag_ids {
+        validate_tags(&state, board_id, tag_ids).await?;
+    }
+    let checklist = input.checklist.as_deref().map(checklist_json).transpose()?;
+
+    let template = state
+        .card_templates
+        .update(
+            template_id,
+            input.name.as_deref().map(str::trim),
+            input.title_pattern.as_deref(),
+            input.body.as_ref().map(|b| b.as_deref()),
+            checklist.as_deref(),
+        )
+        .await?;
+    if let Some(ref tag_ids) = input.tag_ids {
+        state.card_templates.set_tags(template_id, tag_ids).await?;
+    }
+
+    Ok(Json(template_response(&state, template).await?))
+}
+
+pub async fn delete_template(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path((board_id, template_id)): Path<(Uuid, Uuid)>,
+) -> Result<()> {
+    if !board_role(&state, board_id, auth.user.id).await?.can_edit() {
+        return Err(AppError::Forbidden);
+    }
+    board_template(&state, board_id, template_id).await?;
+
+    state.card_templates.delete(template_id).await
+}
+
+/// Create a card pre-filled from a template: title from the pattern, body followed
+/// by the checklist, and the template's tags
+pub async fn create_card_from_template(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path((board_id, template_id)): Path<(Uuid, Uuid)>,
+    input: Option<Json<CreateCardFromTemplate>>,
+) -> Result<(HeaderMap, Json<CardResponse>)> {
+    if !board_role(&state, board_id, auth.user.id).await?.can_edit() {
+        return Err(AppError::Forbidden);
+    }
+    let template = board_template(&state, board_id, template_id).await?;
+    let input = input.map(|Json(input)| input).unwrap_or_default();
+
+    let column_id = match input.column_id {
+        Some(column_id) => {
+            let column = state.columns.get_by_id(column_id).await?;
+            if column.board_id != board_id {
+                return Err(AppError::Validation(
+                    "Column does not belong to this board".to_string(),
+                ));
+            }
+            column_id
+        }
+        None => {
+            let board = state.boards.get_by_id(board_id).await?;
+            match board.default_column_id {
+                Some(column_id) => column_id,
+                None => state
+                    .columns
+                    .list_by_board(board_id)
+                    .await?
+                    .first()
+                    .map(|c| c.id)
+                    .ok_or_else(|| AppError::Validation("Board has no columns".to_string()))?,
+            }
+        }
+    };
+
+    let column = state.columns.get_by_id(column_id).await?;
+    let wip_warning = check_wip_limit(&state, &column).await?;
+
+    let title = render_title(
+        &template.title_pattern,
+        input.title.as_deref(),
+        chrono::Local::now().date_naive(),
+    );
+    if title.is_empty() {
+        return Err(AppError::Validation("Card title is required".to_string()));
+    }
+    let body = render_body(template.body.as_deref(), &template.checklist_items());
+
+    let card = state
+        .cards
+        .create(
+            column_id,
+            &title,
+            body.as_deref(),
+            None,
+            CardVisibility::Restricted,
+            CardStatus::Open,
+            None,
+            None,
+            input.due_date,
+            None,
+            auth.user.id,
+        )
+        .await?;
+
+    for tag in state.card_templates.list_tags(template.id).await? {
+        state.tags.add_to_card(card.id, tag.id).await?;
+    }
+
+    record(
+        &state,
+        BoardEvent::new(BoardEventKind::CardCreated, board_id, card.id, auth.user.id).with_details(
+            json!({"title": card.title, "column_id": column_id, "template_id": template.id}),
+        ),
+    )
+    .await;
+
+    let tags = state.tags.list_for_card(card.id).await?;
+    Ok((
+        wip_warning_headers(wip_warning),
+        Json(card.into_response(tags.into_iter().map(Into::into).collect())),
+    ))
+}
@@ -1,22 +1,51 @@
 use axum::{
-    extract::{Path, Query, State},
+    extract::{OriginalUri, Path, Query, State},
+    http::HeaderMap,
     Json,
 };
+use serde_json::json;
+use sqlx::SqliteConnection;
+use std::collections::{hash_map::Entry, HashMap, HashSet};
 use uuid::Uuid;
 
 use crate::auth::AuthUser;
 use crate::error::{AppError, Result};
+use crate::handlers::activity::{
+    record, record_for_card, record_on_boards, PendingActivity, DEFAULT_ACTIVITY_LIMIT,
+};
+use crate::handlers::columns::{
+    check_manual_order, check_wip_limit, check_wip_limit_on, hex_color, sync_card_status,
+    wip_warning_headers,
+};
+use crate::handlers::comments::can_edit_card;
+use crate::handlers::links::{add_blocker_warning, check_blockers, is_done_column, mark_blocked};
 use crate::models::{
-    CardFilter, CardResponse, CardStatus, CardVisibility, CreateCard, MoveCard, UpdateCard,
+    BoardEvent, BoardEventKind, BoardRole, BulkCardOperation, BulkCardRequest, BulkCardResponse,
+    Card, CardCover, CardFilter, CardFullResponse, CardResponse, CardStatus, CardVisibility,
+    CloneCard, ColumnPositions, CommentResponse, CreateCard, ImportCardsText, MoveCard,
+    MoveCardResponse, PageQuery, Tag, UpdateCard, DEFAULT_PAGE_SIZE,
 };
+use crate::repo::card::CardRepository;
+use crate::repo::column::ColumnRepository;
+use crate::repo::tag::TagRepository;
+use crate::services::activity::changes;
+use crate::services::etag::{ETag, IfMatch};
+use crate::services::pagination::Paginated;
+use crate::services::text_import::parse_card_list;
 use crate::state::AppState;
 
+/// Maximum number of cards a single text import may create
+const MAX_IMPORTED_CARDS: usize = 200;
+
+/// Maximum number of operations in a single bulk card request
+const MAX_BULK_OPERATIONS: usize = 500;
+
 pub async fn create_card(
     State(state): State<AppState>,
     auth: AuthUser,
     Path(column_id): Path<Uuid>,
     Json(input): Json<CreateCard>,
-) -> Result<Json<CardResponse>> {
+) -> Result<(HeaderMap, Json<CardResponse>)> {
     let column = state.columns.get_by_id(column_id).await?;
 
     let role = state
@@ -30,9 +59,11 @@ pub async fn create_card(
     }
 
     if input.title.is_empty() {
-        return Err(AppError::Validation("Card title is required".to_string()));
+        return Err(AppError::invalid_field("title", "Card title is required"));
     }
 
+    let wip_warning = check_wip_limit(&state, &column).await?;
+
     let visibility = input.visibility.unwrap_or(CardVisibility::Restricted);
     let status = input.status.unwrap_or(CardStatus::Open);
 
@@ -48,29 +79,206 @@ pub async fn create_card(
             input.start_date,
             input.end_date,
             input.due_date,
+            input.priority,
             auth.user.id,
         )
         .await?;
 
-    Ok(Json(card.into_response(vec![])))
+    record(
+        &state,
+        BoardEvent::new(
+            BoardEventKind::CardCreated,
+            column.board_id,
+            card.id,
+            auth.user.id,
+        )
+        .with_details(json!({"title": card.title, "column_id": column_id})),
+    )
+    .await;
+
+    Ok((
+        wip_warning_headers(wip_warning),
+        Json(card.into_response(vec![])),
+    ))
 }
 
+/// Copy a card with its body, checklist and tags, and optionally its comments. Board
+/// cards are copied to a column of the same board, inbox cards to the inbox.
+pub async fn clone_card(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(card_id): Path<Uuid>,
+    input: Option<Json<CloneCard>>,
+) -> Result<(HeaderMap, Json<CardResponse>)> {
+    let card = state.cards.get_by_id(card_id).await?;
+    let input = input.map(|Json(input)| input).unwrap_or_default();
+    let title = input
+        .title
+        .as_deref()
+        .map(str::trim)
+        .unwrap_or(card.title.as_str())
+        .to_string();
+    if title.is_empty() {
+        return Err(AppError::invalid_field("title", "Card title is required"));
+    }
+
+    let Some(source_column_id) = card.column_id else {
+        if card.owner_id != Some(auth.user.id) {
+            return Err(AppError::Forbidden);
+        }
+        if input.column_id.is_some() {
+            return Err(AppError::invalid_field(
+                "column_id",
+                "Inbox cards are copied to the inbox",
+            ));
+        }
+        let copy = state
+            .cards
+            .clone_card(card_id, None, &title, input.include_comments, auth.user.id)
+            .await?;
+        let Json(copy) = card_response(&state, copy).await?;
+        return Ok((HeaderMap::new(), Json(copy)));
+    };
+
+    let source_column = state.columns.get_by_id(source_column_id).await?;
+    let role = state
+        .boards
+        .get_user_role(source_column.board_id, auth.user.id)
+        .await?
+        .ok_or(AppError::Forbidden)?;
+    if !role.can_edit() {
+        return Err(AppError::Forbidden);
+    }
+
+    let column = match input.column_id {
+        Some(column_id) if column_id != source_column_id => {
+            let column = state.columns.get_by_id(column_id).await?;
+            if column.board_id != source_column.board_id {
+                return Err(AppError::BadRequest(
+                    "Cannot copy a card to a different board".to_string(),
+                ));
+            }
+            column
+        }
+        _ => source_column,
+    };
+    let wip_warning = check_wip_limit(&state, &column).await?;
+
+    let copy = state
+        .cards
+        .clone_card(
+            card_id,
+            Some(column.id),
+            &title,
+            input.include_comments,
+            auth.user.id,
+        )
+        .await?;
+    record(
+        &state,
+        BoardEvent::new(
+            BoardEventKind::CardCreated,
+            column.board_id,
+            copy.id,
+            auth.user.id,
+        )
+        .with_details(json!({"title": copy.title, "column_id": column.id, "cloned_from": card_id})),
+    )
+    .await;
+
+    let Json(copy) = card_response(&state, copy).await?;
+    Ok((wip_warning_headers(wip_warning), Json(copy)))
+}
+
+/// Create one card per line of a pasted text list, appended to the column in order
+pub async fn import_cards_from_text(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(column_id): Path<Uuid>,
+    Json(input): Json<ImportCardsText>,
+) -> Result<Json<Vec<CardResponse>>> {
+    let column = state.columns.get_by_id(column_id).await?;
+
+    let role = state
+        .boards
+        .get_user_role(column.board_id, auth.user.id)
+        .await?
+        .ok_or(AppError::Forbidden)?;
+
+    if !role.can_edit() {
+        return Err(AppError::Forbidden);
+    }
+
+    let lines = parse_card_list(&input.text);
+    if lines.is_empty() {
+        return Err(AppError::Validation(
+            "Text contains no card titles".to_string(),
+        ));
+    }
+    if lines.len() > MAX_IMPORTED_CARDS {
+        return Err(AppError::Validation(format!(
+            "Cannot import more than {} cards at once",
+            MAX_IMPORTED_CARDS
+        )));
+    }
+
+    let visibility = input.visibility.unwrap_or(CardVisibility::Restricted);
+
+    let mut created = Vec::with_capacity(lines.len());
+    for line in lines {
+        let card = state
+            .cards
+            .create(
+                column_id,
+                &line.title,
+                None,
+                None,
+                visibility,
+                line.status,
+                None,
+                None,
+                None,
+                None,
+                auth.user.id,
+            )
+            .await?;
+        record(
+            &state,
+            BoardEvent::new(
+                BoardEventKind::CardCreated,
+                column.board_id,
+                card.id,
+                auth.user.id,
+            )
+            .with_details(json!({"title": card.title, "column_id": column_id})),
+        )
+        .await;
+        created.push(card.into_response(vec![]));
+    }
+
+    Ok(Json(created))
+}
+
+/// Cards of a board matching the filter, in board order, a page at a time
 pub async fn list_cards(
     State(state): State<AppState>,
     auth: AuthUser,
     Path(board_id): Path<Uuid>,
+    OriginalUri(uri): OriginalUri,
     Query(filter): Query<CardFilter>,
-) -> Result<Json<Vec<CardResponse>>> {
+    Query(paging): Query<PageQuery>,
+) -> Result<Paginated<CardResponse>> {
     let role = state.boards.get_user_role(board_id, auth.user.id).await?;
+    let role = role.as_ref().map(|r| r.to_string());
+    let page = paging.page(DEFAULT_PAGE_SIZE);
 
     let cards = state
         .cards
-        .list_by_board_with_filter(
-            board_id,
-            auth.user.id,
-            role.as_ref().map(|r| r.to_string()).as_deref(),
-            &filter,
-        )
+        .list_page_by_board_with_filter(board_id, auth.user.id, role.as_deref(), &filter, page)
+        .await?;
+    let total = state
+        .cards
+        .count_by_board_with_filter(board_id, role.as_deref(), &filter)
         .await?;
 
     let mut responses = Vec::new();
@@ -78,15 +286,21 @@ pub async fn list_cards(
         let tags = state.tags.list_for_card(card.id).await?;
         responses.push(card.into_response(tags.into_iter().map(|t| t.into()).collect()));
     }
+    mark_blocked(&state, &mut responses).await?;
 
-    Ok(Json(responses))
+    Ok(Paginated {
+        items: responses,
+        total,
+        page,
+        uri,
+    })
 }
 
 pub async fn get_card(
     State(state): State<AppState>,
     auth: AuthUser,
     Path(card_id): Path<Uuid>,
-) -> Result<Json<CardResponse>> {
+) -> Result<(ETag, Json<CardResponse>)> {
     let card = state.cards.get_by_id(card_id).await?;
     let board_id = state.cards.get_board_id_for_card(card_id).await?;
 
@@ -110,18 +324,81 @@ pub async fn get_card(
         }
     }
 
-    let tags = state.tags.list_for_card(card.id).await?;
-    Ok(Json(card.into_response(
-        tags.into_iter().map(|t| t.into()).collect(),
-    )))
+    Ok((ETag(card.version), card_response(&state, card).await?))
+}
+
+/// Card with its tags, boards, assignees, comments, activity and the user's comment
+/// draft in one response, for detail views
+pub async fn get_card_full(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(card_id): Path<Uuid>,
+) -> Result<(ETag, Json<CardFullResponse>)> {
+    let card = state.cards.get_by_id(card_id).await?;
+
+    let (tags, boards, assignees, comments, reactions, blockers, activity, comment_draft) = tokio::try_join!(
+        state.tags.list_for_card(card_id),
+        state
+            .card_boards
+            .list_board_access_for_card(card_id, auth.user.id),
+        state.assignees.list_for_card(card_id),
+        state.comments.list_by_card(card_id),
+        state.reactions.list_for_card(card_id, auth.user.id),
+        state.links.list_open_blockers(card_id),
+        state
+            .activity
+            .list_for_card(card_id, DEFAULT_ACTIVITY_LIMIT),
+        state.comments.find_draft(card_id, auth.user.id),
+    )?;
+
+    // Same visibility rules as get_card; the card's owner always has access
+    let roles: Vec<BoardRole> = boards
+        .iter()
+        .filter_map(|b| b.role.as_deref().and_then(|r| r.parse().ok()))
+        .collect();
+    let is_owner = card.owner_id == Some(auth.user.id) || card.created_by == auth.user.id;
+    let visibility: CardVisibility = card.visibility.parse().unwrap_or(CardVisibility::Private);
+    let has_access = is_owner
+        || match visibility {
+            CardVisibility::Private => roles.iter().any(|r| r.can_edit()),
+            CardVisibility::Restricted => !roles.is_empty(),
+            CardVisibility::Public => true,
+        };
+
+    if !has_access {
+        return Err(AppError::Forbidden);
+    }
+
+    let version = card.version;
+    let mut card = card.into_response(tags.into_iter().map(|t| t.into()).collect());
+    card.is_blocked = !blockers.is_empty();
+    Ok((
+        ETag(version),
+        Json(CardFullResponse {
+            card,
+            boards,
+            assignees,
+            comments: comments
+                .into_iter()
+                .map(|c| CommentResponse {
+                    reactions: reactions.for_comment(c.id),
+                    ..c.into()
+                })
+                .collect(),
+            reactions: reactions.card,
+            activity: activity.into_iter().map(Into::into).collect(),
+            comment_draft,
+        }),
+    ))
 }
 
 pub async fn update_card(
     State(state): State<AppState>,
     auth: AuthUser,
     Path(card_id): Path<Uuid>,
+    if_match: IfMatch,
     Json(input): Json<UpdateCard>,
-) -> Result<Json<CardResponse>> {
+) -> Result<(HeaderMap, ETag, Json<CardResponse>)> {
     let board_id = state.cards.get_board_id_for_card(card_id).await?;
 
     let role = state
@@ -134,24 +411,48 @@ pub async fn update_card(
         return Err(AppError::Forbidden);
     }
 
+    state.cards.ensure_unlocked(card_id).await?;
+    let before = state.cards.get_by_id(card_id).await?;
+    let expected_version = if_match.expected_version(before.version)?;
+
     let card = state
         .cards
         .update(
             card_id,
             input.title.as_deref(),
-            input.body.as_deref(),
+            input.body.as_ref().map(|b| b.as_deref()),
             input.visibility,
             input.status,
             input.start_date,
             input.end_date,
             input.due_date,
+            input.priority,
+            expected_version,
         )
         .await?;
+    state.revisions.record(&before, &card, auth.user.id).await?;
+
+    if let Some(details) = changes(&before, &card) {
+        record_for_card(
+            &state,
+            BoardEventKind::CardUpdated,
+            card.id,
+            card.id,
+            auth.user.id,
+            Some(details),
+        )
+        .await;
+    }
 
-    let tags = state.tags.list_for_card(card.id).await?;
-    Ok(Json(card.into_response(
-        tags.into_iter().map(|t| t.into()).collect(),
-    )))
+    let mut headers = HeaderMap::new();
+    if input.status == Some(CardStatus::Done) {
+        add_blocker_warning(&mut headers, check_blockers(&state, card.id).await?);
+    }
+    Ok((
+        headers,
+        ETag(card.version),
+        card_response(&state, card).await?,
+    ))
 }
 
 pub async fn delete_card(
@@ -171,7 +472,18 @@ pub async fn delete_card(
         return Err(AppError::Forbidden);
     }
 
-    state.cards.delete(card_id).await?;
+    state.cards.ensure_unlocked(card_id).await?;
+    let card = state.cards.get_by_id(card_id).await?;
+    let board_ids = state.card_boards.list_board_ids_for_card(card_id).await?;
+    state.cards.move_to_trash(card_id, auth.user.id).await?;
+
+    record_on_boards(
+        &state,
+        &board_ids,
+        BoardEvent::new(BoardEventKind::CardDeleted, board_id, card_id, auth.user.id)
+            .with_details(json!({"title": card.title})),
+    )
+    .await;
     Ok(())
 }
 
@@ -180,8 +492,10 @@ pub async fn move_card(
     auth: AuthUser,
     Path(card_id): Path<Uuid>,
     Json(input): Json<MoveCard>,
-) -> Result<Json<CardResponse>> {
-    let board_id = state.cards.get_board_id_for_card(card_id).await?;
+) -> Result<(HeaderMap, Json<MoveCardResponse>)> {
+    state.cards.get_by_id(card_id).await?;
+    let target_column = state.columns.get_by_id(input.column_id).await?;
+    let board_id = target_column.board_id;
 
     let role = state
         .boards
@@ -193,21 +507,489 @@ pub async fn move_card(
         return Err(AppError::Forbidden);
     }
 
-    // Verify target column belongs to the same board
-    let target_column = state.columns.get_by_id(input.column_id).await?;
-    if target_column.board_id != board_id {
-        return Err(AppError::BadRequest(
-            "Cannot move card to a different board".to_string(),
-        ));
+    // The card moves within one of the boards it is on
+    let source_column_id = state
+        .card_boards
+        .get_assignment(card_id, board_id)
+        .await?
+        .ok_or_else(|| AppError::BadRequest("Cannot move card to a different board".to_string()))?
+        .column_id;
+    let within_column = source_column_id == Some(target_column.id);
+    check_manual_order(&target_column, within_column)?;
+    let wip_warning = if within_column {
+        None
+    } else {
+        check_wip_limit(&state, &target_column).await?
+    };
+
+    let assignment = state
+        .card_boards
+        .move_card_in_board(card_id, board_id, Some(input.column_id), input.position)
+        .await?;
+    let card = state.cards.get_by_id(card_id).await?;
+
+    record(
+        &state,
+        BoardEvent::new(BoardEventKind::CardMoved, board_id, card.id, auth.user.id).with_details(
+            json!({
+                "from_column_id": source_column_id,
+                "to_column_id": input.column_id,
+                "position": assignment.position,
+            }),
+        ),
+    )
+    .await;
+    let card = if within_column {
+        card
+    } else {
+        sync_card_status(&state, auth.user.id, card, &target_column).await?
+    }
+    .placed(&assignment);
+
+    // Return the new order of the source and target columns
+    let mut affected_columns = Vec::new();
+    if source_column_id != Some(input.column_id) {
+        affected_columns.push(ColumnPositions {
+            column_id: source_column_id,
+            cards: state
+                .card_boards
+                .list_positions(board_id, source_column_id)
+                .await?,
+        });
+    }
+    affected_columns.push(ColumnPositions {
+        column_id: Some(input.column_id),
+        cards: state
+            .card_boards
+            .list_positions(board_id, Some(input.column_id))
+            .await?,
+    });
+
+    let mut headers = wip_warning_headers(wip_warning);
+    if !within_column && is_done_column(&target_column) {
+        add_blocker_warning(&mut headers, check_blockers(&state, card.id).await?);
+    }
+    let Json(card) = card_response(&state, card).await?;
+    Ok((
+        headers,
+        Json(MoveCardResponse {
+            card,
+            affected_columns,
+        }),
+    ))
+}
+
+/// Apply several card operations on a board in one transaction
+pub async fn bulk_update_cards(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(board_id): Path<Uuid>,
+    Json(input): Json<BulkCardRequest>,
+) -> Result<Json<BulkCardResponse>> {
+    let role = state
+        .boards
+        .get_user_role(board_id, auth.user.id)
+        .await?
+        .ok_or(AppError::Forbidden)?;
+
+    if !role.can_edit() {
+        return Err(AppError::Forbidden);
+    }
+
+    apply_bulk_operations(&state, board_id, auth.user.id, &input.operations).await?;
+
+    Ok(Json(BulkCardResponse {
+        applied: input.operations.len(),
+    }))
+}
+
+/// Check that bulk operations only touch cards, columns and tags of the board, and only
+/// move locked cards, apply them together and record them in the board activity. The caller checks that the
+/// user can edit the board.
+pub(crate) async fn apply_bulk_operations(
+    state: &AppState,
+    board_id: Uuid,
+    user_id: Uuid,
+    operations: &[BulkCardOperation],
+) -> Result<()> {
+    let mut activity = PendingActivity::default();
+    let mut tx = state.pool.begin_with("BEGIN IMMEDIATE").await?;
+    apply_bulk_operations_on(&mut tx, &mut activity, board_id, user_id, operations).await?;
+    tx.commit().await?;
+    activity.flush(state).await;
+    Ok(())
+}
+
+/// Like [`apply_bulk_operations`], on the caller's transaction, holding the activity
+/// back in `activity`
+pub(crate) async fn apply_bulk_operations_on(
+    conn: &mut SqliteConnection,
+    activity: &mut PendingActivity,
+    board_id: Uuid,
+    user_id: Uuid,
+    operations: &[BulkCardOperation],
+) -> Result<()> {
+    if operations.is_empty() {
+        return Err(AppError::Validation("No operations given".to_string()));
+    }
+    if operations.len() > MAX_BULK_OPERATIONS {
+        return Err(AppError::Validation(format!(
+            "Cannot apply more than {} operations at once",
+            MAX_BULK_OPERATIONS
+        )));
+    }
+
+    let columns = ColumnRepository::list_by_board_on(conn, board_id).await?;
+    let column_ids: Vec<Uuid> = columns.iter().map(|c| c.id).collect();
+    let mut cards: HashMap<Uuid, Card> = HashMap::new();
+    let mut tags: HashMap<Uuid, Tag> = HashMap::new();
+
+    for operation in operations {
+        let card_id = operation.card_id();
+        let card = match cards.entry(card_id) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(
+                CardRepository::find_on(conn, card_id)
+                    .await?
+                    .filter(|c| c.column_id.is_some_and(|id| column_ids.contains(&id)))
+                    .ok_or_else(|| {
+                        AppError::BadRequest(format!("Card {} is not on this board", card_id))
+                    })?,
+            ),
+        };
+        // Locked cards can still be moved between columns
+        if card.is_locked() && !matches!(operation, BulkCardOperation::Move { .. }) {
+            return Err(AppError::Locked);
+        }
+
+        match operation {
+            BulkCardOperation::Move { column_id, .. } if !column_ids.contains(column_id) => {
+                return Err(AppError::BadRequest(format!(
+                    "Column {} is not on this board",
+                    column_id
+                )));
+            }
+            BulkCardOperation::Tag { tag_id, .. } if !tags.contains_key(tag_id) => {
+                let tag = TagRepository::find_on(conn, *tag_id)
+                    .await?
+                    .filter(|t| t.board_id == Some(board_id))
+                    .ok_or_else(|| {
+                        AppError::BadRequest(format!("Tag {} is not on this board", tag_id))
+                    })?;
+                tags.insert(*tag_id, tag);
+            }
+            _ => {}
+        }
+    }
+
+    CardRepository::bulk_apply_on(conn, operations, user_id).await?;
+
+    // Columns cards were moved into must still fit their WIP limit; going over it on a
+    // board that blocks fails every operation
+    for column in &columns {
+        let receives_cards = operations.iter().any(|operation| {
+            matches!(operation, BulkCardOperation::Move { card_id, column_id, .. }
+                if *column_id == column.id && cards[card_id].column_id != Some(column.id))
+        });
+        if receives_cards {
+            check_wip_limit_on(conn, column, 0).await?;
+        }
+    }
+
+    for operation in operations {
+        let card = &cards[&operation.card_id()];
+        match operation {
+            BulkCardOperation::Move {
+                column_id,
+                position,
+                ..
+            } => {
+                activity.record(
+                    BoardEvent::new(BoardEventKind::CardMoved, board_id, card.id, user_id)
+                        .with_details(json!({
+                            "from_column_id": card.column_id,
+                            "to_column_id": column_id,
+                            "position": position,
+                        })),
+                );
+            }
+            BulkCardOperation::Tag { tag_id, .. } => {
+                activity.record(
+                    BoardEvent::new(BoardEventKind::CardTagged, board_id, *tag_id, user_id)
+                        .with_card(card.id)
+                        .with_details(json!({"tag": tags[tag_id].name})),
+                );
+            }
+            BulkCardOperation::Status { status, .. } => {
+                activity.record(
+                    BoardEvent::new(BoardEventKind::CardUpdated, board_id, card.id, user_id)
+                        .with_details(
+                            json!({"status": {"from": card.status, "to": status.to_string()}}),
+                        ),
+                );
+            }
+            BulkCardOperation::Delete { .. } => {
+                activity.record(
+                    BoardEvent::new(BoardEventKind::CardDeleted, board_id, card.id, user_id)
+                        .with_details(json!({"title": card.title})),
+                );
+            }
+            BulkCardOperation::Archive { .. } => {
+                activity.record(
+                    BoardEvent::new(BoardEventKind::CardUpdated, board_id, card.id, user_id)
+                        .with_details(json!({"archived": true})),
+                );
+            }
+        }
+    }
+
+    // Moved cards took the status of the column they ended up in, unless the request
+    // set their status itself; record the statuses that changed that way
+    let mut moved = HashSet::new();
+    let mut status_set = HashSet::new();
+    for operation in operations {
+        match *operation {
+            BulkCardOperation::Move { card_id, .. } => {
+                moved.insert(card_id);
+            }
+            BulkCardOperation::Status { card_id, .. } => {
+                status_set.insert(card_id);
+            }
+            _ => {}
+        }
+    }
+    for card_id in moved.difference(&status_set) {
+        let before = &cards[card_id];
+        let Some(card) = CardRepository::find_on(conn, *card_id).await? else {
+            continue;
+        };
+        if card.status != before.status {
+            activity.record(
+                BoardEvent::new(BoardEventKind::CardUpdated, board_id, card.id, user_id)
+                    .with_details(json!({"status": {"from": before.status, "to": card.status}})),
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Only the card's owner or an owner of a board it is on may lock or unlock it
+async fn can_lock_card(state: &AppState, card: &Card, user_id: Uuid) -> Result<bool> {
+    if card.owner_id == Some(user_id) || card.created_by == user_id {
+        return Ok(true);
+    }
+
+    let mut board_ids: Vec<Uuid> = state
+        .card_boards
+        .list_boards_for_card(card.id)
+        .await?
+        .into_iter()
+        .map(|b| b.id)
+        .collect();
+    if let Ok(board_id) = state.cards.get_board_id_for_card(card.id).await {
+        board_ids.push(board_id);
+    }
+
+    for board_id in board_ids {
+        if state.boards.get_user_role(board_id, user_id).await? == Some(BoardRole::Owner) {
+            return Ok(true);
+        }
+    }
+
+    Ok(false)
+}
+
+/// Lock a card so edits, tags and comments are rejected until it is unlocked
+pub async fn lock_card(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(card_id): Path<Uuid>,
+) -> Result<Json<CardResponse>> {
+    set_card_lock(state, auth, card_id, true).await
+}
+
+/// Unlock a previously locked card
+pub async fn unlock_card(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(card_id): Path<Uuid>,
+) -> Result<Json<CardResponse>> {
+    set_card_lock(state, auth, card_id, false).await
+}
+
+async fn set_card_lock(
+    state: AppState,
+    auth: AuthUser,
+    card_id: Uuid,
+    locked: bool,
+) -> Result<Json<CardResponse>> {
+    let card = state.cards.get_by_id(card_id).await?;
+
+    if !can_lock_card(&state, &card, auth.user.id).await? {
+        return Err(AppError::Forbidden);
+    }
+
+    let card = if card.is_locked() == locked {
+        card
+    } else {
+        let card = state
+            .cards
+            .set_locked(card_id, locked.then_some(auth.user.id))
+            .await?;
+        tracing::info!(
+            card_id = %card_id,
+            user_id = %auth.user.id,
+            "Card {}",
+            if locked { "locked" } else { "unlocked" }
+        );
+        record_for_card(
+            &state,
+            BoardEventKind::CardUpdated,
+            card_id,
+            card_id,
+            auth.user.id,
+            Some(json!({"locked": locked})),
+        )
+        .await;
+        card
+    };
+
+    card_response(&state, card).await
+}
+
+/// Archive a card: it leaves board views but keeps its history
+pub async fn archive_card(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(card_id): Path<Uuid>,
+) -> Result<Json<CardResponse>> {
+    let card = set_card_archived(&state, &auth, card_id, true).await?;
+    card_response(&state, card).await
+}
+
+/// Put an archived card back at the end of its column
+pub async fn unarchive_card(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(card_id): Path<Uuid>,
+) -> Result<Json<CardResponse>> {
+    let card = set_card_archived(&state, &auth, card_id, false).await?;
+    card_response(&state, card).await
+}
+
+/// Give a card a cover, a color or one of its image attachments
+pub async fn set_card_cover(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(card_id): Path<Uuid>,
+    Json(input): Json<CardCover>,
+) -> Result<Json<CardResponse>> {
+    let card = apply_card_cover(&state, &auth, card_id, &input).await?;
+    card_response(&state, card).await
+}
+
+pub async fn remove_card_cover(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(card_id): Path<Uuid>,
+) -> Result<Json<CardResponse>> {
+    let no_cover = CardCover {
+        color: None,
+        attachment_id: None,
+    };
+    let card = apply_card_cover(&state, &auth, card_id, &no_cover).await?;
+    card_response(&state, card).await
+}
+
+/// Set the cover of a card, removing it when neither a color nor an attachment is
+/// given, and record the change
+pub(crate) async fn apply_card_cover(
+    state: &AppState,
+    auth: &AuthUser,
+    card_id: Uuid,
+    cover: &CardCover,
+) -> Result<Card> {
+    if !can_edit_card(state, card_id, auth.user.id).await? {
+        return Err(AppError::Forbidden);
+    }
+
+    state.cards.ensure_unlocked(card_id).await?;
+
+    let color = hex_color(cover.color.as_deref())?;
+    if let Some(attachment_id) = cover.attachment_id {
+        if color.is_some() {
+            return Err(AppError::invalid_field(
+                "attachment_id",
+                "A cover is either a color or an image, not both",
+            ));
+        }
+        let attachment = state.attachments.find_by_id(attachment_id).await?;
+        if !attachment.is_some_and(|a| a.card_id == card_id && a.content_type.starts_with("image/"))
+        {
+            return Err(AppError::invalid_field(
+                "attachment_id",
+                "Cover must be an image attached to the card",
+            ));
+        }
     }
 
     let card = state
         .cards
-        .move_card(card_id, input.column_id, input.position)
+        .set_cover(card_id, color.as_deref(), cover.attachment_id)
         .await?;
+    record_for_card(
+        state,
+        BoardEventKind::CardUpdated,
+        card_id,
+        card_id,
+        auth.user.id,
+        Some(json!({"cover": {
+            "color": card.cover_color,
+            "attachment_id": card.cover_attachment_id,
+        }})),
+    )
+    .await;
+    Ok(card)
+}
+
+/// A card as returned by the API, with its tags and whether it is blocked
+pub(crate) async fn card_response(state: &AppState, card: Card) -> Result<Json<CardResponse>> {
+    let (tags, blockers) = tokio::try_join!(
+        state.tags.list_for_card(card.id),
+        state.links.list_open_blockers(card.id),
+    )?;
+    let mut response = card.into_response(tags.into_iter().map(|t| t.into()).collect());
+    response.is_blocked = !blockers.is_empty();
+    Ok(Json(response))
+}
+
+pub(crate) async fn set_card_archived(
+    state: &AppState,
+    auth: &AuthUser,
+    card_id: Uuid,
+    archived: bool,
+) -> Result<Card> {
+    if !can_edit_card(state, card_id, auth.user.id).await? {
+        return Err(AppError::Forbidden);
+    }
+
+    state.cards.ensure_unlocked(card_id).await?;
+    let card = state.cards.get_by_id(card_id).await?;
+
+    if card.archived_at.is_some() == archived {
+        return Ok(card);
+    }
 
-    let tags = state.tags.list_for_card(card.id).await?;
-    Ok(Json(card.into_response(
-        tags.into_iter().map(|t| t.into()).collect(),
-    )))
+    let card = state.cards.set_archived(card_id, archived).await?;
+    record_for_card(
+        state,
+        BoardEventKind::CardUpdated,
+        card_id,
+        card_id,
+        auth.user.id,
+        Some(json!({"archived": archived})),
+    )
+    .await;
+    Ok(card)
 }
@@ -1,23 +1,163 @@
 use axum::{
-    extract::{Path, State},
+    extract::{OriginalUri, Path, Query, State},
+    http::header,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
     Json,
 };
+use chrono::{NaiveDate, Utc};
+use sqlx::{Sqlite, SqliteConnection, Transaction};
+use std::collections::{hash_map::Entry, HashMap};
+use std::convert::Infallible;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tokio_stream::{wrappers::ReceiverStream, Stream, StreamExt};
 use tracing::{debug, info, instrument, warn};
 use uuid::Uuid;
 
 use crate::auth::AuthUser;
 use crate::error::{AppError, Result};
+use crate::handlers::activity::PendingActivity;
+use crate::handlers::cards::apply_bulk_operations_on;
+use crate::handlers::columns::{check_wip_limit_on, sync_card_status_on};
+use crate::handlers::comments::{can_edit_card_on, can_view_card_on};
+use crate::handlers::trash::{require_editor, restore_on};
 use crate::models::{
-    ActionTaken, CardStatus, CardVisibility, ChatAction, ChatMessageResponse, ChatResponse,
-    LlmAction, SendChatRequest,
+    ActionTaken, BoardEvent, BoardEventKind, BoardRole, BulkCardOperation, Card, CardPriority,
+    CardStatus, CardVisibility, ChatAction, ChatComment, ChatExport, ChatExportFormat,
+    ChatExportQuery, ChatExportScope, ChatMessage, ChatMessageResponse, ChatResponse, ChatSource,
+    ChatStatsQuery, ChatStatsResponse, Column, ColumnCreationPolicy, ConfirmChatRequest, LlmAction,
+    PageQuery, PendingActionsResponse, SendChatRequest, TrashKind, UndoStep, UndoableAction, User,
+    BOARD_CHAT_ACTIONS, BOARD_PARAM, GLOBAL_CHAT_ACTIONS,
 };
+use crate::repo::assignee::AssigneeRepository;
+use crate::repo::attachment::AttachmentRepository;
+use crate::repo::board::BoardRepository;
+use crate::repo::card::CardRepository;
+use crate::repo::card_board::CardBoardRepository;
+use crate::repo::chat_action::ChatActionRepository;
+use crate::repo::column::ColumnRepository;
+use crate::repo::comment::CommentRepository;
+use crate::repo::revision::CardRevisionRepository;
+use crate::repo::tag::TagRepository;
+use crate::repo::user::UserRepository;
+use crate::services::activity::changes;
+use crate::services::assignments;
+use crate::services::board_ref::{self, BoardCandidate, BoardMatch};
+use crate::services::board_summaries::BoardSummary;
+use crate::services::chat_stats;
+use crate::services::fuzzy::closest_match;
+use crate::services::pagination::Paginated;
+use crate::services::{LlmMessage, LlmOptions, LlmProvider, LlmReply, LlmTool};
 use crate::state::AppState;
 
-/// Build the system prompt with board context and user context
+/// Pending stream events before the LLM task waits for the client to catch up
+const CHAT_STREAM_BUFFER: usize = 64;
+
+/// How long actions proposed for confirmation can be confirmed
+const PENDING_ACTIONS_TTL_MINUTES: i64 = 30;
+
+/// Chat messages per history page when no limit is given
+const DEFAULT_HISTORY_PAGE_SIZE: i64 = 50;
+
+/// Record that chat actions changed a board, with a description of each action
+fn record_chat_actions(
+    activity: &mut PendingActivity,
+    board_id: Uuid,
+    user_id: Uuid,
+    actions: &[&str],
+) {
+    activity.record(
+        BoardEvent::new(BoardEventKind::ChatActions, board_id, board_id, user_id)
+            .with_details(serde_json::json!({ "actions": actions })),
+    );
+}
+
+/// The transaction the actions of one chat request run in, so that they are applied
+/// all together or not at all, and the activity they record once it is committed
+struct ActionTx {
+    conn: Transaction<'static, Sqlite>,
+    activity: PendingActivity,
+}
+
+impl ActionTx {
+    async fn begin(state: &AppState) -> Result<Self> {
+        Ok(Self {
+            conn: state.pool.begin_with("BEGIN IMMEDIATE").await?,
+            activity: PendingActivity::default(),
+        })
+    }
+
+    /// Keep the changes of the actions, then record their activity
+    async fn commit(self, state: &AppState) -> Result<()> {
+        self.conn.commit().await?;
+        self.activity.flush(state).await;
+        Ok(())
+    }
+
+    /// Undo the changes of the actions, recording nothing
+    async fn rollback(self) -> Result<()> {
+        self.conn.rollback().await?;
+        Ok(())
+    }
+}
+
+/// Columns, card counts and tags of the boards for the global prompt, from the cache
+/// where possible and otherwise with one query for all the columns and one for all
+/// the tags
+async fn summarize_boards(
+    state: &AppState,
+    board_ids: &[Uuid],
+) -> Result<HashMap<Uuid, BoardSummary>> {
+    let (mut summaries, generation) = state.board_summaries.get(board_ids);
+    let missing: Vec<Uuid> = board_ids
+        .iter()
+        .copied()
+        .filter(|id| !summaries.contains_key(id))
+        .collect();
+    if missing.is_empty() {
+        return Ok(summaries);
+    }
+
+    let (columns, tags) = tokio::try_join!(
+        state.columns.card_counts_for_boards(&missing),
+        state.tags.list_by_boards(&missing),
+    )?;
+    let mut fresh: HashMap<Uuid, BoardSummary> = missing
+        .iter()
+        .map(|id| (*id, BoardSummary::default()))
+        .collect();
+    for column in columns {
+        if let Some(summary) = fresh.get_mut(&column.board_id) {
+            summary.columns.push(column.name);
+            summary.card_count += column.card_count;
+        }
+    }
+    for tag in tags {
+        if let Some(summary) = tag.board_id.and_then(|id| fresh.get_mut(&id)) {
+            summary.tags.push(tag.name);
+        }
+    }
+
+    state.board_summaries.store(generation, &fresh);
+    summaries.extend(fresh);
+    Ok(summaries)
+}
+
+/// Today's date for the prompts, so the LLM can resolve dates such as "Friday"
+fn today() -> String {
+    Utc::now().date_naive().format("%Y-%m-%d (%A)").to_string()
+}
+
+/// Build the system prompt with board context and user context. With `tools`, actions
+/// are offered as tools instead of being described as JSON replies.
 async fn build_system_prompt(
     state: &AppState,
     board_id: Uuid,
     user_context: Option<&str>,
+    tools: bool,
 ) -> Result<String> {
     let board = state.boards.get_by_id(board_id).await?;
     let columns = state.columns.list_by_board(board_id).await?;
@@ -33,34 +173,58 @@ async fn build_system_prompt(
     }
 
     let examples = r##"
-1. create_card - Create a new card
-   {"action": "create_card", "params": {"column": "column_name", "title": "card title", "body": "optional description"}, "message": "Created card..."}
+1. create_column - Create a new column, at the end unless placed after or before another
+   {"action": "create_column", "params": {"name": "column name", "after": "optional existing column", "before": "optional existing column"}, "message": "Created column..."}
+
+2. create_card - Create a new card
+   {"action": "create_card", "params": {"column": "column_name", "title": "card title", "body": "optional description", "priority": "optional low|medium|high|urgent"}, "message": "Created card..."}
 
-2. move_card - Move a card to another column
+3. move_card - Move a card to another column
    {"action": "move_card", "params": {"card_title": "card to move", "target_column": "destination column"}, "message": "Moved card..."}
 
-3. create_tag - Create a new tag
+4. update_card - Change a card's title, description, status, dates or priority (only the given fields change, empty clears a field)
+   {"action": "update_card", "params": {"card": "card title", "title": "optional new title", "body": "optional description", "status": "open|in_progress|done|closed", "due_date": "YYYY-MM-DD", "start_date": "YYYY-MM-DD", "end_date": "YYYY-MM-DD", "priority": "low|medium|high|urgent"}, "message": "Updated card..."}
+
+5. update_status - Update a card's status
+   {"action": "update_status", "params": {"card": "card title", "status": "open|in_progress|done|closed"}, "message": "Updated status..."}
+
+6. add_comment - Add a comment to a card
+   {"action": "add_comment", "params": {"card": "card title", "comment": "comment text"}, "message": "Added comment..."}
+
+7. create_tag - Create a new tag
    {"action": "create_tag", "params": {"name": "tag name", "color": "#hex_color"}, "message": "Created tag..."}
 
-4. add_tag - Add a tag to a card
+8. add_tag - Add a tag to a card
    {"action": "add_tag", "params": {"card_title": "card title", "tag_name": "tag to add"}, "message": "Added tag..."}
 
-5. list_cards - List cards (optionally filtered by column)
+9. list_cards - List cards (optionally filtered by column)
    {"action": "list_cards", "params": {"column": "optional column name"}, "message": "Here are the cards..."}
 
-6. list_tags - List all tags on the board
+10. list_tags - List all tags on the board
    {"action": "list_tags", "params": {}, "message": "Here are the tags..."}
 
-7. delete_column - Delete a column (and all its cards)
+11. delete_column - Delete a column (and all its cards)
    {"action": "delete_column", "params": {"column": "column name"}, "message": "Deleted column..."}
 
-8. delete_tag - Delete a tag from the board
+12. delete_tag - Delete a tag from the board
    {"action": "delete_tag", "params": {"tag": "tag name"}, "message": "Deleted tag..."}
 
-9. delete_card - Delete a card
+13. delete_card - Delete a card
    {"action": "delete_card", "params": {"card": "card title"}, "message": "Deleted card..."}
 
-10. no_action - Just respond without taking action
+14. bulk_update_cards - Move, tag, set the status of, archive or delete all cards with a tag and/or in a column at once
+   {"action": "bulk_update_cards", "params": {"operation": "move|tag|status|archive|delete", "tag": "optional tag filter", "column": "optional column filter", "target_column": "for move", "add_tag": "for tag", "status": "for status"}, "message": "Moved cards..."}
+
+15. suggest_assignments - Suggest who to assign the unassigned open cards to, from each member's workload; the user can accept the plan
+   {"action": "suggest_assignments", "params": {}, "message": "Here is a suggested plan..."}
+
+16. web_search - Search the internet for information (use when you need current data or external knowledge)
+   {"action": "web_search", "params": {"query": "search query here"}, "message": "Let me search for that..."}
+
+17. undo - Undo the last change you made in this chat
+   {"action": "undo", "params": {}, "message": "Undid..."}
+
+18. no_action - Just respond without taking action
    {"action": "no_action", "params": {}, "message": "Your response here..."}
 "##;
 
@@ -75,34 +239,54 @@ async fn build_system_prompt(
         _ => String::new(),
     };
 
+    let (instructions, closing) = if tools {
+        (
+            "You can change the board by calling the provided tools, several at once if needed.\n"
+                .to_string(),
+            "Reply in plain text when the user is just asking a question or chatting.",
+        )
+    } else {
+        (
+            format!(
+                "You can execute these actions by responding with JSON:\n{}",
+                examples
+            ),
+            r#"IMPORTANT: Always respond with valid JSON in the format shown above. Use "no_action" if the user is just asking a question or chatting."#,
+        )
+    };
+
     Ok(format!(
         r#"You are a Kanban board assistant for the board "{board_name}".
 {user_context}
-You can execute these actions by responding with JSON:
-{examples}
+{instructions}
 Current board state:
 - Board: {board_name}
 - Columns: {columns}
 - Tags: {tags}
+- Today: {today}
 
-IMPORTANT: Always respond with valid JSON in the format shown above. Use "no_action" if the user is just asking a question or chatting.
+{closing}
 "#,
         board_name = board.name,
         user_context = user_context_section,
-        examples = examples,
+        instructions = instructions,
         columns = column_info.join(", "),
-        tags = tags_str
+        tags = tags_str,
+        today = today(),
+        closing = closing
     ))
 }
 
-/// Build the global system prompt with all accessible boards
+/// Build the global system prompt with all accessible boards. With `tools`, actions are
+/// offered as tools instead of being described as JSON replies.
 async fn build_global_system_prompt(
     state: &AppState,
     user_id: Uuid,
     user_context: Option<&str>,
+    tools: bool,
 ) -> Result<String> {
     // Fetch all boards the user has access to
-    let boards = state.boards.list_for_user(user_id).await?;
+    let boards = board_candidates(state, user_id).await?;
 
     if boards.is_empty() {
         let user_context_section = match user_context {
@@ -110,45 +294,63 @@ async fn build_global_system_prompt(
             _ => String::new(),
         };
 
+        let reply_format = if tools {
+            "Reply in plain text, or call create_board if the user asks for a board."
+        } else {
+            r#"Respond with JSON:
+{"action": "no_action", "params": {}, "message": "Your response here..."}"#
+        };
+
         return Ok(format!(
             r#"You are a Kanban board assistant.
 {user_context}
 You don't have access to any boards yet. Suggest the user create a board first.
 
-Respond with JSON:
-{{"action": "no_action", "params": {{}}, "message": "Your response here..."}}
+{reply_format}
 "#,
             user_context = user_context_section,
+            reply_format = reply_format,
         ));
     }
 
     // Build board summaries
+    let board_ids: Vec<Uuid> = boards.iter().map(|c| c.board.id).collect();
+    let summaries = summarize_boards(state, &board_ids).await?;
     let mut board_summaries = Vec::new();
-    for (board, role) in &boards {
-        let columns = state.columns.list_by_board(board.id).await?;
-        let tags = state.tags.list_by_board(board.id).await?;
-
-        let mut card_count = 0;
-        let mut column_names = Vec::new();
-        for col in &columns {
-            let cards = state.cards.list_by_column(col.id).await?;
-            card_count += cards.len();
-            column_names.push(col.name.as_str());
-        }
-
-        let tag_names: Vec<&str> = tags.iter().map(|t| t.name.as_str()).collect();
+    for BoardCandidate {
+        board,
+        role,
+        owner_name,
+        ..
+    } in &boards
+    {
+        // Boards sharing a name must be referenced as "Owner/Board" or by id
+        let shared_name = boards
+            .iter()
+            .filter(|c| c.board.name.eq_ignore_ascii_case(&board.name))
+            .count()
+            > 1;
+        let name = if shared_name {
+            format!(
+                "{} [shared name, refer to it as \"{}/{}\" or by id {}]",
+                board.name, owner_name, board.name, board.id
+            )
+        } else {
+            board.name.clone()
+        };
 
+        let summary = summaries.get(&board.id).cloned().unwrap_or_default();
         board_summaries.push(format!(
             "- {} (role: {}, {} columns: [{}], {} cards, tags: [{}])",
-            board.name,
+            name,
             role,
-            columns.len(),
-            column_names.join(", "),
-            card_count,
-            if tag_names.is_empty() {
+            summary.columns.len(),
+            summary.columns.join(", "),
+            summary.card_count,
+            if summary.tags.is_empty() {
                 "none".to_string()
             } else {
-                tag_names.join(", ")
+                summary.tags.join(", ")
             }
         ));
     }
@@ -160,14 +362,14 @@ Respond with JSON:
 2. delete_board - Delete a board (only owner can delete, requires exact board name)
    {"action": "delete_board", "params": {"board": "exact board name"}, "message": "Deleted board..."}
 
-3. create_column - Create a new column (specify board)
-   {"action": "create_column", "params": {"board": "board name", "name": "column name"}, "message": "Created column..."}
+3. create_column - Create a new column (specify board), at the end unless placed after or before another
+   {"action": "create_column", "params": {"board": "board name", "name": "column name", "after": "optional existing column", "before": "optional existing column"}, "message": "Created column..."}
 
 4. create_card - Create a new card (specify board and column)
-   {"action": "create_card", "params": {"board": "board name", "column": "column_name", "title": "card title", "body": "optional description"}, "message": "Created card..."}
+   {"action": "create_card", "params": {"board": "board name", "column": "column_name", "title": "card title", "body": "optional description", "priority": "optional low|medium|high|urgent"}, "message": "Created card..."}
 
 5. create_inbox_card - Create a standalone card in inbox (not on any board)
-   {"action": "create_inbox_card", "params": {"title": "card title", "body": "optional description", "status": "open|in_progress|done|closed"}, "message": "Created inbox card..."}
+   {"action": "create_inbox_card", "params": {"title": "card title", "body": "optional description", "status": "open|in_progress|done|closed", "due_date": "optional YYYY-MM-DD", "priority": "optional low|medium|high|urgent"}, "message": "Created inbox card..."}
 
 6. move_card - Move a card to another column (within same board)
    {"action": "move_card", "params": {"board": "board name", "card_title": "card to move", "target_column": "destination column"}, "message": "Moved card..."}
@@ -178,37 +380,46 @@ Respond with JSON:
 8. assign_card - Assign a card to a board (cards can be on multiple boards)
    {"action": "assign_card", "params": {"card": "card title", "board": "board name", "column": "optional column"}, "message": "Assigned card to board..."}
 
-9. update_status - Update a card's status
-   {"action": "update_status", "params": {"card": "card title", "status": "open|in_progress|done|closed"}, "message": "Updated status..."}
+9. update_status - Update a card's status (give the board for cards on a board, omit it for your own cards)
+   {"action": "update_status", "params": {"board": "optional board name", "card": "card title", "status": "open|in_progress|done|closed"}, "message": "Updated status..."}
 
-10. add_comment - Add a comment to a card
-   {"action": "add_comment", "params": {"card": "card title", "comment": "comment text"}, "message": "Added comment..."}
+10. update_card - Change a card's title, description, status, dates or priority (only the given fields change, empty clears a field)
+   {"action": "update_card", "params": {"board": "optional board name", "card": "card title", "title": "optional new title", "body": "optional description", "status": "open|in_progress|done|closed", "due_date": "YYYY-MM-DD", "start_date": "YYYY-MM-DD", "end_date": "YYYY-MM-DD", "priority": "low|medium|high|urgent"}, "message": "Updated card..."}
 
-11. create_tag - Create a new tag (specify board)
+11. add_comment - Add a comment to a card (give the board for cards on a board, omit it for your own cards)
+   {"action": "add_comment", "params": {"board": "optional board name", "card": "card title", "comment": "comment text"}, "message": "Added comment..."}
+
+12. create_tag - Create a new tag (specify board)
    {"action": "create_tag", "params": {"board": "board name", "name": "tag name", "color": "#hex_color"}, "message": "Created tag..."}
 
-12. add_tag - Add a tag to a card (specify board)
+13. add_tag - Add a tag to a card (specify board)
    {"action": "add_tag", "params": {"board": "board name", "card_title": "card title", "tag_name": "tag to add"}, "message": "Added tag..."}
 
-13. list_cards - List cards from a board
+14. list_cards - List cards from a board
    {"action": "list_cards", "params": {"board": "board name", "column": "optional column name"}, "message": "Here are the cards..."}
 
-14. list_tags - List all tags on a board
+15. list_tags - List all tags on a board
    {"action": "list_tags", "params": {"board": "board name"}, "message": "Here are the tags..."}
 
-15. delete_column - Delete a column (specify board)
+16. delete_column - Delete a column (specify board)
    {"action": "delete_column", "params": {"board": "board name", "column": "column name"}, "message": "Deleted column..."}
 
-16. delete_tag - Delete a tag (specify board)
+17. delete_tag - Delete a tag (specify board)
    {"action": "delete_tag", "params": {"board": "board name", "tag": "tag name"}, "message": "Deleted tag..."}
 
-17. delete_card - Delete a card (specify board)
+18. delete_card - Delete a card (specify board)
    {"action": "delete_card", "params": {"board": "board name", "card": "card title"}, "message": "Deleted card..."}
 
-18. web_search - Search the internet for information (use when you need current data or external knowledge)
+19. bulk_update_cards - Move, tag, set the status of, archive or delete all cards with a tag and/or in a column (specify board)
+   {"action": "bulk_update_cards", "params": {"board": "board name", "operation": "move|tag|status|archive|delete", "tag": "optional tag filter", "column": "optional column filter", "target_column": "for move", "add_tag": "for tag", "status": "for status"}, "message": "Moved cards..."}
+
+20. web_search - Search the internet for information (use when you need current data or external knowledge)
    {"action": "web_search", "params": {"query": "search query here"}, "message": "Let me search for that..."}
 
-19. no_action - Just respond without taking action
+21. undo - Undo the last change you made in this chat
+   {"action": "undo", "params": {}, "message": "Undid..."}
+
+22. no_action - Just respond without taking action
    {"action": "no_action", "params": {}, "message": "Your response here..."}
 "##;
 
@@ -217,24 +428,129 @@ Respond with JSON:
         _ => String::new(),
     };
 
+    let (instructions, closing) = if tools {
+        (
+            "Call the provided tools to act, several at once if needed.\n".to_string(),
+            r#"Always include the "board" argument for board-specific actions. Reply in plain text for questions."#,
+        )
+    } else {
+        (
+            format!("Available actions:\n{}", examples),
+            r#"IMPORTANT: Always respond with valid JSON. Always include "board" param for board-specific actions. Use "no_action" for questions."#,
+        )
+    };
+
     Ok(format!(
         r#"You are a Kanban board assistant with access to multiple boards.
 {user_context}
 You can execute actions across any of the user's boards. Always specify the "board" parameter.
 
-Available actions:
-{examples}
+{instructions}
+Today: {today}
+
 Your boards:
 {boards}
 
-IMPORTANT: Always respond with valid JSON. Always include "board" param for board-specific actions. Use "no_action" for questions.
+{closing}
 "#,
         user_context = user_context_section,
-        examples = examples,
-        boards = board_summaries.join("\n")
+        instructions = instructions,
+        boards = board_summaries.join("\n"),
+        today = today(),
+        closing = closing
     ))
 }
 
+/// Whether chat actions are offered to the LLM as tools, from LLM_TOOLS. Set it to
+/// false for models without tool support, which then describe actions as JSON in text.
+pub fn native_tools() -> bool {
+    std::env::var("LLM_TOOLS")
+        .map(|v| {
+            !matches!(
+                v.trim().to_lowercase().as_str(),
+                "false" | "0" | "no" | "off"
+            )
+        })
+        .unwrap_or(true)
+}
+
+/// Tools for `actions`, or none when [`native_tools`] is off. Without `board_param`, the
+/// actions apply to the board being chatted on.
+fn action_tools(actions: &[ChatAction], board_param: bool) -> Vec<LlmTool> {
+    if !native_tools() {
+        return Vec::new();
+    }
+
+    actions
+        .iter()
+        .map(|action| {
+            let params: Vec<_> = action
+                .params()
+                .iter()
+                .filter(|p| board_param || p.name != BOARD_PARAM.name)
+                .collect();
+            let mut properties: serde_json::Map<String, serde_json::Value> = params
+                .iter()
+                .map(|p| {
+                    (
+                        p.name.to_string(),
+                        serde_json::json!({ "type": "string", "description": p.description }),
+                    )
+                })
+                .collect();
+            properties.insert(
+                "message".to_string(),
+                serde_json::json!({
+                    "type": "string",
+                    "description": "Short message telling the user what you did",
+                }),
+            );
+            let mut required: Vec<&str> = params
+                .iter()
+                .filter(|p| p.required)
+                .map(|p| p.name)
+                .collect();
+            required.push("message");
+
+            LlmTool {
+                name: action.to_string(),
+                description: action.description().to_string(),
+                parameters: serde_json::json!({
+                    "type": "object",
+                    "properties": properties,
+                    "required": required,
+                }),
+            }
+        })
+        .collect()
+}
+
+/// Actions requested by an LLM reply: its tool calls, or the JSON actions written in its
+/// text when it called no tool
+fn reply_actions(reply: &LlmReply) -> Vec<LlmAction> {
+    if reply.tool_calls.is_empty() {
+        return parse_llm_response(&reply.content);
+    }
+
+    reply
+        .tool_calls
+        .iter()
+        .map(|call| {
+            let mut params = call.arguments.clone();
+            let message = params
+                .as_object_mut()
+                .and_then(|p| p.remove("message"))
+                .and_then(|m| m.as_str().map(str::to_string))
+                .unwrap_or_default();
+            LlmAction {
+                action: call.name.clone(),
+                params,
+                message,
+            }
+        })
+        .collect()
+}
+
 /// Parse the LLM response to extract actions (handles multiple JSON objects)
 fn parse_llm_response(response: &str) -> Vec<LlmAction> {
     let response = response.trim();
@@ -321,6 +637,7 @@ fn extract_readable_message(response: &str, actions: &[LlmAction]) -> String {
 /// Execute an action based on LLM response
 async fn execute_action(
     state: &AppState,
+    tx: &mut ActionTx,
     board_id: Uuid,
     user_id: Uuid,
     action: &LlmAction,
@@ -345,6 +662,18 @@ async fn execute_action(
                 .or_else(|| action.params["description"].as_str())
                 .or_else(|| action.params["content"].as_str());
 
+            let priority = match priority_param(action) {
+                Ok(priority) => priority.flatten(),
+                Err(description) => {
+                    return Ok(ActionTaken {
+                        action: "create_card".to_string(),
+                        description,
+                        success: false,
+                        ..Default::default()
+                    });
+                }
+            };
+
             if column_name.is_empty() || title.is_empty() {
                 return Ok(ActionTaken {
                     action: "create_card".to_string(),
@@ -353,43 +682,66 @@ async fn execute_action(
                         action.params
                     ),
                     success: false,
+                    ..Default::default()
                 });
             }
 
             // Find column by name
-            let columns = state.columns.list_by_board(board_id).await?;
+            let columns = ColumnRepository::list_by_board_on(&mut tx.conn, board_id).await?;
             let column = columns
                 .iter()
                 .find(|c| c.name.to_lowercase() == column_name.to_lowercase());
 
             if let Some(col) = column {
-                state
-                    .cards
-                    .create(
-                        col.id,
-                        title,
-                        body,
-                        None,
-                        CardVisibility::Restricted,
-                        CardStatus::Open,
-                        None,
-                        None,
-                        None,
-                        user_id,
-                    )
-                    .await?;
+                let wip_warning = match wip_limit_param(&mut tx.conn, col).await? {
+                    Ok(warning) => warning,
+                    Err(description) => {
+                        return Ok(ActionTaken {
+                            action: "create_card".to_string(),
+                            description,
+                            success: false,
+                            ..Default::default()
+                        });
+                    }
+                };
+                let card = CardRepository::create_on(
+                    &mut tx.conn,
+                    col.id,
+                    title,
+                    body,
+                    None,
+                    CardVisibility::Restricted,
+                    CardStatus::Open,
+                    None,
+                    None,
+                    None,
+                    priority,
+                    user_id,
+                )
+                .await?;
 
                 Ok(ActionTaken {
                     action: "create_card".to_string(),
-                    description: format!("Created card '{}' in column '{}'", title, col.name),
+                    description: with_wip_warning(
+                        format!("Created card '{}' in column '{}'", title, col.name),
+                        wip_warning,
+                    ),
                     success: true,
+                    undo: Some(UndoStep::TrashCard { card_id: card.id }),
+                    ..Default::default()
                 })
             } else {
-                Ok(ActionTaken {
-                    action: "create_card".to_string(),
-                    description: format!("Column '{}' not found", column_name),
-                    success: false,
-                })
+                execute_create_card_missing_column(
+                    &mut tx.conn,
+                    board_id,
+                    user_id,
+                    column_name,
+                    title,
+                    body,
+                    priority,
+                    &columns,
+                )
+                .await
             }
         }
 
@@ -409,11 +761,12 @@ async fn execute_action(
                         action.params
                     ),
                     success: false,
+                    ..Default::default()
                 });
             }
 
             // Check if column already exists
-            let columns = state.columns.list_by_board(board_id).await?;
+            let columns = ColumnRepository::list_by_board_on(&mut tx.conn, board_id).await?;
             let exists = columns
                 .iter()
                 .any(|c| c.name.to_lowercase() == column_name.to_lowercase());
@@ -423,16 +776,61 @@ async fn execute_action(
                     action: "create_column".to_string(),
                     description: format!("Column '{}' already exists", column_name),
                     success: false,
+                    ..Default::default()
                 });
             }
 
-            // Create the column
-            let column = state.columns.create(board_id, column_name, None).await?;
+            // Place it next to an existing column when asked, at the end otherwise
+            let anchor = match (
+                action.params["after"].as_str().filter(|n| !n.is_empty()),
+                action.params["before"].as_str().filter(|n| !n.is_empty()),
+            ) {
+                (Some(name), _) => Some((name, true)),
+                (None, Some(name)) => Some((name, false)),
+                (None, None) => None,
+            };
+            let position = match anchor {
+                Some((name, after)) => {
+                    match columns
+                        .iter()
+                        .find(|c| c.name.to_lowercase() == name.to_lowercase())
+                    {
+                        Some(col) => Some(col.position + i32::from(after)),
+                        None => {
+                            return Ok(ActionTaken {
+                                action: "create_column".to_string(),
+                                description: format!("Column '{}' not found", name),
+                                success: false,
+                                ..Default::default()
+                            });
+                        }
+                    }
+                }
+                None => None,
+            };
+
+            // Create the column at the end, then shift the following columns to make room
+            let mut column =
+                ColumnRepository::create_on(&mut tx.conn, board_id, column_name, None, None, None)
+                    .await?;
+            if let Some(position) = position.filter(|p| *p < column.position) {
+                column =
+                    ColumnRepository::move_column_on(&mut tx.conn, column.id, position).await?;
+            }
 
+            let placement = match anchor {
+                Some((name, true)) => format!(" after '{}'", name),
+                Some((name, false)) => format!(" before '{}'", name),
+                None => String::new(),
+            };
             Ok(ActionTaken {
                 action: "create_column".to_string(),
-                description: format!("Created column '{}'", column.name),
+                description: format!("Created column '{}'{}", column.name, placement),
                 success: true,
+                undo: Some(UndoStep::TrashColumn {
+                    column_id: column.id,
+                }),
+                ..Default::default()
             })
         }
 
@@ -459,15 +857,16 @@ async fn execute_action(
                         action.params
                     ),
                     success: false,
+                    ..Default::default()
                 });
             }
 
             // Find card by title
-            let columns = state.columns.list_by_board(board_id).await?;
+            let columns = ColumnRepository::list_by_board_on(&mut tx.conn, board_id).await?;
             let mut found_card = None;
 
             for col in &columns {
-                let cards = state.cards.list_by_column(col.id).await?;
+                let cards = CardRepository::list_by_column_on(&mut tx.conn, col.id).await?;
                 if let Some(card) = cards
                     .iter()
                     .find(|c| c.title.to_lowercase() == card_title.to_lowercase())
@@ -484,22 +883,60 @@ async fn execute_action(
 
             match (found_card, target_col) {
                 (Some(card), Some(col)) => {
-                    state.cards.move_card(card.id, col.id, 0).await?;
+                    let wip_warning = if card.column_id == Some(col.id) {
+                        None
+                    } else {
+                        match wip_limit_param(&mut tx.conn, col).await? {
+                            Ok(warning) => warning,
+                            Err(description) => {
+                                return Ok(ActionTaken {
+                                    action: "move_card".to_string(),
+                                    description,
+                                    success: false,
+                                    ..Default::default()
+                                });
+                            }
+                        }
+                    };
+                    CardBoardRepository::move_card_in_board_on(
+                        &mut tx.conn,
+                        card.id,
+                        col.board_id,
+                        Some(col.id),
+                        0,
+                    )
+                    .await?;
+                    let moved = CardRepository::get_on(&mut tx.conn, card.id).await?;
+                    if card.column_id != Some(col.id) {
+                        sync_card_status_on(&mut tx.conn, &mut tx.activity, user_id, moved, col)
+                            .await?;
+                    }
                     Ok(ActionTaken {
                         action: "move_card".to_string(),
-                        description: format!("Moved '{}' to '{}'", card.title, col.name),
+                        description: with_wip_warning(
+                            format!("Moved '{}' to '{}'", card.title, col.name),
+                            wip_warning,
+                        ),
                         success: true,
+                        undo: card.column_id.map(|column_id| UndoStep::MoveCard {
+                            card_id: card.id,
+                            column_id,
+                            position: card.position,
+                        }),
+                        ..Default::default()
                     })
                 }
                 (None, _) => Ok(ActionTaken {
                     action: "move_card".to_string(),
                     description: format!("Card '{}' not found", card_title),
                     success: false,
+                    ..Default::default()
                 }),
                 (_, None) => Ok(ActionTaken {
                     action: "move_card".to_string(),
                     description: format!("Column '{}' not found", target_column),
                     success: false,
+                    ..Default::default()
                 }),
             }
         }
@@ -521,15 +958,18 @@ async fn execute_action(
                     action: "create_tag".to_string(),
                     description: format!("Missing tag name. Received params: {:?}", action.params),
                     success: false,
+                    ..Default::default()
                 });
             }
 
-            state.tags.create(board_id, name, color).await?;
+            let tag = TagRepository::create_on(&mut tx.conn, board_id, name, color).await?;
 
             Ok(ActionTaken {
                 action: "create_tag".to_string(),
                 description: format!("Created tag '{}'", name),
                 success: true,
+                undo: Some(UndoStep::DeleteTag { tag_id: tag.id }),
+                ..Default::default()
             })
         }
 
@@ -554,15 +994,16 @@ async fn execute_action(
                         action.params
                     ),
                     success: false,
+                    ..Default::default()
                 });
             }
 
             // Find card
-            let columns = state.columns.list_by_board(board_id).await?;
+            let columns = ColumnRepository::list_by_board_on(&mut tx.conn, board_id).await?;
             let mut found_card = None;
 
             for col in &columns {
-                let cards = state.cards.list_by_column(col.id).await?;
+                let cards = CardRepository::list_by_column_on(&mut tx.conn, col.id).await?;
                 if let Some(card) = cards
                     .iter()
                     .find(|c| c.title.to_lowercase() == card_title.to_lowercase())
@@ -573,29 +1014,46 @@ async fn execute_action(
             }
 
             // Find tag
-            let tags = state.tags.list_by_board(board_id).await?;
+            let tags = TagRepository::list_by_board_on(&mut tx.conn, board_id).await?;
             let tag = tags
                 .iter()
                 .find(|t| t.name.to_lowercase() == tag_name.to_lowercase());
 
             match (found_card, tag) {
+                (Some(card), Some(_)) if card.is_locked() => Ok(ActionTaken {
+                    action: "add_tag".to_string(),
+                    description: format!("Card '{}' is locked", card.title),
+                    success: false,
+                    ..Default::default()
+                }),
                 (Some(card), Some(t)) => {
-                    state.tags.add_to_card(card.id, t.id).await?;
+                    // Undoing must not remove a tag the card already had
+                    let had_tag = TagRepository::list_card_ids_on(&mut tx.conn, t.id)
+                        .await?
+                        .contains(&card.id);
+                    TagRepository::add_to_card_on(&mut tx.conn, card.id, t.id).await?;
                     Ok(ActionTaken {
                         action: "add_tag".to_string(),
                         description: format!("Added tag '{}' to '{}'", t.name, card.title),
                         success: true,
+                        undo: (!had_tag).then_some(UndoStep::RemoveTag {
+                            card_id: card.id,
+                            tag_id: t.id,
+                        }),
+                        ..Default::default()
                     })
                 }
                 (None, _) => Ok(ActionTaken {
                     action: "add_tag".to_string(),
                     description: format!("Card '{}' not found", card_title),
                     success: false,
+                    ..Default::default()
                 }),
                 (_, None) => Ok(ActionTaken {
                     action: "add_tag".to_string(),
                     description: format!("Tag '{}' not found", tag_name),
                     success: false,
+                    ..Default::default()
                 }),
             }
         }
@@ -616,27 +1074,31 @@ async fn execute_action(
                         action.params
                     ),
                     success: false,
+                    ..Default::default()
                 });
             }
 
             // Find column by name
-            let columns = state.columns.list_by_board(board_id).await?;
+            let columns = ColumnRepository::list_by_board_on(&mut tx.conn, board_id).await?;
             let column = columns
                 .iter()
                 .find(|c| c.name.to_lowercase() == column_name.to_lowercase());
 
             if let Some(col) = column {
-                state.columns.delete(col.id).await?;
+                ColumnRepository::move_to_trash_on(&mut tx.conn, col.id, user_id).await?;
                 Ok(ActionTaken {
                     action: "delete_column".to_string(),
                     description: format!("Deleted column '{}'", col.name),
                     success: true,
+                    undo: Some(UndoStep::RestoreColumn { column_id: col.id }),
+                    ..Default::default()
                 })
             } else {
                 Ok(ActionTaken {
                     action: "delete_column".to_string(),
                     description: format!("Column '{}' not found", column_name),
                     success: false,
+                    ..Default::default()
                 })
             }
         }
@@ -654,27 +1116,38 @@ async fn execute_action(
                     action: "delete_tag".to_string(),
                     description: format!("Missing tag name. Received params: {:?}", action.params),
                     success: false,
+                    ..Default::default()
                 });
             }
 
             // Find tag by name
-            let tags = state.tags.list_by_board(board_id).await?;
+            let tags = TagRepository::list_by_board_on(&mut tx.conn, board_id).await?;
             let tag = tags
                 .iter()
                 .find(|t| t.name.to_lowercase() == tag_name.to_lowercase());
 
             if let Some(t) = tag {
-                state.tags.delete(t.id).await?;
+                let card_ids = TagRepository::list_card_ids_on(&mut tx.conn, t.id).await?;
+                TagRepository::delete_on(&mut tx.conn, t.id).await?;
                 Ok(ActionTaken {
                     action: "delete_tag".to_string(),
                     description: format!("Deleted tag '{}'", t.name),
                     success: true,
+                    undo: Some(UndoStep::RecreateTag {
+                        board_id,
+                        name: t.name.clone(),
+                        color: t.color.clone(),
+                        linked_tag_id: t.linked_tag_id,
+                        card_ids,
+                    }),
+                    ..Default::default()
                 })
             } else {
                 Ok(ActionTaken {
                     action: "delete_tag".to_string(),
                     description: format!("Tag '{}' not found", tag_name),
                     success: false,
+                    ..Default::default()
                 })
             }
         }
@@ -696,44 +1169,79 @@ async fn execute_action(
                         action.params
                     ),
                     success: false,
+                    ..Default::default()
                 });
             }
 
-            // Find card by title
-            let columns = state.columns.list_by_board(board_id).await?;
-            let mut found_card = None;
-
-            for col in &columns {
-                let cards = state.cards.list_by_column(col.id).await?;
-                if let Some(card) = cards
-                    .iter()
-                    .find(|c| c.title.to_lowercase() == card_title.to_lowercase())
-                {
-                    found_card = Some(card.clone());
-                    break;
-                }
-            }
+            let found_card = find_board_card(&mut tx.conn, board_id, card_title).await?;
 
-            if let Some(card) = found_card {
-                state.cards.delete(card.id).await?;
+            if let Some(card) = found_card.as_ref().filter(|c| c.is_locked()) {
+                Ok(ActionTaken {
+                    action: "delete_card".to_string(),
+                    description: format!("Card '{}' is locked", card.title),
+                    success: false,
+                    ..Default::default()
+                })
+            } else if let Some(card) = found_card {
+                CardRepository::move_to_trash_on(&mut tx.conn, card.id, user_id).await?;
                 Ok(ActionTaken {
                     action: "delete_card".to_string(),
                     description: format!("Deleted card '{}'", card.title),
                     success: true,
+                    undo: Some(UndoStep::RestoreCard { card_id: card.id }),
+                    ..Default::default()
                 })
             } else {
                 Ok(ActionTaken {
                     action: "delete_card".to_string(),
                     description: format!("Card '{}' not found", card_title),
                     success: false,
+                    ..Default::default()
                 })
             }
         }
 
-        ChatAction::ListCards | ChatAction::ListTags | ChatAction::NoAction => Ok(ActionTaken {
+        ChatAction::UpdateCard | ChatAction::UpdateStatus | ChatAction::AddComment => {
+            let card_title = card_param(action);
+            if card_title.is_empty() {
+                return Ok(ActionTaken {
+                    action: chat_action.to_string(),
+                    description: format!(
+                        "Missing card title. Received params: {:?}",
+                        action.params
+                    ),
+                    success: false,
+                    ..Default::default()
+                });
+            }
+
+            match find_board_card(&mut tx.conn, board_id, card_title).await? {
+                Some(card) => execute_card_action(tx, user_id, chat_action, &card, action).await,
+                None => Ok(ActionTaken {
+                    action: chat_action.to_string(),
+                    description: format!("Card '{}' not found", card_title),
+                    success: false,
+                    ..Default::default()
+                }),
+            }
+        }
+
+        ChatAction::BulkUpdateCards => {
+            execute_bulk_update_cards(tx, board_id, user_id, action).await
+        }
+
+        ChatAction::AssignUsers => execute_assign_users(tx, board_id, user_id, action).await,
+
+        ChatAction::Undo => execute_undo(tx, user_id, Some(board_id)).await,
+
+        ChatAction::ListCards
+        | ChatAction::ListTags
+        | ChatAction::SuggestAssignments
+        | ChatAction::NoAction => Ok(ActionTaken {
             action: chat_action.to_string(),
             description: "No modification made".to_string(),
             success: true,
+            ..Default::default()
         }),
 
         ChatAction::WebSearch => {
@@ -746,32 +1254,29 @@ async fn execute_action(
         | ChatAction::DeleteBoard
         | ChatAction::MoveCardCrossBoard
         | ChatAction::CreateInboxCard
-        | ChatAction::AssignCard
-        | ChatAction::UpdateStatus
-        | ChatAction::AddComment => Ok(ActionTaken {
+        | ChatAction::AssignCard => Ok(ActionTaken {
             action: chat_action.to_string(),
             description: "This action is only available in global chat".to_string(),
             success: false,
+            ..Default::default()
         }),
 
         ChatAction::Unknown => Ok(ActionTaken {
             action: action.action.clone(),
             description: format!("Unknown action: {}", action.action),
             success: false,
+            ..Default::default()
         }),
     }
 }
 
-/// Send a chat message and get a response
-#[instrument(skip(state, auth, input), fields(user_id = %auth.user.id, board_id = %board_id))]
-pub async fn send_message(
-    State(state): State<AppState>,
-    auth: AuthUser,
-    Path(board_id): Path<Uuid>,
-    Json(input): Json<SendChatRequest>,
-) -> Result<Json<ChatResponse>> {
-    info!(message = %input.message, "Chat message received");
-
+/// Check the user may chat on the board and build the messages and tools for the LLM
+async fn prepare_board_chat(
+    state: &AppState,
+    auth: &AuthUser,
+    board_id: Uuid,
+    message: String,
+) -> Result<(BoardRole, Vec<LlmMessage>, Vec<LlmTool>)> {
     // Verify user has access to board
     let role = state
         .boards
@@ -781,63 +1286,179 @@ pub async fn send_message(
 
     debug!(role = ?role, "User role verified");
 
-    // Store input message before moving
-    let user_message = input.message.clone();
-
     // Build system prompt with board context and user's custom context
-    let system_prompt =
-        build_system_prompt(&state, board_id, auth.user.llm_context.as_deref()).await?;
+    let tools = action_tools(BOARD_CHAT_ACTIONS, false);
+    let system_prompt = build_system_prompt(
+        state,
+        board_id,
+        auth.user.llm_context.as_deref(),
+        !tools.is_empty(),
+    )
+    .await?;
 
     debug!("System prompt built successfully");
 
-    // Create messages for Ollama
-    let messages = vec![
-        crate::services::ollama::OllamaMessage {
-            role: "system".to_string(),
-            content: system_prompt,
-        },
-        crate::services::ollama::OllamaMessage {
-            role: "user".to_string(),
-            content: input.message,
-        },
-    ];
+    // Earlier exchanges let follow-ups refer to what was said or done before
+    let history = state
+        .chat_messages
+        .list_recent_for_user(board_id, auth.user.id, context_messages())
+        .await?;
+    debug!(exchanges = history.len(), "Loaded conversation context");
+
+    let mut messages = vec![LlmMessage {
+        role: "system".to_string(),
+        content: system_prompt,
+    }];
+    messages.extend(history_messages(history));
+    messages.push(LlmMessage {
+        role: "user".to_string(),
+        content: message,
+    });
+
+    Ok((role, messages, tools))
+}
 
-    // Send to Ollama
-    info!("Sending request to LLM");
-    let llm_response = state.ollama.chat(messages).await?;
-    debug!(
-        response_length = llm_response.len(),
-        "LLM response received"
-    );
+/// Number of past exchanges sent with a board chat message when CHAT_CONTEXT_MESSAGES is
+/// not set
+const DEFAULT_CONTEXT_MESSAGES: i64 = 10;
+
+/// Number of past exchanges of the user on the board sent with each board chat message,
+/// from CHAT_CONTEXT_MESSAGES. 0 sends none.
+pub fn context_messages() -> i64 {
+    std::env::var("CHAT_CONTEXT_MESSAGES")
+        .ok()
+        .and_then(|v| v.trim().parse().ok())
+        .filter(|count| *count >= 0)
+        .unwrap_or(DEFAULT_CONTEXT_MESSAGES)
+}
 
-    // Parse LLM response for actions (now handles multiple actions)
-    let mut actions_taken = Vec::new();
-    let parsed_actions = parse_llm_response(&llm_response);
+/// Turn stored exchanges, newest first, into alternating user and assistant messages,
+/// oldest first. Assistant messages list the actions that ran so the LLM knows their
+/// outcome.
+fn history_messages(history: Vec<ChatMessage>) -> impl Iterator<Item = LlmMessage> {
+    history.into_iter().rev().flat_map(|message| {
+        let message = message.into_response();
+        let mut response = message.response;
+        if !message.actions_taken.is_empty() {
+            response.push_str("\n\nActions:");
+            for action in &message.actions_taken {
+                let outcome = if action.success { "done" } else { "failed" };
+                response.push_str(&format!("\n- {} ({})", action.description, outcome));
+            }
+        }
 
-    if parsed_actions.is_empty() {
-        debug!("No actions parsed from LLM response");
-    } else {
-        info!(
-            action_count = parsed_actions.len(),
-            "Parsed actions from LLM response"
-        );
-        for action in &parsed_actions {
-            debug!(action = %action.action, params = ?action.params, "Parsed action");
+        [
+            LlmMessage {
+                role: "user".to_string(),
+                content: message.message,
+            },
+            LlmMessage {
+                role: "assistant".to_string(),
+                content: response,
+            },
+        ]
+    })
+}
+
+/// Whether board chat skips an action instead of executing it. Web searches are run
+/// by [`search_and_answer`] instead.
+fn is_read_only_action(action: &LlmAction) -> bool {
+    action
+        .action
+        .parse::<ChatAction>()
+        .is_ok_and(|a| a.is_read_only())
+}
+
+fn is_web_search(action: &LlmAction) -> bool {
+    action.action.parse::<ChatAction>() == Ok(ChatAction::WebSearch)
+}
+
+/// Messages sent to the user's LLM, kept to ask it follow-up questions
+struct Conversation {
+    llm: Arc<dyn LlmProvider>,
+    options: LlmOptions,
+    messages: Vec<LlmMessage>,
+}
+
+impl Conversation {
+    /// Latest message of the user
+    fn user_message(&self) -> &str {
+        self.messages
+            .last()
+            .map(|m| m.content.as_str())
+            .unwrap_or_default()
+    }
+}
+
+/// Run the web searches among `actions`, then ask the LLM to answer from their results.
+/// Returns the searches and the reply to show: the answer, or `reply` when no search
+/// succeeded or the follow-up request failed.
+async fn search_and_answer(
+    state: &AppState,
+    conversation: &Conversation,
+    actions: &[LlmAction],
+    reply: LlmReply,
+) -> Result<(Vec<ActionTaken>, LlmReply)> {
+    let mut searches = Vec::new();
+    for action in actions.iter().filter(|a| is_web_search(a)) {
+        searches.push(execute_web_search(state, action).await?);
+    }
+
+    let results: Vec<&str> = searches
+        .iter()
+        .filter(|s| s.success)
+        .map(|s| s.description.as_str())
+        .collect();
+    if results.is_empty() {
+        return Ok((searches, reply));
+    }
+
+    info!("Making follow-up LLM call with search results");
+    let mut messages = conversation.messages.clone();
+    messages.push(LlmMessage {
+        role: "assistant".to_string(),
+        content: reply.content.clone(),
+    });
+    messages.push(LlmMessage {
+        role: "user".to_string(),
+        content: format!(
+            "Here are the search results:\n\n{}\n\nBased on these results, please provide a helpful response to my original question. Use no_action since you're just providing information.",
+            results.join("\n")
+        ),
+    });
+
+    // No tools, the follow-up only answers with what was found
+    match conversation
+        .llm
+        .chat(messages, Vec::new(), conversation.options.clone())
+        .await
+    {
+        Ok(answer) => {
+            info!("Follow-up LLM response received");
+            Ok((searches, answer))
+        }
+        Err(e) => {
+            warn!(error = %e, "Follow-up LLM call failed, using search results directly");
+            Ok((searches, reply))
         }
     }
+}
+
+/// Execute the actions of an LLM response on a board. Nothing is executed for users
+/// who cannot edit the board.
+async fn execute_board_actions(
+    state: &AppState,
+    board_id: Uuid,
+    user_id: Uuid,
+    role: BoardRole,
+    actions: &[LlmAction],
+) -> Result<Vec<ActionTaken>> {
+    let mut actions_taken = Vec::new();
+    let mut tx = ActionTx::begin(state).await?;
 
-    // Execute all parsed actions
-    for action in &parsed_actions {
+    for (i, action) in actions.iter().enumerate() {
         // Skip no-op actions
-        let readonly_actions = [
-            "no_action",
-            "noaction",
-            "list_cards",
-            "listcards",
-            "list_tags",
-            "listtags",
-        ];
-        if readonly_actions.contains(&action.action.as_str()) {
+        if is_read_only_action(action) {
             debug!(action = %action.action, "Skipping read-only action");
             continue;
         }
@@ -845,7 +1466,9 @@ pub async fn send_message(
         // Only execute if user can edit
         if role.can_edit() {
             info!(action = %action.action, "Executing action");
-            let action_result = execute_action(&state, board_id, auth.user.id, action).await?;
+            let mut action_result =
+                execute_action(state, &mut tx, board_id, user_id, action).await?;
+            remember_undo(&mut tx.conn, user_id, Some(board_id), &mut action_result).await?;
 
             if action_result.success {
                 info!(
@@ -861,51 +1484,352 @@ pub async fn send_message(
                 );
             }
 
+            let failed = !action_result.success;
             actions_taken.push(action_result);
+            if failed {
+                tx.rollback().await?;
+                let skipped = roll_back(&mut actions_taken, &actions[i + 1..]);
+                actions_taken.extend(skipped);
+                return Ok(actions_taken);
+            }
         } else {
             warn!(action = %action.action, "User lacks permission to execute action");
+            actions_taken.push(ActionTaken {
+                action: action.action.clone(),
+                description: "You don't have permission to edit this board".to_string(),
+                success: false,
+                ..Default::default()
+            });
         }
     }
 
-    // Extract a readable message from the response
-    let response_message = extract_readable_message(&llm_response, &parsed_actions);
+    let succeeded: Vec<&str> = actions_taken
+        .iter()
+        .filter(|a| a.success)
+        .map(|a| a.description.as_str())
+        .collect();
+    if !succeeded.is_empty() {
+        record_chat_actions(&mut tx.activity, board_id, user_id, &succeeded);
+    }
+    tx.commit(state).await?;
 
-    // Persist the chat message
-    let actions_json = if actions_taken.is_empty() {
+    Ok(actions_taken)
+}
+
+fn actions_json(actions_taken: &[ActionTaken]) -> Option<String> {
+    if actions_taken.is_empty() {
         None
     } else {
-        Some(serde_json::to_string(&actions_taken).unwrap_or_default())
-    };
+        Some(serde_json::to_string(actions_taken).unwrap_or_default())
+    }
+}
 
-    state
-        .chat_messages
-        .create(
-            board_id,
-            auth.user.id,
-            &user_message,
-            &response_message,
-            actions_json.as_deref(),
-        )
-        .await?;
+/// Execute the actions of an LLM response, then persist the exchange. Web searches are
+/// answered in a second LLM turn. With `require_confirmation`, actions that change the
+/// board are saved for [`confirm_actions`] instead, as are suggested assignments always.
+async fn complete_board_chat(
+    state: &AppState,
+    board_id: Uuid,
+    user_id: Uuid,
+    role: BoardRole,
+    conversation: &Conversation,
+    reply: LlmReply,
+    require_confirmation: bool,
+) -> Result<ChatResponse> {
+    let user_message = conversation.user_message();
 
-    info!(
-        actions_executed = actions_taken.len(),
-        successful = actions_taken.iter().filter(|a| a.success).count(),
-        "Chat request completed"
-    );
+    // Tool calls, or the JSON actions of the text when the LLM called none
+    let parsed_actions = reply_actions(&reply);
 
-    Ok(Json(ChatResponse {
-        response: response_message,
+    if parsed_actions.is_empty() {
+        debug!("No actions parsed from LLM response");
+    } else {
+        info!(
+            action_count = parsed_actions.len(),
+            "Parsed actions from LLM response"
+        );
+        for action in &parsed_actions {
+            debug!(action = %action.action, params = ?action.params, "Parsed action");
+        }
+    }
+
+    let (searches, reply) = search_and_answer(state, conversation, &parsed_actions, reply).await?;
+
+    // Extract a readable message from the response
+    let response_message = extract_readable_message(&reply.content, &reply_actions(&reply));
+
+    // Suggested assignments are only applied once the user accepts them
+    let (suggestions, assignment_plan) =
+        suggest_assignments(state, board_id, role, &parsed_actions).await?;
+    let mut actions_taken = searches;
+    actions_taken.extend(suggestions);
+
+    let mut proposed: Vec<LlmAction> = parsed_actions
+        .iter()
+        .filter(|a| !is_read_only_action(a))
+        .cloned()
+        .collect();
+    if !(require_confirmation && role.can_edit()) {
+        // Execute all parsed actions
+        actions_taken
+            .extend(execute_board_actions(state, board_id, user_id, role, &parsed_actions).await?);
+        proposed.clear();
+    }
+    proposed.extend(assignment_plan);
+
+    // Persist the chat message
+    let message = state
+        .chat_messages
+        .create(
+            board_id,
+            user_id,
+            user_message,
+            &response_message,
+            actions_json(&actions_taken).as_deref(),
+        )
+        .await?;
+
+    if !proposed.is_empty() {
+        let pending = state
+            .pending_chat_actions
+            .create(
+                user_id,
+                board_id,
+                message.id,
+                &proposed,
+                PENDING_ACTIONS_TTL_MINUTES,
+            )
+            .await?;
+
+        info!(
+            actions_proposed = proposed.len(),
+            "Chat actions waiting for confirmation"
+        );
+        return Ok(ChatResponse {
+            response: response_message,
+            actions_taken,
+            pending: Some(PendingActionsResponse {
+                confirmation_token: pending.id,
+                expires_at: pending.created_at
+                    + chrono::Duration::minutes(PENDING_ACTIONS_TTL_MINUTES),
+                actions: proposed,
+            }),
+        });
+    }
+
+    info!(
+        actions_executed = actions_taken.len(),
+        successful = actions_taken.iter().filter(|a| a.success).count(),
+        "Chat request completed"
+    );
+
+    Ok(ChatResponse {
+        response: response_message,
+        actions_taken,
+        pending: None,
+    })
+}
+
+/// Execute the actions proposed by a board chat message sent with confirmation.
+/// Each proposal can be confirmed once, within PENDING_ACTIONS_TTL_MINUTES.
+#[instrument(skip(state, auth, input), fields(user_id = %auth.user.id, board_id = %board_id))]
+pub async fn confirm_actions(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(board_id): Path<Uuid>,
+    Json(input): Json<ConfirmChatRequest>,
+) -> Result<Json<ChatResponse>> {
+    let role = state
+        .boards
+        .get_user_role(board_id, auth.user.id)
+        .await?
+        .ok_or(AppError::Forbidden)?;
+    if !role.can_edit() {
+        return Err(AppError::Forbidden);
+    }
+
+    let pending = state
+        .pending_chat_actions
+        .take(
+            input.confirmation_token,
+            auth.user.id,
+            board_id,
+            PENDING_ACTIONS_TTL_MINUTES,
+        )
+        .await?
+        .ok_or(AppError::NotFound("pending_chat_action"))?;
+
+    let actions = pending.llm_actions();
+    info!(action_count = actions.len(), "Executing confirmed actions");
+    let actions_taken =
+        execute_board_actions(&state, board_id, auth.user.id, role, &actions).await?;
+    state
+        .chat_messages
+        .set_actions(
+            pending.chat_message_id,
+            actions_json(&actions_taken).as_deref(),
+        )
+        .await?;
+
+    Ok(Json(ChatResponse {
+        response: extract_readable_message("", &actions),
         actions_taken,
+        pending: None,
     }))
 }
 
+/// Send a chat message and get a response
+#[instrument(skip(state, auth, input), fields(user_id = %auth.user.id, board_id = %board_id))]
+pub async fn send_message(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(board_id): Path<Uuid>,
+    Json(input): Json<SendChatRequest>,
+) -> Result<Json<ChatResponse>> {
+    info!(message = %input.message, "Chat message received");
+
+    let require_confirmation = input
+        .require_confirmation
+        .unwrap_or(auth.user.chat_confirm_actions);
+    let (role, messages, tools) =
+        prepare_board_chat(&state, &auth, board_id, input.message).await?;
+    let (llm, options) = state.llm_for(&auth.user);
+    let conversation = Conversation {
+        llm,
+        options,
+        messages,
+    };
+
+    // Send to the user's LLM
+    info!("Sending request to LLM");
+    let llm_reply = conversation
+        .llm
+        .chat(
+            conversation.messages.clone(),
+            tools,
+            conversation.options.clone(),
+        )
+        .await?;
+    debug!(
+        response_length = llm_reply.content.len(),
+        tool_calls = llm_reply.tool_calls.len(),
+        "LLM response received"
+    );
+
+    let response = complete_board_chat(
+        &state,
+        board_id,
+        auth.user.id,
+        role,
+        &conversation,
+        llm_reply,
+        require_confirmation,
+    )
+    .await?;
+
+    Ok(Json(response))
+}
+
+/// Send a chat message and stream the response as Server-Sent Events.
+///
+/// Each piece of generated text is sent as a `token` event while the LLM generates.
+/// Actions are executed once generation completes, then a `done` event carries the
+/// same JSON body as the non-streaming endpoint. Failures after the stream started
+/// are reported as an `error` event.
+#[instrument(skip(state, auth, input), fields(user_id = %auth.user.id, board_id = %board_id))]
+pub async fn send_message_stream(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(board_id): Path<Uuid>,
+    Json(input): Json<SendChatRequest>,
+) -> Result<Sse<impl Stream<Item = std::result::Result<Event, Infallible>>>> {
+    info!(message = %input.message, "Streaming chat message received");
+
+    let require_confirmation = input
+        .require_confirmation
+        .unwrap_or(auth.user.chat_confirm_actions);
+    let (role, messages, tools) =
+        prepare_board_chat(&state, &auth, board_id, input.message).await?;
+    let user_id = auth.user.id;
+    let (llm, options) = state.llm_for(&auth.user);
+    let conversation = Conversation {
+        llm,
+        options,
+        messages,
+    };
+
+    let (events_tx, events_rx) = mpsc::channel::<Event>(CHAT_STREAM_BUFFER);
+    // Tracked so a shutdown waits for the reply and its actions instead of cutting them off
+    let jobs = state.jobs.clone();
+    jobs.spawn(async move {
+        let (tokens_tx, mut tokens_rx) = mpsc::channel::<String>(CHAT_STREAM_BUFFER);
+
+        // Forward tokens while the LLM generates
+        let forward_tx = events_tx.clone();
+        let forward = tokio::spawn(async move {
+            while let Some(token) = tokens_rx.recv().await {
+                if forward_tx
+                    .send(Event::default().event("token").data(token))
+                    .await
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        });
+
+        info!("Sending streaming request to LLM");
+        let result = match conversation
+            .llm
+            .chat_stream(
+                conversation.messages.clone(),
+                tools,
+                conversation.options.clone(),
+                tokens_tx,
+            )
+            .await
+        {
+            Ok(llm_reply) => {
+                complete_board_chat(
+                    &state,
+                    board_id,
+                    user_id,
+                    role,
+                    &conversation,
+                    llm_reply,
+                    require_confirmation,
+                )
+                .await
+            }
+            Err(e) => Err(e),
+        };
+        // Every token is sent before the final event
+        let _ = forward.await;
+
+        let event = match result {
+            Ok(response) => Event::default()
+                .event("done")
+                .json_data(&response)
+                .unwrap_or_else(|_| Event::default().event("done")),
+            Err(e) => {
+                warn!(error = %e, "Streaming chat request failed");
+                Event::default().event("error").data(e.to_string())
+            }
+        };
+        let _ = events_tx.send(event).await;
+    });
+
+    Ok(Sse::new(ReceiverStream::new(events_rx).map(Ok)).keep_alive(KeepAlive::default()))
+}
+
 /// Get chat history for a board
 pub async fn get_history(
     State(state): State<AppState>,
     auth: AuthUser,
     Path(board_id): Path<Uuid>,
-) -> Result<Json<Vec<ChatMessageResponse>>> {
+    OriginalUri(uri): OriginalUri,
+    Query(paging): Query<PageQuery>,
+) -> Result<Paginated<ChatMessageResponse>> {
     // Verify user has access to board
     state
         .boards
@@ -913,8 +1837,9 @@ pub async fn get_history(
         .await?
         .ok_or(AppError::Forbidden)?;
 
-    // Get last 50 messages, ordered by created_at DESC
-    let messages = state.chat_messages.list_by_board(board_id, 50).await?;
+    // Pages count back from the newest message
+    let page = paging.page(DEFAULT_HISTORY_PAGE_SIZE);
+    let messages = state.chat_messages.list_by_board(board_id, page).await?;
 
     // Convert to response format and reverse for chronological order
     let responses: Vec<ChatMessageResponse> = messages
@@ -923,7 +1848,12 @@ pub async fn get_history(
         .rev()
         .collect();
 
-    Ok(Json(responses))
+    Ok(Paginated {
+        items: responses,
+        total: state.chat_messages.count_by_board(board_id).await?,
+        page,
+        uri,
+    })
 }
 
 /// Clear chat history for a board
@@ -954,17 +1884,170 @@ pub async fn clear_history(
 // Global Chat (Cross-Board)
 // =============================================================================
 
+/// Apply the board's column creation policy when create_card names a missing column
+#[allow(clippy::too_many_arguments)]
+async fn execute_create_card_missing_column(
+    conn: &mut SqliteConnection,
+    board_id: Uuid,
+    user_id: Uuid,
+    column_name: &str,
+    title: &str,
+    body: Option<&str>,
+    priority: Option<CardPriority>,
+    columns: &[Column],
+) -> Result<ActionTaken> {
+    let board = BoardRepository::get_on(conn, board_id).await?;
+    let policy = board.column_creation_policy();
+    info!(column = %column_name, policy = %policy, "Column not found for create_card");
+
+    match policy {
+        ColumnCreationPolicy::Reject => Ok(ActionTaken {
+            action: "create_card".to_string(),
+            description: format!(
+                "Column '{}' not found (board policy: reject missing columns)",
+                column_name
+            ),
+            success: false,
+            ..Default::default()
+        }),
+        ColumnCreationPolicy::Suggest => {
+            let suggestion = closest_match(column_name, columns.iter().map(|c| c.name.as_str()));
+            let description = match suggestion {
+                Some(name) => format!(
+                    "Column '{}' not found. Did you mean '{}'? (board policy: suggest closest column)",
+                    column_name, name
+                ),
+                None => format!(
+                    "Column '{}' not found and no similar column exists (board policy: suggest closest column)",
+                    column_name
+                ),
+            };
+            Ok(ActionTaken {
+                action: "create_card".to_string(),
+                description,
+                success: false,
+                ..Default::default()
+            })
+        }
+        ColumnCreationPolicy::AutoCreate => {
+            let column =
+                ColumnRepository::create_on(conn, board_id, column_name, None, None, None).await?;
+            CardRepository::create_on(
+                conn,
+                column.id,
+                title,
+                body,
+                None,
+                CardVisibility::Restricted,
+                CardStatus::Open,
+                None,
+                None,
+                None,
+                priority,
+                user_id,
+            )
+            .await?;
+
+            Ok(ActionTaken {
+                action: "create_card".to_string(),
+                description: format!(
+                    "Created card '{}' in new column '{}' (board policy: auto-create missing columns)",
+                    title, column.name
+                ),
+                success: true,
+                // The card goes to the trash with the column
+                undo: Some(UndoStep::TrashColumn {
+                    column_id: column.id,
+                }),
+                ..Default::default()
+            })
+        }
+    }
+}
+
 /// Helper to find a board by name from the user's accessible boards
 /// Returns (Board, role_string) where role_string is "owner", "editor", or "reader"
-async fn find_board_by_name(
+/// The boards the user can access, with their owners
+pub(crate) async fn board_candidates(
+    state: &AppState,
+    user_id: Uuid,
+) -> Result<Vec<BoardCandidate>> {
+    let mut conn = state.pool.acquire().await?;
+    board_candidates_on(&mut conn, user_id).await
+}
+
+async fn board_candidates_on(
+    conn: &mut SqliteConnection,
+    user_id: Uuid,
+) -> Result<Vec<BoardCandidate>> {
+    let boards = BoardRepository::list_for_user_on(conn, user_id).await?;
+    let mut owners: HashMap<Uuid, (String, String)> = HashMap::new();
+    let mut candidates = Vec::with_capacity(boards.len());
+
+    for (board, role) in boards {
+        if let Entry::Vacant(entry) = owners.entry(board.owner_id) {
+            let owner = UserRepository::get_on(conn, board.owner_id).await?;
+            entry.insert((owner.name, owner.email));
+        }
+        let (owner_name, owner_email) = owners[&board.owner_id].clone();
+        candidates.push(BoardCandidate {
+            board,
+            role,
+            owner_name,
+            owner_email,
+        });
+    }
+
+    Ok(candidates)
+}
+
+/// Resolve a board reference (name, `Owner/Board` or id) from chat params
+pub(crate) async fn find_board_by_name(
     state: &AppState,
     user_id: Uuid,
     board_name: &str,
-) -> Result<Option<(crate::models::Board, String)>> {
-    let boards = state.boards.list_for_user(user_id).await?;
-    Ok(boards
-        .into_iter()
-        .find(|(b, _)| b.name.to_lowercase() == board_name.to_lowercase()))
+) -> Result<BoardMatch> {
+    let mut conn = state.pool.acquire().await?;
+    find_board_by_name_on(&mut conn, user_id, board_name).await
+}
+
+async fn find_board_by_name_on(
+    conn: &mut SqliteConnection,
+    user_id: Uuid,
+    board_name: &str,
+) -> Result<BoardMatch> {
+    let candidates = board_candidates_on(conn, user_id).await?;
+    Ok(board_ref::resolve(board_name, candidates))
+}
+
+/// Failed action for a board reference that did not resolve to exactly one board.
+/// `label` names the board in the message, e.g. "Board" or "Target board".
+pub(crate) fn board_not_resolved(
+    action: &str,
+    label: &str,
+    board_name: &str,
+    result: &BoardMatch,
+) -> ActionTaken {
+    let description = match result {
+        BoardMatch::Ambiguous(candidates) => format!(
+            "{} '{}' is ambiguous. Matching boards: {}. Use the board id or 'Owner/Board' to pick one",
+            label,
+            board_name,
+            candidates
+                .iter()
+                .map(|c| c.describe())
+                .collect::<Vec<_>>()
+                .join("; ")
+        ),
+        _ => format!("{} '{}' not found", label, board_name),
+    };
+
+    ActionTaken {
+        action: action.to_string(),
+        description,
+        success: false,
+        ..Default::default()
+    }
 }
 
 /// Check if a role string allows editing
@@ -973,9 +2056,10 @@ fn role_can_edit(role: &str) -> bool {
 }
 
 /// Execute an action in global context (resolves board from params)
-#[instrument(skip(state), fields(user_id = %user_id, action = %action.action))]
+#[instrument(skip(state, tx), fields(user_id = %user_id, action = %action.action))]
 async fn execute_global_action(
     state: &AppState,
+    tx: &mut ActionTx,
     user_id: Uuid,
     action: &LlmAction,
 ) -> Result<ActionTaken> {
@@ -986,28 +2070,31 @@ async fn execute_global_action(
     // Handle special actions that don't need an existing board
     match chat_action {
         ChatAction::MoveCardCrossBoard => {
-            return execute_cross_board_move(state, user_id, action).await;
+            return execute_cross_board_move(tx, user_id, action).await;
         }
         ChatAction::CreateBoard => {
-            return execute_create_board(state, user_id, action).await;
+            return execute_create_board(&mut tx.conn, user_id, action).await;
         }
         ChatAction::DeleteBoard => {
-            return execute_delete_board(state, user_id, action).await;
+            return execute_delete_board(&mut tx.conn, user_id, action).await;
         }
         ChatAction::WebSearch => {
             return execute_web_search(state, action).await;
         }
         ChatAction::CreateInboxCard => {
-            return execute_create_inbox_card(state, user_id, action).await;
+            return execute_create_inbox_card(&mut tx.conn, user_id, action).await;
         }
         ChatAction::AssignCard => {
-            return execute_assign_card(state, user_id, action).await;
+            return execute_assign_card(tx, user_id, action).await;
         }
-        ChatAction::UpdateStatus => {
-            return execute_update_status(state, user_id, action).await;
+        // Cards on a board go through the board's checks below
+        ChatAction::UpdateStatus | ChatAction::UpdateCard | ChatAction::AddComment
+            if board_param(action).is_empty() =>
+        {
+            return execute_owned_card_action(tx, user_id, chat_action, action).await;
         }
-        ChatAction::AddComment => {
-            return execute_add_comment(state, user_id, action).await;
+        ChatAction::Undo => {
+            return execute_undo(tx, user_id, None).await;
         }
         _ => {}
     }
@@ -1019,14 +2106,11 @@ async fn execute_global_action(
             action: chat_action.to_string(),
             description: "No modification made".to_string(),
             success: true,
+            ..Default::default()
         });
     }
 
-    // Get board name from params
-    let board_name = action.params["board"]
-        .as_str()
-        .or_else(|| action.params["board_name"].as_str())
-        .unwrap_or("");
+    let board_name = board_param(action);
 
     // Find the board
     if board_name.is_empty() {
@@ -1038,19 +2122,20 @@ async fn execute_global_action(
                 action.params
             ),
             success: false,
+            ..Default::default()
         });
     }
 
-    let board_result = find_board_by_name(state, user_id, board_name).await?;
-    let (board, role) = match board_result {
-        Some(br) => br,
-        None => {
-            warn!(board_name = %board_name, "Board not found");
-            return Ok(ActionTaken {
-                action: chat_action.to_string(),
-                description: format!("Board '{}' not found", board_name),
-                success: false,
-            });
+    let (board, role) = match find_board_by_name_on(&mut tx.conn, user_id, board_name).await? {
+        BoardMatch::Found(candidate) => (candidate.board, candidate.role),
+        other => {
+            warn!(board_name = %board_name, "Board not resolved");
+            return Ok(board_not_resolved(
+                &chat_action.to_string(),
+                "Board",
+                board_name,
+                &other,
+            ));
         }
     };
 
@@ -1061,13 +2146,17 @@ async fn execute_global_action(
             action: chat_action.to_string(),
             description: format!("You don't have permission to edit board '{}'", board.name),
             success: false,
+            ..Default::default()
         });
     }
 
     info!(board = %board.name, "Executing action on board");
 
     // Execute the action using the existing single-board function
-    let result = execute_action(state, board.id, user_id, action).await?;
+    let result = execute_action(state, tx, board.id, user_id, action).await?;
+    if result.success {
+        record_chat_actions(&mut tx.activity, board.id, user_id, &[&result.description]);
+    }
 
     info!(
         success = result.success,
@@ -1078,10 +2167,33 @@ async fn execute_global_action(
     Ok(result)
 }
 
+/// Run an action an MCP client called as a tool, the way global chat runs the actions
+/// of the LLM. It can be undone like them.
+pub(crate) async fn run_tool_action(
+    state: &AppState,
+    user_id: Uuid,
+    action: &LlmAction,
+) -> Result<ActionTaken> {
+    // A web search changes nothing, the write lock is not held while it runs
+    if is_web_search(action) {
+        return execute_web_search(state, action).await;
+    }
+
+    let mut tx = ActionTx::begin(state).await?;
+    let mut result = execute_global_action(state, &mut tx, user_id, action).await?;
+    if result.success {
+        remember_undo(&mut tx.conn, user_id, None, &mut result).await?;
+        tx.commit(state).await?;
+    } else {
+        tx.rollback().await?;
+    }
+    Ok(result)
+}
+
 /// Execute a cross-board card move
-#[instrument(skip(state), fields(user_id = %user_id))]
+#[instrument(skip(tx), fields(user_id = %user_id))]
 async fn execute_cross_board_move(
-    state: &AppState,
+    tx: &mut ActionTx,
     user_id: Uuid,
     action: &LlmAction,
 ) -> Result<ActionTaken> {
@@ -1121,6 +2233,7 @@ async fn execute_cross_board_move(
                 action.params
             ),
             success: false,
+            ..Default::default()
         });
     }
 
@@ -1133,28 +2246,30 @@ async fn execute_cross_board_move(
     );
 
     // Find both boards
-    let from_board = find_board_by_name(state, user_id, from_board_name).await?;
-    let to_board = find_board_by_name(state, user_id, to_board_name).await?;
+    let from_board = find_board_by_name_on(&mut tx.conn, user_id, from_board_name).await?;
+    let to_board = find_board_by_name_on(&mut tx.conn, user_id, to_board_name).await?;
 
     let (source_board, source_role) = match from_board {
-        Some(b) => b,
-        None => {
-            return Ok(ActionTaken {
-                action: "move_card_cross_board".to_string(),
-                description: format!("Source board '{}' not found", from_board_name),
-                success: false,
-            });
+        BoardMatch::Found(candidate) => (candidate.board, candidate.role),
+        other => {
+            return Ok(board_not_resolved(
+                "move_card_cross_board",
+                "Source board",
+                from_board_name,
+                &other,
+            ));
         }
     };
 
     let (target_board, target_role) = match to_board {
-        Some(b) => b,
-        None => {
-            return Ok(ActionTaken {
-                action: "move_card_cross_board".to_string(),
-                description: format!("Target board '{}' not found", to_board_name),
-                success: false,
-            });
+        BoardMatch::Found(candidate) => (candidate.board, candidate.role),
+        other => {
+            return Ok(board_not_resolved(
+                "move_card_cross_board",
+                "Target board",
+                to_board_name,
+                &other,
+            ));
         }
     };
 
@@ -1167,6 +2282,7 @@ async fn execute_cross_board_move(
                 source_board.name
             ),
             success: false,
+            ..Default::default()
         });
     }
 
@@ -1178,15 +2294,16 @@ async fn execute_cross_board_move(
                 target_board.name
             ),
             success: false,
+            ..Default::default()
         });
     }
 
     // Find the card in source board
-    let source_columns = state.columns.list_by_board(source_board.id).await?;
+    let source_columns = ColumnRepository::list_by_board_on(&mut tx.conn, source_board.id).await?;
     let mut found_card = None;
 
     for col in &source_columns {
-        let cards = state.cards.list_by_column(col.id).await?;
+        let cards = CardRepository::list_by_column_on(&mut tx.conn, col.id).await?;
         if let Some(card) = cards
             .iter()
             .find(|c| c.title.to_lowercase() == card_title.to_lowercase())
@@ -1206,12 +2323,23 @@ async fn execute_cross_board_move(
                     card_title, source_board.name
                 ),
                 success: false,
+                ..Default::default()
             });
         }
     };
 
+    // A cross-board move recreates the card, which would drop the lock
+    if source_card.is_locked() {
+        return Ok(ActionTaken {
+            action: "move_card_cross_board".to_string(),
+            description: format!("Card '{}' is locked", source_card.title),
+            success: false,
+            ..Default::default()
+        });
+    }
+
     // Find target column
-    let target_columns = state.columns.list_by_board(target_board.id).await?;
+    let target_columns = ColumnRepository::list_by_board_on(&mut tx.conn, target_board.id).await?;
     let target_col = target_columns
         .iter()
         .find(|c| c.name.to_lowercase() == target_column.to_lowercase());
@@ -1226,6 +2354,7 @@ async fn execute_cross_board_move(
                     target_column, target_board.name
                 ),
                 success: false,
+                ..Default::default()
             });
         }
     };
@@ -1235,30 +2364,47 @@ async fn execute_cross_board_move(
         .visibility
         .parse()
         .unwrap_or(CardVisibility::Restricted);
-    let status: CardStatus = source_card
-        .status
-        .parse()
-        .unwrap_or(CardStatus::Open);
+    let status: CardStatus = source_card.status.parse().unwrap_or(CardStatus::Open);
 
-    // Create new card in target board
-    state
-        .cards
-        .create(
-            target_col.id,
-            &source_card.title,
-            source_card.body.as_deref(),
-            None,
-            visibility,
-            status,
-            source_card.start_date,
-            source_card.end_date,
-            source_card.due_date,
-            user_id,
-        )
-        .await?;
+    let wip_warning = match wip_limit_param(&mut tx.conn, target_col).await? {
+        Ok(warning) => warning,
+        Err(description) => {
+            return Ok(ActionTaken {
+                action: "move_card_cross_board".to_string(),
+                description,
+                success: false,
+                ..Default::default()
+            });
+        }
+    };
 
-    // Delete source card
-    state.cards.delete(source_card.id).await?;
+    // Create new card in target board
+    let new_card = CardRepository::create_on(
+        &mut tx.conn,
+        target_col.id,
+        &source_card.title,
+        source_card.body.as_deref(),
+        None,
+        visibility,
+        status,
+        source_card.start_date,
+        source_card.end_date,
+        source_card.due_date,
+        source_card.priority(),
+        user_id,
+    )
+    .await?;
+
+    // Keep the attachments with the copy, then move the source card to the trash so
+    // the move can be undone
+    AttachmentRepository::move_to_card_on(&mut tx.conn, source_card.id, new_card.id).await?;
+    CardRepository::move_to_trash_on(&mut tx.conn, source_card.id, user_id).await?;
+    let description = format!(
+        "Moved '{}' from '{}' to '{}'",
+        source_card.title, source_board.name, target_board.name
+    );
+    record_chat_actions(&mut tx.activity, source_board.id, user_id, &[&description]);
+    record_chat_actions(&mut tx.activity, target_board.id, user_id, &[&description]);
 
     info!(
         card = %source_card.title,
@@ -1269,18 +2415,26 @@ async fn execute_cross_board_move(
 
     Ok(ActionTaken {
         action: "move_card_cross_board".to_string(),
-        description: format!(
-            "Moved '{}' from '{}' to '{}' (column '{}')",
-            source_card.title, source_board.name, target_board.name, target_col.name
+        description: with_wip_warning(
+            format!(
+                "Moved '{}' from '{}' to '{}' (column '{}')",
+                source_card.title, source_board.name, target_board.name, target_col.name
+            ),
+            wip_warning,
         ),
         success: true,
+        undo: Some(UndoStep::UndoCrossBoardMove {
+            card_id: source_card.id,
+            copy_id: new_card.id,
+        }),
+        ..Default::default()
     })
 }
 
 /// Execute create_board action
-#[instrument(skip(state), fields(user_id = %user_id))]
+#[instrument(skip(conn), fields(user_id = %user_id))]
 async fn execute_create_board(
-    state: &AppState,
+    conn: &mut SqliteConnection,
     user_id: Uuid,
     action: &LlmAction,
 ) -> Result<ActionTaken> {
@@ -1299,12 +2453,13 @@ async fn execute_create_board(
             action: "create_board".to_string(),
             description: format!("Missing board name. Params: {:?}", action.params),
             success: false,
+            ..Default::default()
         });
     }
 
     info!(name = %name, description = ?description, "Creating new board");
 
-    let board = state.boards.create(name, description, user_id).await?;
+    let board = BoardRepository::create_on(conn, name, description, user_id).await?;
 
     info!(board_id = %board.id, name = %board.name, "Board created successfully");
 
@@ -1312,13 +2467,15 @@ async fn execute_create_board(
         action: "create_board".to_string(),
         description: format!("Created board '{}'", board.name),
         success: true,
+        undo: Some(UndoStep::TrashBoard { board_id: board.id }),
+        ..Default::default()
     })
 }
 
 /// Execute delete_board action (only owner can delete)
-#[instrument(skip(state), fields(user_id = %user_id))]
+#[instrument(skip(conn), fields(user_id = %user_id))]
 async fn execute_delete_board(
-    state: &AppState,
+    conn: &mut SqliteConnection,
     user_id: Uuid,
     action: &LlmAction,
 ) -> Result<ActionTaken> {
@@ -1333,23 +2490,23 @@ async fn execute_delete_board(
             action: "delete_board".to_string(),
             description: format!("Missing board name. Params: {:?}", action.params),
             success: false,
+            ..Default::default()
         });
     }
 
     info!(board_name = %board_name, "Attempting to delete board");
 
     // Find the board and check ownership
-    let board_result = find_board_by_name(state, user_id, board_name).await?;
-
-    let (board, role) = match board_result {
-        Some(br) => br,
-        None => {
-            warn!(board_name = %board_name, "Board not found for deletion");
-            return Ok(ActionTaken {
-                action: "delete_board".to_string(),
-                description: format!("Board '{}' not found", board_name),
-                success: false,
-            });
+    let (board, role) = match find_board_by_name_on(conn, user_id, board_name).await? {
+        BoardMatch::Found(candidate) => (candidate.board, candidate.role),
+        other => {
+            warn!(board_name = %board_name, "Board not resolved for deletion");
+            return Ok(board_not_resolved(
+                "delete_board",
+                "Board",
+                board_name,
+                &other,
+            ));
         }
     };
 
@@ -1363,11 +2520,12 @@ async fn execute_delete_board(
                 board.name, role
             ),
             success: false,
+            ..Default::default()
         });
     }
 
-    // Delete the board
-    state.boards.delete(board.id).await?;
+    // Move the board to the trash
+    BoardRepository::move_to_trash_on(conn, board.id, user_id).await?;
 
     info!(board_id = %board.id, name = %board.name, "Board deleted successfully");
 
@@ -1375,6 +2533,8 @@ async fn execute_delete_board(
         action: "delete_board".to_string(),
         description: format!("Deleted board '{}'", board.name),
         success: true,
+        undo: Some(UndoStep::RestoreBoard { board_id: board.id }),
+        ..Default::default()
     })
 }
 
@@ -1392,6 +2552,7 @@ async fn execute_web_search(state: &AppState, action: &LlmAction) -> Result<Acti
             action: "web_search".to_string(),
             description: format!("Missing search query. Params: {:?}", action.params),
             success: false,
+            ..Default::default()
         });
     }
 
@@ -1405,6 +2566,14 @@ async fn execute_web_search(state: &AppState, action: &LlmAction) -> Result<Acti
                 action: "web_search".to_string(),
                 description: results_text,
                 success: true,
+                sources: results
+                    .into_iter()
+                    .map(|r| ChatSource {
+                        title: r.title,
+                        url: r.url,
+                    })
+                    .collect(),
+                ..Default::default()
             })
         }
         Err(e) => {
@@ -1413,6 +2582,7 @@ async fn execute_web_search(state: &AppState, action: &LlmAction) -> Result<Acti
                 action: "web_search".to_string(),
                 description: format!("Search failed: {}", e),
                 success: false,
+                ..Default::default()
             })
         }
     }
@@ -1425,40 +2595,65 @@ pub async fn send_global_message(
     auth: AuthUser,
     Json(input): Json<SendChatRequest>,
 ) -> Result<Json<ChatResponse>> {
-    info!(message = %input.message, "Global chat message received");
+    Ok(Json(global_chat(&state, &auth.user, input.message).await?))
+}
 
-    // Store input message before moving
-    let user_message = input.message.clone();
+/// Answer a global chat message from `user` and run the actions the LLM asked for.
+/// Shared by the chat API and the Telegram bot.
+pub(crate) async fn global_chat(
+    state: &AppState,
+    user: &User,
+    message: String,
+) -> Result<ChatResponse> {
+    info!(message = %message, "Global chat message received");
 
     // Build global system prompt with all boards
-    let system_prompt =
-        build_global_system_prompt(&state, auth.user.id, auth.user.llm_context.as_deref()).await?;
+    let tools = action_tools(GLOBAL_CHAT_ACTIONS, true);
+    let system_prompt = build_global_system_prompt(
+        state,
+        user.id,
+        user.llm_context.as_deref(),
+        !tools.is_empty(),
+    )
+    .await?;
 
     debug!("Global system prompt built successfully");
 
-    // Create messages for Ollama
-    let messages = vec![
-        crate::services::ollama::OllamaMessage {
-            role: "system".to_string(),
-            content: system_prompt,
-        },
-        crate::services::ollama::OllamaMessage {
-            role: "user".to_string(),
-            content: input.message,
-        },
-    ];
+    // Create messages for the LLM
+    let (llm, options) = state.llm_for(user);
+    let conversation = Conversation {
+        llm,
+        options,
+        messages: vec![
+            LlmMessage {
+                role: "system".to_string(),
+                content: system_prompt,
+            },
+            LlmMessage {
+                role: "user".to_string(),
+                content: message,
+            },
+        ],
+    };
 
-    // Send to Ollama
+    // Send to the LLM
     info!("Sending global chat request to LLM");
-    let llm_response = state.ollama.chat(messages).await?;
+    let llm_reply = conversation
+        .llm
+        .chat(
+            conversation.messages.clone(),
+            tools,
+            conversation.options.clone(),
+        )
+        .await?;
     debug!(
-        response_length = llm_response.len(),
+        response_length = llm_reply.content.len(),
+        tool_calls = llm_reply.tool_calls.len(),
         "LLM response received"
     );
 
-    // Parse LLM response for actions
-    let mut actions_taken = Vec::new();
-    let parsed_actions = parse_llm_response(&llm_response);
+    // Tool calls, or the JSON actions of the text when the LLM called none
+    let parsed_actions = reply_actions(&llm_reply);
 
     if parsed_actions.is_empty() {
         debug!("No actions parsed from LLM response");
@@ -1469,21 +2664,26 @@ pub async fn send_global_message(
         );
     }
 
-    // Track the final response message (may be updated by web search)
-    let mut final_llm_response = llm_response.clone();
+    // Web searches are answered in a second LLM turn
+    let (mut actions_taken, final_reply) =
+        search_and_answer(state, &conversation, &parsed_actions, llm_reply).await?;
 
-    // Execute all parsed actions
-    for action in &parsed_actions {
+    // Execute all parsed actions, all or nothing
+    let first_action = actions_taken.len();
+    let mut tx = ActionTx::begin(state).await?;
+    let mut failed = false;
+    for (i, action) in parsed_actions.iter().enumerate() {
         let chat_action: ChatAction = action.action.parse().unwrap_or(ChatAction::Unknown);
 
-        // Skip read-only actions (except web search which we handle specially)
-        if chat_action.is_read_only() && chat_action != ChatAction::WebSearch {
+        // Skip read-only actions
+        if chat_action.is_read_only() {
             debug!(action = %action.action, "Skipping read-only action");
             continue;
         }
 
         info!(action = %action.action, "Executing global action");
-        let action_result = execute_global_action(&state, auth.user.id, action).await?;
+        let mut action_result = execute_global_action(state, &mut tx, user.id, action).await?;
+        remember_undo(&mut tx.conn, user.id, None, &mut action_result).await?;
 
         if action_result.success {
             info!(
@@ -1491,51 +2691,6 @@ pub async fn send_global_message(
                 description = %action_result.description,
                 "Global action executed successfully"
             );
-
-            // For web search, make a follow-up LLM call with the results
-            if chat_action == ChatAction::WebSearch {
-                info!("Making follow-up LLM call with search results");
-
-                let system_prompt_clone = build_global_system_prompt(
-                    &state,
-                    auth.user.id,
-                    auth.user.llm_context.as_deref(),
-                )
-                .await?;
-
-                let followup_messages = vec![
-                    crate::services::ollama::OllamaMessage {
-                        role: "system".to_string(),
-                        content: system_prompt_clone,
-                    },
-                    crate::services::ollama::OllamaMessage {
-                        role: "user".to_string(),
-                        content: user_message.clone(),
-                    },
-                    crate::services::ollama::OllamaMessage {
-                        role: "assistant".to_string(),
-                        content: llm_response.clone(),
-                    },
-                    crate::services::ollama::OllamaMessage {
-                        role: "user".to_string(),
-                        content: format!(
-                            "Here are the search results:\n\n{}\n\nBased on these results, please provide a helpful response to my original question. Use no_action since you're just providing information.",
-                            action_result.description
-                        ),
-                    },
-                ];
-
-                match state.ollama.chat(followup_messages).await {
-                    Ok(followup_response) => {
-                        info!("Follow-up LLM response received");
-                        final_llm_response = followup_response;
-                    }
-                    Err(e) => {
-                        warn!(error = %e, "Follow-up LLM call failed, using search results directly");
-                        // Keep the original response with search results shown
-                    }
-                }
-            }
         } else {
             warn!(
                 action = %action_result.action,
@@ -1544,12 +2699,23 @@ pub async fn send_global_message(
             );
         }
 
+        failed = !action_result.success;
         actions_taken.push(action_result);
+        if failed {
+            let skipped = roll_back(&mut actions_taken[first_action..], &parsed_actions[i + 1..]);
+            actions_taken.extend(skipped);
+            break;
+        }
+    }
+    if failed {
+        tx.rollback().await?;
+    } else {
+        tx.commit(state).await?;
     }
 
     // Extract a readable message from the response
-    let final_parsed = parse_llm_response(&final_llm_response);
-    let response_message = extract_readable_message(&final_llm_response, &final_parsed);
+    let final_parsed = reply_actions(&final_reply);
+    let response_message = extract_readable_message(&final_reply.content, &final_parsed);
 
     // Persist the chat message (global = no board_id)
     let actions_json = if actions_taken.is_empty() {
@@ -1561,8 +2727,8 @@ pub async fn send_global_message(
     state
         .chat_messages
         .create_global(
-            auth.user.id,
-            &user_message,
+            user.id,
+            conversation.user_message(),
             &response_message,
             actions_json.as_deref(),
         )
@@ -1574,19 +2740,23 @@ pub async fn send_global_message(
         "Global chat request completed"
     );
 
-    Ok(Json(ChatResponse {
+    Ok(ChatResponse {
         response: response_message,
         actions_taken,
-    }))
+        pending: None,
+    })
 }
 
 /// Get global chat history
 pub async fn get_global_history(
     State(state): State<AppState>,
     auth: AuthUser,
-) -> Result<Json<Vec<ChatMessageResponse>>> {
-    // Get last 50 global messages
-    let messages = state.chat_messages.list_global(auth.user.id, 50).await?;
+    OriginalUri(uri): OriginalUri,
+    Query(paging): Query<PageQuery>,
+) -> Result<Paginated<ChatMessageResponse>> {
+    // Pages count back from the newest message
+    let page = paging.page(DEFAULT_HISTORY_PAGE_SIZE);
+    let messages = state.chat_messages.list_global(auth.user.id, page).await?;
 
     // Convert to response format and reverse for chronological order
     let responses: Vec<ChatMessageResponse> = messages
@@ -1595,7 +2765,12 @@ pub async fn get_global_history(
         .rev()
         .collect();
 
-    Ok(Json(responses))
+    Ok(Paginated {
+        items: responses,
+        total: state.chat_messages.count_global(auth.user.id).await?,
+        page,
+        uri,
+    })
 }
 
 /// Clear global chat history
@@ -1611,9 +2786,9 @@ pub async fn clear_global_history(
 }
 
 /// Execute create_inbox_card action (creates a standalone card)
-#[instrument(skip(state), fields(user_id = %user_id))]
+#[instrument(skip(conn), fields(user_id = %user_id))]
 async fn execute_create_inbox_card(
-    state: &AppState,
+    conn: &mut SqliteConnection,
     user_id: Uuid,
     action: &LlmAction,
 ) -> Result<ActionTaken> {
@@ -1627,6 +2802,7 @@ async fn execute_create_inbox_card(
             action: "create_inbox_card".to_string(),
             description: "Missing card title".to_string(),
             success: false,
+            ..Default::default()
         });
     }
 
@@ -1639,33 +2815,62 @@ async fn execute_create_inbox_card(
         .and_then(|s| s.parse().ok())
         .unwrap_or(CardStatus::Open);
 
-    state
-        .cards
-        .create_standalone(
-            title,
-            body,
-            CardVisibility::Private,
-            status,
-            None,
-            None,
-            None,
-            user_id,
-        )
-        .await?;
+    let due_date = match date_param(action, "due_date") {
+        Ok(due_date) => due_date.flatten(),
+        Err(description) => {
+            return Ok(ActionTaken {
+                action: "create_inbox_card".to_string(),
+                description,
+                success: false,
+                ..Default::default()
+            });
+        }
+    };
+
+    let priority = match priority_param(action) {
+        Ok(priority) => priority.flatten(),
+        Err(description) => {
+            return Ok(ActionTaken {
+                action: "create_inbox_card".to_string(),
+                description,
+                success: false,
+                ..Default::default()
+            });
+        }
+    };
+
+    let card = CardRepository::create_standalone_on(
+        conn,
+        title,
+        body,
+        CardVisibility::Private,
+        status,
+        None,
+        None,
+        due_date,
+        priority,
+        user_id,
+    )
+    .await?;
 
     info!(title = %title, status = %status, "Created inbox card");
 
+    let due = due_date
+        .map(|date| format!(", due {}", date))
+        .unwrap_or_default();
     Ok(ActionTaken {
         action: "create_inbox_card".to_string(),
-        description: format!("Created inbox card '{}'", title),
+        description: format!("Created inbox card '{}'{}", title, due),
         success: true,
+        undo: Some(UndoStep::TrashCard { card_id: card.id }),
+        ..Default::default()
     })
 }
 
 /// Execute assign_card action (assigns a card to a board)
-#[instrument(skip(state), fields(user_id = %user_id))]
+#[instrument(skip(tx), fields(user_id = %user_id))]
 async fn execute_assign_card(
-    state: &AppState,
+    tx: &mut ActionTx,
     user_id: Uuid,
     action: &LlmAction,
 ) -> Result<ActionTaken> {
@@ -1688,6 +2893,7 @@ async fn execute_assign_card(
             action: "assign_card".to_string(),
             description: "Missing card title".to_string(),
             success: false,
+            ..Default::default()
         });
     }
 
@@ -1696,14 +2902,19 @@ async fn execute_assign_card(
             action: "assign_card".to_string(),
             description: "Missing board name".to_string(),
             success: false,
+            ..Default::default()
         });
     }
 
-    // Find the card
-    let cards = state.cards.list_by_owner(user_id).await?;
+    // Find the card, by a close title when none matches exactly
+    let cards = CardRepository::list_by_owner_on(&mut tx.conn, user_id).await?;
     let card = cards
         .iter()
-        .find(|c| c.title.to_lowercase() == card_title.to_lowercase());
+        .find(|c| c.title.to_lowercase() == card_title.to_lowercase())
+        .or_else(|| {
+            let title = closest_match(card_title, cards.iter().map(|c| c.title.as_str()))?;
+            cards.iter().find(|c| c.title == title)
+        });
 
     let card = match card {
         Some(c) => c,
@@ -1712,20 +2923,21 @@ async fn execute_assign_card(
                 action: "assign_card".to_string(),
                 description: format!("Card '{}' not found in inbox", card_title),
                 success: false,
+                ..Default::default()
             });
         }
     };
 
     // Find the board
-    let board_result = find_board_by_name(state, user_id, board_name).await?;
-    let (board, role) = match board_result {
-        Some(br) => br,
-        None => {
-            return Ok(ActionTaken {
-                action: "assign_card".to_string(),
-                description: format!("Board '{}' not found", board_name),
-                success: false,
-            });
+    let (board, role) = match find_board_by_name_on(&mut tx.conn, user_id, board_name).await? {
+        BoardMatch::Found(candidate) => (candidate.board, candidate.role),
+        other => {
+            return Ok(board_not_resolved(
+                "assign_card",
+                "Board",
+                board_name,
+                &other,
+            ));
         }
     };
 
@@ -1735,158 +2947,799 @@ async fn execute_assign_card(
             action: "assign_card".to_string(),
             description: format!("You don't have permission to edit board '{}'", board.name),
             success: false,
+            ..Default::default()
         });
     }
 
-    // Find column if specified
-    let column_id = if let Some(col_name) = column_name {
-        let columns = state.columns.list_by_board(board.id).await?;
-        columns
+    // Find column if specified, by a close name when none matches exactly
+    let columns = ColumnRepository::list_by_board_on(&mut tx.conn, board.id).await?;
+    let column = match column_name.filter(|n| !n.is_empty()) {
+        Some(col_name) => {
+            let found = columns
+                .iter()
+                .find(|c| c.name.to_lowercase() == col_name.to_lowercase())
+                .or_else(|| {
+                    let name = closest_match(col_name, columns.iter().map(|c| c.name.as_str()))?;
+                    columns.iter().find(|c| c.name == name)
+                });
+            match found {
+                Some(column) => Some(column),
+                None => {
+                    let names: Vec<&str> = columns.iter().map(|c| c.name.as_str()).collect();
+                    return Ok(ActionTaken {
+                        action: "assign_card".to_string(),
+                        description: format!(
+                            "Column '{}' not found on board '{}'. Available columns: {}",
+                            col_name,
+                            board.name,
+                            names.join(", ")
+                        ),
+                        success: false,
+                        ..Default::default()
+                    });
+                }
+            }
+        }
+        None => columns
             .iter()
-            .find(|c| c.name.to_lowercase() == col_name.to_lowercase())
-            .map(|c| c.id)
-    } else {
-        None
+            .find(|c| Some(c.id) == board.default_column_id),
+    };
+
+    if CardBoardRepository::is_card_on_board_on(&mut tx.conn, card.id, board.id).await? {
+        return Ok(ActionTaken {
+            action: "assign_card".to_string(),
+            description: format!("'{}' is already on board '{}'", card.title, board.name),
+            success: false,
+            ..Default::default()
+        });
+    }
+
+    let wip_warning = match column {
+        Some(column) => match wip_limit_param(&mut tx.conn, column).await? {
+            Ok(warning) => warning,
+            Err(description) => {
+                return Ok(ActionTaken {
+                    action: "assign_card".to_string(),
+                    description,
+                    success: false,
+                    ..Default::default()
+                });
+            }
+        },
+        None => None,
     };
 
     // Assign the card
-    state
-        .card_boards
-        .assign_card_to_board(card.id, board.id, column_id, None)
-        .await?;
+    CardBoardRepository::assign_card_to_board_on(
+        &mut tx.conn,
+        card.id,
+        board.id,
+        column.map(|c| c.id),
+        None,
+    )
+    .await?;
+    let description = match column {
+        Some(column) => format!(
+            "Assigned '{}' to board '{}' in column '{}'",
+            card.title, board.name, column.name
+        ),
+        None => format!("Assigned '{}' to board '{}'", card.title, board.name),
+    };
+    record_chat_actions(&mut tx.activity, board.id, user_id, &[&description]);
 
     info!(card = %card.title, board = %board.name, "Assigned card to board");
 
     Ok(ActionTaken {
         action: "assign_card".to_string(),
-        description: format!("Assigned '{}' to board '{}'", card.title, board.name),
+        description: with_wip_warning(description, wip_warning),
         success: true,
+        undo: Some(UndoStep::UnassignCard {
+            card_id: card.id,
+            board_id: board.id,
+        }),
+        ..Default::default()
     })
 }
 
-/// Execute update_status action (updates card status)
-#[instrument(skip(state), fields(user_id = %user_id))]
-async fn execute_update_status(
-    state: &AppState,
+/// Board named by a global chat action, empty when there is none
+fn board_param(action: &LlmAction) -> &str {
+    action.params["board"]
+        .as_str()
+        .or_else(|| action.params["board_name"].as_str())
+        .unwrap_or("")
+}
+
+/// Title of the card a card action applies to, empty when there is none
+fn card_param(action: &LlmAction) -> &str {
+    action.params["card"]
+        .as_str()
+        .or_else(|| action.params["card_title"].as_str())
+        .unwrap_or("")
+}
+
+/// Find a card on the board by title, ignoring case
+/// Apply one operation to every card matching a tag and/or column filter in a single transaction
+async fn execute_bulk_update_cards(
+    tx: &mut ActionTx,
+    board_id: Uuid,
     user_id: Uuid,
     action: &LlmAction,
 ) -> Result<ActionTaken> {
-    let card_title = action.params["card"]
-        .as_str()
-        .or_else(|| action.params["card_title"].as_str())
-        .unwrap_or("");
+    let failed = |description: String| {
+        Ok(ActionTaken {
+            action: "bulk_update_cards".to_string(),
+            description,
+            success: false,
+            ..Default::default()
+        })
+    };
+    let param = |name: &str| action.params[name].as_str().unwrap_or("").trim();
+    let operation = param("operation").to_lowercase();
+    let (tag_name, column_name) = (param("tag"), param("column"));
+    if tag_name.is_empty() && column_name.is_empty() {
+        return failed("Give a tag and/or a column to select the cards".to_string());
+    }
 
-    let status_str = action.params["status"]
-        .as_str()
-        .unwrap_or("");
+    let columns = ColumnRepository::list_by_board_on(&mut tx.conn, board_id).await?;
+    let tags = TagRepository::list_by_board_on(&mut tx.conn, board_id).await?;
+    let find_column = |name: &str| {
+        columns
+            .iter()
+            .find(|c| c.name.to_lowercase() == name.to_lowercase())
+    };
+    let find_tag = |name: &str| {
+        tags.iter()
+            .find(|t| t.name.to_lowercase() == name.to_lowercase())
+    };
 
-    if card_title.is_empty() {
+    let mut selection = Vec::new();
+    if !tag_name.is_empty() {
+        let Some(tag) = find_tag(tag_name) else {
+            return failed(format!("Tag '{}' not found", tag_name));
+        };
+        selection.push(format!("tagged '{}'", tag.name));
+    }
+    let source = if column_name.is_empty() {
+        None
+    } else {
+        let Some(col) = find_column(column_name) else {
+            return failed(format!("Column '{}' not found", column_name));
+        };
+        selection.push(format!("in '{}'", col.name));
+        Some(col)
+    };
+
+    let mut cards = Vec::new();
+    for col in columns
+        .iter()
+        .filter(|c| source.is_none_or(|s| s.id == c.id))
+    {
+        cards.extend(CardRepository::list_by_column_on(&mut tx.conn, col.id).await?);
+    }
+    if let Some(tag) = find_tag(tag_name) {
+        let tagged = TagRepository::list_card_ids_on(&mut tx.conn, tag.id).await?;
+        cards.retain(|c| tagged.contains(&c.id));
+    }
+    // Locked cards can still be moved, but not otherwise changed
+    let (locked, mut cards): (Vec<Card>, Vec<Card>) = cards
+        .into_iter()
+        .partition(|c| c.is_locked() && operation != "move");
+
+    let mut operations = Vec::new();
+    let mut undo = Vec::new();
+    let verb = match operation.as_str() {
+        "move" => {
+            let target = param("target_column");
+            let Some(col) = find_column(target) else {
+                return failed(format!("Column '{}' not found", target));
+            };
+            cards.retain(|c| c.column_id != Some(col.id));
+            for card in &cards {
+                operations.push(BulkCardOperation::Move {
+                    card_id: card.id,
+                    column_id: col.id,
+                    position: None,
+                });
+                if let Some(column_id) = card.column_id {
+                    undo.push(UndoStep::MoveCard {
+                        card_id: card.id,
+                        column_id,
+                        position: card.position,
+                    });
+                }
+            }
+            format!("Moved {{}} to '{}'", col.name)
+        }
+        "tag" => {
+            let name = param("add_tag");
+            let Some(tag) = find_tag(name) else {
+                return failed(format!("Tag '{}' not found", name));
+            };
+            // Undoing must not remove a tag a card already had
+            let tagged = TagRepository::list_card_ids_on(&mut tx.conn, tag.id).await?;
+            cards.retain(|c| !tagged.contains(&c.id));
+            for card in &cards {
+                operations.push(BulkCardOperation::Tag {
+                    card_id: card.id,
+                    tag_id: tag.id,
+                });
+                undo.push(UndoStep::RemoveTag {
+                    card_id: card.id,
+                    tag_id: tag.id,
+                });
+            }
+            format!("Tagged {{}} with '{}'", tag.name)
+        }
+        "status" => {
+            let status: CardStatus = match param("status").parse() {
+                Ok(status) => status,
+                Err(e) => return failed(e),
+            };
+            for card in &cards {
+                operations.push(BulkCardOperation::Status {
+                    card_id: card.id,
+                    status,
+                });
+                undo.push(UndoStep::SetStatus {
+                    card_id: card.id,
+                    status: card.status.clone(),
+                });
+            }
+            format!("Set {{}} to {}", status)
+        }
+        "archive" => {
+            for card in &cards {
+                operations.push(BulkCardOperation::Archive { card_id: card.id });
+                undo.push(UndoStep::UnarchiveCard { card_id: card.id });
+            }
+            "Archived {}".to_string()
+        }
+        "delete" => {
+            for card in &cards {
+                operations.push(BulkCardOperation::Delete { card_id: card.id });
+                undo.push(UndoStep::RestoreCard { card_id: card.id });
+            }
+            "Deleted {}".to_string()
+        }
+        _ => {
+            return failed(format!(
+                "Unknown operation '{}'. Use move, tag, status, archive or delete",
+                operation
+            ))
+        }
+    };
+
+    let skipped = if locked.is_empty() {
+        String::new()
+    } else {
+        format!(" (skipped {} locked)", locked.len())
+    };
+    let matched = format!(
+        "{} card{} {}",
+        cards.len(),
+        if cards.len() == 1 { "" } else { "s" },
+        selection.join(" and ")
+    );
+    if operations.is_empty() {
         return Ok(ActionTaken {
-            action: "update_status".to_string(),
-            description: "Missing card title".to_string(),
-            success: false,
+            action: "bulk_update_cards".to_string(),
+            description: format!("No cards {} to change{}", selection.join(" and "), skipped),
+            success: true,
+            ..Default::default()
         });
     }
 
-    let status: CardStatus = match status_str.parse() {
-        Ok(s) => s,
-        Err(_) => {
+    match apply_bulk_operations_on(
+        &mut tx.conn,
+        &mut tx.activity,
+        board_id,
+        user_id,
+        &operations,
+    )
+    .await
+    {
+        Ok(()) => {}
+        // A column over its WIP limit on a board that blocks refuses the whole batch
+        Err(AppError::Conflict(description)) => {
             return Ok(ActionTaken {
-                action: "update_status".to_string(),
-                description: format!(
-                    "Invalid status '{}'. Use: open, in_progress, done, or closed",
-                    status_str
-                ),
+                action: "bulk_update_cards".to_string(),
+                description,
                 success: false,
+                ..Default::default()
             });
         }
-    };
+        Err(e) => return Err(e),
+    }
+    Ok(ActionTaken {
+        action: "bulk_update_cards".to_string(),
+        description: format!("{}{}", verb.replacen("{}", &matched, 1), skipped),
+        success: true,
+        undo: Some(UndoStep::Batch { steps: undo }),
+        ..Default::default()
+    })
+}
 
-    // Find the card (search in user's owned cards first)
-    let cards = state.cards.list_by_owner(user_id).await?;
-    let card = cards
+/// Plan who to assign the board's unassigned open cards to, for each
+/// suggest_assignments action. Returns what was suggested and, when there is a plan
+/// the user may apply, the assign_users action that applies it.
+async fn suggest_assignments(
+    state: &AppState,
+    board_id: Uuid,
+    role: BoardRole,
+    actions: &[LlmAction],
+) -> Result<(Vec<ActionTaken>, Option<LlmAction>)> {
+    let requested = actions
         .iter()
-        .find(|c| c.title.to_lowercase() == card_title.to_lowercase());
+        .any(|a| a.action.parse::<ChatAction>() == Ok(ChatAction::SuggestAssignments));
+    if !requested {
+        return Ok((Vec::new(), None));
+    }
 
-    let card = match card {
-        Some(c) => c,
-        None => {
-            return Ok(ActionTaken {
-                action: "update_status".to_string(),
-                description: format!("Card '{}' not found", card_title),
-                success: false,
+    let cards = state.assignees.list_unassigned_for_board(board_id).await?;
+    let members = state.assignees.workload_for_board(board_id).await?;
+    let plan = assignments::plan(&cards, &members);
+
+    let workload: Vec<String> = members
+        .iter()
+        .map(|m| format!("{} ({} open)", m.name, m.open_cards))
+        .collect();
+    let description = if plan.is_empty() {
+        format!(
+            "No assignments to suggest: {} unassigned open card{}, {} member{} who can edit",
+            cards.len(),
+            if cards.len() == 1 { "" } else { "s" },
+            members.len(),
+            if members.len() == 1 { "" } else { "s" },
+        )
+    } else {
+        let lines: Vec<String> = plan
+            .iter()
+            .map(|(card, member)| format!("- '{}' to {}", card.title, member.name))
+            .collect();
+        format!(
+            "Workload: {}\nSuggested assignments:\n{}",
+            workload.join(", "),
+            lines.join("\n")
+        )
+    };
+
+    let apply = (!plan.is_empty() && role.can_edit()).then(|| LlmAction {
+        action: ChatAction::AssignUsers.to_string(),
+        params: serde_json::json!({
+            "assignments": plan
+                .iter()
+                .map(|(card, member)| serde_json::json!({
+                    "card_id": card.id,
+                    "card": card.title,
+                    "user_id": member.user_id,
+                    "user": member.name,
+                }))
+                .collect::<Vec<_>>(),
+        }),
+        message: format!(
+            "Assigned {} card{}",
+            plan.len(),
+            if plan.len() == 1 { "" } else { "s" }
+        ),
+    });
+
+    Ok((
+        vec![ActionTaken {
+            action: ChatAction::SuggestAssignments.to_string(),
+            description,
+            success: true,
+            ..Default::default()
+        }],
+        apply,
+    ))
+}
+
+/// Assign users to cards of the board in bulk, as planned by suggest_assignments.
+/// Cards that left the board or got locked, and users who lost access, are skipped.
+async fn execute_assign_users(
+    tx: &mut ActionTx,
+    board_id: Uuid,
+    user_id: Uuid,
+    action: &LlmAction,
+) -> Result<ActionTaken> {
+    let id = |value: &serde_json::Value, name: &str| {
+        value[name].as_str().and_then(|id| Uuid::parse_str(id).ok())
+    };
+    let Some(planned) = action.params["assignments"].as_array() else {
+        return Ok(ActionTaken {
+            action: "assign_users".to_string(),
+            description: format!("Missing assignments. Params: {:?}", action.params),
+            success: false,
+            ..Default::default()
+        });
+    };
+
+    let mut assigned = Vec::new();
+    let mut skipped = 0;
+    let mut undo = Vec::new();
+    for assignment in planned {
+        let (Some(card_id), Some(assignee)) =
+            (id(assignment, "card_id"), id(assignment, "user_id"))
+        else {
+            skipped += 1;
+            continue;
+        };
+        let Some(card) = CardRepository::find_on(&mut tx.conn, card_id).await? else {
+            skipped += 1;
+            continue;
+        };
+        let Some(user) = UserRepository::find_on(&mut tx.conn, assignee).await? else {
+            skipped += 1;
+            continue;
+        };
+        if card.is_locked()
+            || !CardBoardRepository::is_card_on_board_on(&mut tx.conn, card_id, board_id).await?
+            || !can_view_card_on(&mut tx.conn, card_id, user.id).await?
+        {
+            skipped += 1;
+            continue;
+        }
+
+        if AssigneeRepository::add_on(&mut tx.conn, card_id, user.id, user_id).await? {
+            tx.activity.record_for_card(
+                BoardEventKind::AssigneeAdded,
+                card_id,
+                user.id,
+                user_id,
+                Some(serde_json::json!({"name": user.name})),
+            );
+            undo.push(UndoStep::UnassignUser {
+                card_id,
+                user_id: user.id,
             });
+            assigned.push(format!("'{}' to {}", card.title, user.name));
+        }
+    }
+
+    let skipped = if skipped == 0 {
+        String::new()
+    } else {
+        format!(" (skipped {})", skipped)
+    };
+    let description = if assigned.is_empty() {
+        format!("No cards assigned{}", skipped)
+    } else {
+        format!("Assigned {}{}", assigned.join(", "), skipped)
+    };
+    Ok(ActionTaken {
+        action: "assign_users".to_string(),
+        description,
+        success: true,
+        undo: (!undo.is_empty()).then_some(UndoStep::Batch { steps: undo }),
+        ..Default::default()
+    })
+}
+
+async fn find_board_card(
+    conn: &mut SqliteConnection,
+    board_id: Uuid,
+    title: &str,
+) -> Result<Option<Card>> {
+    for col in ColumnRepository::list_by_board_on(conn, board_id).await? {
+        let cards = CardRepository::list_by_column_on(conn, col.id).await?;
+        if let Some(card) = cards
+            .into_iter()
+            .find(|c| c.title.to_lowercase() == title.to_lowercase())
+        {
+            return Ok(Some(card));
         }
+    }
+    Ok(None)
+}
+
+/// Execute a card action on one of the user's own cards, when no board is named
+#[instrument(skip(tx), fields(user_id = %user_id))]
+async fn execute_owned_card_action(
+    tx: &mut ActionTx,
+    user_id: Uuid,
+    chat_action: ChatAction,
+    action: &LlmAction,
+) -> Result<ActionTaken> {
+    let card_title = card_param(action);
+    if card_title.is_empty() {
+        return Ok(ActionTaken {
+            action: chat_action.to_string(),
+            description: "Missing card title".to_string(),
+            success: false,
+            ..Default::default()
+        });
+    }
+
+    let cards = CardRepository::list_by_owner_on(&mut tx.conn, user_id).await?;
+    match cards
+        .iter()
+        .find(|c| c.title.to_lowercase() == card_title.to_lowercase())
+    {
+        Some(card) => execute_card_action(tx, user_id, chat_action, card, action).await,
+        None => Ok(ActionTaken {
+            action: chat_action.to_string(),
+            description: format!("Card '{}' not found", card_title),
+            success: false,
+            ..Default::default()
+        }),
+    }
+}
+
+/// Execute update_status, update_card or add_comment on a card the user may edit
+async fn execute_card_action(
+    tx: &mut ActionTx,
+    user_id: Uuid,
+    chat_action: ChatAction,
+    card: &Card,
+    action: &LlmAction,
+) -> Result<ActionTaken> {
+    match chat_action {
+        ChatAction::AddComment => add_comment(tx, user_id, card, action).await,
+        _ => update_card_fields(tx, user_id, chat_action, card, action).await,
+    }
+}
+
+/// Check the WIP limit of a column a chat action puts one card into: the warning to
+/// add to the description, if any, or the reason a board that blocks refuses the card
+async fn wip_limit_param(
+    conn: &mut SqliteConnection,
+    column: &Column,
+) -> Result<std::result::Result<Option<String>, String>> {
+    match check_wip_limit_on(conn, column, 1).await {
+        Ok(warning) => Ok(Ok(warning)),
+        Err(AppError::Conflict(reason)) => Ok(Err(reason)),
+        Err(e) => Err(e),
+    }
+}
+
+/// An action's description followed by the WIP limit warning it gave, if any
+fn with_wip_warning(description: String, warning: Option<String>) -> String {
+    match warning {
+        Some(warning) => format!("{} ({})", description, warning),
+        None => description,
+    }
+}
+
+/// Read a date param: None when it is absent, Some(None) when it is empty to clear the date
+fn date_param(
+    action: &LlmAction,
+    name: &str,
+) -> std::result::Result<Option<Option<NaiveDate>>, String> {
+    match &action.params[name] {
+        serde_json::Value::Null => Ok(None),
+        serde_json::Value::String(s) if s.trim().is_empty() => Ok(Some(None)),
+        serde_json::Value::String(s) => NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d")
+            .map(|date| Some(Some(date)))
+            .map_err(|_| format!("Invalid {} '{}'. Use YYYY-MM-DD", name, s)),
+        other => Err(format!("Invalid {} {}. Use YYYY-MM-DD", name, other)),
+    }
+}
+
+/// Read the priority param: None when it is absent, Some(None) when it is empty to clear it
+fn priority_param(action: &LlmAction) -> std::result::Result<Option<Option<CardPriority>>, String> {
+    match &action.params["priority"] {
+        serde_json::Value::Null => Ok(None),
+        serde_json::Value::String(s) if s.trim().is_empty() => Ok(Some(None)),
+        serde_json::Value::String(s) => s
+            .trim()
+            .parse()
+            .map(|priority| Some(Some(priority)))
+            .map_err(|_| {
+                format!(
+                    "Invalid priority '{}'. Use: low, medium, high, or urgent",
+                    s
+                )
+            }),
+        other => Err(format!(
+            "Invalid priority {}. Use: low, medium, high, or urgent",
+            other
+        )),
+    }
+}
+
+/// Apply the fields of an update_status or update_card action to a card the user may edit
+async fn update_card_fields(
+    tx: &mut ActionTx,
+    user_id: Uuid,
+    chat_action: ChatAction,
+    card: &Card,
+    action: &LlmAction,
+) -> Result<ActionTaken> {
+    let failed = |description: String| ActionTaken {
+        action: chat_action.to_string(),
+        description,
+        success: false,
+        ..Default::default()
     };
 
-    // Update the status
-    state.cards.update_status(card.id, status).await?;
+    if card.is_locked() {
+        return Ok(failed(format!("Card '{}' is locked", card.title)));
+    }
 
-    info!(card = %card.title, status = %status, "Updated card status");
+    // update_status always sets a status, update_card only when one is given
+    let status = match action.params["status"].as_str() {
+        None if chat_action == ChatAction::UpdateCard => None,
+        status_str => match status_str.unwrap_or("").parse::<CardStatus>() {
+            Ok(status) => Some(status),
+            Err(_) => {
+                return Ok(failed(format!(
+                    "Invalid status '{}'. Use: open, in_progress, done, or closed",
+                    status_str.unwrap_or("")
+                )));
+            }
+        },
+    };
+
+    let (mut title, mut body) = (None, None);
+    let (mut start_date, mut end_date, mut due_date) = (None, None, None);
+    let mut priority = None;
+    if chat_action == ChatAction::UpdateCard {
+        title = action.params["title"]
+            .as_str()
+            .map(str::trim)
+            .filter(|t| !t.is_empty());
+        body = action.params["body"]
+            .as_str()
+            .or_else(|| action.params["description"].as_str())
+            .map(|b| Some(b.trim()).filter(|b| !b.is_empty()));
+        let dates = (
+            date_param(action, "start_date"),
+            date_param(action, "end_date"),
+            date_param(action, "due_date"),
+        );
+        match dates {
+            (Ok(start), Ok(end), Ok(due)) => (start_date, end_date, due_date) = (start, end, due),
+            (Err(e), _, _) | (_, Err(e), _) | (_, _, Err(e)) => return Ok(failed(e)),
+        }
+        match priority_param(action) {
+            Ok(p) => priority = p,
+            Err(e) => return Ok(failed(e)),
+        }
+
+        if title.is_none()
+            && body.is_none()
+            && status.is_none()
+            && start_date.is_none()
+            && end_date.is_none()
+            && due_date.is_none()
+            && priority.is_none()
+        {
+            return Ok(failed(format!(
+                "Nothing to update on '{}'. Received params: {:?}",
+                card.title, action.params
+            )));
+        }
+    }
+
+    let updated = CardRepository::update_on(
+        &mut tx.conn,
+        card.id,
+        title,
+        body,
+        None,
+        status,
+        start_date,
+        end_date,
+        due_date,
+        priority,
+        None,
+    )
+    .await?;
+    CardRevisionRepository::record_on(&mut tx.conn, card, &updated, user_id).await?;
+    if let Some(details) = changes(card, &updated) {
+        tx.activity.record_for_card(
+            BoardEventKind::CardUpdated,
+            card.id,
+            card.id,
+            user_id,
+            Some(details),
+        );
+    }
+
+    info!(card = %card.title, action = %chat_action, "Updated card");
+
+    let (description, undo) = if chat_action == ChatAction::UpdateStatus {
+        (
+            format!("Updated '{}' status to '{}'", card.title, updated.status),
+            UndoStep::SetStatus {
+                card_id: card.id,
+                status: card.status.clone(),
+            },
+        )
+    } else {
+        let mut fields = Vec::new();
+        if title.is_some() {
+            fields.push(format!("title to '{}'", updated.title));
+        }
+        if body.is_some() {
+            fields.push("description".to_string());
+        }
+        if status.is_some() {
+            fields.push(format!("status to '{}'", updated.status));
+        }
+        match (priority, &updated.priority) {
+            (None, _) => {}
+            (Some(_), Some(value)) => fields.push(format!("priority to '{}'", value)),
+            (Some(_), None) => fields.push("cleared priority".to_string()),
+        }
+        for (name, change, value) in [
+            ("start date", start_date, updated.start_date),
+            ("end date", end_date, updated.end_date),
+            ("due date", due_date, updated.due_date),
+        ] {
+            match (change, value) {
+                (None, _) => {}
+                (Some(_), Some(date)) => fields.push(format!("{} to {}", name, date)),
+                (Some(_), None) => fields.push(format!("cleared {}", name)),
+            }
+        }
+        (
+            format!("Updated '{}': {}", card.title, fields.join(", ")),
+            UndoStep::RestoreCardFields {
+                card_id: card.id,
+                title: card.title.clone(),
+                body: card.body.clone(),
+                status: card.status.clone(),
+                start_date: card.start_date,
+                end_date: card.end_date,
+                due_date: card.due_date,
+                priority: card.priority.clone(),
+            },
+        )
+    };
 
     Ok(ActionTaken {
-        action: "update_status".to_string(),
-        description: format!("Updated '{}' status to '{}'", card.title, status),
+        action: chat_action.to_string(),
+        description,
         success: true,
+        undo: Some(undo),
+        ..Default::default()
     })
 }
 
-/// Execute add_comment action - add a comment to a card
-async fn execute_add_comment(
-    state: &AppState,
+/// Comment on a card as the chatting user
+async fn add_comment(
+    tx: &mut ActionTx,
     user_id: Uuid,
+    card: &Card,
     action: &LlmAction,
 ) -> Result<ActionTaken> {
-    let card_title = action.params["card"]
-        .as_str()
-        .or_else(|| action.params["card_title"].as_str())
-        .unwrap_or("");
-
     let comment_body = action.params["comment"]
         .as_str()
         .or_else(|| action.params["body"].as_str())
         .or_else(|| action.params["text"].as_str())
         .or_else(|| action.params["content"].as_str())
-        .unwrap_or("");
+        .unwrap_or("")
+        .trim();
 
-    if card_title.is_empty() {
+    if comment_body.is_empty() {
         return Ok(ActionTaken {
             action: "add_comment".to_string(),
-            description: "Missing card title".to_string(),
+            description: "Missing comment body".to_string(),
             success: false,
+            ..Default::default()
         });
     }
 
-    if comment_body.is_empty() {
+    if card.is_locked() {
         return Ok(ActionTaken {
             action: "add_comment".to_string(),
-            description: "Missing comment body".to_string(),
+            description: format!("Card '{}' is locked", card.title),
             success: false,
+            ..Default::default()
         });
     }
 
-    // Find the card (search in user's owned cards first)
-    let cards = state.cards.list_by_owner(user_id).await?;
-    let card = cards
-        .iter()
-        .find(|c| c.title.to_lowercase() == card_title.to_lowercase());
-
-    let card = match card {
-        Some(c) => c,
-        None => {
-            return Ok(ActionTaken {
-                action: "add_comment".to_string(),
-                description: format!("Card '{}' not found", card_title),
-                success: false,
-            });
-        }
-    };
-
     // Add the comment
-    state
-        .comments
-        .create(card.id, user_id, comment_body)
-        .await?;
+    let comment =
+        CommentRepository::create_on(&mut tx.conn, card.id, user_id, comment_body).await?;
+    tx.activity.record_for_card(
+        BoardEventKind::CommentCreated,
+        card.id,
+        comment.id,
+        user_id,
+        Some(serde_json::json!({"body": comment.body})),
+    );
 
     info!(card = %card.title, "Added comment to card");
 
@@ -1894,5 +3747,518 @@ async fn execute_add_comment(
         action: "add_comment".to_string(),
         description: format!("Added comment to '{}'", card.title),
         success: true,
+        undo: Some(UndoStep::DeleteComment {
+            comment_id: comment.id,
+        }),
+        comment: Some(ChatComment {
+            id: comment.id,
+            card_id: card.id,
+            card_title: card.title.clone(),
+            body: comment.body,
+        }),
+        ..Default::default()
     })
 }
+
+// =============================================================================
+// Undo
+// =============================================================================
+
+/// Save how to undo a successful action, giving it the id it can be undone with
+async fn remember_undo(
+    conn: &mut SqliteConnection,
+    user_id: Uuid,
+    board_id: Option<Uuid>,
+    action: &mut ActionTaken,
+) -> Result<()> {
+    if let Some(undo) = action.undo.as_ref().filter(|_| action.success) {
+        let saved = ChatActionRepository::create_on(
+            conn,
+            user_id,
+            board_id,
+            &action.action,
+            &action.description,
+            undo,
+        )
+        .await?;
+        action.id = Some(saved.id);
+    }
+    Ok(())
+}
+
+/// Report a multi-action request whose transaction was rolled back once one of its
+/// actions failed: the actions before it as rolled back, those after it as skipped
+fn roll_back(done: &mut [ActionTaken], remaining: &[LlmAction]) -> Vec<ActionTaken> {
+    for action in done.iter_mut().filter(|a| a.success) {
+        info!(
+            action = %action.action,
+            description = %action.description,
+            "Chat action rolled back"
+        );
+        action.description = format!("Rolled back: {}", action.description);
+        action.success = false;
+        action.rolled_back = true;
+        // The saved undo step went with the transaction
+        action.id = None;
+        action.comment = None;
+    }
+
+    remaining
+        .iter()
+        .filter(|a| !is_read_only_action(a))
+        .map(|a| ActionTaken {
+            action: a.action.clone(),
+            description: "Skipped because an earlier action failed".to_string(),
+            success: false,
+            ..Default::default()
+        })
+        .collect()
+}
+
+/// Undo an action the assistant took for the current user
+pub async fn undo_action(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(action_id): Path<Uuid>,
+) -> Result<Json<ActionTaken>> {
+    let action = state
+        .chat_actions
+        .find_for_user(action_id, auth.user.id)
+        .await?
+        .ok_or(AppError::NotFound("chat_action"))?;
+
+    let mut tx = ActionTx::begin(&state).await?;
+    undo(&mut tx, auth.user.id, &action).await?;
+    tx.commit(&state).await?;
+    Ok(Json(undone(&action)))
+}
+
+/// Execute the undo action: reverse the user's last action in the same chat
+async fn execute_undo(
+    tx: &mut ActionTx,
+    user_id: Uuid,
+    board_id: Option<Uuid>,
+) -> Result<ActionTaken> {
+    let Some(action) = ChatActionRepository::find_last_on(&mut tx.conn, user_id, board_id).await?
+    else {
+        return Ok(ActionTaken {
+            action: "undo".to_string(),
+            description: "Nothing to undo".to_string(),
+            success: false,
+            ..Default::default()
+        });
+    };
+
+    match undo(tx, user_id, &action).await {
+        Ok(()) => Ok(undone(&action)),
+        Err(e @ (AppError::Database(_) | AppError::Internal(_))) => Err(e),
+        Err(e) => Ok(ActionTaken {
+            action: "undo".to_string(),
+            description: format!("Could not undo '{}': {}", action.description, e),
+            success: false,
+            ..Default::default()
+        }),
+    }
+}
+
+fn undone(action: &UndoableAction) -> ActionTaken {
+    ActionTaken {
+        action: "undo".to_string(),
+        description: format!("Undid '{}'", action.description),
+        success: true,
+        ..Default::default()
+    }
+}
+
+/// Reverse an action and mark it as undone
+async fn undo(tx: &mut ActionTx, user_id: Uuid, action: &UndoableAction) -> Result<()> {
+    if action.undone_at.is_some() {
+        return Err(AppError::BadRequest(
+            "Action was already undone".to_string(),
+        ));
+    }
+    let step = action
+        .undo_step()
+        .ok_or_else(|| AppError::Internal("Invalid undo step".to_string()))?;
+
+    apply_undo_step(tx, user_id, step).await?;
+    if !ChatActionRepository::mark_undone_on(&mut tx.conn, action.id).await? {
+        return Err(AppError::BadRequest(
+            "Action was already undone".to_string(),
+        ));
+    }
+
+    info!(action = %action.action, description = %action.description, "Chat action undone");
+    if let Some(board_id) = action.board_id {
+        let description = format!("Undid '{}'", action.description);
+        record_chat_actions(&mut tx.activity, board_id, user_id, &[&description]);
+    }
+    Ok(())
+}
+
+/// Fail unless the user can edit the card and it is unlocked
+async fn require_card_editor(
+    conn: &mut SqliteConnection,
+    card_id: Uuid,
+    user_id: Uuid,
+) -> Result<()> {
+    if !can_edit_card_on(conn, card_id, user_id).await? {
+        return Err(AppError::Forbidden);
+    }
+    CardRepository::ensure_unlocked_on(conn, card_id).await
+}
+
+/// Apply an undo step, with the same access checks as the matching API endpoint
+async fn apply_undo_step(tx: &mut ActionTx, user_id: Uuid, step: UndoStep) -> Result<()> {
+    match step {
+        UndoStep::TrashCard { card_id } => {
+            require_card_editor(&mut tx.conn, card_id, user_id).await?;
+            CardRepository::move_to_trash_on(&mut tx.conn, card_id, user_id).await
+        }
+        UndoStep::TrashColumn { column_id } => {
+            let column = ColumnRepository::find_on(&mut tx.conn, column_id)
+                .await?
+                .ok_or(AppError::NotFound("column"))?;
+            require_editor(&mut tx.conn, column.board_id, user_id).await?;
+            ColumnRepository::move_to_trash_on(&mut tx.conn, column_id, user_id).await
+        }
+        UndoStep::TrashBoard { board_id } => {
+            let board = BoardRepository::find_on(&mut tx.conn, board_id)
+                .await?
+                .ok_or(AppError::NotFound("board"))?;
+            if board.owner_id != user_id {
+                return Err(AppError::Forbidden);
+            }
+            BoardRepository::move_to_trash_on(&mut tx.conn, board_id, user_id).await
+        }
+        UndoStep::RestoreCard { card_id } => {
+            restore_on(
+                &mut tx.conn,
+                &mut tx.activity,
+                TrashKind::Card,
+                card_id,
+                user_id,
+            )
+            .await
+        }
+        UndoStep::RestoreColumn { column_id } => {
+            restore_on(
+                &mut tx.conn,
+                &mut tx.activity,
+                TrashKind::Column,
+                column_id,
+                user_id,
+            )
+            .await
+        }
+        UndoStep::RestoreBoard { board_id } => {
+            restore_on(
+                &mut tx.conn,
+                &mut tx.activity,
+                TrashKind::Board,
+                board_id,
+                user_id,
+            )
+            .await
+        }
+        UndoStep::MoveCard {
+            card_id,
+            column_id,
+            position,
+        } => {
+            // Like the move endpoint, this works on locked cards too
+            if !can_edit_card_on(&mut tx.conn, card_id, user_id).await? {
+                return Err(AppError::Forbidden);
+            }
+            let column = ColumnRepository::find_on(&mut tx.conn, column_id)
+                .await?
+                .ok_or(AppError::NotFound("column"))?;
+            require_editor(&mut tx.conn, column.board_id, user_id).await?;
+            let card = CardRepository::get_on(&mut tx.conn, card_id).await?;
+            if card.column_id != Some(column_id) {
+                check_wip_limit_on(&mut tx.conn, &column, 1).await?;
+            }
+
+            // Cards may have moved since; keep the position within the column
+            let others = CardBoardRepository::list_positions_on(
+                &mut tx.conn,
+                column.board_id,
+                Some(column_id),
+            )
+            .await?
+            .iter()
+            .filter(|p| p.id != card_id)
+            .count() as i32;
+            CardBoardRepository::move_card_in_board_on(
+                &mut tx.conn,
+                card_id,
+                column.board_id,
+                Some(column_id),
+                position.min(others),
+            )
+            .await?;
+            let moved = CardRepository::get_on(&mut tx.conn, card_id).await?;
+            sync_card_status_on(&mut tx.conn, &mut tx.activity, user_id, moved, &column).await?;
+            Ok(())
+        }
+        UndoStep::DeleteTag { tag_id } => {
+            let tag = TagRepository::find_on(&mut tx.conn, tag_id)
+                .await?
+                .ok_or(AppError::NotFound("tag"))?;
+            let board_id = tag.board_id.ok_or(AppError::NotFound("tag"))?;
+            require_editor(&mut tx.conn, board_id, user_id).await?;
+            TagRepository::delete_on(&mut tx.conn, tag_id).await
+        }
+        UndoStep::RecreateTag {
+            board_id,
+            name,
+            color,
+            linked_tag_id,
+            card_ids,
+        } => {
+            require_editor(&mut tx.conn, board_id, user_id).await?;
+            let tag = TagRepository::create_on(&mut tx.conn, board_id, &name, &color).await?;
+            if let Some(global_id) = linked_tag_id {
+                if TagRepository::find_on(&mut tx.conn, global_id)
+                    .await?
+                    .is_some()
+                {
+                    TagRepository::link_on(&mut tx.conn, tag.id, global_id).await?;
+                }
+            }
+            for card_id in card_ids {
+                if CardRepository::find_on(&mut tx.conn, card_id)
+                    .await?
+                    .is_some()
+                {
+                    TagRepository::add_to_card_on(&mut tx.conn, card_id, tag.id).await?;
+                }
+            }
+            Ok(())
+        }
+        UndoStep::RemoveTag { card_id, tag_id } => {
+            require_card_editor(&mut tx.conn, card_id, user_id).await?;
+            TagRepository::remove_from_card_on(&mut tx.conn, card_id, tag_id).await
+        }
+        UndoStep::SetStatus { card_id, status } => {
+            require_card_editor(&mut tx.conn, card_id, user_id).await?;
+            let status: CardStatus = status.parse().map_err(AppError::Internal)?;
+            CardRepository::update_status_on(&mut tx.conn, card_id, status).await?;
+            Ok(())
+        }
+        UndoStep::RestoreCardFields {
+            card_id,
+            title,
+            body,
+            status,
+            start_date,
+            end_date,
+            due_date,
+            priority,
+        } => {
+            require_card_editor(&mut tx.conn, card_id, user_id).await?;
+            let status: CardStatus = status.parse().map_err(AppError::Internal)?;
+            let priority = priority
+                .map(|p| p.parse::<CardPriority>())
+                .transpose()
+                .map_err(AppError::Internal)?;
+            let before = CardRepository::get_on(&mut tx.conn, card_id).await?;
+            let restored = CardRepository::update_on(
+                &mut tx.conn,
+                card_id,
+                Some(&title),
+                Some(body.as_deref()),
+                None,
+                Some(status),
+                Some(start_date),
+                Some(end_date),
+                Some(due_date),
+                Some(priority),
+                None,
+            )
+            .await?;
+            CardRevisionRepository::record_on(&mut tx.conn, &before, &restored, user_id).await?;
+            Ok(())
+        }
+        UndoStep::DeleteComment { comment_id } => {
+            let comment = CommentRepository::find_on(&mut tx.conn, comment_id)
+                .await?
+                .ok_or(AppError::NotFound("comment"))?;
+            if comment.user_id != user_id {
+                return Err(AppError::Forbidden);
+            }
+            CardRepository::ensure_unlocked_on(&mut tx.conn, comment.card_id).await?;
+            CommentRepository::delete_on(&mut tx.conn, comment_id).await
+        }
+        UndoStep::UnassignCard { card_id, board_id } => {
+            require_editor(&mut tx.conn, board_id, user_id).await?;
+            CardBoardRepository::remove_card_from_board_on(&mut tx.conn, card_id, board_id).await
+        }
+        UndoStep::UnarchiveCard { card_id } => {
+            require_card_editor(&mut tx.conn, card_id, user_id).await?;
+            CardRepository::set_archived_on(&mut tx.conn, card_id, false).await?;
+            Ok(())
+        }
+        UndoStep::UnassignUser {
+            card_id,
+            user_id: assignee,
+        } => {
+            require_card_editor(&mut tx.conn, card_id, user_id).await?;
+            CardRepository::ensure_unlocked_on(&mut tx.conn, card_id).await?;
+            AssigneeRepository::remove_on(&mut tx.conn, card_id, assignee).await?;
+            Ok(())
+        }
+        UndoStep::Batch { steps } => {
+            for step in steps.into_iter().rev() {
+                Box::pin(apply_undo_step(tx, user_id, step)).await?;
+            }
+            Ok(())
+        }
+        UndoStep::UndoCrossBoardMove { card_id, copy_id } => {
+            require_card_editor(&mut tx.conn, copy_id, user_id).await?;
+            restore_on(
+                &mut tx.conn,
+                &mut tx.activity,
+                TrashKind::Card,
+                card_id,
+                user_id,
+            )
+            .await?;
+            AttachmentRepository::move_to_card_on(&mut tx.conn, copy_id, card_id).await?;
+            CardRepository::move_to_trash_on(&mut tx.conn, copy_id, user_id).await
+        }
+    }
+}
+
+/// Export chat history (global or board) as Markdown or JSON
+pub async fn export_history(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Query(query): Query<ChatExportQuery>,
+) -> Result<Response> {
+    if let (Some(from), Some(to)) = (query.from, query.to) {
+        if from > to {
+            return Err(AppError::BadRequest(
+                "'from' must not be after 'to'".to_string(),
+            ));
+        }
+    }
+
+    let (board_id, board_name) = match query.scope {
+        ChatExportScope::Global => (None, None),
+        ChatExportScope::Board => {
+            let board_id = query.board_id.ok_or_else(|| {
+                AppError::BadRequest("board_id is required for board scope".to_string())
+            })?;
+            state
+                .boards
+                .get_user_role(board_id, auth.user.id)
+                .await?
+                .ok_or(AppError::Forbidden)?;
+            let board = state.boards.get_by_id(board_id).await?;
+            (Some(board_id), Some(board.name))
+        }
+    };
+
+    let messages = state
+        .chat_messages
+        .list_for_export(auth.user.id, board_id, query.from, query.to)
+        .await?;
+
+    let export = ChatExport {
+        scope: query.scope,
+        board_id,
+        board_name,
+        from: query.from,
+        to: query.to,
+        exported_at: Utc::now(),
+        messages: messages.into_iter().map(|m| m.into_response()).collect(),
+    };
+
+    let filename = format!(
+        "chat-{}-{}",
+        board_id.map_or("global".to_string(), |id| id.to_string()),
+        export.exported_at.format("%Y%m%d")
+    );
+
+    let response = match query.format {
+        ChatExportFormat::Json => (
+            [(
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}.json\"", filename),
+            )],
+            Json(export),
+        )
+            .into_response(),
+        ChatExportFormat::Md => (
+            [
+                (
+                    header::CONTENT_TYPE,
+                    "text/markdown; charset=utf-8".to_string(),
+                ),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!("attachment; filename=\"{}.md\"", filename),
+                ),
+            ],
+            export.to_markdown(),
+        )
+            .into_response(),
+    };
+
+    Ok(response)
+}
+
+const DEFAULT_STATS_DAYS: u32 = 30;
+const MAX_STATS_DAYS: u32 = 365;
+const TOP_FAILURES: usize = 10;
+
+/// Success rates of the chat assistant's actions on a board, with the most common failures
+pub async fn get_stats(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(board_id): Path<Uuid>,
+    Query(query): Query<ChatStatsQuery>,
+) -> Result<Json<ChatStatsResponse>> {
+    state
+        .boards
+        .get_user_role(board_id, auth.user.id)
+        .await?
+        .ok_or(AppError::Forbidden)?;
+
+    let days = query.days.unwrap_or(DEFAULT_STATS_DAYS);
+    if days == 0 || days > MAX_STATS_DAYS {
+        return Err(AppError::Validation(format!(
+            "days must be between 1 and {}",
+            MAX_STATS_DAYS
+        )));
+    }
+
+    let from = Utc::now().date_naive() - chrono::Duration::days(days as i64 - 1);
+    let messages: Vec<ChatMessageResponse> = state
+        .chat_messages
+        .list_for_export(auth.user.id, Some(board_id), Some(from), None)
+        .await?
+        .into_iter()
+        .map(|m| m.into_response())
+        .collect();
+
+    let stats = chat_stats::aggregate(
+        messages.iter().flat_map(|m| {
+            m.actions_taken
+                .iter()
+                .map(move |a| (m.created_at.date_naive(), a))
+        }),
+        TOP_FAILURES,
+    );
+
+    Ok(Json(ChatStatsResponse {
+        board_id,
+        days,
+        messages: messages.len() as u32,
+        overall: stats.overall,
+        by_action: stats.by_action,
+        daily: stats.daily,
+        top_failures: stats.top_failures,
+    }))
+}
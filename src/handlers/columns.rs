@@ -1,14 +1,160 @@
 use axum::{
     extract::{Path, State},
+    http::{HeaderMap, HeaderValue},
     Json,
 };
+use serde_json::json;
+use sqlx::SqliteConnection;
 use uuid::Uuid;
 
 use crate::auth::AuthUser;
 use crate::error::{AppError, Result};
-use crate::models::{ColumnResponse, CreateColumn, MoveColumn, UpdateColumn};
+use crate::handlers::activity::{record, PendingActivity};
+use crate::models::{
+    BoardEvent, BoardEventKind, Card, Column, ColumnResponse, CreateColumn, MoveColumn,
+    StatusSyncResult, UpdateColumn, WipLimitPolicy,
+};
+use crate::repo::board::BoardRepository;
+use crate::repo::card::CardRepository;
+use crate::services::activity::changes;
+use crate::services::etag::{ETag, IfMatch};
 use crate::state::AppState;
 
+/// Response header carrying the warning when a card exceeds a column's WIP limit
+const WIP_WARNING_HEADER: &str = "x-wip-warning";
+
+/// Check that one more card fits in the column's WIP limit. Over the limit, boards
+/// that block fail with 409 Conflict; others get the warning to pass on.
+pub(crate) async fn check_wip_limit(state: &AppState, column: &Column) -> Result<Option<String>> {
+    let mut conn = state.pool.acquire().await?;
+    check_wip_limit_on(&mut conn, column, 1).await
+}
+
+/// Like [`check_wip_limit`] for `incoming` more cards, on the caller's connection. With
+/// no incoming cards, checks that the cards already placed fit.
+pub(crate) async fn check_wip_limit_on(
+    conn: &mut SqliteConnection,
+    column: &Column,
+    incoming: i64,
+) -> Result<Option<String>> {
+    let Some(limit) = column.wip_limit else {
+        return Ok(None);
+    };
+    // Cards placed in the column from any board count, as the board shows them
+    let count = CardRepository::count_by_column_on(conn, column.id).await? + incoming;
+    if count <= i64::from(limit) {
+        return Ok(None);
+    }
+
+    let board = BoardRepository::get_on(conn, column.board_id).await?;
+    match board.wip_limit_policy() {
+        WipLimitPolicy::Block => Err(AppError::Conflict(format!(
+            "Column '{}' has reached its WIP limit of {}",
+            column.name, limit
+        ))),
+        WipLimitPolicy::Warn => Ok(Some(format!(
+            "Column '{}' is over its WIP limit: {} cards, limit {}",
+            column.name, count, limit
+        ))),
+    }
+}
+
+/// Check that a card can be placed by hand in `column`. Auto-sorted columns order
+/// their cards themselves, so cards can be moved into them but not reordered within.
+pub(crate) fn check_manual_order(column: &Column, within_column: bool) -> Result<()> {
+    let policy = column.sort_policy();
+    if within_column && !policy.is_manual() {
+        return Err(AppError::BadRequest(format!(
+            "Column '{}' is {}, its cards cannot be reordered",
+            column.name,
+            policy.label().to_lowercase()
+        )));
+    }
+    Ok(())
+}
+
+/// A color, trimmed and lowercased, as `#rrggbb`; blank means no color
+pub(crate) fn hex_color(color: Option<&str>) -> Result<Option<String>> {
+    let Some(color) = color.map(str::trim).filter(|c| !c.is_empty()) else {
+        return Ok(None);
+    };
+    let valid = color.len() == 7
+        && color.starts_with('#')
+        && color[1..].chars().all(|c| c.is_ascii_hexdigit());
+    if !valid {
+        return Err(AppError::invalid_field(
+            "color",
+            "Color must be a hex color like #1f77b4",
+        ));
+    }
+    Ok(Some(color.to_lowercase()))
+}
+
+/// A column description, trimmed; blank means no description
+fn column_description(description: Option<&str>) -> Option<String> {
+    description
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+        .map(str::to_string)
+}
+
+/// Give a card moved into `column` the status the column maps to, if it has one, and
+/// record the change. Returns the card as it is now.
+pub(crate) async fn sync_card_status(
+    state: &AppState,
+    user_id: Uuid,
+    card: Card,
+    column: &Column,
+) -> Result<Card> {
+    let mut activity = PendingActivity::default();
+    let card = {
+        let mut conn = state.pool.acquire().await?;
+        sync_card_status_on(&mut conn, &mut activity, user_id, card, column).await?
+    };
+    activity.flush(state).await;
+    Ok(card)
+}
+
+/// Like [`sync_card_status`], on the caller's connection, holding the change back in
+/// `activity`
+pub(crate) async fn sync_card_status_on(
+    conn: &mut SqliteConnection,
+    activity: &mut PendingActivity,
+    user_id: Uuid,
+    card: Card,
+    column: &Column,
+) -> Result<Card> {
+    let Some(status) = column.card_status() else {
+        return Ok(card);
+    };
+    if card.status == status.to_string() {
+        return Ok(card);
+    }
+
+    let updated = CardRepository::update_status_on(conn, card.id, status).await?;
+    if let Some(details) = changes(&card, &updated) {
+        activity.record(
+            BoardEvent::new(
+                BoardEventKind::CardUpdated,
+                column.board_id,
+                card.id,
+                user_id,
+            )
+            .with_details(details),
+        );
+    }
+    Ok(updated)
+}
+
+/// Headers passing on a WIP limit warning, if any
+pub(crate) fn wip_warning_headers(warning: Option<String>) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    if let Some(value) = warning.and_then(|w| HeaderValue::from_bytes(w.as_bytes()).ok()) {
+        headers.insert(WIP_WARNING_HEADER, value);
+    }
+    headers
+}
+
 pub async fn create_column(
     State(state): State<AppState>,
     auth: AuthUser,
@@ -26,14 +172,34 @@ pub async fn create_column(
     }
 
     if input.name.is_empty() {
-        return Err(AppError::Validation("Column name is required".to_string()));
+        return Err(AppError::invalid_field("name", "Column name is required"));
     }
 
+    let color = hex_color(input.color.as_deref())?;
+    let description = column_description(input.description.as_deref());
     let column = state
         .columns
-        .create(board_id, &input.name, input.position)
+        .create(
+            board_id,
+            &input.name,
+            input.position,
+            color.as_deref(),
+            description.as_deref(),
+        )
         .await?;
 
+    record(
+        &state,
+        BoardEvent::new(
+            BoardEventKind::ColumnCreated,
+            board_id,
+            column.id,
+            auth.user.id,
+        )
+        .with_details(json!({"name": column.name})),
+    )
+    .await;
+
     Ok(Json(column.into()))
 }
 
@@ -49,15 +215,48 @@ pub async fn list_columns(
         .ok_or(AppError::Forbidden)?;
 
     let columns = state.columns.list_by_board(board_id).await?;
-    Ok(Json(columns.into_iter().map(|c| c.into()).collect()))
+    let collapsed = state
+        .columns
+        .collapsed_for_user(board_id, auth.user.id)
+        .await?;
+    let mut responses = Vec::new();
+    for col in columns {
+        let is_collapsed = collapsed.contains(&col.id);
+        let mut response: ColumnResponse = col.into();
+        response.collapsed = is_collapsed;
+        responses.push(response);
+    }
+    Ok(Json(responses))
+}
+
+/// Give every card of a board the status its column maps to, for cards that drifted
+/// before the mapping was set
+pub async fn sync_board_statuses(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(board_id): Path<Uuid>,
+) -> Result<Json<StatusSyncResult>> {
+    let role = state
+        .boards
+        .get_user_role(board_id, auth.user.id)
+        .await?
+        .ok_or(AppError::Forbidden)?;
+
+    if !role.can_edit() {
+        return Err(AppError::Forbidden);
+    }
+
+    let updated = state.cards.sync_status_with_columns(Some(board_id)).await?;
+    Ok(Json(StatusSyncResult { updated }))
 }
 
 pub async fn update_column(
     State(state): State<AppState>,
     auth: AuthUser,
     Path(column_id): Path<Uuid>,
+    if_match: IfMatch,
     Json(input): Json<UpdateColumn>,
-) -> Result<Json<ColumnResponse>> {
+) -> Result<(ETag, Json<ColumnResponse>)> {
     let column = state.columns.get_by_id(column_id).await?;
 
     let role = state
@@ -66,15 +265,95 @@ pub async fn update_column(
         .await?
         .ok_or(AppError::Forbidden)?;
 
-    if !role.can_edit() {
+    // Collapsing only changes the user's own view, readers may do it too
+    let collapse_only = input.name.is_none()
+        && input.wip_limit.is_none()
+        && input.sort_policy.is_none()
+        && input.color.is_none()
+        && input.description.is_none()
+        && input.card_status.is_none();
+    if !collapse_only && !role.can_edit() {
         return Err(AppError::Forbidden);
     }
 
+    let expected_version = if_match.expected_version(column.version)?;
+    let updated = if collapse_only {
+        column
+    } else {
+        apply_column_update(&state, &auth, &column, &input, expected_version).await?
+    };
+    if let Some(collapsed) = input.collapsed {
+        state
+            .columns
+            .set_collapsed(column_id, auth.user.id, collapsed)
+            .await?;
+    }
+    let collapsed = state
+        .columns
+        .collapsed_for_user(updated.board_id, auth.user.id)
+        .await?
+        .contains(&column_id);
+
+    let version = updated.version;
+    let mut response: ColumnResponse = updated.into();
+    response.collapsed = collapsed;
+    Ok((ETag(version), Json(response)))
+}
+
+/// Rename a column or change its WIP limit, color, description or card status and
+/// record the change. The caller checks that the user can edit the board and, with an
+/// `expected_version`, the update fails when the column has changed since.
+pub(crate) async fn apply_column_update(
+    state: &AppState,
+    auth: &AuthUser,
+    column: &Column,
+    input: &UpdateColumn,
+    expected_version: Option<i64>,
+) -> Result<Column> {
+    if input.wip_limit.flatten().is_some_and(|limit| limit < 1) {
+        return Err(AppError::invalid_field(
+            "wip_limit",
+            "WIP limit must be at least 1",
+        ));
+    }
+
+    let color = match &input.color {
+        Some(color) => Some(hex_color(color.as_deref())?),
+        None => None,
+    };
+    let description = input
+        .description
+        .as_ref()
+        .map(|d| column_description(d.as_deref()));
+
     let updated = state
         .columns
-        .update(column_id, input.name.as_deref())
+        .update(
+            column.id,
+            input.name.as_deref(),
+            input.wip_limit,
+            input.sort_policy,
+            color.as_ref().map(Option::as_deref),
+            description.as_ref().map(Option::as_deref),
+            input.card_status,
+            expected_version,
+        )
         .await?;
-    Ok(Json(updated.into()))
+
+    if let Some(details) = changes(column, &updated) {
+        record(
+            state,
+            BoardEvent::new(
+                BoardEventKind::ColumnUpdated,
+                column.board_id,
+                column.id,
+                auth.user.id,
+            )
+            .with_details(details),
+        )
+        .await;
+    }
+    Ok(updated)
 }
 
 pub async fn delete_column(
@@ -94,7 +373,19 @@ pub async fn delete_column(
         return Err(AppError::Forbidden);
     }
 
-    state.columns.delete(column_id).await?;
+    state.columns.move_to_trash(column_id, auth.user.id).await?;
+
+    record(
+        &state,
+        BoardEvent::new(
+            BoardEventKind::ColumnDeleted,
+            column.board_id,
+            column_id,
+            auth.user.id,
+        )
+        .with_details(json!({"name": column.name})),
+    )
+    .await;
     Ok(())
 }
 
@@ -117,5 +408,75 @@ pub async fn move_column(
     }
 
     let updated = state.columns.move_column(column_id, input.position).await?;
+
+    record(
+        &state,
+        BoardEvent::new(
+            BoardEventKind::ColumnMoved,
+            column.board_id,
+            column_id,
+            auth.user.id,
+        )
+        .with_details(json!({"from_position": column.position, "to_position": updated.position})),
+    )
+    .await;
     Ok(Json(updated.into()))
 }
+
+/// Archive a column: it leaves the board view with its cards, which are kept
+pub async fn archive_column(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(column_id): Path<Uuid>,
+) -> Result<Json<ColumnResponse>> {
+    let column = set_column_archived(&state, &auth, column_id, true).await?;
+    Ok(Json(column.into()))
+}
+
+/// Put an archived column back at the end of its board
+pub async fn unarchive_column(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(column_id): Path<Uuid>,
+) -> Result<Json<ColumnResponse>> {
+    let column = set_column_archived(&state, &auth, column_id, false).await?;
+    Ok(Json(column.into()))
+}
+
+pub(crate) async fn set_column_archived(
+    state: &AppState,
+    auth: &AuthUser,
+    column_id: Uuid,
+    archived: bool,
+) -> Result<Column> {
+    let column = state.columns.get_by_id(column_id).await?;
+
+    let role = state
+        .boards
+        .get_user_role(column.board_id, auth.user.id)
+        .await?
+        .ok_or(AppError::Forbidden)?;
+
+    if !role.can_edit() {
+        return Err(AppError::Forbidden);
+    }
+
+    if column.archived_at.is_some() == archived {
+        return Ok(column);
+    }
+
+    let column = state.columns.set_archived(column_id, archived).await?;
+
+    record(
+        state,
+        BoardEvent::new(
+            BoardEventKind::ColumnUpdated,
+            column.board_id,
+            column_id,
+            auth.user.id,
+        )
+        .with_details(json!({"archived": archived})),
+    )
+    .await;
+    Ok(column)
+}
@@ -1,29 +1,47 @@
 use axum::{
-    extract::{Path, State},
+    extract::{OriginalUri, Path, Query, State},
     Json,
 };
+use serde_json::json;
+use sqlx::SqliteConnection;
 use uuid::Uuid;
 
 use crate::auth::AuthUser;
 use crate::error::{AppError, Result};
-use crate::models::{CommentResponse, CreateComment, UpdateComment};
+use crate::handlers::activity::record_for_card;
+use crate::models::{
+    BoardEventKind, CommentDraft, CommentResponse, CreateComment, PageQuery, SaveCommentDraft,
+    UpdateComment, DEFAULT_PAGE_SIZE,
+};
+use crate::repo::board::BoardRepository;
+use crate::repo::card::CardRepository;
+use crate::repo::CardBoardRepository;
+use crate::services::activity::changes;
+use crate::services::pagination::Paginated;
 use crate::state::AppState;
 
 /// Check if user has view access to a card
-async fn can_view_card(state: &AppState, card_id: Uuid, user_id: Uuid) -> Result<bool> {
-    let card = state.cards.get_by_id(card_id).await?;
+pub(crate) async fn can_view_card(state: &AppState, card_id: Uuid, user_id: Uuid) -> Result<bool> {
+    let mut conn = state.pool.acquire().await?;
+    can_view_card_on(&mut conn, card_id, user_id).await
+}
+
+pub(crate) async fn can_view_card_on(
+    conn: &mut SqliteConnection,
+    card_id: Uuid,
+    user_id: Uuid,
+) -> Result<bool> {
+    let card = CardRepository::get_on(conn, card_id).await?;
 
     // Owner or creator always has access
     if card.owner_id == Some(user_id) || card.created_by == user_id {
         return Ok(true);
     }
 
-    // Check if card is on any board the user has access to
-    let boards = state.card_boards.list_boards_for_card(card_id).await?;
-    for board in boards {
-        if state
-            .boards
-            .get_user_role(board.id, user_id)
+    // Check if card is on any board the user has access to, by assignment or by its column
+    let board_ids = CardBoardRepository::list_board_ids_for_card_on(conn, card_id).await?;
+    for board_id in board_ids {
+        if BoardRepository::get_user_role_on(conn, board_id, user_id)
             .await?
             .is_some()
         {
@@ -35,8 +53,17 @@ async fn can_view_card(state: &AppState, card_id: Uuid, user_id: Uuid) -> Result
 }
 
 /// Check if user has edit access to a card (required for adding comments)
-async fn can_edit_card(state: &AppState, card_id: Uuid, user_id: Uuid) -> Result<bool> {
-    let card = state.cards.get_by_id(card_id).await?;
+pub(crate) async fn can_edit_card(state: &AppState, card_id: Uuid, user_id: Uuid) -> Result<bool> {
+    let mut conn = state.pool.acquire().await?;
+    can_edit_card_on(&mut conn, card_id, user_id).await
+}
+
+pub(crate) async fn can_edit_card_on(
+    conn: &mut SqliteConnection,
+    card_id: Uuid,
+    user_id: Uuid,
+) -> Result<bool> {
+    let card = CardRepository::get_on(conn, card_id).await?;
 
     // Owner or creator always has edit access
     if card.owner_id == Some(user_id) || card.created_by == user_id {
@@ -44,9 +71,9 @@ async fn can_edit_card(state: &AppState, card_id: Uuid, user_id: Uuid) -> Result
     }
 
     // Check if card is on any board the user has edit access to
-    let boards = state.card_boards.list_boards_for_card(card_id).await?;
-    for board in boards {
-        if let Some(role) = state.boards.get_user_role(board.id, user_id).await? {
+    let board_ids = CardBoardRepository::list_board_ids_for_card_on(conn, card_id).await?;
+    for board_id in board_ids {
+        if let Some(role) = BoardRepository::get_user_role_on(conn, board_id, user_id).await? {
             if role.can_edit() {
                 return Ok(true);
             }
@@ -56,19 +83,34 @@ async fn can_edit_card(state: &AppState, card_id: Uuid, user_id: Uuid) -> Result
     Ok(false)
 }
 
-/// List all comments for a card
+/// List the comments of a card, oldest first, a page at a time
 pub async fn list_comments(
     State(state): State<AppState>,
     auth: AuthUser,
     Path(card_id): Path<Uuid>,
-) -> Result<Json<Vec<CommentResponse>>> {
+    OriginalUri(uri): OriginalUri,
+    Query(paging): Query<PageQuery>,
+) -> Result<Paginated<CommentResponse>> {
     // Verify card exists and user has access
     if !can_view_card(&state, card_id, auth.user.id).await? {
         return Err(AppError::Forbidden);
     }
 
-    let comments = state.comments.list_by_card(card_id).await?;
-    Ok(Json(comments.into_iter().map(|c| c.into()).collect()))
+    let page = paging.page(DEFAULT_PAGE_SIZE);
+    let comments = state.comments.list_page_by_card(card_id, page).await?;
+    let reactions = state.reactions.list_for_card(card_id, auth.user.id).await?;
+    Ok(Paginated {
+        items: comments
+            .into_iter()
+            .map(|c| CommentResponse {
+                reactions: reactions.for_comment(c.id),
+                ..c.into()
+            })
+            .collect(),
+        total: state.comments.count_by_card(card_id).await?,
+        page,
+        uri,
+    })
 }
 
 /// Add a comment to a card
@@ -79,7 +121,7 @@ pub async fn create_comment(
     Json(input): Json<CreateComment>,
 ) -> Result<Json<CommentResponse>> {
     if input.body.trim().is_empty() {
-        return Err(AppError::Validation("Comment body is required".to_string()));
+        return Err(AppError::invalid_field("body", "Comment body is required"));
     }
 
     // Verify card exists and user has edit access
@@ -87,10 +129,22 @@ pub async fn create_comment(
         return Err(AppError::Forbidden);
     }
 
+    state.cards.ensure_unlocked(card_id).await?;
+
     let comment = state
         .comments
         .create(card_id, auth.user.id, &input.body)
         .await?;
+    state.comments.delete_draft(card_id, auth.user.id).await?;
+    record_for_card(
+        &state,
+        BoardEventKind::CommentCreated,
+        card_id,
+        comment.id,
+        auth.user.id,
+        Some(json!({"body": comment.body})),
+    )
+    .await;
 
     // Get the author name for the response
     let user = state.users.get_by_id(auth.user.id).await?;
@@ -103,6 +157,7 @@ pub async fn create_comment(
         body: comment.body,
         created_at: comment.created_at,
         updated_at: comment.updated_at,
+        reactions: Vec::new(),
     }))
 }
 
@@ -114,7 +169,7 @@ pub async fn update_comment(
     Json(input): Json<UpdateComment>,
 ) -> Result<Json<CommentResponse>> {
     if input.body.trim().is_empty() {
-        return Err(AppError::Validation("Comment body is required".to_string()));
+        return Err(AppError::invalid_field("body", "Comment body is required"));
     }
 
     let comment = state.comments.get_by_id(comment_id).await?;
@@ -124,9 +179,24 @@ pub async fn update_comment(
         return Err(AppError::Forbidden);
     }
 
+    state.cards.ensure_unlocked(comment.card_id).await?;
+
     let updated = state.comments.update(comment_id, &input.body).await?;
+    record_for_card(
+        &state,
+        BoardEventKind::CommentUpdated,
+        updated.card_id,
+        comment_id,
+        auth.user.id,
+        changes(&comment, &updated),
+    )
+    .await;
 
     let user = state.users.get_by_id(auth.user.id).await?;
+    let reactions = state
+        .reactions
+        .list_for_card(updated.card_id, auth.user.id)
+        .await?;
 
     Ok(Json(CommentResponse {
         id: updated.id,
@@ -136,6 +206,7 @@ pub async fn update_comment(
         body: updated.body,
         created_at: updated.created_at,
         updated_at: updated.updated_at,
+        reactions: reactions.for_comment(comment_id),
     }))
 }
 
@@ -152,6 +223,67 @@ pub async fn delete_comment(
         return Err(AppError::Forbidden);
     }
 
+    state.cards.ensure_unlocked(comment.card_id).await?;
     state.comments.delete(comment_id).await?;
+    record_for_card(
+        &state,
+        BoardEventKind::CommentDeleted,
+        comment.card_id,
+        comment_id,
+        auth.user.id,
+        Some(json!({"body": comment.body})),
+    )
+    .await;
     Ok(())
 }
+
+/// Get the current user's unsent comment draft for a card
+pub async fn get_comment_draft(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(card_id): Path<Uuid>,
+) -> Result<Json<Option<CommentDraft>>> {
+    if !can_edit_card(&state, card_id, auth.user.id).await? {
+        return Err(AppError::Forbidden);
+    }
+
+    let draft = state.comments.find_draft(card_id, auth.user.id).await?;
+    Ok(Json(draft))
+}
+
+/// Autosave the current user's comment draft for a card. A blank body discards the draft.
+pub async fn save_comment_draft(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(card_id): Path<Uuid>,
+    Json(input): Json<SaveCommentDraft>,
+) -> Result<Json<Option<CommentDraft>>> {
+    if !can_edit_card(&state, card_id, auth.user.id).await? {
+        return Err(AppError::Forbidden);
+    }
+
+    if input.body.trim().is_empty() {
+        state.comments.delete_draft(card_id, auth.user.id).await?;
+        return Ok(Json(None));
+    }
+
+    let draft = state
+        .comments
+        .save_draft(card_id, auth.user.id, &input.body)
+        .await?;
+    Ok(Json(Some(draft)))
+}
+
+/// Discard the current user's comment draft for a card
+pub async fn delete_comment_draft(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(card_id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>> {
+    if !can_edit_card(&state, card_id, auth.user.id).await? {
+        return Err(AppError::Forbidden);
+    }
+
+    state.comments.delete_draft(card_id, auth.user.id).await?;
+    Ok(Json(serde_json::json!({"deleted": true})))
+}
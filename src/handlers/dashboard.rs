@@ -0,0 +1,79 @@
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use chrono::{Datelike, Days, NaiveDate, Utc};
+use uuid::Uuid;
+
+use crate::auth::AuthUser;
+use crate::error::Result;
+use crate::models::{DashboardCard, DashboardCardResponse, DashboardQuery, DashboardResponse};
+use crate::state::AppState;
+
+/// How far back "recently updated" goes
+const RECENT_DAYS: u64 = 7;
+/// Most recently updated cards shown
+const RECENT_LIMIT: i64 = 20;
+
+async fn card_responses(
+    state: &AppState,
+    cards: Vec<DashboardCard>,
+) -> Result<Vec<DashboardCardResponse>> {
+    let ids: Vec<Uuid> = cards.iter().map(|c| c.card.id).collect();
+    let blocked = state.links.blocked_card_ids(&ids).await?;
+    let mut responses = Vec::new();
+    for DashboardCard {
+        card,
+        board_id,
+        board_name,
+    } in cards
+    {
+        let tags = state.tags.list_for_card(card.id).await?;
+        let mut card = card.into_response(tags.into_iter().map(Into::into).collect());
+        card.is_blocked = blocked.contains(&card.id);
+        responses.push(DashboardCardResponse {
+            card,
+            board_id,
+            board_name,
+        });
+    }
+    Ok(responses)
+}
+
+/// The dashboard of `user_id` on `today`. The week ends on Sunday.
+pub(crate) async fn build_dashboard(
+    state: &AppState,
+    user_id: Uuid,
+    today: NaiveDate,
+) -> Result<DashboardResponse> {
+    let week_end = today + Days::new(6 - u64::from(today.weekday().num_days_from_monday()));
+    let yesterday = today - Days::new(1);
+    let since = Utc::now() - Days::new(RECENT_DAYS);
+
+    let (assigned, due_this_week, overdue, recently_updated) = tokio::try_join!(
+        state.dashboard.list_assigned(user_id),
+        state.dashboard.list_due(user_id, Some(today), week_end),
+        state.dashboard.list_due(user_id, None, yesterday),
+        state
+            .dashboard
+            .list_updated_since(user_id, since, RECENT_LIMIT),
+    )?;
+
+    Ok(DashboardResponse {
+        today,
+        assigned: card_responses(state, assigned).await?,
+        due_this_week: card_responses(state, due_this_week).await?,
+        overdue: card_responses(state, overdue).await?,
+        recently_updated: card_responses(state, recently_updated).await?,
+    })
+}
+
+/// Cards needing the user's attention across every board they can access
+pub async fn get_dashboard(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Query(query): Query<DashboardQuery>,
+) -> Result<Json<DashboardResponse>> {
+    let today = query.today.unwrap_or_else(|| Utc::now().date_naive());
+    Ok(Json(build_dashboard(&state, auth.user.id, today).await?))
+}
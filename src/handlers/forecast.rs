@@ -0,0 +1,61 @@
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::auth::AuthUser;
+use crate::error::{AppError, Result};
+use crate::models::{ForecastQuery, ForecastResponse};
+use crate::services::forecast::{percentiles, simulate_weeks, weekly_throughput};
+use crate::state::AppState;
+
+const DEFAULT_HISTORY_WEEKS: u32 = 12;
+const MAX_HISTORY_WEEKS: u32 = 104;
+const SIMULATIONS: u32 = 10_000;
+
+/// Forecast when the remaining cards of a board will be done (Monte Carlo over weekly throughput)
+pub async fn get_forecast(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(board_id): Path<Uuid>,
+    Query(query): Query<ForecastQuery>,
+) -> Result<Json<ForecastResponse>> {
+    state
+        .boards
+        .get_user_role(board_id, auth.user.id)
+        .await?
+        .ok_or(AppError::Forbidden)?;
+
+    let history_weeks = query.weeks.unwrap_or(DEFAULT_HISTORY_WEEKS);
+    if history_weeks == 0 || history_weeks > MAX_HISTORY_WEEKS {
+        return Err(AppError::Validation(format!(
+            "weeks must be between 1 and {}",
+            MAX_HISTORY_WEEKS
+        )));
+    }
+
+    let remaining_cards = match query.cards {
+        Some(n) => n,
+        None => state.cards.count_open_by_board(board_id).await? as u32,
+    };
+
+    let now = Utc::now();
+    let completed = state
+        .cards
+        .list_completion_times(board_id, history_weeks as i64 * 7)
+        .await?;
+    let throughput = weekly_throughput(&completed, now, history_weeks);
+
+    let trials = simulate_weeks(&throughput, remaining_cards, SIMULATIONS, &mut rand::rng());
+
+    Ok(Json(ForecastResponse {
+        board_id,
+        remaining_cards,
+        history_weeks,
+        weekly_throughput: throughput,
+        simulations: if trials.is_empty() { 0 } else { SIMULATIONS },
+        percentiles: percentiles(&trials, now.date_naive()),
+    }))
+}
@@ -0,0 +1,144 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use uuid::Uuid;
+
+use crate::auth::{hash_token, AuthUser};
+use crate::error::{AppError, Result};
+use crate::handlers::share_links::require_owner;
+use crate::models::{CardVisibility, CreateGuestComment, GuestComment, ListGuestCommentsQuery};
+use crate::state::AppState;
+
+/// Longest name a guest can sign a comment with
+const MAX_GUEST_NAME_LENGTH: usize = 100;
+
+/// Longest comment a guest can leave
+const MAX_GUEST_COMMENT_LENGTH: usize = 5000;
+
+/// Save a comment left on a public card of the board shared under `token`, to be
+/// shown once an owner approves it. Comments that fill in the honeypot field are
+/// dropped without telling the sender.
+pub(crate) async fn submit(
+    state: &AppState,
+    token: &str,
+    card_id: Uuid,
+    input: &CreateGuestComment,
+) -> Result<()> {
+    let board_id = state
+        .share_links
+        .find_board_by_token(&hash_token(token))
+        .await?
+        .ok_or(AppError::NotFound("share_link"))?;
+
+    let card = state
+        .cards
+        .find_by_id(card_id)
+        .await?
+        .filter(|card| {
+            card.archived_at.is_none()
+                && card.visibility.parse::<CardVisibility>().ok() == Some(CardVisibility::Public)
+        })
+        .ok_or(AppError::NotFound("card"))?;
+    if !state
+        .card_boards
+        .is_card_on_board(card_id, board_id)
+        .await?
+    {
+        return Err(AppError::NotFound("card"));
+    }
+
+    if !input.website.is_empty() {
+        tracing::info!(board_id = %board_id, "Dropping guest comment caught by the honeypot");
+        return Ok(());
+    }
+
+    let name = input.name.trim();
+    if name.is_empty() {
+        return Err(AppError::invalid_field("name", "Name is required"));
+    }
+    if name.chars().count() > MAX_GUEST_NAME_LENGTH {
+        return Err(AppError::invalid_field(
+            "name",
+            format!("Name is longer than {} characters", MAX_GUEST_NAME_LENGTH),
+        ));
+    }
+    let body = input.body.trim();
+    if body.is_empty() {
+        return Err(AppError::invalid_field("body", "Comment body is required"));
+    }
+    if body.chars().count() > MAX_GUEST_COMMENT_LENGTH {
+        return Err(AppError::invalid_field(
+            "body",
+            format!(
+                "Comment is longer than {} characters",
+                MAX_GUEST_COMMENT_LENGTH
+            ),
+        ));
+    }
+
+    if card.is_locked() {
+        return Err(AppError::Locked);
+    }
+
+    state
+        .guest_comments
+        .create(card_id, board_id, name, body)
+        .await?;
+    Ok(())
+}
+
+/// Leave a comment on a public card of a shared board, without an account. It
+/// awaits moderation, so nothing is returned.
+pub async fn create_guest_comment(
+    State(state): State<AppState>,
+    Path((token, card_id)): Path<(String, Uuid)>,
+    Json(input): Json<CreateGuestComment>,
+) -> Result<()> {
+    submit(&state, &token, card_id, &input).await
+}
+
+/// Guest comments on the cards of the board, newest first
+pub async fn list_guest_comments(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(board_id): Path<Uuid>,
+    Query(query): Query<ListGuestCommentsQuery>,
+) -> Result<Json<Vec<GuestComment>>> {
+    require_owner(&state, board_id, auth.user.id).await?;
+    Ok(Json(
+        state
+            .guest_comments
+            .list_for_board(board_id, query.pending)
+            .await?,
+    ))
+}
+
+/// Show a guest comment on the board's share page
+pub async fn approve_guest_comment(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path((board_id, comment_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<GuestComment>> {
+    require_owner(&state, board_id, auth.user.id).await?;
+    let comment = state
+        .guest_comments
+        .approve(comment_id, board_id, auth.user.id)
+        .await?
+        .ok_or(AppError::NotFound("guest_comment"))?;
+    Ok(Json(comment))
+}
+
+/// Delete a guest comment, whether approved or not
+pub async fn delete_guest_comment(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path((board_id, comment_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode> {
+    require_owner(&state, board_id, auth.user.id).await?;
+    if !state.guest_comments.delete(comment_id, board_id).await? {
+        return Err(AppError::NotFound("guest_comment"));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
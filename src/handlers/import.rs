@@ -0,0 +1,116 @@
+use axum::{extract::State, Json};
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::auth::AuthUser;
+use crate::error::{AppError, Result};
+use crate::models::{BoardResponse, CardVisibility};
+use crate::services::trello_import::{plan_import, TrelloBoard};
+use crate::state::AppState;
+
+/// Largest accepted export, in bytes. Exports include the board's action history.
+pub const MAX_TRELLO_EXPORT_SIZE: usize = 20 * 1024 * 1024;
+
+/// Most cards created by one import
+const MAX_TRELLO_CARDS: usize = 5000;
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct TrelloImportResponse {
+    pub board: BoardResponse,
+    pub columns: usize,
+    pub cards: usize,
+    pub tags: usize,
+    pub comments: usize,
+}
+
+/// Create a new board from a Trello JSON export, owned by the current user.
+///
+/// Lists become columns, labels become tags, and cards keep their description,
+/// checklists (as Markdown task lists), due date, labels and comments. Comments are
+/// posted as the importing user and credit their Trello author.
+pub async fn import_trello(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Json(export): Json<TrelloBoard>,
+) -> Result<Json<TrelloImportResponse>> {
+    let plan = plan_import(export);
+    if plan.name.trim().is_empty() {
+        return Err(AppError::Validation("Board name is required".to_string()));
+    }
+    if plan.card_count() > MAX_TRELLO_CARDS {
+        return Err(AppError::Validation(format!(
+            "Cannot import more than {} cards at once",
+            MAX_TRELLO_CARDS
+        )));
+    }
+
+    let board = state
+        .boards
+        .create(&plan.name, plan.description.as_deref(), auth.user.id)
+        .await?;
+
+    let mut tag_ids = Vec::with_capacity(plan.tags.len());
+    for tag in &plan.tags {
+        let tag = state.tags.create(board.id, &tag.name, &tag.color).await?;
+        tag_ids.push(tag.id);
+    }
+
+    let mut cards = 0;
+    let mut comments = 0;
+    for planned_column in &plan.columns {
+        let column = state
+            .columns
+            .create(board.id, &planned_column.name, None, None, None)
+            .await?;
+
+        for planned in &planned_column.cards {
+            let card = state
+                .cards
+                .create(
+                    column.id,
+                    &planned.title,
+                    planned.body.as_deref(),
+                    None,
+                    CardVisibility::Restricted,
+                    planned.status,
+                    None,
+                    None,
+                    planned.due_date,
+                    None,
+                    auth.user.id,
+                )
+                .await?;
+            cards += 1;
+
+            for &tag in &planned.tags {
+                state.tags.add_to_card(card.id, tag_ids[tag]).await?;
+            }
+            for body in &planned.comments {
+                state.comments.create(card.id, auth.user.id, body).await?;
+                comments += 1;
+            }
+        }
+    }
+
+    Ok(Json(TrelloImportResponse {
+        columns: plan.columns.len(),
+        cards,
+        tags: tag_ids.len(),
+        comments,
+        board: BoardResponse {
+            id: board.id,
+            name: board.name,
+            description: board.description,
+            owner_id: board.owner_id,
+            role: "owner".to_string(),
+            chat_column_policy: board.chat_column_policy,
+            default_column_id: board.default_column_id,
+            wip_limit_policy: board.wip_limit_policy,
+            allow_public_links: board.allow_public_links,
+            workspace_id: board.workspace_id,
+            version: board.version,
+            created_at: board.created_at,
+            updated_at: board.updated_at,
+        },
+    }))
+}
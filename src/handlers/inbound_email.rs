@@ -0,0 +1,128 @@
+use axum::{
+    body::Bytes,
+    extract::{Path, State},
+    http::{HeaderValue, StatusCode},
+    Json,
+};
+use uuid::Uuid;
+
+use crate::auth::{generate_token, hash_token, AuthUser};
+use crate::error::{AppError, Result};
+use crate::handlers::attachments::{store_attachment, MAX_ATTACHMENT_SIZE};
+use crate::models::{
+    CardStatus, CardVisibility, InboundEmailAddressResponse, InboundEmailResponse, User,
+};
+use crate::services::inbound_email::parse;
+use crate::state::AppState;
+
+/// Largest accepted email, in bytes. Attachments are base64 encoded in emails, a third
+/// larger than the files themselves.
+pub const MAX_INBOUND_EMAIL_SIZE: usize = 25 * 1024 * 1024;
+
+/// Create a new inbound email token for the user, replacing the previous one, and
+/// return the address the mail server posts emails to
+pub(crate) async fn create_address_token(
+    state: &AppState,
+    user_id: Uuid,
+) -> Result<InboundEmailAddressResponse> {
+    let token = generate_token();
+    state
+        .inbound_email
+        .set_token(user_id, &hash_token(&token))
+        .await?;
+
+    Ok(InboundEmailAddressResponse {
+        url: state.link(&format!("/api/inbound/email/{}", token)),
+        token,
+    })
+}
+
+/// Generate the user's inbound email address. The token is only shown once.
+pub async fn create_inbound_email_address(
+    State(state): State<AppState>,
+    auth: AuthUser,
+) -> Result<Json<InboundEmailAddressResponse>> {
+    Ok(Json(create_address_token(&state, auth.user.id).await?))
+}
+
+/// Revoke the user's inbound email address
+pub async fn delete_inbound_email_address(
+    State(state): State<AppState>,
+    auth: AuthUser,
+) -> Result<StatusCode> {
+    if !state.inbound_email.delete(auth.user.id).await? {
+        return Err(AppError::NotFound("inbound_email_address"));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Turn a raw RFC 822 email, posted by a mail server or forwarding service, into a
+/// private inbox card of the token's user. Attachments over the attachment limit are
+/// left out and listed in the response.
+pub async fn receive_email(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+    body: Bytes,
+) -> Result<Json<InboundEmailResponse>> {
+    let user_id = state
+        .inbound_email
+        .find_user_by_token(&hash_token(&token))
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+    let user = state
+        .users
+        .find_by_id(user_id)
+        .await?
+        .filter(User::is_active)
+        .ok_or(AppError::Unauthorized)?;
+
+    let email =
+        parse(&body).ok_or_else(|| AppError::BadRequest("Not a valid email".to_string()))?;
+
+    let card = state
+        .cards
+        .create_standalone(
+            &email.title,
+            email.body.as_deref(),
+            CardVisibility::Private,
+            CardStatus::Open,
+            None,
+            None,
+            None,
+            None,
+            user.id,
+        )
+        .await?;
+
+    let mut attachments = Vec::new();
+    let mut skipped_attachments = Vec::new();
+    for attachment in email.attachments {
+        if attachment.data.len() > MAX_ATTACHMENT_SIZE {
+            skipped_attachments.push(attachment.filename);
+            continue;
+        }
+        // Served back as a header, so anything that is not a valid header value is dropped
+        let content_type = if HeaderValue::from_str(&attachment.content_type).is_ok() {
+            attachment.content_type.as_str()
+        } else {
+            "application/octet-stream"
+        };
+        let stored = store_attachment(
+            &state,
+            card.id,
+            user.id,
+            &attachment.filename,
+            content_type,
+            &attachment.data,
+        )
+        .await?;
+        attachments.push(stored.filename);
+    }
+
+    Ok(Json(InboundEmailResponse {
+        card_id: card.id,
+        title: card.title,
+        attachments,
+        skipped_attachments,
+    }))
+}
@@ -1,46 +1,67 @@
 use axum::{
-    extract::{Path, Query, State},
+    extract::{OriginalUri, Path, Query, State},
+    http::HeaderMap,
     Json,
 };
+use schemars::JsonSchema;
 use serde::Deserialize;
+use serde_json::json;
 use uuid::Uuid;
 
 use crate::auth::AuthUser;
 use crate::error::{AppError, Result};
+use crate::handlers::activity::{record, record_for_card, record_on_boards};
+use crate::handlers::cards::card_response;
+use crate::handlers::columns::{
+    check_manual_order, check_wip_limit, sync_card_status, wip_warning_headers,
+};
+use crate::handlers::links::{add_blocker_warning, check_blockers, is_done_column, mark_blocked};
 use crate::models::{
-    AssignCardToBoard, CardResponse, CardStatus, CardVisibility, CreateGlobalCard,
-    CreateGlobalTag, MoveCardInBoard, TagResponse, UpdateCard, UpdateCardStatus,
+    AssignCardToBoard, BoardEvent, BoardEventKind, CardResponse, CardStatus, CardVisibility,
+    ColumnPositions, CreateGlobalCard, CreateGlobalTag, MoveCardInBoard, MoveCardResponse,
+    PageQuery, TagResponse, UpdateCard, UpdateCardStatus, DEFAULT_PAGE_SIZE,
 };
+use crate::services::activity::changes;
+use crate::services::etag::{ETag, IfMatch};
+use crate::services::pagination::Paginated;
 use crate::state::AppState;
 
 /// Query parameters for listing cards
-#[derive(Debug, Deserialize, Default)]
+#[derive(Debug, Deserialize, Default, JsonSchema)]
 pub struct ListCardsQuery {
     pub status: Option<String>,
 }
 
-/// List all cards owned by the current user
+/// List the cards owned by the current user, most recently updated first, a page at a time
 pub async fn list_cards(
     State(state): State<AppState>,
     auth: AuthUser,
+    OriginalUri(uri): OriginalUri,
     Query(query): Query<ListCardsQuery>,
-) -> Result<Json<Vec<CardResponse>>> {
-    let status = query
-        .status
-        .and_then(|s| s.parse::<CardStatus>().ok());
+    Query(paging): Query<PageQuery>,
+) -> Result<Paginated<CardResponse>> {
+    let status = query.status.and_then(|s| s.parse::<CardStatus>().ok());
+    let page = paging.page(DEFAULT_PAGE_SIZE);
 
     let cards = state
         .cards
-        .list_by_owner_with_status(auth.user.id, status)
+        .list_page_by_owner(auth.user.id, status, page)
         .await?;
+    let total = state.cards.count_by_owner(auth.user.id, status).await?;
 
     let mut responses = Vec::new();
     for card in cards {
         let tags = state.tags.list_for_card(card.id).await?;
         responses.push(card.into_response(tags.into_iter().map(|t| t.into()).collect()));
     }
-
-    Ok(Json(responses))
+    mark_blocked(&state, &mut responses).await?;
+
+    Ok(Paginated {
+        items: responses,
+        total,
+        page,
+        uri,
+    })
 }
 
 /// Create a standalone (global) card
@@ -50,7 +71,7 @@ pub async fn create_card(
     Json(input): Json<CreateGlobalCard>,
 ) -> Result<Json<CardResponse>> {
     if input.title.is_empty() {
-        return Err(AppError::Validation("Card title is required".to_string()));
+        return Err(AppError::invalid_field("title", "Card title is required"));
     }
 
     let visibility = input.visibility.unwrap_or(CardVisibility::Private);
@@ -66,6 +87,7 @@ pub async fn create_card(
             input.start_date,
             input.end_date,
             input.due_date,
+            input.priority,
             auth.user.id,
         )
         .await?;
@@ -78,7 +100,7 @@ pub async fn get_card(
     State(state): State<AppState>,
     auth: AuthUser,
     Path(card_id): Path<Uuid>,
-) -> Result<Json<CardResponse>> {
+) -> Result<(ETag, Json<CardResponse>)> {
     let card = state.cards.get_by_id(card_id).await?;
 
     // Check access: either owner or has board access
@@ -106,10 +128,7 @@ pub async fn get_card(
         return Err(AppError::Forbidden);
     }
 
-    let tags = state.tags.list_for_card(card.id).await?;
-    Ok(Json(
-        card.into_response(tags.into_iter().map(|t| t.into()).collect()),
-    ))
+    Ok((ETag(card.version), card_response(&state, card).await?))
 }
 
 /// Update a card (user must own it or have edit access via board)
@@ -117,8 +136,9 @@ pub async fn update_card(
     State(state): State<AppState>,
     auth: AuthUser,
     Path(card_id): Path<Uuid>,
+    if_match: IfMatch,
     Json(input): Json<UpdateCard>,
-) -> Result<Json<CardResponse>> {
+) -> Result<(HeaderMap, ETag, Json<CardResponse>)> {
     let card = state.cards.get_by_id(card_id).await?;
 
     // Check edit access
@@ -142,23 +162,51 @@ pub async fn update_card(
         return Err(AppError::Forbidden);
     }
 
+    if card.is_locked() {
+        return Err(AppError::Locked);
+    }
+    let expected_version = if_match.expected_version(card.version)?;
+
     let updated_card = state
         .cards
         .update(
             card_id,
             input.title.as_deref(),
-            input.body.as_deref(),
+            input.body.as_ref().map(|b| b.as_deref()),
             input.visibility,
             input.status,
             input.start_date,
             input.end_date,
             input.due_date,
+            input.priority,
+            expected_version,
         )
         .await?;
+    state
+        .revisions
+        .record(&card, &updated_card, auth.user.id)
+        .await?;
+
+    if let Some(details) = changes(&card, &updated_card) {
+        record_for_card(
+            &state,
+            BoardEventKind::CardUpdated,
+            card_id,
+            card_id,
+            auth.user.id,
+            Some(details),
+        )
+        .await;
+    }
 
-    let tags = state.tags.list_for_card(updated_card.id).await?;
-    Ok(Json(
-        updated_card.into_response(tags.into_iter().map(|t| t.into()).collect()),
+    let mut headers = HeaderMap::new();
+    if input.status == Some(CardStatus::Done) {
+        add_blocker_warning(&mut headers, check_blockers(&state, card_id).await?);
+    }
+    Ok((
+        headers,
+        ETag(updated_card.version),
+        card_response(&state, updated_card).await?,
     ))
 }
 
@@ -168,7 +216,7 @@ pub async fn update_card_status(
     auth: AuthUser,
     Path(card_id): Path<Uuid>,
     Json(input): Json<UpdateCardStatus>,
-) -> Result<Json<CardResponse>> {
+) -> Result<(HeaderMap, Json<CardResponse>)> {
     let card = state.cards.get_by_id(card_id).await?;
 
     // Check edit access
@@ -192,12 +240,29 @@ pub async fn update_card_status(
         return Err(AppError::Forbidden);
     }
 
+    if card.is_locked() {
+        return Err(AppError::Locked);
+    }
+
     let updated_card = state.cards.update_status(card_id, input.status).await?;
 
-    let tags = state.tags.list_for_card(updated_card.id).await?;
-    Ok(Json(
-        updated_card.into_response(tags.into_iter().map(|t| t.into()).collect()),
-    ))
+    if let Some(details) = changes(&card, &updated_card) {
+        record_for_card(
+            &state,
+            BoardEventKind::CardUpdated,
+            card_id,
+            card_id,
+            auth.user.id,
+            Some(details),
+        )
+        .await;
+    }
+
+    let mut headers = HeaderMap::new();
+    if input.status == CardStatus::Done {
+        add_blocker_warning(&mut headers, check_blockers(&state, card_id).await?);
+    }
+    Ok((headers, card_response(&state, updated_card).await?))
 }
 
 /// Delete a card (user must own it or have edit access via board)
@@ -229,7 +294,22 @@ pub async fn delete_card(
         return Err(AppError::Forbidden);
     }
 
-    state.cards.delete(card_id).await?;
+    if card.is_locked() {
+        return Err(AppError::Locked);
+    }
+
+    let board_ids = state.card_boards.list_board_ids_for_card(card_id).await?;
+    state.cards.move_to_trash(card_id, auth.user.id).await?;
+
+    if let Some(board_id) = board_ids.first().copied() {
+        record_on_boards(
+            &state,
+            &board_ids,
+            BoardEvent::new(BoardEventKind::CardDeleted, board_id, card_id, auth.user.id)
+                .with_details(json!({"title": card.title})),
+        )
+        .await;
+    }
     Ok(())
 }
 
@@ -239,7 +319,7 @@ pub async fn assign_card_to_board(
     auth: AuthUser,
     Path((card_id, board_id)): Path<(Uuid, Uuid)>,
     Json(input): Json<AssignCardToBoard>,
-) -> Result<Json<CardResponse>> {
+) -> Result<(HeaderMap, Json<CardResponse>)> {
     let card = state.cards.get_by_id(card_id).await?;
 
     // User must own the card or have edit access to the target board
@@ -277,15 +357,35 @@ pub async fn assign_card_to_board(
         ));
     }
 
+    // Without a column, the card goes to the board's default column
+    let column_id = match input.column_id {
+        Some(column_id) => Some(column_id),
+        None => state.boards.get_by_id(board_id).await?.default_column_id,
+    };
+    let wip_warning = match column_id {
+        Some(column_id) => {
+            let column = state.columns.get_by_id(column_id).await?;
+            check_wip_limit(&state, &column).await?
+        }
+        None => None,
+    };
+
     state
         .card_boards
-        .assign_card_to_board(card_id, board_id, input.column_id, input.position)
+        .assign_card_to_board(card_id, board_id, column_id, input.position)
         .await?;
 
+    record(
+        &state,
+        BoardEvent::new(BoardEventKind::CardAdded, board_id, card_id, auth.user.id)
+            .with_details(json!({"title": card.title, "column_id": column_id})),
+    )
+    .await;
+
     let updated_card = state.cards.get_by_id(card_id).await?;
-    let tags = state.tags.list_for_card(card_id).await?;
-    Ok(Json(
-        updated_card.into_response(tags.into_iter().map(|t| t.into()).collect()),
+    Ok((
+        wip_warning_headers(wip_warning),
+        card_response(&state, updated_card).await?,
     ))
 }
 
@@ -315,6 +415,13 @@ pub async fn remove_card_from_board(
         .card_boards
         .remove_card_from_board(card_id, board_id)
         .await?;
+
+    record(
+        &state,
+        BoardEvent::new(BoardEventKind::CardRemoved, board_id, card_id, auth.user.id)
+            .with_details(json!({"title": card.title})),
+    )
+    .await;
     Ok(())
 }
 
@@ -324,7 +431,7 @@ pub async fn move_card_in_board(
     auth: AuthUser,
     Path((card_id, board_id)): Path<(Uuid, Uuid)>,
     Json(input): Json<MoveCardInBoard>,
-) -> Result<Json<CardResponse>> {
+) -> Result<(HeaderMap, Json<MoveCardResponse>)> {
     // User must have edit access to the board
     let role = state
         .boards
@@ -336,7 +443,15 @@ pub async fn move_card_in_board(
         return Err(AppError::Forbidden);
     }
 
+    let source_column_id = state
+        .card_boards
+        .get_assignment(card_id, board_id)
+        .await?
+        .ok_or(AppError::NotFound("card"))?
+        .column_id;
+
     // Verify column belongs to the board if specified
+    let mut target_column = None;
     if let Some(column_id) = input.column_id {
         let column = state.columns.get_by_id(column_id).await?;
         if column.board_id != board_id {
@@ -344,17 +459,67 @@ pub async fn move_card_in_board(
                 "Column does not belong to this board".to_string(),
             ));
         }
+        check_manual_order(&column, source_column_id == Some(column_id))?;
+        target_column = (source_column_id != Some(column_id)).then_some(column);
     }
+    let moves_to_done = target_column.as_ref().is_some_and(is_done_column);
+    let wip_warning = match &target_column {
+        Some(column) => check_wip_limit(&state, column).await?,
+        None => None,
+    };
 
-    state
+    let assignment = state
         .card_boards
         .move_card_in_board(card_id, board_id, input.column_id, input.position)
         .await?;
 
-    let card = state.cards.get_by_id(card_id).await?;
-    let tags = state.tags.list_for_card(card_id).await?;
-    Ok(Json(
-        card.into_response(tags.into_iter().map(|t| t.into()).collect()),
+    record(
+        &state,
+        BoardEvent::new(BoardEventKind::CardMoved, board_id, card_id, auth.user.id).with_details(
+            json!({
+                "from_column_id": source_column_id,
+                "to_column_id": input.column_id,
+                "position": input.position,
+            }),
+        ),
+    )
+    .await;
+
+    // Return the new order of the source and target columns
+    let mut affected_columns = Vec::new();
+    if source_column_id != input.column_id {
+        affected_columns.push(ColumnPositions {
+            column_id: source_column_id,
+            cards: state
+                .card_boards
+                .list_positions(board_id, source_column_id)
+                .await?,
+        });
+    }
+    affected_columns.push(ColumnPositions {
+        column_id: input.column_id,
+        cards: state
+            .card_boards
+            .list_positions(board_id, input.column_id)
+            .await?,
+    });
+
+    let mut card = state.cards.get_by_id(card_id).await?;
+    if let Some(column) = &target_column {
+        card = sync_card_status(&state, auth.user.id, card, column).await?;
+    }
+    let card = card.placed(&assignment);
+    let mut headers = wip_warning_headers(wip_warning);
+    if moves_to_done {
+        add_blocker_warning(&mut headers, check_blockers(&state, card_id).await?);
+    }
+    let Json(card) = card_response(&state, card).await?;
+    Ok((
+        headers,
+        Json(MoveCardResponse {
+            card,
+            affected_columns,
+        }),
     ))
 }
 
@@ -374,7 +539,7 @@ pub async fn create_global_tag(
     Json(input): Json<CreateGlobalTag>,
 ) -> Result<Json<TagResponse>> {
     if input.name.is_empty() {
-        return Err(AppError::Validation("Tag name is required".to_string()));
+        return Err(AppError::invalid_field("name", "Tag name is required"));
     }
 
     let color = input.color.unwrap_or_else(|| "#6c757d".to_string());
@@ -0,0 +1,181 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use chrono::{Duration, Utc};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::auth::{generate_token, hash_token, AuthUser};
+use crate::error::{AppError, Result};
+use crate::handlers::activity::record;
+use crate::models::{
+    BoardEvent, BoardEventKind, BoardInvitation, BoardRole, CreateBoardInvitation, User,
+};
+use crate::state::AppState;
+
+/// How long an invitation can be accepted
+const INVITATION_TTL_DAYS: i64 = 14;
+
+async fn require_permission_manager(state: &AppState, board_id: Uuid, user_id: Uuid) -> Result<()> {
+    let role = state
+        .boards
+        .get_user_role(board_id, user_id)
+        .await?
+        .ok_or(AppError::Forbidden)?;
+
+    if !role.can_manage_permissions() {
+        return Err(AppError::Forbidden);
+    }
+    Ok(())
+}
+
+/// Invite an email address to a board. The invitation link is emailed; the role is
+/// granted when it is followed, or when someone registers with that address.
+pub async fn create_invitation(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(board_id): Path<Uuid>,
+    Json(input): Json<CreateBoardInvitation>,
+) -> Result<Json<BoardInvitation>> {
+    require_permission_manager(&state, board_id, auth.user.id).await?;
+
+    if input.role == BoardRole::Owner {
+        return Err(AppError::BadRequest("Cannot add another owner".to_string()));
+    }
+
+    let email = input.email.trim();
+    if !email.contains('@') || email.contains(char::is_whitespace) {
+        return Err(AppError::invalid_field("email", "Invalid email address"));
+    }
+
+    if let Some(user) = state.users.find_by_email(email).await? {
+        if state
+            .boards
+            .get_user_role(board_id, user.id)
+            .await?
+            .is_some()
+        {
+            return Err(AppError::BadRequest(
+                "User already has access to this board".to_string(),
+            ));
+        }
+    }
+
+    let board = state.boards.get_by_id(board_id).await?;
+    let token = generate_token();
+    let invitation = state
+        .invitations
+        .create(
+            board_id,
+            email,
+            input.role,
+            &hash_token(&token),
+            auth.user.id,
+            Utc::now() + Duration::days(INVITATION_TTL_DAYS),
+        )
+        .await?;
+
+    let message =
+        state
+            .email
+            .board_invitation(email, &auth.user.name, &board.name, input.role, &token);
+    state.email.send_in_background(message);
+
+    Ok(Json(invitation))
+}
+
+/// List the pending invitations to a board
+pub async fn list_invitations(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(board_id): Path<Uuid>,
+) -> Result<Json<Vec<BoardInvitation>>> {
+    require_permission_manager(&state, board_id, auth.user.id).await?;
+
+    let invitations = state.invitations.list_for_board(board_id).await?;
+    Ok(Json(invitations))
+}
+
+/// Withdraw a pending invitation
+pub async fn revoke_invitation(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path((board_id, invitation_id)): Path<(Uuid, Uuid)>,
+) -> Result<()> {
+    require_permission_manager(&state, board_id, auth.user.id).await?;
+
+    if !state.invitations.delete(invitation_id, board_id).await? {
+        return Err(AppError::NotFound("invitation"));
+    }
+    Ok(())
+}
+
+/// Accept an invitation as the current user, whatever address it was sent to
+pub async fn accept_invitation(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(token): Path<String>,
+) -> Result<Json<Value>> {
+    let board_id = accept(&state, &token, auth.user.id).await?;
+    Ok(Json(json!({ "board_id": board_id })))
+}
+
+/// Grant the role of the invitation with this token to a user, returning the board
+/// it gives access to. Nothing changes for a user who already has a role on it.
+pub(crate) async fn accept(state: &AppState, token: &str, user_id: Uuid) -> Result<Uuid> {
+    let invitation = state
+        .invitations
+        .find_by_token_hash(&hash_token(token))
+        .await?
+        .ok_or(AppError::NotFound("invitation"))?;
+
+    grant(state, &invitation, user_id).await?;
+    Ok(invitation.board_id)
+}
+
+/// Accept every pending invitation sent to a newly registered user's address
+pub(crate) async fn accept_for_new_user(state: &AppState, user: &User) -> Result<()> {
+    for invitation in state.invitations.list_for_email(&user.email).await? {
+        grant(state, &invitation, user.id).await?;
+    }
+    Ok(())
+}
+
+async fn grant(state: &AppState, invitation: &BoardInvitation, user_id: Uuid) -> Result<()> {
+    if state
+        .boards
+        .get_user_role(invitation.board_id, user_id)
+        .await?
+        .is_none()
+    {
+        let role: BoardRole = invitation.role.parse().map_err(AppError::Internal)?;
+        state
+            .boards
+            .add_permission(invitation.board_id, user_id, role)
+            .await?;
+
+        let name = state.users.find_by_id(user_id).await?.map(|u| u.name);
+        record(
+            state,
+            BoardEvent::new(
+                BoardEventKind::MemberAdded,
+                invitation.board_id,
+                user_id,
+                user_id,
+            )
+            .with_details(json!({
+                "name": name,
+                "role": role.to_string(),
+                "invited_by": invitation.invited_by,
+            })),
+        )
+        .await;
+    }
+
+    state
+        .invitations
+        .delete(invitation.id, invitation.board_id)
+        .await?;
+    Ok(())
+}
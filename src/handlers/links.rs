@@ -0,0 +1,155 @@
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, HeaderValue},
+    Json,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::auth::AuthUser;
+use crate::error::{AppError, Result};
+use crate::handlers::activity::record_for_card;
+use crate::handlers::comments::{can_edit_card, can_view_card};
+use crate::models::{BoardEventKind, CardLink, CardResponse, CardStatus, Column, CreateCardLink};
+use crate::state::AppState;
+
+/// Response header warning that a card marked done still has open blockers
+const BLOCKER_WARNING_HEADER: &str = "x-blocker-warning";
+
+/// Links of a card the user can see, leaving out links to cards they cannot
+async fn visible_links(state: &AppState, card_id: Uuid, user_id: Uuid) -> Result<Vec<CardLink>> {
+    let mut links = Vec::new();
+    for link in state.links.list_for_card(card_id).await? {
+        if can_view_card(state, link.card.id, user_id).await? {
+            links.push(link);
+        }
+    }
+    Ok(links)
+}
+
+/// List a card's links in both directions
+pub async fn list_links(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(card_id): Path<Uuid>,
+) -> Result<Json<Vec<CardLink>>> {
+    if !can_view_card(&state, card_id, auth.user.id).await? {
+        return Err(AppError::Forbidden);
+    }
+
+    Ok(Json(visible_links(&state, card_id, auth.user.id).await?))
+}
+
+/// Link a card to another card the user can see
+pub async fn create_link(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(card_id): Path<Uuid>,
+    Json(input): Json<CreateCardLink>,
+) -> Result<Json<Vec<CardLink>>> {
+    if !can_edit_card(&state, card_id, auth.user.id).await? {
+        return Err(AppError::Forbidden);
+    }
+    if input.card_id == card_id {
+        return Err(AppError::invalid_field(
+            "card_id",
+            "A card cannot be linked to itself",
+        ));
+    }
+
+    let other = match state.cards.find_by_id(input.card_id).await? {
+        Some(other) if can_view_card(&state, other.id, auth.user.id).await? => other,
+        _ => return Err(AppError::invalid_field("card_id", "Linked card not found")),
+    };
+
+    let link = state
+        .links
+        .create(card_id, other.id, input.link_type, auth.user.id)
+        .await?;
+
+    for (card, other_card, link_type) in [
+        (card_id, other.id, input.link_type),
+        (other.id, card_id, input.link_type.inverse()),
+    ] {
+        record_for_card(
+            &state,
+            BoardEventKind::LinkAdded,
+            card,
+            link.id,
+            auth.user.id,
+            Some(json!({"link_type": link_type, "card_id": other_card})),
+        )
+        .await;
+    }
+
+    Ok(Json(visible_links(&state, card_id, auth.user.id).await?))
+}
+
+/// Remove a link, from either of its cards
+pub async fn delete_link(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path((card_id, link_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<Vec<CardLink>>> {
+    let link = state.links.get_by_id(link_id).await?;
+    if link.source_card_id != card_id && link.target_card_id != card_id {
+        return Err(AppError::NotFound("link"));
+    }
+    if !can_edit_card(&state, card_id, auth.user.id).await? {
+        return Err(AppError::Forbidden);
+    }
+
+    state.links.delete(link.id).await?;
+
+    for card in [link.source_card_id, link.target_card_id] {
+        record_for_card(
+            &state,
+            BoardEventKind::LinkRemoved,
+            card,
+            link.id,
+            auth.user.id,
+            None,
+        )
+        .await;
+    }
+
+    Ok(Json(visible_links(&state, card_id, auth.user.id).await?))
+}
+
+/// Whether moving a card into the column marks it as done
+pub(crate) fn is_done_column(column: &Column) -> bool {
+    column.card_status() == Some(CardStatus::Done)
+        || column.name.trim().eq_ignore_ascii_case("done")
+}
+
+/// Warning for a card being marked done while cards blocking it are still open.
+/// Blockers are not enforced: the change goes through and the warning is passed on.
+pub(crate) async fn check_blockers(state: &AppState, card_id: Uuid) -> Result<Option<String>> {
+    let blockers = state.links.list_open_blockers(card_id).await?;
+    if blockers.is_empty() {
+        return Ok(None);
+    }
+
+    let titles: Vec<String> = blockers
+        .iter()
+        .map(|c| format!("'{}' ({})", c.title, c.status))
+        .collect();
+    Ok(Some(format!("Still blocked by {}", titles.join(", "))))
+}
+
+/// Set `is_blocked` on cards about to be returned
+pub(crate) async fn mark_blocked(state: &AppState, cards: &mut [CardResponse]) -> Result<()> {
+    let ids: Vec<Uuid> = cards.iter().map(|c| c.id).collect();
+    let blocked = state.links.blocked_card_ids(&ids).await?;
+    for card in cards {
+        card.is_blocked = blocked.contains(&card.id);
+    }
+    Ok(())
+}
+
+/// Add a blocker warning, if any, to response headers
+pub(crate) fn add_blocker_warning(headers: &mut HeaderMap, warning: Option<String>) {
+    if let Some(value) = warning.and_then(|w| HeaderValue::from_bytes(w.as_bytes()).ok()) {
+        headers.insert(BLOCKER_WARNING_HEADER, value);
+    }
+}
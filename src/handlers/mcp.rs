@@ -0,0 +1,443 @@
+//! Model Context Protocol server, so that LLM agents can work on the user's boards
+//! through typed tools. It speaks JSON-RPC over the Streamable HTTP transport, answering
+//! every request with a JSON body; `pos-cli mcp` bridges it to stdio.
+
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::auth::AuthUser;
+use crate::error::{AppError, Result};
+use crate::handlers::chat::{board_not_resolved, find_board_by_name, run_tool_action};
+use crate::models::{ChatAction, LlmAction, TokenScope, GLOBAL_CHAT_ACTIONS};
+use crate::services::board_ref::BoardMatch;
+use crate::state::AppState;
+
+/// Path of the endpoint. API tokens are checked against the scope of each tool called
+/// rather than the HTTP method, as every request is a POST.
+pub const PATH: &str = "/api/mcp";
+
+/// Protocol revisions the server implements, the latest first
+const PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const INTERNAL_ERROR: i64 = -32603;
+
+/// Chat actions offered as tools. Listing actions are replaced by tools returning the
+/// data, since chat only lists things in its prompt.
+fn tool_actions() -> impl Iterator<Item = ChatAction> {
+    GLOBAL_CHAT_ACTIONS.iter().copied().filter(|action| {
+        !matches!(
+            action,
+            ChatAction::ListCards | ChatAction::ListTags | ChatAction::WebSearch | ChatAction::Undo
+        )
+    })
+}
+
+fn string_property(description: &str) -> Value {
+    json!({ "type": "string", "description": description })
+}
+
+/// Every tool, as listed by `tools/list`
+fn tools() -> Vec<Value> {
+    let mut tools = vec![
+        json!({
+            "name": "list_boards",
+            "description": "List the boards you are a member of, with your role on each",
+            "inputSchema": { "type": "object", "properties": {} },
+            "annotations": { "readOnlyHint": true },
+        }),
+        json!({
+            "name": "list_cards",
+            "description": "List the cards of a board, column by column",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "board": string_property("Board name or id"),
+                    "column": string_property("Only list the cards of this column"),
+                },
+                "required": ["board"],
+            },
+            "annotations": { "readOnlyHint": true },
+        }),
+        json!({
+            "name": "list_tags",
+            "description": "List the tags of a board",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "board": string_property("Board name or id") },
+                "required": ["board"],
+            },
+            "annotations": { "readOnlyHint": true },
+        }),
+    ];
+
+    tools.extend(tool_actions().map(|action| {
+        let properties: Map<String, Value> = action
+            .params()
+            .iter()
+            .map(|p| (p.name.to_string(), string_property(p.description)))
+            .collect();
+        let required: Vec<&str> = action
+            .params()
+            .iter()
+            .filter(|p| p.required)
+            .map(|p| p.name)
+            .collect();
+        let destructive = matches!(
+            action,
+            ChatAction::DeleteBoard
+                | ChatAction::DeleteColumn
+                | ChatAction::DeleteTag
+                | ChatAction::DeleteCard
+                | ChatAction::BulkUpdateCards
+        );
+
+        json!({
+            "name": action.to_string(),
+            "description": action.description(),
+            "inputSchema": {
+                "type": "object",
+                "properties": properties,
+                "required": required,
+            },
+            "annotations": { "readOnlyHint": false, "destructiveHint": destructive },
+        })
+    }));
+
+    tools
+}
+
+/// Result of a tool call: text for the agent, flagged when the call failed
+fn tool_result(text: String, is_error: bool) -> Value {
+    json!({
+        "content": [{ "type": "text", "text": text }],
+        "isError": is_error,
+    })
+}
+
+fn rpc_error(id: Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+/// Handle one JSON-RPC message. Notifications and responses get no answer.
+pub async fn handle(State(state): State<AppState>, auth: AuthUser, body: String) -> Response {
+    let message: Value = match serde_json::from_str(&body) {
+        Ok(message) => message,
+        Err(_) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(rpc_error(Value::Null, PARSE_ERROR, "Parse error")),
+            )
+                .into_response()
+        }
+    };
+    let Some(method) = message["method"].as_str() else {
+        if message.get("id").is_some() && message.get("jsonrpc").is_some() {
+            // A response to a request of ours; the server sends none
+            return StatusCode::ACCEPTED.into_response();
+        }
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(rpc_error(Value::Null, INVALID_REQUEST, "Invalid request")),
+        )
+            .into_response();
+    };
+    let Some(id) = message.get("id").cloned() else {
+        // notifications/initialized and other notifications need no reply
+        return StatusCode::ACCEPTED.into_response();
+    };
+    let params = message.get("params").cloned().unwrap_or(json!({}));
+
+    let result = match method {
+        "initialize" => Ok(initialize(&params)),
+        "ping" => Ok(json!({})),
+        "tools/list" => Ok(json!({ "tools": tools() })),
+        "tools/call" => call_tool(&state, &auth, &params).await,
+        _ => Err((METHOD_NOT_FOUND, format!("Method not found: {}", method))),
+    };
+
+    Json(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err((code, message)) => rpc_error(id, code, &message),
+    })
+    .into_response()
+}
+
+fn initialize(params: &Value) -> Value {
+    let requested = params["protocolVersion"].as_str().unwrap_or_default();
+    let version = PROTOCOL_VERSIONS
+        .iter()
+        .find(|v| **v == requested)
+        .unwrap_or(&PROTOCOL_VERSIONS[0]);
+
+    json!({
+        "protocolVersion": version,
+        "capabilities": { "tools": { "listChanged": false } },
+        "serverInfo": { "name": "personal-os", "version": env!("CARGO_PKG_VERSION") },
+        "instructions": "Tools act on the Kanban boards of the user. Refer to boards by name or id, and to cards by title. Call list_boards and list_cards to see what exists before changing it.",
+    })
+}
+
+/// Scope an API token needs to call the tool: the listing tools only read, the others
+/// change boards. None for unknown tools.
+fn tool_scope(name: &str) -> Option<TokenScope> {
+    match name {
+        "list_boards" | "list_cards" | "list_tags" => Some(TokenScope::Read),
+        _ if tool_actions().any(|a| a.to_string() == name) => Some(TokenScope::Write),
+        _ => None,
+    }
+}
+
+async fn call_tool(
+    state: &AppState,
+    auth: &AuthUser,
+    params: &Value,
+) -> std::result::Result<Value, (i64, String)> {
+    let name = params["name"].as_str().unwrap_or_default();
+    let arguments = match params.get("arguments") {
+        None | Some(Value::Null) => json!({}),
+        Some(arguments) if arguments.is_object() => arguments.clone(),
+        Some(_) => return Err((INVALID_PARAMS, "Arguments must be an object".to_string())),
+    };
+    let Some(scope) = tool_scope(name) else {
+        return Err((INVALID_PARAMS, format!("Unknown tool: {}", name)));
+    };
+    if !auth.token_scope.is_none_or(|token| token.allows(scope)) {
+        return Ok(tool_result(
+            format!(
+                "Calling {} needs an API token with the {} scope",
+                name, scope
+            ),
+            true,
+        ));
+    }
+
+    let user_id = auth.user.id;
+
+    let result = match name {
+        "list_boards" => list_boards(state, user_id).await,
+        "list_cards" => list_cards(state, user_id, &arguments).await,
+        "list_tags" => list_tags(state, user_id, &arguments).await,
+        _ => {
+            let Some(action) = tool_actions().find(|a| a.to_string() == name) else {
+                return Err((INVALID_PARAMS, format!("Unknown tool: {}", name)));
+            };
+            run_action(state, user_id, action, arguments).await
+        }
+    };
+
+    match result {
+        Ok(result) => Ok(result),
+        Err(AppError::Database(_) | AppError::Internal(_)) => {
+            Err((INTERNAL_ERROR, "Internal error".to_string()))
+        }
+        // Refusals and bad input are for the agent to read and correct
+        Err(e) => Ok(tool_result(e.to_string(), true)),
+    }
+}
+
+async fn run_action(
+    state: &AppState,
+    user_id: Uuid,
+    action: ChatAction,
+    arguments: Value,
+) -> Result<Value> {
+    let missing: Vec<&str> = action
+        .params()
+        .iter()
+        .filter(|p| p.required)
+        .filter(|p| {
+            arguments[p.name]
+                .as_str()
+                .is_none_or(|v| v.trim().is_empty())
+        })
+        .map(|p| p.name)
+        .collect();
+    if !missing.is_empty() {
+        return Ok(tool_result(
+            format!("Missing arguments: {}", missing.join(", ")),
+            true,
+        ));
+    }
+
+    let taken = run_tool_action(
+        state,
+        user_id,
+        &LlmAction {
+            action: action.to_string(),
+            params: arguments,
+            message: String::new(),
+        },
+    )
+    .await?;
+    Ok(tool_result(taken.description, !taken.success))
+}
+
+/// The board named by the `board` argument, or the tool result explaining why there is
+/// none
+async fn resolve_board(
+    state: &AppState,
+    user_id: Uuid,
+    tool: &str,
+    arguments: &Value,
+) -> Result<std::result::Result<Uuid, Value>> {
+    let board_name = arguments["board"].as_str().unwrap_or_default().trim();
+    if board_name.is_empty() {
+        return Ok(Err(tool_result(
+            "Missing arguments: board".to_string(),
+            true,
+        )));
+    }
+    Ok(
+        match find_board_by_name(state, user_id, board_name).await? {
+            BoardMatch::Found(candidate) => Ok(candidate.board.id),
+            other => Err(tool_result(
+                board_not_resolved(tool, "Board", board_name, &other).description,
+                true,
+            )),
+        },
+    )
+}
+
+fn json_text(value: &Value) -> Value {
+    tool_result(
+        serde_json::to_string_pretty(value).unwrap_or_default(),
+        false,
+    )
+}
+
+async fn list_boards(state: &AppState, user_id: Uuid) -> Result<Value> {
+    let boards = state.boards.list_for_user(user_id).await?;
+    let boards: Vec<Value> = boards
+        .into_iter()
+        .map(|(board, role)| {
+            json!({
+                "id": board.id,
+                "name": board.name,
+                "description": board.description,
+                "role": role,
+            })
+        })
+        .collect();
+    Ok(json_text(&Value::Array(boards)))
+}
+
+async fn list_cards(state: &AppState, user_id: Uuid, arguments: &Value) -> Result<Value> {
+    let board_id = match resolve_board(state, user_id, "list_cards", arguments).await? {
+        Ok(board_id) => board_id,
+        Err(result) => return Ok(result),
+    };
+    let (columns, cards, mut card_tags) = tokio::try_join!(
+        state.columns.list_by_board(board_id),
+        state.cards.list_by_board_columns(board_id),
+        state.tags.list_for_board_cards(board_id),
+    )?;
+
+    let column_filter = arguments["column"].as_str().map(str::trim).unwrap_or("");
+    let columns: Vec<_> = columns
+        .into_iter()
+        .filter(|c| column_filter.is_empty() || c.name.eq_ignore_ascii_case(column_filter))
+        .collect();
+    if columns.is_empty() {
+        return Ok(tool_result(
+            format!("Column '{}' not found", column_filter),
+            true,
+        ));
+    }
+
+    let mut cards_by_column: HashMap<Uuid, Vec<Value>> = HashMap::new();
+    for card in cards {
+        let Some(column_id) = card.column_id else {
+            continue;
+        };
+        let tags: Vec<String> = card_tags
+            .remove(&card.id)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|t| t.name)
+            .collect();
+        cards_by_column.entry(column_id).or_default().push(json!({
+            "id": card.id,
+            "title": card.title,
+            "body": card.body,
+            "status": card.status,
+            "priority": card.priority,
+            "due_date": card.due_date,
+            "tags": tags,
+        }));
+    }
+
+    let columns: Vec<Value> = columns
+        .into_iter()
+        .map(|column| {
+            json!({
+                "column": column.name,
+                "cards": cards_by_column.remove(&column.id).unwrap_or_default(),
+            })
+        })
+        .collect();
+    Ok(json_text(&Value::Array(columns)))
+}
+
+async fn list_tags(state: &AppState, user_id: Uuid, arguments: &Value) -> Result<Value> {
+    let board_id = match resolve_board(state, user_id, "list_tags", arguments).await? {
+        Ok(board_id) => board_id,
+        Err(result) => return Ok(result),
+    };
+    let tags: Vec<Value> = state
+        .tags
+        .list_by_board(board_id)
+        .await?
+        .into_iter()
+        .map(|tag| json!({ "name": tag.name, "color": tag.color }))
+        .collect();
+    Ok(json_text(&Value::Array(tags)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tools_have_unique_names_and_object_schemas() {
+        let tools = tools();
+        let mut names: Vec<&str> = tools.iter().map(|t| t["name"].as_str().unwrap()).collect();
+        let count = names.len();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), count);
+
+        for tool in &tools {
+            let schema = &tool["inputSchema"];
+            assert_eq!(schema["type"], "object");
+            for required in schema["required"].as_array().into_iter().flatten() {
+                let required = required.as_str().unwrap();
+                assert!(schema["properties"][required].is_object(), "{}", required);
+            }
+        }
+        assert!(names.contains(&"create_card"));
+        assert!(!names.contains(&"web_search"));
+    }
+
+    #[test]
+    fn test_initialize_negotiates_the_protocol_version() {
+        let result = initialize(&json!({ "protocolVersion": "2025-03-26" }));
+        assert_eq!(result["protocolVersion"], "2025-03-26");
+        let result = initialize(&json!({ "protocolVersion": "1999-01-01" }));
+        assert_eq!(result["protocolVersion"], PROTOCOL_VERSIONS[0]);
+    }
+}
@@ -1,9 +1,49 @@
+pub mod account;
+pub mod activity;
+pub mod admin;
+pub mod assignees;
+pub mod attachments;
 pub mod auth;
+pub mod backfills;
+pub mod backup;
+pub mod board_preferences;
+pub mod board_templates;
 pub mod boards;
+pub mod calendar;
+pub mod card_templates;
 pub mod cards;
 pub mod chat;
 pub mod columns;
 pub mod comments;
+pub mod dashboard;
+pub mod forecast;
+pub mod guest_comments;
+pub mod import;
+pub mod inbound_email;
 pub mod inbox;
+pub mod invitations;
+pub mod links;
+pub mod mcp;
+pub mod notifications;
+pub mod oauth;
+pub mod openapi;
+pub mod planning;
+pub mod quick_add;
+pub mod reactions;
+pub mod recurrences;
+pub mod revisions;
+pub mod roadmap;
+pub mod saved_views;
+pub mod search;
+pub mod settings;
+pub mod share_links;
+pub mod stats;
 pub mod tags;
+pub mod telegram;
+pub mod timeline;
+pub mod trash;
+pub mod watchers;
 pub mod web;
+pub mod webhooks;
+pub mod workspaces;
+pub mod ws;
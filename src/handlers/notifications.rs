@@ -0,0 +1,109 @@
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use chrono::{Days, NaiveDate};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::auth::AuthUser;
+use crate::error::{AppError, Result};
+use crate::models::{Notification, NotificationKind};
+use crate::state::AppState;
+
+/// How often the scheduler looks for cards coming due
+const SCHEDULER_PERIOD: Duration = Duration::from_secs(5 * 60);
+
+/// Cards due within this many days of today are due soon
+const DUE_SOON_DAYS: u64 = 1;
+
+/// Query parameters for listing notifications
+#[derive(Debug, Deserialize, Default, JsonSchema)]
+pub struct ListNotificationsQuery {
+    /// Only list notifications that have not been read
+    #[serde(default)]
+    pub unread: bool,
+}
+
+/// List the current user's notifications, newest first
+pub async fn list_notifications(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Query(query): Query<ListNotificationsQuery>,
+) -> Result<Json<Vec<Notification>>> {
+    let notifications = state
+        .notifications
+        .list_for_user(auth.user.id, query.unread)
+        .await?;
+    Ok(Json(notifications))
+}
+
+/// Number of unread notifications, for the bell in the web layout
+pub async fn unread_count(State(state): State<AppState>, auth: AuthUser) -> Result<Json<Value>> {
+    let count = state.notifications.count_unread(auth.user.id).await?;
+    Ok(Json(json!({ "count": count })))
+}
+
+/// Mark a notification as read
+pub async fn mark_read(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(notification_id): Path<Uuid>,
+) -> Result<()> {
+    if !state
+        .notifications
+        .mark_read(notification_id, auth.user.id)
+        .await?
+    {
+        return Err(AppError::NotFound("notification"));
+    }
+    Ok(())
+}
+
+/// Mark all of the current user's notifications as read
+pub async fn mark_all_read(State(state): State<AppState>, auth: AuthUser) -> Result<Json<Value>> {
+    let updated = state.notifications.mark_all_read(auth.user.id).await?;
+    Ok(Json(json!({ "updated": updated })))
+}
+
+/// Notify recipients of open cards that are due soon or overdue as of `today`, and
+/// email them, returning the number of notifications created. Each card is notified
+/// about at most once per kind and due date. Failed emails are logged, not retried.
+pub async fn create_due_notifications(state: &AppState, today: NaiveDate) -> Result<usize> {
+    let soon_until = today
+        .checked_add_days(Days::new(DUE_SOON_DAYS))
+        .unwrap_or(today);
+    let reminders = state.notifications.create_due(today, soon_until).await?;
+
+    for reminder in &reminders {
+        let kind: NotificationKind = reminder.kind.parse().map_err(AppError::Internal)?;
+        let email = state.email.due_reminder(
+            &reminder.email,
+            &reminder.card_title,
+            reminder.card_id,
+            kind,
+            reminder.due_date,
+        );
+        if let Err(e) = state.email.send(email).await {
+            tracing::warn!(user_id = %reminder.user_id, error = %e, "Could not email reminder");
+        }
+    }
+
+    Ok(reminders.len())
+}
+
+/// Raise due-date notifications now and then, until the server shuts down
+pub async fn run_scheduler(state: AppState) {
+    let mut ticks = tokio::time::interval(SCHEDULER_PERIOD);
+    while state.jobs.tick(&mut ticks).await {
+        let today = chrono::Local::now().date_naive();
+        match create_due_notifications(&state, today).await {
+            Ok(0) => {}
+            Ok(created) => tracing::info!("Created {} due-date notification(s)", created),
+            Err(e) => tracing::warn!(error = %e, "Due-date notification run failed"),
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use axum::{
+    extract::{Path, Query, State},
+    response::{IntoResponse, Redirect, Response},
+};
+use axum_extra::extract::cookie::{Cookie, CookieJar, SameSite};
+use serde::Deserialize;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::auth::{generate_token, session_cookie, session_lifetime, ClientIp};
+use crate::error::{AppError, Result};
+use crate::handlers::invitations::accept_for_new_user;
+use crate::handlers::web::login_response;
+use crate::models::User;
+use crate::services::ProviderAccount;
+use crate::state::AppState;
+
+/// Cookie tying the provider's callback to the browser that started the login
+const STATE_COOKIE: &str = "oauth_state";
+const STATE_COOKIE_PATH: &str = "/auth/oauth";
+
+/// Minutes the user has to log in at the provider
+const STATE_LIFETIME_MINUTES: i64 = 10;
+
+#[derive(Debug, Deserialize)]
+pub struct CallbackQuery {
+    code: Option<String>,
+    state: Option<String>,
+    /// Set instead of the code when the user declined or the provider failed
+    error: Option<String>,
+}
+
+/// Send the user to the provider's login page
+pub async fn start(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Path(provider): Path<String>,
+) -> Result<Response> {
+    let provider = state
+        .oauth
+        .get(&provider)
+        .ok_or(AppError::NotFound("oauth_provider"))?;
+    let nonce = generate_token();
+    let url = match state.oauth.authorize_url(provider, &nonce).await {
+        Ok(url) => url,
+        Err(e) => {
+            warn!(provider = %provider.name(), error = %e, "Could not start OAuth login");
+            let error = format!("Could not sign in with {}", provider.display_name());
+            return Ok(login_response(&state, Some(error)));
+        }
+    };
+
+    let cookie = Cookie::build((STATE_COOKIE, nonce))
+        .path(STATE_COOKIE_PATH)
+        .http_only(true)
+        // Sent along when the provider redirects back, which is a top-level navigation
+        .same_site(SameSite::Lax)
+        .max_age(time::Duration::minutes(STATE_LIFETIME_MINUTES))
+        .build();
+    Ok((jar.add(cookie), Redirect::to(&url)).into_response())
+}
+
+/// Log in the user the provider vouched for
+pub async fn callback(
+    State(state): State<AppState>,
+    ClientIp(ip): ClientIp,
+    jar: CookieJar,
+    Path(provider): Path<String>,
+    Query(query): Query<CallbackQuery>,
+) -> Result<Response> {
+    let provider = state
+        .oauth
+        .get(&provider)
+        .ok_or(AppError::NotFound("oauth_provider"))?;
+    let expected = jar.get(STATE_COOKIE).map(|c| c.value().to_string());
+    let jar = jar.remove(Cookie::build(STATE_COOKIE).path(STATE_COOKIE_PATH));
+    let failed = |error: String| (jar.clone(), login_response(&state, Some(error))).into_response();
+
+    if let Some(error) = query.error {
+        warn!(provider = %provider.name(), error = %error, "OAuth provider declined the login");
+        return Ok(failed(format!(
+            "Could not sign in with {}",
+            provider.display_name()
+        )));
+    }
+    let code = match (query.code, query.state) {
+        (Some(code), Some(returned)) if expected.as_deref() == Some(returned.as_str()) => code,
+        _ => return Ok(failed("Your login expired, please try again".to_string())),
+    };
+
+    let account = match state.oauth.account(provider, &code).await {
+        Ok(account) => account,
+        Err(AppError::Validation(message)) => return Ok(failed(message)),
+        Err(e) => {
+            warn!(provider = %provider.name(), error = %e, "OAuth login failed");
+            return Ok(failed(format!(
+                "Could not sign in with {}",
+                provider.display_name()
+            )));
+        }
+    };
+    let user = user_for_account(&state, provider.name(), &account).await?;
+    if !user.is_active() {
+        return Ok(failed("This account is deactivated".to_string()));
+    }
+
+    let token = generate_token();
+    state
+        .sessions
+        .create(user.id, &token, Some(ip), false, session_lifetime(false))
+        .await?;
+
+    Ok((
+        jar.add(session_cookie(token, false)),
+        Redirect::to("/boards"),
+    )
+        .into_response())
+}
+
+/// The user signing in with a provider account: the one it is linked to, otherwise the
+/// user with its email address, who it gets linked to, otherwise a new user without a
+/// password
+async fn user_for_account(
+    state: &AppState,
+    provider: &str,
+    account: &ProviderAccount,
+) -> Result<User> {
+    if let Some(identity) = state.identities.find(provider, &account.subject).await? {
+        return state.users.get_by_id(identity.user_id).await;
+    }
+
+    let user = match state.users.find_by_email(&account.email).await? {
+        Some(user) => user,
+        None => {
+            let name = account
+                .name
+                .clone()
+                .filter(|name| !name.trim().is_empty())
+                .unwrap_or_else(|| {
+                    account
+                        .email
+                        .split('@')
+                        .next()
+                        .unwrap_or_default()
+                        .to_string()
+                });
+            let user = state
+                .users
+                .create(Uuid::new_v4(), &account.email, None, &name)
+                .await?;
+            accept_for_new_user(state, &user).await?;
+            user
+        }
+    };
+    state
+        .identities
+        .create(user.id, provider, &account.subject, &account.email)
+        .await?;
+    Ok(user)
+}
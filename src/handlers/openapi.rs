@@ -0,0 +1,834 @@
+use askama::Template;
+use axum::{response::Html, Json};
+use schemars::{generate::SchemaSettings, JsonSchema, Schema, SchemaGenerator};
+use serde_json::{json, Map, Value};
+use std::sync::OnceLock;
+
+use crate::error::ErrorBody;
+use crate::handlers::auth::{AuthResponse, LoginRequest};
+use crate::handlers::import::TrelloImportResponse;
+use crate::handlers::inbox::ListCardsQuery;
+use crate::handlers::notifications::ListNotificationsQuery;
+use crate::models::*;
+use crate::services::trello_import::TrelloBoard;
+
+/// The OpenAPI document of the REST API, generated from the models on first use
+pub async fn openapi_json() -> Json<Value> {
+    static DOCUMENT: OnceLock<Value> = OnceLock::new();
+    Json(DOCUMENT.get_or_init(document).clone())
+}
+
+#[derive(Template)]
+#[template(path = "api_docs.html")]
+struct ApiDocsTemplate;
+
+/// Swagger UI page browsing the OpenAPI document
+pub async fn api_docs() -> Html<String> {
+    Html(ApiDocsTemplate.render().unwrap())
+}
+
+type SchemaFn = fn(&mut SchemaGenerator) -> Schema;
+
+/// Schema of `T`, a reference to `components/schemas` for named types
+fn schema<T: JsonSchema>(generator: &mut SchemaGenerator) -> Schema {
+    let mut schema = generator.subschema_for::<T>();
+    // Definitions get the OpenAPI transforms when taken, inline schemas need them here
+    for transform in generator.transforms_mut() {
+        transform.transform(&mut schema);
+    }
+    schema
+}
+
+/// What an operation returns when it succeeds
+enum Success {
+    /// `200` with an empty body
+    Empty,
+    /// `204 No Content`
+    NoContent,
+    /// `200` with a JSON body
+    Json(SchemaFn),
+    /// `200` with a JSON array of one page, described by the pagination headers
+    Page(SchemaFn),
+    /// `200` with a file download of the given content types
+    File(&'static [&'static str]),
+    /// `200` with a stream of server-sent events
+    EventStream,
+    /// `101` switching to a WebSocket
+    WebSocket,
+}
+
+/// Body an operation accepts
+enum Body {
+    Json(SchemaFn),
+    /// Raw content of the given type
+    Raw(&'static str),
+    /// A `multipart/form-data` upload with a `file` field
+    Upload,
+}
+
+struct Operation {
+    method: &'static str,
+    path: &'static str,
+    tag: &'static str,
+    summary: &'static str,
+    body: Option<Body>,
+    query: Vec<SchemaFn>,
+    success: Success,
+    /// Whether the operation works without a session or token
+    public: bool,
+    /// Whether the operation honors `If-Match` and returns an `ETag`
+    versioned: bool,
+}
+
+fn op(
+    method: &'static str,
+    path: &'static str,
+    tag: &'static str,
+    summary: &'static str,
+) -> Operation {
+    Operation {
+        method,
+        path,
+        tag,
+        summary,
+        body: None,
+        query: Vec::new(),
+        success: Success::Empty,
+        public: false,
+        versioned: false,
+    }
+}
+
+impl Operation {
+    fn body(mut self, body: SchemaFn) -> Self {
+        self.body = Some(Body::Json(body));
+        self
+    }
+
+    fn raw_body(mut self, content_type: &'static str) -> Self {
+        self.body = Some(Body::Raw(content_type));
+        self
+    }
+
+    fn upload(mut self) -> Self {
+        self.body = Some(Body::Upload);
+        self
+    }
+
+    fn query(mut self, query: SchemaFn) -> Self {
+        self.query.push(query);
+        self
+    }
+
+    fn json(mut self, response: SchemaFn) -> Self {
+        self.success = Success::Json(response);
+        self
+    }
+
+    fn page(mut self, item: SchemaFn) -> Self {
+        self.success = Success::Page(item);
+        self
+    }
+
+    fn no_content(mut self) -> Self {
+        self.success = Success::NoContent;
+        self
+    }
+
+    fn file(mut self, content_types: &'static [&'static str]) -> Self {
+        self.success = Success::File(content_types);
+        self
+    }
+
+    fn event_stream(mut self) -> Self {
+        self.success = Success::EventStream;
+        self
+    }
+
+    fn websocket(mut self) -> Self {
+        self.success = Success::WebSocket;
+        self
+    }
+
+    fn public(mut self) -> Self {
+        self.public = true;
+        self
+    }
+
+    fn versioned(mut self) -> Self {
+        self.versioned = true;
+        self
+    }
+}
+
+/// Every route of the REST API, in the order of the router
+#[rustfmt::skip]
+fn operations() -> Vec<Operation> {
+    vec![
+        // Auth
+        op("post", "/auth/register", "Auth", "Create an account and log in")
+            .public().body(schema::<CreateUser>).json(schema::<AuthResponse>),
+        op("post", "/auth/login", "Auth", "Log in and get a session cookie")
+            .public().body(schema::<LoginRequest>).json(schema::<AuthResponse>),
+        op("post", "/auth/logout", "Auth", "End the current session"),
+        op("post", "/auth/password", "Auth", "Change the password and log out other sessions")
+            .body(schema::<ChangePassword>),
+        op("post", "/auth/email", "Auth", "Request a change of email address")
+            .body(schema::<ChangeEmail>).json(schema::<EmailChange>),
+        op("post", "/auth/email/confirm", "Auth", "Confirm a change of email address")
+            .public().body(schema::<ConfirmEmailChange>).json(schema::<UserResponse>),
+        op("post", "/auth/tokens", "Auth", "Create an API token")
+            .body(schema::<CreateApiToken>).json(schema::<ApiTokenCreatedResponse>),
+        op("get", "/auth/tokens", "Auth", "List API tokens")
+            .json(schema::<Vec<ApiTokenResponse>>),
+        op("delete", "/auth/tokens/{token_id}", "Auth", "Revoke an API token"),
+        op("get", "/auth/sessions", "Auth", "List the sessions logged in to the account")
+            .json(schema::<Vec<SessionResponse>>),
+        op("delete", "/auth/sessions", "Auth", "Log out every other session")
+            .json(schema::<SessionsRevoked>),
+        op("delete", "/auth/sessions/{session_id}", "Auth", "Log out a session"),
+        // Account
+        op("delete", "/users/me", "Account", "Delete the account for good")
+            .body(schema::<DeleteAccount>),
+        op("get", "/users/me/export", "Account", "Download everything the account owns")
+            .file(&["application/json"]),
+        op("post", "/users/me/calendar-feed", "Account", "Create or replace the calendar feed")
+            .json(schema::<CalendarFeedResponse>),
+        op("delete", "/users/me/calendar-feed", "Account", "Disable the calendar feed")
+            .no_content(),
+        op("post", "/users/me/inbound-email", "Account", "Create or replace the inbound email address")
+            .json(schema::<InboundEmailAddressResponse>),
+        op("delete", "/users/me/inbound-email", "Account", "Disable the inbound email address")
+            .no_content(),
+        op("post", "/users/me/telegram", "Account", "Get a code linking a Telegram chat")
+            .json(schema::<TelegramLinkResponse>),
+        op("delete", "/users/me/telegram", "Account", "Unlink the Telegram chat")
+            .no_content(),
+        op("post", "/inbound/email/{token}", "Account", "Receive an email as an inbox card")
+            .public().raw_body("message/rfc822").json(schema::<InboundEmailResponse>),
+        // Settings
+        op("get", "/settings", "Settings", "Get the user's settings")
+            .json(schema::<UserSettings>),
+        op("patch", "/settings", "Settings", "Update the user's settings")
+            .body(schema::<UpdateUserSettings>).json(schema::<UserSettings>),
+        // Boards
+        op("post", "/boards", "Boards", "Create a board")
+            .body(schema::<CreateBoard>).json(schema::<BoardResponse>),
+        op("get", "/boards", "Boards", "List the boards the user is a member of")
+            .query(schema::<PageQuery>).page(schema::<BoardResponse>),
+        op("get", "/boards/{board_id}", "Boards", "Get a board with its columns and cards")
+            .versioned().json(schema::<BoardWithDetails>),
+        op("put", "/boards/{board_id}", "Boards", "Update a board")
+            .versioned().body(schema::<UpdateBoard>).json(schema::<BoardResponse>),
+        op("delete", "/boards/{board_id}", "Boards", "Move a board to the trash"),
+        op("post", "/boards/{board_id}/duplicate", "Boards", "Copy a board with its columns, tags and cards")
+            .body(schema::<DuplicateBoard>).json(schema::<BoardResponse>),
+        op("post", "/import/trello", "Boards", "Create a board from a Trello JSON export")
+            .body(schema::<TrelloBoard>).json(schema::<TrelloImportResponse>),
+        op("post", "/boards/{board_id}/save-as-template", "Templates", "Save a board as a template")
+            .body(schema::<SaveBoardTemplate>).json(schema::<BoardTemplateResponse>),
+        op("get", "/board-templates", "Templates", "List board templates")
+            .json(schema::<Vec<BoardTemplateResponse>>),
+        op("delete", "/board-templates/{template_id}", "Templates", "Delete a board template"),
+        op("post", "/boards/{board_id}/permissions", "Members", "Add a member to a board")
+            .body(schema::<AddBoardPermission>),
+        op("delete", "/boards/{board_id}/permissions/{user_id}", "Members", "Remove a member from a board"),
+        op("get", "/boards/{board_id}/share-link", "Members", "Whether the board has a public address")
+            .json(schema::<Option<BoardShareLink>>),
+        op("post", "/boards/{board_id}/share-link", "Members", "Generate a public read-only address of the board")
+            .json(schema::<BoardShareLinkResponse>),
+        op("delete", "/boards/{board_id}/share-link", "Members", "Revoke the board's public address"),
+        op("get", "/boards/{board_id}/guest-comments", "Members", "List comments left through the share link")
+            .query(schema::<ListGuestCommentsQuery>).json(schema::<Vec<GuestComment>>),
+        op("post", "/boards/{board_id}/guest-comments/{comment_id}/approve", "Members", "Show a guest comment on the share page")
+            .json(schema::<GuestComment>),
+        op("delete", "/boards/{board_id}/guest-comments/{comment_id}", "Members", "Delete a guest comment")
+            .no_content(),
+        op("post", "/share/{token}/cards/{card_id}/comments", "Members", "Leave a comment on a public card of a shared board")
+            .public().body(schema::<CreateGuestComment>),
+        op("get", "/boards/{board_id}/webhooks", "Webhooks", "List the addresses receiving the board's events")
+            .json(schema::<Vec<Webhook>>),
+        op("post", "/boards/{board_id}/webhooks", "Webhooks", "Send the board's events to an address")
+            .body(schema::<CreateWebhook>).json(schema::<Webhook>),
+        op("delete", "/webhooks/{webhook_id}", "Webhooks", "Stop sending events to an address")
+            .no_content(),
+        op("post", "/webhooks/{webhook_id}/replay", "Webhooks", "Deliver again the events sent since a time")
+            .query(schema::<ReplayWebhookQuery>).json(schema::<WebhookReplayResponse>),
+        op("post", "/boards/{board_id}/invitations", "Members", "Invite someone to a board by email")
+            .body(schema::<CreateBoardInvitation>).json(schema::<BoardInvitation>),
+        op("get", "/boards/{board_id}/invitations", "Members", "List pending invitations")
+            .json(schema::<Vec<BoardInvitation>>),
+        op("delete", "/boards/{board_id}/invitations/{invitation_id}", "Members", "Revoke an invitation"),
+        op("post", "/invitations/{token}/accept", "Members", "Accept an invitation")
+            .json(schema::<Value>),
+        // Boards page
+        op("put", "/boards/{board_id}/preferences", "Boards", "Star a board or put it in a group")
+            .body(schema::<UpdateBoardPreference>).json(schema::<BoardPreference>),
+        op("get", "/board-preferences", "Boards", "List the stars, groups and positions of the user's boards")
+            .json(schema::<Vec<BoardPreference>>),
+        op("put", "/board-order", "Boards", "Set the order of the user's boards")
+            .body(schema::<ReorderBoards>),
+        op("get", "/board-groups", "Boards", "List the user's board groups")
+            .json(schema::<Vec<BoardGroup>>),
+        op("post", "/board-groups", "Boards", "Create a board group")
+            .body(schema::<CreateBoardGroup>).json(schema::<BoardGroup>),
+        op("put", "/board-groups/{group_id}", "Boards", "Rename or move a board group")
+            .body(schema::<UpdateBoardGroup>).json(schema::<BoardGroup>),
+        op("delete", "/board-groups/{group_id}", "Boards", "Delete a board group, keeping its boards"),
+        // Workspaces
+        op("post", "/workspaces", "Workspaces", "Create a workspace")
+            .body(schema::<CreateWorkspace>).json(schema::<WorkspaceResponse>),
+        op("get", "/workspaces", "Workspaces", "List the user's workspaces")
+            .json(schema::<Vec<WorkspaceResponse>>),
+        op("get", "/workspaces/{workspace_id}", "Workspaces", "Get a workspace")
+            .json(schema::<WorkspaceResponse>),
+        op("put", "/workspaces/{workspace_id}", "Workspaces", "Rename a workspace")
+            .body(schema::<UpdateWorkspace>).json(schema::<WorkspaceResponse>),
+        op("delete", "/workspaces/{workspace_id}", "Workspaces", "Delete a workspace, keeping its boards"),
+        op("get", "/workspaces/{workspace_id}/boards", "Workspaces", "List the boards of a workspace")
+            .json(schema::<Vec<BoardResponse>>),
+        op("get", "/workspaces/{workspace_id}/members", "Workspaces", "List the members of a workspace")
+            .json(schema::<Vec<WorkspaceMember>>),
+        op("post", "/workspaces/{workspace_id}/members", "Workspaces", "Add a member to a workspace")
+            .body(schema::<AddWorkspaceMember>),
+        op("delete", "/workspaces/{workspace_id}/members/{user_id}", "Workspaces", "Remove a member from a workspace"),
+        op("put", "/boards/{board_id}/workspace", "Workspaces", "Move a board into or out of a workspace")
+            .body(schema::<SetBoardWorkspace>).json(schema::<BoardResponse>),
+        op("get", "/boards/{board_id}/card-templates", "Templates", "List card templates")
+            .json(schema::<Vec<CardTemplateResponse>>),
+        op("post", "/boards/{board_id}/card-templates", "Templates", "Create a card template")
+            .body(schema::<CreateCardTemplate>).json(schema::<CardTemplateResponse>),
+        op("put", "/boards/{board_id}/card-templates/{template_id}", "Templates", "Update a card template")
+            .body(schema::<UpdateCardTemplate>).json(schema::<CardTemplateResponse>),
+        op("delete", "/boards/{board_id}/card-templates/{template_id}", "Templates", "Delete a card template"),
+        op("post", "/boards/{board_id}/cards/from-template/{template_id}", "Templates", "Create a card from a template")
+            .body(schema::<CreateCardFromTemplate>).json(schema::<CardResponse>),
+        // Saved views
+        op("get", "/boards/{board_id}/views", "Views", "List your saved views of a board")
+            .json(schema::<Vec<SavedViewResponse>>),
+        op("post", "/boards/{board_id}/views", "Views", "Save a named card filter")
+            .body(schema::<CreateSavedView>).json(schema::<SavedViewResponse>),
+        op("put", "/boards/{board_id}/views/{view_id}", "Views", "Update a saved view")
+            .body(schema::<UpdateSavedView>).json(schema::<SavedViewResponse>),
+        op("delete", "/boards/{board_id}/views/{view_id}", "Views", "Delete a saved view"),
+        // Board views
+        op("get", "/boards/{board_id}/forecast", "Reports", "Forecast when open cards will be done")
+            .query(schema::<ForecastQuery>).json(schema::<ForecastResponse>),
+        op("get", "/boards/{board_id}/roadmap", "Reports", "Cards grouped by period")
+            .query(schema::<RoadmapQuery>).json(schema::<RoadmapResponse>),
+        op("get", "/boards/{board_id}/timeline", "Reports", "Cards laid out on a timeline")
+            .query(schema::<TimelineQuery>).json(schema::<TimelineResponse>),
+        op("get", "/boards/{board_id}/stats", "Reports", "Flow statistics of a board")
+            .query(schema::<BoardStatsQuery>).json(schema::<BoardStats>),
+        op("get", "/boards/{board_id}/ws", "Boards", "Live board events over a WebSocket")
+            .websocket(),
+        op("get", "/boards/{board_id}/activity", "Activity", "Activity log of a board")
+            .query(schema::<ActivityQuery>).json(schema::<Vec<ActivityResponse>>),
+        op("get", "/boards/{board_id}/archived", "Boards", "Archived columns and cards of a board")
+            .json(schema::<ArchivedItems>),
+        op("post", "/boards/{board_id}/positions/repair", "Boards", "Renumber column and card positions")
+            .json(schema::<PositionRepair>),
+        // Trash
+        op("get", "/trash", "Trash", "List what the user can restore")
+            .json(schema::<Vec<TrashItemResponse>>),
+        op("post", "/trash/{kind}/{item_id}/restore", "Trash", "Restore a board, column or card"),
+        // Columns
+        op("post", "/boards/{board_id}/columns", "Columns", "Create a column")
+            .body(schema::<CreateColumn>).json(schema::<ColumnResponse>),
+        op("get", "/boards/{board_id}/columns", "Columns", "List the columns of a board")
+            .json(schema::<Vec<ColumnResponse>>),
+        op("post", "/boards/{board_id}/status-sync", "Columns", "Give cards the status their column maps to")
+            .json(schema::<StatusSyncResult>),
+        op("put", "/columns/{column_id}", "Columns", "Update a column")
+            .versioned().body(schema::<UpdateColumn>).json(schema::<ColumnResponse>),
+        op("delete", "/columns/{column_id}", "Columns", "Move a column to the trash"),
+        op("patch", "/columns/{column_id}/move", "Columns", "Move a column to another position")
+            .body(schema::<MoveColumn>).json(schema::<ColumnResponse>),
+        op("post", "/columns/{column_id}/archive", "Columns", "Archive a column")
+            .json(schema::<ColumnResponse>),
+        op("post", "/columns/{column_id}/unarchive", "Columns", "Unarchive a column")
+            .json(schema::<ColumnResponse>),
+        // Cards
+        op("post", "/columns/{column_id}/cards", "Cards", "Create a card in a column")
+            .body(schema::<CreateCard>).json(schema::<CardResponse>),
+        op("post", "/columns/{column_id}/cards/import-text", "Cards", "Create cards from lines of text")
+            .body(schema::<ImportCardsText>).json(schema::<Vec<CardResponse>>),
+        op("get", "/boards/{board_id}/cards", "Cards", "List and filter the cards of a board")
+            .query(schema::<CardFilter>).query(schema::<PageQuery>).page(schema::<CardResponse>),
+        op("post", "/boards/{board_id}/cards/bulk", "Cards", "Apply operations to several cards")
+            .body(schema::<BulkCardRequest>).json(schema::<BulkCardResponse>),
+        op("get", "/cards/{card_id}", "Cards", "Get a card")
+            .versioned().json(schema::<CardResponse>),
+        op("get", "/cards/{card_id}/full", "Cards", "Get a card with its comments, tags and links")
+            .versioned().json(schema::<CardFullResponse>),
+        op("put", "/cards/{card_id}", "Cards", "Update a card")
+            .versioned().body(schema::<UpdateCard>).json(schema::<CardResponse>),
+        op("patch", "/cards/{card_id}", "Cards", "Update some fields of a card")
+            .versioned().body(schema::<UpdateCard>).json(schema::<CardResponse>),
+        op("delete", "/cards/{card_id}", "Cards", "Move a card to the trash"),
+        op("patch", "/cards/{card_id}/move", "Cards", "Move a card to another column or position")
+            .body(schema::<MoveCard>).json(schema::<MoveCardResponse>),
+        op("post", "/cards/{card_id}/clone", "Cards", "Copy a card with its tags")
+            .body(schema::<CloneCard>).json(schema::<CardResponse>),
+        op("post", "/cards/{card_id}/lock", "Cards", "Lock a card against changes")
+            .json(schema::<CardResponse>),
+        op("post", "/cards/{card_id}/archive", "Cards", "Archive a card")
+            .json(schema::<CardResponse>),
+        op("post", "/cards/{card_id}/unarchive", "Cards", "Unarchive a card")
+            .json(schema::<CardResponse>),
+        op("post", "/cards/{card_id}/unlock", "Cards", "Unlock a card")
+            .json(schema::<CardResponse>),
+        op("put", "/cards/{card_id}/cover", "Cards", "Give a card a cover color or image")
+            .body(schema::<CardCover>).json(schema::<CardResponse>),
+        op("delete", "/cards/{card_id}/cover", "Cards", "Remove the cover of a card")
+            .json(schema::<CardResponse>),
+        // Tags
+        op("post", "/boards/{board_id}/tags", "Tags", "Create a board tag")
+            .body(schema::<CreateTag>).json(schema::<TagResponse>),
+        op("get", "/boards/{board_id}/tags", "Tags", "List the tags of a board")
+            .json(schema::<Vec<TagResponse>>),
+        op("put", "/tags/{tag_id}", "Tags", "Update a tag")
+            .body(schema::<UpdateTag>).json(schema::<TagResponse>),
+        op("delete", "/tags/{tag_id}", "Tags", "Delete a tag"),
+        op("put", "/tags/{tag_id}/link", "Tags", "Link a board tag to a global tag")
+            .body(schema::<LinkTag>).json(schema::<TagResponse>),
+        op("delete", "/tags/{tag_id}/link", "Tags", "Unlink a board tag from its global tag")
+            .json(schema::<TagResponse>),
+        op("post", "/boards/{board_id}/tags/link-duplicates", "Tags", "Link board tags named like global tags")
+            .query(schema::<LinkDuplicateTagsQuery>).json(schema::<LinkDuplicateTagsResponse>),
+        op("post", "/cards/{card_id}/tags/{tag_id}", "Tags", "Add a tag to a card"),
+        op("delete", "/cards/{card_id}/tags/{tag_id}", "Tags", "Remove a tag from a card"),
+        // Chat
+        op("post", "/boards/{board_id}/chat", "Chat", "Send a message to the board assistant")
+            .body(schema::<SendChatRequest>).json(schema::<ChatResponse>),
+        op("post", "/boards/{board_id}/chat/stream", "Chat", "Send a message and stream the answer")
+            .body(schema::<SendChatRequest>).event_stream(),
+        op("post", "/boards/{board_id}/chat/confirm", "Chat", "Confirm or cancel proposed actions")
+            .body(schema::<ConfirmChatRequest>).json(schema::<ChatResponse>),
+        op("get", "/boards/{board_id}/chat/history", "Chat", "Chat history of a board")
+            .query(schema::<PageQuery>).page(schema::<ChatMessageResponse>),
+        op("delete", "/boards/{board_id}/chat/history", "Chat", "Clear the chat history of a board")
+            .json(schema::<Value>),
+        op("get", "/boards/{board_id}/chat-stats", "Chat", "Chat usage of a board")
+            .query(schema::<ChatStatsQuery>).json(schema::<ChatStatsResponse>),
+        op("post", "/chat", "Chat", "Send a message to the global assistant")
+            .body(schema::<SendChatRequest>).json(schema::<ChatResponse>),
+        op("get", "/chat/history", "Chat", "Global chat history")
+            .query(schema::<PageQuery>).page(schema::<ChatMessageResponse>),
+        op("get", "/chat/export", "Chat", "Export chat history as Markdown or JSON")
+            .query(schema::<ChatExportQuery>).file(&["text/markdown", "application/json"]),
+        op("post", "/chat/actions/{action_id}/undo", "Chat", "Undo an action taken by the assistant")
+            .json(schema::<ActionTaken>),
+        op("post", "/chat/sessions", "Chat", "Start a guided planning session")
+            .body(schema::<CreateChatSession>).json(schema::<ChatSessionResponse>),
+        op("get", "/chat/sessions/{session_id}", "Chat", "Get a planning session")
+            .json(schema::<ChatSessionResponse>),
+        op("delete", "/chat/sessions/{session_id}", "Chat", "Cancel a planning session")
+            .json(schema::<ChatSessionResponse>),
+        op("post", "/chat/sessions/{session_id}/turns", "Chat", "Answer the current step of a planning session")
+            .body(schema::<PlanningTurn>).json(schema::<ChatSessionResponse>),
+        op("delete", "/chat/history", "Chat", "Clear the global chat history")
+            .json(schema::<Value>),
+        op("post", "/mcp", "Chat", "Send a JSON-RPC message to the MCP server")
+            .raw_body("application/json").json(schema::<Value>),
+        // Inbox
+        op("get", "/cards", "Inbox", "List the user's cards across boards")
+            .query(schema::<ListCardsQuery>).query(schema::<PageQuery>).page(schema::<CardResponse>),
+        op("post", "/cards", "Inbox", "Create a card in the inbox")
+            .body(schema::<CreateGlobalCard>).json(schema::<CardResponse>),
+        op("post", "/quick-add", "Inbox", "Create a card from one line of text")
+            .body(schema::<QuickAddRequest>).json(schema::<QuickAddResponse>),
+        op("get", "/inbox/cards/{card_id}", "Inbox", "Get an inbox card")
+            .versioned().json(schema::<CardResponse>),
+        op("put", "/inbox/cards/{card_id}", "Inbox", "Update an inbox card")
+            .versioned().body(schema::<UpdateCard>).json(schema::<CardResponse>),
+        op("patch", "/inbox/cards/{card_id}", "Inbox", "Update some fields of an inbox card")
+            .versioned().body(schema::<UpdateCard>).json(schema::<CardResponse>),
+        op("delete", "/inbox/cards/{card_id}", "Inbox", "Move an inbox card to the trash"),
+        op("put", "/inbox/cards/{card_id}/status", "Inbox", "Change the status of a card")
+            .body(schema::<UpdateCardStatus>).json(schema::<CardResponse>),
+        op("post", "/cards/{card_id}/boards/{board_id}", "Inbox", "Put a card on a board")
+            .body(schema::<AssignCardToBoard>).json(schema::<CardResponse>),
+        op("delete", "/cards/{card_id}/boards/{board_id}", "Inbox", "Take a card off a board"),
+        op("put", "/cards/{card_id}/boards/{board_id}", "Inbox", "Move a card within a board")
+            .body(schema::<MoveCardInBoard>).json(schema::<MoveCardResponse>),
+        op("get", "/tags", "Tags", "List global tags")
+            .json(schema::<Vec<TagResponse>>),
+        op("post", "/tags", "Tags", "Create a global tag")
+            .body(schema::<CreateGlobalTag>).json(schema::<TagResponse>),
+        // Comments
+        op("get", "/cards/{card_id}/comments", "Comments", "List the comments of a card")
+            .query(schema::<PageQuery>).page(schema::<CommentResponse>),
+        op("post", "/cards/{card_id}/comments", "Comments", "Comment on a card")
+            .body(schema::<CreateComment>).json(schema::<CommentResponse>),
+        op("get", "/cards/{card_id}/comment-draft", "Comments", "Get the user's unsent comment")
+            .json(schema::<Option<CommentDraft>>),
+        op("put", "/cards/{card_id}/comment-draft", "Comments", "Save the user's unsent comment")
+            .body(schema::<SaveCommentDraft>).json(schema::<Option<CommentDraft>>),
+        op("delete", "/cards/{card_id}/comment-draft", "Comments", "Discard the user's unsent comment")
+            .json(schema::<Value>),
+        op("put", "/comments/{comment_id}", "Comments", "Edit a comment")
+            .body(schema::<UpdateComment>).json(schema::<CommentResponse>),
+        op("delete", "/comments/{comment_id}", "Comments", "Delete a comment"),
+        // Revisions
+        op("get", "/cards/{card_id}/revisions", "Revisions", "List the previous versions of a card's title and body")
+            .json(schema::<Vec<CardRevisionResponse>>),
+        op("post", "/cards/{card_id}/revisions/{revision_id}/restore", "Revisions", "Restore a previous title and body")
+            .json(schema::<CardResponse>),
+        // Reactions
+        op("post", "/cards/{card_id}/reactions/{emoji}", "Reactions", "React to a card")
+            .json(schema::<Vec<ReactionCount>>),
+        op("delete", "/cards/{card_id}/reactions/{emoji}", "Reactions", "Take back a reaction to a card")
+            .json(schema::<Vec<ReactionCount>>),
+        op("post", "/comments/{comment_id}/reactions/{emoji}", "Reactions", "React to a comment")
+            .json(schema::<Vec<ReactionCount>>),
+        op("delete", "/comments/{comment_id}/reactions/{emoji}", "Reactions", "Take back a reaction to a comment")
+            .json(schema::<Vec<ReactionCount>>),
+        // Notifications
+        op("get", "/notifications", "Notifications", "List notifications")
+            .query(schema::<ListNotificationsQuery>).json(schema::<Vec<Notification>>),
+        op("get", "/notifications/unread-count", "Notifications", "Count unread notifications")
+            .json(schema::<Value>),
+        op("post", "/notifications/read-all", "Notifications", "Mark every notification as read")
+            .json(schema::<Value>),
+        op("post", "/notifications/{notification_id}/read", "Notifications", "Mark a notification as read"),
+        op("get", "/dashboard", "Dashboard", "Cards needing the user's attention across boards")
+            .query(schema::<DashboardQuery>).json(schema::<DashboardResponse>),
+        op("get", "/search", "Search", "Search cards and the text of their attachments")
+            .query(schema::<SearchQuery>).json(schema::<Vec<SearchResult>>),
+        // Assignees
+        op("get", "/cards/assigned", "Assignees", "List the cards assigned to the user")
+            .query(schema::<ListCardsQuery>).json(schema::<Vec<CardResponse>>),
+        op("get", "/cards/{card_id}/assignees", "Assignees", "List the assignees of a card")
+            .json(schema::<Vec<CardAssignee>>),
+        op("post", "/cards/{card_id}/assignees", "Assignees", "Assign a user to a card")
+            .body(schema::<AssignCardUser>).json(schema::<Vec<CardAssignee>>),
+        op("delete", "/cards/{card_id}/assignees/{user_id}", "Assignees", "Unassign a user from a card")
+            .json(schema::<Vec<CardAssignee>>),
+        // Links
+        op("get", "/cards/{card_id}/links", "Links", "List the links of a card")
+            .json(schema::<Vec<CardLink>>),
+        op("post", "/cards/{card_id}/links", "Links", "Link a card to another one")
+            .body(schema::<CreateCardLink>).json(schema::<Vec<CardLink>>),
+        op("delete", "/cards/{card_id}/links/{link_id}", "Links", "Remove a link")
+            .json(schema::<Vec<CardLink>>),
+        // Watchers
+        op("get", "/cards/{card_id}/watch", "Notifications", "Whether you watch a card")
+            .json(schema::<WatchStatus>),
+        op("put", "/cards/{card_id}/watch", "Notifications", "Watch a card")
+            .json(schema::<WatchStatus>),
+        op("delete", "/cards/{card_id}/watch", "Notifications", "Stop watching a card")
+            .json(schema::<WatchStatus>),
+        // Recurrences
+        op("get", "/cards/{card_id}/recurrence", "Recurrences", "Get the recurrence of a card")
+            .json(schema::<Option<CardRecurrence>>),
+        op("put", "/cards/{card_id}/recurrence", "Recurrences", "Make a card recur")
+            .body(schema::<SetCardRecurrence>).json(schema::<CardRecurrence>),
+        op("delete", "/cards/{card_id}/recurrence", "Recurrences", "Stop a card from recurring"),
+        // Attachments
+        op("get", "/cards/{card_id}/attachments", "Attachments", "List the attachments of a card")
+            .json(schema::<Vec<AttachmentResponse>>),
+        op("post", "/cards/{card_id}/attachments", "Attachments", "Upload an attachment")
+            .upload().json(schema::<AttachmentResponse>),
+        op("get", "/attachments/{attachment_id}", "Attachments", "Download an attachment")
+            .file(&["application/octet-stream"]),
+        op("delete", "/attachments/{attachment_id}", "Attachments", "Delete an attachment"),
+        // Activity
+        op("get", "/cards/{card_id}/activity", "Activity", "Activity log of a card")
+            .query(schema::<ActivityQuery>).json(schema::<Vec<ActivityResponse>>),
+        // Maintenance
+        op("get", "/backfills", "Admin", "Status of the data backfills")
+            .json(schema::<Vec<BackfillStatus>>),
+        // Admin
+        op("get", "/admin/integrity", "Admin", "Scan the database for orphaned rows")
+            .json(schema::<IntegrityReport>),
+        op("post", "/admin/integrity/repair", "Admin", "Repair orphaned rows")
+            .json(schema::<IntegrityReport>),
+        op("get", "/admin/users", "Admin", "List every user")
+            .json(schema::<Vec<UserAccount>>),
+        op("patch", "/admin/users/{user_id}", "Admin", "Deactivate, reactivate or promote a user")
+            .body(schema::<UpdateUserAccount>).json(schema::<UserAccount>),
+        op("get", "/admin/stats", "Admin", "Usage statistics of the instance")
+            .json(schema::<InstanceStats>),
+        op("post", "/admin/boards/{board_id}/transfer", "Admin", "Transfer the ownership of a board")
+            .body(schema::<TransferBoard>).json(schema::<Board>),
+        op("get", "/admin/backup", "Admin", "Download a snapshot of the database")
+            .file(&["application/vnd.sqlite3"]),
+        op("post", "/admin/restore", "Admin", "Restore the database from a snapshot")
+            .raw_body("application/vnd.sqlite3").json(schema::<Value>),
+        // Documentation
+        op("get", "/openapi.json", "Documentation", "This OpenAPI document")
+            .public().json(schema::<Value>),
+        op("get", "/docs", "Documentation", "Swagger UI browsing this document")
+            .public().file(&["text/html"]),
+    ]
+}
+
+/// Build the OpenAPI 3.0 document of the REST API
+pub fn document() -> Value {
+    let mut generator = SchemaSettings::openapi3().into_generator();
+    let error = schema::<ErrorBody>(&mut generator);
+
+    let mut paths = Map::new();
+    for operation in operations() {
+        let path = paths.entry(operation.path).or_insert_with(|| json!({}));
+        path[operation.method] = operation.to_json(&mut generator, &error);
+    }
+
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "Personal OS API",
+            "version": env!("CARGO_PKG_VERSION"),
+            "description": "REST API of Personal OS. See docs/api.md for the conventions.",
+        },
+        "servers": [{"url": "/api"}],
+        "security": [{"session": []}, {"bearer": []}],
+        "paths": paths,
+        "components": {
+            "schemas": generator.take_definitions(true),
+            "securitySchemes": {
+                "session": {"type": "apiKey", "in": "cookie", "name": "session"},
+                "bearer": {"type": "http", "scheme": "bearer"},
+            },
+        },
+    })
+}
+
+impl Operation {
+    fn to_json(&self, generator: &mut SchemaGenerator, error: &Schema) -> Value {
+        let mut parameters: Vec<Value> = path_parameters(self.path);
+        for query in &self.query {
+            parameters.extend(query_parameters(generator, *query));
+        }
+        if self.versioned && self.method != "get" {
+            parameters.push(json!({
+                "name": "If-Match",
+                "in": "header",
+                "description": "Only apply the change if the version still matches this ETag",
+                "schema": {"type": "string"},
+            }));
+        }
+
+        let mut operation = json!({
+            "tags": [self.tag],
+            "summary": self.summary,
+            "operationId": format!("{}_{}", self.method, operation_name(self.path)),
+            "responses": {
+                "default": {
+                    "description": "Error",
+                    "content": {"application/json": {"schema": error}},
+                },
+            },
+        });
+        if !parameters.is_empty() {
+            operation["parameters"] = Value::Array(parameters);
+        }
+        if self.public {
+            operation["security"] = json!([]);
+        }
+        if let Some(body) = &self.body {
+            let content = match body {
+                Body::Json(schema) => json!({"application/json": {"schema": schema(generator)}}),
+                Body::Raw(content_type) => json!({
+                    *content_type: {"schema": {"type": "string", "format": "binary"}}
+                }),
+                Body::Upload => json!({"multipart/form-data": {"schema": {
+                    "type": "object",
+                    "properties": {"file": {"type": "string", "format": "binary"}},
+                    "required": ["file"],
+                }}}),
+            };
+            operation["requestBody"] = json!({"required": true, "content": content});
+        }
+
+        let (status, mut response) = match &self.success {
+            Success::Empty => ("200", json!({"description": "Done"})),
+            Success::NoContent => ("204", json!({"description": "Done"})),
+            Success::Json(schema) => (
+                "200",
+                json!({
+                    "description": "OK",
+                    "content": {"application/json": {"schema": schema(generator)}},
+                }),
+            ),
+            Success::Page(item) => (
+                "200",
+                json!({
+                    "description": "One page of the list",
+                    "headers": {
+                        "X-Total-Count": {
+                            "description": "Length of the whole list",
+                            "schema": {"type": "integer"},
+                        },
+                        "Link": {
+                            "description": "Addresses of the next and previous pages",
+                            "schema": {"type": "string"},
+                        },
+                    },
+                    "content": {"application/json": {"schema": {
+                        "type": "array",
+                        "items": item(generator),
+                    }}},
+                }),
+            ),
+            Success::File(content_types) => (
+                "200",
+                json!({
+                    "description": "File",
+                    "content": content_types
+                        .iter()
+                        .map(|t| (t.to_string(), json!({"schema": {"type": "string", "format": "binary"}})))
+                        .collect::<Map<_, _>>(),
+                }),
+            ),
+            Success::EventStream => (
+                "200",
+                json!({
+                    "description": "Server-sent events",
+                    "content": {"text/event-stream": {"schema": {"type": "string"}}},
+                }),
+            ),
+            Success::WebSocket => ("101", json!({"description": "Switching to a WebSocket"})),
+        };
+        if self.versioned {
+            response["headers"] = json!({
+                "ETag": {
+                    "description": "Version of the resource, for If-Match",
+                    "schema": {"type": "string"},
+                },
+            });
+        }
+        operation["responses"][status] = response;
+
+        operation
+    }
+}
+
+/// Parameters for the `{name}` segments of `path`. Names ending in `_id` are UUIDs.
+fn path_parameters(path: &str) -> Vec<Value> {
+    path.split('/')
+        .filter_map(|segment| segment.strip_prefix('{')?.strip_suffix('}'))
+        .map(|name| {
+            let schema = if name.ends_with("_id") {
+                json!({"type": "string", "format": "uuid"})
+            } else {
+                json!({"type": "string"})
+            };
+            json!({"name": name, "in": "path", "required": true, "schema": schema})
+        })
+        .collect()
+}
+
+/// One query parameter per property of the query struct
+fn query_parameters(generator: &mut SchemaGenerator, query: SchemaFn) -> Vec<Value> {
+    let defined = generator.definitions().len();
+    let reference = query(generator);
+    let name = reference
+        .get("$ref")
+        .and_then(Value::as_str)
+        .and_then(|r| r.rsplit('/').next())
+        .unwrap_or_default()
+        .to_string();
+    // Query structs are only described through their parameters, unless a body
+    // already refers to them
+    let definition = if generator.definitions().len() > defined {
+        generator.definitions_mut().remove(&name)
+    } else {
+        generator.definitions().get(&name).cloned()
+    };
+    let Some(definition) = definition else {
+        return Vec::new();
+    };
+    let required: Vec<&str> = definition["required"]
+        .as_array()
+        .map(|r| r.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+
+    definition["properties"]
+        .as_object()
+        .map(|properties| {
+            properties
+                .iter()
+                .map(|(name, schema)| {
+                    let mut schema = schema.clone();
+                    let description = schema.as_object_mut().and_then(|s| s.remove("description"));
+                    let mut parameter = json!({
+                        "name": name,
+                        "in": "query",
+                        "required": required.contains(&name.as_str()),
+                        "schema": schema,
+                    });
+                    if let Some(description) = description {
+                        parameter["description"] = description;
+                    }
+                    parameter
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// `boards_board_id_cards` for `/boards/{board_id}/cards`
+fn operation_name(path: &str) -> String {
+    path.split('/')
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            s.trim_matches(|c| c == '{' || c == '}')
+                .replace(['-', '.'], "_")
+        })
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    /// (method, path) of every route registered on the API router in lib.rs
+    fn router_routes() -> BTreeSet<(String, String)> {
+        let source = include_str!("../lib.rs");
+        let start = source.find("let api_routes").unwrap();
+        let end = source.find("let web_routes").unwrap();
+        let api: String = source[start..end]
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect();
+
+        api.split(".route(\"")
+            .skip(1)
+            .map(|route| {
+                let (path, rest) = route.split_once("\",").unwrap();
+                let method = rest.split('(').next().unwrap();
+                (method.to_string(), path.to_string())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_document_covers_every_route() {
+        let documented: BTreeSet<(String, String)> = operations()
+            .iter()
+            .map(|op| (op.method.to_string(), op.path.to_string()))
+            .collect();
+
+        assert_eq!(documented, router_routes());
+    }
+
+    #[test]
+    fn test_document_references_resolve() {
+        let document = document();
+        let text = document.to_string();
+        let schemas = document["components"]["schemas"].as_object().unwrap();
+
+        for reference in text.split("\"$ref\":\"#/components/schemas/").skip(1) {
+            let name = reference.split('"').next().unwrap();
+            assert!(schemas.contains_key(name), "missing schema {}", name);
+        }
+        assert_eq!(
+            document["paths"]["/boards/{board_id}"]["get"]["parameters"][0]["name"],
+            "board_id"
+        );
+    }
+}
@@ -0,0 +1,262 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use chrono::Utc;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::auth::AuthUser;
+use crate::error::{AppError, Result};
+use crate::models::{
+    Card, CardStatus, ChatSession, ChatSessionResponse, ChatSessionStatus, CreateChatSession,
+    PlanSummary, PlannedDueDate, PlannedFocus, PlanningItem, PlanningState, PlanningStep,
+    PlanningTurn,
+};
+use crate::services::planning;
+use crate::state::AppState;
+
+/// Build the response for a session from the user's current cards
+fn session_response(session: &ChatSession, cards: &[Card]) -> ChatSessionResponse {
+    let step = session.planning_step();
+    let state = session.planning_state();
+    let by_id: HashMap<Uuid, &Card> = cards.iter().map(|c| (c.id, c)).collect();
+
+    let item = |card: &Card| PlanningItem {
+        card_id: card.id,
+        title: card.title.clone(),
+        status: card.status.clone(),
+        due_date: card.due_date,
+        proposed_due_date: state.due_dates.get(&card.id).copied(),
+    };
+
+    let items: Vec<PlanningItem> = match step {
+        PlanningStep::Overdue => state
+            .overdue
+            .iter()
+            .filter_map(|id| by_id.get(id).map(|c| item(c)))
+            .collect(),
+        PlanningStep::Unscheduled => state
+            .unscheduled
+            .iter()
+            .filter_map(|id| by_id.get(id).map(|c| item(c)))
+            .collect(),
+        PlanningStep::Focus => planning::focus_candidates(cards, &state)
+            .into_iter()
+            .map(item)
+            .collect(),
+        PlanningStep::Review | PlanningStep::Done => Vec::new(),
+    };
+
+    let plan = PlanSummary {
+        due_dates: state
+            .due_dates
+            .iter()
+            .filter_map(|(id, date)| {
+                by_id.get(id).map(|c| PlannedDueDate {
+                    card_id: c.id,
+                    title: c.title.clone(),
+                    due_date: *date,
+                })
+            })
+            .collect(),
+        focus: state
+            .focus
+            .iter()
+            .filter_map(|id| {
+                by_id.get(id).map(|c| PlannedFocus {
+                    card_id: c.id,
+                    title: c.title.clone(),
+                })
+            })
+            .collect(),
+    };
+
+    ChatSessionResponse {
+        id: session.id,
+        session_type: session.session_type.clone(),
+        status: session.session_status(),
+        step,
+        prompt: planning::prompt(step, items.len()),
+        items,
+        plan,
+        created_at: session.created_at,
+        updated_at: session.updated_at,
+    }
+}
+
+/// Start a planning session over the user's own cards
+pub async fn create_session(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Json(input): Json<CreateChatSession>,
+) -> Result<Json<ChatSessionResponse>> {
+    let cards = state.cards.list_by_owner(auth.user.id).await?;
+    let planning_state = planning::initial_state(&cards, Utc::now().date_naive());
+    let step = planning::first_step(&planning_state);
+
+    let session = state
+        .chat_sessions
+        .create(auth.user.id, input.session_type, step, &planning_state)
+        .await?;
+
+    Ok(Json(session_response(&session, &cards)))
+}
+
+pub async fn get_session(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(session_id): Path<Uuid>,
+) -> Result<Json<ChatSessionResponse>> {
+    let session = state
+        .chat_sessions
+        .get_for_user(session_id, auth.user.id)
+        .await?;
+    let cards = state.cards.list_by_owner(auth.user.id).await?;
+
+    Ok(Json(session_response(&session, &cards)))
+}
+
+/// Answer the current step and move the session forward
+pub async fn submit_turn(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(session_id): Path<Uuid>,
+    Json(turn): Json<PlanningTurn>,
+) -> Result<Json<ChatSessionResponse>> {
+    let session = state
+        .chat_sessions
+        .get_for_user(session_id, auth.user.id)
+        .await?;
+
+    if session.session_status() != ChatSessionStatus::Active {
+        return Err(AppError::BadRequest("Session is not active".to_string()));
+    }
+
+    let cards = state.cards.list_by_owner(auth.user.id).await?;
+    let step = session.planning_step();
+    let mut planning_state = session.planning_state();
+
+    let (status, next) = match step {
+        PlanningStep::Overdue | PlanningStep::Unscheduled => {
+            let allowed = if step == PlanningStep::Overdue {
+                planning_state.overdue.clone()
+            } else {
+                planning_state.unscheduled.clone()
+            };
+            planning::apply_due_dates(&mut planning_state, &allowed, &turn)
+                .map_err(AppError::Validation)?;
+            (
+                ChatSessionStatus::Active,
+                planning::next_step(step, &planning_state),
+            )
+        }
+        PlanningStep::Focus => {
+            let candidates: Vec<Uuid> = planning::focus_candidates(&cards, &planning_state)
+                .into_iter()
+                .map(|c| c.id)
+                .collect();
+            planning::apply_focus(&mut planning_state, &candidates, &turn)
+                .map_err(AppError::Validation)?;
+            (
+                ChatSessionStatus::Active,
+                planning::next_step(step, &planning_state),
+            )
+        }
+        PlanningStep::Review => match turn.confirm {
+            Some(true) => {
+                apply_plan(&state, &cards, &planning_state).await?;
+                (ChatSessionStatus::Completed, PlanningStep::Done)
+            }
+            Some(false) => (ChatSessionStatus::Cancelled, PlanningStep::Done),
+            None => {
+                return Err(AppError::Validation(
+                    "confirm is required to finish the session".to_string(),
+                ))
+            }
+        },
+        PlanningStep::Done => {
+            return Err(AppError::BadRequest("Session is finished".to_string()));
+        }
+    };
+
+    let session = state
+        .chat_sessions
+        .update(session.id, status, next, &planning_state)
+        .await?;
+
+    let cards = state.cards.list_by_owner(auth.user.id).await?;
+    Ok(Json(session_response(&session, &cards)))
+}
+
+/// Cancel a session without applying its plan
+pub async fn cancel_session(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(session_id): Path<Uuid>,
+) -> Result<Json<ChatSessionResponse>> {
+    let session = state
+        .chat_sessions
+        .get_for_user(session_id, auth.user.id)
+        .await?;
+
+    let session = if session.session_status() == ChatSessionStatus::Active {
+        state
+            .chat_sessions
+            .update(
+                session.id,
+                ChatSessionStatus::Cancelled,
+                PlanningStep::Done,
+                &session.planning_state(),
+            )
+            .await?
+    } else {
+        session
+    };
+
+    let cards = state.cards.list_by_owner(auth.user.id).await?;
+    Ok(Json(session_response(&session, &cards)))
+}
+
+/// Set the planned due dates and start the focus cards. Cards that were
+/// deleted, given away or locked since the session started are skipped.
+async fn apply_plan(state: &AppState, cards: &[Card], plan: &PlanningState) -> Result<()> {
+    let by_id: HashMap<Uuid, &Card> = cards.iter().map(|c| (c.id, c)).collect();
+
+    for (card_id, due_date) in &plan.due_dates {
+        match by_id.get(card_id) {
+            Some(card) if !card.is_locked() => {
+                state
+                    .cards
+                    .update(
+                        card.id,
+                        None,
+                        None,
+                        None,
+                        None,
+                        None,
+                        None,
+                        Some(*due_date),
+                        None,
+                        None,
+                    )
+                    .await?;
+            }
+            _ => continue,
+        }
+    }
+
+    for card_id in &plan.focus {
+        match by_id.get(card_id) {
+            Some(card) if !card.is_locked() && card.status == CardStatus::Open.to_string() => {
+                state
+                    .cards
+                    .update_status(card.id, CardStatus::InProgress)
+                    .await?;
+            }
+            _ => continue,
+        }
+    }
+
+    Ok(())
+}
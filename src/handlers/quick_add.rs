@@ -0,0 +1,205 @@
+use axum::{extract::State, http::HeaderMap, Json};
+use chrono::Utc;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::auth::AuthUser;
+use crate::error::{AppError, Result};
+use crate::handlers::activity::record;
+use crate::handlers::chat::board_candidates;
+use crate::handlers::columns::{check_wip_limit, wip_warning_headers};
+use crate::models::{
+    BoardEvent, BoardEventKind, Card, CardResponse, CardStatus, CardVisibility, QuickAddRequest,
+    QuickAddResponse, Tag,
+};
+use crate::services::board_ref::{self, BoardMatch};
+use crate::services::quick_add::{self, QuickAdd};
+use crate::state::AppState;
+
+/// Color of the tags quick-add creates, as for tags created without one
+const DEFAULT_TAG_COLOR: &str = "#6c757d";
+
+/// Create a card from one line of text such as `Pay rent tomorrow #finance !high @Home`.
+/// The card goes to the default column of the board named with `@`, or else to the
+/// inbox. Tags that don't exist yet are created.
+pub async fn quick_add(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Json(input): Json<QuickAddRequest>,
+) -> Result<(HeaderMap, Json<QuickAddResponse>)> {
+    let user_id = auth.user.id;
+    let today = input.today.unwrap_or_else(|| Utc::now().date_naive());
+
+    // Board names are only needed to read a reference of several words
+    let candidates = if input.text.contains('@') {
+        board_candidates(&state, user_id).await?
+    } else {
+        Vec::new()
+    };
+    let board_names: Vec<&str> = candidates.iter().map(|c| c.board.name.as_str()).collect();
+    let parsed = quick_add::parse(&input.text, today, &board_names);
+
+    if parsed.title.is_empty() {
+        return Err(AppError::invalid_field("text", "Card title is required"));
+    }
+
+    let Some(reference) = parsed.board.clone() else {
+        let card = add_to_inbox(&state, user_id, &parsed).await?;
+        return Ok((
+            HeaderMap::new(),
+            Json(QuickAddResponse {
+                card,
+                board_id: None,
+            }),
+        ));
+    };
+
+    let candidate = match board_ref::resolve(&reference, candidates) {
+        BoardMatch::Found(candidate) => candidate,
+        BoardMatch::NotFound => {
+            return Err(AppError::invalid_field(
+                "text",
+                format!("No board named '{}'", reference),
+            ))
+        }
+        BoardMatch::Ambiguous(_) => {
+            return Err(AppError::invalid_field(
+                "text",
+                format!(
+                    "Several boards are named '{}', add the card from the board instead",
+                    reference
+                ),
+            ))
+        }
+    };
+    let board = candidate.board;
+    let role = state
+        .boards
+        .get_user_role(board.id, user_id)
+        .await?
+        .ok_or(AppError::Forbidden)?;
+    if !role.can_edit() {
+        return Err(AppError::Forbidden);
+    }
+
+    let columns = state.columns.list_by_board(board.id).await?;
+    let column = board
+        .default_column_id
+        .and_then(|id| columns.iter().find(|c| c.id == id))
+        .or_else(|| columns.first())
+        .ok_or_else(|| {
+            AppError::invalid_field(
+                "text",
+                format!("Board '{}' has no column to add the card to", board.name),
+            )
+        })?;
+
+    let wip_warning = check_wip_limit(&state, column).await?;
+    let card = state
+        .cards
+        .create(
+            column.id,
+            &parsed.title,
+            None,
+            None,
+            CardVisibility::Restricted,
+            CardStatus::Open,
+            None,
+            None,
+            parsed.due_date,
+            parsed.priority,
+            user_id,
+        )
+        .await?;
+    record(
+        &state,
+        BoardEvent::new(BoardEventKind::CardCreated, board.id, card.id, user_id)
+            .with_details(json!({"title": card.title, "column_id": column.id})),
+    )
+    .await;
+
+    let existing = state.tags.list_by_board(board.id).await?;
+    let mut tags = Vec::new();
+    for name in &parsed.tags {
+        let tag = match find_tag(&existing, name) {
+            Some(tag) => tag.clone(),
+            None => {
+                let tag = state.tags.create(board.id, name, DEFAULT_TAG_COLOR).await?;
+                record(
+                    &state,
+                    BoardEvent::new(BoardEventKind::TagCreated, board.id, tag.id, user_id)
+                        .with_details(json!({"name": tag.name, "color": tag.color})),
+                )
+                .await;
+                tag
+            }
+        };
+        state.tags.add_to_card(card.id, tag.id).await?;
+        record(
+            &state,
+            BoardEvent::new(BoardEventKind::CardTagged, board.id, tag.id, user_id)
+                .with_card(card.id)
+                .with_details(json!({"tag": tag.name})),
+        )
+        .await;
+        tags.push(tag);
+    }
+
+    Ok((
+        wip_warning_headers(wip_warning),
+        Json(QuickAddResponse {
+            card: card_response(&state, card, tags).await?,
+            board_id: Some(board.id),
+        }),
+    ))
+}
+
+/// Create the card in the user's inbox, with their global tags
+async fn add_to_inbox(state: &AppState, user_id: Uuid, parsed: &QuickAdd) -> Result<CardResponse> {
+    let card = state
+        .cards
+        .create_standalone(
+            &parsed.title,
+            None,
+            CardVisibility::Private,
+            CardStatus::Open,
+            None,
+            None,
+            parsed.due_date,
+            parsed.priority,
+            user_id,
+        )
+        .await?;
+
+    let existing = state.tags.list_by_owner(user_id).await?;
+    let mut tags = Vec::new();
+    for name in &parsed.tags {
+        let tag = match find_tag(&existing, name) {
+            Some(tag) => tag.clone(),
+            None => {
+                state
+                    .tags
+                    .create_global(user_id, name, DEFAULT_TAG_COLOR)
+                    .await?
+            }
+        };
+        state.tags.add_to_card(card.id, tag.id).await?;
+        tags.push(tag);
+    }
+
+    card_response(state, card, tags).await
+}
+
+fn find_tag<'a>(tags: &'a [Tag], name: &str) -> Option<&'a Tag> {
+    tags.iter().find(|t| t.name.eq_ignore_ascii_case(name))
+}
+
+/// The card as stored once tagged, since tagging may have changed its version
+async fn card_response(state: &AppState, card: Card, tags: Vec<Tag>) -> Result<CardResponse> {
+    let card = if tags.is_empty() {
+        card
+    } else {
+        state.cards.get_by_id(card.id).await?
+    };
+    Ok(card.into_response(tags.into_iter().map(|t| t.into()).collect()))
+}
@@ -0,0 +1,96 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use uuid::Uuid;
+
+use crate::auth::AuthUser;
+use crate::error::{AppError, Result};
+use crate::handlers::comments::can_view_card;
+use crate::models::{ReactionCount, REACTION_EMOJI};
+use crate::state::AppState;
+
+fn check_emoji(emoji: &str) -> Result<()> {
+    if REACTION_EMOJI.contains(&emoji) {
+        Ok(())
+    } else {
+        Err(AppError::invalid_field(
+            "emoji",
+            format!("Reactions are one of {}", REACTION_EMOJI.join(" ")),
+        ))
+    }
+}
+
+/// React to a card. Anyone who can see the card can react to it.
+pub async fn add_card_reaction(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path((card_id, emoji)): Path<(Uuid, String)>,
+) -> Result<Json<Vec<ReactionCount>>> {
+    check_emoji(&emoji)?;
+    if !can_view_card(&state, card_id, auth.user.id).await? {
+        return Err(AppError::Forbidden);
+    }
+    state
+        .reactions
+        .add(card_id, None, auth.user.id, &emoji)
+        .await?;
+    let reactions = state.reactions.list_for_card(card_id, auth.user.id).await?;
+    Ok(Json(reactions.card))
+}
+
+/// Take back a reaction to a card
+pub async fn remove_card_reaction(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path((card_id, emoji)): Path<(Uuid, String)>,
+) -> Result<Json<Vec<ReactionCount>>> {
+    // Always allowed, so users who lost access can still take theirs back
+    state.cards.get_by_id(card_id).await?;
+    state
+        .reactions
+        .remove(card_id, None, auth.user.id, &emoji)
+        .await?;
+    let reactions = state.reactions.list_for_card(card_id, auth.user.id).await?;
+    Ok(Json(reactions.card))
+}
+
+/// React to a comment. Anyone who can see its card can react to it.
+pub async fn add_comment_reaction(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path((comment_id, emoji)): Path<(Uuid, String)>,
+) -> Result<Json<Vec<ReactionCount>>> {
+    check_emoji(&emoji)?;
+    let comment = state.comments.get_by_id(comment_id).await?;
+    if !can_view_card(&state, comment.card_id, auth.user.id).await? {
+        return Err(AppError::Forbidden);
+    }
+    state
+        .reactions
+        .add(comment.card_id, Some(comment_id), auth.user.id, &emoji)
+        .await?;
+    let reactions = state
+        .reactions
+        .list_for_card(comment.card_id, auth.user.id)
+        .await?;
+    Ok(Json(reactions.for_comment(comment_id)))
+}
+
+/// Take back a reaction to a comment
+pub async fn remove_comment_reaction(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path((comment_id, emoji)): Path<(Uuid, String)>,
+) -> Result<Json<Vec<ReactionCount>>> {
+    let comment = state.comments.get_by_id(comment_id).await?;
+    state
+        .reactions
+        .remove(comment.card_id, Some(comment_id), auth.user.id, &emoji)
+        .await?;
+    let reactions = state
+        .reactions
+        .list_for_card(comment.card_id, auth.user.id)
+        .await?;
+    Ok(Json(reactions.for_comment(comment_id)))
+}
@@ -0,0 +1,249 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use chrono::NaiveDate;
+use serde_json::json;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::auth::AuthUser;
+use crate::error::{AppError, Result};
+use crate::handlers::activity::record_for_card;
+use crate::handlers::comments::{can_edit_card, can_view_card};
+use crate::models::{
+    BoardEventKind, CardRecurrence, CardStatus, CardVisibility, RecurrenceFrequency,
+    SetCardRecurrence,
+};
+use crate::services::recurrence::next_occurrence;
+use crate::state::AppState;
+
+/// How often the scheduler looks for due recurring cards
+const SCHEDULER_PERIOD: Duration = Duration::from_secs(5 * 60);
+
+/// Largest accepted interval between two copies
+const MAX_INTERVAL: i64 = 365;
+
+/// Today's date on the server, which decides when copies are due
+fn today() -> NaiveDate {
+    chrono::Local::now().date_naive()
+}
+
+/// Get the recurrence rule of a card, or null if it has none
+pub async fn get_recurrence(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(card_id): Path<Uuid>,
+) -> Result<Json<Option<CardRecurrence>>> {
+    if !can_view_card(&state, card_id, auth.user.id).await? {
+        return Err(AppError::Forbidden);
+    }
+
+    let recurrence = state.recurrences.find_by_card(card_id).await?;
+    Ok(Json(recurrence))
+}
+
+/// Make a card recurring, or replace its rule. The first copy is created on the
+/// first occurrence from today on.
+pub async fn set_recurrence(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(card_id): Path<Uuid>,
+    Json(input): Json<SetCardRecurrence>,
+) -> Result<Json<CardRecurrence>> {
+    if !can_edit_card(&state, card_id, auth.user.id).await? {
+        return Err(AppError::Forbidden);
+    }
+
+    state.cards.ensure_unlocked(card_id).await?;
+
+    let interval = input.interval.unwrap_or(1);
+    if !(1..=MAX_INTERVAL).contains(&interval) {
+        return Err(AppError::invalid_field(
+            "interval",
+            format!("Interval must be between 1 and {}", MAX_INTERVAL),
+        ));
+    }
+
+    let today = today();
+    let starts_on = input.starts_on.unwrap_or(today);
+    let next_run_on = today
+        .pred_opt()
+        .and_then(|yesterday| {
+            next_occurrence(input.frequency, interval as u32, starts_on, yesterday)
+        })
+        .ok_or_else(|| AppError::invalid_field("starts_on", "Start date is out of range"))?;
+
+    let recurrence = state
+        .recurrences
+        .set(
+            card_id,
+            input.frequency,
+            interval,
+            starts_on,
+            next_run_on,
+            auth.user.id,
+        )
+        .await?;
+
+    record_for_card(
+        &state,
+        BoardEventKind::CardUpdated,
+        card_id,
+        card_id,
+        auth.user.id,
+        Some(json!({"recurrence": {
+            "frequency": recurrence.frequency,
+            "interval": recurrence.interval,
+            "starts_on": recurrence.starts_on,
+        }})),
+    )
+    .await;
+
+    Ok(Json(recurrence))
+}
+
+/// Stop a card from recurring. Copies made so far are kept.
+pub async fn delete_recurrence(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(card_id): Path<Uuid>,
+) -> Result<()> {
+    if !can_edit_card(&state, card_id, auth.user.id).await? {
+        return Err(AppError::Forbidden);
+    }
+
+    if !state.recurrences.delete(card_id).await? {
+        return Err(AppError::NotFound("recurrence"));
+    }
+
+    record_for_card(
+        &state,
+        BoardEventKind::CardUpdated,
+        card_id,
+        card_id,
+        auth.user.id,
+        Some(json!({"recurrence": null})),
+    )
+    .await;
+    Ok(())
+}
+
+/// Copy every recurring card that is due on or before `today`, returning the number
+/// of cards created.
+///
+/// Each due rule creates a single copy, due on the scheduled date, even if several
+/// occurrences were missed while the server was down. A failing rule is logged and
+/// retried on the next run.
+pub async fn create_due_cards(state: &AppState, today: NaiveDate) -> Result<usize> {
+    let mut created = 0;
+    for recurrence in state.recurrences.list_due(today).await? {
+        match create_copy(state, &recurrence, today).await {
+            Ok(()) => created += 1,
+            Err(e) => {
+                tracing::warn!(
+                    card_id = %recurrence.card_id,
+                    error = %e,
+                    "Could not create recurring card"
+                );
+            }
+        }
+    }
+
+    Ok(created)
+}
+
+async fn create_copy(
+    state: &AppState,
+    recurrence: &CardRecurrence,
+    today: NaiveDate,
+) -> Result<()> {
+    let template = state.cards.get_by_id(recurrence.card_id).await?;
+    let visibility: CardVisibility = template
+        .visibility
+        .parse()
+        .unwrap_or(CardVisibility::Private);
+    let due_date = Some(recurrence.next_run_on);
+
+    let card = match template.column_id {
+        Some(column_id) => {
+            state
+                .cards
+                .create(
+                    column_id,
+                    &template.title,
+                    template.body.as_deref(),
+                    None,
+                    visibility,
+                    CardStatus::Open,
+                    None,
+                    None,
+                    due_date,
+                    template.priority(),
+                    recurrence.created_by,
+                )
+                .await?
+        }
+        None => {
+            state
+                .cards
+                .create_standalone(
+                    &template.title,
+                    template.body.as_deref(),
+                    visibility,
+                    CardStatus::Open,
+                    None,
+                    None,
+                    due_date,
+                    template.priority(),
+                    template.owner_id.unwrap_or(recurrence.created_by),
+                )
+                .await?
+        }
+    };
+
+    for tag in state.tags.list_for_card(template.id).await? {
+        state.tags.add_to_card(card.id, tag.id).await?;
+    }
+
+    let frequency: RecurrenceFrequency =
+        recurrence.frequency.parse().map_err(AppError::Internal)?;
+    let next_run_on = next_occurrence(
+        frequency,
+        recurrence.interval as u32,
+        recurrence.starts_on,
+        today,
+    )
+    .ok_or_else(|| AppError::Internal("Recurrence is out of range".to_string()))?;
+    state
+        .recurrences
+        .mark_run(recurrence.card_id, next_run_on)
+        .await?;
+
+    record_for_card(
+        state,
+        BoardEventKind::CardCreated,
+        card.id,
+        card.id,
+        recurrence.created_by,
+        Some(json!({
+            "title": card.title,
+            "column_id": card.column_id,
+            "recurring_card_id": template.id,
+        })),
+    )
+    .await;
+    Ok(())
+}
+
+/// Create due recurring cards now and then, until the server shuts down
+pub async fn run_scheduler(state: AppState) {
+    let mut ticks = tokio::time::interval(SCHEDULER_PERIOD);
+    while state.jobs.tick(&mut ticks).await {
+        match create_due_cards(&state, today()).await {
+            Ok(0) => {}
+            Ok(created) => tracing::info!("Created {} recurring card(s)", created),
+            Err(e) => tracing::warn!(error = %e, "Recurring card run failed"),
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use uuid::Uuid;
+
+use crate::auth::AuthUser;
+use crate::error::{AppError, Result};
+use crate::handlers::activity::record_for_card;
+use crate::handlers::cards::card_response;
+use crate::handlers::comments::{can_edit_card, can_view_card};
+use crate::models::{BoardEventKind, CardResponse, CardRevisionResponse};
+use crate::services::activity::changes;
+use crate::services::revisions::diff;
+use crate::state::AppState;
+
+/// Previous versions of the card's title and body, newest first, each with what the
+/// edit that replaced it changed
+pub async fn list_revisions(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(card_id): Path<Uuid>,
+) -> Result<Json<Vec<CardRevisionResponse>>> {
+    if !can_view_card(&state, card_id, auth.user.id).await? {
+        return Err(AppError::Forbidden);
+    }
+
+    let card = state.cards.get_by_id(card_id).await?;
+    let revisions = state.revisions.list_for_card(card_id).await?;
+
+    // Each revision was replaced by the next newer one, the newest by the card itself
+    let mut next = (card.title, card.body);
+    let mut responses = Vec::with_capacity(revisions.len());
+    for revision in revisions {
+        let diff = diff(
+            &revision.title,
+            revision.body.as_deref(),
+            &next.0,
+            next.1.as_deref(),
+        );
+        next = (revision.title.clone(), revision.body.clone());
+        responses.push(CardRevisionResponse {
+            id: revision.id,
+            card_id: revision.card_id,
+            title: revision.title,
+            body: revision.body,
+            edited_by: revision.edited_by,
+            editor_name: revision.editor_name,
+            created_at: revision.created_at,
+            diff,
+        });
+    }
+
+    Ok(Json(responses))
+}
+
+/// Put a previous title and body back. The version it replaces is kept as a new
+/// revision, so restoring can be undone the same way.
+pub async fn restore_revision(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path((card_id, revision_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<CardResponse>> {
+    if !can_edit_card(&state, card_id, auth.user.id).await? {
+        return Err(AppError::Forbidden);
+    }
+    state.cards.ensure_unlocked(card_id).await?;
+
+    let revision = state.revisions.get_by_id(card_id, revision_id).await?;
+    let before = state.cards.get_by_id(card_id).await?;
+    let card = state
+        .cards
+        .update(
+            card_id,
+            Some(&revision.title),
+            Some(revision.body.as_deref()),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .await?;
+    state.revisions.record(&before, &card, auth.user.id).await?;
+
+    if let Some(details) = changes(&before, &card) {
+        record_for_card(
+            &state,
+            BoardEventKind::CardUpdated,
+            card.id,
+            card.id,
+            auth.user.id,
+            Some(details),
+        )
+        .await;
+    }
+
+    card_response(&state, card).await
+}
@@ -0,0 +1,94 @@
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use uuid::Uuid;
+
+use crate::auth::AuthUser;
+use crate::error::{AppError, Result};
+use crate::models::{
+    BoardRole, CardFilter, CardStatus, RoadmapBucket, RoadmapCard, RoadmapGrouping, RoadmapQuery,
+    RoadmapResponse,
+};
+use crate::services::roadmap;
+use crate::state::AppState;
+
+pub(crate) const DEFAULT_MILESTONE_TAG: &str = "milestone";
+
+/// Bucket the open cards of a board by due date. Cards tagged with
+/// `milestone_tag` (any status) are returned as milestones instead.
+pub(crate) async fn board_roadmap(
+    state: &AppState,
+    board_id: Uuid,
+    user_id: Uuid,
+    role: &BoardRole,
+    grouping: RoadmapGrouping,
+    milestone_tag: &str,
+) -> Result<Vec<RoadmapBucket>> {
+    let role = role.to_string();
+    let board_cards = state
+        .cards
+        .list_by_board_with_filter(board_id, user_id, Some(&role), &CardFilter::default())
+        .await?;
+
+    let mut cards = Vec::new();
+    let mut milestones = Vec::new();
+    for card in board_cards {
+        let tags = state.tags.list_for_card(card.id).await?;
+        let is_milestone = tags
+            .iter()
+            .any(|t| t.name.eq_ignore_ascii_case(milestone_tag));
+        let status: CardStatus = card.status.parse().unwrap_or_default();
+        let entry = RoadmapCard {
+            id: card.id,
+            column_id: card.column_id,
+            title: card.title,
+            status: card.status,
+            due_date: card.due_date,
+        };
+
+        if is_milestone {
+            milestones.push(entry);
+        } else if matches!(status, CardStatus::Open | CardStatus::InProgress) {
+            cards.push(entry);
+        }
+    }
+
+    Ok(roadmap::build(cards, milestones, grouping))
+}
+
+/// Group the open cards of a board by month or quarter of their due date
+pub async fn get_roadmap(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(board_id): Path<Uuid>,
+    Query(query): Query<RoadmapQuery>,
+) -> Result<Json<RoadmapResponse>> {
+    let role = state
+        .boards
+        .get_user_role(board_id, auth.user.id)
+        .await?
+        .ok_or(AppError::Forbidden)?;
+
+    let group_by = query.group_by.unwrap_or_default();
+    let milestone_tag = query
+        .milestone_tag
+        .as_deref()
+        .unwrap_or(DEFAULT_MILESTONE_TAG);
+
+    let buckets = board_roadmap(
+        &state,
+        board_id,
+        auth.user.id,
+        &role,
+        group_by,
+        milestone_tag,
+    )
+    .await?;
+
+    Ok(Json(RoadmapResponse {
+        board_id,
+        group_by,
+        buckets,
+    }))
+}
@@ -0,0 +1,145 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use uuid::Uuid;
+
+use crate::auth::AuthUser;
+use crate::error::{AppError, Result};
+use crate::models::{CardFilter, CreateSavedView, SavedView, SavedViewResponse, UpdateSavedView};
+use crate::state::AppState;
+
+/// A view of the user on the board in the path. Views are personal, so another
+/// user's view is not found rather than forbidden.
+pub(crate) async fn user_view(
+    state: &AppState,
+    board_id: Uuid,
+    view_id: Uuid,
+    user_id: Uuid,
+) -> Result<SavedView> {
+    let view = state.saved_views.get_by_id(view_id).await?;
+    if view.board_id != board_id || view.user_id != user_id {
+        return Err(AppError::NotFound("saved_view"));
+    }
+    Ok(view)
+}
+
+async fn check_member(state: &AppState, board_id: Uuid, user_id: Uuid) -> Result<()> {
+    state
+        .boards
+        .get_user_role(board_id, user_id)
+        .await?
+        .ok_or(AppError::Forbidden)?;
+    Ok(())
+}
+
+fn validate_name(name: &str) -> Result<&str> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::invalid_field("name", "View name is required"));
+    }
+    Ok(name)
+}
+
+async fn check_name_free(
+    state: &AppState,
+    board_id: Uuid,
+    user_id: Uuid,
+    name: &str,
+    except: Option<Uuid>,
+) -> Result<()> {
+    if state
+        .saved_views
+        .name_taken(board_id, user_id, name, except)
+        .await?
+    {
+        return Err(AppError::Conflict(format!(
+            "A view named '{}' already exists",
+            name
+        )));
+    }
+    Ok(())
+}
+
+fn filter_json(filter: &CardFilter) -> Result<String> {
+    serde_json::to_string(filter).map_err(|e| AppError::Internal(e.to_string()))
+}
+
+/// List the user's saved views of a board
+pub async fn list_views(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(board_id): Path<Uuid>,
+) -> Result<Json<Vec<SavedViewResponse>>> {
+    check_member(&state, board_id, auth.user.id).await?;
+
+    let views = state
+        .saved_views
+        .list_for_user(board_id, auth.user.id)
+        .await?;
+    Ok(Json(views.into_iter().map(Into::into).collect()))
+}
+
+/// Save a named card filter for the board. Any member can, since a view only
+/// changes what the member sees.
+pub async fn create_view(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(board_id): Path<Uuid>,
+    Json(input): Json<CreateSavedView>,
+) -> Result<Json<SavedViewResponse>> {
+    check_member(&state, board_id, auth.user.id).await?;
+
+    let view = create_user_view(&state, board_id, auth.user.id, &input.name, &input.filter).await?;
+    Ok(Json(view.into()))
+}
+
+/// Create a view of a member, also used by the board page
+pub(crate) async fn create_user_view(
+    state: &AppState,
+    board_id: Uuid,
+    user_id: Uuid,
+    name: &str,
+    filter: &CardFilter,
+) -> Result<SavedView> {
+    let name = validate_name(name)?;
+    check_name_free(state, board_id, user_id, name, None).await?;
+
+    state
+        .saved_views
+        .create(board_id, user_id, name, &filter_json(filter)?)
+        .await
+}
+
+pub async fn update_view(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path((board_id, view_id)): Path<(Uuid, Uuid)>,
+    Json(input): Json<UpdateSavedView>,
+) -> Result<Json<SavedViewResponse>> {
+    check_member(&state, board_id, auth.user.id).await?;
+    user_view(&state, board_id, view_id, auth.user.id).await?;
+
+    let name = input.name.as_deref().map(validate_name).transpose()?;
+    if let Some(name) = name {
+        check_name_free(&state, board_id, auth.user.id, name, Some(view_id)).await?;
+    }
+    let filter = input.filter.as_ref().map(filter_json).transpose()?;
+
+    let view = state
+        .saved_views
+        .update(view_id, name, filter.as_deref())
+        .await?;
+    Ok(Json(view.into()))
+}
+
+pub async fn delete_view(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path((board_id, view_id)): Path<(Uuid, Uuid)>,
+) -> Result<()> {
+    check_member(&state, board_id, auth.user.id).await?;
+    user_view(&state, board_id, view_id, auth.user.id).await?;
+
+    state.saved_views.delete(view_id).await
+}
@@ -0,0 +1,74 @@
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+
+use crate::auth::AuthUser;
+use crate::error::{AppError, Result};
+use crate::models::{AttachmentSearchMatch, SearchQuery, SearchResult};
+use crate::services::attachments::{search_terms, snippet_html};
+use crate::state::AppState;
+
+/// Results returned at most
+const MAX_RESULTS: usize = 50;
+/// Matches read from each source; a card can match through several attachments
+const CANDIDATES: i64 = 200;
+
+/// Cards matching the query across every board, first those whose title or body
+/// contains it, then those matching only through the text of their attachments
+pub async fn search(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Query(query): Query<SearchQuery>,
+) -> Result<Json<Vec<SearchResult>>> {
+    let text = query.q.trim();
+    if text.is_empty() {
+        return Err(AppError::invalid_field("q", "Search text is required"));
+    }
+
+    // Both sources only return cards the user can see
+    let cards = state
+        .cards
+        .search_text(text, auth.user.id, CANDIDATES)
+        .await?;
+    let attachments = match search_terms(text) {
+        Some(terms) => {
+            state
+                .attachments
+                .search_text(&terms, auth.user.id, CANDIDATES)
+                .await?
+        }
+        None => Vec::new(),
+    };
+
+    let mut results: Vec<SearchResult> = cards
+        .into_iter()
+        .map(|card| SearchResult {
+            card_id: card.id,
+            card_title: card.title,
+            status: card.status,
+            card_matches: true,
+            attachments: Vec::new(),
+        })
+        .collect();
+    for attachment in attachments {
+        let matched = AttachmentSearchMatch {
+            id: attachment.id,
+            filename: attachment.filename,
+            snippet: snippet_html(&attachment.snippet),
+        };
+        match results.iter_mut().find(|r| r.card_id == attachment.card_id) {
+            Some(result) => result.attachments.push(matched),
+            None => results.push(SearchResult {
+                card_id: attachment.card_id,
+                card_title: attachment.card_title,
+                status: attachment.card_status,
+                card_matches: false,
+                attachments: vec![matched],
+            }),
+        }
+    }
+
+    results.truncate(MAX_RESULTS);
+    Ok(Json(results))
+}
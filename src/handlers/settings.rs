@@ -0,0 +1,97 @@
+use axum::{extract::State, Json};
+
+use crate::auth::AuthUser;
+use crate::error::{AppError, Result};
+use crate::models::{Theme, UpdateUserSettings, User, UserSettings};
+use crate::services::ProviderKind;
+use crate::state::AppState;
+
+/// Highest temperature a user can choose, the upper bound of OpenAI-compatible APIs
+const MAX_LLM_TEMPERATURE: f64 = 2.0;
+
+pub async fn get_settings(auth: AuthUser) -> Json<UserSettings> {
+    Json(UserSettings::from(&auth.user))
+}
+
+/// Update the settings present in the request; omitted fields are left unchanged
+pub async fn update_settings(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Json(input): Json<UpdateUserSettings>,
+) -> Result<Json<UserSettings>> {
+    let mut user = auth.user;
+
+    if let Some(theme) = input.theme {
+        let theme: Theme = theme.parse().map_err(AppError::BadRequest)?;
+        user = state.users.update_theme(user.id, theme).await?;
+    }
+
+    if let Some(context) = input.llm_context {
+        // Trim and convert empty string to None, as the settings page does
+        let context = context
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty());
+        user = state
+            .users
+            .update_llm_context(user.id, context.as_deref())
+            .await?;
+    }
+
+    if let Some(enabled) = input.chat_confirm_actions {
+        user = state
+            .users
+            .update_chat_confirm_actions(user.id, enabled)
+            .await?;
+    }
+
+    if input.llm_provider.is_some() || input.llm_model.is_some() || input.llm_temperature.is_some()
+    {
+        let provider = input.llm_provider.unwrap_or(user.llm_provider.clone());
+        let model = input.llm_model.unwrap_or(user.llm_model.clone());
+        let temperature = input.llm_temperature.unwrap_or(user.llm_temperature);
+        user = save_llm_preferences(&state, &user, provider, model, temperature).await?;
+    }
+
+    Ok(Json(UserSettings::from(&user)))
+}
+
+/// Save the LLM used for the user's chat requests. The provider must be configured on
+/// the server and the temperature between 0 and 2. Empty strings go back to the defaults.
+pub async fn save_llm_preferences(
+    state: &AppState,
+    user: &User,
+    provider: Option<String>,
+    model: Option<String>,
+    temperature: Option<f64>,
+) -> Result<User> {
+    let provider = provider
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .map(|p| {
+            let kind: ProviderKind = p.parse().map_err(AppError::BadRequest)?;
+            if !state.llm_providers.contains_key(&kind) {
+                return Err(AppError::BadRequest(format!(
+                    "LLM provider {} is not configured",
+                    kind
+                )));
+            }
+            Ok(kind.to_string())
+        })
+        .transpose()?;
+    let model = model
+        .map(|m| m.trim().to_string())
+        .filter(|m| !m.is_empty());
+    if let Some(temperature) = temperature {
+        if !(0.0..=MAX_LLM_TEMPERATURE).contains(&temperature) {
+            return Err(AppError::BadRequest(format!(
+                "Temperature must be between 0 and {}",
+                MAX_LLM_TEMPERATURE
+            )));
+        }
+    }
+
+    state
+        .users
+        .update_llm_preferences(user.id, provider.as_deref(), model.as_deref(), temperature)
+        .await
+}
@@ -0,0 +1,78 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use uuid::Uuid;
+
+use crate::auth::{generate_token, hash_token, AuthUser};
+use crate::error::{AppError, Result};
+use crate::models::{BoardShareLink, BoardShareLinkResponse};
+use crate::state::AppState;
+
+/// Only board owners decide who can see a board
+pub(crate) async fn require_owner(state: &AppState, board_id: Uuid, user_id: Uuid) -> Result<()> {
+    let role = state
+        .boards
+        .get_user_role(board_id, user_id)
+        .await?
+        .ok_or(AppError::Forbidden)?;
+    if !role.can_manage_permissions() {
+        return Err(AppError::Forbidden);
+    }
+    Ok(())
+}
+
+/// Create a new share token for the board, replacing the previous one, and return its
+/// address. Anyone with the address can see the board's public cards.
+pub(crate) async fn create_share_token(
+    state: &AppState,
+    board_id: Uuid,
+    user_id: Uuid,
+) -> Result<BoardShareLinkResponse> {
+    let token = generate_token();
+    state
+        .share_links
+        .set_token(board_id, &hash_token(&token), user_id)
+        .await?;
+
+    Ok(BoardShareLinkResponse {
+        url: state.link(&format!("/share/{}", token)),
+        token,
+    })
+}
+
+/// Whether the board has a public address
+pub async fn get_share_link(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(board_id): Path<Uuid>,
+) -> Result<Json<Option<BoardShareLink>>> {
+    require_owner(&state, board_id, auth.user.id).await?;
+    Ok(Json(state.share_links.find_by_board(board_id).await?))
+}
+
+/// Generate the board's public address. The token is only shown once.
+pub async fn create_share_link(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(board_id): Path<Uuid>,
+) -> Result<Json<BoardShareLinkResponse>> {
+    require_owner(&state, board_id, auth.user.id).await?;
+    Ok(Json(
+        create_share_token(&state, board_id, auth.user.id).await?,
+    ))
+}
+
+/// Revoke the board's public address
+pub async fn delete_share_link(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(board_id): Path<Uuid>,
+) -> Result<StatusCode> {
+    require_owner(&state, board_id, auth.user.id).await?;
+    if !state.share_links.delete(board_id).await? {
+        return Err(AppError::NotFound("share_link"));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
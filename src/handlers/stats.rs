@@ -0,0 +1,75 @@
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use chrono::{Days, Utc};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+use crate::auth::AuthUser;
+use crate::error::{AppError, Result};
+use crate::handlers::links::is_done_column;
+use crate::models::{BoardStats, BoardStatsQuery};
+use crate::services::stats::{average_cycle_time_days, card_flows, throughput, week_start};
+use crate::state::AppState;
+
+pub(crate) const DEFAULT_STATS_WEEKS: u32 = 12;
+const MAX_STATS_WEEKS: u32 = 104;
+
+/// Statistics of a board over the last `weeks` weeks. Throughput and cycle time come
+/// from the activity log: a card is completed when moved into a column named "Done"
+/// or set to done.
+pub(crate) async fn board_stats(
+    state: &AppState,
+    board_id: Uuid,
+    weeks: u32,
+) -> Result<BoardStats> {
+    let done_columns: HashSet<Uuid> = state
+        .columns
+        .list_by_board(board_id)
+        .await?
+        .iter()
+        .filter(|c| is_done_column(c))
+        .map(|c| c.id)
+        .collect();
+    let events = state.stats.list_flow_events(board_id).await?;
+    let flows = card_flows(&events, &done_columns);
+
+    let today = Utc::now().date_naive();
+    let throughput = throughput(&flows, weeks, today);
+    let since = week_start(today) - Days::new((weeks as u64 - 1) * 7);
+
+    Ok(BoardStats {
+        board_id,
+        columns: state.stats.column_counts(board_id).await?,
+        tags: state.stats.tag_counts(board_id).await?,
+        overdue_cards: state.stats.overdue_count(board_id).await?,
+        completed_cards: throughput.iter().map(|w| w.completed).sum(),
+        throughput,
+        average_cycle_time_days: average_cycle_time_days(&flows, since),
+    })
+}
+
+/// Card counts, throughput, cycle time, tag usage and overdue cards of a board
+pub async fn get_board_stats(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(board_id): Path<Uuid>,
+    Query(query): Query<BoardStatsQuery>,
+) -> Result<Json<BoardStats>> {
+    state
+        .boards
+        .get_user_role(board_id, auth.user.id)
+        .await?
+        .ok_or(AppError::Forbidden)?;
+
+    let weeks = query.weeks.unwrap_or(DEFAULT_STATS_WEEKS);
+    if weeks == 0 || weeks > MAX_STATS_WEEKS {
+        return Err(AppError::Validation(format!(
+            "weeks must be between 1 and {}",
+            MAX_STATS_WEEKS
+        )));
+    }
+
+    Ok(Json(board_stats(&state, board_id, weeks).await?))
+}
@@ -1,12 +1,18 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     Json,
 };
+use serde_json::json;
 use uuid::Uuid;
 
 use crate::auth::AuthUser;
 use crate::error::{AppError, Result};
-use crate::models::{CreateTag, TagResponse, UpdateTag};
+use crate::handlers::activity::record;
+use crate::models::{
+    BoardEvent, BoardEventKind, CreateTag, LinkDuplicateTagsQuery, LinkDuplicateTagsResponse,
+    LinkTag, Tag, TagResponse, UpdateTag,
+};
+use crate::services::activity::changes;
 use crate::state::AppState;
 
 pub async fn create_tag(
@@ -26,12 +32,19 @@ pub async fn create_tag(
     }
 
     if input.name.is_empty() {
-        return Err(AppError::Validation("Tag name is required".to_string()));
+        return Err(AppError::invalid_field("name", "Tag name is required"));
     }
 
     let color = input.color.unwrap_or_else(|| "#6c757d".to_string());
 
     let tag = state.tags.create(board_id, &input.name, &color).await?;
+
+    record(
+        &state,
+        BoardEvent::new(BoardEventKind::TagCreated, board_id, tag.id, auth.user.id)
+            .with_details(json!({"name": tag.name, "color": tag.color})),
+    )
+    .await;
     Ok(Json(tag.into()))
 }
 
@@ -80,11 +93,19 @@ pub async fn update_tag(
         return Err(AppError::Forbidden);
     }
 
+    if tag.linked_tag_id.is_some() && (input.name.is_some() || input.color.is_some()) {
+        return Err(AppError::BadRequest(
+            "Tag is linked to a global tag; edit the global tag or unlink it first".to_string(),
+        ));
+    }
+
     let updated_tag = state
         .tags
         .update(tag_id, input.name.as_deref(), input.color.as_deref())
         .await?;
 
+    record_tag_updated(&state, &tag, &updated_tag, auth.user.id).await;
+
     Ok(Json(updated_tag.into()))
 }
 
@@ -117,6 +138,15 @@ pub async fn delete_tag(
     }
 
     state.tags.delete(tag_id).await?;
+
+    if let Some(board_id) = tag.board_id {
+        record(
+            &state,
+            BoardEvent::new(BoardEventKind::TagDeleted, board_id, tag_id, auth.user.id)
+                .with_details(json!({"name": tag.name})),
+        )
+        .await;
+    }
     Ok(())
 }
 
@@ -178,7 +208,18 @@ pub async fn add_tag_to_card(
         return Err(AppError::Forbidden);
     }
 
+    state.cards.ensure_unlocked(card_id).await?;
     state.tags.add_to_card(card_id, tag_id).await?;
+
+    if let Some(board_id) = card_board_id {
+        record(
+            &state,
+            BoardEvent::new(BoardEventKind::CardTagged, board_id, tag_id, auth.user.id)
+                .with_card(card_id)
+                .with_details(json!({"tag": tag.name})),
+        )
+        .await;
+    }
     Ok(())
 }
 
@@ -209,6 +250,137 @@ pub async fn remove_tag_from_card(
         }
     }
 
+    state.cards.ensure_unlocked(card_id).await?;
+    let tag = state.tags.find_by_id(tag_id).await?;
     state.tags.remove_from_card(card_id, tag_id).await?;
+
+    if let Some(board_id) = card_board_id {
+        record(
+            &state,
+            BoardEvent::new(BoardEventKind::CardUntagged, board_id, tag_id, auth.user.id)
+                .with_card(card_id)
+                .with_details(json!({"tag": tag.map(|t| t.name)})),
+        )
+        .await;
+    }
     Ok(())
 }
+
+/// Load a board tag and check the user may edit its board
+async fn get_editable_board_tag(state: &AppState, tag_id: Uuid, user_id: Uuid) -> Result<Tag> {
+    let tag = state.tags.get_by_id(tag_id).await?;
+    let board_id = tag.board_id.ok_or_else(|| {
+        AppError::BadRequest("Only board tags can be linked to a global tag".to_string())
+    })?;
+
+    let role = state
+        .boards
+        .get_user_role(board_id, user_id)
+        .await?
+        .ok_or(AppError::Forbidden)?;
+
+    if !role.can_edit() {
+        return Err(AppError::Forbidden);
+    }
+
+    Ok(tag)
+}
+
+/// Record the changes to a board tag; global tags have no board to record them on
+async fn record_tag_updated(state: &AppState, before: &Tag, after: &Tag, user_id: Uuid) {
+    if let (Some(board_id), Some(details)) = (after.board_id, changes(before, after)) {
+        record(
+            state,
+            BoardEvent::new(BoardEventKind::TagUpdated, board_id, after.id, user_id)
+                .with_details(details),
+        )
+        .await;
+    }
+}
+
+/// Link a board tag to one of the user's global tags so it inherits its name and color
+pub async fn link_tag(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(tag_id): Path<Uuid>,
+    Json(input): Json<LinkTag>,
+) -> Result<Json<TagResponse>> {
+    let before = get_editable_board_tag(&state, tag_id, auth.user.id).await?;
+
+    let global_tag = state.tags.get_by_id(input.global_tag_id).await?;
+    if global_tag.board_id.is_some() {
+        return Err(AppError::BadRequest(
+            "Board tags can only be linked to a global tag".to_string(),
+        ));
+    }
+    if global_tag.owner_id != Some(auth.user.id) {
+        return Err(AppError::Forbidden);
+    }
+
+    let tag = state.tags.link(tag_id, global_tag.id).await?;
+
+    record_tag_updated(&state, &before, &tag, auth.user.id).await;
+    Ok(Json(tag.into()))
+}
+
+/// Unlink a board tag, keeping the name and color it inherited
+pub async fn unlink_tag(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(tag_id): Path<Uuid>,
+) -> Result<Json<TagResponse>> {
+    let before = get_editable_board_tag(&state, tag_id, auth.user.id).await?;
+
+    let tag = state.tags.unlink(tag_id).await?;
+
+    record_tag_updated(&state, &before, &tag, auth.user.id).await;
+    Ok(Json(tag.into()))
+}
+
+/// Link every unlinked board tag to the user's global tag of the same name
+pub async fn link_duplicate_tags(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(board_id): Path<Uuid>,
+    Query(query): Query<LinkDuplicateTagsQuery>,
+) -> Result<Json<LinkDuplicateTagsResponse>> {
+    let role = state
+        .boards
+        .get_user_role(board_id, auth.user.id)
+        .await?
+        .ok_or(AppError::Forbidden)?;
+
+    if !role.can_edit() {
+        return Err(AppError::Forbidden);
+    }
+
+    let links = state
+        .tags
+        .find_duplicate_links(board_id, auth.user.id)
+        .await?;
+
+    if !query.dry_run {
+        for link in &links {
+            state
+                .tags
+                .link(link.board_tag_id, link.global_tag_id)
+                .await?;
+            record(
+                &state,
+                BoardEvent::new(
+                    BoardEventKind::TagUpdated,
+                    board_id,
+                    link.board_tag_id,
+                    auth.user.id,
+                )
+                .with_details(json!({"linked_tag_id": link.global_tag_id})),
+            )
+            .await;
+        }
+    }
+
+    Ok(Json(LinkDuplicateTagsResponse {
+        dry_run: query.dry_run,
+        links,
+    }))
+}
@@ -0,0 +1,189 @@
+use axum::{extract::State, http::StatusCode, Json};
+use chrono::{Duration, Utc};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::auth::{generate_token, hash_token, AuthUser};
+use crate::error::{AppError, Result};
+use crate::handlers::chat::global_chat;
+use crate::models::{TelegramLinkResponse, User};
+use crate::services::telegram::{reply_text, TelegramClient, TelegramUpdate};
+use crate::state::AppState;
+
+/// How long a link code can be sent to the bot
+const LINK_CODE_LIFETIME: Duration = Duration::minutes(15);
+
+/// Seconds each `getUpdates` call waits for new messages
+const POLL_WAIT_SECS: u64 = 30;
+
+/// Pause after a failed poll, so an unreachable API is not hammered
+const RETRY_DELAY: std::time::Duration = std::time::Duration::from_secs(5);
+
+const HELP: &str = "Send me anything you would type in the chat, like \"add 'buy milk' to my inbox\".\n/stop unlinks this chat from your account.";
+
+/// Create a new link code for the user, replacing the previous one
+pub(crate) async fn create_link_code(
+    state: &AppState,
+    user_id: Uuid,
+) -> Result<TelegramLinkResponse> {
+    if state.telegram_bot.is_none() {
+        return Err(AppError::BadRequest(
+            "The Telegram bot is not configured".to_string(),
+        ));
+    }
+
+    let code = generate_token();
+    let expires_at = Utc::now() + LINK_CODE_LIFETIME;
+    state
+        .telegram_links
+        .set_link_code(user_id, &hash_token(&code), expires_at)
+        .await?;
+
+    Ok(TelegramLinkResponse {
+        command: format!("/start {}", code),
+        code,
+        expires_at,
+    })
+}
+
+/// Generate a code to link a Telegram chat. It is only shown once.
+pub async fn create_telegram_link(
+    State(state): State<AppState>,
+    auth: AuthUser,
+) -> Result<Json<TelegramLinkResponse>> {
+    Ok(Json(create_link_code(&state, auth.user.id).await?))
+}
+
+/// Unlink the user's Telegram chat
+pub async fn delete_telegram_link(
+    State(state): State<AppState>,
+    auth: AuthUser,
+) -> Result<StatusCode> {
+    if !state.telegram_links.delete(auth.user.id).await? {
+        return Err(AppError::NotFound("telegram_link"));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Reply to a message from a Telegram chat. Linked chats talk to the global chat
+/// assistant as their user; other chats can only link themselves with a code.
+async fn answer(state: &AppState, chat_id: i64, text: &str) -> Result<String> {
+    let text = text.trim();
+    let (command, argument) = match text.split_once(char::is_whitespace) {
+        Some((command, argument)) => (command, argument.trim()),
+        None => (text, ""),
+    };
+    // Commands may be addressed to the bot, as in /start@my_bot
+    let command = command.split('@').next().unwrap_or_default();
+
+    match command {
+        "/start" if !argument.is_empty() => {
+            let linked = state
+                .telegram_links
+                .link_chat(&hash_token(argument), chat_id)
+                .await?;
+            return Ok(match linked {
+                Some(_) => format!("This chat is now linked to your account.\n{}", HELP),
+                None => "This link code is unknown or expired. Generate a new one under Settings → Telegram.".to_string(),
+            });
+        }
+        "/stop" => {
+            return Ok(if state.telegram_links.unlink_chat(chat_id).await? {
+                "This chat is no longer linked to your account.".to_string()
+            } else {
+                "This chat is not linked to an account.".to_string()
+            });
+        }
+        _ => {}
+    }
+
+    let user = match state.telegram_links.find_user_by_chat(chat_id).await? {
+        Some(user_id) => state
+            .users
+            .find_by_id(user_id)
+            .await?
+            .filter(User::is_active),
+        None => None,
+    };
+    let Some(user) = user else {
+        return Ok("To use this bot, generate a link code under Settings → Telegram and send it here as /start <code>.".to_string());
+    };
+
+    if command == "/start" || command == "/help" {
+        return Ok(HELP.to_string());
+    }
+
+    match state.rate_limiter.check_chat_user(user.id) {
+        Ok(()) => {}
+        Err(AppError::TooManyRequests { retry_after }) => {
+            return Ok(format!(
+                "Too many messages; try again in {} seconds.",
+                retry_after
+            ))
+        }
+        Err(e) => return Err(e),
+    }
+
+    let response = global_chat(state, &user, text.to_string()).await?;
+    Ok(reply_text(&response))
+}
+
+/// Answer one update, replying in its chat. Failures are logged and reported to the
+/// chat, so one bad message does not stop the bot.
+pub async fn handle_update(state: &AppState, bot: &TelegramClient, update: TelegramUpdate) {
+    let Some(message) = update.message else {
+        return;
+    };
+    let chat_id = message.chat.id;
+    let reply = match message.text {
+        Some(text) => match answer(state, chat_id, &text).await {
+            Ok(reply) => reply,
+            Err(e) => {
+                warn!(chat_id, error = %e, "Telegram message failed");
+                "Sorry, something went wrong. Please try again.".to_string()
+            }
+        },
+        None => "I can only read text messages.".to_string(),
+    };
+
+    if let Err(e) = bot.send_message(chat_id, &reply).await {
+        warn!(chat_id, error = %e, "Failed to send Telegram reply");
+    }
+}
+
+/// Long-poll the Bot API for messages and answer them one at a time, until the server
+/// shuts down. A message being answered when shutdown starts is answered in full.
+pub async fn run_bot(state: AppState, bot: TelegramClient) {
+    info!("Telegram bot polling for messages");
+    let mut offset = 0;
+    while let Some(polled) = state
+        .jobs
+        .until_shutdown(bot.get_updates(offset, POLL_WAIT_SECS))
+        .await
+    {
+        match polled {
+            Ok(updates) => {
+                for update in updates {
+                    offset = offset.max(update.update_id + 1);
+                    handle_update(&state, &bot, update).await;
+                }
+            }
+            Err(e) => {
+                warn!(error = %e, "Telegram poll failed");
+                state
+                    .jobs
+                    .until_shutdown(tokio::time::sleep(RETRY_DELAY))
+                    .await;
+            }
+        }
+    }
+
+    // Updates are only confirmed by the next poll; confirm the answered ones so they
+    // are not answered again after a restart
+    if offset > 0 {
+        if let Err(e) = bot.get_updates(offset, 0).await {
+            warn!(error = %e, "Could not confirm the last Telegram updates");
+        }
+    }
+    info!("Telegram bot stopped");
+}
@@ -0,0 +1,69 @@
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use uuid::Uuid;
+
+use crate::auth::AuthUser;
+use crate::error::{AppError, Result};
+use crate::models::{BoardRole, CardFilter, TimelineQuery, TimelineResponse};
+use crate::services::timeline;
+use crate::state::AppState;
+
+/// Cards of a board the user can see laid out by their start, end and due dates,
+/// one lane per column
+pub(crate) async fn board_timeline(
+    state: &AppState,
+    board_id: Uuid,
+    user_id: Uuid,
+    role: &BoardRole,
+    query: &TimelineQuery,
+) -> Result<TimelineResponse> {
+    let role = role.to_string();
+    let filter = CardFilter {
+        tags: query.tag_id.map(|tag| vec![tag]),
+        ..Default::default()
+    };
+    let cards = state
+        .cards
+        .list_by_board_with_filter(board_id, user_id, Some(&role), &filter)
+        .await?
+        .into_iter()
+        .filter(|card| query.column_id.is_none() || card.column_id == query.column_id)
+        .collect();
+    let columns = state.columns.list_by_board(board_id).await?;
+
+    let (lanes, unscheduled) = timeline::build(&columns, cards, query.from, query.to);
+    let range = timeline::range(&lanes);
+
+    Ok(TimelineResponse {
+        board_id,
+        start_date: range.map(|(start, _)| start),
+        end_date: range.map(|(_, end)| end),
+        lanes,
+        unscheduled,
+    })
+}
+
+/// Lay out the cards of a board on a timeline, optionally filtered by tag, column
+/// and date range
+pub async fn get_timeline(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(board_id): Path<Uuid>,
+    Query(query): Query<TimelineQuery>,
+) -> Result<Json<TimelineResponse>> {
+    let role = state
+        .boards
+        .get_user_role(board_id, auth.user.id)
+        .await?
+        .ok_or(AppError::Forbidden)?;
+
+    if query.from.zip(query.to).is_some_and(|(from, to)| from > to) {
+        return Err(AppError::invalid_field("from", "from must not be after to"));
+    }
+
+    Ok(Json(
+        board_timeline(&state, board_id, auth.user.id, &role, &query).await?,
+    ))
+}
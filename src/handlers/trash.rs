@@ -0,0 +1,192 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use chrono::Duration;
+use serde_json::json;
+use sqlx::SqliteConnection;
+use uuid::Uuid;
+
+use crate::auth::AuthUser;
+use crate::error::{AppError, Result};
+use crate::handlers::activity::PendingActivity;
+use crate::handlers::attachments::delete_card_with_attachments;
+use crate::models::{BoardEvent, BoardEventKind, TrashItemResponse, TrashKind};
+use crate::repo::board::BoardRepository;
+use crate::repo::card::CardRepository;
+use crate::repo::column::ColumnRepository;
+use crate::state::AppState;
+
+/// How often the purge job runs
+const PURGE_PERIOD: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+
+/// Days items stay in the trash when TRASH_RETENTION_DAYS is not set
+const DEFAULT_RETENTION_DAYS: i64 = 30;
+
+/// Days deleted items stay in the trash before they are purged, from
+/// TRASH_RETENTION_DAYS
+pub fn retention_days() -> i64 {
+    std::env::var("TRASH_RETENTION_DAYS")
+        .ok()
+        .and_then(|v| v.trim().parse().ok())
+        .filter(|days| *days > 0)
+        .unwrap_or(DEFAULT_RETENTION_DAYS)
+}
+
+/// List what the current user can restore, most recently deleted first
+pub async fn list_trash(
+    State(state): State<AppState>,
+    auth: AuthUser,
+) -> Result<Json<Vec<TrashItemResponse>>> {
+    let retention = Duration::days(retention_days());
+    let items = state.trash.list_for_user(auth.user.id).await?;
+
+    Ok(Json(
+        items
+            .into_iter()
+            .map(|item| TrashItemResponse {
+                purge_at: item.deleted_at + retention,
+                item,
+            })
+            .collect(),
+    ))
+}
+
+/// Take a board, column or card out of the trash. A board must be restored by its
+/// owner; columns and cards by someone who can edit their board, or by the owner of
+/// an inbox card.
+pub async fn restore_item(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path((kind, item_id)): Path<(TrashKind, Uuid)>,
+) -> Result<()> {
+    restore(&state, kind, item_id, auth.user.id).await
+}
+
+/// Restore an item from the trash on behalf of a user, see [`restore_item`]
+pub(crate) async fn restore(
+    state: &AppState,
+    kind: TrashKind,
+    item_id: Uuid,
+    user_id: Uuid,
+) -> Result<()> {
+    let mut activity = PendingActivity::default();
+    {
+        let mut conn = state.pool.acquire().await?;
+        restore_on(&mut conn, &mut activity, kind, item_id, user_id).await?;
+    }
+    activity.flush(state).await;
+    Ok(())
+}
+
+/// Like [`restore`], on the caller's connection, holding the change back in `activity`
+pub(crate) async fn restore_on(
+    conn: &mut SqliteConnection,
+    activity: &mut PendingActivity,
+    kind: TrashKind,
+    item_id: Uuid,
+    user_id: Uuid,
+) -> Result<()> {
+    match kind {
+        TrashKind::Board => {
+            let board = BoardRepository::find_deleted_on(conn, item_id)
+                .await?
+                .ok_or(AppError::NotFound("board"))?;
+            if board.owner_id != user_id {
+                return Err(AppError::Forbidden);
+            }
+
+            BoardRepository::restore_on(conn, item_id).await?;
+            activity.record(
+                BoardEvent::new(BoardEventKind::BoardUpdated, item_id, item_id, user_id)
+                    .with_details(json!({"restored": true})),
+            );
+        }
+        TrashKind::Column => {
+            let column = ColumnRepository::find_deleted_on(conn, item_id)
+                .await?
+                .ok_or(AppError::NotFound("column"))?;
+            require_editor(conn, column.board_id, user_id).await?;
+
+            ColumnRepository::restore_on(conn, item_id).await?;
+            activity.record(
+                BoardEvent::new(
+                    BoardEventKind::ColumnUpdated,
+                    column.board_id,
+                    item_id,
+                    user_id,
+                )
+                .with_details(json!({"restored": true, "name": column.name})),
+            );
+        }
+        TrashKind::Card => {
+            let card = CardRepository::find_deleted_on(conn, item_id)
+                .await?
+                .ok_or(AppError::NotFound("card"))?;
+            match card.column_id {
+                Some(column_id) => {
+                    let column = ColumnRepository::find_on(conn, column_id)
+                        .await?
+                        .ok_or(AppError::NotFound("column"))?;
+                    require_editor(conn, column.board_id, user_id).await?;
+                }
+                None => {
+                    if card.owner_id != Some(user_id) {
+                        return Err(AppError::Forbidden);
+                    }
+                }
+            }
+
+            CardRepository::restore_on(conn, item_id).await?;
+            activity.record_for_card(
+                BoardEventKind::CardUpdated,
+                item_id,
+                item_id,
+                user_id,
+                Some(json!({"restored": true, "title": card.title})),
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Fail unless the user can edit the board
+pub(crate) async fn require_editor(
+    conn: &mut SqliteConnection,
+    board_id: Uuid,
+    user_id: Uuid,
+) -> Result<()> {
+    let role = BoardRepository::get_user_role_on(conn, board_id, user_id)
+        .await?
+        .ok_or(AppError::Forbidden)?;
+
+    if !role.can_edit() {
+        return Err(AppError::Forbidden);
+    }
+    Ok(())
+}
+
+/// Delete for good everything that has been in the trash for `days` days or more,
+/// with the attachment files of its cards. Returns the number of items deleted.
+pub async fn purge_expired(state: &AppState, days: i64) -> Result<u64> {
+    let card_ids = state.trash.list_expired_card_ids(days).await?;
+    for &card_id in &card_ids {
+        delete_card_with_attachments(state, card_id).await?;
+    }
+
+    let others = state.trash.purge_columns_and_boards(days).await?;
+    Ok(card_ids.len() as u64 + others)
+}
+
+/// Purge expired trash now and then, until the server shuts down
+pub async fn run_purge_job(state: AppState) {
+    let mut ticks = tokio::time::interval(PURGE_PERIOD);
+    while state.jobs.tick(&mut ticks).await {
+        match purge_expired(&state, retention_days()).await {
+            Ok(0) => {}
+            Ok(purged) => tracing::info!("Purged {} item(s) from the trash", purged),
+            Err(e) => tracing::warn!(error = %e, "Trash purge failed"),
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use uuid::Uuid;
+
+use crate::auth::AuthUser;
+use crate::error::{AppError, Result};
+use crate::handlers::comments::can_view_card;
+use crate::models::WatchStatus;
+use crate::state::AppState;
+
+async fn watch_status(state: &AppState, card_id: Uuid, user_id: Uuid) -> Result<WatchStatus> {
+    let (watching, watchers) = tokio::try_join!(
+        state.watchers.is_watching(card_id, user_id),
+        state.watchers.list_user_ids(card_id),
+    )?;
+    Ok(WatchStatus {
+        watching,
+        watchers: watchers.len() as i64,
+    })
+}
+
+/// Whether the current user watches the card
+pub async fn get_watch(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(card_id): Path<Uuid>,
+) -> Result<Json<WatchStatus>> {
+    if !can_view_card(&state, card_id, auth.user.id).await? {
+        return Err(AppError::Forbidden);
+    }
+    Ok(Json(watch_status(&state, card_id, auth.user.id).await?))
+}
+
+/// Subscribe to the card's moves, comments and due date changes. Anyone who can
+/// see the card can watch it.
+pub async fn watch_card(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(card_id): Path<Uuid>,
+) -> Result<Json<WatchStatus>> {
+    if !can_view_card(&state, card_id, auth.user.id).await? {
+        return Err(AppError::Forbidden);
+    }
+    state.watchers.watch(card_id, auth.user.id).await?;
+    Ok(Json(watch_status(&state, card_id, auth.user.id).await?))
+}
+
+pub async fn unwatch_card(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(card_id): Path<Uuid>,
+) -> Result<Json<WatchStatus>> {
+    // Unwatching is always allowed, so users who lost access can still stop
+    state.cards.get_by_id(card_id).await?;
+    state.watchers.unwatch(card_id, auth.user.id).await?;
+    Ok(Json(watch_status(&state, card_id, auth.user.id).await?))
+}
@@ -1,92 +1,396 @@
 use askama::Template;
 use axum::{
     extract::{Path, Query, State},
+    http::header,
     response::{Html, IntoResponse, Redirect, Response},
-    Form,
+    Form, Json,
 };
 use axum_extra::extract::cookie::{Cookie, CookieJar};
+use chrono::{Months, NaiveDate, Utc};
 use serde::Deserialize;
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 
-use crate::auth::{generate_token, hash_password, verify_password, AuthUser, OptionalAuthUser};
+use crate::auth::{
+    generate_token, hash_password, hash_token, session_cookie, session_lifetime, AuthUser,
+    ClientIp, OptionalAuthUser,
+};
 use crate::error::{AppError, Result};
-use crate::models::{CardStatus, CardVisibility};
+use crate::handlers::activity::{record, record_for_card};
+use crate::handlers::board_templates::{apply_layout, resolve_layout};
+use crate::handlers::calendar::create_feed_token;
+use crate::handlers::comments::can_view_card;
+use crate::handlers::guest_comments;
+use crate::handlers::inbound_email::create_address_token;
+use crate::handlers::share_links::create_share_token;
+use crate::handlers::stats::{board_stats, DEFAULT_STATS_WEEKS};
+use crate::handlers::telegram::create_link_code;
+use crate::handlers::{auth, cards, columns, dashboard, invitations, saved_views};
+use crate::models::{
+    ApiTokenResponse, Board, BoardEvent, BoardEventKind, BoardGroup, BoardPreference, BoardStats,
+    CalendarDay, Card, CardCover, CardFilter, CardStatus, CardVisibility, ColumnSortPolicy,
+    CreateGuestComment, DashboardCardResponse, ReactionCount, RoadmapCard, RoadmapGrouping,
+    RoadmapQuery, SessionResponse, Theme, ThemeInfo, TimelineQuery, UpdateColumn, User,
+    WipLimitPolicy, REACTION_EMOJI,
+};
+use crate::repo::reaction::CardReactions;
+use crate::services::activity::changes;
+use crate::services::board_order;
+use crate::services::board_templates::BUILTIN_TEMPLATES;
+use crate::services::calendar::{grid_bounds, month_grid, month_start};
+use crate::services::mentions::{self, Mentionable};
 use crate::state::AppState;
 
 // Template structs
 #[derive(Template)]
 #[template(path = "index.html")]
 struct IndexTemplate {
+    theme: Theme,
     user: Option<String>,
 }
 
 #[derive(Template)]
 #[template(path = "login.html")]
 struct LoginTemplate {
+    theme: Theme,
     error: Option<String>,
+    providers: Vec<LoginProvider>,
+}
+
+/// Button for signing in with an OAuth provider
+struct LoginProvider {
+    name: String,
+    display_name: String,
+}
+
+/// The login page, with a button for every configured OAuth provider
+pub(crate) fn login_response(state: &AppState, error: Option<String>) -> Response {
+    let template = LoginTemplate {
+        theme: Theme::System,
+        error,
+        providers: state
+            .oauth
+            .iter()
+            .map(|p| LoginProvider {
+                name: p.name().to_string(),
+                display_name: p.display_name().to_string(),
+            })
+            .collect(),
+    };
+    Html(template.render().unwrap()).into_response()
 }
 
 #[derive(Template)]
 #[template(path = "register.html")]
 struct RegisterTemplate {
+    theme: Theme,
     error: Option<String>,
 }
 
+#[derive(Template)]
+#[template(path = "invitation.html")]
+struct InvitationTemplate {
+    theme: Theme,
+    invitation: Option<InvitationView>,
+}
+
+struct InvitationView {
+    email: String,
+    board_name: String,
+    role: String,
+}
+
 #[derive(Template)]
 #[template(path = "boards.html")]
 struct BoardsTemplate {
+    theme: Theme,
     user: String,
-    boards: Vec<BoardView>,
+    sections: Vec<BoardSectionView>,
+    groups: Vec<BoardGroupOptionView>,
+    has_boards: bool,
+}
+
+/// Starred boards, a group of the user's or their ungrouped boards
+struct BoardSectionView {
+    /// `starred`, the group id, or empty for ungrouped boards
+    key: String,
+    title: String,
+    /// Whether the section is a group, which can be deleted
+    is_group: bool,
+    boards: Vec<BoardListView>,
+}
+
+struct BoardListView {
+    id: String,
+    name: String,
+    description: Option<String>,
+    role: String,
+    starred: bool,
+    group_id: String,
+}
+
+struct BoardGroupOptionView {
+    id: String,
+    name: String,
 }
 
 #[derive(Template)]
 #[template(path = "board_new.html")]
 struct NewBoardTemplate {
+    theme: Theme,
     user: String,
+    board_templates: Vec<BoardTemplateOption>,
 }
 
 #[derive(Template)]
 #[template(path = "board_detail.html")]
 struct BoardDetailTemplate {
+    theme: Theme,
     user: String,
     board: BoardView,
     columns: Vec<ColumnView>,
     tags: Vec<TagView>,
     filter_tags: Vec<FilterTagView>,
     has_active_filters: bool,
+    views: Vec<SavedViewLink>,
+    active_view: Option<String>,
+    /// Comma-separated ids of the active tags, for saving them as a view
+    active_tags: String,
 }
 
 #[derive(Template)]
 #[template(path = "board_settings.html")]
 struct BoardSettingsTemplate {
+    theme: Theme,
     user: String,
     board: BoardView,
+    columns: Vec<ColumnOptionView>,
+    tags: Vec<TagView>,
+    wip_limit_policy: String,
+    stats: BoardStats,
+    stats_weeks: u32,
+    throughput: Vec<ThroughputBarView>,
+    /// When the board got its current public address, if it has one
+    shared_since: Option<String>,
+    /// The public address just generated, shown once
+    share_url: Option<String>,
+    allow_public_links: bool,
+}
+
+/// A week of the throughput chart, its height in percent of the busiest week
+struct ThroughputBarView {
+    week_start: NaiveDate,
+    completed: u32,
+    height: u32,
+}
+
+#[derive(Template)]
+#[template(path = "shared_board.html")]
+struct SharedBoardTemplate {
+    theme: Theme,
+    token: String,
+    board_name: String,
+    description: Option<String>,
+    columns: Vec<SharedColumnView>,
+    /// A guest comment was just left and awaits approval
+    comment_sent: bool,
+}
+
+struct SharedColumnView {
+    name: String,
+    cards: Vec<SharedCardView>,
+}
+
+struct SharedCardView {
+    id: String,
+    title: String,
+    body: Option<String>,
+    due_date: Option<NaiveDate>,
+    tags: Vec<TagView>,
+    guest_comments: Vec<GuestCommentView>,
+}
+
+/// Approved comment left by a visitor of the share link
+struct GuestCommentView {
+    name: String,
+    body: String,
+}
+
+#[derive(Template)]
+#[template(path = "public_card.html")]
+struct PublicCardTemplate {
+    theme: Theme,
+    card: PublicCardView,
+    comments: Vec<CommentView>,
+}
+
+struct PublicCardView {
+    title: String,
+    body: Option<String>,
+    status: String,
+    due_date: Option<String>,
     tags: Vec<TagView>,
 }
 
+#[derive(Template)]
+#[template(path = "board_roadmap.html")]
+struct BoardRoadmapTemplate {
+    theme: Theme,
+    user: String,
+    board: BoardView,
+    quarterly: bool,
+    buckets: Vec<RoadmapBucketView>,
+}
+
+#[derive(Template)]
+#[template(path = "board_timeline.html")]
+struct BoardTimelineTemplate {
+    theme: Theme,
+    user: String,
+    board: BoardView,
+    /// First and last day of the chart, empty without dated cards
+    start_date: String,
+    end_date: String,
+    /// Months starting inside the chart, as gridlines
+    months: Vec<TimelineTickView>,
+    lanes: Vec<TimelineLaneView>,
+    unscheduled: Vec<RoadmapCardView>,
+    tags: Vec<SelectOptionView>,
+    columns: Vec<SelectOptionView>,
+}
+
+#[derive(Template)]
+#[template(path = "board_archived.html")]
+struct BoardArchivedTemplate {
+    theme: Theme,
+    user: String,
+    board: BoardView,
+    columns: Vec<ArchivedColumnView>,
+    cards: Vec<ArchivedCardView>,
+}
+
 #[derive(Template)]
 #[template(path = "user_settings.html")]
 struct UserSettingsTemplate {
+    theme: Theme,
     user: String,
+    email: String,
+    /// False for users who only sign in through an OAuth provider
+    has_password: bool,
+    /// New address waiting for confirmation
+    pending_email: Option<String>,
+    notice: Option<String>,
+    error: Option<String>,
     chat_message_count: i64,
+    calendar_feed_enabled: bool,
+    inbound_email_enabled: bool,
+    /// Whether the Telegram bot is configured, and a chat is linked to the user
+    telegram_enabled: bool,
+    telegram_linked: bool,
     llm_context: Option<String>,
+    chat_confirm_actions: bool,
+    /// Configured providers the user can choose from
+    llm_providers: Vec<String>,
+    /// Chosen provider, model and temperature, empty for the defaults
+    llm_provider: String,
+    llm_model: String,
+    llm_temperature: String,
+    default_llm_model: String,
+    themes: Vec<ThemeInfo>,
+}
+
+#[derive(Template)]
+#[template(path = "security.html")]
+struct SecurityTemplate {
+    theme: Theme,
+    user: String,
+    notice: Option<String>,
+    sessions: Vec<SessionResponse>,
+    tokens: Vec<ApiTokenResponse>,
 }
 
 #[derive(Template)]
 #[template(path = "inbox.html")]
 struct InboxTemplate {
+    theme: Theme,
+    user: String,
+    cards: Vec<InboxCardView>,
+    current_status: Option<String>,
+}
+
+#[derive(Template)]
+#[template(path = "assigned.html")]
+struct AssignedTemplate {
+    theme: Theme,
     user: String,
     cards: Vec<InboxCardView>,
     current_status: Option<String>,
 }
 
+#[derive(Template)]
+#[template(path = "dashboard.html")]
+struct DashboardTemplate {
+    theme: Theme,
+    user: String,
+    sections: Vec<DashboardSection>,
+}
+
+struct DashboardSection {
+    title: &'static str,
+    empty: &'static str,
+    cards: Vec<DashboardCardView>,
+}
+
+struct DashboardCardView {
+    id: String,
+    title: String,
+    status: String,
+    due_date: Option<String>,
+    priority: Option<String>,
+    board_name: Option<String>,
+}
+
+#[derive(Template)]
+#[template(path = "calendar.html")]
+struct CalendarTemplate {
+    theme: Theme,
+    user: String,
+    /// Month shown, e.g. "October 2026"
+    month_label: String,
+    /// Previous and next month, as YYYY-MM
+    prev_month: String,
+    next_month: String,
+    today: NaiveDate,
+    weeks: Vec<Vec<CalendarDay>>,
+}
+
+/// Filters of the timeline page, empty when "All" is selected
+#[derive(Debug, Deserialize)]
+pub struct TimelineForm {
+    pub tag_id: Option<String>,
+    pub column_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CalendarQuery {
+    /// Month to show, as YYYY-MM, the current month when missing or invalid
+    pub month: Option<String>,
+}
+
 #[derive(Template)]
 #[template(path = "card_detail.html")]
 struct CardDetailTemplate {
+    theme: Theme,
     user: String,
     card: CardDetailView,
     comments: Vec<CommentView>,
     current_user_id: String,
+    comment_draft: Option<String>,
+    watching: bool,
+    /// Reactions to the card itself
+    reactions: Vec<ReactionCount>,
+    reaction_emoji: &'static [&'static str],
 }
 
 // View structs for templates
@@ -97,6 +401,7 @@ struct InboxCardView {
     body: Option<String>,
     status: String,
     due_date: Option<String>,
+    priority: Option<String>,
     boards: Vec<BoardView>,
 }
 
@@ -107,9 +412,30 @@ struct CardDetailView {
     body: Option<String>,
     status: String,
     due_date: Option<String>,
+    priority: Option<String>,
     created_at: String,
+    locked: bool,
+    archived: bool,
     boards: Vec<BoardView>,
     tags: Vec<TagView>,
+    assignees: Vec<String>,
+    links: Vec<LinkView>,
+    cover_color: Option<String>,
+    cover_attachment_id: Option<String>,
+    /// Image attachments of the card, which can be its cover
+    cover_images: Vec<CoverImageView>,
+}
+
+struct CoverImageView {
+    id: String,
+    filename: String,
+}
+
+struct LinkView {
+    label: &'static str,
+    card_id: String,
+    title: String,
+    status: String,
 }
 
 #[allow(dead_code)]
@@ -118,6 +444,9 @@ struct CommentView {
     user_id: String,
     author_name: String,
     body: String,
+    /// Escaped body with mentions highlighted
+    body_html: String,
+    reactions: Vec<ReactionCount>,
     created_at: String,
     updated_at: String,
 }
@@ -136,6 +465,25 @@ struct ColumnView {
     name: String,
     position: i32,
     cards: Vec<CardView>,
+    /// Cards in the column, including those hidden by the tag filter
+    card_count: usize,
+    wip_limit: Option<i32>,
+    sort_policy: ColumnSortPolicy,
+    color: Option<String>,
+    description: Option<String>,
+    /// Collapsed by the user: only the header shows
+    collapsed: bool,
+}
+
+impl ColumnView {
+    fn over_wip_limit(&self) -> bool {
+        self.wip_limit
+            .is_some_and(|limit| self.card_count > limit as usize)
+    }
+
+    fn sorted_by(&self, policy: &str) -> bool {
+        self.sort_policy.to_string() == policy
+    }
 }
 
 #[allow(dead_code)]
@@ -145,7 +493,89 @@ struct CardView {
     body: Option<String>,
     position: i32,
     visibility: String,
+    priority: Option<String>,
     tags: Vec<TagView>,
+    cover_color: Option<String>,
+    cover_attachment_id: Option<String>,
+    is_blocked: bool,
+}
+
+struct ColumnOptionView {
+    id: String,
+    name: String,
+    selected: bool,
+    wip_limit: Option<i32>,
+    color: Option<String>,
+    description: Option<String>,
+    card_status: Option<String>,
+}
+
+struct ArchivedColumnView {
+    id: String,
+    name: String,
+    archived_at: String,
+    cards: Vec<ArchivedCardView>,
+}
+
+struct ArchivedCardView {
+    id: String,
+    title: String,
+    archived_at: String,
+}
+
+struct RoadmapBucketView {
+    label: String,
+    cards: Vec<RoadmapCardView>,
+    milestones: Vec<RoadmapCardView>,
+}
+
+struct RoadmapCardView {
+    id: String,
+    title: String,
+    status: String,
+    due_date: String,
+}
+
+impl From<RoadmapCard> for RoadmapCardView {
+    fn from(card: RoadmapCard) -> Self {
+        Self {
+            id: card.id.to_string(),
+            title: card.title,
+            status: card.status,
+            due_date: card.due_date.map(|d| d.to_string()).unwrap_or_default(),
+        }
+    }
+}
+
+struct TimelineLaneView {
+    name: String,
+    cards: Vec<TimelineBarView>,
+}
+
+/// A card's bar, placed in percent of the chart width
+struct TimelineBarView {
+    id: String,
+    title: String,
+    status: String,
+    dates: String,
+    left: String,
+    width: String,
+}
+
+struct SelectOptionView {
+    id: String,
+    name: String,
+    selected: bool,
+}
+
+struct TimelineTickView {
+    label: String,
+    left: String,
+}
+
+struct BoardTemplateOption {
+    id: String,
+    name: String,
 }
 
 #[derive(Clone)]
@@ -166,11 +596,19 @@ struct FilterTagView {
     toggle_url: String,
 }
 
+struct SavedViewLink {
+    id: String,
+    name: String,
+    is_active: bool,
+}
+
 // Form structs
 #[derive(Deserialize)]
 pub struct LoginForm {
     email: String,
     password: String,
+    /// The "remember me" checkbox, only sent when checked
+    remember: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -184,6 +622,7 @@ pub struct RegisterForm {
 pub struct CreateBoardForm {
     name: String,
     description: Option<String>,
+    template: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -217,8 +656,21 @@ pub struct AddTagToCardForm {
 
 #[derive(Deserialize, Default)]
 pub struct BoardFilterQuery {
+    /// Comma-separated tag ids; when a view is active, replaces the view's tags
     #[serde(default)]
     tags: Option<String>,
+    /// Saved view to apply
+    view: Option<Uuid>,
+}
+
+#[derive(Deserialize)]
+pub struct SaveViewForm {
+    name: String,
+    #[serde(default)]
+    tags: String,
+    /// View the board was showing, whose filter the new view starts from
+    #[serde(default)]
+    view: String,
 }
 
 #[derive(Deserialize, Default)]
@@ -247,52 +699,74 @@ pub struct CommentForm {
 // Handlers
 pub async fn index(auth: OptionalAuthUser) -> impl IntoResponse {
     let template = IndexTemplate {
+        theme: auth
+            .0
+            .as_ref()
+            .map(|a| a.user.ui_theme())
+            .unwrap_or_default(),
         user: auth.0.map(|a| a.user.name),
     };
     Html(template.render().unwrap())
 }
 
-pub async fn login_page() -> impl IntoResponse {
-    let template = LoginTemplate { error: None };
-    Html(template.render().unwrap())
+pub async fn login_page(State(state): State<AppState>) -> Response {
+    login_response(&state, None)
 }
 
 pub async fn login_submit(
     State(state): State<AppState>,
+    ClientIp(ip): ClientIp,
     jar: CookieJar,
     Form(input): Form<LoginForm>,
 ) -> Result<Response> {
     let user = match state.users.find_by_email(&input.email).await? {
         Some(u) => u,
         None => {
-            let template = LoginTemplate {
-                error: Some("Invalid email or password".to_string()),
-            };
-            return Ok(Html(template.render().unwrap()).into_response());
+            return Ok(login_response(
+                &state,
+                Some("Invalid email or password".to_string()),
+            ));
         }
     };
 
-    if !verify_password(&input.password, &user.password_hash)? {
-        let template = LoginTemplate {
-            error: Some("Invalid email or password".to_string()),
-        };
-        return Ok(Html(template.render().unwrap()).into_response());
+    if !user.check_password(&input.password)? {
+        return Ok(login_response(
+            &state,
+            Some("Invalid email or password".to_string()),
+        ));
+    }
+    if !user.is_active() {
+        return Ok(login_response(
+            &state,
+            Some("This account is deactivated".to_string()),
+        ));
     }
 
     let token = generate_token();
-    state.sessions.create(user.id, &token).await?;
-
-    let cookie = Cookie::build(("session", token))
-        .path("/")
-        .http_only(true)
-        .same_site(axum_extra::extract::cookie::SameSite::Lax)
-        .build();
+    let remember = input.remember.is_some();
+    state
+        .sessions
+        .create(
+            user.id,
+            &token,
+            Some(ip),
+            remember,
+            session_lifetime(remember),
+        )
+        .await?;
 
-    Ok((jar.add(cookie), Redirect::to("/boards")).into_response())
+    Ok((
+        jar.add(session_cookie(token, remember)),
+        Redirect::to("/boards"),
+    )
+        .into_response())
 }
 
 pub async fn register_page() -> impl IntoResponse {
-    let template = RegisterTemplate { error: None };
+    let template = RegisterTemplate {
+        theme: Theme::System,
+        error: None,
+    };
     Html(template.render().unwrap())
 }
 
@@ -302,6 +776,7 @@ pub async fn register_submit(
 ) -> Result<Response> {
     if input.name.is_empty() || input.email.is_empty() || input.password.is_empty() {
         let template = RegisterTemplate {
+            theme: Theme::System,
             error: Some("All fields are required".to_string()),
         };
         return Ok(Html(template.render().unwrap()).into_response());
@@ -309,6 +784,7 @@ pub async fn register_submit(
 
     if input.password.len() < 8 {
         let template = RegisterTemplate {
+            theme: Theme::System,
             error: Some("Password must be at least 8 characters".to_string()),
         };
         return Ok(Html(template.render().unwrap()).into_response());
@@ -316,6 +792,7 @@ pub async fn register_submit(
 
     if state.users.email_exists(&input.email).await? {
         let template = RegisterTemplate {
+            theme: Theme::System,
             error: Some("Email already registered".to_string()),
         };
         return Ok(Html(template.render().unwrap()).into_response());
@@ -323,14 +800,57 @@ pub async fn register_submit(
 
     let password_hash = hash_password(&input.password)?;
     let id = Uuid::new_v4();
-    state
+    let user = state
         .users
-        .create(id, &input.email, &password_hash, &input.name)
+        .create(id, &input.email, Some(&password_hash), &input.name)
         .await?;
+    invitations::accept_for_new_user(&state, &user).await?;
 
     Ok(Redirect::to("/login").into_response())
 }
 
+/// Follow an invitation link: accept it when logged in, otherwise explain how to
+pub async fn invitation_page(
+    State(state): State<AppState>,
+    auth: OptionalAuthUser,
+    Path(token): Path<String>,
+) -> Result<Response> {
+    let theme = auth
+        .0
+        .as_ref()
+        .map(|a| a.user.ui_theme())
+        .unwrap_or_default();
+
+    if let Some(auth) = auth.0 {
+        match invitations::accept(&state, &token, auth.user.id).await {
+            Ok(board_id) => {
+                return Ok(Redirect::to(&format!("/boards/{}", board_id)).into_response())
+            }
+            Err(AppError::NotFound(_)) => {}
+            Err(e) => return Err(e),
+        }
+    }
+
+    let invitation = match state
+        .invitations
+        .find_by_token_hash(&hash_token(&token))
+        .await?
+    {
+        Some(invitation) => {
+            let board = state.boards.get_by_id(invitation.board_id).await?;
+            Some(InvitationView {
+                email: invitation.email,
+                board_name: board.name,
+                role: invitation.role,
+            })
+        }
+        None => None,
+    };
+
+    let template = InvitationTemplate { theme, invitation };
+    Ok(Html(template.render().unwrap()).into_response())
+}
+
 pub async fn logout(
     State(state): State<AppState>,
     jar: CookieJar,
@@ -349,72 +869,349 @@ pub async fn logout(
     Ok((jar.add(cookie), Redirect::to("/")).into_response())
 }
 
+/// Section of the boards page that lists the board: `starred`, its group id, or empty
+/// when ungrouped. Starred boards are also listed in their group.
+fn in_board_section(preference: Option<&BoardPreference>, key: &str) -> bool {
+    match key {
+        "starred" => preference.is_some_and(|p| p.starred),
+        "" => preference.and_then(|p| p.group_id).is_none(),
+        group_id => {
+            preference.and_then(|p| p.group_id).map(|id| id.to_string()) == Some(group_id.into())
+        }
+    }
+}
+
+/// The user's boards in their order, with their preferences and groups
+async fn arranged_boards(
+    state: &AppState,
+    user_id: Uuid,
+) -> Result<(
+    Vec<(Board, String)>,
+    HashMap<Uuid, BoardPreference>,
+    Vec<BoardGroup>,
+)> {
+    let (mut boards, preferences, groups) = tokio::try_join!(
+        state.boards.list_for_user(user_id),
+        state.board_preferences.list_for_user(user_id),
+        state.board_preferences.list_groups(user_id),
+    )?;
+    let preferences: HashMap<Uuid, BoardPreference> =
+        preferences.into_iter().map(|p| (p.board_id, p)).collect();
+    board_order::arrange(&mut boards, |(board, _)| board.id, &preferences);
+    Ok((boards, preferences, groups))
+}
+
 pub async fn boards_page(
     State(state): State<AppState>,
     auth: AuthUser,
 ) -> Result<impl IntoResponse> {
-    let boards = state.boards.list_for_user(auth.user.id).await?;
-
-    let board_views: Vec<BoardView> = boards
-        .into_iter()
-        .map(|(b, role)| BoardView {
-            id: b.id.to_string(),
-            name: b.name,
-            description: b.description,
-            role,
-        })
-        .collect();
+    let (boards, preferences, groups) = arranged_boards(&state, auth.user.id).await?;
+
+    let mut sections = vec![BoardSectionView {
+        key: "starred".to_string(),
+        title: "Starred".to_string(),
+        is_group: false,
+        boards: Vec::new(),
+    }];
+    sections.extend(groups.iter().map(|g| BoardSectionView {
+        key: g.id.to_string(),
+        title: g.name.clone(),
+        is_group: true,
+        boards: Vec::new(),
+    }));
+    sections.push(BoardSectionView {
+        key: String::new(),
+        title: if groups.is_empty() {
+            "All Boards".to_string()
+        } else {
+            "Other Boards".to_string()
+        },
+        is_group: false,
+        boards: Vec::new(),
+    });
+    for (board, role) in &boards {
+        let preference = preferences.get(&board.id);
+        for section in &mut sections {
+            if in_board_section(preference, &section.key) {
+                section.boards.push(BoardListView {
+                    id: board.id.to_string(),
+                    name: board.name.clone(),
+                    description: board.description.clone(),
+                    role: role.clone(),
+                    starred: preference.is_some_and(|p| p.starred),
+                    group_id: preference
+                        .and_then(|p| p.group_id)
+                        .map(|id| id.to_string())
+                        .unwrap_or_default(),
+                });
+            }
+        }
+    }
+    // Groups stay visible while empty, so they can be filled or deleted
+    sections.retain(|s| s.is_group || !s.boards.is_empty());
 
     let template = BoardsTemplate {
+        theme: auth.user.ui_theme(),
         user: auth.user.name,
-        boards: board_views,
+        sections,
+        groups: groups
+            .into_iter()
+            .map(|g| BoardGroupOptionView {
+                id: g.id.to_string(),
+                name: g.name,
+            })
+            .collect(),
+        has_boards: !boards.is_empty(),
     };
 
     Ok(Html(template.render().unwrap()))
 }
 
-pub async fn new_board_page(auth: AuthUser) -> impl IntoResponse {
-    let template = NewBoardTemplate {
-        user: auth.user.name,
-    };
-    Html(template.render().unwrap())
-}
-
-pub async fn create_board_submit(
+pub async fn star_board_submit(
     State(state): State<AppState>,
     auth: AuthUser,
-    Form(input): Form<CreateBoardForm>,
+    Path(board_id): Path<Uuid>,
 ) -> Result<Response> {
-    let board = state
+    state
         .boards
-        .create(&input.name, input.description.as_deref(), auth.user.id)
+        .get_user_role(board_id, auth.user.id)
+        .await?
+        .ok_or(AppError::Forbidden)?;
+    let starred = state
+        .board_preferences
+        .find(auth.user.id, board_id)
+        .await?
+        .is_some_and(|p| p.starred);
+
+    state
+        .board_preferences
+        .update(auth.user.id, board_id, Some(!starred), None)
         .await?;
+    Ok(Redirect::to("/boards").into_response())
+}
 
-    Ok(Redirect::to(&format!("/boards/{}", board.id)).into_response())
+#[derive(Deserialize)]
+pub struct BoardGroupForm {
+    /// Empty to take the board out of its group
+    group_id: String,
 }
 
-pub async fn board_detail(
+pub async fn board_group_submit(
     State(state): State<AppState>,
     auth: AuthUser,
     Path(board_id): Path<Uuid>,
-    Query(filter): Query<BoardFilterQuery>,
-) -> Result<impl IntoResponse> {
-    let board = state.boards.get_by_id(board_id).await?;
-    let role = state
-        .boards
+    Form(input): Form<BoardGroupForm>,
+) -> Result<Response> {
+    state
+        .boards
         .get_user_role(board_id, auth.user.id)
         .await?
         .ok_or(AppError::Forbidden)?;
+    let group_id = match input.group_id.as_str() {
+        "" => None,
+        id => {
+            let group_id = id
+                .parse::<Uuid>()
+                .map_err(|_| AppError::Validation("Unknown group".to_string()))?;
+            if !state
+                .board_preferences
+                .owns_group(auth.user.id, group_id)
+                .await?
+            {
+                return Err(AppError::Validation("Unknown group".to_string()));
+            }
+            Some(group_id)
+        }
+    };
 
-    let columns = state.columns.list_by_board(board_id).await?;
-    let tags = state.tags.list_by_board(board_id).await?;
+    state
+        .board_preferences
+        .update(auth.user.id, board_id, None, Some(group_id))
+        .await?;
+    Ok(Redirect::to("/boards").into_response())
+}
 
-    // Parse active tag filters from query string (comma-separated UUIDs)
-    let active_tag_ids: Vec<String> = filter
-        .tags
-        .as_ref()
-        .map(|t| t.split(',').map(|s| s.trim().to_string()).collect())
-        .unwrap_or_default();
+#[derive(Deserialize)]
+pub struct MoveBoardForm {
+    /// Section of the page the board is moved within, see `in_board_section`
+    section: String,
+    direction: String,
+}
+
+/// Move a board one place up or down within its section of the boards page
+pub async fn move_board_submit(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(board_id): Path<Uuid>,
+    Form(input): Form<MoveBoardForm>,
+) -> Result<Response> {
+    let (boards, preferences, _) = arranged_boards(&state, auth.user.id).await?;
+    let mut order: Vec<Uuid> = boards.iter().map(|(board, _)| board.id).collect();
+    let section: Vec<Uuid> = order
+        .iter()
+        .copied()
+        .filter(|id| in_board_section(preferences.get(id), &input.section))
+        .collect();
+
+    if board_order::move_within(&mut order, &section, board_id, input.direction == "up") {
+        state
+            .board_preferences
+            .set_order(auth.user.id, &order)
+            .await?;
+    }
+    Ok(Redirect::to("/boards").into_response())
+}
+
+#[derive(Deserialize)]
+pub struct CreateBoardGroupForm {
+    name: String,
+}
+
+pub async fn create_board_group_submit(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Form(input): Form<CreateBoardGroupForm>,
+) -> Result<Response> {
+    let name = input.name.trim();
+    if name.is_empty() {
+        return Err(AppError::Validation("Group name is required".to_string()));
+    }
+
+    state
+        .board_preferences
+        .create_group(auth.user.id, name)
+        .await?;
+    Ok(Redirect::to("/boards").into_response())
+}
+
+pub async fn delete_board_group_submit(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(group_id): Path<Uuid>,
+) -> Result<Response> {
+    state
+        .board_preferences
+        .delete_group(auth.user.id, group_id)
+        .await?;
+    Ok(Redirect::to("/boards").into_response())
+}
+
+pub async fn new_board_page(
+    State(state): State<AppState>,
+    auth: AuthUser,
+) -> Result<impl IntoResponse> {
+    let mut board_templates: Vec<BoardTemplateOption> = BUILTIN_TEMPLATES
+        .iter()
+        .map(|t| BoardTemplateOption {
+            id: t.key.to_string(),
+            name: t.name.to_string(),
+        })
+        .collect();
+    for saved in state.board_templates.list_for_owner(auth.user.id).await? {
+        board_templates.push(BoardTemplateOption {
+            id: saved.id.to_string(),
+            name: saved.name,
+        });
+    }
+
+    let template = NewBoardTemplate {
+        theme: auth.user.ui_theme(),
+        user: auth.user.name,
+        board_templates,
+    };
+    Ok(Html(template.render().unwrap()))
+}
+
+pub async fn create_board_submit(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Form(input): Form<CreateBoardForm>,
+) -> Result<Response> {
+    let layout = match input.template.as_deref().filter(|t| !t.is_empty()) {
+        Some(template) => Some(resolve_layout(&state, template, auth.user.id).await?),
+        None => None,
+    };
+
+    let board = state
+        .boards
+        .create(&input.name, input.description.as_deref(), auth.user.id)
+        .await?;
+    if let Some(layout) = layout {
+        apply_layout(&state, board.id, &layout).await?;
+    }
+
+    Ok(Redirect::to(&format!("/boards/{}", board.id)).into_response())
+}
+
+pub async fn board_detail(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(board_id): Path<Uuid>,
+    Query(filter): Query<BoardFilterQuery>,
+) -> Result<impl IntoResponse> {
+    let board = state.boards.get_by_id(board_id).await?;
+    let role = state
+        .boards
+        .get_user_role(board_id, auth.user.id)
+        .await?
+        .ok_or(AppError::Forbidden)?;
+
+    let (columns, tags, cards, mut card_tags, collapsed) = tokio::try_join!(
+        state.columns.list_by_board(board_id),
+        state.tags.list_by_board(board_id),
+        state.cards.list_by_board_columns(board_id),
+        state.tags.list_for_board_cards(board_id),
+        state.columns.collapsed_for_user(board_id, auth.user.id),
+    )?;
+
+    let views = state
+        .saved_views
+        .list_for_user(board_id, auth.user.id)
+        .await?;
+    let active_view = match filter.view {
+        Some(view_id) => Some(
+            views
+                .iter()
+                .find(|v| v.id == view_id)
+                .ok_or(AppError::NotFound("saved_view"))?
+                .card_filter(),
+        ),
+        None => None,
+    };
+
+    // Parse active tag filters from query string (comma-separated UUIDs), falling
+    // back to the tags of the view
+    let active_tag_ids: Vec<String> = match (&filter.tags, &active_view) {
+        (Some(t), _) => t
+            .split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect(),
+        (None, Some(view)) => view.tags.iter().flatten().map(|t| t.to_string()).collect(),
+        (None, None) => Vec::new(),
+    };
+
+    // The rest of the view's filter is the one of the card list; tags are matched
+    // below like the tag filter of the page
+    let view_card_ids: Option<HashSet<Uuid>> = match active_view {
+        Some(view_filter) => {
+            let view_filter = CardFilter {
+                tags: None,
+                ..view_filter
+            };
+            let cards = state
+                .cards
+                .list_by_board_with_filter(
+                    board_id,
+                    auth.user.id,
+                    Some(&role.to_string()),
+                    &view_filter,
+                )
+                .await?;
+            Some(cards.into_iter().map(|c| c.id).collect())
+        }
+        None => None,
+    };
 
     let active_tag_uuids: Vec<Uuid> = active_tag_ids
         .iter()
@@ -430,6 +1227,23 @@ pub async fn board_detail(
         })
         .collect();
 
+    // Board URL with the active view and the given tags
+    let board_url = |tag_ids: &[&str]| {
+        let mut params = Vec::new();
+        if let Some(view_id) = filter.view {
+            params.push(format!("view={}", view_id));
+            // An empty list overrides the tags of the view
+            params.push(format!("tags={}", tag_ids.join(",")));
+        } else if !tag_ids.is_empty() {
+            params.push(format!("tags={}", tag_ids.join(",")));
+        }
+        if params.is_empty() {
+            format!("/boards/{}", board_id)
+        } else {
+            format!("/boards/{}?{}", board_id, params.join("&"))
+        }
+    };
+
     // Build filter tags with pre-computed toggle URLs
     let filter_tags: Vec<FilterTagView> = tags
         .iter()
@@ -437,38 +1251,22 @@ pub async fn board_detail(
             let tag_id_str = t.id.to_string();
             let is_active = active_tag_ids.contains(&tag_id_str);
 
-            let toggle_url = if is_active {
+            let toggled: Vec<&str> = if is_active {
                 // Remove this tag from filter
-                let other_tags: Vec<&String> = active_tag_ids
+                active_tag_ids
                     .iter()
-                    .filter(|id| *id != &tag_id_str)
-                    .collect();
-                if other_tags.is_empty() {
-                    format!("/boards/{}", board_id)
-                } else {
-                    format!(
-                        "/boards/{}?tags={}",
-                        board_id,
-                        other_tags
-                            .iter()
-                            .map(|s| s.as_str())
-                            .collect::<Vec<_>>()
-                            .join(",")
-                    )
-                }
+                    .map(String::as_str)
+                    .filter(|id| *id != tag_id_str)
+                    .collect()
             } else {
                 // Add this tag to filter
-                if active_tag_ids.is_empty() {
-                    format!("/boards/{}?tags={}", board_id, tag_id_str)
-                } else {
-                    format!(
-                        "/boards/{}?tags={},{}",
-                        board_id,
-                        active_tag_ids.join(","),
-                        tag_id_str
-                    )
-                }
+                active_tag_ids
+                    .iter()
+                    .map(String::as_str)
+                    .chain([tag_id_str.as_str()])
+                    .collect()
             };
+            let toggle_url = board_url(&toggled);
 
             FilterTagView {
                 id: tag_id_str,
@@ -480,14 +1278,32 @@ pub async fn board_detail(
         })
         .collect();
 
-    let has_active_filters = !active_tag_ids.is_empty();
+    let has_active_filters = !active_tag_ids.is_empty() || filter.view.is_some();
+
+    let card_ids: Vec<Uuid> = cards.iter().map(|c| c.id).collect();
+    let blocked = state.links.blocked_card_ids(&card_ids).await?;
+
+    let mut cards_by_column: HashMap<Uuid, Vec<Card>> = HashMap::new();
+    for card in cards {
+        if let Some(column_id) = card.column_id {
+            cards_by_column.entry(column_id).or_default().push(card);
+        }
+    }
 
     let mut column_views = Vec::new();
     for col in columns {
-        let cards = state.cards.list_by_column(col.id).await?;
+        let cards = cards_by_column.remove(&col.id).unwrap_or_default();
+        let card_count = cards.len();
         let mut card_views = Vec::new();
         for card in cards {
-            let card_tags = state.tags.list_for_card(card.id).await?;
+            let card_tags = card_tags.remove(&card.id).unwrap_or_default();
+
+            if view_card_ids
+                .as_ref()
+                .is_some_and(|ids| !ids.contains(&card.id))
+            {
+                continue;
+            }
 
             // Filter: if active tags are set, only show cards that have ALL of them
             if !active_tag_uuids.is_empty() {
@@ -499,11 +1315,15 @@ pub async fn board_detail(
             }
 
             card_views.push(CardView {
+                is_blocked: blocked.contains(&card.id),
                 id: card.id.to_string(),
                 title: card.title,
                 body: card.body,
                 position: card.position,
                 visibility: card.visibility,
+                priority: card.priority,
+                cover_color: card.cover_color,
+                cover_attachment_id: card.cover_attachment_id.map(|id| id.to_string()),
                 tags: card_tags
                     .into_iter()
                     .map(|t| TagView {
@@ -515,14 +1335,21 @@ pub async fn board_detail(
             });
         }
         column_views.push(ColumnView {
+            sort_policy: col.sort_policy(),
+            collapsed: collapsed.contains(&col.id),
+            color: col.color,
+            description: col.description,
             id: col.id.to_string(),
             name: col.name,
             position: col.position,
             cards: card_views,
+            card_count,
+            wip_limit: col.wip_limit,
         });
     }
 
     let template = BoardDetailTemplate {
+        theme: auth.user.ui_theme(),
         user: auth.user.name,
         board: BoardView {
             id: board.id.to_string(),
@@ -534,16 +1361,79 @@ pub async fn board_detail(
         tags: tag_views,
         filter_tags,
         has_active_filters,
+        views: views
+            .into_iter()
+            .map(|v| SavedViewLink {
+                is_active: filter.view == Some(v.id),
+                id: v.id.to_string(),
+                name: v.name,
+            })
+            .collect(),
+        active_view: filter.view.map(|id| id.to_string()),
+        active_tags: active_tag_ids.join(","),
     };
 
     Ok(Html(template.render().unwrap()))
 }
 
+/// Save the filter the board is showing as a view of the user
+pub async fn save_view_submit(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(board_id): Path<Uuid>,
+    Form(input): Form<SaveViewForm>,
+) -> Result<Response> {
+    state
+        .boards
+        .get_user_role(board_id, auth.user.id)
+        .await?
+        .ok_or(AppError::Forbidden)?;
+
+    let mut filter = match Uuid::parse_str(&input.view) {
+        Ok(view_id) => saved_views::user_view(&state, board_id, view_id, auth.user.id)
+            .await?
+            .card_filter(),
+        Err(_) => CardFilter::default(),
+    };
+    let tags: Vec<Uuid> = input
+        .tags
+        .split(',')
+        .filter_map(|s| Uuid::parse_str(s.trim()).ok())
+        .collect();
+    filter.tags = (!tags.is_empty()).then_some(tags);
+
+    let view =
+        saved_views::create_user_view(&state, board_id, auth.user.id, &input.name, &filter).await?;
+
+    Ok(Redirect::to(&format!("/boards/{}?view={}", board_id, view.id)).into_response())
+}
+
+pub async fn delete_view_submit(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path((board_id, view_id)): Path<(Uuid, Uuid)>,
+) -> Result<Response> {
+    saved_views::user_view(&state, board_id, view_id, auth.user.id).await?;
+    state.saved_views.delete(view_id).await?;
+
+    Ok(Redirect::to(&format!("/boards/{}", board_id)).into_response())
+}
+
 pub async fn board_settings(
     State(state): State<AppState>,
     auth: AuthUser,
     Path(board_id): Path<Uuid>,
-) -> Result<impl IntoResponse> {
+) -> Result<Response> {
+    board_settings_response(&state, auth, board_id, None).await
+}
+
+/// The board settings page, with `share_url` when a public address was just generated
+async fn board_settings_response(
+    state: &AppState,
+    auth: AuthUser,
+    board_id: Uuid,
+    share_url: Option<String>,
+) -> Result<Response> {
     let board = state.boards.get_by_id(board_id).await?;
     let role = state
         .boards
@@ -551,6 +1441,20 @@ pub async fn board_settings(
         .await?
         .ok_or(AppError::Forbidden)?;
 
+    let columns = state.columns.list_by_board(board_id).await?;
+    let column_views: Vec<ColumnOptionView> = columns
+        .into_iter()
+        .map(|c| ColumnOptionView {
+            id: c.id.to_string(),
+            name: c.name,
+            selected: board.default_column_id == Some(c.id),
+            wip_limit: c.wip_limit,
+            color: c.color,
+            description: c.description,
+            card_status: c.card_status,
+        })
+        .collect();
+
     let tags = state.tags.list_by_board(board_id).await?;
     let tag_views: Vec<TagView> = tags
         .into_iter()
@@ -561,7 +1465,25 @@ pub async fn board_settings(
         })
         .collect();
 
+    let stats = board_stats(state, board_id, DEFAULT_STATS_WEEKS).await?;
+    let busiest = stats
+        .throughput
+        .iter()
+        .map(|w| w.completed)
+        .max()
+        .unwrap_or(0);
+    let throughput = stats
+        .throughput
+        .iter()
+        .map(|w| ThroughputBarView {
+            week_start: w.week_start,
+            completed: w.completed,
+            height: (w.completed * 100).checked_div(busiest).unwrap_or(0),
+        })
+        .collect();
+
     let template = BoardSettingsTemplate {
+        theme: auth.user.ui_theme(),
         user: auth.user.name,
         board: BoardView {
             id: board.id.to_string(),
@@ -569,140 +1491,679 @@ pub async fn board_settings(
             description: board.description,
             role: role.to_string(),
         },
+        columns: column_views,
         tags: tag_views,
+        wip_limit_policy: board.wip_limit_policy,
+        allow_public_links: board.allow_public_links,
+        stats,
+        stats_weeks: DEFAULT_STATS_WEEKS,
+        throughput,
+        shared_since: state
+            .share_links
+            .find_by_board(board_id)
+            .await?
+            .map(|l| l.created_at.format("%Y-%m-%d").to_string()),
+        share_url,
     };
 
-    Ok(Html(template.render().unwrap()))
+    Ok(Html(template.render().unwrap()).into_response())
 }
 
-pub async fn create_column_submit(
+/// Generate a new public address for the board, replacing the previous one
+pub async fn create_share_link_submit(
     State(state): State<AppState>,
     auth: AuthUser,
     Path(board_id): Path<Uuid>,
-    Form(input): Form<CreateColumnForm>,
 ) -> Result<Response> {
     let role = state
         .boards
         .get_user_role(board_id, auth.user.id)
         .await?
         .ok_or(AppError::Forbidden)?;
-
-    if !role.can_edit() {
+    if !role.can_manage_permissions() {
         return Err(AppError::Forbidden);
     }
 
-    state.columns.create(board_id, &input.name, None).await?;
-
-    Ok(Redirect::to(&format!("/boards/{}", board_id)).into_response())
+    let link = create_share_token(&state, board_id, auth.user.id).await?;
+    board_settings_response(&state, auth, board_id, Some(link.url)).await
 }
 
-pub async fn create_card_submit(
+pub async fn delete_share_link_submit(
     State(state): State<AppState>,
     auth: AuthUser,
     Path(board_id): Path<Uuid>,
-    Form(input): Form<CreateCardForm>,
-) -> Result<Response> {
-    let column = state.columns.get_by_id(input.column_id).await?;
-
-    let role = state
-        .boards
-        .get_user_role(column.board_id, auth.user.id)
-        .await?
-        .ok_or(AppError::Forbidden)?;
-
-    if !role.can_edit() {
-        return Err(AppError::Forbidden);
-    }
-
-    state
-        .cards
-        .create(
-            input.column_id,
-            &input.title,
-            input.body.as_deref(),
-            None,
-            CardVisibility::Restricted,
-            CardStatus::Open,
-            None,
-            None,
-            None,
-            auth.user.id,
-        )
-        .await?;
-
-    Ok(Redirect::to(&format!("/boards/{}", board_id)).into_response())
-}
-
-pub async fn move_card_submit(
-    State(state): State<AppState>,
-    auth: AuthUser,
-    Path(card_id): Path<Uuid>,
-    Form(input): Form<MoveCardForm>,
 ) -> Result<Response> {
-    let board_id = state.cards.get_board_id_for_card(card_id).await?;
-
     let role = state
         .boards
         .get_user_role(board_id, auth.user.id)
         .await?
         .ok_or(AppError::Forbidden)?;
-
-    if !role.can_edit() {
+    if !role.can_manage_permissions() {
         return Err(AppError::Forbidden);
     }
 
-    state
-        .cards
-        .move_card(card_id, input.column_id, input.position)
-        .await?;
+    state.share_links.delete(board_id).await?;
+    Ok(Redirect::to(&format!("/boards/{}/settings", board_id)).into_response())
+}
 
-    Ok(Redirect::to(&format!("/boards/{}", board_id)).into_response())
+#[derive(Deserialize)]
+pub struct PublicLinksForm {
+    enabled: bool,
 }
 
-pub async fn create_tag_submit(
+/// Turn the public pages of the board's public cards on or off
+pub async fn public_links_submit(
     State(state): State<AppState>,
     auth: AuthUser,
     Path(board_id): Path<Uuid>,
-    Form(input): Form<CreateTagForm>,
+    Form(input): Form<PublicLinksForm>,
 ) -> Result<Response> {
     let role = state
         .boards
         .get_user_role(board_id, auth.user.id)
         .await?
         .ok_or(AppError::Forbidden)?;
-
-    if !role.can_edit() {
+    if !role.can_manage_permissions() {
         return Err(AppError::Forbidden);
     }
 
     state
-        .tags
-        .create(board_id, &input.name, &input.color)
+        .boards
+        .update(
+            board_id,
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(input.enabled),
+            None,
+        )
         .await?;
-
     Ok(Redirect::to(&format!("/boards/{}/settings", board_id)).into_response())
 }
 
-pub async fn delete_tag_submit(
+#[derive(Deserialize)]
+pub struct SharedBoardQuery {
+    comment: Option<String>,
+}
+
+/// Read-only view of the public cards of a shared board, for people without an account.
+/// Visitors can leave comments, shown once approved.
+pub async fn shared_board_page(
     State(state): State<AppState>,
-    auth: AuthUser,
-    Path((board_id, tag_id)): Path<(Uuid, Uuid)>,
+    Path(token): Path<String>,
+    Query(query): Query<SharedBoardQuery>,
 ) -> Result<Response> {
-    let role = state
-        .boards
-        .get_user_role(board_id, auth.user.id)
+    let board_id = state
+        .share_links
+        .find_board_by_token(&hash_token(&token))
         .await?
-        .ok_or(AppError::Forbidden)?;
+        .ok_or(AppError::NotFound("share_link"))?;
+    let board = state.boards.get_by_id(board_id).await?;
 
-    if !role.can_edit() {
-        return Err(AppError::Forbidden);
+    let (columns, cards, mut card_tags, approved) = tokio::try_join!(
+        state.columns.list_by_board(board_id),
+        state.cards.list_by_board_columns(board_id),
+        state.tags.list_for_board_cards(board_id),
+        state.guest_comments.list_approved_for_board(board_id),
+    )?;
+    let mut guest_comments: HashMap<Uuid, Vec<GuestCommentView>> = HashMap::new();
+    for comment in approved {
+        guest_comments
+            .entry(comment.card_id)
+            .or_default()
+            .push(GuestCommentView {
+                name: comment.name,
+                body: comment.body,
+            });
     }
 
-    state.tags.delete(tag_id).await?;
-
-    Ok(Redirect::to(&format!("/boards/{}/settings", board_id)).into_response())
-}
-
+    // Only public cards are shown, whatever else is on the board
+    let mut cards_by_column: HashMap<Uuid, Vec<SharedCardView>> = HashMap::new();
+    for card in cards {
+        let visibility: CardVisibility = card.visibility.parse().unwrap_or(CardVisibility::Private);
+        let Some(column_id) = card.column_id else {
+            continue;
+        };
+        if visibility != CardVisibility::Public {
+            continue;
+        }
+        cards_by_column
+            .entry(column_id)
+            .or_default()
+            .push(SharedCardView {
+                id: card.id.to_string(),
+                guest_comments: guest_comments.remove(&card.id).unwrap_or_default(),
+                tags: card_tags
+                    .remove(&card.id)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|t| TagView {
+                        id: t.id.to_string(),
+                        name: t.name,
+                        color: t.color,
+                    })
+                    .collect(),
+                title: card.title,
+                body: card.body,
+                due_date: card.due_date,
+            });
+    }
+
+    let template = SharedBoardTemplate {
+        theme: Theme::System,
+        token,
+        comment_sent: query.comment.as_deref() == Some("sent"),
+        board_name: board.name,
+        description: board.description,
+        columns: columns
+            .into_iter()
+            .map(|col| SharedColumnView {
+                cards: cards_by_column.remove(&col.id).unwrap_or_default(),
+                name: col.name,
+            })
+            .collect(),
+    };
+
+    Ok((
+        [(header::CACHE_CONTROL, "no-store")],
+        Html(template.render().unwrap()),
+    )
+        .into_response())
+}
+
+/// Leave a comment on a public card of a shared board, then go back to the board
+pub async fn guest_comment_submit(
+    State(state): State<AppState>,
+    Path((token, card_id)): Path<(String, Uuid)>,
+    Form(input): Form<CreateGuestComment>,
+) -> Result<Response> {
+    guest_comments::submit(&state, &token, card_id, &input).await?;
+    Ok(Redirect::to(&format!("/share/{}?comment=sent", token)).into_response())
+}
+
+/// Read-only page for a public card, reachable without an account when one
+/// of its boards allows public links
+pub async fn public_card_page(
+    State(state): State<AppState>,
+    Path(card_id): Path<Uuid>,
+) -> Result<Response> {
+    let card = state
+        .cards
+        .find_by_id(card_id)
+        .await?
+        .filter(|card| {
+            card.archived_at.is_none()
+                && card.visibility.parse::<CardVisibility>().ok() == Some(CardVisibility::Public)
+        })
+        .ok_or(AppError::NotFound("card"))?;
+    if !state.boards.allows_public_link(card_id).await? {
+        return Err(AppError::NotFound("card"));
+    }
+
+    let tags = state.tags.list_for_card(card_id).await?;
+    let reactions = state.reactions.list_for_card(card_id, Uuid::nil()).await?;
+    let comments = comment_views(&state, card_id, &reactions).await?;
+
+    let template = PublicCardTemplate {
+        theme: Theme::System,
+        card: PublicCardView {
+            title: card.title,
+            body: card.body,
+            status: card.status.to_string(),
+            due_date: card.due_date.map(|d| d.format("%Y-%m-%d").to_string()),
+            tags: tags
+                .into_iter()
+                .map(|t| TagView {
+                    id: t.id.to_string(),
+                    name: t.name,
+                    color: t.color,
+                })
+                .collect(),
+        },
+        comments,
+    };
+
+    Ok((
+        [(header::CACHE_CONTROL, "no-store")],
+        Html(template.render().unwrap()),
+    )
+        .into_response())
+}
+
+pub async fn board_roadmap(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(board_id): Path<Uuid>,
+    Query(query): Query<RoadmapQuery>,
+) -> Result<impl IntoResponse> {
+    let board = state.boards.get_by_id(board_id).await?;
+    let role = state
+        .boards
+        .get_user_role(board_id, auth.user.id)
+        .await?
+        .ok_or(AppError::Forbidden)?;
+
+    let grouping = query.group_by.unwrap_or_default();
+    let buckets = crate::handlers::roadmap::board_roadmap(
+        &state,
+        board_id,
+        auth.user.id,
+        &role,
+        grouping,
+        query
+            .milestone_tag
+            .as_deref()
+            .unwrap_or(crate::handlers::roadmap::DEFAULT_MILESTONE_TAG),
+    )
+    .await?;
+
+    let template = BoardRoadmapTemplate {
+        theme: auth.user.ui_theme(),
+        user: auth.user.name,
+        board: BoardView {
+            id: board.id.to_string(),
+            name: board.name,
+            description: board.description,
+            role: role.to_string(),
+        },
+        quarterly: grouping == RoadmapGrouping::Quarter,
+        buckets: buckets
+            .into_iter()
+            .map(|b| RoadmapBucketView {
+                label: b.label,
+                cards: b.cards.into_iter().map(Into::into).collect(),
+                milestones: b.milestones.into_iter().map(Into::into).collect(),
+            })
+            .collect(),
+    };
+
+    Ok(Html(template.render().unwrap()))
+}
+
+pub async fn board_timeline(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(board_id): Path<Uuid>,
+    Query(query): Query<TimelineForm>,
+) -> Result<impl IntoResponse> {
+    let board = state.boards.get_by_id(board_id).await?;
+    let role = state
+        .boards
+        .get_user_role(board_id, auth.user.id)
+        .await?
+        .ok_or(AppError::Forbidden)?;
+
+    // Empty select values mean no filter
+    let query = TimelineQuery {
+        tag_id: query.tag_id.and_then(|t| t.parse().ok()),
+        column_id: query.column_id.and_then(|c| c.parse().ok()),
+        ..Default::default()
+    };
+    let timeline =
+        crate::handlers::timeline::board_timeline(&state, board_id, auth.user.id, &role, &query)
+            .await?;
+
+    // Place bars on a day scale, the last day included
+    let (start, end) = timeline
+        .start_date
+        .zip(timeline.end_date)
+        .unwrap_or_default();
+    let total_days = ((end - start).num_days() + 1) as f64;
+    let percent = |date: NaiveDate| {
+        format!(
+            "{:.3}",
+            (date - start).num_days() as f64 * 100.0 / total_days
+        )
+    };
+
+    let mut months = Vec::new();
+    if timeline.start_date.is_some() {
+        let mut month = crate::services::calendar::month_start(start);
+        while month <= end {
+            if month >= start {
+                months.push(TimelineTickView {
+                    label: month.format("%b %Y").to_string(),
+                    left: percent(month),
+                });
+            }
+            month = match month.checked_add_months(Months::new(1)) {
+                Some(next) => next,
+                None => break,
+            };
+        }
+    }
+
+    let lanes = timeline
+        .lanes
+        .into_iter()
+        .map(|lane| TimelineLaneView {
+            name: lane.column_name,
+            cards: lane
+                .cards
+                .into_iter()
+                .map(|card| TimelineBarView {
+                    id: card.id.to_string(),
+                    title: card.title,
+                    status: card.status,
+                    dates: if card.bar_start == card.bar_end {
+                        card.bar_start.to_string()
+                    } else {
+                        format!("{} → {}", card.bar_start, card.bar_end)
+                    },
+                    left: percent(card.bar_start),
+                    width: format!(
+                        "{:.3}",
+                        ((card.bar_end - card.bar_start).num_days() + 1) as f64 * 100.0
+                            / total_days
+                    ),
+                })
+                .collect(),
+        })
+        .collect();
+
+    let tags = state
+        .tags
+        .list_by_board(board_id)
+        .await?
+        .into_iter()
+        .map(|tag| SelectOptionView {
+            selected: query.tag_id == Some(tag.id),
+            id: tag.id.to_string(),
+            name: tag.name,
+        })
+        .collect();
+    let columns = state
+        .columns
+        .list_by_board(board_id)
+        .await?
+        .into_iter()
+        .map(|column| SelectOptionView {
+            selected: query.column_id == Some(column.id),
+            id: column.id.to_string(),
+            name: column.name,
+        })
+        .collect();
+
+    let template = BoardTimelineTemplate {
+        theme: auth.user.ui_theme(),
+        user: auth.user.name,
+        board: BoardView {
+            id: board.id.to_string(),
+            name: board.name,
+            description: board.description,
+            role: role.to_string(),
+        },
+        start_date: timeline
+            .start_date
+            .map(|d| d.to_string())
+            .unwrap_or_default(),
+        end_date: timeline.end_date.map(|d| d.to_string()).unwrap_or_default(),
+        months,
+        lanes,
+        unscheduled: timeline.unscheduled.into_iter().map(Into::into).collect(),
+        tags,
+        columns,
+    };
+
+    Ok(Html(template.render().unwrap()))
+}
+
+pub async fn board_archived(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(board_id): Path<Uuid>,
+) -> Result<impl IntoResponse> {
+    let board = state.boards.get_by_id(board_id).await?;
+    let role = state
+        .boards
+        .get_user_role(board_id, auth.user.id)
+        .await?
+        .ok_or(AppError::Forbidden)?;
+
+    let card_view = |card: crate::models::Card| ArchivedCardView {
+        id: card.id.to_string(),
+        title: card.title,
+        archived_at: card
+            .archived_at
+            .map(|at| at.format("%Y-%m-%d").to_string())
+            .unwrap_or_default(),
+    };
+
+    let mut columns = Vec::new();
+    for column in state.columns.list_archived_by_board(board_id).await? {
+        let cards = state.cards.list_by_column(column.id).await?;
+        columns.push(ArchivedColumnView {
+            id: column.id.to_string(),
+            name: column.name,
+            archived_at: column
+                .archived_at
+                .map(|at| at.format("%Y-%m-%d").to_string())
+                .unwrap_or_default(),
+            cards: cards.into_iter().map(card_view).collect(),
+        });
+    }
+    let cards = state
+        .cards
+        .list_archived_by_board(board_id)
+        .await?
+        .into_iter()
+        .map(card_view)
+        .collect();
+
+    let template = BoardArchivedTemplate {
+        theme: auth.user.ui_theme(),
+        user: auth.user.name,
+        board: BoardView {
+            id: board.id.to_string(),
+            name: board.name,
+            description: board.description,
+            role: role.to_string(),
+        },
+        columns,
+        cards,
+    };
+
+    Ok(Html(template.render().unwrap()))
+}
+
+pub async fn create_column_submit(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(board_id): Path<Uuid>,
+    Form(input): Form<CreateColumnForm>,
+) -> Result<Response> {
+    let role = state
+        .boards
+        .get_user_role(board_id, auth.user.id)
+        .await?
+        .ok_or(AppError::Forbidden)?;
+
+    if !role.can_edit() {
+        return Err(AppError::Forbidden);
+    }
+
+    let column = state
+        .columns
+        .create(board_id, &input.name, None, None, None)
+        .await?;
+    record(
+        &state,
+        BoardEvent::new(
+            BoardEventKind::ColumnCreated,
+            board_id,
+            column.id,
+            auth.user.id,
+        )
+        .with_details(json!({"name": column.name})),
+    )
+    .await;
+
+    Ok(Redirect::to(&format!("/boards/{}", board_id)).into_response())
+}
+
+pub async fn create_card_submit(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(board_id): Path<Uuid>,
+    Form(input): Form<CreateCardForm>,
+) -> Result<Response> {
+    let column = state.columns.get_by_id(input.column_id).await?;
+
+    let role = state
+        .boards
+        .get_user_role(column.board_id, auth.user.id)
+        .await?
+        .ok_or(AppError::Forbidden)?;
+
+    if !role.can_edit() {
+        return Err(AppError::Forbidden);
+    }
+    // The board shows columns over their limit, only a blocking limit matters here
+    columns::check_wip_limit(&state, &column).await?;
+
+    let card = state
+        .cards
+        .create(
+            input.column_id,
+            &input.title,
+            input.body.as_deref(),
+            None,
+            CardVisibility::Restricted,
+            CardStatus::Open,
+            None,
+            None,
+            None,
+            None,
+            auth.user.id,
+        )
+        .await?;
+    record(
+        &state,
+        BoardEvent::new(
+            BoardEventKind::CardCreated,
+            column.board_id,
+            card.id,
+            auth.user.id,
+        )
+        .with_details(json!({"title": card.title, "column_id": column.id})),
+    )
+    .await;
+
+    Ok(Redirect::to(&format!("/boards/{}", board_id)).into_response())
+}
+
+pub async fn move_card_submit(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(card_id): Path<Uuid>,
+    Form(input): Form<MoveCardForm>,
+) -> Result<Response> {
+    let column = state.columns.get_by_id(input.column_id).await?;
+    let board_id = column.board_id;
+
+    let role = state
+        .boards
+        .get_user_role(board_id, auth.user.id)
+        .await?
+        .ok_or(AppError::Forbidden)?;
+
+    if !role.can_edit() {
+        return Err(AppError::Forbidden);
+    }
+
+    let source_column_id = state
+        .card_boards
+        .get_assignment(card_id, board_id)
+        .await?
+        .ok_or(AppError::NotFound("card"))?
+        .column_id;
+    let within_column = source_column_id == Some(column.id);
+    columns::check_manual_order(&column, within_column)?;
+    if !within_column {
+        columns::check_wip_limit(&state, &column).await?;
+    }
+    state
+        .card_boards
+        .move_card_in_board(card_id, board_id, Some(input.column_id), input.position)
+        .await?;
+    let card = state.cards.get_by_id(card_id).await?;
+    record(
+        &state,
+        BoardEvent::new(BoardEventKind::CardMoved, board_id, card_id, auth.user.id).with_details(
+            json!({
+                "from_column_id": source_column_id,
+                "to_column_id": input.column_id,
+                "position": input.position,
+            }),
+        ),
+    )
+    .await;
+    if !within_column {
+        columns::sync_card_status(&state, auth.user.id, card, &column).await?;
+    }
+
+    Ok(Redirect::to(&format!("/boards/{}", board_id)).into_response())
+}
+
+pub async fn create_tag_submit(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(board_id): Path<Uuid>,
+    Form(input): Form<CreateTagForm>,
+) -> Result<Response> {
+    let role = state
+        .boards
+        .get_user_role(board_id, auth.user.id)
+        .await?
+        .ok_or(AppError::Forbidden)?;
+
+    if !role.can_edit() {
+        return Err(AppError::Forbidden);
+    }
+
+    let tag = state
+        .tags
+        .create(board_id, &input.name, &input.color)
+        .await?;
+    record(
+        &state,
+        BoardEvent::new(BoardEventKind::TagCreated, board_id, tag.id, auth.user.id)
+            .with_details(json!({"name": tag.name, "color": tag.color})),
+    )
+    .await;
+
+    Ok(Redirect::to(&format!("/boards/{}/settings", board_id)).into_response())
+}
+
+pub async fn delete_tag_submit(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path((board_id, tag_id)): Path<(Uuid, Uuid)>,
+) -> Result<Response> {
+    let role = state
+        .boards
+        .get_user_role(board_id, auth.user.id)
+        .await?
+        .ok_or(AppError::Forbidden)?;
+
+    if !role.can_edit() {
+        return Err(AppError::Forbidden);
+    }
+
+    let tag = state.tags.find_by_id(tag_id).await?;
+    state.tags.delete(tag_id).await?;
+    record(
+        &state,
+        BoardEvent::new(BoardEventKind::TagDeleted, board_id, tag_id, auth.user.id)
+            .with_details(json!({"name": tag.map(|t| t.name)})),
+    )
+    .await;
+
+    Ok(Redirect::to(&format!("/boards/{}/settings", board_id)).into_response())
+}
+
 pub async fn delete_column_submit(
     State(state): State<AppState>,
     auth: AuthUser,
@@ -721,9 +2182,434 @@ pub async fn delete_column_submit(
         return Err(AppError::Forbidden);
     }
 
-    state.columns.delete(column_id).await?;
-
-    Ok(Redirect::to(&format!("/boards/{}", board_id)).into_response())
+    state.columns.move_to_trash(column_id, auth.user.id).await?;
+    record(
+        &state,
+        BoardEvent::new(
+            BoardEventKind::ColumnDeleted,
+            board_id,
+            column_id,
+            auth.user.id,
+        )
+        .with_details(json!({"name": column.name})),
+    )
+    .await;
+
+    Ok(Redirect::to(&format!("/boards/{}", board_id)).into_response())
+}
+
+pub async fn archive_column_submit(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(column_id): Path<Uuid>,
+) -> Result<Response> {
+    let column = columns::set_column_archived(&state, &auth, column_id, true).await?;
+    Ok(Redirect::to(&format!("/boards/{}", column.board_id)).into_response())
+}
+
+#[derive(Deserialize)]
+pub struct ColumnSortForm {
+    sort_policy: String,
+}
+
+pub async fn update_column_sort_submit(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(column_id): Path<Uuid>,
+    Form(input): Form<ColumnSortForm>,
+) -> Result<Response> {
+    let column = state.columns.get_by_id(column_id).await?;
+    let role = state
+        .boards
+        .get_user_role(column.board_id, auth.user.id)
+        .await?
+        .ok_or(AppError::Forbidden)?;
+
+    if !role.can_edit() {
+        return Err(AppError::Forbidden);
+    }
+
+    let sort_policy = input.sort_policy.parse().map_err(AppError::BadRequest)?;
+    let update = UpdateColumn {
+        name: None,
+        wip_limit: None,
+        sort_policy: Some(sort_policy),
+        color: None,
+        description: None,
+        collapsed: None,
+        card_status: None,
+    };
+    columns::apply_column_update(&state, &auth, &column, &update, None).await?;
+    Ok(Redirect::to(&format!("/boards/{}", column.board_id)).into_response())
+}
+
+#[derive(Deserialize)]
+pub struct ColumnDetailsForm {
+    color: String,
+    description: String,
+    #[serde(default)]
+    card_status: String,
+}
+
+pub async fn update_column_details_submit(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(column_id): Path<Uuid>,
+    Form(input): Form<ColumnDetailsForm>,
+) -> Result<Response> {
+    let column = state.columns.get_by_id(column_id).await?;
+    let role = state
+        .boards
+        .get_user_role(column.board_id, auth.user.id)
+        .await?
+        .ok_or(AppError::Forbidden)?;
+
+    if !role.can_edit() {
+        return Err(AppError::Forbidden);
+    }
+
+    let card_status = match input.card_status.as_str() {
+        "" => None,
+        status => Some(status.parse().map_err(AppError::BadRequest)?),
+    };
+    let update = UpdateColumn {
+        name: None,
+        wip_limit: None,
+        sort_policy: None,
+        color: Some(Some(input.color)),
+        description: Some(Some(input.description)),
+        collapsed: None,
+        card_status: Some(card_status),
+    };
+    columns::apply_column_update(&state, &auth, &column, &update, None).await?;
+    Ok(Redirect::to(&format!("/boards/{}/settings", column.board_id)).into_response())
+}
+
+#[derive(Deserialize)]
+pub struct ColumnCollapseForm {
+    collapsed: bool,
+}
+
+/// Collapse or expand a column on the user's own view of the board
+pub async fn column_collapse_submit(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(column_id): Path<Uuid>,
+    Form(input): Form<ColumnCollapseForm>,
+) -> Result<Response> {
+    let column = state.columns.get_by_id(column_id).await?;
+    state
+        .boards
+        .get_user_role(column.board_id, auth.user.id)
+        .await?
+        .ok_or(AppError::Forbidden)?;
+
+    state
+        .columns
+        .set_collapsed(column_id, auth.user.id, input.collapsed)
+        .await?;
+    Ok(Redirect::to(&format!("/boards/{}", column.board_id)).into_response())
+}
+
+pub async fn unarchive_column_submit(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(column_id): Path<Uuid>,
+) -> Result<Response> {
+    let column = columns::set_column_archived(&state, &auth, column_id, false).await?;
+    Ok(Redirect::to(&format!("/boards/{}/archived", column.board_id)).into_response())
+}
+
+pub async fn archive_card_submit(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(card_id): Path<Uuid>,
+) -> Result<Response> {
+    cards::set_card_archived(&state, &auth, card_id, true).await?;
+    Ok(Redirect::to(&format!("/cards/{}", card_id)).into_response())
+}
+
+pub async fn unarchive_card_submit(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(card_id): Path<Uuid>,
+) -> Result<Response> {
+    cards::set_card_archived(&state, &auth, card_id, false).await?;
+    Ok(Redirect::to(&format!("/cards/{}", card_id)).into_response())
+}
+
+#[derive(Deserialize)]
+pub struct CardCoverForm {
+    color: String,
+    #[serde(default)]
+    attachment_id: String,
+}
+
+/// Set the cover of a card; an empty form removes it
+pub async fn card_cover_submit(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(card_id): Path<Uuid>,
+    Form(input): Form<CardCoverForm>,
+) -> Result<Response> {
+    let attachment_id = match input.attachment_id.as_str() {
+        "" => None,
+        id => Some(
+            id.parse::<Uuid>()
+                .map_err(|_| AppError::Validation("Unknown image".to_string()))?,
+        ),
+    };
+    let cover = CardCover {
+        color: Some(input.color),
+        attachment_id,
+    };
+    cards::apply_card_cover(&state, &auth, card_id, &cover).await?;
+    Ok(Redirect::to(&format!("/cards/{}", card_id)).into_response())
+}
+
+pub async fn watch_card_submit(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(card_id): Path<Uuid>,
+) -> Result<Response> {
+    if !can_view_card(&state, card_id, auth.user.id).await? {
+        return Err(AppError::Forbidden);
+    }
+    state.watchers.watch(card_id, auth.user.id).await?;
+    Ok(Redirect::to(&format!("/cards/{}", card_id)).into_response())
+}
+
+pub async fn unwatch_card_submit(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(card_id): Path<Uuid>,
+) -> Result<Response> {
+    state.watchers.unwatch(card_id, auth.user.id).await?;
+    Ok(Redirect::to(&format!("/cards/{}", card_id)).into_response())
+}
+
+#[derive(Deserialize)]
+pub struct ReactionForm {
+    emoji: String,
+}
+
+/// Add the user's reaction to the card, or to its comment when `comment_id` is set,
+/// or take it back if they already reacted with that emoji
+async fn toggle_reaction(
+    state: &AppState,
+    user_id: Uuid,
+    card_id: Uuid,
+    comment_id: Option<Uuid>,
+    emoji: &str,
+) -> Result<()> {
+    if !REACTION_EMOJI.contains(&emoji) {
+        return Err(AppError::Validation("Unsupported reaction".to_string()));
+    }
+    if !state
+        .reactions
+        .remove(card_id, comment_id, user_id, emoji)
+        .await?
+    {
+        if !can_view_card(state, card_id, user_id).await? {
+            return Err(AppError::Forbidden);
+        }
+        state
+            .reactions
+            .add(card_id, comment_id, user_id, emoji)
+            .await?;
+    }
+    Ok(())
+}
+
+pub async fn card_reaction_submit(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(card_id): Path<Uuid>,
+    Form(input): Form<ReactionForm>,
+) -> Result<Response> {
+    state.cards.get_by_id(card_id).await?;
+    toggle_reaction(&state, auth.user.id, card_id, None, &input.emoji).await?;
+    Ok(Redirect::to(&format!("/cards/{}", card_id)).into_response())
+}
+
+pub async fn comment_reaction_submit(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path((card_id, comment_id)): Path<(Uuid, Uuid)>,
+    Form(input): Form<ReactionForm>,
+) -> Result<Response> {
+    let comment = state.comments.get_by_id(comment_id).await?;
+    if comment.card_id != card_id {
+        return Err(AppError::NotFound("Comment"));
+    }
+    toggle_reaction(
+        &state,
+        auth.user.id,
+        card_id,
+        Some(comment_id),
+        &input.emoji,
+    )
+    .await?;
+    Ok(Redirect::to(&format!("/cards/{}", card_id)).into_response())
+}
+
+/// Save the WIP limit policy of the board and the limits of its columns, sent as
+/// `wip_limit_policy` and one `limit_{column_id}` field per column. An empty limit
+/// removes it.
+pub async fn update_wip_limits_submit(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(board_id): Path<Uuid>,
+    Form(input): Form<HashMap<String, String>>,
+) -> Result<Response> {
+    let role = state
+        .boards
+        .get_user_role(board_id, auth.user.id)
+        .await?
+        .ok_or(AppError::Forbidden)?;
+
+    if !role.can_edit() {
+        return Err(AppError::Forbidden);
+    }
+
+    if let Some(policy) = input.get("wip_limit_policy") {
+        let policy: WipLimitPolicy = policy.parse().map_err(AppError::BadRequest)?;
+        let before = state.boards.get_by_id(board_id).await?;
+        let board = state
+            .boards
+            .update(board_id, None, None, None, None, Some(policy), None, None)
+            .await?;
+        if let Some(details) = changes(&before, &board) {
+            record(
+                &state,
+                BoardEvent::new(
+                    BoardEventKind::BoardUpdated,
+                    board_id,
+                    board_id,
+                    auth.user.id,
+                )
+                .with_details(details),
+            )
+            .await;
+        }
+    }
+
+    for column in state.columns.list_by_board(board_id).await? {
+        let Some(value) = input.get(&format!("limit_{}", column.id)) else {
+            continue;
+        };
+        let wip_limit = match value.trim() {
+            "" => None,
+            limit => Some(
+                limit
+                    .parse()
+                    .map_err(|_| AppError::BadRequest("Invalid WIP limit".to_string()))?,
+            ),
+        };
+        if wip_limit != column.wip_limit {
+            let update = UpdateColumn {
+                name: None,
+                wip_limit: Some(wip_limit),
+                sort_policy: None,
+                color: None,
+                description: None,
+                collapsed: None,
+                card_status: None,
+            };
+            columns::apply_column_update(&state, &auth, &column, &update, None).await?;
+        }
+    }
+
+    Ok(Redirect::to(&format!("/boards/{}/settings", board_id)).into_response())
+}
+
+/// Give the cards of the board the status their column maps to
+pub async fn sync_statuses_submit(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(board_id): Path<Uuid>,
+) -> Result<Response> {
+    let role = state
+        .boards
+        .get_user_role(board_id, auth.user.id)
+        .await?
+        .ok_or(AppError::Forbidden)?;
+
+    if !role.can_edit() {
+        return Err(AppError::Forbidden);
+    }
+
+    state.cards.sync_status_with_columns(Some(board_id)).await?;
+    Ok(Redirect::to(&format!("/boards/{}/settings", board_id)).into_response())
+}
+
+#[derive(Deserialize)]
+pub struct DefaultColumnForm {
+    default_column_id: String,
+}
+
+pub async fn update_default_column_submit(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(board_id): Path<Uuid>,
+    Form(input): Form<DefaultColumnForm>,
+) -> Result<Response> {
+    let role = state
+        .boards
+        .get_user_role(board_id, auth.user.id)
+        .await?
+        .ok_or(AppError::Forbidden)?;
+
+    if !role.can_edit() {
+        return Err(AppError::Forbidden);
+    }
+
+    // An empty selection clears the default column
+    let column_id = match input.default_column_id.trim() {
+        "" => None,
+        id => {
+            let column_id: Uuid = id
+                .parse()
+                .map_err(|_| AppError::BadRequest("Invalid column id".to_string()))?;
+            let column = state.columns.get_by_id(column_id).await?;
+            if column.board_id != board_id {
+                return Err(AppError::BadRequest(
+                    "Column does not belong to this board".to_string(),
+                ));
+            }
+            Some(column_id)
+        }
+    };
+
+    let before = state.boards.get_by_id(board_id).await?;
+    let board = state
+        .boards
+        .update(
+            board_id,
+            None,
+            None,
+            None,
+            Some(column_id),
+            None,
+            None,
+            None,
+        )
+        .await?;
+    if let Some(details) = changes(&before, &board) {
+        record(
+            &state,
+            BoardEvent::new(
+                BoardEventKind::BoardUpdated,
+                board_id,
+                board_id,
+                auth.user.id,
+            )
+            .with_details(details),
+        )
+        .await;
+    }
+
+    Ok(Redirect::to(&format!("/boards/{}/settings", board_id)).into_response())
 }
 
 pub async fn delete_board_submit(
@@ -741,7 +2627,17 @@ pub async fn delete_board_submit(
         return Err(AppError::Forbidden);
     }
 
-    state.boards.delete(board_id).await?;
+    state.boards.move_to_trash(board_id, auth.user.id).await?;
+    record(
+        &state,
+        BoardEvent::new(
+            BoardEventKind::BoardDeleted,
+            board_id,
+            board_id,
+            auth.user.id,
+        ),
+    )
+    .await;
 
     Ok(Redirect::to("/boards").into_response())
 }
@@ -764,7 +2660,21 @@ pub async fn add_tag_to_card_submit(
         return Err(AppError::Forbidden);
     }
 
+    state.cards.ensure_unlocked(card_id).await?;
     state.tags.add_to_card(card_id, input.tag_id).await?;
+    let tag = state.tags.find_by_id(input.tag_id).await?;
+    record(
+        &state,
+        BoardEvent::new(
+            BoardEventKind::CardTagged,
+            board_id,
+            input.tag_id,
+            auth.user.id,
+        )
+        .with_card(card_id)
+        .with_details(json!({"tag": tag.map(|t| t.name)})),
+    )
+    .await;
 
     Ok(Redirect::to(&format!("/boards/{}", board_id)).into_response())
 }
@@ -786,25 +2696,163 @@ pub async fn remove_tag_from_card_submit(
         return Err(AppError::Forbidden);
     }
 
+    state.cards.ensure_unlocked(card_id).await?;
+    let tag = state.tags.find_by_id(tag_id).await?;
     state.tags.remove_from_card(card_id, tag_id).await?;
+    record(
+        &state,
+        BoardEvent::new(BoardEventKind::CardUntagged, board_id, tag_id, auth.user.id)
+            .with_card(card_id)
+            .with_details(json!({"tag": tag.map(|t| t.name)})),
+    )
+    .await;
 
     Ok(Redirect::to(&format!("/boards/{}", board_id)).into_response())
 }
 
 // User settings handlers
-pub async fn user_settings(
-    State(state): State<AppState>,
-    auth: AuthUser,
-) -> Result<impl IntoResponse> {
-    let chat_message_count = state.chat_messages.count_by_user(auth.user.id).await?;
+pub async fn user_settings(State(state): State<AppState>, auth: AuthUser) -> Result<Response> {
+    settings_response(&state, auth.user, None, None).await
+}
+
+/// The settings page, with the outcome of a change that was just submitted
+async fn settings_response(
+    state: &AppState,
+    user: User,
+    notice: Option<String>,
+    error: Option<String>,
+) -> Result<Response> {
+    let chat_message_count = state.chat_messages.count_by_user(user.id).await?;
+    let pending_email = state
+        .email_changes
+        .find_for_user(user.id)
+        .await?
+        .map(|change| change.new_email);
 
     let template = UserSettingsTemplate {
-        user: auth.user.name.clone(),
+        theme: user.ui_theme(),
+        user: user.name.clone(),
+        email: user.email.clone(),
+        has_password: user.password_hash.is_some(),
+        pending_email,
+        notice,
+        error,
         chat_message_count,
-        llm_context: auth.user.llm_context,
+        calendar_feed_enabled: state.calendar.has_feed(user.id).await?,
+        inbound_email_enabled: state.inbound_email.exists(user.id).await?,
+        telegram_enabled: state.telegram_bot.is_some(),
+        telegram_linked: state.telegram_links.is_linked(user.id).await?,
+        llm_context: user.llm_context,
+        chat_confirm_actions: user.chat_confirm_actions,
+        llm_providers: state.llm_providers.keys().map(|k| k.to_string()).collect(),
+        llm_provider: user.llm_provider.unwrap_or_default(),
+        llm_model: user.llm_model.unwrap_or_default(),
+        llm_temperature: user
+            .llm_temperature
+            .map(|t| t.to_string())
+            .unwrap_or_default(),
+        default_llm_model: state.llm.model().to_string(),
+        themes: Theme::ALL.into_iter().map(ThemeInfo::from).collect(),
     };
 
-    Ok(Html(template.render().unwrap()))
+    Ok(Html(template.render().unwrap()).into_response())
+}
+
+/// Message for a rejected account change, shown on the settings page
+fn account_change_error(error: AppError) -> Result<String> {
+    match error {
+        AppError::BadRequest(msg)
+        | AppError::Validation(msg)
+        | AppError::InvalidField { message: msg, .. } => Ok(msg),
+        e => Err(e),
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ChangePasswordForm {
+    /// Absent for users without a password
+    current_password: Option<String>,
+    new_password: String,
+}
+
+pub async fn change_password_submit(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Form(input): Form<ChangePasswordForm>,
+) -> Result<Response> {
+    let (notice, error) = match auth::set_password(
+        &state,
+        &auth,
+        input.current_password.as_deref(),
+        &input.new_password,
+    )
+    .await
+    {
+        Ok(()) => (Some("Your password was changed".to_string()), None),
+        Err(e) => (None, Some(account_change_error(e)?)),
+    };
+    let user = state.users.get_by_id(auth.user.id).await?;
+    settings_response(&state, user, notice, error).await
+}
+
+#[derive(Deserialize)]
+pub struct ChangeEmailForm {
+    new_email: String,
+    /// Absent for users without a password
+    current_password: Option<String>,
+}
+
+pub async fn change_email_submit(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Form(input): Form<ChangeEmailForm>,
+) -> Result<Response> {
+    let (notice, error) = match auth::request_email_change(
+        &state,
+        &auth,
+        &input.new_email,
+        input.current_password.as_deref(),
+    )
+    .await
+    {
+        Ok(change) => (
+            Some(format!(
+                "Follow the link sent to {} to confirm it",
+                change.new_email
+            )),
+            None,
+        ),
+        Err(e) => (None, Some(account_change_error(e)?)),
+    };
+    settings_response(&state, auth.user, notice, error).await
+}
+
+/// Follow the link confirming a new email address
+pub async fn confirm_email_page(
+    State(state): State<AppState>,
+    auth: OptionalAuthUser,
+    Path(token): Path<String>,
+) -> Result<Response> {
+    let outcome = match auth::confirm_email(&state, &token).await {
+        Ok(user) => Ok(format!("Your email address is now {}", user.email)),
+        Err(AppError::NotFound(_)) => {
+            Err("This confirmation link is invalid or has expired".to_string())
+        }
+        Err(e) => Err(account_change_error(e)?),
+    };
+
+    match (auth.0, outcome) {
+        (Some(auth), outcome) => {
+            let user = state.users.get_by_id(auth.user.id).await?;
+            let (notice, error) = match outcome {
+                Ok(notice) => (Some(notice), None),
+                Err(error) => (None, Some(error)),
+            };
+            settings_response(&state, user, notice, error).await
+        }
+        (None, Ok(_)) => Ok(Redirect::to("/login").into_response()),
+        (None, Err(error)) => Ok(login_response(&state, Some(error))),
+    }
 }
 
 #[derive(Deserialize)]
@@ -831,6 +2879,81 @@ pub async fn update_llm_context_submit(
     Ok(Redirect::to("/settings").into_response())
 }
 
+#[derive(Deserialize)]
+pub struct UpdateChatConfirmationForm {
+    /// Present only when the checkbox is ticked
+    chat_confirm_actions: Option<String>,
+}
+
+pub async fn update_chat_confirmation_submit(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Form(input): Form<UpdateChatConfirmationForm>,
+) -> Result<Response> {
+    state
+        .users
+        .update_chat_confirm_actions(auth.user.id, input.chat_confirm_actions.is_some())
+        .await?;
+
+    Ok(Redirect::to("/settings").into_response())
+}
+
+#[derive(Deserialize)]
+pub struct UpdateLlmModelForm {
+    llm_provider: Option<String>,
+    llm_model: Option<String>,
+    llm_temperature: Option<String>,
+}
+
+pub async fn update_llm_model_submit(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Form(input): Form<UpdateLlmModelForm>,
+) -> Result<Response> {
+    let temperature = input
+        .llm_temperature
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .map(|t| {
+            t.parse::<f64>()
+                .map_err(|_| AppError::BadRequest(format!("Invalid temperature: {}", t)))
+        })
+        .transpose()?;
+
+    crate::handlers::settings::save_llm_preferences(
+        &state,
+        &auth.user,
+        input.llm_provider,
+        input.llm_model,
+        temperature,
+    )
+    .await?;
+
+    Ok(Redirect::to("/settings").into_response())
+}
+
+#[derive(Deserialize)]
+pub struct UpdateThemeForm {
+    theme: String,
+}
+
+pub async fn update_theme_submit(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Form(input): Form<UpdateThemeForm>,
+) -> Result<Response> {
+    let theme: Theme = input.theme.parse().map_err(AppError::BadRequest)?;
+
+    state.users.update_theme(auth.user.id, theme).await?;
+
+    Ok(Redirect::to("/settings").into_response())
+}
+
+/// Theme registry, served next to the static assets as /static/themes.json
+pub async fn themes_registry() -> Json<Vec<ThemeInfo>> {
+    Json(Theme::ALL.into_iter().map(ThemeInfo::from).collect())
+}
+
 pub async fn delete_chat_history_submit(
     State(state): State<AppState>,
     auth: AuthUser,
@@ -840,6 +2963,137 @@ pub async fn delete_chat_history_submit(
     Ok(Redirect::to("/settings").into_response())
 }
 
+pub async fn create_calendar_feed_submit(
+    State(state): State<AppState>,
+    auth: AuthUser,
+) -> Result<Response> {
+    let feed = create_feed_token(&state, auth.user.id).await?;
+    let notice = format!(
+        "Subscribe to this address from your calendar app. It is only shown once: {}",
+        feed.url
+    );
+    settings_response(&state, auth.user, Some(notice), None).await
+}
+
+pub async fn delete_calendar_feed_submit(
+    State(state): State<AppState>,
+    auth: AuthUser,
+) -> Result<Response> {
+    state.calendar.delete_feed(auth.user.id).await?;
+
+    Ok(Redirect::to("/settings").into_response())
+}
+
+pub async fn security_settings(State(state): State<AppState>, auth: AuthUser) -> Result<Response> {
+    security_response(&state, &auth, None).await
+}
+
+/// The security page, with the outcome of a change that was just submitted
+async fn security_response(
+    state: &AppState,
+    auth: &AuthUser,
+    notice: Option<String>,
+) -> Result<Response> {
+    let sessions = state.sessions.list_active_for_user(auth.user.id).await?;
+    let tokens = state.tokens.list_by_user(auth.user.id).await?;
+
+    let template = SecurityTemplate {
+        theme: auth.user.ui_theme(),
+        user: auth.user.name.clone(),
+        notice,
+        sessions: sessions
+            .into_iter()
+            .map(|s| SessionResponse::new(s, auth.session_token.as_deref()))
+            .collect(),
+        tokens: tokens.into_iter().map(|t| t.into()).collect(),
+    };
+
+    Ok(Html(template.render().unwrap()).into_response())
+}
+
+pub async fn revoke_other_sessions_submit(
+    State(state): State<AppState>,
+    auth: AuthUser,
+) -> Result<Response> {
+    let revoked = state
+        .sessions
+        .delete_for_user(auth.user.id, auth.session_token.as_deref())
+        .await?;
+    let notice = format!("Logged out {} other session(s)", revoked);
+    security_response(&state, &auth, Some(notice)).await
+}
+
+pub async fn revoke_session_submit(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(session_id): Path<Uuid>,
+) -> Result<Response> {
+    state
+        .sessions
+        .delete_for_user_by_id(session_id, auth.user.id)
+        .await?;
+
+    Ok(Redirect::to("/settings/security").into_response())
+}
+
+pub async fn revoke_token_submit(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(token_id): Path<Uuid>,
+) -> Result<Response> {
+    state.tokens.delete(token_id, auth.user.id).await?;
+
+    Ok(Redirect::to("/settings/security").into_response())
+}
+
+pub async fn create_inbound_email_submit(
+    State(state): State<AppState>,
+    auth: AuthUser,
+) -> Result<Response> {
+    let address = create_address_token(&state, auth.user.id).await?;
+    let notice = format!(
+        "Have your mail server or forwarding service post raw emails to this address. It is only shown once: {}",
+        address.url
+    );
+    settings_response(&state, auth.user, Some(notice), None).await
+}
+
+pub async fn delete_inbound_email_submit(
+    State(state): State<AppState>,
+    auth: AuthUser,
+) -> Result<Response> {
+    state.inbound_email.delete(auth.user.id).await?;
+
+    Ok(Redirect::to("/settings").into_response())
+}
+
+pub async fn create_telegram_link_submit(
+    State(state): State<AppState>,
+    auth: AuthUser,
+) -> Result<Response> {
+    let link = match create_link_code(&state, auth.user.id).await {
+        Ok(link) => link,
+        Err(AppError::BadRequest(msg)) => {
+            return settings_response(&state, auth.user, None, Some(msg)).await
+        }
+        Err(e) => return Err(e),
+    };
+    let notice = format!(
+        "Send this message to the bot within 15 minutes: {}",
+        link.command
+    );
+    settings_response(&state, auth.user, Some(notice), None).await
+}
+
+pub async fn delete_telegram_link_submit(
+    State(state): State<AppState>,
+    auth: AuthUser,
+) -> Result<Response> {
+    state.telegram_links.delete(auth.user.id).await?;
+
+    Ok(Redirect::to("/settings").into_response())
+}
+
 // Inbox handlers
 pub async fn inbox_page(
     State(state): State<AppState>,
@@ -856,6 +3110,18 @@ pub async fn inbox_page(
         .list_by_owner_with_status(auth.user.id, status)
         .await?;
 
+    let template = InboxTemplate {
+        theme: auth.user.ui_theme(),
+        user: auth.user.name,
+        cards: inbox_card_views(&state, cards).await?,
+        current_status: query.status,
+    };
+
+    Ok(Html(template.render().unwrap()).into_response())
+}
+
+/// Card list entries with the boards each card is on
+async fn inbox_card_views(state: &AppState, cards: Vec<Card>) -> Result<Vec<InboxCardView>> {
     let mut card_views = Vec::new();
     for card in cards {
         // Get boards this card is assigned to
@@ -876,19 +3142,127 @@ pub async fn inbox_page(
             body: card.body,
             status: card.status,
             due_date: card.due_date.map(|d| d.to_string()),
+            priority: card.priority,
             boards: board_views,
         });
     }
 
-    let template = InboxTemplate {
+    Ok(card_views)
+}
+
+/// Cards assigned to the current user, across all boards
+pub async fn assigned_page(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Query(query): Query<InboxQuery>,
+) -> Result<Response> {
+    let status = query
+        .status
+        .as_ref()
+        .and_then(|s| s.parse::<CardStatus>().ok());
+
+    let mut cards = Vec::new();
+    for card in state
+        .assignees
+        .list_cards_for_user(auth.user.id, status)
+        .await?
+    {
+        if can_view_card(&state, card.id, auth.user.id).await? {
+            cards.push(card);
+        }
+    }
+
+    let template = AssignedTemplate {
+        theme: auth.user.ui_theme(),
         user: auth.user.name,
-        cards: card_views,
+        cards: inbox_card_views(&state, cards).await?,
         current_status: query.status,
     };
 
     Ok(Html(template.render().unwrap()).into_response())
 }
 
+/// Cards needing the user's attention across every board they can access
+pub async fn dashboard_page(State(state): State<AppState>, auth: AuthUser) -> Result<Response> {
+    let today = Utc::now().date_naive();
+    let dashboard = dashboard::build_dashboard(&state, auth.user.id, today).await?;
+
+    let section = |title, empty, cards: Vec<DashboardCardResponse>| DashboardSection {
+        title,
+        empty,
+        cards: cards
+            .into_iter()
+            .map(|c| DashboardCardView {
+                id: c.card.id.to_string(),
+                title: c.card.title,
+                status: c.card.status,
+                due_date: c.card.due_date.map(|d| d.to_string()),
+                priority: c.card.priority,
+                board_name: c.board_name,
+            })
+            .collect(),
+    };
+    let template = DashboardTemplate {
+        theme: auth.user.ui_theme(),
+        user: auth.user.name,
+        sections: vec![
+            section("Overdue", "Nothing is overdue.", dashboard.overdue),
+            section(
+                "Due this week",
+                "Nothing is due this week.",
+                dashboard.due_this_week,
+            ),
+            section(
+                "Assigned to me",
+                "No open cards are assigned to you.",
+                dashboard.assigned,
+            ),
+            section(
+                "Recently updated",
+                "No card changed in the last week.",
+                dashboard.recently_updated,
+            ),
+        ],
+    };
+
+    Ok(Html(template.render().unwrap()).into_response())
+}
+
+/// Month grid of the cards the user can see, by due date and start-to-end span
+pub async fn calendar_page(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Query(query): Query<CalendarQuery>,
+) -> Result<Response> {
+    let today = Utc::now().date_naive();
+    let start = query
+        .month
+        .and_then(|m| NaiveDate::parse_from_str(&format!("{}-01", m), "%Y-%m-%d").ok())
+        .map(month_start)
+        .unwrap_or_else(|| month_start(today));
+
+    let (first, last) = grid_bounds(start);
+    let cards = state
+        .calendar
+        .list_dated_cards(auth.user.id, Some(first), Some(last))
+        .await?;
+
+    let prev = start.checked_sub_months(Months::new(1)).unwrap_or(start);
+    let next = start.checked_add_months(Months::new(1)).unwrap_or(start);
+
+    let template = CalendarTemplate {
+        theme: auth.user.ui_theme(),
+        user: auth.user.name,
+        month_label: start.format("%B %Y").to_string(),
+        prev_month: prev.format("%Y-%m").to_string(),
+        next_month: next.format("%Y-%m").to_string(),
+        today,
+        weeks: month_grid(start, &cards),
+    };
+
+    Ok(Html(template.render().unwrap()).into_response())
+}
+
 pub async fn create_inbox_card_submit(
     State(state): State<AppState>,
     auth: AuthUser,
@@ -918,6 +3292,7 @@ pub async fn create_inbox_card_submit(
             None,
             None,
             due_date,
+            None,
             auth.user.id,
         )
         .await?;
@@ -938,16 +3313,66 @@ pub async fn update_card_status_submit(
         return Err(AppError::Forbidden);
     }
 
+    if card.is_locked() {
+        return Err(AppError::Locked);
+    }
+
     let status: CardStatus = input
         .status
         .parse()
         .map_err(|_| AppError::BadRequest("Invalid status".to_string()))?;
 
-    state.cards.update_status(card_id, status).await?;
+    let updated = state.cards.update_status(card_id, status).await?;
+    if let Some(details) = changes(&card, &updated) {
+        record_for_card(
+            &state,
+            BoardEventKind::CardUpdated,
+            card_id,
+            card_id,
+            auth.user.id,
+            Some(details),
+        )
+        .await;
+    }
 
     Ok(Redirect::to("/inbox").into_response())
 }
 
+/// Build the comment list for a card page, with mentions highlighted and
+/// their reactions taken from `reactions`.
+async fn comment_views(
+    state: &AppState,
+    card_id: Uuid,
+    reactions: &CardReactions,
+) -> Result<Vec<CommentView>> {
+    let comments = state.comments.list_by_card(card_id).await?;
+    let mentioned = state.mentions.list_for_card(card_id).await?;
+    Ok(comments
+        .into_iter()
+        .map(|c| {
+            let users: Vec<Mentionable> = mentioned
+                .iter()
+                .filter(|(comment_id, _)| *comment_id == c.id)
+                .map(|(_, u)| Mentionable {
+                    name: &u.name,
+                    email: &u.email,
+                })
+                .collect();
+            (mentions::highlight(&c.body, &users), c)
+        })
+        .map(|(body_html, c)| CommentView {
+            id: c.id.to_string(),
+            user_id: c.user_id.to_string(),
+            author_name: c.author_name,
+            reactions: reactions.for_comment(c.id),
+            body: c.body,
+            body_html,
+            created_at: c.created_at.format("%Y-%m-%d %H:%M").to_string(),
+            updated_at: c.updated_at.format("%Y-%m-%d %H:%M").to_string(),
+        })
+        .collect())
+}
+
 /// Card detail page with comments
 pub async fn card_detail(
     State(state): State<AppState>,
@@ -957,26 +3382,7 @@ pub async fn card_detail(
     let card = state.cards.get_by_id(card_id).await?;
 
     // Check access
-    let has_access = card.owner_id == Some(auth.user.id)
-        || card.created_by == auth.user.id
-        || {
-            let boards = state.card_boards.list_boards_for_card(card_id).await?;
-            let mut has_board_access = false;
-            for board in boards {
-                if state
-                    .boards
-                    .get_user_role(board.id, auth.user.id)
-                    .await?
-                    .is_some()
-                {
-                    has_board_access = true;
-                    break;
-                }
-            }
-            has_board_access
-        };
-
-    if !has_access {
+    if !can_view_card(&state, card_id, auth.user.id).await? {
         return Err(AppError::Forbidden);
     }
 
@@ -1009,36 +3415,78 @@ pub async fn card_detail(
         })
         .collect();
 
-    // Get comments
-    let comments = state.comments.list_by_card(card_id).await?;
-    let comment_views: Vec<CommentView> = comments
+    let assignees = state
+        .assignees
+        .list_for_card(card_id)
+        .await?
         .into_iter()
-        .map(|c| CommentView {
-            id: c.id.to_string(),
-            user_id: c.user_id.to_string(),
-            author_name: c.author_name,
-            body: c.body,
-            created_at: c.created_at.format("%Y-%m-%d %H:%M").to_string(),
-            updated_at: c.updated_at.format("%Y-%m-%d %H:%M").to_string(),
+        .map(|a| a.name)
+        .collect();
+
+    let mut links = Vec::new();
+    for link in state.links.list_for_card(card_id).await? {
+        if can_view_card(&state, link.card.id, auth.user.id).await? {
+            links.push(LinkView {
+                label: link.link_type.label(),
+                card_id: link.card.id.to_string(),
+                title: link.card.title,
+                status: link.card.status,
+            });
+        }
+    }
+
+    let cover_images = state
+        .attachments
+        .list_by_card(card_id)
+        .await?
+        .into_iter()
+        .filter(|a| a.content_type.starts_with("image/"))
+        .map(|a| CoverImageView {
+            id: a.id.to_string(),
+            filename: a.filename,
         })
         .collect();
 
+    // Get comments
+    let reactions = state.reactions.list_for_card(card_id, auth.user.id).await?;
+    let comment_views = comment_views(&state, card_id, &reactions).await?;
+
+    // Restore unsent comment text, if any
+    let comment_draft = state
+        .comments
+        .find_draft(card_id, auth.user.id)
+        .await?
+        .map(|d| d.body);
+
     let card_view = CardDetailView {
+        locked: card.is_locked(),
+        archived: card.archived_at.is_some(),
         id: card.id.to_string(),
         title: card.title,
         body: card.body,
         status: card.status.to_string(),
         due_date: card.due_date.map(|d| d.format("%Y-%m-%d").to_string()),
+        priority: card.priority,
         created_at: card.created_at.format("%Y-%m-%d %H:%M").to_string(),
         boards: board_views,
         tags: tag_views,
+        assignees,
+        links,
+        cover_color: card.cover_color,
+        cover_attachment_id: card.cover_attachment_id.map(|id| id.to_string()),
+        cover_images,
     };
 
     let template = CardDetailTemplate {
+        theme: auth.user.ui_theme(),
         user: auth.user.name,
         card: card_view,
         comments: comment_views,
         current_user_id: auth.user.id.to_string(),
+        comment_draft,
+        watching: state.watchers.is_watching(card_id, auth.user.id).await?,
+        reactions: reactions.card,
+        reaction_emoji: REACTION_EMOJI,
     };
 
     Ok(Html(template.render().unwrap()).into_response())
@@ -1054,34 +3502,46 @@ pub async fn add_comment_submit(
     let card = state.cards.get_by_id(card_id).await?;
 
     // Check edit access
-    let can_edit = card.owner_id == Some(auth.user.id)
-        || card.created_by == auth.user.id
-        || {
-            let boards = state.card_boards.list_boards_for_card(card_id).await?;
-            let mut has_edit_access = false;
-            for board in boards {
-                if let Some(role) = state.boards.get_user_role(board.id, auth.user.id).await? {
-                    if role.can_edit() {
-                        has_edit_access = true;
-                        break;
-                    }
+    let can_edit = card.owner_id == Some(auth.user.id) || card.created_by == auth.user.id || {
+        let boards = state.card_boards.list_boards_for_card(card_id).await?;
+        let mut has_edit_access = false;
+        for board in boards {
+            if let Some(role) = state.boards.get_user_role(board.id, auth.user.id).await? {
+                if role.can_edit() {
+                    has_edit_access = true;
+                    break;
                 }
             }
-            has_edit_access
-        };
+        }
+        has_edit_access
+    };
 
     if !can_edit {
         return Err(AppError::Forbidden);
     }
 
+    if card.is_locked() {
+        return Err(AppError::Locked);
+    }
+
     if input.body.trim().is_empty() {
         return Err(AppError::Validation("Comment body is required".to_string()));
     }
 
-    state
+    let comment = state
         .comments
         .create(card_id, auth.user.id, &input.body)
         .await?;
+    state.comments.delete_draft(card_id, auth.user.id).await?;
+    record_for_card(
+        &state,
+        BoardEventKind::CommentCreated,
+        card_id,
+        comment.id,
+        auth.user.id,
+        Some(json!({"body": comment.body})),
+    )
+    .await;
 
     Ok(Redirect::to(&format!("/cards/{}", card_id)).into_response())
 }
@@ -1099,7 +3559,17 @@ pub async fn delete_comment_submit(
         return Err(AppError::Forbidden);
     }
 
+    state.cards.ensure_unlocked(comment.card_id).await?;
     state.comments.delete(comment_id).await?;
+    record_for_card(
+        &state,
+        BoardEventKind::CommentDeleted,
+        comment.card_id,
+        comment_id,
+        auth.user.id,
+        Some(json!({"body": comment.body})),
+    )
+    .await;
 
     Ok(Redirect::to(&format!("/cards/{}", card_id)).into_response())
 }
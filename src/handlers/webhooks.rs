@@ -0,0 +1,207 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use reqwest::Url;
+use uuid::Uuid;
+
+use crate::auth::AuthUser;
+use crate::error::{AppError, Result};
+use crate::handlers::share_links::require_owner;
+use crate::models::{
+    BoardEvent, CreateWebhook, ReplayWebhookQuery, Webhook, WebhookEvent, WebhookPayload,
+    WebhookReplayResponse,
+};
+use crate::state::AppState;
+
+/// How often events past their retention are deleted
+const PURGE_PERIOD: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+
+/// Days events are kept for replay when WEBHOOK_RETENTION_DAYS is not set
+const DEFAULT_RETENTION_DAYS: i64 = 7;
+
+/// Days events sent to webhooks are kept for replay, from WEBHOOK_RETENTION_DAYS
+pub fn retention_days() -> i64 {
+    std::env::var("WEBHOOK_RETENTION_DAYS")
+        .ok()
+        .and_then(|v| v.trim().parse().ok())
+        .filter(|days| *days > 0)
+        .unwrap_or(DEFAULT_RETENTION_DAYS)
+}
+
+/// The webhook, if the user owns its board. Webhooks of other boards are reported as
+/// missing.
+async fn find_owned(state: &AppState, id: Uuid, user_id: Uuid) -> Result<Webhook> {
+    let webhook = state
+        .webhooks
+        .find_by_id(id)
+        .await?
+        .ok_or(AppError::NotFound("webhook"))?;
+    match require_owner(state, webhook.board_id, user_id).await {
+        Err(AppError::Forbidden) => Err(AppError::NotFound("webhook")),
+        result => result.map(|()| webhook),
+    }
+}
+
+/// List the board's webhooks
+pub async fn list_webhooks(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(board_id): Path<Uuid>,
+) -> Result<Json<Vec<Webhook>>> {
+    require_owner(&state, board_id, auth.user.id).await?;
+    Ok(Json(state.webhooks.list_for_board(board_id).await?))
+}
+
+/// Send the board's events to an address
+pub async fn create_webhook(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(board_id): Path<Uuid>,
+    Json(input): Json<CreateWebhook>,
+) -> Result<Json<Webhook>> {
+    require_owner(&state, board_id, auth.user.id).await?;
+
+    let url = input.url.trim();
+    match Url::parse(url) {
+        Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => {}
+        _ => {
+            return Err(AppError::invalid_field(
+                "url",
+                "Must be an http or https address",
+            ))
+        }
+    }
+
+    let webhook = state.webhooks.create(board_id, url, auth.user.id).await?;
+    Ok(Json(webhook))
+}
+
+/// Stop sending events to the webhook and forget the events kept for it
+pub async fn delete_webhook(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(webhook_id): Path<Uuid>,
+) -> Result<StatusCode> {
+    let webhook = find_owned(&state, webhook_id, auth.user.id).await?;
+    state.webhooks.delete(webhook.id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Deliver again, oldest first, the events sent to the webhook since a time, for
+/// consumers that missed them. Only events still within WEBHOOK_RETENTION_DAYS can be
+/// replayed. The deliveries run in the background.
+pub async fn replay_webhook(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(webhook_id): Path<Uuid>,
+    Query(query): Query<ReplayWebhookQuery>,
+) -> Result<Json<WebhookReplayResponse>> {
+    let webhook = find_owned(&state, webhook_id, auth.user.id).await?;
+    let events = state
+        .webhooks
+        .list_events_since(webhook.id, query.since)
+        .await?;
+    let replayed = events.len();
+
+    let task_state = state.clone();
+    state.jobs.spawn(async move {
+        for event in events {
+            deliver(&task_state, &webhook.url, event, true).await;
+        }
+    });
+
+    Ok(Json(WebhookReplayResponse { replayed }))
+}
+
+/// Keep the event for each webhook of the boards and send it to them in the
+/// background. Each board's webhooks receive the event with that board's id.
+pub(crate) async fn dispatch(state: &AppState, board_ids: &[Uuid], event: &BoardEvent) {
+    for &board_id in board_ids {
+        let webhooks = match state.webhooks.list_for_board(board_id).await {
+            Ok(webhooks) => webhooks,
+            Err(e) => {
+                tracing::warn!(board_id = %board_id, error = %e, "Could not list webhooks");
+                continue;
+            }
+        };
+
+        for webhook in webhooks {
+            let delivery_id = Uuid::new_v4();
+            let payload = WebhookPayload::new(delivery_id, board_id, event);
+            let body = match serde_json::to_string(&payload) {
+                Ok(body) => body,
+                Err(e) => {
+                    tracing::warn!(error = %e, "Could not serialize webhook payload");
+                    continue;
+                }
+            };
+            let name = event.event.to_string();
+            if let Err(e) = state
+                .webhooks
+                .record_event(
+                    delivery_id,
+                    webhook.id,
+                    &name,
+                    payload.schema_version,
+                    &body,
+                )
+                .await
+            {
+                tracing::warn!(webhook_id = %webhook.id, error = %e, "Could not keep webhook event");
+                continue;
+            }
+
+            let stored = WebhookEvent {
+                id: delivery_id,
+                webhook_id: webhook.id,
+                event: name,
+                schema_version: payload.schema_version,
+                payload: body,
+                created_at: event.at,
+            };
+            let task_state = state.clone();
+            state.jobs.spawn(async move {
+                deliver(&task_state, &webhook.url, stored, false).await;
+            });
+        }
+    }
+}
+
+/// Send a kept event to the webhook's address and record how it went
+async fn deliver(state: &AppState, url: &str, event: WebhookEvent, replay: bool) {
+    let result = state
+        .webhook_sender
+        .deliver(
+            url,
+            event.id,
+            &event.event,
+            event.schema_version,
+            &event.payload,
+            replay,
+        )
+        .await;
+    if let Err(error) = &result {
+        tracing::warn!(webhook_id = %event.webhook_id, error = %error, "Webhook delivery failed");
+    }
+    if let Err(e) = state
+        .webhooks
+        .record_attempt(event.id, result.err().as_deref())
+        .await
+    {
+        tracing::warn!(error = %e, "Could not record webhook delivery");
+    }
+}
+
+/// Delete events past their retention now and then, until the server shuts down
+pub async fn run_purge_job(state: AppState) {
+    let mut ticks = tokio::time::interval(PURGE_PERIOD);
+    while state.jobs.tick(&mut ticks).await {
+        match state.webhooks.purge_events(retention_days()).await {
+            Ok(0) => {}
+            Ok(purged) => tracing::info!("Purged {} webhook event(s)", purged),
+            Err(e) => tracing::warn!(error = %e, "Webhook event purge failed"),
+        }
+    }
+}
@@ -0,0 +1,232 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use uuid::Uuid;
+
+use crate::auth::AuthUser;
+use crate::error::{AppError, Result};
+use crate::models::{
+    AddWorkspaceMember, Board, BoardResponse, BoardRole, CreateWorkspace, SetBoardWorkspace,
+    UpdateWorkspace, WorkspaceMember, WorkspaceResponse,
+};
+use crate::state::AppState;
+
+/// The user's role in the workspace; `Forbidden` unless they are a member
+async fn member_role(state: &AppState, workspace_id: Uuid, user_id: Uuid) -> Result<BoardRole> {
+    state.workspaces.get_by_id(workspace_id).await?;
+    state
+        .workspaces
+        .get_user_role(workspace_id, user_id)
+        .await?
+        .ok_or(AppError::Forbidden)
+}
+
+/// Only workspace owners manage the workspace and its members
+async fn require_owner(state: &AppState, workspace_id: Uuid, user_id: Uuid) -> Result<()> {
+    if !member_role(state, workspace_id, user_id)
+        .await?
+        .can_manage_permissions()
+    {
+        return Err(AppError::Forbidden);
+    }
+    Ok(())
+}
+
+/// Check that the user can put boards in the workspace
+pub(crate) async fn require_workspace_editor(
+    state: &AppState,
+    workspace_id: Uuid,
+    user_id: Uuid,
+) -> Result<()> {
+    if !member_role(state, workspace_id, user_id).await?.can_edit() {
+        return Err(AppError::Forbidden);
+    }
+    Ok(())
+}
+
+fn board_response(board: Board, role: String) -> BoardResponse {
+    BoardResponse {
+        id: board.id,
+        name: board.name,
+        description: board.description,
+        owner_id: board.owner_id,
+        role,
+        chat_column_policy: board.chat_column_policy,
+        default_column_id: board.default_column_id,
+        wip_limit_policy: board.wip_limit_policy,
+        allow_public_links: board.allow_public_links,
+        workspace_id: board.workspace_id,
+        version: board.version,
+        created_at: board.created_at,
+        updated_at: board.updated_at,
+    }
+}
+
+pub async fn create_workspace(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Json(input): Json<CreateWorkspace>,
+) -> Result<Json<WorkspaceResponse>> {
+    let name = input.name.trim();
+    if name.is_empty() {
+        return Err(AppError::invalid_field(
+            "name",
+            "Workspace name is required",
+        ));
+    }
+
+    let workspace = state.workspaces.create(name, auth.user.id).await?;
+    Ok(Json(WorkspaceResponse::new(
+        workspace,
+        BoardRole::Owner.to_string(),
+    )))
+}
+
+/// Workspaces the user is a member of, by name
+pub async fn list_workspaces(
+    State(state): State<AppState>,
+    auth: AuthUser,
+) -> Result<Json<Vec<WorkspaceResponse>>> {
+    let workspaces = state.workspaces.list_for_user(auth.user.id).await?;
+    Ok(Json(
+        workspaces
+            .into_iter()
+            .map(|(workspace, role)| WorkspaceResponse::new(workspace, role))
+            .collect(),
+    ))
+}
+
+pub async fn get_workspace(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(workspace_id): Path<Uuid>,
+) -> Result<Json<WorkspaceResponse>> {
+    let role = member_role(&state, workspace_id, auth.user.id).await?;
+    let workspace = state.workspaces.get_by_id(workspace_id).await?;
+    Ok(Json(WorkspaceResponse::new(workspace, role.to_string())))
+}
+
+pub async fn update_workspace(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(workspace_id): Path<Uuid>,
+    Json(input): Json<UpdateWorkspace>,
+) -> Result<Json<WorkspaceResponse>> {
+    require_owner(&state, workspace_id, auth.user.id).await?;
+    let name = input.name.trim();
+    if name.is_empty() {
+        return Err(AppError::invalid_field(
+            "name",
+            "Workspace name is required",
+        ));
+    }
+
+    let workspace = state.workspaces.rename(workspace_id, name).await?;
+    Ok(Json(WorkspaceResponse::new(
+        workspace,
+        BoardRole::Owner.to_string(),
+    )))
+}
+
+/// Delete the workspace. Its boards stay, with their own members only.
+pub async fn delete_workspace(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(workspace_id): Path<Uuid>,
+) -> Result<()> {
+    require_owner(&state, workspace_id, auth.user.id).await?;
+    state.workspaces.delete(workspace_id).await
+}
+
+/// Boards of the workspace with the user's role on each
+pub async fn list_workspace_boards(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(workspace_id): Path<Uuid>,
+) -> Result<Json<Vec<BoardResponse>>> {
+    member_role(&state, workspace_id, auth.user.id).await?;
+
+    let mut boards = Vec::new();
+    for board in state.boards.list_for_workspace(workspace_id).await? {
+        if let Some(role) = state.boards.get_user_role(board.id, auth.user.id).await? {
+            boards.push(board_response(board, role.to_string()));
+        }
+    }
+    Ok(Json(boards))
+}
+
+pub async fn list_members(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(workspace_id): Path<Uuid>,
+) -> Result<Json<Vec<WorkspaceMember>>> {
+    member_role(&state, workspace_id, auth.user.id).await?;
+    Ok(Json(state.workspaces.list_members(workspace_id).await?))
+}
+
+/// Add a member to the workspace, or change their role
+pub async fn add_member(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(workspace_id): Path<Uuid>,
+    Json(input): Json<AddWorkspaceMember>,
+) -> Result<()> {
+    require_owner(&state, workspace_id, auth.user.id).await?;
+
+    // Cannot add another owner
+    if input.role == BoardRole::Owner {
+        return Err(AppError::BadRequest("Cannot add another owner".to_string()));
+    }
+    if state.users.find_by_id(input.user_id).await?.is_none() {
+        return Err(AppError::NotFound("user"));
+    }
+
+    state
+        .workspaces
+        .add_member(workspace_id, input.user_id, input.role)
+        .await
+}
+
+pub async fn remove_member(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path((workspace_id, user_id)): Path<(Uuid, Uuid)>,
+) -> Result<()> {
+    require_owner(&state, workspace_id, auth.user.id).await?;
+    state.workspaces.remove_member(workspace_id, user_id).await
+}
+
+/// Move a board into a workspace, or out of it. Takes the board's owner, who must
+/// also be able to edit in the target workspace.
+pub async fn set_board_workspace(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(board_id): Path<Uuid>,
+    Json(input): Json<SetBoardWorkspace>,
+) -> Result<Json<BoardResponse>> {
+    let role = state
+        .boards
+        .get_user_role(board_id, auth.user.id)
+        .await?
+        .ok_or(AppError::Forbidden)?;
+    if !role.can_manage_permissions() {
+        return Err(AppError::Forbidden);
+    }
+    if let Some(workspace_id) = input.workspace_id {
+        require_workspace_editor(&state, workspace_id, auth.user.id).await?;
+    }
+
+    let board = state
+        .boards
+        .set_workspace(board_id, input.workspace_id)
+        .await?;
+    // Leaving the workspace may change the user's role, when it came from there
+    let role = state
+        .boards
+        .get_user_role(board_id, auth.user.id)
+        .await?
+        .map(|r| r.to_string())
+        .unwrap_or_default();
+    Ok(Json(board_response(board, role)))
+}
@@ -0,0 +1,76 @@
+use axum::{
+    extract::{
+        ws::{close_code, CloseFrame, Message, WebSocket, WebSocketUpgrade},
+        Path, State,
+    },
+    response::Response,
+};
+use tokio::sync::broadcast::{error::RecvError, Receiver};
+use uuid::Uuid;
+
+use crate::auth::AuthUser;
+use crate::error::{AppError, Result};
+use crate::models::BoardEvent;
+use crate::state::AppState;
+
+/// Stream live change events of a board over a WebSocket.
+///
+/// Any role may subscribe. Each event is sent as one JSON text message; the server
+/// ignores messages from the client and closes when the client does, or with a
+/// "going away" close frame when it shuts down.
+pub async fn board_events(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(board_id): Path<Uuid>,
+    ws: WebSocketUpgrade,
+) -> Result<Response> {
+    state
+        .boards
+        .get_user_role(board_id, auth.user.id)
+        .await?
+        .ok_or(AppError::Forbidden)?;
+
+    // Subscribe before the upgrade so no event is lost during the handshake
+    let events = state.events.subscribe(board_id);
+    let user_id = auth.user.id;
+
+    Ok(ws.on_upgrade(move |socket| async move {
+        tracing::debug!(board_id = %board_id, user_id = %user_id, "Board event stream opened");
+        forward_events(&state, socket, events).await;
+        tracing::debug!(board_id = %board_id, user_id = %user_id, "Board event stream closed");
+    }))
+}
+
+async fn forward_events(state: &AppState, mut socket: WebSocket, mut events: Receiver<BoardEvent>) {
+    loop {
+        tokio::select! {
+            // Open sockets would otherwise hold up the shutdown
+            _ = state.jobs.stopping() => {
+                let close = CloseFrame {
+                    code: close_code::AWAY,
+                    reason: "Server shutting down".into(),
+                };
+                let _ = socket.send(Message::Close(Some(close))).await;
+                break;
+            }
+            event = events.recv() => match event {
+                Ok(event) => {
+                    let Ok(text) = serde_json::to_string(&event) else {
+                        continue;
+                    };
+                    if socket.send(Message::Text(text.into())).await.is_err() {
+                        break;
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!(skipped, "Board event subscriber lagging, events dropped");
+                }
+                Err(RecvError::Closed) => break,
+            },
+            message = socket.recv() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
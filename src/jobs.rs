@@ -0,0 +1,140 @@
+use std::future::Future;
+use std::time::Duration;
+use tokio::time::Interval;
+use tokio_util::{sync::CancellationToken, task::TaskTracker};
+
+use crate::state::AppState;
+
+/// Owns the tasks running next to the request handlers: scheduled jobs, and work that
+/// outlives the request that started it, such as a streamed chat reply.
+///
+/// On shutdown, jobs stop at their next tick or wait point, and work already underway is
+/// given time to finish so it is not cut off halfway.
+#[derive(Clone, Default)]
+pub struct JobManager {
+    shutdown: CancellationToken,
+    tasks: TaskTracker,
+}
+
+impl JobManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run `task` in the background, tracked until it completes
+    pub fn spawn<F>(&self, task: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        self.tasks.spawn(task);
+    }
+
+    /// Wait for the next tick of `ticks`. Returns false once shutdown starts, which ends
+    /// the job's loop.
+    pub async fn tick(&self, ticks: &mut Interval) -> bool {
+        tokio::select! {
+            _ = self.shutdown.cancelled() => false,
+            _ = ticks.tick() => true,
+        }
+    }
+
+    /// Run `future` unless shutdown starts first, for waits that may be abandoned
+    pub async fn until_shutdown<F: Future>(&self, future: F) -> Option<F::Output> {
+        self.shutdown.run_until_cancelled(future).await
+    }
+
+    /// Resolves once shutdown starts
+    pub async fn stopping(&self) {
+        self.shutdown.cancelled().await
+    }
+
+    /// Start shutting down: jobs end their loops and waits given up on return
+    pub fn stop(&self) {
+        self.shutdown.cancel();
+    }
+
+    /// Stop the jobs and wait up to `grace` for tracked tasks to finish. Returns whether
+    /// they all did.
+    pub async fn shutdown(&self, grace: Duration) -> bool {
+        self.stop();
+        self.tasks.close();
+        let finished = tokio::time::timeout(grace, self.tasks.wait()).await.is_ok();
+        if !finished {
+            tracing::warn!(
+                remaining = self.tasks.len(),
+                "Background tasks still running after {}s, abandoning them",
+                grace.as_secs()
+            );
+        }
+        finished
+    }
+}
+
+/// Start the scheduled jobs of the server
+pub fn start(state: &AppState) {
+    let jobs = &state.jobs;
+
+    // Copy recurring cards as they come due
+    jobs.spawn(crate::handlers::recurrences::run_scheduler(state.clone()));
+
+    // Remind users of cards that are due soon or overdue
+    jobs.spawn(crate::handlers::notifications::run_scheduler(state.clone()));
+
+    // Delete items that have been in the trash for longer than TRASH_RETENTION_DAYS
+    jobs.spawn(crate::handlers::trash::run_purge_job(state.clone()));
+
+    // Delete events kept for webhooks for longer than WEBHOOK_RETENTION_DAYS
+    jobs.spawn(crate::handlers::webhooks::run_purge_job(state.clone()));
+
+    // Write database snapshots to BACKUP_DIR
+    if let Some(schedule) = crate::services::backup::BackupSchedule::from_env() {
+        jobs.spawn(crate::handlers::backup::run_backup_job(
+            state.clone(),
+            schedule,
+        ));
+    }
+
+    // Delete sessions that expired without activity
+    jobs.spawn(crate::auth::run_session_purge_job(state.clone()));
+
+    // Answer chat messages sent to the Telegram bot
+    if let Some(bot) = state.telegram_bot.clone() {
+        jobs.spawn(crate::handlers::telegram::run_bot(state.clone(), bot));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_shutdown_ends_ticking_jobs_and_waits_for_tasks() {
+        let jobs = JobManager::new();
+        let (done_tx, done_rx) = tokio::sync::oneshot::channel();
+
+        let job = jobs.clone();
+        jobs.spawn(async move {
+            let mut ticks = tokio::time::interval(Duration::from_secs(3600));
+            while job.tick(&mut ticks).await {}
+        });
+        // Work underway is not interrupted by the shutdown
+        let task = jobs.clone();
+        jobs.spawn(async move {
+            task.stopping().await;
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            let _ = done_tx.send(());
+        });
+
+        assert!(jobs.shutdown(Duration::from_secs(5)).await);
+        assert!(done_rx.await.is_ok());
+        assert_eq!(jobs.until_shutdown(async { 1 }).await, None);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_gives_up_after_grace_period() {
+        let jobs = JobManager::new();
+        jobs.spawn(std::future::pending());
+
+        assert!(!jobs.shutdown(Duration::from_millis(20)).await);
+    }
+}
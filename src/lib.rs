@@ -1,45 +1,363 @@
 pub mod auth;
+pub mod client;
+pub mod config;
 pub mod error;
 pub mod handlers;
+pub mod jobs;
 pub mod models;
 pub mod repo;
+pub mod server;
 pub mod services;
 pub mod state;
 
 use axum::{
+    extract::{DefaultBodyLimit, Request},
+    middleware::{from_fn, from_fn_with_state},
     routing::{delete, get, patch, post, put},
     Router,
 };
-use tower_http::services::ServeDir;
+use tower_http::{services::ServeDir, trace::TraceLayer};
 
+use auth::{
+    limit_api, limit_chat, limit_guest_comments, limit_login, peer_ip, renew_session,
+    security_headers,
+};
 use state::AppState;
 
+/// Apply pending migrations. They run with foreign keys off, so a migration can rebuild
+/// a table, SQLite's way of changing a column, without the drop cascading to the rows
+/// referencing it. Violations left behind are logged once all migrations are applied.
+pub async fn run_migrations(
+    pool: &sqlx::SqlitePool,
+) -> std::result::Result<(), sqlx::migrate::MigrateError> {
+    let mut conn = pool.acquire().await?;
+    // Has no effect inside the transaction each migration runs in, so it is set here
+    sqlx::query("PRAGMA foreign_keys = OFF")
+        .execute(&mut *conn)
+        .await?;
+    let result = sqlx::migrate!("./migrations").run(&mut *conn).await;
+    sqlx::query("PRAGMA foreign_keys = ON")
+        .execute(&mut *conn)
+        .await?;
+    result?;
+
+    let violations = sqlx::query("PRAGMA foreign_key_check")
+        .fetch_all(&mut *conn)
+        .await?;
+    if !violations.is_empty() {
+        tracing::warn!(
+            count = violations.len(),
+            "Rows reference missing records, see the admin integrity check"
+        );
+    }
+    Ok(())
+}
+
 pub fn create_router(state: AppState) -> Router {
+    let limit_chat = from_fn_with_state(state.clone(), limit_chat);
+    let limit_login = from_fn_with_state(state.clone(), limit_login);
+    let limit_guest_comments = from_fn_with_state(state.clone(), limit_guest_comments);
+
     let api_routes = Router::new()
         // Auth routes
         .route("/auth/register", post(handlers::auth::register))
-        .route("/auth/login", post(handlers::auth::login))
+        .route(
+            "/auth/login",
+            post(handlers::auth::login).layer(limit_login.clone()),
+        )
         .route("/auth/logout", post(handlers::auth::logout))
+        .route("/auth/password", post(handlers::auth::change_password))
+        .route("/auth/email", post(handlers::auth::change_email))
+        .route(
+            "/auth/email/confirm",
+            post(handlers::auth::confirm_email_change),
+        )
         .route("/auth/tokens", post(handlers::auth::create_api_token))
         .route("/auth/tokens", get(handlers::auth::list_api_tokens))
         .route(
             "/auth/tokens/{token_id}",
             delete(handlers::auth::revoke_api_token),
         )
+        .route("/auth/sessions", get(handlers::auth::list_sessions))
+        .route(
+            "/auth/sessions",
+            delete(handlers::auth::revoke_other_sessions),
+        )
+        .route(
+            "/auth/sessions/{session_id}",
+            delete(handlers::auth::revoke_session),
+        )
+        // Account routes
+        .route("/users/me", delete(handlers::account::delete_account))
+        .route("/users/me/export", get(handlers::account::export_account))
+        .route(
+            "/users/me/calendar-feed",
+            post(handlers::calendar::create_calendar_feed),
+        )
+        .route(
+            "/users/me/calendar-feed",
+            delete(handlers::calendar::delete_calendar_feed),
+        )
+        .route(
+            "/users/me/inbound-email",
+            post(handlers::inbound_email::create_inbound_email_address),
+        )
+        .route(
+            "/users/me/inbound-email",
+            delete(handlers::inbound_email::delete_inbound_email_address),
+        )
+        .route(
+            "/users/me/telegram",
+            post(handlers::telegram::create_telegram_link),
+        )
+        .route(
+            "/users/me/telegram",
+            delete(handlers::telegram::delete_telegram_link),
+        )
+        // Emails posted by a mail server, authenticated by the token in the address
+        .route(
+            "/inbound/email/{token}",
+            post(handlers::inbound_email::receive_email).layer(DefaultBodyLimit::max(
+                handlers::inbound_email::MAX_INBOUND_EMAIL_SIZE,
+            )),
+        )
+        // User settings routes
+        .route("/settings", get(handlers::settings::get_settings))
+        .route("/settings", patch(handlers::settings::update_settings))
         // Board routes
         .route("/boards", post(handlers::boards::create_board))
         .route("/boards", get(handlers::boards::list_boards))
         .route("/boards/{board_id}", get(handlers::boards::get_board))
         .route("/boards/{board_id}", put(handlers::boards::update_board))
         .route("/boards/{board_id}", delete(handlers::boards::delete_board))
+        .route(
+            "/boards/{board_id}/duplicate",
+            post(handlers::boards::duplicate_board),
+        )
+        .route(
+            "/import/trello",
+            post(handlers::import::import_trello).layer(DefaultBodyLimit::max(
+                handlers::import::MAX_TRELLO_EXPORT_SIZE,
+            )),
+        )
+        .route(
+            "/boards/{board_id}/save-as-template",
+            post(handlers::board_templates::save_board_as_template),
+        )
+        .route(
+            "/board-templates",
+            get(handlers::board_templates::list_templates),
+        )
+        .route(
+            "/board-templates/{template_id}",
+            delete(handlers::board_templates::delete_template),
+        )
         .route(
             "/boards/{board_id}/permissions",
             post(handlers::boards::add_permission),
         )
+        .route(
+            "/boards/{board_id}/share-link",
+            get(handlers::share_links::get_share_link),
+        )
+        .route(
+            "/boards/{board_id}/share-link",
+            post(handlers::share_links::create_share_link),
+        )
+        .route(
+            "/boards/{board_id}/share-link",
+            delete(handlers::share_links::delete_share_link),
+        )
+        .route(
+            "/boards/{board_id}/guest-comments",
+            get(handlers::guest_comments::list_guest_comments),
+        )
+        .route(
+            "/boards/{board_id}/guest-comments/{comment_id}/approve",
+            post(handlers::guest_comments::approve_guest_comment),
+        )
+        .route(
+            "/boards/{board_id}/guest-comments/{comment_id}",
+            delete(handlers::guest_comments::delete_guest_comment),
+        )
+        .route(
+            "/share/{token}/cards/{card_id}/comments",
+            post(handlers::guest_comments::create_guest_comment)
+                .layer(limit_guest_comments.clone()),
+        )
+        .route(
+            "/boards/{board_id}/webhooks",
+            get(handlers::webhooks::list_webhooks),
+        )
+        .route(
+            "/boards/{board_id}/webhooks",
+            post(handlers::webhooks::create_webhook),
+        )
+        .route(
+            "/webhooks/{webhook_id}",
+            delete(handlers::webhooks::delete_webhook),
+        )
+        .route(
+            "/webhooks/{webhook_id}/replay",
+            post(handlers::webhooks::replay_webhook),
+        )
         .route(
             "/boards/{board_id}/permissions/{user_id}",
             delete(handlers::boards::remove_permission),
         )
+        .route(
+            "/boards/{board_id}/workspace",
+            put(handlers::workspaces::set_board_workspace),
+        )
+        // Stars, groups and order of the user's boards
+        .route(
+            "/boards/{board_id}/preferences",
+            put(handlers::board_preferences::update_preference),
+        )
+        .route(
+            "/board-preferences",
+            get(handlers::board_preferences::list_preferences),
+        )
+        .route(
+            "/board-order",
+            put(handlers::board_preferences::reorder_boards),
+        )
+        .route(
+            "/board-groups",
+            get(handlers::board_preferences::list_groups),
+        )
+        .route(
+            "/board-groups",
+            post(handlers::board_preferences::create_group),
+        )
+        .route(
+            "/board-groups/{group_id}",
+            put(handlers::board_preferences::update_group),
+        )
+        .route(
+            "/board-groups/{group_id}",
+            delete(handlers::board_preferences::delete_group),
+        )
+        // Workspace routes
+        .route("/workspaces", post(handlers::workspaces::create_workspace))
+        .route("/workspaces", get(handlers::workspaces::list_workspaces))
+        .route(
+            "/workspaces/{workspace_id}",
+            get(handlers::workspaces::get_workspace),
+        )
+        .route(
+            "/workspaces/{workspace_id}",
+            put(handlers::workspaces::update_workspace),
+        )
+        .route(
+            "/workspaces/{workspace_id}",
+            delete(handlers::workspaces::delete_workspace),
+        )
+        .route(
+            "/workspaces/{workspace_id}/boards",
+            get(handlers::workspaces::list_workspace_boards),
+        )
+        .route(
+            "/workspaces/{workspace_id}/members",
+            get(handlers::workspaces::list_members),
+        )
+        .route(
+            "/workspaces/{workspace_id}/members",
+            post(handlers::workspaces::add_member),
+        )
+        .route(
+            "/workspaces/{workspace_id}/members/{user_id}",
+            delete(handlers::workspaces::remove_member),
+        )
+        .route(
+            "/boards/{board_id}/invitations",
+            post(handlers::invitations::create_invitation),
+        )
+        .route(
+            "/boards/{board_id}/invitations",
+            get(handlers::invitations::list_invitations),
+        )
+        .route(
+            "/boards/{board_id}/invitations/{invitation_id}",
+            delete(handlers::invitations::revoke_invitation),
+        )
+        .route(
+            "/invitations/{token}/accept",
+            post(handlers::invitations::accept_invitation),
+        )
+        // Card template routes
+        .route(
+            "/boards/{board_id}/card-templates",
+            get(handlers::card_templates::list_templates),
+        )
+        .route(
+            "/boards/{board_id}/card-templates",
+            post(handlers::card_templates::create_template),
+        )
+        .route(
+            "/boards/{board_id}/card-templates/{template_id}",
+            put(handlers::card_templates::update_template),
+        )
+        .route(
+            "/boards/{board_id}/card-templates/{template_id}",
+            delete(handlers::card_templates::delete_template),
+        )
+        .route(
+            "/boards/{board_id}/cards/from-template/{template_id}",
+            post(handlers::card_templates::create_card_from_template),
+        )
+        // Saved view routes
+        .route(
+            "/boards/{board_id}/views",
+            get(handlers::saved_views::list_views),
+        )
+        .route(
+            "/boards/{board_id}/views",
+            post(handlers::saved_views::create_view),
+        )
+        .route(
+            "/boards/{board_id}/views/{view_id}",
+            put(handlers::saved_views::update_view),
+        )
+        .route(
+            "/boards/{board_id}/views/{view_id}",
+            delete(handlers::saved_views::delete_view),
+        )
+        .route(
+            "/boards/{board_id}/forecast",
+            get(handlers::forecast::get_forecast),
+        )
+        .route(
+            "/boards/{board_id}/roadmap",
+            get(handlers::roadmap::get_roadmap),
+        )
+        .route(
+            "/boards/{board_id}/timeline",
+            get(handlers::timeline::get_timeline),
+        )
+        .route(
+            "/boards/{board_id}/stats",
+            get(handlers::stats::get_board_stats),
+        )
+        .route("/boards/{board_id}/ws", get(handlers::ws::board_events))
+        .route(
+            "/boards/{board_id}/activity",
+            get(handlers::activity::get_board_activity),
+        )
+        .route(
+            "/boards/{board_id}/archived",
+            get(handlers::boards::get_archived),
+        )
+        .route(
+            "/boards/{board_id}/positions/repair",
+            post(handlers::boards::repair_positions),
+        )
+        // Trash routes
+        .route("/trash", get(handlers::trash::list_trash))
+        .route(
+            "/trash/{kind}/{item_id}/restore",
+            post(handlers::trash::restore_item),
+        )
         // Column routes
         .route(
             "/boards/{board_id}/columns",
@@ -49,6 +367,10 @@ pub fn create_router(state: AppState) -> Router {
             "/boards/{board_id}/columns",
             get(handlers::columns::list_columns),
         )
+        .route(
+            "/boards/{board_id}/status-sync",
+            post(handlers::columns::sync_board_statuses),
+        )
         .route(
             "/columns/{column_id}",
             put(handlers::columns::update_column),
@@ -61,21 +383,67 @@ pub fn create_router(state: AppState) -> Router {
             "/columns/{column_id}/move",
             patch(handlers::columns::move_column),
         )
+        .route(
+            "/columns/{column_id}/archive",
+            post(handlers::columns::archive_column),
+        )
+        .route(
+            "/columns/{column_id}/unarchive",
+            post(handlers::columns::unarchive_column),
+        )
         // Card routes
         .route(
             "/columns/{column_id}/cards",
             post(handlers::cards::create_card),
         )
+        .route(
+            "/columns/{column_id}/cards/import-text",
+            post(handlers::cards::import_cards_from_text),
+        )
         .route("/boards/{board_id}/cards", get(handlers::cards::list_cards))
+        .route(
+            "/boards/{board_id}/cards/bulk",
+            post(handlers::cards::bulk_update_cards),
+        )
         .route("/cards/{card_id}", get(handlers::cards::get_card))
+        .route("/cards/{card_id}/full", get(handlers::cards::get_card_full))
         .route("/cards/{card_id}", put(handlers::cards::update_card))
+        .route("/cards/{card_id}", patch(handlers::cards::update_card))
         .route("/cards/{card_id}", delete(handlers::cards::delete_card))
         .route("/cards/{card_id}/move", patch(handlers::cards::move_card))
+        .route("/cards/{card_id}/clone", post(handlers::cards::clone_card))
+        .route("/cards/{card_id}/lock", post(handlers::cards::lock_card))
+        .route(
+            "/cards/{card_id}/archive",
+            post(handlers::cards::archive_card),
+        )
+        .route(
+            "/cards/{card_id}/unarchive",
+            post(handlers::cards::unarchive_card),
+        )
+        .route(
+            "/cards/{card_id}/unlock",
+            post(handlers::cards::unlock_card),
+        )
+        .route(
+            "/cards/{card_id}/cover",
+            put(handlers::cards::set_card_cover),
+        )
+        .route(
+            "/cards/{card_id}/cover",
+            delete(handlers::cards::remove_card_cover),
+        )
         // Tag routes
         .route("/boards/{board_id}/tags", post(handlers::tags::create_tag))
         .route("/boards/{board_id}/tags", get(handlers::tags::list_tags))
         .route("/tags/{tag_id}", put(handlers::tags::update_tag))
         .route("/tags/{tag_id}", delete(handlers::tags::delete_tag))
+        .route("/tags/{tag_id}/link", put(handlers::tags::link_tag))
+        .route("/tags/{tag_id}/link", delete(handlers::tags::unlink_tag))
+        .route(
+            "/boards/{board_id}/tags/link-duplicates",
+            post(handlers::tags::link_duplicate_tags),
+        )
         .route(
             "/cards/{card_id}/tags/{tag_id}",
             post(handlers::tags::add_tag_to_card),
@@ -87,7 +455,15 @@ pub fn create_router(state: AppState) -> Router {
         // Chat routes (board-specific)
         .route(
             "/boards/{board_id}/chat",
-            post(handlers::chat::send_message),
+            post(handlers::chat::send_message).layer(limit_chat.clone()),
+        )
+        .route(
+            "/boards/{board_id}/chat/stream",
+            post(handlers::chat::send_message_stream).layer(limit_chat.clone()),
+        )
+        .route(
+            "/boards/{board_id}/chat/confirm",
+            post(handlers::chat::confirm_actions),
         )
         .route(
             "/boards/{board_id}/chat/history",
@@ -97,18 +473,50 @@ pub fn create_router(state: AppState) -> Router {
             "/boards/{board_id}/chat/history",
             delete(handlers::chat::clear_history),
         )
+        .route(
+            "/boards/{board_id}/chat-stats",
+            get(handlers::chat::get_stats),
+        )
         // Global chat routes (cross-board)
-        .route("/chat", post(handlers::chat::send_global_message))
+        .route(
+            "/chat",
+            post(handlers::chat::send_global_message).layer(limit_chat.clone()),
+        )
         .route("/chat/history", get(handlers::chat::get_global_history))
+        .route("/chat/export", get(handlers::chat::export_history))
+        .route(
+            "/chat/actions/{action_id}/undo",
+            post(handlers::chat::undo_action),
+        )
+        .route("/chat/sessions", post(handlers::planning::create_session))
+        .route(
+            "/chat/sessions/{session_id}",
+            get(handlers::planning::get_session),
+        )
+        .route(
+            "/chat/sessions/{session_id}",
+            delete(handlers::planning::cancel_session),
+        )
+        .route(
+            "/chat/sessions/{session_id}/turns",
+            post(handlers::planning::submit_turn),
+        )
         .route(
             "/chat/history",
             delete(handlers::chat::clear_global_history),
         )
+        // Tools for LLM agents over the Model Context Protocol
+        .route("/mcp", post(handlers::mcp::handle))
         // Inbox routes (standalone cards)
         .route("/cards", get(handlers::inbox::list_cards))
         .route("/cards", post(handlers::inbox::create_card))
+        .route("/quick-add", post(handlers::quick_add::quick_add))
         .route("/inbox/cards/{card_id}", get(handlers::inbox::get_card))
         .route("/inbox/cards/{card_id}", put(handlers::inbox::update_card))
+        .route(
+            "/inbox/cards/{card_id}",
+            patch(handlers::inbox::update_card),
+        )
         .route(
             "/inbox/cards/{card_id}",
             delete(handlers::inbox::delete_card),
@@ -142,6 +550,18 @@ pub fn create_router(state: AppState) -> Router {
             "/cards/{card_id}/comments",
             post(handlers::comments::create_comment),
         )
+        .route(
+            "/cards/{card_id}/comment-draft",
+            get(handlers::comments::get_comment_draft),
+        )
+        .route(
+            "/cards/{card_id}/comment-draft",
+            put(handlers::comments::save_comment_draft),
+        )
+        .route(
+            "/cards/{card_id}/comment-draft",
+            delete(handlers::comments::delete_comment_draft),
+        )
         .route(
             "/comments/{comment_id}",
             put(handlers::comments::update_comment),
@@ -149,23 +569,230 @@ pub fn create_router(state: AppState) -> Router {
         .route(
             "/comments/{comment_id}",
             delete(handlers::comments::delete_comment),
-        );
+        )
+        // Notification routes
+        .route(
+            "/notifications",
+            get(handlers::notifications::list_notifications),
+        )
+        .route(
+            "/notifications/unread-count",
+            get(handlers::notifications::unread_count),
+        )
+        .route(
+            "/notifications/read-all",
+            post(handlers::notifications::mark_all_read),
+        )
+        .route(
+            "/notifications/{notification_id}/read",
+            post(handlers::notifications::mark_read),
+        )
+        .route("/dashboard", get(handlers::dashboard::get_dashboard))
+        .route("/search", get(handlers::search::search))
+        // Assignee routes
+        .route(
+            "/cards/assigned",
+            get(handlers::assignees::list_assigned_cards),
+        )
+        .route(
+            "/cards/{card_id}/assignees",
+            get(handlers::assignees::list_assignees),
+        )
+        .route(
+            "/cards/{card_id}/assignees",
+            post(handlers::assignees::assign_user),
+        )
+        .route(
+            "/cards/{card_id}/assignees/{user_id}",
+            delete(handlers::assignees::unassign_user),
+        )
+        // Card link routes
+        .route("/cards/{card_id}/links", get(handlers::links::list_links))
+        .route("/cards/{card_id}/links", post(handlers::links::create_link))
+        .route(
+            "/cards/{card_id}/links/{link_id}",
+            delete(handlers::links::delete_link),
+        )
+        // Watcher routes
+        .route(
+            "/cards/{card_id}/revisions",
+            get(handlers::revisions::list_revisions),
+        )
+        .route(
+            "/cards/{card_id}/revisions/{revision_id}/restore",
+            post(handlers::revisions::restore_revision),
+        )
+        .route(
+            "/cards/{card_id}/reactions/{emoji}",
+            post(handlers::reactions::add_card_reaction),
+        )
+        .route(
+            "/cards/{card_id}/reactions/{emoji}",
+            delete(handlers::reactions::remove_card_reaction),
+        )
+        .route(
+            "/comments/{comment_id}/reactions/{emoji}",
+            post(handlers::reactions::add_comment_reaction),
+        )
+        .route(
+            "/comments/{comment_id}/reactions/{emoji}",
+            delete(handlers::reactions::remove_comment_reaction),
+        )
+        .route("/cards/{card_id}/watch", get(handlers::watchers::get_watch))
+        .route(
+            "/cards/{card_id}/watch",
+            put(handlers::watchers::watch_card),
+        )
+        .route(
+            "/cards/{card_id}/watch",
+            delete(handlers::watchers::unwatch_card),
+        )
+        // Recurrence routes
+        .route(
+            "/cards/{card_id}/recurrence",
+            get(handlers::recurrences::get_recurrence),
+        )
+        .route(
+            "/cards/{card_id}/recurrence",
+            put(handlers::recurrences::set_recurrence),
+        )
+        .route(
+            "/cards/{card_id}/recurrence",
+            delete(handlers::recurrences::delete_recurrence),
+        )
+        // Attachment routes
+        .route(
+            "/cards/{card_id}/attachments",
+            get(handlers::attachments::list_attachments),
+        )
+        .route(
+            "/cards/{card_id}/attachments",
+            post(handlers::attachments::upload_attachment).layer(DefaultBodyLimit::max(
+                handlers::attachments::MAX_ATTACHMENT_SIZE
+                    + handlers::attachments::MULTIPART_OVERHEAD,
+            )),
+        )
+        .route(
+            "/attachments/{attachment_id}",
+            get(handlers::attachments::download_attachment),
+        )
+        .route(
+            "/attachments/{attachment_id}",
+            delete(handlers::attachments::delete_attachment),
+        )
+        // Activity routes
+        .route(
+            "/cards/{card_id}/activity",
+            get(handlers::activity::get_card_activity),
+        )
+        // Maintenance routes
+        .route("/backfills", get(handlers::backfills::list_backfills))
+        // Admin routes
+        .route("/admin/integrity", get(handlers::admin::get_integrity))
+        .route(
+            "/admin/integrity/repair",
+            post(handlers::admin::repair_integrity),
+        )
+        .route("/admin/users", get(handlers::admin::list_users))
+        .route(
+            "/admin/users/{user_id}",
+            patch(handlers::admin::update_user),
+        )
+        .route("/admin/stats", get(handlers::admin::get_stats))
+        .route(
+            "/admin/boards/{board_id}/transfer",
+            post(handlers::admin::transfer_board),
+        )
+        .route("/admin/backup", get(handlers::backup::download_backup))
+        .route(
+            "/admin/restore",
+            post(handlers::backup::restore_backup)
+                .layer(DefaultBodyLimit::max(handlers::backup::MAX_RESTORE_SIZE)),
+        )
+        // API documentation
+        .route("/openapi.json", get(handlers::openapi::openapi_json))
+        .route("/docs", get(handlers::openapi::api_docs))
+        .fallback(|| async { error::AppError::NotFound("route") })
+        // Every API request counts towards the general limits, chat and login also have their own
+        .layer(from_fn_with_state(state.clone(), limit_api))
+        // Errors from extractors and unknown routes get the same body as handler errors
+        .layer(from_fn(error::json_errors));
 
     let web_routes = Router::new()
         .route("/", get(handlers::web::index))
         .route("/login", get(handlers::web::login_page))
-        .route("/login", post(handlers::web::login_submit))
+        .route(
+            "/login",
+            post(handlers::web::login_submit).layer(limit_login.clone()),
+        )
+        .route("/auth/oauth/{provider}", get(handlers::oauth::start))
+        .route(
+            "/auth/oauth/{provider}/callback",
+            get(handlers::oauth::callback).layer(limit_login.clone()),
+        )
         .route("/register", get(handlers::web::register_page))
         .route("/register", post(handlers::web::register_submit))
         .route("/logout", post(handlers::web::logout))
         .route("/boards", get(handlers::web::boards_page))
+        .route(
+            "/boards/{board_id}/star",
+            post(handlers::web::star_board_submit),
+        )
+        .route(
+            "/boards/{board_id}/group",
+            post(handlers::web::board_group_submit),
+        )
+        .route(
+            "/boards/{board_id}/move",
+            post(handlers::web::move_board_submit),
+        )
+        .route(
+            "/board-groups",
+            post(handlers::web::create_board_group_submit),
+        )
+        .route(
+            "/board-groups/{group_id}/delete",
+            post(handlers::web::delete_board_group_submit),
+        )
         .route("/boards/new", get(handlers::web::new_board_page))
         .route("/boards/new", post(handlers::web::create_board_submit))
         .route("/boards/{board_id}", get(handlers::web::board_detail))
+        .route(
+            "/boards/{board_id}/views/new",
+            post(handlers::web::save_view_submit),
+        )
+        .route(
+            "/boards/{board_id}/views/{view_id}/delete",
+            post(handlers::web::delete_view_submit),
+        )
         .route(
             "/boards/{board_id}/settings",
             get(handlers::web::board_settings),
         )
+        .route(
+            "/boards/{board_id}/settings/default-column",
+            post(handlers::web::update_default_column_submit),
+        )
+        .route(
+            "/boards/{board_id}/settings/wip-limits",
+            post(handlers::web::update_wip_limits_submit),
+        )
+        .route(
+            "/boards/{board_id}/settings/status-sync",
+            post(handlers::web::sync_statuses_submit),
+        )
+        .route(
+            "/boards/{board_id}/roadmap",
+            get(handlers::web::board_roadmap),
+        )
+        .route(
+            "/boards/{board_id}/timeline",
+            get(handlers::web::board_timeline),
+        )
+        .route(
+            "/boards/{board_id}/archived",
+            get(handlers::web::board_archived),
+        )
         .route(
             "/boards/{board_id}/columns/new",
             post(handlers::web::create_column_submit),
@@ -174,6 +801,26 @@ pub fn create_router(state: AppState) -> Router {
             "/columns/{column_id}/delete",
             post(handlers::web::delete_column_submit),
         )
+        .route(
+            "/columns/{column_id}/archive",
+            post(handlers::web::archive_column_submit),
+        )
+        .route(
+            "/columns/{column_id}/sort",
+            post(handlers::web::update_column_sort_submit),
+        )
+        .route(
+            "/columns/{column_id}/details",
+            post(handlers::web::update_column_details_submit),
+        )
+        .route(
+            "/columns/{column_id}/collapse",
+            post(handlers::web::column_collapse_submit),
+        )
+        .route(
+            "/columns/{column_id}/unarchive",
+            post(handlers::web::unarchive_column_submit),
+        )
         .route(
             "/boards/{board_id}/cards/new",
             post(handlers::web::create_card_submit),
@@ -209,10 +856,65 @@ pub fn create_router(state: AppState) -> Router {
             "/settings/llm-context",
             post(handlers::web::update_llm_context_submit),
         )
+        .route(
+            "/settings/chat-confirmation",
+            post(handlers::web::update_chat_confirmation_submit),
+        )
+        .route(
+            "/settings/llm-model",
+            post(handlers::web::update_llm_model_submit),
+        )
         .route(
             "/settings/chat-history/delete",
             post(handlers::web::delete_chat_history_submit),
         )
+        .route("/settings/theme", post(handlers::web::update_theme_submit))
+        .route("/settings/security", get(handlers::web::security_settings))
+        .route(
+            "/settings/security/sessions/delete",
+            post(handlers::web::revoke_other_sessions_submit),
+        )
+        .route(
+            "/settings/security/sessions/{session_id}/delete",
+            post(handlers::web::revoke_session_submit),
+        )
+        .route(
+            "/settings/security/tokens/{token_id}/delete",
+            post(handlers::web::revoke_token_submit),
+        )
+        .route(
+            "/settings/password",
+            post(handlers::web::change_password_submit),
+        )
+        .route("/settings/email", post(handlers::web::change_email_submit))
+        .route(
+            "/settings/calendar-feed",
+            post(handlers::web::create_calendar_feed_submit),
+        )
+        .route(
+            "/settings/calendar-feed/delete",
+            post(handlers::web::delete_calendar_feed_submit),
+        )
+        .route(
+            "/settings/inbound-email",
+            post(handlers::web::create_inbound_email_submit),
+        )
+        .route(
+            "/settings/inbound-email/delete",
+            post(handlers::web::delete_inbound_email_submit),
+        )
+        .route(
+            "/settings/telegram",
+            post(handlers::web::create_telegram_link_submit),
+        )
+        .route(
+            "/settings/telegram/delete",
+            post(handlers::web::delete_telegram_link_submit),
+        )
+        .route(
+            "/settings/email/{token}",
+            get(handlers::web::confirm_email_page),
+        )
         // Inbox web routes
         .route("/inbox", get(handlers::web::inbox_page))
         .route(
@@ -223,6 +925,36 @@ pub fn create_router(state: AppState) -> Router {
             "/inbox/cards/{card_id}/status",
             post(handlers::web::update_card_status_submit),
         )
+        // Board invitation links
+        .route("/invitations/{token}", get(handlers::web::invitation_page))
+        // Public read-only boards
+        .route("/share/{token}", get(handlers::web::shared_board_page))
+        .route(
+            "/share/{token}/cards/{card_id}/comments",
+            post(handlers::web::guest_comment_submit).layer(limit_guest_comments.clone()),
+        )
+        .route(
+            "/cards/{card_id}/public",
+            get(handlers::web::public_card_page),
+        )
+        .route(
+            "/boards/{board_id}/settings/share-link",
+            post(handlers::web::create_share_link_submit),
+        )
+        .route(
+            "/boards/{board_id}/settings/share-link/delete",
+            post(handlers::web::delete_share_link_submit),
+        )
+        .route(
+            "/boards/{board_id}/settings/public-links",
+            post(handlers::web::public_links_submit),
+        )
+        // Cards assigned to the current user
+        .route("/assigned", get(handlers::web::assigned_page))
+        .route("/dashboard", get(handlers::web::dashboard_page))
+        // Cards by date, and the same dates as a feed for calendar apps
+        .route("/calendar", get(handlers::web::calendar_page))
+        .route("/calendar.ics", get(handlers::calendar::calendar_feed))
         // Card detail with comments
         .route("/cards/{card_id}", get(handlers::web::card_detail))
         .route(
@@ -232,12 +964,57 @@ pub fn create_router(state: AppState) -> Router {
         .route(
             "/cards/{card_id}/comments/{comment_id}/delete",
             post(handlers::web::delete_comment_submit),
-        );
+        )
+        .route(
+            "/cards/{card_id}/archive",
+            post(handlers::web::archive_card_submit),
+        )
+        .route(
+            "/cards/{card_id}/unarchive",
+            post(handlers::web::unarchive_card_submit),
+        )
+        .route(
+            "/cards/{card_id}/cover",
+            post(handlers::web::card_cover_submit),
+        )
+        .route(
+            "/cards/{card_id}/reactions",
+            post(handlers::web::card_reaction_submit),
+        )
+        .route(
+            "/cards/{card_id}/comments/{comment_id}/reactions",
+            post(handlers::web::comment_reaction_submit),
+        )
+        .route(
+            "/cards/{card_id}/watch",
+            post(handlers::web::watch_card_submit),
+        )
+        .route(
+            "/cards/{card_id}/unwatch",
+            post(handlers::web::unwatch_card_submit),
+        )
+        // Theme registry, served alongside the static assets
+        .route("/static/themes.json", get(handlers::web::themes_registry));
+
+    // Access log span with the resolved client address
+    let trusted_proxies = state.trusted_proxies.clone();
+    let trace_layer = TraceLayer::new_for_http().make_span_with(move |req: &Request| {
+        let client_ip = trusted_proxies.client_ip(peer_ip(req.extensions()), req.headers());
+        tracing::info_span!(
+            "request",
+            method = %req.method(),
+            uri = %req.uri(),
+            client_ip = %client_ip,
+        )
+    });
 
     Router::new()
         .nest("/api", api_routes)
         .merge(web_routes)
         .nest_service("/static", ServeDir::new("src/static"))
+        .layer(from_fn_with_state(state.clone(), renew_session))
+        .layer(from_fn_with_state(state.clone(), security_headers))
+        .layer(trace_layer)
         .with_state(state)
 }
 
@@ -255,8 +1032,7 @@ pub mod test_utils {
             .await
             .expect("Failed to create test pool");
 
-        sqlx::migrate!("./migrations")
-            .run(&pool)
+        crate::run_migrations(&pool)
             .await
             .expect("Failed to run migrations");
 
@@ -265,7 +1041,13 @@ pub mod test_utils {
 
     pub async fn create_test_state() -> AppState {
         let pool = create_test_pool().await;
-        AppState::new(pool)
+        let mut state = AppState::new(pool, "http://localhost:3000");
+        state.email = crate::services::EmailService::capturing("http://localhost:3000");
+        state.rate_limiter = crate::auth::RateLimiter::new(crate::auth::RateLimits::disabled());
+        crate::services::backfill::run_pending(&state.backfills)
+            .await
+            .expect("Failed to run data backfills");
+        state
     }
 
     pub async fn create_test_user(state: &AppState, email: &str, name: &str) -> Uuid {
@@ -273,7 +1055,7 @@ pub mod test_utils {
         let password_hash = hash_password("testpassword123").unwrap();
         state
             .users
-            .create(id, email, &password_hash, name)
+            .create(id, email, Some(&password_hash), name)
             .await
             .unwrap();
         id
@@ -281,7 +1063,17 @@ pub mod test_utils {
 
     pub async fn create_test_session(state: &AppState, user_id: Uuid) -> String {
         let token = format!("test_token_{}", Uuid::new_v4());
-        state.sessions.create(user_id, &token).await.unwrap();
+        state
+            .sessions
+            .create(
+                user_id,
+                &token,
+                None,
+                false,
+                crate::auth::session_lifetime(false),
+            )
+            .await
+            .unwrap();
         token
     }
 }
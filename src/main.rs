@@ -1,6 +1,5 @@
-use personal_os::{create_router, state::AppState};
+use personal_os::{config::ServerConfig, create_router, state::AppState};
 use sqlx::sqlite::SqlitePoolOptions;
-use std::net::SocketAddr;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 #[tokio::main]
@@ -15,6 +14,9 @@ async fn main() -> anyhow::Result<()> {
         .with(tracing_subscriber::fmt::layer())
         .init();
 
+    let config = ServerConfig::from_env()?;
+    let tls = config.tls.as_ref().map(|files| files.load()).transpose()?;
+
     let database_url =
         std::env::var("DATABASE_URL").unwrap_or_else(|_| "sqlite:data.db?mode=rwc".to_string());
 
@@ -23,26 +25,58 @@ async fn main() -> anyhow::Result<()> {
         .connect(&database_url)
         .await?;
 
-    sqlx::migrate!("./migrations").run(&pool).await?;
+    personal_os::run_migrations(&pool).await?;
+
+    let state = AppState::new(pool, &config.app_url);
+
+    personal_os::services::backfill::run_pending(&state.backfills).await?;
 
-    let state = AppState::new(pool);
+    // Bootstrap administrators from ADMIN_EMAILS (comma-separated)
+    let admin_emails: Vec<String> = std::env::var("ADMIN_EMAILS")
+        .unwrap_or_default()
+        .split(',')
+        .map(|e| e.trim().to_string())
+        .filter(|e| !e.is_empty())
+        .collect();
+    if !admin_emails.is_empty() {
+        let promoted = state.users.promote_admins(&admin_emails).await?;
+        tracing::info!("Promoted {} user(s) to administrator", promoted);
+    }
+
+    personal_os::jobs::start(&state);
+    let jobs = state.jobs.clone();
+    let pool = state.pool.clone();
 
     let app = create_router(state);
 
-    let host: [u8; 4] = std::env::var("HOST")
-        .unwrap_or_else(|_| "127.0.0.1".to_string())
-        .parse::<std::net::Ipv4Addr>()
-        .map(|ip| ip.octets())
-        .unwrap_or([127, 0, 0, 1]);
-    let port: u16 = std::env::var("PORT")
-        .unwrap_or_else(|_| "3000".to_string())
-        .parse()
-        .unwrap_or(3000);
-    let addr = SocketAddr::from((host, port));
-    tracing::info!("Server listening on {}", addr);
-
-    let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
+    let listener = tokio::net::TcpListener::bind(config.addr).await?;
+    tracing::info!(
+        "Server listening on {}://{}, public address {}",
+        if tls.is_some() { "https" } else { "http" },
+        config.addr,
+        config.app_url
+    );
+    let server = personal_os::server::serve(listener, tls, app, jobs.clone());
+    tokio::pin!(server);
+    tokio::select! {
+        result = &mut server => result?,
+        _ = personal_os::server::shutdown_signal() => {
+            tracing::info!("Shutting down, finishing requests in progress");
+            jobs.stop();
+            match tokio::time::timeout(config.shutdown_timeout, server).await {
+                Ok(result) => result?,
+                Err(_) => tracing::warn!(
+                    "Requests still running after {}s, closing their connections",
+                    config.shutdown_timeout.as_secs()
+                ),
+            }
+        }
+    }
+
+    // Scheduled jobs end at their next wait; tasks such as chat replies get to finish
+    jobs.shutdown(config.shutdown_timeout).await;
+    pool.close().await;
+    tracing::info!("Server stopped");
 
     Ok(())
 }
@@ -0,0 +1,47 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    ApiTokenResponse, Board, Card, ChatMessageResponse, Column, Comment, Notification,
+    SavedViewResponse, Tag, UserIdentity, UserResponse, UserSettings,
+};
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct DeleteAccount {
+    /// The user's email address, typed again to confirm the deletion
+    pub confirm_email: String,
+    /// Required unless the user has no password
+    #[serde(default)]
+    pub current_password: Option<String>,
+}
+
+/// Everything stored about a user, for them to download
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct AccountExport {
+    pub exported_at: DateTime<Utc>,
+    pub user: UserResponse,
+    pub settings: UserSettings,
+    /// Providers the user signs in with
+    pub identities: Vec<UserIdentity>,
+    pub boards: Vec<BoardExport>,
+    /// Cards the user owns or created, and every card of the boards they own
+    pub cards: Vec<Card>,
+    pub comments: Vec<Comment>,
+    /// Tags not tied to a board
+    pub tags: Vec<Tag>,
+    /// Every chat message the user sent or received, on boards or not
+    pub chat_messages: Vec<ChatMessageResponse>,
+    pub api_tokens: Vec<ApiTokenResponse>,
+    pub notifications: Vec<Notification>,
+    pub saved_views: Vec<SavedViewResponse>,
+}
+
+/// A board the user can access. Columns are included for boards they own.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct BoardExport {
+    #[serde(flatten)]
+    pub board: Board,
+    pub role: String,
+    pub columns: Vec<Column>,
+}
@@ -0,0 +1,58 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// One recorded change with the actor's name
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ActivityWithActor {
+    pub id: Uuid,
+    pub board_id: Uuid,
+    pub card_id: Option<Uuid>,
+    pub actor_id: Option<Uuid>,
+    pub event: String,
+    pub entity_id: Uuid,
+    /// JSON object describing the change (created values, changed fields, move source and target)
+    pub details: Option<String>,
+    pub created_at: DateTime<Utc>,
+    /// None once the actor's account is deleted
+    pub actor_name: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Default, JsonSchema)]
+pub struct ActivityQuery {
+    /// Maximum number of entries, newest first (default 50, at most 200)
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ActivityResponse {
+    pub id: Uuid,
+    pub board_id: Uuid,
+    pub card_id: Option<Uuid>,
+    pub actor_id: Option<Uuid>,
+    pub actor_name: Option<String>,
+    pub event: String,
+    pub entity_id: Uuid,
+    pub details: Option<serde_json::Value>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<ActivityWithActor> for ActivityResponse {
+    fn from(a: ActivityWithActor) -> Self {
+        Self {
+            id: a.id,
+            board_id: a.board_id,
+            card_id: a.card_id,
+            actor_id: a.actor_id,
+            actor_name: a.actor_name,
+            event: a.event,
+            entity_id: a.entity_id,
+            details: a
+                .details
+                .as_deref()
+                .and_then(|d| serde_json::from_str(d).ok()),
+            created_at: a.created_at,
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A user as listed to administrators
+#[derive(Debug, Serialize, sqlx::FromRow, JsonSchema)]
+pub struct UserAccount {
+    pub id: Uuid,
+    pub email: String,
+    pub name: String,
+    pub is_admin: bool,
+    pub deactivated_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    /// Boards the user owns, not counting the trash
+    pub boards: i64,
+    pub chat_messages: i64,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct UpdateUserAccount {
+    pub is_admin: Option<bool>,
+    /// False deactivates the account, true reactivates it
+    pub active: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct TransferBoard {
+    pub owner_id: Uuid,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct InstanceStats {
+    pub users: UserStats,
+    /// Boards and cards not in the trash
+    pub boards: i64,
+    pub cards: i64,
+    pub chat: ChatUsage,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct UserStats {
+    pub total: i64,
+    pub active: i64,
+    pub admins: i64,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ChatUsage {
+    /// Messages sent to the assistant, each answered by one LLM request or more
+    pub messages: i64,
+    pub messages_last_30_days: i64,
+    /// Actions the assistant ran on boards and cards
+    pub actions: i64,
+}
@@ -0,0 +1,28 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A user assigned to a card, with their name joined from users table
+#[derive(Debug, Clone, Serialize, sqlx::FromRow, JsonSchema)]
+pub struct CardAssignee {
+    pub user_id: Uuid,
+    pub name: String,
+    pub email: String,
+    /// None once the assigning user's account is deleted
+    pub assigned_by: Option<Uuid>,
+    pub assigned_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct AssignCardUser {
+    pub user_id: Uuid,
+}
+
+/// A board member with the number of open cards assigned to them, on any board
+#[derive(Debug, Clone, Serialize, sqlx::FromRow, JsonSchema)]
+pub struct MemberWorkload {
+    pub user_id: Uuid,
+    pub name: String,
+    pub open_cards: i64,
+}
@@ -0,0 +1,71 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::Serialize;
+use uuid::Uuid;
+
+/// Attachment metadata, without the file content
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct Attachment {
+    pub id: Uuid,
+    pub card_id: Uuid,
+    pub uploaded_by: Uuid,
+    pub filename: String,
+    pub content_type: String,
+    pub size: i64,
+    /// File name inside the attachments directory; None when stored in the database
+    pub storage_path: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Attachment with uploader info joined from users table
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct AttachmentWithUploader {
+    pub id: Uuid,
+    pub card_id: Uuid,
+    pub uploaded_by: Uuid,
+    pub filename: String,
+    pub content_type: String,
+    pub size: i64,
+    pub created_at: DateTime<Utc>,
+    pub uploader_name: String,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct AttachmentResponse {
+    pub id: Uuid,
+    pub card_id: Uuid,
+    pub uploaded_by: Uuid,
+    pub uploader_name: String,
+    pub filename: String,
+    pub content_type: String,
+    pub size: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<AttachmentWithUploader> for AttachmentResponse {
+    fn from(a: AttachmentWithUploader) -> Self {
+        Self {
+            id: a.id,
+            card_id: a.card_id,
+            uploaded_by: a.uploaded_by,
+            uploader_name: a.uploader_name,
+            filename: a.filename,
+            content_type: a.content_type,
+            size: a.size,
+            created_at: a.created_at,
+        }
+    }
+}
+
+/// Attachment whose text matches a search, with the matching part of the text
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct AttachmentTextMatch {
+    pub id: Uuid,
+    pub card_id: Uuid,
+    pub card_title: String,
+    pub card_status: String,
+    pub filename: String,
+    /// Text around the matches, each between `SNIPPET_MATCH_START` and
+    /// `SNIPPET_MATCH_END` (see `services::attachments`)
+    pub snippet: String,
+}
@@ -0,0 +1,62 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BackfillState {
+    Pending,
+    Running,
+    Completed,
+    Failed,
+}
+
+impl std::fmt::Display for BackfillState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BackfillState::Pending => write!(f, "pending"),
+            BackfillState::Running => write!(f, "running"),
+            BackfillState::Completed => write!(f, "completed"),
+            BackfillState::Failed => write!(f, "failed"),
+        }
+    }
+}
+
+impl std::str::FromStr for BackfillState {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(BackfillState::Pending),
+            "running" => Ok(BackfillState::Running),
+            "completed" => Ok(BackfillState::Completed),
+            "failed" => Ok(BackfillState::Failed),
+            _ => Err(format!("Invalid backfill state: {}", s)),
+        }
+    }
+}
+
+/// Row of the data_backfills bookkeeping table
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct BackfillRecord {
+    pub version: i64,
+    pub name: String,
+    pub status: String,
+    pub rows_affected: i64,
+    pub error: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// Status of a registered backfill, as returned by the status endpoint
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct BackfillStatus {
+    pub version: i64,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub status: BackfillState,
+    pub rows_affected: i64,
+    pub error: Option<String>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
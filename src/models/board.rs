@@ -1,8 +1,11 @@
 use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+use super::card::deserialize_nullable;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, JsonSchema)]
 #[sqlx(type_name = "TEXT")]
 pub enum BoardRole {
     #[sqlx(rename = "owner")]
@@ -50,17 +53,105 @@ impl BoardRole {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+/// What the chat assistant does when `create_card` names a column that doesn't exist
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ColumnCreationPolicy {
+    /// Fail the action
+    #[default]
+    Reject,
+    /// Fail the action, proposing the closest existing column
+    Suggest,
+    /// Create the missing column, then the card
+    AutoCreate,
+}
+
+impl std::fmt::Display for ColumnCreationPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ColumnCreationPolicy::Reject => write!(f, "reject"),
+            ColumnCreationPolicy::Suggest => write!(f, "suggest"),
+            ColumnCreationPolicy::AutoCreate => write!(f, "auto_create"),
+        }
+    }
+}
+
+impl std::str::FromStr for ColumnCreationPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "reject" => Ok(ColumnCreationPolicy::Reject),
+            "suggest" => Ok(ColumnCreationPolicy::Suggest),
+            "auto_create" => Ok(ColumnCreationPolicy::AutoCreate),
+            _ => Err(format!("Invalid column creation policy: {}", s)),
+        }
+    }
+}
+
+/// What happens when a card is added to a column that has reached its WIP limit
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum WipLimitPolicy {
+    /// Add the card and warn that the column is over its limit
+    #[default]
+    Warn,
+    /// Refuse the card with 409 Conflict
+    Block,
+}
+
+impl std::fmt::Display for WipLimitPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WipLimitPolicy::Warn => write!(f, "warn"),
+            WipLimitPolicy::Block => write!(f, "block"),
+        }
+    }
+}
+
+impl std::str::FromStr for WipLimitPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "warn" => Ok(WipLimitPolicy::Warn),
+            "block" => Ok(WipLimitPolicy::Block),
+            _ => Err(format!("Invalid WIP limit policy: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, JsonSchema)]
 pub struct Board {
     pub id: Uuid,
     pub name: String,
     pub description: Option<String>,
     pub owner_id: Uuid,
+    pub chat_column_policy: String,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Column for cards assigned to the board without a column
+    pub default_column_id: Option<Uuid>,
+    pub wip_limit_policy: String,
+    /// Whether its public cards can be viewed without an account
+    pub allow_public_links: bool,
+    /// Workspace whose members get access to the board
+    pub workspace_id: Option<Uuid>,
+    /// Bumped on every settings change, sent as the board's ETag
+    pub version: i64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+impl Board {
+    pub fn wip_limit_policy(&self) -> WipLimitPolicy {
+        self.wip_limit_policy.parse().unwrap_or_default()
+    }
+
+    pub fn column_creation_policy(&self) -> ColumnCreationPolicy {
+        self.chat_column_policy.parse().unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, JsonSchema)]
 pub struct BoardPermission {
     pub id: Uuid,
     pub board_id: Uuid,
@@ -69,44 +160,85 @@ pub struct BoardPermission {
     pub created_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct CreateBoard {
     pub name: String,
     pub description: Option<String>,
+    /// Built-in template key (`kanban`, `sprint`, `gtd`) or id of a saved template
+    pub template: Option<String>,
+    /// Workspace to create the board in, where the user is an editor or owner
+    pub workspace_id: Option<Uuid>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct UpdateBoard {
     pub name: Option<String>,
     pub description: Option<String>,
+    pub chat_column_policy: Option<ColumnCreationPolicy>,
+    /// `null` clears the default column
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub default_column_id: Option<Option<Uuid>>,
+    pub wip_limit_policy: Option<WipLimitPolicy>,
+    /// Let anyone view the board's public cards at `/cards/{card_id}/public`. Only
+    /// owners can change it.
+    pub allow_public_links: Option<bool>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct AddBoardPermission {
     pub user_id: Uuid,
     pub role: BoardRole,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct BoardResponse {
     pub id: Uuid,
     pub name: String,
     pub description: Option<String>,
     pub owner_id: Uuid,
     pub role: String,
+    pub chat_column_policy: String,
+    pub default_column_id: Option<Uuid>,
+    pub wip_limit_policy: String,
+    pub allow_public_links: bool,
+    pub workspace_id: Option<Uuid>,
+    pub version: i64,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct BoardWithDetails {
     pub id: Uuid,
     pub name: String,
     pub description: Option<String>,
     pub owner_id: Uuid,
     pub role: String,
+    pub chat_column_policy: String,
+    pub default_column_id: Option<Uuid>,
+    pub wip_limit_policy: String,
+    pub allow_public_links: bool,
+    pub workspace_id: Option<Uuid>,
     pub columns: Vec<super::column::ColumnResponse>,
     pub tags: Vec<super::tag::TagResponse>,
+    pub version: i64,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
+
+/// Request to copy a board
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct DuplicateBoard {
+    /// Defaults to the board name followed by "(copy)"
+    pub name: Option<String>,
+    /// Copy the comments of the cards too, with their authors and dates
+    #[serde(default)]
+    pub include_comments: bool,
+}
+
+/// Columns and cards whose position a repair renumbered
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PositionRepair {
+    pub columns: u64,
+    pub cards: u64,
+}
@@ -0,0 +1,52 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::card::deserialize_nullable;
+
+/// A folder of the user's boards page
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, JsonSchema)]
+pub struct BoardGroup {
+    pub id: Uuid,
+    pub name: String,
+    pub position: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CreateBoardGroup {
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct UpdateBoardGroup {
+    pub name: Option<String>,
+    /// Groups are listed by ascending position
+    pub position: Option<i64>,
+}
+
+/// How the user arranges a board on their boards page
+#[derive(Debug, Clone, Default, Serialize, Deserialize, sqlx::FromRow, JsonSchema)]
+pub struct BoardPreference {
+    pub board_id: Uuid,
+    pub starred: bool,
+    pub group_id: Option<Uuid>,
+    /// Place in the user's order; boards without one come after, most recently
+    /// updated first
+    pub position: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct UpdateBoardPreference {
+    pub starred: Option<bool>,
+    /// One of the user's groups; `null` takes the board out of its group
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub group_id: Option<Option<Uuid>>,
+}
+
+/// The user's boards in the order they want them, first to last
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ReorderBoards {
+    pub board_ids: Vec<Uuid>,
+}
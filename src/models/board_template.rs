@@ -0,0 +1,77 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Tag created on boards made from a template
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct TemplateTag {
+    pub name: String,
+    pub color: String,
+}
+
+/// Columns and tags pre-created on a new board
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BoardLayout {
+    pub columns: Vec<String>,
+    pub tags: Vec<TemplateTag>,
+}
+
+/// Board layout saved by a user
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct BoardTemplate {
+    pub id: Uuid,
+    pub owner_id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    /// JSON array of column names
+    pub columns: String,
+    /// JSON array of tags
+    pub tags: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl BoardTemplate {
+    pub fn layout(&self) -> BoardLayout {
+        BoardLayout {
+            columns: serde_json::from_str(&self.columns).unwrap_or_default(),
+            tags: serde_json::from_str(&self.tags).unwrap_or_default(),
+        }
+    }
+}
+
+impl From<BoardTemplate> for BoardTemplateResponse {
+    fn from(template: BoardTemplate) -> Self {
+        let layout = template.layout();
+        BoardTemplateResponse {
+            id: template.id.to_string(),
+            name: template.name,
+            description: template.description,
+            builtin: false,
+            columns: layout.columns,
+            tags: layout.tags,
+            created_at: Some(template.created_at),
+        }
+    }
+}
+
+/// Built-in or user-defined template. Built-in templates are identified by a key
+/// such as `kanban`, user-defined ones by their UUID.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct BoardTemplateResponse {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub builtin: bool,
+    pub columns: Vec<String>,
+    pub tags: Vec<TemplateTag>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// Request to save a board's columns and tags as a template
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct SaveBoardTemplate {
+    /// Defaults to the board name
+    pub name: Option<String>,
+    pub description: Option<String>,
+}
@@ -0,0 +1,38 @@
+use chrono::NaiveDate;
+use schemars::JsonSchema;
+use serde::Serialize;
+use uuid::Uuid;
+
+/// Why a card shows on a calendar day
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CalendarEntryKind {
+    Due,
+    /// The day is between the card's start and end dates
+    Scheduled,
+}
+
+/// A card on a calendar day
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct CalendarEntry {
+    pub card_id: Uuid,
+    pub title: String,
+    pub status: String,
+    pub kind: CalendarEntryKind,
+}
+
+/// One cell of the month grid
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct CalendarDay {
+    pub date: NaiveDate,
+    /// False for the days of the previous and next month that fill the first and last week
+    pub in_month: bool,
+    pub entries: Vec<CalendarEntry>,
+}
+
+/// A freshly generated feed address. The token is only shown once.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct CalendarFeedResponse {
+    pub token: String,
+    pub url: String,
+}
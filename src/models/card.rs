@@ -1,13 +1,17 @@
 use chrono::{DateTime, NaiveDate, Utc};
-use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer, Serialize};
 use std::fmt;
 use std::str::FromStr;
 use uuid::Uuid;
 
 /// Card status for standalone cards
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, JsonSchema,
+)]
 #[sqlx(type_name = "TEXT")]
 pub enum CardStatus {
+    #[default]
     #[sqlx(rename = "open")]
     Open,
     #[sqlx(rename = "in_progress")]
@@ -18,12 +22,6 @@ pub enum CardStatus {
     Closed,
 }
 
-impl Default for CardStatus {
-    fn default() -> Self {
-        CardStatus::Open
-    }
-}
-
 impl fmt::Display for CardStatus {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -49,7 +47,47 @@ impl FromStr for CardStatus {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+/// How urgent a card is. Cards without a priority store NULL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, JsonSchema)]
+#[sqlx(type_name = "TEXT")]
+#[serde(rename_all = "lowercase")]
+pub enum CardPriority {
+    #[sqlx(rename = "low")]
+    Low,
+    #[sqlx(rename = "medium")]
+    Medium,
+    #[sqlx(rename = "high")]
+    High,
+    #[sqlx(rename = "urgent")]
+    Urgent,
+}
+
+impl fmt::Display for CardPriority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CardPriority::Low => write!(f, "low"),
+            CardPriority::Medium => write!(f, "medium"),
+            CardPriority::High => write!(f, "high"),
+            CardPriority::Urgent => write!(f, "urgent"),
+        }
+    }
+}
+
+impl FromStr for CardPriority {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "low" => Ok(CardPriority::Low),
+            "medium" => Ok(CardPriority::Medium),
+            "high" => Ok(CardPriority::High),
+            "urgent" => Ok(CardPriority::Urgent),
+            _ => Err(format!("Invalid priority: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, JsonSchema)]
 #[sqlx(type_name = "TEXT")]
 pub enum CardVisibility {
     #[sqlx(rename = "private")]
@@ -83,9 +121,12 @@ impl std::str::FromStr for CardVisibility {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, JsonSchema)]
 pub struct Card {
     pub id: Uuid,
+    /// Home column of the card. Deprecated: where a card sits on each of its boards is
+    /// in `card_boards`, which the database keeps in step with this column; read
+    /// placements through `CardBoardRepository` or use `Card::placed`.
     pub column_id: Option<Uuid>,
     pub title: String,
     pub body: Option<String>,
@@ -99,10 +140,21 @@ pub struct Card {
     pub created_by: Uuid,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub locked_at: Option<DateTime<Utc>>,
+    pub locked_by: Option<Uuid>,
+    /// Archived cards are hidden from board views
+    pub archived_at: Option<DateTime<Utc>>,
+    pub priority: Option<String>,
+    /// Bumped on every content change, sent as the card's ETag
+    pub version: i64,
+    /// Hex color of the card's cover, as `#rrggbb`
+    pub cover_color: Option<String>,
+    /// Image attachment of the card shown as its cover
+    pub cover_attachment_id: Option<Uuid>,
 }
 
 /// Card-board assignment for multi-board support
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, JsonSchema)]
 pub struct CardBoardAssignment {
     pub id: Uuid,
     pub card_id: Uuid,
@@ -112,7 +164,7 @@ pub struct CardBoardAssignment {
     pub created_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct CreateCard {
     pub title: String,
     pub body: Option<String>,
@@ -122,10 +174,18 @@ pub struct CreateCard {
     pub start_date: Option<NaiveDate>,
     pub end_date: Option<NaiveDate>,
     pub due_date: Option<NaiveDate>,
+    pub priority: Option<CardPriority>,
+}
+
+/// Request to create cards from a pasted newline or Markdown checkbox list
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ImportCardsText {
+    pub text: String,
+    pub visibility: Option<CardVisibility>,
 }
 
 /// Request to create a standalone (global) card
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct CreateGlobalCard {
     pub title: String,
     pub body: Option<String>,
@@ -134,32 +194,125 @@ pub struct CreateGlobalCard {
     pub start_date: Option<NaiveDate>,
     pub end_date: Option<NaiveDate>,
     pub due_date: Option<NaiveDate>,
+    pub priority: Option<CardPriority>,
 }
 
-#[derive(Debug, Deserialize)]
+/// Card update with JSON Merge Patch semantics: omitted fields are left
+/// unchanged, an explicit `null` clears a nullable field
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct UpdateCard {
     pub title: Option<String>,
-    pub body: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub body: Option<Option<String>>,
     pub visibility: Option<CardVisibility>,
     pub status: Option<CardStatus>,
-    pub start_date: Option<NaiveDate>,
-    pub end_date: Option<NaiveDate>,
-    pub due_date: Option<NaiveDate>,
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub start_date: Option<Option<NaiveDate>>,
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub end_date: Option<Option<NaiveDate>>,
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub due_date: Option<Option<NaiveDate>>,
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub priority: Option<Option<CardPriority>>,
+}
+
+/// Distinguish a present `null` (`Some(None)`) from a missing field (`None`)
+pub(crate) fn deserialize_nullable<'de, D, T>(
+    deserializer: D,
+) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+/// Cover shown on top of a card on the board, a color or an image attachment of the
+/// card
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct CardCover {
+    /// Hex color, as `#rrggbb`
+    pub color: Option<String>,
+    /// One of the card's attachments, an image
+    pub attachment_id: Option<Uuid>,
 }
 
 /// Request to update card status only
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct UpdateCardStatus {
     pub status: CardStatus,
 }
 
-#[derive(Debug, Deserialize)]
+/// Request to copy a card
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct CloneCard {
+    /// Defaults to the title of the card
+    pub title: Option<String>,
+    /// Column of the same board to put the copy in; right after the card by default
+    pub column_id: Option<Uuid>,
+    /// Copy the comments too, with their authors and dates
+    #[serde(default)]
+    pub include_comments: bool,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct MoveCard {
     pub column_id: Uuid,
     pub position: i32,
 }
 
-#[derive(Debug, Deserialize, Default)]
+/// One change of a bulk card request
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum BulkCardOperation {
+    /// Move a card to a column of the board, at the end unless a position is given
+    Move {
+        card_id: Uuid,
+        column_id: Uuid,
+        position: Option<i32>,
+    },
+    Tag {
+        card_id: Uuid,
+        tag_id: Uuid,
+    },
+    Status {
+        card_id: Uuid,
+        status: CardStatus,
+    },
+    /// Move a card to the trash
+    Delete {
+        card_id: Uuid,
+    },
+    Archive {
+        card_id: Uuid,
+    },
+}
+
+impl BulkCardOperation {
+    pub fn card_id(&self) -> Uuid {
+        match self {
+            BulkCardOperation::Move { card_id, .. }
+            | BulkCardOperation::Tag { card_id, .. }
+            | BulkCardOperation::Status { card_id, .. }
+            | BulkCardOperation::Delete { card_id }
+            | BulkCardOperation::Archive { card_id } => *card_id,
+        }
+    }
+}
+
+/// Request to change several cards of a board at once
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct BulkCardRequest {
+    pub operations: Vec<BulkCardOperation>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct BulkCardResponse {
+    /// Number of operations applied
+    pub applied: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
 pub struct CardFilter {
     pub tags: Option<Vec<Uuid>>,
     pub query: Option<String>,
@@ -171,9 +324,15 @@ pub struct CardFilter {
     pub due_date_to: Option<NaiveDate>,
     pub updated_from: Option<DateTime<Utc>>,
     pub updated_to: Option<DateTime<Utc>>,
+    /// Only cards assigned to this user
+    pub assignee: Option<Uuid>,
+    pub priority: Option<CardPriority>,
+    pub status: Option<CardStatus>,
+    /// Only cards with (true) or without (false) open blockers
+    pub blocked: Option<bool>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct CardResponse {
     pub id: Uuid,
     pub column_id: Option<Uuid>,
@@ -185,16 +344,110 @@ pub struct CardResponse {
     pub start_date: Option<NaiveDate>,
     pub end_date: Option<NaiveDate>,
     pub due_date: Option<NaiveDate>,
+    pub priority: Option<String>,
     pub owner_id: Option<Uuid>,
     pub tags: Vec<super::tag::TagResponse>,
     pub created_by: Uuid,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub locked: bool,
+    pub locked_at: Option<DateTime<Utc>>,
+    pub locked_by: Option<Uuid>,
+    pub archived_at: Option<DateTime<Utc>>,
+    pub version: i64,
+    pub cover_color: Option<String>,
+    pub cover_attachment_id: Option<Uuid>,
+    /// Whether cards blocking this one are still neither done nor closed
+    pub is_blocked: bool,
+}
+
+/// Position of a card within a column
+#[derive(Debug, Clone, Serialize, sqlx::FromRow, JsonSchema)]
+pub struct CardPosition {
+    pub id: Uuid,
+    pub position: i32,
+}
+
+/// Ordered card positions of a column touched by a move
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ColumnPositions {
+    pub column_id: Option<Uuid>,
+    pub cards: Vec<CardPosition>,
+}
+
+/// Request to create a card from one line of text, see `services::quick_add::parse`
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct QuickAddRequest {
+    pub text: String,
+    /// The user's local date, which relative dates such as `tomorrow` count from.
+    /// Defaults to the server's date (UTC).
+    pub today: Option<NaiveDate>,
+}
+
+/// A card created by quick-add, with the board it was put on
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct QuickAddResponse {
+    #[serde(flatten)]
+    pub card: CardResponse,
+    /// None for inbox cards
+    pub board_id: Option<Uuid>,
+}
+
+/// Response for card moves: the moved card plus the new order of every affected column,
+/// so clients can reconcile without refetching
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct MoveCardResponse {
+    #[serde(flatten)]
+    pub card: CardResponse,
+    pub affected_columns: Vec<ColumnPositions>,
+}
+
+/// A board a card is on, with the requesting user's role on it (if any)
+#[derive(Debug, Clone, Serialize, sqlx::FromRow, JsonSchema)]
+pub struct CardBoardAccess {
+    pub id: Uuid,
+    pub name: String,
+    pub role: Option<String>,
+}
+
+/// Everything a card detail view needs, fetched in one request
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct CardFullResponse {
+    #[serde(flatten)]
+    pub card: CardResponse,
+    pub boards: Vec<CardBoardAccess>,
+    pub assignees: Vec<super::assignee::CardAssignee>,
+    pub comments: Vec<super::comment::CommentResponse>,
+    /// Reactions to the card itself
+    pub reactions: Vec<super::reaction::ReactionCount>,
+    /// Newest changes to the card, as in its activity timeline
+    pub activity: Vec<super::activity::ActivityResponse>,
+    /// The requesting user's unsent comment, if any
+    pub comment_draft: Option<super::comment::CommentDraft>,
 }
 
 impl Card {
+    /// The card as placed on the board of an assignment
+    pub fn placed(self, assignment: &CardBoardAssignment) -> Self {
+        Card {
+            column_id: assignment.column_id,
+            position: assignment.position,
+            ..self
+        }
+    }
+
+    /// Locked cards are read-only until explicitly unlocked
+    pub fn is_locked(&self) -> bool {
+        self.locked_at.is_some()
+    }
+
+    pub fn priority(&self) -> Option<CardPriority> {
+        self.priority.as_deref().and_then(|p| p.parse().ok())
+    }
+
     pub fn into_response(self, tags: Vec<super::tag::TagResponse>) -> CardResponse {
         CardResponse {
+            locked: self.is_locked(),
             id: self.id,
             column_id: self.column_id,
             title: self.title,
@@ -205,24 +458,32 @@ impl Card {
             start_date: self.start_date,
             end_date: self.end_date,
             due_date: self.due_date,
+            priority: self.priority,
             owner_id: self.owner_id,
             tags,
             created_by: self.created_by,
             created_at: self.created_at,
             updated_at: self.updated_at,
+            locked_at: self.locked_at,
+            locked_by: self.locked_by,
+            archived_at: self.archived_at,
+            version: self.version,
+            cover_color: self.cover_color,
+            cover_attachment_id: self.cover_attachment_id,
+            is_blocked: false,
         }
     }
 }
 
 /// Request to assign a card to a board
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct AssignCardToBoard {
     pub column_id: Option<Uuid>,
     pub position: Option<i32>,
 }
 
 /// Request to move a card within a board
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct MoveCardInBoard {
     pub column_id: Option<Uuid>,
     pub position: i32,
@@ -0,0 +1,92 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::card::deserialize_nullable;
+use super::TagResponse;
+
+/// Reusable skeleton for the cards of a board
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct CardTemplate {
+    pub id: Uuid,
+    pub board_id: Uuid,
+    pub name: String,
+    /// Title of created cards, with `{title}` and `{date}` placeholders
+    pub title_pattern: String,
+    pub body: Option<String>,
+    /// JSON array of checklist item texts
+    pub checklist: String,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl CardTemplate {
+    pub fn checklist_items(&self) -> Vec<String> {
+        serde_json::from_str(&self.checklist).unwrap_or_default()
+    }
+
+    pub fn into_response(self, tags: Vec<TagResponse>) -> CardTemplateResponse {
+        CardTemplateResponse {
+            checklist: self.checklist_items(),
+            id: self.id,
+            board_id: self.board_id,
+            name: self.name,
+            title_pattern: self.title_pattern,
+            body: self.body,
+            tags,
+            created_by: self.created_by,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct CardTemplateResponse {
+    pub id: Uuid,
+    pub board_id: Uuid,
+    pub name: String,
+    pub title_pattern: String,
+    pub body: Option<String>,
+    pub checklist: Vec<String>,
+    pub tags: Vec<TagResponse>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CreateCardTemplate {
+    pub name: String,
+    pub title_pattern: String,
+    pub body: Option<String>,
+    #[serde(default)]
+    pub checklist: Vec<String>,
+    /// Tags of the board
+    #[serde(default)]
+    pub tag_ids: Vec<Uuid>,
+}
+
+/// Template update: omitted fields are left unchanged, `null` clears the body
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct UpdateCardTemplate {
+    pub name: Option<String>,
+    pub title_pattern: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub body: Option<Option<String>>,
+    pub checklist: Option<Vec<String>>,
+    /// Replaces the template's tags
+    pub tag_ids: Option<Vec<Uuid>>,
+}
+
+/// Request to create a card from a template. Everything is optional.
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct CreateCardFromTemplate {
+    /// Defaults to the board's default column, then its first column
+    pub column_id: Option<Uuid>,
+    /// Replaces `{title}` in the title pattern
+    pub title: Option<String>,
+    pub due_date: Option<NaiveDate>,
+}
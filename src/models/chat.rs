@@ -1,4 +1,5 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::str::FromStr;
@@ -16,6 +17,7 @@ pub enum ChatAction {
     MoveCardCrossBoard,
     AssignCard,
     UpdateStatus,
+    UpdateCard,
     CreateTag,
     AddTag,
     AddComment,
@@ -24,6 +26,10 @@ pub enum ChatAction {
     DeleteColumn,
     DeleteTag,
     DeleteCard,
+    BulkUpdateCards,
+    SuggestAssignments,
+    AssignUsers,
+    Undo,
     WebSearch,
     NoAction,
     Unknown,
@@ -36,6 +42,7 @@ impl ChatAction {
             self,
             ChatAction::ListCards
                 | ChatAction::ListTags
+                | ChatAction::SuggestAssignments
                 | ChatAction::WebSearch
                 | ChatAction::NoAction
         )
@@ -50,7 +57,9 @@ impl ChatAction {
                 | ChatAction::MoveCardCrossBoard
                 | ChatAction::AssignCard
                 | ChatAction::UpdateStatus
+                | ChatAction::UpdateCard
                 | ChatAction::AddComment
+                | ChatAction::Undo
                 | ChatAction::WebSearch
                 | ChatAction::NoAction
                 | ChatAction::ListCards
@@ -60,6 +69,431 @@ impl ChatAction {
     }
 }
 
+/// Actions offered to the LLM in board chat
+pub const BOARD_CHAT_ACTIONS: &[ChatAction] = &[
+    ChatAction::CreateColumn,
+    ChatAction::CreateCard,
+    ChatAction::MoveCard,
+    ChatAction::UpdateCard,
+    ChatAction::UpdateStatus,
+    ChatAction::AddComment,
+    ChatAction::CreateTag,
+    ChatAction::AddTag,
+    ChatAction::ListCards,
+    ChatAction::ListTags,
+    ChatAction::DeleteColumn,
+    ChatAction::DeleteTag,
+    ChatAction::DeleteCard,
+    ChatAction::BulkUpdateCards,
+    ChatAction::SuggestAssignments,
+    ChatAction::WebSearch,
+    ChatAction::Undo,
+];
+
+/// Actions offered to the LLM in global chat
+pub const GLOBAL_CHAT_ACTIONS: &[ChatAction] = &[
+    ChatAction::CreateBoard,
+    ChatAction::DeleteBoard,
+    ChatAction::CreateColumn,
+    ChatAction::CreateCard,
+    ChatAction::CreateInboxCard,
+    ChatAction::MoveCard,
+    ChatAction::MoveCardCrossBoard,
+    ChatAction::AssignCard,
+    ChatAction::UpdateStatus,
+    ChatAction::UpdateCard,
+    ChatAction::AddComment,
+    ChatAction::CreateTag,
+    ChatAction::AddTag,
+    ChatAction::ListCards,
+    ChatAction::ListTags,
+    ChatAction::DeleteColumn,
+    ChatAction::DeleteTag,
+    ChatAction::DeleteCard,
+    ChatAction::BulkUpdateCards,
+    ChatAction::WebSearch,
+    ChatAction::Undo,
+];
+
+/// A parameter of a chat action, as described to the LLM
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ActionParam {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub required: bool,
+}
+
+/// Board an action applies to. Only global chat names it, board chat acts on its board.
+pub const BOARD_PARAM: ActionParam = ActionParam {
+    name: "board",
+    description: "Board name",
+    required: true,
+};
+
+impl ChatAction {
+    /// What the action does, as described to the LLM
+    pub fn description(&self) -> &'static str {
+        match self {
+            ChatAction::CreateBoard => "Create a new board",
+            ChatAction::DeleteBoard => {
+                "Delete a board (only owner can delete, requires exact board name)"
+            }
+            ChatAction::CreateColumn => "Create a new column, at the end unless placed after or before another",
+            ChatAction::CreateCard => "Create a new card",
+            ChatAction::CreateInboxCard => {
+                "Create a standalone card in inbox (not on any board)"
+            }
+            ChatAction::MoveCard => "Move a card to another column (within same board)",
+            ChatAction::MoveCardCrossBoard => "Move a card between boards",
+            ChatAction::AssignCard => {
+                "Assign a card to a board (cards can be on multiple boards)"
+            }
+            ChatAction::UpdateStatus => "Update a card's status",
+            ChatAction::UpdateCard => {
+                "Change a card's title, description, status, dates or priority (only the given fields change)"
+            }
+            ChatAction::CreateTag => "Create a new tag",
+            ChatAction::AddTag => "Add a tag to a card",
+            ChatAction::AddComment => "Add a comment to a card",
+            ChatAction::ListCards => "List cards (optionally filtered by column)",
+            ChatAction::ListTags => "List all tags on the board",
+            ChatAction::DeleteColumn => "Delete a column (and all its cards)",
+            ChatAction::DeleteTag => "Delete a tag from the board",
+            ChatAction::DeleteCard => "Delete a card",
+            ChatAction::BulkUpdateCards => {
+                "Move, tag, set the status of, archive or delete all cards with a tag and/or in a column at once"
+            }
+            ChatAction::SuggestAssignments => {
+                "Suggest who to assign the unassigned open cards to, from each member's workload; the user can accept the plan"
+            }
+            ChatAction::AssignUsers => "Assign users to cards",
+            ChatAction::Undo => "Undo the last change you made in this chat",
+            ChatAction::WebSearch => {
+                "Search the internet for information (use when you need current data or external knowledge)"
+            }
+            ChatAction::NoAction => "Just respond without taking action",
+            ChatAction::Unknown => "Unknown action",
+        }
+    }
+
+    /// Parameters the action reads. [`BOARD_PARAM`] is included for actions that act on
+    /// a board named in global chat.
+    pub fn params(&self) -> &'static [ActionParam] {
+        const STATUS: ActionParam = ActionParam {
+            name: "status",
+            description: "One of open, in_progress, done, closed",
+            required: true,
+        };
+        // Cards outside boards are found among the user's own cards
+        const CARD_BOARD: ActionParam = ActionParam {
+            description: "Board the card is on; omit for your own cards",
+            required: false,
+            ..BOARD_PARAM
+        };
+        const PRIORITY: ActionParam = ActionParam {
+            name: "priority",
+            description: "One of low, medium, high, urgent",
+            required: false,
+        };
+
+        match self {
+            ChatAction::CreateBoard => &[
+                ActionParam {
+                    name: "name",
+                    description: "Board name",
+                    required: true,
+                },
+                ActionParam {
+                    name: "description",
+                    description: "Board description",
+                    required: false,
+                },
+            ],
+            ChatAction::DeleteBoard => &[ActionParam {
+                name: "board",
+                description: "Exact board name",
+                required: true,
+            }],
+            ChatAction::CreateColumn => &[
+                BOARD_PARAM,
+                ActionParam {
+                    name: "name",
+                    description: "Column name",
+                    required: true,
+                },
+                ActionParam {
+                    name: "after",
+                    description: "Existing column to place the new one after",
+                    required: false,
+                },
+                ActionParam {
+                    name: "before",
+                    description: "Existing column to place the new one before",
+                    required: false,
+                },
+            ],
+            ChatAction::CreateCard => &[
+                BOARD_PARAM,
+                ActionParam {
+                    name: "column",
+                    description: "Column to create the card in",
+                    required: true,
+                },
+                ActionParam {
+                    name: "title",
+                    description: "Card title",
+                    required: true,
+                },
+                ActionParam {
+                    name: "body",
+                    description: "Card description",
+                    required: false,
+                },
+                PRIORITY,
+            ],
+            ChatAction::CreateInboxCard => &[
+                ActionParam {
+                    name: "title",
+                    description: "Card title",
+                    required: true,
+                },
+                ActionParam {
+                    name: "body",
+                    description: "Card description",
+                    required: false,
+                },
+                ActionParam {
+                    required: false,
+                    ..STATUS
+                },
+                ActionParam {
+                    name: "due_date",
+                    description: "Due date as YYYY-MM-DD",
+                    required: false,
+                },
+                PRIORITY,
+            ],
+            ChatAction::MoveCard => &[
+                BOARD_PARAM,
+                ActionParam {
+                    name: "card_title",
+                    description: "Title of the card to move",
+                    required: true,
+                },
+                ActionParam {
+                    name: "target_column",
+                    description: "Destination column",
+                    required: true,
+                },
+            ],
+            ChatAction::MoveCardCrossBoard => &[
+                ActionParam {
+                    name: "from_board",
+                    description: "Source board",
+                    required: true,
+                },
+                ActionParam {
+                    name: "to_board",
+                    description: "Target board",
+                    required: true,
+                },
+                ActionParam {
+                    name: "card",
+                    description: "Card title",
+                    required: true,
+                },
+                ActionParam {
+                    name: "column",
+                    description: "Destination column",
+                    required: true,
+                },
+            ],
+            ChatAction::AssignCard => &[
+                ActionParam {
+                    name: "card",
+                    description: "Card title",
+                    required: true,
+                },
+                ActionParam {
+                    name: "board",
+                    description: "Board to assign the card to",
+                    required: true,
+                },
+                ActionParam {
+                    name: "column",
+                    description: "Column on that board",
+                    required: false,
+                },
+            ],
+            ChatAction::UpdateStatus => &[
+                CARD_BOARD,
+                ActionParam {
+                    name: "card",
+                    description: "Card title",
+                    required: true,
+                },
+                STATUS,
+            ],
+            ChatAction::UpdateCard => &[
+                CARD_BOARD,
+                ActionParam {
+                    name: "card",
+                    description: "Current card title",
+                    required: true,
+                },
+                ActionParam {
+                    name: "title",
+                    description: "New card title",
+                    required: false,
+                },
+                ActionParam {
+                    name: "body",
+                    description: "New card description, empty to clear it",
+                    required: false,
+                },
+                ActionParam {
+                    required: false,
+                    ..STATUS
+                },
+                ActionParam {
+                    name: "due_date",
+                    description: "Due date as YYYY-MM-DD, empty to clear it",
+                    required: false,
+                },
+                ActionParam {
+                    name: "start_date",
+                    description: "Start date as YYYY-MM-DD, empty to clear it",
+                    required: false,
+                },
+                ActionParam {
+                    name: "end_date",
+                    description: "End date as YYYY-MM-DD, empty to clear it",
+                    required: false,
+                },
+                ActionParam {
+                    description: "One of low, medium, high, urgent, empty to clear it",
+                    ..PRIORITY
+                },
+            ],
+            ChatAction::CreateTag => &[
+                BOARD_PARAM,
+                ActionParam {
+                    name: "name",
+                    description: "Tag name",
+                    required: true,
+                },
+                ActionParam {
+                    name: "color",
+                    description: "Hex color such as #3b82f6",
+                    required: false,
+                },
+            ],
+            ChatAction::AddTag => &[
+                BOARD_PARAM,
+                ActionParam {
+                    name: "card_title",
+                    description: "Card title",
+                    required: true,
+                },
+                ActionParam {
+                    name: "tag_name",
+                    description: "Tag to add",
+                    required: true,
+                },
+            ],
+            ChatAction::AddComment => &[
+                CARD_BOARD,
+                ActionParam {
+                    name: "card",
+                    description: "Card title",
+                    required: true,
+                },
+                ActionParam {
+                    name: "comment",
+                    description: "Comment text",
+                    required: true,
+                },
+            ],
+            ChatAction::ListCards => &[
+                BOARD_PARAM,
+                ActionParam {
+                    name: "column",
+                    description: "Only list the cards of this column",
+                    required: false,
+                },
+            ],
+            ChatAction::ListTags => &[BOARD_PARAM],
+            ChatAction::DeleteColumn => &[
+                BOARD_PARAM,
+                ActionParam {
+                    name: "column",
+                    description: "Column name",
+                    required: true,
+                },
+            ],
+            ChatAction::DeleteTag => &[
+                BOARD_PARAM,
+                ActionParam {
+                    name: "tag",
+                    description: "Tag name",
+                    required: true,
+                },
+            ],
+            ChatAction::DeleteCard => &[
+                BOARD_PARAM,
+                ActionParam {
+                    name: "card",
+                    description: "Card title",
+                    required: true,
+                },
+            ],
+            ChatAction::BulkUpdateCards => &[
+                BOARD_PARAM,
+                ActionParam {
+                    name: "operation",
+                    description: "One of move, tag, status, archive, delete",
+                    required: true,
+                },
+                ActionParam {
+                    name: "tag",
+                    description: "Only cards with this tag",
+                    required: false,
+                },
+                ActionParam {
+                    name: "column",
+                    description: "Only cards in this column",
+                    required: false,
+                },
+                ActionParam {
+                    name: "target_column",
+                    description: "Column to move the cards to, for move",
+                    required: false,
+                },
+                ActionParam {
+                    name: "add_tag",
+                    description: "Tag to add to the cards, for tag",
+                    required: false,
+                },
+                ActionParam {
+                    description:
+                        "Status to set, for status: one of open, in_progress, done, closed",
+                    required: false,
+                    ..STATUS
+                },
+            ],
+            ChatAction::SuggestAssignments => &[BOARD_PARAM],
+            // Only proposed by suggest_assignments, its assignments are not a string
+            ChatAction::AssignUsers => &[BOARD_PARAM],
+            ChatAction::WebSearch => &[ActionParam {
+                name: "query",
+                description: "Search query",
+                required: true,
+            }],
+            ChatAction::Undo | ChatAction::NoAction | ChatAction::Unknown => &[],
+        }
+    }
+}
+
 impl FromStr for ChatAction {
     type Err = ();
 
@@ -74,6 +508,7 @@ impl FromStr for ChatAction {
             "movecardcrossboard" => Ok(ChatAction::MoveCardCrossBoard),
             "assigncard" | "assigntoboard" => Ok(ChatAction::AssignCard),
             "updatestatus" | "setstatus" | "changestatus" => Ok(ChatAction::UpdateStatus),
+            "updatecard" | "editcard" => Ok(ChatAction::UpdateCard),
             "createtag" => Ok(ChatAction::CreateTag),
             "addtag" => Ok(ChatAction::AddTag),
             "addcomment" | "comment" => Ok(ChatAction::AddComment),
@@ -82,6 +517,10 @@ impl FromStr for ChatAction {
             "deletecolumn" => Ok(ChatAction::DeleteColumn),
             "deletetag" => Ok(ChatAction::DeleteTag),
             "deletecard" => Ok(ChatAction::DeleteCard),
+            "bulkupdatecards" | "bulkcards" => Ok(ChatAction::BulkUpdateCards),
+            "suggestassignments" => Ok(ChatAction::SuggestAssignments),
+            "assignusers" => Ok(ChatAction::AssignUsers),
+            "undo" | "undolast" => Ok(ChatAction::Undo),
             "websearch" | "search" => Ok(ChatAction::WebSearch),
             "noaction" => Ok(ChatAction::NoAction),
             _ => Ok(ChatAction::Unknown),
@@ -101,6 +540,7 @@ impl fmt::Display for ChatAction {
             ChatAction::MoveCardCrossBoard => write!(f, "move_card_cross_board"),
             ChatAction::AssignCard => write!(f, "assign_card"),
             ChatAction::UpdateStatus => write!(f, "update_status"),
+            ChatAction::UpdateCard => write!(f, "update_card"),
             ChatAction::CreateTag => write!(f, "create_tag"),
             ChatAction::AddTag => write!(f, "add_tag"),
             ChatAction::AddComment => write!(f, "add_comment"),
@@ -109,6 +549,10 @@ impl fmt::Display for ChatAction {
             ChatAction::DeleteColumn => write!(f, "delete_column"),
             ChatAction::DeleteTag => write!(f, "delete_tag"),
             ChatAction::DeleteCard => write!(f, "delete_card"),
+            ChatAction::BulkUpdateCards => write!(f, "bulk_update_cards"),
+            ChatAction::SuggestAssignments => write!(f, "suggest_assignments"),
+            ChatAction::AssignUsers => write!(f, "assign_users"),
+            ChatAction::Undo => write!(f, "undo"),
             ChatAction::WebSearch => write!(f, "web_search"),
             ChatAction::NoAction => write!(f, "no_action"),
             ChatAction::Unknown => write!(f, "unknown"),
@@ -118,7 +562,7 @@ impl fmt::Display for ChatAction {
 
 /// Persisted chat message
 /// board_id is optional to support global chat (None = global, Some = board-specific)
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, JsonSchema)]
 pub struct ChatMessage {
     pub id: Uuid,
     pub board_id: Option<Uuid>,
@@ -130,7 +574,7 @@ pub struct ChatMessage {
 }
 
 /// Chat message response for API
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct ChatMessageResponse {
     pub id: Uuid,
     pub message: String,
@@ -156,29 +600,349 @@ impl ChatMessage {
     }
 }
 
+/// Which conversation a chat export covers
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ChatExportScope {
+    #[default]
+    Global,
+    Board,
+}
+
+/// Output format of a chat export
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ChatExportFormat {
+    #[default]
+    Md,
+    Json,
+}
+
+/// Query parameters for exporting chat history
+#[derive(Debug, Deserialize, Default, JsonSchema)]
+pub struct ChatExportQuery {
+    #[serde(default)]
+    pub scope: ChatExportScope,
+    pub board_id: Option<Uuid>,
+    #[serde(default)]
+    pub format: ChatExportFormat,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+/// Exported chat transcript
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ChatExport {
+    pub scope: ChatExportScope,
+    pub board_id: Option<Uuid>,
+    pub board_name: Option<String>,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub exported_at: DateTime<Utc>,
+    pub messages: Vec<ChatMessageResponse>,
+}
+
+impl ChatExport {
+    /// Render the transcript as a readable Markdown document
+    pub fn to_markdown(&self) -> String {
+        let mut out = match &self.board_name {
+            Some(name) => format!("# Chat history: {}\n\n", name),
+            None => String::from("# Chat history: global\n\n"),
+        };
+
+        out.push_str(&format!(
+            "Exported at {}\n",
+            self.exported_at.format("%Y-%m-%d %H:%M UTC")
+        ));
+        match (self.from, self.to) {
+            (Some(from), Some(to)) => out.push_str(&format!("Range: {} to {}\n", from, to)),
+            (Some(from), None) => out.push_str(&format!("Range: from {}\n", from)),
+            (None, Some(to)) => out.push_str(&format!("Range: until {}\n", to)),
+            (None, None) => {}
+        }
+
+        if self.messages.is_empty() {
+            out.push_str("\nNo messages.\n");
+            return out;
+        }
+
+        for msg in &self.messages {
+            out.push_str(&format!(
+                "\n## {}\n\n**You:** {}\n\n**Assistant:** {}\n",
+                msg.created_at.format("%Y-%m-%d %H:%M"),
+                msg.message,
+                msg.response
+            ));
+
+            if !msg.actions_taken.is_empty() {
+                out.push_str("\nActions:\n");
+                for action in &msg.actions_taken {
+                    out.push_str(&format!(
+                        "- [{}] {}: {}\n",
+                        if action.success { "x" } else { " " },
+                        action.action,
+                        action.description
+                    ));
+                }
+            }
+        }
+
+        out
+    }
+}
+
+/// Query parameters for the board chat statistics endpoint
+#[derive(Debug, Deserialize, Default, JsonSchema)]
+pub struct ChatStatsQuery {
+    /// Number of past days to aggregate
+    pub days: Option<u32>,
+}
+
+/// Success counts for one group of chat actions
+#[derive(Debug, Clone, Default, PartialEq, Serialize, JsonSchema)]
+pub struct ActionOutcomeStats {
+    pub total: u32,
+    pub succeeded: u32,
+    pub failed: u32,
+    /// Share of successful actions, `null` when there were none
+    pub success_rate: Option<f64>,
+}
+
+/// Outcome counts for one action type
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+pub struct ActionTypeStats {
+    pub action: String,
+    #[serde(flatten)]
+    pub stats: ActionOutcomeStats,
+}
+
+/// Outcome counts for one day
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+pub struct DailyActionStats {
+    pub date: NaiveDate,
+    #[serde(flatten)]
+    pub stats: ActionOutcomeStats,
+}
+
+/// A recurring failure, with names and other specifics replaced by `*`
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+pub struct FailureReason {
+    pub action: String,
+    pub reason: String,
+    pub count: u32,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ChatStatsResponse {
+    pub board_id: Uuid,
+    pub days: u32,
+    pub messages: u32,
+    #[serde(flatten)]
+    pub overall: ActionOutcomeStats,
+    pub by_action: Vec<ActionTypeStats>,
+    pub daily: Vec<DailyActionStats>,
+    pub top_failures: Vec<FailureReason>,
+}
+
 /// Request to send a chat message
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct SendChatRequest {
     pub message: String,
+    /// Return the proposed actions for confirmation instead of executing them.
+    /// Defaults to the user's setting.
+    #[serde(default)]
+    pub require_confirmation: Option<bool>,
 }
 
 /// Response from chat endpoint
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct ChatResponse {
     pub response: String,
     pub actions_taken: Vec<ActionTaken>,
+    /// Actions waiting for confirmation, when confirmation was required
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pending: Option<PendingActionsResponse>,
+}
+
+/// Actions proposed by the assistant, waiting to be confirmed
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct PendingChatActions {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub board_id: Uuid,
+    pub chat_message_id: Uuid,
+    pub actions: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl PendingChatActions {
+    pub fn llm_actions(&self) -> Vec<LlmAction> {
+        serde_json::from_str(&self.actions).unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PendingActionsResponse {
+    pub confirmation_token: Uuid,
+    pub actions: Vec<LlmAction>,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Request to execute actions proposed in a board chat
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ConfirmChatRequest {
+    pub confirmation_token: Uuid,
 }
 
 /// An action that was executed by the chat handler
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct ActionTaken {
     pub action: String,
     pub description: String,
     pub success: bool,
+    /// Set on actions that can be undone through /api/chat/actions/{id}/undo
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<Uuid>,
+    /// How to reverse the action; saved with it rather than sent to clients
+    #[serde(skip)]
+    pub undo: Option<UndoStep>,
+    /// Pages a web search found, which the answer is based on
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sources: Vec<ChatSource>,
+    /// Comment added by an add_comment action
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<ChatComment>,
+    /// Set when the action was undone because a later action of the same request failed
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub rolled_back: bool,
+}
+
+/// A comment added through chat
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ChatComment {
+    pub id: Uuid,
+    pub card_id: Uuid,
+    pub card_title: String,
+    pub body: String,
+}
+
+/// A web page found by a chat web search
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ChatSource {
+    pub title: String,
+    pub url: String,
+}
+
+/// What it takes to reverse a chat action
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum UndoStep {
+    /// Move a card the action created to the trash
+    TrashCard {
+        card_id: Uuid,
+    },
+    /// Move a column the action created, and its cards, to the trash
+    TrashColumn {
+        column_id: Uuid,
+    },
+    /// Move a board the action created to the trash
+    TrashBoard {
+        board_id: Uuid,
+    },
+    RestoreCard {
+        card_id: Uuid,
+    },
+    RestoreColumn {
+        column_id: Uuid,
+    },
+    RestoreBoard {
+        board_id: Uuid,
+    },
+    /// Put a moved card back where it was
+    MoveCard {
+        card_id: Uuid,
+        column_id: Uuid,
+        position: i32,
+    },
+    DeleteTag {
+        tag_id: Uuid,
+    },
+    /// Create a deleted tag again and put it back on its cards
+    RecreateTag {
+        board_id: Uuid,
+        name: String,
+        color: String,
+        linked_tag_id: Option<Uuid>,
+        card_ids: Vec<Uuid>,
+    },
+    RemoveTag {
+        card_id: Uuid,
+        tag_id: Uuid,
+    },
+    SetStatus {
+        card_id: Uuid,
+        status: String,
+    },
+    /// Put back the fields an update_card action changed
+    RestoreCardFields {
+        card_id: Uuid,
+        title: String,
+        body: Option<String>,
+        status: String,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+        due_date: Option<NaiveDate>,
+        /// Missing from undo steps recorded before cards had a priority
+        #[serde(default)]
+        priority: Option<String>,
+    },
+    DeleteComment {
+        comment_id: Uuid,
+    },
+    UnassignCard {
+        card_id: Uuid,
+        board_id: Uuid,
+    },
+    UnarchiveCard {
+        card_id: Uuid,
+    },
+    /// Take a user assigned by the action off a card
+    UnassignUser {
+        card_id: Uuid,
+        user_id: Uuid,
+    },
+    /// Undo several changes made by one action, last first
+    Batch {
+        steps: Vec<UndoStep>,
+    },
+    /// Bring back the card a cross-board move replaced with `copy_id`
+    UndoCrossBoardMove {
+        card_id: Uuid,
+        copy_id: Uuid,
+    },
+}
+
+/// A chat action that can be undone
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct UndoableAction {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub board_id: Option<Uuid>,
+    pub action: String,
+    pub description: String,
+    pub undo: String,
+    pub undone_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl UndoableAction {
+    pub fn undo_step(&self) -> Option<UndoStep> {
+        serde_json::from_str(&self.undo).ok()
+    }
 }
 
 /// Parsed action from LLM response
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct LlmAction {
     pub action: String,
     #[serde(default)]
@@ -187,7 +951,7 @@ pub struct LlmAction {
 }
 
 /// Multiple actions response from LLM
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct LlmResponse {
     #[serde(default)]
     pub actions: Vec<LlmAction>,
@@ -1,8 +1,67 @@
 use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+use super::card::{deserialize_nullable, CardStatus};
+
+/// How a column orders its cards
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ColumnSortPolicy {
+    /// By position, as cards were placed
+    #[default]
+    Manual,
+    /// Earliest due date first, cards without one last
+    DueDate,
+    /// Most recently updated first
+    UpdatedAt,
+    /// Most urgent first, cards without a priority last
+    Priority,
+}
+
+impl ColumnSortPolicy {
+    /// Whether cards are ordered by hand and can be moved within the column
+    pub fn is_manual(&self) -> bool {
+        matches!(self, ColumnSortPolicy::Manual)
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ColumnSortPolicy::Manual => "Manual order",
+            ColumnSortPolicy::DueDate => "Sorted by due date",
+            ColumnSortPolicy::UpdatedAt => "Sorted by last update",
+            ColumnSortPolicy::Priority => "Sorted by priority",
+        }
+    }
+}
+
+impl std::fmt::Display for ColumnSortPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ColumnSortPolicy::Manual => write!(f, "manual"),
+            ColumnSortPolicy::DueDate => write!(f, "due_date"),
+            ColumnSortPolicy::UpdatedAt => write!(f, "updated_at"),
+            ColumnSortPolicy::Priority => write!(f, "priority"),
+        }
+    }
+}
+
+impl std::str::FromStr for ColumnSortPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "manual" => Ok(ColumnSortPolicy::Manual),
+            "due_date" => Ok(ColumnSortPolicy::DueDate),
+            "updated_at" => Ok(ColumnSortPolicy::UpdatedAt),
+            "priority" => Ok(ColumnSortPolicy::Priority),
+            _ => Err(format!("Invalid sort policy: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, JsonSchema)]
 pub struct Column {
     pub id: Uuid,
     pub board_id: Uuid,
@@ -10,25 +69,76 @@ pub struct Column {
     pub position: i32,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Archived columns and their cards are hidden from board views
+    pub archived_at: Option<DateTime<Utc>>,
+    /// Most cards the column should hold, no limit when `None`
+    pub wip_limit: Option<i32>,
+    pub sort_policy: String,
+    /// Bumped on every settings change, sent as the column's ETag
+    pub version: i64,
+    /// Hex color shown along the column header, as `#rrggbb`
+    pub color: Option<String>,
+    /// What belongs in the column
+    pub description: Option<String>,
+    /// Status cards take when they are moved into the column
+    pub card_status: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+impl Column {
+    pub fn sort_policy(&self) -> ColumnSortPolicy {
+        self.sort_policy.parse().unwrap_or_default()
+    }
+
+    pub fn card_status(&self) -> Option<CardStatus> {
+        self.card_status.as_deref().and_then(|s| s.parse().ok())
+    }
+}
+
+/// Status cards moved into a new column take, for columns named like a status
+pub fn default_card_status(column_name: &str) -> Option<CardStatus> {
+    match column_name.trim().to_lowercase().as_str() {
+        "done" => Some(CardStatus::Done),
+        "in progress" | "doing" => Some(CardStatus::InProgress),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct CreateColumn {
     pub name: String,
     pub position: Option<i32>,
+    /// Hex color, as `#rrggbb`
+    pub color: Option<String>,
+    pub description: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct UpdateColumn {
     pub name: Option<String>,
+    /// `null` removes the limit
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub wip_limit: Option<Option<i32>>,
+    pub sort_policy: Option<ColumnSortPolicy>,
+    /// Hex color, as `#rrggbb`; `null` removes it
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub color: Option<Option<String>>,
+    /// `null` removes the description
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub description: Option<Option<String>>,
+    /// Collapse or expand the column on the requesting user's view of the board only
+    pub collapsed: Option<bool>,
+    /// Status cards take when they are moved into the column; `null` leaves their
+    /// status alone
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub card_status: Option<Option<CardStatus>>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct MoveColumn {
     pub position: i32,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct ColumnResponse {
     pub id: Uuid,
     pub board_id: Uuid,
@@ -37,6 +147,31 @@ pub struct ColumnResponse {
     pub cards: Vec<super::card::CardResponse>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub archived_at: Option<DateTime<Utc>>,
+    pub wip_limit: Option<i32>,
+    pub sort_policy: String,
+    pub version: i64,
+    pub color: Option<String>,
+    pub description: Option<String>,
+    pub card_status: Option<String>,
+    /// Whether the requesting user has collapsed the column
+    pub collapsed: bool,
+}
+
+/// Outcome of giving the cards of a board the status their column maps to
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct StatusSyncResult {
+    /// Cards whose status changed
+    pub updated: u64,
+}
+
+/// A column with the number of cards in it, for summaries spanning several boards
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct BoardColumnCardCount {
+    pub board_id: Uuid,
+    pub column_id: Uuid,
+    pub name: String,
+    pub card_count: i64,
 }
 
 impl From<Column> for ColumnResponse {
@@ -49,6 +184,23 @@ impl From<Column> for ColumnResponse {
             cards: vec![],
             created_at: col.created_at,
             updated_at: col.updated_at,
+            archived_at: col.archived_at,
+            wip_limit: col.wip_limit,
+            sort_policy: col.sort_policy,
+            version: col.version,
+            color: col.color,
+            description: col.description,
+            card_status: col.card_status,
+            collapsed: false,
         }
     }
 }
+
+/// Archived columns and cards of a board
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ArchivedItems {
+    /// Archived columns, with the cards they hold
+    pub columns: Vec<ColumnResponse>,
+    /// Cards archived on their own
+    pub cards: Vec<super::card::CardResponse>,
+}
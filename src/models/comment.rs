@@ -1,8 +1,9 @@
 use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, JsonSchema)]
 pub struct Comment {
     pub id: Uuid,
     pub card_id: Uuid,
@@ -12,17 +13,32 @@ pub struct Comment {
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct CreateComment {
     pub body: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct UpdateComment {
     pub body: String,
 }
 
-#[derive(Debug, Serialize)]
+/// Unsent comment text, autosaved per user and card
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, JsonSchema)]
+pub struct CommentDraft {
+    pub card_id: Uuid,
+    pub user_id: Uuid,
+    pub body: String,
+    pub updated_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SaveCommentDraft {
+    pub body: String,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct CommentResponse {
     pub id: Uuid,
     pub card_id: Uuid,
@@ -31,6 +47,7 @@ pub struct CommentResponse {
     pub body: String,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub reactions: Vec<super::reaction::ReactionCount>,
 }
 
 /// Comment with author info joined from users table
@@ -55,6 +72,15 @@ impl From<CommentWithAuthor> for CommentResponse {
             body: c.body,
             created_at: c.created_at,
             updated_at: c.updated_at,
+            reactions: Vec::new(),
         }
     }
 }
+
+/// A user mentioned, or who can be mentioned, in a comment
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct MentionedUser {
+    pub id: Uuid,
+    pub name: String,
+    pub email: String,
+}
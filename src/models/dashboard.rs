@@ -0,0 +1,44 @@
+use chrono::NaiveDate;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{Card, CardResponse};
+
+/// A card with the board its column is on
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct DashboardCard {
+    #[sqlx(flatten)]
+    pub card: Card,
+    pub board_id: Option<Uuid>,
+    pub board_name: Option<String>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct DashboardCardResponse {
+    #[serde(flatten)]
+    pub card: CardResponse,
+    /// None for inbox cards
+    pub board_id: Option<Uuid>,
+    pub board_name: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct DashboardQuery {
+    /// Defaults to the server's date (UTC).
+    pub today: Option<NaiveDate>,
+}
+
+/// The cards of every board the user can access that need their attention
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct DashboardResponse {
+    pub today: NaiveDate,
+    /// Unfinished cards assigned to the user, by due date
+    pub assigned: Vec<DashboardCardResponse>,
+    /// Unfinished cards due from today to Sunday
+    pub due_this_week: Vec<DashboardCardResponse>,
+    /// Unfinished cards due before today
+    pub overdue: Vec<DashboardCardResponse>,
+    /// Cards changed in the last week, most recent first
+    pub recently_updated: Vec<DashboardCardResponse>,
+}
@@ -0,0 +1,141 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::fmt;
+use uuid::Uuid;
+
+/// What changed on a board
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BoardEventKind {
+    BoardUpdated,
+    BoardDeleted,
+    ColumnCreated,
+    ColumnUpdated,
+    ColumnDeleted,
+    ColumnMoved,
+    CardCreated,
+    CardUpdated,
+    CardDeleted,
+    CardMoved,
+    /// An existing card was assigned to the board
+    CardAdded,
+    /// A card was unassigned from the board without being deleted
+    CardRemoved,
+    CardTagged,
+    CardUntagged,
+    TagCreated,
+    TagUpdated,
+    TagDeleted,
+    CommentCreated,
+    CommentUpdated,
+    CommentDeleted,
+    AttachmentCreated,
+    AttachmentDeleted,
+    AssigneeAdded,
+    AssigneeRemoved,
+    LinkAdded,
+    LinkRemoved,
+    /// A user was given a role on the board
+    MemberAdded,
+    /// A user's role on the board was taken away
+    MemberRemoved,
+    /// A user was given another role on the board
+    MemberRoleChanged,
+    /// The board chat ran one or more actions; clients should reload the board
+    ChatActions,
+}
+
+impl BoardEventKind {
+    /// Events whose `id` is a card
+    pub fn is_card_event(&self) -> bool {
+        matches!(
+            self,
+            BoardEventKind::CardCreated
+                | BoardEventKind::CardUpdated
+                | BoardEventKind::CardDeleted
+                | BoardEventKind::CardMoved
+                | BoardEventKind::CardAdded
+                | BoardEventKind::CardRemoved
+        )
+    }
+}
+
+impl fmt::Display for BoardEventKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            BoardEventKind::BoardUpdated => "board_updated",
+            BoardEventKind::BoardDeleted => "board_deleted",
+            BoardEventKind::ColumnCreated => "column_created",
+            BoardEventKind::ColumnUpdated => "column_updated",
+            BoardEventKind::ColumnDeleted => "column_deleted",
+            BoardEventKind::ColumnMoved => "column_moved",
+            BoardEventKind::CardCreated => "card_created",
+            BoardEventKind::CardUpdated => "card_updated",
+            BoardEventKind::CardDeleted => "card_deleted",
+            BoardEventKind::CardMoved => "card_moved",
+            BoardEventKind::CardAdded => "card_added",
+            BoardEventKind::CardRemoved => "card_removed",
+            BoardEventKind::CardTagged => "card_tagged",
+            BoardEventKind::CardUntagged => "card_untagged",
+            BoardEventKind::TagCreated => "tag_created",
+            BoardEventKind::TagUpdated => "tag_updated",
+            BoardEventKind::TagDeleted => "tag_deleted",
+            BoardEventKind::CommentCreated => "comment_created",
+            BoardEventKind::CommentUpdated => "comment_updated",
+            BoardEventKind::CommentDeleted => "comment_deleted",
+            BoardEventKind::AttachmentCreated => "attachment_created",
+            BoardEventKind::AttachmentDeleted => "attachment_deleted",
+            BoardEventKind::AssigneeAdded => "assignee_added",
+            BoardEventKind::AssigneeRemoved => "assignee_removed",
+            BoardEventKind::LinkAdded => "link_added",
+            BoardEventKind::LinkRemoved => "link_removed",
+            BoardEventKind::MemberAdded => "member_added",
+            BoardEventKind::MemberRemoved => "member_removed",
+            BoardEventKind::MemberRoleChanged => "member_role_changed",
+            BoardEventKind::ChatActions => "chat_actions",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Change notification pushed to clients subscribed to a board.
+/// Events carry ids only; clients fetch the current state through the API.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct BoardEvent {
+    pub event: BoardEventKind,
+    pub board_id: Uuid,
+    /// The column, card, tag or comment that changed (the board itself for board events)
+    pub id: Uuid,
+    /// Card the change belongs to, for tag assignments, comments and attachments
+    pub card_id: Option<Uuid>,
+    pub actor_id: Uuid,
+    pub at: DateTime<Utc>,
+    /// What changed, kept in the activity log but not pushed to subscribers
+    #[serde(skip)]
+    pub details: Option<serde_json::Value>,
+}
+
+impl BoardEvent {
+    pub fn new(event: BoardEventKind, board_id: Uuid, id: Uuid, actor_id: Uuid) -> Self {
+        Self {
+            event,
+            board_id,
+            id,
+            card_id: None,
+            actor_id,
+            at: Utc::now(),
+            details: None,
+        }
+    }
+
+    pub fn with_card(mut self, card_id: Uuid) -> Self {
+        self.card_id = Some(card_id);
+        self
+    }
+
+    pub fn with_details(mut self, details: serde_json::Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+}
@@ -0,0 +1,31 @@
+use chrono::NaiveDate;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Query parameters for the board forecast endpoint
+#[derive(Debug, Deserialize, Default, JsonSchema)]
+pub struct ForecastQuery {
+    /// Number of cards to forecast (defaults to the open cards on the board)
+    pub cards: Option<u32>,
+    /// Number of past weeks used as throughput history
+    pub weeks: Option<u32>,
+}
+
+/// A single percentile estimate of the completion date
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ForecastPercentile {
+    pub percentile: u8,
+    pub weeks: u32,
+    pub date: NaiveDate,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ForecastResponse {
+    pub board_id: Uuid,
+    pub remaining_cards: u32,
+    pub history_weeks: u32,
+    pub weekly_throughput: Vec<u32>,
+    pub simulations: u32,
+    pub percentiles: Vec<ForecastPercentile>,
+}
@@ -0,0 +1,37 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Comment left by a visitor of a board's share link, with the title of its card
+#[derive(Debug, Clone, Serialize, sqlx::FromRow, JsonSchema)]
+pub struct GuestComment {
+    pub id: Uuid,
+    pub card_id: Uuid,
+    pub card_title: String,
+    pub board_id: Uuid,
+    /// Name the guest gave, not checked against any account
+    pub name: String,
+    pub body: String,
+    /// None while the comment awaits moderation
+    pub approved_at: Option<DateTime<Utc>>,
+    pub approved_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CreateGuestComment {
+    pub name: String,
+    pub body: String,
+    /// Hidden from people on the share page; comments that fill it in are dropped
+    #[serde(default)]
+    pub website: String,
+}
+
+/// Query parameters for listing a board's guest comments
+#[derive(Debug, Deserialize, Default, JsonSchema)]
+pub struct ListGuestCommentsQuery {
+    /// Only list comments awaiting moderation
+    #[serde(default)]
+    pub pending: bool,
+}
@@ -0,0 +1,18 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// An account at an OAuth provider that a user signs in with
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, JsonSchema)]
+pub struct UserIdentity {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    /// Name of the provider, as in its login route
+    pub provider: String,
+    /// The provider's stable id for the account
+    pub subject: String,
+    /// Email address the provider reported when the identity was linked
+    pub email: String,
+    pub created_at: DateTime<Utc>,
+}
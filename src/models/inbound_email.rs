@@ -0,0 +1,22 @@
+use schemars::JsonSchema;
+use serde::Serialize;
+use uuid::Uuid;
+
+/// A freshly generated inbound email address. The token is only shown once.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct InboundEmailAddressResponse {
+    pub token: String,
+    /// Where the mail server or forwarding service posts the raw emails
+    pub url: String,
+}
+
+/// The inbox card created from a received email
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct InboundEmailResponse {
+    pub card_id: Uuid,
+    pub title: String,
+    /// Names of the files attached to the card
+    pub attachments: Vec<String>,
+    /// Names of the files left out for being larger than the attachment limit
+    pub skipped_attachments: Vec<String>,
+}
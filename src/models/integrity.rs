@@ -0,0 +1,33 @@
+use schemars::JsonSchema;
+use serde::Serialize;
+
+/// Outcome of one integrity check
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct IntegrityIssue {
+    pub check: &'static str,
+    pub description: &'static str,
+    /// Number of orphaned or dangling rows found
+    pub found: u64,
+    /// Number of rows deleted or detached by a repair run
+    pub fixed: u64,
+}
+
+/// Report returned by the integrity audit and repair endpoints
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct IntegrityReport {
+    pub repaired: bool,
+    pub total_found: u64,
+    pub total_fixed: u64,
+    pub checks: Vec<IntegrityIssue>,
+}
+
+impl IntegrityReport {
+    pub fn new(repaired: bool, checks: Vec<IntegrityIssue>) -> Self {
+        Self {
+            repaired,
+            total_found: checks.iter().map(|c| c.found).sum(),
+            total_fixed: checks.iter().map(|c| c.fixed).sum(),
+            checks,
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::BoardRole;
+
+/// Pending invitation to a board. The token that accepts it is only sent by email.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow, JsonSchema)]
+pub struct BoardInvitation {
+    pub id: Uuid,
+    pub board_id: Uuid,
+    pub email: String,
+    pub role: String,
+    #[serde(skip_serializing)]
+    pub token_hash: String,
+    pub invited_by: Option<Uuid>,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CreateBoardInvitation {
+    pub email: String,
+    pub role: BoardRole,
+}
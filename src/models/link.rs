@@ -0,0 +1,137 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use uuid::Uuid;
+
+/// How one card relates to another, read from the first card
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CardLinkType {
+    /// The other card cannot be done before this one
+    Blocks,
+    /// This card cannot be done before the other one
+    BlockedBy,
+    RelatesTo,
+    /// This card repeats the other one
+    DuplicateOf,
+    /// The other card repeats this one
+    DuplicatedBy,
+}
+
+impl CardLinkType {
+    /// The same relation read from the other card
+    pub fn inverse(&self) -> Self {
+        match self {
+            CardLinkType::Blocks => CardLinkType::BlockedBy,
+            CardLinkType::BlockedBy => CardLinkType::Blocks,
+            CardLinkType::RelatesTo => CardLinkType::RelatesTo,
+            CardLinkType::DuplicateOf => CardLinkType::DuplicatedBy,
+            CardLinkType::DuplicatedBy => CardLinkType::DuplicateOf,
+        }
+    }
+
+    /// Whether links of this type are stored from the other card
+    pub fn is_stored_reversed(&self) -> bool {
+        matches!(self, CardLinkType::BlockedBy | CardLinkType::DuplicatedBy)
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            CardLinkType::Blocks => "Blocks",
+            CardLinkType::BlockedBy => "Blocked by",
+            CardLinkType::RelatesTo => "Relates to",
+            CardLinkType::DuplicateOf => "Duplicate of",
+            CardLinkType::DuplicatedBy => "Duplicated by",
+        }
+    }
+}
+
+impl fmt::Display for CardLinkType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CardLinkType::Blocks => write!(f, "blocks"),
+            CardLinkType::BlockedBy => write!(f, "blocked_by"),
+            CardLinkType::RelatesTo => write!(f, "relates_to"),
+            CardLinkType::DuplicateOf => write!(f, "duplicate_of"),
+            CardLinkType::DuplicatedBy => write!(f, "duplicated_by"),
+        }
+    }
+}
+
+impl FromStr for CardLinkType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace('-', "_").as_str() {
+            "blocks" => Ok(CardLinkType::Blocks),
+            "blocked_by" => Ok(CardLinkType::BlockedBy),
+            "relates_to" => Ok(CardLinkType::RelatesTo),
+            "duplicate_of" => Ok(CardLinkType::DuplicateOf),
+            "duplicated_by" => Ok(CardLinkType::DuplicatedBy),
+            _ => Err(format!("Invalid link type: {}", s)),
+        }
+    }
+}
+
+/// A stored link, always in its forward direction
+#[derive(Debug, Clone, Serialize, sqlx::FromRow, JsonSchema)]
+pub struct CardLinkRow {
+    pub id: Uuid,
+    pub source_card_id: Uuid,
+    pub target_card_id: Uuid,
+    pub link_type: String,
+    /// None once the linking user's account is deleted
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A link seen from one of its cards, with the other card's summary
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct CardLink {
+    pub id: Uuid,
+    pub link_type: CardLinkType,
+    pub card: LinkedCard,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// The card at the other end of a link
+#[derive(Debug, Clone, Serialize, sqlx::FromRow, JsonSchema)]
+pub struct LinkedCard {
+    pub id: Uuid,
+    pub title: String,
+    pub status: String,
+    pub column_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CreateCardLink {
+    /// The other card
+    pub card_id: Uuid,
+    pub link_type: CardLinkType,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_link_type_inverse_and_parse() {
+        for link_type in [
+            CardLinkType::Blocks,
+            CardLinkType::BlockedBy,
+            CardLinkType::RelatesTo,
+            CardLinkType::DuplicateOf,
+            CardLinkType::DuplicatedBy,
+        ] {
+            assert_eq!(link_type.inverse().inverse(), link_type);
+            assert_eq!(link_type.to_string().parse::<CardLinkType>(), Ok(link_type));
+            if link_type.is_stored_reversed() {
+                assert!(!link_type.inverse().is_stored_reversed());
+            }
+        }
+        assert_eq!("blocked-by".parse(), Ok(CardLinkType::BlockedBy));
+    }
+}
@@ -1,19 +1,91 @@
+pub mod account;
+pub mod activity;
+pub mod admin;
+pub mod assignee;
+pub mod attachment;
+pub mod backfill;
 pub mod board;
+pub mod board_preference;
+pub mod board_template;
+pub mod calendar;
 pub mod card;
+pub mod card_template;
 pub mod chat;
 pub mod column;
 pub mod comment;
+pub mod dashboard;
+pub mod event;
+pub mod forecast;
+pub mod guest_comment;
+pub mod identity;
+pub mod inbound_email;
+pub mod integrity;
+pub mod invitation;
+pub mod link;
+pub mod notification;
+pub mod pagination;
+pub mod planning;
+pub mod reaction;
+pub mod recurrence;
+pub mod revision;
+pub mod roadmap;
+pub mod saved_view;
+pub mod search;
 pub mod session;
+pub mod share_link;
+pub mod stats;
 pub mod tag;
+pub mod telegram;
+pub mod theme;
+pub mod timeline;
 pub mod token;
+pub mod trash;
 pub mod user;
+pub mod webhook;
+pub mod workspace;
 
+pub use account::*;
+pub use activity::*;
+pub use admin::*;
+pub use assignee::*;
+pub use attachment::*;
+pub use backfill::*;
 pub use board::*;
+pub use board_preference::*;
+pub use board_template::*;
+pub use calendar::*;
 pub use card::*;
+pub use card_template::*;
 pub use chat::*;
 pub use column::*;
 pub use comment::*;
+pub use dashboard::*;
+pub use event::*;
+pub use forecast::*;
+pub use guest_comment::*;
+pub use identity::*;
+pub use inbound_email::*;
+pub use integrity::*;
+pub use invitation::*;
+pub use link::*;
+pub use notification::*;
+pub use pagination::*;
+pub use planning::*;
+pub use reaction::*;
+pub use recurrence::*;
+pub use revision::*;
+pub use roadmap::*;
+pub use saved_view::*;
+pub use search::*;
 pub use session::*;
+pub use share_link::*;
+pub use stats::*;
 pub use tag::*;
+pub use telegram::*;
+pub use theme::*;
+pub use timeline::*;
 pub use token::*;
+pub use trash::*;
 pub use user::*;
+pub use webhook::*;
+pub use workspace::*;
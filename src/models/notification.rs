@@ -0,0 +1,110 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use uuid::Uuid;
+
+/// Why a user is being notified about a card or board
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    /// The card is due today or tomorrow
+    DueSoon,
+    /// The card's due date has passed
+    Overdue,
+    /// A watched card was moved to another column
+    CardMoved,
+    /// Someone commented on a watched card
+    CardCommented,
+    /// The due date of a watched card was changed or removed
+    DueDateChanged,
+    /// Someone mentioned the user in a comment
+    Mentioned,
+    /// The last open card blocking a watched card was done or closed
+    CardUnblocked,
+    /// Someone gave the user a role on a board
+    AddedToBoard,
+    /// Someone took the user's role on a board away
+    RemovedFromBoard,
+    /// Someone gave the user another role on a board
+    BoardRoleChanged,
+}
+
+impl fmt::Display for NotificationKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NotificationKind::DueSoon => write!(f, "due_soon"),
+            NotificationKind::Overdue => write!(f, "overdue"),
+            NotificationKind::CardMoved => write!(f, "card_moved"),
+            NotificationKind::CardCommented => write!(f, "card_commented"),
+            NotificationKind::DueDateChanged => write!(f, "due_date_changed"),
+            NotificationKind::Mentioned => write!(f, "mentioned"),
+            NotificationKind::CardUnblocked => write!(f, "card_unblocked"),
+            NotificationKind::AddedToBoard => write!(f, "added_to_board"),
+            NotificationKind::RemovedFromBoard => write!(f, "removed_from_board"),
+            NotificationKind::BoardRoleChanged => write!(f, "board_role_changed"),
+        }
+    }
+}
+
+impl FromStr for NotificationKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "due_soon" => Ok(NotificationKind::DueSoon),
+            "overdue" => Ok(NotificationKind::Overdue),
+            "card_moved" => Ok(NotificationKind::CardMoved),
+            "card_commented" => Ok(NotificationKind::CardCommented),
+            "due_date_changed" => Ok(NotificationKind::DueDateChanged),
+            "mentioned" => Ok(NotificationKind::Mentioned),
+            "card_unblocked" => Ok(NotificationKind::CardUnblocked),
+            "added_to_board" => Ok(NotificationKind::AddedToBoard),
+            "removed_from_board" => Ok(NotificationKind::RemovedFromBoard),
+            "board_role_changed" => Ok(NotificationKind::BoardRoleChanged),
+            _ => Err(format!("Invalid notification kind: {}", s)),
+        }
+    }
+}
+
+/// Notification with the title of its card or the name of its board, as listed to its
+/// recipient
+#[derive(Debug, Clone, Serialize, sqlx::FromRow, JsonSchema)]
+pub struct Notification {
+    pub id: Uuid,
+    /// None for changes to the user's role on a board
+    pub card_id: Option<Uuid>,
+    pub card_title: Option<String>,
+    /// Set for changes to the user's role on a board
+    pub board_id: Option<Uuid>,
+    pub board_name: Option<String>,
+    pub kind: String,
+    /// Due date a reminder was raised for; None for changes to watched cards
+    pub due_date: Option<NaiveDate>,
+    /// Who changed a watched card, mentioned the user or changed their role
+    pub actor_name: Option<String>,
+    /// What changed on a watched card, such as "moved it to Done", or the comment
+    /// the user was mentioned in
+    pub message: Option<String>,
+    pub read_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Notification raised by the scheduler, with what is needed to email it
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct DueReminder {
+    pub user_id: Uuid,
+    pub email: String,
+    pub card_id: Uuid,
+    pub card_title: String,
+    pub kind: String,
+    pub due_date: NaiveDate,
+}
+
+/// Whether the user watches a card, and how many users do
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct WatchStatus {
+    pub watching: bool,
+    pub watchers: i64,
+}
@@ -0,0 +1,42 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+/// Items per page when no limit is given
+pub const DEFAULT_PAGE_SIZE: i64 = 100;
+/// Largest accepted limit
+pub const MAX_PAGE_SIZE: i64 = 500;
+
+/// Query parameters of paginated list endpoints
+#[derive(Debug, Deserialize, Default, JsonSchema)]
+pub struct PageQuery {
+    /// Maximum number of items (at most 500)
+    pub limit: Option<i64>,
+    /// Items to skip, from the start of the list
+    pub offset: Option<i64>,
+}
+
+impl PageQuery {
+    /// The page asked for, with `default_limit` when no limit is given. Out of range
+    /// values are brought back in range.
+    pub fn page(&self, default_limit: i64) -> Page {
+        Page {
+            limit: self.limit.unwrap_or(default_limit).clamp(1, MAX_PAGE_SIZE),
+            offset: self.offset.unwrap_or(0).max(0),
+        }
+    }
+}
+
+/// A slice of a list, as bound to SQL `LIMIT` and `OFFSET`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Page {
+    pub limit: i64,
+    pub offset: i64,
+}
+
+impl Page {
+    /// The whole list; SQLite reads a negative limit as no limit
+    pub const ALL: Page = Page {
+        limit: -1,
+        offset: 0,
+    };
+}
@@ -0,0 +1,215 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use uuid::Uuid;
+
+/// Kind of stateful chat session
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ChatSessionType {
+    WeeklyPlanning,
+}
+
+impl fmt::Display for ChatSessionType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChatSessionType::WeeklyPlanning => write!(f, "weekly_planning"),
+        }
+    }
+}
+
+impl FromStr for ChatSessionType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "weekly_planning" => Ok(ChatSessionType::WeeklyPlanning),
+            _ => Err(format!("Invalid session type: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ChatSessionStatus {
+    Active,
+    Completed,
+    Cancelled,
+}
+
+impl fmt::Display for ChatSessionStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChatSessionStatus::Active => write!(f, "active"),
+            ChatSessionStatus::Completed => write!(f, "completed"),
+            ChatSessionStatus::Cancelled => write!(f, "cancelled"),
+        }
+    }
+}
+
+impl FromStr for ChatSessionStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "active" => Ok(ChatSessionStatus::Active),
+            "completed" => Ok(ChatSessionStatus::Completed),
+            "cancelled" => Ok(ChatSessionStatus::Cancelled),
+            _ => Err(format!("Invalid session status: {}", s)),
+        }
+    }
+}
+
+/// Steps of a weekly planning session, in order
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum PlanningStep {
+    /// Reschedule or drop the due date of overdue cards
+    Overdue,
+    /// Give due dates to open cards without one
+    Unscheduled,
+    /// Pick the cards to focus on this week
+    Focus,
+    /// Confirm the plan before it is applied
+    Review,
+    Done,
+}
+
+impl fmt::Display for PlanningStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlanningStep::Overdue => write!(f, "overdue"),
+            PlanningStep::Unscheduled => write!(f, "unscheduled"),
+            PlanningStep::Focus => write!(f, "focus"),
+            PlanningStep::Review => write!(f, "review"),
+            PlanningStep::Done => write!(f, "done"),
+        }
+    }
+}
+
+impl FromStr for PlanningStep {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "overdue" => Ok(PlanningStep::Overdue),
+            "unscheduled" => Ok(PlanningStep::Unscheduled),
+            "focus" => Ok(PlanningStep::Focus),
+            "review" => Ok(PlanningStep::Review),
+            "done" => Ok(PlanningStep::Done),
+            _ => Err(format!("Invalid planning step: {}", s)),
+        }
+    }
+}
+
+/// Server-side progress of a planning session, stored as JSON
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct PlanningState {
+    /// Overdue cards found when the session started
+    pub overdue: Vec<Uuid>,
+    /// Open cards without a due date found when the session started
+    pub unscheduled: Vec<Uuid>,
+    /// Proposed due dates; `None` clears the due date
+    pub due_dates: BTreeMap<Uuid, Option<NaiveDate>>,
+    /// Cards to focus on this week
+    pub focus: Vec<Uuid>,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ChatSession {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub session_type: String,
+    pub status: String,
+    pub step: String,
+    pub state: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ChatSession {
+    pub fn planning_step(&self) -> PlanningStep {
+        self.step.parse().unwrap_or(PlanningStep::Done)
+    }
+
+    pub fn session_status(&self) -> ChatSessionStatus {
+        self.status.parse().unwrap_or(ChatSessionStatus::Cancelled)
+    }
+
+    pub fn planning_state(&self) -> PlanningState {
+        serde_json::from_str(&self.state).unwrap_or_default()
+    }
+}
+
+/// Request to start a chat session
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CreateChatSession {
+    #[serde(rename = "type")]
+    pub session_type: ChatSessionType,
+}
+
+/// Answer to the current step of a planning session
+#[derive(Debug, Deserialize, Default, JsonSchema)]
+pub struct PlanningTurn {
+    /// New due dates for cards of the current step (`null` clears the due date)
+    #[serde(default)]
+    pub due_dates: BTreeMap<Uuid, Option<NaiveDate>>,
+    /// Cards to focus on (focus step)
+    #[serde(default)]
+    pub focus: Vec<Uuid>,
+    /// Apply (`true`) or discard (`false`) the plan (review step)
+    pub confirm: Option<bool>,
+}
+
+/// A card discussed in the current step
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PlanningItem {
+    pub card_id: Uuid,
+    pub title: String,
+    pub status: String,
+    pub due_date: Option<NaiveDate>,
+    /// Due date proposed so far in this session
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proposed_due_date: Option<Option<NaiveDate>>,
+}
+
+/// Proposed due date change in the plan
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PlannedDueDate {
+    pub card_id: Uuid,
+    pub title: String,
+    pub due_date: Option<NaiveDate>,
+}
+
+/// Focus card in the plan
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PlannedFocus {
+    pub card_id: Uuid,
+    pub title: String,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PlanSummary {
+    pub due_dates: Vec<PlannedDueDate>,
+    pub focus: Vec<PlannedFocus>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ChatSessionResponse {
+    pub id: Uuid,
+    #[serde(rename = "type")]
+    pub session_type: String,
+    pub status: ChatSessionStatus,
+    pub step: PlanningStep,
+    /// What the assistant asks at this step
+    pub prompt: String,
+    pub items: Vec<PlanningItem>,
+    pub plan: PlanSummary,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
@@ -0,0 +1,23 @@
+use schemars::JsonSchema;
+use serde::Serialize;
+use uuid::Uuid;
+
+/// Emoji users can react with
+pub const REACTION_EMOJI: &[&str] = &["👍", "👎", "😄", "🎉", "😕", "❤️", "🚀", "👀"];
+
+/// How many users reacted to a card or comment with an emoji
+#[derive(Debug, Clone, Serialize, sqlx::FromRow, JsonSchema)]
+pub struct ReactionCount {
+    pub emoji: String,
+    pub count: i64,
+    /// Whether the current user is one of them
+    pub reacted: bool,
+}
+
+/// Reaction counts of a card or of one of its comments
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ReactionCountRow {
+    pub comment_id: Option<Uuid>,
+    #[sqlx(flatten)]
+    pub reaction: ReactionCount,
+}
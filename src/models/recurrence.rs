@@ -0,0 +1,64 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use uuid::Uuid;
+
+/// How often a recurring card is copied
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RecurrenceFrequency {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+impl fmt::Display for RecurrenceFrequency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecurrenceFrequency::Daily => write!(f, "daily"),
+            RecurrenceFrequency::Weekly => write!(f, "weekly"),
+            RecurrenceFrequency::Monthly => write!(f, "monthly"),
+        }
+    }
+}
+
+impl FromStr for RecurrenceFrequency {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "daily" => Ok(RecurrenceFrequency::Daily),
+            "weekly" => Ok(RecurrenceFrequency::Weekly),
+            "monthly" => Ok(RecurrenceFrequency::Monthly),
+            _ => Err(format!("Invalid frequency: {}", s)),
+        }
+    }
+}
+
+/// Recurrence rule of a template card
+#[derive(Debug, Clone, Serialize, sqlx::FromRow, JsonSchema)]
+pub struct CardRecurrence {
+    pub card_id: Uuid,
+    pub frequency: String,
+    /// Number of days, weeks or months between two copies
+    pub interval: i64,
+    /// First occurrence; later ones fall on the same weekday or day of the month
+    pub starts_on: NaiveDate,
+    /// Date of the next copy
+    pub next_run_on: NaiveDate,
+    /// User the copies are created as
+    pub created_by: Uuid,
+    pub last_run_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SetCardRecurrence {
+    pub frequency: RecurrenceFrequency,
+    /// Defaults to 1
+    pub interval: Option<i64>,
+    /// Defaults to today
+    pub starts_on: Option<NaiveDate>,
+}
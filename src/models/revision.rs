@@ -0,0 +1,47 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::Serialize;
+use uuid::Uuid;
+
+/// A previous version of a card's title and body, saved when an edit replaced it
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct CardRevision {
+    pub id: Uuid,
+    pub card_id: Uuid,
+    pub title: String,
+    pub body: Option<String>,
+    pub edited_by: Option<Uuid>,
+    pub editor_name: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A title before and after an edit
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+pub struct TitleChange {
+    pub from: String,
+    pub to: String,
+}
+
+/// What the edit that replaced a revision changed
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct RevisionDiff {
+    /// Set when the title changed
+    pub title: Option<TitleChange>,
+    /// Unified diff of the body, set when the body changed
+    pub body: Option<String>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct CardRevisionResponse {
+    pub id: Uuid,
+    pub card_id: Uuid,
+    pub title: String,
+    pub body: Option<String>,
+    /// Who made the edit that replaced this version; `null` once their account is gone
+    pub edited_by: Option<Uuid>,
+    pub editor_name: Option<String>,
+    /// When this version was replaced
+    pub created_at: DateTime<Utc>,
+    /// From this version to the one that replaced it
+    pub diff: RevisionDiff,
+}
@@ -0,0 +1,48 @@
+use chrono::NaiveDate;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Size of the roadmap time buckets
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum RoadmapGrouping {
+    #[default]
+    Month,
+    Quarter,
+}
+
+/// Query parameters for the board roadmap endpoint
+#[derive(Debug, Deserialize, Default, JsonSchema)]
+pub struct RoadmapQuery {
+    pub group_by: Option<RoadmapGrouping>,
+    /// Name of the tag marking milestone cards (defaults to "milestone")
+    pub milestone_tag: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct RoadmapCard {
+    pub id: Uuid,
+    pub column_id: Option<Uuid>,
+    pub title: String,
+    pub status: String,
+    pub due_date: Option<NaiveDate>,
+}
+
+/// Cards due in one month or quarter. The undated bucket has no key and no dates.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct RoadmapBucket {
+    pub key: Option<String>,
+    pub label: String,
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+    pub cards: Vec<RoadmapCard>,
+    pub milestones: Vec<RoadmapCard>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct RoadmapResponse {
+    pub board_id: Uuid,
+    pub group_by: RoadmapGrouping,
+    pub buckets: Vec<RoadmapBucket>,
+}
@@ -0,0 +1,63 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::CardFilter;
+
+/// Card filter a user saved under a name for a board
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct SavedView {
+    pub id: Uuid,
+    pub board_id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    /// JSON object of a `CardFilter`
+    pub filter: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl SavedView {
+    pub fn card_filter(&self) -> CardFilter {
+        serde_json::from_str(&self.filter).unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct SavedViewResponse {
+    pub id: Uuid,
+    pub board_id: Uuid,
+    pub name: String,
+    pub filter: CardFilter,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<SavedView> for SavedViewResponse {
+    fn from(view: SavedView) -> Self {
+        Self {
+            filter: view.card_filter(),
+            id: view.id,
+            board_id: view.board_id,
+            name: view.name,
+            created_at: view.created_at,
+            updated_at: view.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CreateSavedView {
+    pub name: String,
+    /// Same fields as the card list filter; omitted means no filter
+    #[serde(default)]
+    pub filter: CardFilter,
+}
+
+/// View update: omitted fields are left unchanged, a filter replaces the previous one
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct UpdateSavedView {
+    pub name: Option<String>,
+    pub filter: Option<CardFilter>,
+}
@@ -0,0 +1,29 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SearchQuery {
+    /// Words to look for in card titles and bodies and in the text of attachments
+    pub q: String,
+}
+
+/// Attachment whose text matches a search
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct AttachmentSearchMatch {
+    pub id: Uuid,
+    pub filename: String,
+    /// Text around the matches as escaped HTML, the matching words in `<mark>` tags
+    pub snippet: String,
+}
+
+/// A card matching a search, by its own text or by the text of its attachments
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct SearchResult {
+    pub card_id: Uuid,
+    pub card_title: String,
+    pub status: String,
+    /// Whether the card's title or body matches
+    pub card_matches: bool,
+    pub attachments: Vec<AttachmentSearchMatch>,
+}
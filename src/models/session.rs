@@ -1,12 +1,47 @@
 use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, JsonSchema)]
 pub struct Session {
     pub id: Uuid,
     pub user_id: Uuid,
     pub token: String,
     pub expires_at: DateTime<Utc>,
     pub created_at: DateTime<Utc>,
+    pub ip_address: Option<String>,
+    /// Created by a "remember me" login, which keeps it for longer
+    pub remember: bool,
+}
+
+/// A session as listed to its user; the token itself is never shown
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct SessionResponse {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub ip_address: Option<String>,
+    pub remember: bool,
+    /// The session the request was made with
+    pub current: bool,
+}
+
+impl SessionResponse {
+    pub fn new(session: Session, current_token: Option<&str>) -> Self {
+        Self {
+            current: current_token == Some(session.token.as_str()),
+            id: session.id,
+            created_at: session.created_at,
+            expires_at: session.expires_at,
+            ip_address: session.ip_address,
+            remember: session.remember,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct SessionsRevoked {
+    /// Number of sessions logged out
+    pub revoked: u64,
 }
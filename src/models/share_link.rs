@@ -0,0 +1,19 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::Serialize;
+use uuid::Uuid;
+
+/// The public read-only address of a board, without its token
+#[derive(Debug, Clone, Serialize, sqlx::FromRow, JsonSchema)]
+pub struct BoardShareLink {
+    pub board_id: Uuid,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A freshly generated public address of a board. The token is only shown once.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct BoardShareLinkResponse {
+    pub token: String,
+    pub url: String,
+}
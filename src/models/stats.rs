@@ -0,0 +1,60 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Query parameters for the board statistics endpoint
+#[derive(Debug, Deserialize, Default, JsonSchema)]
+pub struct BoardStatsQuery {
+    /// Number of past weeks, the current one included, for throughput and cycle time
+    pub weeks: Option<u32>,
+}
+
+/// A card event from the activity log that moves a card through the board
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct CardFlowEvent {
+    pub card_id: Uuid,
+    pub event: String,
+    pub details: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow, JsonSchema)]
+pub struct ColumnCardCount {
+    pub column_id: Uuid,
+    pub name: String,
+    pub cards: i64,
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow, JsonSchema)]
+pub struct TagCardCount {
+    pub tag_id: Uuid,
+    pub name: String,
+    pub color: String,
+    pub cards: i64,
+}
+
+/// Cards completed in the week starting on `week_start`, a Monday
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct WeeklyThroughput {
+    pub week_start: NaiveDate,
+    pub completed: u32,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct BoardStats {
+    pub board_id: Uuid,
+    /// Cards in each column, archived cards left out
+    pub columns: Vec<ColumnCardCount>,
+    /// Open and in-progress cards per tag, most used first
+    pub tags: Vec<TagCardCount>,
+    /// Cards past their due date that are neither done nor closed
+    pub overdue_cards: i64,
+    /// Oldest week first
+    pub throughput: Vec<WeeklyThroughput>,
+    /// Cards completed during the weeks covered by `throughput`
+    pub completed_cards: u32,
+    /// Average days from starting work on a card to completing it, None without
+    /// completed cards
+    pub average_cycle_time_days: Option<f64>,
+}
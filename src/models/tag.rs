@@ -1,8 +1,9 @@
 use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, JsonSchema)]
 pub struct Tag {
     pub id: Uuid,
     pub board_id: Option<Uuid>,
@@ -10,28 +11,37 @@ pub struct Tag {
     pub name: String,
     pub color: String,
     pub created_at: DateTime<Utc>,
+    /// Global tag this board tag inherits its name and color from
+    pub linked_tag_id: Option<Uuid>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+impl Tag {
+    /// The tag that defines this tag's name and color
+    pub fn effective_id(&self) -> Uuid {
+        self.linked_tag_id.unwrap_or(self.id)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, JsonSchema)]
 pub struct CardTag {
     pub card_id: Uuid,
     pub tag_id: Uuid,
     pub created_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct CreateTag {
     pub name: String,
     pub color: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct UpdateTag {
     pub name: Option<String>,
     pub color: Option<String>,
 }
 
-#[derive(Debug, Serialize, Clone)]
+#[derive(Debug, Serialize, Clone, JsonSchema)]
 pub struct TagResponse {
     pub id: Uuid,
     pub board_id: Option<Uuid>,
@@ -39,6 +49,7 @@ pub struct TagResponse {
     pub name: String,
     pub color: String,
     pub created_at: DateTime<Utc>,
+    pub linked_tag_id: Option<Uuid>,
 }
 
 impl From<Tag> for TagResponse {
@@ -50,13 +61,41 @@ impl From<Tag> for TagResponse {
             name: tag.name,
             color: tag.color,
             created_at: tag.created_at,
+            linked_tag_id: tag.linked_tag_id,
         }
     }
 }
 
 /// Request to create a global (user-scoped) tag
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct CreateGlobalTag {
     pub name: String,
     pub color: Option<String>,
 }
+
+/// Request to link a board tag to a global tag
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct LinkTag {
+    pub global_tag_id: Uuid,
+}
+
+#[derive(Debug, Deserialize, Default, JsonSchema)]
+pub struct LinkDuplicateTagsQuery {
+    /// Only report the links that would be made
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// A board tag paired with the global tag of the same name
+#[derive(Debug, Clone, Serialize, sqlx::FromRow, JsonSchema)]
+pub struct TagLink {
+    pub board_tag_id: Uuid,
+    pub global_tag_id: Uuid,
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct LinkDuplicateTagsResponse {
+    pub dry_run: bool,
+    pub links: Vec<TagLink>,
+}
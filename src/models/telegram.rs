@@ -0,0 +1,12 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::Serialize;
+
+/// A one-time code linking a Telegram chat to the user
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct TelegramLinkResponse {
+    pub code: String,
+    /// Message to send the bot to link the chat
+    pub command: String,
+    pub expires_at: DateTime<Utc>,
+}
@@ -0,0 +1,91 @@
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::fmt;
+use std::str::FromStr;
+
+/// UI theme a user can select. `System` follows the browser color scheme preference.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Theme {
+    #[default]
+    System,
+    Light,
+    Dark,
+    HighContrast,
+}
+
+impl Theme {
+    /// All selectable themes, in display order
+    pub const ALL: [Theme; 4] = [
+        Theme::System,
+        Theme::Light,
+        Theme::Dark,
+        Theme::HighContrast,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Theme::System => "System default",
+            Theme::Light => "Light",
+            Theme::Dark => "Dark",
+            Theme::HighContrast => "High contrast",
+        }
+    }
+
+    /// Theme CSS bundle layered on top of the base stylesheet, if any.
+    /// Light is the base stylesheet itself; System resolves to light or dark client-side.
+    /// Bundles are scoped to `[data-theme="..."]`, so all of them can be linked at once.
+    pub fn stylesheet(&self) -> Option<&'static str> {
+        match self {
+            Theme::System | Theme::Light => None,
+            Theme::Dark => Some("/static/css/themes/dark.css"),
+            Theme::HighContrast => Some("/static/css/themes/high-contrast.css"),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Theme::System => "system",
+            Theme::Light => "light",
+            Theme::Dark => "dark",
+            Theme::HighContrast => "high-contrast",
+        }
+    }
+}
+
+impl fmt::Display for Theme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for Theme {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "system" => Ok(Theme::System),
+            "light" => Ok(Theme::Light),
+            "dark" => Ok(Theme::Dark),
+            "high-contrast" => Ok(Theme::HighContrast),
+            _ => Err(format!("Invalid theme: {}", s)),
+        }
+    }
+}
+
+/// Theme registry entry, served to clients as `/static/themes.json`
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ThemeInfo {
+    pub id: &'static str,
+    pub label: &'static str,
+    pub stylesheet: Option<&'static str>,
+}
+
+impl From<Theme> for ThemeInfo {
+    fn from(theme: Theme) -> Self {
+        Self {
+            id: theme.as_str(),
+            label: theme.label(),
+            stylesheet: theme.stylesheet(),
+        }
+    }
+}
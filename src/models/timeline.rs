@@ -0,0 +1,53 @@
+use chrono::NaiveDate;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::RoadmapCard;
+
+/// Query parameters for the board timeline endpoint
+#[derive(Debug, Deserialize, Default, JsonSchema)]
+pub struct TimelineQuery {
+    /// Only cards with this tag
+    pub tag_id: Option<Uuid>,
+    /// Only cards in this column
+    pub column_id: Option<Uuid>,
+    /// Only cards whose bar ends on or after this date
+    pub from: Option<NaiveDate>,
+    /// Only cards whose bar starts on or before this date
+    pub to: Option<NaiveDate>,
+}
+
+/// A card drawn as a bar from `bar_start` to `bar_end`, both inclusive. Cards with
+/// only some of their dates set get a bar from the dates they have.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct TimelineCard {
+    pub id: Uuid,
+    pub column_id: Option<Uuid>,
+    pub title: String,
+    pub status: String,
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+    pub due_date: Option<NaiveDate>,
+    pub bar_start: NaiveDate,
+    pub bar_end: NaiveDate,
+}
+
+/// The cards of one column, by start date
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct TimelineLane {
+    pub column_id: Uuid,
+    pub column_name: String,
+    pub cards: Vec<TimelineCard>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct TimelineResponse {
+    pub board_id: Uuid,
+    /// First and last day covered by the bars, None when no card has a date
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+    pub lanes: Vec<TimelineLane>,
+    /// Matching cards without any date
+    pub unscheduled: Vec<RoadmapCard>,
+}
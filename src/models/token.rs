@@ -1,8 +1,9 @@
 use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, JsonSchema)]
 #[sqlx(type_name = "TEXT")]
 pub enum TokenScope {
     #[sqlx(rename = "read")]
@@ -13,6 +14,19 @@ pub enum TokenScope {
     Admin,
 }
 
+impl TokenScope {
+    /// Whether a token with this scope may do what `scope` allows: read tokens only
+    /// read, write tokens also change data, admin tokens can do everything
+    pub fn allows(self, scope: TokenScope) -> bool {
+        let rank = |s: TokenScope| match s {
+            TokenScope::Read => 0,
+            TokenScope::Write => 1,
+            TokenScope::Admin => 2,
+        };
+        rank(self) >= rank(scope)
+    }
+}
+
 impl std::fmt::Display for TokenScope {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -36,7 +50,7 @@ impl std::str::FromStr for TokenScope {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, JsonSchema)]
 pub struct ApiToken {
     pub id: Uuid,
     pub user_id: Uuid,
@@ -46,16 +60,24 @@ pub struct ApiToken {
     pub expires_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub last_used_at: Option<DateTime<Utc>>,
+    pub last_used_ip: Option<String>,
+}
+
+impl ApiToken {
+    /// Unknown scopes are treated as read-only
+    pub fn scope(&self) -> TokenScope {
+        self.scope.parse().unwrap_or(TokenScope::Read)
+    }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct CreateApiToken {
     pub name: String,
     pub scope: TokenScope,
     pub expires_in_days: Option<i64>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct ApiTokenResponse {
     pub id: Uuid,
     pub name: String,
@@ -63,9 +85,11 @@ pub struct ApiTokenResponse {
     pub expires_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub last_used_at: Option<DateTime<Utc>>,
+    /// Client IP of the last request made with the token
+    pub last_used_ip: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct ApiTokenCreatedResponse {
     pub id: Uuid,
     pub token: String,
@@ -83,6 +107,7 @@ impl From<ApiToken> for ApiTokenResponse {
             expires_at: token.expires_at,
             created_at: token.created_at,
             last_used_at: token.last_used_at,
+            last_used_ip: token.last_used_ip,
         }
     }
 }
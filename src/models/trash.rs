@@ -0,0 +1,46 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use uuid::Uuid;
+
+/// What a trash entry is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TrashKind {
+    Board,
+    Column,
+    Card,
+}
+
+impl fmt::Display for TrashKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TrashKind::Board => write!(f, "board"),
+            TrashKind::Column => write!(f, "column"),
+            TrashKind::Card => write!(f, "card"),
+        }
+    }
+}
+
+/// Deleted board, column or card that can still be restored
+#[derive(Debug, Clone, Serialize, sqlx::FromRow, JsonSchema)]
+pub struct TrashItem {
+    /// `board`, `column` or `card`
+    pub kind: String,
+    pub id: Uuid,
+    /// Name of the board or column, title of the card
+    pub name: String,
+    /// Board the item is on; `None` for inbox cards
+    pub board_id: Option<Uuid>,
+    pub deleted_at: DateTime<Utc>,
+    pub deleted_by: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct TrashItemResponse {
+    #[serde(flatten)]
+    pub item: TrashItem,
+    /// When the item is deleted for good
+    pub purge_at: DateTime<Utc>,
+}
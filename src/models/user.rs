@@ -1,26 +1,96 @@
 use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+use super::card::deserialize_nullable;
+use super::Theme;
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, JsonSchema)]
 pub struct User {
     pub id: Uuid,
     pub email: String,
-    pub password_hash: String,
+    /// None for users who only sign in through an OAuth provider
+    pub password_hash: Option<String>,
     pub name: String,
     pub llm_context: Option<String>,
+    pub theme: String,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub is_admin: bool,
+    /// Board chat proposes actions for confirmation instead of executing them
+    pub chat_confirm_actions: bool,
+    /// LLM provider for chat requests, the server default when `None`
+    pub llm_provider: Option<String>,
+    /// Model for chat requests, the provider's configured model when `None`
+    pub llm_model: Option<String>,
+    pub llm_temperature: Option<f64>,
+    /// Set when an administrator deactivated the account
+    pub deactivated_at: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, Deserialize)]
+impl User {
+    /// Whether the user may log in and use their API tokens
+    pub fn is_active(&self) -> bool {
+        self.deactivated_at.is_none()
+    }
+
+    /// Selected UI theme, falling back to the system default for unknown values
+    pub fn ui_theme(&self) -> Theme {
+        self.theme.parse().unwrap_or_default()
+    }
+
+    /// Whether `password` is the user's password. Users without one never match.
+    pub fn check_password(&self, password: &str) -> crate::error::Result<bool> {
+        match &self.password_hash {
+            Some(hash) => crate::auth::verify_password(password, hash),
+            None => Ok(false),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct CreateUser {
     pub email: String,
     pub password: String,
     pub name: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ChangePassword {
+    /// Required unless the user has no password yet
+    #[serde(default)]
+    pub current_password: Option<String>,
+    pub new_password: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ChangeEmail {
+    pub new_email: String,
+    /// Required unless the user has no password
+    #[serde(default)]
+    pub current_password: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ConfirmEmailChange {
+    pub token: String,
+}
+
+/// Email address change waiting for the new address to be confirmed. The token that
+/// confirms it is only sent by email.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow, JsonSchema)]
+pub struct EmailChange {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub new_email: String,
+    #[serde(skip_serializing)]
+    pub token_hash: String,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct UserResponse {
     pub id: Uuid,
     pub email: String,
@@ -38,3 +108,45 @@ impl From<User> for UserResponse {
         }
     }
 }
+
+/// Preferences also editable on the web settings page
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct UserSettings {
+    pub theme: String,
+    pub llm_context: Option<String>,
+    pub chat_confirm_actions: bool,
+    pub llm_provider: Option<String>,
+    pub llm_model: Option<String>,
+    pub llm_temperature: Option<f64>,
+}
+
+impl From<&User> for UserSettings {
+    fn from(user: &User) -> Self {
+        Self {
+            theme: user.ui_theme().to_string(),
+            llm_context: user.llm_context.clone(),
+            chat_confirm_actions: user.chat_confirm_actions,
+            llm_provider: user.llm_provider.clone(),
+            llm_model: user.llm_model.clone(),
+            llm_temperature: user.llm_temperature,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct UpdateUserSettings {
+    pub theme: Option<String>,
+    /// `null` or an empty string clears the context
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub llm_context: Option<Option<String>>,
+    pub chat_confirm_actions: Option<bool>,
+    /// `null` or an empty string goes back to the server default
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub llm_provider: Option<Option<String>>,
+    /// `null` or an empty string goes back to the provider's model
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub llm_model: Option<Option<String>>,
+    /// Between 0 and 2, `null` goes back to the model's default
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub llm_temperature: Option<Option<f64>>,
+}
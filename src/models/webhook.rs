@@ -0,0 +1,87 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::event::{BoardEvent, BoardEventKind};
+
+/// Version of the payload webhooks receive. It changes when a field is renamed or
+/// removed, not when one is added, and is sent with every delivery so consumers can
+/// tell payloads apart.
+pub const WEBHOOK_SCHEMA_VERSION: i64 = 1;
+
+/// Address that receives the events of a board
+#[derive(Debug, Clone, Serialize, sqlx::FromRow, JsonSchema)]
+pub struct Webhook {
+    pub id: Uuid,
+    pub board_id: Uuid,
+    pub url: String,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CreateWebhook {
+    /// http or https address the events are POSTed to
+    pub url: String,
+}
+
+/// Event kept for a webhook with the payload it was sent with
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct WebhookEvent {
+    pub id: Uuid,
+    pub webhook_id: Uuid,
+    pub event: String,
+    pub schema_version: i64,
+    pub payload: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Body POSTed to a webhook
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct WebhookPayload {
+    /// Version of this payload's shape, also sent in X-Webhook-Schema-Version
+    pub schema_version: i64,
+    /// Identifies the event, unchanged when it is replayed so consumers can skip
+    /// events they already handled
+    pub delivery_id: Uuid,
+    pub event: BoardEventKind,
+    pub board_id: Uuid,
+    /// The column, card, tag or comment that changed (the board itself for board events)
+    pub id: Uuid,
+    /// Card the change belongs to, for tag assignments, comments and attachments
+    pub card_id: Option<Uuid>,
+    pub actor_id: Uuid,
+    pub at: DateTime<Utc>,
+    /// What changed, as recorded in the board's activity
+    pub details: Option<serde_json::Value>,
+}
+
+impl WebhookPayload {
+    pub fn new(delivery_id: Uuid, board_id: Uuid, event: &BoardEvent) -> Self {
+        Self {
+            schema_version: WEBHOOK_SCHEMA_VERSION,
+            delivery_id,
+            event: event.event,
+            board_id,
+            id: event.id,
+            card_id: event.card_id,
+            actor_id: event.actor_id,
+            at: event.at,
+            details: event.details.clone(),
+        }
+    }
+}
+
+/// Query parameters for replaying a webhook's events
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ReplayWebhookQuery {
+    /// Replay the events sent at or after this time, oldest first
+    pub since: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct WebhookReplayResponse {
+    /// Number of events queued for delivery again
+    pub replayed: usize,
+}
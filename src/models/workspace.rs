@@ -0,0 +1,73 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::board::BoardRole;
+
+/// A group of boards shared by the same people. Its members get their workspace
+/// role on every board of the workspace that doesn't give them one of its own.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, JsonSchema)]
+pub struct Workspace {
+    pub id: Uuid,
+    pub name: String,
+    pub owner_id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, JsonSchema)]
+pub struct WorkspaceMember {
+    pub user_id: Uuid,
+    pub name: String,
+    pub email: String,
+    pub role: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CreateWorkspace {
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct UpdateWorkspace {
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct AddWorkspaceMember {
+    pub user_id: Uuid,
+    /// Role on every board of the workspace, unless the board gives another one
+    pub role: BoardRole,
+}
+
+/// Request to move a board into a workspace, or out of it with `null`
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SetBoardWorkspace {
+    pub workspace_id: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct WorkspaceResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub owner_id: Uuid,
+    /// The current user's role in the workspace
+    pub role: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl WorkspaceResponse {
+    pub fn new(workspace: Workspace, role: String) -> Self {
+        Self {
+            id: workspace.id,
+            name: workspace.name,
+            owner_id: workspace.owner_id,
+            role,
+            created_at: workspace.created_at,
+            updated_at: workspace.updated_at,
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use chrono::Utc;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::error::Result;
+use crate::models::{Card, ChatMessage, Comment};
+
+/// Rows that belong to the user alone and go with their account. Cards, comments and
+/// attachments they left on other people's boards stay, credited to "Deleted user".
+const PERSONAL_DATA: &[&str] = &[
+    "DELETE FROM boards WHERE owner_id = $1",
+    "DELETE FROM board_templates WHERE owner_id = $1",
+    "DELETE FROM board_permissions WHERE user_id = $1",
+    "DELETE FROM workspaces WHERE owner_id = $1",
+    "DELETE FROM workspace_members WHERE user_id = $1",
+    "DELETE FROM board_preferences WHERE user_id = $1",
+    "DELETE FROM board_groups WHERE user_id = $1",
+    "DELETE FROM column_collapses WHERE user_id = $1",
+    "DELETE FROM sessions WHERE user_id = $1",
+    "DELETE FROM api_tokens WHERE user_id = $1",
+    "DELETE FROM calendar_feeds WHERE user_id = $1",
+    "DELETE FROM inbound_email_addresses WHERE user_id = $1",
+    "DELETE FROM telegram_links WHERE user_id = $1",
+    "DELETE FROM user_identities WHERE user_id = $1",
+    "DELETE FROM email_changes WHERE user_id = $1",
+    "DELETE FROM chat_messages WHERE user_id = $1",
+    "DELETE FROM chat_sessions WHERE user_id = $1",
+    "DELETE FROM chat_actions WHERE user_id = $1",
+    "DELETE FROM pending_chat_actions WHERE user_id = $1",
+    "DELETE FROM comment_drafts WHERE user_id = $1",
+    "DELETE FROM card_assignees WHERE user_id = $1",
+    "DELETE FROM card_recurrences WHERE created_by = $1",
+    "DELETE FROM notifications WHERE user_id = $1",
+    "DELETE FROM card_watchers WHERE user_id = $1",
+    "DELETE FROM comment_mentions WHERE user_id = $1",
+    "DELETE FROM reactions WHERE user_id = $1",
+    "DELETE FROM saved_views WHERE user_id = $1",
+    "DELETE FROM tags WHERE owner_id = $1 AND board_id IS NULL",
+    "UPDATE tags SET owner_id = NULL WHERE owner_id = $1",
+    "UPDATE cards SET owner_id = NULL WHERE owner_id = $1",
+    "UPDATE card_revisions SET edited_by = NULL WHERE edited_by = $1",
+    "UPDATE cards SET locked_by = NULL, locked_at = NULL WHERE locked_by = $1",
+];
+
+/// Export and deletion of everything stored about a user
+#[derive(Clone)]
+pub struct AccountRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl AccountRepository {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+
+    /// Cards the user owns or created and every card of the boards they own,
+    /// including archived cards and the trash
+    pub async fn list_cards(&self, user_id: Uuid) -> Result<Vec<Card>> {
+        let cards = sqlx::query_as::<_, Card>(
+            r#"
+            SELECT c.* FROM cards c
+            LEFT JOIN columns col ON col.id = c.column_id
+            LEFT JOIN boards b ON b.id = col.board_id
+            WHERE c.owner_id = $1 OR c.created_by = $1 OR b.owner_id = $1
+            ORDER BY c.created_at, c.id
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(cards)
+    }
+
+    pub async fn list_comments(&self, user_id: Uuid) -> Result<Vec<Comment>> {
+        let comments = sqlx::query_as::<_, Comment>(
+            "SELECT * FROM comments WHERE user_id = $1 ORDER BY created_at, id",
+        )
+        .bind(user_id)
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(comments)
+    }
+
+    /// The user's chat history across all boards and the global chat
+    pub async fn list_chat_messages(&self, user_id: Uuid) -> Result<Vec<ChatMessage>> {
+        let messages = sqlx::query_as::<_, ChatMessage>(
+            "SELECT * FROM chat_messages WHERE user_id = $1 ORDER BY created_at, id",
+        )
+        .bind(user_id)
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(messages)
+    }
+
+    /// Cards deleted with the account: those of the boards the user owns and their
+    /// inbox cards. Their attachment files must be removed too.
+    pub async fn list_card_ids_to_delete(&self, user_id: Uuid) -> Result<Vec<Uuid>> {
+        let ids = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            SELECT c.id FROM cards c
+            LEFT JOIN columns col ON col.id = c.column_id
+            LEFT JOIN boards b ON b.id = col.board_id
+            WHERE b.owner_id = $1 OR (c.column_id IS NULL AND c.owner_id = $1)
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(ids)
+    }
+
+    /// Delete the user's personal data and anonymize the account, in one transaction.
+    /// The user row stays as "Deleted user", deactivated and without credentials, so
+    /// the cards and comments left on other people's boards keep an author.
+    pub async fn delete_and_anonymize(&self, user_id: Uuid) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        for statement in PERSONAL_DATA {
+            sqlx::query(statement)
+                .bind(user_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        sqlx::query(
+            r#"
+            UPDATE users
+            SET email = 'deleted-' || id || '@deleted.invalid',
+                name = 'Deleted user',
+                password_hash = NULL,
+                llm_context = NULL,
+                llm_provider = NULL,
+                llm_model = NULL,
+                llm_temperature = NULL,
+                is_admin = 0,
+                deactivated_at = COALESCE(deactivated_at, $2),
+                updated_at = datetime('now')
+            WHERE id = $1
+            "#,
+        )
+        .bind(user_id)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
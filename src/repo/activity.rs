@@ -0,0 +1,95 @@
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::error::Result;
+use crate::models::{ActivityWithActor, BoardRole};
+
+#[derive(Clone)]
+pub struct ActivityRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl ActivityRepository {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+
+    /// Append one change to the activity log
+    pub async fn record(
+        &self,
+        board_id: Uuid,
+        card_id: Option<Uuid>,
+        actor_id: Uuid,
+        event: &str,
+        entity_id: Uuid,
+        details: Option<&str>,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO activity_log (id, board_id, card_id, actor_id, event, entity_id, details, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, datetime('now'))
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(board_id)
+        .bind(card_id)
+        .bind(actor_id)
+        .bind(event)
+        .bind(entity_id)
+        .bind(details)
+        .execute(self.pool.as_ref())
+        .await?;
+
+        Ok(())
+    }
+
+    /// Newest changes recorded on the board or on any card currently on it
+    ///
+    /// Readers do not see changes to private cards, as in the board's card list.
+    pub async fn list_for_board(
+        &self,
+        board_id: Uuid,
+        role: &BoardRole,
+        limit: i64,
+    ) -> Result<Vec<ActivityWithActor>> {
+        let entries = sqlx::query_as::<_, ActivityWithActor>(
+            r#"
+            SELECT a.*, u.name AS actor_name FROM activity_log a
+            LEFT JOIN users u ON u.id = a.actor_id
+            LEFT JOIN cards c ON c.id = a.card_id
+            WHERE (a.board_id = $1
+               OR a.card_id IN (SELECT card_id FROM card_boards WHERE board_id = $1))
+              AND ($3 OR a.card_id IS NULL OR c.visibility != 'private')
+            ORDER BY a.created_at DESC, a.rowid DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(board_id)
+        .bind(limit)
+        .bind(role.can_edit())
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(entries)
+    }
+
+    /// Newest changes to a card, its tags and its comments, whatever board they were made on
+    pub async fn list_for_card(&self, card_id: Uuid, limit: i64) -> Result<Vec<ActivityWithActor>> {
+        let entries = sqlx::query_as::<_, ActivityWithActor>(
+            r#"
+            SELECT a.*, u.name AS actor_name FROM activity_log a
+            LEFT JOIN users u ON u.id = a.actor_id
+            WHERE a.card_id = $1
+            ORDER BY a.created_at DESC, a.rowid DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(card_id)
+        .bind(limit)
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(entries)
+    }
+}
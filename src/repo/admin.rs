@@ -0,0 +1,96 @@
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::error::Result;
+use crate::models::{ChatUsage, InstanceStats, UserAccount, UserStats};
+
+/// Users with the number of boards they own and chat messages they sent
+const USER_ACCOUNTS: &str = r#"
+    SELECT u.id, u.email, u.name, u.is_admin, u.deactivated_at, u.created_at,
+           (SELECT COUNT(*) FROM boards b
+            WHERE b.owner_id = u.id AND b.deleted_at IS NULL) AS boards,
+           (SELECT COUNT(*) FROM chat_messages m WHERE m.user_id = u.id) AS chat_messages
+    FROM users u
+"#;
+
+/// Instance-wide queries for administrators
+#[derive(Clone)]
+pub struct AdminRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl AdminRepository {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+
+    /// Every user, oldest first
+    pub async fn list_users(&self) -> Result<Vec<UserAccount>> {
+        let users = sqlx::query_as::<_, UserAccount>(&format!(
+            "{} ORDER BY u.created_at, u.email",
+            USER_ACCOUNTS
+        ))
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(users)
+    }
+
+    pub async fn find_user(&self, id: Uuid) -> Result<Option<UserAccount>> {
+        let user = sqlx::query_as::<_, UserAccount>(&format!("{} WHERE u.id = $1", USER_ACCOUNTS))
+            .bind(id)
+            .fetch_optional(self.pool.as_ref())
+            .await?;
+
+        Ok(user)
+    }
+
+    pub async fn stats(&self) -> Result<InstanceStats> {
+        let (total, active, admins): (i64, i64, i64) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*),
+                   COUNT(*) FILTER (WHERE deactivated_at IS NULL),
+                   COUNT(*) FILTER (WHERE is_admin = 1)
+            FROM users
+            "#,
+        )
+        .fetch_one(self.pool.as_ref())
+        .await?;
+
+        let (boards, cards): (i64, i64) = sqlx::query_as(
+            r#"
+            SELECT (SELECT COUNT(*) FROM boards WHERE deleted_at IS NULL),
+                   (SELECT COUNT(*) FROM cards WHERE deleted_at IS NULL)
+            "#,
+        )
+        .fetch_one(self.pool.as_ref())
+        .await?;
+
+        let (messages, messages_last_30_days, actions): (i64, i64, i64) = sqlx::query_as(
+            r#"
+            SELECT (SELECT COUNT(*) FROM chat_messages),
+                   (SELECT COUNT(*) FROM chat_messages
+                    WHERE created_at >= datetime('now', '-30 days')),
+                   (SELECT COUNT(*) FROM chat_actions)
+            "#,
+        )
+        .fetch_one(self.pool.as_ref())
+        .await?;
+
+        Ok(InstanceStats {
+            users: UserStats {
+                total,
+                active,
+                admins,
+            },
+            boards,
+            cards,
+            chat: ChatUsage {
+                messages,
+                messages_last_30_days,
+                actions,
+            },
+        })
+    }
+}
@@ -0,0 +1,158 @@
+use sqlx::{SqliteConnection, SqlitePool};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::error::Result;
+use crate::models::{Card, CardAssignee, CardStatus, MemberWorkload};
+
+#[derive(Clone)]
+pub struct AssigneeRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl AssigneeRepository {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+
+    /// Assign a user to a card. Returns false if they were already assigned.
+    pub async fn add(&self, card_id: Uuid, user_id: Uuid, assigned_by: Uuid) -> Result<bool> {
+        let mut conn = self.pool.acquire().await?;
+        Self::add_on(&mut conn, card_id, user_id, assigned_by).await
+    }
+
+    pub(crate) async fn add_on(
+        conn: &mut SqliteConnection,
+        card_id: Uuid,
+        user_id: Uuid,
+        assigned_by: Uuid,
+    ) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            INSERT OR IGNORE INTO card_assignees (card_id, user_id, assigned_by, assigned_at)
+            VALUES ($1, $2, $3, datetime('now'))
+            "#,
+        )
+        .bind(card_id)
+        .bind(user_id)
+        .bind(assigned_by)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Unassign a user from a card. Returns false if they were not assigned.
+    pub async fn remove(&self, card_id: Uuid, user_id: Uuid) -> Result<bool> {
+        let mut conn = self.pool.acquire().await?;
+        Self::remove_on(&mut conn, card_id, user_id).await
+    }
+
+    pub(crate) async fn remove_on(
+        conn: &mut SqliteConnection,
+        card_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM card_assignees WHERE card_id = $1 AND user_id = $2")
+            .bind(card_id)
+            .bind(user_id)
+            .execute(&mut *conn)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// List the users assigned to a card, in assignment order
+    pub async fn list_for_card(&self, card_id: Uuid) -> Result<Vec<CardAssignee>> {
+        let assignees = sqlx::query_as::<_, CardAssignee>(
+            r#"
+            SELECT ca.user_id, u.name, u.email, ca.assigned_by, ca.assigned_at
+            FROM card_assignees ca
+            JOIN users u ON u.id = ca.user_id
+            WHERE ca.card_id = $1
+            ORDER BY ca.assigned_at ASC, ca.rowid ASC
+            "#,
+        )
+        .bind(card_id)
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(assignees)
+    }
+
+    /// Cards assigned to a user, optionally with the given status, by due date then
+    /// most recently updated. Archived and deleted cards are left out.
+    pub async fn list_cards_for_user(
+        &self,
+        user_id: Uuid,
+        status: Option<CardStatus>,
+    ) -> Result<Vec<Card>> {
+        let cards = sqlx::query_as::<_, Card>(
+            r#"
+            SELECT c.* FROM cards c
+            INNER JOIN card_assignees ca ON ca.card_id = c.id
+            WHERE ca.user_id = $1 AND ($2 IS NULL OR c.status = $2)
+              AND c.archived_at IS NULL AND c.deleted_at IS NULL
+              AND (c.column_id IS NULL
+                   OR c.column_id IN (
+                       SELECT col.id FROM columns col
+                       INNER JOIN boards b ON b.id = col.board_id
+                       WHERE col.archived_at IS NULL AND col.deleted_at IS NULL
+                         AND b.deleted_at IS NULL
+                   ))
+            ORDER BY c.due_date IS NULL, c.due_date ASC, c.updated_at DESC
+            "#,
+        )
+        .bind(user_id)
+        .bind(status.map(|s| s.to_string()))
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(cards)
+    }
+
+    /// Open and in-progress cards of the board that nobody is assigned to, in board
+    /// order. Archived cards and cards in archived columns are left out.
+    pub async fn list_unassigned_for_board(&self, board_id: Uuid) -> Result<Vec<Card>> {
+        let cards = sqlx::query_as::<_, Card>(
+            r#"
+            SELECT c.* FROM cards c
+            INNER JOIN card_boards cb ON cb.card_id = c.id
+            INNER JOIN columns col ON col.id = cb.column_id
+            WHERE cb.board_id = $1 AND c.status IN ('open', 'in_progress')
+              AND c.archived_at IS NULL AND c.deleted_at IS NULL
+              AND col.archived_at IS NULL AND col.deleted_at IS NULL
+              AND NOT EXISTS (SELECT 1 FROM card_assignees ca WHERE ca.card_id = c.id)
+            ORDER BY col.position ASC, cb.position ASC, c.id ASC
+            "#,
+        )
+        .bind(board_id)
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(cards)
+    }
+
+    /// Owners and editors of the board, each with the open and in-progress cards
+    /// assigned to them on any board, least loaded first
+    pub async fn workload_for_board(&self, board_id: Uuid) -> Result<Vec<MemberWorkload>> {
+        let workload = sqlx::query_as::<_, MemberWorkload>(
+            r#"
+            SELECT u.id AS user_id, u.name,
+                   (SELECT COUNT(*) FROM card_assignees ca
+                    INNER JOIN cards c ON c.id = ca.card_id
+                    WHERE ca.user_id = u.id AND c.status IN ('open', 'in_progress')
+                      AND c.archived_at IS NULL AND c.deleted_at IS NULL) AS open_cards
+            FROM board_access ba
+            INNER JOIN users u ON u.id = ba.user_id
+            WHERE ba.board_id = $1 AND ba.role IN ('owner', 'editor')
+            ORDER BY open_cards ASC, u.name ASC, u.id ASC
+            "#,
+        )
+        .bind(board_id)
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(workload)
+    }
+}
@@ -0,0 +1,204 @@
+use sqlx::{SqliteConnection, SqlitePool};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::models::{Attachment, AttachmentTextMatch, AttachmentWithUploader};
+use crate::repo::card::VISIBLE_TO_USER;
+
+/// Metadata columns, leaving out the content blob
+const ATTACHMENT_COLUMNS: &str =
+    "id, card_id, uploaded_by, filename, content_type, size, storage_path, created_at";
+
+#[derive(Clone)]
+pub struct AttachmentRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl AttachmentRepository {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+
+    /// Create an attachment whose content is either `data` or the file at `storage_path`
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create(
+        &self,
+        id: Uuid,
+        card_id: Uuid,
+        uploaded_by: Uuid,
+        filename: &str,
+        content_type: &str,
+        size: i64,
+        storage_path: Option<&str>,
+        data: Option<&[u8]>,
+    ) -> Result<Attachment> {
+        let attachment = sqlx::query_as::<_, Attachment>(&format!(
+            r#"
+            INSERT INTO attachments
+                (id, card_id, uploaded_by, filename, content_type, size, storage_path, data, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, datetime('now'))
+            RETURNING {}
+            "#,
+            ATTACHMENT_COLUMNS
+        ))
+        .bind(id)
+        .bind(card_id)
+        .bind(uploaded_by)
+        .bind(filename)
+        .bind(content_type)
+        .bind(size)
+        .bind(storage_path)
+        .bind(data)
+        .fetch_one(self.pool.as_ref())
+        .await?;
+
+        Ok(attachment)
+    }
+
+    /// Find an attachment by ID
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Option<Attachment>> {
+        let attachment = sqlx::query_as::<_, Attachment>(&format!(
+            "SELECT {} FROM attachments WHERE id = $1",
+            ATTACHMENT_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(self.pool.as_ref())
+        .await?;
+
+        Ok(attachment)
+    }
+
+    /// Get an attachment by ID or return NotFound error
+    pub async fn get_by_id(&self, id: Uuid) -> Result<Attachment> {
+        self.find_by_id(id)
+            .await?
+            .ok_or(AppError::NotFound("attachment"))
+    }
+
+    /// Content stored in the database, None for attachments stored on disk
+    pub async fn get_data(&self, id: Uuid) -> Result<Option<Vec<u8>>> {
+        let data =
+            sqlx::query_scalar::<_, Option<Vec<u8>>>("SELECT data FROM attachments WHERE id = $1")
+                .bind(id)
+                .fetch_optional(self.pool.as_ref())
+                .await?
+                .ok_or(AppError::NotFound("attachment"))?;
+
+        Ok(data)
+    }
+
+    /// List all attachments of a card, oldest first
+    pub async fn list_by_card(&self, card_id: Uuid) -> Result<Vec<AttachmentWithUploader>> {
+        let attachments = sqlx::query_as::<_, AttachmentWithUploader>(
+            r#"
+            SELECT a.id, a.card_id, a.uploaded_by, a.filename, a.content_type, a.size,
+                   a.created_at, u.name AS uploader_name
+            FROM attachments a
+            JOIN users u ON u.id = a.uploaded_by
+            WHERE a.card_id = $1
+            ORDER BY a.created_at ASC, a.rowid ASC
+            "#,
+        )
+        .bind(card_id)
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(attachments)
+    }
+
+    /// File names of a card's attachments stored on disk
+    pub async fn list_storage_paths_for_card(&self, card_id: Uuid) -> Result<Vec<String>> {
+        let paths = sqlx::query_scalar::<_, String>(
+            "SELECT storage_path FROM attachments WHERE card_id = $1 AND storage_path IS NOT NULL",
+        )
+        .bind(card_id)
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(paths)
+    }
+
+    /// Move every attachment of a card to another card
+    pub async fn move_to_card(&self, from_card_id: Uuid, to_card_id: Uuid) -> Result<u64> {
+        let mut conn = self.pool.acquire().await?;
+        Self::move_to_card_on(&mut conn, from_card_id, to_card_id).await
+    }
+
+    pub(crate) async fn move_to_card_on(
+        conn: &mut SqliteConnection,
+        from_card_id: Uuid,
+        to_card_id: Uuid,
+    ) -> Result<u64> {
+        let result = sqlx::query("UPDATE attachments SET card_id = $2 WHERE card_id = $1")
+            .bind(from_card_id)
+            .bind(to_card_id)
+            .execute(&mut *conn)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Delete an attachment
+    pub async fn delete(&self, id: Uuid) -> Result<()> {
+        let result = sqlx::query("DELETE FROM attachments WHERE id = $1")
+            .bind(id)
+            .execute(self.pool.as_ref())
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("attachment"));
+        }
+
+        Ok(())
+    }
+
+    /// Index the text of an attachment for search
+    pub async fn set_text(&self, id: Uuid, text: &str) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM attachment_text WHERE attachment_id = $1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("INSERT INTO attachment_text (text, attachment_id) VALUES ($1, $2)")
+            .bind(text)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Attachments whose text matches `terms`, a full-text query, best matches first,
+    /// on cards the user can see. Attachments of archived cards and cards in the trash
+    /// are left out.
+    pub async fn search_text(
+        &self,
+        terms: &str,
+        user_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<AttachmentTextMatch>> {
+        let matches = sqlx::query_as::<_, AttachmentTextMatch>(&format!(
+            r#"
+            SELECT a.id, a.card_id, c.title AS card_title, c.status AS card_status, a.filename,
+                   snippet(attachment_text, 0, char(2), char(3), '…', 16) AS snippet
+            FROM attachment_text t
+            JOIN attachments a ON a.id = t.attachment_id
+            JOIN cards c ON c.id = a.card_id
+            WHERE attachment_text MATCH $1 AND c.archived_at IS NULL AND c.deleted_at IS NULL
+              AND {}
+            ORDER BY rank
+            LIMIT $2
+            "#,
+            VISIBLE_TO_USER
+        ))
+        .bind(terms)
+        .bind(limit)
+        .bind(user_id)
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(matches)
+    }
+}
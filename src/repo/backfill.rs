@@ -0,0 +1,82 @@
+use sqlx::SqlitePool;
+use std::sync::Arc;
+
+use crate::error::Result;
+use crate::models::{BackfillRecord, BackfillState};
+
+#[derive(Clone)]
+pub struct BackfillRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl BackfillRepository {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+
+    pub fn pool(&self) -> &SqlitePool {
+        self.pool.as_ref()
+    }
+
+    pub async fn list(&self) -> Result<Vec<BackfillRecord>> {
+        let records = sqlx::query_as::<_, BackfillRecord>(
+            "SELECT * FROM data_backfills ORDER BY version ASC",
+        )
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(records)
+    }
+
+    /// Record that a backfill started, resetting any previous failed or interrupted run
+    pub async fn mark_started(&self, version: i64, name: &str) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO data_backfills (version, name, status, rows_affected, error, started_at, completed_at)
+            VALUES ($1, $2, $3, 0, NULL, datetime('now'), NULL)
+            ON CONFLICT(version) DO UPDATE SET
+                name = $2,
+                status = $3,
+                rows_affected = 0,
+                error = NULL,
+                started_at = datetime('now'),
+                completed_at = NULL
+            "#,
+        )
+        .bind(version)
+        .bind(name)
+        .bind(BackfillState::Running.to_string())
+        .execute(self.pool.as_ref())
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn mark_completed(&self, version: i64, rows_affected: u64) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE data_backfills
+            SET status = $2, rows_affected = $3, completed_at = datetime('now')
+            WHERE version = $1
+            "#,
+        )
+        .bind(version)
+        .bind(BackfillState::Completed.to_string())
+        .bind(rows_affected as i64)
+        .execute(self.pool.as_ref())
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn mark_failed(&self, version: i64, error: &str) -> Result<()> {
+        sqlx::query("UPDATE data_backfills SET status = $2, error = $3 WHERE version = $1")
+            .bind(version)
+            .bind(BackfillState::Failed.to_string())
+            .bind(error)
+            .execute(self.pool.as_ref())
+            .await?;
+
+        Ok(())
+    }
+}
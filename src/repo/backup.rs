@@ -0,0 +1,132 @@
+use sqlx::{Connection, SqliteConnection, SqlitePool};
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::error::{AppError, Result};
+
+/// Consistent copies of the whole database, and restoring from them
+#[derive(Clone)]
+pub struct BackupRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl BackupRepository {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+
+    /// Write a snapshot of the database to `path`, which must not exist yet
+    pub async fn snapshot(&self, path: &Path) -> Result<()> {
+        sqlx::query("VACUUM INTO $1")
+            .bind(file_uri(path, "rwc"))
+            .execute(self.pool.as_ref())
+            .await?;
+        Ok(())
+    }
+
+    /// Replace the content of every table with the one of the snapshot at `path`, in
+    /// one transaction. The snapshot must come from the same schema version. Returns
+    /// the number of rows restored.
+    pub async fn restore(&self, path: &Path) -> Result<u64> {
+        let mut conn = self.pool.acquire().await?;
+        sqlx::query("ATTACH DATABASE $1 AS snapshot")
+            .bind(file_uri(path, "ro"))
+            .execute(&mut *conn)
+            .await
+            .map_err(not_a_snapshot)?;
+
+        let result = Self::copy_snapshot(&mut conn).await;
+
+        sqlx::query("PRAGMA foreign_keys = ON")
+            .execute(&mut *conn)
+            .await?;
+        sqlx::query("DETACH DATABASE snapshot")
+            .execute(&mut *conn)
+            .await?;
+        result
+    }
+
+    async fn copy_snapshot(conn: &mut SqliteConnection) -> Result<u64> {
+        let check = sqlx::query_scalar::<_, String>("PRAGMA snapshot.quick_check")
+            .fetch_one(&mut *conn)
+            .await
+            .map_err(not_a_snapshot)?;
+        if check != "ok" {
+            return Err(AppError::Validation(format!(
+                "The snapshot is corrupted: {}",
+                check
+            )));
+        }
+
+        let snapshot_version = sqlx::query_scalar::<_, Option<i64>>(
+            "SELECT MAX(version) FROM snapshot._sqlx_migrations",
+        )
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(not_a_snapshot)?;
+        let version =
+            sqlx::query_scalar::<_, Option<i64>>("SELECT MAX(version) FROM main._sqlx_migrations")
+                .fetch_one(&mut *conn)
+                .await?;
+        if snapshot_version != version {
+            return Err(AppError::Validation(format!(
+                "The snapshot has schema version {}, the server {}",
+                snapshot_version.unwrap_or_default(),
+                version.unwrap_or_default()
+            )));
+        }
+
+        // The tables behind full-text indexes are filled through the index itself, which
+        // comes last so the triggers of the other tables do not empty it again
+        let tables = sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT name FROM pragma_table_list
+            WHERE schema = 'main' AND type IN ('table', 'virtual')
+              AND name NOT LIKE 'sqlite_%' AND name <> '_sqlx_migrations'
+            ORDER BY type = 'virtual'
+            "#,
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+
+        // Rows are copied in any table order, with the references checked by the
+        // snapshot itself
+        sqlx::query("PRAGMA foreign_keys = OFF")
+            .execute(&mut *conn)
+            .await?;
+        let mut tx = conn.begin().await?;
+        let mut rows = 0;
+        for table in &tables {
+            sqlx::query(&format!("DELETE FROM main.\"{}\"", table))
+                .execute(&mut *tx)
+                .await?;
+            rows += sqlx::query(&format!(
+                "INSERT INTO main.\"{0}\" SELECT * FROM snapshot.\"{0}\"",
+                table
+            ))
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        }
+        tx.commit().await?;
+
+        Ok(rows)
+    }
+}
+
+fn not_a_snapshot(_: sqlx::Error) -> AppError {
+    AppError::Validation("The file is not a database snapshot".to_string())
+}
+
+/// URI for the file at `path`. The explicit mode keeps SQLite from opening it in
+/// memory like the main database when that one is.
+fn file_uri(path: &Path, mode: &str) -> String {
+    let mut uri = String::from("file:");
+    for c in path.to_string_lossy().chars() {
+        match c {
+            '%' | '?' | '#' => uri.push_str(&format!("%{:02X}", c as u32)),
+            c => uri.push(c),
+        }
+    }
+    format!("{}?mode={}", uri, mode)
+}
@@ -1,10 +1,15 @@
 use chrono::{DateTime, Utc};
-use sqlx::SqlitePool;
+use sqlx::{SqliteConnection, SqlitePool};
+use std::collections::HashMap;
 use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::error::{AppError, Result};
-use crate::models::{Board, BoardPermission, BoardRole};
+use crate::models::{
+    Board, BoardPermission, BoardRole, ColumnCreationPolicy, Page, WipLimitPolicy,
+};
+use crate::repo::card::CardRepository;
+use crate::repo::CommentRepository;
 
 #[derive(Debug, sqlx::FromRow)]
 struct BoardWithRole {
@@ -12,8 +17,14 @@ struct BoardWithRole {
     name: String,
     description: Option<String>,
     owner_id: Uuid,
+    chat_column_policy: String,
     created_at: DateTime<Utc>,
     updated_at: DateTime<Utc>,
+    default_column_id: Option<Uuid>,
+    wip_limit_policy: String,
+    allow_public_links: bool,
+    workspace_id: Option<Uuid>,
+    version: i64,
     role: String,
 }
 
@@ -32,6 +43,16 @@ impl BoardRepository {
         name: &str,
         description: Option<&str>,
         owner_id: Uuid,
+    ) -> Result<Board> {
+        let mut conn = self.pool.acquire().await?;
+        Self::create_on(&mut conn, name, description, owner_id).await
+    }
+
+    pub(crate) async fn create_on(
+        conn: &mut SqliteConnection,
+        name: &str,
+        description: Option<&str>,
+        owner_id: Uuid,
     ) -> Result<Board> {
         let id = Uuid::new_v4();
 
@@ -46,41 +67,287 @@ impl BoardRepository {
         .bind(name)
         .bind(description)
         .bind(owner_id)
-        .fetch_one(self.pool.as_ref())
+        .fetch_one(&mut *conn)
         .await?;
 
         // Add owner permission
-        self.add_permission(board.id, owner_id, BoardRole::Owner)
-            .await?;
+        Self::add_permission_on(conn, board.id, owner_id, BoardRole::Owner).await?;
 
         Ok(board)
     }
 
-    pub async fn find_by_id(&self, id: Uuid) -> Result<Option<Board>> {
-        let board = sqlx::query_as::<_, Board>("SELECT * FROM boards WHERE id = $1")
+    /// Copy a board under new ids, owned by `owner_id`: its settings, columns, tags
+    /// and cards with their tags and links, and their comments when `with_comments`.
+    /// Archived and deleted items, members, assignees and attachments are not copied.
+    pub async fn duplicate(
+        &self,
+        id: Uuid,
+        name: &str,
+        owner_id: Uuid,
+        with_comments: bool,
+    ) -> Result<Board> {
+        let mut tx = self.pool.begin_with("BEGIN IMMEDIATE").await?;
+        let source =
+            sqlx::query_as::<_, Board>("SELECT * FROM boards WHERE id = $1 AND deleted_at IS NULL")
+                .bind(id)
+                .fetch_optional(&mut *tx)
+                .await?
+                .ok_or(AppError::NotFound("board"))?;
+
+        let board_id = Uuid::new_v4();
+        sqlx::query(
+            r#"
+            INSERT INTO boards (id, name, description, owner_id, chat_column_policy, wip_limit_policy, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, datetime('now'), datetime('now'))
+            "#,
+        )
+        .bind(board_id)
+        .bind(name)
+        .bind(&source.description)
+        .bind(owner_id)
+        .bind(&source.chat_column_policy)
+        .bind(&source.wip_limit_policy)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query(
+            r#"
+            INSERT INTO board_permissions (id, board_id, user_id, role, created_at)
+            VALUES ($1, $2, $3, $4, datetime('now'))
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(board_id)
+        .bind(owner_id)
+        .bind(BoardRole::Owner.to_string())
+        .execute(&mut *tx)
+        .await?;
+
+        let old_columns = sqlx::query_scalar::<_, Uuid>(
+            "SELECT id FROM columns WHERE board_id = $1 AND archived_at IS NULL AND deleted_at IS NULL ORDER BY position ASC, rowid ASC",
+        )
+        .bind(id)
+        .fetch_all(&mut *tx)
+        .await?;
+        let mut columns = HashMap::new();
+        for (position, old_id) in old_columns.iter().enumerate() {
+            let new_id = Uuid::new_v4();
+            sqlx::query(
+                r#"
+                INSERT INTO columns (id, board_id, name, position, wip_limit, sort_policy, color, description, card_status, created_at, updated_at)
+                SELECT $1, $2, name, $3, wip_limit, sort_policy, color, description, card_status, datetime('now'), datetime('now')
+                FROM columns WHERE id = $4
+                "#,
+            )
+            .bind(new_id)
+            .bind(board_id)
+            .bind(position as i32)
+            .bind(old_id)
+            .execute(&mut *tx)
+            .await?;
+            columns.insert(*old_id, new_id);
+        }
+        if let Some(default_column_id) = source.default_column_id.and_then(|c| columns.get(&c)) {
+            sqlx::query("UPDATE boards SET default_column_id = $2 WHERE id = $1")
+                .bind(board_id)
+                .bind(default_column_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        // Links to global tags are kept only when the new owner owns them
+        let old_tags = sqlx::query_scalar::<_, Uuid>("SELECT id FROM tags WHERE board_id = $1")
             .bind(id)
-            .fetch_optional(self.pool.as_ref())
+            .fetch_all(&mut *tx)
+            .await?;
+        let mut tags = HashMap::new();
+        for old_id in old_tags {
+            let new_id = Uuid::new_v4();
+            sqlx::query(
+                r#"
+                INSERT INTO tags (id, board_id, name, color, linked_tag_id, created_at)
+                SELECT $1, $2, name, color,
+                    CASE WHEN linked_tag_id IN (SELECT id FROM tags WHERE owner_id = $3)
+                        THEN linked_tag_id END,
+                    datetime('now')
+                FROM tags WHERE id = $4
+                "#,
+            )
+            .bind(new_id)
+            .bind(board_id)
+            .bind(owner_id)
+            .bind(old_id)
+            .execute(&mut *tx)
+            .await?;
+            tags.insert(old_id, new_id);
+        }
+
+        let mut cards = HashMap::new();
+        for old_column in &old_columns {
+            let old_cards = sqlx::query_scalar::<_, Uuid>(
+                "SELECT id FROM cards WHERE column_id = $1 AND archived_at IS NULL AND deleted_at IS NULL ORDER BY position ASC, rowid ASC",
+            )
+            .bind(old_column)
+            .fetch_all(&mut *tx)
+            .await?;
+            for (position, old_id) in old_cards.into_iter().enumerate() {
+                let copy = CardRepository::copy_on(
+                    &mut tx,
+                    old_id,
+                    Some(columns[old_column]),
+                    None,
+                    position as i32,
+                    owner_id,
+                )
+                .await?;
+                cards.insert(old_id, copy.id);
+            }
+        }
+
+        for (old_id, new_id) in &cards {
+            let card_tags =
+                sqlx::query_scalar::<_, Uuid>("SELECT tag_id FROM card_tags WHERE card_id = $1")
+                    .bind(old_id)
+                    .fetch_all(&mut *tx)
+                    .await?;
+            for tag_id in card_tags.iter().filter_map(|t| tags.get(t)) {
+                sqlx::query(
+                    "INSERT INTO card_tags (card_id, tag_id, created_at) VALUES ($1, $2, datetime('now'))",
+                )
+                .bind(new_id)
+                .bind(tag_id)
+                .execute(&mut *tx)
+                .await?;
+            }
+
+            let links = sqlx::query_as::<_, (Uuid, String)>(
+                "SELECT target_card_id, link_type FROM card_links WHERE source_card_id = $1",
+            )
+            .bind(old_id)
+            .fetch_all(&mut *tx)
+            .await?;
+            for (target, link_type) in links {
+                let Some(target) = cards.get(&target) else {
+                    continue;
+                };
+                sqlx::query(
+                    r#"
+                    INSERT INTO card_links (id, source_card_id, target_card_id, link_type, created_by, created_at)
+                    VALUES ($1, $2, $3, $4, $5, datetime('now'))
+                    "#,
+                )
+                .bind(Uuid::new_v4())
+                .bind(new_id)
+                .bind(target)
+                .bind(link_type)
+                .bind(owner_id)
+                .execute(&mut *tx)
+                .await?;
+            }
+
+            if with_comments {
+                CommentRepository::copy_on(&mut tx, *old_id, *new_id).await?;
+            }
+        }
+
+        let board = sqlx::query_as::<_, Board>("SELECT * FROM boards WHERE id = $1")
+            .bind(board_id)
+            .fetch_one(&mut *tx)
             .await?;
+        tx.commit().await?;
+        Ok(board)
+    }
+
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Option<Board>> {
+        let mut conn = self.pool.acquire().await?;
+        Self::find_on(&mut conn, id).await
+    }
+
+    pub(crate) async fn find_on(conn: &mut SqliteConnection, id: Uuid) -> Result<Option<Board>> {
+        let board =
+            sqlx::query_as::<_, Board>("SELECT * FROM boards WHERE id = $1 AND deleted_at IS NULL")
+                .bind(id)
+                .fetch_optional(&mut *conn)
+                .await?;
 
         Ok(board)
     }
 
     pub async fn get_by_id(&self, id: Uuid) -> Result<Board> {
-        self.find_by_id(id).await?.ok_or(AppError::NotFound)
+        self.find_by_id(id)
+            .await?
+            .ok_or(AppError::NotFound("board"))
+    }
+
+    pub(crate) async fn get_on(conn: &mut SqliteConnection, id: Uuid) -> Result<Board> {
+        Self::find_on(conn, id)
+            .await?
+            .ok_or(AppError::NotFound("board"))
+    }
+
+    /// Whether the card is on a board, through a column or an assignment, that allows
+    /// public links
+    pub async fn allows_public_link(&self, card_id: Uuid) -> Result<bool> {
+        let allowed = sqlx::query_scalar::<_, bool>(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM boards b
+                WHERE b.allow_public_links = 1 AND b.deleted_at IS NULL
+                  AND (b.id IN (SELECT board_id FROM card_boards WHERE card_id = $1)
+                       OR b.id = (
+                           SELECT col.board_id FROM cards c
+                           INNER JOIN columns col ON col.id = c.column_id
+                           WHERE c.id = $1
+                       ))
+            )
+            "#,
+        )
+        .bind(card_id)
+        .fetch_one(self.pool.as_ref())
+        .await?;
+
+        Ok(allowed)
     }
 
     pub async fn list_for_user(&self, user_id: Uuid) -> Result<Vec<(Board, String)>> {
+        self.list_page_for_user(user_id, Page::ALL).await
+    }
+
+    pub(crate) async fn list_for_user_on(
+        conn: &mut SqliteConnection,
+        user_id: Uuid,
+    ) -> Result<Vec<(Board, String)>> {
+        Self::list_page_for_user_on(conn, user_id, Page::ALL).await
+    }
+
+    /// Boards the user is a member of with their role, most recently updated first
+    pub async fn list_page_for_user(
+        &self,
+        user_id: Uuid,
+        page: Page,
+    ) -> Result<Vec<(Board, String)>> {
+        let mut conn = self.pool.acquire().await?;
+        Self::list_page_for_user_on(&mut conn, user_id, page).await
+    }
+
+    pub(crate) async fn list_page_for_user_on(
+        conn: &mut SqliteConnection,
+        user_id: Uuid,
+        page: Page,
+    ) -> Result<Vec<(Board, String)>> {
         let rows = sqlx::query_as::<_, BoardWithRole>(
             r#"
-            SELECT b.id, b.name, b.description, b.owner_id, b.created_at, b.updated_at, bp.role
+            SELECT b.id, b.name, b.description, b.owner_id, b.chat_column_policy, b.created_at, b.updated_at, b.default_column_id, b.wip_limit_policy, b.allow_public_links, b.workspace_id, b.version, bp.role
             FROM boards b
-            INNER JOIN board_permissions bp ON b.id = bp.board_id
-            WHERE bp.user_id = $1
-            ORDER BY b.updated_at DESC
+            INNER JOIN board_access bp ON b.id = bp.board_id
+            WHERE bp.user_id = $1 AND b.deleted_at IS NULL
+            ORDER BY b.updated_at DESC, b.id ASC
+            LIMIT $2 OFFSET $3
             "#,
         )
         .bind(user_id)
-        .fetch_all(self.pool.as_ref())
+        .bind(page.limit)
+        .bind(page.offset)
+        .fetch_all(&mut *conn)
         .await?;
 
         let boards = rows
@@ -92,8 +359,14 @@ impl BoardRepository {
                         name: r.name,
                         description: r.description,
                         owner_id: r.owner_id,
+                        chat_column_policy: r.chat_column_policy,
                         created_at: r.created_at,
                         updated_at: r.updated_at,
+                        default_column_id: r.default_column_id,
+                        wip_limit_policy: r.wip_limit_policy,
+                        allow_public_links: r.allow_public_links,
+                        workspace_id: r.workspace_id,
+                        version: r.version,
                     },
                     r.role,
                 )
@@ -103,29 +376,68 @@ impl BoardRepository {
         Ok(boards)
     }
 
+    pub async fn count_for_user(&self, user_id: Uuid) -> Result<i64> {
+        let count = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(*) FROM boards b
+            INNER JOIN board_access bp ON b.id = bp.board_id
+            WHERE bp.user_id = $1 AND b.deleted_at IS NULL
+            "#,
+        )
+        .bind(user_id)
+        .fetch_one(self.pool.as_ref())
+        .await?;
+
+        Ok(count)
+    }
+
+    /// Update the board's settings; `None` leaves a field unchanged. With an
+    /// `expected_version`, a board that has changed since that version is left
+    /// alone and the update fails with `PreconditionFailed`.
+    #[allow(clippy::too_many_arguments)]
     pub async fn update(
         &self,
         id: Uuid,
         name: Option<&str>,
         description: Option<&str>,
+        chat_column_policy: Option<ColumnCreationPolicy>,
+        default_column_id: Option<Option<Uuid>>,
+        wip_limit_policy: Option<WipLimitPolicy>,
+        allow_public_links: Option<bool>,
+        expected_version: Option<i64>,
     ) -> Result<Board> {
         let board = sqlx::query_as::<_, Board>(
             r#"
             UPDATE boards
             SET name = COALESCE($2, name),
                 description = COALESCE($3, description),
+                chat_column_policy = COALESCE($4, chat_column_policy),
+                default_column_id = CASE WHEN $5 THEN $6 ELSE default_column_id END,
+                wip_limit_policy = COALESCE($7, wip_limit_policy),
+                allow_public_links = COALESCE($8, allow_public_links),
+                version = version + 1,
                 updated_at = datetime('now')
-            WHERE id = $1
+            WHERE id = $1 AND ($9 IS NULL OR version = $9)
             RETURNING *
             "#,
         )
         .bind(id)
         .bind(name)
         .bind(description)
-        .fetch_one(self.pool.as_ref())
+        .bind(chat_column_policy.map(|p| p.to_string()))
+        .bind(default_column_id.is_some())
+        .bind(default_column_id.flatten())
+        .bind(wip_limit_policy.map(|p| p.to_string()))
+        .bind(allow_public_links)
+        .bind(expected_version)
+        .fetch_optional(self.pool.as_ref())
         .await?;
 
-        Ok(board)
+        match board {
+            Some(board) => Ok(board),
+            None if self.find_by_id(id).await?.is_some() => Err(AppError::PreconditionFailed),
+            None => Err(AppError::NotFound("board")),
+        }
     }
 
     pub async fn delete(&self, id: Uuid) -> Result<()> {
@@ -135,13 +447,148 @@ impl BoardRepository {
             .await?;
 
         if result.rows_affected() == 0 {
-            return Err(AppError::NotFound);
+            return Err(AppError::NotFound("board"));
+        }
+
+        Ok(())
+    }
+
+    /// Move a board with everything on it to the trash
+    pub async fn move_to_trash(&self, id: Uuid, deleted_by: Uuid) -> Result<()> {
+        let mut conn = self.pool.acquire().await?;
+        Self::move_to_trash_on(&mut conn, id, deleted_by).await
+    }
+
+    pub(crate) async fn move_to_trash_on(
+        conn: &mut SqliteConnection,
+        id: Uuid,
+        deleted_by: Uuid,
+    ) -> Result<()> {
+        let result = sqlx::query(
+            "UPDATE boards SET deleted_at = datetime('now'), deleted_by = $2 WHERE id = $1 AND deleted_at IS NULL",
+        )
+        .bind(id)
+        .bind(deleted_by)
+        .execute(&mut *conn)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("board"));
         }
 
         Ok(())
     }
 
+    /// A board in the trash
+    pub async fn find_deleted(&self, id: Uuid) -> Result<Option<Board>> {
+        let mut conn = self.pool.acquire().await?;
+        Self::find_deleted_on(&mut conn, id).await
+    }
+
+    pub(crate) async fn find_deleted_on(
+        conn: &mut SqliteConnection,
+        id: Uuid,
+    ) -> Result<Option<Board>> {
+        let board = sqlx::query_as::<_, Board>(
+            "SELECT * FROM boards WHERE id = $1 AND deleted_at IS NOT NULL",
+        )
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await?;
+
+        Ok(board)
+    }
+
+    /// Take a board out of the trash
+    pub async fn restore(&self, id: Uuid) -> Result<Board> {
+        let mut conn = self.pool.acquire().await?;
+        Self::restore_on(&mut conn, id).await
+    }
+
+    pub(crate) async fn restore_on(conn: &mut SqliteConnection, id: Uuid) -> Result<Board> {
+        let board = sqlx::query_as::<_, Board>(
+            r#"
+            UPDATE boards
+            SET deleted_at = NULL, deleted_by = NULL, updated_at = datetime('now')
+            WHERE id = $1 AND deleted_at IS NOT NULL
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await?
+        .ok_or(AppError::NotFound("board"))?;
+
+        Ok(board)
+    }
+
+    /// Move the board into a workspace, or out of any with `None`
+    pub async fn set_workspace(&self, id: Uuid, workspace_id: Option<Uuid>) -> Result<Board> {
+        let board = sqlx::query_as::<_, Board>(
+            r#"
+            UPDATE boards SET workspace_id = $2, updated_at = datetime('now')
+            WHERE id = $1 AND deleted_at IS NULL
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(workspace_id)
+        .fetch_optional(self.pool.as_ref())
+        .await?
+        .ok_or(AppError::NotFound("board"))?;
+
+        Ok(board)
+    }
+
+    /// Boards of a workspace, by name
+    pub async fn list_for_workspace(&self, workspace_id: Uuid) -> Result<Vec<Board>> {
+        let boards = sqlx::query_as::<_, Board>(
+            r#"
+            SELECT * FROM boards
+            WHERE workspace_id = $1 AND deleted_at IS NULL
+            ORDER BY name ASC, id ASC
+            "#,
+        )
+        .bind(workspace_id)
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(boards)
+    }
+
+    /// The user's role on the board: their own permission, or else their role in the
+    /// board's workspace
     pub async fn get_user_role(&self, board_id: Uuid, user_id: Uuid) -> Result<Option<BoardRole>> {
+        let mut conn = self.pool.acquire().await?;
+        Self::get_user_role_on(&mut conn, board_id, user_id).await
+    }
+
+    pub(crate) async fn get_user_role_on(
+        conn: &mut SqliteConnection,
+        board_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<BoardRole>> {
+        let role = sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT bp.role FROM board_access bp
+            INNER JOIN boards b ON b.id = bp.board_id
+            WHERE bp.board_id = $1 AND bp.user_id = $2 AND b.deleted_at IS NULL
+            "#,
+        )
+        .bind(board_id)
+        .bind(user_id)
+        .fetch_optional(&mut *conn)
+        .await?;
+
+        Ok(role.and_then(|r| r.parse().ok()))
+    }
+
+    /// The role the board itself gives the user, not counting their workspace role
+    pub async fn find_permission(
+        &self,
+        board_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<BoardRole>> {
         let role = sqlx::query_scalar::<_, String>(
             "SELECT role FROM board_permissions WHERE board_id = $1 AND user_id = $2",
         )
@@ -158,6 +605,16 @@ impl BoardRepository {
         board_id: Uuid,
         user_id: Uuid,
         role: BoardRole,
+    ) -> Result<BoardPermission> {
+        let mut conn = self.pool.acquire().await?;
+        Self::add_permission_on(&mut conn, board_id, user_id, role).await
+    }
+
+    pub(crate) async fn add_permission_on(
+        conn: &mut SqliteConnection,
+        board_id: Uuid,
+        user_id: Uuid,
+        role: BoardRole,
     ) -> Result<BoardPermission> {
         let id = Uuid::new_v4();
 
@@ -173,7 +630,7 @@ impl BoardRepository {
         .bind(board_id)
         .bind(user_id)
         .bind(role.to_string())
-        .fetch_one(self.pool.as_ref())
+        .fetch_one(&mut *conn)
         .await?;
 
         Ok(permission)
@@ -197,6 +654,52 @@ impl BoardRepository {
         Ok(())
     }
 
+    /// Make another user the owner of a board. The previous owner stays on it as an
+    /// editor.
+    pub async fn transfer_ownership(&self, board_id: Uuid, new_owner_id: Uuid) -> Result<Board> {
+        let mut tx = self.pool.begin().await?;
+
+        let board = sqlx::query_as::<_, Board>(
+            r#"
+            UPDATE boards SET owner_id = $2, updated_at = datetime('now')
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(board_id)
+        .bind(new_owner_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(AppError::NotFound("board"))?;
+
+        sqlx::query(
+            "UPDATE board_permissions SET role = $2 WHERE board_id = $1 AND role = $3 AND user_id != $4",
+        )
+        .bind(board_id)
+        .bind(BoardRole::Editor.to_string())
+        .bind(BoardRole::Owner.to_string())
+        .bind(new_owner_id)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO board_permissions (id, board_id, user_id, role, created_at)
+            VALUES ($1, $2, $3, $4, datetime('now'))
+            ON CONFLICT(board_id, user_id) DO UPDATE SET role = $4
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(board_id)
+        .bind(new_owner_id)
+        .bind(BoardRole::Owner.to_string())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(board)
+    }
+
     pub async fn list_permissions(&self, board_id: Uuid) -> Result<Vec<BoardPermission>> {
         let permissions = sqlx::query_as::<_, BoardPermission>(
             "SELECT * FROM board_permissions WHERE board_id = $1",
@@ -0,0 +1,197 @@
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::models::{BoardGroup, BoardPreference};
+
+/// Each user's own arrangement of their boards: stars, groups and order
+#[derive(Clone)]
+pub struct BoardPreferenceRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl BoardPreferenceRepository {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+
+    /// The user's groups, in their order
+    pub async fn list_groups(&self, user_id: Uuid) -> Result<Vec<BoardGroup>> {
+        let groups = sqlx::query_as::<_, BoardGroup>(
+            r#"
+            SELECT id, name, position, created_at FROM board_groups
+            WHERE user_id = $1
+            ORDER BY position ASC, name ASC
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(groups)
+    }
+
+    /// Create a group after the user's other groups
+    pub async fn create_group(&self, user_id: Uuid, name: &str) -> Result<BoardGroup> {
+        let group = sqlx::query_as::<_, BoardGroup>(
+            r#"
+            INSERT INTO board_groups (id, user_id, name, position, created_at)
+            VALUES ($1, $2, $3,
+                    (SELECT COALESCE(MAX(position) + 1, 0) FROM board_groups WHERE user_id = $2),
+                    datetime('now'))
+            RETURNING id, name, position, created_at
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(user_id)
+        .bind(name)
+        .fetch_one(self.pool.as_ref())
+        .await?;
+
+        Ok(group)
+    }
+
+    /// Rename or move one of the user's groups; `None` leaves a field unchanged
+    pub async fn update_group(
+        &self,
+        user_id: Uuid,
+        id: Uuid,
+        name: Option<&str>,
+        position: Option<i64>,
+    ) -> Result<BoardGroup> {
+        sqlx::query_as::<_, BoardGroup>(
+            r#"
+            UPDATE board_groups
+            SET name = COALESCE($3, name), position = COALESCE($4, position)
+            WHERE id = $2 AND user_id = $1
+            RETURNING id, name, position, created_at
+            "#,
+        )
+        .bind(user_id)
+        .bind(id)
+        .bind(name)
+        .bind(position)
+        .fetch_optional(self.pool.as_ref())
+        .await?
+        .ok_or(AppError::NotFound("board_group"))
+    }
+
+    /// Delete one of the user's groups. Its boards stay, outside any group.
+    pub async fn delete_group(&self, user_id: Uuid, id: Uuid) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(
+            "UPDATE board_preferences SET group_id = NULL WHERE user_id = $1 AND group_id = $2",
+        )
+        .bind(user_id)
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+        let result = sqlx::query("DELETE FROM board_groups WHERE id = $2 AND user_id = $1")
+            .bind(user_id)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("board_group"));
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Whether the group exists and belongs to the user
+    pub async fn owns_group(&self, user_id: Uuid, id: Uuid) -> Result<bool> {
+        let owned = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS(SELECT 1 FROM board_groups WHERE id = $2 AND user_id = $1)",
+        )
+        .bind(user_id)
+        .bind(id)
+        .fetch_one(self.pool.as_ref())
+        .await?;
+
+        Ok(owned)
+    }
+
+    /// The user's preferences for the boards they arranged in some way
+    pub async fn list_for_user(&self, user_id: Uuid) -> Result<Vec<BoardPreference>> {
+        let preferences = sqlx::query_as::<_, BoardPreference>(
+            r#"
+            SELECT board_id, starred, group_id, position FROM board_preferences
+            WHERE user_id = $1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(preferences)
+    }
+
+    pub async fn find(&self, user_id: Uuid, board_id: Uuid) -> Result<Option<BoardPreference>> {
+        let preference = sqlx::query_as::<_, BoardPreference>(
+            r#"
+            SELECT board_id, starred, group_id, position FROM board_preferences
+            WHERE user_id = $1 AND board_id = $2
+            "#,
+        )
+        .bind(user_id)
+        .bind(board_id)
+        .fetch_optional(self.pool.as_ref())
+        .await?;
+
+        Ok(preference)
+    }
+
+    /// Star or unstar a board and move it between groups; `None` leaves a field
+    /// unchanged, `Some(None)` takes the board out of its group
+    pub async fn update(
+        &self,
+        user_id: Uuid,
+        board_id: Uuid,
+        starred: Option<bool>,
+        group_id: Option<Option<Uuid>>,
+    ) -> Result<BoardPreference> {
+        let preference = sqlx::query_as::<_, BoardPreference>(
+            r#"
+            INSERT INTO board_preferences (user_id, board_id, starred, group_id)
+            VALUES ($1, $2, COALESCE($3, 0), $5)
+            ON CONFLICT(user_id, board_id) DO UPDATE SET
+                starred = COALESCE($3, starred),
+                group_id = CASE WHEN $4 THEN $5 ELSE group_id END
+            RETURNING board_id, starred, group_id, position
+            "#,
+        )
+        .bind(user_id)
+        .bind(board_id)
+        .bind(starred)
+        .bind(group_id.is_some())
+        .bind(group_id.flatten())
+        .fetch_one(self.pool.as_ref())
+        .await?;
+
+        Ok(preference)
+    }
+
+    /// Put the boards in this order, before the boards without a position
+    pub async fn set_order(&self, user_id: Uuid, board_ids: &[Uuid]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        for (position, board_id) in board_ids.iter().enumerate() {
+            sqlx::query(
+                r#"
+                INSERT INTO board_preferences (user_id, board_id, position)
+                VALUES ($1, $2, $3)
+                ON CONFLICT(user_id, board_id) DO UPDATE SET position = $3
+                "#,
+            )
+            .bind(user_id)
+            .bind(board_id)
+            .bind(position as i64)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,84 @@
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::models::{BoardLayout, BoardTemplate};
+
+#[derive(Clone)]
+pub struct BoardTemplateRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl BoardTemplateRepository {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(
+        &self,
+        owner_id: Uuid,
+        name: &str,
+        description: Option<&str>,
+        layout: &BoardLayout,
+    ) -> Result<BoardTemplate> {
+        let columns = serde_json::to_string(&layout.columns)
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+        let tags =
+            serde_json::to_string(&layout.tags).map_err(|e| AppError::Internal(e.to_string()))?;
+
+        let template = sqlx::query_as::<_, BoardTemplate>(
+            r#"
+            INSERT INTO board_templates (id, owner_id, name, description, columns, tags, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, datetime('now'))
+            RETURNING *
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(owner_id)
+        .bind(name)
+        .bind(description)
+        .bind(columns)
+        .bind(tags)
+        .fetch_one(self.pool.as_ref())
+        .await?;
+
+        Ok(template)
+    }
+
+    /// A template of this user
+    pub async fn find_for_owner(&self, id: Uuid, owner_id: Uuid) -> Result<Option<BoardTemplate>> {
+        let template = sqlx::query_as::<_, BoardTemplate>(
+            "SELECT * FROM board_templates WHERE id = $1 AND owner_id = $2",
+        )
+        .bind(id)
+        .bind(owner_id)
+        .fetch_optional(self.pool.as_ref())
+        .await?;
+
+        Ok(template)
+    }
+
+    /// Templates of a user, by name
+    pub async fn list_for_owner(&self, owner_id: Uuid) -> Result<Vec<BoardTemplate>> {
+        let templates = sqlx::query_as::<_, BoardTemplate>(
+            "SELECT * FROM board_templates WHERE owner_id = $1 ORDER BY name COLLATE NOCASE ASC, rowid ASC",
+        )
+        .bind(owner_id)
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(templates)
+    }
+
+    /// Delete a template of this user, returning whether it existed
+    pub async fn delete(&self, id: Uuid, owner_id: Uuid) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM board_templates WHERE id = $1 AND owner_id = $2")
+            .bind(id)
+            .bind(owner_id)
+            .execute(self.pool.as_ref())
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
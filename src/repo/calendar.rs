@@ -0,0 +1,113 @@
+use chrono::NaiveDate;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::error::Result;
+use crate::models::Card;
+
+#[derive(Clone)]
+pub struct CalendarRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl CalendarRepository {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+
+    /// Set the user's feed token, replacing the previous one
+    pub async fn set_feed_token(&self, user_id: Uuid, token_hash: &str) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO calendar_feeds (user_id, token_hash, created_at)
+            VALUES ($1, $2, datetime('now'))
+            ON CONFLICT(user_id) DO UPDATE SET token_hash = excluded.token_hash, created_at = excluded.created_at
+            "#,
+        )
+        .bind(user_id)
+        .bind(token_hash)
+        .execute(self.pool.as_ref())
+        .await?;
+
+        Ok(())
+    }
+
+    /// Remove the user's feed. Returns whether there was one.
+    pub async fn delete_feed(&self, user_id: Uuid) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM calendar_feeds WHERE user_id = $1")
+            .bind(user_id)
+            .execute(self.pool.as_ref())
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn has_feed(&self, user_id: Uuid) -> Result<bool> {
+        let exists = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS(SELECT 1 FROM calendar_feeds WHERE user_id = $1)",
+        )
+        .bind(user_id)
+        .fetch_one(self.pool.as_ref())
+        .await?;
+
+        Ok(exists)
+    }
+
+    pub async fn find_user_by_feed_token(&self, token_hash: &str) -> Result<Option<Uuid>> {
+        let user_id = sqlx::query_scalar::<_, Uuid>(
+            "SELECT user_id FROM calendar_feeds WHERE token_hash = $1",
+        )
+        .bind(token_hash)
+        .fetch_optional(self.pool.as_ref())
+        .await?;
+
+        Ok(user_id)
+    }
+
+    /// Cards the user can see with a start, end or due date, by the same rules as
+    /// card access: their own cards and cards on boards they are a member of.
+    /// With `from` and `to`, only cards with a date in that range are returned.
+    /// Archived and deleted cards, and cards in archived columns, are left out.
+    pub async fn list_dated_cards(
+        &self,
+        user_id: Uuid,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> Result<Vec<Card>> {
+        let cards = sqlx::query_as::<_, Card>(
+            r#"
+            SELECT c.* FROM cards c
+            WHERE c.archived_at IS NULL AND c.deleted_at IS NULL
+              AND (c.start_date IS NOT NULL OR c.end_date IS NOT NULL OR c.due_date IS NOT NULL)
+              AND (c.column_id IS NULL
+                   OR c.column_id IN (
+                       SELECT col.id FROM columns col
+                       INNER JOIN boards b ON b.id = col.board_id
+                       WHERE col.archived_at IS NULL AND col.deleted_at IS NULL
+                         AND b.deleted_at IS NULL
+                   ))
+              AND (c.owner_id = $1 OR c.created_by = $1
+                   OR EXISTS (
+                       SELECT 1 FROM board_access bp
+                       INNER JOIN boards b ON b.id = bp.board_id
+                       WHERE bp.user_id = $1 AND b.deleted_at IS NULL
+                         AND (bp.board_id IN (SELECT cb.board_id FROM card_boards cb WHERE cb.card_id = c.id)
+                              OR bp.board_id = (SELECT col.board_id FROM columns col WHERE col.id = c.column_id))
+                   ))
+              AND ($2 IS NULL OR COALESCE(c.end_date, c.due_date, c.start_date) >= $2
+                   OR c.due_date >= $2)
+              AND ($3 IS NULL OR COALESCE(c.start_date, c.end_date, c.due_date) <= $3
+                   OR c.due_date <= $3)
+            ORDER BY COALESCE(c.due_date, c.start_date, c.end_date) ASC, c.title ASC
+            "#,
+        )
+        .bind(user_id)
+        .bind(from)
+        .bind(to)
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(cards)
+    }
+}
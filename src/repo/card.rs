@@ -1,10 +1,56 @@
-use chrono::NaiveDate;
-use sqlx::SqlitePool;
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::{QueryBuilder, Sqlite, SqliteConnection, SqlitePool};
 use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::error::{AppError, Result};
-use crate::models::{Card, CardFilter, CardStatus, CardVisibility};
+use crate::models::{
+    BulkCardOperation, Card, CardFilter, CardPosition, CardPriority, CardStatus, CardVisibility,
+    Page,
+};
+use crate::repo::CommentRepository;
+
+/// Order of the cards within a column (aliased `col`) according to its sort policy
+const COLUMN_CARD_ORDER: &str = r#"
+    CASE WHEN col.sort_policy = 'due_date' THEN c.due_date IS NULL END,
+    CASE WHEN col.sort_policy = 'due_date' THEN c.due_date END,
+    CASE WHEN col.sort_policy = 'updated_at' THEN c.updated_at END DESC,
+    CASE WHEN col.sort_policy = 'priority' THEN
+        CASE c.priority WHEN 'urgent' THEN 0 WHEN 'high' THEN 1 WHEN 'medium' THEN 2 WHEN 'low' THEN 3 ELSE 4 END
+    END,
+    cb.position ASC
+"#;
+
+/// Whether the user bound as `$3` can see the card (aliased `c`): they own or created
+/// it, or it is on a board they have a role on, as `can_view_card` checks
+pub(crate) const VISIBLE_TO_USER: &str = r#"
+    (c.owner_id = $3 OR c.created_by = $3 OR EXISTS (
+        SELECT 1 FROM card_boards vcb
+        INNER JOIN board_access va ON va.board_id = vcb.board_id
+        INNER JOIN boards vb ON vb.id = vcb.board_id
+        WHERE vcb.card_id = c.id AND va.user_id = $3 AND vb.deleted_at IS NULL
+    ))
+"#;
+
+/// A card with its placement on the board it was listed for, which differs from its
+/// home column for cards assigned to several boards
+#[derive(sqlx::FromRow)]
+struct PlacedCard {
+    #[sqlx(flatten)]
+    card: Card,
+    placed_column_id: Option<Uuid>,
+    placed_position: i32,
+}
+
+impl From<PlacedCard> for Card {
+    fn from(placed: PlacedCard) -> Self {
+        Card {
+            column_id: placed.placed_column_id,
+            position: placed.placed_position,
+            ..placed.card
+        }
+    }
+}
 
 #[derive(Clone)]
 pub struct CardRepository {
@@ -28,6 +74,30 @@ impl CardRepository {
         start_date: Option<NaiveDate>,
         end_date: Option<NaiveDate>,
         due_date: Option<NaiveDate>,
+        priority: Option<CardPriority>,
+        created_by: Uuid,
+    ) -> Result<Card> {
+        let mut conn = self.pool.acquire().await?;
+        Self::create_on(
+            &mut conn, column_id, title, body, position, visibility, status, start_date, end_date,
+            due_date, priority, created_by,
+        )
+        .await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) async fn create_on(
+        conn: &mut SqliteConnection,
+        column_id: Uuid,
+        title: &str,
+        body: Option<&str>,
+        position: Option<i32>,
+        visibility: CardVisibility,
+        status: CardStatus,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+        due_date: Option<NaiveDate>,
+        priority: Option<CardPriority>,
         created_by: Uuid,
     ) -> Result<Card> {
         let id = Uuid::new_v4();
@@ -39,7 +109,7 @@ impl CardRepository {
                     "SELECT MAX(position) FROM cards WHERE column_id = $1",
                 )
                 .bind(column_id)
-                .fetch_one(self.pool.as_ref())
+                .fetch_one(&mut *conn)
                 .await?;
                 max_pos.unwrap_or(-1) + 1
             }
@@ -47,8 +117,8 @@ impl CardRepository {
 
         let card = sqlx::query_as::<_, Card>(
             r#"
-            INSERT INTO cards (id, column_id, title, body, position, visibility, status, start_date, end_date, due_date, owner_id, created_by, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $11, datetime('now'), datetime('now'))
+            INSERT INTO cards (id, column_id, title, body, position, visibility, status, start_date, end_date, due_date, priority, owner_id, created_by, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $12, datetime('now'), datetime('now'))
             RETURNING *
             "#,
         )
@@ -62,8 +132,9 @@ impl CardRepository {
         .bind(start_date)
         .bind(end_date)
         .bind(due_date)
+        .bind(priority.map(|p| p.to_string()))
         .bind(created_by)
-        .fetch_one(self.pool.as_ref())
+        .fetch_one(&mut *conn)
         .await?;
 
         Ok(card)
@@ -80,14 +151,36 @@ impl CardRepository {
         start_date: Option<NaiveDate>,
         end_date: Option<NaiveDate>,
         due_date: Option<NaiveDate>,
+        priority: Option<CardPriority>,
+        owner_id: Uuid,
+    ) -> Result<Card> {
+        let mut conn = self.pool.acquire().await?;
+        Self::create_standalone_on(
+            &mut conn, title, body, visibility, status, start_date, end_date, due_date, priority,
+            owner_id,
+        )
+        .await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) async fn create_standalone_on(
+        conn: &mut SqliteConnection,
+        title: &str,
+        body: Option<&str>,
+        visibility: CardVisibility,
+        status: CardStatus,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+        due_date: Option<NaiveDate>,
+        priority: Option<CardPriority>,
         owner_id: Uuid,
     ) -> Result<Card> {
         let id = Uuid::new_v4();
 
         let card = sqlx::query_as::<_, Card>(
             r#"
-            INSERT INTO cards (id, column_id, title, body, position, visibility, status, start_date, end_date, due_date, owner_id, created_by, created_at, updated_at)
-            VALUES ($1, NULL, $2, $3, 0, $4, $5, $6, $7, $8, $9, $9, datetime('now'), datetime('now'))
+            INSERT INTO cards (id, column_id, title, body, position, visibility, status, start_date, end_date, due_date, priority, owner_id, created_by, created_at, updated_at)
+            VALUES ($1, NULL, $2, $3, 0, $4, $5, $6, $7, $8, $9, $10, $10, datetime('now'), datetime('now'))
             RETURNING *
             "#,
         )
@@ -99,8 +192,9 @@ impl CardRepository {
         .bind(start_date)
         .bind(end_date)
         .bind(due_date)
+        .bind(priority.map(|p| p.to_string()))
         .bind(owner_id)
-        .fetch_one(self.pool.as_ref())
+        .fetch_one(&mut *conn)
         .await?;
 
         Ok(card)
@@ -108,10 +202,42 @@ impl CardRepository {
 
     /// List all cards owned by a user (standalone cards)
     pub async fn list_by_owner(&self, owner_id: Uuid) -> Result<Vec<Card>> {
+        let mut conn = self.pool.acquire().await?;
+        Self::list_by_owner_on(&mut conn, owner_id).await
+    }
+
+    pub(crate) async fn list_by_owner_on(
+        conn: &mut SqliteConnection,
+        owner_id: Uuid,
+    ) -> Result<Vec<Card>> {
         let cards = sqlx::query_as::<_, Card>(
-            "SELECT * FROM cards WHERE owner_id = $1 ORDER BY updated_at DESC",
+            "SELECT * FROM cards WHERE owner_id = $1 AND archived_at IS NULL AND deleted_at IS NULL ORDER BY updated_at DESC",
         )
         .bind(owner_id)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        Ok(cards)
+    }
+
+    /// Cards the user can see whose title or body contains `text`, most recently
+    /// updated first, apart from archived cards and cards in the trash
+    pub async fn search_text(&self, text: &str, user_id: Uuid, limit: i64) -> Result<Vec<Card>> {
+        let pattern = format!("%{}%", text);
+        let cards = sqlx::query_as::<_, Card>(&format!(
+            r#"
+            SELECT c.* FROM cards c
+            WHERE (c.title LIKE $1 OR c.body LIKE $1)
+              AND c.archived_at IS NULL AND c.deleted_at IS NULL
+              AND {}
+            ORDER BY c.updated_at DESC, c.id ASC
+            LIMIT $2
+            "#,
+            VISIBLE_TO_USER
+        ))
+        .bind(pattern)
+        .bind(limit)
+        .bind(user_id)
         .fetch_all(self.pool.as_ref())
         .await?;
 
@@ -124,189 +250,395 @@ impl CardRepository {
         owner_id: Uuid,
         status: Option<CardStatus>,
     ) -> Result<Vec<Card>> {
-        if let Some(s) = status {
-            let cards = sqlx::query_as::<_, Card>(
-                "SELECT * FROM cards WHERE owner_id = $1 AND status = $2 ORDER BY updated_at DESC",
-            )
-            .bind(owner_id)
-            .bind(s.to_string())
-            .fetch_all(self.pool.as_ref())
-            .await?;
-            Ok(cards)
-        } else {
-            self.list_by_owner(owner_id).await
-        }
+        self.list_page_by_owner(owner_id, status, Page::ALL).await
+    }
+
+    /// Cards of the owner, most recently updated first, optionally with one status
+    pub async fn list_page_by_owner(
+        &self,
+        owner_id: Uuid,
+        status: Option<CardStatus>,
+        page: Page,
+    ) -> Result<Vec<Card>> {
+        let cards = sqlx::query_as::<_, Card>(
+            r#"
+            SELECT * FROM cards
+            WHERE owner_id = $1 AND ($2 IS NULL OR status = $2)
+              AND archived_at IS NULL AND deleted_at IS NULL
+            ORDER BY updated_at DESC, id ASC
+            LIMIT $3 OFFSET $4
+            "#,
+        )
+        .bind(owner_id)
+        .bind(status.map(|s| s.to_string()))
+        .bind(page.limit)
+        .bind(page.offset)
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(cards)
+    }
+
+    pub async fn count_by_owner(&self, owner_id: Uuid, status: Option<CardStatus>) -> Result<i64> {
+        let count = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(*) FROM cards
+            WHERE owner_id = $1 AND ($2 IS NULL OR status = $2)
+              AND archived_at IS NULL AND deleted_at IS NULL
+            "#,
+        )
+        .bind(owner_id)
+        .bind(status.map(|s| s.to_string()))
+        .fetch_one(self.pool.as_ref())
+        .await?;
+
+        Ok(count)
     }
 
     /// Update card status
     pub async fn update_status(&self, id: Uuid, status: CardStatus) -> Result<Card> {
+        let mut conn = self.pool.acquire().await?;
+        Self::update_status_on(&mut conn, id, status).await
+    }
+
+    pub(crate) async fn update_status_on(
+        conn: &mut SqliteConnection,
+        id: Uuid,
+        status: CardStatus,
+    ) -> Result<Card> {
         let card = sqlx::query_as::<_, Card>(
             r#"
             UPDATE cards
-            SET status = $2, updated_at = datetime('now')
+            SET status = $2, version = version + 1, updated_at = datetime('now')
             WHERE id = $1
             RETURNING *
             "#,
         )
         .bind(id)
         .bind(status.to_string())
-        .fetch_one(self.pool.as_ref())
+        .fetch_one(&mut *conn)
         .await?;
 
         Ok(card)
     }
 
+    /// Give the cards of a board, or of every board with `None`, the status their
+    /// column maps to, and return how many changed
+    pub async fn sync_status_with_columns(&self, board_id: Option<Uuid>) -> Result<u64> {
+        let mut conn = self.pool.acquire().await?;
+        Self::sync_status_with_columns_on(&mut conn, board_id, None).await
+    }
+
+    /// Like `sync_status_with_columns`, limited to one card in one column when
+    /// `placement` (card id, column id) is given
+    pub(crate) async fn sync_status_with_columns_on(
+        conn: &mut SqliteConnection,
+        board_id: Option<Uuid>,
+        placement: Option<(Uuid, Uuid)>,
+    ) -> Result<u64> {
+        let result = sqlx::query(
+            r#"
+            UPDATE cards
+            SET status = col.card_status, version = cards.version + 1, updated_at = datetime('now')
+            FROM card_boards cb
+            INNER JOIN columns col ON col.id = cb.column_id
+            WHERE cb.card_id = cards.id
+              AND col.card_status IS NOT NULL AND cards.status != col.card_status
+              AND cards.deleted_at IS NULL AND col.deleted_at IS NULL
+              AND ($1 IS NULL OR cb.board_id = $1)
+              AND ($2 IS NULL OR (cards.id = $2 AND cb.column_id = $3))
+            "#,
+        )
+        .bind(board_id)
+        .bind(placement.map(|(card_id, _)| card_id))
+        .bind(placement.map(|(_, column_id)| column_id))
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Lock or unlock a card; `locked_by` is None to unlock
+    pub async fn set_locked(&self, id: Uuid, locked_by: Option<Uuid>) -> Result<Card> {
+        let card = sqlx::query_as::<_, Card>(
+            r#"
+            UPDATE cards
+            SET locked_at = CASE WHEN $2 IS NULL THEN NULL ELSE datetime('now') END,
+                locked_by = $2,
+                updated_at = datetime('now')
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(locked_by)
+        .fetch_optional(self.pool.as_ref())
+        .await?
+        .ok_or(AppError::NotFound("card"))?;
+
+        Ok(card)
+    }
+
+    /// Fail with `AppError::Locked` if the card is locked
+    pub async fn ensure_unlocked(&self, id: Uuid) -> Result<()> {
+        let mut conn = self.pool.acquire().await?;
+        Self::ensure_unlocked_on(&mut conn, id).await
+    }
+
+    pub(crate) async fn ensure_unlocked_on(conn: &mut SqliteConnection, id: Uuid) -> Result<()> {
+        if Self::get_on(conn, id).await?.is_locked() {
+            return Err(AppError::Locked);
+        }
+        Ok(())
+    }
+
     pub async fn find_by_id(&self, id: Uuid) -> Result<Option<Card>> {
-        let card = sqlx::query_as::<_, Card>("SELECT * FROM cards WHERE id = $1")
-            .bind(id)
-            .fetch_optional(self.pool.as_ref())
-            .await?;
+        let mut conn = self.pool.acquire().await?;
+        Self::find_on(&mut conn, id).await
+    }
+
+    pub(crate) async fn find_on(conn: &mut SqliteConnection, id: Uuid) -> Result<Option<Card>> {
+        let card =
+            sqlx::query_as::<_, Card>("SELECT * FROM cards WHERE id = $1 AND deleted_at IS NULL")
+                .bind(id)
+                .fetch_optional(&mut *conn)
+                .await?;
 
         Ok(card)
     }
 
+    pub(crate) async fn get_on(conn: &mut SqliteConnection, id: Uuid) -> Result<Card> {
+        Self::find_on(conn, id)
+            .await?
+            .ok_or(AppError::NotFound("card"))
+    }
+
     pub async fn get_by_id(&self, id: Uuid) -> Result<Card> {
-        self.find_by_id(id).await?.ok_or(AppError::NotFound)
+        self.find_by_id(id).await?.ok_or(AppError::NotFound("card"))
     }
 
+    /// Cards placed in a column that are not archived, in the order of the column's sort
+    /// policy, with their column and position on the column's board
     pub async fn list_by_column(&self, column_id: Uuid) -> Result<Vec<Card>> {
-        let cards = sqlx::query_as::<_, Card>(
-            "SELECT * FROM cards WHERE column_id = $1 ORDER BY position ASC",
-        )
+        let mut conn = self.pool.acquire().await?;
+        Self::list_by_column_on(&mut conn, column_id).await
+    }
+
+    pub(crate) async fn list_by_column_on(
+        conn: &mut SqliteConnection,
+        column_id: Uuid,
+    ) -> Result<Vec<Card>> {
+        let cards = sqlx::query_as::<_, PlacedCard>(&format!(
+            r#"
+            SELECT c.*, cb.column_id AS placed_column_id, cb.position AS placed_position
+            FROM cards c
+            INNER JOIN card_boards cb ON cb.card_id = c.id
+            INNER JOIN columns col ON col.id = cb.column_id
+            WHERE cb.column_id = $1 AND c.archived_at IS NULL AND c.deleted_at IS NULL
+            ORDER BY {}
+            "#,
+            COLUMN_CARD_ORDER
+        ))
         .bind(column_id)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        Ok(cards.into_iter().map(Card::from).collect())
+    }
+
+    /// Cards placed in the board's columns that are not archived, in one query: grouped by
+    /// column, and within a column in the order of its sort policy, as `list_by_column`
+    /// returns them. Archived columns are left out. Each card comes with its column and
+    /// position on this board.
+    pub async fn list_by_board_columns(&self, board_id: Uuid) -> Result<Vec<Card>> {
+        let cards = sqlx::query_as::<_, PlacedCard>(&format!(
+            r#"
+            SELECT c.*, cb.column_id AS placed_column_id, cb.position AS placed_position
+            FROM cards c
+            INNER JOIN card_boards cb ON cb.card_id = c.id
+            INNER JOIN columns col ON col.id = cb.column_id
+            WHERE cb.board_id = $1 AND col.archived_at IS NULL AND col.deleted_at IS NULL
+              AND c.archived_at IS NULL AND c.deleted_at IS NULL
+            ORDER BY col.position ASC, col.id ASC, {}
+            "#,
+            COLUMN_CARD_ORDER
+        ))
+        .bind(board_id)
         .fetch_all(self.pool.as_ref())
         .await?;
 
-        Ok(cards)
+        Ok(cards.into_iter().map(Card::from).collect())
     }
 
     pub async fn list_by_board_with_filter(
+        &self,
+        board_id: Uuid,
+        user_id: Uuid,
+        user_role: Option<&str>,
+        filter: &CardFilter,
+    ) -> Result<Vec<Card>> {
+        self.list_page_by_board_with_filter(board_id, user_id, user_role, filter, Page::ALL)
+            .await
+    }
+
+    /// Cards of the board matching the filter that the role may see, in board order
+    pub async fn list_page_by_board_with_filter(
         &self,
         board_id: Uuid,
         _user_id: Uuid,
         user_role: Option<&str>,
         filter: &CardFilter,
+        page: Page,
     ) -> Result<Vec<Card>> {
-        let mut query = String::from(
-            r#"
-            SELECT c.* FROM cards c
-            INNER JOIN columns col ON c.column_id = col.id
-            WHERE col.board_id = $1
-            "#,
+        let mut query = board_filter_query(
+            "SELECT c.*, cb.column_id AS placed_column_id, cb.position AS placed_position",
+            board_id,
+            user_role,
+            filter,
         );
+        query
+            .push(" ORDER BY col.position ASC, cb.position ASC, c.id ASC LIMIT ")
+            .push_bind(page.limit)
+            .push(" OFFSET ")
+            .push_bind(page.offset);
 
-        // Filter by visibility based on user role
-        if let Some(role) = user_role {
-            match role {
-                "owner" | "editor" => {
-                    // Can see all cards
-                }
-                "reader" => {
-                    query.push_str(" AND (c.visibility != 'private')");
-                }
-                _ => {
-                    query.push_str(" AND c.visibility = 'public'");
-                }
-            }
-        } else {
-            query.push_str(" AND c.visibility = 'public'");
-        }
-
-        // Full-text query on title/body
-        if let Some(ref q) = filter.query {
-            query.push_str(&format!(
-                " AND (c.title LIKE '%{}%' OR c.body LIKE '%{}%')",
-                q.replace('\'', "''"),
-                q.replace('\'', "''")
-            ));
-        }
-
-        // Date filters
-        if let Some(date) = filter.start_date_from {
-            query.push_str(&format!(" AND c.start_date >= '{}'", date));
-        }
-        if let Some(date) = filter.start_date_to {
-            query.push_str(&format!(" AND c.start_date <= '{}'", date));
-        }
-        if let Some(date) = filter.end_date_from {
-            query.push_str(&format!(" AND c.end_date >= '{}'", date));
-        }
-        if let Some(date) = filter.end_date_to {
-            query.push_str(&format!(" AND c.end_date <= '{}'", date));
-        }
-        if let Some(date) = filter.due_date_from {
-            query.push_str(&format!(" AND c.due_date >= '{}'", date));
-        }
-        if let Some(date) = filter.due_date_to {
-            query.push_str(&format!(" AND c.due_date <= '{}'", date));
-        }
-        if let Some(date) = filter.updated_from {
-            query.push_str(&format!(" AND c.updated_at >= '{}'", date));
-        }
-        if let Some(date) = filter.updated_to {
-            query.push_str(&format!(" AND c.updated_at <= '{}'", date));
-        }
-
-        // Tag filter
-        if let Some(ref tags) = filter.tags {
-            if !tags.is_empty() {
-                let tag_ids: Vec<String> = tags.iter().map(|t| format!("'{}'", t)).collect();
-                query.push_str(&format!(
-                    " AND c.id IN (SELECT card_id FROM card_tags WHERE tag_id IN ({}))",
-                    tag_ids.join(",")
-                ));
-            }
-        }
+        let cards = query
+            .build_query_as::<PlacedCard>()
+            .fetch_all(self.pool.as_ref())
+            .await?;
 
-        query.push_str(" ORDER BY col.position ASC, c.position ASC");
+        Ok(cards.into_iter().map(Card::from).collect())
+    }
 
-        let cards = sqlx::query_as::<_, Card>(&query)
-            .bind(board_id)
-            .fetch_all(self.pool.as_ref())
+    pub async fn count_by_board_with_filter(
+        &self,
+        board_id: Uuid,
+        user_role: Option<&str>,
+        filter: &CardFilter,
+    ) -> Result<i64> {
+        let count = board_filter_query("SELECT COUNT(*)", board_id, user_role, filter)
+            .build_query_scalar::<i64>()
+            .fetch_one(self.pool.as_ref())
             .await?;
 
-        Ok(cards)
+        Ok(count)
     }
 
     #[allow(clippy::too_many_arguments)]
+    /// Update a card. `None` leaves a field unchanged; for the nullable fields
+    /// `Some(None)` clears the value. With an `expected_version`, a card that has
+    /// changed since that version is left alone and the update fails with
+    /// `PreconditionFailed`.
     pub async fn update(
         &self,
         id: Uuid,
         title: Option<&str>,
-        body: Option<&str>,
+        body: Option<Option<&str>>,
         visibility: Option<CardVisibility>,
         status: Option<CardStatus>,
-        start_date: Option<NaiveDate>,
-        end_date: Option<NaiveDate>,
-        due_date: Option<NaiveDate>,
+        start_date: Option<Option<NaiveDate>>,
+        end_date: Option<Option<NaiveDate>>,
+        due_date: Option<Option<NaiveDate>>,
+        priority: Option<Option<CardPriority>>,
+        expected_version: Option<i64>,
+    ) -> Result<Card> {
+        let mut conn = self.pool.acquire().await?;
+        Self::update_on(
+            &mut conn,
+            id,
+            title,
+            body,
+            visibility,
+            status,
+            start_date,
+            end_date,
+            due_date,
+            priority,
+            expected_version,
+        )
+        .await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) async fn update_on(
+        conn: &mut SqliteConnection,
+        id: Uuid,
+        title: Option<&str>,
+        body: Option<Option<&str>>,
+        visibility: Option<CardVisibility>,
+        status: Option<CardStatus>,
+        start_date: Option<Option<NaiveDate>>,
+        end_date: Option<Option<NaiveDate>>,
+        due_date: Option<Option<NaiveDate>>,
+        priority: Option<Option<CardPriority>>,
+        expected_version: Option<i64>,
     ) -> Result<Card> {
         let card = sqlx::query_as::<_, Card>(
             r#"
             UPDATE cards
             SET title = COALESCE($2, title),
-                body = COALESCE($3, body),
-                visibility = COALESCE($4, visibility),
-                status = COALESCE($5, status),
-                start_date = COALESCE($6, start_date),
-                end_date = COALESCE($7, end_date),
-                due_date = COALESCE($8, due_date),
+                body = CASE WHEN $3 THEN $4 ELSE body END,
+                visibility = COALESCE($5, visibility),
+                status = COALESCE($6, status),
+                start_date = CASE WHEN $7 THEN $8 ELSE start_date END,
+                end_date = CASE WHEN $9 THEN $10 ELSE end_date END,
+                due_date = CASE WHEN $11 THEN $12 ELSE due_date END,
+                priority = CASE WHEN $13 THEN $14 ELSE priority END,
+                version = version + 1,
                 updated_at = datetime('now')
-            WHERE id = $1
+            WHERE id = $1 AND ($15 IS NULL OR version = $15)
             RETURNING *
             "#,
         )
         .bind(id)
         .bind(title)
-        .bind(body)
+        .bind(body.is_some())
+        .bind(body.flatten())
         .bind(visibility.map(|v| v.to_string()))
         .bind(status.map(|s| s.to_string()))
-        .bind(start_date)
-        .bind(end_date)
-        .bind(due_date)
-        .fetch_one(self.pool.as_ref())
+        .bind(start_date.is_some())
+        .bind(start_date.flatten())
+        .bind(end_date.is_some())
+        .bind(end_date.flatten())
+        .bind(due_date.is_some())
+        .bind(due_date.flatten())
+        .bind(priority.is_some())
+        .bind(priority.flatten().map(|p| p.to_string()))
+        .bind(expected_version)
+        .fetch_optional(&mut *conn)
         .await?;
 
-        Ok(card)
+        match card {
+            Some(card) => Ok(card),
+            None if Self::find_on(conn, id).await?.is_some() => Err(AppError::PreconditionFailed),
+            None => Err(AppError::NotFound("card")),
+        }
+    }
+
+    /// Set the cover of a card, a color or an attachment, or remove it with neither
+    pub async fn set_cover(
+        &self,
+        id: Uuid,
+        color: Option<&str>,
+        attachment_id: Option<Uuid>,
+    ) -> Result<Card> {
+        sqlx::query_as::<_, Card>(
+            r#"
+            UPDATE cards
+            SET cover_color = $2,
+                cover_attachment_id = $3,
+                version = version + 1,
+                updated_at = datetime('now')
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(color)
+        .bind(attachment_id)
+        .fetch_optional(self.pool.as_ref())
+        .await?
+        .ok_or(AppError::NotFound("card"))
     }
 
     pub async fn delete(&self, id: Uuid) -> Result<()> {
@@ -316,19 +648,52 @@ impl CardRepository {
             .await?;
 
         if result.rows_affected() == 0 {
-            return Err(AppError::NotFound);
+            return Err(AppError::NotFound("card"));
         }
 
         Ok(())
     }
 
+    /// Move a card to `new_position` in a column. The position is clamped to the
+    /// column, so the cards of both columns stay numbered from 0 without gaps.
     pub async fn move_card(
         &self,
         id: Uuid,
         new_column_id: Uuid,
         new_position: i32,
     ) -> Result<Card> {
-        let card = self.get_by_id(id).await?;
+        // Take the write lock up front: concurrent moves then wait for each other
+        // instead of shifting positions read before the other one committed
+        let mut tx = self.pool.begin_with("BEGIN IMMEDIATE").await?;
+        let card = Self::move_card_on(&mut tx, id, new_column_id, new_position).await?;
+        tx.commit().await?;
+        Ok(card)
+    }
+
+    pub(crate) async fn move_card_on(
+        conn: &mut SqliteConnection,
+        id: Uuid,
+        new_column_id: Uuid,
+        new_position: i32,
+    ) -> Result<Card> {
+        // Shifting relies on positions 0..n, so first repair what older writes left
+        let card = Self::get_on(conn, id).await?;
+        if let Some(old_column_id) = card.column_id {
+            Self::rebalance_column_on(conn, old_column_id).await?;
+        }
+        if card.column_id != Some(new_column_id) {
+            Self::rebalance_column_on(conn, new_column_id).await?;
+        }
+        let card = Self::get_on(conn, id).await?;
+
+        let others = sqlx::query_scalar::<_, i32>(
+            "SELECT COUNT(*) FROM cards WHERE column_id = $1 AND id != $2 AND archived_at IS NULL AND deleted_at IS NULL",
+        )
+        .bind(new_column_id)
+        .bind(id)
+        .fetch_one(&mut *conn)
+        .await?;
+        let new_position = new_position.clamp(0, others);
 
         // If moving within the same column
         if card.column_id == Some(new_column_id) {
@@ -338,12 +703,13 @@ impl CardRepository {
                     UPDATE cards
                     SET position = position - 1
                     WHERE column_id = $1 AND position > $2 AND position <= $3
+                      AND archived_at IS NULL AND deleted_at IS NULL
                     "#,
                 )
                 .bind(card.column_id)
                 .bind(card.position)
                 .bind(new_position)
-                .execute(self.pool.as_ref())
+                .execute(&mut *conn)
                 .await?;
             } else if new_position < card.position {
                 sqlx::query(
@@ -351,12 +717,13 @@ impl CardRepository {
                     UPDATE cards
                     SET position = position + 1
                     WHERE column_id = $1 AND position >= $2 AND position < $3
+                      AND archived_at IS NULL AND deleted_at IS NULL
                     "#,
                 )
                 .bind(card.column_id)
                 .bind(new_position)
                 .bind(card.position)
-                .execute(self.pool.as_ref())
+                .execute(&mut *conn)
                 .await?;
             }
         } else {
@@ -368,11 +735,12 @@ impl CardRepository {
                     UPDATE cards
                     SET position = position - 1
                     WHERE column_id = $1 AND position > $2
+                      AND archived_at IS NULL AND deleted_at IS NULL
                     "#,
                 )
                 .bind(old_column_id)
                 .bind(card.position)
-                .execute(self.pool.as_ref())
+                .execute(&mut *conn)
                 .await?;
             }
 
@@ -382,11 +750,12 @@ impl CardRepository {
                 UPDATE cards
                 SET position = position + 1
                 WHERE column_id = $1 AND position >= $2
+                  AND archived_at IS NULL AND deleted_at IS NULL
                 "#,
             )
             .bind(new_column_id)
             .bind(new_position)
-            .execute(self.pool.as_ref())
+            .execute(&mut *conn)
             .await?;
         }
 
@@ -394,7 +763,7 @@ impl CardRepository {
         let updated = sqlx::query_as::<_, Card>(
             r#"
             UPDATE cards
-            SET column_id = $2, position = $3, updated_at = datetime('now')
+            SET column_id = $2, position = $3, version = version + 1, updated_at = datetime('now')
             WHERE id = $1
             RETURNING *
             "#,
@@ -402,25 +771,620 @@ impl CardRepository {
         .bind(id)
         .bind(new_column_id)
         .bind(new_position)
-        .fetch_one(self.pool.as_ref())
+        .fetch_one(&mut *conn)
         .await?;
 
         Ok(updated)
     }
 
+    /// Renumber the cards of a column 0, 1, 2... keeping their order, and return how
+    /// many had to change. Cards sharing a position keep the order they were created in.
+    pub async fn rebalance_column(&self, column_id: Uuid) -> Result<u64> {
+        let mut tx = self.pool.begin_with("BEGIN IMMEDIATE").await?;
+        let changed = Self::rebalance_column_on(&mut tx, column_id).await?;
+        tx.commit().await?;
+        Ok(changed)
+    }
+
+    async fn rebalance_column_on(conn: &mut SqliteConnection, column_id: Uuid) -> Result<u64> {
+        let result = sqlx::query(
+            r#"
+            UPDATE cards
+            SET position = ranked.position
+            FROM (
+                SELECT id, ROW_NUMBER() OVER (ORDER BY position ASC, rowid ASC) - 1 AS position
+                FROM cards
+                WHERE column_id = $1 AND archived_at IS NULL AND deleted_at IS NULL
+            ) AS ranked
+            WHERE cards.id = ranked.id AND cards.position != ranked.position
+            "#,
+        )
+        .bind(column_id)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Copy a card with its tags, and its comments when `with_comments`. The copy goes
+    /// right after the original when `column_id` is its column, and at the end of
+    /// `column_id` otherwise; copies of inbox cards stay in the inbox.
+    pub async fn clone_card(
+        &self,
+        id: Uuid,
+        column_id: Option<Uuid>,
+        title: &str,
+        with_comments: bool,
+        created_by: Uuid,
+    ) -> Result<Card> {
+        let mut tx = self.pool.begin_with("BEGIN IMMEDIATE").await?;
+        let card = Self::get_on(&mut tx, id).await?;
+
+        let position = match column_id {
+            Some(column_id) => {
+                Self::rebalance_column_on(&mut tx, column_id).await?;
+                let card = Self::get_on(&mut tx, id).await?;
+                if card.column_id == Some(column_id) && card.archived_at.is_none() {
+                    sqlx::query(
+                        r#"
+                        UPDATE cards
+                        SET position = position + 1
+                        WHERE column_id = $1 AND position > $2
+                          AND archived_at IS NULL AND deleted_at IS NULL
+                        "#,
+                    )
+                    .bind(column_id)
+                    .bind(card.position)
+                    .execute(&mut *tx)
+                    .await?;
+                    card.position + 1
+                } else {
+                    sqlx::query_scalar::<_, i32>(
+                        "SELECT COUNT(*) FROM cards WHERE column_id = $1 AND archived_at IS NULL AND deleted_at IS NULL",
+                    )
+                    .bind(column_id)
+                    .fetch_one(&mut *tx)
+                    .await?
+                }
+            }
+            None => 0,
+        };
+
+        let copy = Self::copy_on(
+            &mut tx,
+            card.id,
+            column_id,
+            Some(title),
+            position,
+            created_by,
+        )
+        .await?;
+        sqlx::query(
+            r#"
+            INSERT INTO card_tags (card_id, tag_id, created_at)
+            SELECT $2, tag_id, datetime('now') FROM card_tags WHERE card_id = $1
+            "#,
+        )
+        .bind(card.id)
+        .bind(copy.id)
+        .execute(&mut *tx)
+        .await?;
+        if with_comments {
+            CommentRepository::copy_on(&mut tx, card.id, copy.id).await?;
+        }
+
+        tx.commit().await?;
+        Ok(copy)
+    }
+
+    /// Insert a copy of a card's content under a new id. Tags, comments and everything
+    /// else attached to the card are left to the caller.
+    pub(crate) async fn copy_on(
+        conn: &mut SqliteConnection,
+        id: Uuid,
+        column_id: Option<Uuid>,
+        title: Option<&str>,
+        position: i32,
+        created_by: Uuid,
+    ) -> Result<Card> {
+        let card = sqlx::query_as::<_, Card>(
+            r#"
+            INSERT INTO cards (id, column_id, title, body, position, visibility, status, start_date, end_date, due_date, priority, cover_color, owner_id, created_by, created_at, updated_at)
+            SELECT $2, $3, COALESCE($4, title), body, $5, visibility, status, start_date, end_date, due_date, priority, cover_color, $6, $6, datetime('now'), datetime('now')
+            FROM cards WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(Uuid::new_v4())
+        .bind(column_id)
+        .bind(title)
+        .bind(position)
+        .bind(created_by)
+        .fetch_optional(&mut *conn)
+        .await?;
+
+        card.ok_or(AppError::NotFound("card"))
+    }
+
+    /// Apply card operations on the caller's transaction, so either all of them or none
+    /// take effect. Moved cards take the status of the column they end up in, unless
+    /// the operations set their status themselves. Cards, columns and tags are
+    /// expected to have been checked by the caller.
+    pub(crate) async fn bulk_apply_on(
+        conn: &mut SqliteConnection,
+        operations: &[BulkCardOperation],
+        user_id: Uuid,
+    ) -> Result<()> {
+        let mut moved = Vec::new();
+        let mut status_set = Vec::new();
+
+        for operation in operations {
+            match *operation {
+                BulkCardOperation::Move {
+                    card_id,
+                    column_id,
+                    position,
+                } => {
+                    let position = match position {
+                        Some(position) => position,
+                        None => {
+                            sqlx::query_scalar::<_, i32>(
+                                "SELECT COUNT(*) FROM cards WHERE column_id = $1 AND id != $2 AND archived_at IS NULL AND deleted_at IS NULL",
+                            )
+                            .bind(column_id)
+                            .bind(card_id)
+                            .fetch_one(&mut *conn)
+                            .await?
+                        }
+                    };
+                    Self::move_card_on(conn, card_id, column_id, position).await?;
+                    moved.push((card_id, column_id));
+                }
+                BulkCardOperation::Tag { card_id, tag_id } => {
+                    sqlx::query(
+                        r#"
+                        INSERT INTO card_tags (card_id, tag_id, created_at)
+                        VALUES ($1, $2, datetime('now'))
+                        ON CONFLICT(card_id, tag_id) DO NOTHING
+                        "#,
+                    )
+                    .bind(card_id)
+                    .bind(tag_id)
+                    .execute(&mut *conn)
+                    .await?;
+                }
+                BulkCardOperation::Status { card_id, status } => {
+                    Self::update_status_on(conn, card_id, status).await?;
+                    status_set.push(card_id);
+                }
+                BulkCardOperation::Delete { card_id } => {
+                    Self::move_to_trash_on(conn, card_id, user_id).await?;
+                }
+                BulkCardOperation::Archive { card_id } => {
+                    Self::set_archived_on(conn, card_id, true).await?;
+                }
+            }
+        }
+
+        for (card_id, column_id) in moved {
+            if !status_set.contains(&card_id) {
+                Self::sync_status_with_columns_on(conn, None, Some((card_id, column_id))).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Archive or unarchive a card. An archived card leaves the order of its column;
+    /// an unarchived one goes back at the end of it.
+    pub async fn set_archived(&self, id: Uuid, archived: bool) -> Result<Card> {
+        let mut conn = self.pool.acquire().await?;
+        Self::set_archived_on(&mut conn, id, archived).await
+    }
+
+    pub(crate) async fn set_archived_on(
+        conn: &mut SqliteConnection,
+        id: Uuid,
+        archived: bool,
+    ) -> Result<Card> {
+        let card = Self::get_on(conn, id).await?;
+        if card.archived_at.is_some() == archived {
+            return Ok(card);
+        }
+
+        let position = match (archived, card.column_id) {
+            (true, Some(column_id)) => {
+                sqlx::query(
+                    r#"
+                    UPDATE cards
+                    SET position = position - 1
+                    WHERE column_id = $1 AND position > $2 AND archived_at IS NULL
+                    "#,
+                )
+                .bind(column_id)
+                .bind(card.position)
+                .execute(&mut *conn)
+                .await?;
+                -1
+            }
+            (false, Some(column_id)) => {
+                let max_pos = sqlx::query_scalar::<_, Option<i32>>(
+                    "SELECT MAX(position) FROM cards WHERE column_id = $1 AND archived_at IS NULL AND deleted_at IS NULL",
+                )
+                .bind(column_id)
+                .fetch_one(&mut *conn)
+                .await?;
+                max_pos.unwrap_or(-1) + 1
+            }
+            (_, None) => card.position,
+        };
+
+        let card = sqlx::query_as::<_, Card>(
+            r#"
+            UPDATE cards
+            SET archived_at = CASE WHEN $2 THEN datetime('now') ELSE NULL END,
+                position = $3,
+                version = version + 1,
+                updated_at = datetime('now')
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(archived)
+        .bind(position)
+        .fetch_one(&mut *conn)
+        .await?;
+
+        Ok(card)
+    }
+
+    /// Move a card to the trash. It leaves the order of its column.
+    pub async fn move_to_trash(&self, id: Uuid, deleted_by: Uuid) -> Result<()> {
+        let mut conn = self.pool.acquire().await?;
+        Self::move_to_trash_on(&mut conn, id, deleted_by).await
+    }
+
+    pub(crate) async fn move_to_trash_on(
+        conn: &mut SqliteConnection,
+        id: Uuid,
+        deleted_by: Uuid,
+    ) -> Result<()> {
+        let card = Self::get_on(conn, id).await?;
+        if let (Some(column_id), None) = (card.column_id, card.archived_at) {
+            sqlx::query(
+                r#"
+                UPDATE cards
+                SET position = position - 1
+                WHERE column_id = $1 AND position > $2 AND archived_at IS NULL AND deleted_at IS NULL
+                "#,
+            )
+            .bind(column_id)
+            .bind(card.position)
+            .execute(&mut *conn)
+            .await?;
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE cards
+            SET deleted_at = datetime('now'),
+                deleted_by = $2,
+                position = CASE WHEN column_id IS NULL THEN position ELSE -1 END
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(deleted_by)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+
+    /// A card in the trash
+    pub async fn find_deleted(&self, id: Uuid) -> Result<Option<Card>> {
+        let mut conn = self.pool.acquire().await?;
+        Self::find_deleted_on(&mut conn, id).await
+    }
+
+    pub(crate) async fn find_deleted_on(
+        conn: &mut SqliteConnection,
+        id: Uuid,
+    ) -> Result<Option<Card>> {
+        let card = sqlx::query_as::<_, Card>(
+            "SELECT * FROM cards WHERE id = $1 AND deleted_at IS NOT NULL",
+        )
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await?;
+
+        Ok(card)
+    }
+
+    /// Take a card out of the trash, at the end of its column
+    pub async fn restore(&self, id: Uuid) -> Result<Card> {
+        let mut conn = self.pool.acquire().await?;
+        Self::restore_on(&mut conn, id).await
+    }
+
+    pub(crate) async fn restore_on(conn: &mut SqliteConnection, id: Uuid) -> Result<Card> {
+        let card = Self::find_deleted_on(conn, id)
+            .await?
+            .ok_or(AppError::NotFound("card"))?;
+
+        let position = match (card.column_id, card.archived_at) {
+            (Some(column_id), None) => {
+                let max_pos = sqlx::query_scalar::<_, Option<i32>>(
+                    "SELECT MAX(position) FROM cards WHERE column_id = $1 AND archived_at IS NULL AND deleted_at IS NULL",
+                )
+                .bind(column_id)
+                .fetch_one(&mut *conn)
+                .await?;
+                max_pos.unwrap_or(-1) + 1
+            }
+            _ => card.position,
+        };
+
+        let card = sqlx::query_as::<_, Card>(
+            r#"
+            UPDATE cards
+            SET deleted_at = NULL, deleted_by = NULL, position = $2, updated_at = datetime('now')
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(position)
+        .fetch_one(&mut *conn)
+        .await?;
+
+        Ok(card)
+    }
+
+    /// Cards of a board archived on their own, most recently archived first
+    pub async fn list_archived_by_board(&self, board_id: Uuid) -> Result<Vec<Card>> {
+        let cards = sqlx::query_as::<_, Card>(
+            r#"
+            SELECT c.* FROM cards c
+            INNER JOIN card_boards cb ON cb.card_id = c.id
+            INNER JOIN columns col ON col.id = cb.column_id
+            WHERE cb.board_id = $1 AND c.archived_at IS NOT NULL
+              AND c.deleted_at IS NULL AND col.deleted_at IS NULL
+            ORDER BY c.archived_at DESC, c.rowid DESC
+            "#,
+        )
+        .bind(board_id)
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(cards)
+    }
+
+    /// List card positions in a column, in order
+    pub async fn list_positions(&self, column_id: Uuid) -> Result<Vec<CardPosition>> {
+        let positions = sqlx::query_as::<_, CardPosition>(
+            "SELECT id, position FROM cards WHERE column_id = $1 AND archived_at IS NULL AND deleted_at IS NULL ORDER BY position ASC",
+        )
+        .bind(column_id)
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(positions)
+    }
+
+    /// Count the cards placed in a column, as shown on the board
+    pub(crate) async fn count_by_column_on(
+        conn: &mut SqliteConnection,
+        column_id: Uuid,
+    ) -> Result<i64> {
+        let count = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(*) FROM card_boards cb
+            INNER JOIN cards c ON c.id = cb.card_id
+            WHERE cb.column_id = $1 AND c.archived_at IS NULL AND c.deleted_at IS NULL
+            "#,
+        )
+        .bind(column_id)
+        .fetch_one(&mut *conn)
+        .await?;
+
+        Ok(count)
+    }
+
+    /// Count cards on a board that are not done, closed or archived yet
+    pub async fn count_open_by_board(&self, board_id: Uuid) -> Result<i64> {
+        let count = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(*) FROM cards c
+            INNER JOIN card_boards cb ON cb.card_id = c.id
+            INNER JOIN columns col ON col.id = cb.column_id
+            WHERE cb.board_id = $1 AND c.status NOT IN ('done', 'closed')
+              AND c.archived_at IS NULL AND c.deleted_at IS NULL
+              AND col.archived_at IS NULL AND col.deleted_at IS NULL
+            "#,
+        )
+        .bind(board_id)
+        .fetch_one(self.pool.as_ref())
+        .await?;
+
+        Ok(count)
+    }
+
+    /// List the completion times of done or closed cards on a board over the last `days` days.
+    /// A card completes when the activity log last records it marked done or closed,
+    /// or moved into a done column, so later edits do not shift its completion.
+    pub async fn list_completion_times(
+        &self,
+        board_id: Uuid,
+        days: i64,
+    ) -> Result<Vec<DateTime<Utc>>> {
+        let times = sqlx::query_scalar::<_, DateTime<Utc>>(
+            r#"
+            SELECT MAX(a.created_at) AS completed_at FROM cards c
+            INNER JOIN card_boards cb ON cb.card_id = c.id
+            INNER JOIN activity_log a ON a.card_id = c.id
+            LEFT JOIN columns target ON a.event = 'card_moved'
+                AND lower(hex(target.id)) = replace(json_extract(a.details, '$.to_column_id'), '-', '')
+            WHERE cb.board_id = $1
+              AND c.status IN ('done', 'closed')
+              AND c.deleted_at IS NULL
+              AND (
+                  (a.event = 'card_updated'
+                   AND lower(json_extract(a.details, '$.status.to')) IN ('done', 'closed'))
+                  OR target.card_status = 'done'
+                  OR lower(trim(target.name)) = 'done'
+              )
+            GROUP BY c.id
+            HAVING completed_at >= datetime('now', $2)
+            "#,
+        )
+        .bind(board_id)
+        .bind(format!("-{} days", days))
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(times)
+    }
+
+    /// The board of the card's home column, or else the first board it was placed on
     pub async fn get_board_id_for_card(&self, card_id: Uuid) -> Result<Uuid> {
         let board_id = sqlx::query_scalar::<_, Uuid>(
             r#"
-            SELECT col.board_id FROM cards c
-            INNER JOIN columns col ON c.column_id = col.id
+            SELECT cb.board_id FROM cards c
+            INNER JOIN card_boards cb ON cb.card_id = c.id
             WHERE c.id = $1
+            ORDER BY cb.column_id IS c.column_id DESC, cb.created_at ASC, cb.rowid ASC
+            LIMIT 1
             "#,
         )
         .bind(card_id)
         .fetch_optional(self.pool.as_ref())
         .await?
-        .ok_or(AppError::NotFound)?;
+        .ok_or(AppError::NotFound("card"))?;
 
         Ok(board_id)
     }
 }
+
+/// `select` over the cards of the board that match the filter and that a member with
+/// `user_role` may see
+fn board_filter_query(
+    select: &str,
+    board_id: Uuid,
+    user_role: Option<&str>,
+    filter: &CardFilter,
+) -> QueryBuilder<'static, Sqlite> {
+    // Every value from the filter is bound, never formatted into the SQL
+    let mut query = QueryBuilder::<Sqlite>::new(select);
+    query.push(
+        r#"
+            FROM cards c
+            INNER JOIN card_boards cb ON cb.card_id = c.id
+            INNER JOIN columns col ON col.id = cb.column_id
+            WHERE c.archived_at IS NULL AND c.deleted_at IS NULL
+              AND col.archived_at IS NULL AND col.deleted_at IS NULL
+              AND cb.board_id = "#,
+    );
+    query.push_bind(board_id);
+
+    // Filter by visibility based on user role
+    match user_role {
+        Some("owner" | "editor") => {
+            // Can see all cards
+        }
+        Some("reader") => {
+            query.push(" AND (c.visibility != 'private')");
+        }
+        _ => {
+            query.push(" AND c.visibility = 'public'");
+        }
+    }
+
+    // Full-text query on title/body
+    if let Some(ref q) = filter.query {
+        let pattern = format!("%{}%", q);
+        query
+            .push(" AND (c.title LIKE ")
+            .push_bind(pattern.clone())
+            .push(" OR c.body LIKE ")
+            .push_bind(pattern)
+            .push(")");
+    }
+
+    // Date filters
+    let date_bounds = [
+        ("c.start_date >= ", filter.start_date_from),
+        ("c.start_date <= ", filter.start_date_to),
+        ("c.end_date >= ", filter.end_date_from),
+        ("c.end_date <= ", filter.end_date_to),
+        ("c.due_date >= ", filter.due_date_from),
+        ("c.due_date <= ", filter.due_date_to),
+    ];
+    for (condition, date) in date_bounds {
+        if let Some(date) = date {
+            query.push(" AND ").push(condition).push_bind(date);
+        }
+    }
+
+    // updated_at is stored in SQLite's datetime('now') format, so compare in it
+    let updated_bounds = [
+        ("c.updated_at >= ", filter.updated_from),
+        ("c.updated_at <= ", filter.updated_to),
+    ];
+    for (condition, date) in updated_bounds {
+        if let Some(date) = date {
+            query
+                .push(" AND ")
+                .push(condition)
+                .push_bind(date.format("%Y-%m-%d %H:%M:%S").to_string());
+        }
+    }
+
+    // Tag filter
+    if let Some(ref tags) = filter.tags {
+        if !tags.is_empty() {
+            query.push(" AND c.id IN (SELECT card_id FROM card_tags WHERE tag_id IN (");
+            let mut ids = query.separated(", ");
+            for tag in tags {
+                ids.push_bind(*tag);
+            }
+            query.push("))");
+        }
+    }
+
+    // Assignee filter
+    if let Some(assignee) = filter.assignee {
+        query
+            .push(" AND c.id IN (SELECT card_id FROM card_assignees WHERE user_id = ")
+            .push_bind(assignee)
+            .push(")");
+    }
+
+    if let Some(priority) = filter.priority {
+        query
+            .push(" AND c.priority = ")
+            .push_bind(priority.to_string());
+    }
+
+    if let Some(status) = filter.status {
+        query.push(" AND c.status = ").push_bind(status.to_string());
+    }
+
+    // Blocked cards have a blocker that is neither done nor closed
+    if let Some(blocked) = filter.blocked {
+        query
+            .push(if blocked { " AND " } else { " AND NOT " })
+            .push(
+                r#"EXISTS (
+                SELECT 1 FROM card_links l
+                JOIN cards b ON b.id = l.source_card_id
+                WHERE l.target_card_id = c.id AND l.link_type = 'blocks'
+                  AND b.deleted_at IS NULL AND b.status NOT IN ('done', 'closed')
+            )"#,
+            );
+    }
+
+    query
+}
@@ -1,9 +1,10 @@
-use sqlx::SqlitePool;
+use sqlx::{SqliteConnection, SqlitePool};
 use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::error::{AppError, Result};
-use crate::models::{Board, Card, CardBoardAssignment};
+use crate::models::{Board, Card, CardBoardAccess, CardBoardAssignment, CardPosition};
+use crate::repo::card::CardRepository;
 
 #[derive(Clone)]
 pub struct CardBoardRepository {
@@ -22,6 +23,17 @@ impl CardBoardRepository {
         board_id: Uuid,
         column_id: Option<Uuid>,
         position: Option<i32>,
+    ) -> Result<CardBoardAssignment> {
+        let mut conn = self.pool.acquire().await?;
+        Self::assign_card_to_board_on(&mut conn, card_id, board_id, column_id, position).await
+    }
+
+    pub(crate) async fn assign_card_to_board_on(
+        conn: &mut SqliteConnection,
+        card_id: Uuid,
+        board_id: Uuid,
+        column_id: Option<Uuid>,
+        position: Option<i32>,
     ) -> Result<CardBoardAssignment> {
         let id = Uuid::new_v4();
 
@@ -35,14 +47,14 @@ impl CardBoardRepository {
                     )
                     .bind(board_id)
                     .bind(col_id)
-                    .fetch_one(self.pool.as_ref())
+                    .fetch_one(&mut *conn)
                     .await?
                 } else {
                     sqlx::query_scalar::<_, Option<i32>>(
                         "SELECT MAX(position) FROM card_boards WHERE board_id = $1 AND column_id IS NULL",
                     )
                     .bind(board_id)
-                    .fetch_one(self.pool.as_ref())
+                    .fetch_one(&mut *conn)
                     .await?
                 };
                 max_pos.unwrap_or(-1) + 1
@@ -61,30 +73,70 @@ impl CardBoardRepository {
         .bind(board_id)
         .bind(column_id)
         .bind(pos)
-        .fetch_one(self.pool.as_ref())
+        .fetch_one(&mut *conn)
         .await?;
 
+        // A card without a home column gets this one, so the board shows it everywhere
+        if let Some(column_id) = column_id {
+            sqlx::query(
+                r#"
+                UPDATE cards
+                SET column_id = $2, position = $3, version = version + 1, updated_at = datetime('now')
+                WHERE id = $1 AND column_id IS NULL
+                "#,
+            )
+            .bind(card_id)
+            .bind(column_id)
+            .bind(pos)
+            .execute(&mut *conn)
+            .await?;
+        }
+
         Ok(assignment)
     }
 
-    /// Remove a card from a board
+    /// Remove a card from a board. A card whose home column is on the board becomes
+    /// standalone.
     pub async fn remove_card_from_board(&self, card_id: Uuid, board_id: Uuid) -> Result<()> {
-        let result = sqlx::query(
-            "DELETE FROM card_boards WHERE card_id = $1 AND board_id = $2",
+        let mut tx = self.pool.begin().await?;
+        Self::remove_card_from_board_on(&mut tx, card_id, board_id).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub(crate) async fn remove_card_from_board_on(
+        conn: &mut SqliteConnection,
+        card_id: Uuid,
+        board_id: Uuid,
+    ) -> Result<()> {
+        let result = sqlx::query("DELETE FROM card_boards WHERE card_id = $1 AND board_id = $2")
+            .bind(card_id)
+            .bind(board_id)
+            .execute(&mut *conn)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("card"));
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE cards
+            SET column_id = NULL, version = version + 1, updated_at = datetime('now')
+            WHERE id = $1 AND column_id IN (SELECT id FROM columns WHERE board_id = $2)
+            "#,
         )
         .bind(card_id)
         .bind(board_id)
-        .execute(self.pool.as_ref())
+        .execute(&mut *conn)
         .await?;
 
-        if result.rows_affected() == 0 {
-            return Err(AppError::NotFound);
-        }
-
         Ok(())
     }
 
-    /// Move a card within a board (change column and/or position)
+    /// Move a card within a board (change column and/or position). On the card's home
+    /// board, or for a card without a home column, the home column moves along and the
+    /// other cards of both columns shift as `CardRepository::move_card` does.
     pub async fn move_card_in_board(
         &self,
         card_id: Uuid,
@@ -92,25 +144,113 @@ impl CardBoardRepository {
         column_id: Option<Uuid>,
         position: i32,
     ) -> Result<CardBoardAssignment> {
-        let assignment = sqlx::query_as::<_, CardBoardAssignment>(
+        let mut tx = self.pool.begin_with("BEGIN IMMEDIATE").await?;
+        let assignment =
+            Self::move_card_in_board_on(&mut tx, card_id, board_id, column_id, position).await?;
+        tx.commit().await?;
+        Ok(assignment)
+    }
+
+    pub(crate) async fn move_card_in_board_on(
+        conn: &mut SqliteConnection,
+        card_id: Uuid,
+        board_id: Uuid,
+        column_id: Option<Uuid>,
+        position: i32,
+    ) -> Result<CardBoardAssignment> {
+        let home_board_id = sqlx::query_scalar::<_, Option<Uuid>>(
             r#"
-            UPDATE card_boards
-            SET column_id = $3, position = $4
-            WHERE card_id = $1 AND board_id = $2
-            RETURNING *
+            SELECT col.board_id FROM card_boards cb
+            INNER JOIN cards c ON c.id = cb.card_id
+            LEFT JOIN columns col ON col.id = c.column_id
+            WHERE cb.card_id = $1 AND cb.board_id = $2
             "#,
         )
         .bind(card_id)
         .bind(board_id)
-        .bind(column_id)
-        .bind(position)
-        .fetch_optional(self.pool.as_ref())
+        .fetch_optional(&mut *conn)
         .await?
-        .ok_or(AppError::NotFound)?;
+        .ok_or(AppError::NotFound("card"))?;
+
+        let on_home_board = home_board_id.is_none_or(|home| home == board_id);
+        let assignment = match column_id {
+            Some(column_id) if on_home_board => {
+                // The placement triggers copy the home column into the assignment
+                CardRepository::move_card_on(conn, card_id, column_id, position).await?;
+                sqlx::query_as::<_, CardBoardAssignment>(
+                    "SELECT * FROM card_boards WHERE card_id = $1 AND board_id = $2",
+                )
+                .bind(card_id)
+                .bind(board_id)
+                .fetch_one(&mut *conn)
+                .await?
+            }
+            _ => {
+                if column_id.is_none() && home_board_id == Some(board_id) {
+                    sqlx::query(
+                        r#"
+                        UPDATE cards
+                        SET column_id = NULL, version = version + 1, updated_at = datetime('now')
+                        WHERE id = $1
+                        "#,
+                    )
+                    .bind(card_id)
+                    .execute(&mut *conn)
+                    .await?;
+                }
+                sqlx::query_as::<_, CardBoardAssignment>(
+                    r#"
+                    UPDATE card_boards
+                    SET column_id = $3, position = $4
+                    WHERE card_id = $1 AND board_id = $2
+                    RETURNING *
+                    "#,
+                )
+                .bind(card_id)
+                .bind(board_id)
+                .bind(column_id)
+                .bind(position)
+                .fetch_one(&mut *conn)
+                .await?
+            }
+        };
 
         Ok(assignment)
     }
 
+    /// List card positions of a board column (None = cards without a column), in order.
+    /// Archived and trashed cards are left out.
+    pub async fn list_positions(
+        &self,
+        board_id: Uuid,
+        column_id: Option<Uuid>,
+    ) -> Result<Vec<CardPosition>> {
+        let mut conn = self.pool.acquire().await?;
+        Self::list_positions_on(&mut conn, board_id, column_id).await
+    }
+
+    pub(crate) async fn list_positions_on(
+        conn: &mut SqliteConnection,
+        board_id: Uuid,
+        column_id: Option<Uuid>,
+    ) -> Result<Vec<CardPosition>> {
+        let positions = sqlx::query_as::<_, CardPosition>(
+            r#"
+            SELECT cb.card_id AS id, cb.position FROM card_boards cb
+            INNER JOIN cards c ON c.id = cb.card_id
+            WHERE cb.board_id = $1 AND cb.column_id IS $2
+              AND c.archived_at IS NULL AND c.deleted_at IS NULL
+            ORDER BY cb.position ASC
+            "#,
+        )
+        .bind(board_id)
+        .bind(column_id)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        Ok(positions)
+    }
+
     /// Get assignment for a card on a specific board
     pub async fn get_assignment(
         &self,
@@ -134,7 +274,7 @@ impl CardBoardRepository {
             r#"
             SELECT b.* FROM boards b
             INNER JOIN card_boards cb ON b.id = cb.board_id
-            WHERE cb.card_id = $1
+            WHERE cb.card_id = $1 AND b.deleted_at IS NULL
             ORDER BY cb.created_at ASC
             "#,
         )
@@ -145,13 +285,57 @@ impl CardBoardRepository {
         Ok(boards)
     }
 
-    /// List all cards assigned to a board
+    /// List every board a card is on together with the user's role on each, in a
+    /// single query
+    pub async fn list_board_access_for_card(
+        &self,
+        card_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Vec<CardBoardAccess>> {
+        let boards = sqlx::query_as::<_, CardBoardAccess>(
+            r#"
+            SELECT b.id, b.name, bp.role FROM boards b
+            LEFT JOIN board_access bp ON bp.board_id = b.id AND bp.user_id = $2
+            WHERE b.deleted_at IS NULL
+              AND b.id IN (SELECT board_id FROM card_boards WHERE card_id = $1)
+            ORDER BY b.name ASC
+            "#,
+        )
+        .bind(card_id)
+        .bind(user_id)
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(boards)
+    }
+
+    /// Ids of every board a card is on
+    pub async fn list_board_ids_for_card(&self, card_id: Uuid) -> Result<Vec<Uuid>> {
+        let mut conn = self.pool.acquire().await?;
+        Self::list_board_ids_for_card_on(&mut conn, card_id).await
+    }
+
+    pub(crate) async fn list_board_ids_for_card_on(
+        conn: &mut SqliteConnection,
+        card_id: Uuid,
+    ) -> Result<Vec<Uuid>> {
+        let board_ids = sqlx::query_scalar::<_, Uuid>(
+            "SELECT board_id FROM card_boards WHERE card_id = $1 ORDER BY created_at ASC",
+        )
+        .bind(card_id)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        Ok(board_ids)
+    }
+
+    /// List the cards assigned to a board, except archived and deleted ones
     pub async fn list_cards_for_board(&self, board_id: Uuid) -> Result<Vec<Card>> {
         let cards = sqlx::query_as::<_, Card>(
             r#"
             SELECT c.* FROM cards c
             INNER JOIN card_boards cb ON c.id = cb.card_id
-            WHERE cb.board_id = $1
+            WHERE cb.board_id = $1 AND c.archived_at IS NULL AND c.deleted_at IS NULL
             ORDER BY cb.column_id, cb.position ASC
             "#,
         )
@@ -163,7 +347,10 @@ impl CardBoardRepository {
     }
 
     /// List all assignments for a board (includes position info)
-    pub async fn list_assignments_for_board(&self, board_id: Uuid) -> Result<Vec<CardBoardAssignment>> {
+    pub async fn list_assignments_for_board(
+        &self,
+        board_id: Uuid,
+    ) -> Result<Vec<CardBoardAssignment>> {
         let assignments = sqlx::query_as::<_, CardBoardAssignment>(
             r#"
             SELECT * FROM card_boards
@@ -180,12 +367,21 @@ impl CardBoardRepository {
 
     /// Check if a card is assigned to a specific board
     pub async fn is_card_on_board(&self, card_id: Uuid, board_id: Uuid) -> Result<bool> {
+        let mut conn = self.pool.acquire().await?;
+        Self::is_card_on_board_on(&mut conn, card_id, board_id).await
+    }
+
+    pub(crate) async fn is_card_on_board_on(
+        conn: &mut SqliteConnection,
+        card_id: Uuid,
+        board_id: Uuid,
+    ) -> Result<bool> {
         let exists = sqlx::query_scalar::<_, bool>(
             "SELECT EXISTS(SELECT 1 FROM card_boards WHERE card_id = $1 AND board_id = $2)",
         )
         .bind(card_id)
         .bind(board_id)
-        .fetch_one(self.pool.as_ref())
+        .fetch_one(&mut *conn)
         .await?;
 
         Ok(exists)
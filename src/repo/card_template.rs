@@ -0,0 +1,161 @@
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::models::{CardTemplate, Tag};
+
+#[derive(Clone)]
+pub struct CardTemplateRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl CardTemplateRepository {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+
+    /// Create a template. `checklist` is a JSON array of item texts.
+    pub async fn create(
+        &self,
+        board_id: Uuid,
+        name: &str,
+        title_pattern: &str,
+        body: Option<&str>,
+        checklist: &str,
+        created_by: Uuid,
+    ) -> Result<CardTemplate> {
+        let template = sqlx::query_as::<_, CardTemplate>(
+            r#"
+            INSERT INTO card_templates (id, board_id, name, title_pattern, body, checklist, created_by, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, datetime('now'), datetime('now'))
+            RETURNING *
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(board_id)
+        .bind(name)
+        .bind(title_pattern)
+        .bind(body)
+        .bind(checklist)
+        .bind(created_by)
+        .fetch_one(self.pool.as_ref())
+        .await?;
+
+        Ok(template)
+    }
+
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Option<CardTemplate>> {
+        let template =
+            sqlx::query_as::<_, CardTemplate>("SELECT * FROM card_templates WHERE id = $1")
+                .bind(id)
+                .fetch_optional(self.pool.as_ref())
+                .await?;
+
+        Ok(template)
+    }
+
+    pub async fn get_by_id(&self, id: Uuid) -> Result<CardTemplate> {
+        self.find_by_id(id)
+            .await?
+            .ok_or(AppError::NotFound("card_template"))
+    }
+
+    /// Templates of a board, by name
+    pub async fn list_by_board(&self, board_id: Uuid) -> Result<Vec<CardTemplate>> {
+        let templates = sqlx::query_as::<_, CardTemplate>(
+            "SELECT * FROM card_templates WHERE board_id = $1 ORDER BY name COLLATE NOCASE ASC, rowid ASC",
+        )
+        .bind(board_id)
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(templates)
+    }
+
+    /// Update a template. `None` leaves a field unchanged; `Some(None)` clears the body.
+    pub async fn update(
+        &self,
+        id: Uuid,
+        name: Option<&str>,
+        title_pattern: Option<&str>,
+        body: Option<Option<&str>>,
+        checklist: Option<&str>,
+    ) -> Result<CardTemplate> {
+        let template = sqlx::query_as::<_, CardTemplate>(
+            r#"
+            UPDATE card_templates
+            SET name = COALESCE($2, name),
+                title_pattern = COALESCE($3, title_pattern),
+                body = CASE WHEN $4 THEN $5 ELSE body END,
+                checklist = COALESCE($6, checklist),
+                updated_at = datetime('now')
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(name)
+        .bind(title_pattern)
+        .bind(body.is_some())
+        .bind(body.flatten())
+        .bind(checklist)
+        .fetch_optional(self.pool.as_ref())
+        .await?
+        .ok_or(AppError::NotFound("card_template"))?;
+
+        Ok(template)
+    }
+
+    pub async fn delete(&self, id: Uuid) -> Result<()> {
+        let result = sqlx::query("DELETE FROM card_templates WHERE id = $1")
+            .bind(id)
+            .execute(self.pool.as_ref())
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("card_template"));
+        }
+
+        Ok(())
+    }
+
+    /// Replace the tags of a template
+    pub async fn set_tags(&self, id: Uuid, tag_ids: &[Uuid]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM card_template_tags WHERE template_id = $1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        for tag_id in tag_ids {
+            sqlx::query(
+                "INSERT OR IGNORE INTO card_template_tags (template_id, tag_id) VALUES ($1, $2)",
+            )
+            .bind(id)
+            .bind(tag_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Tags of a template, by name
+    pub async fn list_tags(&self, id: Uuid) -> Result<Vec<Tag>> {
+        let tags = sqlx::query_as::<_, Tag>(
+            r#"
+            SELECT t.* FROM tags t
+            INNER JOIN card_template_tags ctt ON ctt.tag_id = t.id
+            WHERE ctt.template_id = $1
+            ORDER BY t.name ASC
+            "#,
+        )
+        .bind(id)
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(tags)
+    }
+}
@@ -0,0 +1,122 @@
+use sqlx::{SqliteConnection, SqlitePool};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::models::{UndoStep, UndoableAction};
+
+#[derive(Clone)]
+pub struct ChatActionRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl ChatActionRepository {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+
+    /// Remember how to undo an action taken in a board chat, or in global chat when
+    /// `board_id` is None
+    pub async fn create(
+        &self,
+        user_id: Uuid,
+        board_id: Option<Uuid>,
+        action: &str,
+        description: &str,
+        undo: &UndoStep,
+    ) -> Result<UndoableAction> {
+        let mut conn = self.pool.acquire().await?;
+        Self::create_on(&mut conn, user_id, board_id, action, description, undo).await
+    }
+
+    pub(crate) async fn create_on(
+        conn: &mut SqliteConnection,
+        user_id: Uuid,
+        board_id: Option<Uuid>,
+        action: &str,
+        description: &str,
+        undo: &UndoStep,
+    ) -> Result<UndoableAction> {
+        let id = Uuid::new_v4();
+        let undo = serde_json::to_string(undo).map_err(|e| AppError::Internal(e.to_string()))?;
+
+        let action = sqlx::query_as::<_, UndoableAction>(
+            r#"
+            INSERT INTO chat_actions (id, user_id, board_id, action, description, undo, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, datetime('now'))
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .bind(board_id)
+        .bind(action)
+        .bind(description)
+        .bind(undo)
+        .fetch_one(&mut *conn)
+        .await?;
+
+        Ok(action)
+    }
+
+    /// Find an action taken by a user
+    pub async fn find_for_user(&self, id: Uuid, user_id: Uuid) -> Result<Option<UndoableAction>> {
+        let action = sqlx::query_as::<_, UndoableAction>(
+            "SELECT * FROM chat_actions WHERE id = $1 AND user_id = $2",
+        )
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(self.pool.as_ref())
+        .await?;
+
+        Ok(action)
+    }
+
+    /// The user's most recent action in a chat that has not been undone yet
+    pub async fn find_last(
+        &self,
+        user_id: Uuid,
+        board_id: Option<Uuid>,
+    ) -> Result<Option<UndoableAction>> {
+        let mut conn = self.pool.acquire().await?;
+        Self::find_last_on(&mut conn, user_id, board_id).await
+    }
+
+    pub(crate) async fn find_last_on(
+        conn: &mut SqliteConnection,
+        user_id: Uuid,
+        board_id: Option<Uuid>,
+    ) -> Result<Option<UndoableAction>> {
+        let action = sqlx::query_as::<_, UndoableAction>(
+            r#"
+            SELECT * FROM chat_actions
+            WHERE user_id = $1 AND board_id IS $2 AND undone_at IS NULL
+            ORDER BY created_at DESC, rowid DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(user_id)
+        .bind(board_id)
+        .fetch_optional(&mut *conn)
+        .await?;
+
+        Ok(action)
+    }
+
+    /// Mark an action as undone. Returns false if it already was.
+    pub async fn mark_undone(&self, id: Uuid) -> Result<bool> {
+        let mut conn = self.pool.acquire().await?;
+        Self::mark_undone_on(&mut conn, id).await
+    }
+
+    pub(crate) async fn mark_undone_on(conn: &mut SqliteConnection, id: Uuid) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE chat_actions SET undone_at = datetime('now') WHERE id = $1 AND undone_at IS NULL",
+        )
+        .bind(id)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -1,9 +1,10 @@
+use chrono::NaiveDate;
 use sqlx::SqlitePool;
 use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::error::Result;
-use crate::models::ChatMessage;
+use crate::models::{ChatMessage, Page};
 
 #[derive(Clone)]
 pub struct ChatMessageRepository {
@@ -70,16 +71,63 @@ impl ChatMessageRepository {
         Ok(chat_message)
     }
 
-    pub async fn list_by_board(&self, board_id: Uuid, limit: i64) -> Result<Vec<ChatMessage>> {
+    /// Record the actions executed for a message after it was saved
+    pub async fn set_actions(&self, id: Uuid, actions_taken: Option<&str>) -> Result<()> {
+        sqlx::query("UPDATE chat_messages SET actions_taken = $2 WHERE id = $1")
+            .bind(id)
+            .bind(actions_taken)
+            .execute(self.pool.as_ref())
+            .await?;
+
+        Ok(())
+    }
+
+    /// Messages of a board, newest first
+    pub async fn list_by_board(&self, board_id: Uuid, page: Page) -> Result<Vec<ChatMessage>> {
         let messages = sqlx::query_as::<_, ChatMessage>(
             r#"
             SELECT * FROM chat_messages
             WHERE board_id = $1
-            ORDER BY created_at DESC
-            LIMIT $2
+            ORDER BY created_at DESC, rowid DESC
+            LIMIT $2 OFFSET $3
             "#,
         )
         .bind(board_id)
+        .bind(page.limit)
+        .bind(page.offset)
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(messages)
+    }
+
+    pub async fn count_by_board(&self, board_id: Uuid) -> Result<i64> {
+        let count =
+            sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM chat_messages WHERE board_id = $1")
+                .bind(board_id)
+                .fetch_one(self.pool.as_ref())
+                .await?;
+
+        Ok(count)
+    }
+
+    /// Most recent messages of one user on a board, newest first
+    pub async fn list_recent_for_user(
+        &self,
+        board_id: Uuid,
+        user_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<ChatMessage>> {
+        let messages = sqlx::query_as::<_, ChatMessage>(
+            r#"
+            SELECT * FROM chat_messages
+            WHERE board_id = $1 AND user_id = $2
+            ORDER BY created_at DESC, rowid DESC
+            LIMIT $3
+            "#,
+        )
+        .bind(board_id)
+        .bind(user_id)
         .bind(limit)
         .fetch_all(self.pool.as_ref())
         .await?;
@@ -88,17 +136,57 @@ impl ChatMessageRepository {
     }
 
     /// List global chat messages (where board_id IS NULL)
-    pub async fn list_global(&self, user_id: Uuid, limit: i64) -> Result<Vec<ChatMessage>> {
+    pub async fn list_global(&self, user_id: Uuid, page: Page) -> Result<Vec<ChatMessage>> {
         let messages = sqlx::query_as::<_, ChatMessage>(
             r#"
             SELECT * FROM chat_messages
             WHERE user_id = $1 AND board_id IS NULL
-            ORDER BY created_at DESC
-            LIMIT $2
+            ORDER BY created_at DESC, rowid DESC
+            LIMIT $2 OFFSET $3
             "#,
         )
         .bind(user_id)
-        .bind(limit)
+        .bind(page.limit)
+        .bind(page.offset)
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(messages)
+    }
+
+    pub async fn count_global(&self, user_id: Uuid) -> Result<i64> {
+        let count = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM chat_messages WHERE user_id = $1 AND board_id IS NULL",
+        )
+        .bind(user_id)
+        .fetch_one(self.pool.as_ref())
+        .await?;
+
+        Ok(count)
+    }
+
+    /// List chat messages for export in chronological order, optionally bounded by date (inclusive).
+    /// With a board, all messages of the board are returned; without, the user's global messages.
+    pub async fn list_for_export(
+        &self,
+        user_id: Uuid,
+        board_id: Option<Uuid>,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> Result<Vec<ChatMessage>> {
+        let messages = sqlx::query_as::<_, ChatMessage>(
+            r#"
+            SELECT * FROM chat_messages
+            WHERE (($2 IS NULL AND user_id = $1 AND board_id IS NULL) OR board_id = $2)
+              AND ($3 IS NULL OR date(created_at) >= $3)
+              AND ($4 IS NULL OR date(created_at) <= $4)
+            ORDER BY created_at ASC
+            "#,
+        )
+        .bind(user_id)
+        .bind(board_id)
+        .bind(from)
+        .bind(to)
         .fetch_all(self.pool.as_ref())
         .await?;
 
@@ -0,0 +1,93 @@
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::models::{ChatSession, ChatSessionStatus, ChatSessionType, PlanningState, PlanningStep};
+
+#[derive(Clone)]
+pub struct ChatSessionRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl ChatSessionRepository {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(
+        &self,
+        user_id: Uuid,
+        session_type: ChatSessionType,
+        step: PlanningStep,
+        state: &PlanningState,
+    ) -> Result<ChatSession> {
+        let id = Uuid::new_v4();
+        let state = serde_json::to_string(state).map_err(|e| AppError::Internal(e.to_string()))?;
+
+        let session = sqlx::query_as::<_, ChatSession>(
+            r#"
+            INSERT INTO chat_sessions (id, user_id, session_type, status, step, state, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, datetime('now'), datetime('now'))
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .bind(session_type.to_string())
+        .bind(ChatSessionStatus::Active.to_string())
+        .bind(step.to_string())
+        .bind(state)
+        .fetch_one(self.pool.as_ref())
+        .await?;
+
+        Ok(session)
+    }
+
+    /// Find a session owned by the given user
+    pub async fn find_for_user(&self, id: Uuid, user_id: Uuid) -> Result<Option<ChatSession>> {
+        let session = sqlx::query_as::<_, ChatSession>(
+            "SELECT * FROM chat_sessions WHERE id = $1 AND user_id = $2",
+        )
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(self.pool.as_ref())
+        .await?;
+
+        Ok(session)
+    }
+
+    pub async fn get_for_user(&self, id: Uuid, user_id: Uuid) -> Result<ChatSession> {
+        self.find_for_user(id, user_id)
+            .await?
+            .ok_or(AppError::NotFound("chat_session"))
+    }
+
+    /// Save session progress
+    pub async fn update(
+        &self,
+        id: Uuid,
+        status: ChatSessionStatus,
+        step: PlanningStep,
+        state: &PlanningState,
+    ) -> Result<ChatSession> {
+        let state = serde_json::to_string(state).map_err(|e| AppError::Internal(e.to_string()))?;
+
+        let session = sqlx::query_as::<_, ChatSession>(
+            r#"
+            UPDATE chat_sessions
+            SET status = $2, step = $3, state = $4, updated_at = datetime('now')
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(status.to_string())
+        .bind(step.to_string())
+        .bind(state)
+        .fetch_one(self.pool.as_ref())
+        .await?;
+
+        Ok(session)
+    }
+}
@@ -1,9 +1,12 @@
-use sqlx::SqlitePool;
+use sqlx::{QueryBuilder, Sqlite, SqliteConnection, SqlitePool};
+use std::collections::HashSet;
 use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::error::{AppError, Result};
-use crate::models::Column;
+use crate::models::{
+    default_card_status, BoardColumnCardCount, CardStatus, Column, ColumnSortPolicy,
+};
 
 #[derive(Clone)]
 pub struct ColumnRepository {
@@ -20,6 +23,20 @@ impl ColumnRepository {
         board_id: Uuid,
         name: &str,
         position: Option<i32>,
+        color: Option<&str>,
+        description: Option<&str>,
+    ) -> Result<Column> {
+        let mut conn = self.pool.acquire().await?;
+        Self::create_on(&mut conn, board_id, name, position, color, description).await
+    }
+
+    pub(crate) async fn create_on(
+        conn: &mut SqliteConnection,
+        board_id: Uuid,
+        name: &str,
+        position: Option<i32>,
+        color: Option<&str>,
+        description: Option<&str>,
     ) -> Result<Column> {
         let id = Uuid::new_v4();
 
@@ -28,10 +45,10 @@ impl ColumnRepository {
             Some(p) => p,
             None => {
                 let max_pos = sqlx::query_scalar::<_, Option<i32>>(
-                    "SELECT MAX(position) FROM columns WHERE board_id = $1",
+                    "SELECT MAX(position) FROM columns WHERE board_id = $1 AND archived_at IS NULL",
                 )
                 .bind(board_id)
-                .fetch_one(self.pool.as_ref())
+                .fetch_one(&mut *conn)
                 .await?;
                 max_pos.unwrap_or(-1) + 1
             }
@@ -39,8 +56,8 @@ impl ColumnRepository {
 
         let column = sqlx::query_as::<_, Column>(
             r#"
-            INSERT INTO columns (id, board_id, name, position, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, datetime('now'), datetime('now'))
+            INSERT INTO columns (id, board_id, name, position, color, description, card_status, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, datetime('now'), datetime('now'))
             RETURNING *
             "#,
         )
@@ -48,52 +65,175 @@ impl ColumnRepository {
         .bind(board_id)
         .bind(name)
         .bind(pos)
-        .fetch_one(self.pool.as_ref())
+        .bind(color)
+        .bind(description)
+        .bind(default_card_status(name).map(|s| s.to_string()))
+        .fetch_one(&mut *conn)
         .await?;
 
         Ok(column)
     }
 
     pub async fn find_by_id(&self, id: Uuid) -> Result<Option<Column>> {
-        let column = sqlx::query_as::<_, Column>("SELECT * FROM columns WHERE id = $1")
-            .bind(id)
-            .fetch_optional(self.pool.as_ref())
-            .await?;
+        let mut conn = self.pool.acquire().await?;
+        Self::find_on(&mut conn, id).await
+    }
+
+    pub(crate) async fn find_on(conn: &mut SqliteConnection, id: Uuid) -> Result<Option<Column>> {
+        let column = sqlx::query_as::<_, Column>(
+            "SELECT * FROM columns WHERE id = $1 AND deleted_at IS NULL",
+        )
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await?;
 
         Ok(column)
     }
 
     pub async fn get_by_id(&self, id: Uuid) -> Result<Column> {
-        self.find_by_id(id).await?.ok_or(AppError::NotFound)
+        self.find_by_id(id)
+            .await?
+            .ok_or(AppError::NotFound("column"))
     }
 
+    /// Columns of a board that are not archived, in order
     pub async fn list_by_board(&self, board_id: Uuid) -> Result<Vec<Column>> {
+        let mut conn = self.pool.acquire().await?;
+        Self::list_by_board_on(&mut conn, board_id).await
+    }
+
+    pub(crate) async fn list_by_board_on(
+        conn: &mut SqliteConnection,
+        board_id: Uuid,
+    ) -> Result<Vec<Column>> {
         let columns = sqlx::query_as::<_, Column>(
-            "SELECT * FROM columns WHERE board_id = $1 ORDER BY position ASC",
+            "SELECT * FROM columns WHERE board_id = $1 AND archived_at IS NULL AND deleted_at IS NULL ORDER BY position ASC",
         )
         .bind(board_id)
-        .fetch_all(self.pool.as_ref())
+        .fetch_all(&mut *conn)
         .await?;
 
         Ok(columns)
     }
 
-    pub async fn update(&self, id: Uuid, name: Option<&str>) -> Result<Column> {
+    /// Columns of the given boards that are not archived, in order, each with the number
+    /// of cards it holds, in one query
+    pub async fn card_counts_for_boards(
+        &self,
+        board_ids: &[Uuid],
+    ) -> Result<Vec<BoardColumnCardCount>> {
+        if board_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut query = QueryBuilder::<Sqlite>::new(
+            r#"
+            SELECT col.board_id, col.id AS column_id, col.name, COUNT(c.id) AS card_count
+            FROM columns col
+            LEFT JOIN card_boards cb ON cb.column_id = col.id
+            LEFT JOIN cards c ON c.id = cb.card_id
+                AND c.archived_at IS NULL AND c.deleted_at IS NULL
+            WHERE col.archived_at IS NULL AND col.deleted_at IS NULL AND col.board_id IN (
+            "#,
+        );
+        let mut ids = query.separated(", ");
+        for board_id in board_ids {
+            ids.push_bind(*board_id);
+        }
+        query.push(") GROUP BY col.id ORDER BY col.board_id, col.position ASC");
+
+        let counts = query
+            .build_query_as::<BoardColumnCardCount>()
+            .fetch_all(self.pool.as_ref())
+            .await?;
+
+        Ok(counts)
+    }
+
+    /// Rename the column, set or clear its WIP limit, color, description and card
+    /// status and change how it sorts its cards; `None` leaves a field unchanged. With an `expected_version`, a column that has
+    /// changed since that version is left alone and the update fails with
+    /// `PreconditionFailed`.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn update(
+        &self,
+        id: Uuid,
+        name: Option<&str>,
+        wip_limit: Option<Option<i32>>,
+        sort_policy: Option<ColumnSortPolicy>,
+        color: Option<Option<&str>>,
+        description: Option<Option<&str>>,
+        card_status: Option<Option<CardStatus>>,
+        expected_version: Option<i64>,
+    ) -> Result<Column> {
         let column = sqlx::query_as::<_, Column>(
             r#"
             UPDATE columns
             SET name = COALESCE($2, name),
+                wip_limit = CASE WHEN $3 THEN $4 ELSE wip_limit END,
+                sort_policy = COALESCE($5, sort_policy),
+                color = CASE WHEN $7 THEN $8 ELSE color END,
+                description = CASE WHEN $9 THEN $10 ELSE description END,
+                card_status = CASE WHEN $11 THEN $12 ELSE card_status END,
+                version = version + 1,
                 updated_at = datetime('now')
-            WHERE id = $1
+            WHERE id = $1 AND ($6 IS NULL OR version = $6)
             RETURNING *
             "#,
         )
         .bind(id)
         .bind(name)
-        .fetch_one(self.pool.as_ref())
+        .bind(wip_limit.is_some())
+        .bind(wip_limit.flatten())
+        .bind(sort_policy.map(|p| p.to_string()))
+        .bind(expected_version)
+        .bind(color.is_some())
+        .bind(color.flatten())
+        .bind(description.is_some())
+        .bind(description.flatten())
+        .bind(card_status.is_some())
+        .bind(card_status.flatten().map(|s| s.to_string()))
+        .fetch_optional(self.pool.as_ref())
         .await?;
 
-        Ok(column)
+        match column {
+            Some(column) => Ok(column),
+            None if self.find_by_id(id).await?.is_some() => Err(AppError::PreconditionFailed),
+            None => Err(AppError::NotFound("column")),
+        }
+    }
+
+    /// Collapse or expand a column on one user's view of its board
+    pub async fn set_collapsed(&self, id: Uuid, user_id: Uuid, collapsed: bool) -> Result<()> {
+        let query = if collapsed {
+            "INSERT OR IGNORE INTO column_collapses (user_id, column_id) VALUES ($1, $2)"
+        } else {
+            "DELETE FROM column_collapses WHERE user_id = $1 AND column_id = $2"
+        };
+        sqlx::query(query)
+            .bind(user_id)
+            .bind(id)
+            .execute(self.pool.as_ref())
+            .await?;
+
+        Ok(())
+    }
+
+    /// Columns of a board the user has collapsed
+    pub async fn collapsed_for_user(&self, board_id: Uuid, user_id: Uuid) -> Result<HashSet<Uuid>> {
+        let ids = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            SELECT cc.column_id FROM column_collapses cc
+            JOIN columns col ON col.id = cc.column_id
+            WHERE col.board_id = $1 AND cc.user_id = $2
+            "#,
+        )
+        .bind(board_id)
+        .bind(user_id)
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(ids.into_iter().collect())
     }
 
     pub async fn delete(&self, id: Uuid) -> Result<()> {
@@ -103,14 +243,199 @@ impl ColumnRepository {
             .await?;
 
         if result.rows_affected() == 0 {
-            return Err(AppError::NotFound);
+            return Err(AppError::NotFound("column"));
         }
 
         Ok(())
     }
 
-    pub async fn move_column(&self, id: Uuid, new_position: i32) -> Result<Column> {
+    /// Archive or unarchive a column. An archived column leaves the order of the
+    /// board; an unarchived one goes back at the end of it.
+    pub async fn set_archived(&self, id: Uuid, archived: bool) -> Result<Column> {
         let column = self.get_by_id(id).await?;
+        if column.archived_at.is_some() == archived {
+            return Ok(column);
+        }
+
+        let position = if archived {
+            sqlx::query(
+                r#"
+                UPDATE columns
+                SET position = position - 1
+                WHERE board_id = $1 AND position > $2 AND archived_at IS NULL
+                "#,
+            )
+            .bind(column.board_id)
+            .bind(column.position)
+            .execute(self.pool.as_ref())
+            .await?;
+            -1
+        } else {
+            let max_pos = sqlx::query_scalar::<_, Option<i32>>(
+                "SELECT MAX(position) FROM columns WHERE board_id = $1 AND archived_at IS NULL",
+            )
+            .bind(column.board_id)
+            .fetch_one(self.pool.as_ref())
+            .await?;
+            max_pos.unwrap_or(-1) + 1
+        };
+
+        let column = sqlx::query_as::<_, Column>(
+            r#"
+            UPDATE columns
+            SET archived_at = CASE WHEN $2 THEN datetime('now') ELSE NULL END,
+                position = $3,
+                version = version + 1,
+                updated_at = datetime('now')
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(archived)
+        .bind(position)
+        .fetch_one(self.pool.as_ref())
+        .await?;
+
+        Ok(column)
+    }
+
+    /// Move a column and the cards it holds to the trash. It leaves the order of the
+    /// board.
+    pub async fn move_to_trash(&self, id: Uuid, deleted_by: Uuid) -> Result<()> {
+        let mut conn = self.pool.acquire().await?;
+        Self::move_to_trash_on(&mut conn, id, deleted_by).await
+    }
+
+    pub(crate) async fn move_to_trash_on(
+        conn: &mut SqliteConnection,
+        id: Uuid,
+        deleted_by: Uuid,
+    ) -> Result<()> {
+        let column = Self::get_on(conn, id).await?;
+        if column.archived_at.is_none() {
+            sqlx::query(
+                r#"
+                UPDATE columns
+                SET position = position - 1
+                WHERE board_id = $1 AND position > $2 AND archived_at IS NULL AND deleted_at IS NULL
+                "#,
+            )
+            .bind(column.board_id)
+            .bind(column.position)
+            .execute(&mut *conn)
+            .await?;
+        }
+
+        sqlx::query(
+            "UPDATE columns SET deleted_at = datetime('now'), deleted_by = $2, position = -1 WHERE id = $1",
+        )
+        .bind(id)
+        .bind(deleted_by)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+
+    /// A column in the trash
+    pub async fn find_deleted(&self, id: Uuid) -> Result<Option<Column>> {
+        let mut conn = self.pool.acquire().await?;
+        Self::find_deleted_on(&mut conn, id).await
+    }
+
+    pub(crate) async fn find_deleted_on(
+        conn: &mut SqliteConnection,
+        id: Uuid,
+    ) -> Result<Option<Column>> {
+        let column = sqlx::query_as::<_, Column>(
+            "SELECT * FROM columns WHERE id = $1 AND deleted_at IS NOT NULL",
+        )
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await?;
+
+        Ok(column)
+    }
+
+    /// Take a column out of the trash, at the end of its board
+    pub async fn restore(&self, id: Uuid) -> Result<Column> {
+        let mut conn = self.pool.acquire().await?;
+        Self::restore_on(&mut conn, id).await
+    }
+
+    pub(crate) async fn restore_on(conn: &mut SqliteConnection, id: Uuid) -> Result<Column> {
+        let column = Self::find_deleted_on(conn, id)
+            .await?
+            .ok_or(AppError::NotFound("column"))?;
+
+        let position = if column.archived_at.is_none() {
+            let max_pos = sqlx::query_scalar::<_, Option<i32>>(
+                "SELECT MAX(position) FROM columns WHERE board_id = $1 AND archived_at IS NULL AND deleted_at IS NULL",
+            )
+            .bind(column.board_id)
+            .fetch_one(&mut *conn)
+            .await?;
+            max_pos.unwrap_or(-1) + 1
+        } else {
+            column.position
+        };
+
+        let column = sqlx::query_as::<_, Column>(
+            r#"
+            UPDATE columns
+            SET deleted_at = NULL, deleted_by = NULL, position = $2, updated_at = datetime('now')
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(position)
+        .fetch_one(&mut *conn)
+        .await?;
+
+        Ok(column)
+    }
+
+    /// Archived columns of a board, most recently archived first
+    pub async fn list_archived_by_board(&self, board_id: Uuid) -> Result<Vec<Column>> {
+        let columns = sqlx::query_as::<_, Column>(
+            "SELECT * FROM columns WHERE board_id = $1 AND archived_at IS NOT NULL AND deleted_at IS NULL ORDER BY archived_at DESC, rowid DESC",
+        )
+        .bind(board_id)
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(columns)
+    }
+
+    /// Move a column to `new_position` on its board. The position is clamped to the
+    /// board, so the columns stay numbered from 0 without gaps.
+    pub async fn move_column(&self, id: Uuid, new_position: i32) -> Result<Column> {
+        // Take the write lock up front, as for card moves
+        let mut tx = self.pool.begin_with("BEGIN IMMEDIATE").await?;
+        let column = Self::move_column_on(&mut tx, id, new_position).await?;
+        tx.commit().await?;
+        Ok(column)
+    }
+
+    pub(crate) async fn move_column_on(
+        conn: &mut SqliteConnection,
+        id: Uuid,
+        new_position: i32,
+    ) -> Result<Column> {
+        let column = Self::get_on(conn, id).await?;
+        Self::rebalance_board_on(conn, column.board_id).await?;
+        let column = Self::get_on(conn, id).await?;
+
+        let others = sqlx::query_scalar::<_, i32>(
+            "SELECT COUNT(*) FROM columns WHERE board_id = $1 AND id != $2 AND archived_at IS NULL AND deleted_at IS NULL",
+        )
+        .bind(column.board_id)
+        .bind(id)
+        .fetch_one(&mut *conn)
+        .await?;
+        let new_position = new_position.clamp(0, others);
 
         // Shift other columns
         if new_position > column.position {
@@ -119,12 +444,13 @@ impl ColumnRepository {
                 UPDATE columns
                 SET position = position - 1
                 WHERE board_id = $1 AND position > $2 AND position <= $3
+                  AND archived_at IS NULL AND deleted_at IS NULL
                 "#,
             )
             .bind(column.board_id)
             .bind(column.position)
             .bind(new_position)
-            .execute(self.pool.as_ref())
+            .execute(&mut *conn)
             .await?;
         } else if new_position < column.position {
             sqlx::query(
@@ -132,12 +458,13 @@ impl ColumnRepository {
                 UPDATE columns
                 SET position = position + 1
                 WHERE board_id = $1 AND position >= $2 AND position < $3
+                  AND archived_at IS NULL AND deleted_at IS NULL
                 "#,
             )
             .bind(column.board_id)
             .bind(new_position)
             .bind(column.position)
-            .execute(self.pool.as_ref())
+            .execute(&mut *conn)
             .await?;
         }
 
@@ -152,9 +479,46 @@ impl ColumnRepository {
         )
         .bind(id)
         .bind(new_position)
-        .fetch_one(self.pool.as_ref())
+        .fetch_one(&mut *conn)
         .await?;
 
         Ok(updated)
     }
+
+    pub(crate) async fn get_on(conn: &mut SqliteConnection, id: Uuid) -> Result<Column> {
+        sqlx::query_as::<_, Column>("SELECT * FROM columns WHERE id = $1 AND deleted_at IS NULL")
+            .bind(id)
+            .fetch_optional(&mut *conn)
+            .await?
+            .ok_or(AppError::NotFound("column"))
+    }
+
+    /// Renumber the columns of a board 0, 1, 2... keeping their order, and return how
+    /// many had to change
+    pub async fn rebalance_board(&self, board_id: Uuid) -> Result<u64> {
+        let mut tx = self.pool.begin_with("BEGIN IMMEDIATE").await?;
+        let changed = Self::rebalance_board_on(&mut tx, board_id).await?;
+        tx.commit().await?;
+        Ok(changed)
+    }
+
+    async fn rebalance_board_on(conn: &mut SqliteConnection, board_id: Uuid) -> Result<u64> {
+        let result = sqlx::query(
+            r#"
+            UPDATE columns
+            SET position = ranked.position
+            FROM (
+                SELECT id, ROW_NUMBER() OVER (ORDER BY position ASC, rowid ASC) - 1 AS position
+                FROM columns
+                WHERE board_id = $1 AND archived_at IS NULL AND deleted_at IS NULL
+            ) AS ranked
+            WHERE columns.id = ranked.id AND columns.position != ranked.position
+            "#,
+        )
+        .bind(board_id)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
 }
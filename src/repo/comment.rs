@@ -1,9 +1,12 @@
-use sqlx::SqlitePool;
+use sqlx::{SqliteConnection, SqlitePool};
 use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::error::{AppError, Result};
-use crate::models::{Comment, CommentWithAuthor};
+use crate::models::{Comment, CommentDraft, CommentWithAuthor, Page};
+
+/// How long an untouched comment draft is kept
+const DRAFT_TTL: &str = "+7 days";
 
 #[derive(Clone)]
 pub struct CommentRepository {
@@ -17,6 +20,16 @@ impl CommentRepository {
 
     /// Create a new comment on a card
     pub async fn create(&self, card_id: Uuid, user_id: Uuid, body: &str) -> Result<Comment> {
+        let mut conn = self.pool.acquire().await?;
+        Self::create_on(&mut conn, card_id, user_id, body).await
+    }
+
+    pub(crate) async fn create_on(
+        conn: &mut SqliteConnection,
+        card_id: Uuid,
+        user_id: Uuid,
+        body: &str,
+    ) -> Result<Comment> {
         let id = Uuid::new_v4();
 
         let comment = sqlx::query_as::<_, Comment>(
@@ -30,17 +43,51 @@ impl CommentRepository {
         .bind(card_id)
         .bind(user_id)
         .bind(body)
-        .fetch_one(self.pool.as_ref())
+        .fetch_one(&mut *conn)
         .await?;
 
         Ok(comment)
     }
 
+    /// Copy the comments of a card to another, keeping their authors and dates
+    pub(crate) async fn copy_on(
+        conn: &mut SqliteConnection,
+        from_card_id: Uuid,
+        to_card_id: Uuid,
+    ) -> Result<()> {
+        let rows = sqlx::query_scalar::<_, i64>(
+            "SELECT rowid FROM comments WHERE card_id = $1 ORDER BY created_at ASC, rowid ASC",
+        )
+        .bind(from_card_id)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        for row in rows {
+            sqlx::query(
+                r#"
+                INSERT INTO comments (id, card_id, user_id, body, created_at, updated_at)
+                SELECT $1, $2, user_id, body, created_at, updated_at FROM comments WHERE rowid = $3
+                "#,
+            )
+            .bind(Uuid::new_v4())
+            .bind(to_card_id)
+            .bind(row)
+            .execute(&mut *conn)
+            .await?;
+        }
+        Ok(())
+    }
+
     /// Find a comment by ID
     pub async fn find_by_id(&self, id: Uuid) -> Result<Option<Comment>> {
+        let mut conn = self.pool.acquire().await?;
+        Self::find_on(&mut conn, id).await
+    }
+
+    pub(crate) async fn find_on(conn: &mut SqliteConnection, id: Uuid) -> Result<Option<Comment>> {
         let comment = sqlx::query_as::<_, Comment>("SELECT * FROM comments WHERE id = $1")
             .bind(id)
-            .fetch_optional(self.pool.as_ref())
+            .fetch_optional(&mut *conn)
             .await?;
 
         Ok(comment)
@@ -48,11 +95,22 @@ impl CommentRepository {
 
     /// Get a comment by ID or return NotFound error
     pub async fn get_by_id(&self, id: Uuid) -> Result<Comment> {
-        self.find_by_id(id).await?.ok_or(AppError::NotFound)
+        self.find_by_id(id)
+            .await?
+            .ok_or(AppError::NotFound("comment"))
     }
 
     /// List all comments for a card, ordered by creation time
     pub async fn list_by_card(&self, card_id: Uuid) -> Result<Vec<CommentWithAuthor>> {
+        self.list_page_by_card(card_id, Page::ALL).await
+    }
+
+    /// Comments of a card with their authors, oldest first
+    pub async fn list_page_by_card(
+        &self,
+        card_id: Uuid,
+        page: Page,
+    ) -> Result<Vec<CommentWithAuthor>> {
         let comments = sqlx::query_as::<_, CommentWithAuthor>(
             r#"
             SELECT c.id, c.card_id, c.user_id, c.body, c.created_at, c.updated_at,
@@ -60,10 +118,13 @@ impl CommentRepository {
             FROM comments c
             INNER JOIN users u ON c.user_id = u.id
             WHERE c.card_id = $1
-            ORDER BY c.created_at ASC
+            ORDER BY c.created_at ASC, c.rowid ASC
+            LIMIT $2 OFFSET $3
             "#,
         )
         .bind(card_id)
+        .bind(page.limit)
+        .bind(page.offset)
         .fetch_all(self.pool.as_ref())
         .await?;
 
@@ -90,13 +151,18 @@ impl CommentRepository {
 
     /// Delete a comment
     pub async fn delete(&self, id: Uuid) -> Result<()> {
+        let mut conn = self.pool.acquire().await?;
+        Self::delete_on(&mut conn, id).await
+    }
+
+    pub(crate) async fn delete_on(conn: &mut SqliteConnection, id: Uuid) -> Result<()> {
         let result = sqlx::query("DELETE FROM comments WHERE id = $1")
             .bind(id)
-            .execute(self.pool.as_ref())
+            .execute(&mut *conn)
             .await?;
 
         if result.rows_affected() == 0 {
-            return Err(AppError::NotFound);
+            return Err(AppError::NotFound("comment"));
         }
 
         Ok(())
@@ -104,12 +170,70 @@ impl CommentRepository {
 
     /// Count comments for a card
     pub async fn count_by_card(&self, card_id: Uuid) -> Result<i64> {
-        let count: (i64,) =
-            sqlx::query_as("SELECT COUNT(*) FROM comments WHERE card_id = $1")
-                .bind(card_id)
-                .fetch_one(self.pool.as_ref())
-                .await?;
+        let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM comments WHERE card_id = $1")
+            .bind(card_id)
+            .fetch_one(self.pool.as_ref())
+            .await?;
 
         Ok(count.0)
     }
+
+    /// Save the user's draft for a card, replacing any previous one and pruning expired drafts
+    pub async fn save_draft(
+        &self,
+        card_id: Uuid,
+        user_id: Uuid,
+        body: &str,
+    ) -> Result<CommentDraft> {
+        sqlx::query("DELETE FROM comment_drafts WHERE expires_at <= datetime('now')")
+            .execute(self.pool.as_ref())
+            .await?;
+
+        let draft = sqlx::query_as::<_, CommentDraft>(
+            r#"
+            INSERT INTO comment_drafts (card_id, user_id, body, updated_at, expires_at)
+            VALUES ($1, $2, $3, datetime('now'), datetime('now', $4))
+            ON CONFLICT (card_id, user_id) DO UPDATE
+            SET body = excluded.body,
+                updated_at = excluded.updated_at,
+                expires_at = excluded.expires_at
+            RETURNING *
+            "#,
+        )
+        .bind(card_id)
+        .bind(user_id)
+        .bind(body)
+        .bind(DRAFT_TTL)
+        .fetch_one(self.pool.as_ref())
+        .await?;
+
+        Ok(draft)
+    }
+
+    /// Find the user's unexpired draft for a card
+    pub async fn find_draft(&self, card_id: Uuid, user_id: Uuid) -> Result<Option<CommentDraft>> {
+        let draft = sqlx::query_as::<_, CommentDraft>(
+            r#"
+            SELECT * FROM comment_drafts
+            WHERE card_id = $1 AND user_id = $2 AND expires_at > datetime('now')
+            "#,
+        )
+        .bind(card_id)
+        .bind(user_id)
+        .fetch_optional(self.pool.as_ref())
+        .await?;
+
+        Ok(draft)
+    }
+
+    /// Delete the user's draft for a card (no-op if there is none)
+    pub async fn delete_draft(&self, card_id: Uuid, user_id: Uuid) -> Result<()> {
+        sqlx::query("DELETE FROM comment_drafts WHERE card_id = $1 AND user_id = $2")
+            .bind(card_id)
+            .bind(user_id)
+            .execute(self.pool.as_ref())
+            .await?;
+
+        Ok(())
+    }
 }
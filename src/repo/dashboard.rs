@@ -0,0 +1,107 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::error::Result;
+use crate::models::DashboardCard;
+
+/// Live cards user `$1` can see, by the same rules as card access: their own cards
+/// and cards on boards they are a member of. Cards in archived columns and on
+/// deleted boards are left out.
+const VISIBLE_CARDS: &str = r#"
+    SELECT c.*, col.board_id AS board_id, b.name AS board_name
+    FROM cards c
+    LEFT JOIN columns col ON col.id = c.column_id
+    LEFT JOIN boards b ON b.id = col.board_id
+    WHERE c.archived_at IS NULL AND c.deleted_at IS NULL
+      AND (c.column_id IS NULL
+           OR (col.archived_at IS NULL AND col.deleted_at IS NULL AND b.deleted_at IS NULL))
+      AND (c.owner_id = $1 OR c.created_by = $1
+           OR EXISTS (
+               SELECT 1 FROM board_access bp
+               INNER JOIN boards pb ON pb.id = bp.board_id
+               WHERE bp.user_id = $1 AND pb.deleted_at IS NULL
+                 AND (bp.board_id IN (SELECT cb.board_id FROM card_boards cb WHERE cb.card_id = c.id)
+                      OR bp.board_id = col.board_id)
+           ))
+"#;
+
+/// Cards still to be worked on
+const UNFINISHED: &str = "c.status IN ('open', 'in_progress')";
+
+/// Cross-board lists of cards for the "My Work" dashboard
+#[derive(Clone)]
+pub struct DashboardRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl DashboardRepository {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+
+    /// Unfinished cards assigned to the user, soonest due first
+    pub async fn list_assigned(&self, user_id: Uuid) -> Result<Vec<DashboardCard>> {
+        let query = format!(
+            r#"{VISIBLE_CARDS}
+              AND {UNFINISHED}
+              AND c.id IN (SELECT card_id FROM card_assignees WHERE user_id = $1)
+            ORDER BY c.due_date IS NULL, c.due_date ASC, c.updated_at DESC"#
+        );
+        let cards = sqlx::query_as::<_, DashboardCard>(&query)
+            .bind(user_id)
+            .fetch_all(self.pool.as_ref())
+            .await?;
+
+        Ok(cards)
+    }
+
+    /// Unfinished cards due between `from` and `to`, both included. Without `from`,
+    /// every card due up to `to`.
+    pub async fn list_due(
+        &self,
+        user_id: Uuid,
+        from: Option<NaiveDate>,
+        to: NaiveDate,
+    ) -> Result<Vec<DashboardCard>> {
+        let query = format!(
+            r#"{VISIBLE_CARDS}
+              AND {UNFINISHED}
+              AND c.due_date <= $3 AND ($2 IS NULL OR c.due_date >= $2)
+            ORDER BY c.due_date ASC, c.title ASC"#
+        );
+        let cards = sqlx::query_as::<_, DashboardCard>(&query)
+            .bind(user_id)
+            .bind(from)
+            .bind(to)
+            .fetch_all(self.pool.as_ref())
+            .await?;
+
+        Ok(cards)
+    }
+
+    /// Cards updated since `since`, most recent first
+    pub async fn list_updated_since(
+        &self,
+        user_id: Uuid,
+        since: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<DashboardCard>> {
+        let query = format!(
+            r#"{VISIBLE_CARDS}
+              AND c.updated_at >= $2
+            ORDER BY c.updated_at DESC, c.rowid DESC
+            LIMIT $3"#
+        );
+        // updated_at is stored in SQLite's datetime('now') format, so compare in it
+        let cards = sqlx::query_as::<_, DashboardCard>(&query)
+            .bind(user_id)
+            .bind(since.format("%Y-%m-%d %H:%M:%S").to_string())
+            .bind(limit)
+            .fetch_all(self.pool.as_ref())
+            .await?;
+
+        Ok(cards)
+    }
+}
@@ -0,0 +1,85 @@
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::error::Result;
+use crate::models::EmailChange;
+
+#[derive(Clone)]
+pub struct EmailChangeRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl EmailChangeRepository {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+
+    /// Record a requested change, replacing the user's pending one
+    pub async fn create(
+        &self,
+        user_id: Uuid,
+        new_email: &str,
+        token_hash: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<EmailChange> {
+        let change = sqlx::query_as::<_, EmailChange>(
+            r#"
+            INSERT INTO email_changes (id, user_id, new_email, token_hash, expires_at, created_at)
+            VALUES ($1, $2, $3, $4, $5, datetime('now'))
+            ON CONFLICT (user_id) DO UPDATE SET
+                id = excluded.id,
+                new_email = excluded.new_email,
+                token_hash = excluded.token_hash,
+                expires_at = excluded.expires_at,
+                created_at = excluded.created_at
+            RETURNING *
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(user_id)
+        .bind(new_email)
+        .bind(token_hash)
+        .bind(expires_at)
+        .fetch_one(self.pool.as_ref())
+        .await?;
+
+        Ok(change)
+    }
+
+    /// The user's unexpired pending change
+    pub async fn find_for_user(&self, user_id: Uuid) -> Result<Option<EmailChange>> {
+        let change = sqlx::query_as::<_, EmailChange>(
+            "SELECT * FROM email_changes WHERE user_id = $1 AND expires_at > $2",
+        )
+        .bind(user_id)
+        .bind(Utc::now())
+        .fetch_optional(self.pool.as_ref())
+        .await?;
+
+        Ok(change)
+    }
+
+    /// Find an unexpired change by the hash of its token
+    pub async fn find_by_token_hash(&self, token_hash: &str) -> Result<Option<EmailChange>> {
+        let change = sqlx::query_as::<_, EmailChange>(
+            "SELECT * FROM email_changes WHERE token_hash = $1 AND expires_at > $2",
+        )
+        .bind(token_hash)
+        .bind(Utc::now())
+        .fetch_optional(self.pool.as_ref())
+        .await?;
+
+        Ok(change)
+    }
+
+    pub async fn delete(&self, id: Uuid) -> Result<()> {
+        sqlx::query("DELETE FROM email_changes WHERE id = $1")
+            .bind(id)
+            .execute(self.pool.as_ref())
+            .await?;
+
+        Ok(())
+    }
+}
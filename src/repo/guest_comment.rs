@@ -0,0 +1,135 @@
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::error::Result;
+use crate::models::GuestComment;
+
+/// Guest comments with the title of their card
+const SELECT_GUEST_COMMENT: &str = r#"
+    SELECT g.id, g.card_id, c.title AS card_title, g.board_id, g.name, g.body,
+           g.approved_at, g.approved_by, g.created_at
+    FROM guest_comments g
+    INNER JOIN cards c ON c.id = g.card_id
+"#;
+
+/// Comments left by visitors of share links, awaiting or past moderation
+#[derive(Clone)]
+pub struct GuestCommentRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl GuestCommentRepository {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(
+        &self,
+        card_id: Uuid,
+        board_id: Uuid,
+        name: &str,
+        body: &str,
+    ) -> Result<Uuid> {
+        let id = Uuid::new_v4();
+        sqlx::query(
+            r#"
+            INSERT INTO guest_comments (id, card_id, board_id, name, body, created_at)
+            VALUES ($1, $2, $3, $4, $5, datetime('now'))
+            "#,
+        )
+        .bind(id)
+        .bind(card_id)
+        .bind(board_id)
+        .bind(name)
+        .bind(body)
+        .execute(self.pool.as_ref())
+        .await?;
+
+        Ok(id)
+    }
+
+    /// Guest comments on the board's cards, newest first, only those awaiting
+    /// moderation when `pending_only`. Comments on deleted cards are left out.
+    pub async fn list_for_board(
+        &self,
+        board_id: Uuid,
+        pending_only: bool,
+    ) -> Result<Vec<GuestComment>> {
+        let comments = sqlx::query_as::<_, GuestComment>(&format!(
+            r#"{}
+            WHERE g.board_id = $1 AND ($2 = 0 OR g.approved_at IS NULL)
+              AND c.deleted_at IS NULL
+            ORDER BY g.created_at DESC, g.rowid DESC
+            "#,
+            SELECT_GUEST_COMMENT
+        ))
+        .bind(board_id)
+        .bind(pending_only)
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(comments)
+    }
+
+    /// Approved guest comments left through the board's share link, oldest first
+    pub async fn list_approved_for_board(&self, board_id: Uuid) -> Result<Vec<GuestComment>> {
+        let comments = sqlx::query_as::<_, GuestComment>(&format!(
+            r#"{}
+            WHERE g.board_id = $1 AND g.approved_at IS NOT NULL
+            ORDER BY g.created_at ASC, g.rowid ASC
+            "#,
+            SELECT_GUEST_COMMENT
+        ))
+        .bind(board_id)
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(comments)
+    }
+
+    /// Approve a guest comment on the board. Returns None if the board has no such
+    /// comment.
+    pub async fn approve(
+        &self,
+        id: Uuid,
+        board_id: Uuid,
+        approved_by: Uuid,
+    ) -> Result<Option<GuestComment>> {
+        let result = sqlx::query(
+            r#"
+            UPDATE guest_comments
+            SET approved_at = COALESCE(approved_at, datetime('now')),
+                approved_by = COALESCE(approved_by, $3)
+            WHERE id = $1 AND board_id = $2
+            "#,
+        )
+        .bind(id)
+        .bind(board_id)
+        .bind(approved_by)
+        .execute(self.pool.as_ref())
+        .await?;
+        if result.rows_affected() == 0 {
+            return Ok(None);
+        }
+
+        let comment =
+            sqlx::query_as::<_, GuestComment>(&format!("{} WHERE g.id = $1", SELECT_GUEST_COMMENT))
+                .bind(id)
+                .fetch_optional(self.pool.as_ref())
+                .await?;
+
+        Ok(comment)
+    }
+
+    /// Delete a guest comment on the board. Returns whether there was one.
+    pub async fn delete(&self, id: Uuid, board_id: Uuid) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM guest_comments WHERE id = $1 AND board_id = $2")
+            .bind(id)
+            .bind(board_id)
+            .execute(self.pool.as_ref())
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,66 @@
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::error::Result;
+use crate::models::UserIdentity;
+
+#[derive(Clone)]
+pub struct IdentityRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl IdentityRepository {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+
+    /// Link a provider account to a user
+    pub async fn create(
+        &self,
+        user_id: Uuid,
+        provider: &str,
+        subject: &str,
+        email: &str,
+    ) -> Result<UserIdentity> {
+        let identity = sqlx::query_as::<_, UserIdentity>(
+            r#"
+            INSERT INTO user_identities (id, user_id, provider, subject, email, created_at)
+            VALUES ($1, $2, $3, $4, $5, datetime('now'))
+            RETURNING *
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(user_id)
+        .bind(provider)
+        .bind(subject)
+        .bind(email)
+        .fetch_one(self.pool.as_ref())
+        .await?;
+
+        Ok(identity)
+    }
+
+    pub async fn find(&self, provider: &str, subject: &str) -> Result<Option<UserIdentity>> {
+        let identity = sqlx::query_as::<_, UserIdentity>(
+            "SELECT * FROM user_identities WHERE provider = $1 AND subject = $2",
+        )
+        .bind(provider)
+        .bind(subject)
+        .fetch_optional(self.pool.as_ref())
+        .await?;
+
+        Ok(identity)
+    }
+
+    pub async fn list_for_user(&self, user_id: Uuid) -> Result<Vec<UserIdentity>> {
+        let identities = sqlx::query_as::<_, UserIdentity>(
+            "SELECT * FROM user_identities WHERE user_id = $1 ORDER BY created_at",
+        )
+        .bind(user_id)
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(identities)
+    }
+}
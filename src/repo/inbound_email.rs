@@ -0,0 +1,65 @@
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::error::Result;
+
+#[derive(Clone)]
+pub struct InboundEmailRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl InboundEmailRepository {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+
+    /// Set the user's address token, replacing the previous one
+    pub async fn set_token(&self, user_id: Uuid, token_hash: &str) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO inbound_email_addresses (user_id, token_hash, created_at)
+            VALUES ($1, $2, datetime('now'))
+            ON CONFLICT(user_id) DO UPDATE SET token_hash = excluded.token_hash, created_at = excluded.created_at
+            "#,
+        )
+        .bind(user_id)
+        .bind(token_hash)
+        .execute(self.pool.as_ref())
+        .await?;
+
+        Ok(())
+    }
+
+    /// Remove the user's address. Returns whether there was one.
+    pub async fn delete(&self, user_id: Uuid) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM inbound_email_addresses WHERE user_id = $1")
+            .bind(user_id)
+            .execute(self.pool.as_ref())
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn exists(&self, user_id: Uuid) -> Result<bool> {
+        let exists = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS(SELECT 1 FROM inbound_email_addresses WHERE user_id = $1)",
+        )
+        .bind(user_id)
+        .fetch_one(self.pool.as_ref())
+        .await?;
+
+        Ok(exists)
+    }
+
+    pub async fn find_user_by_token(&self, token_hash: &str) -> Result<Option<Uuid>> {
+        let user_id = sqlx::query_scalar::<_, Uuid>(
+            "SELECT user_id FROM inbound_email_addresses WHERE token_hash = $1",
+        )
+        .bind(token_hash)
+        .fetch_optional(self.pool.as_ref())
+        .await?;
+
+        Ok(user_id)
+    }
+}
@@ -0,0 +1,204 @@
+use sqlx::SqlitePool;
+use std::sync::Arc;
+
+use crate::error::Result;
+use crate::models::IntegrityIssue;
+
+/// A kind of orphaned or dangling row, with the queries to count and repair it
+struct IntegrityCheck {
+    name: &'static str,
+    description: &'static str,
+    count: &'static str,
+    repair: &'static str,
+}
+
+const CHECKS: &[IntegrityCheck] = &[
+    IntegrityCheck {
+        name: "card_boards_missing_card",
+        description: "Board assignments of deleted cards",
+        count: "SELECT COUNT(*) FROM card_boards WHERE card_id NOT IN (SELECT id FROM cards)",
+        repair: "DELETE FROM card_boards WHERE card_id NOT IN (SELECT id FROM cards)",
+    },
+    IntegrityCheck {
+        name: "card_boards_missing_board",
+        description: "Card assignments to deleted boards",
+        count: "SELECT COUNT(*) FROM card_boards WHERE board_id NOT IN (SELECT id FROM boards)",
+        repair: "DELETE FROM card_boards WHERE board_id NOT IN (SELECT id FROM boards)",
+    },
+    IntegrityCheck {
+        name: "card_boards_invalid_column",
+        description: "Card assignments placed in a deleted column or a column of another board",
+        count: r#"
+            SELECT COUNT(*) FROM card_boards cb
+            WHERE cb.column_id IS NOT NULL
+              AND NOT EXISTS (
+                  SELECT 1 FROM columns col WHERE col.id = cb.column_id AND col.board_id = cb.board_id
+              )
+        "#,
+        repair: r#"
+            UPDATE card_boards SET column_id = NULL
+            WHERE column_id IS NOT NULL
+              AND NOT EXISTS (
+                  SELECT 1 FROM columns col
+                  WHERE col.id = card_boards.column_id AND col.board_id = card_boards.board_id
+              )
+        "#,
+    },
+    IntegrityCheck {
+        name: "cards_missing_column",
+        description: "Cards pointing to a deleted column",
+        count: r#"
+            SELECT COUNT(*) FROM cards
+            WHERE column_id IS NOT NULL AND column_id NOT IN (SELECT id FROM columns)
+        "#,
+        repair: r#"
+            UPDATE cards SET column_id = NULL
+            WHERE column_id IS NOT NULL AND column_id NOT IN (SELECT id FROM columns)
+        "#,
+    },
+    IntegrityCheck {
+        name: "cards_missing_placement",
+        description: "Cards whose home column is not mirrored in their board assignment",
+        count: r#"
+            SELECT COUNT(*) FROM cards c
+            INNER JOIN columns col ON col.id = c.column_id
+            WHERE NOT EXISTS (
+                SELECT 1 FROM card_boards cb
+                WHERE cb.card_id = c.id AND cb.board_id = col.board_id
+                  AND cb.column_id IS c.column_id AND cb.position = c.position
+            )
+        "#,
+        repair: r#"
+            INSERT INTO card_boards (id, card_id, board_id, column_id, position, created_at)
+            SELECT randomblob(16), c.id, col.board_id, c.column_id, c.position, datetime('now')
+            FROM cards c
+            INNER JOIN columns col ON col.id = c.column_id
+            WHERE NOT EXISTS (
+                SELECT 1 FROM card_boards cb
+                WHERE cb.card_id = c.id AND cb.board_id = col.board_id
+                  AND cb.column_id IS c.column_id AND cb.position = c.position
+            )
+            ON CONFLICT(card_id, board_id) DO UPDATE SET
+                column_id = excluded.column_id,
+                position = excluded.position
+        "#,
+    },
+    IntegrityCheck {
+        name: "card_tags_missing_card_or_tag",
+        description: "Tag assignments of deleted cards or deleted tags",
+        count: r#"
+            SELECT COUNT(*) FROM card_tags
+            WHERE card_id NOT IN (SELECT id FROM cards) OR tag_id NOT IN (SELECT id FROM tags)
+        "#,
+        repair: r#"
+            DELETE FROM card_tags
+            WHERE card_id NOT IN (SELECT id FROM cards) OR tag_id NOT IN (SELECT id FROM tags)
+        "#,
+    },
+    IntegrityCheck {
+        name: "tags_missing_link",
+        description: "Board tags linked to a deleted global tag",
+        count: r#"
+            SELECT COUNT(*) FROM tags
+            WHERE linked_tag_id IS NOT NULL AND linked_tag_id NOT IN (SELECT id FROM tags)
+        "#,
+        repair: r#"
+            UPDATE tags SET linked_tag_id = NULL
+            WHERE linked_tag_id IS NOT NULL AND linked_tag_id NOT IN (SELECT id FROM tags)
+        "#,
+    },
+    IntegrityCheck {
+        name: "comments_missing_card",
+        description: "Comments on deleted cards",
+        count: "SELECT COUNT(*) FROM comments WHERE card_id NOT IN (SELECT id FROM cards)",
+        repair: "DELETE FROM comments WHERE card_id NOT IN (SELECT id FROM cards)",
+    },
+    IntegrityCheck {
+        name: "comment_drafts_missing_card",
+        description: "Comment drafts on deleted cards",
+        count: "SELECT COUNT(*) FROM comment_drafts WHERE card_id NOT IN (SELECT id FROM cards)",
+        repair: "DELETE FROM comment_drafts WHERE card_id NOT IN (SELECT id FROM cards)",
+    },
+    IntegrityCheck {
+        name: "board_permissions_missing_board_or_user",
+        description: "Permissions on deleted boards or for deleted users",
+        count: r#"
+            SELECT COUNT(*) FROM board_permissions
+            WHERE board_id NOT IN (SELECT id FROM boards) OR user_id NOT IN (SELECT id FROM users)
+        "#,
+        repair: r#"
+            DELETE FROM board_permissions
+            WHERE board_id NOT IN (SELECT id FROM boards) OR user_id NOT IN (SELECT id FROM users)
+        "#,
+    },
+    IntegrityCheck {
+        name: "chat_messages_missing_board",
+        description: "Board chat messages of deleted boards",
+        count: r#"
+            SELECT COUNT(*) FROM chat_messages
+            WHERE board_id IS NOT NULL AND board_id NOT IN (SELECT id FROM boards)
+        "#,
+        repair: r#"
+            DELETE FROM chat_messages
+            WHERE board_id IS NOT NULL AND board_id NOT IN (SELECT id FROM boards)
+        "#,
+    },
+];
+
+#[derive(Clone)]
+pub struct IntegrityRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl IntegrityRepository {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+
+    /// Count the rows affected by every known integrity problem
+    pub async fn scan(&self) -> Result<Vec<IntegrityIssue>> {
+        let mut issues = Vec::with_capacity(CHECKS.len());
+        for check in CHECKS {
+            let found = sqlx::query_scalar::<_, i64>(check.count)
+                .fetch_one(self.pool.as_ref())
+                .await?;
+            issues.push(IntegrityIssue {
+                check: check.name,
+                description: check.description,
+                found: found as u64,
+                fixed: 0,
+            });
+        }
+
+        Ok(issues)
+    }
+
+    /// Repair every problem in a single transaction, reporting what was fixed
+    pub async fn repair(&self) -> Result<Vec<IntegrityIssue>> {
+        let mut tx = self.pool.begin().await?;
+        let mut issues = Vec::with_capacity(CHECKS.len());
+
+        for check in CHECKS {
+            let found = sqlx::query_scalar::<_, i64>(check.count)
+                .fetch_one(&mut *tx)
+                .await?;
+            let fixed = if found > 0 {
+                sqlx::query(check.repair)
+                    .execute(&mut *tx)
+                    .await?
+                    .rows_affected()
+            } else {
+                0
+            };
+            issues.push(IntegrityIssue {
+                check: check.name,
+                description: check.description,
+                found: found as u64,
+                fixed,
+            });
+        }
+
+        tx.commit().await?;
+        Ok(issues)
+    }
+}
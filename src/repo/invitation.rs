@@ -0,0 +1,108 @@
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::error::Result;
+use crate::models::{BoardInvitation, BoardRole};
+
+#[derive(Clone)]
+pub struct InvitationRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl InvitationRepository {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+
+    /// Invite an email address to a board. Inviting the same address again replaces
+    /// the role, token and expiry of its pending invitation.
+    pub async fn create(
+        &self,
+        board_id: Uuid,
+        email: &str,
+        role: BoardRole,
+        token_hash: &str,
+        invited_by: Uuid,
+        expires_at: DateTime<Utc>,
+    ) -> Result<BoardInvitation> {
+        let invitation = sqlx::query_as::<_, BoardInvitation>(
+            r#"
+            INSERT INTO board_invitations (id, board_id, email, role, token_hash, invited_by, expires_at, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, datetime('now'))
+            ON CONFLICT (board_id, email) DO UPDATE SET
+                role = excluded.role,
+                token_hash = excluded.token_hash,
+                invited_by = excluded.invited_by,
+                expires_at = excluded.expires_at,
+                created_at = excluded.created_at
+            RETURNING *
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(board_id)
+        .bind(email)
+        .bind(role.to_string())
+        .bind(token_hash)
+        .bind(invited_by)
+        .bind(expires_at)
+        .fetch_one(self.pool.as_ref())
+        .await?;
+
+        Ok(invitation)
+    }
+
+    /// Pending invitations to a board, oldest first
+    pub async fn list_for_board(&self, board_id: Uuid) -> Result<Vec<BoardInvitation>> {
+        let invitations = sqlx::query_as::<_, BoardInvitation>(
+            r#"
+            SELECT * FROM board_invitations
+            WHERE board_id = $1
+            ORDER BY created_at ASC, rowid ASC
+            "#,
+        )
+        .bind(board_id)
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(invitations)
+    }
+
+    /// Find an unexpired invitation by the hash of its token
+    pub async fn find_by_token_hash(&self, token_hash: &str) -> Result<Option<BoardInvitation>> {
+        let invitation = sqlx::query_as::<_, BoardInvitation>(
+            "SELECT * FROM board_invitations WHERE token_hash = $1 AND expires_at > $2",
+        )
+        .bind(token_hash)
+        .bind(Utc::now())
+        .fetch_optional(self.pool.as_ref())
+        .await?;
+
+        Ok(invitation)
+    }
+
+    /// Unexpired invitations addressed to an email, compared case-insensitively
+    pub async fn list_for_email(&self, email: &str) -> Result<Vec<BoardInvitation>> {
+        let invitations = sqlx::query_as::<_, BoardInvitation>(
+            "SELECT * FROM board_invitations WHERE email = $1 AND expires_at > $2",
+        )
+        .bind(email)
+        .bind(Utc::now())
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(invitations)
+    }
+
+    /// Remove an invitation. Returns false if the board has no such invitation.
+    pub async fn delete(&self, id: Uuid, board_id: Uuid) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM board_invitations WHERE id = $1 AND board_id = $2")
+            .bind(id)
+            .bind(board_id)
+            .execute(self.pool.as_ref())
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,198 @@
+use chrono::{DateTime, Utc};
+use sqlx::{QueryBuilder, Sqlite, SqlitePool};
+use std::collections::HashSet;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::models::{CardLink, CardLinkRow, CardLinkType, LinkedCard};
+
+/// A link joined with the card at its other end
+#[derive(sqlx::FromRow)]
+struct LinkListRow {
+    id: Uuid,
+    link_type: String,
+    /// Whether the listed card is the stored source of the link
+    outgoing: bool,
+    created_by: Option<Uuid>,
+    created_at: DateTime<Utc>,
+    card_id: Uuid,
+    title: String,
+    status: String,
+    column_id: Option<Uuid>,
+}
+
+#[derive(Clone)]
+pub struct CardLinkRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl CardLinkRepository {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+
+    /// Link `card_id` to `other_card_id`. Reverse types are stored as their forward
+    /// type from the other card, so each relation exists once.
+    pub async fn create(
+        &self,
+        card_id: Uuid,
+        other_card_id: Uuid,
+        link_type: CardLinkType,
+        created_by: Uuid,
+    ) -> Result<CardLinkRow> {
+        let (source, target, link_type) = if link_type.is_stored_reversed() {
+            (other_card_id, card_id, link_type.inverse())
+        } else {
+            (card_id, other_card_id, link_type)
+        };
+
+        let link = sqlx::query_as::<_, CardLinkRow>(
+            r#"
+            INSERT OR IGNORE INTO card_links (id, source_card_id, target_card_id, link_type, created_by, created_at)
+            VALUES ($1, $2, $3, $4, $5, datetime('now'))
+            RETURNING *
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(source)
+        .bind(target)
+        .bind(link_type.to_string())
+        .bind(created_by)
+        .fetch_optional(self.pool.as_ref())
+        .await?;
+
+        link.ok_or_else(|| AppError::Conflict("These cards are already linked".to_string()))
+    }
+
+    pub async fn get_by_id(&self, id: Uuid) -> Result<CardLinkRow> {
+        sqlx::query_as::<_, CardLinkRow>("SELECT * FROM card_links WHERE id = $1")
+            .bind(id)
+            .fetch_optional(self.pool.as_ref())
+            .await?
+            .ok_or(AppError::NotFound("link"))
+    }
+
+    pub async fn delete(&self, id: Uuid) -> Result<()> {
+        let result = sqlx::query("DELETE FROM card_links WHERE id = $1")
+            .bind(id)
+            .execute(self.pool.as_ref())
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("link"));
+        }
+
+        Ok(())
+    }
+
+    /// Links of a card in both directions, read from the card, oldest first.
+    /// Links to cards in the trash are left out.
+    pub async fn list_for_card(&self, card_id: Uuid) -> Result<Vec<CardLink>> {
+        let rows = sqlx::query_as::<_, LinkListRow>(
+            r#"
+            SELECT l.id, l.link_type, l.source_card_id = $1 AS outgoing, l.created_by, l.created_at,
+                   c.id AS card_id, c.title, c.status, c.column_id
+            FROM card_links l
+            JOIN cards c ON c.id = CASE WHEN l.source_card_id = $1 THEN l.target_card_id ELSE l.source_card_id END
+            WHERE (l.source_card_id = $1 OR l.target_card_id = $1) AND c.deleted_at IS NULL
+            ORDER BY l.created_at ASC, l.rowid ASC
+            "#,
+        )
+        .bind(card_id)
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        let links = rows
+            .into_iter()
+            .filter_map(|row| {
+                let link_type: CardLinkType = row.link_type.parse().ok()?;
+                Some(CardLink {
+                    id: row.id,
+                    link_type: if row.outgoing {
+                        link_type
+                    } else {
+                        link_type.inverse()
+                    },
+                    card: LinkedCard {
+                        id: row.card_id,
+                        title: row.title,
+                        status: row.status,
+                        column_id: row.column_id,
+                    },
+                    created_by: row.created_by,
+                    created_at: row.created_at,
+                })
+            })
+            .collect();
+
+        Ok(links)
+    }
+
+    /// Cards blocking `card_id` that are neither done nor closed
+    pub async fn list_open_blockers(&self, card_id: Uuid) -> Result<Vec<LinkedCard>> {
+        let blockers = sqlx::query_as::<_, LinkedCard>(
+            r#"
+            SELECT c.id, c.title, c.status, c.column_id
+            FROM card_links l
+            JOIN cards c ON c.id = l.source_card_id
+            WHERE l.target_card_id = $1 AND l.link_type = 'blocks'
+              AND c.deleted_at IS NULL AND c.status NOT IN ('done', 'closed')
+            ORDER BY l.created_at ASC, l.rowid ASC
+            "#,
+        )
+        .bind(card_id)
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(blockers)
+    }
+
+    /// Cards that `card_id` blocks, apart from those in the trash
+    pub async fn list_blocked(&self, card_id: Uuid) -> Result<Vec<LinkedCard>> {
+        let blocked = sqlx::query_as::<_, LinkedCard>(
+            r#"
+            SELECT c.id, c.title, c.status, c.column_id
+            FROM card_links l
+            JOIN cards c ON c.id = l.target_card_id
+            WHERE l.source_card_id = $1 AND l.link_type = 'blocks' AND c.deleted_at IS NULL
+            ORDER BY l.created_at ASC, l.rowid ASC
+            "#,
+        )
+        .bind(card_id)
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(blocked)
+    }
+
+    /// The given cards that have open blockers, in one query
+    pub async fn blocked_card_ids(&self, card_ids: &[Uuid]) -> Result<HashSet<Uuid>> {
+        if card_ids.is_empty() {
+            return Ok(HashSet::new());
+        }
+
+        let mut query = QueryBuilder::<Sqlite>::new(
+            r#"
+            SELECT DISTINCT l.target_card_id
+            FROM card_links l
+            JOIN cards c ON c.id = l.source_card_id
+            WHERE l.link_type = 'blocks'
+              AND c.deleted_at IS NULL AND c.status NOT IN ('done', 'closed')
+              AND l.target_card_id IN (
+            "#,
+        );
+        let mut ids = query.separated(", ");
+        for card_id in card_ids {
+            ids.push_bind(*card_id);
+        }
+        query.push(")");
+
+        let blocked = query
+            .build_query_scalar::<Uuid>()
+            .fetch_all(self.pool.as_ref())
+            .await?;
+
+        Ok(blocked.into_iter().collect())
+    }
+}
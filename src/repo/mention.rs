@@ -0,0 +1,114 @@
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::error::Result;
+use crate::models::MentionedUser;
+
+/// Users mentioned in comments
+#[derive(Clone)]
+pub struct MentionRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl MentionRepository {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+
+    /// Active users who can see the card and so can be mentioned on it: its owner and
+    /// creator, and the members of the boards it is on
+    pub async fn list_card_users(&self, card_id: Uuid) -> Result<Vec<MentionedUser>> {
+        let users = sqlx::query_as::<_, MentionedUser>(
+            r#"
+            SELECT u.id, u.name, u.email FROM users u
+            WHERE u.deactivated_at IS NULL
+              AND (u.id IN (SELECT owner_id FROM cards WHERE id = $1)
+                   OR u.id IN (SELECT created_by FROM cards WHERE id = $1)
+                   OR u.id IN (
+                       SELECT bp.user_id FROM board_access bp
+                       INNER JOIN boards b ON b.id = bp.board_id
+                       WHERE b.deleted_at IS NULL
+                         AND (bp.board_id IN (SELECT board_id FROM card_boards WHERE card_id = $1)
+                              OR bp.board_id = (
+                                  SELECT col.board_id FROM cards c
+                                  INNER JOIN columns col ON col.id = c.column_id
+                                  WHERE c.id = $1
+                              ))
+                   ))
+            ORDER BY u.name ASC
+            "#,
+        )
+        .bind(card_id)
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(users)
+    }
+
+    /// Replace the users mentioned in a comment, returning the ones it did not
+    /// mention before
+    pub async fn set_for_comment(&self, comment_id: Uuid, user_ids: &[Uuid]) -> Result<Vec<Uuid>> {
+        let mut tx = self.pool.begin().await?;
+
+        let previous = sqlx::query_scalar::<_, Uuid>(
+            "SELECT user_id FROM comment_mentions WHERE comment_id = $1",
+        )
+        .bind(comment_id)
+        .fetch_all(&mut *tx)
+        .await?;
+        for user_id in previous.iter().filter(|id| !user_ids.contains(id)) {
+            sqlx::query("DELETE FROM comment_mentions WHERE comment_id = $1 AND user_id = $2")
+                .bind(comment_id)
+                .bind(user_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        let added: Vec<Uuid> = user_ids
+            .iter()
+            .filter(|id| !previous.contains(id))
+            .copied()
+            .collect();
+        for user_id in &added {
+            sqlx::query(
+                r#"
+                INSERT INTO comment_mentions (comment_id, user_id, created_at)
+                VALUES ($1, $2, datetime('now'))
+                "#,
+            )
+            .bind(comment_id)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(added)
+    }
+
+    /// Users mentioned in the comments of a card, with the comment they are in
+    pub async fn list_for_card(&self, card_id: Uuid) -> Result<Vec<(Uuid, MentionedUser)>> {
+        let rows = sqlx::query_as::<_, MentionRow>(
+            r#"
+            SELECT cm.comment_id, u.id, u.name, u.email
+            FROM comment_mentions cm
+            INNER JOIN comments c ON c.id = cm.comment_id
+            INNER JOIN users u ON u.id = cm.user_id
+            WHERE c.card_id = $1
+            "#,
+        )
+        .bind(card_id)
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(rows.into_iter().map(|r| (r.comment_id, r.user)).collect())
+    }
+}
+
+/// A mentioned user together with the comment mentioning them
+#[derive(sqlx::FromRow)]
+struct MentionRow {
+    comment_id: Uuid,
+    #[sqlx(flatten)]
+    user: MentionedUser,
+}
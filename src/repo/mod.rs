@@ -1,13 +1,48 @@
+pub mod account;
+pub mod activity;
+pub mod admin;
+pub mod assignee;
+pub mod attachment;
+pub mod backfill;
+pub mod backup;
 pub mod board;
+pub mod board_preference;
+pub mod board_template;
+pub mod calendar;
 pub mod card;
 pub mod card_board;
+pub mod card_template;
+pub mod chat_action;
 pub mod chat_message;
+pub mod chat_session;
 pub mod column;
 pub mod comment;
+pub mod dashboard;
+pub mod email_change;
+pub mod guest_comment;
+pub mod identity;
+pub mod inbound_email;
+pub mod integrity;
+pub mod invitation;
+pub mod link;
+pub mod mention;
+pub mod notification;
+pub mod pending_chat_action;
+pub mod reaction;
+pub mod recurrence;
+pub mod revision;
+pub mod saved_view;
 pub mod session;
+pub mod share_link;
+pub mod stats;
 pub mod tag;
+pub mod telegram;
 pub mod token;
+pub mod trash;
 pub mod user;
+pub mod watcher;
+pub mod webhook;
+pub mod workspace;
 
 pub use card_board::CardBoardRepository;
 pub use comment::CommentRepository;
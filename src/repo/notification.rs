@@ -0,0 +1,226 @@
+use chrono::NaiveDate;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::error::Result;
+use crate::models::{DueReminder, Notification, NotificationKind};
+
+/// Most notifications returned by a listing
+const LIST_LIMIT: i64 = 100;
+
+#[derive(Clone)]
+pub struct NotificationRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl NotificationRepository {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+
+    /// Notify about every open card due on or before `soon_until` that its recipients
+    /// have not yet been notified about. Cards due before `today` are overdue, the
+    /// others are due soon. Recipients are the assignees of a card, or its owner when
+    /// nobody is assigned. Archived and deleted cards are skipped. Returns the notifications created.
+    pub async fn create_due(
+        &self,
+        today: NaiveDate,
+        soon_until: NaiveDate,
+    ) -> Result<Vec<DueReminder>> {
+        let mut tx = self.pool.begin().await?;
+
+        let reminders = sqlx::query_as::<_, DueReminder>(
+            r#"
+            SELECT r.user_id, u.email, c.id AS card_id, c.title AS card_title,
+                   CASE WHEN c.due_date < $1 THEN $3 ELSE $4 END AS kind,
+                   c.due_date
+            FROM cards c
+            INNER JOIN (
+                SELECT card_id, user_id FROM card_assignees
+                UNION
+                SELECT id, COALESCE(owner_id, created_by) FROM cards
+                WHERE id NOT IN (SELECT card_id FROM card_assignees)
+            ) r ON r.card_id = c.id
+            INNER JOIN users u ON u.id = r.user_id
+            WHERE c.due_date IS NOT NULL
+              AND c.due_date <= $2
+              AND c.status IN ('open', 'in_progress')
+              AND c.archived_at IS NULL AND c.deleted_at IS NULL
+              AND (c.column_id IS NULL
+                   OR c.column_id IN (
+                       SELECT col.id FROM columns col
+                       INNER JOIN boards b ON b.id = col.board_id
+                       WHERE col.archived_at IS NULL AND col.deleted_at IS NULL
+                         AND b.deleted_at IS NULL
+                   ))
+              AND NOT EXISTS (
+                  SELECT 1 FROM notifications n
+                  WHERE n.user_id = r.user_id
+                    AND n.card_id = c.id
+                    AND n.due_date = c.due_date
+                    AND n.kind = CASE WHEN c.due_date < $1 THEN $3 ELSE $4 END
+              )
+            "#,
+        )
+        .bind(today)
+        .bind(soon_until)
+        .bind(NotificationKind::Overdue.to_string())
+        .bind(NotificationKind::DueSoon.to_string())
+        .fetch_all(&mut *tx)
+        .await?;
+
+        for reminder in &reminders {
+            sqlx::query(
+                r#"
+                INSERT OR IGNORE INTO notifications (id, user_id, card_id, kind, due_date, created_at)
+                VALUES ($1, $2, $3, $4, $5, datetime('now'))
+                "#,
+            )
+            .bind(Uuid::new_v4())
+            .bind(reminder.user_id)
+            .bind(reminder.card_id)
+            .bind(&reminder.kind)
+            .bind(reminder.due_date)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(reminders)
+    }
+
+    /// Notify users of a change to a card made by `actor_id`
+    pub async fn create_for_users(
+        &self,
+        user_ids: &[Uuid],
+        card_id: Uuid,
+        actor_id: Uuid,
+        kind: NotificationKind,
+        message: &str,
+    ) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        for user_id in user_ids {
+            sqlx::query(
+                r#"
+                INSERT INTO notifications (id, user_id, card_id, kind, actor_id, message, created_at)
+                VALUES ($1, $2, $3, $4, $5, $6, datetime('now'))
+                "#,
+            )
+            .bind(Uuid::new_v4())
+            .bind(user_id)
+            .bind(card_id)
+            .bind(kind.to_string())
+            .bind(actor_id)
+            .bind(message)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Notify a user of a change to their role on a board made by `actor_id`
+    pub async fn create_for_board(
+        &self,
+        user_id: Uuid,
+        board_id: Uuid,
+        actor_id: Uuid,
+        kind: NotificationKind,
+        message: &str,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO notifications (id, user_id, board_id, kind, actor_id, message, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, datetime('now'))
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(user_id)
+        .bind(board_id)
+        .bind(kind.to_string())
+        .bind(actor_id)
+        .bind(message)
+        .execute(self.pool.as_ref())
+        .await?;
+
+        Ok(())
+    }
+
+    /// A user's notifications, newest first
+    pub async fn list_for_user(
+        &self,
+        user_id: Uuid,
+        unread_only: bool,
+    ) -> Result<Vec<Notification>> {
+        let notifications = sqlx::query_as::<_, Notification>(
+            r#"
+            SELECT n.id, n.card_id, c.title AS card_title, n.board_id, b.name AS board_name,
+                   n.kind, n.due_date, u.name AS actor_name, n.message, n.read_at, n.created_at
+            FROM notifications n
+            LEFT JOIN cards c ON c.id = n.card_id
+            LEFT JOIN boards b ON b.id = n.board_id
+            LEFT JOIN users u ON u.id = n.actor_id
+            WHERE n.user_id = $1 AND ($2 = 0 OR n.read_at IS NULL)
+              AND c.deleted_at IS NULL AND b.deleted_at IS NULL
+            ORDER BY n.created_at DESC, n.rowid DESC
+            LIMIT $3
+            "#,
+        )
+        .bind(user_id)
+        .bind(unread_only)
+        .bind(LIST_LIMIT)
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(notifications)
+    }
+
+    /// Number of notifications a user has not read
+    pub async fn count_unread(&self, user_id: Uuid) -> Result<i64> {
+        let count: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*) FROM notifications n
+            LEFT JOIN cards c ON c.id = n.card_id
+            LEFT JOIN boards b ON b.id = n.board_id
+            WHERE n.user_id = $1 AND n.read_at IS NULL
+              AND c.deleted_at IS NULL AND b.deleted_at IS NULL
+            "#,
+        )
+        .bind(user_id)
+        .fetch_one(self.pool.as_ref())
+        .await?;
+
+        Ok(count)
+    }
+
+    /// Mark one of a user's notifications as read. Returns false if the user has no
+    /// such notification.
+    pub async fn mark_read(&self, id: Uuid, user_id: Uuid) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE notifications SET read_at = COALESCE(read_at, datetime('now'))
+            WHERE id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .execute(self.pool.as_ref())
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Mark all of a user's notifications as read, returning how many were unread
+    pub async fn mark_all_read(&self, user_id: Uuid) -> Result<u64> {
+        let result = sqlx::query(
+            "UPDATE notifications SET read_at = datetime('now') WHERE user_id = $1 AND read_at IS NULL",
+        )
+        .bind(user_id)
+        .execute(self.pool.as_ref())
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
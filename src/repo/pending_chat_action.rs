@@ -0,0 +1,88 @@
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::models::{LlmAction, PendingChatActions};
+
+#[derive(Clone)]
+pub struct PendingChatActionRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl PendingChatActionRepository {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+
+    /// Keep proposed actions until they are confirmed or expire. Expired proposals
+    /// of every user are dropped on the way.
+    pub async fn create(
+        &self,
+        user_id: Uuid,
+        board_id: Uuid,
+        chat_message_id: Uuid,
+        actions: &[LlmAction],
+        ttl_minutes: i64,
+    ) -> Result<PendingChatActions> {
+        self.delete_expired(ttl_minutes).await?;
+
+        let id = Uuid::new_v4();
+        let actions =
+            serde_json::to_string(actions).map_err(|e| AppError::Internal(e.to_string()))?;
+
+        let pending = sqlx::query_as::<_, PendingChatActions>(
+            r#"
+            INSERT INTO pending_chat_actions (id, user_id, board_id, chat_message_id, actions, created_at)
+            VALUES ($1, $2, $3, $4, $5, datetime('now'))
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .bind(board_id)
+        .bind(chat_message_id)
+        .bind(actions)
+        .fetch_one(self.pool.as_ref())
+        .await?;
+
+        Ok(pending)
+    }
+
+    /// Remove and return a user's proposal on a board, unless it has expired. Each
+    /// proposal can only be taken once.
+    pub async fn take(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        board_id: Uuid,
+        ttl_minutes: i64,
+    ) -> Result<Option<PendingChatActions>> {
+        let pending = sqlx::query_as::<_, PendingChatActions>(
+            r#"
+            DELETE FROM pending_chat_actions
+            WHERE id = $1 AND user_id = $2 AND board_id = $3
+              AND created_at > datetime('now', $4)
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .bind(board_id)
+        .bind(format!("-{} minutes", ttl_minutes))
+        .fetch_optional(self.pool.as_ref())
+        .await?;
+
+        Ok(pending)
+    }
+
+    async fn delete_expired(&self, ttl_minutes: i64) -> Result<u64> {
+        let result =
+            sqlx::query("DELETE FROM pending_chat_actions WHERE created_at <= datetime('now', $1)")
+                .bind(format!("-{} minutes", ttl_minutes))
+                .execute(self.pool.as_ref())
+                .await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,114 @@
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::error::Result;
+use crate::models::{ReactionCount, ReactionCountRow};
+
+/// Emoji reactions on cards and comments
+#[derive(Clone)]
+pub struct ReactionRepository {
+    pool: Arc<SqlitePool>,
+}
+
+/// Reaction counts of a card: on the card itself and per comment
+#[derive(Debug, Default)]
+pub struct CardReactions {
+    pub card: Vec<ReactionCount>,
+    pub comments: HashMap<Uuid, Vec<ReactionCount>>,
+}
+
+impl CardReactions {
+    /// Reaction counts of a comment of the card
+    pub fn for_comment(&self, comment_id: Uuid) -> Vec<ReactionCount> {
+        self.comments.get(&comment_id).cloned().unwrap_or_default()
+    }
+}
+
+impl ReactionRepository {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+
+    /// React to the card, or to its comment when `comment_id` is set. Reacting twice
+    /// with the same emoji is a no-op.
+    pub async fn add(
+        &self,
+        card_id: Uuid,
+        comment_id: Option<Uuid>,
+        user_id: Uuid,
+        emoji: &str,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT OR IGNORE INTO reactions (id, card_id, comment_id, user_id, emoji, created_at)
+            VALUES ($1, $2, $3, $4, $5, datetime('now'))
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(card_id)
+        .bind(comment_id)
+        .bind(user_id)
+        .bind(emoji)
+        .execute(self.pool.as_ref())
+        .await?;
+
+        Ok(())
+    }
+
+    /// Take back a reaction. Returns whether the user had reacted.
+    pub async fn remove(
+        &self,
+        card_id: Uuid,
+        comment_id: Option<Uuid>,
+        user_id: Uuid,
+        emoji: &str,
+    ) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM reactions
+            WHERE card_id = $1 AND comment_id IS $2 AND user_id = $3 AND emoji = $4
+            "#,
+        )
+        .bind(card_id)
+        .bind(comment_id)
+        .bind(user_id)
+        .bind(emoji)
+        .execute(self.pool.as_ref())
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Reaction counts of the card and of each of its comments, in the order the
+    /// emoji were first used, marking those `user_id` reacted with
+    pub async fn list_for_card(&self, card_id: Uuid, user_id: Uuid) -> Result<CardReactions> {
+        let rows = sqlx::query_as::<_, ReactionCountRow>(
+            r#"
+            SELECT comment_id, emoji, COUNT(*) AS count, MAX(user_id = $2) AS reacted
+            FROM reactions
+            WHERE card_id = $1
+            GROUP BY comment_id, emoji
+            ORDER BY MIN(created_at) ASC, MIN(rowid) ASC
+            "#,
+        )
+        .bind(card_id)
+        .bind(user_id)
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        let mut reactions = CardReactions::default();
+        for row in rows {
+            match row.comment_id {
+                Some(comment_id) => reactions
+                    .comments
+                    .entry(comment_id)
+                    .or_default()
+                    .push(row.reaction),
+                None => reactions.card.push(row.reaction),
+            }
+        }
+        Ok(reactions)
+    }
+}
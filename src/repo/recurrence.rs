@@ -0,0 +1,110 @@
+use chrono::NaiveDate;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::error::Result;
+use crate::models::{CardRecurrence, RecurrenceFrequency};
+
+#[derive(Clone)]
+pub struct RecurrenceRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl RecurrenceRepository {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+
+    /// Create or replace the recurrence rule of a card
+    pub async fn set(
+        &self,
+        card_id: Uuid,
+        frequency: RecurrenceFrequency,
+        interval: i64,
+        starts_on: NaiveDate,
+        next_run_on: NaiveDate,
+        created_by: Uuid,
+    ) -> Result<CardRecurrence> {
+        let recurrence = sqlx::query_as::<_, CardRecurrence>(
+            r#"
+            INSERT INTO card_recurrences (card_id, frequency, interval, starts_on, next_run_on, created_by, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, datetime('now'))
+            ON CONFLICT (card_id) DO UPDATE SET
+                frequency = excluded.frequency,
+                interval = excluded.interval,
+                starts_on = excluded.starts_on,
+                next_run_on = excluded.next_run_on,
+                created_by = excluded.created_by
+            RETURNING *
+            "#,
+        )
+        .bind(card_id)
+        .bind(frequency.to_string())
+        .bind(interval)
+        .bind(starts_on)
+        .bind(next_run_on)
+        .bind(created_by)
+        .fetch_one(self.pool.as_ref())
+        .await?;
+
+        Ok(recurrence)
+    }
+
+    /// Find the recurrence rule of a card
+    pub async fn find_by_card(&self, card_id: Uuid) -> Result<Option<CardRecurrence>> {
+        let recurrence = sqlx::query_as::<_, CardRecurrence>(
+            "SELECT * FROM card_recurrences WHERE card_id = $1",
+        )
+        .bind(card_id)
+        .fetch_optional(self.pool.as_ref())
+        .await?;
+
+        Ok(recurrence)
+    }
+
+    /// Remove the recurrence rule of a card. Returns false if it had none.
+    pub async fn delete(&self, card_id: Uuid) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM card_recurrences WHERE card_id = $1")
+            .bind(card_id)
+            .execute(self.pool.as_ref())
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Rules whose next copy is due on or before `today`. Archived and deleted cards
+    /// are paused.
+    pub async fn list_due(&self, today: NaiveDate) -> Result<Vec<CardRecurrence>> {
+        let recurrences = sqlx::query_as::<_, CardRecurrence>(
+            r#"
+            SELECT r.* FROM card_recurrences r
+            INNER JOIN cards c ON c.id = r.card_id
+            WHERE r.next_run_on <= $1 AND c.archived_at IS NULL AND c.deleted_at IS NULL
+            ORDER BY r.next_run_on ASC
+            "#,
+        )
+        .bind(today)
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(recurrences)
+    }
+
+    /// Record a run and schedule the next copy
+    pub async fn mark_run(&self, card_id: Uuid, next_run_on: NaiveDate) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE card_recurrences
+            SET next_run_on = $2, last_run_at = datetime('now')
+            WHERE card_id = $1
+            "#,
+        )
+        .bind(card_id)
+        .bind(next_run_on)
+        .execute(self.pool.as_ref())
+        .await?;
+
+        Ok(())
+    }
+}
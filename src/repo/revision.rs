@@ -0,0 +1,89 @@
+use sqlx::{SqliteConnection, SqlitePool};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::models::{Card, CardRevision};
+
+/// Previous titles and bodies of cards
+#[derive(Clone)]
+pub struct CardRevisionRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl CardRevisionRepository {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+
+    /// Save the title and body of `before` if `edited_by` changed either of them, as
+    /// seen in `after`
+    pub async fn record(&self, before: &Card, after: &Card, edited_by: Uuid) -> Result<()> {
+        let mut conn = self.pool.acquire().await?;
+        Self::record_on(&mut conn, before, after, edited_by).await
+    }
+
+    pub(crate) async fn record_on(
+        conn: &mut SqliteConnection,
+        before: &Card,
+        after: &Card,
+        edited_by: Uuid,
+    ) -> Result<()> {
+        if before.title == after.title && before.body == after.body {
+            return Ok(());
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO card_revisions (id, card_id, title, body, edited_by, created_at)
+            VALUES ($1, $2, $3, $4, $5, datetime('now'))
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(before.id)
+        .bind(&before.title)
+        .bind(&before.body)
+        .bind(edited_by)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Revisions of a card, newest first
+    pub async fn list_for_card(&self, card_id: Uuid) -> Result<Vec<CardRevision>> {
+        let revisions = sqlx::query_as::<_, CardRevision>(
+            r#"
+            SELECT r.id, r.card_id, r.title, r.body, r.edited_by, u.name AS editor_name,
+                   r.created_at
+            FROM card_revisions r
+            LEFT JOIN users u ON u.id = r.edited_by
+            WHERE r.card_id = $1
+            ORDER BY r.created_at DESC, r.rowid DESC
+            "#,
+        )
+        .bind(card_id)
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(revisions)
+    }
+
+    /// A revision of the card
+    pub async fn get_by_id(&self, card_id: Uuid, id: Uuid) -> Result<CardRevision> {
+        sqlx::query_as::<_, CardRevision>(
+            r#"
+            SELECT r.id, r.card_id, r.title, r.body, r.edited_by, u.name AS editor_name,
+                   r.created_at
+            FROM card_revisions r
+            LEFT JOIN users u ON u.id = r.edited_by
+            WHERE r.card_id = $1 AND r.id = $2
+            "#,
+        )
+        .bind(card_id)
+        .bind(id)
+        .fetch_optional(self.pool.as_ref())
+        .await?
+        .ok_or(AppError::NotFound("Revision"))
+    }
+}
@@ -0,0 +1,155 @@
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::models::SavedView;
+
+/// Named card filters of users, per board
+#[derive(Clone)]
+pub struct SavedViewRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl SavedViewRepository {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+
+    /// Create a view. `filter` is the JSON of a card filter.
+    pub async fn create(
+        &self,
+        board_id: Uuid,
+        user_id: Uuid,
+        name: &str,
+        filter: &str,
+    ) -> Result<SavedView> {
+        let view = sqlx::query_as::<_, SavedView>(
+            r#"
+            INSERT INTO saved_views (id, board_id, user_id, name, filter, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, datetime('now'), datetime('now'))
+            RETURNING *
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(board_id)
+        .bind(user_id)
+        .bind(name)
+        .bind(filter)
+        .fetch_one(self.pool.as_ref())
+        .await?;
+
+        Ok(view)
+    }
+
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Option<SavedView>> {
+        let view = sqlx::query_as::<_, SavedView>("SELECT * FROM saved_views WHERE id = $1")
+            .bind(id)
+            .fetch_optional(self.pool.as_ref())
+            .await?;
+
+        Ok(view)
+    }
+
+    pub async fn get_by_id(&self, id: Uuid) -> Result<SavedView> {
+        self.find_by_id(id)
+            .await?
+            .ok_or(AppError::NotFound("saved_view"))
+    }
+
+    /// Views of a user on a board, by name
+    pub async fn list_for_user(&self, board_id: Uuid, user_id: Uuid) -> Result<Vec<SavedView>> {
+        let views = sqlx::query_as::<_, SavedView>(
+            r#"
+            SELECT * FROM saved_views
+            WHERE board_id = $1 AND user_id = $2
+            ORDER BY name COLLATE NOCASE ASC, rowid ASC
+            "#,
+        )
+        .bind(board_id)
+        .bind(user_id)
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(views)
+    }
+
+    /// Views of a user on every board
+    pub async fn list_by_user(&self, user_id: Uuid) -> Result<Vec<SavedView>> {
+        let views = sqlx::query_as::<_, SavedView>(
+            "SELECT * FROM saved_views WHERE user_id = $1 ORDER BY created_at ASC, rowid ASC",
+        )
+        .bind(user_id)
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(views)
+    }
+
+    /// Whether the user has another view with this name on the board
+    pub async fn name_taken(
+        &self,
+        board_id: Uuid,
+        user_id: Uuid,
+        name: &str,
+        except: Option<Uuid>,
+    ) -> Result<bool> {
+        let taken = sqlx::query_scalar::<_, bool>(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM saved_views
+                WHERE board_id = $1 AND user_id = $2 AND name = $3
+                  AND ($4 IS NULL OR id != $4)
+            )
+            "#,
+        )
+        .bind(board_id)
+        .bind(user_id)
+        .bind(name)
+        .bind(except)
+        .fetch_one(self.pool.as_ref())
+        .await?;
+
+        Ok(taken)
+    }
+
+    /// Update a view. `None` leaves a field unchanged.
+    pub async fn update(
+        &self,
+        id: Uuid,
+        name: Option<&str>,
+        filter: Option<&str>,
+    ) -> Result<SavedView> {
+        let view = sqlx::query_as::<_, SavedView>(
+            r#"
+            UPDATE saved_views
+            SET name = COALESCE($2, name),
+                filter = COALESCE($3, filter),
+                updated_at = datetime('now')
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(name)
+        .bind(filter)
+        .fetch_optional(self.pool.as_ref())
+        .await?
+        .ok_or(AppError::NotFound("saved_view"))?;
+
+        Ok(view)
+    }
+
+    pub async fn delete(&self, id: Uuid) -> Result<()> {
+        let result = sqlx::query("DELETE FROM saved_views WHERE id = $1")
+            .bind(id)
+            .execute(self.pool.as_ref())
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("saved_view"));
+        }
+
+        Ok(())
+    }
+}
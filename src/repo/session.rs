@@ -1,9 +1,10 @@
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
 use sqlx::SqlitePool;
+use std::net::IpAddr;
 use std::sync::Arc;
 use uuid::Uuid;
 
-use crate::error::Result;
+use crate::error::{AppError, Result};
 use crate::models::Session;
 
 #[derive(Clone)]
@@ -16,21 +17,31 @@ impl SessionRepository {
         Self { pool }
     }
 
-    pub async fn create(&self, user_id: Uuid, token: &str) -> Result<Session> {
+    pub async fn create(
+        &self,
+        user_id: Uuid,
+        token: &str,
+        ip_address: Option<IpAddr>,
+        remember: bool,
+        lifetime: Duration,
+    ) -> Result<Session> {
         let id = Uuid::new_v4();
-        let expires_at = Utc::now() + Duration::days(7);
+        let expires_at = Utc::now() + lifetime;
 
         let session = sqlx::query_as::<_, Session>(
             r#"
-            INSERT INTO sessions (id, user_id, token, expires_at, created_at)
-            VALUES ($1, $2, $3, $4, datetime('now'))
+            INSERT INTO sessions (id, user_id, token, expires_at, created_at, ip_address, remember)
+            VALUES ($1, $2, $3, $4, datetime('now'), $5, $6)
             RETURNING *
             "#,
         )
         .bind(id)
         .bind(user_id)
         .bind(token)
-        .bind(expires_at)
+        // In the format of datetime('now'), which expiry is compared with
+        .bind(expires_at.naive_utc())
+        .bind(ip_address.map(|ip| ip.to_string()))
+        .bind(remember)
         .fetch_one(self.pool.as_ref())
         .await?;
 
@@ -48,6 +59,48 @@ impl SessionRepository {
         Ok(session)
     }
 
+    /// Move the expiry of a session
+    pub async fn set_expires_at(&self, id: Uuid, expires_at: DateTime<Utc>) -> Result<()> {
+        sqlx::query("UPDATE sessions SET expires_at = $1 WHERE id = $2")
+            .bind(expires_at.naive_utc())
+            .bind(id)
+            .execute(self.pool.as_ref())
+            .await?;
+
+        Ok(())
+    }
+
+    /// Sessions of a user that have not expired, most recent first
+    pub async fn list_active_for_user(&self, user_id: Uuid) -> Result<Vec<Session>> {
+        let sessions = sqlx::query_as::<_, Session>(
+            r#"
+            SELECT * FROM sessions
+            WHERE user_id = $1 AND expires_at > datetime('now')
+            ORDER BY created_at DESC
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(sessions)
+    }
+
+    /// Log out one session of a user
+    pub async fn delete_for_user_by_id(&self, id: Uuid, user_id: Uuid) -> Result<()> {
+        let result = sqlx::query("DELETE FROM sessions WHERE id = $1 AND user_id = $2")
+            .bind(id)
+            .bind(user_id)
+            .execute(self.pool.as_ref())
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("session"));
+        }
+
+        Ok(())
+    }
+
     pub async fn delete(&self, id: Uuid) -> Result<()> {
         sqlx::query("DELETE FROM sessions WHERE id = $1")
             .bind(id)
@@ -66,6 +119,17 @@ impl SessionRepository {
         Ok(())
     }
 
+    /// Log a user out everywhere except in the session with token `keep`
+    pub async fn delete_for_user(&self, user_id: Uuid, keep: Option<&str>) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM sessions WHERE user_id = $1 AND token IS NOT $2")
+            .bind(user_id)
+            .bind(keep)
+            .execute(self.pool.as_ref())
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
     pub async fn delete_expired(&self) -> Result<u64> {
         let result = sqlx::query("DELETE FROM sessions WHERE expires_at <= datetime('now')")
             .execute(self.pool.as_ref())
@@ -0,0 +1,79 @@
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::error::Result;
+use crate::models::BoardShareLink;
+
+/// Public read-only addresses of boards
+#[derive(Clone)]
+pub struct BoardShareLinkRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl BoardShareLinkRepository {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+
+    /// Set the board's share token, replacing the previous one
+    pub async fn set_token(
+        &self,
+        board_id: Uuid,
+        token_hash: &str,
+        created_by: Uuid,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO board_share_links (board_id, token_hash, created_by, created_at)
+            VALUES ($1, $2, $3, datetime('now'))
+            ON CONFLICT(board_id) DO UPDATE SET token_hash = excluded.token_hash,
+                created_by = excluded.created_by, created_at = excluded.created_at
+            "#,
+        )
+        .bind(board_id)
+        .bind(token_hash)
+        .bind(created_by)
+        .execute(self.pool.as_ref())
+        .await?;
+
+        Ok(())
+    }
+
+    /// Revoke the board's share link. Returns whether there was one.
+    pub async fn delete(&self, board_id: Uuid) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM board_share_links WHERE board_id = $1")
+            .bind(board_id)
+            .execute(self.pool.as_ref())
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn find_by_board(&self, board_id: Uuid) -> Result<Option<BoardShareLink>> {
+        let link = sqlx::query_as::<_, BoardShareLink>(
+            "SELECT board_id, created_by, created_at FROM board_share_links WHERE board_id = $1",
+        )
+        .bind(board_id)
+        .fetch_optional(self.pool.as_ref())
+        .await?;
+
+        Ok(link)
+    }
+
+    /// The board shared under the token, unless it was deleted
+    pub async fn find_board_by_token(&self, token_hash: &str) -> Result<Option<Uuid>> {
+        let board_id = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            SELECT s.board_id FROM board_share_links s
+            INNER JOIN boards b ON b.id = s.board_id
+            WHERE s.token_hash = $1 AND b.deleted_at IS NULL
+            "#,
+        )
+        .bind(token_hash)
+        .fetch_optional(self.pool.as_ref())
+        .await?;
+
+        Ok(board_id)
+    }
+}
@@ -0,0 +1,102 @@
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::error::Result;
+use crate::models::{CardFlowEvent, ColumnCardCount, TagCardCount};
+
+/// Aggregate queries behind the board statistics
+#[derive(Clone)]
+pub struct BoardStatsRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl BoardStatsRepository {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+
+    /// Cards in each column of the board, in board order, archived and deleted cards
+    /// left out
+    pub async fn column_counts(&self, board_id: Uuid) -> Result<Vec<ColumnCardCount>> {
+        let counts = sqlx::query_as::<_, ColumnCardCount>(
+            r#"
+            SELECT col.id AS column_id, col.name, COUNT(c.id) AS cards
+            FROM columns col
+            LEFT JOIN card_boards cb ON cb.column_id = col.id
+            LEFT JOIN cards c ON c.id = cb.card_id
+                AND c.archived_at IS NULL AND c.deleted_at IS NULL
+            WHERE col.board_id = $1 AND col.archived_at IS NULL AND col.deleted_at IS NULL
+            GROUP BY col.id
+            ORDER BY col.position ASC
+            "#,
+        )
+        .bind(board_id)
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(counts)
+    }
+
+    /// Open and in-progress cards carrying each tag of the board, most used first
+    pub async fn tag_counts(&self, board_id: Uuid) -> Result<Vec<TagCardCount>> {
+        let counts = sqlx::query_as::<_, TagCardCount>(
+            r#"
+            SELECT t.id AS tag_id, COALESCE(g.name, t.name) AS name,
+                   COALESCE(g.color, t.color) AS color, COUNT(c.id) AS cards
+            FROM tags t
+            LEFT JOIN tags g ON g.id = t.linked_tag_id
+            LEFT JOIN card_tags ct ON ct.tag_id = t.id
+            LEFT JOIN cards c ON c.id = ct.card_id
+                AND c.status IN ('open', 'in_progress')
+                AND c.archived_at IS NULL AND c.deleted_at IS NULL
+            WHERE t.board_id = $1
+            GROUP BY t.id
+            ORDER BY cards DESC, name ASC
+            "#,
+        )
+        .bind(board_id)
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(counts)
+    }
+
+    /// Cards on the board past their due date that are neither done nor closed
+    pub async fn overdue_count(&self, board_id: Uuid) -> Result<i64> {
+        let count = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(*) FROM cards c
+            INNER JOIN card_boards cb ON cb.card_id = c.id
+            INNER JOIN columns col ON col.id = cb.column_id
+            WHERE cb.board_id = $1
+              AND col.archived_at IS NULL AND col.deleted_at IS NULL
+              AND c.archived_at IS NULL AND c.deleted_at IS NULL
+              AND c.status NOT IN ('done', 'closed')
+              AND c.due_date < date('now')
+            "#,
+        )
+        .bind(board_id)
+        .fetch_one(self.pool.as_ref())
+        .await?;
+
+        Ok(count)
+    }
+
+    /// Creations, moves and updates of the cards currently on the board, oldest first
+    pub async fn list_flow_events(&self, board_id: Uuid) -> Result<Vec<CardFlowEvent>> {
+        let events = sqlx::query_as::<_, CardFlowEvent>(
+            r#"
+            SELECT a.card_id, a.event, a.details, a.created_at FROM activity_log a
+            WHERE a.event IN ('card_created', 'card_moved', 'card_updated')
+              AND a.card_id IN (SELECT card_id FROM card_boards WHERE board_id = $1)
+            ORDER BY a.created_at ASC, a.rowid ASC
+            "#,
+        )
+        .bind(board_id)
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(events)
+    }
+}
@@ -1,9 +1,38 @@
-use sqlx::SqlitePool;
+use sqlx::{QueryBuilder, Sqlite, SqliteConnection, SqlitePool};
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::error::{AppError, Result};
-use crate::models::{CardTag, Tag};
+use crate::models::{CardTag, Tag, TagLink};
+
+/// A tag together with the card it is on
+#[derive(sqlx::FromRow)]
+struct CardTagRow {
+    card_id: Uuid,
+    #[sqlx(flatten)]
+    tag: Tag,
+}
+
+/// Check a tag color before it ends up in a `style` attribute: a hex color such as
+/// `#1f77b4`, or a CSS color name such as `red`
+fn check_color(color: &str) -> Result<()> {
+    let valid = match color.strip_prefix('#') {
+        Some(hex) => {
+            matches!(hex.len(), 3 | 4 | 6 | 8) && hex.chars().all(|c| c.is_ascii_hexdigit())
+        }
+        None => {
+            !color.is_empty() && color.len() <= 32 && color.chars().all(|c| c.is_ascii_alphabetic())
+        }
+    };
+    if !valid {
+        return Err(AppError::invalid_field(
+            "color",
+            "Color must be a hex color like #1f77b4 or a color name like red",
+        ));
+    }
+    Ok(())
+}
 
 #[derive(Clone)]
 pub struct TagRepository {
@@ -17,6 +46,17 @@ impl TagRepository {
 
     /// Create a board-scoped tag
     pub async fn create(&self, board_id: Uuid, name: &str, color: &str) -> Result<Tag> {
+        let mut conn = self.pool.acquire().await?;
+        Self::create_on(&mut conn, board_id, name, color).await
+    }
+
+    pub(crate) async fn create_on(
+        conn: &mut SqliteConnection,
+        board_id: Uuid,
+        name: &str,
+        color: &str,
+    ) -> Result<Tag> {
+        check_color(color)?;
         let id = Uuid::new_v4();
 
         let tag = sqlx::query_as::<_, Tag>(
@@ -30,7 +70,7 @@ impl TagRepository {
         .bind(board_id)
         .bind(name)
         .bind(color)
-        .fetch_one(self.pool.as_ref())
+        .fetch_one(&mut *conn)
         .await?;
 
         Ok(tag)
@@ -38,6 +78,7 @@ impl TagRepository {
 
     /// Create a global (user-scoped) tag
     pub async fn create_global(&self, owner_id: Uuid, name: &str, color: &str) -> Result<Tag> {
+        check_color(color)?;
         let id = Uuid::new_v4();
 
         let tag = sqlx::query_as::<_, Tag>(
@@ -69,30 +110,172 @@ impl TagRepository {
         Ok(tags)
     }
 
+    /// Find a tag, with the name and color of its linked global tag if any
     pub async fn find_by_id(&self, id: Uuid) -> Result<Option<Tag>> {
-        let tag = sqlx::query_as::<_, Tag>("SELECT * FROM tags WHERE id = $1")
-            .bind(id)
-            .fetch_optional(self.pool.as_ref())
-            .await?;
+        let mut conn = self.pool.acquire().await?;
+        Self::find_on(&mut conn, id).await
+    }
+
+    pub(crate) async fn find_on(conn: &mut SqliteConnection, id: Uuid) -> Result<Option<Tag>> {
+        let tag = sqlx::query_as::<_, Tag>(
+            r#"
+            SELECT t.id, t.board_id, t.owner_id,
+                   COALESCE(g.name, t.name) AS name,
+                   COALESCE(g.color, t.color) AS color,
+                   t.created_at, t.linked_tag_id
+            FROM tags t
+            LEFT JOIN tags g ON g.id = t.linked_tag_id
+            WHERE t.id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await?;
 
         Ok(tag)
     }
 
     pub async fn get_by_id(&self, id: Uuid) -> Result<Tag> {
-        self.find_by_id(id).await?.ok_or(AppError::NotFound)
+        self.find_by_id(id).await?.ok_or(AppError::NotFound("tag"))
     }
 
+    /// List a board's tags; linked tags carry the name and color of their global tag
     pub async fn list_by_board(&self, board_id: Uuid) -> Result<Vec<Tag>> {
-        let tags =
-            sqlx::query_as::<_, Tag>("SELECT * FROM tags WHERE board_id = $1 ORDER BY name ASC")
-                .bind(board_id)
-                .fetch_all(self.pool.as_ref())
-                .await?;
+        let mut conn = self.pool.acquire().await?;
+        Self::list_by_board_on(&mut conn, board_id).await
+    }
+
+    pub(crate) async fn list_by_board_on(
+        conn: &mut SqliteConnection,
+        board_id: Uuid,
+    ) -> Result<Vec<Tag>> {
+        let tags = sqlx::query_as::<_, Tag>(
+            r#"
+            SELECT t.id, t.board_id, t.owner_id,
+                   COALESCE(g.name, t.name) AS name,
+                   COALESCE(g.color, t.color) AS color,
+                   t.created_at, t.linked_tag_id
+            FROM tags t
+            LEFT JOIN tags g ON g.id = t.linked_tag_id
+            WHERE t.board_id = $1
+            ORDER BY name ASC
+            "#,
+        )
+        .bind(board_id)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        Ok(tags)
+    }
+
+    /// Tags of the given boards, each board's by name, in one query
+    pub async fn list_by_boards(&self, board_ids: &[Uuid]) -> Result<Vec<Tag>> {
+        if board_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut query = QueryBuilder::<Sqlite>::new(
+            r#"
+            SELECT t.id, t.board_id, t.owner_id,
+                   COALESCE(g.name, t.name) AS name,
+                   COALESCE(g.color, t.color) AS color,
+                   t.created_at, t.linked_tag_id
+            FROM tags t
+            LEFT JOIN tags g ON g.id = t.linked_tag_id
+            WHERE t.board_id IN (
+            "#,
+        );
+        let mut ids = query.separated(", ");
+        for board_id in board_ids {
+            ids.push_bind(*board_id);
+        }
+        query.push(") ORDER BY t.board_id, name ASC");
+
+        let tags = query
+            .build_query_as::<Tag>()
+            .fetch_all(self.pool.as_ref())
+            .await?;
 
         Ok(tags)
     }
 
+    /// Link a board tag to a global tag
+    pub async fn link(&self, id: Uuid, global_tag_id: Uuid) -> Result<Tag> {
+        let mut conn = self.pool.acquire().await?;
+        Self::link_on(&mut conn, id, global_tag_id).await
+    }
+
+    pub(crate) async fn link_on(
+        conn: &mut SqliteConnection,
+        id: Uuid,
+        global_tag_id: Uuid,
+    ) -> Result<Tag> {
+        let result = sqlx::query("UPDATE tags SET linked_tag_id = $2 WHERE id = $1")
+            .bind(id)
+            .bind(global_tag_id)
+            .execute(&mut *conn)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("tag"));
+        }
+
+        Self::find_on(conn, id)
+            .await?
+            .ok_or(AppError::NotFound("tag"))
+    }
+
+    /// Remove a link, keeping the inherited name and color on the board tag
+    pub async fn unlink(&self, id: Uuid) -> Result<Tag> {
+        let tag = sqlx::query_as::<_, Tag>(
+            r#"
+            UPDATE tags
+            SET name = COALESCE((SELECT g.name FROM tags g WHERE g.id = tags.linked_tag_id), name),
+                color = COALESCE((SELECT g.color FROM tags g WHERE g.id = tags.linked_tag_id), color),
+                linked_tag_id = NULL
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(self.pool.as_ref())
+        .await?
+        .ok_or(AppError::NotFound("tag"))?;
+
+        Ok(tag)
+    }
+
+    /// Pair unlinked board tags with the owner's global tags of the same name (case-insensitive)
+    pub async fn find_duplicate_links(
+        &self,
+        board_id: Uuid,
+        owner_id: Uuid,
+    ) -> Result<Vec<TagLink>> {
+        let links = sqlx::query_as::<_, TagLink>(
+            r#"
+            SELECT b.id AS board_tag_id, MIN(g.id) AS global_tag_id, b.name AS name
+            FROM tags b
+            INNER JOIN tags g
+                ON g.board_id IS NULL
+               AND g.owner_id = $2
+               AND lower(trim(g.name)) = lower(trim(b.name))
+            WHERE b.board_id = $1 AND b.linked_tag_id IS NULL
+            GROUP BY b.id
+            ORDER BY b.name ASC
+            "#,
+        )
+        .bind(board_id)
+        .bind(owner_id)
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(links)
+    }
+
     pub async fn update(&self, id: Uuid, name: Option<&str>, color: Option<&str>) -> Result<Tag> {
+        if let Some(color) = color {
+            check_color(color)?;
+        }
         let tag = sqlx::query_as::<_, Tag>(
             r#"
             UPDATE tags
@@ -112,19 +295,33 @@ impl TagRepository {
     }
 
     pub async fn delete(&self, id: Uuid) -> Result<()> {
+        let mut conn = self.pool.acquire().await?;
+        Self::delete_on(&mut conn, id).await
+    }
+
+    pub(crate) async fn delete_on(conn: &mut SqliteConnection, id: Uuid) -> Result<()> {
         let result = sqlx::query("DELETE FROM tags WHERE id = $1")
             .bind(id)
-            .execute(self.pool.as_ref())
+            .execute(&mut *conn)
             .await?;
 
         if result.rows_affected() == 0 {
-            return Err(AppError::NotFound);
+            return Err(AppError::NotFound("tag"));
         }
 
         Ok(())
     }
 
     pub async fn add_to_card(&self, card_id: Uuid, tag_id: Uuid) -> Result<CardTag> {
+        let mut conn = self.pool.acquire().await?;
+        Self::add_to_card_on(&mut conn, card_id, tag_id).await
+    }
+
+    pub(crate) async fn add_to_card_on(
+        conn: &mut SqliteConnection,
+        card_id: Uuid,
+        tag_id: Uuid,
+    ) -> Result<CardTag> {
         let card_tag = sqlx::query_as::<_, CardTag>(
             r#"
             INSERT INTO card_tags (card_id, tag_id, created_at)
@@ -135,39 +332,136 @@ impl TagRepository {
         )
         .bind(card_id)
         .bind(tag_id)
-        .fetch_one(self.pool.as_ref())
+        .fetch_one(&mut *conn)
         .await?;
 
         Ok(card_tag)
     }
 
     pub async fn remove_from_card(&self, card_id: Uuid, tag_id: Uuid) -> Result<()> {
+        let mut conn = self.pool.acquire().await?;
+        Self::remove_from_card_on(&mut conn, card_id, tag_id).await
+    }
+
+    pub(crate) async fn remove_from_card_on(
+        conn: &mut SqliteConnection,
+        card_id: Uuid,
+        tag_id: Uuid,
+    ) -> Result<()> {
         let result = sqlx::query("DELETE FROM card_tags WHERE card_id = $1 AND tag_id = $2")
             .bind(card_id)
             .bind(tag_id)
-            .execute(self.pool.as_ref())
+            .execute(&mut *conn)
             .await?;
 
         if result.rows_affected() == 0 {
-            return Err(AppError::NotFound);
+            return Err(AppError::NotFound("tag"));
         }
 
         Ok(())
     }
 
+    /// Ids of the cards carrying a tag
+    pub async fn list_card_ids(&self, tag_id: Uuid) -> Result<Vec<Uuid>> {
+        let mut conn = self.pool.acquire().await?;
+        Self::list_card_ids_on(&mut conn, tag_id).await
+    }
+
+    pub(crate) async fn list_card_ids_on(
+        conn: &mut SqliteConnection,
+        tag_id: Uuid,
+    ) -> Result<Vec<Uuid>> {
+        let ids = sqlx::query_scalar::<_, Uuid>(
+            "SELECT card_id FROM card_tags WHERE tag_id = $1 ORDER BY created_at, rowid",
+        )
+        .bind(tag_id)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        Ok(ids)
+    }
+
+    /// List a card's tags. Linked board tags resolve to their global tag's name and
+    /// color, and a board tag is hidden when the card also carries the global tag
+    /// it links to (or another board tag linked to the same one).
     pub async fn list_for_card(&self, card_id: Uuid) -> Result<Vec<Tag>> {
         let tags = sqlx::query_as::<_, Tag>(
             r#"
-            SELECT t.* FROM tags t
+            SELECT t.id, t.board_id, t.owner_id,
+                   COALESCE(g.name, t.name) AS name,
+                   COALESCE(g.color, t.color) AS color,
+                   t.created_at, t.linked_tag_id
+            FROM tags t
             INNER JOIN card_tags ct ON t.id = ct.tag_id
+            LEFT JOIN tags g ON g.id = t.linked_tag_id
             WHERE ct.card_id = $1
-            ORDER BY t.name ASC
+            ORDER BY name ASC, t.linked_tag_id IS NOT NULL, t.created_at ASC
             "#,
         )
         .bind(card_id)
         .fetch_all(self.pool.as_ref())
         .await?;
 
+        let mut seen = HashSet::new();
+        Ok(tags
+            .into_iter()
+            .filter(|t| seen.insert(t.effective_id()))
+            .collect())
+    }
+
+    /// Tags of the cards `CardRepository::list_by_board_columns` returns, in one query,
+    /// keyed by card and each list as `list_for_card` returns it. Cards without tags
+    /// are left out.
+    pub async fn list_for_board_cards(&self, board_id: Uuid) -> Result<HashMap<Uuid, Vec<Tag>>> {
+        let rows = sqlx::query_as::<_, CardTagRow>(
+            r#"
+            SELECT ct.card_id, t.id, t.board_id, t.owner_id,
+                   COALESCE(g.name, t.name) AS name,
+                   COALESCE(g.color, t.color) AS color,
+                   t.created_at, t.linked_tag_id
+            FROM card_tags ct
+            INNER JOIN cards c ON c.id = ct.card_id
+            INNER JOIN card_boards cb ON cb.card_id = c.id
+            INNER JOIN columns col ON col.id = cb.column_id
+            INNER JOIN tags t ON t.id = ct.tag_id
+            LEFT JOIN tags g ON g.id = t.linked_tag_id
+            WHERE cb.board_id = $1 AND col.archived_at IS NULL AND col.deleted_at IS NULL
+              AND c.archived_at IS NULL AND c.deleted_at IS NULL
+            ORDER BY ct.card_id, name ASC, t.linked_tag_id IS NOT NULL, t.created_at ASC
+            "#,
+        )
+        .bind(board_id)
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        let mut tags: HashMap<Uuid, Vec<Tag>> = HashMap::new();
+        let mut seen = HashSet::new();
+        for row in rows {
+            if seen.insert((row.card_id, row.tag.effective_id())) {
+                tags.entry(row.card_id).or_default().push(row.tag);
+            }
+        }
         Ok(tags)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_color() {
+        for color in ["#1f77b4", "#FFF", "#1f77b480", "red", "RebeccaPurple"] {
+            assert!(check_color(color).is_ok(), "{color} should be accepted");
+        }
+        for color in [
+            "",
+            "#12",
+            "#gggggg",
+            "red; background: url(x)",
+            "rgb(1,2,3)",
+        ] {
+            assert!(check_color(color).is_err(), "{color} should be rejected");
+        }
+    }
+}
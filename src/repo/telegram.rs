@@ -0,0 +1,122 @@
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::error::Result;
+
+/// Telegram chats linked to users, and the codes that link them
+#[derive(Clone)]
+pub struct TelegramRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl TelegramRepository {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+
+    /// Set the user's link code, replacing the previous one. A chat already linked
+    /// stays linked until another one is.
+    pub async fn set_link_code(
+        &self,
+        user_id: Uuid,
+        code_hash: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO telegram_links (user_id, link_code_hash, link_code_expires_at, created_at)
+            VALUES ($1, $2, $3, datetime('now'))
+            ON CONFLICT(user_id) DO UPDATE SET
+                link_code_hash = excluded.link_code_hash,
+                link_code_expires_at = excluded.link_code_expires_at
+            "#,
+        )
+        .bind(user_id)
+        .bind(code_hash)
+        .bind(expires_at)
+        .execute(self.pool.as_ref())
+        .await?;
+
+        Ok(())
+    }
+
+    /// Link the chat to the user of an unexpired code, which can then not be used
+    /// again. A chat linked to another user is moved over. Returns the user, or None
+    /// for an unknown or expired code.
+    pub async fn link_chat(&self, code_hash: &str, chat_id: i64) -> Result<Option<Uuid>> {
+        let mut tx = self.pool.begin().await?;
+
+        let user_id = sqlx::query_scalar::<_, Uuid>(
+            "SELECT user_id FROM telegram_links WHERE link_code_hash = $1 AND link_code_expires_at > $2",
+        )
+        .bind(code_hash)
+        .bind(Utc::now())
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some(user_id) = user_id else {
+            return Ok(None);
+        };
+
+        sqlx::query("UPDATE telegram_links SET chat_id = NULL WHERE chat_id = $1")
+            .bind(chat_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(
+            r#"
+            UPDATE telegram_links
+            SET chat_id = $1, link_code_hash = NULL, link_code_expires_at = NULL
+            WHERE user_id = $2
+            "#,
+        )
+        .bind(chat_id)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(Some(user_id))
+    }
+
+    pub async fn find_user_by_chat(&self, chat_id: i64) -> Result<Option<Uuid>> {
+        let user_id =
+            sqlx::query_scalar::<_, Uuid>("SELECT user_id FROM telegram_links WHERE chat_id = $1")
+                .bind(chat_id)
+                .fetch_optional(self.pool.as_ref())
+                .await?;
+
+        Ok(user_id)
+    }
+
+    pub async fn is_linked(&self, user_id: Uuid) -> Result<bool> {
+        let linked = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS(SELECT 1 FROM telegram_links WHERE user_id = $1 AND chat_id IS NOT NULL)",
+        )
+        .bind(user_id)
+        .fetch_one(self.pool.as_ref())
+        .await?;
+
+        Ok(linked)
+    }
+
+    /// Unlink the chat from its user. Returns whether it was linked.
+    pub async fn unlink_chat(&self, chat_id: i64) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM telegram_links WHERE chat_id = $1")
+            .bind(chat_id)
+            .execute(self.pool.as_ref())
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Unlink the user's chat and drop their link code. Returns whether there was either.
+    pub async fn delete(&self, user_id: Uuid) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM telegram_links WHERE user_id = $1")
+            .bind(user_id)
+            .execute(self.pool.as_ref())
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
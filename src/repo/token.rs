@@ -1,5 +1,6 @@
 use chrono::{Duration, Utc};
 use sqlx::SqlitePool;
+use std::net::IpAddr;
 use std::sync::Arc;
 use uuid::Uuid;
 
@@ -76,17 +77,21 @@ impl ApiTokenRepository {
             .await?;
 
         if result.rows_affected() == 0 {
-            return Err(AppError::NotFound);
+            return Err(AppError::NotFound("token"));
         }
 
         Ok(())
     }
 
-    pub async fn update_last_used(&self, id: Uuid) -> Result<()> {
-        sqlx::query("UPDATE api_tokens SET last_used_at = datetime('now') WHERE id = $1")
-            .bind(id)
-            .execute(self.pool.as_ref())
-            .await?;
+    /// Record a request made with the token, and the client IP it came from
+    pub async fn update_last_used(&self, id: Uuid, ip_address: IpAddr) -> Result<()> {
+        sqlx::query(
+            "UPDATE api_tokens SET last_used_at = datetime('now'), last_used_ip = $2 WHERE id = $1",
+        )
+        .bind(id)
+        .bind(ip_address.to_string())
+        .execute(self.pool.as_ref())
+        .await?;
 
         Ok(())
     }
@@ -0,0 +1,99 @@
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::error::Result;
+use crate::models::TrashItem;
+
+/// Listing and purging of deleted boards, columns and cards. Moving items to the
+/// trash and restoring them is done by their own repositories.
+#[derive(Clone)]
+pub struct TrashRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl TrashRepository {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+
+    /// Items a user can restore, most recently deleted first: boards they own,
+    /// columns and cards of boards they can edit, and their inbox cards. Items
+    /// deleted along with a board or column are restored with it and not listed.
+    pub async fn list_for_user(&self, user_id: Uuid) -> Result<Vec<TrashItem>> {
+        let items = sqlx::query_as::<_, TrashItem>(
+            r#"
+            SELECT 'board' AS kind, b.id, b.name, b.id AS board_id, b.deleted_at, b.deleted_by
+            FROM boards b
+            WHERE b.deleted_at IS NOT NULL AND b.owner_id = $1
+            UNION ALL
+            SELECT 'column' AS kind, col.id, col.name, col.board_id, col.deleted_at, col.deleted_by
+            FROM columns col
+            INNER JOIN boards b ON b.id = col.board_id
+            INNER JOIN board_access bp ON bp.board_id = b.id AND bp.user_id = $1
+            WHERE col.deleted_at IS NOT NULL AND b.deleted_at IS NULL
+              AND bp.role IN ('owner', 'editor')
+            UNION ALL
+            SELECT 'card' AS kind, c.id, c.title AS name, col.board_id, c.deleted_at, c.deleted_by
+            FROM cards c
+            LEFT JOIN columns col ON col.id = c.column_id
+            LEFT JOIN boards b ON b.id = col.board_id
+            WHERE c.deleted_at IS NOT NULL
+              AND (
+                  (c.column_id IS NULL AND c.owner_id = $1)
+                  OR (
+                      col.deleted_at IS NULL AND b.deleted_at IS NULL
+                      AND EXISTS (
+                          SELECT 1 FROM board_access bp
+                          WHERE bp.board_id = b.id AND bp.user_id = $1
+                            AND bp.role IN ('owner', 'editor')
+                      )
+                  )
+              )
+            ORDER BY deleted_at DESC
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(items)
+    }
+
+    /// Cards that have been in the trash for `days` days or more, on their own or
+    /// with their column or board
+    pub async fn list_expired_card_ids(&self, days: i64) -> Result<Vec<Uuid>> {
+        let ids = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            SELECT c.id FROM cards c
+            LEFT JOIN columns col ON col.id = c.column_id
+            LEFT JOIN boards b ON b.id = col.board_id
+            WHERE c.deleted_at <= datetime('now', $1)
+               OR col.deleted_at <= datetime('now', $1)
+               OR b.deleted_at <= datetime('now', $1)
+            "#,
+        )
+        .bind(format!("-{} days", days))
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(ids)
+    }
+
+    /// Delete for good the columns and boards that have been in the trash for `days`
+    /// days or more. Their cards must be purged first. Returns the number of
+    /// columns and boards deleted.
+    pub async fn purge_columns_and_boards(&self, days: i64) -> Result<u64> {
+        let age = format!("-{} days", days);
+        let columns = sqlx::query("DELETE FROM columns WHERE deleted_at <= datetime('now', $1)")
+            .bind(&age)
+            .execute(self.pool.as_ref())
+            .await?;
+        let boards = sqlx::query("DELETE FROM boards WHERE deleted_at <= datetime('now', $1)")
+            .bind(&age)
+            .execute(self.pool.as_ref())
+            .await?;
+
+        Ok(columns.rows_affected() + boards.rows_affected())
+    }
+}
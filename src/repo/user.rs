@@ -1,9 +1,10 @@
-use sqlx::SqlitePool;
+use chrono::Utc;
+use sqlx::{SqliteConnection, SqlitePool};
 use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::error::{AppError, Result};
-use crate::models::User;
+use crate::models::{Theme, User};
 
 #[derive(Clone)]
 pub struct UserRepository {
@@ -19,7 +20,7 @@ impl UserRepository {
         &self,
         id: Uuid,
         email: &str,
-        password_hash: &str,
+        password_hash: Option<&str>,
         name: &str,
     ) -> Result<User> {
         let user = sqlx::query_as::<_, User>(
@@ -40,9 +41,14 @@ impl UserRepository {
     }
 
     pub async fn find_by_id(&self, id: Uuid) -> Result<Option<User>> {
+        let mut conn = self.pool.acquire().await?;
+        Self::find_on(&mut conn, id).await
+    }
+
+    pub(crate) async fn find_on(conn: &mut SqliteConnection, id: Uuid) -> Result<Option<User>> {
         let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = $1")
             .bind(id)
-            .fetch_optional(self.pool.as_ref())
+            .fetch_optional(&mut *conn)
             .await?;
 
         Ok(user)
@@ -58,7 +64,13 @@ impl UserRepository {
     }
 
     pub async fn get_by_id(&self, id: Uuid) -> Result<User> {
-        self.find_by_id(id).await?.ok_or(AppError::NotFound)
+        self.find_by_id(id).await?.ok_or(AppError::NotFound("user"))
+    }
+
+    pub(crate) async fn get_on(conn: &mut SqliteConnection, id: Uuid) -> Result<User> {
+        Self::find_on(conn, id)
+            .await?
+            .ok_or(AppError::NotFound("user"))
     }
 
     pub async fn email_exists(&self, email: &str) -> Result<bool> {
@@ -86,4 +98,155 @@ impl UserRepository {
 
         Ok(user)
     }
+
+    pub async fn update_theme(&self, id: Uuid, theme: Theme) -> Result<User> {
+        let user = sqlx::query_as::<_, User>(
+            r#"
+            UPDATE users
+            SET theme = $2, updated_at = datetime('now')
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(theme.to_string())
+        .fetch_one(self.pool.as_ref())
+        .await?;
+
+        Ok(user)
+    }
+
+    pub async fn update_chat_confirm_actions(&self, id: Uuid, enabled: bool) -> Result<User> {
+        let user = sqlx::query_as::<_, User>(
+            r#"
+            UPDATE users
+            SET chat_confirm_actions = $2, updated_at = datetime('now')
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(enabled)
+        .fetch_one(self.pool.as_ref())
+        .await?;
+
+        Ok(user)
+    }
+
+    pub async fn update_llm_preferences(
+        &self,
+        id: Uuid,
+        provider: Option<&str>,
+        model: Option<&str>,
+        temperature: Option<f64>,
+    ) -> Result<User> {
+        let user = sqlx::query_as::<_, User>(
+            r#"
+            UPDATE users
+            SET llm_provider = $2, llm_model = $3, llm_temperature = $4,
+                updated_at = datetime('now')
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(provider)
+        .bind(model)
+        .bind(temperature)
+        .fetch_one(self.pool.as_ref())
+        .await?;
+
+        Ok(user)
+    }
+
+    pub async fn update_password(&self, id: Uuid, password_hash: &str) -> Result<User> {
+        let user = sqlx::query_as::<_, User>(
+            r#"
+            UPDATE users
+            SET password_hash = $2, updated_at = datetime('now')
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(password_hash)
+        .fetch_optional(self.pool.as_ref())
+        .await?
+        .ok_or(AppError::NotFound("user"))?;
+
+        Ok(user)
+    }
+
+    pub async fn update_email(&self, id: Uuid, email: &str) -> Result<User> {
+        let user = sqlx::query_as::<_, User>(
+            r#"
+            UPDATE users
+            SET email = $2, updated_at = datetime('now')
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(email)
+        .fetch_optional(self.pool.as_ref())
+        .await?
+        .ok_or(AppError::NotFound("user"))?;
+
+        Ok(user)
+    }
+
+    /// Deactivate an account, or reactivate it
+    pub async fn set_active(&self, id: Uuid, active: bool) -> Result<User> {
+        let user = sqlx::query_as::<_, User>(
+            r#"
+            UPDATE users
+            SET deactivated_at = CASE WHEN $2 THEN NULL ELSE COALESCE(deactivated_at, $3) END,
+                updated_at = datetime('now')
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(active)
+        .bind(Utc::now())
+        .fetch_optional(self.pool.as_ref())
+        .await?
+        .ok_or(AppError::NotFound("user"))?;
+
+        Ok(user)
+    }
+
+    pub async fn set_admin(&self, id: Uuid, is_admin: bool) -> Result<User> {
+        let user = sqlx::query_as::<_, User>(
+            r#"
+            UPDATE users
+            SET is_admin = $2, updated_at = datetime('now')
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(is_admin)
+        .fetch_optional(self.pool.as_ref())
+        .await?
+        .ok_or(AppError::NotFound("user"))?;
+
+        Ok(user)
+    }
+
+    /// Make the users with the given emails administrators; returns how many were promoted
+    pub async fn promote_admins(&self, emails: &[String]) -> Result<u64> {
+        let mut promoted = 0;
+        for email in emails {
+            let result = sqlx::query(
+                "UPDATE users SET is_admin = 1, updated_at = datetime('now') WHERE lower(email) = lower($1) AND is_admin = 0",
+            )
+            .bind(email)
+            .execute(self.pool.as_ref())
+            .await?;
+            promoted += result.rows_affected();
+        }
+
+        Ok(promoted)
+    }
 }
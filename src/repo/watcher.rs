@@ -0,0 +1,68 @@
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::error::Result;
+
+/// Users subscribed to the changes of cards
+#[derive(Clone)]
+pub struct CardWatcherRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl CardWatcherRepository {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+
+    /// Subscribe the user to the card. Watching twice is a no-op.
+    pub async fn watch(&self, card_id: Uuid, user_id: Uuid) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT OR IGNORE INTO card_watchers (card_id, user_id, created_at)
+            VALUES ($1, $2, datetime('now'))
+            "#,
+        )
+        .bind(card_id)
+        .bind(user_id)
+        .execute(self.pool.as_ref())
+        .await?;
+
+        Ok(())
+    }
+
+    /// Unsubscribe the user from the card. Returns whether they were watching it.
+    pub async fn unwatch(&self, card_id: Uuid, user_id: Uuid) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM card_watchers WHERE card_id = $1 AND user_id = $2")
+            .bind(card_id)
+            .bind(user_id)
+            .execute(self.pool.as_ref())
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn is_watching(&self, card_id: Uuid, user_id: Uuid) -> Result<bool> {
+        let watching = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS(SELECT 1 FROM card_watchers WHERE card_id = $1 AND user_id = $2)",
+        )
+        .bind(card_id)
+        .bind(user_id)
+        .fetch_one(self.pool.as_ref())
+        .await?;
+
+        Ok(watching)
+    }
+
+    /// Users watching the card, in the order they started
+    pub async fn list_user_ids(&self, card_id: Uuid) -> Result<Vec<Uuid>> {
+        let user_ids = sqlx::query_scalar::<_, Uuid>(
+            "SELECT user_id FROM card_watchers WHERE card_id = $1 ORDER BY created_at ASC, rowid ASC",
+        )
+        .bind(card_id)
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(user_ids)
+    }
+}
@@ -0,0 +1,153 @@
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::error::Result;
+use crate::models::{Webhook, WebhookEvent};
+
+/// Webhooks of boards and the events sent to them
+#[derive(Clone)]
+pub struct WebhookRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl WebhookRepository {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(&self, board_id: Uuid, url: &str, created_by: Uuid) -> Result<Webhook> {
+        let webhook = sqlx::query_as::<_, Webhook>(
+            r#"
+            INSERT INTO webhooks (id, board_id, url, created_by, created_at)
+            VALUES ($1, $2, $3, $4, datetime('now'))
+            RETURNING id, board_id, url, created_by, created_at
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(board_id)
+        .bind(url)
+        .bind(created_by)
+        .fetch_one(self.pool.as_ref())
+        .await?;
+
+        Ok(webhook)
+    }
+
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Option<Webhook>> {
+        let webhook = sqlx::query_as::<_, Webhook>(
+            "SELECT id, board_id, url, created_by, created_at FROM webhooks WHERE id = $1",
+        )
+        .bind(id)
+        .fetch_optional(self.pool.as_ref())
+        .await?;
+
+        Ok(webhook)
+    }
+
+    /// The board's webhooks, oldest first
+    pub async fn list_for_board(&self, board_id: Uuid) -> Result<Vec<Webhook>> {
+        let webhooks = sqlx::query_as::<_, Webhook>(
+            r#"
+            SELECT id, board_id, url, created_by, created_at FROM webhooks
+            WHERE board_id = $1
+            ORDER BY created_at ASC, rowid ASC
+            "#,
+        )
+        .bind(board_id)
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(webhooks)
+    }
+
+    /// Delete the webhook with the events kept for it. Returns whether it existed.
+    pub async fn delete(&self, id: Uuid) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM webhooks WHERE id = $1")
+            .bind(id)
+            .execute(self.pool.as_ref())
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Keep an event sent to the webhook with its payload, so it can be replayed
+    pub async fn record_event(
+        &self,
+        id: Uuid,
+        webhook_id: Uuid,
+        event: &str,
+        schema_version: i64,
+        payload: &str,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO webhook_events (id, webhook_id, event, schema_version, payload, created_at)
+            VALUES ($1, $2, $3, $4, $5, datetime('now'))
+            "#,
+        )
+        .bind(id)
+        .bind(webhook_id)
+        .bind(event)
+        .bind(schema_version)
+        .bind(payload)
+        .execute(self.pool.as_ref())
+        .await?;
+
+        Ok(())
+    }
+
+    /// Count an attempt to deliver the event, successful unless `error` is given
+    pub async fn record_attempt(&self, id: Uuid, error: Option<&str>) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE webhook_events
+            SET attempts = attempts + 1,
+                delivered_at = CASE WHEN $2 IS NULL THEN datetime('now') ELSE delivered_at END,
+                last_error = $2
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(error)
+        .execute(self.pool.as_ref())
+        .await?;
+
+        Ok(())
+    }
+
+    /// Events sent to the webhook at or after `since`, oldest first
+    pub async fn list_events_since(
+        &self,
+        webhook_id: Uuid,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<WebhookEvent>> {
+        // created_at is stored in SQLite's datetime('now') format, so compare in it
+        let events = sqlx::query_as::<_, WebhookEvent>(
+            r#"
+            SELECT id, webhook_id, event, schema_version, payload, created_at
+            FROM webhook_events
+            WHERE webhook_id = $1 AND created_at >= $2
+            ORDER BY created_at ASC, rowid ASC
+            "#,
+        )
+        .bind(webhook_id)
+        .bind(since.format("%Y-%m-%d %H:%M:%S").to_string())
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(events)
+    }
+
+    /// Delete the events kept for `days` days or more. Returns how many were deleted.
+    pub async fn purge_events(&self, days: i64) -> Result<u64> {
+        let result =
+            sqlx::query("DELETE FROM webhook_events WHERE created_at <= datetime('now', $1)")
+                .bind(format!("-{} days", days))
+                .execute(self.pool.as_ref())
+                .await?;
+
+        Ok(result.rows_affected())
+    }
+}
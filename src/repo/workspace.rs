@@ -0,0 +1,214 @@
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::models::{BoardRole, Workspace, WorkspaceMember};
+
+#[derive(Debug, sqlx::FromRow)]
+struct WorkspaceWithRole {
+    id: Uuid,
+    name: String,
+    owner_id: Uuid,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    role: String,
+}
+
+/// Workspaces and their members
+#[derive(Clone)]
+pub struct WorkspaceRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl WorkspaceRepository {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+
+    /// Create a workspace with `owner_id` as its owner
+    pub async fn create(&self, name: &str, owner_id: Uuid) -> Result<Workspace> {
+        let mut tx = self.pool.begin().await?;
+        let workspace = sqlx::query_as::<_, Workspace>(
+            r#"
+            INSERT INTO workspaces (id, name, owner_id, created_at, updated_at)
+            VALUES ($1, $2, $3, datetime('now'), datetime('now'))
+            RETURNING *
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(name)
+        .bind(owner_id)
+        .fetch_one(&mut *tx)
+        .await?;
+        sqlx::query(
+            r#"
+            INSERT INTO workspace_members (id, workspace_id, user_id, role, created_at)
+            VALUES ($1, $2, $3, $4, datetime('now'))
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(workspace.id)
+        .bind(owner_id)
+        .bind(BoardRole::Owner.to_string())
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(workspace)
+    }
+
+    pub async fn get_by_id(&self, id: Uuid) -> Result<Workspace> {
+        sqlx::query_as::<_, Workspace>("SELECT * FROM workspaces WHERE id = $1")
+            .bind(id)
+            .fetch_optional(self.pool.as_ref())
+            .await?
+            .ok_or(AppError::NotFound("workspace"))
+    }
+
+    /// Workspaces the user is a member of with their role, by name
+    pub async fn list_for_user(&self, user_id: Uuid) -> Result<Vec<(Workspace, String)>> {
+        let rows = sqlx::query_as::<_, WorkspaceWithRole>(
+            r#"
+            SELECT w.id, w.name, w.owner_id, w.created_at, w.updated_at, wm.role
+            FROM workspaces w
+            INNER JOIN workspace_members wm ON wm.workspace_id = w.id
+            WHERE wm.user_id = $1
+            ORDER BY w.name ASC, w.id ASC
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|r| {
+                (
+                    Workspace {
+                        id: r.id,
+                        name: r.name,
+                        owner_id: r.owner_id,
+                        created_at: r.created_at,
+                        updated_at: r.updated_at,
+                    },
+                    r.role,
+                )
+            })
+            .collect())
+    }
+
+    pub async fn rename(&self, id: Uuid, name: &str) -> Result<Workspace> {
+        sqlx::query_as::<_, Workspace>(
+            r#"
+            UPDATE workspaces SET name = $2, updated_at = datetime('now')
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(name)
+        .fetch_optional(self.pool.as_ref())
+        .await?
+        .ok_or(AppError::NotFound("workspace"))
+    }
+
+    /// Delete a workspace and its memberships. Its boards stay, outside any workspace.
+    pub async fn delete(&self, id: Uuid) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("UPDATE boards SET workspace_id = NULL WHERE workspace_id = $1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM workspace_members WHERE workspace_id = $1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        let result = sqlx::query("DELETE FROM workspaces WHERE id = $1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("workspace"));
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    pub async fn get_user_role(
+        &self,
+        workspace_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<BoardRole>> {
+        let role = sqlx::query_scalar::<_, String>(
+            "SELECT role FROM workspace_members WHERE workspace_id = $1 AND user_id = $2",
+        )
+        .bind(workspace_id)
+        .bind(user_id)
+        .fetch_optional(self.pool.as_ref())
+        .await?;
+
+        Ok(role.and_then(|r| r.parse().ok()))
+    }
+
+    /// Add a member, or change the role of an existing one
+    pub async fn add_member(
+        &self,
+        workspace_id: Uuid,
+        user_id: Uuid,
+        role: BoardRole,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO workspace_members (id, workspace_id, user_id, role, created_at)
+            VALUES ($1, $2, $3, $4, datetime('now'))
+            ON CONFLICT(workspace_id, user_id) DO UPDATE SET role = $4
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(workspace_id)
+        .bind(user_id)
+        .bind(role.to_string())
+        .execute(self.pool.as_ref())
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn remove_member(&self, workspace_id: Uuid, user_id: Uuid) -> Result<()> {
+        let result = sqlx::query(
+            "DELETE FROM workspace_members WHERE workspace_id = $1 AND user_id = $2 AND role != 'owner'",
+        )
+        .bind(workspace_id)
+        .bind(user_id)
+        .execute(self.pool.as_ref())
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::BadRequest(
+                "Cannot remove the owner or member not found".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    pub async fn list_members(&self, workspace_id: Uuid) -> Result<Vec<WorkspaceMember>> {
+        let members = sqlx::query_as::<_, WorkspaceMember>(
+            r#"
+            SELECT wm.user_id, u.name, u.email, wm.role, wm.created_at
+            FROM workspace_members wm
+            INNER JOIN users u ON u.id = wm.user_id
+            WHERE wm.workspace_id = $1
+            ORDER BY u.name ASC
+            "#,
+        )
+        .bind(workspace_id)
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        Ok(members)
+    }
+}
@@ -0,0 +1,214 @@
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+use crate::models::{BoardEventKind, NotificationKind};
+
+/// Characters of a comment quoted in a notification
+const QUOTE_CHARS: usize = 100;
+
+/// Fields that change on every write and say nothing about what the user did
+const IGNORED_FIELDS: &[&str] = &["updated_at", "version"];
+
+/// Fields that differ between two versions of a record, as
+/// `{"field": {"from": old, "to": new}}`, or None when nothing changed
+pub fn changes<T: Serialize>(before: &T, after: &T) -> Option<Value> {
+    let (Ok(Value::Object(before)), Ok(Value::Object(after))) =
+        (serde_json::to_value(before), serde_json::to_value(after))
+    else {
+        return None;
+    };
+
+    let mut changed = Map::new();
+    for (field, new) in &after {
+        if IGNORED_FIELDS.contains(&field.as_str()) {
+            continue;
+        }
+        let old = before.get(field).unwrap_or(&Value::Null);
+        if old != new {
+            changed.insert(field.clone(), json!({"from": old, "to": new}));
+        }
+    }
+
+    (!changed.is_empty()).then_some(Value::Object(changed))
+}
+
+/// The start of a comment as quoted in notifications
+pub fn quote(body: &str) -> String {
+    let mut quote: String = body.chars().take(QUOTE_CHARS).collect();
+    if quote.len() < body.len() {
+        quote.push('…');
+    }
+    quote
+}
+
+/// What watchers of a card are told about a change, if they are told about that kind
+/// of change at all: moves, new comments and due date changes. `column_name` is the
+/// column a moved card went to.
+pub fn watcher_notice(
+    event: BoardEventKind,
+    details: Option<&Value>,
+    column_name: Option<&str>,
+) -> Option<(NotificationKind, String)> {
+    match event {
+        BoardEventKind::CardMoved => Some((
+            NotificationKind::CardMoved,
+            match column_name {
+                Some(name) => format!("moved it to {}", name),
+                None => "moved it".to_string(),
+            },
+        )),
+        BoardEventKind::CommentCreated => {
+            let body = details?.get("body")?.as_str()?;
+            Some((
+                NotificationKind::CardCommented,
+                format!("commented: {}", quote(body)),
+            ))
+        }
+        BoardEventKind::CardUpdated => {
+            let due_date = details?.get("due_date")?;
+            Some((
+                NotificationKind::DueDateChanged,
+                match due_date.get("to").and_then(Value::as_str) {
+                    Some(date) => format!("changed the due date to {}", date),
+                    None => "removed the due date".to_string(),
+                },
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// Whether a change marked a card done or closed, which stops it from blocking others
+pub fn completes_card(event: BoardEventKind, details: Option<&Value>) -> bool {
+    let is_completed =
+        |status: Option<&Value>| matches!(status.and_then(Value::as_str), Some("done" | "closed"));
+    let Some(status) = details.and_then(|d| d.get("status")) else {
+        return false;
+    };
+    event == BoardEventKind::CardUpdated
+        && is_completed(status.get("to"))
+        && !is_completed(status.get("from"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Record {
+        title: &'static str,
+        due_date: Option<&'static str>,
+        updated_at: &'static str,
+    }
+
+    #[test]
+    fn test_changes_lists_only_changed_fields() {
+        let before = Record {
+            title: "Draft",
+            due_date: None,
+            updated_at: "2024-01-01",
+        };
+        let after = Record {
+            title: "Final",
+            due_date: None,
+            updated_at: "2024-01-02",
+        };
+
+        assert_eq!(
+            changes(&before, &after),
+            Some(json!({"title": {"from": "Draft", "to": "Final"}}))
+        );
+    }
+
+    #[test]
+    fn test_changes_none_when_only_timestamps_differ() {
+        let before = Record {
+            title: "Same",
+            due_date: Some("2024-03-01"),
+            updated_at: "2024-01-01",
+        };
+        let after = Record {
+            title: "Same",
+            due_date: Some("2024-03-01"),
+            updated_at: "2024-01-02",
+        };
+
+        assert_eq!(changes(&before, &after), None);
+    }
+
+    #[test]
+    fn test_watcher_notice() {
+        assert_eq!(
+            watcher_notice(BoardEventKind::CardMoved, None, Some("Done")),
+            Some((NotificationKind::CardMoved, "moved it to Done".to_string()))
+        );
+        assert_eq!(
+            watcher_notice(
+                BoardEventKind::CardUpdated,
+                Some(&json!({"due_date": {"from": null, "to": "2024-03-08"}})),
+                None
+            ),
+            Some((
+                NotificationKind::DueDateChanged,
+                "changed the due date to 2024-03-08".to_string()
+            ))
+        );
+        assert_eq!(
+            watcher_notice(
+                BoardEventKind::CardUpdated,
+                Some(&json!({"due_date": {"from": "2024-03-08", "to": null}})),
+                None
+            ),
+            Some((
+                NotificationKind::DueDateChanged,
+                "removed the due date".to_string()
+            ))
+        );
+        // Other edits are not worth a notification
+        assert_eq!(
+            watcher_notice(
+                BoardEventKind::CardUpdated,
+                Some(&json!({"title": {"from": "a", "to": "b"}})),
+                None
+            ),
+            None
+        );
+
+        let body = "x".repeat(150);
+        let (kind, message) = watcher_notice(
+            BoardEventKind::CommentCreated,
+            Some(&json!({ "body": body })),
+            None,
+        )
+        .unwrap();
+        assert_eq!(kind, NotificationKind::CardCommented);
+        assert_eq!(message, format!("commented: {}…", "x".repeat(100)));
+    }
+
+    #[test]
+    fn test_completes_card() {
+        let status = |from: &str, to: &str| json!({"status": {"from": from, "to": to}});
+
+        assert!(completes_card(
+            BoardEventKind::CardUpdated,
+            Some(&status("in_progress", "done"))
+        ));
+        assert!(completes_card(
+            BoardEventKind::CardUpdated,
+            Some(&status("open", "closed"))
+        ));
+        // Already completed, or reopened
+        assert!(!completes_card(
+            BoardEventKind::CardUpdated,
+            Some(&status("done", "closed"))
+        ));
+        assert!(!completes_card(
+            BoardEventKind::CardUpdated,
+            Some(&status("done", "open"))
+        ));
+        assert!(!completes_card(
+            BoardEventKind::CardUpdated,
+            Some(&json!({"title": {"from": "a", "to": "b"}}))
+        ));
+    }
+}
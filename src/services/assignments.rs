@@ -0,0 +1,121 @@
+use crate::models::{Card, CardPriority, MemberWorkload};
+
+/// Maximum number of cards one suggestion assigns
+pub const MAX_SUGGESTED_ASSIGNMENTS: usize = 20;
+
+fn urgency(card: &Card) -> u8 {
+    match card.priority.as_deref().map(str::parse::<CardPriority>) {
+        Some(Ok(CardPriority::Urgent)) => 0,
+        Some(Ok(CardPriority::High)) => 1,
+        Some(Ok(CardPriority::Medium)) => 2,
+        Some(Ok(CardPriority::Low)) => 4,
+        _ => 3,
+    }
+}
+
+/// Spread unassigned cards over the members, most urgent card first (then soonest
+/// due), each going to the member with the fewest open cards so far. Locked cards
+/// are left out, they cannot be assigned.
+pub fn plan<'a>(
+    cards: &'a [Card],
+    members: &'a [MemberWorkload],
+) -> Vec<(&'a Card, &'a MemberWorkload)> {
+    if members.is_empty() {
+        return Vec::new();
+    }
+
+    let mut cards: Vec<&Card> = cards.iter().filter(|c| !c.is_locked()).collect();
+    cards.sort_by_key(|c| (urgency(c), c.due_date.is_none(), c.due_date));
+
+    let mut load: Vec<i64> = members.iter().map(|m| m.open_cards).collect();
+    cards
+        .into_iter()
+        .take(MAX_SUGGESTED_ASSIGNMENTS)
+        .map(|card| {
+            // Ties go to the member listed first
+            let (i, _) = load
+                .iter()
+                .enumerate()
+                .min_by_key(|(i, open)| (**open, *i))
+                .expect("members is not empty");
+            load[i] += 1;
+            (card, &members[i])
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDate, Utc};
+    use uuid::Uuid;
+
+    fn card(title: &str, priority: Option<&str>, due_date: Option<NaiveDate>) -> Card {
+        let user = Uuid::new_v4();
+        Card {
+            id: Uuid::new_v4(),
+            column_id: None,
+            title: title.to_string(),
+            body: None,
+            position: 0,
+            visibility: "private".to_string(),
+            status: "open".to_string(),
+            start_date: None,
+            end_date: None,
+            due_date,
+            owner_id: Some(user),
+            created_by: user,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            locked_at: None,
+            locked_by: None,
+            archived_at: None,
+            priority: priority.map(str::to_string),
+            version: 1,
+            cover_color: None,
+            cover_attachment_id: None,
+        }
+    }
+
+    fn member(name: &str, open_cards: i64) -> MemberWorkload {
+        MemberWorkload {
+            user_id: Uuid::new_v4(),
+            name: name.to_string(),
+            open_cards,
+        }
+    }
+
+    #[test]
+    fn test_plan_balances_workload() {
+        let mut locked = card("locked", Some("urgent"), None);
+        locked.locked_at = Some(Utc::now());
+        let cards = vec![
+            card("low", Some("low"), None),
+            card("later", None, NaiveDate::from_ymd_opt(2024, 3, 20)),
+            card("urgent", Some("urgent"), None),
+            card("sooner", None, NaiveDate::from_ymd_opt(2024, 3, 1)),
+            locked,
+        ];
+        let members = vec![member("Ann", 0), member("Bob", 1)];
+
+        let plan: Vec<(&str, &str)> = plan(&cards, &members)
+            .into_iter()
+            .map(|(c, m)| (c.title.as_str(), m.name.as_str()))
+            .collect();
+        assert_eq!(
+            plan,
+            vec![
+                ("urgent", "Ann"),
+                ("sooner", "Ann"),
+                ("later", "Bob"),
+                ("low", "Ann"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_plan_without_members() {
+        let cards = vec![card("card", None, None)];
+        assert!(plan(&cards, &[]).is_empty());
+    }
+}
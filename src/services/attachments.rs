@@ -0,0 +1,328 @@
+use std::path::PathBuf;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::services::mentions::escape_html;
+
+/// Longest accepted attachment file name, in characters
+const MAX_FILENAME_LENGTH: usize = 255;
+
+/// Characters of an attachment's text kept for search
+const MAX_TEXT_CHARS: usize = 200_000;
+
+/// Extensions of files read as plain text whatever their declared content type
+const TEXT_EXTENSIONS: &[&str] = &[
+    "txt", "md", "markdown", "csv", "tsv", "json", "log", "xml", "yaml", "yml", "html", "htm",
+];
+
+/// Words of a search query used at most
+const MAX_SEARCH_TERMS: usize = 10;
+
+/// Bounds of the matched words in snippets returned by the attachment search, replaced
+/// by `<mark>` tags once the snippet is escaped
+pub const SNIPPET_MATCH_START: char = '\u{2}';
+pub const SNIPPET_MATCH_END: char = '\u{3}';
+
+/// Where attachment content is kept: in the database, or in files under a directory
+#[derive(Debug, Clone, Default)]
+pub struct AttachmentStorage {
+    dir: Option<PathBuf>,
+}
+
+impl AttachmentStorage {
+    pub fn new(dir: Option<PathBuf>) -> Self {
+        Self { dir }
+    }
+
+    /// Store files under ATTACHMENTS_DIR when it is set, in the database otherwise
+    pub fn from_env() -> Self {
+        let dir = std::env::var("ATTACHMENTS_DIR")
+            .ok()
+            .filter(|dir| !dir.trim().is_empty())
+            .map(PathBuf::from);
+        Self::new(dir)
+    }
+
+    /// Write the content to a file named after the attachment and return that name,
+    /// or None when attachments are stored in the database
+    pub async fn write(&self, id: Uuid, data: &[u8]) -> Result<Option<String>> {
+        let Some(dir) = &self.dir else {
+            return Ok(None);
+        };
+
+        let name = id.simple().to_string();
+        tokio::fs::create_dir_all(dir).await.map_err(|e| {
+            AppError::Internal(format!("Cannot create attachments directory: {}", e))
+        })?;
+        tokio::fs::write(dir.join(&name), data)
+            .await
+            .map_err(|e| AppError::Internal(format!("Cannot write attachment: {}", e)))?;
+
+        Ok(Some(name))
+    }
+
+    /// Read the content of a file written by `write`
+    pub async fn read(&self, storage_path: &str) -> Result<Vec<u8>> {
+        let dir = self.dir.as_ref().ok_or_else(|| {
+            AppError::Internal(
+                "Attachment is stored on disk but ATTACHMENTS_DIR is not set".to_string(),
+            )
+        })?;
+
+        tokio::fs::read(dir.join(storage_path))
+            .await
+            .map_err(|e| AppError::Internal(format!("Cannot read attachment: {}", e)))
+    }
+
+    /// Remove a file written by `write`. Its row is already gone, so a failure is
+    /// only logged.
+    pub async fn remove(&self, storage_path: &str) {
+        let Some(dir) = &self.dir else {
+            return;
+        };
+
+        if let Err(e) = tokio::fs::remove_file(dir.join(storage_path)).await {
+            tracing::warn!(path = storage_path, error = %e, "Could not remove attachment file");
+        }
+    }
+}
+
+/// Keep the last path component of an uploaded file name, without control characters.
+/// Returns None when nothing usable is left.
+pub fn sanitize_filename(name: &str) -> Option<String> {
+    let name: String = name
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| !c.is_control())
+        .take(MAX_FILENAME_LENGTH)
+        .collect();
+    let name = name.trim();
+
+    if name.is_empty() || name == "." || name == ".." {
+        None
+    } else {
+        Some(name.to_string())
+    }
+}
+
+/// Content-Disposition value that makes browsers download the file under its name,
+/// with an ASCII fallback for clients that do not understand `filename*`
+pub fn content_disposition(filename: &str) -> String {
+    let fallback: String = filename
+        .chars()
+        .map(|c| {
+            if c.is_ascii_graphic() && c != '"' && c != '\\' || c == ' ' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    format!(
+        "attachment; filename=\"{}\"; filename*=UTF-8''{}",
+        fallback,
+        urlencoding::encode(filename)
+    )
+}
+
+/// Text of a plain text or PDF file, with runs of whitespace collapsed, for the search
+/// index. None for other files and files without text.
+///
+/// Parsing a PDF is CPU-bound and the parser panics on some malformed files, so call
+/// this from a blocking task; a panic is treated as a file without text.
+pub fn extract_text(filename: &str, content_type: &str, data: &[u8]) -> Option<String> {
+    let extension = filename
+        .rsplit_once('.')
+        .map(|(_, ext)| ext.to_ascii_lowercase())
+        .unwrap_or_default();
+    let content_type = content_type.to_ascii_lowercase();
+
+    let text = if content_type == "application/pdf" || extension == "pdf" {
+        std::panic::catch_unwind(|| pdf_extract::extract_text_from_mem(data))
+            .ok()?
+            .ok()?
+    } else if content_type.starts_with("text/") || TEXT_EXTENSIONS.contains(&extension.as_str()) {
+        // A NUL byte means the file is binary whatever it claims to be
+        if data.contains(&0) {
+            return None;
+        }
+        String::from_utf8_lossy(data).into_owned()
+    } else {
+        return None;
+    };
+
+    let text = text
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+        .take(MAX_TEXT_CHARS)
+        .collect::<String>();
+    (!text.is_empty()).then_some(text)
+}
+
+/// Full-text query matching the words of `query`, each as a prefix. Punctuation is
+/// dropped, so user input never reaches the query syntax. None without any word.
+pub fn search_terms(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .take(MAX_SEARCH_TERMS)
+        .map(|word| format!("\"{}\"*", word))
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+/// A snippet from the search index as escaped HTML, its matches wrapped in `<mark>`
+pub fn snippet_html(snippet: &str) -> String {
+    let mut html = String::with_capacity(snippet.len());
+    for (i, part) in snippet.split(SNIPPET_MATCH_START).enumerate() {
+        // Every part after the first starts with a match
+        let (matched, rest) = match part.split_once(SNIPPET_MATCH_END) {
+            Some((matched, rest)) if i > 0 => (matched, rest),
+            _ => ("", part),
+        };
+        if !matched.is_empty() {
+            html.push_str("<mark>");
+            escape_html(matched, &mut html);
+            html.push_str("</mark>");
+        }
+        escape_html(rest, &mut html);
+    }
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_filename() {
+        assert_eq!(
+            sanitize_filename("report.pdf").as_deref(),
+            Some("report.pdf")
+        );
+        assert_eq!(
+            sanitize_filename("../../etc/passwd").as_deref(),
+            Some("passwd")
+        );
+        assert_eq!(
+            sanitize_filename("C:\\Users\\me\\notes.txt").as_deref(),
+            Some("notes.txt")
+        );
+        assert_eq!(sanitize_filename("a\nb.txt").as_deref(), Some("ab.txt"));
+        assert_eq!(sanitize_filename("dir/"), None);
+        assert_eq!(sanitize_filename(".."), None);
+        assert_eq!(sanitize_filename("  "), None);
+    }
+
+    #[test]
+    fn test_content_disposition_escapes_names() {
+        assert_eq!(
+            content_disposition("plan v2.pdf"),
+            "attachment; filename=\"plan v2.pdf\"; filename*=UTF-8''plan%20v2.pdf"
+        );
+        assert_eq!(
+            content_disposition("say \"hi\".txt"),
+            "attachment; filename=\"say _hi_.txt\"; filename*=UTF-8''say%20%22hi%22.txt"
+        );
+        assert_eq!(
+            content_disposition("café.txt"),
+            "attachment; filename=\"caf_.txt\"; filename*=UTF-8''caf%C3%A9.txt"
+        );
+    }
+
+    #[test]
+    fn test_extract_text() {
+        assert_eq!(
+            extract_text("notes.txt", "text/plain", b"Quarterly\n\n  budget\treview").as_deref(),
+            Some("Quarterly budget review")
+        );
+        // Known text extensions are read whatever the declared type
+        assert_eq!(
+            extract_text("data.csv", "application/octet-stream", b"a,b\n1,2").as_deref(),
+            Some("a,b 1,2")
+        );
+        assert_eq!(extract_text("photo.png", "image/png", b"\x89PNG"), None);
+        assert_eq!(extract_text("blob.txt", "text/plain", b"a\0b"), None);
+        assert_eq!(extract_text("empty.md", "text/markdown", b"  \n "), None);
+        // Not a valid PDF
+        assert_eq!(
+            extract_text("scan.pdf", "application/pdf", b"%PDF-1.4 junk"),
+            None
+        );
+    }
+
+    /// A one-page PDF showing `text` in Helvetica
+    fn pdf(text: &str) -> Vec<u8> {
+        let content = format!("BT /F1 12 Tf 72 720 Td ({}) Tj ET", text);
+        let objects = [
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            "<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_string(),
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 612 792] \
+             /Resources << /Font << /F1 5 0 R >> >> /Contents 4 0 R >>"
+                .to_string(),
+            format!(
+                "<< /Length {} >>\nstream\n{}\nendstream",
+                content.len(),
+                content
+            ),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>".to_string(),
+        ];
+
+        let mut pdf = b"%PDF-1.4\n".to_vec();
+        let mut offsets = Vec::new();
+        for (i, object) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            pdf.extend(format!("{} 0 obj\n{}\nendobj\n", i + 1, object).bytes());
+        }
+        let xref = pdf.len();
+        pdf.extend(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).bytes());
+        for offset in offsets {
+            pdf.extend(format!("{:010} 00000 n \n", offset).bytes());
+        }
+        pdf.extend(
+            format!(
+                "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+                objects.len() + 1,
+                xref
+            )
+            .bytes(),
+        );
+        pdf
+    }
+
+    #[test]
+    fn test_extract_pdf_text() {
+        assert_eq!(
+            extract_text(
+                "minutes.pdf",
+                "application/pdf",
+                &pdf("Quarterly budget review")
+            )
+            .as_deref(),
+            Some("Quarterly budget review")
+        );
+    }
+
+    #[test]
+    fn test_search_terms() {
+        assert_eq!(
+            search_terms("budget \"review\" OR*").as_deref(),
+            Some("\"budget\"* \"review\"* \"OR\"*")
+        );
+        assert_eq!(search_terms(" -- "), None);
+    }
+
+    #[test]
+    fn test_snippet_html() {
+        assert_eq!(
+            snippet_html("…the \u{2}budget\u{3} <b>review</b> for \u{2}Q3\u{3}"),
+            "…the <mark>budget</mark> &lt;b&gt;review&lt;/b&gt; for <mark>Q3</mark>"
+        );
+        assert_eq!(snippet_html("no match"), "no match");
+    }
+}
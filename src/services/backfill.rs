@@ -0,0 +1,232 @@
+//! Versioned data backfills.
+//!
+//! Schema changes go through the sqlx migrations in `migrations/`. Backfills are
+//! for data fixes that have to run after the schema exists (filling new columns
+//! from existing data, normalizing values). Every backfill must be idempotent:
+//! a run interrupted half-way is simply started again on the next boot.
+
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+use crate::models::{BackfillState, BackfillStatus};
+use crate::repo::backfill::BackfillRepository;
+use crate::repo::card::CardRepository;
+use crate::services::attachments::{extract_text, AttachmentStorage};
+
+pub type BackfillFuture<'a> = Pin<Box<dyn Future<Output = Result<u64>> + Send + 'a>>;
+
+/// A registered data backfill
+pub struct Backfill {
+    /// Strictly increasing version, never reused once shipped
+    pub version: i64,
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Runs the backfill and returns the number of rows it changed
+    pub run: fn(&SqlitePool) -> BackfillFuture<'_>,
+}
+
+/// All backfills, in the order they run. Append new entries at the end.
+pub const BACKFILLS: &[Backfill] = &[
+    Backfill {
+        version: 1,
+        name: "card_owner_from_creator",
+        description: "Set the owner of legacy cards without one to the user who created them",
+        run: card_owner_from_creator,
+    },
+    Backfill {
+        version: 2,
+        name: "card_status_from_column",
+        description: "Give cards the status their column maps to, such as done for cards in Done",
+        run: card_status_from_column,
+    },
+    Backfill {
+        version: 3,
+        name: "attachment_text",
+        description:
+            "Index the text of plain text and PDF attachments uploaded before search covered them",
+        run: attachment_text,
+    },
+];
+
+fn card_owner_from_creator(pool: &SqlitePool) -> BackfillFuture<'_> {
+    Box::pin(async move {
+        let result = sqlx::query("UPDATE cards SET owner_id = created_by WHERE owner_id IS NULL")
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    })
+}
+
+fn card_status_from_column(pool: &SqlitePool) -> BackfillFuture<'_> {
+    Box::pin(async move {
+        let mut conn = pool.acquire().await?;
+        CardRepository::sync_status_with_columns_on(&mut conn, None, None).await
+    })
+}
+
+fn attachment_text(pool: &SqlitePool) -> BackfillFuture<'_> {
+    Box::pin(async move {
+        let storage = AttachmentStorage::from_env();
+        let attachments = sqlx::query_as::<_, (Uuid, String, String, Option<String>)>(
+            r#"
+            SELECT id, filename, content_type, storage_path FROM attachments
+            WHERE id NOT IN (SELECT attachment_id FROM attachment_text)
+            "#,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let mut indexed = 0;
+        for (id, filename, content_type, storage_path) in attachments {
+            let data = match &storage_path {
+                Some(path) => match storage.read(path).await {
+                    Ok(data) => data,
+                    // A missing file must not hold up the backfills after this one
+                    Err(e) => {
+                        tracing::warn!(attachment_id = %id, error = %e, "Skipping attachment");
+                        continue;
+                    }
+                },
+                None => sqlx::query_scalar::<_, Option<Vec<u8>>>(
+                    "SELECT data FROM attachments WHERE id = $1",
+                )
+                .bind(id)
+                .fetch_one(pool)
+                .await?
+                .unwrap_or_default(),
+            };
+            let text =
+                tokio::task::spawn_blocking(move || extract_text(&filename, &content_type, &data))
+                    .await
+                    .map_err(|e| AppError::Internal(e.to_string()))?;
+            if let Some(text) = text {
+                sqlx::query("INSERT INTO attachment_text (text, attachment_id) VALUES ($1, $2)")
+                    .bind(text)
+                    .bind(id)
+                    .execute(pool)
+                    .await?;
+                indexed += 1;
+            }
+        }
+        Ok(indexed)
+    })
+}
+
+/// Run every backfill that has not completed yet, in version order.
+///
+/// Stops at the first failure so later backfills never see data an earlier one
+/// was supposed to fix. The failure is recorded and reported by the status
+/// endpoint; it is retried on the next start.
+pub async fn run_pending(repo: &BackfillRepository) -> Result<()> {
+    run_backfills(repo, BACKFILLS).await
+}
+
+async fn run_backfills(repo: &BackfillRepository, backfills: &[Backfill]) -> Result<()> {
+    let completed: Vec<i64> = repo
+        .list()
+        .await?
+        .into_iter()
+        .filter(|r| r.status == BackfillState::Completed.to_string())
+        .map(|r| r.version)
+        .collect();
+
+    let pending: Vec<&Backfill> = backfills
+        .iter()
+        .filter(|b| !completed.contains(&b.version))
+        .collect();
+
+    if pending.is_empty() {
+        tracing::debug!("No pending data backfills");
+        return Ok(());
+    }
+
+    let total = pending.len();
+    for (i, backfill) in pending.into_iter().enumerate() {
+        tracing::info!(
+            version = backfill.version,
+            "Running data backfill {}/{}: {}",
+            i + 1,
+            total,
+            backfill.name
+        );
+        repo.mark_started(backfill.version, backfill.name).await?;
+
+        match (backfill.run)(repo.pool()).await {
+            Ok(rows) => {
+                repo.mark_completed(backfill.version, rows).await?;
+                tracing::info!(
+                    version = backfill.version,
+                    rows_affected = rows,
+                    "Data backfill {} completed",
+                    backfill.name
+                );
+            }
+            Err(e) => {
+                repo.mark_failed(backfill.version, &e.to_string()).await?;
+                tracing::error!(
+                    version = backfill.version,
+                    error = %e,
+                    "Data backfill {} failed, skipping the remaining {}",
+                    backfill.name,
+                    total - i - 1
+                );
+                break;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Combine the registered backfills with their recorded runs
+pub async fn status(repo: &BackfillRepository) -> Result<Vec<BackfillStatus>> {
+    let mut records: HashMap<i64, _> = repo
+        .list()
+        .await?
+        .into_iter()
+        .map(|r| (r.version, r))
+        .collect();
+
+    Ok(BACKFILLS
+        .iter()
+        .map(|b| match records.remove(&b.version) {
+            Some(r) => BackfillStatus {
+                version: b.version,
+                name: b.name,
+                description: b.description,
+                status: r.status.parse().unwrap_or(BackfillState::Failed),
+                rows_affected: r.rows_affected,
+                error: r.error,
+                started_at: Some(r.started_at),
+                completed_at: r.completed_at,
+            },
+            None => BackfillStatus {
+                version: b.version,
+                name: b.name,
+                description: b.description,
+                status: BackfillState::Pending,
+                rows_affected: 0,
+                error: None,
+                started_at: None,
+                completed_at: None,
+            },
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backfill_versions_strictly_increase() {
+        for pair in BACKFILLS.windows(2) {
+            assert!(pair[0].version < pair[1].version);
+        }
+        assert!(BACKFILLS.iter().all(|b| b.version > 0));
+    }
+}
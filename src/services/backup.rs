@@ -0,0 +1,101 @@
+use chrono::{DateTime, Utc};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Hours between periodic snapshots when BACKUP_INTERVAL_HOURS is not set
+const DEFAULT_INTERVAL_HOURS: u64 = 24;
+
+/// Snapshots kept in BACKUP_DIR when BACKUP_RETENTION is not set
+const DEFAULT_RETENTION: usize = 7;
+
+const FILE_PREFIX: &str = "pos-backup-";
+const FILE_EXTENSION: &str = ".db";
+
+/// Periodic snapshots of the database, written to a directory
+#[derive(Debug, Clone)]
+pub struct BackupSchedule {
+    pub dir: PathBuf,
+    pub interval: Duration,
+    /// How many snapshots to keep, the oldest ones being deleted first
+    pub retention: usize,
+}
+
+impl BackupSchedule {
+    /// Snapshots under BACKUP_DIR every BACKUP_INTERVAL_HOURS, keeping the last
+    /// BACKUP_RETENTION ones. None when BACKUP_DIR is not set.
+    pub fn from_env() -> Option<Self> {
+        let dir = std::env::var("BACKUP_DIR")
+            .ok()
+            .filter(|dir| !dir.trim().is_empty())
+            .map(PathBuf::from)?;
+        let hours = std::env::var("BACKUP_INTERVAL_HOURS")
+            .ok()
+            .and_then(|v| v.trim().parse().ok())
+            .filter(|hours| *hours > 0)
+            .unwrap_or(DEFAULT_INTERVAL_HOURS);
+        let retention = std::env::var("BACKUP_RETENTION")
+            .ok()
+            .and_then(|v| v.trim().parse().ok())
+            .filter(|count| *count > 0)
+            .unwrap_or(DEFAULT_RETENTION);
+
+        Some(Self {
+            dir,
+            interval: Duration::from_secs(hours * 60 * 60),
+            retention,
+        })
+    }
+}
+
+/// Name of a snapshot taken at `at`. Names sort in the order snapshots were taken.
+pub fn file_name(at: DateTime<Utc>) -> String {
+    format!(
+        "{}{}{}",
+        FILE_PREFIX,
+        at.format("%Y%m%d-%H%M%S"),
+        FILE_EXTENSION
+    )
+}
+
+/// Snapshots among `names` to delete so that only the `retention` most recent ones
+/// remain. Files that are not snapshots are left alone.
+pub fn expired(names: &[String], retention: usize) -> Vec<String> {
+    let mut snapshots: Vec<&String> = names
+        .iter()
+        .filter(|name| name.starts_with(FILE_PREFIX) && name.ends_with(FILE_EXTENSION))
+        .collect();
+    snapshots.sort();
+    let excess = snapshots.len().saturating_sub(retention);
+    snapshots.into_iter().take(excess).cloned().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_file_names_sort_by_time() {
+        let earlier = file_name(Utc.with_ymd_and_hms(2024, 9, 30, 23, 0, 0).unwrap());
+        let later = file_name(Utc.with_ymd_and_hms(2024, 10, 1, 1, 0, 0).unwrap());
+        assert_eq!(earlier, "pos-backup-20240930-230000.db");
+        assert!(earlier < later);
+    }
+
+    #[test]
+    fn test_expired_keeps_the_most_recent_snapshots() {
+        let names: Vec<String> = [
+            "pos-backup-20241003-000000.db",
+            "notes.txt",
+            "pos-backup-20241001-000000.db",
+            "pos-backup-20241002-000000.db",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+
+        assert_eq!(expired(&names, 2), vec!["pos-backup-20241001-000000.db"]);
+        assert_eq!(expired(&names, 1).len(), 2);
+        assert!(expired(&names, 5).is_empty());
+    }
+}
@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::models::BoardPreference;
+
+/// Sort boards, given in their default order, into the user's order: boards with a
+/// position first, by position, then the others in their default order
+pub fn arrange<T>(
+    boards: &mut [T],
+    board_id: impl Fn(&T) -> Uuid,
+    preferences: &HashMap<Uuid, BoardPreference>,
+) {
+    boards.sort_by_key(
+        |board| match preferences.get(&board_id(board)).and_then(|p| p.position) {
+            Some(position) => (0, position),
+            None => (1, 0),
+        },
+    );
+}
+
+/// Move a board one place up or down among the boards of its section of the page, by
+/// swapping it with its neighbour in `order`, the whole list. Returns whether it moved.
+pub fn move_within(order: &mut [Uuid], section: &[Uuid], board_id: Uuid, up: bool) -> bool {
+    let Some(index) = section.iter().position(|id| *id == board_id) else {
+        return false;
+    };
+    let neighbour = if up {
+        index.checked_sub(1).map(|i| section[i])
+    } else {
+        section.get(index + 1).copied()
+    };
+    let Some(neighbour) = neighbour else {
+        return false;
+    };
+
+    let from = order.iter().position(|id| *id == board_id);
+    let to = order.iter().position(|id| *id == neighbour);
+    match (from, to) {
+        (Some(from), Some(to)) => {
+            order.swap(from, to);
+            true
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn positioned_boards_come_first() {
+        let ids: Vec<Uuid> = (0..4).map(|_| Uuid::new_v4()).collect();
+        let preferences = HashMap::from([
+            (
+                ids[2],
+                BoardPreference {
+                    board_id: ids[2],
+                    position: Some(1),
+                    ..Default::default()
+                },
+            ),
+            (
+                ids[3],
+                BoardPreference {
+                    board_id: ids[3],
+                    position: Some(0),
+                    ..Default::default()
+                },
+            ),
+            (
+                ids[0],
+                BoardPreference {
+                    board_id: ids[0],
+                    starred: true,
+                    ..Default::default()
+                },
+            ),
+        ]);
+
+        let mut boards = ids.clone();
+        arrange(&mut boards, |id| *id, &preferences);
+        assert_eq!(boards, vec![ids[3], ids[2], ids[0], ids[1]]);
+    }
+
+    #[test]
+    fn moves_past_boards_of_other_sections() {
+        let ids: Vec<Uuid> = (0..4).map(|_| Uuid::new_v4()).collect();
+        let mut order = ids.clone();
+        let section = [ids[0], ids[3]];
+
+        assert!(move_within(&mut order, &section, ids[3], true));
+        assert_eq!(order, vec![ids[3], ids[1], ids[2], ids[0]]);
+        let section = [ids[3], ids[0]];
+        assert!(!move_within(&mut order, &section, ids[3], true));
+        assert!(!move_within(&mut order, &section, ids[0], false));
+        assert!(!move_within(&mut order, &section, ids[1], false));
+    }
+}
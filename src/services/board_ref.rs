@@ -0,0 +1,160 @@
+use uuid::Uuid;
+
+use crate::models::Board;
+
+/// A board the user can access, with what chat can use to tell it apart from
+/// boards of the same name
+#[derive(Debug, Clone)]
+pub struct BoardCandidate {
+    pub board: Board,
+    pub role: String,
+    pub owner_name: String,
+    pub owner_email: String,
+}
+
+impl BoardCandidate {
+    /// One-line description listed when a reference is ambiguous
+    pub fn describe(&self) -> String {
+        format!(
+            "'{}' (id: {}, owner: {}, {})",
+            self.board.name,
+            self.board.id,
+            self.owner_name,
+            match self.board.description.as_deref() {
+                Some(desc) if !desc.is_empty() => format!("description: {}", desc),
+                _ => "no description".to_string(),
+            }
+        )
+    }
+}
+
+#[derive(Debug)]
+pub enum BoardMatch {
+    Found(Box<BoardCandidate>),
+    NotFound,
+    Ambiguous(Vec<BoardCandidate>),
+}
+
+impl BoardMatch {
+    fn from_matches(mut matches: Vec<BoardCandidate>) -> Self {
+        match matches.len() {
+            0 => BoardMatch::NotFound,
+            1 => BoardMatch::Found(Box::new(matches.remove(0))),
+            _ => BoardMatch::Ambiguous(matches),
+        }
+    }
+}
+
+/// Resolve a board reference from chat: a board id, a board name, or a name
+/// qualified by the owner's name or email as `Owner/Board`. Names are compared
+/// case-insensitively. A plain name shared by several boards is ambiguous rather
+/// than resolved to whichever comes first.
+pub fn resolve(reference: &str, candidates: Vec<BoardCandidate>) -> BoardMatch {
+    let reference = reference.trim();
+
+    if let Ok(id) = reference.parse::<Uuid>() {
+        return BoardMatch::from_matches(
+            candidates
+                .into_iter()
+                .filter(|c| c.board.id == id)
+                .collect(),
+        );
+    }
+
+    let (by_name, others): (Vec<_>, Vec<_>) = candidates
+        .into_iter()
+        .partition(|c| c.board.name.eq_ignore_ascii_case(reference));
+    if !by_name.is_empty() {
+        return BoardMatch::from_matches(by_name);
+    }
+
+    // Board names may contain '/', so try every split point
+    let qualified = others
+        .into_iter()
+        .filter(|c| {
+            reference.match_indices('/').any(|(i, _)| {
+                let (owner, name) = (reference[..i].trim(), reference[i + 1..].trim());
+                c.board.name.eq_ignore_ascii_case(name)
+                    && (c.owner_name.eq_ignore_ascii_case(owner)
+                        || c.owner_email.eq_ignore_ascii_case(owner))
+            })
+        })
+        .collect();
+
+    BoardMatch::from_matches(qualified)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn candidate(name: &str, owner: &str) -> BoardCandidate {
+        BoardCandidate {
+            board: Board {
+                id: Uuid::new_v4(),
+                name: name.to_string(),
+                description: None,
+                owner_id: Uuid::new_v4(),
+                chat_column_policy: "reject".to_string(),
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+                default_column_id: None,
+                wip_limit_policy: "warn".to_string(),
+                allow_public_links: false,
+                workspace_id: None,
+                version: 1,
+            },
+            role: "owner".to_string(),
+            owner_name: owner.to_string(),
+            owner_email: format!("{}@example.com", owner.to_lowercase()),
+        }
+    }
+
+    #[test]
+    fn test_resolve_unique_and_ambiguous_names() {
+        let boards = vec![
+            candidate("Website", "Alice"),
+            candidate("Website", "Bob"),
+            candidate("Personal", "Alice"),
+        ];
+
+        assert!(matches!(
+            resolve("personal", boards.clone()),
+            BoardMatch::Found(c) if c.board.name == "Personal"
+        ));
+        assert!(matches!(
+            resolve("Website", boards.clone()),
+            BoardMatch::Ambiguous(c) if c.len() == 2
+        ));
+        assert!(matches!(resolve("Garden", boards), BoardMatch::NotFound));
+    }
+
+    #[test]
+    fn test_resolve_qualified_references() {
+        let boards = vec![
+            candidate("Website", "Alice"),
+            candidate("Website", "Bob"),
+            candidate("Client/Website", "Carol"),
+        ];
+        let bob_board = boards[1].board.id;
+
+        assert!(matches!(
+            resolve("bob/Website", boards.clone()),
+            BoardMatch::Found(c) if c.board.id == bob_board
+        ));
+        assert!(matches!(
+            resolve("alice@example.com/website", boards.clone()),
+            BoardMatch::Found(c) if c.owner_name == "Alice"
+        ));
+        assert!(matches!(
+            resolve(&bob_board.to_string(), boards.clone()),
+            BoardMatch::Found(c) if c.board.id == bob_board
+        ));
+        // A board whose name contains '/' still matches by its full name
+        assert!(matches!(
+            resolve("Client/Website", boards),
+            BoardMatch::Found(c) if c.owner_name == "Carol"
+        ));
+    }
+}
@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use uuid::Uuid;
+
+/// What the global chat prompt lists for a board: its columns, how many cards they
+/// hold and its tags
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BoardSummary {
+    pub columns: Vec<String>,
+    pub card_count: i64,
+    pub tags: Vec<String>,
+}
+
+/// Board summaries for the global chat prompt, kept until the board changes.
+///
+/// The summaries only depend on the board, so members share them; the user's own
+/// role and the board name come from the board list, which is read on every message.
+/// Every recorded board event invalidates the board's summary. A summary computed
+/// while the board changed is not stored, so a slow reader can't bring back stale
+/// data after the invalidation.
+#[derive(Clone, Default)]
+pub struct BoardSummaryCache {
+    inner: Arc<Mutex<Summaries>>,
+}
+
+#[derive(Default)]
+struct Summaries {
+    /// Bumped by every invalidation
+    generation: u64,
+    boards: HashMap<Uuid, BoardSummary>,
+}
+
+impl BoardSummaryCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cached summaries of the given boards, and the generation to store fresh ones
+    /// under
+    pub fn get(&self, board_ids: &[Uuid]) -> (HashMap<Uuid, BoardSummary>, u64) {
+        let inner = self.inner.lock().expect("board summary lock poisoned");
+        let found = board_ids
+            .iter()
+            .filter_map(|id| inner.boards.get(id).map(|s| (*id, s.clone())))
+            .collect();
+        (found, inner.generation)
+    }
+
+    /// Store summaries read since `get` returned `generation`, unless a board changed
+    /// in the meantime
+    pub fn store(&self, generation: u64, summaries: &HashMap<Uuid, BoardSummary>) {
+        let mut inner = self.inner.lock().expect("board summary lock poisoned");
+        if inner.generation == generation {
+            inner
+                .boards
+                .extend(summaries.iter().map(|(id, s)| (*id, s.clone())));
+        }
+    }
+
+    /// Forget the board's summary after a change
+    pub fn invalidate(&self, board_id: Uuid) {
+        let mut inner = self.inner.lock().expect("board summary lock poisoned");
+        inner.generation += 1;
+        inner.boards.remove(&board_id);
+    }
+
+    /// Forget every summary, after the whole database was replaced
+    pub fn clear(&self) {
+        let mut inner = self.inner.lock().expect("board summary lock poisoned");
+        inner.generation += 1;
+        inner.boards.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(cards: i64) -> BoardSummary {
+        BoardSummary {
+            columns: vec!["To Do".to_string()],
+            card_count: cards,
+            tags: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_summaries_are_kept_until_invalidated() {
+        let cache = BoardSummaryCache::new();
+        let board = Uuid::new_v4();
+        let other = Uuid::new_v4();
+
+        let (found, generation) = cache.get(&[board, other]);
+        assert!(found.is_empty());
+        cache.store(
+            generation,
+            &HashMap::from([(board, summary(1)), (other, summary(2))]),
+        );
+        assert_eq!(cache.get(&[board]).0[&board], summary(1));
+
+        cache.invalidate(board);
+        let (found, _) = cache.get(&[board, other]);
+        assert!(!found.contains_key(&board));
+        assert_eq!(found[&other], summary(2));
+    }
+
+    #[test]
+    fn test_summaries_read_during_a_change_are_not_stored() {
+        let cache = BoardSummaryCache::new();
+        let board = Uuid::new_v4();
+
+        let (_, generation) = cache.get(&[board]);
+        cache.invalidate(board);
+        cache.store(generation, &HashMap::from([(board, summary(1))]));
+
+        assert!(cache.get(&[board]).0.is_empty());
+    }
+}
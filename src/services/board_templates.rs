@@ -0,0 +1,101 @@
+use crate::models::{BoardLayout, BoardTemplateResponse, TemplateTag};
+
+/// Board layout shipped with the application
+pub struct BuiltinTemplate {
+    pub key: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub columns: &'static [&'static str],
+    /// Tag names and colors
+    pub tags: &'static [(&'static str, &'static str)],
+}
+
+pub const BUILTIN_TEMPLATES: &[BuiltinTemplate] = &[
+    BuiltinTemplate {
+        key: "kanban",
+        name: "Kanban",
+        description: "Simple to do, doing, done flow",
+        columns: &["To Do", "In Progress", "Done"],
+        tags: &[("Bug", "#dc3545"), ("Feature", "#0d6efd")],
+    },
+    BuiltinTemplate {
+        key: "sprint",
+        name: "Sprint",
+        description: "Backlog and review stages for time-boxed iterations",
+        columns: &["Backlog", "Sprint", "In Progress", "Review", "Done"],
+        tags: &[
+            ("Bug", "#dc3545"),
+            ("Feature", "#0d6efd"),
+            ("Chore", "#6c757d"),
+        ],
+    },
+    BuiltinTemplate {
+        key: "gtd",
+        name: "Getting Things Done",
+        description: "Inbox, next actions and waiting lists, with context tags",
+        columns: &[
+            "Inbox",
+            "Next Actions",
+            "Waiting For",
+            "Someday/Maybe",
+            "Done",
+        ],
+        tags: &[
+            ("@home", "#198754"),
+            ("@work", "#0d6efd"),
+            ("@errands", "#fd7e14"),
+        ],
+    },
+];
+
+/// Built-in template with this key, ignoring case
+pub fn find_builtin(key: &str) -> Option<&'static BuiltinTemplate> {
+    BUILTIN_TEMPLATES
+        .iter()
+        .find(|t| t.key.eq_ignore_ascii_case(key.trim()))
+}
+
+impl BuiltinTemplate {
+    pub fn layout(&self) -> BoardLayout {
+        BoardLayout {
+            columns: self.columns.iter().map(|c| c.to_string()).collect(),
+            tags: self
+                .tags
+                .iter()
+                .map(|(name, color)| TemplateTag {
+                    name: name.to_string(),
+                    color: color.to_string(),
+                })
+                .collect(),
+        }
+    }
+
+    pub fn to_response(&self) -> BoardTemplateResponse {
+        let layout = self.layout();
+        BoardTemplateResponse {
+            id: self.key.to_string(),
+            name: self.name.to_string(),
+            description: Some(self.description.to_string()),
+            builtin: true,
+            columns: layout.columns,
+            tags: layout.tags,
+            created_at: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_builtin() {
+        assert_eq!(find_builtin("GTD").unwrap().columns[0], "Inbox");
+        assert_eq!(find_builtin(" kanban ").unwrap().name, "Kanban");
+        assert!(find_builtin("scrumban").is_none());
+
+        let layout = find_builtin("sprint").unwrap().layout();
+        assert_eq!(layout.columns.len(), 5);
+        assert_eq!(layout.tags[2].name, "Chore");
+    }
+}
@@ -0,0 +1,252 @@
+use chrono::{Datelike, Days, Months, NaiveDate};
+
+use crate::models::{CalendarDay, CalendarEntry, CalendarEntryKind, Card};
+
+/// Longest content line of an iCalendar file, in bytes, before it is folded
+const ICS_LINE_LIMIT: usize = 75;
+
+/// First day of the month containing `date`
+pub fn month_start(date: NaiveDate) -> NaiveDate {
+    date.with_day(1).expect("first day of month is valid")
+}
+
+/// First and last day shown on the grid of the month starting at `start`: whole
+/// weeks, Monday to Sunday
+pub fn grid_bounds(start: NaiveDate) -> (NaiveDate, NaiveDate) {
+    let first = start - Days::new(start.weekday().num_days_from_monday() as u64);
+    let month_end = start
+        .checked_add_months(Months::new(1))
+        .and_then(|d| d.pred_opt())
+        .expect("calendar dates stay in range");
+    let last = month_end + Days::new(6 - month_end.weekday().num_days_from_monday() as u64);
+    (first, last)
+}
+
+/// The weeks of the month starting at `start`, with the cards due or scheduled on
+/// each day. A card with a start and end date shows on every day in between; one
+/// with only one of them shows on that day.
+pub fn month_grid(start: NaiveDate, cards: &[Card]) -> Vec<Vec<CalendarDay>> {
+    let (first, last) = grid_bounds(start);
+    let mut days: Vec<CalendarDay> = first
+        .iter_days()
+        .take_while(|d| *d <= last)
+        .map(|date| CalendarDay {
+            date,
+            in_month: date.month() == start.month(),
+            entries: Vec::new(),
+        })
+        .collect();
+
+    for card in cards {
+        let entry = |kind| CalendarEntry {
+            card_id: card.id,
+            title: card.title.clone(),
+            status: card.status.clone(),
+            kind,
+        };
+        if let Some((from, to)) = scheduled_span(card) {
+            for day in days.iter_mut().filter(|d| d.date >= from && d.date <= to) {
+                day.entries.push(entry(CalendarEntryKind::Scheduled));
+            }
+        }
+        if let Some(due) = card.due_date {
+            if let Some(day) = days.iter_mut().find(|d| d.date == due) {
+                day.entries.push(entry(CalendarEntryKind::Due));
+            }
+        }
+    }
+
+    days.chunks(7).map(|week| week.to_vec()).collect()
+}
+
+/// Days a card is scheduled on, from its start and end dates
+fn scheduled_span(card: &Card) -> Option<(NaiveDate, NaiveDate)> {
+    match (card.start_date, card.end_date) {
+        (Some(start), Some(end)) if end >= start => Some((start, end)),
+        (Some(start), Some(_)) => Some((start, start)),
+        (Some(date), None) | (None, Some(date)) => Some((date, date)),
+        (None, None) => None,
+    }
+}
+
+/// iCalendar feed with an all-day event for each due date and each start-to-end span.
+/// `app_url` is the public address of the web UI, which events link to.
+pub fn ics_feed(cards: &[Card], app_url: &str) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//Personal OS//Card dates//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+        "X-WR-CALNAME:Personal OS".to_string(),
+    ];
+
+    for card in cards {
+        let mut events = Vec::new();
+        if let Some(due) = card.due_date {
+            events.push(("due", format!("Due: {}", card.title), due, due));
+        }
+        if let Some((from, to)) = scheduled_span(card) {
+            events.push(("scheduled", card.title.clone(), from, to));
+        }
+
+        for (kind, summary, from, to) in events {
+            lines.push("BEGIN:VEVENT".to_string());
+            lines.push(format!("UID:{}-{}@personal-os", card.id, kind));
+            lines.push(format!(
+                "DTSTAMP:{}",
+                card.updated_at.format("%Y%m%dT%H%M%SZ")
+            ));
+            lines.push(format!("DTSTART;VALUE=DATE:{}", from.format("%Y%m%d")));
+            // All-day events end on the day after their last day
+            let end = to.succ_opt().unwrap_or(to);
+            lines.push(format!("DTEND;VALUE=DATE:{}", end.format("%Y%m%d")));
+            lines.push(format!("SUMMARY:{}", escape_text(&summary)));
+            if let Some(body) = card.body.as_deref().filter(|b| !b.trim().is_empty()) {
+                lines.push(format!("DESCRIPTION:{}", escape_text(body)));
+            }
+            lines.push(format!("URL:{}/cards/{}", app_url, card.id));
+            if matches!(card.status.as_str(), "done" | "closed") {
+                lines.push("STATUS:CANCELLED".to_string());
+            }
+            lines.push("END:VEVENT".to_string());
+        }
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    lines.iter().map(|line| fold_line(line)).collect()
+}
+
+/// Escape a TEXT value: backslashes, separators and line breaks
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace(['\n', '\r'], "\\n")
+}
+
+/// Split a content line into lines of at most 75 bytes, continuation lines starting
+/// with a space, and terminate it with CRLF
+fn fold_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + 8);
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > ICS_LINE_LIMIT {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    fn card(
+        title: &str,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+        due_date: Option<NaiveDate>,
+    ) -> Card {
+        let user = Uuid::new_v4();
+        Card {
+            id: Uuid::new_v4(),
+            column_id: None,
+            title: title.to_string(),
+            body: None,
+            position: 0,
+            visibility: "private".to_string(),
+            status: "open".to_string(),
+            start_date,
+            end_date,
+            due_date,
+            owner_id: Some(user),
+            created_by: user,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            locked_at: None,
+            locked_by: None,
+            archived_at: None,
+            priority: None,
+            version: 1,
+            cover_color: None,
+            cover_attachment_id: None,
+        }
+    }
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_month_grid() {
+        // October 2026 starts on a Thursday and ends on a Saturday
+        let cards = [
+            card(
+                "Trip",
+                Some(date(2026, 10, 30)),
+                Some(date(2026, 11, 2)),
+                None,
+            ),
+            card("Taxes", None, None, Some(date(2026, 10, 15))),
+        ];
+        let weeks = month_grid(date(2026, 10, 1), &cards);
+
+        assert_eq!(weeks.len(), 5);
+        assert_eq!(weeks[0][0].date, date(2026, 9, 28));
+        assert!(!weeks[0][0].in_month);
+        assert_eq!(weeks[4][6].date, date(2026, 11, 1));
+
+        let entries = |d: NaiveDate| {
+            weeks
+                .iter()
+                .flatten()
+                .find(|day| day.date == d)
+                .unwrap()
+                .entries
+                .iter()
+                .map(|e| (e.title.as_str(), e.kind))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            entries(date(2026, 10, 15)),
+            [("Taxes", CalendarEntryKind::Due)]
+        );
+        assert_eq!(
+            entries(date(2026, 11, 1)),
+            [("Trip", CalendarEntryKind::Scheduled)]
+        );
+        assert!(entries(date(2026, 10, 29)).is_empty());
+    }
+
+    #[test]
+    fn test_ics_feed() {
+        let mut due = card(
+            "Pay rent; call, landlord",
+            None,
+            None,
+            Some(date(2026, 10, 31)),
+        );
+        due.body = Some("Line one\nLine two".to_string());
+        let long = card(&"x".repeat(100), Some(date(2026, 11, 2)), None, None);
+        let feed = ics_feed(&[due.clone(), long], "https://os.example.com");
+
+        assert!(feed.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(feed.ends_with("END:VCALENDAR\r\n"));
+        assert!(feed.contains(&format!("UID:{}-due@personal-os\r\n", due.id)));
+        assert!(feed.contains("DTSTART;VALUE=DATE:20261031\r\nDTEND;VALUE=DATE:20261101\r\n"));
+        assert!(feed.contains("SUMMARY:Due: Pay rent\\; call\\, landlord\r\n"));
+        assert!(feed.contains("DESCRIPTION:Line one\\nLine two\r\n"));
+        assert!(feed.contains(&format!("URL:https://os.example.com/cards/{}\r\n", due.id)));
+        assert!(feed.contains("DTSTART;VALUE=DATE:20261102\r\nDTEND;VALUE=DATE:20261103\r\n"));
+        assert!(feed.split("\r\n").all(|line| line.len() <= ICS_LINE_LIMIT));
+        assert!(feed.contains("\r\n xxxx"));
+    }
+}
@@ -0,0 +1,65 @@
+use chrono::NaiveDate;
+
+/// Title of a card created from a template: `{title}` becomes the given title (or
+/// nothing) and `{date}` today's date
+pub fn render_title(pattern: &str, title: Option<&str>, today: NaiveDate) -> String {
+    pattern
+        .replace("{title}", title.unwrap_or_default().trim())
+        .replace("{date}", &today.to_string())
+        .trim()
+        .to_string()
+}
+
+/// Body of a card created from a template: the template body followed by its
+/// checklist as a Markdown task list
+pub fn render_body(body: Option<&str>, checklist: &[String]) -> Option<String> {
+    let mut sections: Vec<String> = Vec::new();
+    if let Some(body) = body.map(str::trim).filter(|b| !b.is_empty()) {
+        sections.push(body.to_string());
+    }
+
+    let items: Vec<String> = checklist
+        .iter()
+        .map(|item| item.trim())
+        .filter(|item| !item.is_empty())
+        .map(|item| format!("- [ ] {}", item))
+        .collect();
+    if !items.is_empty() {
+        sections.push(items.join("\n"));
+    }
+
+    if sections.is_empty() {
+        None
+    } else {
+        Some(sections.join("\n\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_title_fills_placeholders() {
+        let today: NaiveDate = "2024-05-06".parse().unwrap();
+        assert_eq!(
+            render_title("Meeting notes {date}", None, today),
+            "Meeting notes 2024-05-06"
+        );
+        assert_eq!(
+            render_title("Bug: {title}", Some(" Login fails "), today),
+            "Bug: Login fails"
+        );
+        assert_eq!(render_title("{title}", None, today), "");
+    }
+
+    #[test]
+    fn test_render_body_appends_checklist() {
+        let checklist = vec!["Steps".to_string(), " ".to_string(), "Expected".to_string()];
+        assert_eq!(
+            render_body(Some("## Bug\n"), &checklist).as_deref(),
+            Some("## Bug\n\n- [ ] Steps\n- [ ] Expected")
+        );
+        assert_eq!(render_body(None, &[]), None);
+    }
+}
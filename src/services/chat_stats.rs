@@ -0,0 +1,180 @@
+use chrono::NaiveDate;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::models::{
+    ActionOutcomeStats, ActionTaken, ActionTypeStats, DailyActionStats, FailureReason,
+};
+
+/// Aggregated chat action outcomes
+#[derive(Debug, Default)]
+pub struct ChatStats {
+    pub overall: ActionOutcomeStats,
+    pub by_action: Vec<ActionTypeStats>,
+    pub daily: Vec<DailyActionStats>,
+    pub top_failures: Vec<FailureReason>,
+}
+
+/// Reduce a failure description to its reason, so failures about different cards
+/// or columns group together: quoted names become `*` and the raw LLM params
+/// echoed by some failures are dropped.
+pub fn failure_reason(description: &str) -> String {
+    let description = description
+        .split(". Received params")
+        .next()
+        .unwrap_or(description);
+
+    let mut reason = String::with_capacity(description.len());
+    let mut in_quote = false;
+    for c in description.chars() {
+        if c == '\'' {
+            if !in_quote {
+                reason.push_str("'*'");
+            }
+            in_quote = !in_quote;
+        } else if !in_quote {
+            reason.push(c);
+        }
+    }
+
+    reason.trim().to_string()
+}
+
+impl ActionOutcomeStats {
+    fn record(&mut self, success: bool) {
+        self.total += 1;
+        if success {
+            self.succeeded += 1;
+        } else {
+            self.failed += 1;
+        }
+        self.success_rate = Some(self.succeeded as f64 / self.total as f64);
+    }
+}
+
+/// Aggregate dated action outcomes into overall, per-action and per-day counts,
+/// plus the `top` most frequent failure reasons
+pub fn aggregate<'a, I>(actions: I, top: usize) -> ChatStats
+where
+    I: IntoIterator<Item = (NaiveDate, &'a ActionTaken)>,
+{
+    let mut overall = ActionOutcomeStats::default();
+    let mut by_action: HashMap<&str, ActionOutcomeStats> = HashMap::new();
+    let mut daily: BTreeMap<NaiveDate, ActionOutcomeStats> = BTreeMap::new();
+    let mut failures: HashMap<(&str, String), u32> = HashMap::new();
+
+    for (date, action) in actions {
+        overall.record(action.success);
+        by_action
+            .entry(action.action.as_str())
+            .or_default()
+            .record(action.success);
+        daily.entry(date).or_default().record(action.success);
+
+        if !action.success {
+            *failures
+                .entry((action.action.as_str(), failure_reason(&action.description)))
+                .or_default() += 1;
+        }
+    }
+
+    let mut by_action: Vec<ActionTypeStats> = by_action
+        .into_iter()
+        .map(|(action, stats)| ActionTypeStats {
+            action: action.to_string(),
+            stats,
+        })
+        .collect();
+    by_action.sort_by(|a, b| {
+        b.stats
+            .total
+            .cmp(&a.stats.total)
+            .then_with(|| a.action.cmp(&b.action))
+    });
+
+    let mut top_failures: Vec<FailureReason> = failures
+        .into_iter()
+        .map(|((action, reason), count)| FailureReason {
+            action: action.to_string(),
+            reason,
+            count,
+        })
+        .collect();
+    top_failures.sort_by(|a, b| {
+        b.count
+            .cmp(&a.count)
+            .then_with(|| a.action.cmp(&b.action))
+            .then_with(|| a.reason.cmp(&b.reason))
+    });
+    top_failures.truncate(top);
+
+    ChatStats {
+        overall,
+        by_action,
+        daily: daily
+            .into_iter()
+            .map(|(date, stats)| DailyActionStats { date, stats })
+            .collect(),
+        top_failures,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn action(name: &str, description: &str, success: bool) -> ActionTaken {
+        ActionTaken {
+            action: name.to_string(),
+            description: description.to_string(),
+            success,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_failure_reason_masks_names() {
+        assert_eq!(
+            failure_reason("Column 'Backlog' not found"),
+            "Column '*' not found"
+        );
+        assert_eq!(
+            failure_reason("Missing card title. Received params: {\"x\": 1}"),
+            "Missing card title"
+        );
+    }
+
+    #[test]
+    fn test_aggregate_groups_outcomes() {
+        let day1 = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let day2 = NaiveDate::from_ymd_opt(2024, 1, 2).unwrap();
+        let actions = [
+            action("create_card", "Created card 'A'", true),
+            action("create_card", "Column 'Todo' not found", false),
+            action("create_card", "Column 'Doing' not found", false),
+            action("move_card", "Moved 'A' to 'Done'", true),
+        ];
+        let dated = [
+            (day1, &actions[0]),
+            (day1, &actions[1]),
+            (day2, &actions[2]),
+            (day2, &actions[3]),
+        ];
+
+        let stats = aggregate(dated, 5);
+
+        assert_eq!(stats.overall.total, 4);
+        assert_eq!(stats.overall.success_rate, Some(0.5));
+        assert_eq!(stats.by_action[0].action, "create_card");
+        assert_eq!(stats.by_action[0].stats.failed, 2);
+        assert_eq!(stats.daily.len(), 2);
+        assert_eq!(stats.daily[0].date, day1);
+        assert_eq!(
+            stats.top_failures,
+            vec![FailureReason {
+                action: "create_card".to_string(),
+                reason: "Column '*' not found".to_string(),
+                count: 2,
+            }]
+        );
+    }
+}
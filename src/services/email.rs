@@ -0,0 +1,393 @@
+use chrono::NaiveDate;
+use lettre::message::{header::ContentType, Mailbox};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use tracing::{debug, info, warn};
+
+use crate::error::{AppError, Result};
+use crate::models::{BoardRole, NotificationKind};
+
+/// How the connection to the SMTP server is secured
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmtpSecurity {
+    /// Upgrade a plain connection with STARTTLS (usually port 587)
+    StartTls,
+    /// Connect over TLS (usually port 465)
+    Tls,
+    /// No encryption, for local relays only
+    None,
+}
+
+impl SmtpSecurity {
+    fn default_port(self) -> u16 {
+        match self {
+            SmtpSecurity::StartTls => 587,
+            SmtpSecurity::Tls => 465,
+            SmtpSecurity::None => 25,
+        }
+    }
+}
+
+impl FromStr for SmtpSecurity {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "starttls" => Ok(SmtpSecurity::StartTls),
+            "tls" => Ok(SmtpSecurity::Tls),
+            "none" => Ok(SmtpSecurity::None),
+            _ => Err(format!("Invalid SMTP security: {}", s)),
+        }
+    }
+}
+
+/// A plain-text email
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Email {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+#[derive(Clone)]
+enum Delivery {
+    /// No SMTP server is configured; emails are dropped
+    Disabled,
+    Smtp {
+        transport: Box<AsyncSmtpTransport<Tokio1Executor>>,
+        from: Mailbox,
+    },
+    /// Emails are kept in memory, for tests
+    Capture(Arc<Mutex<Vec<Email>>>),
+}
+
+/// Sends invitations and reminders over SMTP
+#[derive(Clone)]
+pub struct EmailService {
+    delivery: Delivery,
+    /// Public address of the web UI, used in links
+    app_url: String,
+}
+
+impl EmailService {
+    /// Service that drops every email
+    pub fn disabled(app_url: &str) -> Self {
+        Self {
+            delivery: Delivery::Disabled,
+            app_url: app_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// Service that keeps emails in memory instead of sending them
+    pub fn capturing(app_url: &str) -> Self {
+        Self {
+            delivery: Delivery::Capture(Arc::default()),
+            ..Self::disabled(app_url)
+        }
+    }
+
+    /// Send through SMTP_HOST when it is set; drop emails otherwise.
+    ///
+    /// SMTP_PORT, SMTP_USERNAME, SMTP_PASSWORD and SMTP_SECURITY (`starttls`, `tls` or
+    /// `none`) are optional. SMTP_FROM is the sender address; links point to `app_url`.
+    /// An invalid configuration is logged and disables email.
+    pub fn from_env(app_url: &str) -> Self {
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.trim().is_empty());
+
+        let Some(host) = var("SMTP_HOST") else {
+            info!("SMTP_HOST is not set, email delivery is disabled");
+            return Self::disabled(app_url);
+        };
+
+        match Self::smtp(
+            &host,
+            var("SMTP_PORT").as_deref(),
+            var("SMTP_SECURITY").as_deref(),
+            var("SMTP_USERNAME").zip(var("SMTP_PASSWORD")),
+            var("SMTP_FROM").as_deref(),
+        ) {
+            Ok(delivery) => {
+                info!(host = %host, "Sending email through SMTP");
+                Self {
+                    delivery,
+                    app_url: app_url.trim_end_matches('/').to_string(),
+                }
+            }
+            Err(e) => {
+                warn!(error = %e, "Invalid SMTP configuration, email delivery is disabled");
+                Self::disabled(app_url)
+            }
+        }
+    }
+
+    fn smtp(
+        host: &str,
+        port: Option<&str>,
+        security: Option<&str>,
+        credentials: Option<(String, String)>,
+        from: Option<&str>,
+    ) -> std::result::Result<Delivery, String> {
+        let security: SmtpSecurity = security.unwrap_or("starttls").parse()?;
+        let port = match port {
+            Some(port) => port
+                .parse()
+                .map_err(|_| format!("Invalid SMTP port: {}", port))?,
+            None => security.default_port(),
+        };
+        let from: Mailbox = from
+            .ok_or("SMTP_FROM is not set")?
+            .parse()
+            .map_err(|e| format!("Invalid SMTP_FROM: {}", e))?;
+
+        let mut builder = match security {
+            SmtpSecurity::StartTls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)
+                .map_err(|e| e.to_string())?,
+            SmtpSecurity::Tls => {
+                AsyncSmtpTransport::<Tokio1Executor>::relay(host).map_err(|e| e.to_string())?
+            }
+            SmtpSecurity::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host),
+        }
+        .port(port);
+        if let Some((username, password)) = credentials {
+            builder = builder.credentials(Credentials::new(username, password));
+        }
+
+        Ok(Delivery::Smtp {
+            transport: Box::new(builder.build()),
+            from,
+        })
+    }
+
+    /// Absolute link to a page of the web UI
+    pub fn link(&self, path: &str) -> String {
+        format!("{}{}", self.app_url, path)
+    }
+
+    /// Emails kept by a capturing service, oldest first
+    pub fn sent(&self) -> Vec<Email> {
+        match &self.delivery {
+            Delivery::Capture(sent) => sent.lock().unwrap().clone(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Send an email and wait for the server to accept it
+    pub async fn send(&self, email: Email) -> Result<()> {
+        match &self.delivery {
+            Delivery::Disabled => {
+                debug!(to = %email.to, subject = %email.subject, "Email delivery disabled, dropping email");
+                Ok(())
+            }
+            Delivery::Capture(sent) => {
+                sent.lock().unwrap().push(email);
+                Ok(())
+            }
+            Delivery::Smtp { transport, from } => {
+                let to: Mailbox = email
+                    .to
+                    .parse()
+                    .map_err(|e| AppError::Internal(format!("Invalid recipient: {}", e)))?;
+                let message = Message::builder()
+                    .from(from.clone())
+                    .to(to)
+                    .subject(email.subject)
+                    .header(ContentType::TEXT_PLAIN)
+                    .body(email.body)
+                    .map_err(|e| AppError::Internal(format!("Cannot build email: {}", e)))?;
+                transport
+                    .send(message)
+                    .await
+                    .map_err(|e| AppError::Internal(format!("Cannot send email: {}", e)))?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Send an email without waiting for it. Failures are logged.
+    pub fn send_in_background(&self, email: Email) {
+        // Captured emails are recorded right away so tests can see them
+        if let Delivery::Capture(sent) = &self.delivery {
+            sent.lock().unwrap().push(email);
+            return;
+        }
+
+        let service = self.clone();
+        tokio::spawn(async move {
+            let to = email.to.clone();
+            if let Err(e) = service.send(email).await {
+                warn!(to = %to, error = %e, "Could not send email");
+            }
+        });
+    }
+
+    /// Tell a user they were given access to a board
+    pub fn board_shared(
+        &self,
+        to: &str,
+        shared_by: &str,
+        board_name: &str,
+        board_id: uuid::Uuid,
+        role: BoardRole,
+    ) -> Email {
+        Email {
+            to: to.to_string(),
+            subject: format!("{} shared \"{}\" with you", shared_by, board_name),
+            body: format!(
+                "{} gave you {} access to the board \"{}\".\n\nOpen it at {}\n",
+                shared_by,
+                role,
+                board_name,
+                self.link(&format!("/boards/{}", board_id))
+            ),
+        }
+    }
+
+    /// Tell a user they were given another role on a board
+    pub fn board_role_changed(
+        &self,
+        to: &str,
+        changed_by: &str,
+        board_name: &str,
+        board_id: uuid::Uuid,
+        role: BoardRole,
+    ) -> Email {
+        Email {
+            to: to.to_string(),
+            subject: format!("Your role on \"{}\" changed", board_name),
+            body: format!(
+                "{} gave you {} access to the board \"{}\".\n\nOpen it at {}\n",
+                changed_by,
+                role,
+                board_name,
+                self.link(&format!("/boards/{}", board_id))
+            ),
+        }
+    }
+
+    /// Tell a user they no longer have access to a board
+    pub fn board_unshared(&self, to: &str, removed_by: &str, board_name: &str) -> Email {
+        Email {
+            to: to.to_string(),
+            subject: format!("{} removed you from \"{}\"", removed_by, board_name),
+            body: format!(
+                "{} removed your access to the board \"{}\".\n\nYour boards are at {}\n",
+                removed_by,
+                board_name,
+                self.link("/boards")
+            ),
+        }
+    }
+
+    /// Invite someone to a board, with the link that accepts the invitation
+    pub fn board_invitation(
+        &self,
+        to: &str,
+        invited_by: &str,
+        board_name: &str,
+        role: BoardRole,
+        token: &str,
+    ) -> Email {
+        Email {
+            to: to.to_string(),
+            subject: format!("{} invited you to \"{}\"", invited_by, board_name),
+            body: format!(
+                "{} invited you to the board \"{}\" as {}.\n\n\
+                 Accept the invitation at {}\n\n\
+                 Registering with this email address also accepts it.\n",
+                invited_by,
+                board_name,
+                role,
+                self.link(&format!("/invitations/{}", token))
+            ),
+        }
+    }
+
+    /// Ask a user to confirm their new email address, sent to that address
+    pub fn email_change(&self, to: &str, name: &str, token: &str) -> Email {
+        Email {
+            to: to.to_string(),
+            subject: "Confirm your new email address".to_string(),
+            body: format!(
+                "Hi {},\n\nConfirm {} as the email address of your Personal OS account at {}\n\n\
+                 Ignore this email if you did not ask for the change.\n",
+                name,
+                to,
+                self.link(&format!("/settings/email/{}", token))
+            ),
+        }
+    }
+
+    /// Remind a user of a card that is due soon or overdue
+    pub fn due_reminder(
+        &self,
+        to: &str,
+        card_title: &str,
+        card_id: uuid::Uuid,
+        kind: NotificationKind,
+        due_date: NaiveDate,
+    ) -> Email {
+        let (subject, status) = match kind {
+            NotificationKind::Overdue => (
+                format!("Overdue: {}", card_title),
+                format!("was due on {}", due_date),
+            ),
+            _ => (
+                format!("Due soon: {}", card_title),
+                format!("is due on {}", due_date),
+            ),
+        };
+        Email {
+            to: to.to_string(),
+            subject,
+            body: format!(
+                "The card \"{}\" {}.\n\nOpen it at {}\n",
+                card_title,
+                status,
+                self.link(&format!("/cards/{}", card_id))
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_smtp_configuration() {
+        assert!(EmailService::smtp("localhost", None, Some("none"), None, None).is_err());
+        assert!(EmailService::smtp("localhost", Some("x"), None, None, Some("a@b.c")).is_err());
+        assert!(EmailService::smtp("localhost", None, Some("plain"), None, Some("a@b.c")).is_err());
+        assert!(EmailService::smtp(
+            "localhost",
+            Some("2525"),
+            Some("none"),
+            None,
+            Some("Personal OS <pos@example.com>")
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_due_reminder_links_to_card() {
+        let service = EmailService::disabled("https://pos.example.com/");
+        let card_id = uuid::Uuid::nil();
+        let email = service.due_reminder(
+            "me@example.com",
+            "File taxes",
+            card_id,
+            NotificationKind::Overdue,
+            "2024-03-10".parse().unwrap(),
+        );
+        assert_eq!(email.subject, "Overdue: File taxes");
+        assert_eq!(
+            email.body,
+            format!(
+                "The card \"File taxes\" was due on 2024-03-10.\n\nOpen it at https://pos.example.com/cards/{}\n",
+                card_id
+            )
+        );
+    }
+}
@@ -0,0 +1,138 @@
+use axum::{
+    extract::FromRequestParts,
+    http::{header, request::Parts, HeaderMap, HeaderValue},
+    response::{IntoResponseParts, ResponseParts},
+};
+use std::convert::Infallible;
+
+use crate::error::{AppError, Result};
+
+/// ETag response header for a card, board or column at the given version
+#[derive(Debug, Clone, Copy)]
+pub struct ETag(pub i64);
+
+impl ETag {
+    pub fn value(self) -> HeaderValue {
+        HeaderValue::from_str(&format!("\"{}\"", self.0))
+            .expect("a quoted number is a valid header")
+    }
+}
+
+impl IntoResponseParts for ETag {
+    type Error = Infallible;
+
+    fn into_response_parts(
+        self,
+        mut res: ResponseParts,
+    ) -> std::result::Result<ResponseParts, Self::Error> {
+        res.headers_mut().insert(header::ETAG, self.value());
+        Ok(res)
+    }
+}
+
+/// The `If-Match` precondition of a request. Without the header, or with `*`,
+/// any version is accepted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IfMatch {
+    Any,
+    Tags(Vec<String>),
+}
+
+impl IfMatch {
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let mut tags = Vec::new();
+        for value in headers.get_all(header::IF_MATCH) {
+            // A header that isn't valid text can't match any ETag we hand out
+            let Ok(value) = value.to_str() else {
+                tags.push(String::new());
+                continue;
+            };
+            for tag in value.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+                if tag == "*" {
+                    return IfMatch::Any;
+                }
+                tags.push(tag.to_string());
+            }
+        }
+
+        if tags.is_empty() {
+            IfMatch::Any
+        } else {
+            IfMatch::Tags(tags)
+        }
+    }
+
+    /// The version an update must still find when it writes, given the version
+    /// currently stored. Fails when none of the listed ETags is the current one;
+    /// weak ETags never match, as If-Match uses strong comparison.
+    pub fn expected_version(&self, current: i64) -> Result<Option<i64>> {
+        match self {
+            IfMatch::Any => Ok(None),
+            IfMatch::Tags(tags) => {
+                let current_tag = format!("\"{}\"", current);
+                if tags.contains(&current_tag) {
+                    Ok(Some(current))
+                } else {
+                    Err(AppError::PreconditionFailed)
+                }
+            }
+        }
+    }
+}
+
+impl<S> FromRequestParts<S> for IfMatch
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        _state: &S,
+    ) -> std::result::Result<Self, Self::Rejection> {
+        Ok(IfMatch::from_headers(&parts.headers))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(values: &[&'static str]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for value in values {
+            headers.append(header::IF_MATCH, HeaderValue::from_static(value));
+        }
+        headers
+    }
+
+    #[test]
+    fn test_etag_value_is_quoted_version() {
+        assert_eq!(ETag(7).value(), "\"7\"");
+    }
+
+    #[test]
+    fn test_missing_or_wildcard_matches_any_version() {
+        assert_eq!(IfMatch::from_headers(&headers(&[])), IfMatch::Any);
+        assert_eq!(IfMatch::from_headers(&headers(&["*"])), IfMatch::Any);
+        assert_eq!(IfMatch::Any.expected_version(3).unwrap(), None);
+    }
+
+    #[test]
+    fn test_listed_tags_must_include_current_version() {
+        let if_match = IfMatch::from_headers(&headers(&["\"2\", \"3\""]));
+        assert_eq!(if_match.expected_version(3).unwrap(), Some(3));
+        assert!(matches!(
+            if_match.expected_version(4),
+            Err(AppError::PreconditionFailed)
+        ));
+    }
+
+    #[test]
+    fn test_weak_and_unquoted_tags_never_match() {
+        for value in ["W/\"3\"", "3"] {
+            let if_match = IfMatch::from_headers(&headers(&[value]));
+            assert!(if_match.expected_version(3).is_err());
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+use crate::models::BoardEvent;
+
+/// Events buffered per board before a slow subscriber starts missing them
+const CHANNEL_CAPACITY: usize = 64;
+
+/// In-process fan-out of board events, with one broadcast channel per board.
+///
+/// Channels are created on the first subscription and dropped on the next publish
+/// once every subscriber has gone, so idle boards cost nothing.
+#[derive(Clone, Default)]
+pub struct EventHub {
+    channels: Arc<Mutex<HashMap<Uuid, broadcast::Sender<BoardEvent>>>>,
+}
+
+impl EventHub {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Receive every event published on the board from now on
+    pub fn subscribe(&self, board_id: Uuid) -> broadcast::Receiver<BoardEvent> {
+        let mut channels = self.channels.lock().expect("event hub lock poisoned");
+        channels
+            .entry(board_id)
+            .or_insert_with(|| broadcast::channel(CHANNEL_CAPACITY).0)
+            .subscribe()
+    }
+
+    /// Send an event to the board's subscribers, if any
+    pub fn publish(&self, event: BoardEvent) {
+        let mut channels = self.channels.lock().expect("event hub lock poisoned");
+        if let Some(sender) = channels.get(&event.board_id) {
+            let board_id = event.board_id;
+            if sender.send(event).is_err() {
+                channels.remove(&board_id);
+            }
+        }
+    }
+
+    /// Number of boards with at least one channel open
+    pub fn active_boards(&self) -> usize {
+        self.channels.lock().expect("event hub lock poisoned").len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::BoardEventKind;
+
+    fn event(board_id: Uuid) -> BoardEvent {
+        BoardEvent::new(
+            BoardEventKind::CardCreated,
+            board_id,
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_publish_reaches_only_the_board_subscribers() {
+        let hub = EventHub::new();
+        let board = Uuid::new_v4();
+        let other = Uuid::new_v4();
+        let mut rx = hub.subscribe(board);
+        let mut other_rx = hub.subscribe(other);
+
+        let sent = event(board);
+        hub.publish(sent.clone());
+
+        let received = rx.recv().await.unwrap();
+        assert_eq!(received.id, sent.id);
+        assert!(other_rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_channel_dropped_when_subscribers_leave() {
+        let hub = EventHub::new();
+        let board = Uuid::new_v4();
+
+        // Publishing without subscribers does not open a channel
+        hub.publish(event(board));
+        assert_eq!(hub.active_boards(), 0);
+
+        let rx = hub.subscribe(board);
+        assert_eq!(hub.active_boards(), 1);
+        drop(rx);
+
+        hub.publish(event(board));
+        assert_eq!(hub.active_boards(), 0);
+    }
+}
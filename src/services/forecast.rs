@@ -0,0 +1,135 @@
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use rand::Rng;
+
+use crate::models::ForecastPercentile;
+
+/// Percentiles reported by the forecast endpoint
+pub const FORECAST_PERCENTILES: [u8; 4] = [50, 70, 85, 95];
+
+/// Upper bound on simulated weeks, so a throughput history made mostly of
+/// zero weeks cannot make a single trial loop forever
+const MAX_SIMULATED_WEEKS: u32 = 520;
+
+/// Bucket completion timestamps into weekly throughput counts.
+///
+/// Returns one entry per week, oldest first, for the `weeks` weeks ending at `now`.
+pub fn weekly_throughput(
+    completed_at: &[DateTime<Utc>],
+    now: DateTime<Utc>,
+    weeks: u32,
+) -> Vec<u32> {
+    let mut buckets = vec![0u32; weeks as usize];
+
+    for ts in completed_at {
+        let age = now.signed_duration_since(*ts);
+        if age < Duration::zero() {
+            continue;
+        }
+        let week = age.num_weeks();
+        if week < weeks as i64 {
+            let index = weeks as usize - 1 - week as usize;
+            buckets[index] += 1;
+        }
+    }
+
+    buckets
+}
+
+/// Run a Monte Carlo simulation of how many weeks it takes to finish `remaining` cards.
+///
+/// Each trial repeatedly draws a random week from the throughput history until the
+/// remaining backlog is exhausted. Returns the sorted number of weeks per trial, or
+/// an empty vector when the history contains no completed cards at all.
+pub fn simulate_weeks<R: Rng>(
+    samples: &[u32],
+    remaining: u32,
+    trials: u32,
+    rng: &mut R,
+) -> Vec<u32> {
+    if samples.iter().all(|s| *s == 0) {
+        return Vec::new();
+    }
+
+    let mut results: Vec<u32> = (0..trials)
+        .map(|_| {
+            let mut left = remaining as i64;
+            let mut weeks = 0;
+            while left > 0 && weeks < MAX_SIMULATED_WEEKS {
+                left -= samples[rng.random_range(0..samples.len())] as i64;
+                weeks += 1;
+            }
+            weeks
+        })
+        .collect();
+
+    results.sort_unstable();
+    results
+}
+
+/// Pick percentile values out of sorted trial results and convert them to dates
+pub fn percentiles(sorted_weeks: &[u32], start: NaiveDate) -> Vec<ForecastPercentile> {
+    if sorted_weeks.is_empty() {
+        return Vec::new();
+    }
+
+    FORECAST_PERCENTILES
+        .iter()
+        .map(|p| {
+            let rank = (sorted_weeks.len() * *p as usize).div_ceil(100);
+            let weeks = sorted_weeks[rank.saturating_sub(1)];
+            ForecastPercentile {
+                percentile: *p,
+                weeks,
+                date: start + Duration::weeks(weeks as i64),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_weekly_throughput_buckets() {
+        let now = Utc::now();
+        let completed = vec![
+            now - Duration::days(1),
+            now - Duration::days(2),
+            now - Duration::days(8),
+            now - Duration::days(100),
+        ];
+
+        assert_eq!(weekly_throughput(&completed, now, 3), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_simulate_weeks_constant_throughput() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let weeks = simulate_weeks(&[2, 2, 2], 5, 100, &mut rng);
+
+        assert_eq!(weeks.len(), 100);
+        assert!(weeks.iter().all(|w| *w == 3));
+    }
+
+    #[test]
+    fn test_simulate_weeks_without_history() {
+        let mut rng = StdRng::seed_from_u64(7);
+        assert!(simulate_weeks(&[0, 0], 5, 100, &mut rng).is_empty());
+    }
+
+    #[test]
+    fn test_percentiles_are_monotonic() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let weeks = simulate_weeks(&[0, 1, 3, 5, 2], 20, 1000, &mut rng);
+        let start = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let result = percentiles(&weeks, start);
+
+        assert_eq!(result.len(), FORECAST_PERCENTILES.len());
+        for pair in result.windows(2) {
+            assert!(pair[0].weeks <= pair[1].weeks);
+            assert!(pair[0].date <= pair[1].date);
+        }
+    }
+}
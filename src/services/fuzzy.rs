@@ -0,0 +1,68 @@
+/// Levenshtein edit distance between two strings, by characters
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0; b.len() + 1];
+
+    for (i, ca) in a.chars().enumerate() {
+        curr[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == *cb { 0 } else { 1 };
+            curr[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    prev[b.len()]
+}
+
+/// Find the candidate closest to `name`, ignoring case.
+///
+/// A candidate containing the name (or contained in it) always qualifies; otherwise the
+/// edit distance must be at most a third of the name length (minimum 2) to count as a match.
+pub fn closest_match<'a, I>(name: &str, candidates: I) -> Option<&'a str>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let needle = name.trim().to_lowercase();
+    if needle.is_empty() {
+        return None;
+    }
+    let max_distance = (needle.chars().count() / 3).max(2);
+
+    candidates
+        .into_iter()
+        .filter_map(|candidate| {
+            let hay = candidate.to_lowercase();
+            let distance = if hay.contains(&needle) || needle.contains(&hay) {
+                0
+            } else {
+                levenshtein(&needle, &hay)
+            };
+            (distance <= max_distance).then_some((distance, candidate))
+        })
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_levenshtein() {
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+        assert_eq!(levenshtein("", "abc"), 3);
+        assert_eq!(levenshtein("done", "done"), 0);
+    }
+
+    #[test]
+    fn test_closest_match() {
+        let columns = ["To Do", "In Progress", "Done"];
+
+        assert_eq!(closest_match("todo", columns), Some("To Do"));
+        assert_eq!(closest_match("progress", columns), Some("In Progress"));
+        assert_eq!(closest_match("dones", columns), Some("Done"));
+        assert_eq!(closest_match("Backlog", columns), None);
+    }
+}
@@ -0,0 +1,145 @@
+use mail_parser::{MessageParser, MimeHeaders};
+
+use crate::services::attachments::sanitize_filename;
+
+/// Title of cards made from emails without a subject
+const NO_SUBJECT: &str = "(no subject)";
+
+/// Card contents taken from a received email
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InboundEmail {
+    pub title: String,
+    pub body: Option<String>,
+    pub attachments: Vec<InboundAttachment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InboundAttachment {
+    pub filename: String,
+    pub content_type: String,
+    pub data: Vec<u8>,
+}
+
+/// Subject without the "Fwd:" and "Fw:" prefixes added when forwarding
+fn card_title(subject: Option<&str>) -> String {
+    let mut title = subject.unwrap_or_default().trim();
+    while let Some((prefix, rest)) = title.split_once(':') {
+        if !matches!(prefix.trim().to_ascii_lowercase().as_str(), "fwd" | "fw") {
+            break;
+        }
+        title = rest.trim_start();
+    }
+
+    if title.is_empty() {
+        NO_SUBJECT.to_string()
+    } else {
+        title.to_string()
+    }
+}
+
+/// Parse a raw RFC 822 email: the subject becomes the card title, the text body (or
+/// the HTML body converted to text) the card body, and every attachment a card
+/// attachment. Returns None when the input is not an email.
+pub fn parse(raw: &[u8]) -> Option<InboundEmail> {
+    let message = MessageParser::default().parse(raw)?;
+    // A parsed message always has a root part; without headers it is not an email
+    message.headers().first()?;
+
+    let body = message
+        .body_text(0)
+        .map(|text| text.trim().to_string())
+        .filter(|text| !text.is_empty());
+
+    let attachments = message
+        .attachments()
+        .enumerate()
+        .filter(|(_, part)| !part.contents().is_empty())
+        .map(|(i, part)| {
+            let content_type = part
+                .content_type()
+                .map(|ct| match ct.subtype() {
+                    Some(subtype) => format!("{}/{}", ct.ctype(), subtype),
+                    None => ct.ctype().to_string(),
+                })
+                .unwrap_or_else(|| "application/octet-stream".to_string());
+            InboundAttachment {
+                filename: part
+                    .attachment_name()
+                    .and_then(sanitize_filename)
+                    .unwrap_or_else(|| format!("attachment-{}", i + 1)),
+                content_type,
+                data: part.contents().to_vec(),
+            }
+        })
+        .collect();
+
+    Some(InboundEmail {
+        title: card_title(message.subject()),
+        body,
+        attachments,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_card_title() {
+        assert_eq!(card_title(Some("  Call the plumber ")), "Call the plumber");
+        assert_eq!(card_title(Some("Fwd: FW: Invoice")), "Invoice");
+        assert_eq!(card_title(Some("Re: Invoice")), "Re: Invoice");
+        assert_eq!(card_title(Some("Fwd:")), NO_SUBJECT);
+        assert_eq!(card_title(None), NO_SUBJECT);
+    }
+
+    #[test]
+    fn test_parse_multipart_email() {
+        let raw = concat!(
+            "From: Alice <alice@example.com>\r\n",
+            "To: inbox@example.com\r\n",
+            "Subject: Fwd: Quarterly report\r\n",
+            "MIME-Version: 1.0\r\n",
+            "Content-Type: multipart/mixed; boundary=\"XYZ\"\r\n",
+            "\r\n",
+            "--XYZ\r\n",
+            "Content-Type: text/plain; charset=utf-8\r\n",
+            "\r\n",
+            "Numbers attached.\r\n",
+            "--XYZ\r\n",
+            "Content-Type: text/csv\r\n",
+            "Content-Disposition: attachment; filename=\"q3.csv\"\r\n",
+            "Content-Transfer-Encoding: base64\r\n",
+            "\r\n",
+            "YSxiCjEsMgo=\r\n",
+            "--XYZ--\r\n",
+        );
+
+        let email = parse(raw.as_bytes()).unwrap();
+        assert_eq!(email.title, "Quarterly report");
+        assert_eq!(email.body.as_deref(), Some("Numbers attached."));
+        assert_eq!(
+            email.attachments,
+            [InboundAttachment {
+                filename: "q3.csv".to_string(),
+                content_type: "text/csv".to_string(),
+                data: b"a,b\n1,2\n".to_vec(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_parse_html_only_email() {
+        let raw = concat!(
+            "Subject: \r\n",
+            "Content-Type: text/html\r\n",
+            "\r\n",
+            "<p>Hello <b>there</b></p>\r\n",
+        );
+
+        let email = parse(raw.as_bytes()).unwrap();
+        assert_eq!(email.title, NO_SUBJECT);
+        assert!(email.body.unwrap().contains("Hello there"));
+        assert!(email.attachments.is_empty());
+    }
+}
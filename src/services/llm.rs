@@ -0,0 +1,253 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+use crate::error::Result;
+use crate::services::{OllamaClient, OpenAiClient};
+
+/// A message of a chat conversation, as sent to and received from a provider
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LlmMessage {
+    pub role: String,
+    pub content: String,
+}
+
+/// A function the model may call instead of answering in text
+#[derive(Debug, Clone, Serialize)]
+pub struct LlmTool {
+    pub name: String,
+    pub description: String,
+    /// JSON schema of the arguments
+    pub parameters: serde_json::Value,
+}
+
+/// A tool in the function-calling format shared by Ollama and OpenAI
+#[derive(Debug, Serialize)]
+pub(crate) struct FunctionTool {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    function: LlmTool,
+}
+
+impl From<LlmTool> for FunctionTool {
+    fn from(function: LlmTool) -> Self {
+        Self {
+            kind: "function",
+            function,
+        }
+    }
+}
+
+/// A call the model made to one of the tools it was offered
+#[derive(Debug, Clone, PartialEq)]
+pub struct LlmToolCall {
+    pub name: String,
+    /// Arguments object, `Null` when the model sent arguments that are not JSON
+    pub arguments: serde_json::Value,
+}
+
+/// What the model replied: text, tool calls or both
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LlmReply {
+    pub content: String,
+    pub tool_calls: Vec<LlmToolCall>,
+}
+
+/// Per-request settings overriding the provider's defaults
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LlmOptions {
+    /// Model to use instead of the provider's configured one
+    pub model: Option<String>,
+    pub temperature: Option<f64>,
+}
+
+/// Future returned by [`LlmProvider`] methods
+pub type LlmFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T>> + Send + 'a>>;
+
+/// A chat completion backend
+pub trait LlmProvider: Send + Sync {
+    /// Model the requests are sent to unless [`LlmOptions::model`] is set
+    fn model(&self) -> &str;
+
+    /// Send a conversation, offering `tools` to the model, and return its full reply
+    fn chat(
+        &self,
+        messages: Vec<LlmMessage>,
+        tools: Vec<LlmTool>,
+        options: LlmOptions,
+    ) -> LlmFuture<'_, LlmReply>;
+
+    /// Stream a chat response, sending each piece of generated text to `tokens` as it
+    /// arrives, and return the full reply once generation completes. Tool calls are
+    /// only part of the returned reply.
+    ///
+    /// Generation continues if the receiver is dropped, so the caller still gets the
+    /// complete response.
+    fn chat_stream(
+        &self,
+        messages: Vec<LlmMessage>,
+        tools: Vec<LlmTool>,
+        options: LlmOptions,
+        tokens: mpsc::Sender<String>,
+    ) -> LlmFuture<'_, LlmReply>;
+}
+
+/// Which API the chat requests are sent to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ProviderKind {
+    Ollama,
+    OpenAi,
+    Anthropic,
+    OpenRouter,
+    LlamaCpp,
+}
+
+impl ProviderKind {
+    /// Base URL of the OpenAI-compatible API, when the provider has a well-known one
+    fn default_base_url(self) -> Option<&'static str> {
+        match self {
+            ProviderKind::Ollama => None,
+            ProviderKind::OpenAi => Some("https://api.openai.com/v1"),
+            ProviderKind::Anthropic => Some("https://api.anthropic.com/v1"),
+            ProviderKind::OpenRouter => Some("https://openrouter.ai/api/v1"),
+            ProviderKind::LlamaCpp => Some("http://localhost:8080/v1"),
+        }
+    }
+}
+
+impl fmt::Display for ProviderKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProviderKind::Ollama => write!(f, "ollama"),
+            ProviderKind::OpenAi => write!(f, "openai"),
+            ProviderKind::Anthropic => write!(f, "anthropic"),
+            ProviderKind::OpenRouter => write!(f, "openrouter"),
+            ProviderKind::LlamaCpp => write!(f, "llamacpp"),
+        }
+    }
+}
+
+impl FromStr for ProviderKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "ollama" => Ok(ProviderKind::Ollama),
+            "openai" | "openai-compatible" => Ok(ProviderKind::OpenAi),
+            "anthropic" => Ok(ProviderKind::Anthropic),
+            "openrouter" => Ok(ProviderKind::OpenRouter),
+            "llamacpp" | "llama.cpp" => Ok(ProviderKind::LlamaCpp),
+            _ => Err(format!("Invalid LLM provider: {}", s)),
+        }
+    }
+}
+
+/// Providers chat requests can be sent to, by kind
+pub type LlmProviders = BTreeMap<ProviderKind, Arc<dyn LlmProvider>>;
+
+/// Configured providers: Ollama, and the one selected by LLM_PROVIDER when it is set.
+///
+/// Ollama reads OLLAMA_URL and OLLAMA_MODEL. The other providers speak the OpenAI
+/// chat completions API and read LLM_BASE_URL (defaulting to the provider's public
+/// endpoint), LLM_API_KEY and LLM_MODEL, which is required except for llama.cpp. An
+/// invalid configuration is logged and leaves only Ollama.
+pub fn from_env() -> LlmProviders {
+    let var = |name: &str| std::env::var(name).ok().filter(|v| !v.trim().is_empty());
+
+    let mut providers = LlmProviders::new();
+    providers.insert(ProviderKind::Ollama, Arc::new(OllamaClient::from_env()));
+
+    match openai_compatible(
+        var("LLM_PROVIDER").as_deref(),
+        var("LLM_BASE_URL"),
+        var("LLM_API_KEY"),
+        var("LLM_MODEL"),
+    ) {
+        Ok(Some((kind, client))) => {
+            info!(model = %client.model(), "Sending chat requests to an OpenAI-compatible API");
+            providers.insert(kind, Arc::new(client));
+        }
+        Ok(None) => {}
+        Err(e) => warn!(error = %e, "Invalid LLM configuration, using Ollama"),
+    }
+
+    providers
+}
+
+/// Provider for users who did not choose one: the one selected by LLM_PROVIDER, Ollama
+/// when it is not set
+pub fn default_provider(providers: &LlmProviders) -> Arc<dyn LlmProvider> {
+    providers
+        .iter()
+        .find(|(kind, _)| **kind != ProviderKind::Ollama)
+        .or_else(|| providers.iter().next())
+        .map(|(_, provider)| provider.clone())
+        .unwrap_or_else(|| Arc::new(OllamaClient::from_env()))
+}
+
+fn openai_compatible(
+    provider: Option<&str>,
+    base_url: Option<String>,
+    api_key: Option<String>,
+    model: Option<String>,
+) -> std::result::Result<Option<(ProviderKind, OpenAiClient)>, String> {
+    let kind: ProviderKind = provider.unwrap_or("ollama").parse()?;
+    let Some(default_base_url) = kind.default_base_url() else {
+        return Ok(None);
+    };
+
+    let model = match (model, kind) {
+        (Some(model), _) => model,
+        // llama.cpp serves the model it was started with, whatever the request names
+        (None, ProviderKind::LlamaCpp) => "default".to_string(),
+        (None, _) => return Err("LLM_MODEL is not set".to_string()),
+    };
+    let base_url = base_url.unwrap_or_else(|| default_base_url.to_string());
+
+    Ok(Some((kind, OpenAiClient::new(base_url, api_key, model))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_provider_configuration() {
+        assert!(openai_compatible(None, None, None, None).unwrap().is_none());
+        assert!(
+            openai_compatible(Some("Ollama"), None, None, Some("x".into()))
+                .unwrap()
+                .is_none()
+        );
+        assert!(openai_compatible(Some("gpt"), None, None, None).is_err());
+        assert!(openai_compatible(Some("openai"), None, None, None).is_err());
+
+        let (kind, client) = openai_compatible(
+            Some("openrouter"),
+            None,
+            Some("key".into()),
+            Some("meta-llama/llama-3.1-8b-instruct".into()),
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(kind, ProviderKind::OpenRouter);
+        assert_eq!(client.model(), "meta-llama/llama-3.1-8b-instruct");
+        assert_eq!(client.base_url(), "https://openrouter.ai/api/v1");
+
+        let (_, client) = openai_compatible(
+            Some("llamacpp"),
+            Some("http://gpu-box:8080/v1/".into()),
+            None,
+            None,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(client.base_url(), "http://gpu-box:8080/v1");
+    }
+}
@@ -0,0 +1,139 @@
+use std::ops::Range;
+
+/// A user as they can be mentioned
+pub struct Mentionable<'a> {
+    pub name: &'a str,
+    pub email: &'a str,
+}
+
+impl Mentionable<'_> {
+    /// Whether `handle` (lowercase, without the `@`) is this user's email address or
+    /// name without spaces
+    pub fn matches(&self, handle: &str) -> bool {
+        self.email.eq_ignore_ascii_case(handle) || handle_of(self.name) == handle
+    }
+}
+
+/// The handle a name is mentioned by: the name without spaces, in lowercase
+pub fn handle_of(name: &str) -> String {
+    name.split_whitespace().collect::<String>().to_lowercase()
+}
+
+fn is_handle_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '.' | '_' | '+' | '-')
+}
+
+/// Mentions in a comment body, as the byte range of each `@handle` and its handle in
+/// lowercase. A handle is a run of letters, digits and `._+-`, optionally followed by
+/// `@domain` for email addresses. An `@` right after a letter or digit is part of an
+/// email address rather than a mention, and trailing dots end the sentence.
+pub fn find(body: &str) -> Vec<(Range<usize>, String)> {
+    let mut mentions = Vec::new();
+    let mut previous: Option<char> = None;
+    let mut chars = body.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        let after_word = previous.is_some_and(|p| p.is_alphanumeric() || p == '_');
+        previous = Some(c);
+        if c != '@' || after_word {
+            continue;
+        }
+
+        let mut end = start + 1;
+        let mut seen_at = false;
+        while let Some(&(i, c)) = chars.peek() {
+            let takes = is_handle_char(c) || (c == '@' && !seen_at && end > start + 1);
+            if !takes {
+                break;
+            }
+            seen_at |= c == '@';
+            end = i + c.len_utf8();
+            previous = Some(c);
+            chars.next();
+        }
+
+        let handle = body[start + 1..end].trim_end_matches(['.', '@']);
+        if !handle.is_empty() {
+            let end = start + 1 + handle.len();
+            mentions.push((start..end, handle.to_lowercase()));
+        }
+    }
+
+    mentions
+}
+
+/// Distinct handles mentioned in a comment body
+pub fn handles(body: &str) -> Vec<String> {
+    let mut handles: Vec<String> = Vec::new();
+    for (_, handle) in find(body) {
+        if !handles.contains(&handle) {
+            handles.push(handle);
+        }
+    }
+    handles
+}
+
+/// Append `text` to `out`, escaped for HTML
+pub(crate) fn escape_html(text: &str, out: &mut String) {
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#x27;"),
+            c => out.push(c),
+        }
+    }
+}
+
+/// The body as escaped HTML, with the mentions of `users` highlighted
+pub fn highlight(body: &str, users: &[Mentionable]) -> String {
+    let mut html = String::with_capacity(body.len());
+    let mut done = 0;
+    for (range, handle) in find(body) {
+        if !users.iter().any(|u| u.matches(&handle)) {
+            continue;
+        }
+        escape_html(&body[done..range.start], &mut html);
+        html.push_str(r#"<span class="mention text-primary fw-semibold">"#);
+        escape_html(&body[range.clone()], &mut html);
+        html.push_str("</span>");
+        done = range.end;
+    }
+    escape_html(&body[done..], &mut html);
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_mentions() {
+        assert_eq!(
+            handles("@Alice and @bob.smith@example.com, please review. Thanks @Carol."),
+            ["alice", "bob.smith@example.com", "carol"]
+        );
+        // Email addresses and lone signs are not mentions
+        assert!(handles("Mail dave@example.com at 5 @ noon").is_empty());
+        assert_eq!(handles("@alice @Alice"), ["alice"]);
+        assert_eq!(find("hi @Zoë!"), [(3..8, "zoë".to_string())]);
+    }
+
+    #[test]
+    fn test_highlight_escapes_and_marks_known_users() {
+        let users = [Mentionable {
+            name: "Alice Smith",
+            email: "alice@example.com",
+        }];
+        assert_eq!(
+            highlight("<b>@AliceSmith</b> & @nobody", &users),
+            "&lt;b&gt;<span class=\"mention text-primary fw-semibold\">@AliceSmith</span>&lt;/b&gt; &amp; @nobody"
+        );
+        assert_eq!(
+            highlight("cc @alice@example.com.", &users),
+            "cc <span class=\"mention text-primary fw-semibold\">@alice@example.com</span>."
+        );
+    }
+}
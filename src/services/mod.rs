@@ -1,5 +1,50 @@
+pub mod activity;
+pub mod assignments;
+pub mod attachments;
+pub mod backfill;
+pub mod backup;
+pub mod board_order;
+pub mod board_ref;
+pub mod board_summaries;
+pub mod board_templates;
+pub mod calendar;
+pub mod card_templates;
+pub mod chat_stats;
+pub mod email;
+pub mod etag;
+pub mod events;
+pub mod forecast;
+pub mod fuzzy;
+pub mod inbound_email;
+pub mod llm;
+pub mod mentions;
+pub mod oauth;
 pub mod ollama;
+pub mod openai;
+pub mod pagination;
+pub mod planning;
+pub mod quick_add;
+pub mod recurrence;
+pub mod revisions;
+pub mod roadmap;
+pub mod stats;
+pub mod telegram;
+pub mod text_import;
+pub mod timeline;
+pub mod trello_import;
 pub mod web_search;
+pub mod webhooks;
 
+pub use attachments::AttachmentStorage;
+pub use board_summaries::BoardSummaryCache;
+pub use email::EmailService;
+pub use events::EventHub;
+pub use llm::{
+    LlmMessage, LlmOptions, LlmProvider, LlmProviders, LlmReply, LlmTool, LlmToolCall, ProviderKind,
+};
+pub use oauth::{OAuthProvider, OAuthProviders, ProviderAccount};
 pub use ollama::OllamaClient;
+pub use openai::OpenAiClient;
+pub use telegram::TelegramClient;
 pub use web_search::{format_search_results, WebSearchClient};
+pub use webhooks::WebhookSender;
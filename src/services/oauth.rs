@@ -0,0 +1,377 @@
+use reqwest::{Client, Url};
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::OnceCell;
+use tracing::{error, info, warn};
+
+use crate::error::{AppError, Result};
+
+const GITHUB_AUTHORIZE_URL: &str = "https://github.com/login/oauth/authorize";
+const GITHUB_TOKEN_URL: &str = "https://github.com/login/oauth/access_token";
+const GITHUB_API_URL: &str = "https://api.github.com";
+
+/// Endpoints of an OpenID Connect provider, from its discovery document
+#[derive(Debug, Clone, Deserialize)]
+struct OidcEndpoints {
+    authorization_endpoint: String,
+    token_endpoint: String,
+    userinfo_endpoint: String,
+}
+
+enum Flavor {
+    GitHub,
+    /// Any OpenID Connect provider, whose endpoints are discovered on first use
+    Oidc {
+        issuer_url: String,
+        endpoints: OnceCell<OidcEndpoints>,
+    },
+}
+
+/// A provider users can sign in with instead of a password
+pub struct OAuthProvider {
+    /// Name in the login routes, `/auth/oauth/{name}`
+    name: String,
+    /// Name on the login button
+    display_name: String,
+    client_id: String,
+    client_secret: String,
+    flavor: Flavor,
+}
+
+impl OAuthProvider {
+    pub fn github(client_id: String, client_secret: String) -> Self {
+        Self {
+            name: "github".to_string(),
+            display_name: "GitHub".to_string(),
+            client_id,
+            client_secret,
+            flavor: Flavor::GitHub,
+        }
+    }
+
+    /// OpenID Connect provider publishing its configuration under `issuer_url`
+    pub fn oidc(
+        issuer_url: String,
+        client_id: String,
+        client_secret: String,
+        display_name: Option<String>,
+    ) -> Self {
+        Self {
+            name: "oidc".to_string(),
+            display_name: display_name.unwrap_or_else(|| "Single sign-on".to_string()),
+            client_id,
+            client_secret,
+            flavor: Flavor::Oidc {
+                issuer_url: issuer_url.trim_end_matches('/').to_string(),
+                endpoints: OnceCell::new(),
+            },
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn display_name(&self) -> &str {
+        &self.display_name
+    }
+}
+
+/// The account a provider signed a user in with
+#[derive(Debug, Clone)]
+pub struct ProviderAccount {
+    /// The provider's stable id for the account
+    pub subject: String,
+    /// Verified email address
+    pub email: String,
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: Option<String>,
+    error: Option<String>,
+    error_description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitHubUser {
+    id: u64,
+    login: String,
+    name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitHubEmail {
+    email: String,
+    primary: bool,
+    verified: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct OidcUserInfo {
+    sub: String,
+    email: Option<String>,
+    /// A boolean, sent as a string by some providers
+    #[serde(default)]
+    email_verified: serde_json::Value,
+    name: Option<String>,
+}
+
+/// The configured OAuth providers
+#[derive(Clone)]
+pub struct OAuthProviders {
+    providers: Arc<Vec<OAuthProvider>>,
+    client: Client,
+    /// Public address of the web UI, which providers redirect back to
+    app_url: String,
+}
+
+impl OAuthProviders {
+    pub fn new(app_url: &str, providers: Vec<OAuthProvider>) -> Self {
+        Self {
+            providers: Arc::new(providers),
+            client: Client::builder()
+                // GitHub rejects API requests without a user agent
+                .user_agent("personal-os")
+                .timeout(Duration::from_secs(15))
+                .build()
+                .expect("Failed to create HTTP client"),
+            app_url: app_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// GitHub when OAUTH_GITHUB_CLIENT_ID and OAUTH_GITHUB_CLIENT_SECRET are set, an
+    /// OpenID Connect provider when OIDC_ISSUER_URL, OIDC_CLIENT_ID and
+    /// OIDC_CLIENT_SECRET are set. OIDC_DISPLAY_NAME labels its login button. Providers
+    /// redirect back to `app_url`.
+    pub fn from_env(app_url: &str) -> Self {
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.trim().is_empty());
+
+        let mut providers = Vec::new();
+        match (
+            var("OAUTH_GITHUB_CLIENT_ID"),
+            var("OAUTH_GITHUB_CLIENT_SECRET"),
+        ) {
+            (Some(id), Some(secret)) => {
+                info!("GitHub login is enabled");
+                providers.push(OAuthProvider::github(id, secret));
+            }
+            (None, None) => {}
+            _ => warn!(
+                "GitHub login needs both OAUTH_GITHUB_CLIENT_ID and OAUTH_GITHUB_CLIENT_SECRET"
+            ),
+        }
+        match (
+            var("OIDC_ISSUER_URL"),
+            var("OIDC_CLIENT_ID"),
+            var("OIDC_CLIENT_SECRET"),
+        ) {
+            (Some(issuer), Some(id), Some(secret)) => {
+                info!(issuer = %issuer, "OpenID Connect login is enabled");
+                providers.push(OAuthProvider::oidc(
+                    issuer,
+                    id,
+                    secret,
+                    var("OIDC_DISPLAY_NAME"),
+                ));
+            }
+            (None, None, None) => {}
+            _ => warn!(
+                "OpenID Connect login needs OIDC_ISSUER_URL, OIDC_CLIENT_ID and OIDC_CLIENT_SECRET"
+            ),
+        }
+
+        Self::new(app_url, providers)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&OAuthProvider> {
+        self.providers.iter().find(|p| p.name == name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &OAuthProvider> {
+        self.providers.iter()
+    }
+
+    /// Address the provider sends the user back to after login
+    fn redirect_uri(&self, provider: &OAuthProvider) -> String {
+        format!("{}/auth/oauth/{}/callback", self.app_url, provider.name)
+    }
+
+    /// Address of the provider's login page, which returns `state` to the callback
+    pub async fn authorize_url(&self, provider: &OAuthProvider, state: &str) -> Result<String> {
+        let (endpoint, scope) = match &provider.flavor {
+            Flavor::GitHub => (GITHUB_AUTHORIZE_URL.to_string(), "read:user user:email"),
+            Flavor::Oidc { .. } => (
+                self.endpoints(provider)
+                    .await?
+                    .authorization_endpoint
+                    .clone(),
+                "openid email profile",
+            ),
+        };
+        let redirect_uri = self.redirect_uri(provider);
+        let url = Url::parse_with_params(
+            &endpoint,
+            &[
+                ("response_type", "code"),
+                ("client_id", provider.client_id.as_str()),
+                ("redirect_uri", redirect_uri.as_str()),
+                ("scope", scope),
+                ("state", state),
+            ],
+        )
+        .map_err(|e| AppError::Internal(format!("Invalid authorization endpoint: {}", e)))?;
+        Ok(url.to_string())
+    }
+
+    /// Exchange the code the provider returned for the account that signed in
+    pub async fn account(&self, provider: &OAuthProvider, code: &str) -> Result<ProviderAccount> {
+        match &provider.flavor {
+            Flavor::GitHub => {
+                let token = self.exchange_code(provider, GITHUB_TOKEN_URL, code).await?;
+                self.github_account(provider, &token).await
+            }
+            Flavor::Oidc { .. } => {
+                let endpoints = self.endpoints(provider).await?;
+                let token = self
+                    .exchange_code(provider, &endpoints.token_endpoint, code)
+                    .await?;
+                self.oidc_account(provider, &endpoints.userinfo_endpoint, &token)
+                    .await
+            }
+        }
+    }
+
+    async fn endpoints<'a>(&self, provider: &'a OAuthProvider) -> Result<&'a OidcEndpoints> {
+        let Flavor::Oidc {
+            issuer_url,
+            endpoints,
+        } = &provider.flavor
+        else {
+            return Err(AppError::Internal(format!(
+                "{} is not an OpenID Connect provider",
+                provider.display_name
+            )));
+        };
+        endpoints
+            .get_or_try_init(|| async {
+                let url = format!("{}/.well-known/openid-configuration", issuer_url);
+                self.get_json(self.client.get(&url)).await
+            })
+            .await
+    }
+
+    async fn exchange_code(
+        &self,
+        provider: &OAuthProvider,
+        token_url: &str,
+        code: &str,
+    ) -> Result<String> {
+        let redirect_uri = self.redirect_uri(provider);
+        let request = self
+            .client
+            .post(token_url)
+            .header("Accept", "application/json")
+            .form(&[
+                ("grant_type", "authorization_code"),
+                ("code", code),
+                ("redirect_uri", redirect_uri.as_str()),
+                ("client_id", provider.client_id.as_str()),
+                ("client_secret", provider.client_secret.as_str()),
+            ]);
+        let response: TokenResponse = self.get_json(request).await?;
+        // GitHub reports a bad code with a successful status
+        response.access_token.ok_or_else(|| {
+            let reason = response
+                .error_description
+                .or(response.error)
+                .unwrap_or_else(|| "no access token".to_string());
+            warn!(provider = %provider.name, reason = %reason, "OAuth code exchange failed");
+            AppError::Internal(format!("Code exchange failed: {}", reason))
+        })
+    }
+
+    async fn github_account(
+        &self,
+        provider: &OAuthProvider,
+        token: &str,
+    ) -> Result<ProviderAccount> {
+        let user: GitHubUser = self
+            .get_json(
+                self.client
+                    .get(format!("{}/user", GITHUB_API_URL))
+                    .bearer_auth(token),
+            )
+            .await?;
+        let emails: Vec<GitHubEmail> = self
+            .get_json(
+                self.client
+                    .get(format!("{}/user/emails", GITHUB_API_URL))
+                    .bearer_auth(token),
+            )
+            .await?;
+        let email = emails
+            .into_iter()
+            .find(|e| e.primary && e.verified)
+            .ok_or_else(|| no_verified_email(provider))?;
+
+        Ok(ProviderAccount {
+            subject: user.id.to_string(),
+            email: email.email,
+            name: user.name.or(Some(user.login)),
+        })
+    }
+
+    async fn oidc_account(
+        &self,
+        provider: &OAuthProvider,
+        userinfo_url: &str,
+        token: &str,
+    ) -> Result<ProviderAccount> {
+        let info: OidcUserInfo = self
+            .get_json(self.client.get(userinfo_url).bearer_auth(token))
+            .await?;
+        let verified = matches!(&info.email_verified, serde_json::Value::Bool(true))
+            || info.email_verified.as_str() == Some("true");
+        let email = info
+            .email
+            .filter(|_| verified)
+            .ok_or_else(|| no_verified_email(provider))?;
+
+        Ok(ProviderAccount {
+            subject: info.sub,
+            email,
+            name: info.name,
+        })
+    }
+
+    async fn get_json<T: serde::de::DeserializeOwned>(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> Result<T> {
+        let response = request.send().await.map_err(|e| {
+            error!(error = %e, "OAuth provider request failed");
+            AppError::Internal(format!("OAuth provider request failed: {}", e))
+        })?;
+        if !response.status().is_success() {
+            warn!(status = %response.status(), url = %response.url(), "OAuth provider returned an error");
+            return Err(AppError::Internal(format!(
+                "OAuth provider returned status {}",
+                response.status()
+            )));
+        }
+        response.json().await.map_err(|e| {
+            error!(error = %e, "Invalid OAuth provider response");
+            AppError::Internal(format!("Invalid OAuth provider response: {}", e))
+        })
+    }
+}
+
+fn no_verified_email(provider: &OAuthProvider) -> AppError {
+    AppError::Validation(format!(
+        "Your {} account has no verified email address",
+        provider.display_name
+    ))
+}
@@ -1,7 +1,11 @@
 use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
 use tracing::{debug, error, info, instrument, warn};
 
 use crate::error::{AppError, Result};
+use crate::services::llm::{
+    FunctionTool, LlmFuture, LlmMessage, LlmOptions, LlmProvider, LlmReply, LlmTool, LlmToolCall,
+};
 
 #[derive(Clone)]
 pub struct OllamaClient {
@@ -13,19 +17,91 @@ pub struct OllamaClient {
 #[derive(Debug, Serialize)]
 struct OllamaChatRequest {
     model: String,
-    messages: Vec<OllamaMessage>,
+    messages: Vec<LlmMessage>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tools: Vec<FunctionTool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    options: Option<OllamaOptions>,
     stream: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct OllamaMessage {
-    pub role: String,
-    pub content: String,
+/// Model parameters of a chat request
+#[derive(Debug, Serialize)]
+struct OllamaOptions {
+    temperature: f64,
 }
 
 #[derive(Debug, Deserialize)]
 struct OllamaChatResponse {
-    message: OllamaMessage,
+    message: OllamaResponseMessage,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct OllamaResponseMessage {
+    #[serde(default)]
+    content: String,
+    #[serde(default)]
+    tool_calls: Vec<OllamaToolCall>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OllamaToolCall {
+    function: OllamaFunctionCall,
+}
+
+#[derive(Debug, Deserialize)]
+struct OllamaFunctionCall {
+    name: String,
+    #[serde(default)]
+    arguments: serde_json::Value,
+}
+
+impl From<OllamaToolCall> for LlmToolCall {
+    fn from(call: OllamaToolCall) -> Self {
+        Self {
+            name: call.function.name,
+            arguments: call.function.arguments,
+        }
+    }
+}
+
+/// One line of a streamed chat response
+#[derive(Debug, Deserialize)]
+struct OllamaStreamChunk {
+    #[serde(default)]
+    message: Option<OllamaResponseMessage>,
+    #[serde(default)]
+    done: bool,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+/// Splits the newline-delimited JSON of a streamed response into chunks,
+/// buffering lines that arrive split across network reads
+#[derive(Debug, Default)]
+struct StreamDecoder {
+    buffer: Vec<u8>,
+}
+
+impl StreamDecoder {
+    fn push(&mut self, bytes: &[u8]) -> Result<Vec<OllamaStreamChunk>> {
+        self.buffer.extend_from_slice(bytes);
+
+        let mut chunks = Vec::new();
+        while let Some(end) = self.buffer.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=end).collect();
+            let line = line.trim_ascii();
+            if line.is_empty() {
+                continue;
+            }
+            let chunk = serde_json::from_slice::<OllamaStreamChunk>(line).map_err(|e| {
+                AppError::Internal(format!("Failed to parse Ollama stream chunk: {}", e))
+            })?;
+            chunks.push(chunk);
+        }
+
+        Ok(chunks)
+    }
 }
 
 impl OllamaClient {
@@ -52,23 +128,28 @@ impl OllamaClient {
         Self::new(base_url, model)
     }
 
-    pub fn model(&self) -> &str {
-        &self.model
-    }
-
-    #[instrument(skip(self, messages), fields(model = %self.model, message_count = messages.len()))]
-    pub async fn chat(&self, messages: Vec<OllamaMessage>) -> Result<String> {
+    /// Send a chat request and return the response once its status is known to be a success
+    async fn send_chat(
+        &self,
+        messages: Vec<LlmMessage>,
+        tools: Vec<LlmTool>,
+        options: LlmOptions,
+        stream: bool,
+    ) -> Result<reqwest::Response> {
         let request = OllamaChatRequest {
-            model: self.model.clone(),
+            model: options.model.unwrap_or_else(|| self.model.clone()),
             messages,
-            stream: false,
+            tools: tools.into_iter().map(FunctionTool::from).collect(),
+            options: options
+                .temperature
+                .map(|temperature| OllamaOptions { temperature }),
+            stream,
         };
 
         let url = format!("{}/api/chat", self.base_url);
 
-        debug!(url = %url, "Sending chat request to Ollama");
+        debug!(url = %url, model = %request.model, stream, "Sending chat request to Ollama");
 
-        let start = std::time::Instant::now();
         let response: reqwest::Response = self
             .client
             .post(&url)
@@ -80,8 +161,6 @@ impl OllamaClient {
                 AppError::Internal(format!("Ollama request failed: {}", e))
             })?;
 
-        let elapsed = start.elapsed();
-
         if !response.status().is_success() {
             let status = response.status();
             let body: String = response
@@ -99,6 +178,20 @@ impl OllamaClient {
             )));
         }
 
+        Ok(response)
+    }
+
+    #[instrument(skip(self, messages, tools, options), fields(model = %self.model, message_count = messages.len()))]
+    async fn complete(
+        &self,
+        messages: Vec<LlmMessage>,
+        tools: Vec<LlmTool>,
+        options: LlmOptions,
+    ) -> Result<LlmReply> {
+        let start = std::time::Instant::now();
+        let response = self.send_chat(messages, tools, options, false).await?;
+        let elapsed = start.elapsed();
+
         let chat_response: OllamaChatResponse =
             response.json::<OllamaChatResponse>().await.map_err(|e| {
                 error!(error = %e, "Failed to parse Ollama response");
@@ -108,12 +201,73 @@ impl OllamaClient {
         info!(
             elapsed_ms = elapsed.as_millis(),
             response_length = chat_response.message.content.len(),
+            tool_calls = chat_response.message.tool_calls.len(),
             "Ollama chat completed"
         );
 
         debug!(response = %chat_response.message.content, "LLM response content");
 
-        Ok(chat_response.message.content)
+        Ok(LlmReply {
+            content: chat_response.message.content,
+            tool_calls: chat_response
+                .message
+                .tool_calls
+                .into_iter()
+                .map(LlmToolCall::from)
+                .collect(),
+        })
+    }
+
+    #[instrument(skip(self, messages, tools, options, tokens), fields(model = %self.model, message_count = messages.len()))]
+    async fn complete_stream(
+        &self,
+        messages: Vec<LlmMessage>,
+        tools: Vec<LlmTool>,
+        options: LlmOptions,
+        tokens: mpsc::Sender<String>,
+    ) -> Result<LlmReply> {
+        let start = std::time::Instant::now();
+        let mut response = self.send_chat(messages, tools, options, true).await?;
+
+        let mut decoder = StreamDecoder::default();
+        let mut reply = LlmReply::default();
+        'stream: while let Some(bytes) = response.chunk().await.map_err(|e| {
+            error!(error = %e, "Ollama stream interrupted");
+            AppError::Internal(format!("Ollama stream interrupted: {}", e))
+        })? {
+            for chunk in decoder.push(&bytes)? {
+                if let Some(error) = chunk.error {
+                    error!(error = %error, "Ollama stream returned error");
+                    return Err(AppError::Internal(format!(
+                        "Ollama returned error: {}",
+                        error
+                    )));
+                }
+                let message = chunk.message.unwrap_or_default();
+                reply
+                    .tool_calls
+                    .extend(message.tool_calls.into_iter().map(LlmToolCall::from));
+                if !message.content.is_empty() {
+                    reply.content.push_str(&message.content);
+                    // A closed receiver only means nobody is listening any more
+                    let _ = tokens.send(message.content).await;
+                }
+                if chunk.done {
+                    break 'stream;
+                }
+            }
+        }
+
+        info!(
+            elapsed_ms = start.elapsed().as_millis(),
+            response_length = reply.content.len(),
+            tool_calls = reply.tool_calls.len(),
+            "Ollama chat stream completed"
+        );
+
+        debug!(response = %reply.content, "LLM response content");
+
+        Ok(reply)
     }
 
     pub async fn is_available(&self) -> bool {
@@ -130,3 +284,82 @@ impl OllamaClient {
         }
     }
 }
+
+impl LlmProvider for OllamaClient {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn chat(
+        &self,
+        messages: Vec<LlmMessage>,
+        tools: Vec<LlmTool>,
+        options: LlmOptions,
+    ) -> LlmFuture<'_, LlmReply> {
+        Box::pin(self.complete(messages, tools, options))
+    }
+
+    fn chat_stream(
+        &self,
+        messages: Vec<LlmMessage>,
+        tools: Vec<LlmTool>,
+        options: LlmOptions,
+        tokens: mpsc::Sender<String>,
+    ) -> LlmFuture<'_, LlmReply> {
+        Box::pin(self.complete_stream(messages, tools, options, tokens))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contents(chunks: &[OllamaStreamChunk]) -> Vec<&str> {
+        chunks
+            .iter()
+            .filter_map(|c| c.message.as_ref().map(|m| m.content.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_decoder_joins_lines_split_across_reads() {
+        let mut decoder = StreamDecoder::default();
+
+        let first = decoder
+            .push(b"{\"message\":{\"role\":\"assistant\",\"content\":\"Hel\"},\"done\":false}\n{\"message\":")
+            .unwrap();
+        assert_eq!(contents(&first), vec!["Hel"]);
+
+        let second = decoder
+            .push(
+                b"{\"role\":\"assistant\",\"content\":\"lo\"},\"done\":false}\n\n{\"done\":true}\n",
+            )
+            .unwrap();
+        assert_eq!(contents(&second), vec!["lo"]);
+        assert!(second.last().unwrap().done);
+    }
+
+    #[test]
+    fn test_decoder_rejects_invalid_json() {
+        let mut decoder = StreamDecoder::default();
+        assert!(decoder.push(b"not json\n").is_err());
+    }
+
+    #[test]
+    fn test_decoder_reads_tool_calls() {
+        let mut decoder = StreamDecoder::default();
+        let chunks = decoder
+            .push(b"{\"message\":{\"role\":\"assistant\",\"content\":\"\",\"tool_calls\":[{\"function\":{\"name\":\"create_tag\",\"arguments\":{\"name\":\"urgent\"}}}]},\"done\":true}\n")
+            .unwrap();
+
+        let message = chunks.into_iter().next().unwrap().message.unwrap();
+        let calls: Vec<LlmToolCall> = message.tool_calls.into_iter().map(Into::into).collect();
+        assert_eq!(
+            calls,
+            vec![LlmToolCall {
+                name: "create_tag".to_string(),
+                arguments: serde_json::json!({"name": "urgent"}),
+            }]
+        );
+    }
+}
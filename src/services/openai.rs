@@ -0,0 +1,477 @@
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use tracing::{debug, error, info, instrument, warn};
+
+use crate::error::{AppError, Result};
+use crate::services::llm::{
+    FunctionTool, LlmFuture, LlmMessage, LlmOptions, LlmProvider, LlmReply, LlmTool, LlmToolCall,
+};
+
+/// Client for APIs compatible with the OpenAI chat completions endpoint: OpenAI,
+/// Anthropic, OpenRouter, the llama.cpp server and most gateways
+#[derive(Clone)]
+pub struct OpenAiClient {
+    client: reqwest::Client,
+    base_url: String,
+    api_key: Option<String>,
+    model: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ChatCompletionRequest<'a> {
+    model: &'a str,
+    messages: Vec<LlmMessage>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tools: Vec<FunctionTool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f64>,
+    stream: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatCompletionResponse {
+    choices: Vec<Choice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Choice {
+    message: ResponseMessage,
+}
+
+#[derive(Debug, Deserialize)]
+struct ResponseMessage {
+    /// Null when the model only called tools
+    #[serde(default)]
+    content: Option<String>,
+    #[serde(default)]
+    tool_calls: Vec<ToolCall>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ToolCall {
+    function: FunctionCall,
+}
+
+#[derive(Debug, Deserialize)]
+struct FunctionCall {
+    name: String,
+    /// JSON-encoded arguments object, sent as a plain object by some gateways
+    #[serde(default)]
+    arguments: serde_json::Value,
+}
+
+impl From<ToolCall> for LlmToolCall {
+    fn from(call: ToolCall) -> Self {
+        let arguments = match call.function.arguments {
+            serde_json::Value::String(arguments) => {
+                parse_arguments(&call.function.name, &arguments)
+            }
+            serde_json::Value::Null => serde_json::json!({}),
+            arguments => arguments,
+        };
+        Self {
+            name: call.function.name,
+            arguments,
+        }
+    }
+}
+
+/// Decode the JSON-encoded arguments of a tool call
+fn parse_arguments(name: &str, arguments: &str) -> serde_json::Value {
+    if arguments.trim().is_empty() {
+        return serde_json::json!({});
+    }
+    serde_json::from_str(arguments).unwrap_or_else(|e| {
+        warn!(tool = %name, error = %e, "Tool call arguments are not valid JSON");
+        serde_json::Value::Null
+    })
+}
+
+/// Data of one server-sent event of a streamed completion
+#[derive(Debug, Deserialize)]
+struct StreamChunk {
+    #[serde(default)]
+    choices: Vec<StreamChoice>,
+    #[serde(default)]
+    error: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamChoice {
+    #[serde(default)]
+    delta: Delta,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Delta {
+    #[serde(default)]
+    content: Option<String>,
+    #[serde(default)]
+    tool_calls: Vec<ToolCallDelta>,
+}
+
+/// Piece of a streamed tool call. The name comes first, then the arguments are
+/// spread over the following pieces with the same index.
+#[derive(Debug, PartialEq, Eq, Deserialize)]
+struct ToolCallDelta {
+    #[serde(default)]
+    index: usize,
+    #[serde(default)]
+    function: FunctionCallDelta,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Deserialize)]
+struct FunctionCallDelta {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    arguments: Option<String>,
+}
+
+/// What a streamed completion sent
+#[derive(Debug, PartialEq, Eq)]
+enum StreamEvent {
+    Content(String),
+    ToolCall(ToolCallDelta),
+    Error(String),
+    Done,
+}
+
+/// Tool calls of a streamed completion, assembled from their pieces
+#[derive(Debug, Default)]
+struct ToolCallBuffer {
+    calls: Vec<(String, String)>,
+}
+
+impl ToolCallBuffer {
+    fn push(&mut self, delta: ToolCallDelta) {
+        if self.calls.len() <= delta.index {
+            self.calls.resize(delta.index + 1, Default::default());
+        }
+        let (name, arguments) = &mut self.calls[delta.index];
+        if let Some(piece) = delta.function.name {
+            name.push_str(&piece);
+        }
+        if let Some(piece) = delta.function.arguments {
+            arguments.push_str(&piece);
+        }
+    }
+
+    fn finish(self) -> Vec<LlmToolCall> {
+        self.calls
+            .into_iter()
+            .filter(|(name, _)| !name.is_empty())
+            .map(|(name, arguments)| LlmToolCall {
+                arguments: parse_arguments(&name, &arguments),
+                name,
+            })
+            .collect()
+    }
+}
+
+/// Splits the server-sent events of a streamed completion, buffering lines that
+/// arrive split across network reads
+#[derive(Debug, Default)]
+struct SseDecoder {
+    buffer: Vec<u8>,
+}
+
+impl SseDecoder {
+    fn push(&mut self, bytes: &[u8]) -> Result<Vec<StreamEvent>> {
+        self.buffer.extend_from_slice(bytes);
+
+        let mut events = Vec::new();
+        while let Some(end) = self.buffer.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=end).collect();
+            // Comments, event names and blank separators carry no content
+            let Some(data) = line.trim_ascii().strip_prefix(b"data:") else {
+                continue;
+            };
+            let data = data.trim_ascii();
+            if data == b"[DONE]" {
+                events.push(StreamEvent::Done);
+                continue;
+            }
+
+            let chunk = serde_json::from_slice::<StreamChunk>(data).map_err(|e| {
+                AppError::Internal(format!("Failed to parse LLM stream chunk: {}", e))
+            })?;
+            if let Some(error) = chunk.error {
+                events.push(StreamEvent::Error(error.to_string()));
+            }
+            for choice in chunk.choices {
+                if let Some(content) = choice.delta.content.filter(|c| !c.is_empty()) {
+                    events.push(StreamEvent::Content(content));
+                }
+                events.extend(
+                    choice
+                        .delta
+                        .tool_calls
+                        .into_iter()
+                        .map(StreamEvent::ToolCall),
+                );
+            }
+        }
+
+        Ok(events)
+    }
+}
+
+impl OpenAiClient {
+    pub fn new(base_url: String, api_key: Option<String>, model: String) -> Self {
+        let base_url = base_url.trim_end_matches('/').to_string();
+
+        info!(
+            base_url = %base_url,
+            model = %model,
+            "Initializing OpenAI-compatible client"
+        );
+
+        Self {
+            client: reqwest::Client::new(),
+            base_url,
+            api_key,
+            model,
+        }
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Send a chat request and return the response once its status is known to be a success
+    async fn send_chat(
+        &self,
+        messages: Vec<LlmMessage>,
+        tools: Vec<LlmTool>,
+        options: LlmOptions,
+        stream: bool,
+    ) -> Result<reqwest::Response> {
+        let request = ChatCompletionRequest {
+            model: options.model.as_deref().unwrap_or(&self.model),
+            messages,
+            tools: tools.into_iter().map(FunctionTool::from).collect(),
+            temperature: options.temperature,
+            stream,
+        };
+
+        let url = format!("{}/chat/completions", self.base_url);
+
+        debug!(url = %url, model = %request.model, stream, "Sending chat completion request");
+
+        let mut builder = self.client.post(&url).json(&request);
+        if let Some(api_key) = &self.api_key {
+            builder = builder.bearer_auth(api_key);
+        }
+        let response = builder.send().await.map_err(|e| {
+            error!(error = %e, "LLM request failed");
+            AppError::Internal(format!("LLM request failed: {}", e))
+        })?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            error!(status = %status, body = %body, "LLM API returned error");
+            return Err(AppError::Internal(format!(
+                "LLM API returned error {}: {}",
+                status, body
+            )));
+        }
+
+        Ok(response)
+    }
+
+    #[instrument(skip(self, messages, tools, options), fields(model = %self.model, message_count = messages.len()))]
+    async fn complete(
+        &self,
+        messages: Vec<LlmMessage>,
+        tools: Vec<LlmTool>,
+        options: LlmOptions,
+    ) -> Result<LlmReply> {
+        let start = std::time::Instant::now();
+        let response = self.send_chat(messages, tools, options, false).await?;
+
+        let completion = response
+            .json::<ChatCompletionResponse>()
+            .await
+            .map_err(|e| {
+                error!(error = %e, "Failed to parse LLM response");
+                AppError::Internal(format!("Failed to parse LLM response: {}", e))
+            })?;
+        let message = completion
+            .choices
+            .into_iter()
+            .next()
+            .map(|c| c.message)
+            .ok_or_else(|| AppError::Internal("LLM response has no choices".to_string()))?;
+        let reply = LlmReply {
+            content: message.content.unwrap_or_default(),
+            tool_calls: message
+                .tool_calls
+                .into_iter()
+                .map(LlmToolCall::from)
+                .collect(),
+        };
+
+        info!(
+            elapsed_ms = start.elapsed().as_millis(),
+            response_length = reply.content.len(),
+            tool_calls = reply.tool_calls.len(),
+            "LLM chat completed"
+        );
+
+        debug!(response = %reply.content, "LLM response content");
+
+        Ok(reply)
+    }
+
+    #[instrument(skip(self, messages, tools, options, tokens), fields(model = %self.model, message_count = messages.len()))]
+    async fn complete_stream(
+        &self,
+        messages: Vec<LlmMessage>,
+        tools: Vec<LlmTool>,
+        options: LlmOptions,
+        tokens: mpsc::Sender<String>,
+    ) -> Result<LlmReply> {
+        let start = std::time::Instant::now();
+        let mut response = self.send_chat(messages, tools, options, true).await?;
+
+        let mut decoder = SseDecoder::default();
+        let mut content = String::new();
+        let mut tool_calls = ToolCallBuffer::default();
+        'stream: while let Some(bytes) = response.chunk().await.map_err(|e| {
+            error!(error = %e, "LLM stream interrupted");
+            AppError::Internal(format!("LLM stream interrupted: {}", e))
+        })? {
+            for event in decoder.push(&bytes)? {
+                match event {
+                    StreamEvent::Content(text) => {
+                        content.push_str(&text);
+                        // A closed receiver only means nobody is listening any more
+                        let _ = tokens.send(text).await;
+                    }
+                    StreamEvent::ToolCall(delta) => tool_calls.push(delta),
+                    StreamEvent::Error(error) => {
+                        error!(error = %error, "LLM stream returned error");
+                        return Err(AppError::Internal(format!(
+                            "LLM API returned error: {}",
+                            error
+                        )));
+                    }
+                    StreamEvent::Done => break 'stream,
+                }
+            }
+        }
+
+        let reply = LlmReply {
+            content,
+            tool_calls: tool_calls.finish(),
+        };
+
+        info!(
+            elapsed_ms = start.elapsed().as_millis(),
+            response_length = reply.content.len(),
+            tool_calls = reply.tool_calls.len(),
+            "LLM chat stream completed"
+        );
+
+        debug!(response = %reply.content, "LLM response content");
+
+        Ok(reply)
+    }
+}
+
+impl LlmProvider for OpenAiClient {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn chat(
+        &self,
+        messages: Vec<LlmMessage>,
+        tools: Vec<LlmTool>,
+        options: LlmOptions,
+    ) -> LlmFuture<'_, LlmReply> {
+        Box::pin(self.complete(messages, tools, options))
+    }
+
+    fn chat_stream(
+        &self,
+        messages: Vec<LlmMessage>,
+        tools: Vec<LlmTool>,
+        options: LlmOptions,
+        tokens: mpsc::Sender<String>,
+    ) -> LlmFuture<'_, LlmReply> {
+        Box::pin(self.complete_stream(messages, tools, options, tokens))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decoder_joins_events_split_across_reads() {
+        let mut decoder = SseDecoder::default();
+
+        let first = decoder
+            .push(b": keep-alive\n\ndata: {\"choices\":[{\"delta\":{\"role\":\"assistant\",\"content\":\"Hel\"}}]}\n\ndata: {\"choi")
+            .unwrap();
+        assert_eq!(first, vec![StreamEvent::Content("Hel".to_string())]);
+
+        let second = decoder
+            .push(b"ces\":[{\"delta\":{\"content\":\"lo\"}}]}\n\ndata: {\"choices\":[{\"delta\":{},\"finish_reason\":\"stop\"}]}\n\ndata: [DONE]\n\n")
+            .unwrap();
+        assert_eq!(
+            second,
+            vec![StreamEvent::Content("lo".to_string()), StreamEvent::Done]
+        );
+    }
+
+    #[test]
+    fn test_decoder_reports_errors() {
+        let mut decoder = SseDecoder::default();
+        let events = decoder
+            .push(b"data: {\"error\":{\"message\":\"rate limited\"}}\n")
+            .unwrap();
+        assert!(matches!(&events[..], [StreamEvent::Error(e)] if e.contains("rate limited")));
+        assert!(decoder.push(b"data: not json\n").is_err());
+    }
+
+    #[test]
+    fn test_tool_calls_assembled_from_stream_pieces() {
+        let mut decoder = SseDecoder::default();
+        let events = decoder
+            .push(concat!(
+                "data: {\"choices\":[{\"delta\":{\"tool_calls\":[{\"index\":0,\"id\":\"call_1\",\"type\":\"function\",\"function\":{\"name\":\"create_tag\",\"arguments\":\"\"}}]}}]}\n\n",
+                "data: {\"choices\":[{\"delta\":{\"tool_calls\":[{\"index\":0,\"function\":{\"arguments\":\"{\\\"name\\\": \"}}]}}]}\n\n",
+                "data: {\"choices\":[{\"delta\":{\"tool_calls\":[{\"index\":0,\"function\":{\"arguments\":\"\\\"urgent\\\"}\"}}]}}]}\n\n",
+                "data: {\"choices\":[{\"delta\":{\"tool_calls\":[{\"index\":1,\"function\":{\"name\":\"undo\"}}]}}]}\n\n",
+            ).as_bytes())
+            .unwrap();
+
+        let mut buffer = ToolCallBuffer::default();
+        for event in events {
+            match event {
+                StreamEvent::ToolCall(delta) => buffer.push(delta),
+                other => panic!("unexpected event {:?}", other),
+            }
+        }
+        assert_eq!(
+            buffer.finish(),
+            vec![
+                LlmToolCall {
+                    name: "create_tag".to_string(),
+                    arguments: serde_json::json!({"name": "urgent"}),
+                },
+                LlmToolCall {
+                    name: "undo".to_string(),
+                    arguments: serde_json::json!({}),
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,120 @@
+use axum::{
+    http::{header, HeaderValue, Uri},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+
+use crate::models::Page;
+
+/// One page of a list. The body is the JSON array of its items; the `X-Total-Count`
+/// header gives the length of the whole list and the `Link` header the addresses of
+/// the first, previous, next and last pages.
+pub struct Paginated<T> {
+    pub items: Vec<T>,
+    pub total: i64,
+    pub page: Page,
+    /// Address the page was requested at, whose other query parameters the links keep
+    pub uri: Uri,
+}
+
+impl<T: Serialize> IntoResponse for Paginated<T> {
+    fn into_response(self) -> Response {
+        let link = link_header(&self.uri, self.page, self.total);
+        let mut response = Json(self.items).into_response();
+        let headers = response.headers_mut();
+        headers.insert("x-total-count", HeaderValue::from(self.total));
+        if let Some(link) = link.and_then(|l| HeaderValue::from_str(&l).ok()) {
+            headers.insert(header::LINK, link);
+        }
+        response
+    }
+}
+
+/// Address of the page starting at `offset`, keeping the other query parameters
+fn page_url(uri: &Uri, limit: i64, offset: i64) -> String {
+    let mut params: Vec<&str> = uri
+        .query()
+        .unwrap_or_default()
+        .split('&')
+        .filter(|pair| {
+            let key = pair.split('=').next().unwrap_or_default();
+            !pair.is_empty() && key != "limit" && key != "offset"
+        })
+        .collect();
+    let paging = format!("limit={}&offset={}", limit, offset);
+    params.push(&paging);
+    format!("{}?{}", uri.path(), params.join("&"))
+}
+
+/// `Link` header value (RFC 8288) pointing to the neighbouring pages, None when the
+/// whole list fits on the page
+pub fn link_header(uri: &Uri, page: Page, total: i64) -> Option<String> {
+    if page.offset == 0 && page.limit >= total {
+        return None;
+    }
+
+    let last = (total - 1).max(0) / page.limit * page.limit;
+    let mut links = vec![(0, "first")];
+    if page.offset > 0 {
+        links.push(((page.offset - page.limit).clamp(0, last), "prev"));
+    }
+    if page.offset + page.limit < total {
+        links.push((page.offset + page.limit, "next"));
+    }
+    links.push((last, "last"));
+
+    Some(
+        links
+            .into_iter()
+            .map(|(offset, rel)| {
+                format!("<{}>; rel=\"{}\"", page_url(uri, page.limit, offset), rel)
+            })
+            .collect::<Vec<_>>()
+            .join(", "),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(limit: i64, offset: i64) -> Page {
+        Page { limit, offset }
+    }
+
+    #[test]
+    fn test_link_header() {
+        let uri: Uri = "/api/cards?status=open&limit=10&offset=10".parse().unwrap();
+        assert_eq!(
+            link_header(&uri, page(10, 10), 35).unwrap(),
+            concat!(
+                "</api/cards?status=open&limit=10&offset=0>; rel=\"first\", ",
+                "</api/cards?status=open&limit=10&offset=0>; rel=\"prev\", ",
+                "</api/cards?status=open&limit=10&offset=20>; rel=\"next\", ",
+                "</api/cards?status=open&limit=10&offset=30>; rel=\"last\""
+            )
+        );
+
+        let uri: Uri = "/api/boards".parse().unwrap();
+        assert_eq!(
+            link_header(&uri, page(2, 0), 4).unwrap(),
+            concat!(
+                "</api/boards?limit=2&offset=0>; rel=\"first\", ",
+                "</api/boards?limit=2&offset=2>; rel=\"next\", ",
+                "</api/boards?limit=2&offset=2>; rel=\"last\""
+            )
+        );
+    }
+
+    #[test]
+    fn test_link_header_single_page() {
+        let uri: Uri = "/api/boards".parse().unwrap();
+        assert_eq!(link_header(&uri, page(100, 0), 3), None);
+        assert_eq!(link_header(&uri, page(100, 0), 0), None);
+        // Past the end, the previous page is the last one
+        assert!(link_header(&uri, page(10, 50), 3)
+            .unwrap()
+            .contains("offset=0>; rel=\"prev\""));
+    }
+}
@@ -0,0 +1,212 @@
+use chrono::NaiveDate;
+use std::collections::HashSet;
+use uuid::Uuid;
+
+use crate::models::{Card, CardStatus, PlanningState, PlanningStep, PlanningTurn};
+
+/// Maximum number of cards in the weekly focus list
+pub const MAX_FOCUS_CARDS: usize = 5;
+
+/// Maximum number of unscheduled cards walked through in one session
+pub const MAX_UNSCHEDULED_CARDS: usize = 20;
+
+fn is_active(card: &Card) -> bool {
+    let status: CardStatus = card.status.parse().unwrap_or_default();
+    matches!(status, CardStatus::Open | CardStatus::InProgress) && !card.is_locked()
+}
+
+/// Collect the overdue and unscheduled cards a planning session walks through
+pub fn initial_state(cards: &[Card], today: NaiveDate) -> PlanningState {
+    let overdue = cards
+        .iter()
+        .filter(|c| is_active(c) && c.due_date.is_some_and(|d| d < today))
+        .map(|c| c.id)
+        .collect();
+    let unscheduled = cards
+        .iter()
+        .filter(|c| is_active(c) && c.due_date.is_none())
+        .take(MAX_UNSCHEDULED_CARDS)
+        .map(|c| c.id)
+        .collect();
+
+    PlanningState {
+        overdue,
+        unscheduled,
+        ..Default::default()
+    }
+}
+
+/// Cards that can be picked as focus, soonest (planned) due date first
+pub fn focus_candidates<'a>(cards: &'a [Card], state: &PlanningState) -> Vec<&'a Card> {
+    let mut candidates: Vec<&Card> = cards.iter().filter(|c| is_active(c)).collect();
+    candidates.sort_by_key(|c| {
+        let due = state.due_dates.get(&c.id).copied().unwrap_or(c.due_date);
+        (due.is_none(), due)
+    });
+    candidates
+}
+
+/// The step after `step`, skipping steps with nothing to discuss
+pub fn next_step(step: PlanningStep, state: &PlanningState) -> PlanningStep {
+    let next = match step {
+        PlanningStep::Overdue => PlanningStep::Unscheduled,
+        PlanningStep::Unscheduled => PlanningStep::Focus,
+        PlanningStep::Focus => PlanningStep::Review,
+        PlanningStep::Review | PlanningStep::Done => PlanningStep::Done,
+    };
+
+    match next {
+        PlanningStep::Unscheduled if state.unscheduled.is_empty() => next_step(next, state),
+        _ => next,
+    }
+}
+
+/// The step a new session starts at
+pub fn first_step(state: &PlanningState) -> PlanningStep {
+    if state.overdue.is_empty() {
+        next_step(PlanningStep::Overdue, state)
+    } else {
+        PlanningStep::Overdue
+    }
+}
+
+/// What the assistant asks at each step
+pub fn prompt(step: PlanningStep, items: usize) -> String {
+    match step {
+        PlanningStep::Overdue => format!(
+            "{} card(s) are overdue. Give each a new due date, or null to drop the due date. Cards you leave out keep their current date.",
+            items
+        ),
+        PlanningStep::Unscheduled => format!(
+            "{} open card(s) have no due date. Schedule the ones you want to tackle soon.",
+            items
+        ),
+        PlanningStep::Focus => format!(
+            "Pick up to {} cards to focus on this week.",
+            MAX_FOCUS_CARDS
+        ),
+        PlanningStep::Review => {
+            "Here is your plan. Confirm to apply the due dates and start the focus cards, or decline to discard it.".to_string()
+        }
+        PlanningStep::Done => "This planning session is finished.".to_string(),
+    }
+}
+
+/// Record the answer to a due date step, rejecting cards that are not part of it
+pub fn apply_due_dates(
+    state: &mut PlanningState,
+    allowed: &[Uuid],
+    turn: &PlanningTurn,
+) -> Result<(), String> {
+    if let Some(id) = turn.due_dates.keys().find(|id| !allowed.contains(id)) {
+        return Err(format!("Card {} is not part of this step", id));
+    }
+    state
+        .due_dates
+        .extend(turn.due_dates.iter().map(|(id, date)| (*id, *date)));
+    Ok(())
+}
+
+/// Record the focus list, rejecting unknown cards and lists that are too long
+pub fn apply_focus(
+    state: &mut PlanningState,
+    candidates: &[Uuid],
+    turn: &PlanningTurn,
+) -> Result<(), String> {
+    let mut seen = HashSet::new();
+    let focus: Vec<Uuid> = turn
+        .focus
+        .iter()
+        .copied()
+        .filter(|id| seen.insert(*id))
+        .collect();
+
+    if focus.len() > MAX_FOCUS_CARDS {
+        return Err(format!("Pick at most {} focus cards", MAX_FOCUS_CARDS));
+    }
+    if let Some(id) = focus.iter().find(|id| !candidates.contains(id)) {
+        return Err(format!("Card {} cannot be a focus card", id));
+    }
+
+    state.focus = focus;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn card(title: &str, status: &str, due_date: Option<NaiveDate>) -> Card {
+        let user = Uuid::new_v4();
+        Card {
+            id: Uuid::new_v4(),
+            column_id: None,
+            title: title.to_string(),
+            body: None,
+            position: 0,
+            visibility: "private".to_string(),
+            status: status.to_string(),
+            start_date: None,
+            end_date: None,
+            due_date,
+            owner_id: Some(user),
+            created_by: user,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            locked_at: None,
+            locked_by: None,
+            archived_at: None,
+            priority: None,
+            version: 1,
+            cover_color: None,
+            cover_attachment_id: None,
+        }
+    }
+
+    #[test]
+    fn test_initial_state_and_steps() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 11).unwrap();
+        let cards = vec![
+            card("late", "open", NaiveDate::from_ymd_opt(2024, 3, 1)),
+            card("later", "open", NaiveDate::from_ymd_opt(2024, 3, 20)),
+            card("done late", "done", NaiveDate::from_ymd_opt(2024, 3, 1)),
+        ];
+
+        let state = initial_state(&cards, today);
+        assert_eq!(state.overdue, vec![cards[0].id]);
+        assert!(state.unscheduled.is_empty());
+
+        assert_eq!(first_step(&state), PlanningStep::Overdue);
+        assert_eq!(
+            next_step(PlanningStep::Overdue, &state),
+            PlanningStep::Focus
+        );
+        assert_eq!(first_step(&PlanningState::default()), PlanningStep::Focus);
+    }
+
+    #[test]
+    fn test_apply_focus_validates_cards() {
+        let mut state = PlanningState::default();
+        let allowed: Vec<Uuid> = (0..7).map(|_| Uuid::new_v4()).collect();
+
+        let too_many = PlanningTurn {
+            focus: allowed.clone(),
+            ..Default::default()
+        };
+        assert!(apply_focus(&mut state, &allowed, &too_many).is_err());
+
+        let unknown = PlanningTurn {
+            focus: vec![Uuid::new_v4()],
+            ..Default::default()
+        };
+        assert!(apply_focus(&mut state, &allowed, &unknown).is_err());
+
+        let ok = PlanningTurn {
+            focus: vec![allowed[0], allowed[0], allowed[1]],
+            ..Default::default()
+        };
+        apply_focus(&mut state, &allowed, &ok).unwrap();
+        assert_eq!(state.focus, vec![allowed[0], allowed[1]]);
+    }
+}
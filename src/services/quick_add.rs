@@ -0,0 +1,357 @@
+use chrono::{Datelike, Duration, Months, NaiveDate, Weekday};
+
+use crate::models::CardPriority;
+
+/// A card described by a single line of text
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct QuickAdd {
+    pub title: String,
+    pub due_date: Option<NaiveDate>,
+    pub priority: Option<CardPriority>,
+    pub tags: Vec<String>,
+    /// Board written after `@`
+    pub board: Option<String>,
+}
+
+/// Words that may introduce a date, dropped along with it
+const DATE_PREFIXES: &[&str] = &["due", "on", "by"];
+
+/// Parse a quick-add line such as `Pay rent tomorrow #finance !high @Home board`.
+///
+/// - `#tag` adds a tag
+/// - `!low`, `!medium`, `!high` or `!urgent` sets the priority
+/// - `@Board` puts the card on a board. The longest run of words naming one of
+///   `board_names` is taken, so `@Side project` works; a `board` right after it is
+///   dropped.
+/// - The first date sets the due date: `today`, `tonight`, `tomorrow`, a weekday
+///   (`friday` or `next friday`: the next one after today), `next week` (its
+///   Monday), `next month` (its first day), `in 3 days`, `in 2 weeks`, `in a month`,
+///   `may 3`, `3rd may`, `may 3 2025` or `2025-05-03`, optionally after `due`, `on`
+///   or `by`. Dates without a year are the next ones to come.
+///
+/// What remains is the title.
+pub fn parse(text: &str, today: NaiveDate, board_names: &[&str]) -> QuickAdd {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let mut parsed = QuickAdd::default();
+    let mut title: Vec<&str> = Vec::new();
+
+    let mut i = 0;
+    while i < words.len() {
+        let word = words[i];
+
+        if let Some(tag) = word.strip_prefix('#').filter(|t| !t.is_empty()) {
+            if !parsed.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                parsed.tags.push(tag.to_string());
+            }
+            i += 1;
+            continue;
+        }
+
+        if let Some(priority) = word
+            .strip_prefix('!')
+            .and_then(|p| p.parse::<CardPriority>().ok())
+        {
+            parsed.priority = Some(priority);
+            i += 1;
+            continue;
+        }
+
+        if parsed.board.is_none() && word.len() > 1 && word.starts_with('@') {
+            let (board, used) = board_at(&words[i..], board_names);
+            parsed.board = Some(board);
+            i += used;
+            if words
+                .get(i)
+                .is_some_and(|w| w.eq_ignore_ascii_case("board"))
+            {
+                i += 1;
+            }
+            continue;
+        }
+
+        if parsed.due_date.is_none() {
+            if let Some((date, used)) = date_with_prefix(&words[i..], today) {
+                parsed.due_date = Some(date);
+                i += used;
+                continue;
+            }
+        }
+
+        title.push(word);
+        i += 1;
+    }
+
+    parsed.title = title.join(" ");
+    parsed
+}
+
+/// The board named by the words starting with `@`, and how many words it took
+fn board_at(words: &[&str], board_names: &[&str]) -> (String, usize) {
+    let first = &words[0][1..];
+    for len in (1..=words.len()).rev() {
+        let candidate = std::iter::once(first)
+            .chain(words[1..len].iter().copied())
+            .collect::<Vec<_>>()
+            .join(" ");
+        if board_names
+            .iter()
+            .any(|name| name.eq_ignore_ascii_case(&candidate))
+        {
+            return (candidate, len);
+        }
+    }
+    (first.to_string(), 1)
+}
+
+fn date_with_prefix(words: &[&str], today: NaiveDate) -> Option<(NaiveDate, usize)> {
+    if DATE_PREFIXES.contains(&normalize(words[0]).as_str()) && words.len() > 1 {
+        if let Some((date, used)) = date_at(&words[1..], today) {
+            return Some((date, used + 1));
+        }
+    }
+    date_at(words, today)
+}
+
+/// Lowercase word without the punctuation that may follow it in a sentence
+fn normalize(word: &str) -> String {
+    word.trim_end_matches([',', '.', ';', ':'])
+        .to_ascii_lowercase()
+}
+
+/// The date the words start with, and how many words it takes
+fn date_at(words: &[&str], today: NaiveDate) -> Option<(NaiveDate, usize)> {
+    let first = normalize(words[0]);
+    let second = words.get(1).map(|w| normalize(w));
+    let third = words.get(2).map(|w| normalize(w));
+
+    match first.as_str() {
+        "today" | "tonight" => return Some((today, 1)),
+        "tomorrow" => return Some((today + Duration::days(1), 1)),
+        _ => {}
+    }
+
+    if let Some(weekday) = weekday(&first) {
+        return Some((next_weekday(today, weekday), 1));
+    }
+
+    if first == "next" || first == "this" {
+        match second.as_deref() {
+            Some("week") if first == "next" => {
+                let monday = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+                return Some((monday + Duration::days(7), 2));
+            }
+            Some("month") if first == "next" => {
+                let first_day = today.with_day(1)?;
+                return Some((first_day.checked_add_months(Months::new(1))?, 2));
+            }
+            Some(word) => {
+                if let Some(weekday) = weekday(word) {
+                    return Some((next_weekday(today, weekday), 2));
+                }
+            }
+            None => {}
+        }
+        return None;
+    }
+
+    if first == "in" {
+        let count: u32 = match second.as_deref() {
+            Some("a" | "an" | "one") => 1,
+            Some(number) => number.parse().ok()?,
+            None => return None,
+        };
+        let date = match third.as_deref()? {
+            "day" | "days" => today.checked_add_signed(Duration::days(count.into()))?,
+            "week" | "weeks" => today.checked_add_signed(Duration::weeks(count.into()))?,
+            "month" | "months" => today.checked_add_months(Months::new(count))?,
+            _ => return None,
+        };
+        return Some((date, 3));
+    }
+
+    if let Ok(date) = NaiveDate::parse_from_str(&first, "%Y-%m-%d") {
+        return Some((date, 1));
+    }
+
+    // "may 3" or "3 may", with an optional year
+    let (month, day) = match (month(&first), second.as_deref().and_then(day)) {
+        (Some(month), Some(day)) => (month, day),
+        _ => match (day(&first), second.as_deref().and_then(month)) {
+            (Some(day), Some(month)) => (month, day),
+            _ => return None,
+        },
+    };
+    if let Some(year) = third.as_deref().and_then(year) {
+        return NaiveDate::from_ymd_opt(year, month, day).map(|date| (date, 3));
+    }
+    let this_year = NaiveDate::from_ymd_opt(today.year(), month, day);
+    match this_year {
+        Some(date) if date >= today => Some((date, 2)),
+        _ => NaiveDate::from_ymd_opt(today.year() + 1, month, day).map(|date| (date, 2)),
+    }
+}
+
+/// The first `weekday` after `today`
+fn next_weekday(today: NaiveDate, weekday: Weekday) -> NaiveDate {
+    let ahead = (weekday.num_days_from_monday() as i64
+        - today.weekday().num_days_from_monday() as i64)
+        .rem_euclid(7);
+    today + Duration::days(if ahead == 0 { 7 } else { ahead })
+}
+
+fn weekday(word: &str) -> Option<Weekday> {
+    Some(match word {
+        "monday" => Weekday::Mon,
+        "tuesday" => Weekday::Tue,
+        "wednesday" => Weekday::Wed,
+        "thursday" => Weekday::Thu,
+        "friday" => Weekday::Fri,
+        "saturday" => Weekday::Sat,
+        "sunday" => Weekday::Sun,
+        _ => return None,
+    })
+}
+
+fn month(word: &str) -> Option<u32> {
+    const MONTHS: [&str; 12] = [
+        "january",
+        "february",
+        "march",
+        "april",
+        "may",
+        "june",
+        "july",
+        "august",
+        "september",
+        "october",
+        "november",
+        "december",
+    ];
+    if word.len() < 3 {
+        return None;
+    }
+    let word = if word == "sept" { "sep" } else { word };
+    MONTHS
+        .iter()
+        .position(|month| *month == word || (word.len() == 3 && month.starts_with(word)))
+        .map(|index| index as u32 + 1)
+}
+
+/// Day of the month, as `3` or `3rd`
+fn day(word: &str) -> Option<u32> {
+    let digits = word
+        .strip_suffix("st")
+        .or_else(|| word.strip_suffix("nd"))
+        .or_else(|| word.strip_suffix("rd"))
+        .or_else(|| word.strip_suffix("th"))
+        .unwrap_or(word);
+    digits.parse().ok().filter(|day| (1..=31).contains(day))
+}
+
+fn year(word: &str) -> Option<i32> {
+    (word.len() == 4).then(|| word.parse().ok()).flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A Wednesday
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 5, 15).unwrap()
+    }
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_parse_extracts_tags_priority_board_and_date() {
+        let parsed = parse(
+            "Pay rent tomorrow #finance !high @Home board",
+            today(),
+            &["Home", "Work"],
+        );
+
+        assert_eq!(
+            parsed,
+            QuickAdd {
+                title: "Pay rent".to_string(),
+                due_date: Some(date(5, 16)),
+                priority: Some(CardPriority::High),
+                tags: vec!["finance".to_string()],
+                board: Some("Home".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_matches_board_names_of_several_words() {
+        let parsed = parse(
+            "Write landing page @side project by friday",
+            today(),
+            &["Side", "Side project"],
+        );
+        assert_eq!(parsed.board.as_deref(), Some("side project"));
+        assert_eq!(parsed.title, "Write landing page");
+        assert_eq!(parsed.due_date, Some(date(5, 17)));
+
+        // Unknown boards keep the first word, for the caller to report
+        let parsed = parse("Call @Garden shed", today(), &["Home"]);
+        assert_eq!(parsed.board.as_deref(), Some("Garden"));
+        assert_eq!(parsed.title, "Call shed");
+    }
+
+    #[test]
+    fn test_parse_relative_dates() {
+        let due = |text: &str| parse(text, today(), &[]).due_date;
+
+        assert_eq!(due("Report today"), Some(today()));
+        assert_eq!(due("Report wednesday"), Some(date(5, 22)));
+        assert_eq!(due("Report on Monday"), Some(date(5, 20)));
+        assert_eq!(due("Report next friday"), Some(date(5, 17)));
+        assert_eq!(due("Report next week"), Some(date(5, 20)));
+        assert_eq!(due("Report next month"), Some(date(6, 1)));
+        assert_eq!(due("Report in 3 days"), Some(date(5, 18)));
+        assert_eq!(due("Report in a week"), Some(date(5, 22)));
+        assert_eq!(due("Report in 2 months"), Some(date(7, 15)));
+    }
+
+    #[test]
+    fn test_parse_calendar_dates() {
+        let due = |text: &str| parse(text, today(), &[]).due_date;
+
+        assert_eq!(due("Taxes due 2024-06-30"), Some(date(6, 30)));
+        assert_eq!(due("Taxes by June 3rd"), Some(date(6, 3)));
+        assert_eq!(due("Taxes 3 jun"), Some(date(6, 3)));
+        assert_eq!(
+            due("Taxes apr 1"),
+            NaiveDate::from_ymd_opt(2025, 4, 1),
+            "past dates roll over to next year"
+        );
+        assert_eq!(
+            due("Taxes dec 24 2026"),
+            NaiveDate::from_ymd_opt(2026, 12, 24)
+        );
+    }
+
+    #[test]
+    fn test_parse_leaves_other_words_in_the_title() {
+        let parsed = parse(
+            "Buy sun cream on the train ! #  !important in time @ mail@example.com",
+            today(),
+            &[],
+        );
+        assert_eq!(
+            parsed.title,
+            "Buy sun cream on the train ! # !important in time @ mail@example.com"
+        );
+        assert_eq!(parsed.due_date, None);
+        assert_eq!(parsed.board, None);
+
+        // Only the first date is taken
+        let parsed = parse("Move meeting from monday to friday", today(), &[]);
+        assert_eq!(parsed.title, "Move meeting from to friday");
+        assert_eq!(parsed.due_date, Some(date(5, 20)));
+    }
+}
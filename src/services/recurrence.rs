@@ -0,0 +1,98 @@
+use chrono::{Days, Months, NaiveDate};
+
+use crate::models::RecurrenceFrequency;
+
+/// The `n`th occurrence of a rule, counting `starts_on` as occurrence 0.
+///
+/// Monthly occurrences are computed from the start date rather than from the
+/// previous occurrence, so a rule starting on the 31st falls on the last day of
+/// shorter months without drifting to an earlier day afterwards.
+pub fn occurrence(
+    frequency: RecurrenceFrequency,
+    interval: u32,
+    starts_on: NaiveDate,
+    n: u32,
+) -> Option<NaiveDate> {
+    let steps = interval.checked_mul(n)?;
+    match frequency {
+        RecurrenceFrequency::Daily => starts_on.checked_add_days(Days::new(steps.into())),
+        RecurrenceFrequency::Weekly => starts_on.checked_add_days(Days::new(u64::from(steps) * 7)),
+        RecurrenceFrequency::Monthly => starts_on.checked_add_months(Months::new(steps)),
+    }
+}
+
+/// First occurrence strictly after `after`, or `starts_on` if it is later
+pub fn next_occurrence(
+    frequency: RecurrenceFrequency,
+    interval: u32,
+    starts_on: NaiveDate,
+    after: NaiveDate,
+) -> Option<NaiveDate> {
+    if starts_on > after {
+        return Some(starts_on);
+    }
+
+    // Jump close to the answer, then step until past it
+    let days = (after - starts_on).num_days().max(0) as u32;
+    let period_days = match frequency {
+        RecurrenceFrequency::Daily => interval,
+        RecurrenceFrequency::Weekly => interval.checked_mul(7)?,
+        RecurrenceFrequency::Monthly => interval.checked_mul(31)?,
+    };
+    let mut n = days / period_days.max(1);
+    loop {
+        let date = occurrence(frequency, interval, starts_on, n)?;
+        if date > after {
+            return Some(date);
+        }
+        n = n.checked_add(1)?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_monthly_keeps_day_of_month() {
+        let start = date("2024-01-31");
+        let dates: Vec<NaiveDate> = (0..4)
+            .map(|n| occurrence(RecurrenceFrequency::Monthly, 1, start, n).unwrap())
+            .collect();
+        assert_eq!(
+            dates,
+            [
+                date("2024-01-31"),
+                date("2024-02-29"),
+                date("2024-03-31"),
+                date("2024-04-30")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_next_occurrence_skips_missed_runs() {
+        let start = date("2024-01-01");
+        assert_eq!(
+            next_occurrence(RecurrenceFrequency::Weekly, 2, start, date("2024-02-01")),
+            Some(date("2024-02-12"))
+        );
+        assert_eq!(
+            next_occurrence(RecurrenceFrequency::Daily, 1, start, date("2024-01-01")),
+            Some(date("2024-01-02"))
+        );
+        assert_eq!(
+            next_occurrence(RecurrenceFrequency::Monthly, 3, start, date("2024-05-15")),
+            Some(date("2024-07-01"))
+        );
+        // A start in the future is the next occurrence
+        assert_eq!(
+            next_occurrence(RecurrenceFrequency::Daily, 1, start, date("2023-12-01")),
+            Some(start)
+        );
+    }
+}
@@ -0,0 +1,60 @@
+use similar::TextDiff;
+
+use crate::models::{RevisionDiff, TitleChange};
+
+/// Lines of unchanged context around each change of a body diff
+const CONTEXT_LINES: usize = 3;
+
+/// What changed from one version of a card's title and body to the next. Bodies are
+/// compared line by line as a unified diff; a missing body counts as empty.
+pub fn diff(
+    title: &str,
+    body: Option<&str>,
+    next_title: &str,
+    next_body: Option<&str>,
+) -> RevisionDiff {
+    let title = (title != next_title).then(|| TitleChange {
+        from: title.to_string(),
+        to: next_title.to_string(),
+    });
+    let (body, next_body) = (body.unwrap_or_default(), next_body.unwrap_or_default());
+    let body = (body != next_body).then(|| {
+        TextDiff::from_lines(body, next_body)
+            .unified_diff()
+            .context_radius(CONTEXT_LINES)
+            .missing_newline_hint(false)
+            .to_string()
+    });
+
+    RevisionDiff { title, body }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_lists_changed_lines() {
+        let changes = diff(
+            "Plan",
+            Some("Goals\nShip v1\nParty"),
+            "Plan",
+            Some("Goals\nShip v2\nParty"),
+        );
+        assert_eq!(changes.title, None);
+        assert_eq!(
+            changes.body.as_deref(),
+            Some("@@ -1,3 +1,3 @@\n Goals\n-Ship v1\n+Ship v2\n Party\n")
+        );
+
+        let changes = diff("Plan", None, "Release plan", None);
+        assert_eq!(
+            changes.title,
+            Some(TitleChange {
+                from: "Plan".to_string(),
+                to: "Release plan".to_string()
+            })
+        );
+        assert_eq!(changes.body, None);
+    }
+}
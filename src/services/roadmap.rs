@@ -0,0 +1,165 @@
+use chrono::{Datelike, Months, NaiveDate};
+use std::collections::BTreeMap;
+
+use crate::models::{RoadmapBucket, RoadmapCard, RoadmapGrouping};
+
+/// First day of the month or quarter containing `date`
+pub fn bucket_start(date: NaiveDate, grouping: RoadmapGrouping) -> NaiveDate {
+    let month = match grouping {
+        RoadmapGrouping::Month => date.month(),
+        RoadmapGrouping::Quarter => (date.month0() / 3) * 3 + 1,
+    };
+    NaiveDate::from_ymd_opt(date.year(), month, 1).expect("first day of month is valid")
+}
+
+fn bucket(start: NaiveDate, grouping: RoadmapGrouping) -> RoadmapBucket {
+    let (key, label, months) = match grouping {
+        RoadmapGrouping::Month => (
+            start.format("%Y-%m").to_string(),
+            start.format("%B %Y").to_string(),
+            1,
+        ),
+        RoadmapGrouping::Quarter => {
+            let quarter = start.month0() / 3 + 1;
+            (
+                format!("{}-Q{}", start.year(), quarter),
+                format!("Q{} {}", quarter, start.year()),
+                3,
+            )
+        }
+    };
+    let end = start
+        .checked_add_months(Months::new(months))
+        .and_then(|d| d.pred_opt())
+        .expect("roadmap dates stay in range");
+
+    RoadmapBucket {
+        key: Some(key),
+        label,
+        start_date: Some(start),
+        end_date: Some(end),
+        cards: Vec::new(),
+        milestones: Vec::new(),
+    }
+}
+
+/// Group cards into chronological buckets by due date, followed by a bucket for
+/// undated cards. Milestones are overlaid on the bucket of their due date; undated
+/// milestones cannot be placed and are left out. Only non-empty buckets are returned.
+pub fn build(
+    cards: Vec<RoadmapCard>,
+    milestones: Vec<RoadmapCard>,
+    grouping: RoadmapGrouping,
+) -> Vec<RoadmapBucket> {
+    let mut dated: BTreeMap<NaiveDate, RoadmapBucket> = BTreeMap::new();
+    let mut undated = Vec::new();
+
+    for card in cards {
+        match card.due_date {
+            Some(due) => {
+                let start = bucket_start(due, grouping);
+                dated
+                    .entry(start)
+                    .or_insert_with(|| bucket(start, grouping))
+                    .cards
+                    .push(card);
+            }
+            None => undated.push(card),
+        }
+    }
+
+    for milestone in milestones {
+        if let Some(due) = milestone.due_date {
+            let start = bucket_start(due, grouping);
+            dated
+                .entry(start)
+                .or_insert_with(|| bucket(start, grouping))
+                .milestones
+                .push(milestone);
+        }
+    }
+
+    let mut buckets: Vec<RoadmapBucket> = dated
+        .into_values()
+        .map(|mut b| {
+            b.cards.sort_by_key(|c| c.due_date);
+            b.milestones.sort_by_key(|c| c.due_date);
+            b
+        })
+        .collect();
+
+    if !undated.is_empty() {
+        buckets.push(RoadmapBucket {
+            key: None,
+            label: "No due date".to_string(),
+            start_date: None,
+            end_date: None,
+            cards: undated,
+            milestones: Vec::new(),
+        });
+    }
+
+    buckets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn card(title: &str, due_date: Option<NaiveDate>) -> RoadmapCard {
+        RoadmapCard {
+            id: Uuid::new_v4(),
+            column_id: None,
+            title: title.to_string(),
+            status: "open".to_string(),
+            due_date,
+        }
+    }
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_bucket_start() {
+        assert_eq!(
+            bucket_start(date(2024, 2, 29), RoadmapGrouping::Month),
+            date(2024, 2, 1)
+        );
+        assert_eq!(
+            bucket_start(date(2024, 6, 30), RoadmapGrouping::Quarter),
+            date(2024, 4, 1)
+        );
+        assert_eq!(
+            bucket_start(date(2024, 12, 1), RoadmapGrouping::Quarter),
+            date(2024, 10, 1)
+        );
+    }
+
+    #[test]
+    fn test_build_groups_by_quarter() {
+        let cards = vec![
+            card("later", Some(date(2024, 5, 20))),
+            card("sooner", Some(date(2024, 1, 10))),
+            card("april", Some(date(2024, 4, 2))),
+            card("someday", None),
+        ];
+        let milestones = vec![
+            card("release", Some(date(2024, 6, 30))),
+            card("undated", None),
+        ];
+
+        let buckets = build(cards, milestones, RoadmapGrouping::Quarter);
+
+        assert_eq!(buckets.len(), 3);
+        assert_eq!(buckets[0].key.as_deref(), Some("2024-Q1"));
+        assert_eq!(buckets[0].end_date, Some(date(2024, 3, 31)));
+        assert_eq!(buckets[1].label, "Q2 2024");
+        assert_eq!(buckets[1].cards[0].title, "april");
+        assert_eq!(buckets[1].milestones[0].title, "release");
+        assert_eq!(buckets[2].key, None);
+        assert_eq!(buckets[2].cards[0].title, "someday");
+        assert!(buckets[2].milestones.is_empty());
+    }
+}
@@ -0,0 +1,248 @@
+use chrono::{DateTime, Datelike, Days, NaiveDate, Utc};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+use crate::models::{CardFlowEvent, WeeklyThroughput};
+
+/// When work on a card started and when it was last completed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CardFlow {
+    pub started_at: DateTime<Utc>,
+    pub completed_at: DateTime<Utc>,
+}
+
+/// What an activity entry did to a card
+enum Step {
+    Created,
+    Started,
+    Completed,
+    Other,
+}
+
+fn step(event: &CardFlowEvent, done_columns: &HashSet<Uuid>) -> Step {
+    let details: Value = event
+        .details
+        .as_deref()
+        .and_then(|d| serde_json::from_str(d).ok())
+        .unwrap_or(Value::Null);
+    let into_done = |column: &Value| {
+        column
+            .as_str()
+            .and_then(|c| c.parse().ok())
+            .is_some_and(|c| done_columns.contains(&c))
+    };
+
+    match event.event.as_str() {
+        "card_created" => Step::Created,
+        "card_moved" if into_done(&details["to_column_id"]) => Step::Completed,
+        "card_moved" => Step::Started,
+        "card_updated" => match details["status"]["to"].as_str() {
+            Some("done") => Step::Completed,
+            Some("in_progress") => Step::Started,
+            _ if into_done(&details["column_id"]["to"]) => Step::Completed,
+            _ if !details["column_id"].is_null() => Step::Started,
+            _ => Step::Other,
+        },
+        _ => Step::Other,
+    }
+}
+
+/// Flow of each completed card, from its activity in chronological order. A card is
+/// completed when moved into one of `done_columns` or set to done, and started at its
+/// first move or switch to in progress before that; a card completed without being
+/// started counts from its creation. Cards whose start is not in the log are left out.
+pub fn card_flows(events: &[CardFlowEvent], done_columns: &HashSet<Uuid>) -> Vec<CardFlow> {
+    #[derive(Default)]
+    struct Progress {
+        created_at: Option<DateTime<Utc>>,
+        started_at: Option<DateTime<Utc>>,
+        completed_at: Option<DateTime<Utc>>,
+    }
+
+    let mut cards: HashMap<Uuid, Progress> = HashMap::new();
+    for event in events {
+        let progress = cards.entry(event.card_id).or_default();
+        match step(event, done_columns) {
+            Step::Created => progress.created_at = Some(event.created_at),
+            Step::Started => {
+                progress.started_at.get_or_insert(event.created_at);
+            }
+            Step::Completed => progress.completed_at = Some(event.created_at),
+            Step::Other => {}
+        }
+    }
+
+    cards
+        .into_values()
+        .filter_map(|progress| {
+            let completed_at = progress.completed_at?;
+            let started_at = progress
+                .started_at
+                .filter(|started| *started <= completed_at)
+                .or(progress.created_at)?;
+            Some(CardFlow {
+                started_at,
+                completed_at,
+            })
+        })
+        .collect()
+}
+
+/// Monday of the week containing `date`
+pub fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Days::new(date.weekday().num_days_from_monday() as u64)
+}
+
+/// Cards completed in each of the last `weeks` weeks, the week of `today` included,
+/// oldest first
+pub fn throughput(flows: &[CardFlow], weeks: u32, today: NaiveDate) -> Vec<WeeklyThroughput> {
+    let current = week_start(today);
+    let mut buckets: Vec<WeeklyThroughput> = (0..weeks as u64)
+        .rev()
+        .map(|ago| WeeklyThroughput {
+            week_start: current - Days::new(ago * 7),
+            completed: 0,
+        })
+        .collect();
+
+    for flow in flows {
+        let week = week_start(flow.completed_at.date_naive());
+        if let Some(bucket) = buckets.iter_mut().find(|b| b.week_start == week) {
+            bucket.completed += 1;
+        }
+    }
+
+    buckets
+}
+
+/// Average days from start to completion of the cards completed since `since`, to
+/// one decimal
+pub fn average_cycle_time_days(flows: &[CardFlow], since: NaiveDate) -> Option<f64> {
+    let durations: Vec<f64> = flows
+        .iter()
+        .filter(|f| f.completed_at.date_naive() >= since)
+        .map(|f| (f.completed_at - f.started_at).num_seconds() as f64 / 86_400.0)
+        .collect();
+    if durations.is_empty() {
+        return None;
+    }
+
+    let average = durations.iter().sum::<f64>() / durations.len() as f64;
+    Some((average * 10.0).round() / 10.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    fn at(day: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 4, day, 12, 0, 0).unwrap()
+    }
+
+    fn event(card_id: Uuid, event: &str, details: Value, day: u32) -> CardFlowEvent {
+        CardFlowEvent {
+            card_id,
+            event: event.to_string(),
+            details: Some(details.to_string()),
+            created_at: at(day),
+        }
+    }
+
+    #[test]
+    fn test_card_flows() {
+        let (todo, doing, done) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let done_columns = HashSet::from([done]);
+        let (moved, updated, straight, open) = (
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+        );
+        let events = [
+            event(moved, "card_created", json!({"column_id": todo}), 1),
+            event(moved, "card_moved", json!({"to_column_id": doing}), 2),
+            event(moved, "card_moved", json!({"to_column_id": done}), 5),
+            event(updated, "card_created", json!({}), 1),
+            event(
+                updated,
+                "card_updated",
+                json!({"status": {"from": "open", "to": "in_progress"}}),
+                3,
+            ),
+            event(
+                updated,
+                "card_updated",
+                json!({"status": {"from": "in_progress", "to": "done"}}),
+                4,
+            ),
+            event(straight, "card_created", json!({}), 8),
+            event(straight, "card_moved", json!({"to_column_id": done}), 10),
+            event(open, "card_created", json!({}), 1),
+            event(open, "card_moved", json!({"to_column_id": doing}), 2),
+        ];
+
+        let mut flows = card_flows(&events, &done_columns);
+        flows.sort_by_key(|f| f.completed_at);
+        assert_eq!(
+            flows,
+            [
+                CardFlow {
+                    started_at: at(3),
+                    completed_at: at(4)
+                },
+                CardFlow {
+                    started_at: at(2),
+                    completed_at: at(5)
+                },
+                CardFlow {
+                    started_at: at(8),
+                    completed_at: at(10)
+                },
+            ]
+        );
+
+        assert_eq!(
+            average_cycle_time_days(&flows, at(1).date_naive()),
+            Some(2.0)
+        );
+        assert_eq!(
+            average_cycle_time_days(&flows, at(6).date_naive()),
+            Some(2.0)
+        );
+        assert_eq!(average_cycle_time_days(&flows, at(11).date_naive()), None);
+    }
+
+    #[test]
+    fn test_throughput() {
+        let flow = |day| CardFlow {
+            started_at: at(1),
+            completed_at: at(day),
+        };
+        // 2024-04-10 is a Wednesday; weeks start on April 1 and 8
+        let weeks = throughput(
+            &[flow(2), flow(5), flow(9), flow(20)],
+            3,
+            at(10).date_naive(),
+        );
+        assert_eq!(
+            weeks,
+            [
+                WeeklyThroughput {
+                    week_start: NaiveDate::from_ymd_opt(2024, 3, 25).unwrap(),
+                    completed: 0
+                },
+                WeeklyThroughput {
+                    week_start: NaiveDate::from_ymd_opt(2024, 4, 1).unwrap(),
+                    completed: 2
+                },
+                WeeklyThroughput {
+                    week_start: NaiveDate::from_ymd_opt(2024, 4, 8).unwrap(),
+                    completed: 1
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,184 @@
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::json;
+use std::time::Duration;
+use tracing::info;
+
+use crate::error::{AppError, Result};
+use crate::models::ChatResponse;
+
+/// Telegram Bot API endpoint
+const TELEGRAM_API_URL: &str = "https://api.telegram.org";
+
+/// Longest message Telegram accepts, in characters
+const MAX_MESSAGE_LENGTH: usize = 4096;
+
+/// An incoming update from `getUpdates`. Updates other than messages are skipped.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TelegramUpdate {
+    pub update_id: i64,
+    pub message: Option<TelegramMessage>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TelegramMessage {
+    pub chat: TelegramChat,
+    /// None for photos, stickers and other messages without text
+    pub text: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TelegramChat {
+    pub id: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiResponse<T> {
+    ok: bool,
+    result: Option<T>,
+    description: Option<String>,
+}
+
+/// Client for the Telegram Bot API, configured with TELEGRAM_BOT_TOKEN
+#[derive(Clone)]
+pub struct TelegramClient {
+    client: Client,
+    /// API address with the bot token, such as https://api.telegram.org/bot123:abc
+    base_url: String,
+}
+
+impl TelegramClient {
+    /// Bot from TELEGRAM_BOT_TOKEN, or None when it is not set. TELEGRAM_API_URL
+    /// points to a local Bot API server instead of Telegram's.
+    pub fn from_env() -> Option<Self> {
+        let token = std::env::var("TELEGRAM_BOT_TOKEN")
+            .ok()
+            .filter(|t| !t.trim().is_empty())?;
+        let api_url = std::env::var("TELEGRAM_API_URL")
+            .ok()
+            .filter(|u| !u.trim().is_empty())
+            .unwrap_or_else(|| TELEGRAM_API_URL.to_string());
+        info!("Telegram bot enabled");
+        Some(Self::new(&api_url, token.trim()))
+    }
+
+    pub fn new(api_url: &str, token: &str) -> Self {
+        Self {
+            client: Client::new(),
+            base_url: format!("{}/bot{}", api_url.trim_end_matches('/'), token),
+        }
+    }
+
+    async fn call<T: serde::de::DeserializeOwned>(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+        timeout: Duration,
+    ) -> Result<T> {
+        let response: ApiResponse<T> = self
+            .client
+            .post(format!("{}/{}", self.base_url, method))
+            .json(&params)
+            .timeout(timeout)
+            .send()
+            .await
+            .map_err(|e| AppError::Internal(format!("Telegram request failed: {}", e)))?
+            .json()
+            .await
+            .map_err(|e| AppError::Internal(format!("Invalid Telegram response: {}", e)))?;
+
+        match response.result {
+            Some(result) if response.ok => Ok(result),
+            _ => Err(AppError::Internal(format!(
+                "Telegram {} failed: {}",
+                method,
+                response.description.unwrap_or_default()
+            ))),
+        }
+    }
+
+    /// Updates from `offset` on, waiting up to `wait_secs` for one to arrive
+    pub async fn get_updates(&self, offset: i64, wait_secs: u64) -> Result<Vec<TelegramUpdate>> {
+        self.call(
+            "getUpdates",
+            json!({"offset": offset, "timeout": wait_secs, "allowed_updates": ["message"]}),
+            Duration::from_secs(wait_secs + 10),
+        )
+        .await
+    }
+
+    /// Send a plain text message, cut to the length Telegram accepts
+    pub async fn send_message(&self, chat_id: i64, text: &str) -> Result<()> {
+        let text: String = text.chars().take(MAX_MESSAGE_LENGTH).collect();
+        self.call::<serde_json::Value>(
+            "sendMessage",
+            json!({"chat_id": chat_id, "text": text}),
+            Duration::from_secs(30),
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+/// The assistant's answer followed by a line for each action it took
+pub fn reply_text(response: &ChatResponse) -> String {
+    let mut text = response.response.trim().to_string();
+    for action in &response.actions_taken {
+        let mark = if action.rolled_back {
+            "↩"
+        } else if action.success {
+            "✓"
+        } else {
+            "✗"
+        };
+        if !text.is_empty() {
+            text.push('\n');
+        }
+        text.push_str(&format!("{} {}", mark, action.description));
+    }
+
+    if text.is_empty() {
+        "Done.".to_string()
+    } else {
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ActionTaken;
+
+    #[test]
+    fn test_reply_text() {
+        let response = ChatResponse {
+            response: "Added it to your inbox.".to_string(),
+            actions_taken: vec![
+                ActionTaken {
+                    action: "create_inbox_card".to_string(),
+                    description: "Created card 'buy milk'".to_string(),
+                    success: true,
+                    ..Default::default()
+                },
+                ActionTaken {
+                    action: "move_card".to_string(),
+                    description: "Card not found".to_string(),
+                    success: false,
+                    ..Default::default()
+                },
+            ],
+            pending: None,
+        };
+        assert_eq!(
+            reply_text(&response),
+            "Added it to your inbox.\n✓ Created card 'buy milk'\n✗ Card not found"
+        );
+
+        let empty = ChatResponse {
+            response: " ".to_string(),
+            actions_taken: vec![],
+            pending: None,
+        };
+        assert_eq!(reply_text(&empty), "Done.");
+    }
+}
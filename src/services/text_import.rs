@@ -0,0 +1,103 @@
+use crate::models::CardStatus;
+
+/// A card parsed from one line of a pasted text list
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportedCard {
+    pub title: String,
+    pub status: CardStatus,
+}
+
+/// Parse a plain text or Markdown list into cards, one per non-empty line.
+///
+/// Bullet (`-`, `*`, `+`) and numbered (`1.`, `1)`) markers are stripped. A Markdown
+/// checkbox maps to the card status: `[x]` is done, `[ ]` (or no checkbox) is open.
+pub fn parse_card_list(text: &str) -> Vec<ImportedCard> {
+    text.lines()
+        .filter_map(|line| {
+            let line = strip_list_marker(line.trim());
+            let (status, title) = split_checkbox(line);
+            let title = title.trim();
+            if title.is_empty() {
+                None
+            } else {
+                Some(ImportedCard {
+                    title: title.to_string(),
+                    status,
+                })
+            }
+        })
+        .collect()
+}
+
+fn strip_list_marker(line: &str) -> &str {
+    for bullet in ["- ", "* ", "+ "] {
+        if let Some(rest) = line.strip_prefix(bullet) {
+            return rest.trim_start();
+        }
+    }
+
+    let digits = line.chars().take_while(|c| c.is_ascii_digit()).count();
+    if digits > 0 {
+        let rest = &line[digits..];
+        if let Some(rest) = rest.strip_prefix(". ").or_else(|| rest.strip_prefix(") ")) {
+            return rest.trim_start();
+        }
+    }
+
+    line
+}
+
+fn split_checkbox(line: &str) -> (CardStatus, &str) {
+    if let Some(rest) = line.strip_prefix("[ ]") {
+        (CardStatus::Open, rest)
+    } else if let Some(rest) = line
+        .strip_prefix("[x]")
+        .or_else(|| line.strip_prefix("[X]"))
+    {
+        (CardStatus::Done, rest)
+    } else {
+        (CardStatus::Open, line)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_card_list_markers_and_checkboxes() {
+        let text = "Buy milk\n\n- [ ] Call the bank\n* [x] File taxes\n3. Plan trip\n  - [X]   Book hotel  \n- [ ]\n";
+        let cards = parse_card_list(text);
+
+        let titles: Vec<&str> = cards.iter().map(|c| c.title.as_str()).collect();
+        assert_eq!(
+            titles,
+            vec![
+                "Buy milk",
+                "Call the bank",
+                "File taxes",
+                "Plan trip",
+                "Book hotel"
+            ]
+        );
+        let statuses: Vec<CardStatus> = cards.iter().map(|c| c.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                CardStatus::Open,
+                CardStatus::Open,
+                CardStatus::Done,
+                CardStatus::Open,
+                CardStatus::Done
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_card_list_keeps_plain_brackets_and_numbers() {
+        let cards = parse_card_list("2024 roadmap\n[draft] notes");
+
+        assert_eq!(cards[0].title, "2024 roadmap");
+        assert_eq!(cards[1].title, "[draft] notes");
+    }
+}
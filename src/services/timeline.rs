@@ -0,0 +1,195 @@
+use chrono::NaiveDate;
+
+use crate::models::{Card, Column, RoadmapCard, TimelineCard, TimelineLane};
+
+/// Days a card's bar covers. Cards with only some dates get a bar from the dates they
+/// have: a card with a start and due date runs until it is due, a card with a single
+/// date gets a one-day bar. An end before the start is treated as the start.
+pub fn bar_span(
+    start: Option<NaiveDate>,
+    end: Option<NaiveDate>,
+    due: Option<NaiveDate>,
+) -> Option<(NaiveDate, NaiveDate)> {
+    let first = start.or(end).or(due)?;
+    let last = end.or(due).unwrap_or(first).max(first);
+    Some((first, last))
+}
+
+/// Lanes of dated cards, one per column in board order, and the cards without dates.
+/// With `from` and `to`, only bars overlapping that range are kept. Empty lanes are
+/// left out.
+pub fn build(
+    columns: &[Column],
+    cards: Vec<Card>,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> (Vec<TimelineLane>, Vec<RoadmapCard>) {
+    let mut lanes: Vec<TimelineLane> = columns
+        .iter()
+        .map(|column| TimelineLane {
+            column_id: column.id,
+            column_name: column.name.clone(),
+            cards: Vec::new(),
+        })
+        .collect();
+    let mut unscheduled = Vec::new();
+
+    for card in cards {
+        let Some((bar_start, bar_end)) = bar_span(card.start_date, card.end_date, card.due_date)
+        else {
+            unscheduled.push(RoadmapCard {
+                id: card.id,
+                column_id: card.column_id,
+                title: card.title,
+                status: card.status,
+                due_date: card.due_date,
+            });
+            continue;
+        };
+        if from.is_some_and(|from| bar_end < from) || to.is_some_and(|to| bar_start > to) {
+            continue;
+        }
+        let Some(lane) = lanes
+            .iter_mut()
+            .find(|lane| Some(lane.column_id) == card.column_id)
+        else {
+            continue;
+        };
+        lane.cards.push(TimelineCard {
+            id: card.id,
+            column_id: card.column_id,
+            title: card.title,
+            status: card.status,
+            start_date: card.start_date,
+            end_date: card.end_date,
+            due_date: card.due_date,
+            bar_start,
+            bar_end,
+        });
+    }
+
+    lanes.retain(|lane| !lane.cards.is_empty());
+    for lane in &mut lanes {
+        lane.cards.sort_by_key(|c| (c.bar_start, c.bar_end));
+    }
+
+    (lanes, unscheduled)
+}
+
+/// First and last day covered by the bars of the lanes
+pub fn range(lanes: &[TimelineLane]) -> Option<(NaiveDate, NaiveDate)> {
+    let cards = lanes.iter().flat_map(|lane| &lane.cards);
+    let start = cards.clone().map(|c| c.bar_start).min()?;
+    let end = cards.map(|c| c.bar_end).max()?;
+    Some((start, end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn column(name: &str) -> Column {
+        Column {
+            id: Uuid::new_v4(),
+            board_id: Uuid::nil(),
+            name: name.to_string(),
+            position: 0,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            archived_at: None,
+            wip_limit: None,
+            sort_policy: "manual".to_string(),
+            version: 1,
+            color: None,
+            description: None,
+            card_status: None,
+        }
+    }
+
+    fn card(
+        column: &Column,
+        title: &str,
+        start: Option<NaiveDate>,
+        end: Option<NaiveDate>,
+    ) -> Card {
+        Card {
+            id: Uuid::new_v4(),
+            column_id: Some(column.id),
+            title: title.to_string(),
+            body: None,
+            position: 0,
+            visibility: "private".to_string(),
+            status: "open".to_string(),
+            start_date: start,
+            end_date: end,
+            due_date: None,
+            owner_id: None,
+            created_by: Uuid::nil(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            locked_at: None,
+            locked_by: None,
+            archived_at: None,
+            priority: None,
+            version: 1,
+            cover_color: None,
+            cover_attachment_id: None,
+        }
+    }
+
+    #[test]
+    fn test_bar_span() {
+        let (a, b) = (date(2024, 3, 1), date(2024, 3, 10));
+        assert_eq!(bar_span(Some(a), Some(b), None), Some((a, b)));
+        assert_eq!(bar_span(Some(a), None, Some(b)), Some((a, b)));
+        assert_eq!(bar_span(None, None, Some(b)), Some((b, b)));
+        assert_eq!(bar_span(Some(b), Some(a), None), Some((b, b)));
+        assert_eq!(bar_span(None, None, None), None);
+    }
+
+    #[test]
+    fn test_build_lanes() {
+        let todo = column("To Do");
+        let doing = column("Doing");
+        let cards = vec![
+            card(
+                &todo,
+                "Later",
+                Some(date(2024, 5, 1)),
+                Some(date(2024, 5, 3)),
+            ),
+            card(
+                &todo,
+                "Sooner",
+                Some(date(2024, 4, 1)),
+                Some(date(2024, 4, 9)),
+            ),
+            card(&todo, "Someday", None, None),
+            card(
+                &doing,
+                "Old",
+                Some(date(2024, 1, 1)),
+                Some(date(2024, 1, 2)),
+            ),
+        ];
+
+        let (lanes, unscheduled) = build(&[todo.clone(), doing.clone()], cards.clone(), None, None);
+        assert_eq!(lanes.len(), 2);
+        let titles: Vec<&str> = lanes[0].cards.iter().map(|c| c.title.as_str()).collect();
+        assert_eq!(titles, ["Sooner", "Later"]);
+        assert_eq!(unscheduled.len(), 1);
+        assert_eq!(range(&lanes), Some((date(2024, 1, 1), date(2024, 5, 3))));
+
+        // Bars outside the range are left out, with their lanes
+        let (lanes, _) = build(&[todo, doing], cards, Some(date(2024, 4, 5)), None);
+        assert_eq!(lanes.len(), 1);
+        assert_eq!(lanes[0].column_name, "To Do");
+        assert_eq!(lanes[0].cards.len(), 2);
+    }
+}
@@ -0,0 +1,443 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+use crate::models::CardStatus;
+
+/// Tag color used for Trello labels without a known color
+const DEFAULT_TAG_COLOR: &str = "#6c757d";
+
+/// The parts of a Trello board export (Menu > Print, export and share > Export as
+/// JSON) that are imported. Everything else in the file is ignored.
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct TrelloBoard {
+    pub name: String,
+    #[serde(default)]
+    pub desc: Option<String>,
+    #[serde(default)]
+    pub lists: Vec<TrelloList>,
+    #[serde(default)]
+    pub cards: Vec<TrelloCard>,
+    #[serde(default)]
+    pub labels: Vec<TrelloLabel>,
+    #[serde(default)]
+    pub checklists: Vec<TrelloChecklist>,
+    #[serde(default)]
+    pub actions: Vec<TrelloAction>,
+}
+
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TrelloList {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub closed: bool,
+    #[serde(default)]
+    pub pos: f64,
+}
+
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TrelloCard {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub desc: Option<String>,
+    pub id_list: String,
+    #[serde(default)]
+    pub closed: bool,
+    #[serde(default)]
+    pub pos: f64,
+    #[serde(default)]
+    pub due: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub due_complete: bool,
+    #[serde(default)]
+    pub id_labels: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct TrelloLabel {
+    pub id: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub color: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TrelloChecklist {
+    pub id_card: String,
+    pub name: String,
+    #[serde(default)]
+    pub pos: f64,
+    #[serde(default)]
+    pub check_items: Vec<TrelloCheckItem>,
+}
+
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct TrelloCheckItem {
+    pub name: String,
+    #[serde(default)]
+    pub state: String,
+    #[serde(default)]
+    pub pos: f64,
+}
+
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TrelloAction {
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default)]
+    pub date: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub data: TrelloActionData,
+    #[serde(default)]
+    pub member_creator: Option<TrelloMember>,
+}
+
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct TrelloActionData {
+    #[serde(default)]
+    pub text: Option<String>,
+    #[serde(default)]
+    pub card: Option<TrelloCardRef>,
+}
+
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct TrelloCardRef {
+    pub id: String,
+}
+
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TrelloMember {
+    #[serde(default)]
+    pub full_name: Option<String>,
+}
+
+/// What a Trello export turns into, in creation order
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportPlan {
+    pub name: String,
+    pub description: Option<String>,
+    pub tags: Vec<PlannedTag>,
+    pub columns: Vec<PlannedColumn>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedTag {
+    pub name: String,
+    pub color: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedColumn {
+    pub name: String,
+    pub cards: Vec<PlannedCard>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedCard {
+    pub title: String,
+    /// Description followed by the card's checklists as Markdown task lists
+    pub body: Option<String>,
+    pub status: CardStatus,
+    pub due_date: Option<NaiveDate>,
+    /// Indexes into `ImportPlan::tags`
+    pub tags: Vec<usize>,
+    /// Comment bodies, oldest first
+    pub comments: Vec<String>,
+}
+
+impl ImportPlan {
+    pub fn card_count(&self) -> usize {
+        self.columns.iter().map(|c| c.cards.len()).sum()
+    }
+}
+
+/// Map a Trello export to columns, cards and tags.
+///
+/// Archived lists and cards are skipped. Lists, cards and checklist items keep their
+/// Trello order. Labels sharing a name become a single tag, and unnamed labels are
+/// named after their color. A card whose due date is marked complete is done.
+pub fn plan_import(board: TrelloBoard) -> ImportPlan {
+    let mut tags: Vec<PlannedTag> = Vec::new();
+    let mut tag_by_label: HashMap<&str, usize> = HashMap::new();
+    for label in &board.labels {
+        let color = label.color.as_deref().unwrap_or_default();
+        let name = match label.name.as_deref().map(str::trim) {
+            Some(name) if !name.is_empty() => name.to_string(),
+            _ if !color.is_empty() => capitalize(color.split('_').next().unwrap_or(color)),
+            _ => continue,
+        };
+        let index = match tags.iter().position(|t| t.name == name) {
+            Some(index) => index,
+            None => {
+                tags.push(PlannedTag {
+                    name,
+                    color: tag_color(color).to_string(),
+                });
+                tags.len() - 1
+            }
+        };
+        tag_by_label.insert(label.id.as_str(), index);
+    }
+
+    let mut checklists: HashMap<&str, Vec<&TrelloChecklist>> = HashMap::new();
+    for checklist in &board.checklists {
+        checklists
+            .entry(checklist.id_card.as_str())
+            .or_default()
+            .push(checklist);
+    }
+
+    let mut comments: HashMap<&str, Vec<&TrelloAction>> = HashMap::new();
+    for action in &board.actions {
+        if action.kind != "commentCard" || action.data.text.is_none() {
+            continue;
+        }
+        if let Some(card) = &action.data.card {
+            comments.entry(card.id.as_str()).or_default().push(action);
+        }
+    }
+
+    let mut lists: Vec<&TrelloList> = board.lists.iter().filter(|l| !l.closed).collect();
+    lists.sort_by(|a, b| a.pos.total_cmp(&b.pos));
+
+    let columns = lists
+        .into_iter()
+        .map(|list| {
+            let mut cards: Vec<&TrelloCard> = board
+                .cards
+                .iter()
+                .filter(|c| !c.closed && c.id_list == list.id)
+                .collect();
+            cards.sort_by(|a, b| a.pos.total_cmp(&b.pos));
+
+            PlannedColumn {
+                name: list.name.clone(),
+                cards: cards
+                    .into_iter()
+                    .map(|card| {
+                        let mut card_comments =
+                            comments.remove(card.id.as_str()).unwrap_or_default();
+                        card_comments.sort_by_key(|action| action.date);
+
+                        let mut card_tags: Vec<usize> = card
+                            .id_labels
+                            .iter()
+                            .filter_map(|id| tag_by_label.get(id.as_str()).copied())
+                            .collect();
+                        card_tags.sort_unstable();
+                        card_tags.dedup();
+
+                        PlannedCard {
+                            title: card.name.clone(),
+                            body: card_body(
+                                card.desc.as_deref(),
+                                checklists.get(card.id.as_str()).map(Vec::as_slice),
+                            ),
+                            status: if card.due_complete {
+                                CardStatus::Done
+                            } else {
+                                CardStatus::Open
+                            },
+                            due_date: card.due.map(|d| d.date_naive()),
+                            tags: card_tags,
+                            comments: card_comments.into_iter().map(comment_body).collect(),
+                        }
+                    })
+                    .collect(),
+            }
+        })
+        .collect();
+
+    ImportPlan {
+        name: board.name,
+        description: board.desc.filter(|d| !d.trim().is_empty()),
+        tags,
+        columns,
+    }
+}
+
+/// Text of a Trello comment, crediting its author
+fn comment_body(action: &TrelloAction) -> String {
+    let author = action
+        .member_creator
+        .as_ref()
+        .and_then(|m| m.full_name.as_deref())
+        .unwrap_or("Unknown");
+    let when = action
+        .date
+        .map(|d| format!(", {}", d.date_naive()))
+        .unwrap_or_default();
+    format!(
+        "{}\n\n_{} on Trello{}_",
+        action.data.text.as_deref().unwrap_or_default().trim(),
+        author,
+        when
+    )
+}
+
+fn card_body(desc: Option<&str>, checklists: Option<&[&TrelloChecklist]>) -> Option<String> {
+    let mut sections: Vec<String> = Vec::new();
+    if let Some(desc) = desc.map(str::trim).filter(|d| !d.is_empty()) {
+        sections.push(desc.to_string());
+    }
+
+    let mut checklists = checklists.unwrap_or_default().to_vec();
+    checklists.sort_by(|a, b| a.pos.total_cmp(&b.pos));
+    for checklist in checklists {
+        let mut items: Vec<&TrelloCheckItem> = checklist.check_items.iter().collect();
+        items.sort_by(|a, b| a.pos.total_cmp(&b.pos));
+
+        let mut section = format!("**{}**", checklist.name.trim());
+        for item in items {
+            let mark = if item.state == "complete" { 'x' } else { ' ' };
+            section.push_str(&format!("\n- [{}] {}", mark, item.name.trim()));
+        }
+        sections.push(section);
+    }
+
+    if sections.is_empty() {
+        None
+    } else {
+        Some(sections.join("\n\n"))
+    }
+}
+
+/// Hex color of a Trello label color such as `green` or `green_dark`
+fn tag_color(color: &str) -> &'static str {
+    match color.split('_').next().unwrap_or_default() {
+        "green" => "#61bd4f",
+        "yellow" => "#f2d600",
+        "orange" => "#ff9f1a",
+        "red" => "#eb5a46",
+        "purple" => "#c377e0",
+        "blue" => "#0079bf",
+        "sky" => "#00c2e0",
+        "lime" => "#51e898",
+        "pink" => "#ff78cb",
+        "black" => "#344563",
+        _ => DEFAULT_TAG_COLOR,
+    }
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn export() -> TrelloBoard {
+        serde_json::from_value(serde_json::json!({
+            "name": "Migrated",
+            "desc": "",
+            "lists": [
+                {"id": "l2", "name": "Done", "pos": 200},
+                {"id": "l1", "name": "To Do", "pos": 100},
+                {"id": "l3", "name": "Old", "pos": 300, "closed": true}
+            ],
+            "labels": [
+                {"id": "a", "name": "Urgent", "color": "red"},
+                {"id": "b", "name": "", "color": "green_dark"},
+                {"id": "c", "name": "Urgent", "color": "orange"}
+            ],
+            "cards": [
+                {"id": "c2", "name": "Second", "idList": "l1", "pos": 2, "idLabels": ["a", "c"]},
+                {"id": "c1", "name": "First", "idList": "l1", "pos": 1, "desc": "Details",
+                 "due": "2024-05-01T12:00:00.000Z", "dueComplete": true, "idLabels": ["b"]},
+                {"id": "c3", "name": "Archived", "idList": "l1", "pos": 3, "closed": true},
+                {"id": "c4", "name": "In archived list", "idList": "l3", "pos": 1}
+            ],
+            "checklists": [
+                {"id": "k1", "idCard": "c1", "name": "Steps", "pos": 1, "checkItems": [
+                    {"name": "Two", "state": "incomplete", "pos": 2},
+                    {"name": "One", "state": "complete", "pos": 1}
+                ]}
+            ],
+            "actions": [
+                {"type": "commentCard", "date": "2024-04-02T08:00:00.000Z",
+                 "data": {"text": "Later", "card": {"id": "c1"}},
+                 "memberCreator": {"fullName": "Ada"}},
+                {"type": "updateCard", "data": {"card": {"id": "c1"}}},
+                {"type": "commentCard", "date": "2024-04-01T08:00:00.000Z",
+                 "data": {"text": "Earlier", "card": {"id": "c1"}},
+                 "memberCreator": {"fullName": "Bob"}}
+            ]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_plan_keeps_trello_order_and_skips_archived() {
+        let plan = plan_import(export());
+
+        assert_eq!(plan.description, None);
+        let columns: Vec<(&str, Vec<&str>)> = plan
+            .columns
+            .iter()
+            .map(|c| {
+                (
+                    c.name.as_str(),
+                    c.cards.iter().map(|card| card.title.as_str()).collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            columns,
+            vec![("To Do", vec!["First", "Second"]), ("Done", vec![])]
+        );
+        assert_eq!(plan.card_count(), 2);
+    }
+
+    #[test]
+    fn test_plan_maps_labels_checklists_and_comments() {
+        let plan = plan_import(export());
+
+        assert_eq!(
+            plan.tags,
+            vec![
+                PlannedTag {
+                    name: "Urgent".to_string(),
+                    color: "#eb5a46".to_string()
+                },
+                PlannedTag {
+                    name: "Green".to_string(),
+                    color: "#61bd4f".to_string()
+                },
+            ]
+        );
+
+        let first = &plan.columns[0].cards[0];
+        assert_eq!(first.status, CardStatus::Done);
+        assert_eq!(first.due_date, Some("2024-05-01".parse().unwrap()));
+        assert_eq!(first.tags, vec![1]);
+        assert_eq!(
+            first.body.as_deref(),
+            Some("Details\n\n**Steps**\n- [x] One\n- [ ] Two")
+        );
+        assert_eq!(
+            first.comments,
+            vec![
+                "Earlier\n\n_Bob on Trello, 2024-04-01_",
+                "Later\n\n_Ada on Trello, 2024-04-02_"
+            ]
+        );
+
+        // Both "Urgent" labels map to the same tag
+        assert_eq!(plan.columns[0].cards[1].tags, vec![0]);
+        assert_eq!(plan.columns[0].cards[1].body, None);
+    }
+}
@@ -13,10 +13,14 @@ pub struct SearchResult {
     pub snippet: String,
 }
 
+/// DuckDuckGo HTML search endpoint
+const DUCKDUCKGO_URL: &str = "https://html.duckduckgo.com/html/";
+
 /// Client for performing web searches via DuckDuckGo
 #[derive(Clone)]
 pub struct WebSearchClient {
     client: Client,
+    base_url: String,
 }
 
 impl Default for WebSearchClient {
@@ -27,11 +31,17 @@ impl Default for WebSearchClient {
 
 impl WebSearchClient {
     pub fn new() -> Self {
+        Self::with_base_url(DUCKDUCKGO_URL.to_string())
+    }
+
+    /// Client sending searches to a server answering like DuckDuckGo's HTML endpoint
+    pub fn with_base_url(base_url: String) -> Self {
         Self {
             client: Client::builder()
                 .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36")
                 .build()
                 .expect("Failed to create HTTP client"),
+            base_url,
         }
     }
 
@@ -44,7 +54,7 @@ impl WebSearchClient {
         info!(query = %query, max_results = %max_results, "Performing web search");
 
         let encoded_query = urlencoding::encode(query);
-        let url = format!("https://html.duckduckgo.com/html/?q={}", encoded_query);
+        let url = format!("{}?q={}", self.base_url, encoded_query);
 
         let response = self.client.get(&url).send().await.map_err(|e| {
             error!(error = %e, "Failed to fetch search results");
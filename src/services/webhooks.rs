@@ -0,0 +1,65 @@
+use reqwest::Client;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// How long a webhook has to answer a delivery
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// POSTs board events to the addresses of webhooks
+#[derive(Clone)]
+pub struct WebhookSender {
+    client: Client,
+}
+
+impl Default for WebhookSender {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WebhookSender {
+    pub fn new() -> Self {
+        Self {
+            client: Client::builder()
+                .timeout(DELIVERY_TIMEOUT)
+                .build()
+                .expect("Failed to create HTTP client"),
+        }
+    }
+
+    /// Send a stored payload to `url`. Any answer but a 2xx is an error, described by
+    /// the returned message.
+    pub async fn deliver(
+        &self,
+        url: &str,
+        delivery_id: Uuid,
+        event: &str,
+        schema_version: i64,
+        payload: &str,
+        replay: bool,
+    ) -> Result<(), String> {
+        let mut request = self
+            .client
+            .post(url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header("X-Webhook-Event", event)
+            .header("X-Webhook-Schema-Version", schema_version.to_string())
+            .header("X-Webhook-Delivery", delivery_id.to_string());
+        if replay {
+            request = request.header("X-Webhook-Replay", "true");
+        }
+
+        let response = request
+            .body(payload.to_string())
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if !response.status().is_success() {
+            return Err(format!(
+                "Webhook answered with status {}",
+                response.status()
+            ));
+        }
+        Ok(())
+    }
+}
@@ -1,47 +1,200 @@
 use sqlx::SqlitePool;
 use std::sync::Arc;
 
+use crate::auth::{RateLimiter, RateLimits, SecurityHeaders, TrustedProxies};
+use crate::jobs::JobManager;
+use crate::models::User;
 use crate::repo::{
-    board::BoardRepository, card::CardRepository, card_board::CardBoardRepository,
-    chat_message::ChatMessageRepository, column::ColumnRepository, comment::CommentRepository,
-    session::SessionRepository, tag::TagRepository, token::ApiTokenRepository, user::UserRepository,
+    account::AccountRepository, activity::ActivityRepository, admin::AdminRepository,
+    assignee::AssigneeRepository, attachment::AttachmentRepository, backfill::BackfillRepository,
+    backup::BackupRepository, board::BoardRepository, board_preference::BoardPreferenceRepository,
+    board_template::BoardTemplateRepository, calendar::CalendarRepository, card::CardRepository,
+    card_board::CardBoardRepository, card_template::CardTemplateRepository,
+    chat_action::ChatActionRepository, chat_message::ChatMessageRepository,
+    chat_session::ChatSessionRepository, column::ColumnRepository, comment::CommentRepository,
+    dashboard::DashboardRepository, email_change::EmailChangeRepository,
+    guest_comment::GuestCommentRepository, identity::IdentityRepository,
+    inbound_email::InboundEmailRepository, integrity::IntegrityRepository,
+    invitation::InvitationRepository, link::CardLinkRepository, mention::MentionRepository,
+    notification::NotificationRepository, pending_chat_action::PendingChatActionRepository,
+    reaction::ReactionRepository, recurrence::RecurrenceRepository,
+    revision::CardRevisionRepository, saved_view::SavedViewRepository, session::SessionRepository,
+    share_link::BoardShareLinkRepository, stats::BoardStatsRepository, tag::TagRepository,
+    telegram::TelegramRepository, token::ApiTokenRepository, trash::TrashRepository,
+    user::UserRepository, watcher::CardWatcherRepository, webhook::WebhookRepository,
+    workspace::WorkspaceRepository,
+};
+use crate::services::{
+    AttachmentStorage, BoardSummaryCache, EmailService, EventHub, LlmOptions, LlmProvider,
+    LlmProviders, OAuthProviders, TelegramClient, WebSearchClient, WebhookSender,
 };
-use crate::services::{OllamaClient, WebSearchClient};
 
 #[derive(Clone)]
 pub struct AppState {
     pub users: UserRepository,
     pub sessions: SessionRepository,
+    pub identities: IdentityRepository,
+    pub email_changes: EmailChangeRepository,
     pub tokens: ApiTokenRepository,
     pub boards: BoardRepository,
+    pub board_templates: BoardTemplateRepository,
+    pub invitations: InvitationRepository,
     pub columns: ColumnRepository,
     pub cards: CardRepository,
     pub card_boards: CardBoardRepository,
+    pub card_templates: CardTemplateRepository,
     pub tags: TagRepository,
     pub comments: CommentRepository,
+    pub dashboard: DashboardRepository,
+    pub recurrences: RecurrenceRepository,
+    pub saved_views: SavedViewRepository,
+    pub assignees: AssigneeRepository,
+    pub links: CardLinkRepository,
+    pub calendar: CalendarRepository,
+    pub inbound_email: InboundEmailRepository,
+    pub telegram_links: TelegramRepository,
+    pub notifications: NotificationRepository,
+    pub mentions: MentionRepository,
+    pub reactions: ReactionRepository,
+    pub revisions: CardRevisionRepository,
+    pub share_links: BoardShareLinkRepository,
+    pub guest_comments: GuestCommentRepository,
+    pub workspaces: WorkspaceRepository,
+    pub board_preferences: BoardPreferenceRepository,
+    pub watchers: CardWatcherRepository,
+    pub attachments: AttachmentRepository,
     pub chat_messages: ChatMessageRepository,
-    pub ollama: OllamaClient,
+    pub chat_actions: ChatActionRepository,
+    pub pending_chat_actions: PendingChatActionRepository,
+    pub chat_sessions: ChatSessionRepository,
+    pub backfills: BackfillRepository,
+    pub backups: BackupRepository,
+    pub activity: ActivityRepository,
+    pub stats: BoardStatsRepository,
+    pub admin: AdminRepository,
+    pub accounts: AccountRepository,
+    pub integrity: IntegrityRepository,
+    pub trash: TrashRepository,
+    pub webhooks: WebhookRepository,
+    pub attachment_storage: AttachmentStorage,
+    pub email: EmailService,
+    pub events: EventHub,
+    /// Board summaries for the global chat prompt, dropped when a board changes
+    pub board_summaries: BoardSummaryCache,
+    /// Provider for users who did not choose one
+    pub llm: Arc<dyn LlmProvider>,
+    /// Every configured provider, which users can choose from
+    pub llm_providers: LlmProviders,
     pub web_search: WebSearchClient,
+    /// Sends board events to webhooks
+    pub webhook_sender: WebhookSender,
+    /// Providers users can sign in with instead of a password
+    pub oauth: OAuthProviders,
+    /// Bot answering chat messages sent from Telegram, when TELEGRAM_BOT_TOKEN is set
+    pub telegram_bot: Option<TelegramClient>,
+    pub trusted_proxies: TrustedProxies,
+    /// Public address of the web UI, without a trailing slash
+    pub app_url: String,
+    pub security_headers: SecurityHeaders,
+    /// Background jobs and tasks, stopped on shutdown
+    pub jobs: JobManager,
+    pub rate_limiter: RateLimiter,
     pub pool: Arc<SqlitePool>,
 }
 
 impl AppState {
-    pub fn new(pool: SqlitePool) -> Self {
+    /// State of an app whose web UI is reached at `app_url`, which generated links use
+    pub fn new(pool: SqlitePool, app_url: &str) -> Self {
+        let app_url = app_url.trim_end_matches('/').to_string();
         let pool = Arc::new(pool);
+        let llm_providers = crate::services::llm::from_env();
         Self {
             users: UserRepository::new(pool.clone()),
             sessions: SessionRepository::new(pool.clone()),
+            identities: IdentityRepository::new(pool.clone()),
+            email_changes: EmailChangeRepository::new(pool.clone()),
             tokens: ApiTokenRepository::new(pool.clone()),
             boards: BoardRepository::new(pool.clone()),
+            board_templates: BoardTemplateRepository::new(pool.clone()),
+            invitations: InvitationRepository::new(pool.clone()),
             columns: ColumnRepository::new(pool.clone()),
             cards: CardRepository::new(pool.clone()),
             card_boards: CardBoardRepository::new(pool.clone()),
+            card_templates: CardTemplateRepository::new(pool.clone()),
             tags: TagRepository::new(pool.clone()),
             comments: CommentRepository::new(pool.clone()),
+            dashboard: DashboardRepository::new(pool.clone()),
+            recurrences: RecurrenceRepository::new(pool.clone()),
+            saved_views: SavedViewRepository::new(pool.clone()),
+            assignees: AssigneeRepository::new(pool.clone()),
+            links: CardLinkRepository::new(pool.clone()),
+            calendar: CalendarRepository::new(pool.clone()),
+            inbound_email: InboundEmailRepository::new(pool.clone()),
+            telegram_links: TelegramRepository::new(pool.clone()),
+            notifications: NotificationRepository::new(pool.clone()),
+            mentions: MentionRepository::new(pool.clone()),
+            reactions: ReactionRepository::new(pool.clone()),
+            revisions: CardRevisionRepository::new(pool.clone()),
+            share_links: BoardShareLinkRepository::new(pool.clone()),
+            guest_comments: GuestCommentRepository::new(pool.clone()),
+            workspaces: WorkspaceRepository::new(pool.clone()),
+            board_preferences: BoardPreferenceRepository::new(pool.clone()),
+            watchers: CardWatcherRepository::new(pool.clone()),
+            attachments: AttachmentRepository::new(pool.clone()),
             chat_messages: ChatMessageRepository::new(pool.clone()),
-            ollama: OllamaClient::from_env(),
+            chat_actions: ChatActionRepository::new(pool.clone()),
+            pending_chat_actions: PendingChatActionRepository::new(pool.clone()),
+            chat_sessions: ChatSessionRepository::new(pool.clone()),
+            backfills: BackfillRepository::new(pool.clone()),
+            backups: BackupRepository::new(pool.clone()),
+            activity: ActivityRepository::new(pool.clone()),
+            stats: BoardStatsRepository::new(pool.clone()),
+            admin: AdminRepository::new(pool.clone()),
+            accounts: AccountRepository::new(pool.clone()),
+            integrity: IntegrityRepository::new(pool.clone()),
+            trash: TrashRepository::new(pool.clone()),
+            webhooks: WebhookRepository::new(pool.clone()),
+            attachment_storage: AttachmentStorage::from_env(),
+            email: EmailService::from_env(&app_url),
+            events: EventHub::new(),
+            board_summaries: BoardSummaryCache::new(),
+            llm: crate::services::llm::default_provider(&llm_providers),
+            llm_providers,
             web_search: WebSearchClient::new(),
+            webhook_sender: WebhookSender::new(),
+            oauth: OAuthProviders::from_env(&app_url),
+            telegram_bot: TelegramClient::from_env(),
+            trusted_proxies: TrustedProxies::from_env(),
+            app_url,
+            security_headers: SecurityHeaders::from_env(),
+            jobs: JobManager::new(),
+            rate_limiter: RateLimiter::new(RateLimits::from_env()),
             pool,
         }
     }
+
+    /// Absolute link to a page of the web UI
+    pub fn link(&self, path: &str) -> String {
+        format!("{}{}", self.app_url, path)
+    }
+
+    /// Provider and options for the chat requests of `user`. A provider that is no
+    /// longer configured falls back to the default one.
+    pub fn llm_for(&self, user: &User) -> (Arc<dyn LlmProvider>, LlmOptions) {
+        let provider = user
+            .llm_provider
+            .as_deref()
+            .and_then(|p| p.parse().ok())
+            .and_then(|kind| self.llm_providers.get(&kind))
+            .cloned()
+            .unwrap_or_else(|| self.llm.clone());
+
+        (
+            provider,
+            LlmOptions {
+                model: user.llm_model.clone(),
+                temperature: user.llm_temperature,
+            },
+        )
+    }
 }
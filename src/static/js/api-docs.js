@@ -0,0 +1,13 @@
+// Interactive API reference rendered from the OpenAPI document
+(function() {
+    'use strict';
+
+    const container = document.getElementById('swagger-ui');
+
+    window.ui = SwaggerUIBundle({
+        url: container.dataset.url,
+        dom_id: '#swagger-ui',
+        // Requests from the page use the session cookie of the web UI
+        withCredentials: true,
+    });
+})();
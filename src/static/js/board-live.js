@@ -0,0 +1,48 @@
+// Live board updates over the board event WebSocket
+(function() {
+    'use strict';
+
+    const RECONNECT_DELAY_MS = 5000;
+
+    function getBoardId() {
+        const chatContainer = document.getElementById('chat-container');
+        return chatContainer ? chatContainer.dataset.boardId : null;
+    }
+
+    function showBanner(event) {
+        const banner = document.getElementById('board-live-banner');
+        if (!banner) return;
+
+        const message = banner.querySelector('.board-live-message');
+        if (message) {
+            message.textContent = event.event === 'board_deleted'
+                ? 'This board was deleted.'
+                : 'This board has changed.';
+        }
+        banner.classList.remove('d-none');
+    }
+
+    function connect(boardId) {
+        const protocol = window.location.protocol === 'https:' ? 'wss:' : 'ws:';
+        const socket = new WebSocket(protocol + '//' + window.location.host + '/api/boards/' + boardId + '/ws');
+
+        socket.addEventListener('message', function(message) {
+            try {
+                showBanner(JSON.parse(message.data));
+            } catch (e) {
+                console.error('Invalid board event:', e);
+            }
+        });
+
+        socket.addEventListener('close', function() {
+            setTimeout(function() { connect(boardId); }, RECONNECT_DELAY_MS);
+        });
+    }
+
+    document.addEventListener('DOMContentLoaded', function() {
+        const boardId = getBoardId();
+        if (boardId && 'WebSocket' in window) {
+            connect(boardId);
+        }
+    });
+})();
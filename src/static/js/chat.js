@@ -75,6 +75,9 @@
 
             const data = await response.json();
             addMessage(data.response, 'assistant', data.actions_taken);
+            if (data.pending) {
+                addPendingActions(data.pending);
+            }
 
             // If actions were taken that modified the board, refresh after a delay
             if (data.actions_taken && data.actions_taken.some(a => a.success)) {
@@ -84,7 +87,7 @@
             }
         } catch (error) {
             loadingEl.remove();
-            addMessage('Failed to connect to chat service. Is Ollama running?', 'error');
+            addMessage('Failed to connect to the chat service. Is the LLM provider running?', 'error');
         }
     });
 
@@ -122,9 +125,28 @@
             for (const action of actions) {
                 const cls = action.success ? 'action-success' : 'action-failed';
                 const icon = action.success ? 'check-circle' : 'x-circle';
-                html += `<div class="${cls}"><i class="bi bi-${icon}"></i> ${escapeHtml(action.description)}</div>`;
+                // Search results are listed as sources below
+                const description = action.sources && action.sources.length > 0
+                    ? 'Searched the web'
+                    : action.description;
+                html += `<div class="${cls}"><i class="bi bi-${icon}"></i> ${escapeHtml(description)}</div>`;
+                if (action.comment) {
+                    html += `<div class="fst-italic ms-3">${escapeHtml(action.comment.body)}</div>`;
+                }
             }
             html += '</div>';
+
+            const sources = actions
+                .flatMap(a => a.sources || [])
+                .filter(source => /^https?:\/\//i.test(source.url));
+            if (sources.length > 0) {
+                html += '<div class="chat-message-sources small mt-1">Sources:';
+                for (const source of sources) {
+                    const href = escapeHtml(source.url).replace(/"/g, '&quot;');
+                    html += ` <a href="${href}" target="_blank" rel="noopener noreferrer">${escapeHtml(source.title || source.url)}</a>`;
+                }
+                html += '</div>';
+            }
         }
 
         msgEl.innerHTML = html;
@@ -133,6 +155,56 @@
         return msgEl;
     }
 
+    // Proposed actions waiting for the user to confirm or discard them
+    function addPendingActions(pending) {
+        const pendingEl = document.createElement('div');
+        pendingEl.className = 'chat-message chat-message--assistant';
+
+        let html = '<div class="chat-message-content">Run these actions?</div><div class="chat-message-actions">';
+        for (const action of pending.actions) {
+            html += `<div><i class="bi bi-question-circle"></i> ${escapeHtml(action.action)}</div>`;
+        }
+        html += '</div><div class="mt-2">';
+        html += '<button type="button" class="btn btn-sm btn-primary me-1" data-pending="confirm">Confirm</button>';
+        html += '<button type="button" class="btn btn-sm btn-outline-secondary" data-pending="discard">Discard</button>';
+        html += '</div>';
+
+        pendingEl.innerHTML = html;
+        chatMessages.appendChild(pendingEl);
+        chatMessages.scrollTop = chatMessages.scrollHeight;
+
+        pendingEl.querySelector('[data-pending="discard"]').addEventListener('click', () => {
+            pendingEl.remove();
+        });
+        pendingEl.querySelector('[data-pending="confirm"]').addEventListener('click', async () => {
+            pendingEl.remove();
+            try {
+                const response = await fetch(`/api/boards/${boardId}/chat/confirm`, {
+                    method: 'POST',
+                    headers: {
+                        'Content-Type': 'application/json',
+                    },
+                    body: JSON.stringify({ confirmation_token: pending.confirmation_token }),
+                });
+
+                if (!response.ok) {
+                    addMessage('These actions expired or were already confirmed', 'error');
+                    return;
+                }
+
+                const data = await response.json();
+                addMessage(data.response, 'assistant', data.actions_taken);
+                if (data.actions_taken.some(a => a.success)) {
+                    setTimeout(() => {
+                        window.location.reload();
+                    }, 1500);
+                }
+            } catch (error) {
+                addMessage('Failed to confirm the actions', 'error');
+            }
+        });
+    }
+
     function formatContent(content, type) {
         if (type === 'user') {
             return escapeHtml(content);
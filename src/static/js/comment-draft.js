@@ -0,0 +1,48 @@
+// Comment draft autosave: keeps unsent comment text server-side so it survives navigation
+(function() {
+    'use strict';
+
+    const SAVE_DELAY_MS = 1000;
+
+    document.addEventListener('DOMContentLoaded', function() {
+        const textarea = document.getElementById('comment-body');
+        if (!textarea || !textarea.dataset.draftUrl) return;
+
+        const status = document.getElementById('comment-draft-status');
+        const url = textarea.dataset.draftUrl;
+        let timer = null;
+
+        function setStatus(text) {
+            if (status) status.textContent = text;
+        }
+
+        // Save the current text (a blank body discards the draft)
+        function saveDraft() {
+            fetch(url, {
+                method: 'PUT',
+                headers: { 'Content-Type': 'application/json' },
+                body: JSON.stringify({ body: textarea.value })
+            })
+                .then(function(response) {
+                    if (response.ok) {
+                        setStatus(textarea.value.trim() ? 'Draft saved' : '');
+                    }
+                })
+                .catch(function(e) {
+                    console.error('Failed to save comment draft:', e);
+                });
+        }
+
+        textarea.addEventListener('input', function() {
+            clearTimeout(timer);
+            timer = setTimeout(saveDraft, SAVE_DELAY_MS);
+        });
+
+        // The server discards the draft once the comment is posted
+        if (textarea.form) {
+            textarea.form.addEventListener('submit', function() {
+                clearTimeout(timer);
+            });
+        }
+    });
+})();
@@ -0,0 +1,30 @@
+// Form behaviours declared with data attributes, as inline handlers are refused by the
+// Content-Security-Policy:
+//   data-confirm="Question?" on a form asks before submitting it
+//   data-autosubmit on a control submits its form when the value changes
+//   data-select-on-focus on a field selects its text when it gets the focus
+(function() {
+    'use strict';
+
+    document.addEventListener('submit', function(event) {
+        const form = event.target;
+        const question = form.dataset ? form.dataset.confirm : null;
+        if (question && !window.confirm(question)) {
+            event.preventDefault();
+        }
+    });
+
+    document.addEventListener('change', function(event) {
+        const control = event.target;
+        if (control.form && control.hasAttribute('data-autosubmit')) {
+            control.form.requestSubmit();
+        }
+    });
+
+    document.addEventListener('focusin', function(event) {
+        const field = event.target;
+        if (field.hasAttribute && field.hasAttribute('data-select-on-focus')) {
+            field.select();
+        }
+    });
+})();
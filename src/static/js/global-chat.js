@@ -86,7 +86,7 @@
             }
         } catch (error) {
             loadingEl.remove();
-            addMessage('Failed to connect to chat service. Is Ollama running?', 'error');
+            addMessage('Failed to connect to the chat service. Is the LLM provider running?', 'error');
         }
     });
 
@@ -124,9 +124,28 @@
             for (const action of actions) {
                 const cls = action.success ? 'action-success' : 'action-failed';
                 const icon = action.success ? 'check-circle' : 'x-circle';
-                html += `<div class="${cls}"><i class="bi bi-${icon}"></i> ${escapeHtml(action.description)}</div>`;
+                // Search results are listed as sources below
+                const description = action.sources && action.sources.length > 0
+                    ? 'Searched the web'
+                    : action.description;
+                html += `<div class="${cls}"><i class="bi bi-${icon}"></i> ${escapeHtml(description)}</div>`;
+                if (action.comment) {
+                    html += `<div class="fst-italic ms-3">${escapeHtml(action.comment.body)}</div>`;
+                }
             }
             html += '</div>';
+
+            const sources = actions
+                .flatMap(a => a.sources || [])
+                .filter(source => /^https?:\/\//i.test(source.url));
+            if (sources.length > 0) {
+                html += '<div class="chat-message-sources small mt-1">Sources:';
+                for (const source of sources) {
+                    const href = escapeHtml(source.url).replace(/"/g, '&quot;');
+                    html += ` <a href="${href}" target="_blank" rel="noopener noreferrer">${escapeHtml(source.title || source.url)}</a>`;
+                }
+                html += '</div>';
+            }
         }
 
         msgEl.innerHTML = html;
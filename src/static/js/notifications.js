@@ -0,0 +1,103 @@
+// Notification bell: unread count in the navbar, with the latest notifications
+// listed when the dropdown opens
+(function() {
+    'use strict';
+
+    const REFRESH_INTERVAL_MS = 60000;
+
+    const bell = document.getElementById('notification-bell');
+    if (!bell) return;
+
+    const countBadge = document.getElementById('notification-count');
+    const list = document.getElementById('notification-list');
+
+    function describe(notification) {
+        if (notification.message) {
+            return (notification.actor_name || 'Someone') + ' ' + notification.message;
+        }
+        return notification.kind === 'overdue'
+            ? 'Overdue since ' + notification.due_date
+            : 'Due ' + notification.due_date;
+    }
+
+    function showCount(count) {
+        countBadge.textContent = count > 99 ? '99+' : String(count);
+        countBadge.classList.toggle('d-none', count === 0);
+    }
+
+    async function refreshCount() {
+        try {
+            const response = await fetch('/api/notifications/unread-count');
+            if (!response.ok) return;
+            const data = await response.json();
+            showCount(data.count);
+        } catch (e) {
+            console.error('Could not load notifications:', e);
+        }
+    }
+
+    function renderItem(notification) {
+        const item = document.createElement('a');
+        item.className = 'dropdown-item';
+        // Changes to the user's role name a board instead of a card
+        item.href = notification.card_id
+            ? '/cards/' + notification.card_id
+            : (notification.kind === 'removed_from_board' ? '/boards' : '/boards/' + notification.board_id);
+        if (!notification.read_at) {
+            item.classList.add('fw-semibold');
+        }
+
+        const title = document.createElement('div');
+        title.className = 'text-truncate';
+        title.textContent = notification.card_title || notification.board_name;
+        const detail = document.createElement('small');
+        detail.className = notification.kind === 'overdue' ? 'text-danger' : 'text-muted';
+        detail.textContent = describe(notification);
+        item.append(title, detail);
+
+        item.addEventListener('click', function() {
+            if (!notification.read_at) {
+                fetch('/api/notifications/' + notification.id + '/read', {
+                    method: 'POST',
+                    keepalive: true
+                });
+            }
+        });
+        return item;
+    }
+
+    async function loadList() {
+        try {
+            const response = await fetch('/api/notifications');
+            if (!response.ok) return;
+            const notifications = await response.json();
+            list.replaceChildren();
+            if (notifications.length === 0) {
+                const empty = document.createElement('span');
+                empty.className = 'dropdown-item-text text-muted';
+                empty.textContent = 'No notifications';
+                list.append(empty);
+                return;
+            }
+            notifications.forEach(function(notification) {
+                list.append(renderItem(notification));
+            });
+        } catch (e) {
+            console.error('Could not load notifications:', e);
+        }
+    }
+
+    bell.addEventListener('show.bs.dropdown', loadList);
+
+    document.getElementById('notification-read-all').addEventListener('click', async function(event) {
+        event.stopPropagation();
+        const response = await fetch('/api/notifications/read-all', { method: 'POST' });
+        if (response.ok) {
+            showCount(0);
+            loadList();
+        }
+    });
+
+    refreshCount();
+    setInterval(refreshCount, REFRESH_INTERVAL_MS);
+})();
@@ -1,14 +1,22 @@
-// Theme switching with localStorage persistence and system preference detection
+// Theme switching: the server renders the user's saved theme on <html data-theme>.
+// "system" falls back to localStorage or the system preference.
 
 (function() {
     'use strict';
 
     const STORAGE_KEY = 'theme';
+    const SYSTEM = 'system';
     const DARK = 'dark';
     const LIGHT = 'light';
 
-    // Get theme from localStorage or system preference
+    // Theme saved server-side for the current user ("system" when anonymous)
+    const serverTheme = document.documentElement.getAttribute('data-theme') || SYSTEM;
+
+    // Get theme from the server, localStorage or system preference
     function getPreferredTheme() {
+        if (serverTheme !== SYSTEM) {
+            return serverTheme;
+        }
         const stored = localStorage.getItem(STORAGE_KEY);
         if (stored) {
             return stored;
@@ -23,7 +31,6 @@
     // Apply theme to document
     function setTheme(theme) {
         document.documentElement.setAttribute('data-theme', theme);
-        localStorage.setItem(STORAGE_KEY, theme);
         updateToggleIcon(theme);
     }
 
@@ -33,16 +40,25 @@
         if (toggle) {
             const icon = toggle.querySelector('i');
             if (icon) {
-                icon.className = theme === DARK ? 'bi bi-sun-fill' : 'bi bi-moon-fill';
+                icon.className = theme === LIGHT ? 'bi bi-moon-fill' : 'bi bi-sun-fill';
             }
         }
     }
 
+    // Save the theme for the logged-in user; anonymous requests are simply rejected
+    function saveTheme(theme) {
+        const body = new URLSearchParams({ theme: theme });
+        fetch('/settings/theme', { method: 'POST', body: body, redirect: 'manual' })
+            .catch(function() {});
+    }
+
     // Toggle between light and dark
     function toggleTheme() {
         const current = document.documentElement.getAttribute('data-theme') || LIGHT;
-        const next = current === DARK ? LIGHT : DARK;
+        const next = current === LIGHT ? DARK : LIGHT;
         setTheme(next);
+        localStorage.setItem(STORAGE_KEY, next);
+        saveTheme(next);
     }
 
     // Initialize theme on page load
@@ -63,8 +79,8 @@
         // Listen for system preference changes
         if (window.matchMedia) {
             window.matchMedia('(prefers-color-scheme: dark)').addEventListener('change', function(e) {
-                // Only auto-switch if user hasn't manually set a preference
-                if (!localStorage.getItem(STORAGE_KEY)) {
+                // Only auto-switch if the user hasn't picked a theme
+                if (serverTheme === SYSTEM && !localStorage.getItem(STORAGE_KEY)) {
                     setTheme(e.matches ? DARK : LIGHT);
                 }
             });
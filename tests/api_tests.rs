@@ -1,6 +1,9 @@
+// Some assertions predate the clippy::len_zero lint
+#![allow(clippy::len_zero)]
+
 use axum_test::TestServer;
 use cookie::Cookie;
-use personal_os::{create_router, state::AppState, test_utils};
+use personal_os::{auth::TrustedProxies, create_router, state::AppState, test_utils};
 use serde_json::{json, Value};
 
 async fn setup_server() -> TestServer {
@@ -277,6 +280,565 @@ mod auth_tests {
 
         response.assert_status_ok();
     }
+
+    async fn create_token(server: &TestServer, session: &str, scope: &str) -> String {
+        server
+            .post("/api/auth/tokens")
+            .add_cookie(session_cookie(session))
+            .json(&json!({"name": scope, "scope": scope}))
+            .await
+            .json::<Value>()["token"]
+            .as_str()
+            .unwrap()
+            .to_string()
+    }
+
+    #[tokio::test]
+    async fn test_token_records_last_use() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+        let token = create_token(&server, &session, "Read").await;
+
+        let tokens: Value = server
+            .get("/api/auth/tokens")
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert!(tokens[0]["last_used_at"].is_null());
+        assert!(tokens[0]["last_used_ip"].is_null());
+
+        server
+            .get("/api/boards")
+            .add_header("Authorization", &format!("Bearer {}", token))
+            .await
+            .assert_status_ok();
+        let tokens: Value = server
+            .get("/api/auth/tokens")
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert!(tokens[0]["last_used_at"].is_string());
+        assert_eq!(tokens[0]["last_used_ip"], "127.0.0.1");
+    }
+
+    #[tokio::test]
+    async fn test_list_and_revoke_sessions() {
+        let server = setup_server().await;
+        let credentials = json!({
+            "email": format!("test_{}@example.com", uuid::Uuid::new_v4()),
+            "password": "testpassword123",
+            "name": "Test User"
+        });
+        server.post("/api/auth/register").json(&credentials).await;
+        let mut sessions = Vec::new();
+        for _ in 0..3 {
+            let response = server.post("/api/auth/login").json(&credentials).await;
+            sessions.push(response.cookie("session").value().to_string());
+        }
+
+        let listed: Value = server
+            .get("/api/auth/sessions")
+            .add_cookie(session_cookie(&sessions[0]))
+            .await
+            .json();
+        let listed = listed.as_array().unwrap();
+        assert_eq!(listed.len(), 3);
+        assert_eq!(listed.iter().filter(|s| s["current"] == true).count(), 1);
+        assert!(listed.iter().all(|s| s.get("token").is_none()));
+
+        // Log out one other session, then all the rest
+        let other = listed.iter().find(|s| s["current"] == false).unwrap();
+        server
+            .delete(&format!(
+                "/api/auth/sessions/{}",
+                other["id"].as_str().unwrap()
+            ))
+            .add_cookie(session_cookie(&sessions[0]))
+            .await
+            .assert_status_ok();
+        let revoked: Value = server
+            .delete("/api/auth/sessions")
+            .add_cookie(session_cookie(&sessions[0]))
+            .await
+            .json();
+        assert_eq!(revoked["revoked"], 1);
+
+        for (i, session) in sessions.iter().enumerate() {
+            let response = server
+                .get("/api/auth/sessions")
+                .add_cookie(session_cookie(session))
+                .await;
+            if i == 0 {
+                response.assert_status_ok();
+            } else {
+                response.assert_status_unauthorized();
+            }
+        }
+
+        // The security page lists the remaining session
+        let page = server
+            .get("/settings/security")
+            .add_cookie(session_cookie(&sessions[0]))
+            .await;
+        page.assert_status_ok();
+        assert!(page.text().contains("This session"));
+    }
+
+    #[tokio::test]
+    async fn test_read_token_cannot_change_anything() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+        let token = create_token(&server, &session, "Read").await;
+        let bearer = format!("Bearer {}", token);
+
+        let board: Value = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Scoped"}))
+            .await
+            .json();
+        let board_id = board["id"].as_str().unwrap();
+        let column: Value = server
+            .post(&format!("/api/boards/{}/columns", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "To Do"}))
+            .await
+            .json();
+        let column_id = column["id"].as_str().unwrap();
+        let card: Value = server
+            .post(&format!("/api/columns/{}/cards", column_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"title": "Read me"}))
+            .await
+            .json();
+        let card_id = card["id"].as_str().unwrap();
+        let tag: Value = server
+            .post(&format!("/api/boards/{}/tags", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "docs", "color": "#0d6efd"}))
+            .await
+            .json();
+        let tag_id = tag["id"].as_str().unwrap();
+
+        // Reading works
+        for path in [
+            "/api/boards".to_string(),
+            format!("/api/boards/{}", board_id),
+            format!("/api/boards/{}/cards", board_id),
+            format!("/api/cards/{}", card_id),
+            format!("/api/boards/{}/tags", board_id),
+            format!("/api/cards/{}/comments", card_id),
+            "/api/chat/history".to_string(),
+        ] {
+            server
+                .get(&path)
+                .add_header("Authorization", &bearer)
+                .await
+                .assert_status_ok();
+        }
+
+        // Boards, columns, cards, tags, comments and chat all refuse changes
+        let requests = [
+            server.post("/api/boards").json(&json!({"name": "New"})),
+            server
+                .put(&format!("/api/boards/{}", board_id))
+                .json(&json!({"name": "Renamed"})),
+            server.delete(&format!("/api/boards/{}", board_id)),
+            server
+                .post(&format!("/api/boards/{}/columns", board_id))
+                .json(&json!({"name": "Done"})),
+            server
+                .put(&format!("/api/columns/{}", column_id))
+                .json(&json!({"name": "Doing"})),
+            server.delete(&format!("/api/columns/{}", column_id)),
+            server
+                .post(&format!("/api/columns/{}/cards", column_id))
+                .json(&json!({"title": "New"})),
+            server
+                .patch(&format!("/api/cards/{}", card_id))
+                .json(&json!({"title": "Changed"})),
+            server.delete(&format!("/api/cards/{}", card_id)),
+            server
+                .post(&format!("/api/boards/{}/tags", board_id))
+                .json(&json!({"name": "new", "color": "#000000"})),
+            server.post(&format!("/api/cards/{}/tags/{}", card_id, tag_id)),
+            server.delete(&format!("/api/tags/{}", tag_id)),
+            server
+                .post(&format!("/api/cards/{}/comments", card_id))
+                .json(&json!({"body": "Hello"})),
+            server
+                .post(&format!("/api/boards/{}/chat", board_id))
+                .json(&json!({"message": "Add a card"})),
+            server
+                .post("/api/chat")
+                .json(&json!({"message": "Add a card"})),
+            server
+                .post("/api/auth/tokens")
+                .json(&json!({"name": "More", "scope": "Write"})),
+        ];
+        for request in requests {
+            request
+                .add_header("Authorization", &bearer)
+                .await
+                .assert_status_forbidden();
+        }
+
+        let card: Value = server
+            .get(&format!("/api/cards/{}", card_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(card["title"], "Read me");
+    }
+
+    #[tokio::test]
+    async fn test_write_token_changes_data_but_not_its_scope() {
+        let (server, state) = setup_server_with_state().await;
+        let user_id = test_utils::create_test_user(&state, "script@example.com", "Script").await;
+        let session = test_utils::create_test_session(&state, user_id).await;
+        let bearer = format!("Bearer {}", create_token(&server, &session, "Write").await);
+
+        server
+            .post("/api/boards")
+            .add_header("Authorization", &bearer)
+            .json(&json!({"name": "From a script"}))
+            .await
+            .assert_status_ok();
+        server
+            .post("/api/auth/tokens")
+            .add_header("Authorization", &bearer)
+            .json(&json!({"name": "Reader", "scope": "Read"}))
+            .await
+            .assert_status_ok();
+        server
+            .post("/api/auth/tokens")
+            .add_header("Authorization", &bearer)
+            .json(&json!({"name": "Escalated", "scope": "Admin"}))
+            .await
+            .assert_status_forbidden();
+
+        // Admin endpoints need an admin token, even for administrators
+        state.users.set_admin(user_id, true).await.unwrap();
+        server
+            .get("/api/admin/integrity")
+            .add_header("Authorization", &bearer)
+            .await
+            .assert_status_forbidden();
+        let admin_bearer = format!("Bearer {}", create_token(&server, &session, "Admin").await);
+        server
+            .get("/api/admin/integrity")
+            .add_header("Authorization", &admin_bearer)
+            .await
+            .assert_status_ok();
+    }
+
+    async fn login(server: &TestServer, remember: bool) -> axum_test::TestResponse {
+        let email = format!("test_{}@example.com", uuid::Uuid::new_v4());
+        server
+            .post("/api/auth/register")
+            .json(&json!({
+                "email": email,
+                "password": "testpassword123",
+                "name": "Returning User"
+            }))
+            .await;
+
+        server
+            .post("/api/auth/login")
+            .json(&json!({
+                "email": email,
+                "password": "testpassword123",
+                "remember": remember
+            }))
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_remember_me_keeps_session_longer() {
+        let (server, state) = setup_server_with_state().await;
+
+        let response = login(&server, false).await;
+        let cookie = response.cookie("session");
+        assert!(cookie.max_age().is_none());
+        let session = state
+            .sessions
+            .find_by_token(cookie.value())
+            .await
+            .unwrap()
+            .unwrap();
+        let left = session.expires_at - chrono::Utc::now();
+        assert!(left > chrono::Duration::days(6) && left <= chrono::Duration::days(7));
+        assert!(!session.remember);
+
+        let response = login(&server, true).await;
+        let cookie = response.cookie("session");
+        assert_eq!(cookie.max_age(), Some(cookie::time::Duration::days(30)));
+        let session = state
+            .sessions
+            .find_by_token(cookie.value())
+            .await
+            .unwrap()
+            .unwrap();
+        let left = session.expires_at - chrono::Utc::now();
+        assert!(left > chrono::Duration::days(29) && left <= chrono::Duration::days(30));
+        assert!(session.remember);
+    }
+
+    #[tokio::test]
+    async fn test_sessions_slide_on_activity_and_expire() {
+        let (server, state) = setup_server_with_state().await;
+        let token = login(&server, true)
+            .await
+            .cookie("session")
+            .value()
+            .to_string();
+        let session = state.sessions.find_by_token(&token).await.unwrap().unwrap();
+
+        // Activity extends a session that is about to expire and resends its cookie
+        state
+            .sessions
+            .set_expires_at(session.id, chrono::Utc::now() + chrono::Duration::hours(1))
+            .await
+            .unwrap();
+        let response = server
+            .get("/api/boards")
+            .add_cookie(session_cookie(&token))
+            .await;
+        response.assert_status_ok();
+        assert_eq!(response.cookie("session").value(), token);
+        let renewed = state.sessions.find_by_token(&token).await.unwrap().unwrap();
+        assert!(renewed.expires_at - chrono::Utc::now() > chrono::Duration::days(29));
+
+        // A recently extended session is left alone
+        let response = server
+            .get("/api/boards")
+            .add_cookie(session_cookie(&token))
+            .await;
+        assert!(response.maybe_cookie("session").is_none());
+
+        // Expired sessions are refused, then purged
+        state
+            .sessions
+            .set_expires_at(
+                session.id,
+                chrono::Utc::now() - chrono::Duration::minutes(1),
+            )
+            .await
+            .unwrap();
+        server
+            .get("/api/boards")
+            .add_cookie(session_cookie(&token))
+            .await
+            .assert_status_unauthorized();
+        assert_eq!(state.sessions.delete_expired().await.unwrap(), 1);
+    }
+
+    async fn login_from(server: &TestServer, forwarded_for: &str) -> String {
+        let email = format!("test_{}@example.com", uuid::Uuid::new_v4());
+        server
+            .post("/api/auth/register")
+            .json(&json!({
+                "email": email,
+                "password": "testpassword123",
+                "name": "Proxied User"
+            }))
+            .await;
+
+        server
+            .post("/api/auth/login")
+            .add_header("X-Forwarded-For", forwarded_for)
+            .json(&json!({
+                "email": email,
+                "password": "testpassword123"
+            }))
+            .await
+            .cookie("session")
+            .value()
+            .to_string()
+    }
+
+    #[tokio::test]
+    async fn test_login_records_forwarded_client_ip() {
+        let mut state = test_utils::create_test_state().await;
+        state.trusted_proxies = TrustedProxies::parse("127.0.0.1");
+        let server = TestServer::new(create_router(state.clone())).unwrap();
+
+        let token = login_from(&server, "6.6.6.6, 203.0.113.5").await;
+
+        let session = state.sessions.find_by_token(&token).await.unwrap().unwrap();
+        assert_eq!(session.ip_address.as_deref(), Some("203.0.113.5"));
+    }
+
+    #[tokio::test]
+    async fn test_login_ignores_forwarded_for_without_trusted_proxy() {
+        let (server, state) = setup_server_with_state().await;
+
+        let token = login_from(&server, "203.0.113.5").await;
+
+        let session = state.sessions.find_by_token(&token).await.unwrap().unwrap();
+        assert_eq!(session.ip_address.as_deref(), Some("127.0.0.1"));
+    }
+
+    /// Register and log in twice, as on two devices
+    async fn register_with_two_sessions(server: &TestServer, email: &str) -> (String, String) {
+        server
+            .post("/api/auth/register")
+            .json(&json!({"email": email, "password": "testpassword123", "name": "Sam"}))
+            .await
+            .assert_status_ok();
+        let mut sessions = Vec::new();
+        for _ in 0..2 {
+            let response = server
+                .post("/api/auth/login")
+                .json(&json!({"email": email, "password": "testpassword123"}))
+                .await;
+            sessions.push(response.cookie("session").value().to_string());
+        }
+        (sessions.remove(0), sessions.remove(0))
+    }
+
+    #[tokio::test]
+    async fn test_change_password_logs_out_other_sessions() {
+        let server = setup_server().await;
+        let (session, other) = register_with_two_sessions(&server, "sam@example.com").await;
+
+        let response = server
+            .post("/api/auth/password")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"current_password": "wrong", "new_password": "newpassword456"}))
+            .await;
+        response.assert_status_bad_request();
+        assert_eq!(
+            response.json::<Value>()["message"],
+            "Current password is incorrect"
+        );
+        server
+            .post("/api/auth/password")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"current_password": "testpassword123", "new_password": "short"}))
+            .await
+            .assert_status(axum::http::StatusCode::UNPROCESSABLE_ENTITY);
+
+        server
+            .post("/api/auth/password")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"current_password": "testpassword123", "new_password": "newpassword456"}))
+            .await
+            .assert_status_ok();
+
+        // Only the session that changed the password stays logged in
+        server
+            .get("/api/settings")
+            .add_cookie(session_cookie(&session))
+            .await
+            .assert_status_ok();
+        server
+            .get("/api/settings")
+            .add_cookie(session_cookie(&other))
+            .await
+            .assert_status_unauthorized();
+
+        server
+            .post("/api/auth/login")
+            .json(&json!({"email": "sam@example.com", "password": "testpassword123"}))
+            .await
+            .assert_status_unauthorized();
+        server
+            .post("/api/auth/login")
+            .json(&json!({"email": "sam@example.com", "password": "newpassword456"}))
+            .await
+            .assert_status_ok();
+
+        // The settings page form reports mistakes on the page
+        let page = server
+            .post("/settings/password")
+            .add_cookie(session_cookie(&session))
+            .form(&[
+                ("current_password", "wrong"),
+                ("new_password", "another789"),
+            ])
+            .await;
+        page.assert_status_ok();
+        assert!(page.text().contains("Current password is incorrect"));
+    }
+
+    /// Token of the last email confirming a new address
+    fn email_change_token(state: &AppState) -> String {
+        let sent = state.email.sent();
+        let body = &sent.last().unwrap().body;
+        let start = body.find("/settings/email/").unwrap() + "/settings/email/".len();
+        body[start..].split_whitespace().next().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn test_change_email_after_confirmation() {
+        let (server, state) = setup_server_with_state().await;
+        let (session, _) = register_with_two_sessions(&server, "old@example.com").await;
+        test_utils::create_test_user(&state, "taken@example.com", "Taken").await;
+
+        let change = |email: &'static str, password: &'static str| {
+            server
+                .post("/api/auth/email")
+                .add_cookie(session_cookie(&session))
+                .json(&json!({"new_email": email, "current_password": password}))
+        };
+        change("new@example.com", "wrong")
+            .await
+            .assert_status_bad_request();
+        change("taken@example.com", "testpassword123")
+            .await
+            .assert_status_bad_request();
+        assert!(state.email.sent().is_empty());
+
+        let response = change("new@example.com", "testpassword123").await;
+        response.assert_status_ok();
+        assert_eq!(response.json::<Value>()["new_email"], "new@example.com");
+        let sent = state.email.sent();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].to, "new@example.com");
+
+        // Nothing changes until the new address is confirmed
+        server
+            .post("/api/auth/login")
+            .json(&json!({"email": "old@example.com", "password": "testpassword123"}))
+            .await
+            .assert_status_ok();
+
+        let token = email_change_token(&state);
+        let user: Value = server
+            .post("/api/auth/email/confirm")
+            .json(&json!({"token": token}))
+            .await
+            .json();
+        assert_eq!(user["email"], "new@example.com");
+        server
+            .post("/api/auth/email/confirm")
+            .json(&json!({"token": token}))
+            .await
+            .assert_status_not_found();
+        server
+            .post("/api/auth/login")
+            .json(&json!({"email": "new@example.com", "password": "testpassword123"}))
+            .await
+            .assert_status_ok();
+
+        // The link in the email works from the settings page too
+        server
+            .post("/settings/email")
+            .add_cookie(session_cookie(&session))
+            .form(&[
+                ("new_email", "newest@example.com"),
+                ("current_password", "testpassword123"),
+            ])
+            .await
+            .assert_status_ok();
+        let page = server
+            .get(&format!("/settings/email/{}", email_change_token(&state)))
+            .add_cookie(session_cookie(&session))
+            .await
+            .text();
+        assert!(page.contains("Your email address is now newest@example.com"));
+    }
 }
 
 // ============================================================================
@@ -427,63 +989,521 @@ mod board_tests {
     }
 
     #[tokio::test]
-    async fn test_delete_board() {
+    async fn test_update_board_chat_column_policy() {
         let server = setup_server().await;
         let session = register_and_login(&server).await;
 
         let create_response = server
             .post("/api/boards")
             .add_cookie(session_cookie(&session))
-            .json(&json!({"name": "Board to Delete"}))
+            .json(&json!({"name": "Policy Board"}))
             .await;
 
-        let board_id = create_response.json::<Value>()["id"]
-            .as_str()
-            .unwrap()
-            .to_string();
+        let body: Value = create_response.json();
+        assert_eq!(body["chat_column_policy"], "reject");
+        let board_id = body["id"].as_str().unwrap().to_string();
 
         let response = server
-            .delete(&format!("/api/boards/{}", board_id))
+            .put(&format!("/api/boards/{}", board_id))
             .add_cookie(session_cookie(&session))
+            .json(&json!({"chat_column_policy": "auto_create"}))
             .await;
 
         response.assert_status_ok();
+        let body: Value = response.json();
+        assert_eq!(body["chat_column_policy"], "auto_create");
+        assert_eq!(body["name"], "Policy Board");
 
-        let get_response = server
-            .get(&format!("/api/boards/{}", board_id))
+        let response = server
+            .put(&format!("/api/boards/{}", board_id))
             .add_cookie(session_cookie(&session))
+            .json(&json!({"chat_column_policy": "guess"}))
             .await;
 
-        get_response.assert_status_not_found();
+        response.assert_status_unprocessable_entity();
     }
 
     #[tokio::test]
-    async fn test_unauthorized_board_access() {
-        let server = setup_server().await;
-
-        let response = server.get("/api/boards").await;
-
-        response.assert_status_unauthorized();
-    }
-}
-
-// ============================================================================
-// Column Tests
-// ============================================================================
+    async fn test_board_default_column() {
+        let (server, state) = setup_server_with_state().await;
+        let session = register_and_login(&server).await;
 
-mod column_tests {
-    use super::*;
+        let mut board_ids = Vec::new();
+        let mut column_ids = Vec::new();
+        for name in ["Default Board", "Other Board"] {
+            let board_id = server
+                .post("/api/boards")
+                .add_cookie(session_cookie(&session))
+                .json(&json!({"name": name}))
+                .await
+                .json::<Value>()["id"]
+                .as_str()
+                .unwrap()
+                .to_string();
+            let column_id = server
+                .post(&format!("/api/boards/{}/columns", board_id))
+                .add_cookie(session_cookie(&session))
+                .json(&json!({"name": "Triage"}))
+                .await
+                .json::<Value>()["id"]
+                .as_str()
+                .unwrap()
+                .to_string();
+            board_ids.push(board_id);
+            column_ids.push(column_id);
+        }
+
+        // A column of another board is rejected
+        server
+            .put(&format!("/api/boards/{}", board_ids[0]))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"default_column_id": column_ids[1]}))
+            .await
+            .assert_status_unprocessable_entity();
 
-    async fn create_board(server: &TestServer, session: &str) -> String {
         let response = server
-            .post("/api/boards")
-            .add_cookie(session_cookie(session))
-            .json(&json!({"name": "Test Board"}))
+            .put(&format!("/api/boards/{}", board_ids[0]))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"default_column_id": column_ids[0]}))
             .await;
+        response.assert_status_ok();
+        assert_eq!(response.json::<Value>()["default_column_id"], column_ids[0]);
+
+        // Assigning without a column places the card in the default column
+        let card_id = server
+            .post("/api/cards")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"title": "Needs triage"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        server
+            .post(&format!("/api/cards/{}/boards/{}", card_id, board_ids[0]))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({}))
+            .await
+            .assert_status_ok();
+
+        let positions = state
+            .card_boards
+            .list_positions(
+                board_ids[0].parse().unwrap(),
+                Some(column_ids[0].parse().unwrap()),
+            )
+            .await
+            .unwrap();
+        assert_eq!(positions.len(), 1);
+        assert_eq!(positions[0].id.to_string(), card_id);
+
+        // Other fields leave the default alone; null clears it
+        let response = server
+            .put(&format!("/api/boards/{}", board_ids[0]))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Renamed"}))
+            .await;
+        assert_eq!(response.json::<Value>()["default_column_id"], column_ids[0]);
+
+        let response = server
+            .put(&format!("/api/boards/{}", board_ids[0]))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"default_column_id": null}))
+            .await;
+        assert!(response.json::<Value>()["default_column_id"].is_null());
+    }
+
+    #[tokio::test]
+    async fn test_delete_board() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+
+        let create_response = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Board to Delete"}))
+            .await;
+
+        let board_id = create_response.json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let response = server
+            .delete(&format!("/api/boards/{}", board_id))
+            .add_cookie(session_cookie(&session))
+            .await;
+
+        response.assert_status_ok();
+
+        let get_response = server
+            .get(&format!("/api/boards/{}", board_id))
+            .add_cookie(session_cookie(&session))
+            .await;
+
+        get_response.assert_status_not_found();
+    }
+
+    #[tokio::test]
+    async fn test_sharing_a_board_emails_the_user() {
+        let (server, state) = setup_server_with_state().await;
+        let owner_id = test_utils::create_test_user(&state, "owner@example.com", "Owner").await;
+        let owner_session = test_utils::create_test_session(&state, owner_id).await;
+        let member_id = test_utils::create_test_user(&state, "member@example.com", "Member").await;
+
+        let board_id = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"name": "Team"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        server
+            .post(&format!("/api/boards/{}/permissions", board_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"user_id": member_id, "role": "Editor"}))
+            .await
+            .assert_status_ok();
+
+        let sent = state.email.sent();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].to, "member@example.com");
+        assert_eq!(sent[0].subject, "Owner shared \"Team\" with you");
+        assert!(sent[0]
+            .body
+            .contains(&format!("http://localhost:3000/boards/{}", board_id)));
+    }
+
+    #[tokio::test]
+    async fn test_membership_changes_notify_the_user() {
+        let (server, state) = setup_server_with_state().await;
+        let owner_id = test_utils::create_test_user(&state, "owner@example.com", "Owner").await;
+        let owner_session = test_utils::create_test_session(&state, owner_id).await;
+        let member_id = test_utils::create_test_user(&state, "member@example.com", "Member").await;
+        let member_session = test_utils::create_test_session(&state, member_id).await;
+
+        let board_id = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"name": "Team"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        for role in ["Editor", "Editor", "Reader"] {
+            server
+                .post(&format!("/api/boards/{}/permissions", board_id))
+                .add_cookie(session_cookie(&owner_session))
+                .json(&json!({"user_id": member_id, "role": role}))
+                .await
+                .assert_status_ok();
+        }
+
+        // Granting the role the user already has changes nothing
+        let activity: Value = server
+            .get(&format!("/api/boards/{}/activity", board_id))
+            .add_cookie(session_cookie(&owner_session))
+            .await
+            .json();
+        assert_eq!(activity[0]["event"], "member_role_changed");
+        assert_eq!(activity[0]["entity_id"], member_id.to_string());
+        assert_eq!(activity[0]["actor_name"], "Owner");
+        assert_eq!(
+            activity[0]["details"]["role"],
+            json!({"from": "editor", "to": "reader"})
+        );
+        assert_eq!(activity[1]["event"], "member_added");
+        assert_eq!(activity[1]["details"]["role"], "editor");
+
+        server
+            .delete(&format!(
+                "/api/boards/{}/permissions/{}",
+                board_id, member_id
+            ))
+            .add_cookie(session_cookie(&owner_session))
+            .await
+            .assert_status_ok();
+        let activity: Value = server
+            .get(&format!("/api/boards/{}/activity", board_id))
+            .add_cookie(session_cookie(&owner_session))
+            .await
+            .json();
+        assert_eq!(activity[0]["event"], "member_removed");
+        assert_eq!(activity[0]["details"]["name"], "Member");
+
+        let notifications: Value = server
+            .get("/api/notifications")
+            .add_cookie(session_cookie(&member_session))
+            .await
+            .json();
+        let kinds: Vec<&str> = notifications
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|n| n["kind"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            kinds,
+            vec!["removed_from_board", "board_role_changed", "added_to_board"]
+        );
+        assert_eq!(notifications[1]["board_id"], board_id);
+        assert_eq!(notifications[1]["board_name"], "Team");
+        assert_eq!(notifications[1]["card_id"], Value::Null);
+        assert_eq!(notifications[1]["actor_name"], "Owner");
+        assert_eq!(notifications[1]["message"], "gave you reader access");
+
+        let subjects: Vec<String> = state.email.sent().into_iter().map(|e| e.subject).collect();
+        assert_eq!(
+            subjects,
+            vec![
+                "Owner shared \"Team\" with you",
+                "Your role on \"Team\" changed",
+                "Owner removed you from \"Team\"",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unauthorized_board_access() {
+        let server = setup_server().await;
+
+        let response = server.get("/api/boards").await;
+
+        response.assert_status_unauthorized();
+    }
+
+    #[tokio::test]
+    async fn test_repair_positions() {
+        let (server, state) = setup_server_with_state().await;
+        let session = register_and_login(&server).await;
+        let board: Value = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Repairs", "template": "kanban"}))
+            .await
+            .json();
+        let board_id = board["id"].as_str().unwrap();
+        let details: Value = server
+            .get(&format!("/api/boards/{}", board_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        let todo = details["columns"][0]["id"].as_str().unwrap();
+        for title in ["A", "B"] {
+            server
+                .post(&format!("/api/columns/{}/cards", todo))
+                .add_cookie(session_cookie(&session))
+                .json(&json!({"title": title}))
+                .await
+                .assert_status_ok();
+        }
+
+        // Both cards on the same slot
+        sqlx::query("UPDATE cards SET position = 3 WHERE column_id = $1")
+            .bind(uuid::Uuid::parse_str(todo).unwrap())
+            .execute(state.pool.as_ref())
+            .await
+            .unwrap();
+
+        let path = format!("/api/boards/{}/positions/repair", board_id);
+        let repair: Value = server
+            .post(&path)
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(repair, json!({"columns": 0, "cards": 2}));
+        let details: Value = server
+            .get(&format!("/api/boards/{}", board_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        let cards = &details["columns"][0]["cards"];
+        assert_eq!(cards[0]["title"], "A");
+        assert_eq!(cards[0]["position"], 0);
+        assert_eq!(cards[1]["position"], 1);
+
+        let repair: Value = server
+            .post(&path)
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(repair, json!({"columns": 0, "cards": 0}));
+
+        let other = register_and_login(&server).await;
+        server
+            .post(&path)
+            .add_cookie(session_cookie(&other))
+            .await
+            .assert_status_forbidden();
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_board() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+        let board: Value = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Sprint 12", "description": "Two weeks", "template": "kanban"}))
+            .await
+            .json();
+        let board_id = board["id"].as_str().unwrap();
+        let details: Value = server
+            .get(&format!("/api/boards/{}", board_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        let todo = details["columns"][0]["id"].as_str().unwrap();
+        let done = details["columns"][2]["id"].as_str().unwrap();
+        server
+            .put(&format!("/api/boards/{}", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"default_column_id": done}))
+            .await
+            .assert_status_ok();
+        let tag: Value = server
+            .post(&format!("/api/boards/{}/tags", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "backend", "color": "#112233"}))
+            .await
+            .json();
+
+        let mut ids = Vec::new();
+        for title in ["API", "Docs"] {
+            let card: Value = server
+                .post(&format!("/api/columns/{}/cards", todo))
+                .add_cookie(session_cookie(&session))
+                .json(&json!({"title": title}))
+                .await
+                .json();
+            ids.push(card["id"].as_str().unwrap().to_string());
+        }
+        server
+            .post(&format!(
+                "/api/cards/{}/tags/{}",
+                ids[0],
+                tag["id"].as_str().unwrap()
+            ))
+            .add_cookie(session_cookie(&session))
+            .await
+            .assert_status_ok();
+        server
+            .post(&format!("/api/cards/{}/links", ids[1]))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"card_id": ids[0], "link_type": "blocked_by"}))
+            .await
+            .assert_status_ok();
+        server
+            .post(&format!("/api/cards/{}/comments", ids[0]))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"body": "Versioned endpoints"}))
+            .await
+            .assert_status_ok();
+        let archived: Value = server
+            .post(&format!("/api/columns/{}/cards", todo))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"title": "Old"}))
+            .await
+            .json();
+        server
+            .post(&format!(
+                "/api/cards/{}/archive",
+                archived["id"].as_str().unwrap()
+            ))
+            .add_cookie(session_cookie(&session))
+            .await
+            .assert_status_ok();
+
+        let response = server
+            .post(&format!("/api/boards/{}/duplicate", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"include_comments": true}))
+            .await;
+        response.assert_status_ok();
+        let copy: Value = response.json();
+        assert_ne!(copy["id"], board["id"]);
+        assert_eq!(copy["name"], "Sprint 12 (copy)");
+        assert_eq!(copy["description"], "Two weeks");
+        assert_eq!(copy["role"], "owner");
+
+        let copy: Value = server
+            .get(&format!("/api/boards/{}", copy["id"].as_str().unwrap()))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        let columns = copy["columns"].as_array().unwrap();
+        let names: Vec<&str> = columns
+            .iter()
+            .map(|c| c["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, ["To Do", "In Progress", "Done"]);
+        assert_ne!(columns[0]["id"], todo);
+        assert_eq!(copy["default_column_id"], columns[2]["id"]);
+
+        // Archived cards stay behind; the others keep their tags and links
+        let cards = columns[0]["cards"].as_array().unwrap();
+        let titles: Vec<&str> = cards.iter().map(|c| c["title"].as_str().unwrap()).collect();
+        assert_eq!(titles, ["API", "Docs"]);
+        assert_ne!(cards[0]["id"], ids[0].as_str());
+        assert_eq!(cards[0]["tags"][0]["name"], "backend");
+        assert_ne!(cards[0]["tags"][0]["id"], tag["id"]);
+        let links: Vec<Value> = server
+            .get(&format!(
+                "/api/cards/{}/links",
+                cards[1]["id"].as_str().unwrap()
+            ))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0]["card"]["id"], cards[0]["id"]);
+        let comments: Value = server
+            .get(&format!(
+                "/api/cards/{}/comments",
+                cards[0]["id"].as_str().unwrap()
+            ))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(comments[0]["body"], "Versioned endpoints");
+
+        // The original is untouched
+        let original: Value = server
+            .get(&format!("/api/boards/{}", board_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(original["columns"][0]["cards"].as_array().unwrap().len(), 2);
+
+        let other = register_and_login(&server).await;
+        server
+            .post(&format!("/api/boards/{}/duplicate", board_id))
+            .add_cookie(session_cookie(&other))
+            .await
+            .assert_status_forbidden();
+    }
+}
+
+// ============================================================================
+// Column Tests
+// ============================================================================
+
+mod column_tests {
+    use super::*;
+
+    async fn create_board(server: &TestServer, session: &str) -> String {
+        let response = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(session))
+            .json(&json!({"name": "Test Board"}))
+            .await;
+
+        response.json::<Value>()["id"].as_str().unwrap().to_string()
+    }
 
-        response.json::<Value>()["id"].as_str().unwrap().to_string()
-    }
-
     #[tokio::test]
     async fn test_create_column() {
         let server = setup_server().await;
@@ -637,77 +1657,392 @@ mod column_tests {
         let body: Value = response.json();
         assert_eq!(body["position"], 1);
     }
-}
-
-// ============================================================================
-// Card Tests
-// ============================================================================
-
-mod card_tests {
-    use super::*;
-
-    async fn create_board_and_column(server: &TestServer, session: &str) -> (String, String) {
-        let board_response = server
-            .post("/api/boards")
-            .add_cookie(session_cookie(session))
-            .json(&json!({"name": "Test Board"}))
-            .await;
-        let board_id = board_response.json::<Value>()["id"]
-            .as_str()
-            .unwrap()
-            .to_string();
-
-        let column_response = server
-            .post(&format!("/api/boards/{}/columns", board_id))
-            .add_cookie(session_cookie(session))
-            .json(&json!({"name": "To Do"}))
-            .await;
-        let column_id = column_response.json::<Value>()["id"]
-            .as_str()
-            .unwrap()
-            .to_string();
-
-        (board_id, column_id)
-    }
 
     #[tokio::test]
-    async fn test_create_card() {
+    async fn test_wip_limit_warns_or_blocks() {
         let server = setup_server().await;
         let session = register_and_login(&server).await;
-        let (_board_id, column_id) = create_board_and_column(&server, &session).await;
+        let board_id = create_board(&server, &session).await;
+        let mut column_ids = Vec::new();
+        for name in ["Doing", "Done"] {
+            let column: Value = server
+                .post(&format!("/api/boards/{}/columns", board_id))
+                .add_cookie(session_cookie(&session))
+                .json(&json!({"name": name}))
+                .await
+                .json();
+            column_ids.push(column["id"].as_str().unwrap().to_string());
+        }
 
         let response = server
-            .post(&format!("/api/columns/{}/cards", column_id))
+            .put(&format!("/api/columns/{}", column_ids[0]))
             .add_cookie(session_cookie(&session))
-            .json(&json!({
-                "title": "Test Card",
-                "body": "Card description"
-            }))
+            .json(&json!({"wip_limit": 0}))
+            .await;
+        response.assert_status(axum::http::StatusCode::UNPROCESSABLE_ENTITY);
+        let response = server
+            .put(&format!("/api/columns/{}", column_ids[0]))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"wip_limit": 1}))
             .await;
+        response.assert_status_ok();
+        assert_eq!(response.json::<Value>()["wip_limit"], 1);
+
+        let create = |column_id: &str, title: &str| {
+            server
+                .post(&format!("/api/columns/{}/cards", column_id))
+                .add_cookie(session_cookie(&session))
+                .json(&json!({"title": title}))
+        };
 
+        // Within the limit, no warning
+        let response = create(&column_ids[0], "First").await;
         response.assert_status_ok();
-        let body: Value = response.json();
-        assert_eq!(body["title"], "Test Card");
-        assert_eq!(body["body"], "Card description");
-    }
+        assert!(response.maybe_header("x-wip-warning").is_none());
 
-    #[tokio::test]
-    async fn test_create_card_with_visibility() {
-        let server = setup_server().await;
-        let session = register_and_login(&server).await;
-        let (_board_id, column_id) = create_board_and_column(&server, &session).await;
+        // Over the limit, boards warn by default
+        let response = create(&column_ids[0], "Second").await;
+        response.assert_status_ok();
+        assert_eq!(
+            response.header("x-wip-warning"),
+            "Column 'Doing' is over its WIP limit: 2 cards, limit 1"
+        );
 
+        // Blocking boards refuse the card, whether created or moved there
         let response = server
-            .post(&format!("/api/columns/{}/cards", column_id))
+            .put(&format!("/api/boards/{}", board_id))
             .add_cookie(session_cookie(&session))
-            .json(&json!({
-                "title": "Private Card",
-                "visibility": "Private"
-            }))
+            .json(&json!({"wip_limit_policy": "block"}))
             .await;
-
         response.assert_status_ok();
-        let body: Value = response.json();
+        assert_eq!(response.json::<Value>()["wip_limit_policy"], "block");
+        create(&column_ids[0], "Third")
+            .await
+            .assert_status(axum::http::StatusCode::CONFLICT);
+        let card: Value = create(&column_ids[1], "Elsewhere").await.json();
+        server
+            .patch(&format!("/api/cards/{}/move", card["id"].as_str().unwrap()))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"column_id": column_ids[0], "position": 0}))
+            .await
+            .assert_status(axum::http::StatusCode::CONFLICT);
+
+        // Removing the limit lets cards in again
+        server
+            .put(&format!("/api/columns/{}", column_ids[0]))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"wip_limit": null}))
+            .await
+            .assert_status_ok();
+        create(&column_ids[0], "Third").await.assert_status_ok();
+    }
+
+    #[tokio::test]
+    async fn test_wip_limit_counts_placements_and_guards_every_path() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+        let board_id = create_board(&server, &session).await;
+        let mut column_ids = Vec::new();
+        for name in ["Doing", "Done"] {
+            let column: Value = server
+                .post(&format!("/api/boards/{}/columns", board_id))
+                .add_cookie(session_cookie(&session))
+                .json(&json!({"name": name}))
+                .await
+                .json();
+            column_ids.push(column["id"].as_str().unwrap().to_string());
+        }
+        server
+            .put(&format!("/api/columns/{}", column_ids[0]))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"wip_limit": 1}))
+            .await
+            .assert_status_ok();
+        server
+            .put(&format!("/api/boards/{}", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"wip_limit_policy": "block"}))
+            .await
+            .assert_status_ok();
+
+        // A card from another board placed in the column takes the only slot
+        let other_board_id = create_board(&server, &session).await;
+        let other_column: Value = server
+            .post(&format!("/api/boards/{}/columns", other_board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Backlog"}))
+            .await
+            .json();
+        let shared: Value = server
+            .post(&format!(
+                "/api/columns/{}/cards",
+                other_column["id"].as_str().unwrap()
+            ))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"title": "Shared"}))
+            .await
+            .json();
+        server
+            .post(&format!(
+                "/api/cards/{}/boards/{}",
+                shared["id"].as_str().unwrap(),
+                board_id
+            ))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"column_id": column_ids[0]}))
+            .await
+            .assert_status_ok();
+        server
+            .post(&format!("/api/columns/{}/cards", column_ids[0]))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"title": "Second"}))
+            .await
+            .assert_status(axum::http::StatusCode::CONFLICT);
+
+        // Inbox cards cannot be filed into the full column
+        let inbox_card: Value = server
+            .post("/api/cards")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"title": "From the inbox"}))
+            .await
+            .json();
+        server
+            .post(&format!(
+                "/api/cards/{}/boards/{}",
+                inbox_card["id"].as_str().unwrap(),
+                board_id
+            ))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"column_id": column_ids[0]}))
+            .await
+            .assert_status(axum::http::StatusCode::CONFLICT);
+
+        // Nor moved there in bulk, which leaves every card where it was
+        let done_card: Value = server
+            .post(&format!("/api/columns/{}/cards", column_ids[1]))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"title": "Finished"}))
+            .await
+            .json();
+        server
+            .post(&format!("/api/boards/{}/cards/bulk", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"operations": [
+                {"op": "move", "card_id": done_card["id"], "column_id": column_ids[0]},
+            ]}))
+            .await
+            .assert_status(axum::http::StatusCode::CONFLICT);
+        let card: Value = server
+            .get(&format!("/api/cards/{}", done_card["id"].as_str().unwrap()))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(card["column_id"], column_ids[1]);
+    }
+
+    #[tokio::test]
+    async fn test_chat_respects_blocking_wip_limits() {
+        let (server, session, board_id, column_id) = chat_undo_tests::setup(&[
+            r#"{"action": "create_card", "params": {"column": "To Do", "title": "Third"}, "message": "Created"}"#,
+            r#"{"action": "move_card", "params": {"card_title": "Elsewhere", "target_column": "To Do"}, "message": "Moved"}"#,
+        ])
+        .await;
+        let board: Value = server
+            .get(&format!("/api/boards/{}", board_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(board["columns"][0]["name"], "To Do");
+        let other_column_id = board["columns"][1]["id"].as_str().unwrap();
+        server
+            .put(&format!("/api/columns/{}", column_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"wip_limit": 1}))
+            .await
+            .assert_status_ok();
+        server
+            .put(&format!("/api/boards/{}", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"wip_limit_policy": "block"}))
+            .await
+            .assert_status_ok();
+        for (column, title) in [
+            (column_id.as_str(), "First"),
+            (other_column_id, "Elsewhere"),
+        ] {
+            server
+                .post(&format!("/api/columns/{}/cards", column))
+                .add_cookie(session_cookie(&session))
+                .json(&json!({"title": title}))
+                .await
+                .assert_status_ok();
+        }
+
+        for message in ["Add a card", "Move the card"] {
+            let response: Value = server
+                .post(&format!("/api/boards/{}/chat", board_id))
+                .add_cookie(session_cookie(&session))
+                .json(&json!({"message": message}))
+                .await
+                .json();
+            assert_eq!(response["actions_taken"][0]["success"], false);
+            assert_eq!(
+                response["actions_taken"][0]["description"],
+                "Column 'To Do' has reached its WIP limit of 1"
+            );
+        }
+        let board: Value = server
+            .get(&format!("/api/boards/{}", board_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(board["columns"][0]["cards"].as_array().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_column_sort_policy() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+        let board_id = create_board(&server, &session).await;
+        let mut column_ids = Vec::new();
+        for name in ["Due soon", "Later"] {
+            let column: Value = server
+                .post(&format!("/api/boards/{}/columns", board_id))
+                .add_cookie(session_cookie(&session))
+                .json(&json!({"name": name}))
+                .await
+                .json();
+            column_ids.push(column["id"].as_str().unwrap().to_string());
+        }
+        let mut card_ids = Vec::new();
+        for (title, due_date) in [
+            ("Someday", None),
+            ("March", Some("2024-03-01")),
+            ("February", Some("2024-02-01")),
+        ] {
+            let card: Value = server
+                .post(&format!("/api/columns/{}/cards", column_ids[0]))
+                .add_cookie(session_cookie(&session))
+                .json(&json!({"title": title, "due_date": due_date}))
+                .await
+                .json();
+            card_ids.push(card["id"].as_str().unwrap().to_string());
+        }
+
+        let titles = || async {
+            let board: Value = server
+                .get(&format!("/api/boards/{}", board_id))
+                .add_cookie(session_cookie(&session))
+                .await
+                .json();
+            board["columns"][0]["cards"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|c| c["title"].as_str().unwrap().to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(titles().await, ["Someday", "March", "February"]);
+
+        let response = server
+            .put(&format!("/api/columns/{}", column_ids[0]))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"sort_policy": "due_date"}))
+            .await;
+        response.assert_status_ok();
+        assert_eq!(response.json::<Value>()["sort_policy"], "due_date");
+        assert_eq!(titles().await, ["February", "March", "Someday"]);
+
+        // Cards cannot be reordered within an auto-sorted column
+        server
+            .patch(&format!("/api/cards/{}/move", card_ids[0]))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"column_id": column_ids[0], "position": 0}))
+            .await
+            .assert_status_bad_request();
+
+        // But can be moved out of it and back in, where they take their sorted place
+        for column_id in [&column_ids[1], &column_ids[0]] {
+            server
+                .patch(&format!("/api/cards/{}/move", card_ids[1]))
+                .add_cookie(session_cookie(&session))
+                .json(&json!({"column_id": column_id, "position": 0}))
+                .await
+                .assert_status_ok();
+        }
+        assert_eq!(titles().await, ["February", "March", "Someday"]);
+    }
+}
+
+// ============================================================================
+// Card Tests
+// ============================================================================
+
+mod card_tests {
+    use super::*;
+
+    async fn create_board_and_column(server: &TestServer, session: &str) -> (String, String) {
+        let board_response = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(session))
+            .json(&json!({"name": "Test Board"}))
+            .await;
+        let board_id = board_response.json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let column_response = server
+            .post(&format!("/api/boards/{}/columns", board_id))
+            .add_cookie(session_cookie(session))
+            .json(&json!({"name": "To Do"}))
+            .await;
+        let column_id = column_response.json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        (board_id, column_id)
+    }
+
+    #[tokio::test]
+    async fn test_create_card() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+        let (_board_id, column_id) = create_board_and_column(&server, &session).await;
+
+        let response = server
+            .post(&format!("/api/columns/{}/cards", column_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({
+                "title": "Test Card",
+                "body": "Card description"
+            }))
+            .await;
+
+        response.assert_status_ok();
+        let body: Value = response.json();
+        assert_eq!(body["title"], "Test Card");
+        assert_eq!(body["body"], "Card description");
+    }
+
+    #[tokio::test]
+    async fn test_create_card_with_visibility() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+        let (_board_id, column_id) = create_board_and_column(&server, &session).await;
+
+        let response = server
+            .post(&format!("/api/columns/{}/cards", column_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({
+                "title": "Private Card",
+                "visibility": "Private"
+            }))
+            .await;
+
+        response.assert_status_ok();
+        let body: Value = response.json();
         assert_eq!(body["visibility"], "private");
     }
 
@@ -878,6 +2213,52 @@ mod card_tests {
         let body: Value = response.json();
         assert_eq!(body["column_id"], col2_id);
         assert_eq!(body["position"], 0);
+
+        let affected = body["affected_columns"].as_array().unwrap();
+        assert_eq!(affected.len(), 2);
+        assert_eq!(affected[0]["column_id"], col1_id);
+        assert!(affected[0]["cards"].as_array().unwrap().is_empty());
+        assert_eq!(affected[1]["column_id"], col2_id);
+        assert_eq!(affected[1]["cards"][0]["id"], card_id);
+    }
+
+    #[tokio::test]
+    async fn test_import_cards_from_text() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+        let (board_id, column_id) = create_board_and_column(&server, &session).await;
+
+        let response = server
+            .post(&format!("/api/columns/{}/cards/import-text", column_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({
+                "text": "- [ ] Call the bank\n\n- [x] File taxes\nPlan trip\n"
+            }))
+            .await;
+
+        response.assert_status_ok();
+        let body: Value = response.json();
+        let cards = body.as_array().unwrap();
+        assert_eq!(cards.len(), 3);
+        assert_eq!(cards[0]["title"], "Call the bank");
+        assert_eq!(cards[0]["status"], "open");
+        assert_eq!(cards[1]["title"], "File taxes");
+        assert_eq!(cards[1]["status"], "done");
+        assert_eq!(cards[2]["position"], 2);
+
+        let list: Value = server
+            .get(&format!("/api/boards/{}/cards", board_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(list.as_array().unwrap().len(), 3);
+
+        let response = server
+            .post(&format!("/api/columns/{}/cards/import-text", column_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"text": "\n  \n"}))
+            .await;
+        response.assert_status_unprocessable_entity();
     }
 
     #[tokio::test]
@@ -914,512 +2295,10521 @@ mod card_tests {
         response.assert_status_ok();
         let body: Value = response.json();
         assert_eq!(body["position"], 0);
-    }
-}
 
-// ============================================================================
-// Tag Tests
-// ============================================================================
+        let affected = body["affected_columns"].as_array().unwrap();
+        assert_eq!(affected.len(), 1);
+        let cards = affected[0]["cards"].as_array().unwrap();
+        assert_eq!(cards[0]["id"], card2_id);
+        assert_eq!(cards[0]["position"], 0);
+        assert_eq!(cards[1]["position"], 1);
+    }
 
-mod tag_tests {
-    use super::*;
+    #[tokio::test]
+    async fn test_get_card_full() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+        let other_session = register_and_login(&server).await;
+        let (board_id, column_id) = create_board_and_column(&server, &session).await;
 
-    async fn create_board_and_column(server: &TestServer, session: &str) -> (String, String) {
-        let board_response = server
-            .post("/api/boards")
-            .add_cookie(session_cookie(session))
-            .json(&json!({"name": "Test Board"}))
+        let card_response = server
+            .post(&format!("/api/columns/{}/cards", column_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"title": "Full Card"}))
             .await;
-        let board_id = board_response.json::<Value>()["id"]
+        let card_id = card_response.json::<Value>()["id"]
             .as_str()
             .unwrap()
             .to_string();
 
-        let column_response = server
-            .post(&format!("/api/boards/{}/columns", board_id))
-            .add_cookie(session_cookie(session))
-            .json(&json!({"name": "To Do"}))
+        server
+            .post(&format!("/api/cards/{}/comments", card_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"body": "First comment"}))
+            .await
+            .assert_status_ok();
+
+        let response = server
+            .get(&format!("/api/cards/{}/full", card_id))
+            .add_cookie(session_cookie(&session))
             .await;
-        let column_id = column_response.json::<Value>()["id"]
-            .as_str()
+
+        response.assert_status_ok();
+        let body: Value = response.json();
+        assert_eq!(body["title"], "Full Card");
+        assert!(body["tags"].as_array().unwrap().is_empty());
+        assert_eq!(body["boards"][0]["id"], board_id);
+        assert_eq!(body["boards"][0]["role"], "owner");
+        assert_eq!(body["comments"][0]["body"], "First comment");
+        let events: Vec<&str> = body["activity"]
+            .as_array()
             .unwrap()
-            .to_string();
+            .iter()
+            .map(|a| a["event"].as_str().unwrap())
+            .collect();
+        assert_eq!(events, ["comment_created", "card_created"]);
+        assert!(body["comment_draft"].is_null());
 
-        (board_id, column_id)
+        // The caller's unsent comment comes along
+        server
+            .put(&format!("/api/cards/{}/comment-draft", card_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"body": "Half a thought"}))
+            .await
+            .assert_status_ok();
+        let body: Value = server
+            .get(&format!("/api/cards/{}/full", card_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(body["comment_draft"]["body"], "Half a thought");
+
+        let response = server
+            .get(&format!("/api/cards/{}/full", card_id))
+            .add_cookie(session_cookie(&other_session))
+            .await;
+
+        response.assert_status_forbidden();
     }
 
     #[tokio::test]
-    async fn test_create_tag() {
+    async fn test_patch_card_clears_explicit_nulls() {
         let server = setup_server().await;
         let session = register_and_login(&server).await;
-        let (board_id, _column_id) = create_board_and_column(&server, &session).await;
+        let (_board_id, column_id) = create_board_and_column(&server, &session).await;
 
-        let response = server
-            .post(&format!("/api/boards/{}/tags", board_id))
+        let card_response = server
+            .post(&format!("/api/columns/{}/cards", column_id))
             .add_cookie(session_cookie(&session))
             .json(&json!({
-                "name": "Urgent",
-                "color": "#dc3545"
+                "title": "Dated Card",
+                "body": "Keep me",
+                "due_date": "2024-01-25"
             }))
             .await;
+        let card_id = card_response.json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let response = server
+            .patch(&format!("/api/cards/{}", card_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"due_date": null}))
+            .await;
 
         response.assert_status_ok();
         let body: Value = response.json();
-        assert_eq!(body["name"], "Urgent");
-        assert_eq!(body["color"], "#dc3545");
-    }
-
-    #[tokio::test]
-    async fn test_create_tag_default_color() {
-        let server = setup_server().await;
-        let session = register_and_login(&server).await;
-        let (board_id, _column_id) = create_board_and_column(&server, &session).await;
+        assert!(body["due_date"].is_null());
+        assert_eq!(body["body"], "Keep me");
+        assert_eq!(body["title"], "Dated Card");
 
         let response = server
-            .post(&format!("/api/boards/{}/tags", board_id))
+            .put(&format!("/api/cards/{}", card_id))
             .add_cookie(session_cookie(&session))
-            .json(&json!({"name": "Default Color Tag"}))
+            .json(&json!({"body": null, "due_date": "2024-02-01"}))
             .await;
 
         response.assert_status_ok();
         let body: Value = response.json();
-        assert_eq!(body["color"], "#6c757d");
+        assert!(body["body"].is_null());
+        assert_eq!(body["due_date"], "2024-02-01");
     }
 
     #[tokio::test]
-    async fn test_list_tags() {
+    async fn test_locked_card_rejects_changes() {
         let server = setup_server().await;
         let session = register_and_login(&server).await;
-        let (board_id, _column_id) = create_board_and_column(&server, &session).await;
-
-        server
-            .post(&format!("/api/boards/{}/tags", board_id))
-            .add_cookie(session_cookie(&session))
-            .json(&json!({"name": "Tag 1"}))
-            .await;
+        let (_board_id, column_id) = create_board_and_column(&server, &session).await;
 
-        server
-            .post(&format!("/api/boards/{}/tags", board_id))
+        let card_response = server
+            .post(&format!("/api/columns/{}/cards", column_id))
             .add_cookie(session_cookie(&session))
-            .json(&json!({"name": "Tag 2"}))
+            .json(&json!({"title": "Decision record"}))
             .await;
+        let card_id = card_response.json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
 
         let response = server
-            .get(&format!("/api/boards/{}/tags", board_id))
+            .post(&format!("/api/cards/{}/lock", card_id))
             .add_cookie(session_cookie(&session))
             .await;
-
         response.assert_status_ok();
         let body: Value = response.json();
-        assert_eq!(body.as_array().unwrap().len(), 2);
-    }
+        assert_eq!(body["locked"], true);
+        assert!(body["locked_at"].is_string());
 
-    #[tokio::test]
-    async fn test_update_tag() {
-        let server = setup_server().await;
-        let session = register_and_login(&server).await;
-        let (board_id, _column_id) = create_board_and_column(&server, &session).await;
+        let response = server
+            .put(&format!("/api/cards/{}", card_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"title": "Changed"}))
+            .await;
+        response.assert_status(axum::http::StatusCode::LOCKED);
 
-        let create_response = server
-            .post(&format!("/api/boards/{}/tags", board_id))
+        let response = server
+            .post(&format!("/api/cards/{}/comments", card_id))
             .add_cookie(session_cookie(&session))
-            .json(&json!({"name": "Original", "color": "#000000"}))
+            .json(&json!({"body": "Reopening this"}))
             .await;
-        let tag_id = create_response.json::<Value>()["id"]
-            .as_str()
-            .unwrap()
-            .to_string();
+        response.assert_status(axum::http::StatusCode::LOCKED);
 
         let response = server
-            .put(&format!("/api/tags/{}", tag_id))
+            .post(&format!("/api/cards/{}/unlock", card_id))
             .add_cookie(session_cookie(&session))
-            .json(&json!({
-                "name": "Updated",
-                "color": "#ffffff"
-            }))
             .await;
+        response.assert_status_ok();
+        assert_eq!(response.json::<Value>()["locked"], false);
 
+        let response = server
+            .put(&format!("/api/cards/{}", card_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"title": "Changed"}))
+            .await;
         response.assert_status_ok();
-        let body: Value = response.json();
-        assert_eq!(body["name"], "Updated");
-        assert_eq!(body["color"], "#ffffff");
+        assert_eq!(response.json::<Value>()["title"], "Changed");
     }
 
     #[tokio::test]
-    async fn test_delete_tag() {
+    async fn test_lock_card_requires_owner() {
         let server = setup_server().await;
-        let session = register_and_login(&server).await;
-        let (board_id, _column_id) = create_board_and_column(&server, &session).await;
+        let owner_session = register_and_login(&server).await;
+        let other_session = register_and_login(&server).await;
+        let (_board_id, column_id) = create_board_and_column(&server, &owner_session).await;
 
-        let create_response = server
-            .post(&format!("/api/boards/{}/tags", board_id))
-            .add_cookie(session_cookie(&session))
-            .json(&json!({"name": "Tag to Delete"}))
+        let card_response = server
+            .post(&format!("/api/columns/{}/cards", column_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"title": "Not yours"}))
             .await;
-        let tag_id = create_response.json::<Value>()["id"]
+        let card_id = card_response.json::<Value>()["id"]
             .as_str()
             .unwrap()
             .to_string();
 
         let response = server
-            .delete(&format!("/api/tags/{}", tag_id))
-            .add_cookie(session_cookie(&session))
+            .post(&format!("/api/cards/{}/lock", card_id))
+            .add_cookie(session_cookie(&other_session))
             .await;
-
-        response.assert_status_ok();
+        response.assert_status_forbidden();
     }
 
     #[tokio::test]
-    async fn test_add_tag_to_card() {
+    async fn test_bulk_card_operations() {
         let server = setup_server().await;
         let session = register_and_login(&server).await;
         let (board_id, column_id) = create_board_and_column(&server, &session).await;
-
-        let card_response = server
-            .post(&format!("/api/columns/{}/cards", column_id))
+        let done: Value = server
+            .post(&format!("/api/boards/{}/columns", board_id))
             .add_cookie(session_cookie(&session))
-            .json(&json!({"title": "Card with Tag"}))
-            .await;
-        let card_id = card_response.json::<Value>()["id"]
-            .as_str()
-            .unwrap()
-            .to_string();
-
-        let tag_response = server
+            .json(&json!({"name": "Done"}))
+            .await
+            .json();
+        let mut card_ids = Vec::new();
+        for title in ["One", "Two", "Three", "Four"] {
+            let card: Value = server
+                .post(&format!("/api/columns/{}/cards", column_id))
+                .add_cookie(session_cookie(&session))
+                .json(&json!({"title": title}))
+                .await
+                .json();
+            card_ids.push(card["id"].as_str().unwrap().to_string());
+        }
+        let tag: Value = server
             .post(&format!("/api/boards/{}/tags", board_id))
             .add_cookie(session_cookie(&session))
-            .json(&json!({"name": "Important"}))
-            .await;
-        let tag_id = tag_response.json::<Value>()["id"]
-            .as_str()
-            .unwrap()
-            .to_string();
+            .json(&json!({"name": "shipped", "color": "#198754"}))
+            .await
+            .json();
 
         let response = server
-            .post(&format!("/api/cards/{}/tags/{}", card_id, tag_id))
+            .post(&format!("/api/boards/{}/cards/bulk", board_id))
             .add_cookie(session_cookie(&session))
+            .json(&json!({"operations": [
+                {"op": "move", "card_id": card_ids[0], "column_id": done["id"]},
+                {"op": "tag", "card_id": card_ids[0], "tag_id": tag["id"]},
+                {"op": "status", "card_id": card_ids[0], "status": "Done"},
+                {"op": "archive", "card_id": card_ids[1]},
+                {"op": "delete", "card_id": card_ids[2]},
+                {"op": "move", "card_id": card_ids[3], "column_id": done["id"]},
+            ]}))
             .await;
-
         response.assert_status_ok();
+        assert_eq!(response.json::<Value>()["applied"], 6);
 
-        let card_detail = server
-            .get(&format!("/api/cards/{}", card_id))
+        let card: Value = server
+            .get(&format!("/api/cards/{}", card_ids[0]))
             .add_cookie(session_cookie(&session))
-            .await;
+            .await
+            .json();
+        assert_eq!(card["column_id"], done["id"]);
+        assert_eq!(card["status"], "done");
+        let card: Value = server
+            .get(&format!("/api/cards/{}", card_ids[1]))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert!(!card["archived_at"].is_null());
+        // A moved card takes the status of its new column
+        let card: Value = server
+            .get(&format!("/api/cards/{}", card_ids[3]))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(card["status"], "done");
+        server
+            .get(&format!("/api/cards/{}", card_ids[2]))
+            .add_cookie(session_cookie(&session))
+            .await
+            .assert_status_not_found();
 
-        let body: Value = card_detail.json();
-        let tags = body["tags"].as_array().unwrap();
-        assert_eq!(tags.len(), 1);
-        assert_eq!(tags[0]["name"], "Important");
+        // A card from another board rejects the whole batch
+        let (_other_board, other_column) = create_board_and_column(&server, &session).await;
+        let other: Value = server
+            .post(&format!("/api/columns/{}/cards", other_column))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"title": "Elsewhere"}))
+            .await
+            .json();
+        let response = server
+            .post(&format!("/api/boards/{}/cards/bulk", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"operations": [
+                {"op": "move", "card_id": card_ids[0], "column_id": column_id},
+                {"op": "archive", "card_id": other["id"]},
+            ]}))
+            .await;
+        response.assert_status_bad_request();
+        let card: Value = server
+            .get(&format!("/api/cards/{}", card_ids[0]))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(card["column_id"], done["id"]);
     }
 
     #[tokio::test]
-    async fn test_remove_tag_from_card() {
+    async fn test_card_priority() {
         let server = setup_server().await;
         let session = register_and_login(&server).await;
         let (board_id, column_id) = create_board_and_column(&server, &session).await;
 
-        let card_response = server
+        let mut card_ids = Vec::new();
+        for (title, priority) in [
+            ("Whenever", None),
+            ("Soon", Some("medium")),
+            ("Now", Some("urgent")),
+        ] {
+            let card: Value = server
+                .post(&format!("/api/columns/{}/cards", column_id))
+                .add_cookie(session_cookie(&session))
+                .json(&json!({"title": title, "priority": priority}))
+                .await
+                .json();
+            assert_eq!(card["priority"], json!(priority));
+            card_ids.push(card["id"].as_str().unwrap().to_string());
+        }
+
+        server
             .post(&format!("/api/columns/{}/cards", column_id))
             .add_cookie(session_cookie(&session))
-            .json(&json!({"title": "Card with Tag"}))
-            .await;
-        let card_id = card_response.json::<Value>()["id"]
-            .as_str()
-            .unwrap()
-            .to_string();
+            .json(&json!({"title": "Bad", "priority": "critical"}))
+            .await
+            .assert_status(axum::http::StatusCode::UNPROCESSABLE_ENTITY);
 
-        let tag_response = server
-            .post(&format!("/api/boards/{}/tags", board_id))
+        // Omitted leaves the priority alone, null clears it
+        let card: Value = server
+            .put(&format!("/api/cards/{}", card_ids[1]))
             .add_cookie(session_cookie(&session))
-            .json(&json!({"name": "Removable"}))
-            .await;
-        let tag_id = tag_response.json::<Value>()["id"]
-            .as_str()
+            .json(&json!({"priority": "high"}))
+            .await
+            .json();
+        assert_eq!(card["priority"], "high");
+        let card: Value = server
+            .put(&format!("/api/cards/{}", card_ids[1]))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"title": "Soon-ish"}))
+            .await
+            .json();
+        assert_eq!(card["priority"], "high");
+
+        let cards: Vec<Value> = server
+            .get(&format!("/api/boards/{}/cards?priority=urgent", board_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(cards.len(), 1);
+        assert_eq!(cards[0]["title"], "Now");
+
+        server
+            .put(&format!("/api/columns/{}", column_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"sort_policy": "priority"}))
+            .await
+            .assert_status_ok();
+        let board: Value = server
+            .get(&format!("/api/boards/{}", board_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        let titles: Vec<&str> = board["columns"][0]["cards"]
+            .as_array()
             .unwrap()
-            .to_string();
+            .iter()
+            .map(|c| c["title"].as_str().unwrap())
+            .collect();
+        assert_eq!(titles, ["Now", "Soon-ish", "Whenever"]);
+
+        let card: Value = server
+            .put(&format!("/api/cards/{}", card_ids[2]))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"priority": null}))
+            .await
+            .json();
+        assert!(card["priority"].is_null());
+    }
 
+    #[tokio::test]
+    async fn test_clone_card() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+        let (board_id, column_id) = create_board_and_column(&server, &session).await;
+        let done: Value = server
+            .post(&format!("/api/boards/{}/columns", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Done"}))
+            .await
+            .json();
+        let mut ids = Vec::new();
+        for title in ["Plan sprint", "Retro"] {
+            let card: Value = server
+                .post(&format!("/api/columns/{}/cards", column_id))
+                .add_cookie(session_cookie(&session))
+                .json(&json!({
+                    "title": title,
+                    "body": "- [ ] Invite team\n- [x] Book room",
+                    "priority": "high"
+                }))
+                .await
+                .json();
+            ids.push(card["id"].as_str().unwrap().to_string());
+        }
+        let tag: Value = server
+            .post(&format!("/api/boards/{}/tags", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "ritual", "color": "#123456"}))
+            .await
+            .json();
         server
-            .post(&format!("/api/cards/{}/tags/{}", card_id, tag_id))
+            .post(&format!(
+                "/api/cards/{}/tags/{}",
+                ids[0],
+                tag["id"].as_str().unwrap()
+            ))
             .add_cookie(session_cookie(&session))
-            .await;
+            .await
+            .assert_status_ok();
+        server
+            .post(&format!("/api/cards/{}/comments", ids[0]))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"body": "Use the new template"}))
+            .await
+            .assert_status_ok();
 
+        // By default the copy lands right after the card, without comments
         let response = server
-            .delete(&format!("/api/cards/{}/tags/{}", card_id, tag_id))
+            .post(&format!("/api/cards/{}/clone", ids[0]))
             .add_cookie(session_cookie(&session))
             .await;
-
         response.assert_status_ok();
+        let copy: Value = response.json();
+        assert_ne!(copy["id"], ids[0].as_str());
+        assert_eq!(copy["title"], "Plan sprint");
+        assert_eq!(copy["body"], "- [ ] Invite team\n- [x] Book room");
+        assert_eq!(copy["priority"], "high");
+        assert_eq!(copy["position"], 1);
+        assert_eq!(copy["tags"][0]["name"], "ritual");
+        let comments: Value = server
+            .get(&format!(
+                "/api/cards/{}/comments",
+                copy["id"].as_str().unwrap()
+            ))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(comments.as_array().unwrap().len(), 0);
 
-        let card_detail = server
-            .get(&format!("/api/cards/{}", card_id))
+        let board: Value = server
+            .get(&format!("/api/boards/{}", board_id))
             .add_cookie(session_cookie(&session))
-            .await;
+            .await
+            .json();
+        let titles: Vec<&str> = board["columns"][0]["cards"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|c| c["title"].as_str().unwrap())
+            .collect();
+        assert_eq!(titles, ["Plan sprint", "Plan sprint", "Retro"]);
+
+        // To another column, renamed, with comments
+        let copy: Value = server
+            .post(&format!("/api/cards/{}/clone", ids[0]))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({
+                "title": "Plan next sprint",
+                "column_id": done["id"],
+                "include_comments": true
+            }))
+            .await
+            .json();
+        assert_eq!(copy["title"], "Plan next sprint");
+        assert_eq!(copy["column_id"], done["id"]);
+        assert_eq!(copy["position"], 0);
+        let comments: Value = server
+            .get(&format!(
+                "/api/cards/{}/comments",
+                copy["id"].as_str().unwrap()
+            ))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(comments[0]["body"], "Use the new template");
 
-        let body: Value = card_detail.json();
-        let tags = body["tags"].as_array().unwrap();
-        assert!(tags.is_empty());
+        // Not to another board, and not by someone who cannot edit the board
+        let (_, other_column) = create_board_and_column(&server, &session).await;
+        server
+            .post(&format!("/api/cards/{}/clone", ids[0]))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"column_id": other_column}))
+            .await
+            .assert_status_bad_request();
+        let other = register_and_login(&server).await;
+        server
+            .post(&format!("/api/cards/{}/clone", ids[0]))
+            .add_cookie(session_cookie(&other))
+            .await
+            .assert_status_forbidden();
+
+        // Inbox cards are copied to the inbox
+        let inbox: Value = server
+            .post("/api/cards")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"title": "Call the bank"}))
+            .await
+            .json();
+        let copy: Value = server
+            .post(&format!(
+                "/api/cards/{}/clone",
+                inbox["id"].as_str().unwrap()
+            ))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(copy["title"], "Call the bank");
+        assert_eq!(copy["column_id"], Value::Null);
+        assert_eq!(copy["owner_id"], inbox["owner_id"]);
     }
 }
 
 // ============================================================================
-// E2E Tests
+// Pagination Tests
 // ============================================================================
 
-mod e2e_tests {
+mod pagination_tests {
     use super::*;
 
     #[tokio::test]
-    async fn test_full_kanban_workflow() {
+    async fn test_list_boards_by_page() {
         let server = setup_server().await;
         let session = register_and_login(&server).await;
+        for name in ["One", "Two", "Three"] {
+            server
+                .post("/api/boards")
+                .add_cookie(session_cookie(&session))
+                .json(&json!({"name": name}))
+                .await
+                .assert_status_ok();
+        }
 
-        let board_response = server
-            .post("/api/boards")
+        let response = server
+            .get("/api/boards?limit=2")
             .add_cookie(session_cookie(&session))
-            .json(&json!({"name": "Project Board", "description": "For tracking project tasks"}))
             .await;
-        board_response.assert_status_ok();
-        let board_id = board_response.json::<Value>()["id"]
-            .as_str()
-            .unwrap()
-            .to_string();
-
-        let todo_response = server
-            .post(&format!("/api/boards/{}/columns", board_id))
+        assert_eq!(response.header("x-total-count"), "3");
+        let link = response.header("link");
+        let link = link.to_str().unwrap();
+        assert!(link.contains("</api/boards?limit=2&offset=2>; rel=\"next\""));
+        assert!(!link.contains("rel=\"prev\""));
+        let first: Value = response.json();
+        assert_eq!(first.as_array().unwrap().len(), 2);
+
+        let rest: Value = server
+            .get("/api/boards?limit=2&offset=2")
             .add_cookie(session_cookie(&session))
-            .json(&json!({"name": "To Do"}))
-            .await;
-        let todo_id = todo_response.json::<Value>()["id"]
-            .as_str()
-            .unwrap()
-            .to_string();
+            .await
+            .json();
+        assert_eq!(rest.as_array().unwrap().len(), 1);
+        assert!(!first.as_array().unwrap().contains(&rest[0]));
 
-        let in_progress_response = server
-            .post(&format!("/api/boards/{}/columns", board_id))
+        // The whole list fits on the default page, without links
+        let response = server
+            .get("/api/boards")
             .add_cookie(session_cookie(&session))
-            .json(&json!({"name": "In Progress"}))
             .await;
-        let in_progress_id = in_progress_response.json::<Value>()["id"]
-            .as_str()
-            .unwrap()
-            .to_string();
+        assert_eq!(response.json::<Value>().as_array().unwrap().len(), 3);
+        assert!(response.maybe_header("link").is_none());
+    }
 
-        let done_response = server
-            .post(&format!("/api/boards/{}/columns", board_id))
+    #[tokio::test]
+    async fn test_filtered_cards_and_comments_by_page() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+        let board: Value = server
+            .post("/api/boards")
             .add_cookie(session_cookie(&session))
-            .json(&json!({"name": "Done"}))
-            .await;
-        let done_id = done_response.json::<Value>()["id"]
-            .as_str()
-            .unwrap()
-            .to_string();
+            .json(&json!({"name": "Paged", "template": "kanban"}))
+            .await
+            .json();
+        let board_id = board["id"].as_str().unwrap();
+        let board: Value = server
+            .get(&format!("/api/boards/{}", board_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        let column_id = board["columns"][0]["id"].as_str().unwrap();
+        for title in ["Bug 1", "Bug 2", "Bug 3", "Feature"] {
+            server
+                .post(&format!("/api/columns/{}/cards", column_id))
+                .add_cookie(session_cookie(&session))
+                .json(&json!({"title": title}))
+                .await
+                .assert_status_ok();
+        }
 
-        let bug_tag_response = server
-            .post(&format!("/api/boards/{}/tags", board_id))
+        let response = server
+            .get(&format!(
+                "/api/boards/{}/cards?query=Bug&limit=2&offset=2",
+                board_id
+            ))
             .add_cookie(session_cookie(&session))
-            .json(&json!({"name": "Bug", "color": "#dc3545"}))
             .await;
-        let bug_tag_id = bug_tag_response.json::<Value>()["id"]
-            .as_str()
+        assert_eq!(response.header("x-total-count"), "3");
+        assert!(response
+            .header("link")
+            .to_str()
             .unwrap()
-            .to_string();
+            .contains("?query=Bug&limit=2&offset=0>; rel=\"prev\""));
+        let cards: Value = response.json();
+        assert_eq!(cards[0]["title"], "Bug 3");
+        assert_eq!(cards.as_array().unwrap().len(), 1);
 
-        let feature_tag_response = server
-            .post(&format!("/api/boards/{}/tags", board_id))
+        let card_id = cards[0]["id"].as_str().unwrap();
+        for body in ["First", "Second", "Third"] {
+            server
+                .post(&format!("/api/cards/{}/comments", card_id))
+                .add_cookie(session_cookie(&session))
+                .json(&json!({"body": body}))
+                .await
+                .assert_status_ok();
+        }
+        let response = server
+            .get(&format!("/api/cards/{}/comments?limit=1&offset=1", card_id))
             .add_cookie(session_cookie(&session))
-            .json(&json!({"name": "Feature", "color": "#28a745"}))
             .await;
-        let feature_tag_id = feature_tag_response.json::<Value>()["id"]
-            .as_str()
-            .unwrap()
-            .to_string();
+        assert_eq!(response.header("x-total-count"), "3");
+        let comments: Value = response.json();
+        assert_eq!(comments[0]["body"], "Second");
+    }
+}
 
-        let card1_response = server
-            .post(&format!("/api/columns/{}/cards", todo_id))
+// ============================================================================
+// Concurrency Control Tests
+// ============================================================================
+
+mod concurrency_tests {
+    use super::*;
+    use axum::http::StatusCode;
+    use axum_test::TestResponse;
+
+    /// A board with one column and one card in it, as (board_id, column_id, card_id)
+    async fn create_card(server: &TestServer, session: &str) -> (String, String, String) {
+        let board: Value = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(session))
+            .json(&json!({"name": "Shared"}))
+            .await
+            .json();
+        let board_id = board["id"].as_str().unwrap().to_string();
+        let column: Value = server
+            .post(&format!("/api/boards/{}/columns", board_id))
+            .add_cookie(session_cookie(session))
+            .json(&json!({"name": "To Do"}))
+            .await
+            .json();
+        let column_id = column["id"].as_str().unwrap().to_string();
+        let card: Value = server
+            .post(&format!("/api/columns/{}/cards", column_id))
+            .add_cookie(session_cookie(session))
+            .json(&json!({"title": "Draft"}))
+            .await
+            .json();
+        let card_id = card["id"].as_str().unwrap().to_string();
+        (board_id, column_id, card_id)
+    }
+
+    fn etag(response: &TestResponse) -> String {
+        response.header("etag").to_str().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn test_stale_card_update_is_rejected() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+        let (_, _, card_id) = create_card(&server, &session).await;
+        let url = format!("/api/cards/{}", card_id);
+
+        // Two clients read the same version of the card
+        let response = server.get(&url).add_cookie(session_cookie(&session)).await;
+        let read = etag(&response);
+        assert_eq!(read, "\"1\"");
+        assert_eq!(response.json::<Value>()["version"], 1);
+
+        let first = server
+            .put(&url)
             .add_cookie(session_cookie(&session))
-            .json(&json!({
-                "title": "Fix login bug",
-                "body": "Users can't login with special characters in password"
-            }))
+            .add_header("if-match", read.as_str())
+            .json(&json!({"title": "First"}))
             .await;
-        let card1_id = card1_response.json::<Value>()["id"]
-            .as_str()
-            .unwrap()
-            .to_string();
+        first.assert_status_ok();
+        assert_eq!(etag(&first), "\"2\"");
 
-        let card2_response = server
-            .post(&format!("/api/columns/{}/cards", todo_id))
+        let second = server
+            .put(&url)
             .add_cookie(session_cookie(&session))
-            .json(&json!({
-                "title": "Add dark mode",
-                "body": "Implement dark mode theme"
-            }))
+            .add_header("if-match", read.as_str())
+            .json(&json!({"title": "Second"}))
             .await;
-        let card2_id = card2_response.json::<Value>()["id"]
-            .as_str()
-            .unwrap()
-            .to_string();
+        second.assert_status(StatusCode::PRECONDITION_FAILED);
 
-        server
-            .post(&format!("/api/cards/{}/tags/{}", card1_id, bug_tag_id))
+        let card: Value = server
+            .get(&url)
             .add_cookie(session_cookie(&session))
             .await
-            .assert_status_ok();
+            .json();
+        assert_eq!(card["title"], "First");
 
+        // Retrying with the fresh ETag goes through
         server
-            .post(&format!("/api/cards/{}/tags/{}", card2_id, feature_tag_id))
+            .patch(&url)
             .add_cookie(session_cookie(&session))
+            .add_header("if-match", etag(&first).as_str())
+            .json(&json!({"title": "Second"}))
             .await
             .assert_status_ok();
+    }
 
-        let move_response = server
-            .patch(&format!("/api/cards/{}/move", card1_id))
+    #[tokio::test]
+    async fn test_concurrent_updates_of_one_version_let_one_through() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+        let (_, _, card_id) = create_card(&server, &session).await;
+        let url = format!("/api/cards/{}", card_id);
+
+        let update = |title: &'static str| {
+            server
+                .put(&url)
+                .add_cookie(session_cookie(&session))
+                .add_header("if-match", "\"1\"")
+                .json(&json!({"title": title}))
+        };
+        let (a, b) = tokio::join!(update("A"), update("B"));
+
+        let mut statuses = [a.status_code(), b.status_code()];
+        statuses.sort();
+        assert_eq!(statuses, [StatusCode::OK, StatusCode::PRECONDITION_FAILED]);
+        let winner = if a.status_code() == StatusCode::OK {
+            "A"
+        } else {
+            "B"
+        };
+        let card: Value = server
+            .get(&url)
             .add_cookie(session_cookie(&session))
-            .json(&json!({"column_id": in_progress_id, "position": 0}))
-            .await;
-        move_response.assert_status_ok();
+            .await
+            .json();
+        assert_eq!(card["title"], winner);
+        assert_eq!(card["version"], 2);
+    }
 
-        let board_detail = server
-            .get(&format!("/api/boards/{}", board_id))
+    #[tokio::test]
+    async fn test_other_changes_invalidate_the_etag() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+        let (_, _, card_id) = create_card(&server, &session).await;
+        let url = format!("/api/cards/{}", card_id);
+        let read = etag(&server.get(&url).add_cookie(session_cookie(&session)).await);
+
+        server
+            .put(&format!("/api/inbox/cards/{}/status", card_id))
             .add_cookie(session_cookie(&session))
-            .await;
-        board_detail.assert_status_ok();
+            .json(&json!({"status": "Done"}))
+            .await
+            .assert_status_ok();
 
-        let board_data: Value = board_detail.json();
-        assert_eq!(board_data["columns"].as_array().unwrap().len(), 3);
-        assert_eq!(board_data["tags"].as_array().unwrap().len(), 2);
+        server
+            .put(&format!("/api/inbox/cards/{}", card_id))
+            .add_cookie(session_cookie(&session))
+            .add_header("if-match", read.as_str())
+            .json(&json!({"title": "Stale"}))
+            .await
+            .assert_status(StatusCode::PRECONDITION_FAILED);
 
-        let complete_response = server
-            .patch(&format!("/api/cards/{}/move", card1_id))
+        // Without If-Match, or with a wildcard, updates are unconditional
+        server
+            .put(&url)
             .add_cookie(session_cookie(&session))
-            .json(&json!({"column_id": done_id, "position": 0}))
-            .await;
-        complete_response.assert_status_ok();
-        assert_eq!(complete_response.json::<Value>()["column_id"], done_id);
+            .json(&json!({"title": "Anyway"}))
+            .await
+            .assert_status_ok();
+        server
+            .put(&url)
+            .add_cookie(session_cookie(&session))
+            .add_header("if-match", "*")
+            .json(&json!({"title": "Again"}))
+            .await
+            .assert_status_ok();
     }
 
     #[tokio::test]
-    async fn test_cards_list_returns_all_cards() {
+    async fn test_board_and_column_updates_honor_if_match() {
         let server = setup_server().await;
         let session = register_and_login(&server).await;
-
-        let board_response = server
-            .post("/api/boards")
+        let (board_id, column_id, _) = create_card(&server, &session).await;
+
+        let board_url = format!("/api/boards/{}", board_id);
+        let read = etag(
+            &server
+                .get(&board_url)
+                .add_cookie(session_cookie(&session))
+                .await,
+        );
+        let response = server
+            .put(&board_url)
             .add_cookie(session_cookie(&session))
-            .json(&json!({"name": "Filter Test Board"}))
+            .add_header("if-match", read.as_str())
+            .json(&json!({"name": "Renamed"}))
             .await;
-        let board_id = board_response.json::<Value>()["id"]
-            .as_str()
-            .unwrap()
-            .to_string();
-
-        let column_response = server
-            .post(&format!("/api/boards/{}/columns", board_id))
+        response.assert_status_ok();
+        assert_ne!(etag(&response), read);
+        server
+            .put(&board_url)
             .add_cookie(session_cookie(&session))
-            .json(&json!({"name": "Tasks"}))
-            .await;
-        let column_id = column_response.json::<Value>()["id"]
-            .as_str()
-            .unwrap()
-            .to_string();
+            .add_header("if-match", read.as_str())
+            .json(&json!({"name": "Stale"}))
+            .await
+            .assert_status(StatusCode::PRECONDITION_FAILED);
 
-        let tag_response = server
-            .post(&format!("/api/boards/{}/tags", board_id))
+        let column_url = format!("/api/columns/{}", column_id);
+        let response = server
+            .put(&column_url)
             .add_cookie(session_cookie(&session))
-            .json(&json!({"name": "Priority"}))
+            .add_header("if-match", "\"1\"")
+            .json(&json!({"wip_limit": 3}))
             .await;
-        let tag_id = tag_response.json::<Value>()["id"]
-            .as_str()
-            .unwrap()
-            .to_string();
-
-        let card1_response = server
-            .post(&format!("/api/columns/{}/cards", column_id))
+        response.assert_status_ok();
+        assert_eq!(etag(&response), "\"2\"");
+        server
+            .put(&column_url)
             .add_cookie(session_cookie(&session))
-            .json(&json!({"title": "Tagged Card"}))
-            .await;
-        let card1_id = card1_response.json::<Value>()["id"]
-            .as_str()
-            .unwrap()
-            .to_string();
+            .add_header("if-match", "\"1\"")
+            .json(&json!({"name": "Stale"}))
+            .await
+            .assert_status(StatusCode::PRECONDITION_FAILED);
 
-        server
-            .post(&format!("/api/columns/{}/cards", column_id))
+        let board: Value = server
+            .get(&board_url)
             .add_cookie(session_cookie(&session))
-            .json(&json!({"title": "Untagged Card"}))
-            .await;
+            .await
+            .json();
+        assert_eq!(board["name"], "Renamed");
+        assert_eq!(board["columns"][0]["name"], "To Do");
+        assert_eq!(board["columns"][0]["version"], 2);
+    }
+}
 
-        server
-            .post(&format!("/api/cards/{}/tags/{}", card1_id, tag_id))
+// ============================================================================
+// Error Response Tests
+// ============================================================================
+
+mod error_response_tests {
+    use super::*;
+    use axum::http::StatusCode;
+
+    #[tokio::test]
+    async fn test_errors_have_a_code_and_a_message() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+
+        let response = server
+            .get(&format!("/api/boards/{}", uuid::Uuid::new_v4()))
             .add_cookie(session_cookie(&session))
             .await;
+        response.assert_status_not_found();
+        response.assert_json(&json!({
+            "code": "board_not_found",
+            "message": "Board not found"
+        }));
 
-        // Verify cards list returns all cards
-        let all_cards_response = server
-            .get(&format!("/api/boards/{}/cards", board_id))
+        let response = server
+            .post("/api/boards")
             .add_cookie(session_cookie(&session))
+            .json(&json!({"name": ""}))
             .await;
+        response.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+        response.assert_json(&json!({
+            "code": "validation_failed",
+            "message": "Board name is required",
+            "details": {"fields": {"name": "Board name is required"}}
+        }));
 
-        all_cards_response.assert_status_ok();
-        let cards: Value = all_cards_response.json();
-        assert_eq!(cards.as_array().unwrap().len(), 2);
+        server
+            .get("/api/boards")
+            .await
+            .assert_json(&json!({"code": "unauthorized", "message": "Unauthorized"}));
     }
 
     #[tokio::test]
-    async fn test_filter_cards_by_query() {
+    async fn test_rejected_requests_use_the_error_format() {
         let server = setup_server().await;
         let session = register_and_login(&server).await;
 
-        let board_response = server
+        // A body missing a required field never reaches the handler
+        let response = server
             .post("/api/boards")
             .add_cookie(session_cookie(&session))
-            .json(&json!({"name": "Query Test Board"}))
+            .json(&json!({"description": "No name"}))
             .await;
-        let board_id = board_response.json::<Value>()["id"]
-            .as_str()
-            .unwrap()
-            .to_string();
+        response.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+        let body: Value = response.json();
+        assert_eq!(body["code"], "invalid_request");
+        assert!(body["message"].as_str().unwrap().contains("name"));
 
-        let column_response = server
-            .post(&format!("/api/boards/{}/columns", board_id))
+        let response = server
+            .get("/api/boards/not-a-uuid")
             .add_cookie(session_cookie(&session))
-            .json(&json!({"name": "Tasks"}))
             .await;
-        let column_id = column_response.json::<Value>()["id"]
-            .as_str()
-            .unwrap()
-            .to_string();
+        response.assert_status_bad_request();
+        assert_eq!(response.json::<Value>()["code"], "invalid_request");
 
-        server
-            .post(&format!("/api/columns/{}/cards", column_id))
+        let response = server
+            .get("/api/no-such-endpoint")
             .add_cookie(session_cookie(&session))
-            .json(&json!({
-                "title": "Fix authentication bug",
-                "body": "Users cannot login"
-            }))
             .await;
+        response.assert_status_not_found();
+        assert_eq!(response.json::<Value>()["code"], "route_not_found");
 
-        server
-            .post(&format!("/api/columns/{}/cards", column_id))
+        let response = server
+            .patch("/api/boards")
             .add_cookie(session_cookie(&session))
-            .json(&json!({
-                "title": "Add new feature",
-                "body": "Implement dashboard"
-            }))
             .await;
+        response.assert_status(StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(response.json::<Value>()["code"], "method_not_allowed");
+    }
+}
 
-        let filter_response = server
-            .get(&format!(
-                "/api/boards/{}/cards?query=authentication",
-                board_id
-            ))
-            .add_cookie(session_cookie(&session))
+// ============================================================================
+// API Documentation Tests
+// ============================================================================
+
+mod openapi_tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_openapi_document_is_public() {
+        let server = setup_server().await;
+
+        let response = server.get("/api/openapi.json").await;
+        response.assert_status_ok();
+        let document: Value = response.json();
+        assert_eq!(document["openapi"], "3.0.3");
+        assert!(document["paths"]["/boards/{board_id}"]["get"].is_object());
+        assert!(document["components"]["schemas"]["CardResponse"].is_object());
+
+        let response = server.get("/api/docs").await;
+        response.assert_status_ok();
+        assert!(response.text().contains("/api/openapi.json"));
+    }
+}
+
+// ============================================================================
+// MCP Server Tests
+// ============================================================================
+
+mod mcp_tests {
+    use super::*;
+    use axum::http::StatusCode;
+
+    async fn rpc(server: &TestServer, session: &str, method: &str, params: Value) -> Value {
+        let response = server
+            .post("/api/mcp")
+            .add_cookie(session_cookie(session))
+            .json(&json!({"jsonrpc": "2.0", "id": 1, "method": method, "params": params}))
             .await;
+        response.assert_status_ok();
+        let body: Value = response.json();
+        assert_eq!(body["id"], 1);
+        body
+    }
 
-        filter_response.assert_status_ok();
-        let cards: Value = filter_response.json();
-        assert_eq!(cards.as_array().unwrap().len(), 1);
+    async fn call(server: &TestServer, session: &str, tool: &str, arguments: Value) -> Value {
+        rpc(
+            server,
+            session,
+            "tools/call",
+            json!({"name": tool, "arguments": arguments}),
+        )
+        .await["result"]
+            .clone()
+    }
+
+    #[tokio::test]
+    async fn test_mcp_handshake_lists_tools() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+
+        let init = rpc(
+            &server,
+            &session,
+            "initialize",
+            json!({"protocolVersion": "2025-03-26", "capabilities": {}, "clientInfo": {"name": "test", "version": "1"}}),
+        )
+        .await;
+        assert_eq!(init["result"]["protocolVersion"], "2025-03-26");
+        assert!(init["result"]["capabilities"]["tools"].is_object());
+
+        server
+            .post("/api/mcp")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"jsonrpc": "2.0", "method": "notifications/initialized"}))
+            .await
+            .assert_status(StatusCode::ACCEPTED);
+
+        let tools = rpc(&server, &session, "tools/list", json!({})).await;
+        let names: Vec<&str> = tools["result"]["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["name"].as_str().unwrap())
+            .collect();
+        for name in [
+            "list_boards",
+            "list_cards",
+            "create_card",
+            "move_card",
+            "add_tag",
+        ] {
+            assert!(names.contains(&name), "{} missing", name);
+        }
+
+        let unknown = rpc(&server, &session, "resources/list", json!({})).await;
+        assert_eq!(unknown["error"]["code"], -32601);
+    }
+
+    #[tokio::test]
+    async fn test_mcp_tools_act_on_boards() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+
+        let created = call(
+            &server,
+            &session,
+            "create_board",
+            json!({"name": "Agent Board"}),
+        )
+        .await;
+        assert_eq!(created["isError"], false, "{}", created);
+        let created = call(
+            &server,
+            &session,
+            "create_column",
+            json!({"board": "Agent Board", "name": "Doing"}),
+        )
+        .await;
+        assert_eq!(created["isError"], false, "{}", created);
+        let created = call(
+            &server,
+            &session,
+            "create_card",
+            json!({"board": "Agent Board", "column": "Doing", "title": "Draft the plan"}),
+        )
+        .await;
+        assert_eq!(created["isError"], false, "{}", created);
+
+        let boards = call(&server, &session, "list_boards", json!({})).await;
+        let boards: Value =
+            serde_json::from_str(boards["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(boards[0]["name"], "Agent Board");
+
+        let cards = call(
+            &server,
+            &session,
+            "list_cards",
+            json!({"board": "agent board"}),
+        )
+        .await;
+        let columns: Value =
+            serde_json::from_str(cards["content"][0]["text"].as_str().unwrap()).unwrap();
+        let doing = columns
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|c| c["column"] == "Doing")
+            .unwrap();
+        assert_eq!(doing["cards"][0]["title"], "Draft the plan");
+    }
+
+    /// Bearer header of a new API token of the session's user
+    async fn bearer(server: &TestServer, session: &str, scope: &str) -> String {
+        let token: Value = server
+            .post("/api/auth/tokens")
+            .add_cookie(session_cookie(session))
+            .json(&json!({"name": "agent", "scope": scope}))
+            .await
+            .json();
+        format!("Bearer {}", token["token"].as_str().unwrap())
+    }
+
+    async fn call_with_token(
+        server: &TestServer,
+        bearer: &str,
+        tool: &str,
+        arguments: Value,
+    ) -> Value {
+        let response = server
+            .post("/api/mcp")
+            .add_header("Authorization", bearer)
+            .json(&json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "tools/call",
+                "params": {"name": tool, "arguments": arguments},
+            }))
+            .await;
+        response.assert_status_ok();
+        response.json::<Value>()["result"].clone()
+    }
+
+    #[tokio::test]
+    async fn test_mcp_read_token_can_only_list() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+        call(&server, &session, "create_board", json!({"name": "Shared"})).await;
+        let bearer = bearer(&server, &session, "Read").await;
+
+        let boards = call_with_token(&server, &bearer, "list_boards", json!({})).await;
+        assert_eq!(boards["isError"], false, "{}", boards);
+        let tags = call_with_token(&server, &bearer, "list_tags", json!({"board": "Shared"})).await;
+        assert_eq!(tags["isError"], false, "{}", tags);
+
+        let created = call_with_token(
+            &server,
+            &bearer,
+            "create_board",
+            json!({"name": "Not allowed"}),
+        )
+        .await;
+        assert_eq!(created["isError"], true);
+        assert_eq!(
+            created["content"][0]["text"],
+            "Calling create_board needs an API token with the write scope"
+        );
+        let boards = call(&server, &session, "list_boards", json!({})).await;
+        let boards: Value =
+            serde_json::from_str(boards["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(boards.as_array().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_mcp_write_token_changes_boards() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+        let bearer = bearer(&server, &session, "Write").await;
+
+        let created = call_with_token(
+            &server,
+            &bearer,
+            "create_board",
+            json!({"name": "From an agent"}),
+        )
+        .await;
+        assert_eq!(created["isError"], false, "{}", created);
+        let boards = call_with_token(&server, &bearer, "list_boards", json!({})).await;
+        let boards: Value =
+            serde_json::from_str(boards["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(boards[0]["name"], "From an agent");
+    }
+
+    #[tokio::test]
+    async fn test_mcp_tool_errors_are_reported_to_the_agent() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+
+        let missing = call(&server, &session, "create_card", json!({"board": "Nope"})).await;
+        assert_eq!(missing["isError"], true);
+        assert!(missing["content"][0]["text"]
+            .as_str()
+            .unwrap()
+            .contains("column"));
+
+        let unknown_board = call(&server, &session, "list_cards", json!({"board": "Nope"})).await;
+        assert_eq!(unknown_board["isError"], true);
+
+        let unknown_tool = rpc(
+            &server,
+            &session,
+            "tools/call",
+            json!({"name": "web_search", "arguments": {}}),
+        )
+        .await;
+        assert_eq!(unknown_tool["error"]["code"], -32602);
+
+        let response = server
+            .post("/api/mcp")
+            .add_cookie(session_cookie(&session))
+            .text("{not json")
+            .await;
+        response.assert_status_bad_request();
+        assert_eq!(response.json::<Value>()["error"]["code"], -32700);
+
+        server
+            .post("/api/mcp")
+            .json(&json!({"jsonrpc": "2.0", "id": 1, "method": "ping"}))
+            .await
+            .assert_status_unauthorized();
+    }
+}
+
+// ============================================================================
+// Command Line Client Tests
+// ============================================================================
+
+mod client_tests {
+    use super::*;
+    use personal_os::client::{short_id, ApiClient, ClientError};
+
+    /// The client sends real HTTP requests, so the server listens on a port
+    async fn setup_client() -> (TestServer, ApiClient) {
+        let state = test_utils::create_test_state().await;
+        let server = TestServer::builder()
+            .http_transport()
+            .build(create_router(state))
+            .unwrap();
+        let session = register_and_login(&server).await;
+        let token = server
+            .post("/api/auth/tokens")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "cli", "scope": "Write"}))
+            .await
+            .json::<Value>()["token"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let url = server.server_address().unwrap().to_string();
+        (server, ApiClient::new(&url, &token))
+    }
+
+    #[tokio::test]
+    async fn test_client_manages_boards_and_cards() {
+        let (_server, client) = setup_client().await;
+
+        let board = client
+            .create_board("Work", None, Some("kanban"))
+            .await
+            .unwrap();
+        assert_eq!(client.find_board("work").await.unwrap().id, board.id);
+        let prefix = short_id(board.id);
+        assert_eq!(client.find_board(&prefix).await.unwrap().name, "Work");
+
+        let details = client.board(board.id).await.unwrap();
+        let todo = details.find_column("to do").unwrap().clone();
+        let done = details.find_column("Done").unwrap().clone();
+        assert_eq!(details.default_column().unwrap().id, todo.id);
+
+        let card = client
+            .create_card(todo.id, "Write report", Some("By Friday"))
+            .await
+            .unwrap();
+        assert_eq!(card.column_id, Some(todo.id));
+
+        let details = client.board(board.id).await.unwrap();
+        let found = details.find_card(&short_id(card.id)).unwrap();
+        assert_eq!(found.title, "Write report");
+        let moved = client.move_card(found.id, done.id, 0).await.unwrap();
+        assert_eq!(moved.column_id, Some(done.id));
+
+        let inbox = client
+            .create_inbox_card("Call the bank", None)
+            .await
+            .unwrap();
+        assert_eq!(inbox.column_id, None);
+        assert_eq!(inbox.status, "open");
+    }
+
+    #[tokio::test]
+    async fn test_client_relays_mcp_messages() {
+        let (server, client) = setup_client().await;
+
+        let answer = client
+            .mcp(r#"{"jsonrpc":"2.0","id":7,"method":"ping"}"#)
+            .await
+            .unwrap();
+        assert_eq!(
+            serde_json::from_str::<Value>(&answer).unwrap(),
+            json!({"jsonrpc": "2.0", "id": 7, "result": {}})
+        );
+        assert!(client
+            .mcp(r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#)
+            .await
+            .is_none());
+
+        let url = server.server_address().unwrap().to_string();
+        let answer = ApiClient::new(&url, "not-a-token")
+            .mcp(r#"{"jsonrpc":"2.0","id":"a","method":"tools/list"}"#)
+            .await
+            .unwrap();
+        let answer: Value = serde_json::from_str(&answer).unwrap();
+        assert_eq!(answer["id"], "a");
+        assert_eq!(answer["error"]["message"], "Unauthorized");
+    }
+
+    #[tokio::test]
+    async fn test_client_reports_api_errors() {
+        let (server, client) = setup_client().await;
+
+        let error = client.create_board("", None, None).await.unwrap_err();
+        assert!(matches!(
+            error,
+            ClientError::Api { ref code, .. } if code == "validation_failed"
+        ));
+        assert!(matches!(
+            client.find_board("Missing").await,
+            Err(ClientError::Lookup(_))
+        ));
+
+        let url = server.server_address().unwrap().to_string();
+        let error = ApiClient::new(&url, "not-a-token")
+            .boards()
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            ClientError::Api { ref code, .. } if code == "unauthorized"
+        ));
+    }
+}
+
+// ============================================================================
+// Tag Tests
+// ============================================================================
+
+mod tag_tests {
+    use super::*;
+
+    async fn create_board_and_column(server: &TestServer, session: &str) -> (String, String) {
+        let board_response = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(session))
+            .json(&json!({"name": "Test Board"}))
+            .await;
+        let board_id = board_response.json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let column_response = server
+            .post(&format!("/api/boards/{}/columns", board_id))
+            .add_cookie(session_cookie(session))
+            .json(&json!({"name": "To Do"}))
+            .await;
+        let column_id = column_response.json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        (board_id, column_id)
+    }
+
+    #[tokio::test]
+    async fn test_create_tag() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+        let (board_id, _column_id) = create_board_and_column(&server, &session).await;
+
+        let response = server
+            .post(&format!("/api/boards/{}/tags", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({
+                "name": "Urgent",
+                "color": "#dc3545"
+            }))
+            .await;
+
+        response.assert_status_ok();
+        let body: Value = response.json();
+        assert_eq!(body["name"], "Urgent");
+        assert_eq!(body["color"], "#dc3545");
+    }
+
+    #[tokio::test]
+    async fn test_create_tag_default_color() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+        let (board_id, _column_id) = create_board_and_column(&server, &session).await;
+
+        let response = server
+            .post(&format!("/api/boards/{}/tags", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Default Color Tag"}))
+            .await;
+
+        response.assert_status_ok();
+        let body: Value = response.json();
+        assert_eq!(body["color"], "#6c757d");
+    }
+
+    #[tokio::test]
+    async fn test_create_tag_rejects_invalid_color() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+        let (board_id, _column_id) = create_board_and_column(&server, &session).await;
+
+        let response = server
+            .post(&format!("/api/boards/{}/tags", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({
+                "name": "Styled",
+                "color": "red; background-image: url(https://example.com/x)"
+            }))
+            .await;
+        response.assert_status(axum::http::StatusCode::UNPROCESSABLE_ENTITY);
+        assert!(response.json::<Value>()["details"]["fields"]["color"].is_string());
+
+        let response = server
+            .post(&format!("/api/boards/{}/tags", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Named", "color": "red"}))
+            .await;
+        response.assert_status_ok();
+        let tag_id = response.json::<Value>()["id"].as_str().unwrap().to_string();
+
+        server
+            .put(&format!("/api/tags/{}", tag_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"color": "#12345"}))
+            .await
+            .assert_status(axum::http::StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    #[tokio::test]
+    async fn test_list_tags() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+        let (board_id, _column_id) = create_board_and_column(&server, &session).await;
+
+        server
+            .post(&format!("/api/boards/{}/tags", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Tag 1"}))
+            .await;
+
+        server
+            .post(&format!("/api/boards/{}/tags", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Tag 2"}))
+            .await;
+
+        let response = server
+            .get(&format!("/api/boards/{}/tags", board_id))
+            .add_cookie(session_cookie(&session))
+            .await;
+
+        response.assert_status_ok();
+        let body: Value = response.json();
+        assert_eq!(body.as_array().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_update_tag() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+        let (board_id, _column_id) = create_board_and_column(&server, &session).await;
+
+        let create_response = server
+            .post(&format!("/api/boards/{}/tags", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Original", "color": "#000000"}))
+            .await;
+        let tag_id = create_response.json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let response = server
+            .put(&format!("/api/tags/{}", tag_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({
+                "name": "Updated",
+                "color": "#ffffff"
+            }))
+            .await;
+
+        response.assert_status_ok();
+        let body: Value = response.json();
+        assert_eq!(body["name"], "Updated");
+        assert_eq!(body["color"], "#ffffff");
+    }
+
+    #[tokio::test]
+    async fn test_delete_tag() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+        let (board_id, _column_id) = create_board_and_column(&server, &session).await;
+
+        let create_response = server
+            .post(&format!("/api/boards/{}/tags", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Tag to Delete"}))
+            .await;
+        let tag_id = create_response.json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let response = server
+            .delete(&format!("/api/tags/{}", tag_id))
+            .add_cookie(session_cookie(&session))
+            .await;
+
+        response.assert_status_ok();
+    }
+
+    #[tokio::test]
+    async fn test_add_tag_to_card() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+        let (board_id, column_id) = create_board_and_column(&server, &session).await;
+
+        let card_response = server
+            .post(&format!("/api/columns/{}/cards", column_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"title": "Card with Tag"}))
+            .await;
+        let card_id = card_response.json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let tag_response = server
+            .post(&format!("/api/boards/{}/tags", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Important"}))
+            .await;
+        let tag_id = tag_response.json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let response = server
+            .post(&format!("/api/cards/{}/tags/{}", card_id, tag_id))
+            .add_cookie(session_cookie(&session))
+            .await;
+
+        response.assert_status_ok();
+
+        let card_detail = server
+            .get(&format!("/api/cards/{}", card_id))
+            .add_cookie(session_cookie(&session))
+            .await;
+
+        let body: Value = card_detail.json();
+        let tags = body["tags"].as_array().unwrap();
+        assert_eq!(tags.len(), 1);
+        assert_eq!(tags[0]["name"], "Important");
+    }
+
+    #[tokio::test]
+    async fn test_remove_tag_from_card() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+        let (board_id, column_id) = create_board_and_column(&server, &session).await;
+
+        let card_response = server
+            .post(&format!("/api/columns/{}/cards", column_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"title": "Card with Tag"}))
+            .await;
+        let card_id = card_response.json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let tag_response = server
+            .post(&format!("/api/boards/{}/tags", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Removable"}))
+            .await;
+        let tag_id = tag_response.json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        server
+            .post(&format!("/api/cards/{}/tags/{}", card_id, tag_id))
+            .add_cookie(session_cookie(&session))
+            .await;
+
+        let response = server
+            .delete(&format!("/api/cards/{}/tags/{}", card_id, tag_id))
+            .add_cookie(session_cookie(&session))
+            .await;
+
+        response.assert_status_ok();
+
+        let card_detail = server
+            .get(&format!("/api/cards/{}", card_id))
+            .add_cookie(session_cookie(&session))
+            .await;
+
+        let body: Value = card_detail.json();
+        let tags = body["tags"].as_array().unwrap();
+        assert!(tags.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_link_duplicate_tags() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+
+        let board_id = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Linked Tags Board"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let column_id = server
+            .post(&format!("/api/boards/{}/columns", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "To Do"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let card_id = server
+            .post(&format!("/api/columns/{}/cards", column_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"title": "Tagged twice"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let board_tag_id = server
+            .post(&format!("/api/boards/{}/tags", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Urgent", "color": "#ff0000"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let global_tag_id = server
+            .post("/api/tags")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "urgent", "color": "#0000ff"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        for tag_id in [&board_tag_id, &global_tag_id] {
+            server
+                .post(&format!("/api/cards/{}/tags/{}", card_id, tag_id))
+                .add_cookie(session_cookie(&session))
+                .await
+                .assert_status_ok();
+        }
+
+        let response = server
+            .post(&format!(
+                "/api/boards/{}/tags/link-duplicates?dry_run=true",
+                board_id
+            ))
+            .add_cookie(session_cookie(&session))
+            .await;
+        response.assert_status_ok();
+        let body: Value = response.json();
+        assert_eq!(body["links"][0]["board_tag_id"], board_tag_id.as_str());
+        assert_eq!(body["links"][0]["global_tag_id"], global_tag_id.as_str());
+
+        let tags: Value = server
+            .get(&format!("/api/boards/{}/tags", board_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert!(tags[0]["linked_tag_id"].is_null());
+
+        server
+            .post(&format!("/api/boards/{}/tags/link-duplicates", board_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .assert_status_ok();
+
+        let tags: Value = server
+            .get(&format!("/api/boards/{}/tags", board_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(tags[0]["linked_tag_id"], global_tag_id.as_str());
+        assert_eq!(tags[0]["name"], "urgent");
+        assert_eq!(tags[0]["color"], "#0000ff");
+
+        // The card shows the global tag once instead of two look-alikes
+        let card: Value = server
+            .get(&format!("/api/cards/{}", card_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        let card_tags = card["tags"].as_array().unwrap();
+        assert_eq!(card_tags.len(), 1);
+        assert_eq!(card_tags[0]["id"], global_tag_id.as_str());
+
+        server
+            .put(&format!("/api/tags/{}", board_tag_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"color": "#00ff00"}))
+            .await
+            .assert_status_bad_request();
+
+        let response = server
+            .delete(&format!("/api/tags/{}/link", board_tag_id))
+            .add_cookie(session_cookie(&session))
+            .await;
+        response.assert_status_ok();
+        let body: Value = response.json();
+        assert!(body["linked_tag_id"].is_null());
+        assert_eq!(body["color"], "#0000ff");
+    }
+
+    #[tokio::test]
+    async fn test_link_tag_to_foreign_global_tag_forbidden() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+        let other_session = register_and_login(&server).await;
+
+        let board_id = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Board"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let board_tag_id = server
+            .post(&format!("/api/boards/{}/tags", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Bug"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let foreign_tag_id = server
+            .post("/api/tags")
+            .add_cookie(session_cookie(&other_session))
+            .json(&json!({"name": "Bug"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        server
+            .put(&format!("/api/tags/{}/link", board_tag_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"global_tag_id": foreign_tag_id}))
+            .await
+            .assert_status_forbidden();
+    }
+}
+
+// ============================================================================
+// Comment Draft Tests
+// ============================================================================
+
+mod comment_draft_tests {
+    use super::*;
+
+    async fn create_inbox_card(server: &TestServer, session: &str) -> String {
+        server
+            .post("/api/cards")
+            .add_cookie(session_cookie(session))
+            .json(&json!({"title": "Inbox Card"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string()
+    }
+
+    #[tokio::test]
+    async fn test_save_and_get_comment_draft() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+        let card_id = create_inbox_card(&server, &session).await;
+        let url = format!("/api/cards/{}/comment-draft", card_id);
+
+        let response = server.get(&url).add_cookie(session_cookie(&session)).await;
+        response.assert_status_ok();
+        assert!(response.json::<Value>().is_null());
+
+        server
+            .put(&url)
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"body": "First try"}))
+            .await
+            .assert_status_ok();
+        let response = server
+            .put(&url)
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"body": "Second try"}))
+            .await;
+        response.assert_status_ok();
+        assert_eq!(response.json::<Value>()["body"], "Second try");
+
+        let response = server.get(&url).add_cookie(session_cookie(&session)).await;
+        assert_eq!(response.json::<Value>()["body"], "Second try");
+
+        let page = server
+            .get(&format!("/cards/{}", card_id))
+            .add_cookie(session_cookie(&session))
+            .await;
+        assert!(page.text().contains("Second try"));
+    }
+
+    #[tokio::test]
+    async fn test_posting_comment_discards_draft() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+        let card_id = create_inbox_card(&server, &session).await;
+        let url = format!("/api/cards/{}/comment-draft", card_id);
+
+        server
+            .put(&url)
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"body": "Almost done"}))
+            .await
+            .assert_status_ok();
+
+        server
+            .post(&format!("/api/cards/{}/comments", card_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"body": "Almost done"}))
+            .await
+            .assert_status_ok();
+
+        let response = server.get(&url).add_cookie(session_cookie(&session)).await;
+        assert!(response.json::<Value>().is_null());
+    }
+
+    #[tokio::test]
+    async fn test_comment_draft_requires_access() {
+        let server = setup_server().await;
+        let owner = register_and_login(&server).await;
+        let other = register_and_login(&server).await;
+        let card_id = create_inbox_card(&server, &owner).await;
+
+        server
+            .put(&format!("/api/cards/{}/comment-draft", card_id))
+            .add_cookie(session_cookie(&other))
+            .json(&json!({"body": "Sneaky"}))
+            .await
+            .assert_status_forbidden();
+    }
+}
+
+// ============================================================================
+// Forecast Tests
+// ============================================================================
+
+mod forecast_tests {
+    use super::*;
+    use uuid::Uuid;
+
+    async fn create_board_with_cards(
+        server: &TestServer,
+        session: &str,
+        count: usize,
+    ) -> (String, Vec<String>) {
+        let board_id = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(session))
+            .json(&json!({"name": "Forecast Board"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let column_id = server
+            .post(&format!("/api/boards/{}/columns", board_id))
+            .add_cookie(session_cookie(session))
+            .json(&json!({"name": "To Do"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let mut card_ids = Vec::new();
+        for i in 0..count {
+            let card_id = server
+                .post(&format!("/api/columns/{}/cards", column_id))
+                .add_cookie(session_cookie(session))
+                .json(&json!({"title": format!("Card {}", i)}))
+                .await
+                .json::<Value>()["id"]
+                .as_str()
+                .unwrap()
+                .to_string();
+            card_ids.push(card_id);
+        }
+
+        (board_id, card_ids)
+    }
+
+    #[tokio::test]
+    async fn test_forecast_without_history() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+        let (board_id, _) = create_board_with_cards(&server, &session, 3).await;
+
+        let response = server
+            .get(&format!("/api/boards/{}/forecast", board_id))
+            .add_cookie(session_cookie(&session))
+            .await;
+
+        response.assert_status_ok();
+        let body: Value = response.json();
+        assert_eq!(body["remaining_cards"], 3);
+        assert_eq!(body["history_weeks"], 12);
+        assert_eq!(body["simulations"], 0);
+        assert!(body["percentiles"].as_array().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_forecast_with_completed_cards() {
+        let (server, state) = setup_server_with_state().await;
+        let session = register_and_login(&server).await;
+        let (board_id, card_ids) = create_board_with_cards(&server, &session, 4).await;
+
+        for card_id in &card_ids[..2] {
+            server
+                .patch(&format!("/api/cards/{}", card_id))
+                .add_cookie(session_cookie(&session))
+                .json(&json!({"status": "Done"}))
+                .await
+                .assert_status_ok();
+        }
+
+        // Completion is dated by the status change, not by later edits
+        sqlx::query(
+            "UPDATE activity_log SET created_at = datetime('now', '-10 days') WHERE card_id = $1",
+        )
+        .bind(card_ids[0].parse::<Uuid>().unwrap())
+        .execute(state.pool.as_ref())
+        .await
+        .unwrap();
+        server
+            .patch(&format!("/api/cards/{}", card_ids[0]))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"title": "Edited after completion"}))
+            .await
+            .assert_status_ok();
+
+        let response = server
+            .get(&format!(
+                "/api/boards/{}/forecast?weeks=4&cards=5",
+                board_id
+            ))
+            .add_cookie(session_cookie(&session))
+            .await;
+
+        response.assert_status_ok();
+        let body: Value = response.json();
+        assert_eq!(body["remaining_cards"], 5);
+        assert_eq!(body["weekly_throughput"], json!([0, 0, 1, 1]));
+        let percentiles = body["percentiles"].as_array().unwrap();
+        assert_eq!(percentiles.len(), 4);
+        assert_eq!(percentiles[0]["percentile"], 50);
+        assert!(percentiles[3]["weeks"].as_u64().unwrap() >= 3);
+    }
+
+    #[tokio::test]
+    async fn test_forecast_requires_access() {
+        let server = setup_server().await;
+        let owner = register_and_login(&server).await;
+        let other = register_and_login(&server).await;
+        let (board_id, _) = create_board_with_cards(&server, &owner, 0).await;
+
+        let response = server
+            .get(&format!("/api/boards/{}/forecast", board_id))
+            .add_cookie(session_cookie(&other))
+            .await;
+
+        response.assert_status_forbidden();
+    }
+}
+
+// ============================================================================
+// Board Statistics Tests
+// ============================================================================
+
+mod stats_tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_board_stats() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+        let other = register_and_login(&server).await;
+
+        let board_id = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Stats Board"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let mut column_ids = Vec::new();
+        for name in ["To Do", "Done"] {
+            let column: Value = server
+                .post(&format!("/api/boards/{}/columns", board_id))
+                .add_cookie(session_cookie(&session))
+                .json(&json!({"name": name}))
+                .await
+                .json();
+            column_ids.push(column["id"].as_str().unwrap().to_string());
+        }
+        let mut card_ids = Vec::new();
+        for card in [
+            json!({"title": "Moved"}),
+            json!({"title": "Updated"}),
+            json!({"title": "Late", "due_date": "2020-01-01"}),
+        ] {
+            let id = server
+                .post(&format!("/api/columns/{}/cards", column_ids[0]))
+                .add_cookie(session_cookie(&session))
+                .json(&card)
+                .await
+                .json::<Value>()["id"]
+                .as_str()
+                .unwrap()
+                .to_string();
+            card_ids.push(id);
+        }
+        let tag_id = server
+            .post(&format!("/api/boards/{}/tags", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "bug", "color": "#dc3545"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        server
+            .post(&format!("/api/cards/{}/tags/{}", card_ids[2], tag_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .assert_status_ok();
+
+        // One card is completed by moving it to Done, the other by its status
+        server
+            .patch(&format!("/api/cards/{}/move", card_ids[0]))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"column_id": column_ids[1], "position": 0}))
+            .await
+            .assert_status_ok();
+        server
+            .patch(&format!("/api/cards/{}", card_ids[1]))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"status": "Done"}))
+            .await
+            .assert_status_ok();
+
+        let response = server
+            .get(&format!("/api/boards/{}/stats?weeks=4", board_id))
+            .add_cookie(session_cookie(&session))
+            .await;
+        response.assert_status_ok();
+        let stats: Value = response.json();
+        assert_eq!(stats["columns"][0]["name"], "To Do");
+        assert_eq!(stats["columns"][0]["cards"], 2);
+        assert_eq!(stats["columns"][1]["cards"], 1);
+        assert_eq!(stats["tags"][0]["name"], "bug");
+        assert_eq!(stats["tags"][0]["cards"], 1);
+        assert_eq!(stats["overdue_cards"], 1);
+        assert_eq!(stats["throughput"].as_array().unwrap().len(), 4);
+        assert_eq!(stats["throughput"][3]["completed"], 2);
+        assert_eq!(stats["completed_cards"], 2);
+        assert!(stats["average_cycle_time_days"].is_number());
+
+        // The settings page shows the same figures
+        let page = server
+            .get(&format!("/boards/{}/settings", board_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .text();
+        assert!(page.contains("Statistics"));
+        assert!(page.contains("bug: 1"));
+
+        server
+            .get(&format!("/api/boards/{}/stats?weeks=0", board_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .assert_status(axum::http::StatusCode::UNPROCESSABLE_ENTITY);
+        server
+            .get(&format!("/api/boards/{}/stats", board_id))
+            .add_cookie(session_cookie(&other))
+            .await
+            .assert_status_forbidden();
+    }
+}
+
+// ============================================================================
+// Settings Tests
+// ============================================================================
+
+mod settings_tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_get_and_update_settings() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+
+        let response = server
+            .get("/api/settings")
+            .add_cookie(session_cookie(&session))
+            .await;
+        response.assert_status_ok();
+        let body: Value = response.json();
+        assert_eq!(body["theme"], "system");
+        assert!(body["llm_context"].is_null());
+
+        let response = server
+            .patch("/api/settings")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"theme": "dark", "llm_context": "  I work on billing  "}))
+            .await;
+        response.assert_status_ok();
+        let body: Value = response.json();
+        assert_eq!(body["theme"], "dark");
+        assert_eq!(body["llm_context"], "I work on billing");
+
+        // Omitted fields are kept, null clears the context
+        let response = server
+            .patch("/api/settings")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"llm_context": null}))
+            .await;
+        let body: Value = response.json();
+        assert_eq!(body["theme"], "dark");
+        assert!(body["llm_context"].is_null());
+
+        server
+            .patch("/api/settings")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"theme": "neon"}))
+            .await
+            .assert_status_bad_request();
+    }
+}
+
+// ============================================================================
+// Roadmap Tests
+// ============================================================================
+
+mod roadmap_tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_roadmap_groups_by_quarter() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+
+        let board_id = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Roadmap Board"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let column_id = server
+            .post(&format!("/api/boards/{}/columns", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "To Do"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let cards = [
+            json!({"title": "Migrate billing", "due_date": "2024-05-20"}),
+            json!({"title": "Fix login", "due_date": "2024-01-10"}),
+            json!({"title": "Someday"}),
+            json!({"title": "Shipped", "status": "Done", "due_date": "2024-02-01"}),
+            json!({"title": "v2 release", "due_date": "2024-06-30"}),
+        ];
+        let mut card_ids = Vec::new();
+        for card in cards {
+            let id = server
+                .post(&format!("/api/columns/{}/cards", column_id))
+                .add_cookie(session_cookie(&session))
+                .json(&card)
+                .await
+                .json::<Value>()["id"]
+                .as_str()
+                .unwrap()
+                .to_string();
+            card_ids.push(id);
+        }
+
+        let tag_id = server
+            .post(&format!("/api/boards/{}/tags", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Milestone", "color": "#ff0000"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        server
+            .post(&format!("/api/cards/{}/tags/{}", card_ids[4], tag_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .assert_status_ok();
+
+        let response = server
+            .get(&format!(
+                "/api/boards/{}/roadmap?group_by=quarter",
+                board_id
+            ))
+            .add_cookie(session_cookie(&session))
+            .await;
+
+        response.assert_status_ok();
+        let body: Value = response.json();
+        assert_eq!(body["group_by"], "quarter");
+        let buckets = body["buckets"].as_array().unwrap();
+        assert_eq!(buckets.len(), 3);
+
+        assert_eq!(buckets[0]["key"], "2024-Q1");
+        assert_eq!(buckets[0]["cards"].as_array().unwrap().len(), 1);
+        assert_eq!(buckets[0]["cards"][0]["title"], "Fix login");
+
+        assert_eq!(buckets[1]["label"], "Q2 2024");
+        assert_eq!(buckets[1]["cards"][0]["title"], "Migrate billing");
+        assert_eq!(buckets[1]["milestones"][0]["title"], "v2 release");
+
+        assert!(buckets[2]["key"].is_null());
+        assert_eq!(buckets[2]["cards"][0]["title"], "Someday");
+
+        let page = server
+            .get(&format!("/boards/{}/roadmap?group_by=quarter", board_id))
+            .add_cookie(session_cookie(&session))
+            .await;
+        page.assert_status_ok();
+        assert!(page.text().contains("Q2 2024"));
+    }
+
+    #[tokio::test]
+    async fn test_roadmap_requires_board_access() {
+        let server = setup_server().await;
+        let owner = register_and_login(&server).await;
+        let other = register_and_login(&server).await;
+
+        let board_id = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&owner))
+            .json(&json!({"name": "Private Board"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        server
+            .get(&format!("/api/boards/{}/roadmap", board_id))
+            .add_cookie(session_cookie(&other))
+            .await
+            .assert_status_forbidden();
+    }
+}
+
+// ============================================================================
+// Timeline Tests
+// ============================================================================
+
+mod timeline_tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_timeline_lanes_and_filters() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+
+        let board_id = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Timeline Board"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let mut column_ids = Vec::new();
+        for name in ["To Do", "Doing"] {
+            let column: Value = server
+                .post(&format!("/api/boards/{}/columns", board_id))
+                .add_cookie(session_cookie(&session))
+                .json(&json!({"name": name}))
+                .await
+                .json();
+            column_ids.push(column["id"].as_str().unwrap().to_string());
+        }
+
+        let cards = [
+            (
+                0,
+                json!({"title": "Design", "start_date": "2024-04-01", "end_date": "2024-04-10"}),
+            ),
+            (
+                0,
+                json!({"title": "Launch", "start_date": "2024-05-01", "due_date": "2024-05-03"}),
+            ),
+            (0, json!({"title": "Someday"})),
+            (
+                1,
+                json!({"title": "Build", "start_date": "2024-04-05", "end_date": "2024-04-20"}),
+            ),
+        ];
+        let mut card_ids = Vec::new();
+        for (column, card) in cards {
+            let id = server
+                .post(&format!("/api/columns/{}/cards", column_ids[column]))
+                .add_cookie(session_cookie(&session))
+                .json(&card)
+                .await
+                .json::<Value>()["id"]
+                .as_str()
+                .unwrap()
+                .to_string();
+            card_ids.push(id);
+        }
+
+        let timeline: Value = server
+            .get(&format!("/api/boards/{}/timeline", board_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(timeline["start_date"], "2024-04-01");
+        assert_eq!(timeline["end_date"], "2024-05-03");
+        let lanes = timeline["lanes"].as_array().unwrap();
+        assert_eq!(lanes.len(), 2);
+        assert_eq!(lanes[0]["column_name"], "To Do");
+        assert_eq!(lanes[0]["cards"][0]["title"], "Design");
+        assert_eq!(lanes[0]["cards"][1]["bar_end"], "2024-05-03");
+        assert_eq!(timeline["unscheduled"][0]["title"], "Someday");
+
+        // Filter by column
+        let timeline: Value = server
+            .get(&format!(
+                "/api/boards/{}/timeline?column_id={}",
+                board_id, column_ids[1]
+            ))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(timeline["lanes"].as_array().unwrap().len(), 1);
+        assert_eq!(timeline["lanes"][0]["cards"][0]["title"], "Build");
+
+        // Filter by tag
+        let tag_id = server
+            .post(&format!("/api/boards/{}/tags", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "launch", "color": "#ff0000"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        server
+            .post(&format!("/api/cards/{}/tags/{}", card_ids[1], tag_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .assert_status_ok();
+        let timeline: Value = server
+            .get(&format!(
+                "/api/boards/{}/timeline?tag_id={}",
+                board_id, tag_id
+            ))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(timeline["start_date"], "2024-05-01");
+        assert_eq!(timeline["lanes"][0]["cards"].as_array().unwrap().len(), 1);
+
+        // Date range
+        let timeline: Value = server
+            .get(&format!(
+                "/api/boards/{}/timeline?from=2024-04-15&to=2024-04-30",
+                board_id
+            ))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(timeline["lanes"].as_array().unwrap().len(), 1);
+        assert_eq!(timeline["lanes"][0]["cards"][0]["title"], "Build");
+        server
+            .get(&format!(
+                "/api/boards/{}/timeline?from=2024-05-01&to=2024-04-01",
+                board_id
+            ))
+            .add_cookie(session_cookie(&session))
+            .await
+            .assert_status(axum::http::StatusCode::UNPROCESSABLE_ENTITY);
+
+        // The web page draws the bars
+        let page = server
+            .get(&format!("/boards/{}/timeline", board_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .text();
+        assert!(page.contains("Design"));
+        assert!(page.contains("Apr 2024"));
+    }
+}
+
+// ============================================================================
+// Chat Export Tests
+// ============================================================================
+
+mod chat_export_tests {
+    use super::*;
+
+    async fn current_user_id(state: &AppState, session: &str) -> uuid::Uuid {
+        state
+            .sessions
+            .find_by_token(session)
+            .await
+            .unwrap()
+            .unwrap()
+            .user_id
+    }
+
+    #[tokio::test]
+    async fn test_export_global_markdown() {
+        let (server, state) = setup_server_with_state().await;
+        let session = register_and_login(&server).await;
+        let user_id = current_user_id(&state, &session).await;
+
+        let actions = r#"[{"action":"create_inbox_card","description":"Created card 'Buy milk'","success":true}]"#;
+        state
+            .chat_messages
+            .create_global(user_id, "Remind me to buy milk", "Done!", Some(actions))
+            .await
+            .unwrap();
+
+        let response = server
+            .get("/api/chat/export")
+            .add_cookie(session_cookie(&session))
+            .await;
+
+        response.assert_status_ok();
+        assert!(response
+            .header("content-type")
+            .to_str()
+            .unwrap()
+            .starts_with("text/markdown"));
+        let text = response.text();
+        assert!(text.contains("# Chat history: global"));
+        assert!(text.contains("**You:** Remind me to buy milk"));
+        assert!(text.contains("- [x] create_inbox_card: Created card 'Buy milk'"));
+    }
+
+    #[tokio::test]
+    async fn test_export_board_json_with_date_range() {
+        let (server, state) = setup_server_with_state().await;
+        let session = register_and_login(&server).await;
+        let user_id = current_user_id(&state, &session).await;
+
+        let board: Value = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Chat Board"}))
+            .await
+            .json();
+        let board_id: uuid::Uuid = board["id"].as_str().unwrap().parse().unwrap();
+
+        state
+            .chat_messages
+            .create(board_id, user_id, "Hello board", "Hi", None)
+            .await
+            .unwrap();
+        state
+            .chat_messages
+            .create_global(user_id, "Hello global", "Hi", None)
+            .await
+            .unwrap();
+
+        let response = server
+            .get(&format!(
+                "/api/chat/export?scope=board&board_id={}&format=json",
+                board_id
+            ))
+            .add_cookie(session_cookie(&session))
+            .await;
+
+        response.assert_status_ok();
+        let body: Value = response.json();
+        assert_eq!(body["scope"], "board");
+        assert_eq!(body["board_name"], "Chat Board");
+        let messages = body["messages"].as_array().unwrap();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0]["message"], "Hello board");
+
+        let response = server
+            .get(&format!(
+                "/api/chat/export?scope=board&board_id={}&format=json&to=2000-01-01",
+                board_id
+            ))
+            .add_cookie(session_cookie(&session))
+            .await;
+
+        response.assert_status_ok();
+        let body: Value = response.json();
+        assert!(body["messages"].as_array().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_export_board_scope_requires_access() {
+        let server = setup_server().await;
+        let owner = register_and_login(&server).await;
+        let other = register_and_login(&server).await;
+
+        let board: Value = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&owner))
+            .json(&json!({"name": "Private Board"}))
+            .await
+            .json();
+
+        let response = server
+            .get(&format!(
+                "/api/chat/export?scope=board&board_id={}",
+                board["id"].as_str().unwrap()
+            ))
+            .add_cookie(session_cookie(&other))
+            .await;
+        response.assert_status_forbidden();
+
+        let response = server
+            .get("/api/chat/export?scope=board")
+            .add_cookie(session_cookie(&owner))
+            .await;
+        response.assert_status_bad_request();
+    }
+
+    #[tokio::test]
+    async fn test_board_chat_stats() {
+        let (server, state) = setup_server_with_state().await;
+        let session = register_and_login(&server).await;
+        let user_id = current_user_id(&state, &session).await;
+
+        let board: Value = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Stats Board"}))
+            .await
+            .json();
+        let board_id: uuid::Uuid = board["id"].as_str().unwrap().parse().unwrap();
+
+        let first = r#"[{"action":"create_card","description":"Created card 'A'","success":true},{"action":"create_card","description":"Column 'Todo' not found","success":false}]"#;
+        let second = r#"[{"action":"create_card","description":"Column 'Later' not found","success":false}]"#;
+        for actions in [first, second] {
+            state
+                .chat_messages
+                .create(board_id, user_id, "Add cards", "Ok", Some(actions))
+                .await
+                .unwrap();
+        }
+
+        let response = server
+            .get(&format!("/api/boards/{}/chat-stats?days=7", board_id))
+            .add_cookie(session_cookie(&session))
+            .await;
+
+        response.assert_status_ok();
+        let body: Value = response.json();
+        assert_eq!(body["messages"], 2);
+        assert_eq!(body["total"], 3);
+        assert_eq!(body["failed"], 2);
+        assert_eq!(body["by_action"][0]["action"], "create_card");
+        assert_eq!(body["daily"].as_array().unwrap().len(), 1);
+        assert_eq!(body["top_failures"][0]["reason"], "Column '*' not found");
+        assert_eq!(body["top_failures"][0]["count"], 2);
+
+        let response = server
+            .get(&format!("/api/boards/{}/chat-stats?days=0", board_id))
+            .add_cookie(session_cookie(&session))
+            .await;
+
+        response.assert_status_unprocessable_entity();
+    }
+}
+
+// ============================================================================
+// Theme Tests
+// ============================================================================
+
+mod theme_tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_theme_registry() {
+        let server = setup_server().await;
+
+        let response = server.get("/static/themes.json").await;
+
+        response.assert_status_ok();
+        let body: Value = response.json();
+        let ids: Vec<&str> = body
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["id"].as_str().unwrap())
+            .collect();
+        assert_eq!(ids, vec!["system", "light", "dark", "high-contrast"]);
+        assert_eq!(body[2]["stylesheet"], "/static/css/themes/dark.css");
+    }
+
+    #[tokio::test]
+    async fn test_user_theme_is_rendered_server_side() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+
+        let page = server
+            .get("/settings")
+            .add_cookie(session_cookie(&session))
+            .await;
+        assert!(page.text().contains(r#"data-theme="system""#));
+
+        server
+            .post("/settings/theme")
+            .add_cookie(session_cookie(&session))
+            .form(&[("theme", "high-contrast")])
+            .await
+            .assert_status_see_other();
+
+        let page = server
+            .get("/boards")
+            .add_cookie(session_cookie(&session))
+            .await;
+        assert!(page.text().contains(r#"data-theme="high-contrast""#));
+
+        server
+            .post("/settings/theme")
+            .add_cookie(session_cookie(&session))
+            .form(&[("theme", "neon")])
+            .await
+            .assert_status_bad_request();
+    }
+}
+
+// ============================================================================
+// Data Backfill Tests
+// ============================================================================
+
+mod backfill_tests {
+    use super::*;
+    use personal_os::services::backfill;
+
+    #[tokio::test]
+    async fn test_backfills_run_at_startup() {
+        let (server, state) = setup_server_with_state().await;
+        let session = register_and_login(&server).await;
+
+        // Running again is a no-op once every backfill completed
+        backfill::run_pending(&state.backfills).await.unwrap();
+
+        let response = server
+            .get("/api/backfills")
+            .add_cookie(session_cookie(&session))
+            .await;
+
+        response.assert_status_ok();
+        let body: Value = response.json();
+        let backfills = body.as_array().unwrap();
+        assert_eq!(backfills.len(), backfill::BACKFILLS.len());
+        assert_eq!(backfills[0]["version"], 1);
+        assert_eq!(backfills[0]["name"], "card_owner_from_creator");
+        assert_eq!(backfills[0]["status"], "completed");
+        assert!(backfills[0]["completed_at"].is_string());
+    }
+
+    #[tokio::test]
+    async fn test_backfills_require_auth() {
+        let server = setup_server().await;
+
+        let response = server.get("/api/backfills").await;
+
+        response.assert_status_unauthorized();
+    }
+}
+
+// ============================================================================
+// Planning Session Tests
+// ============================================================================
+
+mod planning_session_tests {
+    use super::*;
+
+    async fn create_inbox_card(server: &TestServer, session: &str, card: Value) -> String {
+        server
+            .post("/api/cards")
+            .add_cookie(session_cookie(session))
+            .json(&card)
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string()
+    }
+
+    #[tokio::test]
+    async fn test_weekly_planning_session() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+
+        let overdue_id = create_inbox_card(
+            &server,
+            &session,
+            json!({"title": "Overdue report", "due_date": "2020-01-01"}),
+        )
+        .await;
+        let unscheduled_id =
+            create_inbox_card(&server, &session, json!({"title": "Someday task"})).await;
+
+        let response = server
+            .post("/api/chat/sessions")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"type": "weekly_planning"}))
+            .await;
+        response.assert_status_ok();
+        let body: Value = response.json();
+        assert_eq!(body["step"], "overdue");
+        assert_eq!(body["items"][0]["card_id"], overdue_id.as_str());
+        let session_id = body["id"].as_str().unwrap().to_string();
+        let turns_url = format!("/api/chat/sessions/{}/turns", session_id);
+
+        // Only cards of the current step can be scheduled
+        server
+            .post(&turns_url)
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"due_dates": {unscheduled_id.clone(): "2099-01-01"}}))
+            .await
+            .assert_status_unprocessable_entity();
+
+        let body: Value = server
+            .post(&turns_url)
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"due_dates": {overdue_id.clone(): "2099-01-01"}}))
+            .await
+            .json();
+        assert_eq!(body["step"], "unscheduled");
+        assert_eq!(body["items"][0]["card_id"], unscheduled_id.as_str());
+
+        let body: Value = server
+            .post(&turns_url)
+            .add_cookie(session_cookie(&session))
+            .json(&json!({}))
+            .await
+            .json();
+        assert_eq!(body["step"], "focus");
+
+        let body: Value = server
+            .post(&turns_url)
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"focus": [unscheduled_id]}))
+            .await
+            .json();
+        assert_eq!(body["step"], "review");
+        assert_eq!(body["plan"]["due_dates"][0]["due_date"], "2099-01-01");
+        assert_eq!(body["plan"]["focus"][0]["card_id"], unscheduled_id.as_str());
+
+        // Nothing is applied before confirmation
+        let card: Value = server
+            .get(&format!("/api/inbox/cards/{}", overdue_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(card["due_date"], "2020-01-01");
+
+        let body: Value = server
+            .post(&turns_url)
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"confirm": true}))
+            .await
+            .json();
+        assert_eq!(body["status"], "completed");
+        assert_eq!(body["step"], "done");
+
+        let card: Value = server
+            .get(&format!("/api/inbox/cards/{}", overdue_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(card["due_date"], "2099-01-01");
+        let card: Value = server
+            .get(&format!("/api/inbox/cards/{}", unscheduled_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(card["status"], "in_progress");
+
+        server
+            .post(&turns_url)
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"confirm": true}))
+            .await
+            .assert_status_bad_request();
+    }
+
+    #[tokio::test]
+    async fn test_planning_session_is_private() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+        let other_session = register_and_login(&server).await;
+
+        let session_id = server
+            .post("/api/chat/sessions")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"type": "weekly_planning"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        server
+            .get(&format!("/api/chat/sessions/{}", session_id))
+            .add_cookie(session_cookie(&other_session))
+            .await
+            .assert_status_not_found();
+    }
+}
+
+// ============================================================================
+// Admin Tests
+// ============================================================================
+
+mod admin_tests {
+    use super::*;
+    use personal_os::models::BoardRole;
+
+    async fn login_admin(state: &AppState) -> String {
+        let user_id = test_utils::create_test_user(state, "admin@example.com", "Admin").await;
+        state.users.set_admin(user_id, true).await.unwrap();
+        test_utils::create_test_session(state, user_id).await
+    }
+
+    fn check<'a>(report: &'a Value, name: &str) -> &'a Value {
+        report["checks"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|c| c["check"] == name)
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_integrity_scan_and_repair() {
+        let (server, state) = setup_server_with_state().await;
+        let session = login_admin(&state).await;
+
+        let card: Value = server
+            .post("/api/cards")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"title": "Orphaned links"}))
+            .await
+            .json();
+        let card_id = card["id"].as_str().unwrap();
+        let card_uuid: uuid::Uuid = card_id.parse().unwrap();
+
+        // Simulate rows left behind by data written before the foreign keys existed
+        sqlx::query("PRAGMA foreign_keys = OFF")
+            .execute(state.pool.as_ref())
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO card_boards (id, card_id, board_id) VALUES ($1, $2, $3)")
+            .bind(uuid::Uuid::new_v4())
+            .bind(card_uuid)
+            .bind(uuid::Uuid::new_v4())
+            .execute(state.pool.as_ref())
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO card_tags (card_id, tag_id) VALUES ($1, $2)")
+            .bind(card_uuid)
+            .bind(uuid::Uuid::new_v4())
+            .execute(state.pool.as_ref())
+            .await
+            .unwrap();
+        sqlx::query("PRAGMA foreign_keys = ON")
+            .execute(state.pool.as_ref())
+            .await
+            .unwrap();
+
+        let response = server
+            .get("/api/admin/integrity")
+            .add_cookie(session_cookie(&session))
+            .await;
+        response.assert_status_ok();
+        let report: Value = response.json();
+        assert_eq!(report["repaired"], false);
+        assert_eq!(report["total_found"], 2);
+        assert_eq!(check(&report, "card_boards_missing_board")["found"], 1);
+        assert_eq!(check(&report, "card_tags_missing_card_or_tag")["found"], 1);
+
+        let response = server
+            .post("/api/admin/integrity/repair")
+            .add_cookie(session_cookie(&session))
+            .await;
+        response.assert_status_ok();
+        let report: Value = response.json();
+        assert_eq!(report["repaired"], true);
+        assert_eq!(report["total_fixed"], 2);
+
+        let report: Value = server
+            .get("/api/admin/integrity")
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(report["total_found"], 0);
+
+        // The card itself is untouched
+        server
+            .get(&format!("/api/inbox/cards/{}", card_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .assert_status_ok();
+    }
+
+    #[tokio::test]
+    async fn test_integrity_requires_admin() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+
+        server
+            .get("/api/admin/integrity")
+            .add_cookie(session_cookie(&session))
+            .await
+            .assert_status_forbidden();
+        server
+            .post("/api/admin/integrity/repair")
+            .add_cookie(session_cookie(&session))
+            .await
+            .assert_status_forbidden();
+    }
+
+    #[tokio::test]
+    async fn test_admin_manages_accounts() {
+        let (server, state) = setup_server_with_state().await;
+        let admin = login_admin(&state).await;
+        let member_id = test_utils::create_test_user(&state, "member@example.com", "Member").await;
+        let member = test_utils::create_test_session(&state, member_id).await;
+
+        server
+            .get("/api/admin/users")
+            .add_cookie(session_cookie(&member))
+            .await
+            .assert_status_forbidden();
+        let users: Value = server
+            .get("/api/admin/users")
+            .add_cookie(session_cookie(&admin))
+            .await
+            .json();
+        let emails: Vec<&str> = users
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|u| u["email"].as_str().unwrap())
+            .collect();
+        assert_eq!(emails, ["admin@example.com", "member@example.com"]);
+
+        // Deactivating logs the user out and keeps them out
+        let account: Value = server
+            .patch(&format!("/api/admin/users/{}", member_id))
+            .add_cookie(session_cookie(&admin))
+            .json(&json!({"active": false}))
+            .await
+            .json();
+        assert!(account["deactivated_at"].is_string());
+        server
+            .get("/api/boards")
+            .add_cookie(session_cookie(&member))
+            .await
+            .assert_status_unauthorized();
+        let login = json!({"email": "member@example.com", "password": "testpassword123"});
+        server
+            .post("/api/auth/login")
+            .json(&login)
+            .await
+            .assert_status_forbidden();
+
+        // Administrators cannot lock themselves out
+        let admin_id = state
+            .users
+            .find_by_email("admin@example.com")
+            .await
+            .unwrap()
+            .unwrap()
+            .id;
+        server
+            .patch(&format!("/api/admin/users/{}", admin_id))
+            .add_cookie(session_cookie(&admin))
+            .json(&json!({"is_admin": false}))
+            .await
+            .assert_status_bad_request();
+
+        let account: Value = server
+            .patch(&format!("/api/admin/users/{}", member_id))
+            .add_cookie(session_cookie(&admin))
+            .json(&json!({"active": true, "is_admin": true}))
+            .await
+            .json();
+        assert!(account["deactivated_at"].is_null());
+        assert_eq!(account["is_admin"], true);
+        let member = server
+            .post("/api/auth/login")
+            .json(&login)
+            .await
+            .cookie("session");
+        server
+            .get("/api/admin/stats")
+            .add_cookie(member)
+            .await
+            .assert_status_ok();
+    }
+
+    #[tokio::test]
+    async fn test_admin_stats_and_board_transfer() {
+        let (server, state) = setup_server_with_state().await;
+        let admin = login_admin(&state).await;
+        let member_id = test_utils::create_test_user(&state, "member@example.com", "Member").await;
+        let member = test_utils::create_test_session(&state, member_id).await;
+        let board: Value = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&member))
+            .json(&json!({"name": "Household"}))
+            .await
+            .json();
+        let board_id = board["id"].as_str().unwrap();
+        server
+            .post("/api/cards")
+            .add_cookie(session_cookie(&member))
+            .json(&json!({"title": "Fix the sink"}))
+            .await
+            .assert_status_ok();
+
+        let stats: Value = server
+            .get("/api/admin/stats")
+            .add_cookie(session_cookie(&admin))
+            .await
+            .json();
+        assert_eq!(
+            stats["users"],
+            json!({"total": 2, "active": 2, "admins": 1})
+        );
+        assert_eq!(stats["boards"], 1);
+        assert_eq!(stats["cards"], 1);
+        assert_eq!(stats["chat"]["messages"], 0);
+
+        let admin_id = state
+            .users
+            .find_by_email("admin@example.com")
+            .await
+            .unwrap()
+            .unwrap()
+            .id;
+        let transferred: Value = server
+            .post(&format!("/api/admin/boards/{}/transfer", board_id))
+            .add_cookie(session_cookie(&admin))
+            .json(&json!({"owner_id": admin_id}))
+            .await
+            .json();
+        assert_eq!(transferred["owner_id"], admin_id.to_string());
+
+        // The previous owner keeps editing the board
+        let board_uuid = board_id.parse().unwrap();
+        let role = |user_id| state.boards.get_user_role(board_uuid, user_id);
+        assert_eq!(role(admin_id).await.unwrap(), Some(BoardRole::Owner));
+        assert_eq!(role(member_id).await.unwrap(), Some(BoardRole::Editor));
+
+        server
+            .post(&format!(
+                "/api/admin/boards/{}/transfer",
+                uuid::Uuid::new_v4()
+            ))
+            .add_cookie(session_cookie(&admin))
+            .json(&json!({"owner_id": admin_id}))
+            .await
+            .assert_status_not_found();
+    }
+
+    #[tokio::test]
+    async fn test_backup_and_restore() {
+        let (server, state) = setup_server_with_state().await;
+        let session = login_admin(&state).await;
+
+        let board: Value = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Kept"}))
+            .await
+            .json();
+
+        let backup = server
+            .get("/api/admin/backup")
+            .add_cookie(session_cookie(&session))
+            .await;
+        backup.assert_status_ok();
+        assert_eq!(backup.header("content-type"), "application/vnd.sqlite3");
+        let snapshot = backup.as_bytes().clone();
+        assert!(snapshot.starts_with(b"SQLite format 3"));
+
+        // Changes made after the snapshot are undone by the restore
+        server
+            .delete(&format!("/api/boards/{}", board["id"].as_str().unwrap()))
+            .add_cookie(session_cookie(&session))
+            .await;
+        server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Added later"}))
+            .await;
+
+        let restored: Value = server
+            .post("/api/admin/restore")
+            .add_cookie(session_cookie(&session))
+            .bytes(snapshot)
+            .await
+            .json();
+        assert!(restored["restored_rows"].as_u64().unwrap() > 0);
+
+        let boards: Value = server
+            .get("/api/boards")
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        let names: Vec<&str> = boards
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|b| b["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, vec!["Kept"]);
+    }
+
+    #[tokio::test]
+    async fn test_backup_requires_admin() {
+        let (server, state) = setup_server_with_state().await;
+        let admin = login_admin(&state).await;
+        let user_id = test_utils::create_test_user(&state, "user@example.com", "User").await;
+        let user = test_utils::create_test_session(&state, user_id).await;
+
+        server
+            .get("/api/admin/backup")
+            .add_cookie(session_cookie(&user))
+            .await
+            .assert_status_forbidden();
+        server
+            .post("/api/admin/restore")
+            .add_cookie(session_cookie(&user))
+            .bytes(b"SQLite format 3".to_vec().into())
+            .await
+            .assert_status_forbidden();
+
+        // Files that are not snapshots leave the database untouched
+        server
+            .post("/api/admin/restore")
+            .add_cookie(session_cookie(&admin))
+            .bytes(b"not a database, just some text".repeat(200).into())
+            .await
+            .assert_status(axum::http::StatusCode::UNPROCESSABLE_ENTITY);
+        assert!(state
+            .users
+            .find_by_email("user@example.com")
+            .await
+            .unwrap()
+            .is_some());
+    }
+}
+
+// ============================================================================
+// E2E Tests
+// ============================================================================
+
+mod e2e_tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_full_kanban_workflow() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+
+        let board_response = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Project Board", "description": "For tracking project tasks"}))
+            .await;
+        board_response.assert_status_ok();
+        let board_id = board_response.json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let todo_response = server
+            .post(&format!("/api/boards/{}/columns", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "To Do"}))
+            .await;
+        let todo_id = todo_response.json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let in_progress_response = server
+            .post(&format!("/api/boards/{}/columns", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "In Progress"}))
+            .await;
+        let in_progress_id = in_progress_response.json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let done_response = server
+            .post(&format!("/api/boards/{}/columns", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Done"}))
+            .await;
+        let done_id = done_response.json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let bug_tag_response = server
+            .post(&format!("/api/boards/{}/tags", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Bug", "color": "#dc3545"}))
+            .await;
+        let bug_tag_id = bug_tag_response.json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let feature_tag_response = server
+            .post(&format!("/api/boards/{}/tags", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Feature", "color": "#28a745"}))
+            .await;
+        let feature_tag_id = feature_tag_response.json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let card1_response = server
+            .post(&format!("/api/columns/{}/cards", todo_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({
+                "title": "Fix login bug",
+                "body": "Users can't login with special characters in password"
+            }))
+            .await;
+        let card1_id = card1_response.json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let card2_response = server
+            .post(&format!("/api/columns/{}/cards", todo_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({
+                "title": "Add dark mode",
+                "body": "Implement dark mode theme"
+            }))
+            .await;
+        let card2_id = card2_response.json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        server
+            .post(&format!("/api/cards/{}/tags/{}", card1_id, bug_tag_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .assert_status_ok();
+
+        server
+            .post(&format!("/api/cards/{}/tags/{}", card2_id, feature_tag_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .assert_status_ok();
+
+        let move_response = server
+            .patch(&format!("/api/cards/{}/move", card1_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"column_id": in_progress_id, "position": 0}))
+            .await;
+        move_response.assert_status_ok();
+
+        let board_detail = server
+            .get(&format!("/api/boards/{}", board_id))
+            .add_cookie(session_cookie(&session))
+            .await;
+        board_detail.assert_status_ok();
+
+        let board_data: Value = board_detail.json();
+        assert_eq!(board_data["columns"].as_array().unwrap().len(), 3);
+        assert_eq!(board_data["tags"].as_array().unwrap().len(), 2);
+
+        let complete_response = server
+            .patch(&format!("/api/cards/{}/move", card1_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"column_id": done_id, "position": 0}))
+            .await;
+        complete_response.assert_status_ok();
+        assert_eq!(complete_response.json::<Value>()["column_id"], done_id);
+    }
+
+    #[tokio::test]
+    async fn test_cards_list_returns_all_cards() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+
+        let board_response = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Filter Test Board"}))
+            .await;
+        let board_id = board_response.json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let column_response = server
+            .post(&format!("/api/boards/{}/columns", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Tasks"}))
+            .await;
+        let column_id = column_response.json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let tag_response = server
+            .post(&format!("/api/boards/{}/tags", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Priority"}))
+            .await;
+        let tag_id = tag_response.json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let card1_response = server
+            .post(&format!("/api/columns/{}/cards", column_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"title": "Tagged Card"}))
+            .await;
+        let card1_id = card1_response.json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        server
+            .post(&format!("/api/columns/{}/cards", column_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"title": "Untagged Card"}))
+            .await;
+
+        server
+            .post(&format!("/api/cards/{}/tags/{}", card1_id, tag_id))
+            .add_cookie(session_cookie(&session))
+            .await;
+
+        // Verify cards list returns all cards
+        let all_cards_response = server
+            .get(&format!("/api/boards/{}/cards", board_id))
+            .add_cookie(session_cookie(&session))
+            .await;
+
+        all_cards_response.assert_status_ok();
+        let cards: Value = all_cards_response.json();
+        assert_eq!(cards.as_array().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_filter_cards_by_query() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+
+        let board_response = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Query Test Board"}))
+            .await;
+        let board_id = board_response.json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let column_response = server
+            .post(&format!("/api/boards/{}/columns", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Tasks"}))
+            .await;
+        let column_id = column_response.json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        server
+            .post(&format!("/api/columns/{}/cards", column_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({
+                "title": "Fix authentication bug",
+                "body": "Users cannot login"
+            }))
+            .await;
+
+        server
+            .post(&format!("/api/columns/{}/cards", column_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({
+                "title": "Add new feature",
+                "body": "Implement dashboard"
+            }))
+            .await;
+
+        let filter_response = server
+            .get(&format!(
+                "/api/boards/{}/cards?query=authentication",
+                board_id
+            ))
+            .add_cookie(session_cookie(&session))
+            .await;
+
+        filter_response.assert_status_ok();
+        let cards: Value = filter_response.json();
+        assert_eq!(cards.as_array().unwrap().len(), 1);
         assert_eq!(cards[0]["title"], "Fix authentication bug");
     }
 }
+
+// ============================================================================
+// Board Event Stream Tests
+// ============================================================================
+
+mod board_event_tests {
+    use super::*;
+
+    /// WebSocket upgrades need a real HTTP transport
+    async fn setup_http_server() -> TestServer {
+        let state = test_utils::create_test_state().await;
+        TestServer::builder()
+            .http_transport()
+            .build(create_router(state))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_board_events_stream_changes() {
+        let server = setup_http_server().await;
+        let session = register_and_login(&server).await;
+
+        let board_id = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Live Board"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let mut events = server
+            .get_websocket(&format!("/api/boards/{}/ws", board_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .into_websocket()
+            .await;
+
+        let column_id = server
+            .post(&format!("/api/boards/{}/columns", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "To Do"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let card_id = server
+            .post(&format!("/api/columns/{}/cards", column_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"title": "Watched card"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let comment_id = server
+            .post(&format!("/api/cards/{}/comments", card_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"body": "Looks good"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let event: Value = events.receive_json().await;
+        assert_eq!(event["event"], "column_created");
+        assert_eq!(event["board_id"], board_id);
+        assert_eq!(event["id"], column_id);
+
+        let event: Value = events.receive_json().await;
+        assert_eq!(event["event"], "card_created");
+        assert_eq!(event["id"], card_id);
+
+        let event: Value = events.receive_json().await;
+        assert_eq!(event["event"], "comment_created");
+        assert_eq!(event["id"], comment_id);
+        assert_eq!(event["card_id"], card_id);
+    }
+
+    #[tokio::test]
+    async fn test_board_events_require_access() {
+        let server = setup_http_server().await;
+        let owner_session = register_and_login(&server).await;
+        let other_session = register_and_login(&server).await;
+
+        let board_id = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"name": "Private Board"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let response = server
+            .get_websocket(&format!("/api/boards/{}/ws", board_id))
+            .add_cookie(session_cookie(&other_session))
+            .expect_failure()
+            .await;
+        response.assert_status(axum::http::StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_board_events_close_on_shutdown() {
+        let state = test_utils::create_test_state().await;
+        let jobs = state.jobs.clone();
+        let server = TestServer::builder()
+            .http_transport()
+            .build(create_router(state))
+            .unwrap();
+        let session = register_and_login(&server).await;
+
+        let board_id = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Live Board"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let mut events = server
+            .get_websocket(&format!("/api/boards/{}/ws", board_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .into_websocket()
+            .await;
+
+        jobs.stop();
+
+        match events.receive_message().await {
+            axum_test::WsMessage::Close(Some(frame)) => {
+                assert_eq!(u16::from(frame.code), 1001);
+            }
+            message => panic!("expected a close frame, got {:?}", message),
+        }
+    }
+}
+
+// ============================================================================
+// Chat Stream Tests
+// ============================================================================
+
+mod chat_stream_tests {
+    use super::*;
+    use personal_os::services::OllamaClient;
+    use std::sync::Arc;
+
+    /// Serve a canned streamed chat response, split into several chunks, on a local port
+    async fn start_fake_ollama(tokens: &'static [&'static str]) -> String {
+        let body: String = tokens
+            .iter()
+            .map(|t| {
+                json!({"message": {"role": "assistant", "content": t}, "done": false}).to_string()
+                    + "\n"
+            })
+            .chain(std::iter::once(json!({"done": true}).to_string() + "\n"))
+            .collect();
+
+        let app = axum::Router::new().route(
+            "/api/chat",
+            axum::routing::post(move || async move { body }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        url
+    }
+
+    /// Data lines of every event of the given type in an SSE body
+    fn event_data(body: &str, event: &str) -> Vec<String> {
+        body.split("\n\n")
+            .filter(|block| block.lines().any(|l| l == format!("event: {}", event)))
+            .map(|block| {
+                block
+                    .lines()
+                    .filter_map(|l| l.strip_prefix("data: "))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_chat_stream_sends_tokens_then_result() {
+        let ollama_url = start_fake_ollama(&[
+            r#"{"action": "create_column", "#,
+            r#""params": {"name": "Doing"}, "#,
+            r#""message": "Added a Doing column"}"#,
+        ])
+        .await;
+
+        let mut state = test_utils::create_test_state().await;
+        state.llm = Arc::new(OllamaClient::new(Some(ollama_url), None));
+        let server = TestServer::new(create_router(state)).unwrap();
+        let session = register_and_login(&server).await;
+
+        let board_id = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Streaming Board"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let response = server
+            .post(&format!("/api/boards/{}/chat/stream", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"message": "Add a Doing column"}))
+            .await;
+
+        response.assert_status_ok();
+        assert!(response
+            .header("content-type")
+            .to_str()
+            .unwrap()
+            .starts_with("text/event-stream"));
+
+        let body = response.text();
+        let tokens = event_data(&body, "token");
+        assert_eq!(tokens.len(), 3);
+        assert_eq!(tokens[1], r#""params": {"name": "Doing"}, "#);
+
+        let done = event_data(&body, "done");
+        assert_eq!(done.len(), 1);
+        let result: Value = serde_json::from_str(&done[0]).unwrap();
+        assert_eq!(result["response"], "Added a Doing column");
+        assert_eq!(result["actions_taken"][0]["action"], "create_column");
+        assert_eq!(result["actions_taken"][0]["success"], true);
+
+        // The action ran and the exchange was saved
+        let columns: Value = server
+            .get(&format!("/api/boards/{}/columns", board_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(columns[0]["name"], "Doing");
+
+        let history: Value = server
+            .get(&format!("/api/boards/{}/chat/history", board_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(history.as_array().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_chat_stream_requires_board_access() {
+        let server = setup_server().await;
+        let owner_session = register_and_login(&server).await;
+        let other_session = register_and_login(&server).await;
+
+        let board_id = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"name": "Private Board"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let response = server
+            .post(&format!("/api/boards/{}/chat/stream", board_id))
+            .add_cookie(session_cookie(&other_session))
+            .json(&json!({"message": "Hello"}))
+            .await;
+        response.assert_status(axum::http::StatusCode::FORBIDDEN);
+    }
+}
+
+// ============================================================================
+// Activity Tests
+// ============================================================================
+
+mod activity_tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_board_and_card_activity() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+
+        let board_id = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Audited Board"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let mut column_ids = Vec::new();
+        for name in ["To Do", "Done"] {
+            let column: Value = server
+                .post(&format!("/api/boards/{}/columns", board_id))
+                .add_cookie(session_cookie(&session))
+                .json(&json!({"name": name}))
+                .await
+                .json();
+            column_ids.push(column["id"].as_str().unwrap().to_string());
+        }
+        let card_id = server
+            .post(&format!("/api/columns/{}/cards", column_ids[0]))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"title": "Draft"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        server
+            .put(&format!("/api/cards/{}", card_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"title": "Final"}))
+            .await
+            .assert_status_ok();
+        server
+            .patch(&format!("/api/cards/{}/move", card_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"column_id": column_ids[1], "position": 0}))
+            .await
+            .assert_status_ok();
+
+        let activity: Value = server
+            .get(&format!("/api/boards/{}/activity", board_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        let events: Vec<&str> = activity
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|a| a["event"].as_str().unwrap())
+            .collect();
+        // Moving into Done also gives the card the column's status
+        assert_eq!(
+            events,
+            [
+                "card_updated",
+                "card_moved",
+                "card_updated",
+                "card_created",
+                "column_created",
+                "column_created"
+            ]
+        );
+        assert_eq!(
+            activity[0]["details"],
+            json!({"status": {"from": "open", "to": "done"}})
+        );
+        assert_eq!(activity[1]["actor_name"], "Test User");
+        assert_eq!(activity[1]["details"]["from_column_id"], column_ids[0]);
+        assert_eq!(activity[1]["details"]["to_column_id"], column_ids[1]);
+        assert_eq!(
+            activity[2]["details"],
+            json!({"title": {"from": "Draft", "to": "Final"}})
+        );
+
+        let limited: Value = server
+            .get(&format!("/api/boards/{}/activity?limit=2", board_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(limited.as_array().unwrap().len(), 2);
+
+        // The card timeline leaves out the columns
+        let timeline: Value = server
+            .get(&format!("/api/cards/{}/activity", card_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        let events: Vec<&str> = timeline
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|a| a["event"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            events,
+            ["card_updated", "card_moved", "card_updated", "card_created"]
+        );
+        assert!(timeline
+            .as_array()
+            .unwrap()
+            .iter()
+            .all(|a| a["card_id"] == card_id));
+    }
+
+    #[tokio::test]
+    async fn test_activity_requires_board_access() {
+        let server = setup_server().await;
+        let owner_session = register_and_login(&server).await;
+        let other_session = register_and_login(&server).await;
+
+        let board_id = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"name": "Private Board"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let column_id = server
+            .post(&format!("/api/boards/{}/columns", board_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"name": "To Do"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let card_id = server
+            .post(&format!("/api/columns/{}/cards", column_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"title": "Secret"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        server
+            .get(&format!("/api/boards/{}/activity", board_id))
+            .add_cookie(session_cookie(&other_session))
+            .await
+            .assert_status(axum::http::StatusCode::FORBIDDEN);
+        server
+            .get(&format!("/api/cards/{}/activity", card_id))
+            .add_cookie(session_cookie(&other_session))
+            .await
+            .assert_status(axum::http::StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_board_activity_hides_private_cards_from_readers() {
+        let state = test_utils::create_test_state().await;
+        let server = TestServer::new(create_router(state.clone())).unwrap();
+        let owner_id = test_utils::create_test_user(&state, "owner@example.com", "Ann").await;
+        let owner_session = test_utils::create_test_session(&state, owner_id).await;
+        let reader_id = test_utils::create_test_user(&state, "reader@example.com", "Cy").await;
+        let reader_session = test_utils::create_test_session(&state, reader_id).await;
+
+        let board_id = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"name": "Shared Board"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let column_id = server
+            .post(&format!("/api/boards/{}/columns", board_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"name": "To Do"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        server
+            .post(&format!("/api/boards/{}/permissions", board_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"user_id": reader_id, "role": "Reader"}))
+            .await
+            .assert_status_ok();
+        let mut card_ids = Vec::new();
+        for (title, visibility) in [("Salaries", "Private"), ("Offsite", "Public")] {
+            let card: Value = server
+                .post(&format!("/api/columns/{}/cards", column_id))
+                .add_cookie(session_cookie(&owner_session))
+                .json(&json!({"title": title, "visibility": visibility}))
+                .await
+                .json();
+            card_ids.push(card["id"].as_str().unwrap().to_string());
+        }
+        server
+            .put(&format!("/api/cards/{}", card_ids[0]))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"body": "Raise for everyone"}))
+            .await
+            .assert_status_ok();
+
+        let owner_view: Value = server
+            .get(&format!("/api/boards/{}/activity", board_id))
+            .add_cookie(session_cookie(&owner_session))
+            .await
+            .json();
+        let reader_view: Value = server
+            .get(&format!("/api/boards/{}/activity", board_id))
+            .add_cookie(session_cookie(&reader_session))
+            .await
+            .json();
+        let cards_in = |view: &Value| -> Vec<String> {
+            view.as_array()
+                .unwrap()
+                .iter()
+                .filter_map(|a| a["card_id"].as_str().map(str::to_string))
+                .collect()
+        };
+        assert_eq!(
+            cards_in(&owner_view),
+            [
+                card_ids[0].clone(),
+                card_ids[1].clone(),
+                card_ids[0].clone()
+            ]
+        );
+        // Readers still see changes to the board itself
+        assert_eq!(cards_in(&reader_view), [card_ids[1].clone()]);
+        assert_eq!(
+            reader_view.as_array().unwrap().len(),
+            owner_view.as_array().unwrap().len() - 2
+        );
+        assert!(!reader_view.to_string().contains("Salaries"));
+    }
+}
+
+// ============================================================================
+// Attachment Tests
+// ============================================================================
+
+mod attachment_tests {
+    use super::*;
+    use axum_test::multipart::{MultipartForm, Part};
+    use personal_os::services::AttachmentStorage;
+
+    fn file_form(name: &str, content: &'static [u8]) -> MultipartForm {
+        MultipartForm::new().add_part(
+            "file",
+            Part::bytes(content).file_name(name).mime_type("text/plain"),
+        )
+    }
+
+    /// Create a board with one column and a card, returning the board and card ids
+    async fn create_card(server: &TestServer, session: &str) -> (String, String) {
+        let board_id = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(session))
+            .json(&json!({"name": "Files"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let column_id = server
+            .post(&format!("/api/boards/{}/columns", board_id))
+            .add_cookie(session_cookie(session))
+            .json(&json!({"name": "To Do"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let card_id = server
+            .post(&format!("/api/columns/{}/cards", column_id))
+            .add_cookie(session_cookie(session))
+            .json(&json!({"title": "With files"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        (board_id, card_id)
+    }
+
+    #[tokio::test]
+    async fn test_upload_list_download_delete() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+        let (_, card_id) = create_card(&server, &session).await;
+
+        let response = server
+            .post(&format!("/api/cards/{}/attachments", card_id))
+            .add_cookie(session_cookie(&session))
+            .multipart(file_form("../notes.txt", b"hello attachments"))
+            .await;
+        response.assert_status_ok();
+        let attachment: Value = response.json();
+        assert_eq!(attachment["filename"], "notes.txt");
+        assert_eq!(attachment["content_type"], "text/plain");
+        assert_eq!(attachment["size"], 17);
+        assert_eq!(attachment["uploader_name"], "Test User");
+        let attachment_id = attachment["id"].as_str().unwrap();
+
+        let list: Value = server
+            .get(&format!("/api/cards/{}/attachments", card_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(list.as_array().unwrap().len(), 1);
+
+        let download = server
+            .get(&format!("/api/attachments/{}", attachment_id))
+            .add_cookie(session_cookie(&session))
+            .await;
+        download.assert_status_ok();
+        assert_eq!(download.as_bytes().as_ref(), b"hello attachments");
+        assert_eq!(download.header("content-type"), "text/plain");
+        assert!(download
+            .header("content-disposition")
+            .to_str()
+            .unwrap()
+            .starts_with("attachment; filename=\"notes.txt\""));
+
+        server
+            .delete(&format!("/api/attachments/{}", attachment_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .assert_status_ok();
+        server
+            .get(&format!("/api/attachments/{}", attachment_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .assert_status_not_found();
+    }
+
+    #[tokio::test]
+    async fn test_upload_requires_a_file() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+        let (_, card_id) = create_card(&server, &session).await;
+
+        server
+            .post(&format!("/api/cards/{}/attachments", card_id))
+            .add_cookie(session_cookie(&session))
+            .multipart(MultipartForm::new().add_text("note", "no file here"))
+            .await
+            .assert_status_unprocessable_entity();
+    }
+
+    #[tokio::test]
+    async fn test_attachments_require_card_access() {
+        let server = setup_server().await;
+        let owner_session = register_and_login(&server).await;
+        let other_session = register_and_login(&server).await;
+        let (_, card_id) = create_card(&server, &owner_session).await;
+
+        let attachment: Value = server
+            .post(&format!("/api/cards/{}/attachments", card_id))
+            .add_cookie(session_cookie(&owner_session))
+            .multipart(file_form("plan.txt", b"secret plan"))
+            .await
+            .json();
+        let attachment_id = attachment["id"].as_str().unwrap();
+
+        server
+            .post(&format!("/api/cards/{}/attachments", card_id))
+            .add_cookie(session_cookie(&other_session))
+            .multipart(file_form("intruder.txt", b"nope"))
+            .await
+            .assert_status(axum::http::StatusCode::FORBIDDEN);
+        server
+            .get(&format!("/api/cards/{}/attachments", card_id))
+            .add_cookie(session_cookie(&other_session))
+            .await
+            .assert_status(axum::http::StatusCode::FORBIDDEN);
+        server
+            .get(&format!("/api/attachments/{}", attachment_id))
+            .add_cookie(session_cookie(&other_session))
+            .await
+            .assert_status(axum::http::StatusCode::FORBIDDEN);
+        server
+            .delete(&format!("/api/attachments/{}", attachment_id))
+            .add_cookie(session_cookie(&other_session))
+            .await
+            .assert_status(axum::http::StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_disk_storage_removes_files_with_card() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut state = test_utils::create_test_state().await;
+        state.attachment_storage = AttachmentStorage::new(Some(dir.path().to_path_buf()));
+        let server = TestServer::new(create_router(state.clone())).unwrap();
+        let session = register_and_login(&server).await;
+        let (_, card_id) = create_card(&server, &session).await;
+
+        let attachment: Value = server
+            .post(&format!("/api/cards/{}/attachments", card_id))
+            .add_cookie(session_cookie(&session))
+            .multipart(file_form("disk.txt", b"on disk"))
+            .await
+            .json();
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+
+        let download = server
+            .get(&format!(
+                "/api/attachments/{}",
+                attachment["id"].as_str().unwrap()
+            ))
+            .add_cookie(session_cookie(&session))
+            .await;
+        assert_eq!(download.as_bytes().as_ref(), b"on disk");
+
+        server
+            .delete(&format!("/api/cards/{}", card_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .assert_status_ok();
+        // Files are kept while the card is in the trash
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+
+        personal_os::handlers::trash::purge_expired(&state, 0)
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_card_cover() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+        let (board_id, card_id) = create_card(&server, &session).await;
+
+        let card: Value = server
+            .put(&format!("/api/cards/{}/cover", card_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"color": "#D62728"}))
+            .await
+            .json();
+        assert_eq!(card["cover_color"], "#d62728");
+        assert!(card["cover_attachment_id"].is_null());
+        server
+            .put(&format!("/api/cards/{}/cover", card_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"color": "crimson"}))
+            .await
+            .assert_status(axum::http::StatusCode::UNPROCESSABLE_ENTITY);
+
+        // Only image attachments of the card can be its cover
+        let text: Value = server
+            .post(&format!("/api/cards/{}/attachments", card_id))
+            .add_cookie(session_cookie(&session))
+            .multipart(file_form("notes.txt", b"not an image"))
+            .await
+            .json();
+        server
+            .put(&format!("/api/cards/{}/cover", card_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"attachment_id": text["id"]}))
+            .await
+            .assert_status(axum::http::StatusCode::UNPROCESSABLE_ENTITY);
+        let image: Value = server
+            .post(&format!("/api/cards/{}/attachments", card_id))
+            .add_cookie(session_cookie(&session))
+            .multipart(
+                MultipartForm::new().add_part(
+                    "file",
+                    Part::bytes(&b"\x89PNG"[..])
+                        .file_name("cover.png")
+                        .mime_type("image/png"),
+                ),
+            )
+            .await
+            .json();
+        let image_id = image["id"].as_str().unwrap();
+        server
+            .put(&format!("/api/cards/{}/cover", card_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"color": "#d62728", "attachment_id": image_id}))
+            .await
+            .assert_status(axum::http::StatusCode::UNPROCESSABLE_ENTITY);
+        let card: Value = server
+            .put(&format!("/api/cards/{}/cover", card_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"attachment_id": image_id}))
+            .await
+            .json();
+        assert_eq!(card["cover_attachment_id"], image_id);
+        assert!(card["cover_color"].is_null());
+
+        let page = server
+            .get(&format!("/boards/{}", board_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .text();
+        assert!(page.contains(&format!("/api/attachments/{}", image_id)));
+
+        // Deleting the image removes the cover
+        server
+            .delete(&format!("/api/attachments/{}", image_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .assert_status_ok();
+        let card: Value = server
+            .get(&format!("/api/cards/{}", card_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert!(card["cover_attachment_id"].is_null());
+
+        server
+            .post(&format!("/cards/{}/cover", card_id))
+            .add_cookie(session_cookie(&session))
+            .form(&[("color", "#2ca02c")])
+            .await
+            .assert_status(axum::http::StatusCode::SEE_OTHER);
+        let card: Value = server
+            .delete(&format!("/api/cards/{}/cover", card_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert!(card["cover_color"].is_null());
+    }
+
+    #[tokio::test]
+    async fn test_search_finds_attachment_text() {
+        let (server, state) = setup_server_with_state().await;
+        let session = register_and_login(&server).await;
+        let other_id = test_utils::create_test_user(&state, "other@example.com", "Other").await;
+        let other_session = test_utils::create_test_session(&state, other_id).await;
+        let (_, card_id) = create_card(&server, &session).await;
+
+        let attachment: Value = server
+            .post(&format!("/api/cards/{}/attachments", card_id))
+            .add_cookie(session_cookie(&session))
+            .multipart(file_form(
+                "minutes.txt",
+                b"Agreed on the <quarterly> budget review.\nNext meeting in May.",
+            ))
+            .await
+            .json();
+        server
+            .post(&format!("/api/cards/{}/attachments", card_id))
+            .add_cookie(session_cookie(&session))
+            .multipart(
+                MultipartForm::new().add_part(
+                    "file",
+                    Part::bytes(&b"\x89PNG budget"[..])
+                        .file_name("chart.png")
+                        .mime_type("image/png"),
+                ),
+            )
+            .await
+            .assert_status_ok();
+
+        let results: Value = server
+            .get("/api/search?q=budg")
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(results.as_array().unwrap().len(), 1);
+        assert_eq!(results[0]["card_title"], "With files");
+        assert_eq!(results[0]["card_matches"], false);
+        let matches = results[0]["attachments"].as_array().unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0]["filename"], "minutes.txt");
+        assert_eq!(
+            matches[0]["snippet"],
+            "Agreed on the &lt;quarterly&gt; <mark>budget</mark> review. Next meeting in May."
+        );
+
+        // Card titles match too, and both kinds of match are merged per card
+        let results: Value = server
+            .get("/api/search?q=files")
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(results[0]["card_matches"], true);
+        assert!(results[0]["attachments"].as_array().unwrap().is_empty());
+
+        // Cards the user cannot see are left out
+        let results: Value = server
+            .get("/api/search?q=budget")
+            .add_cookie(session_cookie(&other_session))
+            .await
+            .json();
+        assert!(results.as_array().unwrap().is_empty());
+
+        server
+            .get("/api/search?q=%20")
+            .add_cookie(session_cookie(&session))
+            .await
+            .assert_status(axum::http::StatusCode::UNPROCESSABLE_ENTITY);
+
+        // Deleted attachments no longer match
+        server
+            .delete(&format!(
+                "/api/attachments/{}",
+                attachment["id"].as_str().unwrap()
+            ))
+            .add_cookie(session_cookie(&session))
+            .await
+            .assert_status_ok();
+        let results: Value = server
+            .get("/api/search?q=budget")
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert!(results.as_array().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_search_skips_other_users_cards_and_archived_cards() {
+        let (server, state) = setup_server_with_state().await;
+        let session = register_and_login(&server).await;
+        let other_id = test_utils::create_test_user(&state, "other@example.com", "Other").await;
+        let other_session = test_utils::create_test_session(&state, other_id).await;
+        let (_, card_id) = create_card(&server, &session).await;
+        let (_, other_card_id) = create_card(&server, &other_session).await;
+        server
+            .post(&format!("/api/cards/{}/attachments", card_id))
+            .add_cookie(session_cookie(&session))
+            .multipart(file_form("notes.txt", b"Budget review"))
+            .await
+            .assert_status_ok();
+
+        // More recent matches on cards the user cannot see than are read per search
+        sqlx::query(
+            r#"
+            WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 250)
+            INSERT INTO cards (id, column_id, title, created_by, updated_at)
+            SELECT randomblob(16), c.column_id, c.title, c.created_by, '2099-01-01 00:00:00'
+            FROM cards c, n WHERE c.id = $1
+            "#,
+        )
+        .bind(uuid::Uuid::parse_str(&other_card_id).unwrap())
+        .execute(state.pool.as_ref())
+        .await
+        .unwrap();
+
+        let results: Value = server
+            .get("/api/search?q=files")
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        let results = results.as_array().unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0]["card_id"], card_id.as_str());
+
+        // Archived cards no longer match through their attachments either
+        server
+            .post(&format!("/api/cards/{}/archive", card_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .assert_status_ok();
+        let results: Value = server
+            .get("/api/search?q=budget")
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert!(results.as_array().unwrap().is_empty());
+    }
+}
+
+// ============================================================================
+// Assignee Tests
+// ============================================================================
+
+mod assignee_tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_assign_filter_and_unassign() {
+        let (server, state) = setup_server_with_state().await;
+        let owner_id = test_utils::create_test_user(&state, "owner@example.com", "Owner").await;
+        let owner_session = test_utils::create_test_session(&state, owner_id).await;
+        let member_id = test_utils::create_test_user(&state, "member@example.com", "Member").await;
+        let member_session = test_utils::create_test_session(&state, member_id).await;
+
+        let board_id = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"name": "Team"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        server
+            .post(&format!("/api/boards/{}/permissions", board_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"user_id": member_id, "role": "Editor"}))
+            .await
+            .assert_status_ok();
+        let column_id = server
+            .post(&format!("/api/boards/{}/columns", board_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"name": "To Do"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let mut card_ids = Vec::new();
+        for title in ["Write spec", "Review spec"] {
+            let card: Value = server
+                .post(&format!("/api/columns/{}/cards", column_id))
+                .add_cookie(session_cookie(&owner_session))
+                .json(&json!({"title": title}))
+                .await
+                .json();
+            card_ids.push(card["id"].as_str().unwrap().to_string());
+        }
+
+        let response = server
+            .post(&format!("/api/cards/{}/assignees", card_ids[0]))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"user_id": member_id}))
+            .await;
+        response.assert_status_ok();
+        let assignees: Value = response.json();
+        assert_eq!(assignees.as_array().unwrap().len(), 1);
+        assert_eq!(assignees[0]["name"], "Member");
+
+        // Assigning twice keeps a single entry
+        let assignees: Value = server
+            .post(&format!("/api/cards/{}/assignees", card_ids[0]))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"user_id": member_id}))
+            .await
+            .json();
+        assert_eq!(assignees.as_array().unwrap().len(), 1);
+
+        let filtered: Value = server
+            .get(&format!(
+                "/api/boards/{}/cards?assignee={}",
+                board_id, member_id
+            ))
+            .add_cookie(session_cookie(&owner_session))
+            .await
+            .json();
+        assert_eq!(filtered.as_array().unwrap().len(), 1);
+        assert_eq!(filtered[0]["title"], "Write spec");
+
+        let assigned: Value = server
+            .get("/api/cards/assigned")
+            .add_cookie(session_cookie(&member_session))
+            .await
+            .json();
+        assert_eq!(assigned.as_array().unwrap().len(), 1);
+        assert_eq!(assigned[0]["id"], card_ids[0]);
+
+        let full: Value = server
+            .get(&format!("/api/cards/{}/full", card_ids[0]))
+            .add_cookie(session_cookie(&member_session))
+            .await
+            .json();
+        assert_eq!(full["assignees"][0]["user_id"], member_id.to_string());
+
+        let page = server
+            .get("/assigned")
+            .add_cookie(session_cookie(&member_session))
+            .await;
+        page.assert_status_ok();
+        assert!(page.text().contains("Write spec"));
+        assert!(!page.text().contains("Review spec"));
+
+        let assignees: Value = server
+            .delete(&format!(
+                "/api/cards/{}/assignees/{}",
+                card_ids[0], member_id
+            ))
+            .add_cookie(session_cookie(&owner_session))
+            .await
+            .json();
+        assert!(assignees.as_array().unwrap().is_empty());
+        let assigned: Value = server
+            .get("/api/cards/assigned")
+            .add_cookie(session_cookie(&member_session))
+            .await
+            .json();
+        assert!(assigned.as_array().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_assignee_needs_card_access() {
+        let (server, state) = setup_server_with_state().await;
+        let owner_id = test_utils::create_test_user(&state, "owner@example.com", "Owner").await;
+        let owner_session = test_utils::create_test_session(&state, owner_id).await;
+        let stranger_id =
+            test_utils::create_test_user(&state, "stranger@example.com", "Stranger").await;
+        let stranger_session = test_utils::create_test_session(&state, stranger_id).await;
+
+        let card_id = server
+            .post("/api/cards")
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"title": "Private errand"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        server
+            .post(&format!("/api/cards/{}/assignees", card_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"user_id": stranger_id}))
+            .await
+            .assert_status_unprocessable_entity();
+        server
+            .post(&format!("/api/cards/{}/assignees", card_id))
+            .add_cookie(session_cookie(&stranger_session))
+            .json(&json!({"user_id": stranger_id}))
+            .await
+            .assert_status(axum::http::StatusCode::FORBIDDEN);
+        server
+            .get(&format!("/api/cards/{}/assignees", card_id))
+            .add_cookie(session_cookie(&stranger_session))
+            .await
+            .assert_status(axum::http::StatusCode::FORBIDDEN);
+    }
+}
+
+// ============================================================================
+// Card Link Tests
+// ============================================================================
+
+mod link_tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_card_links_and_blocker_warning() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+        let board_id = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Release"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let mut column_ids = Vec::new();
+        for name in ["To Do", "Done"] {
+            let column: Value = server
+                .post(&format!("/api/boards/{}/columns", board_id))
+                .add_cookie(session_cookie(&session))
+                .json(&json!({"name": name}))
+                .await
+                .json();
+            column_ids.push(column["id"].as_str().unwrap().to_string());
+        }
+        let mut card_ids = Vec::new();
+        for title in ["Ship release", "Fix tests", "Release notes"] {
+            let card: Value = server
+                .post(&format!("/api/columns/{}/cards", column_ids[0]))
+                .add_cookie(session_cookie(&session))
+                .json(&json!({"title": title}))
+                .await
+                .json();
+            card_ids.push(card["id"].as_str().unwrap().to_string());
+        }
+
+        // "Ship release" is blocked by "Fix tests" and relates to "Release notes"
+        let response = server
+            .post(&format!("/api/cards/{}/links", card_ids[0]))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"card_id": card_ids[1], "link_type": "blocked_by"}))
+            .await;
+        response.assert_status_ok();
+        server
+            .post(&format!("/api/cards/{}/links", card_ids[0]))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"card_id": card_ids[2], "link_type": "relates_to"}))
+            .await
+            .assert_status_ok();
+
+        // The same relation from the other card is a duplicate
+        server
+            .post(&format!("/api/cards/{}/links", card_ids[1]))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"card_id": card_ids[0], "link_type": "blocks"}))
+            .await
+            .assert_status(axum::http::StatusCode::CONFLICT);
+        server
+            .post(&format!("/api/cards/{}/links", card_ids[0]))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"card_id": card_ids[0], "link_type": "relates_to"}))
+            .await
+            .assert_status(axum::http::StatusCode::UNPROCESSABLE_ENTITY);
+
+        let links: Vec<Value> = server
+            .get(&format!("/api/cards/{}/links", card_ids[1]))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0]["link_type"], "blocks");
+        assert_eq!(links[0]["card"]["title"], "Ship release");
+
+        // Moving the blocked card to Done warns but goes through
+        let response = server
+            .patch(&format!("/api/cards/{}/move", card_ids[0]))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"column_id": column_ids[1], "position": 0}))
+            .await;
+        response.assert_status_ok();
+        let warning = response.header("x-blocker-warning");
+        assert!(warning.to_str().unwrap().contains("Fix tests"));
+
+        // Once the blocker is done, there is nothing to warn about
+        server
+            .put(&format!("/api/cards/{}", card_ids[1]))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"status": "Done"}))
+            .await
+            .assert_status_ok();
+        let response = server
+            .put(&format!("/api/cards/{}", card_ids[0]))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"status": "Done"}))
+            .await;
+        response.assert_status_ok();
+        assert!(response.maybe_header("x-blocker-warning").is_none());
+
+        let links: Vec<Value> = server
+            .get(&format!("/api/cards/{}/links", card_ids[0]))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(links.len(), 2);
+        assert_eq!(links[0]["link_type"], "blocked_by");
+        let links: Vec<Value> = server
+            .delete(&format!(
+                "/api/cards/{}/links/{}",
+                card_ids[2],
+                links[1]["id"].as_str().unwrap()
+            ))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert!(links.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_blocked_cards_and_unblocked_notification() {
+        let (server, state) = setup_server_with_state().await;
+        let owner_id = test_utils::create_test_user(&state, "owner@example.com", "Owner").await;
+        let owner_session = test_utils::create_test_session(&state, owner_id).await;
+        let watcher_id =
+            test_utils::create_test_user(&state, "watcher@example.com", "Watcher").await;
+        let watcher_session = test_utils::create_test_session(&state, watcher_id).await;
+
+        let board_id = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"name": "Release"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let column_id = server
+            .post(&format!("/api/boards/{}/columns", board_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"name": "To Do"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let mut card_ids = Vec::new();
+        for title in ["Ship release", "Fix tests", "Update docs"] {
+            let card: Value = server
+                .post(&format!("/api/columns/{}/cards", column_id))
+                .add_cookie(session_cookie(&owner_session))
+                .json(&json!({"title": title, "visibility": "Restricted"}))
+                .await
+                .json();
+            card_ids.push(card["id"].as_str().unwrap().to_string());
+        }
+        for blocker in &card_ids[1..] {
+            server
+                .post(&format!("/api/cards/{}/links", card_ids[0]))
+                .add_cookie(session_cookie(&owner_session))
+                .json(&json!({"card_id": blocker, "link_type": "blocked_by"}))
+                .await
+                .assert_status_ok();
+        }
+        server
+            .post(&format!("/api/boards/{}/permissions", board_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"user_id": watcher_id, "role": "Reader"}))
+            .await
+            .assert_status_ok();
+        server
+            .put(&format!("/api/cards/{}/watch", card_ids[0]))
+            .add_cookie(session_cookie(&watcher_session))
+            .await
+            .assert_status_ok();
+
+        let card: Value = server
+            .get(&format!("/api/cards/{}", card_ids[0]))
+            .add_cookie(session_cookie(&owner_session))
+            .await
+            .json();
+        assert_eq!(card["is_blocked"], true);
+        let blocked: Vec<Value> = server
+            .get(&format!("/api/boards/{}/cards?blocked=true", board_id))
+            .add_cookie(session_cookie(&owner_session))
+            .await
+            .json();
+        assert_eq!(blocked.len(), 1);
+        assert_eq!(blocked[0]["title"], "Ship release");
+        assert_eq!(blocked[0]["is_blocked"], true);
+        let unblocked: Vec<Value> = server
+            .get(&format!("/api/boards/{}/cards?blocked=false", board_id))
+            .add_cookie(session_cookie(&owner_session))
+            .await
+            .json();
+        assert_eq!(unblocked.len(), 2);
+        assert!(unblocked.iter().all(|c| c["is_blocked"] == false));
+        let page = server
+            .get(&format!("/boards/{}", board_id))
+            .add_cookie(session_cookie(&owner_session))
+            .await
+            .text();
+        assert!(page.contains("Blocked</span>"));
+
+        // Completing one of two blockers leaves the card blocked
+        server
+            .put(&format!("/api/cards/{}", card_ids[1]))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"status": "Done"}))
+            .await
+            .assert_status_ok();
+        let notifications: Value = server
+            .get("/api/notifications")
+            .add_cookie(session_cookie(&watcher_session))
+            .await
+            .json();
+        assert_eq!(notifications.as_array().unwrap().len(), 1);
+        assert_eq!(notifications[0]["kind"], "added_to_board");
+
+        server
+            .put(&format!("/api/cards/{}", card_ids[2]))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"status": "Closed"}))
+            .await
+            .assert_status_ok();
+        let notifications: Value = server
+            .get("/api/notifications")
+            .add_cookie(session_cookie(&watcher_session))
+            .await
+            .json();
+        assert_eq!(notifications.as_array().unwrap().len(), 2);
+        assert_eq!(notifications[0]["kind"], "card_unblocked");
+        assert_eq!(notifications[0]["card_title"], "Ship release");
+        assert_eq!(notifications[0]["actor_name"], "Owner");
+        assert_eq!(
+            notifications[0]["message"],
+            "completed the last card blocking it"
+        );
+
+        let card: Value = server
+            .get(&format!("/api/cards/{}", card_ids[0]))
+            .add_cookie(session_cookie(&owner_session))
+            .await
+            .json();
+        assert_eq!(card["is_blocked"], false);
+
+        // Editing a completed blocker does not notify again
+        server
+            .put(&format!("/api/cards/{}", card_ids[2]))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"title": "Update the docs"}))
+            .await
+            .assert_status_ok();
+        let notifications: Value = server
+            .get("/api/notifications")
+            .add_cookie(session_cookie(&watcher_session))
+            .await
+            .json();
+        assert_eq!(notifications.as_array().unwrap().len(), 2);
+    }
+}
+
+// ============================================================================
+// Calendar Tests
+// ============================================================================
+
+mod calendar_tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_calendar_feed() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+        let other = register_and_login(&server).await;
+        server
+            .post("/api/cards")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"title": "File taxes", "due_date": "2026-04-15"}))
+            .await
+            .assert_status_ok();
+        server
+            .post("/api/cards")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"title": "No date"}))
+            .await
+            .assert_status_ok();
+
+        // Without a feed address, the calendar cannot be read
+        server
+            .get("/calendar.ics?token=unknown")
+            .await
+            .assert_status_unauthorized();
+
+        let feed: Value = server
+            .post("/api/users/me/calendar-feed")
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        let token = feed["token"].as_str().unwrap();
+        assert!(feed["url"]
+            .as_str()
+            .unwrap()
+            .ends_with(&format!("/calendar.ics?token={}", token)));
+
+        let response = server.get(&format!("/calendar.ics?token={}", token)).await;
+        response.assert_status_ok();
+        assert!(response
+            .header("content-type")
+            .to_str()
+            .unwrap()
+            .starts_with("text/calendar"));
+        let ics = response.text();
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.contains("SUMMARY:Due: File taxes\r\n"));
+        assert!(ics.contains("DTSTART;VALUE=DATE:20260415\r\n"));
+        assert!(!ics.contains("No date"));
+
+        // Another user's feed does not include the card
+        let other_feed: Value = server
+            .post("/api/users/me/calendar-feed")
+            .add_cookie(session_cookie(&other))
+            .await
+            .json();
+        let ics = server
+            .get(&format!(
+                "/calendar.ics?token={}",
+                other_feed["token"].as_str().unwrap()
+            ))
+            .await
+            .text();
+        assert!(!ics.contains("File taxes"));
+
+        // The month grid shows the card on its due date
+        let page = server
+            .get("/calendar?month=2026-04")
+            .add_cookie(session_cookie(&session))
+            .await
+            .text();
+        assert!(page.contains("April 2026"));
+        assert!(page.contains("File taxes"));
+
+        // A new address replaces the old one, and deleting it revokes the feed
+        let renewed: Value = server
+            .post("/api/users/me/calendar-feed")
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        server
+            .get(&format!("/calendar.ics?token={}", token))
+            .await
+            .assert_status_unauthorized();
+        server
+            .delete("/api/users/me/calendar-feed")
+            .add_cookie(session_cookie(&session))
+            .await
+            .assert_status(axum::http::StatusCode::NO_CONTENT);
+        server
+            .get(&format!(
+                "/calendar.ics?token={}",
+                renewed["token"].as_str().unwrap()
+            ))
+            .await
+            .assert_status_unauthorized();
+    }
+}
+
+// ============================================================================
+// Quick Add Tests
+// ============================================================================
+
+mod quick_add_tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_quick_add_to_inbox() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+
+        let response = server
+            .post("/api/quick-add")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({
+                "text": "Pay rent tomorrow #finance !high",
+                "today": "2026-03-10"
+            }))
+            .await;
+        response.assert_status_ok();
+        let card: Value = response.json();
+        assert_eq!(card["title"], "Pay rent");
+        assert_eq!(card["due_date"], "2026-03-11");
+        assert_eq!(card["priority"], "high");
+        assert_eq!(card["column_id"], Value::Null);
+        assert_eq!(card["board_id"], Value::Null);
+        assert_eq!(card["tags"][0]["name"], "finance");
+
+        // The tag now exists and is reused
+        let card: Value = server
+            .post("/api/quick-add")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"text": "Check bank fees #Finance"}))
+            .await
+            .json();
+        assert_eq!(card["tags"][0]["name"], "finance");
+        assert_eq!(card["due_date"], Value::Null);
+
+        server
+            .post("/api/quick-add")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"text": "#finance tomorrow"}))
+            .await
+            .assert_status(axum::http::StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    #[tokio::test]
+    async fn test_quick_add_to_board() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+        let board: Value = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Side project", "template": "kanban"}))
+            .await
+            .json();
+        let board_id = board["id"].as_str().unwrap();
+        let details: Value = server
+            .get(&format!("/api/boards/{}", board_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        let todo = details["columns"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|c| c["name"] == "To Do")
+            .unwrap();
+
+        let card: Value = server
+            .post("/api/quick-add")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({
+                "text": "Write landing page @side project board by friday #launch",
+                "today": "2026-03-10"
+            }))
+            .await
+            .json();
+        assert_eq!(card["title"], "Write landing page");
+        assert_eq!(card["board_id"], board["id"]);
+        assert_eq!(card["column_id"], todo["id"]);
+        assert_eq!(card["due_date"], "2026-03-13");
+        assert_eq!(card["tags"][0]["name"], "launch");
+
+        let tags: Value = server
+            .get(&format!("/api/boards/{}/tags", board_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        let launch = tags
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|t| t["name"] == "launch")
+            .count();
+        assert_eq!(launch, 1);
+
+        // Boards the user is not a member of are not found
+        let other = register_and_login(&server).await;
+        let response = server
+            .post("/api/quick-add")
+            .add_cookie(session_cookie(&other))
+            .json(&json!({"text": "Sneak in @side project"}))
+            .await;
+        response.assert_status(axum::http::StatusCode::UNPROCESSABLE_ENTITY);
+        let error: Value = response.json();
+        assert_eq!(error["code"], "validation_failed");
+        assert!(error["details"]["fields"]["text"].is_string());
+    }
+}
+
+// ============================================================================
+// Inbound Email Tests
+// ============================================================================
+
+mod inbound_email_tests {
+    use super::*;
+
+    const EMAIL: &str = concat!(
+        "From: Alice <alice@example.com>\r\n",
+        "Subject: Fwd: Renew passport\r\n",
+        "MIME-Version: 1.0\r\n",
+        "Content-Type: multipart/mixed; boundary=\"XYZ\"\r\n",
+        "\r\n",
+        "--XYZ\r\n",
+        "Content-Type: text/plain; charset=utf-8\r\n",
+        "\r\n",
+        "Appointment form attached.\r\n",
+        "--XYZ\r\n",
+        "Content-Type: text/plain\r\n",
+        "Content-Disposition: attachment; filename=\"form.txt\"\r\n",
+        "\r\n",
+        "Name: Alice\r\n",
+        "--XYZ--\r\n",
+    );
+
+    #[tokio::test]
+    async fn test_email_becomes_inbox_card() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+
+        server
+            .post("/api/inbound/email/unknown")
+            .text(EMAIL)
+            .await
+            .assert_status_unauthorized();
+
+        let address: Value = server
+            .post("/api/users/me/inbound-email")
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        let token = address["token"].as_str().unwrap();
+        assert!(address["url"]
+            .as_str()
+            .unwrap()
+            .ends_with(&format!("/api/inbound/email/{}", token)));
+
+        let received: Value = server
+            .post(&format!("/api/inbound/email/{}", token))
+            .bytes(EMAIL.as_bytes().to_vec().into())
+            .content_type("message/rfc822")
+            .await
+            .json();
+        assert_eq!(received["title"], "Renew passport");
+        assert_eq!(received["attachments"], json!(["form.txt"]));
+
+        let cards: Value = server
+            .get("/api/cards")
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        let card = &cards.as_array().unwrap()[0];
+        assert_eq!(card["id"], received["card_id"]);
+        assert_eq!(card["body"], "Appointment form attached.");
+        assert_eq!(card["visibility"], "private");
+
+        let attachments: Value = server
+            .get(&format!(
+                "/api/cards/{}/attachments",
+                received["card_id"].as_str().unwrap()
+            ))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        let attachment_id = attachments[0]["id"].as_str().unwrap();
+        let file = server
+            .get(&format!("/api/attachments/{}", attachment_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .text();
+        assert_eq!(file, "Name: Alice");
+
+        // Anything that is not an email is rejected
+        server
+            .post(&format!("/api/inbound/email/{}", token))
+            .text("")
+            .await
+            .assert_status_bad_request();
+
+        // Revoking the address stops new cards
+        server
+            .delete("/api/users/me/inbound-email")
+            .add_cookie(session_cookie(&session))
+            .await
+            .assert_status(axum::http::StatusCode::NO_CONTENT);
+        server
+            .post(&format!("/api/inbound/email/{}", token))
+            .text(EMAIL)
+            .await
+            .assert_status_unauthorized();
+    }
+}
+
+// ============================================================================
+// Recurrence Tests
+// ============================================================================
+
+mod recurrence_tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use personal_os::handlers::recurrences::create_due_cards;
+
+    #[tokio::test]
+    async fn test_recurring_card_is_copied_when_due() {
+        let (server, state) = setup_server_with_state().await;
+        let session = register_and_login(&server).await;
+
+        let board_id = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Routines"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let column_id = server
+            .post(&format!("/api/boards/{}/columns", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "To Do"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let card_id = server
+            .post(&format!("/api/columns/{}/cards", column_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"title": "Weekly review", "body": "Inbox zero"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let tag_id = server
+            .post(&format!("/api/boards/{}/tags", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "routine", "color": "#00ff00"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        server
+            .post(&format!("/api/cards/{}/tags/{}", card_id, tag_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .assert_status_ok();
+
+        let response = server
+            .put(&format!("/api/cards/{}/recurrence", card_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"frequency": "weekly"}))
+            .await;
+        response.assert_status_ok();
+        let rule: Value = response.json();
+        assert_eq!(rule["frequency"], "weekly");
+        assert_eq!(rule["interval"], 1);
+        assert_eq!(rule["next_run_on"], rule["starts_on"]);
+        let due: NaiveDate = rule["next_run_on"].as_str().unwrap().parse().unwrap();
+
+        // Nothing is due the day before
+        assert_eq!(
+            create_due_cards(&state, due.pred_opt().unwrap())
+                .await
+                .unwrap(),
+            0
+        );
+        assert_eq!(create_due_cards(&state, due).await.unwrap(), 1);
+        assert_eq!(create_due_cards(&state, due).await.unwrap(), 0);
+
+        let cards: Value = server
+            .get(&format!("/api/boards/{}/cards", board_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        let cards = cards.as_array().unwrap();
+        assert_eq!(cards.len(), 2);
+        let copy = cards.iter().find(|c| c["id"] != card_id).unwrap();
+        assert_eq!(copy["title"], "Weekly review");
+        assert_eq!(copy["body"], "Inbox zero");
+        assert_eq!(copy["status"], "open");
+        assert_eq!(copy["due_date"], due.to_string());
+        assert_eq!(copy["tags"][0]["name"], "routine");
+
+        let rule: Value = server
+            .get(&format!("/api/cards/{}/recurrence", card_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(
+            rule["next_run_on"],
+            (due + chrono::Days::new(7)).to_string()
+        );
+
+        server
+            .delete(&format!("/api/cards/{}/recurrence", card_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .assert_status_ok();
+        let rule: Value = server
+            .get(&format!("/api/cards/{}/recurrence", card_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert!(rule.is_null());
+    }
+
+    #[tokio::test]
+    async fn test_recurrence_validation_and_access() {
+        let server = setup_server().await;
+        let owner_session = register_and_login(&server).await;
+        let other_session = register_and_login(&server).await;
+
+        let card_id = server
+            .post("/api/cards")
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"title": "Water plants"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let url = format!("/api/cards/{}/recurrence", card_id);
+
+        server
+            .put(&url)
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"frequency": "daily", "interval": 0}))
+            .await
+            .assert_status_unprocessable_entity();
+        server
+            .put(&url)
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"frequency": "hourly"}))
+            .await
+            .assert_status_unprocessable_entity();
+        server
+            .put(&url)
+            .add_cookie(session_cookie(&other_session))
+            .json(&json!({"frequency": "daily"}))
+            .await
+            .assert_status(axum::http::StatusCode::FORBIDDEN);
+
+        // A start date in the past schedules the next occurrence from today on
+        let rule: Value = server
+            .put(&url)
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"frequency": "monthly", "starts_on": "2020-01-31"}))
+            .await
+            .json();
+        let next: NaiveDate = rule["next_run_on"].as_str().unwrap().parse().unwrap();
+        assert!(next >= chrono::Local::now().date_naive());
+    }
+}
+
+// ============================================================================
+// Notification Tests
+// ============================================================================
+
+mod notification_tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use personal_os::handlers::notifications::create_due_notifications;
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_due_notifications_are_raised_once_and_marked_read() {
+        let (server, state) = setup_server_with_state().await;
+        let session = register_and_login(&server).await;
+
+        server
+            .post("/api/cards")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"title": "File taxes", "due_date": "2024-03-10"}))
+            .await
+            .assert_status_ok();
+        server
+            .post("/api/cards")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"title": "Already done", "due_date": "2024-03-10", "status": "Done"}))
+            .await
+            .assert_status_ok();
+
+        let create = |today| create_due_notifications(&state, date(today));
+        assert_eq!(create("2024-03-08").await.unwrap(), 0);
+        assert_eq!(create("2024-03-09").await.unwrap(), 1);
+        let sent = state.email.sent();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].subject, "Due soon: File taxes");
+        assert_eq!(create("2024-03-10").await.unwrap(), 0);
+        assert_eq!(create("2024-03-11").await.unwrap(), 1);
+        assert_eq!(create("2024-03-12").await.unwrap(), 0);
+
+        let notifications: Value = server
+            .get("/api/notifications")
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        let notifications = notifications.as_array().unwrap();
+        assert_eq!(notifications.len(), 2);
+        assert_eq!(notifications[0]["kind"], "overdue");
+        assert_eq!(notifications[0]["card_title"], "File taxes");
+        assert_eq!(notifications[1]["kind"], "due_soon");
+        assert_eq!(notifications[1]["due_date"], "2024-03-10");
+
+        let count: Value = server
+            .get("/api/notifications/unread-count")
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(count["count"], 2);
+
+        let overdue_id = notifications[0]["id"].as_str().unwrap();
+        server
+            .post(&format!("/api/notifications/{}/read", overdue_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .assert_status_ok();
+        let unread: Value = server
+            .get("/api/notifications?unread=true")
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(unread.as_array().unwrap().len(), 1);
+        assert_eq!(unread[0]["kind"], "due_soon");
+
+        let response = server
+            .post("/api/notifications/read-all")
+            .add_cookie(session_cookie(&session))
+            .await;
+        response.assert_status_ok();
+        assert_eq!(response.json::<Value>()["updated"], 1);
+        let count: Value = server
+            .get("/api/notifications/unread-count")
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(count["count"], 0);
+    }
+
+    #[tokio::test]
+    async fn test_due_notifications_go_to_assignees() {
+        let (server, state) = setup_server_with_state().await;
+        let owner_id = test_utils::create_test_user(&state, "owner@example.com", "Owner").await;
+        let owner_session = test_utils::create_test_session(&state, owner_id).await;
+        let member_id = test_utils::create_test_user(&state, "member@example.com", "Member").await;
+        let member_session = test_utils::create_test_session(&state, member_id).await;
+
+        let board_id = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"name": "Team"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        server
+            .post(&format!("/api/boards/{}/permissions", board_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"user_id": member_id, "role": "Editor"}))
+            .await
+            .assert_status_ok();
+        let column_id = server
+            .post(&format!("/api/boards/{}/columns", board_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"name": "To Do"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let card_id = server
+            .post(&format!("/api/columns/{}/cards", column_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"title": "Ship release", "due_date": "2024-03-10"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        server
+            .post(&format!("/api/cards/{}/assignees", card_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"user_id": member_id}))
+            .await
+            .assert_status_ok();
+
+        assert_eq!(
+            create_due_notifications(&state, date("2024-03-10"))
+                .await
+                .unwrap(),
+            1
+        );
+
+        let owner_notifications: Value = server
+            .get("/api/notifications")
+            .add_cookie(session_cookie(&owner_session))
+            .await
+            .json();
+        assert!(owner_notifications.as_array().unwrap().is_empty());
+        let member_notifications: Value = server
+            .get("/api/notifications")
+            .add_cookie(session_cookie(&member_session))
+            .await
+            .json();
+        assert_eq!(member_notifications[0]["card_id"], card_id.as_str());
+
+        // Only the recipient can mark a notification as read
+        let notification_id = member_notifications[0]["id"].as_str().unwrap();
+        server
+            .post(&format!("/api/notifications/{}/read", notification_id))
+            .add_cookie(session_cookie(&owner_session))
+            .await
+            .assert_status_not_found();
+    }
+
+    #[tokio::test]
+    async fn test_watchers_are_notified() {
+        let (server, state) = setup_server_with_state().await;
+        let owner_id = test_utils::create_test_user(&state, "owner@example.com", "Owner").await;
+        let owner_session = test_utils::create_test_session(&state, owner_id).await;
+        let watcher_id =
+            test_utils::create_test_user(&state, "watcher@example.com", "Watcher").await;
+        let watcher_session = test_utils::create_test_session(&state, watcher_id).await;
+
+        let board_id = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"name": "Team"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let mut column_ids = Vec::new();
+        for name in ["To Do", "Done"] {
+            let column: Value = server
+                .post(&format!("/api/boards/{}/columns", board_id))
+                .add_cookie(session_cookie(&owner_session))
+                .json(&json!({"name": name}))
+                .await
+                .json();
+            column_ids.push(column["id"].as_str().unwrap().to_string());
+        }
+        let card_id = server
+            .post(&format!("/api/columns/{}/cards", column_ids[0]))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"title": "Ship release"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let watch_url = format!("/api/cards/{}/watch", card_id);
+
+        // Only users who can see the card can watch it
+        server
+            .put(&watch_url)
+            .add_cookie(session_cookie(&watcher_session))
+            .await
+            .assert_status_forbidden();
+        server
+            .post(&format!("/api/boards/{}/permissions", board_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"user_id": watcher_id, "role": "Reader"}))
+            .await
+            .assert_status_ok();
+        let status: Value = server
+            .put(&watch_url)
+            .add_cookie(session_cookie(&watcher_session))
+            .await
+            .json();
+        assert_eq!(status, json!({"watching": true, "watchers": 1}));
+        server
+            .put(&watch_url)
+            .add_cookie(session_cookie(&owner_session))
+            .await
+            .assert_status_ok();
+
+        server
+            .patch(&format!("/api/cards/{}/move", card_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"column_id": column_ids[1], "position": 0}))
+            .await
+            .assert_status_ok();
+        server
+            .post(&format!("/api/cards/{}/comments", card_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"body": "Released!"}))
+            .await
+            .assert_status_ok();
+        server
+            .put(&format!("/api/cards/{}", card_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"due_date": "2024-03-08"}))
+            .await
+            .assert_status_ok();
+        // Other edits do not notify
+        server
+            .put(&format!("/api/cards/{}", card_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"title": "Ship the release"}))
+            .await
+            .assert_status_ok();
+
+        let notifications: Value = server
+            .get("/api/notifications")
+            .add_cookie(session_cookie(&watcher_session))
+            .await
+            .json();
+        let messages: Vec<(&str, &str)> = notifications
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|n| (n["kind"].as_str().unwrap(), n["message"].as_str().unwrap()))
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                ("due_date_changed", "changed the due date to 2024-03-08"),
+                ("card_commented", "commented: Released!"),
+                ("card_moved", "moved it to Done"),
+                ("added_to_board", "gave you reader access"),
+            ]
+        );
+        assert_eq!(notifications[0]["actor_name"], "Owner");
+        assert!(notifications[0]["due_date"].is_null());
+
+        // Nobody is notified of their own changes
+        let own: Value = server
+            .get("/api/notifications")
+            .add_cookie(session_cookie(&owner_session))
+            .await
+            .json();
+        assert!(own.as_array().unwrap().is_empty());
+
+        let status: Value = server
+            .delete(&watch_url)
+            .add_cookie(session_cookie(&watcher_session))
+            .await
+            .json();
+        assert_eq!(status, json!({"watching": false, "watchers": 1}));
+        server
+            .post(&format!("/api/cards/{}/comments", card_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"body": "One more thing"}))
+            .await
+            .assert_status_ok();
+        let count: Value = server
+            .get("/api/notifications/unread-count")
+            .add_cookie(session_cookie(&watcher_session))
+            .await
+            .json();
+        assert_eq!(count["count"], 4);
+    }
+
+    #[tokio::test]
+    async fn test_mentions_notify_members() {
+        let (server, state) = setup_server_with_state().await;
+        let owner_id = test_utils::create_test_user(&state, "owner@example.com", "Owner").await;
+        let owner_session = test_utils::create_test_session(&state, owner_id).await;
+        let member_id = test_utils::create_test_user(&state, "jane@example.com", "Jane Doe").await;
+        let member_session = test_utils::create_test_session(&state, member_id).await;
+        let outsider_id = test_utils::create_test_user(&state, "bob@example.com", "Bob").await;
+        let outsider_session = test_utils::create_test_session(&state, outsider_id).await;
+
+        let board_id = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"name": "Team"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        server
+            .post(&format!("/api/boards/{}/permissions", board_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"user_id": member_id, "role": "Reader"}))
+            .await
+            .assert_status_ok();
+        let column_id = server
+            .post(&format!("/api/boards/{}/columns", board_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"name": "To Do"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let card_id = server
+            .post(&format!("/api/columns/{}/cards", column_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"title": "Ship release"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        // Only members of the board can be mentioned, and not the author
+        let comment_id = server
+            .post(&format!("/api/cards/{}/comments", card_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"body": "@JaneDoe and @bob, can you review? cc @owner@example.com"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let notifications: Value = server
+            .get("/api/notifications")
+            .add_cookie(session_cookie(&member_session))
+            .await
+            .json();
+        assert_eq!(notifications.as_array().unwrap().len(), 2);
+        assert_eq!(notifications[0]["kind"], "mentioned");
+        assert_eq!(
+            notifications[0]["message"],
+            "mentioned you: @JaneDoe and @bob, can you review? cc @owner@example.com"
+        );
+        assert_eq!(notifications[0]["actor_name"], "Owner");
+        for session in [&outsider_session, &owner_session] {
+            let none: Value = server
+                .get("/api/notifications")
+                .add_cookie(session_cookie(session))
+                .await
+                .json();
+            assert!(none.as_array().unwrap().is_empty());
+        }
+
+        // Editing a comment only notifies users it did not mention before
+        server
+            .put(&format!("/api/comments/{}", comment_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"body": "@jane@example.com, can you review?"}))
+            .await
+            .assert_status_ok();
+        let count: Value = server
+            .get("/api/notifications/unread-count")
+            .add_cookie(session_cookie(&member_session))
+            .await
+            .json();
+        assert_eq!(count["count"], 2);
+
+        // A mentioned watcher is told once
+        server
+            .put(&format!("/api/cards/{}/watch", card_id))
+            .add_cookie(session_cookie(&member_session))
+            .await
+            .assert_status_ok();
+        server
+            .post(&format!("/api/cards/{}/comments", card_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"body": "Thanks @janedoe."}))
+            .await
+            .assert_status_ok();
+        let notifications: Value = server
+            .get("/api/notifications")
+            .add_cookie(session_cookie(&member_session))
+            .await
+            .json();
+        let kinds: Vec<&str> = notifications
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|n| n["kind"].as_str().unwrap())
+            .collect();
+        assert_eq!(kinds, ["mentioned", "mentioned", "added_to_board"]);
+
+        // The card page highlights mentions of known users only
+        let page = server
+            .get(&format!("/cards/{}", card_id))
+            .add_cookie(session_cookie(&member_session))
+            .await
+            .text();
+        assert!(page.contains(r#"<span class="mention text-primary fw-semibold">@janedoe</span>"#));
+        assert!(page.contains(
+            r#"<span class="mention text-primary fw-semibold">@jane@example.com</span>"#
+        ));
+    }
+}
+
+// ============================================================================
+// Reaction Tests
+// ============================================================================
+
+mod reaction_tests {
+    use super::*;
+
+    /// Percent-encode an emoji for use in a path
+    fn encode(emoji: &str) -> String {
+        emoji.bytes().map(|b| format!("%{:02X}", b)).collect()
+    }
+
+    #[tokio::test]
+    async fn test_react_to_cards_and_comments() {
+        let (server, state) = setup_server_with_state().await;
+        let owner_id = test_utils::create_test_user(&state, "owner@example.com", "Owner").await;
+        let owner_session = test_utils::create_test_session(&state, owner_id).await;
+        let reader_id = test_utils::create_test_user(&state, "reader@example.com", "Reader").await;
+        let reader_session = test_utils::create_test_session(&state, reader_id).await;
+        let outsider_id =
+            test_utils::create_test_user(&state, "outsider@example.com", "Outsider").await;
+        let outsider_session = test_utils::create_test_session(&state, outsider_id).await;
+
+        let board_id = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"name": "Team"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        server
+            .post(&format!("/api/boards/{}/permissions", board_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"user_id": reader_id, "role": "Reader"}))
+            .await
+            .assert_status_ok();
+        let column_id = server
+            .post(&format!("/api/boards/{}/columns", board_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"name": "To Do"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let card_id = server
+            .post(&format!("/api/columns/{}/cards", column_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"title": "Ship release"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let comment_id = server
+            .post(&format!("/api/cards/{}/comments", card_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"body": "Released!"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let thumbs_up = encode("👍");
+        let party = encode("🎉");
+        let comment_url = |emoji: &str| format!("/api/comments/{}/reactions/{}", comment_id, emoji);
+
+        // Readers can react, reacting twice counts once
+        for session in [&reader_session, &owner_session, &reader_session] {
+            server
+                .post(&comment_url(&thumbs_up))
+                .add_cookie(session_cookie(session))
+                .await
+                .assert_status_ok();
+        }
+        let reactions: Value = server
+            .post(&comment_url(&party))
+            .add_cookie(session_cookie(&reader_session))
+            .await
+            .json();
+        assert_eq!(
+            reactions,
+            json!([
+                {"emoji": "👍", "count": 2, "reacted": true},
+                {"emoji": "🎉", "count": 1, "reacted": true},
+            ])
+        );
+
+        // Only known emoji, and only on cards the user can see
+        server
+            .post(&comment_url(&encode("🦀")))
+            .add_cookie(session_cookie(&reader_session))
+            .await
+            .assert_status(axum::http::StatusCode::UNPROCESSABLE_ENTITY);
+        server
+            .post(&comment_url(&thumbs_up))
+            .add_cookie(session_cookie(&outsider_session))
+            .await
+            .assert_status_forbidden();
+
+        let reactions: Value = server
+            .delete(&comment_url(&party))
+            .add_cookie(session_cookie(&reader_session))
+            .await
+            .json();
+        assert_eq!(
+            reactions,
+            json!([{"emoji": "👍", "count": 2, "reacted": true}])
+        );
+        server
+            .post(&format!("/api/cards/{}/reactions/{}", card_id, party))
+            .add_cookie(session_cookie(&reader_session))
+            .await
+            .assert_status_ok();
+
+        // Counts are part of comments, as seen by each user
+        let comments: Value = server
+            .get(&format!("/api/cards/{}/comments", card_id))
+            .add_cookie(session_cookie(&owner_session))
+            .await
+            .json();
+        assert_eq!(
+            comments[0]["reactions"],
+            json!([{"emoji": "👍", "count": 2, "reacted": true}])
+        );
+        let full: Value = server
+            .get(&format!("/api/cards/{}/full", card_id))
+            .add_cookie(session_cookie(&owner_session))
+            .await
+            .json();
+        assert_eq!(
+            full["reactions"],
+            json!([{"emoji": "🎉", "count": 1, "reacted": false}])
+        );
+        assert_eq!(full["comments"][0]["reactions"][0]["count"], 2);
+
+        // The card page toggles reactions
+        server
+            .post(&format!(
+                "/cards/{}/comments/{}/reactions",
+                card_id, comment_id
+            ))
+            .add_cookie(session_cookie(&owner_session))
+            .form(&[("emoji", "👍")])
+            .await
+            .assert_status(axum::http::StatusCode::SEE_OTHER);
+        let page = server
+            .get(&format!("/cards/{}", card_id))
+            .add_cookie(session_cookie(&owner_session))
+            .await
+            .text();
+        assert!(page.contains("👍 1"));
+        assert!(page.contains("🎉 1"));
+    }
+}
+
+// ============================================================================
+// Revision Tests
+// ============================================================================
+
+mod revision_tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_card_revisions() {
+        let (server, state) = setup_server_with_state().await;
+        let owner_id = test_utils::create_test_user(&state, "owner@example.com", "Owner").await;
+        let owner_session = test_utils::create_test_session(&state, owner_id).await;
+        let reader_id = test_utils::create_test_user(&state, "reader@example.com", "Reader").await;
+        let reader_session = test_utils::create_test_session(&state, reader_id).await;
+
+        let board_id = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"name": "Team"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        server
+            .post(&format!("/api/boards/{}/permissions", board_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"user_id": reader_id, "role": "Reader"}))
+            .await
+            .assert_status_ok();
+        let column_id = server
+            .post(&format!("/api/boards/{}/columns", board_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"name": "To Do"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let card_id = server
+            .post(&format!("/api/columns/{}/cards", column_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"title": "Plan", "body": "Goals\nShip v1"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let card_url = format!("/api/cards/{}", card_id);
+        let revisions_url = format!("/api/cards/{}/revisions", card_id);
+
+        for change in [
+            json!({"title": "Release plan"}),
+            json!({"status": "InProgress"}),
+            json!({"body": "Goals\nShip v2"}),
+        ] {
+            server
+                .put(&card_url)
+                .add_cookie(session_cookie(&owner_session))
+                .json(&change)
+                .await
+                .assert_status_ok();
+        }
+
+        // Only title and body edits are kept, newest first
+        let revisions: Value = server
+            .get(&revisions_url)
+            .add_cookie(session_cookie(&reader_session))
+            .await
+            .json();
+        let revisions = revisions.as_array().unwrap();
+        assert_eq!(revisions.len(), 2);
+        assert_eq!(revisions[0]["title"], "Release plan");
+        assert_eq!(revisions[0]["body"], "Goals\nShip v1");
+        assert_eq!(revisions[0]["editor_name"], "Owner");
+        assert_eq!(
+            revisions[0]["diff"],
+            json!({
+                "title": null,
+                "body": "@@ -1,2 +1,2 @@\n Goals\n-Ship v1\n+Ship v2\n",
+            })
+        );
+        assert_eq!(revisions[1]["title"], "Plan");
+        assert_eq!(
+            revisions[1]["diff"],
+            json!({"title": {"from": "Plan", "to": "Release plan"}, "body": null})
+        );
+
+        // Readers cannot restore
+        let oldest = revisions[1]["id"].as_str().unwrap();
+        let restore_url = format!("{}/{}/restore", revisions_url, oldest);
+        server
+            .post(&restore_url)
+            .add_cookie(session_cookie(&reader_session))
+            .await
+            .assert_status_forbidden();
+        server
+            .post(&format!(
+                "{}/{}/restore",
+                revisions_url,
+                uuid::Uuid::new_v4()
+            ))
+            .add_cookie(session_cookie(&owner_session))
+            .await
+            .assert_status_not_found();
+
+        let card: Value = server
+            .post(&restore_url)
+            .add_cookie(session_cookie(&owner_session))
+            .await
+            .json();
+        assert_eq!(card["title"], "Plan");
+        assert_eq!(card["body"], "Goals\nShip v1");
+        assert_eq!(card["status"], "in_progress");
+
+        // The replaced version is kept too
+        let revisions: Value = server
+            .get(&revisions_url)
+            .add_cookie(session_cookie(&owner_session))
+            .await
+            .json();
+        assert_eq!(revisions.as_array().unwrap().len(), 3);
+        assert_eq!(revisions[0]["title"], "Release plan");
+        assert_eq!(revisions[0]["body"], "Goals\nShip v2");
+    }
+}
+
+// ============================================================================
+// Share Link Tests
+// ============================================================================
+
+mod share_link_tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_public_board_share_link() {
+        let (server, state) = setup_server_with_state().await;
+        let owner_id = test_utils::create_test_user(&state, "owner@example.com", "Owner").await;
+        let owner_session = test_utils::create_test_session(&state, owner_id).await;
+        let editor_id = test_utils::create_test_user(&state, "editor@example.com", "Editor").await;
+        let editor_session = test_utils::create_test_session(&state, editor_id).await;
+
+        let board_id = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"name": "Roadmap"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        server
+            .post(&format!("/api/boards/{}/permissions", board_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"user_id": editor_id, "role": "Editor"}))
+            .await
+            .assert_status_ok();
+        let column_id = server
+            .post(&format!("/api/boards/{}/columns", board_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"name": "Next Quarter"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let mut card_ids = Vec::new();
+        for (title, visibility) in [
+            ("Dark mode", "Public"),
+            ("Acquire competitor", "Private"),
+            ("Team offsite", "Restricted"),
+        ] {
+            let card: Value = server
+                .post(&format!("/api/columns/{}/cards", column_id))
+                .add_cookie(session_cookie(&owner_session))
+                .json(&json!({"title": title, "visibility": visibility}))
+                .await
+                .json();
+            card_ids.push(card["id"].as_str().unwrap().to_string());
+        }
+        let tag_id = server
+            .post(&format!("/api/boards/{}/tags", board_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"name": "Design"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        server
+            .post(&format!("/api/cards/{}/tags/{}", card_ids[0], tag_id))
+            .add_cookie(session_cookie(&owner_session))
+            .await
+            .assert_status_ok();
+        let link_url = format!("/api/boards/{}/share-link", board_id);
+
+        // Only the owner manages the public address
+        server
+            .post(&link_url)
+            .add_cookie(session_cookie(&editor_session))
+            .await
+            .assert_status_forbidden();
+        let status: Value = server
+            .get(&link_url)
+            .add_cookie(session_cookie(&owner_session))
+            .await
+            .json();
+        assert!(status.is_null());
+
+        let link: Value = server
+            .post(&link_url)
+            .add_cookie(session_cookie(&owner_session))
+            .await
+            .json();
+        let token = link["token"].as_str().unwrap().to_string();
+        assert!(link["url"]
+            .as_str()
+            .unwrap()
+            .ends_with(&format!("/share/{}", token)));
+        let status: Value = server
+            .get(&link_url)
+            .add_cookie(session_cookie(&owner_session))
+            .await
+            .json();
+        assert_eq!(status["board_id"], board_id.as_str());
+
+        // Anyone with the address sees the public cards only
+        let response = server.get(&format!("/share/{}", token)).await;
+        response.assert_status_ok();
+        let page = response.text();
+        assert!(page.contains("Roadmap"));
+        assert!(page.contains("Next Quarter"));
+        assert!(page.contains("Dark mode"));
+        assert!(page.contains("Design"));
+        assert!(!page.contains("Acquire competitor"));
+        assert!(!page.contains("Team offsite"));
+
+        // A new address replaces the old one
+        let new_token = server
+            .post(&link_url)
+            .add_cookie(session_cookie(&owner_session))
+            .await
+            .json::<Value>()["token"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        server
+            .get(&format!("/share/{}", token))
+            .await
+            .assert_status_not_found();
+        server
+            .get(&format!("/share/{}", new_token))
+            .await
+            .assert_status_ok();
+
+        // Revoking it from the settings page takes the board offline
+        let settings = server
+            .get(&format!("/boards/{}/settings", board_id))
+            .add_cookie(session_cookie(&owner_session))
+            .await
+            .text();
+        assert!(settings.contains("Shared publicly since"));
+        server
+            .post(&format!("/boards/{}/settings/share-link/delete", board_id))
+            .add_cookie(session_cookie(&owner_session))
+            .await
+            .assert_status(axum::http::StatusCode::SEE_OTHER);
+        server
+            .get(&format!("/share/{}", new_token))
+            .await
+            .assert_status_not_found();
+        server
+            .delete(&link_url)
+            .add_cookie(session_cookie(&owner_session))
+            .await
+            .assert_status_not_found();
+    }
+
+    #[tokio::test]
+    async fn test_guest_comments_await_approval() {
+        let (server, state) = setup_server_with_state().await;
+        let owner_id = test_utils::create_test_user(&state, "owner@example.com", "Owner").await;
+        let owner_session = test_utils::create_test_session(&state, owner_id).await;
+        let editor_id = test_utils::create_test_user(&state, "editor@example.com", "Editor").await;
+        let editor_session = test_utils::create_test_session(&state, editor_id).await;
+
+        let board_id = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"name": "Roadmap"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        server
+            .post(&format!("/api/boards/{}/permissions", board_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"user_id": editor_id, "role": "Editor"}))
+            .await
+            .assert_status_ok();
+        let column_id = server
+            .post(&format!("/api/boards/{}/columns", board_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"name": "Next Quarter"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let mut card_ids = Vec::new();
+        for (title, visibility) in [("Dark mode", "Public"), ("Acquire competitor", "Private")] {
+            let card: Value = server
+                .post(&format!("/api/columns/{}/cards", column_id))
+                .add_cookie(session_cookie(&owner_session))
+                .json(&json!({"title": title, "visibility": visibility}))
+                .await
+                .json();
+            card_ids.push(card["id"].as_str().unwrap().to_string());
+        }
+        let token = server
+            .post(&format!("/api/boards/{}/share-link", board_id))
+            .add_cookie(session_cookie(&owner_session))
+            .await
+            .json::<Value>()["token"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let comments_url =
+            |card_id: &str| format!("/api/share/{}/cards/{}/comments", token, card_id);
+        let list_url = format!("/api/boards/{}/guest-comments", board_id);
+
+        // Private cards and unknown links take no comments
+        server
+            .post(&comments_url(&card_ids[1]))
+            .json(&json!({"name": "Sam", "body": "Hmm"}))
+            .await
+            .assert_status_not_found();
+        server
+            .post(&format!("/api/share/nope/cards/{}/comments", card_ids[0]))
+            .json(&json!({"name": "Sam", "body": "Hmm"}))
+            .await
+            .assert_status_not_found();
+        server
+            .post(&comments_url(&card_ids[0]))
+            .json(&json!({"name": " ", "body": "Hmm"}))
+            .await
+            .assert_status_unprocessable_entity();
+
+        // Bots filling in the honeypot are dropped quietly
+        server
+            .post(&comments_url(&card_ids[0]))
+            .json(&json!({"name": "Bot", "body": "Cheap pills", "website": "spam.example.com"}))
+            .await
+            .assert_status_ok();
+        server
+            .post(&comments_url(&card_ids[0]))
+            .json(&json!({"name": "Sam", "body": "Looking forward to this one"}))
+            .await
+            .assert_status_ok();
+
+        // Comments wait for an owner before showing up
+        server
+            .get(&list_url)
+            .add_cookie(session_cookie(&editor_session))
+            .await
+            .assert_status_forbidden();
+        let pending: Vec<Value> = server
+            .get(&format!("{}?pending=true", list_url))
+            .add_cookie(session_cookie(&owner_session))
+            .await
+            .json();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0]["name"], "Sam");
+        assert_eq!(pending[0]["card_title"], "Dark mode");
+        assert!(pending[0]["approved_at"].is_null());
+        let comment_id = pending[0]["id"].as_str().unwrap().to_string();
+        let page = server.get(&format!("/share/{}", token)).await.text();
+        assert!(!page.contains("Looking forward to this one"));
+
+        let approved: Value = server
+            .post(&format!("{}/{}/approve", list_url, comment_id))
+            .add_cookie(session_cookie(&owner_session))
+            .await
+            .json();
+        assert_eq!(approved["approved_by"], owner_id.to_string());
+        let page = server.get(&format!("/share/{}", token)).await.text();
+        assert!(page.contains("Looking forward to this one"));
+        let pending: Vec<Value> = server
+            .get(&format!("{}?pending=true", list_url))
+            .add_cookie(session_cookie(&owner_session))
+            .await
+            .json();
+        assert!(pending.is_empty());
+
+        // The share page form submits a comment too
+        server
+            .post(&format!("/share/{}/cards/{}/comments", token, card_ids[0]))
+            .form(&[("name", "Alex"), ("body", "Count me in")])
+            .await
+            .assert_status(axum::http::StatusCode::SEE_OTHER);
+        let all: Vec<Value> = server
+            .get(&list_url)
+            .add_cookie(session_cookie(&owner_session))
+            .await
+            .json();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0]["name"], "Alex");
+
+        server
+            .delete(&format!("{}/{}", list_url, comment_id))
+            .add_cookie(session_cookie(&owner_session))
+            .await
+            .assert_status(axum::http::StatusCode::NO_CONTENT);
+        let page = server.get(&format!("/share/{}", token)).await.text();
+        assert!(!page.contains("Looking forward to this one"));
+        server
+            .delete(&format!("{}/{}", list_url, comment_id))
+            .add_cookie(session_cookie(&owner_session))
+            .await
+            .assert_status_not_found();
+    }
+}
+
+// ============================================================================
+// Public Card Tests
+// ============================================================================
+
+mod public_card_tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_public_card_page() {
+        let (server, state) = setup_server_with_state().await;
+        let owner_id = test_utils::create_test_user(&state, "owner@example.com", "Owner").await;
+        let owner_session = test_utils::create_test_session(&state, owner_id).await;
+        let editor_id = test_utils::create_test_user(&state, "editor@example.com", "Editor").await;
+        let editor_session = test_utils::create_test_session(&state, editor_id).await;
+
+        let board: Value = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"name": "Roadmap"}))
+            .await
+            .json();
+        assert_eq!(board["allow_public_links"], false);
+        let board_id = board["id"].as_str().unwrap().to_string();
+        server
+            .post(&format!("/api/boards/{}/permissions", board_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"user_id": editor_id, "role": "Editor"}))
+            .await
+            .assert_status_ok();
+        let column_id = server
+            .post(&format!("/api/boards/{}/columns", board_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"name": "Next"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let mut card_ids = Vec::new();
+        for (title, visibility) in [
+            ("Dark mode", "Public"),
+            ("Acquire competitor", "Private"),
+            ("Team offsite", "Restricted"),
+        ] {
+            let card: Value = server
+                .post(&format!("/api/columns/{}/cards", column_id))
+                .add_cookie(session_cookie(&owner_session))
+                .json(&json!({"title": title, "visibility": visibility}))
+                .await
+                .json();
+            card_ids.push(card["id"].as_str().unwrap().to_string());
+        }
+        server
+            .post(&format!("/api/cards/{}/comments", card_ids[0]))
+            .add_cookie(session_cookie(&editor_session))
+            .json(&json!({"body": "Follows the system theme"}))
+            .await
+            .assert_status_ok();
+        let public_url = format!("/cards/{}/public", card_ids[0]);
+
+        // Nothing is public until the board allows it
+        server.get(&public_url).await.assert_status_not_found();
+
+        // Only the owner can allow it
+        let board_url = format!("/api/boards/{}", board_id);
+        server
+            .put(&board_url)
+            .add_cookie(session_cookie(&editor_session))
+            .json(&json!({"allow_public_links": true}))
+            .await
+            .assert_status_forbidden();
+        let board: Value = server
+            .put(&board_url)
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"allow_public_links": true}))
+            .await
+            .json();
+        assert_eq!(board["allow_public_links"], true);
+
+        // Anyone sees the public card and its comments
+        let response = server.get(&public_url).await;
+        response.assert_status_ok();
+        let page = response.text();
+        assert!(page.contains("Dark mode"));
+        assert!(page.contains("Follows the system theme"));
+        assert!(page.contains("Editor"));
+
+        // Private and restricted cards stay hidden
+        for card_id in &card_ids[1..] {
+            server
+                .get(&format!("/cards/{}/public", card_id))
+                .await
+                .assert_status_not_found();
+        }
+
+        // Turning it off from the settings page takes the card offline
+        server
+            .post(&format!("/boards/{}/settings/public-links", board_id))
+            .add_cookie(session_cookie(&owner_session))
+            .form(&[("enabled", "false")])
+            .await
+            .assert_status(axum::http::StatusCode::SEE_OTHER);
+        server.get(&public_url).await.assert_status_not_found();
+    }
+}
+
+// ============================================================================
+// Board Preference Tests
+// ============================================================================
+
+mod board_preference_tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_star_group_and_order_boards() {
+        let (server, state) = setup_server_with_state().await;
+        let user_id = test_utils::create_test_user(&state, "user@example.com", "User").await;
+        let session = test_utils::create_test_session(&state, user_id).await;
+        let other_id = test_utils::create_test_user(&state, "other@example.com", "Other").await;
+        let other_session = test_utils::create_test_session(&state, other_id).await;
+
+        let mut board_ids = Vec::new();
+        for name in ["Alpha", "Beta", "Gamma"] {
+            let board: Value = server
+                .post("/api/boards")
+                .add_cookie(session_cookie(&session))
+                .json(&json!({"name": name}))
+                .await
+                .json();
+            board_ids.push(board["id"].as_str().unwrap().to_string());
+        }
+        let group: Value = server
+            .post("/api/board-groups")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Clients"}))
+            .await
+            .json();
+        let group_id = group["id"].as_str().unwrap().to_string();
+
+        // Stars and groups are per user and limited to the user's boards and groups
+        let preference: Value = server
+            .put(&format!("/api/boards/{}/preferences", board_ids[0]))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"starred": true, "group_id": group_id}))
+            .await
+            .json();
+        assert_eq!(preference["starred"], true);
+        assert_eq!(preference["group_id"], group_id.as_str());
+        server
+            .put(&format!("/api/boards/{}/preferences", board_ids[0]))
+            .add_cookie(session_cookie(&other_session))
+            .json(&json!({"starred": true}))
+            .await
+            .assert_status_forbidden();
+        let other_board = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&other_session))
+            .json(&json!({"name": "Theirs"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        server
+            .put(&format!("/api/boards/{}/preferences", other_board))
+            .add_cookie(session_cookie(&other_session))
+            .json(&json!({"group_id": group_id}))
+            .await
+            .assert_status(axum::http::StatusCode::UNPROCESSABLE_ENTITY);
+        server
+            .put("/api/board-order")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"board_ids": [other_board]}))
+            .await
+            .assert_status(axum::http::StatusCode::UNPROCESSABLE_ENTITY);
+
+        // Ordered boards come first on the boards page, in their order
+        server
+            .put("/api/board-order")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"board_ids": [board_ids[2], board_ids[1]]}))
+            .await
+            .assert_status_ok();
+        let preferences: Value = server
+            .get("/api/board-preferences")
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(preferences.as_array().unwrap().len(), 3);
+
+        let page = server
+            .get("/boards")
+            .add_cookie(session_cookie(&session))
+            .await
+            .text();
+        let starred = page.find("Starred").unwrap();
+        let clients = page.find("Clients").unwrap();
+        let other = page.find("Other Boards").unwrap();
+        assert!(starred < clients && clients < other);
+        let gamma = page.rfind("Gamma").unwrap();
+        let beta = page.rfind("Beta").unwrap();
+        assert!(other < gamma && gamma < beta);
+
+        // Moving a board in the page swaps it with its neighbour of the section
+        server
+            .post(&format!("/boards/{}/move", board_ids[1]))
+            .add_cookie(session_cookie(&session))
+            .form(&[("section", ""), ("direction", "up")])
+            .await
+            .assert_status(axum::http::StatusCode::SEE_OTHER);
+        let page = server
+            .get("/boards")
+            .add_cookie(session_cookie(&session))
+            .await
+            .text();
+        assert!(page.rfind("Beta").unwrap() < page.rfind("Gamma").unwrap());
+
+        // Deleting the group keeps its boards
+        server
+            .delete(&format!("/api/board-groups/{}", group_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .assert_status_ok();
+        let preferences: Value = server
+            .get("/api/board-preferences")
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert!(preferences
+            .as_array()
+            .unwrap()
+            .iter()
+            .all(|p| p["group_id"].is_null()));
+        let groups: Value = server
+            .get("/api/board-groups")
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(groups, json!([]));
+    }
+}
+
+// ============================================================================
+// Workspace Tests
+// ============================================================================
+
+mod workspace_tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_workspace_members_get_access_to_its_boards() {
+        let (server, state) = setup_server_with_state().await;
+        let owner_id = test_utils::create_test_user(&state, "owner@example.com", "Owner").await;
+        let owner_session = test_utils::create_test_session(&state, owner_id).await;
+        let member_id = test_utils::create_test_user(&state, "member@example.com", "Member").await;
+        let member_session = test_utils::create_test_session(&state, member_id).await;
+        let outsider_id =
+            test_utils::create_test_user(&state, "outsider@example.com", "Outsider").await;
+        let outsider_session = test_utils::create_test_session(&state, outsider_id).await;
+
+        let workspace: Value = server
+            .post("/api/workspaces")
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"name": "Acme"}))
+            .await
+            .json();
+        assert_eq!(workspace["role"], "owner");
+        let workspace_id = workspace["id"].as_str().unwrap().to_string();
+        let members_url = format!("/api/workspaces/{}/members", workspace_id);
+
+        // Boards are created in the workspace or moved into it
+        let board: Value = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"name": "Roadmap", "workspace_id": workspace_id}))
+            .await
+            .json();
+        assert_eq!(board["workspace_id"], workspace_id.as_str());
+        let roadmap_id = board["id"].as_str().unwrap().to_string();
+        let ops_id = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"name": "Ops"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let moved: Value = server
+            .put(&format!("/api/boards/{}/workspace", ops_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"workspace_id": workspace_id}))
+            .await
+            .json();
+        assert_eq!(moved["workspace_id"], workspace_id.as_str());
+
+        // Outsiders can neither see the workspace nor add boards to it
+        server
+            .get(&format!("/api/workspaces/{}", workspace_id))
+            .add_cookie(session_cookie(&outsider_session))
+            .await
+            .assert_status_forbidden();
+        server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&outsider_session))
+            .json(&json!({"name": "Mine", "workspace_id": workspace_id}))
+            .await
+            .assert_status_forbidden();
+
+        // A reader of the workspace reads every board of it
+        server
+            .post(&members_url)
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"user_id": member_id, "role": "Reader"}))
+            .await
+            .assert_status_ok();
+        let members: Value = server
+            .get(&members_url)
+            .add_cookie(session_cookie(&member_session))
+            .await
+            .json();
+        assert_eq!(members.as_array().unwrap().len(), 2);
+        server
+            .post(&members_url)
+            .add_cookie(session_cookie(&member_session))
+            .json(&json!({"user_id": outsider_id, "role": "Reader"}))
+            .await
+            .assert_status_forbidden();
+
+        let boards: Value = server
+            .get("/api/boards")
+            .add_cookie(session_cookie(&member_session))
+            .await
+            .json();
+        let boards = boards.as_array().unwrap();
+        assert_eq!(boards.len(), 2);
+        assert!(boards.iter().all(|b| b["role"] == "reader"));
+        server
+            .get(&format!("/api/boards/{}", roadmap_id))
+            .add_cookie(session_cookie(&member_session))
+            .await
+            .assert_status_ok();
+        server
+            .post(&format!("/api/boards/{}/columns", roadmap_id))
+            .add_cookie(session_cookie(&member_session))
+            .json(&json!({"name": "Later"}))
+            .await
+            .assert_status_forbidden();
+
+        // A board permission takes precedence over the workspace role
+        server
+            .post(&format!("/api/boards/{}/permissions", roadmap_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"user_id": member_id, "role": "Editor"}))
+            .await
+            .assert_status_ok();
+        server
+            .post(&format!("/api/boards/{}/columns", roadmap_id))
+            .add_cookie(session_cookie(&member_session))
+            .json(&json!({"name": "Later"}))
+            .await
+            .assert_status_ok();
+        let boards: Value = server
+            .get(&format!("/api/workspaces/{}/boards", workspace_id))
+            .add_cookie(session_cookie(&member_session))
+            .await
+            .json();
+        let roles: Vec<(&str, &str)> = boards
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|b| (b["name"].as_str().unwrap(), b["role"].as_str().unwrap()))
+            .collect();
+        assert_eq!(roles, vec![("Ops", "reader"), ("Roadmap", "editor")]);
+
+        // Leaving the workspace takes the access that came from it
+        server
+            .put(&format!("/api/boards/{}/workspace", ops_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"workspace_id": null}))
+            .await
+            .assert_status_ok();
+        server
+            .get(&format!("/api/boards/{}", ops_id))
+            .add_cookie(session_cookie(&member_session))
+            .await
+            .assert_status_forbidden();
+
+        // Deleting the workspace keeps its boards and their own members
+        server
+            .delete(&format!("/api/workspaces/{}", workspace_id))
+            .add_cookie(session_cookie(&member_session))
+            .await
+            .assert_status_forbidden();
+        server
+            .delete(&format!("/api/workspaces/{}", workspace_id))
+            .add_cookie(session_cookie(&owner_session))
+            .await
+            .assert_status_ok();
+        let board: Value = server
+            .get(&format!("/api/boards/{}", roadmap_id))
+            .add_cookie(session_cookie(&member_session))
+            .await
+            .json();
+        assert_eq!(board["role"], "editor");
+        assert!(board["workspace_id"].is_null());
+        let workspaces: Value = server
+            .get("/api/workspaces")
+            .add_cookie(session_cookie(&member_session))
+            .await
+            .json();
+        assert_eq!(workspaces, json!([]));
+    }
+}
+
+// ============================================================================
+// Column Detail Tests
+// ============================================================================
+
+mod column_detail_tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_column_color_description_and_collapse() {
+        let (server, state) = setup_server_with_state().await;
+        let owner_id = test_utils::create_test_user(&state, "owner@example.com", "Owner").await;
+        let owner_session = test_utils::create_test_session(&state, owner_id).await;
+        let reader_id = test_utils::create_test_user(&state, "reader@example.com", "Reader").await;
+        let reader_session = test_utils::create_test_session(&state, reader_id).await;
+
+        let board_id = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"name": "Team"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        server
+            .post(&format!("/api/boards/{}/permissions", board_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"user_id": reader_id, "role": "Reader"}))
+            .await
+            .assert_status_ok();
+
+        server
+            .post(&format!("/api/boards/{}/columns", board_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"name": "To Do", "color": "red"}))
+            .await
+            .assert_status(axum::http::StatusCode::UNPROCESSABLE_ENTITY);
+        let column: Value = server
+            .post(&format!("/api/boards/{}/columns", board_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"name": "To Do", "color": "#1F77B4", "description": "  Not started  "}))
+            .await
+            .json();
+        assert_eq!(column["color"], "#1f77b4");
+        assert_eq!(column["description"], "Not started");
+        assert_eq!(column["collapsed"], false);
+        let column_id = column["id"].as_str().unwrap().to_string();
+
+        // Readers may collapse the column for themselves but not change it
+        server
+            .put(&format!("/api/columns/{}", column_id))
+            .add_cookie(session_cookie(&reader_session))
+            .json(&json!({"color": "#000000"}))
+            .await
+            .assert_status_forbidden();
+        let collapsed: Value = server
+            .put(&format!("/api/columns/{}", column_id))
+            .add_cookie(session_cookie(&reader_session))
+            .json(&json!({"collapsed": true}))
+            .await
+            .json();
+        assert_eq!(collapsed["collapsed"], true);
+        assert_eq!(collapsed["version"], column["version"]);
+
+        let columns: Value = server
+            .get(&format!("/api/boards/{}/columns", board_id))
+            .add_cookie(session_cookie(&owner_session))
+            .await
+            .json();
+        assert_eq!(columns[0]["collapsed"], false);
+        let board: Value = server
+            .get(&format!("/api/boards/{}", board_id))
+            .add_cookie(session_cookie(&reader_session))
+            .await
+            .json();
+        assert_eq!(board["columns"][0]["collapsed"], true);
+
+        let updated: Value = server
+            .put(&format!("/api/columns/{}", column_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"color": null, "description": "Agreed on"}))
+            .await
+            .json();
+        assert!(updated["color"].is_null());
+        assert_eq!(updated["description"], "Agreed on");
+        assert!(updated["version"].as_i64() > column["version"].as_i64());
+
+        // The board page shows the description and hides the cards of collapsed columns
+        server
+            .post(&format!("/api/columns/{}/cards", column_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"title": "Hidden card"}))
+            .await
+            .assert_status_ok();
+        let page = server
+            .get(&format!("/boards/{}", board_id))
+            .add_cookie(session_cookie(&reader_session))
+            .await
+            .text();
+        assert!(page.contains("Agreed on"));
+        assert!(!page.contains("Hidden card"));
+        server
+            .post(&format!("/columns/{}/collapse", column_id))
+            .add_cookie(session_cookie(&reader_session))
+            .form(&[("collapsed", "false")])
+            .await
+            .assert_status(axum::http::StatusCode::SEE_OTHER);
+        let page = server
+            .get(&format!("/boards/{}", board_id))
+            .add_cookie(session_cookie(&reader_session))
+            .await
+            .text();
+        assert!(page.contains("Hidden card"));
+    }
+}
+
+// ============================================================================
+// Status Sync Tests
+// ============================================================================
+
+mod status_sync_tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_moves_follow_column_status() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+        let board_id = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Flow"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let mut columns = Vec::new();
+        for name in ["To Do", "Done"] {
+            let column: Value = server
+                .post(&format!("/api/boards/{}/columns", board_id))
+                .add_cookie(session_cookie(&session))
+                .json(&json!({"name": name}))
+                .await
+                .json();
+            columns.push(column);
+        }
+        assert!(columns[0]["card_status"].is_null());
+        assert_eq!(columns[1]["card_status"], "done");
+        let todo = columns[0]["id"].as_str().unwrap().to_string();
+        let done = columns[1]["id"].as_str().unwrap().to_string();
+
+        let card_id = server
+            .post(&format!("/api/columns/{}/cards", todo))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"title": "Ship it"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let moved: Value = server
+            .patch(&format!("/api/cards/{}/move", card_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"column_id": done, "position": 0}))
+            .await
+            .json();
+        assert_eq!(moved["status"], "done");
+
+        let column: Value = server
+            .put(&format!("/api/columns/{}", todo))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"card_status": "Open"}))
+            .await
+            .json();
+        assert_eq!(column["card_status"], "open");
+        server
+            .post(&format!("/api/boards/{}/cards/bulk", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"operations": [
+                {"op": "move", "card_id": card_id, "column_id": todo},
+            ]}))
+            .await
+            .assert_status_ok();
+        let card: Value = server
+            .get(&format!("/api/cards/{}", card_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(card["status"], "open");
+
+        // Cards that drifted from their column are brought back in line on request
+        server
+            .patch(&format!("/api/cards/{}", card_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"status": "Closed"}))
+            .await
+            .assert_status_ok();
+        let result: Value = server
+            .post(&format!("/api/boards/{}/status-sync", board_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(result["updated"], 1);
+        let card: Value = server
+            .get(&format!("/api/cards/{}", card_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(card["status"], "open");
+    }
+}
+
+// ============================================================================
+// Card Placement Tests
+// ============================================================================
+
+mod card_placement_tests {
+    use super::*;
+
+    async fn create_board_with_columns(
+        server: &TestServer,
+        session: &str,
+        name: &str,
+        columns: &[&str],
+    ) -> (String, Vec<String>) {
+        let board_id = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(session))
+            .json(&json!({"name": name}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let mut column_ids = Vec::new();
+        for column in columns {
+            let column: Value = server
+                .post(&format!("/api/boards/{}/columns", board_id))
+                .add_cookie(session_cookie(session))
+                .json(&json!({"name": column}))
+                .await
+                .json();
+            column_ids.push(column["id"].as_str().unwrap().to_string());
+        }
+        (board_id, column_ids)
+    }
+
+    /// Card ids per column, as the board endpoint lists them
+    async fn board_cards(server: &TestServer, session: &str, board_id: &str) -> Vec<Vec<String>> {
+        let board: Value = server
+            .get(&format!("/api/boards/{}", board_id))
+            .add_cookie(session_cookie(session))
+            .await
+            .json();
+        board["columns"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|column| {
+                column["cards"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|card| card["id"].as_str().unwrap().to_string())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_cards_show_on_every_board_they_are_placed_on() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+        let (home_id, home_columns) =
+            create_board_with_columns(&server, &session, "Home", &["To Do"]).await;
+        let (other_id, other_columns) =
+            create_board_with_columns(&server, &session, "Other", &["Backlog", "Next"]).await;
+
+        let card_id = server
+            .post(&format!("/api/columns/{}/cards", home_columns[0]))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"title": "Shared"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        server
+            .post(&format!("/api/cards/{}/boards/{}", card_id, other_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"column_id": other_columns[0]}))
+            .await
+            .assert_status_ok();
+        assert_eq!(
+            board_cards(&server, &session, &other_id).await,
+            [vec![card_id.clone()], vec![]]
+        );
+
+        // Moving on the other board leaves the home column alone
+        let moved: Value = server
+            .patch(&format!("/api/cards/{}/move", card_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"column_id": other_columns[1], "position": 0}))
+            .await
+            .json();
+        assert_eq!(moved["column_id"], other_columns[1]);
+        assert_eq!(
+            board_cards(&server, &session, &other_id).await,
+            [vec![], vec![card_id.clone()]]
+        );
+        assert_eq!(
+            board_cards(&server, &session, &home_id).await,
+            [vec![card_id.clone()]]
+        );
+        let card: Value = server
+            .get(&format!("/api/cards/{}", card_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(card["column_id"], home_columns[0]);
+
+        // A board the card is not on cannot be moved onto
+        let (_, stranger_columns) =
+            create_board_with_columns(&server, &session, "Stranger", &["Inbox"]).await;
+        server
+            .patch(&format!("/api/cards/{}/move", card_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"column_id": stranger_columns[0], "position": 0}))
+            .await
+            .assert_status_bad_request();
+    }
+
+    #[tokio::test]
+    async fn test_filtered_cards_include_cards_placed_on_the_board() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+        let (_, home_columns) =
+            create_board_with_columns(&server, &session, "Home", &["To Do"]).await;
+        let (other_id, other_columns) =
+            create_board_with_columns(&server, &session, "Other", &["Backlog"]).await;
+
+        let card_id = server
+            .post(&format!("/api/columns/{}/cards", home_columns[0]))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"title": "Shared report"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        server
+            .post(&format!("/api/cards/{}/boards/{}", card_id, other_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"column_id": other_columns[0]}))
+            .await
+            .assert_status_ok();
+
+        let cards: Value = server
+            .get(&format!("/api/boards/{}/cards?query=report", other_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        let cards = cards.as_array().unwrap();
+        assert_eq!(cards.len(), 1);
+        assert_eq!(cards[0]["id"], card_id.as_str());
+        // Listed in its column on this board
+        assert_eq!(cards[0]["column_id"], other_columns[0]);
+    }
+
+    #[tokio::test]
+    async fn test_stats_count_cards_placed_on_the_board() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+        let (_, home_columns) =
+            create_board_with_columns(&server, &session, "Home", &["To Do"]).await;
+        let (other_id, other_columns) =
+            create_board_with_columns(&server, &session, "Other", &["Backlog"]).await;
+
+        let card_id = server
+            .post(&format!("/api/columns/{}/cards", home_columns[0]))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"title": "Late report", "due_date": "2020-01-01"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        server
+            .post(&format!("/api/cards/{}/boards/{}", card_id, other_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"column_id": other_columns[0]}))
+            .await
+            .assert_status_ok();
+
+        let stats: Value = server
+            .get(&format!("/api/boards/{}/stats", other_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(stats["columns"][0]["name"], "Backlog");
+        assert_eq!(stats["columns"][0]["cards"], 1);
+        assert_eq!(stats["overdue_cards"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_inbox_cards_get_a_home_column() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+        let (board_id, column_ids) =
+            create_board_with_columns(&server, &session, "Board", &["To Do", "Done"]).await;
+
+        let card_id = server
+            .post("/api/cards")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"title": "From the inbox"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let card: Value = server
+            .post(&format!("/api/cards/{}/boards/{}", card_id, board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"column_id": column_ids[0]}))
+            .await
+            .json();
+        assert_eq!(card["column_id"], column_ids[0]);
+        assert_eq!(
+            board_cards(&server, &session, &board_id).await,
+            [vec![card_id.clone()], vec![]]
+        );
+
+        // Both move endpoints move the same placement
+        server
+            .put(&format!("/api/cards/{}/boards/{}", card_id, board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"column_id": column_ids[1], "position": 0}))
+            .await
+            .assert_status_ok();
+        assert_eq!(
+            board_cards(&server, &session, &board_id).await,
+            [vec![], vec![card_id.clone()]]
+        );
+        let card: Value = server
+            .get(&format!("/api/cards/{}", card_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(card["column_id"], column_ids[1]);
+
+        // Removing it from its home board makes it standalone again
+        server
+            .delete(&format!("/api/cards/{}/boards/{}", card_id, board_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .assert_status_ok();
+        assert_eq!(
+            board_cards(&server, &session, &board_id).await,
+            [Vec::<String>::new(), vec![]]
+        );
+        let card: Value = server
+            .get(&format!("/api/cards/{}", card_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert!(card["column_id"].is_null());
+    }
+}
+
+// ============================================================================
+// Security Header Tests
+// ============================================================================
+
+mod security_header_tests {
+    use super::*;
+    use personal_os::auth::SecurityHeaders;
+
+    #[tokio::test]
+    async fn test_responses_carry_security_headers() {
+        let server = setup_server().await;
+
+        for path in ["/login", "/api/boards", "/static/js/forms.js"] {
+            let response = server.get(path).await;
+            let csp = response.header("content-security-policy");
+            let csp = csp.to_str().unwrap();
+            assert!(csp.contains("frame-ancestors 'none'"), "{path}");
+            assert!(!csp.contains("script-src 'self' 'unsafe-inline'"), "{path}");
+            assert_eq!(response.header("x-frame-options"), "DENY");
+            assert_eq!(response.header("x-content-type-options"), "nosniff");
+            assert_eq!(response.header("referrer-policy"), "same-origin");
+            assert!(response.maybe_header("strict-transport-security").is_none());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hsts_when_configured() {
+        let mut state = test_utils::create_test_state().await;
+        state.security_headers = SecurityHeaders::with_hsts(86400);
+        let server = TestServer::new(create_router(state)).unwrap();
+
+        let response = server.get("/login").await;
+        assert_eq!(
+            response.header("strict-transport-security"),
+            "max-age=86400; includeSubDomains"
+        );
+    }
+}
+
+// ============================================================================
+// Invitation Tests
+// ============================================================================
+
+mod invitation_tests {
+    use super::*;
+
+    /// Token of the invitation link in the last email sent
+    fn invitation_token(state: &AppState) -> String {
+        let sent = state.email.sent();
+        let body = &sent.last().unwrap().body;
+        let start = body.find("/invitations/").unwrap() + "/invitations/".len();
+        body[start..].split_whitespace().next().unwrap().to_string()
+    }
+
+    async fn create_board(server: &TestServer, session: &str) -> String {
+        server
+            .post("/api/boards")
+            .add_cookie(session_cookie(session))
+            .json(&json!({"name": "Team"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string()
+    }
+
+    #[tokio::test]
+    async fn test_registering_accepts_invitation() {
+        let (server, state) = setup_server_with_state().await;
+        let owner_session = register_and_login(&server).await;
+        let board_id = create_board(&server, &owner_session).await;
+
+        let response = server
+            .post(&format!("/api/boards/{}/invitations", board_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"email": "New.Person@example.com", "role": "Editor"}))
+            .await;
+        response.assert_status_ok();
+        let invitation: Value = response.json();
+        assert_eq!(invitation["role"], "editor");
+        assert!(invitation.get("token_hash").is_none());
+        assert_eq!(state.email.sent()[0].to, "New.Person@example.com");
+
+        let pending: Value = server
+            .get(&format!("/api/boards/{}/invitations", board_id))
+            .add_cookie(session_cookie(&owner_session))
+            .await
+            .json();
+        assert_eq!(pending.as_array().unwrap().len(), 1);
+
+        // The address is matched case-insensitively
+        server
+            .post("/api/auth/register")
+            .json(&json!({
+                "email": "new.person@example.com",
+                "password": "testpassword123",
+                "name": "New Person"
+            }))
+            .await
+            .assert_status_ok();
+        let session = server
+            .post("/api/auth/login")
+            .json(&json!({"email": "new.person@example.com", "password": "testpassword123"}))
+            .await
+            .cookie("session")
+            .value()
+            .to_string();
+
+        server
+            .get(&format!("/api/boards/{}", board_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .assert_status_ok();
+        let pending: Value = server
+            .get(&format!("/api/boards/{}/invitations", board_id))
+            .add_cookie(session_cookie(&owner_session))
+            .await
+            .json();
+        assert!(pending.as_array().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_accept_and_revoke_invitation_links() {
+        let (server, state) = setup_server_with_state().await;
+        let owner_session = register_and_login(&server).await;
+        let board_id = create_board(&server, &owner_session).await;
+        let member_session = register_and_login(&server).await;
+
+        server
+            .post(&format!("/api/boards/{}/invitations", board_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"email": "someone@example.com", "role": "Reader"}))
+            .await
+            .assert_status_ok();
+        let token = invitation_token(&state);
+
+        // Only the owner manages invitations
+        server
+            .get(&format!("/api/boards/{}/invitations", board_id))
+            .add_cookie(session_cookie(&member_session))
+            .await
+            .assert_status_forbidden();
+
+        // Anonymous visitors are told how to join
+        let page = server.get(&format!("/invitations/{}", token)).await;
+        page.assert_status_ok();
+        assert!(page.text().contains("someone@example.com"));
+
+        let response = server
+            .post(&format!("/api/invitations/{}/accept", token))
+            .add_cookie(session_cookie(&member_session))
+            .await;
+        response.assert_status_ok();
+        assert_eq!(response.json::<Value>()["board_id"], board_id.as_str());
+        server
+            .get(&format!("/api/boards/{}", board_id))
+            .add_cookie(session_cookie(&member_session))
+            .await
+            .assert_status_ok();
+
+        // A link works once
+        server
+            .post(&format!("/api/invitations/{}/accept", token))
+            .add_cookie(session_cookie(&member_session))
+            .await
+            .assert_status_not_found();
+
+        let invitation: Value = server
+            .post(&format!("/api/boards/{}/invitations", board_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"email": "other@example.com", "role": "Reader"}))
+            .await
+            .json();
+        let token = invitation_token(&state);
+        server
+            .delete(&format!(
+                "/api/boards/{}/invitations/{}",
+                board_id,
+                invitation["id"].as_str().unwrap()
+            ))
+            .add_cookie(session_cookie(&owner_session))
+            .await
+            .assert_status_ok();
+        server
+            .post(&format!("/api/invitations/{}/accept", token))
+            .add_cookie(session_cookie(&member_session))
+            .await
+            .assert_status_not_found();
+    }
+}
+
+// ============================================================================
+// Import Tests
+// ============================================================================
+
+mod import_tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_import_trello_board() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+
+        let export = json!({
+            "name": "From Trello",
+            "desc": "Imported board",
+            "lists": [
+                {"id": "l1", "name": "Backlog", "pos": 1},
+                {"id": "l2", "name": "Done", "pos": 2}
+            ],
+            "labels": [{"id": "a", "name": "Bug", "color": "red"}],
+            "cards": [
+                {"id": "c1", "name": "Fix login", "idList": "l1", "pos": 1,
+                 "desc": "Steps to reproduce", "idLabels": ["a"],
+                 "due": "2024-06-01T09:00:00.000Z"},
+                {"id": "c2", "name": "Ship v1", "idList": "l2", "pos": 1, "dueComplete": true}
+            ],
+            "checklists": [
+                {"id": "k1", "idCard": "c1", "name": "Checks", "checkItems": [
+                    {"name": "Reproduce", "state": "complete", "pos": 1}
+                ]}
+            ],
+            "actions": [
+                {"type": "commentCard", "date": "2024-05-01T10:00:00.000Z",
+                 "data": {"text": "Seen on mobile", "card": {"id": "c1"}},
+                 "memberCreator": {"fullName": "Ada Lovelace"}}
+            ]
+        });
+
+        let response = server
+            .post("/api/import/trello")
+            .add_cookie(session_cookie(&session))
+            .json(&export)
+            .await;
+        response.assert_status_ok();
+        let result: Value = response.json();
+        assert_eq!(result["board"]["name"], "From Trello");
+        assert_eq!(result["board"]["role"], "owner");
+        assert_eq!(result["columns"], 2);
+        assert_eq!(result["cards"], 2);
+        assert_eq!(result["tags"], 1);
+        assert_eq!(result["comments"], 1);
+        let board_id = result["board"]["id"].as_str().unwrap();
+
+        let cards: Value = server
+            .get(&format!("/api/boards/{}/cards", board_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        let fix = cards
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|c| c["title"] == "Fix login")
+            .unwrap();
+        assert_eq!(
+            fix["body"],
+            "Steps to reproduce\n\n**Checks**\n- [x] Reproduce"
+        );
+        assert_eq!(fix["due_date"], "2024-06-01");
+        assert_eq!(fix["tags"][0]["name"], "Bug");
+
+        let full: Value = server
+            .get(&format!("/api/cards/{}/full", fix["id"].as_str().unwrap()))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(
+            full["comments"][0]["body"],
+            "Seen on mobile\n\n_Ada Lovelace on Trello, 2024-05-01_"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_import_trello_requires_board_name() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+
+        server
+            .post("/api/import/trello")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "  ", "lists": []}))
+            .await
+            .assert_status_unprocessable_entity();
+    }
+}
+
+// ============================================================================
+// Card Template Tests
+// ============================================================================
+
+mod card_template_tests {
+    use super::*;
+
+    async fn create_board_with_column(server: &TestServer, session: &str) -> (String, String) {
+        let board: Value = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(session))
+            .json(&json!({"name": "Bugs"}))
+            .await
+            .json();
+        let board_id = board["id"].as_str().unwrap().to_string();
+        let column: Value = server
+            .post(&format!("/api/boards/{}/columns", board_id))
+            .add_cookie(session_cookie(session))
+            .json(&json!({"name": "Triage"}))
+            .await
+            .json();
+        (board_id, column["id"].as_str().unwrap().to_string())
+    }
+
+    #[tokio::test]
+    async fn test_create_card_from_template() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+        let (board_id, column_id) = create_board_with_column(&server, &session).await;
+
+        let tag: Value = server
+            .post(&format!("/api/boards/{}/tags", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "bug"}))
+            .await
+            .json();
+
+        let response = server
+            .post(&format!("/api/boards/{}/card-templates", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({
+                "name": "Bug report",
+                "title_pattern": "Bug: {title}",
+                "body": "Steps to reproduce:",
+                "checklist": ["Reproduce", "Fix"],
+                "tag_ids": [tag["id"]]
+            }))
+            .await;
+        response.assert_status_ok();
+        let template: Value = response.json();
+        assert_eq!(template["checklist"], json!(["Reproduce", "Fix"]));
+        assert_eq!(template["tags"][0]["name"], "bug");
+
+        let response = server
+            .post(&format!(
+                "/api/boards/{}/cards/from-template/{}",
+                board_id,
+                template["id"].as_str().unwrap()
+            ))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"title": "Login fails"}))
+            .await;
+        response.assert_status_ok();
+        let card: Value = response.json();
+        assert_eq!(card["title"], "Bug: Login fails");
+        assert_eq!(card["column_id"], column_id.as_str());
+        assert_eq!(
+            card["body"],
+            "Steps to reproduce:\n\n- [ ] Reproduce\n- [ ] Fix"
+        );
+        assert_eq!(card["tags"][0]["id"], tag["id"]);
+
+        let templates: Value = server
+            .get(&format!("/api/boards/{}/card-templates", board_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(templates.as_array().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_template_belongs_to_board() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+        let (board_id, _) = create_board_with_column(&server, &session).await;
+        let (other_board_id, _) = create_board_with_column(&server, &session).await;
+
+        let other_tag: Value = server
+            .post(&format!("/api/boards/{}/tags", other_board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "elsewhere"}))
+            .await
+            .json();
+        server
+            .post(&format!("/api/boards/{}/card-templates", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({
+                "name": "Task",
+                "title_pattern": "{title}",
+                "tag_ids": [other_tag["id"]]
+            }))
+            .await
+            .assert_status_unprocessable_entity();
+
+        let template: Value = server
+            .post(&format!("/api/boards/{}/card-templates", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Task", "title_pattern": "{title}"}))
+            .await
+            .json();
+        let template_id = template["id"].as_str().unwrap();
+
+        server
+            .post(&format!(
+                "/api/boards/{}/cards/from-template/{}",
+                other_board_id, template_id
+            ))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"title": "Anything"}))
+            .await
+            .assert_status_not_found();
+
+        // The pattern only uses the title, so one is required
+        server
+            .post(&format!(
+                "/api/boards/{}/cards/from-template/{}",
+                board_id, template_id
+            ))
+            .add_cookie(session_cookie(&session))
+            .await
+            .assert_status_unprocessable_entity();
+    }
+}
+
+// ============================================================================
+// Saved View Tests
+// ============================================================================
+
+mod saved_view_tests {
+    use super::*;
+
+    async fn create_board(server: &TestServer, session: &str) -> (String, String) {
+        let board: Value = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(session))
+            .json(&json!({"name": "Work"}))
+            .await
+            .json();
+        let board_id = board["id"].as_str().unwrap().to_string();
+        let column: Value = server
+            .post(&format!("/api/boards/{}/columns", board_id))
+            .add_cookie(session_cookie(session))
+            .json(&json!({"name": "To Do"}))
+            .await
+            .json();
+        (board_id, column["id"].as_str().unwrap().to_string())
+    }
+
+    #[tokio::test]
+    async fn test_saved_views() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+        let (board_id, column_id) = create_board(&server, &session).await;
+
+        for title in ["Fix login bug", "Fix signup bug", "Write docs"] {
+            server
+                .post(&format!("/api/columns/{}/cards", column_id))
+                .add_cookie(session_cookie(&session))
+                .json(&json!({"title": title}))
+                .await
+                .assert_status_ok();
+        }
+        let cards: Value = server
+            .get(&format!("/api/boards/{}/cards", board_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        let signup_id = cards
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|c| c["title"] == "Fix signup bug")
+            .unwrap()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        server
+            .put(&format!("/api/cards/{}", signup_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"status": "Done"}))
+            .await
+            .assert_status_ok();
+
+        let response = server
+            .post(&format!("/api/boards/{}/views", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Open bugs", "filter": {"query": "bug", "status": "Open"}}))
+            .await;
+        response.assert_status_ok();
+        let view: Value = response.json();
+        assert_eq!(view["name"], "Open bugs");
+        assert_eq!(view["filter"]["query"], "bug");
+        assert_eq!(view["filter"]["status"], "Open");
+
+        // The card list takes the same filter
+        let open: Value = server
+            .get(&format!(
+                "/api/boards/{}/cards?status=Open&query=bug",
+                board_id
+            ))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(open.as_array().unwrap().len(), 1);
+        assert_eq!(open[0]["title"], "Fix login bug");
+
+        // The board page shows only the cards of the view
+        let view_id = view["id"].as_str().unwrap();
+        let page = server
+            .get(&format!("/boards/{}?view={}", board_id, view_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .text();
+        assert!(page.contains("Fix login bug"));
+        assert!(!page.contains("Fix signup bug"));
+        assert!(!page.contains("Write docs"));
+        let page = server
+            .get(&format!("/boards/{}", board_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .text();
+        assert!(page.contains("Write docs"));
+        assert!(page.contains("Open bugs"));
+
+        server
+            .post(&format!("/api/boards/{}/views", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Open bugs"}))
+            .await
+            .assert_status(axum::http::StatusCode::CONFLICT);
+        server
+            .post(&format!("/api/boards/{}/views", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "  "}))
+            .await
+            .assert_status(axum::http::StatusCode::UNPROCESSABLE_ENTITY);
+
+        let response = server
+            .put(&format!("/api/boards/{}/views/{}", board_id, view_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Bugs"}))
+            .await;
+        response.assert_status_ok();
+        let updated: Value = response.json();
+        assert_eq!(updated["name"], "Bugs");
+        assert_eq!(updated["filter"]["query"], "bug");
+
+        server
+            .delete(&format!("/api/boards/{}/views/{}", board_id, view_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .assert_status_ok();
+        let views: Value = server
+            .get(&format!("/api/boards/{}/views", board_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert!(views.as_array().unwrap().is_empty());
+        server
+            .get(&format!("/boards/{}?view={}", board_id, view_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .assert_status_not_found();
+    }
+
+    #[tokio::test]
+    async fn test_views_are_personal() {
+        let (server, state) = setup_server_with_state().await;
+        let owner_id = test_utils::create_test_user(&state, "owner@example.com", "Owner").await;
+        let owner_session = test_utils::create_test_session(&state, owner_id).await;
+        let reader_id = test_utils::create_test_user(&state, "reader@example.com", "Reader").await;
+        let reader_session = test_utils::create_test_session(&state, reader_id).await;
+        let (board_id, _) = create_board(&server, &owner_session).await;
+
+        let outsider = register_and_login(&server).await;
+        server
+            .post(&format!("/api/boards/{}/views", board_id))
+            .add_cookie(session_cookie(&outsider))
+            .json(&json!({"name": "Mine"}))
+            .await
+            .assert_status_forbidden();
+
+        server
+            .post(&format!("/api/boards/{}/permissions", board_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"user_id": reader_id, "role": "Reader"}))
+            .await
+            .assert_status_ok();
+        let view: Value = server
+            .post(&format!("/api/boards/{}/views", board_id))
+            .add_cookie(session_cookie(&reader_session))
+            .json(&json!({"name": "Mine"}))
+            .await
+            .json();
+        // The same name is free for another user
+        server
+            .post(&format!("/api/boards/{}/views", board_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"name": "Mine"}))
+            .await
+            .assert_status_ok();
+
+        let views: Value = server
+            .get(&format!("/api/boards/{}/views", board_id))
+            .add_cookie(session_cookie(&reader_session))
+            .await
+            .json();
+        assert_eq!(views.as_array().unwrap().len(), 1);
+        assert_eq!(views[0]["id"], view["id"]);
+
+        server
+            .delete(&format!(
+                "/api/boards/{}/views/{}",
+                board_id,
+                view["id"].as_str().unwrap()
+            ))
+            .add_cookie(session_cookie(&owner_session))
+            .await
+            .assert_status_not_found();
+    }
+}
+
+// ============================================================================
+// Dashboard Tests
+// ============================================================================
+
+mod dashboard_tests {
+    use super::*;
+
+    fn titles(cards: &Value) -> Vec<&str> {
+        cards
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|c| c["title"].as_str().unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_dashboard() {
+        let (server, state) = setup_server_with_state().await;
+        let user_id = test_utils::create_test_user(&state, "me@example.com", "Me").await;
+        let session = test_utils::create_test_session(&state, user_id).await;
+        let other_id = test_utils::create_test_user(&state, "other@example.com", "Other").await;
+        let other_session = test_utils::create_test_session(&state, other_id).await;
+
+        let mut column_ids = Vec::new();
+        for (session, name) in [(&session, "Work"), (&other_session, "Private")] {
+            let board: Value = server
+                .post("/api/boards")
+                .add_cookie(session_cookie(session))
+                .json(&json!({"name": name}))
+                .await
+                .json();
+            let column: Value = server
+                .post(&format!(
+                    "/api/boards/{}/columns",
+                    board["id"].as_str().unwrap()
+                ))
+                .add_cookie(session_cookie(session))
+                .json(&json!({"name": "To Do"}))
+                .await
+                .json();
+            column_ids.push(column["id"].as_str().unwrap().to_string());
+        }
+
+        let mut card_ids = Vec::new();
+        for (title, due_date) in [
+            ("Renew passport", "2024-03-04"),
+            ("File taxes", "2024-03-08"),
+            ("Plan trip", "2024-03-12"),
+            ("Pay rent", "2024-03-05"),
+        ] {
+            let card: Value = server
+                .post(&format!("/api/columns/{}/cards", column_ids[0]))
+                .add_cookie(session_cookie(&session))
+                .json(&json!({"title": title, "due_date": due_date}))
+                .await
+                .json();
+            card_ids.push(card["id"].as_str().unwrap().to_string());
+        }
+        server
+            .put(&format!("/api/cards/{}", card_ids[3]))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"status": "Done"}))
+            .await
+            .assert_status_ok();
+        server
+            .post(&format!("/api/cards/{}/assignees", card_ids[2]))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"user_id": user_id}))
+            .await
+            .assert_status_ok();
+        // Cards of boards the user cannot access stay out
+        server
+            .post(&format!("/api/columns/{}/cards", column_ids[1]))
+            .add_cookie(session_cookie(&other_session))
+            .json(&json!({"title": "Secret", "due_date": "2024-03-07"}))
+            .await
+            .assert_status_ok();
+
+        // Wednesday: the week runs to Sunday the 10th
+        let response = server
+            .get("/api/dashboard?today=2024-03-06")
+            .add_cookie(session_cookie(&session))
+            .await;
+        response.assert_status_ok();
+        let dashboard: Value = response.json();
+        assert_eq!(titles(&dashboard["overdue"]), ["Renew passport"]);
+        assert_eq!(titles(&dashboard["due_this_week"]), ["File taxes"]);
+        assert_eq!(titles(&dashboard["assigned"]), ["Plan trip"]);
+        assert_eq!(dashboard["assigned"][0]["board_name"], "Work");
+        let recent = titles(&dashboard["recently_updated"]);
+        assert_eq!(recent.len(), 4);
+        assert!(!recent.contains(&"Secret"));
+
+        let page = server
+            .get("/dashboard")
+            .add_cookie(session_cookie(&session))
+            .await
+            .text();
+        assert!(page.contains("My Work"));
+        assert!(page.contains("Plan trip"));
+        assert!(!page.contains("Secret"));
+    }
+}
+
+// ============================================================================
+// Board Template Tests
+// ============================================================================
+
+mod board_template_tests {
+    use super::*;
+
+    async fn board_layout(
+        server: &TestServer,
+        session: &str,
+        board_id: &str,
+    ) -> (Vec<String>, Vec<String>) {
+        let board: Value = server
+            .get(&format!("/api/boards/{}", board_id))
+            .add_cookie(session_cookie(session))
+            .await
+            .json();
+        let names = |key: &str| {
+            board[key]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|v| v["name"].as_str().unwrap().to_string())
+                .collect()
+        };
+        (names("columns"), names("tags"))
+    }
+
+    #[tokio::test]
+    async fn test_create_board_from_builtin_template() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+
+        let templates: Value = server
+            .get("/api/board-templates")
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(templates[0]["id"], "kanban");
+        assert_eq!(templates[0]["builtin"], true);
+
+        let board: Value = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Life", "template": "gtd"}))
+            .await
+            .json();
+        let (columns, tags) = board_layout(&server, &session, board["id"].as_str().unwrap()).await;
+        assert_eq!(
+            columns,
+            [
+                "Inbox",
+                "Next Actions",
+                "Waiting For",
+                "Someday/Maybe",
+                "Done"
+            ]
+        );
+        assert_eq!(tags.len(), 3);
+
+        server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Nope", "template": "scrumban"}))
+            .await
+            .assert_status_unprocessable_entity();
+        let boards: Value = server
+            .get("/api/boards")
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(boards.as_array().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_save_board_as_template() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+
+        let board: Value = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Client A", "template": "kanban"}))
+            .await
+            .json();
+        let board_id = board["id"].as_str().unwrap();
+        server
+            .post(&format!("/api/boards/{}/columns", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Invoiced"}))
+            .await
+            .assert_status_ok();
+
+        let response = server
+            .post(&format!("/api/boards/{}/save-as-template", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Client project"}))
+            .await;
+        response.assert_status_ok();
+        let template: Value = response.json();
+        assert_eq!(template["builtin"], false);
+        assert_eq!(template["columns"][3], "Invoiced");
+
+        let board: Value = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Client B", "template": template["id"]}))
+            .await
+            .json();
+        let (columns, tags) = board_layout(&server, &session, board["id"].as_str().unwrap()).await;
+        assert_eq!(columns, ["To Do", "In Progress", "Done", "Invoiced"]);
+        assert_eq!(tags, ["Bug", "Feature"]);
+
+        // Saved templates are private
+        let other = register_and_login(&server).await;
+        server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&other))
+            .json(&json!({"name": "Copy", "template": template["id"]}))
+            .await
+            .assert_status_unprocessable_entity();
+        server
+            .delete(&format!(
+                "/api/board-templates/{}",
+                template["id"].as_str().unwrap()
+            ))
+            .add_cookie(session_cookie(&other))
+            .await
+            .assert_status_not_found();
+
+        server
+            .delete(&format!(
+                "/api/board-templates/{}",
+                template["id"].as_str().unwrap()
+            ))
+            .add_cookie(session_cookie(&session))
+            .await
+            .assert_status_ok();
+    }
+}
+
+// ============================================================================
+// Archive Tests
+// ============================================================================
+
+mod archive_tests {
+    use super::*;
+
+    async fn create_card(
+        server: &TestServer,
+        session: &str,
+        column_id: &str,
+        title: &str,
+    ) -> String {
+        server
+            .post(&format!("/api/columns/{}/cards", column_id))
+            .add_cookie(session_cookie(session))
+            .json(&json!({"title": title}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string()
+    }
+
+    #[tokio::test]
+    async fn test_archive_and_restore_card() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+        let board: Value = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Archive", "template": "kanban"}))
+            .await
+            .json();
+        let board_id = board["id"].as_str().unwrap();
+        let board: Value = server
+            .get(&format!("/api/boards/{}", board_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        let column_id = board["columns"][0]["id"].as_str().unwrap();
+
+        let first = create_card(&server, &session, column_id, "First").await;
+        let second = create_card(&server, &session, column_id, "Second").await;
+
+        let response = server
+            .post(&format!("/api/cards/{}/archive", first))
+            .add_cookie(session_cookie(&session))
+            .await;
+        response.assert_status_ok();
+        assert!(response.json::<Value>()["archived_at"].is_string());
+
+        let board: Value = server
+            .get(&format!("/api/boards/{}", board_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        let cards = board["columns"][0]["cards"].as_array().unwrap();
+        assert_eq!(cards.len(), 1);
+        assert_eq!(cards[0]["id"], second.as_str());
+        assert_eq!(cards[0]["position"], 0);
+
+        let archived: Value = server
+            .get(&format!("/api/boards/{}/archived", board_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(archived["cards"][0]["id"], first.as_str());
+
+        // Restored cards go back at the end of their column
+        let card: Value = server
+            .post(&format!("/api/cards/{}/unarchive", first))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert!(card["archived_at"].is_null());
+        assert_eq!(card["position"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_archive_column_hides_its_cards() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+        let board: Value = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Archive", "template": "kanban"}))
+            .await
+            .json();
+        let board_id = board["id"].as_str().unwrap();
+        let board: Value = server
+            .get(&format!("/api/boards/{}", board_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        let done_id = board["columns"][2]["id"].as_str().unwrap();
+        create_card(&server, &session, done_id, "Shipped").await;
+
+        server
+            .post(&format!("/api/columns/{}/archive", done_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .assert_status_ok();
+
+        let board: Value = server
+            .get(&format!("/api/boards/{}", board_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(board["columns"].as_array().unwrap().len(), 2);
+        let cards: Value = server
+            .get(&format!("/api/boards/{}/cards", board_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert!(cards.as_array().unwrap().is_empty());
+
+        let archived: Value = server
+            .get(&format!("/api/boards/{}/archived", board_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(archived["columns"][0]["id"], done_id);
+        assert_eq!(archived["columns"][0]["cards"][0]["title"], "Shipped");
+
+        let column: Value = server
+            .post(&format!("/api/columns/{}/unarchive", done_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(column["position"], 2);
+    }
+}
+
+// ============================================================================
+// Trash Tests
+// ============================================================================
+
+mod trash_tests {
+    use super::*;
+    use personal_os::handlers::trash::purge_expired;
+
+    async fn create_board_with_card(server: &TestServer, session: &str) -> (String, String) {
+        let board: Value = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(session))
+            .json(&json!({"name": "Trash", "template": "kanban"}))
+            .await
+            .json();
+        let board_id = board["id"].as_str().unwrap().to_string();
+        let board: Value = server
+            .get(&format!("/api/boards/{}", board_id))
+            .add_cookie(session_cookie(session))
+            .await
+            .json();
+        let column_id = board["columns"][0]["id"].as_str().unwrap();
+        let card: Value = server
+            .post(&format!("/api/columns/{}/cards", column_id))
+            .add_cookie(session_cookie(session))
+            .json(&json!({"title": "Deleted by mistake"}))
+            .await
+            .json();
+        (board_id, card["id"].as_str().unwrap().to_string())
+    }
+
+    #[tokio::test]
+    async fn test_deleted_card_can_be_restored() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+        let (board_id, card_id) = create_board_with_card(&server, &session).await;
+
+        server
+            .delete(&format!("/api/cards/{}", card_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .assert_status_ok();
+        server
+            .get(&format!("/api/cards/{}", card_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .assert_status_not_found();
+
+        let trash: Value = server
+            .get("/api/trash")
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(trash[0]["kind"], "card");
+        assert_eq!(trash[0]["id"], card_id.as_str());
+        assert_eq!(trash[0]["board_id"], board_id.as_str());
+        assert!(trash[0]["purge_at"].is_string());
+
+        // Someone without access to the board cannot restore it
+        let other = register_and_login(&server).await;
+        server
+            .post(&format!("/api/trash/card/{}/restore", card_id))
+            .add_cookie(session_cookie(&other))
+            .await
+            .assert_status_forbidden();
+
+        server
+            .post(&format!("/api/trash/card/{}/restore", card_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .assert_status_ok();
+        let card: Value = server
+            .get(&format!("/api/cards/{}", card_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(card["position"], 0);
+
+        let trash: Value = server
+            .get("/api/trash")
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert!(trash.as_array().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_deleted_board_is_restored_or_purged() {
+        let (server, state) = setup_server_with_state().await;
+        let session = register_and_login(&server).await;
+        let (board_id, card_id) = create_board_with_card(&server, &session).await;
+
+        server
+            .delete(&format!("/api/boards/{}", board_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .assert_status_ok();
+        let boards: Value = server
+            .get("/api/boards")
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert!(boards.as_array().unwrap().is_empty());
+
+        server
+            .post(&format!("/api/trash/board/{}/restore", board_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .assert_status_ok();
+        server
+            .get(&format!("/api/cards/{}", card_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .assert_status_ok();
+
+        // Items are only purged once they have been in the trash long enough
+        server
+            .delete(&format!("/api/boards/{}", board_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .assert_status_ok();
+        assert_eq!(purge_expired(&state, 30).await.unwrap(), 0);
+        assert!(purge_expired(&state, 0).await.unwrap() > 0);
+        server
+            .post(&format!("/api/trash/board/{}/restore", board_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .assert_status_not_found();
+    }
+}
+
+// ============================================================================
+// Chat Undo Tests
+// ============================================================================
+
+mod chat_undo_tests {
+    use super::*;
+    use personal_os::services::OllamaClient;
+    use std::sync::{Arc, Mutex};
+
+    /// Serve the given chat replies in order, one per request, on a local port
+    pub(super) async fn start_fake_ollama(replies: &[&str]) -> String {
+        start_recording_ollama(replies).await.0
+    }
+
+    /// Like `start_fake_ollama`, also keeping the body of each request
+    pub(super) async fn start_recording_ollama(
+        replies: &[&str],
+    ) -> (String, Arc<Mutex<Vec<Value>>>) {
+        let replies: Arc<Mutex<Vec<String>>> = Arc::new(Mutex::new(
+            replies.iter().rev().map(|r| r.to_string()).collect(),
+        ));
+        let requests: Arc<Mutex<Vec<Value>>> = Arc::default();
+        let recorded = requests.clone();
+        let app = axum::Router::new().route(
+            "/api/chat",
+            axum::routing::post(move |axum::Json(body): axum::Json<Value>| async move {
+                recorded.lock().unwrap().push(body);
+                let content = replies.lock().unwrap().pop().unwrap_or_default();
+                axum::Json(json!({"message": {"role": "assistant", "content": content}}))
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (url, requests)
+    }
+
+    /// Server chatting with a fake Ollama, a logged-in user and their kanban board and
+    /// its first column
+    pub(super) async fn setup(replies: &[&str]) -> (TestServer, String, String, String) {
+        let mut state = test_utils::create_test_state().await;
+        state.llm = Arc::new(OllamaClient::new(
+            Some(start_fake_ollama(replies).await),
+            None,
+        ));
+        let server = TestServer::new(create_router(state)).unwrap();
+        let session = register_and_login(&server).await;
+
+        let board: Value = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Undo", "template": "kanban"}))
+            .await
+            .json();
+        let board_id = board["id"].as_str().unwrap().to_string();
+        let board: Value = server
+            .get(&format!("/api/boards/{}", board_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        let column_id = board["columns"][0]["id"].as_str().unwrap().to_string();
+        (server, session, board_id, column_id)
+    }
+
+    #[tokio::test]
+    async fn test_undo_deleted_card() {
+        let (server, session, board_id, column_id) = setup(&[
+            r#"{"action": "delete_card", "params": {"card": "Groceries"}, "message": "Deleted"}"#,
+        ])
+        .await;
+        let card: Value = server
+            .post(&format!("/api/columns/{}/cards", column_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"title": "Groceries"}))
+            .await
+            .json();
+        let card_id = card["id"].as_str().unwrap();
+
+        let response: Value = server
+            .post(&format!("/api/boards/{}/chat", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"message": "Delete the groceries card"}))
+            .await
+            .json();
+        assert_eq!(response["actions_taken"][0]["success"], true);
+        let action_id = response["actions_taken"][0]["id"].as_str().unwrap();
+        server
+            .get(&format!("/api/cards/{}", card_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .assert_status_not_found();
+
+        // Only the user who chatted can undo
+        let other = register_and_login(&server).await;
+        server
+            .post(&format!("/api/chat/actions/{}/undo", action_id))
+            .add_cookie(session_cookie(&other))
+            .await
+            .assert_status_not_found();
+
+        let undone: Value = server
+            .post(&format!("/api/chat/actions/{}/undo", action_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(undone["action"], "undo");
+        assert_eq!(undone["description"], "Undid 'Deleted card 'Groceries''");
+        server
+            .get(&format!("/api/cards/{}", card_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .assert_status_ok();
+
+        server
+            .post(&format!("/api/chat/actions/{}/undo", action_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .assert_status_bad_request();
+    }
+
+    #[tokio::test]
+    async fn test_undo_chat_action_reverses_last_action() {
+        let (server, session, board_id, _) = setup(&[
+            r#"{"action": "create_column", "params": {"name": "Blocked"}, "message": "Added"}"#,
+            r#"{"action": "undo", "params": {}, "message": "Undone"}"#,
+            r#"{"action": "undo", "params": {}, "message": "Undone"}"#,
+        ])
+        .await;
+        let chat_url = format!("/api/boards/{}/chat", board_id);
+
+        server
+            .post(&chat_url)
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"message": "Add a Blocked column"}))
+            .await
+            .assert_status_ok();
+        let response: Value = server
+            .post(&chat_url)
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"message": "Undo that"}))
+            .await
+            .json();
+        assert_eq!(response["actions_taken"][0]["action"], "undo");
+        assert_eq!(response["actions_taken"][0]["success"], true);
+
+        let columns: Value = server
+            .get(&format!("/api/boards/{}/columns", board_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert!(columns
+            .as_array()
+            .unwrap()
+            .iter()
+            .all(|c| c["name"] != "Blocked"));
+
+        // Each action is undone once
+        let response: Value = server
+            .post(&chat_url)
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"message": "Undo again"}))
+            .await
+            .json();
+        assert_eq!(response["actions_taken"][0]["success"], false);
+        assert_eq!(
+            response["actions_taken"][0]["description"],
+            "Nothing to undo"
+        );
+    }
+}
+
+// ============================================================================
+// Chat Confirmation Tests
+// ============================================================================
+
+mod chat_confirm_tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_confirm_proposed_actions() {
+        let (server, session, board_id, column_id) = chat_undo_tests::setup(&[
+            r#"{"action": "delete_card", "params": {"card": "Groceries"}, "message": "Deleting"}"#,
+        ])
+        .await;
+        let card: Value = server
+            .post(&format!("/api/columns/{}/cards", column_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"title": "Groceries"}))
+            .await
+            .json();
+        let card_id = card["id"].as_str().unwrap();
+
+        let response: Value = server
+            .post(&format!("/api/boards/{}/chat", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"message": "Delete the groceries card", "require_confirmation": true}))
+            .await
+            .json();
+        assert_eq!(response["actions_taken"], json!([]));
+        assert_eq!(response["pending"]["actions"][0]["action"], "delete_card");
+        let token = response["pending"]["confirmation_token"].as_str().unwrap();
+
+        // Nothing runs before confirmation
+        server
+            .get(&format!("/api/cards/{}", card_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .assert_status_ok();
+
+        // Only the user who chatted can confirm
+        let other = register_and_login(&server).await;
+        server
+            .post(&format!("/api/boards/{}/chat/confirm", board_id))
+            .add_cookie(session_cookie(&other))
+            .json(&json!({"confirmation_token": token}))
+            .await
+            .assert_status_forbidden();
+
+        let confirmed: Value = server
+            .post(&format!("/api/boards/{}/chat/confirm", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"confirmation_token": token}))
+            .await
+            .json();
+        assert_eq!(confirmed["actions_taken"][0]["success"], true);
+        assert!(confirmed.get("pending").is_none());
+        server
+            .get(&format!("/api/cards/{}", card_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .assert_status_not_found();
+
+        // The history shows what ran, and a token is single use
+        let history: Value = server
+            .get(&format!("/api/boards/{}/chat/history", board_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(history[0]["actions_taken"][0]["action"], "delete_card");
+        server
+            .post(&format!("/api/boards/{}/chat/confirm", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"confirmation_token": token}))
+            .await
+            .assert_status_not_found();
+    }
+
+    #[tokio::test]
+    async fn test_confirmation_setting() {
+        let (server, session, board_id, _) = chat_undo_tests::setup(&[
+            r#"{"action": "create_card", "params": {"column": "To Do", "title": "Milk"}, "message": "Adding"}"#,
+            r#"{"action": "create_card", "params": {"column": "To Do", "title": "Eggs"}, "message": "Adding"}"#,
+        ])
+        .await;
+
+        let settings: Value = server
+            .patch("/api/settings")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"chat_confirm_actions": true}))
+            .await
+            .json();
+        assert_eq!(settings["chat_confirm_actions"], true);
+
+        let response: Value = server
+            .post(&format!("/api/boards/{}/chat", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"message": "Add milk"}))
+            .await
+            .json();
+        assert!(response["pending"]["confirmation_token"].is_string());
+
+        // The request overrides the setting
+        let response: Value = server
+            .post(&format!("/api/boards/{}/chat", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"message": "Add eggs", "require_confirmation": false}))
+            .await
+            .json();
+        assert!(response.get("pending").is_none());
+        assert_eq!(response["actions_taken"][0]["success"], true);
+    }
+
+    #[tokio::test]
+    async fn test_accept_suggested_assignments() {
+        use personal_os::services::OllamaClient;
+        use std::sync::Arc;
+
+        let mut state = test_utils::create_test_state().await;
+        state.llm = Arc::new(OllamaClient::new(
+            Some(
+                chat_undo_tests::start_fake_ollama(&[
+                    r#"{"action": "suggest_assignments", "params": {}, "message": "Here is a plan"}"#,
+                ])
+                .await,
+            ),
+            None,
+        ));
+        let server = TestServer::new(create_router(state.clone())).unwrap();
+        let owner_id = test_utils::create_test_user(&state, "owner@example.com", "Ann").await;
+        let session = test_utils::create_test_session(&state, owner_id).await;
+        let member_id = test_utils::create_test_user(&state, "member@example.com", "Bob").await;
+        let reader_id = test_utils::create_test_user(&state, "reader@example.com", "Cy").await;
+
+        let board: Value = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Team", "template": "kanban"}))
+            .await
+            .json();
+        let board_id = board["id"].as_str().unwrap();
+        for (user_id, role) in [(member_id, "Editor"), (reader_id, "Reader")] {
+            server
+                .post(&format!("/api/boards/{}/permissions", board_id))
+                .add_cookie(session_cookie(&session))
+                .json(&json!({"user_id": user_id, "role": role}))
+                .await
+                .assert_status_ok();
+        }
+        let board: Value = server
+            .get(&format!("/api/boards/{}", board_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        let column_id = board["columns"][0]["id"].as_str().unwrap();
+
+        let mut card_ids = Vec::new();
+        for (title, priority) in [("Taxes", "urgent"), ("Dishes", "low"), ("Done", "medium")] {
+            let card: Value = server
+                .post(&format!("/api/columns/{}/cards", column_id))
+                .add_cookie(session_cookie(&session))
+                .json(&json!({"title": title, "priority": priority}))
+                .await
+                .json();
+            card_ids.push(card["id"].as_str().unwrap().to_string());
+        }
+        server
+            .patch(&format!("/api/cards/{}", card_ids[2]))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"status": "Done"}))
+            .await
+            .assert_status_ok();
+
+        // Ann already has an open card, so Bob gets the most urgent one and, on a tie,
+        // the next
+        let busy: Value = server
+            .post(&format!("/api/columns/{}/cards", column_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"title": "Busy"}))
+            .await
+            .json();
+        server
+            .post(&format!(
+                "/api/cards/{}/assignees",
+                busy["id"].as_str().unwrap()
+            ))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"user_id": owner_id}))
+            .await
+            .assert_status_ok();
+
+        let response: Value = server
+            .post(&format!("/api/boards/{}/chat", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"message": "Who should work on what?"}))
+            .await
+            .json();
+        assert_eq!(
+            response["actions_taken"][0]["action"],
+            "suggest_assignments"
+        );
+        let plan = response["actions_taken"][0]["description"]
+            .as_str()
+            .unwrap();
+        assert!(plan.contains("Bob (0 open), Ann (1 open)"), "{}", plan);
+        assert!(plan.contains("'Taxes' to Bob"), "{}", plan);
+        assert!(plan.contains("'Dishes' to Bob"), "{}", plan);
+        assert!(!plan.contains("Cy"), "{}", plan);
+        let pending = &response["pending"];
+        assert_eq!(pending["actions"][0]["action"], "assign_users");
+        assert_eq!(
+            pending["actions"][0]["params"]["assignments"]
+                .as_array()
+                .unwrap()
+                .len(),
+            2
+        );
+
+        // Nothing is assigned before the plan is accepted
+        let assignees: Value = server
+            .get(&format!("/api/cards/{}/assignees", card_ids[0]))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(assignees, json!([]));
+
+        let confirmed: Value = server
+            .post(&format!("/api/boards/{}/chat/confirm", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"confirmation_token": pending["confirmation_token"]}))
+            .await
+            .json();
+        assert_eq!(confirmed["actions_taken"][0]["action"], "assign_users");
+        assert_eq!(confirmed["actions_taken"][0]["success"], true);
+        for (card_id, name) in [(&card_ids[0], "Bob"), (&card_ids[1], "Bob")] {
+            let assignees: Value = server
+                .get(&format!("/api/cards/{}/assignees", card_id))
+                .add_cookie(session_cookie(&session))
+                .await
+                .json();
+            assert_eq!(assignees[0]["name"], name);
+        }
+
+        // The bulk assignment is undone in one step
+        let action_id = confirmed["actions_taken"][0]["id"].as_str().unwrap();
+        server
+            .post(&format!("/api/chat/actions/{}/undo", action_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .assert_status_ok();
+        let assignees: Value = server
+            .get(&format!("/api/cards/{}/assignees", card_ids[0]))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(assignees, json!([]));
+    }
+}
+
+// ============================================================================
+// Chat Context Tests
+// ============================================================================
+
+mod chat_context_tests {
+    use super::*;
+    use personal_os::services::OllamaClient;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_board_chat_sends_previous_exchanges() {
+        let (url, requests) = chat_undo_tests::start_recording_ollama(&[
+            r#"{"action": "create_card", "params": {"column": "To Do", "title": "Milk"}, "message": "Added milk"}"#,
+            r#"{"action": "no_action", "params": {}, "message": "Sure"}"#,
+            r#"{"action": "no_action", "params": {}, "message": "Hi"}"#,
+        ])
+        .await;
+        let mut state = test_utils::create_test_state().await;
+        state.llm = Arc::new(OllamaClient::new(Some(url), None));
+        let owner_id = test_utils::create_test_user(&state, "owner@example.com", "Owner").await;
+        let session = test_utils::create_test_session(&state, owner_id).await;
+        let member_id = test_utils::create_test_user(&state, "member@example.com", "Member").await;
+        let member_session = test_utils::create_test_session(&state, member_id).await;
+        let server = TestServer::new(create_router(state)).unwrap();
+
+        let board: Value = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Context", "template": "kanban"}))
+            .await
+            .json();
+        let board_id = board["id"].as_str().unwrap();
+        let chat_url = format!("/api/boards/{}/chat", board_id);
+
+        for message in ["Add milk", "Now move it to Done"] {
+            server
+                .post(&chat_url)
+                .add_cookie(session_cookie(&session))
+                .json(&json!({"message": message}))
+                .await
+                .assert_status_ok();
+        }
+
+        let follow_up = requests.lock().unwrap()[1]["messages"].clone();
+        let turns: Vec<(&str, &str)> = follow_up
+            .as_array()
+            .unwrap()
+            .iter()
+            .skip(1)
+            .map(|m| (m["role"].as_str().unwrap(), m["content"].as_str().unwrap()))
+            .collect();
+        assert_eq!(turns.len(), 3);
+        assert_eq!(turns[0], ("user", "Add milk"));
+        assert_eq!(turns[1].0, "assistant");
+        assert!(turns[1].1.contains("Created card 'Milk'"));
+        assert_eq!(turns[2], ("user", "Now move it to Done"));
+
+        // Another member's conversation on the board is not included
+        server
+            .post(&format!("/api/boards/{}/permissions", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"user_id": member_id, "role": "Editor"}))
+            .await
+            .assert_status_ok();
+        server
+            .post(&chat_url)
+            .add_cookie(session_cookie(&member_session))
+            .json(&json!({"message": "Hello"}))
+            .await
+            .assert_status_ok();
+        let messages = requests.lock().unwrap()[2]["messages"].clone();
+        assert_eq!(messages.as_array().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_global_prompt_summaries_follow_board_changes() {
+        let reply = r#"{"action": "no_action", "params": {}, "message": "Ok"}"#;
+        let (url, requests) = chat_undo_tests::start_recording_ollama(&[reply, reply, reply]).await;
+        let mut state = test_utils::create_test_state().await;
+        state.llm = Arc::new(OllamaClient::new(Some(url), None));
+        let owner_id = test_utils::create_test_user(&state, "owner@example.com", "Owner").await;
+        let session = test_utils::create_test_session(&state, owner_id).await;
+        let server = TestServer::new(create_router(state)).unwrap();
+
+        let board: Value = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Groceries"}))
+            .await
+            .json();
+        let board_id = board["id"].as_str().unwrap();
+        let column: Value = server
+            .post(&format!("/api/boards/{}/columns", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "To Buy"}))
+            .await
+            .json();
+        let cards_url = format!("/api/columns/{}/cards", column["id"].as_str().unwrap());
+        server
+            .post(&cards_url)
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"title": "Milk"}))
+            .await
+            .assert_status_ok();
+
+        let system_prompt = |index: usize| {
+            requests.lock().unwrap()[index]["messages"][0]["content"]
+                .as_str()
+                .unwrap()
+                .to_string()
+        };
+        let send = || {
+            server
+                .post("/api/chat")
+                .add_cookie(session_cookie(&session))
+                .json(&json!({"message": "What's on my boards?"}))
+        };
+
+        send().await.assert_status_ok();
+        assert!(system_prompt(0)
+            .contains("- Groceries (role: owner, 1 columns: [To Buy], 1 cards, tags: [none])"));
+
+        // Unchanged boards are summarized the same way from the cache
+        send().await.assert_status_ok();
+        assert!(system_prompt(1).contains("1 columns: [To Buy], 1 cards, tags: [none]"));
+
+        server
+            .post(&cards_url)
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"title": "Eggs"}))
+            .await
+            .assert_status_ok();
+        server
+            .post(&format!("/api/boards/{}/tags", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "weekly"}))
+            .await
+            .assert_status_ok();
+        send().await.assert_status_ok();
+        assert!(system_prompt(2).contains("1 columns: [To Buy], 2 cards, tags: [weekly]"));
+    }
+}
+
+// ============================================================================
+// LLM Provider Tests
+// ============================================================================
+
+mod llm_provider_tests {
+    use super::*;
+    use personal_os::services::{LlmProvider, OpenAiClient, ProviderKind};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_board_chat_with_openai_compatible_api() {
+        let app = axum::Router::new().route(
+            "/v1/chat/completions",
+            axum::routing::post(
+                |headers: axum::http::HeaderMap, axum::Json(body): axum::Json<Value>| async move {
+                    assert_eq!(headers["authorization"], "Bearer secret");
+                    assert_eq!(body["model"], "gateway-model");
+                    assert_eq!(body["messages"][0]["role"], "system");
+                    let content = r#"{"action": "create_column", "params": {"name": "Doing"}, "message": "Added"}"#;
+                    axum::Json(json!({
+                        "choices": [{"index": 0, "message": {"role": "assistant", "content": content}}]
+                    }))
+                },
+            ),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/v1/", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let mut state = test_utils::create_test_state().await;
+        state.llm = Arc::new(OpenAiClient::new(
+            url,
+            Some("secret".to_string()),
+            "gateway-model".to_string(),
+        ));
+        let server = TestServer::new(create_router(state)).unwrap();
+        let session = register_and_login(&server).await;
+        let board: Value = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Gateway"}))
+            .await
+            .json();
+
+        let response: Value = server
+            .post(&format!(
+                "/api/boards/{}/chat",
+                board["id"].as_str().unwrap()
+            ))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"message": "Add a Doing column"}))
+            .await
+            .json();
+        assert_eq!(response["actions_taken"][0]["action"], "create_column");
+        assert_eq!(response["actions_taken"][0]["success"], true);
+    }
+
+    #[tokio::test]
+    async fn test_board_chat_uses_user_model_and_temperature() {
+        let app = axum::Router::new().route(
+            "/chat/completions",
+            axum::routing::post(|axum::Json(body): axum::Json<Value>| async move {
+                let content = format!("{} at {}", body["model"], body["temperature"]);
+                axum::Json(json!({
+                    "choices": [{"index": 0, "message": {"role": "assistant", "content": content}}]
+                }))
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let mut state = test_utils::create_test_state().await;
+        let gateway: Arc<dyn LlmProvider> =
+            Arc::new(OpenAiClient::new(url, None, "default-model".to_string()));
+        state.llm_providers.insert(ProviderKind::OpenAi, gateway);
+        let server = TestServer::new(create_router(state)).unwrap();
+        let session = register_and_login(&server).await;
+
+        // Only configured providers and temperatures from 0 to 2 are accepted
+        server
+            .patch("/api/settings")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"llm_provider": "anthropic"}))
+            .await
+            .assert_status_bad_request();
+        server
+            .patch("/api/settings")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"llm_temperature": 2.5}))
+            .await
+            .assert_status_bad_request();
+
+        let settings: Value = server
+            .patch("/api/settings")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"llm_provider": "OpenAI", "llm_model": " small-model ", "llm_temperature": 0.5}))
+            .await
+            .json();
+        assert_eq!(settings["llm_provider"], "openai");
+        assert_eq!(settings["llm_model"], "small-model");
+        assert_eq!(settings["llm_temperature"], 0.5);
+
+        let board: Value = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Models"}))
+            .await
+            .json();
+        let chat_url = format!("/api/boards/{}/chat", board["id"].as_str().unwrap());
+        let response: Value = server
+            .post(&chat_url)
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"message": "Hi"}))
+            .await
+            .json();
+        assert_eq!(response["response"], "\"small-model\" at 0.5");
+
+        // Clearing the model falls back to the provider's
+        server
+            .patch("/api/settings")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"llm_model": null, "llm_temperature": null}))
+            .await
+            .assert_status_ok();
+        let response: Value = server
+            .post(&chat_url)
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"message": "Hi"}))
+            .await
+            .json();
+        assert_eq!(response["response"], "\"default-model\" at null");
+    }
+}
+
+// ============================================================================
+// Chat Tool Calling Tests
+// ============================================================================
+
+mod chat_tool_tests {
+    use super::*;
+    use personal_os::services::{OllamaClient, WebSearchClient};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_board_chat_executes_tool_calls() {
+        let app = axum::Router::new().route(
+            "/api/chat",
+            axum::routing::post(|axum::Json(body): axum::Json<Value>| async move {
+                let tools: Vec<&str> = body["tools"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|t| t["function"]["name"].as_str().unwrap())
+                    .collect();
+                assert!(tools.contains(&"create_tag"));
+                assert!(!tools.contains(&"no_action"));
+                let create_tag = body["tools"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .find(|t| t["function"]["name"] == "create_tag")
+                    .unwrap();
+                // Board chat acts on its own board
+                assert!(create_tag["function"]["parameters"]["properties"]["board"].is_null());
+
+                axum::Json(json!({"message": {
+                    "role": "assistant",
+                    "content": "",
+                    "tool_calls": [{"function": {
+                        "name": "create_tag",
+                        "arguments": {"name": "urgent", "color": "#ff0000", "message": "Tagged it"}
+                    }}]
+                }}))
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let mut state = test_utils::create_test_state().await;
+        state.llm = Arc::new(OllamaClient::new(Some(url), None));
+        let server = TestServer::new(create_router(state)).unwrap();
+        let session = register_and_login(&server).await;
+        let board: Value = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Tools"}))
+            .await
+            .json();
+        let board_id = board["id"].as_str().unwrap();
+
+        let response: Value = server
+            .post(&format!("/api/boards/{}/chat", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"message": "Create an urgent tag"}))
+            .await
+            .json();
+        assert_eq!(response["response"], "Tagged it");
+        assert_eq!(response["actions_taken"][0]["action"], "create_tag");
+        assert_eq!(response["actions_taken"][0]["success"], true);
+
+        let tags: Value = server
+            .get(&format!("/api/boards/{}/tags", board_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(tags[0]["name"], "urgent");
+    }
+
+    #[tokio::test]
+    async fn test_board_chat_creates_column_in_place() {
+        let (server, session, board_id, _) = chat_undo_tests::setup(&[
+            r#"{"action": "create_column", "params": {"name": "Blocked", "after": "in progress"}, "message": "Added"}"#,
+            r#"{"action": "create_column", "params": {"name": "Ideas", "before": "Someday"}, "message": "Added"}"#,
+            r#"{"action": "create_column", "params": {"name": "Review"}, "message": "Added"}"#,
+        ])
+        .await;
+        let chat_url = format!("/api/boards/{}/chat", board_id);
+
+        let response: Value = server
+            .post(&chat_url)
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"message": "Add a Blocked column after In Progress"}))
+            .await
+            .json();
+        assert_eq!(
+            response["actions_taken"][0]["description"],
+            "Created column 'Blocked' after 'in progress'"
+        );
+        let board: Value = server
+            .get(&format!("/api/boards/{}", board_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        let names: Vec<&str> = board["columns"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|c| c["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, ["To Do", "In Progress", "Blocked", "Done"]);
+
+        let response: Value = server
+            .post(&chat_url)
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"message": "Add Ideas before Someday"}))
+            .await
+            .json();
+        assert_eq!(response["actions_taken"][0]["success"], false);
+        assert_eq!(
+            response["actions_taken"][0]["description"],
+            "Column 'Someday' not found"
+        );
+
+        // Readers are told they cannot change the board
+        let reader: Value = server
+            .post("/api/auth/register")
+            .json(&json!({
+                "email": "reader@example.com",
+                "password": "testpassword123",
+                "name": "Reader"
+            }))
+            .await
+            .json();
+        let reader_session = server
+            .post("/api/auth/login")
+            .json(&json!({"email": "reader@example.com", "password": "testpassword123"}))
+            .await
+            .cookie("session")
+            .value()
+            .to_string();
+        server
+            .post(&format!("/api/boards/{}/permissions", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"user_id": reader["user"]["id"], "role": "Reader"}))
+            .await
+            .assert_status_ok();
+        let response: Value = server
+            .post(&chat_url)
+            .add_cookie(session_cookie(&reader_session))
+            .json(&json!({"message": "Add a Review column"}))
+            .await
+            .json();
+        assert_eq!(response["actions_taken"][0]["success"], false);
+        assert_eq!(
+            response["actions_taken"][0]["description"],
+            "You don't have permission to edit this board"
+        );
+        let board: Value = server
+            .get(&format!("/api/boards/{}", board_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(board["columns"].as_array().unwrap().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_chat_comments_on_board_cards() {
+        let (server, session, board_id, column_id) = chat_undo_tests::setup(&[
+            r#"{"action": "add_comment", "params": {"card": "deploy", "comment": "Staging is broken"}, "message": "Noted"}"#,
+            r#"{"action": "add_comment", "params": {"board": "Undo", "card": "Deploy", "comment": "Fixed now"}, "message": "Noted"}"#,
+        ])
+        .await;
+        let card: Value = server
+            .post(&format!("/api/columns/{}/cards", column_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"title": "Deploy"}))
+            .await
+            .json();
+        let card_id = card["id"].as_str().unwrap();
+
+        let response: Value = server
+            .post(&format!("/api/boards/{}/chat", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"message": "Comment on 'Deploy' that staging is broken"}))
+            .await
+            .json();
+        let action = &response["actions_taken"][0];
+        assert_eq!(action["description"], "Added comment to 'Deploy'");
+        assert_eq!(action["comment"]["card_id"], card_id);
+        assert_eq!(action["comment"]["body"], "Staging is broken");
+
+        let response: Value = server
+            .post("/api/chat")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"message": "Tell Deploy on the Undo board it is fixed"}))
+            .await
+            .json();
+        assert_eq!(response["actions_taken"][0]["success"], true);
+
+        let comments: Value = server
+            .get(&format!("/api/cards/{}/comments", card_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        let bodies: Vec<&str> = comments
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|c| c["body"].as_str().unwrap())
+            .collect();
+        assert_eq!(bodies, ["Staging is broken", "Fixed now"]);
+        assert_eq!(comments[0]["author_name"], "Test User");
+    }
+
+    #[tokio::test]
+    async fn test_global_chat_files_inbox_card_on_board() {
+        let (server, session, _, _) = chat_undo_tests::setup(&[
+            r#"{"action": "create_inbox_card", "params": {"title": "Renew the domain", "due_date": "2026-11-01"}, "message": "I'll remind you"}"#,
+            r#"{"action": "assign_card", "params": {"card": "domain", "board": "Undo", "column": "Todo"}, "message": "Filed"}"#,
+            r#"{"action": "assign_card", "params": {"card": "Renew the domain", "board": "Undo", "column": "Someday"}, "message": "Filed"}"#,
+        ])
+        .await;
+
+        let response: Value = server
+            .post("/api/chat")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"message": "Remind me to renew the domain by November 1st"}))
+            .await
+            .json();
+        assert_eq!(
+            response["actions_taken"][0]["description"],
+            "Created inbox card 'Renew the domain', due 2026-11-01"
+        );
+        let cards: Value = server
+            .get("/api/cards")
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(cards[0]["due_date"], "2026-11-01");
+
+        let response: Value = server
+            .post("/api/chat")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"message": "Put the domain card on the Undo board in Todo"}))
+            .await
+            .json();
+        assert_eq!(
+            response["actions_taken"][0]["description"],
+            "Assigned 'Renew the domain' to board 'Undo' in column 'To Do'"
+        );
+
+        let response: Value = server
+            .post("/api/chat")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"message": "Put it in Someday"}))
+            .await
+            .json();
+        assert_eq!(response["actions_taken"][0]["success"], false);
+        assert_eq!(
+            response["actions_taken"][0]["description"],
+            "Column 'Someday' not found on board 'Undo'. Available columns: To Do, In Progress, Done"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_board_chat_updates_card_fields() {
+        let (server, session, board_id, column_id) = chat_undo_tests::setup(&[
+            r#"{"action": "update_card", "params": {"card": "ship v2", "due_date": "2026-10-23", "body": "Release notes first"}, "message": "Due Friday"}
+{"action": "update_status", "params": {"card": "Fix bug", "status": "done"}, "message": "Done"}"#,
+            r#"{"action": "update_card", "params": {"card": "Ship v2", "due_date": "next friday"}, "message": "Due"}"#,
+        ])
+        .await;
+        let ship: Value = server
+            .post(&format!("/api/columns/{}/cards", column_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"title": "Ship v2"}))
+            .await
+            .json();
+        let fix: Value = server
+            .post(&format!("/api/columns/{}/cards", column_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"title": "Fix bug"}))
+            .await
+            .json();
+
+        let response: Value = server
+            .post(&format!("/api/boards/{}/chat", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"message": "Ship v2 is due Friday, and Fix bug is done"}))
+            .await
+            .json();
+        let actions = response["actions_taken"].as_array().unwrap();
+        assert_eq!(actions[0]["action"], "update_card");
+        assert_eq!(
+            actions[0]["description"],
+            "Updated 'Ship v2': description, due date to 2026-10-23"
+        );
+        assert_eq!(actions[1]["action"], "update_status");
+        assert_eq!(actions[1]["success"], true);
+
+        let card: Value = server
+            .get(&format!("/api/cards/{}", ship["id"].as_str().unwrap()))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(card["due_date"], "2026-10-23");
+        assert_eq!(card["body"], "Release notes first");
+        let card: Value = server
+            .get(&format!("/api/cards/{}", fix["id"].as_str().unwrap()))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(card["status"], "done");
+
+        // Undo puts the previous fields back
+        server
+            .post(&format!(
+                "/api/chat/actions/{}/undo",
+                actions[0]["id"].as_str().unwrap()
+            ))
+            .add_cookie(session_cookie(&session))
+            .await
+            .assert_status_ok();
+        let card: Value = server
+            .get(&format!("/api/cards/{}", ship["id"].as_str().unwrap()))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert!(card["due_date"].is_null());
+        assert!(card["body"].is_null());
+
+        let response: Value = server
+            .post(&format!("/api/boards/{}/chat", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"message": "Ship v2 is due next friday"}))
+            .await
+            .json();
+        assert_eq!(response["actions_taken"][0]["success"], false);
+        assert_eq!(
+            response["actions_taken"][0]["description"],
+            "Invalid due_date 'next friday'. Use YYYY-MM-DD"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_board_chat_bulk_moves_tagged_cards() {
+        let (server, session, board_id, column_id) = chat_undo_tests::setup(&[
+            r#"{"action": "bulk_update_cards", "params": {"operation": "move", "tag": "done", "target_column": "Done"}, "message": "Moved"}"#,
+            r#"{"action": "bulk_update_cards", "params": {"operation": "status", "column": "done", "status": "closed"}, "message": "Closed"}"#,
+        ])
+        .await;
+        let tag: Value = server
+            .post(&format!("/api/boards/{}/tags", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "done", "color": "#198754"}))
+            .await
+            .json();
+        let mut card_ids = Vec::new();
+        for title in ["Write docs", "Fix bug", "Frozen", "Untagged"] {
+            let card: Value = server
+                .post(&format!("/api/columns/{}/cards", column_id))
+                .add_cookie(session_cookie(&session))
+                .json(&json!({"title": title}))
+                .await
+                .json();
+            let card_id = card["id"].as_str().unwrap().to_string();
+            if title != "Untagged" {
+                server
+                    .post(&format!(
+                        "/api/cards/{}/tags/{}",
+                        card_id,
+                        tag["id"].as_str().unwrap()
+                    ))
+                    .add_cookie(session_cookie(&session))
+                    .await
+                    .assert_status_ok();
+            }
+            card_ids.push(card_id);
+        }
+        server
+            .post(&format!("/api/cards/{}/lock", card_ids[2]))
+            .add_cookie(session_cookie(&session))
+            .await
+            .assert_status_ok();
+
+        let response: Value = server
+            .post(&format!("/api/boards/{}/chat", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"message": "Move all cards tagged 'done' to the Done column"}))
+            .await
+            .json();
+        let action = &response["actions_taken"][0];
+        assert_eq!(action["action"], "bulk_update_cards");
+        assert_eq!(
+            action["description"],
+            "Moved 3 cards tagged 'done' to 'Done'"
+        );
+
+        let column_of = |card_id: String| {
+            let server = &server;
+            let session = &session;
+            async move {
+                server
+                    .get(&format!("/api/cards/{}", card_id))
+                    .add_cookie(session_cookie(session))
+                    .await
+                    .json::<Value>()["column_id"]
+                    .clone()
+            }
+        };
+        let done_column = column_of(card_ids[0].clone()).await;
+        assert_ne!(done_column, column_id.as_str());
+        assert_eq!(column_of(card_ids[1].clone()).await, done_column);
+        assert_eq!(column_of(card_ids[2].clone()).await, done_column);
+        assert_eq!(column_of(card_ids[3].clone()).await, column_id.as_str());
+
+        // Locked cards keep their status
+        let response: Value = server
+            .post(&format!("/api/boards/{}/chat", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"message": "Close everything in Done"}))
+            .await
+            .json();
+        assert_eq!(
+            response["actions_taken"][0]["description"],
+            "Set 2 cards in 'Done' to closed (skipped 1 locked)"
+        );
+
+        // One undo puts every moved card back
+        server
+            .post(&format!(
+                "/api/chat/actions/{}/undo",
+                action["id"].as_str().unwrap()
+            ))
+            .add_cookie(session_cookie(&session))
+            .await
+            .assert_status_ok();
+        for card_id in &card_ids[..3] {
+            assert_eq!(column_of(card_id.clone()).await, column_id.as_str());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_board_chat_rolls_back_failed_requests() {
+        let (server, session, board_id, _column_id) = chat_undo_tests::setup(&[
+            r#"{"action": "create_column", "params": {"name": "Blocked"}, "message": "Created"}
+{"action": "create_card", "params": {"column": "Blocked", "title": "Waiting on legal"}, "message": "Created"}
+{"action": "move_card", "params": {"card_title": "Waiting on legal", "target_column": "Nowhere"}, "message": "Moved"}
+{"action": "create_tag", "params": {"name": "legal"}, "message": "Created"}"#,
+        ])
+        .await;
+
+        let response: Value = server
+            .post(&format!("/api/boards/{}/chat", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"message": "Track the legal review in a Blocked column"}))
+            .await
+            .json();
+        let actions = response["actions_taken"].as_array().unwrap();
+        assert_eq!(actions.len(), 4);
+        assert_eq!(
+            actions[0]["description"],
+            "Rolled back: Created column 'Blocked'"
+        );
+        assert_eq!(actions[0]["rolled_back"], true);
+        assert_eq!(actions[0]["success"], false);
+        assert!(actions[0]["id"].is_null());
+        assert_eq!(actions[1]["rolled_back"], true);
+        assert_eq!(actions[2]["description"], "Column 'Nowhere' not found");
+        assert!(actions[2].get("rolled_back").is_none());
+        assert_eq!(actions[3]["action"], "create_tag");
+        assert_eq!(
+            actions[3]["description"],
+            "Skipped because an earlier action failed"
+        );
+
+        // The board is left as it was
+        let columns: Value = server
+            .get(&format!("/api/boards/{}/columns", board_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert!(columns
+            .as_array()
+            .unwrap()
+            .iter()
+            .all(|c| c["name"] != "Blocked"));
+        let tags: Value = server
+            .get(&format!("/api/boards/{}/tags", board_id))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert!(tags
+            .as_array()
+            .unwrap()
+            .iter()
+            .all(|t| t["name"] != "legal"));
+    }
+
+    #[tokio::test]
+    async fn test_board_chat_rolls_back_in_one_transaction() {
+        let mut state = test_utils::create_test_state().await;
+        state.llm = Arc::new(OllamaClient::new(
+            Some(
+                chat_undo_tests::start_fake_ollama(&[
+                    r#"{"action": "create_card", "params": {"column": "To Do", "title": "Draft contract"}, "message": "Created"}
+{"action": "move_card", "params": {"card_title": "Draft contract", "target_column": "Nowhere"}, "message": "Moved"}
+{"action": "create_tag", "params": {"name": "legal"}, "message": "Created"}"#,
+                ])
+                .await,
+            ),
+            None,
+        ));
+        let server = TestServer::new(create_router(state.clone())).unwrap();
+        let session = register_and_login(&server).await;
+        let board: Value = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Legal", "template": "kanban"}))
+            .await
+            .json();
+
+        let response: Value = server
+            .post(&format!(
+                "/api/boards/{}/chat",
+                board["id"].as_str().unwrap()
+            ))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"message": "Draft the contract"}))
+            .await
+            .json();
+        let actions = response["actions_taken"].as_array().unwrap();
+        assert_eq!(actions.len(), 3);
+        assert_eq!(actions[0]["rolled_back"], true);
+        assert_eq!(actions[1]["description"], "Column 'Nowhere' not found");
+        assert_eq!(
+            actions[2]["description"],
+            "Skipped because an earlier action failed"
+        );
+
+        // Nothing of the first action is left, not even in the trash
+        for query in [
+            "SELECT COUNT(*) FROM cards WHERE title = 'Draft contract'",
+            "SELECT COUNT(*) FROM chat_actions",
+            "SELECT COUNT(*) FROM activity_log WHERE event = 'chat_actions'",
+        ] {
+            let count: i64 = sqlx::query_scalar(query)
+                .fetch_one(state.pool.as_ref())
+                .await
+                .unwrap();
+            assert_eq!(count, 0, "{}", query);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_board_chat_answers_from_web_search() {
+        let search = axum::Router::new().route(
+            "/html/",
+            axum::routing::get(|| async {
+                axum::response::Html(
+                    r#"<div class="result"><a class="result__a" href="https://www.rust-lang.org/">Rust</a><a class="result__snippet">Rust 1.90 is out</a></div>"#,
+                )
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let search_url = format!("http://{}/html/", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, search).await.unwrap() });
+
+        let (url, requests) = chat_undo_tests::start_recording_ollama(&[
+            r#"{"action": "web_search", "params": {"query": "latest rust"}, "message": "Searching"}"#,
+            "Rust 1.90 is the latest release.",
+        ])
+        .await;
+        let mut state = test_utils::create_test_state().await;
+        state.llm = Arc::new(OllamaClient::new(Some(url), None));
+        state.web_search = WebSearchClient::with_base_url(search_url);
+        let server = TestServer::new(create_router(state)).unwrap();
+        let session = register_and_login(&server).await;
+        let board: Value = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Research"}))
+            .await
+            .json();
+
+        let response: Value = server
+            .post(&format!(
+                "/api/boards/{}/chat",
+                board["id"].as_str().unwrap()
+            ))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"message": "What is the latest Rust?"}))
+            .await
+            .json();
+        assert_eq!(response["response"], "Rust 1.90 is the latest release.");
+        assert_eq!(response["actions_taken"][0]["action"], "web_search");
+        assert_eq!(
+            response["actions_taken"][0]["sources"][0]["url"],
+            "https://www.rust-lang.org/"
+        );
+
+        // The second turn carries the results
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        let followup = requests[1]["messages"].as_array().unwrap().last().unwrap();
+        assert!(followup["content"]
+            .as_str()
+            .unwrap()
+            .contains("Rust 1.90 is out"));
+    }
+}
+
+// ============================================================================
+// Telegram Bot Tests
+// ============================================================================
+
+mod telegram_tests {
+    use super::*;
+    use personal_os::handlers::telegram::handle_update;
+    use personal_os::services::telegram::TelegramUpdate;
+    use personal_os::services::{OllamaClient, TelegramClient};
+    use std::sync::{Arc, Mutex};
+
+    /// Bot API answering sendMessage for the bot "TOKEN", keeping the sent messages
+    async fn start_fake_telegram() -> (TelegramClient, Arc<Mutex<Vec<Value>>>) {
+        let sent: Arc<Mutex<Vec<Value>>> = Arc::default();
+        let recorded = sent.clone();
+        let app = axum::Router::new().route(
+            "/botTOKEN/sendMessage",
+            axum::routing::post(move |axum::Json(body): axum::Json<Value>| async move {
+                recorded.lock().unwrap().push(body);
+                axum::Json(json!({"ok": true, "result": {}}))
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (TelegramClient::new(&url, "TOKEN"), sent)
+    }
+
+    fn update(chat_id: i64, text: &str) -> TelegramUpdate {
+        serde_json::from_value(json!({
+            "update_id": 1,
+            "message": {"chat": {"id": chat_id}, "text": text},
+        }))
+        .unwrap()
+    }
+
+    fn last_reply(sent: &Mutex<Vec<Value>>) -> String {
+        let sent = sent.lock().unwrap();
+        sent.last().unwrap()["text"].as_str().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn test_linked_chat_talks_to_global_chat() {
+        let (bot, sent) = start_fake_telegram().await;
+        let mut state = test_utils::create_test_state().await;
+        state.llm = Arc::new(OllamaClient::new(
+            Some(
+                chat_undo_tests::start_fake_ollama(&[
+                    r#"{"action": "create_inbox_card", "params": {"title": "buy milk"}, "message": "Added to your inbox"}"#,
+                ])
+                .await,
+            ),
+            None,
+        ));
+        state.telegram_bot = Some(bot.clone());
+        let server = TestServer::new(create_router(state.clone())).unwrap();
+        let session = register_and_login(&server).await;
+
+        // An unlinked chat is told how to link itself
+        handle_update(&state, &bot, update(42, "add 'buy milk' to my inbox")).await;
+        assert!(last_reply(&sent).contains("/start <code>"));
+
+        let link: Value = server
+            .post("/api/users/me/telegram")
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        let command = link["command"].as_str().unwrap();
+        assert_eq!(
+            command,
+            format!("/start {}", link["code"].as_str().unwrap())
+        );
+
+        handle_update(&state, &bot, update(42, command)).await;
+        assert!(last_reply(&sent).starts_with("This chat is now linked"));
+        // The code can only be used once
+        handle_update(&state, &bot, update(7, command)).await;
+        assert!(last_reply(&sent).contains("unknown or expired"));
+
+        handle_update(&state, &bot, update(42, "add 'buy milk' to my inbox")).await;
+        assert!(last_reply(&sent).starts_with("Added to your inbox\n✓ "));
+        assert_eq!(sent.lock().unwrap().last().unwrap()["chat_id"], 42);
+
+        let cards: Value = server
+            .get("/api/cards")
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(cards[0]["title"], "buy milk");
+
+        // Unlinking from the chat stops it from acting as the user
+        handle_update(&state, &bot, update(42, "/stop")).await;
+        assert!(last_reply(&sent).contains("no longer linked"));
+        handle_update(&state, &bot, update(42, "hello")).await;
+        assert!(last_reply(&sent).contains("/start <code>"));
+        server
+            .delete("/api/users/me/telegram")
+            .add_cookie(session_cookie(&session))
+            .await
+            .assert_status_not_found();
+    }
+
+    #[tokio::test]
+    async fn test_link_code_requires_configured_bot() {
+        let server = setup_server().await;
+        let session = register_and_login(&server).await;
+
+        server
+            .post("/api/users/me/telegram")
+            .add_cookie(session_cookie(&session))
+            .await
+            .assert_status_bad_request();
+    }
+}
+
+// ============================================================================
+// Rate Limit Tests
+// ============================================================================
+
+mod rate_limit_tests {
+    use super::*;
+    use personal_os::auth::{RateLimiter, RateLimits};
+    use personal_os::services::OllamaClient;
+    use std::sync::Arc;
+
+    async fn setup_limited(limits: RateLimits) -> TestServer {
+        let mut state = test_utils::create_test_state().await;
+        state.rate_limiter = RateLimiter::new(limits);
+        state.llm = Arc::new(OllamaClient::new(
+            Some(chat_undo_tests::start_fake_ollama(&["Hello", "Hello", "Hello"]).await),
+            None,
+        ));
+        TestServer::new(create_router(state)).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_login_attempts_are_limited_per_ip() {
+        let server = setup_limited(RateLimits {
+            login_per_ip: 2,
+            ..RateLimits::disabled()
+        })
+        .await;
+
+        for _ in 0..2 {
+            server
+                .post("/api/auth/login")
+                .json(&json!({"email": "nobody@example.com", "password": "guess"}))
+                .await
+                .assert_status_unauthorized();
+        }
+        let response = server
+            .post("/api/auth/login")
+            .json(&json!({"email": "nobody@example.com", "password": "guess"}))
+            .await;
+        response.assert_status(axum::http::StatusCode::TOO_MANY_REQUESTS);
+        let retry_after: u64 = response
+            .header("retry-after")
+            .to_str()
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!((1..=30).contains(&retry_after));
+
+        // The web login form shares the limit
+        server
+            .post("/login")
+            .form(&[("email", "nobody@example.com"), ("password", "guess")])
+            .await
+            .assert_status(axum::http::StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    #[tokio::test]
+    async fn test_guest_comments_are_limited_per_ip() {
+        let server = setup_limited(RateLimits {
+            guest_comments_per_ip: 2,
+            ..RateLimits::disabled()
+        })
+        .await;
+        let url = format!("/api/share/nope/cards/{}/comments", uuid::Uuid::new_v4());
+
+        for _ in 0..2 {
+            server
+                .post(&url)
+                .json(&json!({"name": "Sam", "body": "Hi"}))
+                .await
+                .assert_status_not_found();
+        }
+        server
+            .post(&url)
+            .json(&json!({"name": "Sam", "body": "Hi"}))
+            .await
+            .assert_status(axum::http::StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    #[tokio::test]
+    async fn test_chat_is_limited_per_user() {
+        let server = setup_limited(RateLimits {
+            chat_per_user: 1,
+            ..RateLimits::disabled()
+        })
+        .await;
+        let session = register_and_login(&server).await;
+        let other_session = register_and_login(&server).await;
+
+        server
+            .post("/api/chat")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"message": "Hi"}))
+            .await
+            .assert_status_ok();
+        let response = server
+            .post("/api/chat")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"message": "Hi again"}))
+            .await;
+        response.assert_status(axum::http::StatusCode::TOO_MANY_REQUESTS);
+        let body: Value = response.json();
+        assert_eq!(body["code"], "rate_limited");
+        assert!(body["message"]
+            .as_str()
+            .unwrap()
+            .starts_with("Too many requests"));
+        assert!(body["details"]["retry_after"].as_u64().is_some());
+
+        // Other users and other endpoints are not affected
+        server
+            .post("/api/chat")
+            .add_cookie(session_cookie(&other_session))
+            .json(&json!({"message": "Hi"}))
+            .await
+            .assert_status_ok();
+        server
+            .get("/api/chat/history")
+            .add_cookie(session_cookie(&session))
+            .await
+            .assert_status_ok();
+    }
+
+    #[tokio::test]
+    async fn test_api_requests_are_limited_per_ip() {
+        let server = setup_limited(RateLimits {
+            api_per_ip: 3,
+            ..RateLimits::disabled()
+        })
+        .await;
+
+        for _ in 0..3 {
+            server.get("/api/boards").await.assert_status_unauthorized();
+        }
+        server
+            .get("/api/boards")
+            .await
+            .assert_status(axum::http::StatusCode::TOO_MANY_REQUESTS);
+    }
+}
+
+// ============================================================================
+// OAuth Login Tests
+// ============================================================================
+
+mod oauth_tests {
+    use super::*;
+    use personal_os::services::{OAuthProvider, OAuthProviders};
+
+    /// OpenID Connect provider that issues the code it is asked to, as the access token,
+    /// and describes the account `subject|email|email_verified` the code names
+    async fn start_fake_oidc() -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let issuer = url.clone();
+        let app =
+            axum::Router::new()
+                .route(
+                    "/.well-known/openid-configuration",
+                    axum::routing::get(move || async move {
+                        axum::Json(json!({
+                            "issuer": issuer,
+                            "authorization_endpoint": format!("{}/authorize", issuer),
+                            "token_endpoint": format!("{}/token", issuer),
+                            "userinfo_endpoint": format!("{}/userinfo", issuer),
+                        }))
+                    }),
+                )
+                .route(
+                    "/token",
+                    axum::routing::post(
+                        |axum::Form(form): axum::Form<
+                            std::collections::HashMap<String, String>,
+                        >| async move {
+                            assert_eq!(form["grant_type"], "authorization_code");
+                            assert_eq!(form["client_secret"], "secret");
+                            axum::Json(
+                                json!({"access_token": form["code"], "token_type": "Bearer"}),
+                            )
+                        },
+                    ),
+                )
+                .route(
+                    "/userinfo",
+                    axum::routing::get(|headers: axum::http::HeaderMap| async move {
+                        let token = headers["authorization"]
+                            .to_str()
+                            .unwrap()
+                            .trim_start_matches("Bearer ")
+                            .to_string();
+                        let parts: Vec<&str> = token.split('|').collect();
+                        axum::Json(json!({
+                            "sub": parts[0],
+                            "email": parts[1],
+                            "email_verified": parts[2] == "true",
+                            "name": "Olivia Auth",
+                        }))
+                    }),
+                );
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        url
+    }
+
+    async fn setup() -> (TestServer, AppState) {
+        let mut state = test_utils::create_test_state().await;
+        state.oauth = OAuthProviders::new(
+            "http://localhost:3000",
+            vec![OAuthProvider::oidc(
+                start_fake_oidc().await,
+                "client".to_string(),
+                "secret".to_string(),
+                Some("Acme SSO".to_string()),
+            )],
+        );
+        (
+            TestServer::new(create_router(state.clone())).unwrap(),
+            state,
+        )
+    }
+
+    /// Go through the provider's login with the account `code` names, returning the
+    /// callback's response
+    async fn oauth_login(server: &TestServer, code: &str) -> axum_test::TestResponse {
+        let start = server.get("/auth/oauth/oidc").await;
+        start.assert_status(axum::http::StatusCode::SEE_OTHER);
+        let location = reqwest::Url::parse(start.header("location").to_str().unwrap()).unwrap();
+        assert!(location.path().ends_with("/authorize"));
+        let query: std::collections::HashMap<_, _> = location.query_pairs().into_owned().collect();
+        assert_eq!(query["client_id"], "client");
+        assert_eq!(
+            query["redirect_uri"],
+            "http://localhost:3000/auth/oauth/oidc/callback"
+        );
+
+        server
+            .get("/auth/oauth/oidc/callback")
+            .add_query_param("code", code)
+            .add_query_param("state", &query["state"])
+            .add_cookie(start.cookie("oauth_state"))
+            .await
+    }
+
+    async fn session_user(state: &AppState, response: &axum_test::TestResponse) -> uuid::Uuid {
+        let token = response.cookie("session").value().to_string();
+        state
+            .sessions
+            .find_by_token(&token)
+            .await
+            .unwrap()
+            .unwrap()
+            .user_id
+    }
+
+    #[tokio::test]
+    async fn test_login_page_offers_configured_providers() {
+        let (server, _) = setup().await;
+        let page = server.get("/login").await.text();
+        assert!(page.contains(r#"href="/auth/oauth/oidc""#));
+        assert!(page.contains("Sign in with Acme SSO"));
+
+        setup_server()
+            .await
+            .get("/auth/oauth/github")
+            .await
+            .assert_status_not_found();
+    }
+
+    #[tokio::test]
+    async fn test_oauth_login_creates_user_without_password() {
+        let (server, state) = setup().await;
+
+        let response = oauth_login(&server, "sub-1|olivia@example.com|true").await;
+        response.assert_status(axum::http::StatusCode::SEE_OTHER);
+        assert_eq!(response.header("location"), "/boards");
+        let user_id = session_user(&state, &response).await;
+        let user = state.users.get_by_id(user_id).await.unwrap();
+        assert_eq!(user.email, "olivia@example.com");
+        assert_eq!(user.name, "Olivia Auth");
+        assert!(user.password_hash.is_none());
+
+        server
+            .get("/api/settings")
+            .add_cookie(response.cookie("session"))
+            .await
+            .assert_status_ok();
+        // There is no password to log in with
+        server
+            .post("/api/auth/login")
+            .json(&json!({"email": "olivia@example.com", "password": ""}))
+            .await
+            .assert_status_unauthorized();
+
+        // The identity keeps pointing to the user when the provider's email changes
+        let again = oauth_login(&server, "sub-1|olivia@new.example.com|true").await;
+        assert_eq!(session_user(&state, &again).await, user_id);
+    }
+
+    #[tokio::test]
+    async fn test_oauth_login_links_account_with_same_email() {
+        let (server, state) = setup().await;
+        let user_id = test_utils::create_test_user(&state, "pat@example.com", "Pat").await;
+
+        // An unverified address proves nothing about owning the account
+        let response = oauth_login(&server, "sub-2|pat@example.com|false").await;
+        response.assert_status_ok();
+        assert!(response.maybe_cookie("session").is_none());
+        assert!(response
+            .text()
+            .contains("Your Acme SSO account has no verified email address"));
+
+        let response = oauth_login(&server, "sub-2|pat@example.com|true").await;
+        assert_eq!(session_user(&state, &response).await, user_id);
+        let identity = state
+            .identities
+            .find("oidc", "sub-2")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(identity.user_id, user_id);
+
+        // The password keeps working
+        server
+            .post("/api/auth/login")
+            .json(&json!({"email": "pat@example.com", "password": "testpassword123"}))
+            .await
+            .assert_status_ok();
+    }
+
+    #[tokio::test]
+    async fn test_oauth_callback_checks_state() {
+        let (server, _) = setup().await;
+        let start = server.get("/auth/oauth/oidc").await;
+
+        let response = server
+            .get("/auth/oauth/oidc/callback")
+            .add_query_param("code", "sub-3|mallory@example.com|true")
+            .add_query_param("state", "forged")
+            .add_cookie(start.cookie("oauth_state"))
+            .await;
+        assert!(response.maybe_cookie("session").is_none());
+        assert!(response.text().contains("Your login expired"));
+
+        // Declining at the provider lands back on the login page
+        let response = server
+            .get("/auth/oauth/oidc/callback")
+            .add_query_param("error", "access_denied")
+            .await;
+        assert!(response.text().contains("Could not sign in with Acme SSO"));
+    }
+}
+
+// ============================================================================
+// Account Export and Deletion Tests
+// ============================================================================
+
+mod account_tests {
+    use super::*;
+
+    async fn post(server: &TestServer, session: &str, path: &str, body: Value) -> Value {
+        let response = server
+            .post(path)
+            .add_cookie(session_cookie(session))
+            .json(&body)
+            .await;
+        response.assert_status_ok();
+        response.json()
+    }
+
+    #[tokio::test]
+    async fn test_export_account() {
+        let (server, state) = setup_server_with_state().await;
+        let user_id = test_utils::create_test_user(&state, "me@example.com", "Me").await;
+        let session = test_utils::create_test_session(&state, user_id).await;
+
+        let board = post(&server, &session, "/api/boards", json!({"name": "Home"})).await;
+        let column = post(
+            &server,
+            &session,
+            &format!("/api/boards/{}/columns", board["id"].as_str().unwrap()),
+            json!({"name": "To Do"}),
+        )
+        .await;
+        let card = post(
+            &server,
+            &session,
+            &format!("/api/columns/{}/cards", column["id"].as_str().unwrap()),
+            json!({"title": "Fix the sink"}),
+        )
+        .await;
+        post(
+            &server,
+            &session,
+            &format!("/api/cards/{}/comments", card["id"].as_str().unwrap()),
+            json!({"body": "Call the plumber"}),
+        )
+        .await;
+        post(&server, &session, "/api/cards", json!({"title": "Inbox"})).await;
+
+        let response = server
+            .get("/api/users/me/export")
+            .add_cookie(session_cookie(&session))
+            .await;
+        response.assert_status_ok();
+        assert!(response
+            .header("content-disposition")
+            .to_str()
+            .unwrap()
+            .starts_with("attachment; filename=\"account-"));
+        let export: Value = response.json();
+        assert_eq!(export["user"]["email"], "me@example.com");
+        assert_eq!(export["boards"][0]["name"], "Home");
+        assert_eq!(export["boards"][0]["role"], "owner");
+        assert_eq!(export["boards"][0]["columns"][0]["name"], "To Do");
+        assert_eq!(export["cards"].as_array().unwrap().len(), 2);
+        assert_eq!(export["comments"][0]["body"], "Call the plumber");
+        assert!(export["chat_messages"].as_array().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_delete_account_keeps_work_on_shared_boards() {
+        let (server, state) = setup_server_with_state().await;
+        let owner_id = test_utils::create_test_user(&state, "owner@example.com", "Owner").await;
+        let owner = test_utils::create_test_session(&state, owner_id).await;
+        let user_id = test_utils::create_test_user(&state, "leaver@example.com", "Leaver").await;
+        let session = test_utils::create_test_session(&state, user_id).await;
+
+        // A board shared with the user, where they add a card and a comment
+        let shared = post(&server, &owner, "/api/boards", json!({"name": "Team"})).await;
+        let shared_id = shared["id"].as_str().unwrap();
+        server
+            .post(&format!("/api/boards/{}/permissions", shared_id))
+            .add_cookie(session_cookie(&owner))
+            .json(&json!({"user_id": user_id, "role": "Editor"}))
+            .await
+            .assert_status_ok();
+        let column = post(
+            &server,
+            &owner,
+            &format!("/api/boards/{}/columns", shared_id),
+            json!({"name": "To Do"}),
+        )
+        .await;
+        let card = post(
+            &server,
+            &session,
+            &format!("/api/columns/{}/cards", column["id"].as_str().unwrap()),
+            json!({"title": "Shared work"}),
+        )
+        .await;
+        let card_id = card["id"].as_str().unwrap();
+        post(
+            &server,
+            &session,
+            &format!("/api/cards/{}/comments", card_id),
+            json!({"body": "Done"}),
+        )
+        .await;
+
+        // The user's own board and inbox card
+        let own = post(&server, &session, "/api/boards", json!({"name": "Mine"})).await;
+        let own_id = own["id"].as_str().unwrap();
+        let inbox = post(&server, &session, "/api/cards", json!({"title": "Private"})).await;
+
+        let delete = |confirm: &'static str, password: &'static str| {
+            server
+                .delete("/api/users/me")
+                .add_cookie(session_cookie(&session))
+                .json(&json!({"confirm_email": confirm, "current_password": password}))
+        };
+        delete("someone@example.com", "testpassword123")
+            .await
+            .assert_status_bad_request();
+        delete("leaver@example.com", "wrong")
+            .await
+            .assert_status_bad_request();
+        delete("leaver@example.com", "testpassword123")
+            .await
+            .assert_status_ok();
+
+        // Logged out and unable to log in again
+        server
+            .get("/api/boards")
+            .add_cookie(session_cookie(&session))
+            .await
+            .assert_status_unauthorized();
+        server
+            .post("/api/auth/login")
+            .json(&json!({"email": "leaver@example.com", "password": "testpassword123"}))
+            .await
+            .assert_status_unauthorized();
+
+        // Their board and inbox card are gone
+        assert!(state
+            .boards
+            .find_by_id(own_id.parse().unwrap())
+            .await
+            .unwrap()
+            .is_none());
+        assert!(state
+            .cards
+            .find_by_id(inbox["id"].as_str().unwrap().parse().unwrap())
+            .await
+            .unwrap()
+            .is_none());
+
+        // Their work on the shared board stays, credited to "Deleted user"
+        server
+            .get(&format!("/api/cards/{}", card_id))
+            .add_cookie(session_cookie(&owner))
+            .await
+            .assert_status_ok();
+        let comments: Value = server
+            .get(&format!("/api/cards/{}/comments", card_id))
+            .add_cookie(session_cookie(&owner))
+            .await
+            .json();
+        assert_eq!(comments[0]["author_name"], "Deleted user");
+        let permissions = state
+            .boards
+            .list_permissions(shared_id.parse().unwrap())
+            .await
+            .unwrap();
+        assert!(permissions.iter().all(|p| p.user_id != user_id));
+
+        // The email address can be registered again
+        server
+            .post("/api/auth/register")
+            .json(&json!({
+                "email": "leaver@example.com",
+                "password": "testpassword123",
+                "name": "Back again"
+            }))
+            .await
+            .assert_status_ok();
+    }
+}
+
+mod webhook_tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    /// Requests received by a fake webhook consumer: the headers that matter and the body
+    type Received = Arc<Mutex<Vec<(axum::http::HeaderMap, Value)>>>;
+
+    /// Fake webhook consumer recording what it receives, answering 503 while `down`
+    async fn start_receiver(down: Arc<AtomicBool>) -> (String, Received) {
+        let received: Received = Arc::default();
+        let recorded = received.clone();
+        let app = axum::Router::new().route(
+            "/hook",
+            axum::routing::post(
+                move |headers: axum::http::HeaderMap, axum::Json(body): axum::Json<Value>| async move {
+                    recorded.lock().unwrap().push((headers, body));
+                    if down.load(Ordering::SeqCst) {
+                        axum::http::StatusCode::SERVICE_UNAVAILABLE
+                    } else {
+                        axum::http::StatusCode::OK
+                    }
+                },
+            ),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (url, received)
+    }
+
+    /// Wait until the consumer has received `count` requests
+    async fn wait_for(received: &Received, count: usize) {
+        for _ in 0..200 {
+            if received.lock().unwrap().len() >= count {
+                return;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        panic!("Webhook received fewer than {} requests", count);
+    }
+
+    #[tokio::test]
+    async fn test_webhook_events_are_versioned_and_can_be_replayed() {
+        let (server, state) = setup_server_with_state().await;
+        let owner_id = test_utils::create_test_user(&state, "owner@example.com", "Owner").await;
+        let owner_session = test_utils::create_test_session(&state, owner_id).await;
+        let editor_id = test_utils::create_test_user(&state, "editor@example.com", "Editor").await;
+        let editor_session = test_utils::create_test_session(&state, editor_id).await;
+        let since = (chrono::Utc::now() - chrono::Duration::minutes(1))
+            .format("%Y-%m-%dT%H:%M:%SZ")
+            .to_string();
+
+        let board_id = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"name": "Hooked"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        server
+            .post(&format!("/api/boards/{}/permissions", board_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"user_id": editor_id, "role": "Editor"}))
+            .await
+            .assert_status_ok();
+
+        let down = Arc::new(AtomicBool::new(true));
+        let (url, received) = start_receiver(down.clone()).await;
+
+        // Only owners manage webhooks, and only http(s) addresses are accepted
+        server
+            .post(&format!("/api/boards/{}/webhooks", board_id))
+            .add_cookie(session_cookie(&editor_session))
+            .json(&json!({"url": url}))
+            .await
+            .assert_status_forbidden();
+        server
+            .post(&format!("/api/boards/{}/webhooks", board_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"url": "ftp://example.com/hook"}))
+            .await
+            .assert_status_unprocessable_entity();
+        let webhook: Value = server
+            .post(&format!("/api/boards/{}/webhooks", board_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"url": url}))
+            .await
+            .json();
+        let webhook_id = webhook["id"].as_str().unwrap().to_string();
+        let webhooks: Value = server
+            .get(&format!("/api/boards/{}/webhooks", board_id))
+            .add_cookie(session_cookie(&owner_session))
+            .await
+            .json();
+        assert_eq!(webhooks.as_array().unwrap().len(), 1);
+
+        // Events are sent with their schema version while the consumer is down
+        let column: Value = server
+            .post(&format!("/api/boards/{}/columns", board_id))
+            .add_cookie(session_cookie(&editor_session))
+            .json(&json!({"name": "Doing"}))
+            .await
+            .json();
+        wait_for(&received, 1).await;
+        let (headers, body) = received.lock().unwrap()[0].clone();
+        assert_eq!(headers["x-webhook-event"], "column_created");
+        assert_eq!(headers["x-webhook-schema-version"], "1");
+        assert!(headers.get("x-webhook-replay").is_none());
+        assert_eq!(body["schema_version"], 1);
+        assert_eq!(body["event"], "column_created");
+        assert_eq!(body["board_id"], board_id.as_str());
+        assert_eq!(body["id"], column["id"]);
+        assert_eq!(body["actor_id"], editor_id.to_string());
+        let delivery_id = body["delivery_id"].clone();
+        assert_eq!(headers["x-webhook-delivery"], delivery_id.as_str().unwrap());
+
+        // Once back up, the consumer recovers what it missed under the same delivery id
+        down.store(false, Ordering::SeqCst);
+        server
+            .post(&format!(
+                "/api/webhooks/{}/replay?since={}",
+                webhook_id, since
+            ))
+            .add_cookie(session_cookie(&editor_session))
+            .await
+            .assert_status_not_found();
+        let replay: Value = server
+            .post(&format!(
+                "/api/webhooks/{}/replay?since={}",
+                webhook_id, since
+            ))
+            .add_cookie(session_cookie(&owner_session))
+            .await
+            .json();
+        assert_eq!(replay["replayed"], 1);
+        wait_for(&received, 2).await;
+        let (headers, body) = received.lock().unwrap()[1].clone();
+        assert_eq!(headers["x-webhook-replay"], "true");
+        assert_eq!(body["delivery_id"], delivery_id);
+        assert_eq!(body["event"], "column_created");
+
+        // Events older than `since` are not replayed
+        let replay: Value = server
+            .post(&format!(
+                "/api/webhooks/{}/replay?since=2999-01-01T00:00:00Z",
+                webhook_id
+            ))
+            .add_cookie(session_cookie(&owner_session))
+            .await
+            .json();
+        assert_eq!(replay["replayed"], 0);
+
+        // Deleting the webhook stops the deliveries
+        server
+            .delete(&format!("/api/webhooks/{}", webhook_id))
+            .add_cookie(session_cookie(&owner_session))
+            .await
+            .assert_status(axum::http::StatusCode::NO_CONTENT);
+        server
+            .post(&format!("/api/boards/{}/columns", board_id))
+            .add_cookie(session_cookie(&owner_session))
+            .json(&json!({"name": "Done"}))
+            .await
+            .assert_status_ok();
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        assert_eq!(received.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_old_webhook_events_are_purged() {
+        let (server, state) = setup_server_with_state().await;
+        let session = register_and_login(&server).await;
+        let board_id = server
+            .post("/api/boards")
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Hooked"}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let (url, received) = start_receiver(Arc::new(AtomicBool::new(false))).await;
+        let webhook_id = server
+            .post(&format!("/api/boards/{}/webhooks", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"url": url}))
+            .await
+            .json::<Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        server
+            .post(&format!("/api/boards/{}/columns", board_id))
+            .add_cookie(session_cookie(&session))
+            .json(&json!({"name": "Doing"}))
+            .await
+            .assert_status_ok();
+        wait_for(&received, 1).await;
+
+        sqlx::query("UPDATE webhook_events SET created_at = datetime('now', '-8 days')")
+            .execute(state.pool.as_ref())
+            .await
+            .unwrap();
+        assert_eq!(state.webhooks.purge_events(7).await.unwrap(), 1);
+
+        let replay: Value = server
+            .post(&format!(
+                "/api/webhooks/{}/replay?since=2000-01-01T00:00:00Z",
+                webhook_id
+            ))
+            .add_cookie(session_cookie(&session))
+            .await
+            .json();
+        assert_eq!(replay["replayed"], 0);
+    }
+}
@@ -0,0 +1,788 @@
+//! Repository tests against the in-memory database.
+//!
+//! Fixtures are built directly through the repositories, and query results are
+//! rendered to plain text and compared with the snapshots in `tests/snapshots`.
+//! Ids and timestamps are left out of the rendering so snapshots stay stable.
+//! After an intended change, review and accept new snapshots with
+//! `cargo insta review` (or run with `INSTA_UPDATE=always`).
+
+use chrono::NaiveDate;
+use insta::assert_snapshot;
+use personal_os::models::{BoardRole, Card, CardFilter, CardStatus, CardVisibility};
+use personal_os::{state::AppState, test_utils};
+use uuid::Uuid;
+
+// ============================================================================
+// Fixtures
+// ============================================================================
+
+struct Fixture {
+    state: AppState,
+}
+
+impl Fixture {
+    async fn new() -> Self {
+        Self {
+            state: test_utils::create_test_state().await,
+        }
+    }
+
+    async fn user(&self, name: &str) -> Uuid {
+        let email = format!("{}@example.com", name.to_lowercase());
+        test_utils::create_test_user(&self.state, &email, name).await
+    }
+
+    async fn board(&self, owner: Uuid, name: &str) -> Uuid {
+        self.state
+            .boards
+            .create(name, None, owner)
+            .await
+            .unwrap()
+            .id
+    }
+
+    async fn share(&self, board: Uuid, user: Uuid, role: BoardRole) {
+        self.state
+            .boards
+            .add_permission(board, user, role)
+            .await
+            .unwrap();
+    }
+
+    async fn column(&self, board: Uuid, name: &str) -> Uuid {
+        self.state
+            .columns
+            .create(board, name, None, None, None)
+            .await
+            .unwrap()
+            .id
+    }
+
+    async fn tag(&self, board: Uuid, name: &str) -> Uuid {
+        self.state
+            .tags
+            .create(board, name, "#808080")
+            .await
+            .unwrap()
+            .id
+    }
+
+    fn card(&self, author: Uuid, column: Uuid, title: &str) -> CardBuilder<'_> {
+        CardBuilder {
+            fixture: self,
+            author,
+            column,
+            title: title.to_string(),
+            body: None,
+            visibility: CardVisibility::Restricted,
+            status: CardStatus::Open,
+            due_date: None,
+            tags: Vec::new(),
+        }
+    }
+}
+
+struct CardBuilder<'a> {
+    fixture: &'a Fixture,
+    author: Uuid,
+    column: Uuid,
+    title: String,
+    body: Option<String>,
+    visibility: CardVisibility,
+    status: CardStatus,
+    due_date: Option<NaiveDate>,
+    tags: Vec<Uuid>,
+}
+
+impl CardBuilder<'_> {
+    fn body(mut self, body: &str) -> Self {
+        self.body = Some(body.to_string());
+        self
+    }
+
+    fn visibility(mut self, visibility: CardVisibility) -> Self {
+        self.visibility = visibility;
+        self
+    }
+
+    fn status(mut self, status: CardStatus) -> Self {
+        self.status = status;
+        self
+    }
+
+    fn due(mut self, date: &str) -> Self {
+        self.due_date = Some(date.parse().unwrap());
+        self
+    }
+
+    fn tag(mut self, tag: Uuid) -> Self {
+        self.tags.push(tag);
+        self
+    }
+
+    async fn create(self) -> Card {
+        let state = &self.fixture.state;
+        let card = state
+            .cards
+            .create(
+                self.column,
+                &self.title,
+                self.body.as_deref(),
+                None,
+                self.visibility,
+                self.status,
+                None,
+                None,
+                self.due_date,
+                None,
+                self.author,
+            )
+            .await
+            .unwrap();
+        for tag in self.tags {
+            state.tags.add_to_card(card.id, tag).await.unwrap();
+        }
+        card
+    }
+}
+
+// ============================================================================
+// Rendering
+// ============================================================================
+
+/// One line per card: title, status, visibility and due date
+fn render_cards(cards: &[Card]) -> String {
+    if cards.is_empty() {
+        return "(none)".to_string();
+    }
+    cards
+        .iter()
+        .map(|c| {
+            format!(
+                "{} [{}, {}, due {}]",
+                c.title,
+                c.status,
+                c.visibility,
+                c.due_date
+                    .map(|d| d.to_string())
+                    .unwrap_or_else(|| "-".to_string())
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Named sections, so one snapshot covers a family of related queries
+fn render_sections(sections: &[(&str, String)]) -> String {
+    sections
+        .iter()
+        .map(|(name, body)| format!("## {}\n{}", name, body))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+// ============================================================================
+// Card Filter Tests
+// ============================================================================
+
+mod card_filter_tests {
+    use super::*;
+
+    /// A board with a mix of visibilities, statuses, due dates and tags
+    async fn filter_board(fx: &Fixture) -> (Uuid, Uuid, Uuid) {
+        let owner = fx.user("Owner").await;
+        let board = fx.board(owner, "Filters").await;
+        let todo = fx.column(board, "To Do").await;
+        let done = fx.column(board, "Done").await;
+        let urgent = fx.tag(board, "urgent").await;
+
+        fx.card(owner, todo, "Public roadmap")
+            .visibility(CardVisibility::Public)
+            .due("2024-03-01")
+            .create()
+            .await;
+        fx.card(owner, todo, "Team sync notes")
+            .body("Agenda for O'Brien")
+            .tag(urgent)
+            .create()
+            .await;
+        fx.card(owner, todo, "Private journal")
+            .visibility(CardVisibility::Private)
+            .due("2024-04-15")
+            .tag(urgent)
+            .create()
+            .await;
+        fx.card(owner, done, "Shipped release")
+            .status(CardStatus::Done)
+            .due("2024-02-10")
+            .create()
+            .await;
+
+        (owner, board, urgent)
+    }
+
+    #[tokio::test]
+    async fn test_filter_visibility_by_role() {
+        let fx = Fixture::new().await;
+        let (owner, board, _) = filter_board(&fx).await;
+        let reader = fx.user("Reader").await;
+        fx.share(board, reader, BoardRole::Reader).await;
+        let stranger = fx.user("Stranger").await;
+
+        let filter = CardFilter::default();
+        let mut sections = Vec::new();
+        for (name, user, role) in [
+            ("owner", owner, Some("owner")),
+            ("reader", reader, Some("reader")),
+            ("no role", stranger, None),
+        ] {
+            let cards = fx
+                .state
+                .cards
+                .list_by_board_with_filter(board, user, role, &filter)
+                .await
+                .unwrap();
+            sections.push((name, render_cards(&cards)));
+        }
+
+        assert_snapshot!("filter_visibility_by_role", render_sections(&sections));
+    }
+
+    #[tokio::test]
+    async fn test_filter_by_text_dates_and_tags() {
+        let fx = Fixture::new().await;
+        let (owner, board, urgent) = filter_board(&fx).await;
+
+        let cases = [
+            (
+                "query matches title",
+                CardFilter {
+                    query: Some("release".to_string()),
+                    ..Default::default()
+                },
+            ),
+            (
+                "query with a quote matches body",
+                CardFilter {
+                    query: Some("O'Brien".to_string()),
+                    ..Default::default()
+                },
+            ),
+            (
+                "due in March or later",
+                CardFilter {
+                    due_date_from: Some("2024-03-01".parse().unwrap()),
+                    ..Default::default()
+                },
+            ),
+            (
+                "due before March",
+                CardFilter {
+                    due_date_to: Some("2024-02-29".parse().unwrap()),
+                    ..Default::default()
+                },
+            ),
+            (
+                "tagged urgent",
+                CardFilter {
+                    tags: Some(vec![urgent]),
+                    ..Default::default()
+                },
+            ),
+        ];
+
+        let mut sections = Vec::new();
+        for (name, filter) in cases {
+            let cards = fx
+                .state
+                .cards
+                .list_by_board_with_filter(board, owner, Some("owner"), &filter)
+                .await
+                .unwrap();
+            sections.push((name, render_cards(&cards)));
+        }
+
+        assert_snapshot!("filter_by_text_dates_and_tags", render_sections(&sections));
+    }
+
+    #[tokio::test]
+    async fn test_filter_values_are_not_sql() {
+        let fx = Fixture::new().await;
+        let (owner, board, _) = filter_board(&fx).await;
+
+        for query in ["' OR '1'='1", "%') OR 1=1 --", "x'; DROP TABLE cards; --"] {
+            let filter = CardFilter {
+                query: Some(query.to_string()),
+                ..Default::default()
+            };
+            let cards = fx
+                .state
+                .cards
+                .list_by_board_with_filter(board, owner, Some("owner"), &filter)
+                .await
+                .unwrap();
+            assert!(
+                cards.is_empty(),
+                "{query:?} matched {} card(s)",
+                cards.len()
+            );
+        }
+
+        let all = fx
+            .state
+            .cards
+            .list_by_board_with_filter(board, owner, Some("owner"), &CardFilter::default())
+            .await
+            .unwrap();
+        assert_eq!(all.len(), 4);
+    }
+}
+
+// ============================================================================
+// Card Move Tests
+// ============================================================================
+
+mod card_move_tests {
+    use super::*;
+
+    async fn render_column(fx: &Fixture, column: Uuid) -> String {
+        let cards = fx.state.cards.list_by_column(column).await.unwrap();
+        if cards.is_empty() {
+            return "(empty)".to_string();
+        }
+        cards
+            .iter()
+            .map(|c| format!("{}: {}", c.position, c.title))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[tokio::test]
+    async fn test_moves_keep_positions_contiguous() {
+        let fx = Fixture::new().await;
+        let owner = fx.user("Owner").await;
+        let board = fx.board(owner, "Moves").await;
+        let todo = fx.column(board, "To Do").await;
+        let doing = fx.column(board, "Doing").await;
+
+        let mut cards = Vec::new();
+        for title in ["A", "B", "C", "D"] {
+            cards.push(fx.card(owner, todo, title).create().await);
+        }
+        let mut sections = Vec::new();
+
+        // Down within the column
+        fx.state
+            .cards
+            .move_card(cards[0].id, todo, 2)
+            .await
+            .unwrap();
+        sections.push(("A moved to 2", render_column(&fx, todo).await));
+
+        // Up within the column
+        fx.state
+            .cards
+            .move_card(cards[3].id, todo, 0)
+            .await
+            .unwrap();
+        sections.push(("D moved to 0", render_column(&fx, todo).await));
+
+        // Across columns
+        fx.state
+            .cards
+            .move_card(cards[1].id, doing, 0)
+            .await
+            .unwrap();
+        fx.state
+            .cards
+            .move_card(cards[2].id, doing, 0)
+            .await
+            .unwrap();
+        sections.push((
+            "B then C moved to Doing",
+            format!(
+                "To Do\n{}\nDoing\n{}",
+                render_column(&fx, todo).await,
+                render_column(&fx, doing).await
+            ),
+        ));
+
+        assert_snapshot!(
+            "moves_keep_positions_contiguous",
+            render_sections(&sections)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rebalance_repairs_duplicates_and_gaps() {
+        let fx = Fixture::new().await;
+        let owner = fx.user("Owner").await;
+        let board = fx.board(owner, "Repairs").await;
+        let todo = fx.column(board, "To Do").await;
+        let doing = fx.column(board, "Doing").await;
+        for title in ["A", "B", "C", "D"] {
+            fx.card(owner, todo, title).create().await;
+        }
+
+        // Positions as a buggy bulk move could leave them: C and D on the same
+        // slot, nothing at 0 and 2
+        for (title, position) in [("A", 1), ("B", 3), ("C", 5), ("D", 5)] {
+            sqlx::query("UPDATE cards SET position = $1 WHERE title = $2")
+                .bind(position)
+                .bind(title)
+                .execute(fx.state.pool.as_ref())
+                .await
+                .unwrap();
+        }
+        sqlx::query("UPDATE columns SET position = 4 WHERE id = $1")
+            .bind(doing)
+            .execute(fx.state.pool.as_ref())
+            .await
+            .unwrap();
+        let mut sections = vec![("Corrupted", render_column(&fx, todo).await)];
+
+        let changed = fx.state.cards.rebalance_column(todo).await.unwrap();
+        sections.push((
+            "Rebalanced",
+            format!(
+                "{}
+({} changed)",
+                render_column(&fx, todo).await,
+                changed
+            ),
+        ));
+        let changed = fx.state.cards.rebalance_column(todo).await.unwrap();
+        sections.push(("Rebalanced again", format!("({} changed)", changed)));
+
+        let changed = fx.state.columns.rebalance_board(board).await.unwrap();
+        let columns = fx.state.columns.list_by_board(board).await.unwrap();
+        sections.push((
+            "Columns",
+            format!(
+                "{}
+({} changed)",
+                columns
+                    .iter()
+                    .map(|c| format!("{}: {}", c.position, c.name))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                changed
+            ),
+        ));
+
+        assert_snapshot!(
+            "rebalance_repairs_duplicates_and_gaps",
+            render_sections(&sections)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_moves_clamp_positions_to_the_column() {
+        let fx = Fixture::new().await;
+        let owner = fx.user("Owner").await;
+        let board = fx.board(owner, "Clamps").await;
+        let todo = fx.column(board, "To Do").await;
+        let doing = fx.column(board, "Doing").await;
+        let a = fx.card(owner, todo, "A").create().await;
+        let b = fx.card(owner, todo, "B").create().await;
+        fx.card(owner, doing, "C").create().await;
+
+        let moved = fx.state.cards.move_card(a.id, doing, 10).await.unwrap();
+        assert_eq!(moved.position, 1);
+        let moved = fx.state.cards.move_card(b.id, todo, -3).await.unwrap();
+        assert_eq!(moved.position, 0);
+        let moved = fx.state.columns.move_column(todo, 7).await.unwrap();
+        assert_eq!(moved.position, 1);
+
+        assert_eq!(render_column(&fx, doing).await, "0: C\n1: A");
+    }
+}
+
+// ============================================================================
+// Position Property Tests
+// ============================================================================
+
+mod position_property_tests {
+    use super::*;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    const CARDS: usize = 6;
+    const COLUMNS: usize = 3;
+
+    /// A move of a card (by index) to a column (by index) at a position, possibly out
+    /// of range
+    fn moves() -> impl Strategy<Value = Vec<(usize, usize, i32)>> {
+        vec((0..CARDS, 0..COLUMNS, -2..(CARDS as i32 + 2)), 1..30)
+    }
+
+    /// A board whose cards are spread over its columns
+    async fn board(state: &AppState) -> (Vec<Uuid>, Vec<Uuid>) {
+        let owner = test_utils::create_test_user(state, "owner@example.com", "Owner").await;
+        let board = state.boards.create("Moves", None, owner).await.unwrap().id;
+        let mut columns = Vec::new();
+        for name in ["To Do", "Doing", "Done"] {
+            columns.push(
+                state
+                    .columns
+                    .create(board, name, None, None, None)
+                    .await
+                    .unwrap()
+                    .id,
+            );
+        }
+        let mut cards = Vec::new();
+        for i in 0..CARDS {
+            let card = state
+                .cards
+                .create(
+                    columns[i % COLUMNS],
+                    &format!("Card {}", i),
+                    None,
+                    None,
+                    CardVisibility::Restricted,
+                    CardStatus::Open,
+                    None,
+                    None,
+                    None,
+                    None,
+                    owner,
+                )
+                .await
+                .unwrap();
+            cards.push(card.id);
+        }
+        (columns, cards)
+    }
+
+    /// Every column holds its cards at 0, 1, 2... and every card is in one column
+    async fn assert_contiguous(state: &AppState, columns: &[Uuid]) {
+        let mut total = 0;
+        for column in columns {
+            let positions: Vec<i32> = state
+                .cards
+                .list_positions(*column)
+                .await
+                .unwrap()
+                .iter()
+                .map(|c| c.position)
+                .collect();
+            let expected: Vec<i32> = (0..positions.len() as i32).collect();
+            assert_eq!(positions, expected);
+            total += positions.len();
+        }
+        assert_eq!(total, CARDS);
+    }
+
+    fn runtime() -> tokio::runtime::Runtime {
+        tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(32))]
+
+        #[test]
+        fn test_sequential_moves_keep_positions_contiguous(moves in moves()) {
+            runtime().block_on(async {
+                let state = test_utils::create_test_state().await;
+                let (columns, cards) = board(&state).await;
+                for (card, column, position) in moves {
+                    state
+                        .cards
+                        .move_card(cards[card], columns[column], position)
+                        .await
+                        .unwrap();
+                    assert_contiguous(&state, &columns).await;
+                }
+            });
+        }
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(8))]
+
+        /// Moves sent at once over several connections, as by users dragging cards on
+        /// the same board
+        #[test]
+        fn test_concurrent_moves_never_duplicate_positions(moves in moves()) {
+            runtime().block_on(async {
+                let dir = tempfile::tempdir().unwrap();
+                let pool = SqlitePoolOptions::new()
+                    .max_connections(4)
+                    .connect(&format!("sqlite:{}?mode=rwc", dir.path().join("pos.db").display()))
+                    .await
+                    .unwrap();
+                personal_os::run_migrations(&pool).await.unwrap();
+                let state = AppState::new(pool, "http://localhost:3000");
+                let (columns, cards) = board(&state).await;
+
+                let mut tasks = tokio::task::JoinSet::new();
+                for (card, column, position) in moves {
+                    let state = state.clone();
+                    let (card, column) = (cards[card], columns[column]);
+                    tasks.spawn(async move {
+                        state.cards.move_card(card, column, position).await.unwrap();
+                    });
+                }
+                while let Some(result) = tasks.join_next().await {
+                    result.unwrap();
+                }
+                assert_contiguous(&state, &columns).await;
+            });
+        }
+    }
+}
+
+// ============================================================================
+// Board Detail Tests
+// ============================================================================
+
+mod board_detail_tests {
+    use super::*;
+    use personal_os::models::{ColumnSortPolicy, Tag};
+
+    #[tokio::test]
+    async fn test_batched_board_queries_match_per_card_queries() {
+        let fx = Fixture::new().await;
+        let owner = fx.user("Owner").await;
+        let board = fx.board(owner, "Batched").await;
+        let other_board = fx.board(owner, "Other").await;
+        let todo = fx.column(board, "To Do").await;
+        let doing = fx.column(board, "Doing").await;
+        let archived = fx.column(board, "Old").await;
+        let elsewhere = fx.column(other_board, "Elsewhere").await;
+        let bug = fx.tag(board, "bug").await;
+        let docs = fx.tag(board, "docs").await;
+
+        fx.state
+            .columns
+            .update(
+                doing,
+                None,
+                None,
+                Some(ColumnSortPolicy::DueDate),
+                None,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        fx.card(owner, todo, "A").tag(bug).tag(docs).create().await;
+        fx.card(owner, todo, "B").create().await;
+        let hidden = fx.card(owner, todo, "Archived").tag(bug).create().await;
+        fx.state.cards.set_archived(hidden.id, true).await.unwrap();
+        fx.card(owner, doing, "Later")
+            .due("2024-03-01")
+            .create()
+            .await;
+        fx.card(owner, doing, "Sooner")
+            .due("2024-02-01")
+            .tag(docs)
+            .create()
+            .await;
+        fx.card(owner, archived, "In archived column")
+            .create()
+            .await;
+        fx.state.columns.set_archived(archived, true).await.unwrap();
+        fx.card(owner, elsewhere, "Other board")
+            .tag(bug)
+            .create()
+            .await;
+
+        let cards = fx.state.cards.list_by_board_columns(board).await.unwrap();
+        let tags = fx.state.tags.list_for_board_cards(board).await.unwrap();
+
+        let mut expected = Vec::new();
+        for column in fx.state.columns.list_by_board(board).await.unwrap() {
+            expected.extend(fx.state.cards.list_by_column(column.id).await.unwrap());
+        }
+        let ids = |cards: &[Card]| cards.iter().map(|c| c.id).collect::<Vec<_>>();
+        assert_eq!(ids(&cards), ids(&expected));
+        assert_snapshot!("board_columns_in_one_query", render_cards(&cards));
+
+        for card in &cards {
+            let one_by_one = fx.state.tags.list_for_card(card.id).await.unwrap();
+            let batched = tags.get(&card.id).cloned().unwrap_or_default();
+            let names = |tags: &[Tag]| tags.iter().map(|t| t.name.clone()).collect::<Vec<_>>();
+            assert_eq!(
+                names(&batched),
+                names(&one_by_one),
+                "tags of {}",
+                card.title
+            );
+        }
+        assert_eq!(tags.len(), 2);
+    }
+}
+
+// ============================================================================
+// Board Permission Tests
+// ============================================================================
+
+mod board_permission_tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_roles_and_board_listing() {
+        let fx = Fixture::new().await;
+        let alice = fx.user("Alice").await;
+        let bob = fx.user("Bob").await;
+        let carol = fx.user("Carol").await;
+
+        let work = fx.board(alice, "Work").await;
+        let home = fx.board(bob, "Home").await;
+        fx.share(work, bob, BoardRole::Editor).await;
+        fx.share(work, carol, BoardRole::Reader).await;
+
+        let mut sections = Vec::new();
+        for (name, user) in [("Alice", alice), ("Bob", bob), ("Carol", carol)] {
+            let mut roles = Vec::new();
+            for (board_name, board) in [("Work", work), ("Home", home)] {
+                let role = fx.state.boards.get_user_role(board, user).await.unwrap();
+                roles.push(format!(
+                    "{}: {}",
+                    board_name,
+                    role.map(|r| r.to_string())
+                        .unwrap_or_else(|| "-".to_string())
+                ));
+            }
+
+            let mut listed: Vec<String> = fx
+                .state
+                .boards
+                .list_for_user(user)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|(board, role)| format!("{} ({})", board.name, role))
+                .collect();
+            listed.sort();
+            roles.push(format!("listed: {}", listed.join(", ")));
+
+            sections.push((name, roles.join("\n")));
+        }
+
+        // Removing a permission revokes access
+        fx.state
+            .boards
+            .remove_permission(work, carol)
+            .await
+            .unwrap();
+        let role = fx.state.boards.get_user_role(work, carol).await.unwrap();
+        sections.push((
+            "Carol after removal",
+            format!(
+                "Work: {}",
+                role.map(|r| r.to_string())
+                    .unwrap_or_else(|| "-".to_string())
+            ),
+        ));
+
+        assert_snapshot!("roles_and_board_listing", render_sections(&sections));
+    }
+}